@@ -0,0 +1,12 @@
+-- LLM Orchestrator Audit Event Tenants
+-- Migration: 003_tenants
+-- Description: Record the tenant each audit event belongs to
+
+ALTER TABLE audit_events ADD COLUMN IF NOT EXISTS tenant_id VARCHAR(255);
+
+-- Index for tenant-scoped searches, newest first
+CREATE INDEX IF NOT EXISTS idx_audit_tenant_id
+ON audit_events(tenant_id, timestamp DESC)
+WHERE tenant_id IS NOT NULL;
+
+COMMENT ON COLUMN audit_events.tenant_id IS 'Tenant the event belongs to';
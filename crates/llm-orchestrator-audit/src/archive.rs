@@ -0,0 +1,394 @@
+//! Archival of old audit events to object storage
+//!
+//! [`AuditArchive`] moves events out of the primary storage into gzipped
+//! JSONL objects, one or more per UTC day, laid out as
+//! `<prefix>YYYY/MM/DD/<time>-<id>.jsonl.gz`. Archived ranges can be queried
+//! in place or restored into a storage backend. Objects are written through
+//! an [`ArchiveStore`]: a local directory ([`LocalArchiveStore`]) or an
+//! S3-compatible bucket (`S3ArchiveStore`, requires the `s3` feature).
+
+use crate::models::{AuditEvent, AuditFilter};
+use crate::storage::{AuditStorage, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Number of events read per page while archiving
+const ARCHIVE_PAGE_SIZE: usize = 1000;
+
+/// Object storage holding archived audit events
+#[async_trait]
+pub trait ArchiveStore: Send + Sync {
+    /// Write an object, replacing any existing one
+    async fn put(&self, key: &str, body: Vec<u8>) -> Result<()>;
+
+    /// Read an object
+    async fn get(&self, key: &str) -> Result<Vec<u8>>;
+
+    /// List the keys of all objects starting with `prefix`, sorted
+    async fn list(&self, prefix: &str) -> Result<Vec<String>>;
+
+    /// Delete an object
+    async fn delete(&self, key: &str) -> Result<()>;
+}
+
+/// Archive store keeping objects as files under a directory
+pub struct LocalArchiveStore {
+    root: PathBuf,
+}
+
+impl LocalArchiveStore {
+    /// Create a store rooted at `root`
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Collect the keys of all files below `dir`
+    fn walk(&self, dir: &std::path::Path, keys: &mut Vec<String>) -> std::io::Result<()> {
+        let entries = match std::fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e),
+        };
+
+        for entry in entries {
+            let path = entry?.path();
+            if path.is_dir() {
+                self.walk(&path, keys)?;
+            } else if let Ok(relative) = path.strip_prefix(&self.root) {
+                let key: Vec<_> = relative
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect();
+                keys.push(key.join("/"));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ArchiveStore for LocalArchiveStore {
+    async fn put(&self, key: &str, body: Vec<u8>) -> Result<()> {
+        let path = self.root.join(key);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(path, body).await?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>> {
+        Ok(tokio::fs::read(self.root.join(key)).await?)
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        let mut keys = Vec::new();
+        self.walk(&self.root, &mut keys)?;
+        keys.retain(|key| key.starts_with(prefix));
+        keys.sort();
+        Ok(keys)
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        tokio::fs::remove_file(self.root.join(key)).await?;
+        Ok(())
+    }
+}
+
+/// Outcome of an archive run
+#[derive(Debug, Clone, Default)]
+pub struct ArchiveReport {
+    /// Number of events written to the archive
+    pub events: u64,
+
+    /// Keys of the objects written
+    pub objects: Vec<String>,
+}
+
+/// Moves audit events between a storage backend and object storage
+pub struct AuditArchive {
+    store: Arc<dyn ArchiveStore>,
+    prefix: String,
+    max_object_events: usize,
+}
+
+impl AuditArchive {
+    /// Create an archive writing objects under `audit/`
+    pub fn new(store: Arc<dyn ArchiveStore>) -> Self {
+        Self {
+            store,
+            prefix: "audit/".to_string(),
+            max_object_events: 50_000,
+        }
+    }
+
+    /// Set the key prefix of archive objects
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        let mut prefix = prefix.into();
+        if !prefix.is_empty() && !prefix.ends_with('/') {
+            prefix.push('/');
+        }
+        self.prefix = prefix;
+        self
+    }
+
+    /// Set the maximum number of events per object
+    pub fn with_max_object_events(mut self, max_object_events: usize) -> Self {
+        self.max_object_events = max_object_events.max(1);
+        self
+    }
+
+    /// Copy every event older than `cutoff` from `storage` into the archive
+    ///
+    /// Events are not removed from `storage`; delete them with
+    /// [`AuditStorage::delete_older_than`] once this succeeds.
+    pub async fn archive_before(
+        &self,
+        storage: &dyn AuditStorage,
+        cutoff: DateTime<Utc>,
+    ) -> Result<ArchiveReport> {
+        let mut report = ArchiveReport::default();
+        let mut days: BTreeMap<NaiveDate, Vec<AuditEvent>> = BTreeMap::new();
+        let mut offset = 0;
+
+        loop {
+            let filter = AuditFilter {
+                end_time: Some(cutoff),
+                limit: ARCHIVE_PAGE_SIZE,
+                offset,
+                ..AuditFilter::new()
+            };
+            let page = storage.query(filter).await?;
+            let page_len = page.len();
+            offset += page_len;
+
+            // Pages are newest first, so days after the oldest one seen are complete
+            let oldest_day = page.last().map(|e| e.timestamp.date_naive());
+            for event in page.into_iter().filter(|e| e.timestamp < cutoff) {
+                let day = days.entry(event.timestamp.date_naive()).or_default();
+                day.push(event);
+                if day.len() >= self.max_object_events {
+                    let events = std::mem::take(day);
+                    self.write_object(events, &mut report).await?;
+                }
+            }
+
+            if page_len < ARCHIVE_PAGE_SIZE {
+                break;
+            }
+            if let Some(oldest_day) = oldest_day {
+                let complete = days.split_off(&oldest_day.succ_opt().unwrap_or(oldest_day));
+                for (_, events) in complete {
+                    self.write_object(events, &mut report).await?;
+                }
+            }
+        }
+
+        for (_, events) in days {
+            self.write_object(events, &mut report).await?;
+        }
+
+        tracing::info!(
+            events = report.events,
+            objects = report.objects.len(),
+            cutoff = %cutoff,
+            "Archived audit events"
+        );
+        Ok(report)
+    }
+
+    /// Query archived events; results are newest first and paginated like storage queries
+    pub async fn query(&self, filter: &AuditFilter) -> Result<Vec<AuditEvent>> {
+        let events = self.read(filter).await?;
+        Ok(events
+            .into_iter()
+            .skip(filter.offset)
+            .take(filter.limit)
+            .collect())
+    }
+
+    /// Copy archived events matching `filter` back into `storage`
+    ///
+    /// Pagination of the filter is ignored and events already present in
+    /// `storage` are skipped. Returns the number of events restored
+    pub async fn restore(&self, filter: &AuditFilter, storage: &dyn AuditStorage) -> Result<u64> {
+        let mut restored = 0;
+        for event in self.read(filter).await? {
+            if storage.get(event.id).await?.is_none() {
+                storage.store(&event).await?;
+                restored += 1;
+            }
+        }
+
+        tracing::info!(restored, "Restored archived audit events");
+        Ok(restored)
+    }
+
+    /// Delete archive objects for days entirely before `cutoff`
+    /// Returns the number of objects deleted
+    pub async fn expire_before(&self, cutoff: DateTime<Utc>) -> Result<u64> {
+        let cutoff_day = cutoff.date_naive();
+        let mut deleted = 0;
+        for key in self.store.list(&self.prefix).await? {
+            if self.day_of(&key).is_some_and(|day| day < cutoff_day) {
+                self.store.delete(&key).await?;
+                deleted += 1;
+            }
+        }
+        Ok(deleted)
+    }
+
+    /// Read every archived event matching `filter`, newest first
+    async fn read(&self, filter: &AuditFilter) -> Result<Vec<AuditEvent>> {
+        let first_day = filter.start_time.map(|t| t.date_naive());
+        let last_day = filter.end_time.map(|t| t.date_naive());
+
+        let mut events = Vec::new();
+        for key in self.store.list(&self.prefix).await? {
+            let Some(day) = self.day_of(&key) else {
+                continue;
+            };
+            if first_day.is_some_and(|first| day < first) || last_day.is_some_and(|last| day > last) {
+                continue;
+            }
+
+            let body = self.store.get(&key).await?;
+            for line in BufReader::new(GzDecoder::new(body.as_slice())).lines() {
+                let line = line?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let event: AuditEvent = serde_json::from_str(&line)?;
+                if filter.matches(&event) {
+                    events.push(event);
+                }
+            }
+        }
+
+        events.sort_by_key(|e| std::cmp::Reverse(e.timestamp));
+        Ok(events)
+    }
+
+    /// Compress one day's events into a new object
+    async fn write_object(&self, mut events: Vec<AuditEvent>, report: &mut ArchiveReport) -> Result<()> {
+        if events.is_empty() {
+            return Ok(());
+        }
+        events.sort_by_key(|e| e.timestamp);
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        for event in &events {
+            writeln!(encoder, "{}", serde_json::to_string(event)?)?;
+        }
+        let body = encoder.finish()?;
+
+        let first = events[0].timestamp;
+        let key = format!(
+            "{}{}/{}-{}.jsonl.gz",
+            self.prefix,
+            first.format("%Y/%m/%d"),
+            first.format("%H%M%S"),
+            Uuid::new_v4()
+        );
+        self.store.put(&key, body).await?;
+
+        report.events += events.len() as u64;
+        report.objects.push(key);
+        Ok(())
+    }
+
+    /// Get the day an object holds events for from its key
+    fn day_of(&self, key: &str) -> Option<NaiveDate> {
+        let path = key.strip_prefix(&self.prefix)?;
+        let date = path.rsplit_once('/')?.0;
+        NaiveDate::parse_from_str(date, "%Y/%m/%d").ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file::{FileAuditStorage, RotationPolicy};
+    use crate::models::{AuditEventType, AuditResult, ResourceType};
+    use chrono::Duration;
+    use tempfile::{tempdir, NamedTempFile};
+
+    fn event_at(timestamp: DateTime<Utc>, user_id: &str) -> AuditEvent {
+        let mut event = AuditEvent::new(
+            AuditEventType::WorkflowExecution,
+            "Workflow executed".to_string(),
+            ResourceType::Workflow,
+            "workflow-1".to_string(),
+            AuditResult::Success,
+        )
+        .with_user_id(user_id.to_string());
+        event.timestamp = timestamp;
+        event
+    }
+
+    #[tokio::test]
+    async fn test_archive_query_and_restore() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let storage =
+            FileAuditStorage::new(temp_file.path().to_path_buf(), RotationPolicy::Never).unwrap();
+        let dir = tempdir().unwrap();
+        let archive = AuditArchive::new(Arc::new(LocalArchiveStore::new(dir.path())))
+            .with_max_object_events(2);
+
+        let now = Utc::now();
+        for days in [10, 10, 10, 9, 1] {
+            let user = if days == 9 { "bob" } else { "alice" };
+            storage.store(&event_at(now - Duration::days(days), user)).await.unwrap();
+        }
+
+        let cutoff = now - Duration::days(5);
+        let report = archive.archive_before(&storage, cutoff).await.unwrap();
+        assert_eq!(report.events, 4);
+        // Day 10 is split into two objects of at most two events
+        assert_eq!(report.objects.len(), 3);
+        assert!(report.objects.iter().all(|key| key.starts_with("audit/") && key.ends_with(".jsonl.gz")));
+
+        storage.delete_older_than(cutoff).await.unwrap();
+        assert_eq!(storage.count(AuditFilter::new()).await.unwrap(), 1);
+
+        let bob = archive
+            .query(&AuditFilter::new().with_user_id("bob".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(bob.len(), 1);
+
+        let range = AuditFilter::new().with_time_range(now - Duration::days(11), now - Duration::days(8));
+        assert_eq!(archive.restore(&range, &storage).await.unwrap(), 4);
+        // Restoring again skips events already present
+        assert_eq!(archive.restore(&range, &storage).await.unwrap(), 0);
+        assert_eq!(storage.count(AuditFilter::new()).await.unwrap(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_expire_before() {
+        let dir = tempdir().unwrap();
+        let store = Arc::new(LocalArchiveStore::new(dir.path()));
+        let archive = AuditArchive::new(store.clone()).with_prefix("archive");
+
+        let now = Utc::now();
+        let temp_file = NamedTempFile::new().unwrap();
+        let storage =
+            FileAuditStorage::new(temp_file.path().to_path_buf(), RotationPolicy::Never).unwrap();
+        for days in [400, 30] {
+            storage.store(&event_at(now - Duration::days(days), "alice")).await.unwrap();
+        }
+        archive.archive_before(&storage, now).await.unwrap();
+
+        let expired = archive.expire_before(now - Duration::days(365)).await.unwrap();
+        assert_eq!(expired, 1);
+        assert_eq!(store.list("archive/").await.unwrap().len(), 1);
+    }
+}
@@ -52,6 +52,7 @@ impl DatabaseAuditStorage {
                 timestamp TIMESTAMP WITH TIME ZONE NOT NULL,
                 event_type VARCHAR(100) NOT NULL,
                 user_id VARCHAR(255),
+                tenant_id VARCHAR(255),
                 action VARCHAR(255) NOT NULL,
                 resource_type VARCHAR(50) NOT NULL,
                 resource_id VARCHAR(255) NOT NULL,
@@ -76,6 +77,17 @@ impl DatabaseAuditStorage {
             .await
             .map_err(|e| StorageError::DatabaseError(e.to_string()))?;
 
+        // Tables created before tenants were recorded lack the column
+        sqlx::query("ALTER TABLE audit_events ADD COLUMN IF NOT EXISTS tenant_id VARCHAR(255)")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| StorageError::DatabaseError(e.to_string()))?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_tenant_id ON audit_events(tenant_id, timestamp DESC)")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| StorageError::DatabaseError(e.to_string()))?;
+
         sqlx::query("CREATE INDEX IF NOT EXISTS idx_user_id ON audit_events(user_id)")
             .execute(&self.pool)
             .await
@@ -109,8 +121,8 @@ impl AuditStorage for DatabaseAuditStorage {
             INSERT INTO audit_events (
                 id, timestamp, event_type, user_id, action,
                 resource_type, resource_id, result, result_error, details,
-                ip_address, user_agent, request_id, previous_hash, event_hash
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
+                ip_address, user_agent, request_id, previous_hash, event_hash, tenant_id
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)
             "#,
         )
         .bind(event.id)
@@ -128,6 +140,7 @@ impl AuditStorage for DatabaseAuditStorage {
         .bind(&event.request_id)
         .bind(&event.previous_hash)
         .bind(&event.event_hash)
+        .bind(&event.tenant_id)
         .execute(&self.pool)
         .await
         .map_err(|e| StorageError::DatabaseError(e.to_string()))?;
@@ -137,42 +150,7 @@ impl AuditStorage for DatabaseAuditStorage {
 
     async fn query(&self, filter: AuditFilter) -> Result<Vec<AuditEvent>> {
         let mut query = String::from("SELECT * FROM audit_events WHERE 1=1");
-        let mut params: Vec<String> = Vec::new();
-
-        if let Some(user_id) = &filter.user_id {
-            params.push(user_id.clone());
-            query.push_str(&format!(" AND user_id = ${}", params.len()));
-        }
-
-        if let Some(event_type) = &filter.event_type {
-            params.push(event_type.as_str().to_string());
-            query.push_str(&format!(" AND event_type = ${}", params.len()));
-        }
-
-        if let Some(resource_type) = &filter.resource_type {
-            params.push(resource_type.as_str().to_string());
-            query.push_str(&format!(" AND resource_type = ${}", params.len()));
-        }
-
-        if let Some(resource_id) = &filter.resource_id {
-            params.push(resource_id.clone());
-            query.push_str(&format!(" AND resource_id = ${}", params.len()));
-        }
-
-        if let Some(start_time) = filter.start_time {
-            params.push(start_time.to_rfc3339());
-            query.push_str(&format!(" AND timestamp >= ${}", params.len()));
-        }
-
-        if let Some(end_time) = filter.end_time {
-            params.push(end_time.to_rfc3339());
-            query.push_str(&format!(" AND timestamp <= ${}", params.len()));
-        }
-
-        if let Some(result) = &filter.result {
-            params.push(result.as_str().to_string());
-            query.push_str(&format!(" AND result = ${}", params.len()));
-        }
+        let params = push_conditions(&filter, &mut query);
 
         query.push_str(" ORDER BY timestamp DESC");
         query.push_str(&format!(" LIMIT {} OFFSET {}", filter.limit, filter.offset));
@@ -212,6 +190,8 @@ impl AuditStorage for DatabaseAuditStorage {
                     "api_key_create" => AuditEventType::ApiKeyCreate,
                     "api_key_revoke" => AuditEventType::ApiKeyRevoke,
                     "step_execution" => AuditEventType::StepExecution,
+                    "provider_call" => AuditEventType::ProviderCall,
+                    "guardrail_violation" => AuditEventType::GuardrailViolation,
                     _ => AuditEventType::SystemEvent,
                 };
 
@@ -223,6 +203,7 @@ impl AuditStorage for DatabaseAuditStorage {
                     "secret" => ResourceType::Secret,
                     "configuration" => ResourceType::Configuration,
                     "step" => ResourceType::Step,
+                    "provider" => ResourceType::Provider,
                     _ => ResourceType::System,
                 };
 
@@ -231,6 +212,7 @@ impl AuditStorage for DatabaseAuditStorage {
                     timestamp: row.get("timestamp"),
                     event_type,
                     user_id: row.get("user_id"),
+                    tenant_id: row.get("tenant_id"),
                     action: row.get("action"),
                     resource_type,
                     resource_id: row.get("resource_id"),
@@ -278,6 +260,8 @@ impl AuditStorage for DatabaseAuditStorage {
                 "api_key_create" => AuditEventType::ApiKeyCreate,
                 "api_key_revoke" => AuditEventType::ApiKeyRevoke,
                 "step_execution" => AuditEventType::StepExecution,
+                "provider_call" => AuditEventType::ProviderCall,
+                "guardrail_violation" => AuditEventType::GuardrailViolation,
                 _ => AuditEventType::SystemEvent,
             };
 
@@ -289,6 +273,7 @@ impl AuditStorage for DatabaseAuditStorage {
                 "secret" => ResourceType::Secret,
                 "configuration" => ResourceType::Configuration,
                 "step" => ResourceType::Step,
+                "provider" => ResourceType::Provider,
                 _ => ResourceType::System,
             };
 
@@ -297,6 +282,7 @@ impl AuditStorage for DatabaseAuditStorage {
                 timestamp: row.get("timestamp"),
                 event_type,
                 user_id: row.get("user_id"),
+                tenant_id: row.get("tenant_id"),
                 action: row.get("action"),
                 resource_type,
                 resource_id: row.get("resource_id"),
@@ -325,32 +311,7 @@ impl AuditStorage for DatabaseAuditStorage {
 
     async fn count(&self, filter: AuditFilter) -> Result<u64> {
         let mut query = String::from("SELECT COUNT(*) FROM audit_events WHERE 1=1");
-        let mut params: Vec<String> = Vec::new();
-
-        if let Some(user_id) = &filter.user_id {
-            params.push(user_id.clone());
-            query.push_str(&format!(" AND user_id = ${}", params.len()));
-        }
-
-        if let Some(event_type) = &filter.event_type {
-            params.push(event_type.as_str().to_string());
-            query.push_str(&format!(" AND event_type = ${}", params.len()));
-        }
-
-        if let Some(resource_type) = &filter.resource_type {
-            params.push(resource_type.as_str().to_string());
-            query.push_str(&format!(" AND resource_type = ${}", params.len()));
-        }
-
-        if let Some(start_time) = filter.start_time {
-            params.push(start_time.to_rfc3339());
-            query.push_str(&format!(" AND timestamp >= ${}", params.len()));
-        }
-
-        if let Some(end_time) = filter.end_time {
-            params.push(end_time.to_rfc3339());
-            query.push_str(&format!(" AND timestamp <= ${}", params.len()));
-        }
+        let params = push_conditions(&filter, &mut query);
 
         let mut sql_query = sqlx::query(&query);
         for param in params {
@@ -375,3 +336,59 @@ impl AuditStorage for DatabaseAuditStorage {
         Ok(())
     }
 }
+
+/// Append the `WHERE` conditions of a filter to `query`, returning the bound parameters
+#[cfg(feature = "database")]
+fn push_conditions(filter: &AuditFilter, query: &mut String) -> Vec<String> {
+    let mut params: Vec<String> = Vec::new();
+
+    if let Some(user_id) = &filter.user_id {
+        params.push(user_id.clone());
+        query.push_str(&format!(" AND user_id = ${}", params.len()));
+    }
+
+    if let Some(tenant_id) = &filter.tenant_id {
+        params.push(tenant_id.clone());
+        query.push_str(&format!(" AND tenant_id = ${}", params.len()));
+    }
+
+    if let Some(event_type) = &filter.event_type {
+        params.push(event_type.as_str().to_string());
+        query.push_str(&format!(" AND event_type = ${}", params.len()));
+    }
+
+    if let Some(resource_type) = &filter.resource_type {
+        params.push(resource_type.as_str().to_string());
+        query.push_str(&format!(" AND resource_type = ${}", params.len()));
+    }
+
+    if let Some(resource_id) = &filter.resource_id {
+        params.push(resource_id.clone());
+        query.push_str(&format!(" AND resource_id = ${}", params.len()));
+    }
+
+    if let Some(workflow_id) = &filter.workflow_id {
+        params.push(workflow_id.clone());
+        query.push_str(&format!(
+            " AND ((resource_type = 'workflow' AND resource_id = ${0}) OR details->>'workflow_id' = ${0})",
+            params.len()
+        ));
+    }
+
+    if let Some(start_time) = filter.start_time {
+        params.push(start_time.to_rfc3339());
+        query.push_str(&format!(" AND timestamp >= ${}::timestamptz", params.len()));
+    }
+
+    if let Some(end_time) = filter.end_time {
+        params.push(end_time.to_rfc3339());
+        query.push_str(&format!(" AND timestamp <= ${}::timestamptz", params.len()));
+    }
+
+    if let Some(result) = &filter.result {
+        params.push(result.as_str().to_string());
+        query.push_str(&format!(" AND result = ${}", params.len()));
+    }
+
+    params
+}
@@ -0,0 +1,753 @@
+//! Export of audit events to SIEM systems
+//!
+//! Events are rendered as newline-delimited JSON or ArcSight CEF lines and
+//! shipped to an [`AuditSink`]: a syslog collector over TCP or UDP
+//! ([`SyslogSink`]) or an HTTP endpoint ([`WebhookSink`]). Wrap a storage
+//! backend in [`ExportingStorage`] to stream events as they are logged, or use
+//! [`export_events`] to write a time range of stored events to a file.
+
+use crate::models::{AuditEvent, AuditEventType, AuditFilter, AuditResult};
+use crate::query::AuditQuery;
+use crate::storage::{AuditStorage, AuditStorageRef, Result, StorageError};
+use async_trait::async_trait;
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::io::Write;
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::net::{TcpStream, UdpSocket};
+use tokio::sync::{mpsc, Mutex};
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+/// Syslog facility 13, "log audit"
+const SYSLOG_AUDIT_FACILITY: u8 = 13;
+
+/// Number of events read per page by [`export_events`]
+const EXPORT_PAGE_SIZE: usize = 1000;
+
+/// Line format of exported events
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// One JSON object per line
+    #[default]
+    Json,
+
+    /// ArcSight Common Event Format
+    Cef,
+}
+
+impl ExportFormat {
+    /// Render one event as a single line, without the trailing newline
+    pub fn format(&self, event: &AuditEvent) -> Result<String> {
+        match self {
+            Self::Json => Ok(serde_json::to_string(event)?),
+            Self::Cef => Ok(to_cef(event)),
+        }
+    }
+
+    /// Get the HTTP content type of a batch in this format
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Json => "application/x-ndjson",
+            Self::Cef => "text/plain",
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "json" | "jsonl" => Ok(Self::Json),
+            "cef" => Ok(Self::Cef),
+            _ => Err(format!("Unknown export format: {}", s)),
+        }
+    }
+}
+
+/// Render an event as a CEF line
+fn to_cef(event: &AuditEvent) -> String {
+    let severity = match (&event.result, &event.event_type) {
+        (
+            AuditResult::Failure(_),
+            AuditEventType::Authentication | AuditEventType::Authorization,
+        ) => 8,
+        (AuditResult::Failure(_), _) => 7,
+        (AuditResult::PartialSuccess, _) => 5,
+        (AuditResult::Success, _) => 3,
+    };
+
+    let mut extension = vec![
+        format!("rt={}", event.timestamp.timestamp_millis()),
+        format!("externalId={}", event.id),
+        format!("outcome={}", event.result.as_str()),
+        "cs1Label=resourceType".to_string(),
+        format!("cs1={}", event.resource_type.as_str()),
+        "cs2Label=resourceId".to_string(),
+        format!("cs2={}", cef_value(&event.resource_id)),
+    ];
+    if let Some(user_id) = &event.user_id {
+        extension.push(format!("suser={}", cef_value(user_id)));
+    }
+    if let Some(ip_address) = &event.ip_address {
+        extension.push(format!("src={}", cef_value(ip_address)));
+    }
+    if let Some(user_agent) = &event.user_agent {
+        extension.push(format!("requestClientApplication={}", cef_value(user_agent)));
+    }
+    if let Some(request_id) = &event.request_id {
+        extension.push("cs3Label=requestId".to_string());
+        extension.push(format!("cs3={}", cef_value(request_id)));
+    }
+    if let Some(workflow_id) = event.workflow_id() {
+        extension.push("cs4Label=workflowId".to_string());
+        extension.push(format!("cs4={}", cef_value(workflow_id)));
+    }
+    if let Some(error) = event.result.error_message() {
+        extension.push(format!("reason={}", cef_value(error)));
+    }
+    if !event.details.is_null() {
+        extension.push(format!("msg={}", cef_value(&event.details.to_string())));
+    }
+
+    format!(
+        "CEF:0|LLM DevOps|llm-orchestrator|{}|{}|{}|{}|{}",
+        env!("CARGO_PKG_VERSION"),
+        event.event_type.as_str(),
+        cef_header(&event.action),
+        severity,
+        extension.join(" ")
+    )
+}
+
+/// Escape a CEF header field
+fn cef_header(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace(['\r', '\n'], " ")
+}
+
+/// Escape a CEF extension value
+fn cef_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('=', "\\=")
+        .replace('\r', "\\r")
+        .replace('\n', "\\n")
+}
+
+/// Destination for exported audit events
+#[async_trait]
+pub trait AuditSink: Send + Sync {
+    /// Send a batch of events
+    async fn send(&self, events: &[AuditEvent]) -> Result<()>;
+}
+
+/// Transport used to reach a syslog collector
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyslogTransport {
+    /// One datagram per event
+    Udp,
+
+    /// Newline-framed messages over a persistent connection
+    Tcp,
+}
+
+/// Ships events to a syslog collector as RFC 5424 messages
+///
+/// The message body is the event rendered in the configured [`ExportFormat`].
+/// Failed events map to severity warning, partial successes to notice, and
+/// everything else to informational.
+pub struct SyslogSink {
+    addr: String,
+    transport: SyslogTransport,
+    format: ExportFormat,
+    facility: u8,
+    hostname: String,
+    app_name: String,
+    udp: Mutex<Option<UdpSocket>>,
+    tcp: Mutex<Option<TcpStream>>,
+}
+
+impl SyslogSink {
+    /// Create a sink sending to `addr` (host:port)
+    pub fn new(addr: impl Into<String>, transport: SyslogTransport) -> Self {
+        Self {
+            addr: addr.into(),
+            transport,
+            format: ExportFormat::Json,
+            facility: SYSLOG_AUDIT_FACILITY,
+            hostname: std::env::var("HOSTNAME").unwrap_or_else(|_| "-".to_string()),
+            app_name: "llm-orchestrator".to_string(),
+            udp: Mutex::new(None),
+            tcp: Mutex::new(None),
+        }
+    }
+
+    /// Set the message body format
+    pub fn with_format(mut self, format: ExportFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Set the syslog facility (0-23)
+    pub fn with_facility(mut self, facility: u8) -> Self {
+        self.facility = facility.min(23);
+        self
+    }
+
+    /// Set the hostname reported in messages
+    pub fn with_hostname(mut self, hostname: impl Into<String>) -> Self {
+        self.hostname = hostname.into();
+        self
+    }
+
+    /// Set the application name reported in messages
+    pub fn with_app_name(mut self, app_name: impl Into<String>) -> Self {
+        self.app_name = app_name.into();
+        self
+    }
+
+    /// Render an event as a syslog message
+    fn message(&self, event: &AuditEvent) -> Result<String> {
+        let severity = match event.result {
+            AuditResult::Failure(_) => 4,
+            AuditResult::PartialSuccess => 5,
+            AuditResult::Success => 6,
+        };
+
+        Ok(format!(
+            "<{}>1 {} {} {} - {} - {}",
+            self.facility * 8 + severity,
+            event.timestamp.to_rfc3339_opts(SecondsFormat::Micros, true),
+            self.hostname,
+            self.app_name,
+            event.event_type.as_str(),
+            self.format.format(event)?
+        ))
+    }
+
+    /// Resolve the collector address
+    async fn resolve(&self) -> Result<SocketAddr> {
+        tokio::net::lookup_host(&self.addr)
+            .await?
+            .next()
+            .ok_or_else(|| {
+                StorageError::ConfigurationError(format!("Cannot resolve syslog address: {}", self.addr))
+            })
+    }
+
+    async fn send_udp(&self, messages: &[String]) -> Result<()> {
+        let mut socket = self.udp.lock().await;
+        if socket.is_none() {
+            let addr = self.resolve().await?;
+            let bind_addr = if addr.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" };
+            let udp = UdpSocket::bind(bind_addr).await?;
+            udp.connect(addr).await?;
+            *socket = Some(udp);
+        }
+
+        let udp = socket.as_ref().expect("socket connected above");
+        for message in messages {
+            udp.send(message.as_bytes()).await?;
+        }
+        Ok(())
+    }
+
+    async fn send_tcp(&self, messages: &[String]) -> Result<()> {
+        let mut payload = messages.join("\n");
+        payload.push('\n');
+
+        let mut stream = self.tcp.lock().await;
+        // Reconnect once if the collector dropped the connection
+        for attempt in 0..2 {
+            if stream.is_none() {
+                let addr = self.resolve().await?;
+                *stream = Some(TcpStream::connect(addr).await.map_err(|e| {
+                    StorageError::ConnectionError(format!("Cannot connect to syslog collector: {}", e))
+                })?);
+            }
+
+            let tcp = stream.as_mut().expect("stream connected above");
+            match tcp.write_all(payload.as_bytes()).await {
+                Ok(()) => return Ok(()),
+                Err(e) => {
+                    *stream = None;
+                    if attempt == 1 {
+                        return Err(e.into());
+                    }
+                    tracing::warn!(error = %e, "Syslog connection lost, reconnecting");
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl AuditSink for SyslogSink {
+    async fn send(&self, events: &[AuditEvent]) -> Result<()> {
+        let messages = events
+            .iter()
+            .map(|event| self.message(event))
+            .collect::<Result<Vec<_>>>()?;
+
+        match self.transport {
+            SyslogTransport::Udp => self.send_udp(&messages).await,
+            SyslogTransport::Tcp => self.send_tcp(&messages).await,
+        }
+    }
+}
+
+/// Posts batches of events to an HTTP endpoint
+///
+/// Each request carries up to `max_batch_size` events, one per line. Requests
+/// failing with a connection error, 429, or 5xx are retried with exponential
+/// backoff; other client errors fail immediately.
+pub struct WebhookSink {
+    client: reqwest::Client,
+    url: String,
+    headers: Vec<(String, String)>,
+    format: ExportFormat,
+    max_batch_size: usize,
+    max_retries: u32,
+    initial_backoff: Duration,
+}
+
+impl WebhookSink {
+    /// Create a sink posting to `url`
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(30))
+                .build()
+                .unwrap_or_default(),
+            url: url.into(),
+            headers: Vec::new(),
+            format: ExportFormat::Json,
+            max_batch_size: 100,
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(500),
+        }
+    }
+
+    /// Add a header to every request
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Authenticate with a bearer token
+    pub fn with_bearer_token(self, token: impl AsRef<str>) -> Self {
+        self.with_header("Authorization", format!("Bearer {}", token.as_ref()))
+    }
+
+    /// Set the line format of request bodies
+    pub fn with_format(mut self, format: ExportFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Set the maximum number of events per request
+    pub fn with_max_batch_size(mut self, max_batch_size: usize) -> Self {
+        self.max_batch_size = max_batch_size.max(1);
+        self
+    }
+
+    /// Set the number of retries and the delay before the first one
+    pub fn with_retry(mut self, max_retries: u32, initial_backoff: Duration) -> Self {
+        self.max_retries = max_retries;
+        self.initial_backoff = initial_backoff;
+        self
+    }
+
+    async fn post(&self, body: String) -> Result<()> {
+        let mut backoff = self.initial_backoff;
+        let mut attempt = 0;
+
+        loop {
+            let mut request = self
+                .client
+                .post(&self.url)
+                .header(reqwest::header::CONTENT_TYPE, self.format.content_type())
+                .body(body.clone());
+            for (name, value) in &self.headers {
+                request = request.header(name, value);
+            }
+
+            let error = match request.send().await {
+                Ok(response) if response.status().is_success() => return Ok(()),
+                Ok(response) => {
+                    let status = response.status();
+                    let error = format!("Webhook returned {}", status);
+                    if status != reqwest::StatusCode::TOO_MANY_REQUESTS && !status.is_server_error() {
+                        return Err(StorageError::ConnectionError(error));
+                    }
+                    error
+                }
+                Err(e) => format!("Webhook request failed: {}", e),
+            };
+
+            if attempt >= self.max_retries {
+                return Err(StorageError::ConnectionError(error));
+            }
+            attempt += 1;
+            tracing::warn!(attempt, error = %error, "Retrying audit webhook");
+            tokio::time::sleep(backoff).await;
+            backoff *= 2;
+        }
+    }
+}
+
+#[async_trait]
+impl AuditSink for WebhookSink {
+    async fn send(&self, events: &[AuditEvent]) -> Result<()> {
+        for chunk in events.chunks(self.max_batch_size) {
+            let lines = chunk
+                .iter()
+                .map(|event| self.format.format(event))
+                .collect::<Result<Vec<_>>>()?;
+            let mut body = lines.join("\n");
+            body.push('\n');
+            self.post(body).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Batching of streamed events
+#[derive(Debug, Clone)]
+pub struct ExportConfig {
+    /// Send once this many events are buffered
+    pub batch_size: usize,
+
+    /// Send buffered events at least this often
+    pub flush_interval: Duration,
+
+    /// Events queued for export before new ones are dropped
+    pub queue_size: usize,
+}
+
+impl Default for ExportConfig {
+    fn default() -> Self {
+        Self {
+            batch_size: 100,
+            flush_interval: Duration::from_secs(5),
+            queue_size: 10_000,
+        }
+    }
+}
+
+/// Storage wrapper that streams every stored event to a sink
+///
+/// Events are stored in the wrapped backend first, then queued and sent in
+/// batches by a background task, so a slow or unreachable sink never blocks
+/// audit logging. Events are dropped when the queue is full or the sink fails
+/// after its retries; [`dropped`](Self::dropped) counts them.
+///
+/// Dropping the storage flushes the remaining events; await the returned
+/// handle to wait for the final batch.
+pub struct ExportingStorage {
+    inner: AuditStorageRef,
+    sender: mpsc::Sender<AuditEvent>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl ExportingStorage {
+    /// Wrap `inner` and start streaming its events to `sink`
+    pub fn new(
+        inner: AuditStorageRef,
+        sink: Arc<dyn AuditSink>,
+        config: ExportConfig,
+    ) -> (Self, JoinHandle<()>) {
+        let (sender, receiver) = mpsc::channel(config.queue_size.max(1));
+        let dropped = Arc::new(AtomicU64::new(0));
+        let handle = tokio::spawn(run_export(receiver, sink, config, dropped.clone()));
+
+        (
+            Self {
+                inner,
+                sender,
+                dropped,
+            },
+            handle,
+        )
+    }
+
+    /// Get the number of events that could not be exported
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+/// Batch queued events and send them until the storage is dropped
+async fn run_export(
+    mut receiver: mpsc::Receiver<AuditEvent>,
+    sink: Arc<dyn AuditSink>,
+    config: ExportConfig,
+    dropped: Arc<AtomicU64>,
+) {
+    let batch_size = config.batch_size.max(1);
+    let mut batch = Vec::with_capacity(batch_size);
+    let mut ticker = tokio::time::interval(config.flush_interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            received = receiver.recv() => match received {
+                Some(event) => {
+                    batch.push(event);
+                    if batch.len() >= batch_size {
+                        flush(sink.as_ref(), &mut batch, &dropped).await;
+                    }
+                }
+                None => {
+                    flush(sink.as_ref(), &mut batch, &dropped).await;
+                    break;
+                }
+            },
+            _ = ticker.tick() => flush(sink.as_ref(), &mut batch, &dropped).await,
+        }
+    }
+}
+
+async fn flush(sink: &dyn AuditSink, batch: &mut Vec<AuditEvent>, dropped: &AtomicU64) {
+    if batch.is_empty() {
+        return;
+    }
+
+    if let Err(e) = sink.send(batch).await {
+        dropped.fetch_add(batch.len() as u64, Ordering::Relaxed);
+        tracing::error!(error = %e, events = batch.len(), "Failed to export audit events");
+    }
+    batch.clear();
+}
+
+#[async_trait]
+impl AuditStorage for ExportingStorage {
+    async fn store(&self, event: &AuditEvent) -> Result<()> {
+        self.inner.store(event).await?;
+
+        if self.sender.try_send(event.clone()).is_err() {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+            tracing::warn!(event_id = %event.id, "Audit export queue full, dropping event");
+        }
+        Ok(())
+    }
+
+    async fn query(&self, filter: AuditFilter) -> Result<Vec<AuditEvent>> {
+        self.inner.query(filter).await
+    }
+
+    async fn get(&self, id: Uuid) -> Result<Option<AuditEvent>> {
+        self.inner.get(id).await
+    }
+
+    async fn delete_older_than(&self, cutoff: DateTime<Utc>) -> Result<u64> {
+        self.inner.delete_older_than(cutoff).await
+    }
+
+    async fn count(&self, filter: AuditFilter) -> Result<u64> {
+        self.inner.count(filter).await
+    }
+
+    async fn health_check(&self) -> Result<()> {
+        self.inner.health_check().await
+    }
+}
+
+/// Write every stored event matching `query` to `writer`, one per line
+///
+/// The query's own limit and offset are ignored. Without an end time the
+/// export stops at the current time, so events logged while exporting are
+/// not read twice. Events are written newest first.
+/// Returns the number of events written
+pub async fn export_events<W: Write>(
+    storage: &dyn AuditStorage,
+    query: AuditQuery,
+    format: ExportFormat,
+    writer: &mut W,
+) -> Result<u64> {
+    let mut query = query;
+    if query.filter().end_time.is_none() {
+        query = query.until(Utc::now());
+    }
+
+    let mut written = 0;
+    let mut page = 1;
+    loop {
+        let events = storage
+            .query(query.clone().page(page, EXPORT_PAGE_SIZE).into_filter())
+            .await?;
+        for event in &events {
+            writeln!(writer, "{}", format.format(event)?)?;
+        }
+        written += events.len() as u64;
+
+        if events.len() < EXPORT_PAGE_SIZE {
+            break;
+        }
+        page += 1;
+    }
+    writer.flush()?;
+
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file::{FileAuditStorage, RotationPolicy};
+    use crate::models::ResourceType;
+    use chrono::Duration as ChronoDuration;
+    use tempfile::NamedTempFile;
+
+    fn event(result: AuditResult) -> AuditEvent {
+        AuditEvent::new(
+            AuditEventType::ProviderCall,
+            "Provider called: openai|gpt".to_string(),
+            ResourceType::Provider,
+            "openai".to_string(),
+            result,
+        )
+        .with_user_id("alice".to_string())
+        .with_details(serde_json::json!({"workflow_id": "wf=1"}))
+    }
+
+    /// Records every batch it receives
+    #[derive(Default)]
+    struct RecordingSink {
+        batches: parking_lot::Mutex<Vec<Vec<AuditEvent>>>,
+    }
+
+    #[async_trait]
+    impl AuditSink for RecordingSink {
+        async fn send(&self, events: &[AuditEvent]) -> Result<()> {
+            self.batches.lock().push(events.to_vec());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_cef_format_escapes_fields() {
+        let line = ExportFormat::Cef
+            .format(&event(AuditResult::Failure("bad\nkey".to_string())))
+            .unwrap();
+
+        assert!(line.starts_with("CEF:0|LLM DevOps|llm-orchestrator|"));
+        assert!(line.contains("|provider_call|Provider called: openai\\|gpt|7|"));
+        assert!(line.contains("suser=alice"));
+        assert!(line.contains("cs4=wf\\=1"));
+        assert!(line.contains("reason=bad\\nkey"));
+        assert!(!line.contains('\n'));
+    }
+
+    #[tokio::test]
+    async fn test_syslog_udp_sink() {
+        let collector = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let sink = SyslogSink::new(collector.local_addr().unwrap().to_string(), SyslogTransport::Udp)
+            .with_hostname("host-1");
+
+        sink.send(&[event(AuditResult::Success)]).await.unwrap();
+
+        let mut buf = vec![0; 4096];
+        let len = collector.recv(&mut buf).await.unwrap();
+        let message = std::str::from_utf8(&buf[..len]).unwrap();
+        assert!(message.starts_with("<110>1 "));
+        assert!(message.contains(" host-1 llm-orchestrator - provider_call - {"));
+
+        let json = message.split(" - ").last().unwrap();
+        let parsed: AuditEvent = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed.user_id.as_deref(), Some("alice"));
+    }
+
+    #[tokio::test]
+    async fn test_webhook_retries_server_errors() {
+        let mut server = mockito::Server::new_async().await;
+        let failure = server
+            .mock("POST", "/ingest")
+            .with_status(503)
+            .expect(1)
+            .create_async()
+            .await;
+        let success = server
+            .mock("POST", "/ingest")
+            .match_header("authorization", "Bearer t0ken")
+            .match_header("content-type", "application/x-ndjson")
+            .with_status(200)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let sink = WebhookSink::new(format!("{}/ingest", server.url()))
+            .with_bearer_token("t0ken")
+            .with_max_batch_size(2)
+            .with_retry(2, Duration::from_millis(1));
+        let events: Vec<_> = (0..3).map(|_| event(AuditResult::Success)).collect();
+        sink.send(&events).await.unwrap();
+
+        failure.assert_async().await;
+        success.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_exporting_storage_batches_events() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let inner =
+            Arc::new(FileAuditStorage::new(temp_file.path().to_path_buf(), RotationPolicy::Never).unwrap());
+        let sink = Arc::new(RecordingSink::default());
+        let config = ExportConfig {
+            batch_size: 2,
+            flush_interval: Duration::from_secs(60),
+            ..ExportConfig::default()
+        };
+
+        let (storage, handle) = ExportingStorage::new(inner, sink.clone(), config);
+        for _ in 0..3 {
+            storage.store(&event(AuditResult::Success)).await.unwrap();
+        }
+        assert_eq!(storage.count(AuditFilter::new()).await.unwrap(), 3);
+
+        // Dropping the storage flushes the partial batch
+        drop(storage);
+        handle.await.unwrap();
+
+        let sizes: Vec<_> = sink.batches.lock().iter().map(Vec::len).collect();
+        assert_eq!(sizes, vec![2, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_export_events_time_range() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let storage =
+            FileAuditStorage::new(temp_file.path().to_path_buf(), RotationPolicy::Never).unwrap();
+
+        let start = Utc::now() - ChronoDuration::days(3);
+        for day in 0..3 {
+            let mut event = event(AuditResult::Success);
+            event.timestamp = start + ChronoDuration::days(day);
+            storage.store(&event).await.unwrap();
+        }
+
+        let mut output = Vec::new();
+        let query = AuditQuery::new().since(start + ChronoDuration::hours(12));
+        let written = export_events(&storage, query, ExportFormat::Json, &mut output)
+            .await
+            .unwrap();
+
+        assert_eq!(written, 2);
+        let lines: Vec<AuditEvent> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines.iter().all(|e| e.timestamp > start));
+    }
+}
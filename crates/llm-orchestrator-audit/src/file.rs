@@ -155,55 +155,11 @@ impl FileAuditStorage {
     fn filter_events(&self, events: Vec<AuditEvent>, filter: &AuditFilter) -> Vec<AuditEvent> {
         let mut filtered: Vec<AuditEvent> = events
             .into_iter()
-            .filter(|event| {
-                if let Some(ref user_id) = filter.user_id {
-                    if event.user_id.as_ref() != Some(user_id) {
-                        return false;
-                    }
-                }
-
-                if let Some(ref event_type) = filter.event_type {
-                    if &event.event_type != event_type {
-                        return false;
-                    }
-                }
-
-                if let Some(ref resource_type) = filter.resource_type {
-                    if &event.resource_type != resource_type {
-                        return false;
-                    }
-                }
-
-                if let Some(ref resource_id) = filter.resource_id {
-                    if &event.resource_id != resource_id {
-                        return false;
-                    }
-                }
-
-                if let Some(start_time) = filter.start_time {
-                    if event.timestamp < start_time {
-                        return false;
-                    }
-                }
-
-                if let Some(end_time) = filter.end_time {
-                    if event.timestamp > end_time {
-                        return false;
-                    }
-                }
-
-                if let Some(ref result) = filter.result {
-                    if event.result.as_str() != result.as_str() {
-                        return false;
-                    }
-                }
-
-                true
-            })
+            .filter(|event| filter.matches(event))
             .collect();
 
         // Sort by timestamp descending
-        filtered.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        filtered.sort_by_key(|e| std::cmp::Reverse(e.timestamp));
 
         // Apply offset and limit
         filtered
@@ -286,8 +242,7 @@ impl AuditStorage for FileAuditStorage {
 
     async fn count(&self, filter: AuditFilter) -> Result<u64> {
         let events = self.read_events()?;
-        let filtered = self.filter_events(events, &filter);
-        Ok(filtered.len() as u64)
+        Ok(events.iter().filter(|event| filter.matches(event)).count() as u64)
     }
 
     async fn health_check(&self) -> Result<()> {
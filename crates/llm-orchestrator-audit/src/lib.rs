@@ -6,19 +6,35 @@
 //! This crate provides comprehensive audit logging capabilities,
 //! including event tracking, storage backends, and compliance features.
 
+pub mod archive;
 pub mod database;
+pub mod export;
 pub mod file;
 pub mod logger;
 pub mod models;
+pub mod query;
 pub mod retention;
+#[cfg(feature = "s3")]
+pub mod s3;
 pub mod storage;
+pub mod tenant;
 
 // Re-export commonly used types
+pub use archive::{ArchiveReport, ArchiveStore, AuditArchive, LocalArchiveStore};
+pub use export::{
+    export_events, AuditSink, ExportConfig, ExportFormat, ExportingStorage, SyslogSink,
+    SyslogTransport, WebhookSink,
+};
 pub use file::{FileAuditStorage, RotationPolicy};
 pub use logger::AuditLogger;
 pub use models::{AuditEvent, AuditEventType, AuditFilter, AuditResult, ResourceType};
+pub use query::{AuditPage, AuditQuery};
 pub use retention::AuditRetentionManager;
 pub use storage::{AuditStorage, Result, StorageError};
+pub use tenant::TenantAuditStorage;
 
 #[cfg(feature = "database")]
 pub use database::DatabaseAuditStorage;
+
+#[cfg(feature = "s3")]
+pub use s3::S3ArchiveStore;
@@ -1,6 +1,7 @@
 use crate::models::{AuditEvent, AuditEventType, AuditResult, ResourceType};
 use crate::storage::{AuditStorage, AuditStorageRef, Result};
 use chrono::{DateTime, Utc};
+use llm_orchestrator_secrets::Redactor;
 use parking_lot::RwLock;
 use std::sync::Arc;
 use std::time::Duration;
@@ -10,6 +11,8 @@ pub struct AuditLogger {
     storage: AuditStorageRef,
     enabled: bool,
     previous_hash: Arc<RwLock<Option<String>>>,
+    redactor: Option<Redactor>,
+    tenant_id: Option<String>,
 }
 
 impl AuditLogger {
@@ -19,15 +22,31 @@ impl AuditLogger {
             storage,
             enabled: true,
             previous_hash: Arc::new(RwLock::new(None)),
+            redactor: None,
+            tenant_id: None,
         }
     }
 
+    /// Mask secrets in event actions, details, and failure messages before storing
+    pub fn with_redactor(mut self, redactor: Redactor) -> Self {
+        self.redactor = Some(redactor);
+        self
+    }
+
+    /// Record events without a tenant as belonging to `tenant_id`
+    pub fn with_tenant_id(mut self, tenant_id: impl Into<String>) -> Self {
+        self.tenant_id = Some(tenant_id.into());
+        self
+    }
+
     /// Create a disabled audit logger (for testing)
     pub fn disabled() -> Self {
         Self {
             storage: Arc::new(NoOpStorage),
             enabled: false,
             previous_hash: Arc::new(RwLock::new(None)),
+            redactor: None,
+            tenant_id: None,
         }
     }
 
@@ -334,12 +353,63 @@ impl AuditLogger {
         self.log_event(event).await
     }
 
+    /// Log an LLM provider call
+    ///
+    /// `details` is extended with the model and duration; pass an object
+    /// with any further context such as the step ID or token usage.
+    pub async fn log_provider_call(
+        &self,
+        provider: &str,
+        model: &str,
+        user_id: &str,
+        result: AuditResult,
+        duration: Duration,
+        mut details: serde_json::Value,
+    ) -> Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+
+        if let Some(object) = details.as_object_mut() {
+            object.insert("model".to_string(), serde_json::json!(model));
+            object.insert(
+                "duration_ms".to_string(),
+                serde_json::json!(duration.as_millis() as u64),
+            );
+        }
+
+        let event = AuditEvent::new(
+            AuditEventType::ProviderCall,
+            format!("Provider called: {}", provider),
+            ResourceType::Provider,
+            provider.to_string(),
+            result,
+        )
+        .with_user_id(user_id.to_string())
+        .with_details(details);
+
+        self.log_event(event).await
+    }
+
     /// Log a generic audit event
     pub async fn log_event(&self, mut event: AuditEvent) -> Result<()> {
         if !self.enabled {
             return Ok(());
         }
 
+        if event.tenant_id.is_none() {
+            event.tenant_id = self.tenant_id.clone();
+        }
+
+        // Redact before hashing so the chain covers the stored event
+        if let Some(redactor) = &self.redactor {
+            event.action = redactor.redact(&event.action).into_owned();
+            redactor.redact_json(&mut event.details);
+            if let AuditResult::Failure(message) = &mut event.result {
+                *message = redactor.redact(message).into_owned();
+            }
+        }
+
         // Add hash chain for tamper detection
         let prev_hash = self.previous_hash.read().clone();
         event.previous_hash = prev_hash;
@@ -497,4 +567,31 @@ mod tests {
         assert_eq!(events[0].event_type, AuditEventType::SecretAccess);
         assert_eq!(events[0].resource_type, ResourceType::Secret);
     }
+
+    #[tokio::test]
+    async fn test_audit_logger_redacts_secrets() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let storage = Arc::new(
+            FileAuditStorage::new(temp_file.path().to_path_buf(), RotationPolicy::Never).unwrap(),
+        );
+        let redactor = Redactor::new();
+        redactor.add_value("db-password-123");
+        let logger = AuditLogger::new(storage.clone()).with_redactor(redactor);
+
+        logger
+            .log_step_execution(
+                "workflow-123",
+                "step-1",
+                "user-456",
+                AuditResult::Failure("connect failed with db-password-123".to_string()),
+                Duration::from_millis(20),
+            )
+            .await
+            .unwrap();
+
+        let events = storage.query(crate::models::AuditFilter::new()).await.unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].result.error_message(), Some("connect failed with [REDACTED]"));
+        assert_eq!(events[0].event_hash, Some(events[0].compute_hash()));
+    }
 }
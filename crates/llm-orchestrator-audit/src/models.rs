@@ -1,5 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 use uuid::Uuid;
 
 /// Represents an audit event in the system
@@ -17,6 +18,10 @@ pub struct AuditEvent {
     /// ID of the user who performed the action (if applicable)
     pub user_id: Option<String>,
 
+    /// Tenant the event belongs to (if applicable)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant_id: Option<String>,
+
     /// Human-readable description of the action
     pub action: String,
 
@@ -62,6 +67,7 @@ impl AuditEvent {
             timestamp: Utc::now(),
             event_type,
             user_id: None,
+            tenant_id: None,
             action,
             resource_type,
             resource_id,
@@ -81,6 +87,12 @@ impl AuditEvent {
         self
     }
 
+    /// Set the tenant for this event
+    pub fn with_tenant_id(mut self, tenant_id: String) -> Self {
+        self.tenant_id = Some(tenant_id);
+        self
+    }
+
     /// Set additional details for this event
     pub fn with_details(mut self, details: serde_json::Value) -> Self {
         self.details = details;
@@ -105,11 +117,23 @@ impl AuditEvent {
         self
     }
 
+    /// Get the workflow this event belongs to, if any
+    ///
+    /// Workflow events name the workflow as their resource; step, provider,
+    /// and secret events carry it as `workflow_id` in their details.
+    pub fn workflow_id(&self) -> Option<&str> {
+        if self.resource_type == ResourceType::Workflow {
+            Some(&self.resource_id)
+        } else {
+            self.details.get("workflow_id").and_then(|id| id.as_str())
+        }
+    }
+
     /// Compute the hash of this audit event for tamper detection
     pub fn compute_hash(&self) -> String {
         use sha2::{Digest, Sha256};
 
-        let data = format!(
+        let mut data = format!(
             "{}|{}|{}|{}|{}|{}|{}|{}",
             self.id,
             self.timestamp.to_rfc3339(),
@@ -120,6 +144,11 @@ impl AuditEvent {
             self.result.as_str(),
             self.previous_hash.as_deref().unwrap_or(""),
         );
+        // Appended only when set, so hashes of events without a tenant are unchanged
+        if let Some(tenant_id) = &self.tenant_id {
+            data.push('|');
+            data.push_str(tenant_id);
+        }
 
         let mut hasher = Sha256::new();
         hasher.update(data.as_bytes());
@@ -163,6 +192,12 @@ pub enum AuditEventType {
     /// Step execution
     StepExecution,
 
+    /// LLM provider call
+    ProviderCall,
+
+    /// Guardrail flagged or blocked content
+    GuardrailViolation,
+
     /// System event
     SystemEvent,
 }
@@ -182,11 +217,37 @@ impl AuditEventType {
             Self::ApiKeyCreate => "api_key_create",
             Self::ApiKeyRevoke => "api_key_revoke",
             Self::StepExecution => "step_execution",
+            Self::ProviderCall => "provider_call",
+            Self::GuardrailViolation => "guardrail_violation",
             Self::SystemEvent => "system_event",
         }
     }
 }
 
+impl FromStr for AuditEventType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "authentication" => Ok(Self::Authentication),
+            "authorization" => Ok(Self::Authorization),
+            "workflow_execution" => Ok(Self::WorkflowExecution),
+            "workflow_create" => Ok(Self::WorkflowCreate),
+            "workflow_update" => Ok(Self::WorkflowUpdate),
+            "workflow_delete" => Ok(Self::WorkflowDelete),
+            "secret_access" => Ok(Self::SecretAccess),
+            "config_change" => Ok(Self::ConfigChange),
+            "api_key_create" => Ok(Self::ApiKeyCreate),
+            "api_key_revoke" => Ok(Self::ApiKeyRevoke),
+            "step_execution" => Ok(Self::StepExecution),
+            "provider_call" => Ok(Self::ProviderCall),
+            "guardrail_violation" => Ok(Self::GuardrailViolation),
+            "system_event" => Ok(Self::SystemEvent),
+            _ => Err(format!("Unknown audit event type: {}", s)),
+        }
+    }
+}
+
 /// Type of resource affected by the audit event
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum ResourceType {
@@ -208,6 +269,9 @@ pub enum ResourceType {
     /// Step resource
     Step,
 
+    /// LLM provider resource
+    Provider,
+
     /// System resource
     System,
 }
@@ -222,11 +286,30 @@ impl ResourceType {
             Self::Secret => "secret",
             Self::Configuration => "configuration",
             Self::Step => "step",
+            Self::Provider => "provider",
             Self::System => "system",
         }
     }
 }
 
+impl FromStr for ResourceType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "workflow" => Ok(Self::Workflow),
+            "user" => Ok(Self::User),
+            "api_key" => Ok(Self::ApiKey),
+            "secret" => Ok(Self::Secret),
+            "configuration" => Ok(Self::Configuration),
+            "step" => Ok(Self::Step),
+            "provider" => Ok(Self::Provider),
+            "system" => Ok(Self::System),
+            _ => Err(format!("Unknown resource type: {}", s)),
+        }
+    }
+}
+
 /// Result of an audit action
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum AuditResult {
@@ -264,12 +347,29 @@ impl AuditResult {
     }
 }
 
+impl FromStr for AuditResult {
+    type Err = String;
+
+    /// Parse a result kind; failures parse with an empty message
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "success" => Ok(Self::Success),
+            "failure" => Ok(Self::Failure(String::new())),
+            "partial_success" => Ok(Self::PartialSuccess),
+            _ => Err(format!("Unknown audit result: {}", s)),
+        }
+    }
+}
+
 /// Filter for querying audit events
 #[derive(Debug, Clone, Default)]
 pub struct AuditFilter {
     /// Filter by user ID
     pub user_id: Option<String>,
 
+    /// Filter by tenant ID
+    pub tenant_id: Option<String>,
+
     /// Filter by event type
     pub event_type: Option<AuditEventType>,
 
@@ -279,6 +379,9 @@ pub struct AuditFilter {
     /// Filter by resource ID
     pub resource_id: Option<String>,
 
+    /// Filter by workflow ID (see [`AuditEvent::workflow_id`])
+    pub workflow_id: Option<String>,
+
     /// Filter by start time (inclusive)
     pub start_time: Option<DateTime<Utc>>,
 
@@ -310,6 +413,12 @@ impl AuditFilter {
         self
     }
 
+    /// Set the tenant ID filter
+    pub fn with_tenant_id(mut self, tenant_id: String) -> Self {
+        self.tenant_id = Some(tenant_id);
+        self
+    }
+
     /// Set the event type filter
     pub fn with_event_type(mut self, event_type: AuditEventType) -> Self {
         self.event_type = Some(event_type);
@@ -328,6 +437,12 @@ impl AuditFilter {
         self
     }
 
+    /// Set the workflow ID filter
+    pub fn with_workflow_id(mut self, workflow_id: String) -> Self {
+        self.workflow_id = Some(workflow_id);
+        self
+    }
+
     /// Set the time range filter
     pub fn with_time_range(mut self, start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
         self.start_time = Some(start);
@@ -352,6 +467,65 @@ impl AuditFilter {
         self.offset = offset;
         self
     }
+
+    /// Check if an event matches this filter, ignoring limit and offset
+    pub fn matches(&self, event: &AuditEvent) -> bool {
+        if let Some(ref user_id) = self.user_id {
+            if event.user_id.as_ref() != Some(user_id) {
+                return false;
+            }
+        }
+
+        if let Some(ref tenant_id) = self.tenant_id {
+            if event.tenant_id.as_ref() != Some(tenant_id) {
+                return false;
+            }
+        }
+
+        if let Some(ref event_type) = self.event_type {
+            if &event.event_type != event_type {
+                return false;
+            }
+        }
+
+        if let Some(ref resource_type) = self.resource_type {
+            if &event.resource_type != resource_type {
+                return false;
+            }
+        }
+
+        if let Some(ref resource_id) = self.resource_id {
+            if &event.resource_id != resource_id {
+                return false;
+            }
+        }
+
+        if let Some(ref workflow_id) = self.workflow_id {
+            if event.workflow_id() != Some(workflow_id.as_str()) {
+                return false;
+            }
+        }
+
+        if let Some(start_time) = self.start_time {
+            if event.timestamp < start_time {
+                return false;
+            }
+        }
+
+        if let Some(end_time) = self.end_time {
+            if event.timestamp > end_time {
+                return false;
+            }
+        }
+
+        if let Some(ref result) = self.result {
+            if event.result.as_str() != result.as_str() {
+                return false;
+            }
+        }
+
+        true
+    }
 }
 
 #[cfg(test)]
@@ -433,4 +607,43 @@ mod tests {
         assert_eq!(filter.event_type, Some(AuditEventType::WorkflowExecution));
         assert_eq!(filter.limit, 50);
     }
+
+    #[test]
+    fn test_audit_filter_matches_workflow_id() {
+        let workflow_event = AuditEvent::new(
+            AuditEventType::WorkflowExecution,
+            "Workflow executed".to_string(),
+            ResourceType::Workflow,
+            "wf-1".to_string(),
+            AuditResult::Success,
+        );
+        let step_event = AuditEvent::new(
+            AuditEventType::StepExecution,
+            "Step executed: ask".to_string(),
+            ResourceType::Step,
+            "ask".to_string(),
+            AuditResult::Failure("boom".to_string()),
+        )
+        .with_details(serde_json::json!({ "workflow_id": "wf-1" }));
+
+        let filter = AuditFilter::new().with_workflow_id("wf-1".to_string());
+        assert!(filter.matches(&workflow_event));
+        assert!(filter.matches(&step_event));
+        assert!(!AuditFilter::new()
+            .with_workflow_id("wf-2".to_string())
+            .matches(&step_event));
+
+        let failures = AuditFilter::new().with_result("failure".parse().unwrap());
+        assert!(failures.matches(&step_event));
+        assert!(!failures.matches(&workflow_event));
+    }
+
+    #[test]
+    fn test_parse_event_type() {
+        assert_eq!(
+            "provider_call".parse::<AuditEventType>().unwrap(),
+            AuditEventType::ProviderCall
+        );
+        assert!("nope".parse::<AuditEventType>().is_err());
+    }
 }
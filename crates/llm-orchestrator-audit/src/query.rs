@@ -0,0 +1,228 @@
+use crate::models::{AuditEvent, AuditEventType, AuditFilter, AuditResult, ResourceType};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// Typed builder for audit searches
+///
+/// Build a query and pass it to [`AuditStorage::search`](crate::AuditStorage::search)
+/// to get one page of matching events, newest first, with the total count.
+///
+/// ```no_run
+/// # async fn example(storage: &dyn llm_orchestrator_audit::AuditStorage) -> llm_orchestrator_audit::Result<()> {
+/// use chrono::{TimeZone, Utc};
+/// use llm_orchestrator_audit::{AuditEventType, AuditQuery};
+///
+/// let query = AuditQuery::new()
+///     .between(
+///         Utc.with_ymd_and_hms(2025, 6, 10, 0, 0, 0).unwrap(),
+///         Utc.with_ymd_and_hms(2025, 6, 10, 23, 59, 59).unwrap(),
+///     )
+///     .event_type(AuditEventType::ProviderCall)
+///     .page(1, 50);
+/// let page = storage.search(query).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct AuditQuery {
+    filter: AuditFilter,
+}
+
+impl Default for AuditQuery {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AuditQuery {
+    /// Create a query matching every event, returning the first 100
+    pub fn new() -> Self {
+        Self {
+            filter: AuditFilter::new(),
+        }
+    }
+
+    /// Only events at or after `start`
+    pub fn since(mut self, start: DateTime<Utc>) -> Self {
+        self.filter.start_time = Some(start);
+        self
+    }
+
+    /// Only events at or before `end`
+    pub fn until(mut self, end: DateTime<Utc>) -> Self {
+        self.filter.end_time = Some(end);
+        self
+    }
+
+    /// Only events between `start` and `end` (inclusive)
+    pub fn between(self, start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        self.since(start).until(end)
+    }
+
+    /// Only events of one type
+    pub fn event_type(mut self, event_type: AuditEventType) -> Self {
+        self.filter.event_type = Some(event_type);
+        self
+    }
+
+    /// Only events belonging to one workflow
+    pub fn workflow_id(mut self, workflow_id: impl Into<String>) -> Self {
+        self.filter.workflow_id = Some(workflow_id.into());
+        self
+    }
+
+    /// Only events belonging to one tenant
+    pub fn tenant_id(mut self, tenant_id: impl Into<String>) -> Self {
+        self.filter.tenant_id = Some(tenant_id.into());
+        self
+    }
+
+    /// Only events attributed to one user
+    pub fn user_id(mut self, user_id: impl Into<String>) -> Self {
+        self.filter.user_id = Some(user_id.into());
+        self
+    }
+
+    /// Only events with this result kind (failure messages are ignored)
+    pub fn result(mut self, result: AuditResult) -> Self {
+        self.filter.result = Some(result);
+        self
+    }
+
+    /// Only events affecting one resource type
+    pub fn resource_type(mut self, resource_type: ResourceType) -> Self {
+        self.filter.resource_type = Some(resource_type);
+        self
+    }
+
+    /// Only events affecting one resource
+    pub fn resource_id(mut self, resource_id: impl Into<String>) -> Self {
+        self.filter.resource_id = Some(resource_id.into());
+        self
+    }
+
+    /// Maximum number of events per page
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.filter.limit = limit;
+        self
+    }
+
+    /// Number of matching events to skip
+    pub fn offset(mut self, offset: usize) -> Self {
+        self.filter.offset = offset;
+        self
+    }
+
+    /// Select a page of `page_size` events; pages are numbered from 1
+    pub fn page(self, page: usize, page_size: usize) -> Self {
+        self.limit(page_size)
+            .offset(page.saturating_sub(1) * page_size)
+    }
+
+    /// Get the underlying filter
+    pub fn filter(&self) -> &AuditFilter {
+        &self.filter
+    }
+
+    /// Convert into the underlying filter
+    pub fn into_filter(self) -> AuditFilter {
+        self.filter
+    }
+}
+
+impl From<AuditQuery> for AuditFilter {
+    fn from(query: AuditQuery) -> Self {
+        query.into_filter()
+    }
+}
+
+/// One page of audit search results
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditPage {
+    /// Matching events on this page, newest first
+    pub events: Vec<AuditEvent>,
+
+    /// Total number of matching events across all pages
+    pub total: u64,
+
+    /// Offset of the first event on this page
+    pub offset: usize,
+
+    /// Maximum number of events per page
+    pub limit: usize,
+}
+
+impl AuditPage {
+    /// Check if more matching events follow this page
+    pub fn has_more(&self) -> bool {
+        ((self.offset + self.events.len()) as u64) < self.total
+    }
+
+    /// Get the offset of the next page, if any
+    pub fn next_offset(&self) -> Option<usize> {
+        self.has_more().then_some(self.offset + self.events.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file::{FileAuditStorage, RotationPolicy};
+    use crate::storage::AuditStorage;
+    use chrono::Duration;
+    use tempfile::NamedTempFile;
+
+    fn provider_call(user_id: &str, workflow_id: &str, timestamp: DateTime<Utc>) -> AuditEvent {
+        let mut event = AuditEvent::new(
+            AuditEventType::ProviderCall,
+            "Provider called: openai".to_string(),
+            ResourceType::Provider,
+            "openai".to_string(),
+            AuditResult::Success,
+        )
+        .with_user_id(user_id.to_string())
+        .with_details(serde_json::json!({
+            "workflow_id": workflow_id,
+            "model": "gpt-4",
+        }));
+        event.timestamp = timestamp;
+        event
+    }
+
+    #[tokio::test]
+    async fn test_search_filters_and_paginates() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let storage =
+            FileAuditStorage::new(temp_file.path().to_path_buf(), RotationPolicy::Never).unwrap();
+
+        let tuesday = Utc::now() - Duration::days(7);
+        for hour in 0..5 {
+            let event = provider_call("alice", "wf-1", tuesday + Duration::hours(hour));
+            storage.store(&event).await.unwrap();
+        }
+        storage
+            .store(&provider_call("bob", "wf-1", tuesday))
+            .await
+            .unwrap();
+        storage
+            .store(&provider_call("alice", "wf-2", Utc::now()))
+            .await
+            .unwrap();
+
+        let query = AuditQuery::new()
+            .between(tuesday - Duration::hours(1), tuesday + Duration::hours(12))
+            .user_id("alice")
+            .workflow_id("wf-1")
+            .event_type(AuditEventType::ProviderCall);
+
+        let first = storage.search(query.clone().page(1, 2)).await.unwrap();
+        assert_eq!(first.total, 5);
+        assert_eq!(first.events.len(), 2);
+        assert_eq!(first.next_offset(), Some(2));
+        assert!(first.events[0].timestamp > first.events[1].timestamp);
+
+        let last = storage.search(query.page(3, 2)).await.unwrap();
+        assert_eq!(last.events.len(), 1);
+        assert!(!last.has_more());
+    }
+}
@@ -1,12 +1,19 @@
+use crate::archive::AuditArchive;
 use crate::storage::{AuditStorageRef, Result};
 use chrono::{DateTime, Duration, Utc};
 use std::sync::Arc;
 use tokio::time;
 
 /// Manages retention policy for audit events
+///
+/// Without an archive, events older than the retention period are deleted.
+/// With one, events move through two tiers: they stay in storage for the
+/// archive period, are then moved to the archive, and archive objects are
+/// deleted once older than the retention period.
 pub struct AuditRetentionManager {
     storage: AuditStorageRef,
     retention_days: u32,
+    archive: Option<(Arc<AuditArchive>, u32)>,
 }
 
 impl AuditRetentionManager {
@@ -15,12 +22,23 @@ impl AuditRetentionManager {
         Self {
             storage,
             retention_days,
+            archive: None,
         }
     }
 
+    /// Move events older than `archive_after_days` to `archive` instead of deleting them
+    pub fn with_archive(mut self, archive: Arc<AuditArchive>, archive_after_days: u32) -> Self {
+        self.archive = Some((archive, archive_after_days));
+        self
+    }
+
     /// Run cleanup of old audit events
-    /// Returns the number of events deleted
+    /// Returns the number of events removed from storage
     pub async fn cleanup(&self) -> Result<u64> {
+        if let Some((archive, archive_after_days)) = &self.archive {
+            return self.archive_and_expire(archive, *archive_after_days).await;
+        }
+
         let cutoff = Utc::now() - Duration::days(self.retention_days as i64);
 
         tracing::info!(
@@ -39,6 +57,24 @@ impl AuditRetentionManager {
         Ok(deleted)
     }
 
+    /// Archive events past the storage tier, then expire old archive objects
+    async fn archive_and_expire(&self, archive: &AuditArchive, archive_after_days: u32) -> Result<u64> {
+        let archive_cutoff = Utc::now() - Duration::days(archive_after_days as i64);
+
+        // Only delete from storage once the events are safely archived
+        archive.archive_before(self.storage.as_ref(), archive_cutoff).await?;
+        let deleted = self.storage.delete_older_than(archive_cutoff).await?;
+        let expired = archive.expire_before(self.cutoff_date()).await?;
+
+        tracing::info!(
+            deleted_count = deleted,
+            expired_objects = expired,
+            "Audit log archival completed"
+        );
+
+        Ok(deleted)
+    }
+
     /// Start background cleanup task
     /// Returns a handle that can be used to cancel the task
     pub fn start_background_cleanup(
@@ -72,13 +108,20 @@ impl AuditRetentionManager {
     pub fn cutoff_date(&self) -> DateTime<Utc> {
         Utc::now() - Duration::days(self.retention_days as i64)
     }
+
+    /// Calculate the cutoff date for archival, if an archive is configured
+    pub fn archive_cutoff_date(&self) -> Option<DateTime<Utc>> {
+        self.archive
+            .as_ref()
+            .map(|(_, days)| Utc::now() - Duration::days(*days as i64))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::archive::LocalArchiveStore;
     use crate::file::{FileAuditStorage, RotationPolicy};
-    
     use crate::models::{AuditEventType, AuditFilter, AuditResult, ResourceType};
     use crate::models::AuditEvent;
     use std::time::Duration as StdDuration;
@@ -128,6 +171,44 @@ mod tests {
         assert_eq!(events[0].resource_id, "workflow-recent");
     }
 
+    #[tokio::test]
+    async fn test_retention_cleanup_with_archive() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let storage: AuditStorageRef = Arc::new(
+            FileAuditStorage::new(temp_file.path().to_path_buf(), RotationPolicy::Never).unwrap(),
+        );
+        let archive_dir = tempfile::tempdir().unwrap();
+        let archive = Arc::new(AuditArchive::new(Arc::new(LocalArchiveStore::new(
+            archive_dir.path(),
+        ))));
+
+        // One event per tier: in storage, archived, and expired
+        for days in [1, 10, 100] {
+            let mut event = AuditEvent::new(
+                AuditEventType::WorkflowExecution,
+                format!("Workflow {} days ago", days),
+                ResourceType::Workflow,
+                format!("workflow-{}", days),
+                AuditResult::Success,
+            );
+            event.timestamp = Utc::now() - Duration::days(days);
+            storage.store(&event).await.unwrap();
+        }
+
+        let manager =
+            AuditRetentionManager::new(storage.clone(), 30).with_archive(archive.clone(), 7);
+        let deleted = manager.cleanup().await.unwrap();
+        assert_eq!(deleted, 2);
+
+        let events = storage.query(AuditFilter::new()).await.unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].resource_id, "workflow-1");
+
+        let archived = archive.query(&AuditFilter::new()).await.unwrap();
+        assert_eq!(archived.len(), 1);
+        assert_eq!(archived[0].resource_id, "workflow-10");
+    }
+
     #[tokio::test]
     async fn test_retention_cutoff_date() {
         let temp_file = NamedTempFile::new().unwrap();
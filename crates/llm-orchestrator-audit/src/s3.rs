@@ -0,0 +1,115 @@
+//! S3-compatible archive store (requires the `s3` feature)
+
+use crate::archive::ArchiveStore;
+use crate::storage::{Result, StorageError};
+use async_trait::async_trait;
+use aws_config::BehaviorVersion;
+use aws_sdk_s3::error::DisplayErrorContext;
+use aws_sdk_s3::primitives::ByteStream;
+use aws_sdk_s3::Client;
+
+/// Archive store keeping objects in an S3 bucket
+///
+/// Works with any S3-compatible service (MinIO, Ceph, R2) through
+/// [`with_endpoint`](Self::with_endpoint).
+pub struct S3ArchiveStore {
+    client: Client,
+    bucket: String,
+}
+
+impl S3ArchiveStore {
+    /// Create a store for `bucket` using credentials and region from the environment
+    pub async fn new(bucket: impl Into<String>) -> Self {
+        let config = aws_config::load_defaults(BehaviorVersion::latest()).await;
+        Self::with_client(Client::new(&config), bucket)
+    }
+
+    /// Create a store for `bucket` on an S3-compatible endpoint, using path-style addressing
+    pub async fn with_endpoint(bucket: impl Into<String>, endpoint_url: impl Into<String>) -> Self {
+        let config = aws_config::load_defaults(BehaviorVersion::latest()).await;
+        let s3_config = aws_sdk_s3::config::Builder::from(&config)
+            .endpoint_url(endpoint_url)
+            .force_path_style(true)
+            .build();
+        Self::with_client(Client::from_conf(s3_config), bucket)
+    }
+
+    /// Create a store from a configured client
+    pub fn with_client(client: Client, bucket: impl Into<String>) -> Self {
+        Self {
+            client,
+            bucket: bucket.into(),
+        }
+    }
+}
+
+fn s3_error(operation: &str, key: &str, error: impl std::error::Error) -> StorageError {
+    StorageError::ConnectionError(format!(
+        "S3 {} failed for {}: {}",
+        operation,
+        key,
+        DisplayErrorContext(error)
+    ))
+}
+
+#[async_trait]
+impl ArchiveStore for S3ArchiveStore {
+    async fn put(&self, key: &str, body: Vec<u8>) -> Result<()> {
+        self.client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .content_type("application/gzip")
+            .body(ByteStream::from(body))
+            .send()
+            .await
+            .map_err(|e| s3_error("put", key, e))?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>> {
+        let object = self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await
+            .map_err(|e| s3_error("get", key, e))?;
+        let body = object
+            .body
+            .collect()
+            .await
+            .map_err(|e| s3_error("get", key, e))?;
+        Ok(body.into_bytes().to_vec())
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        let mut pages = self
+            .client
+            .list_objects_v2()
+            .bucket(&self.bucket)
+            .prefix(prefix)
+            .into_paginator()
+            .send();
+
+        let mut keys = Vec::new();
+        while let Some(page) = pages.next().await {
+            let page = page.map_err(|e| s3_error("list", prefix, e))?;
+            keys.extend(page.contents().iter().filter_map(|o| o.key().map(str::to_string)));
+        }
+        keys.sort();
+        Ok(keys)
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        self.client
+            .delete_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await
+            .map_err(|e| s3_error("delete", key, e))?;
+        Ok(())
+    }
+}
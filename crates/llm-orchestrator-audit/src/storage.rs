@@ -1,4 +1,5 @@
 use crate::models::{AuditEvent, AuditFilter};
+use crate::query::{AuditPage, AuditQuery};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use std::sync::Arc;
@@ -31,6 +32,10 @@ pub enum StorageError {
     /// Connection error
     #[error("Connection error: {0}")]
     ConnectionError(String),
+
+    /// Access to another tenant's events
+    #[error("Tenant violation: {0}")]
+    TenantViolation(String),
 }
 
 pub type Result<T> = std::result::Result<T, StorageError>;
@@ -54,6 +59,20 @@ pub trait AuditStorage: Send + Sync {
     /// Count audit events matching the filter
     async fn count(&self, filter: AuditFilter) -> Result<u64>;
 
+    /// Run a query and return one page of matching events with the total count
+    async fn search(&self, query: AuditQuery) -> Result<AuditPage> {
+        let filter = query.into_filter();
+        let total = self.count(filter.clone()).await?;
+        let events = self.query(filter.clone()).await?;
+
+        Ok(AuditPage {
+            events,
+            total,
+            offset: filter.offset,
+            limit: filter.limit,
+        })
+    }
+
     /// Check if the storage backend is healthy
     async fn health_check(&self) -> Result<()>;
 }
@@ -0,0 +1,132 @@
+use crate::models::{AuditEvent, AuditFilter};
+use crate::storage::{AuditStorage, AuditStorageRef, Result, StorageError};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+/// Storage wrapper confining reads and writes to one tenant
+///
+/// Queries and counts are always filtered by the tenant, whatever filter the
+/// caller passes; events of other tenants are not returned by
+/// [`get`](AuditStorage::get); and storing an event of another tenant, or
+/// without a tenant, fails. Retention spans all tenants and is rejected here;
+/// run it against the unscoped storage.
+pub struct TenantAuditStorage {
+    inner: AuditStorageRef,
+    tenant_id: String,
+}
+
+impl TenantAuditStorage {
+    /// Confine `inner` to the events of `tenant_id`
+    pub fn new(inner: AuditStorageRef, tenant_id: impl Into<String>) -> Self {
+        Self {
+            inner,
+            tenant_id: tenant_id.into(),
+        }
+    }
+
+    /// Get the tenant this storage is confined to
+    pub fn tenant_id(&self) -> &str {
+        &self.tenant_id
+    }
+
+    fn scope(&self, mut filter: AuditFilter) -> AuditFilter {
+        filter.tenant_id = Some(self.tenant_id.clone());
+        filter
+    }
+}
+
+#[async_trait]
+impl AuditStorage for TenantAuditStorage {
+    async fn store(&self, event: &AuditEvent) -> Result<()> {
+        if event.tenant_id.as_deref() != Some(self.tenant_id.as_str()) {
+            return Err(StorageError::TenantViolation(format!(
+                "Event {} does not belong to tenant {}",
+                event.id, self.tenant_id
+            )));
+        }
+        self.inner.store(event).await
+    }
+
+    async fn query(&self, filter: AuditFilter) -> Result<Vec<AuditEvent>> {
+        self.inner.query(self.scope(filter)).await
+    }
+
+    async fn get(&self, id: Uuid) -> Result<Option<AuditEvent>> {
+        Ok(self
+            .inner
+            .get(id)
+            .await?
+            .filter(|event| event.tenant_id.as_deref() == Some(self.tenant_id.as_str())))
+    }
+
+    async fn delete_older_than(&self, _cutoff: DateTime<Utc>) -> Result<u64> {
+        Err(StorageError::TenantViolation(format!(
+            "Retention applies to all tenants and cannot run for tenant {}",
+            self.tenant_id
+        )))
+    }
+
+    async fn count(&self, filter: AuditFilter) -> Result<u64> {
+        self.inner.count(self.scope(filter)).await
+    }
+
+    async fn health_check(&self) -> Result<()> {
+        self.inner.health_check().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file::{FileAuditStorage, RotationPolicy};
+    use crate::logger::AuditLogger;
+    use crate::models::AuditResult;
+    use crate::query::AuditQuery;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_storage_is_confined_to_tenant() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage: AuditStorageRef = Arc::new(
+            FileAuditStorage::new(dir.path().join("audit.log"), RotationPolicy::Never).unwrap(),
+        );
+
+        AuditLogger::new(storage.clone())
+            .with_tenant_id("acme")
+            .log_workflow_execution("wf-1", "user-1", AuditResult::Success, Duration::from_millis(5))
+            .await
+            .unwrap();
+        AuditLogger::new(storage.clone())
+            .with_tenant_id("globex")
+            .log_workflow_execution("wf-2", "user-2", AuditResult::Success, Duration::from_millis(5))
+            .await
+            .unwrap();
+
+        let acme = TenantAuditStorage::new(storage.clone(), "acme");
+        let page = acme.search(AuditQuery::new()).await.unwrap();
+        assert_eq!(page.total, 1);
+        assert_eq!(page.events[0].resource_id, "wf-1");
+        assert_eq!(page.events[0].event_hash, Some(page.events[0].compute_hash()));
+
+        // Filters naming another tenant are overridden
+        let globex_event = storage.query(AuditFilter::new().with_tenant_id("globex".to_string())).await.unwrap();
+        assert_eq!(globex_event.len(), 1);
+        assert!(acme
+            .query(AuditFilter::new().with_tenant_id("globex".to_string()))
+            .await
+            .unwrap()
+            .iter()
+            .all(|e| e.tenant_id.as_deref() == Some("acme")));
+        assert!(acme.get(globex_event[0].id).await.unwrap().is_none());
+
+        // Writes must carry the tenant
+        let unscoped = AuditLogger::new(Arc::new(TenantAuditStorage::new(storage.clone(), "acme")));
+        assert!(matches!(
+            unscoped.log_workflow_execution("wf-3", "user-1", AuditResult::Success, Duration::from_millis(5)).await,
+            Err(StorageError::TenantViolation(_))
+        ));
+        assert!(matches!(acme.delete_older_than(Utc::now()).await, Err(StorageError::TenantViolation(_))));
+    }
+}
@@ -9,6 +9,24 @@ use uuid::Uuid;
 /// API key prefix for easy identification
 const API_KEY_PREFIX: &str = "llm_orch_";
 
+/// Shortest static API key accepted from a secret store
+#[cfg(feature = "secrets")]
+const MIN_STATIC_KEY_LENGTH: usize = 32;
+
+/// API key provisioned in a secret store
+#[cfg(feature = "secrets")]
+#[derive(serde::Deserialize)]
+struct StaticApiKey {
+    key: String,
+    user_id: String,
+    #[serde(default)]
+    tenant_id: Option<String>,
+    #[serde(default)]
+    scopes: Vec<String>,
+    #[serde(default)]
+    name: Option<String>,
+}
+
 /// API key manager for creating and managing API keys
 pub struct ApiKeyManager {
     /// Backend store for API keys
@@ -37,6 +55,32 @@ impl ApiKeyManager {
         scopes: Vec<String>,
         name: Option<String>,
         expires_in_days: Option<i64>,
+    ) -> AuthResult<ApiKey> {
+        self.issue_key(user_id, None, scopes, name, expires_in_days).await
+    }
+
+    /// Create a new API key scoped to a tenant
+    ///
+    /// Requests authenticated with the key act for `tenant_id`.
+    pub async fn create_tenant_key(
+        &self,
+        user_id: &str,
+        tenant_id: &str,
+        scopes: Vec<String>,
+        name: Option<String>,
+        expires_in_days: Option<i64>,
+    ) -> AuthResult<ApiKey> {
+        self.issue_key(user_id, Some(tenant_id.to_string()), scopes, name, expires_in_days)
+            .await
+    }
+
+    async fn issue_key(
+        &self,
+        user_id: &str,
+        tenant_id: Option<String>,
+        scopes: Vec<String>,
+        name: Option<String>,
+        expires_in_days: Option<i64>,
     ) -> AuthResult<ApiKey> {
         // Generate a secure random key
         let raw_key = Self::generate_raw_key();
@@ -53,6 +97,7 @@ impl ApiKeyManager {
             key: key_with_prefix.clone(),
             key_hash,
             user_id: user_id.to_string(),
+            tenant_id,
             scopes,
             created_at: Utc::now(),
             expires_at,
@@ -64,6 +109,55 @@ impl ApiKeyManager {
         Ok(api_key)
     }
 
+    /// Import static API keys provisioned in a secret store
+    ///
+    /// The secret holds a JSON array of keys, each with the raw `key`, its
+    /// `user_id`, and optionally `scopes`, `tenant_id` and `name`. Keys are
+    /// hashed on import like generated ones and never expire.
+    ///
+    /// # Returns
+    /// The number of keys imported
+    #[cfg(feature = "secrets")]
+    pub async fn import_static_keys(
+        &self,
+        secrets: &dyn llm_orchestrator_secrets::SecretStore,
+        secret_key: &str,
+    ) -> AuthResult<usize> {
+        let secret = secrets
+            .get_secret(secret_key)
+            .await
+            .map_err(|e| AuthError::Internal(format!("Failed to read API keys from {}: {}", secret_key, e)))?;
+        let keys: Vec<StaticApiKey> = serde_json::from_str(&secret.value)?;
+
+        for key in &keys {
+            if key.key.len() < MIN_STATIC_KEY_LENGTH {
+                return Err(AuthError::Internal(format!(
+                    "Static API key for {} is shorter than {} characters",
+                    key.user_id, MIN_STATIC_KEY_LENGTH
+                )));
+            }
+        }
+
+        for key in &keys {
+            let key_hash = Self::hash_key(&key.key);
+            let api_key = ApiKey {
+                id: format!("static:{}", key.name.clone().unwrap_or_else(|| key_hash[..12].to_string())),
+                // Only the hash is kept
+                key: String::new(),
+                key_hash,
+                user_id: key.user_id.clone(),
+                tenant_id: key.tenant_id.clone(),
+                scopes: key.scopes.clone(),
+                created_at: Utc::now(),
+                expires_at: None,
+                name: key.name.clone(),
+            };
+            self.store.create_key(&api_key).await?;
+        }
+
+        Ok(keys.len())
+    }
+
     /// Lookup and validate an API key
     ///
     /// # Arguments
@@ -176,6 +270,7 @@ impl ApiKeyStore for InMemoryApiKeyStore {
             id: key.id.clone(),
             key_hash: key.key_hash.clone(),
             user_id: key.user_id.clone(),
+            tenant_id: key.tenant_id.clone(),
             scopes: key.scopes.clone(),
             created_at: key.created_at,
             expires_at: key.expires_at,
@@ -306,6 +401,7 @@ mod tests {
             id: key.id.clone(),
             key_hash: key.key_hash.clone(),
             user_id: key.user_id.clone(),
+            tenant_id: key.tenant_id.clone(),
             scopes: key.scopes.clone(),
             created_at: key.created_at,
             expires_at: Some(Utc::now() - Duration::days(1)),
@@ -389,4 +485,38 @@ mod tests {
         assert_eq!(key1.len(), 48);
         assert_eq!(key2.len(), 48);
     }
+
+    #[cfg(feature = "secrets")]
+    #[tokio::test]
+    async fn test_import_static_keys() {
+        use llm_orchestrator_secrets::EnvSecretStore;
+
+        let raw_key = "static-key-0123456789abcdefghijklmnop";
+        std::env::set_var(
+            "AUTH_TEST_API_KEYS",
+            serde_json::json!([
+                {"key": raw_key, "user_id": "ci", "scopes": ["submit"], "tenant_id": "acme", "name": "ci"},
+            ])
+            .to_string(),
+        );
+        let secrets = EnvSecretStore::with_prefix("AUTH_TEST_".to_string());
+
+        let manager = create_test_manager().await;
+        assert_eq!(manager.import_static_keys(&secrets, "api_keys").await.unwrap(), 1);
+
+        let info = manager.lookup_key(raw_key).await.unwrap();
+        assert_eq!(info.id, "static:ci");
+        assert_eq!(info.user_id, "ci");
+        assert_eq!(info.tenant_id.as_deref(), Some("acme"));
+        assert_eq!(info.scopes, vec!["submit"]);
+        assert!(info.expires_at.is_none());
+
+        // Short keys are refused
+        std::env::set_var(
+            "AUTH_TEST_WEAK_KEYS",
+            serde_json::json!([{"key": "short", "user_id": "ci"}]).to_string(),
+        );
+        assert!(manager.import_static_keys(&secrets, "weak_keys").await.is_err());
+        assert!(matches!(manager.lookup_key("short").await, Err(AuthError::ApiKeyNotFound)));
+    }
 }
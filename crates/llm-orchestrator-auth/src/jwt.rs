@@ -65,11 +65,24 @@ impl JwtAuth {
     /// # Returns
     /// A signed JWT token string
     pub fn generate_token(&self, user_id: &str, roles: Vec<String>) -> AuthResult<String> {
+        self.sign_access_token(user_id, None, roles)
+    }
+
+    /// Generate an access token for a user acting for a tenant
+    ///
+    /// The tenant is carried in the `tenant_id` claim and ends up in the
+    /// [`AuthContext`](crate::AuthContext) of authenticated requests.
+    pub fn generate_tenant_token(&self, user_id: &str, tenant_id: &str, roles: Vec<String>) -> AuthResult<String> {
+        self.sign_access_token(user_id, Some(tenant_id.to_string()), roles)
+    }
+
+    fn sign_access_token(&self, user_id: &str, tenant_id: Option<String>, roles: Vec<String>) -> AuthResult<String> {
         let now = Utc::now();
         let exp = now + Duration::seconds(self.expiry_seconds);
 
         let claims = Claims {
             sub: user_id.to_string(),
+            tenant_id,
             roles,
             exp: exp.timestamp() as u64,
             iat: now.timestamp() as u64,
@@ -88,11 +101,23 @@ impl JwtAuth {
     ///
     /// Refresh tokens have a longer expiry and minimal claims
     pub fn generate_refresh_token(&self, user_id: &str) -> AuthResult<String> {
+        self.sign_refresh_token(user_id, None)
+    }
+
+    /// Generate a refresh token for a user acting for a tenant
+    ///
+    /// Access tokens obtained with it are scoped to the same tenant.
+    pub fn generate_tenant_refresh_token(&self, user_id: &str, tenant_id: &str) -> AuthResult<String> {
+        self.sign_refresh_token(user_id, Some(tenant_id.to_string()))
+    }
+
+    fn sign_refresh_token(&self, user_id: &str, tenant_id: Option<String>) -> AuthResult<String> {
         let now = Utc::now();
         let exp = now + Duration::seconds(self.refresh_expiry_seconds);
 
         let claims = RefreshClaims {
             sub: user_id.to_string(),
+            tenant_id,
             exp: exp.timestamp() as u64,
             iat: now.timestamp() as u64,
             iss: self.issuer.clone(),
@@ -135,6 +160,10 @@ impl JwtAuth {
 
     /// Verify a refresh token
     pub fn verify_refresh_token(&self, token: &str) -> AuthResult<String> {
+        Ok(self.decode_refresh_token(token)?.sub)
+    }
+
+    fn decode_refresh_token(&self, token: &str) -> AuthResult<RefreshClaims> {
         let mut validation = Validation::new(self.algorithm);
         validation.set_issuer(&[&self.issuer]);
 
@@ -157,7 +186,7 @@ impl JwtAuth {
             ));
         }
 
-        Ok(token_data.claims.sub)
+        Ok(token_data.claims)
     }
 
     /// Refresh an access token using a refresh token
@@ -167,14 +196,14 @@ impl JwtAuth {
     /// * `roles` - The user's current roles (may have changed since refresh token was issued)
     ///
     /// # Returns
-    /// A new access token
+    /// A new access token, scoped to the refresh token's tenant
     pub fn refresh_access_token(
         &self,
         refresh_token: &str,
         roles: Vec<String>,
     ) -> AuthResult<String> {
-        let user_id = self.verify_refresh_token(refresh_token)?;
-        self.generate_token(&user_id, roles)
+        let claims = self.decode_refresh_token(refresh_token)?;
+        self.sign_access_token(&claims.sub, claims.tenant_id, roles)
     }
 }
 
@@ -230,6 +259,10 @@ struct RefreshClaims {
     /// Subject (user ID)
     sub: String,
 
+    /// Tenant the user acts for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tenant_id: Option<String>,
+
     /// Expiry timestamp
     exp: u64,
 
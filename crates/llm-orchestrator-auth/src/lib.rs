@@ -9,6 +9,10 @@
 //! - **API Key Management**: Secure API key generation, hashing, and validation
 //! - **Role-Based Access Control (RBAC)**: Fine-grained permission system with predefined roles
 //! - **Auth Middleware**: Ready-to-use middleware for authenticating requests
+//! - **OpenID Connect** (`oidc` feature): Validation of identity provider tokens against the
+//!   provider's JWKS, refreshed as keys roll over
+//! - **Static API Keys** (`secrets` feature): API keys provisioned through a secret store
+//! - **Failure Auditing** (`audit` feature): Failed authentication attempts recorded as audit events
 //!
 //! ## Quick Start
 //!
@@ -68,12 +72,16 @@ pub mod api_keys;
 pub mod jwt;
 pub mod middleware;
 pub mod models;
+#[cfg(feature = "oidc")]
+pub mod oidc;
 pub mod rbac;
 
 // Re-export main types for convenience
 pub use api_keys::{ApiKeyManager, ApiKeyStore, InMemoryApiKeyStore};
 pub use jwt::JwtAuth;
 pub use middleware::AuthMiddleware;
+#[cfg(feature = "oidc")]
+pub use oidc::{HttpJwksSource, JwksSource, OidcConfig, OidcIdentity, OidcValidator};
 pub use models::{
     ApiKey, ApiKeyInfo, AuthContext, AuthError, AuthResult, AuthType, Claims, Permission,
     RolePolicy,
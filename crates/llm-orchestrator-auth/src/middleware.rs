@@ -1,8 +1,12 @@
 use crate::api_keys::ApiKeyManager;
 use crate::jwt::JwtAuth;
-use crate::models::{AuthContext, AuthError, AuthResult, AuthType};
+use crate::models::{AuthContext, AuthError, AuthResult, AuthType, Permission};
+#[cfg(feature = "oidc")]
+use crate::oidc::OidcValidator;
 use crate::rbac::RbacEngine;
 use chrono::{DateTime, Utc};
+#[cfg(feature = "audit")]
+use llm_orchestrator_audit::{AuditEvent, AuditEventType, AuditLogger, AuditResult, ResourceType};
 use std::sync::Arc;
 
 /// Authentication middleware for validating requests
@@ -15,6 +19,14 @@ pub struct AuthMiddleware {
 
     /// RBAC engine for permission checks
     rbac: Arc<RbacEngine>,
+
+    /// Validator for tokens of an external identity provider
+    #[cfg(feature = "oidc")]
+    oidc: Option<Arc<OidcValidator>>,
+
+    /// Audit logger recording failed authentication attempts
+    #[cfg(feature = "audit")]
+    audit_logger: Option<Arc<AuditLogger>>,
 }
 
 impl AuthMiddleware {
@@ -28,9 +40,30 @@ impl AuthMiddleware {
             jwt_auth,
             api_key_manager,
             rbac,
+            #[cfg(feature = "oidc")]
+            oidc: None,
+            #[cfg(feature = "audit")]
+            audit_logger: None,
         }
     }
 
+    /// Also accept tokens of an OpenID Connect identity provider
+    ///
+    /// Bearer tokens signed with an asymmetric algorithm are validated by
+    /// `oidc`; tokens signed with the shared secret still go to [`JwtAuth`].
+    #[cfg(feature = "oidc")]
+    pub fn with_oidc(mut self, oidc: Arc<OidcValidator>) -> Self {
+        self.oidc = Some(oidc);
+        self
+    }
+
+    /// Record failed authentication attempts in the audit log
+    #[cfg(feature = "audit")]
+    pub fn with_audit_logger(mut self, audit_logger: Arc<AuditLogger>) -> Self {
+        self.audit_logger = Some(audit_logger);
+        self
+    }
+
     /// Authenticate a request using either JWT or API key
     ///
     /// # Arguments
@@ -39,10 +72,41 @@ impl AuthMiddleware {
     /// # Returns
     /// An authenticated context if successful
     pub async fn authenticate(&self, authorization_header: Option<&str>) -> AuthResult<AuthContext> {
+        self.authenticate_from(authorization_header, None).await
+    }
+
+    /// Authenticate a request from a known client address
+    ///
+    /// Like [`authenticate`](Self::authenticate), but failed attempts are
+    /// audited with `ip_address`.
+    pub async fn authenticate_from(
+        &self,
+        authorization_header: Option<&str>,
+        ip_address: Option<&str>,
+    ) -> AuthResult<AuthContext> {
+        let result = self.authenticate_header(authorization_header).await;
+
+        #[cfg(feature = "audit")]
+        if let Err(e) = &result {
+            self.audit_failure(authorization_header, ip_address, e).await;
+        }
+        #[cfg(not(feature = "audit"))]
+        let _ = ip_address;
+
+        result
+    }
+
+    async fn authenticate_header(&self, authorization_header: Option<&str>) -> AuthResult<AuthContext> {
         let auth_header = authorization_header.ok_or(AuthError::MissingCredentials)?;
 
         // Parse the authorization header
         if let Some(token) = auth_header.strip_prefix("Bearer ") {
+            #[cfg(feature = "oidc")]
+            if let Some(oidc) = &self.oidc {
+                if !Self::is_shared_secret_token(token) {
+                    return self.authenticate_oidc(oidc, token).await;
+                }
+            }
             self.authenticate_jwt(token).await
         } else if let Some(api_key) = auth_header.strip_prefix("ApiKey ") {
             self.authenticate_api_key(api_key).await
@@ -51,6 +115,69 @@ impl AuthMiddleware {
         }
     }
 
+    /// Whether a token is signed with a shared secret, as issued by [`JwtAuth`]
+    #[cfg(feature = "oidc")]
+    fn is_shared_secret_token(token: &str) -> bool {
+        use jsonwebtoken::Algorithm;
+
+        jsonwebtoken::decode_header(token)
+            .map(|header| matches!(header.alg, Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512))
+            .unwrap_or(true)
+    }
+
+    /// Authenticate using a token of the OpenID Connect identity provider
+    #[cfg(feature = "oidc")]
+    async fn authenticate_oidc(&self, oidc: &OidcValidator, token: &str) -> AuthResult<AuthContext> {
+        let identity = oidc.validate(token).await?;
+
+        // Roles grant their permissions; scopes can grant more
+        let mut permissions = self.rbac.compute_permissions(&identity.roles);
+        for permission in self.scopes_to_permissions(&identity.scopes) {
+            if !permissions.contains(&permission) {
+                permissions.push(permission);
+            }
+        }
+
+        Ok(AuthContext {
+            user_id: identity.subject,
+            tenant_id: identity.tenant_id,
+            roles: identity.roles,
+            permissions,
+            auth_type: AuthType::Jwt(token.to_string()),
+            expires_at: DateTime::from_timestamp(identity.expires_at, 0).unwrap_or_else(Utc::now),
+        })
+    }
+
+    /// Record a failed authentication attempt
+    #[cfg(feature = "audit")]
+    async fn audit_failure(&self, authorization_header: Option<&str>, ip_address: Option<&str>, error: &AuthError) {
+        let Some(logger) = &self.audit_logger else {
+            return;
+        };
+
+        // Name the scheme only; the credentials themselves never reach the log
+        let scheme = match authorization_header {
+            None => "none",
+            Some(header) if header.starts_with("Bearer ") => "bearer",
+            Some(header) if header.starts_with("ApiKey ") => "api_key",
+            Some(_) => "unknown",
+        };
+        let mut event = AuditEvent::new(
+            AuditEventType::Authentication,
+            "Authentication failed".to_string(),
+            ResourceType::User,
+            "anonymous".to_string(),
+            AuditResult::Failure(error.to_string()),
+        )
+        .with_details(serde_json::json!({ "scheme": scheme }));
+        if let Some(ip_address) = ip_address {
+            event = event.with_ip_address(ip_address.to_string());
+        }
+
+        // A broken audit log must not change why the request was rejected
+        let _ = logger.log_event(event).await;
+    }
+
     /// Authenticate using JWT token
     async fn authenticate_jwt(&self, token: &str) -> AuthResult<AuthContext> {
         // Verify and decode the token
@@ -61,6 +188,7 @@ impl AuthMiddleware {
 
         Ok(AuthContext {
             user_id: claims.sub,
+            tenant_id: claims.tenant_id,
             roles: claims.roles,
             permissions,
             auth_type: AuthType::Jwt(token.to_string()),
@@ -82,6 +210,7 @@ impl AuthMiddleware {
 
         Ok(AuthContext {
             user_id: key_info.user_id,
+            tenant_id: key_info.tenant_id,
             roles,
             permissions,
             auth_type: AuthType::ApiKey(key_info.id),
@@ -93,31 +222,42 @@ impl AuthMiddleware {
     }
 
     /// Convert API key scopes to permissions
-    fn scopes_to_permissions(&self, scopes: &[String]) -> Vec<crate::models::Permission> {
-        use crate::models::Permission;
-
-        scopes
-            .iter()
-            .filter_map(|scope| match scope.as_str() {
-                "workflow:read" => Some(Permission::WorkflowRead),
-                "workflow:write" => Some(Permission::WorkflowWrite),
-                "workflow:execute" => Some(Permission::WorkflowExecute),
-                "workflow:delete" => Some(Permission::WorkflowDelete),
-                "execution:read" => Some(Permission::ExecutionRead),
-                "execution:cancel" => Some(Permission::ExecutionCancel),
-                "admin" => Some(Permission::AdminAccess),
-                _ => None,
-            })
-            .collect()
+    ///
+    /// Besides the fine-grained `resource:action` scopes, the coarse scopes
+    /// `read`, `submit` and `admin` grant the permissions of the viewer,
+    /// executor and admin roles.
+    fn scopes_to_permissions(&self, scopes: &[String]) -> Vec<Permission> {
+        let mut permissions = Vec::new();
+        for scope in scopes {
+            let granted = match scope.as_str() {
+                "workflow:read" => vec![Permission::WorkflowRead],
+                "workflow:write" => vec![Permission::WorkflowWrite],
+                "workflow:execute" => vec![Permission::WorkflowExecute],
+                "workflow:delete" => vec![Permission::WorkflowDelete],
+                "execution:read" => vec![Permission::ExecutionRead],
+                "execution:cancel" => vec![Permission::ExecutionCancel],
+                "read" => Permission::for_role("viewer"),
+                "submit" => Permission::for_role("executor"),
+                "admin" => Permission::all(),
+                _ => vec![],
+            };
+            for permission in granted {
+                if !permissions.contains(&permission) {
+                    permissions.push(permission);
+                }
+            }
+        }
+        permissions
     }
 
     /// Convert API key scopes to roles (for backward compatibility)
     fn scopes_to_roles(&self, scopes: &[String]) -> Vec<String> {
         // Check if scopes match predefined role patterns
-        let has_read = scopes.contains(&"workflow:read".to_string());
-        let has_write = scopes.contains(&"workflow:write".to_string());
-        let has_execute = scopes.contains(&"workflow:execute".to_string());
-        let has_admin = scopes.contains(&"admin".to_string());
+        let has = |scope: &str| scopes.iter().any(|s| s == scope);
+        let has_read = has("workflow:read") || has("read");
+        let has_write = has("workflow:write");
+        let has_execute = has("workflow:execute") || has("submit");
+        let has_admin = has("admin");
 
         if has_admin {
             vec!["admin".to_string()]
@@ -150,7 +290,7 @@ impl AuthMiddleware {
     pub fn authorize(
         &self,
         ctx: &AuthContext,
-        permission: &crate::models::Permission,
+        permission: &Permission,
     ) -> AuthResult<()> {
         self.rbac.require_permission(ctx, permission)
     }
@@ -227,6 +367,41 @@ mod tests {
             .contains(&crate::models::Permission::WorkflowExecute));
     }
 
+    #[tokio::test]
+    async fn test_authenticate_extracts_tenant() {
+        let middleware = create_test_middleware().await;
+
+        // Tenant-scoped JWT, and the access token refreshed from its refresh token
+        let token = middleware
+            .jwt_auth
+            .generate_tenant_token("user123", "acme", vec!["executor".to_string()])
+            .unwrap();
+        let ctx = middleware.authenticate(Some(&format!("Bearer {}", token))).await.unwrap();
+        assert_eq!(ctx.require_tenant().unwrap(), "acme");
+
+        let refresh = middleware.jwt_auth.generate_tenant_refresh_token("user123", "acme").unwrap();
+        let token = middleware
+            .jwt_auth
+            .refresh_access_token(&refresh, vec!["executor".to_string()])
+            .unwrap();
+        let ctx = middleware.authenticate(Some(&format!("Bearer {}", token))).await.unwrap();
+        assert_eq!(ctx.tenant_id.as_deref(), Some("acme"));
+
+        // Tenant-scoped API key
+        let api_key = middleware
+            .api_key_manager
+            .create_tenant_key("user456", "globex", vec!["workflow:read".to_string()], None, None)
+            .await
+            .unwrap();
+        let ctx = middleware.authenticate(Some(&format!("ApiKey {}", api_key.key))).await.unwrap();
+        assert_eq!(ctx.require_tenant().unwrap(), "globex");
+
+        // Unscoped credentials have no tenant
+        let token = middleware.jwt_auth.generate_token("user123", vec![]).unwrap();
+        let ctx = middleware.authenticate(Some(&format!("Bearer {}", token))).await.unwrap();
+        assert!(matches!(ctx.require_tenant(), Err(AuthError::MissingTenant)));
+    }
+
     #[tokio::test]
     async fn test_authenticate_missing_credentials() {
         let middleware = create_test_middleware().await;
@@ -368,4 +543,91 @@ mod tests {
         let roles = middleware.scopes_to_roles(&scopes);
         assert_eq!(roles, vec!["admin"]);
     }
+
+    #[tokio::test]
+    async fn test_coarse_scopes() {
+        let middleware = create_test_middleware().await;
+
+        let read = middleware.scopes_to_permissions(&["read".to_string()]);
+        assert_eq!(read, Permission::for_role("viewer"));
+        assert_eq!(middleware.scopes_to_roles(&["read".to_string()]), vec!["viewer"]);
+
+        let submit = middleware.scopes_to_permissions(&["submit".to_string(), "read".to_string()]);
+        assert_eq!(submit, Permission::for_role("executor"));
+        assert_eq!(middleware.scopes_to_roles(&["submit".to_string()]), vec!["executor"]);
+
+        let admin = middleware.scopes_to_permissions(&["admin".to_string()]);
+        assert_eq!(admin, Permission::all());
+    }
+
+    #[cfg(feature = "oidc")]
+    #[tokio::test]
+    async fn test_authenticate_with_oidc() {
+        use crate::oidc::tests::{sign, StaticJwks, AUDIENCE, ISSUER};
+        use crate::oidc::{OidcConfig, OidcValidator};
+        use serde_json::json;
+
+        let oidc = OidcValidator::new(OidcConfig::new(ISSUER, AUDIENCE), Arc::new(StaticJwks::new(vec!["key-a"])));
+        let middleware = create_test_middleware().await.with_oidc(Arc::new(oidc));
+
+        let token = sign(
+            "key-a",
+            json!({"sub": "alice", "tenant_id": "acme", "roles": ["viewer"], "scope": "openid submit"}),
+        );
+        let ctx = middleware
+            .authenticate(Some(&format!("Bearer {}", token)))
+            .await
+            .unwrap();
+        assert_eq!(ctx.user_id, "alice");
+        assert_eq!(ctx.tenant_id.as_deref(), Some("acme"));
+        assert_eq!(ctx.roles, vec!["viewer"]);
+        // The submit scope adds execution to the viewer role
+        assert!(ctx.has_permission(&Permission::WorkflowExecute));
+        assert!(!ctx.has_permission(&Permission::WorkflowWrite));
+
+        // Tokens signed with the shared secret are still accepted
+        let local = middleware
+            .jwt_auth
+            .generate_token("bob", vec!["developer".to_string()])
+            .unwrap();
+        let ctx = middleware
+            .authenticate(Some(&format!("Bearer {}", local)))
+            .await
+            .unwrap();
+        assert_eq!(ctx.user_id, "bob");
+    }
+
+    #[cfg(feature = "audit")]
+    #[tokio::test]
+    async fn test_failures_are_audited() {
+        use llm_orchestrator_audit::{
+            AuditEventType, AuditFilter, AuditLogger, AuditResult, AuditStorage, FileAuditStorage, RotationPolicy,
+        };
+
+        let dir = tempfile::tempdir().unwrap();
+        let storage = Arc::new(FileAuditStorage::new(dir.path().join("audit.log"), RotationPolicy::Never).unwrap());
+        let middleware = create_test_middleware()
+            .await
+            .with_audit_logger(Arc::new(AuditLogger::new(storage.clone())));
+
+        assert!(middleware
+            .authenticate_from(Some("ApiKey llm_orch_not-a-key"), Some("203.0.113.7"))
+            .await
+            .is_err());
+        assert!(middleware.authenticate(None).await.is_err());
+        let token = middleware.jwt_auth.generate_token("alice", vec![]).unwrap();
+        middleware
+            .authenticate(Some(&format!("Bearer {}", token)))
+            .await
+            .unwrap();
+
+        let events = storage.query(AuditFilter::new()).await.unwrap();
+        assert_eq!(events.len(), 2);
+        assert!(events.iter().all(|e| e.event_type == AuditEventType::Authentication
+            && matches!(e.result, AuditResult::Failure(_))));
+
+        let api_key_failure = events.iter().find(|e| e.details["scheme"] == "api_key").unwrap();
+        assert_eq!(api_key_failure.ip_address.as_deref(), Some("203.0.113.7"));
+        assert!(!serde_json::to_string(api_key_failure).unwrap().contains("not-a-key"));
+    }
 }
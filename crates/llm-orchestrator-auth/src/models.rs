@@ -7,6 +7,10 @@ pub struct AuthContext {
     /// Unique user identifier
     pub user_id: String,
 
+    /// Tenant the user acts for, if the credentials are tenant-scoped
+    #[serde(default)]
+    pub tenant_id: Option<String>,
+
     /// User roles
     pub roles: Vec<String>,
 
@@ -31,6 +35,11 @@ impl AuthContext {
         Utc::now() > self.expires_at
     }
 
+    /// Require tenant-scoped credentials, returning the tenant ID
+    pub fn require_tenant(&self) -> Result<&str, AuthError> {
+        self.tenant_id.as_deref().ok_or(AuthError::MissingTenant)
+    }
+
     /// Require a specific permission, returning an error if not present
     pub fn require_permission(&self, permission: &Permission) -> Result<(), AuthError> {
         if self.has_permission(permission) {
@@ -133,6 +142,10 @@ pub struct ApiKey {
     /// User who owns this key
     pub user_id: String,
 
+    /// Tenant the key is scoped to
+    #[serde(default)]
+    pub tenant_id: Option<String>,
+
     /// Scopes/permissions for this key
     pub scopes: Vec<String>,
 
@@ -158,6 +171,10 @@ pub struct ApiKeyInfo {
     /// User who owns this key
     pub user_id: String,
 
+    /// Tenant the key is scoped to
+    #[serde(default)]
+    pub tenant_id: Option<String>,
+
     /// Scopes/permissions for this key
     pub scopes: Vec<String>,
 
@@ -193,6 +210,10 @@ pub struct Claims {
     /// Subject (user ID)
     pub sub: String,
 
+    /// Tenant the user acts for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant_id: Option<String>,
+
     /// User roles
     pub roles: Vec<String>,
 
@@ -237,6 +258,9 @@ pub enum AuthError {
         available: Vec<Permission>,
     },
 
+    #[error("Credentials are not scoped to a tenant")]
+    MissingTenant,
+
     #[error("Role not found: {0}")]
     RoleNotFound(String),
 
@@ -376,6 +376,7 @@ mod tests {
 
         let ctx = AuthContext {
             user_id: "user123".to_string(),
+            tenant_id: None,
             roles: vec!["developer".to_string()],
             permissions: rbac.compute_permissions(&["developer".to_string()]),
             auth_type: crate::models::AuthType::Jwt("token".to_string()),
@@ -393,6 +394,7 @@ mod tests {
 
         let ctx = AuthContext {
             user_id: "user123".to_string(),
+            tenant_id: None,
             roles: vec!["viewer".to_string()],
             permissions: rbac.compute_permissions(&["viewer".to_string()]),
             auth_type: crate::models::AuthType::Jwt("token".to_string()),
@@ -0,0 +1,21 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Generates the gRPC service from the protos in `proto/` at the repository root.
+
+use std::path::PathBuf;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let protos = PathBuf::from("../../proto");
+    let descriptors = PathBuf::from(std::env::var("OUT_DIR")?).join("orchestrator_descriptor.bin");
+
+    // Builds need no protoc installed
+    std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?);
+    let well_known = protoc_bin_vendored::include_path()?;
+
+    tonic_prost_build::configure()
+        .build_client(false)
+        .file_descriptor_set_path(descriptors)
+        .compile_protos(&[protos.join("llm_orchestrator/v1/orchestrator.proto")], &[protos, well_known])?;
+    Ok(())
+}
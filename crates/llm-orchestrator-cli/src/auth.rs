@@ -0,0 +1,227 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Authentication of serve mode's HTTP and gRPC APIs.
+//!
+//! Both APIs authenticate callers with the auth crate's [`AuthMiddleware`]
+//! from their `authorization` header: `Bearer` tokens signed with the
+//! `auth/jwt_secret` secret or issued by the `--oidc-issuer` identity
+//! provider, and `ApiKey` keys from the optional `auth/api_keys` secret.
+//! Failed attempts are recorded in the audit log when one is configured.
+//!
+//! HTTP routes are guarded with [`require`], which answers `401` to missing
+//! or invalid credentials and `403` to credentials lacking the route's
+//! permission, and hands the caller's [`AuthContext`] to the handler as a
+//! request extension.
+
+use crate::{Audit, AuthArgs};
+use anyhow::{Context, Result};
+use axum::extract::{ConnectInfo, Request, State};
+use axum::http::StatusCode;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use llm_orchestrator_auth::{
+    ApiKeyManager, AuthContext, AuthMiddleware, HttpJwksSource, InMemoryApiKeyStore, JwtAuth,
+    OidcConfig, OidcValidator, Permission, RbacEngine,
+};
+use llm_orchestrator_core::gateway;
+use llm_orchestrator_secrets::SecretStore;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// Secret holding the key bearer tokens are signed with.
+const JWT_SECRET: &str = "auth/jwt_secret";
+
+/// Secret holding the static API keys, a JSON array as read by
+/// [`ApiKeyManager::import_static_keys`].
+const API_KEYS_SECRET: &str = "auth/api_keys";
+
+/// Builds the authenticator of serve mode from `secrets` and `args`.
+///
+/// The token signing key is required; static API keys and an identity
+/// provider are optional.
+pub(crate) async fn authenticator(
+    secrets: &dyn SecretStore,
+    args: &AuthArgs,
+    audit: Option<&Audit>,
+) -> Result<AuthMiddleware> {
+    let jwt_secret = secrets.get_secret(JWT_SECRET).await.with_context(|| {
+        format!(
+            "Serve mode needs a token signing key in the {} secret",
+            JWT_SECRET
+        )
+    })?;
+    let api_keys = ApiKeyManager::new(Arc::new(InMemoryApiKeyStore::new()));
+    if secrets.get_secret(API_KEYS_SECRET).await.is_ok() {
+        let imported = api_keys
+            .import_static_keys(secrets, API_KEYS_SECRET)
+            .await
+            .with_context(|| {
+                format!(
+                    "Failed to import API keys from the {} secret",
+                    API_KEYS_SECRET
+                )
+            })?;
+        info!(keys = imported, "Imported API keys");
+    }
+
+    let mut auth = AuthMiddleware::new(
+        Arc::new(JwtAuth::new(jwt_secret.value.into_bytes())),
+        Arc::new(api_keys),
+        Arc::new(RbacEngine::new()),
+    );
+    if let (Some(issuer), Some(audience)) = (&args.oidc_issuer, &args.oidc_audience) {
+        let source = HttpJwksSource::discover(issuer)
+            .await
+            .with_context(|| format!("Failed to discover the signing keys of {}", issuer))?;
+        let config = OidcConfig::new(issuer, audience);
+        auth = auth.with_oidc(Arc::new(OidcValidator::new(config, Arc::new(source))));
+    }
+    if let Some(audit) = audit {
+        auth = auth.with_audit_logger(audit.logger.clone());
+    }
+    Ok(auth)
+}
+
+/// What a guarded route requires of its callers.
+#[derive(Clone)]
+pub(crate) struct Guard {
+    auth: Arc<AuthMiddleware>,
+    permission: Permission,
+}
+
+impl Guard {
+    pub(crate) fn new(auth: Arc<AuthMiddleware>, permission: Permission) -> Self {
+        Self { auth, permission }
+    }
+}
+
+/// Authenticates a request and checks its credentials hold the guard's permission.
+///
+/// Use with [`axum::middleware::from_fn_with_state`]. Errors are answered in
+/// the OpenAI error format, as the gateway routes are the ones clients see.
+pub(crate) async fn require(
+    State(guard): State<Guard>,
+    address: Option<ConnectInfo<SocketAddr>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let header = request
+        .headers()
+        .get("authorization")
+        .and_then(|value| value.to_str().ok());
+    let address = address.map(|ConnectInfo(address)| address.ip().to_string());
+    let context = match guard
+        .auth
+        .authenticate_from(header, address.as_deref())
+        .await
+    {
+        Ok(context) => context,
+        Err(e) => {
+            warn!(path = %request.uri().path(), "Rejected request: {}", e);
+            return refused(StatusCode::UNAUTHORIZED, &e.to_string(), "invalid_api_key");
+        }
+    };
+    if let Err(e) = context.require_permission(&guard.permission) {
+        return refused(
+            StatusCode::FORBIDDEN,
+            &e.to_string(),
+            "insufficient_permissions",
+        );
+    }
+
+    request.extensions_mut().insert::<AuthContext>(context);
+    next.run(request).await
+}
+
+fn refused(status: StatusCode, message: &str, code: &'static str) -> Response {
+    (
+        status,
+        Json(gateway::error_body(
+            message,
+            "invalid_request_error",
+            Some(code),
+        )),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::routing::get;
+    use axum::{Extension, Router};
+    use tower::ServiceExt;
+
+    const SECRET: &[u8] = b"serve-test-signing-key-of-32-bytes";
+
+    fn router() -> Router {
+        let auth = Arc::new(AuthMiddleware::new(
+            Arc::new(JwtAuth::new(SECRET.to_vec())),
+            Arc::new(ApiKeyManager::new(Arc::new(InMemoryApiKeyStore::new()))),
+            Arc::new(RbacEngine::new()),
+        ));
+        Router::new()
+            .route(
+                "/tenant",
+                get(|Extension(context): Extension<AuthContext>| async move {
+                    context.tenant_id.unwrap_or_default()
+                }),
+            )
+            .route_layer(axum::middleware::from_fn_with_state(
+                Guard::new(auth, Permission::WorkflowExecute),
+                require,
+            ))
+            .route("/open", get(|| async { "ok" }))
+    }
+
+    async fn status(authorization: Option<String>, path: &str) -> (StatusCode, String) {
+        let mut request = Request::get(path);
+        if let Some(authorization) = authorization {
+            request = request.header("authorization", authorization);
+        }
+        let response = router()
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    fn bearer(role: &str) -> Option<String> {
+        let token = JwtAuth::new(SECRET.to_vec())
+            .generate_tenant_token("caller", "acme", vec![role.to_string()])
+            .unwrap();
+        Some(format!("Bearer {}", token))
+    }
+
+    #[tokio::test]
+    async fn test_guarded_routes_need_credentials_with_the_permission() {
+        let (code, body) = status(None, "/tenant").await;
+        assert_eq!(code, StatusCode::UNAUTHORIZED);
+        assert!(body.contains("invalid_api_key"), "{}", body);
+        assert_eq!(
+            status(Some("Bearer nope".to_string()), "/tenant").await.0,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            status(bearer("viewer"), "/tenant").await.0,
+            StatusCode::FORBIDDEN
+        );
+
+        // The handler sees the tenant of the credentials
+        assert_eq!(
+            status(bearer("executor"), "/tenant").await,
+            (StatusCode::OK, "acme".to_string())
+        );
+
+        // Routes outside the layer stay open
+        assert_eq!(status(None, "/open").await.0, StatusCode::OK);
+    }
+}
@@ -0,0 +1,336 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Shell completion scripts.
+//!
+//! `completions <SHELL>` prints a script completing subcommands, flags, and
+//! the values of enumerated flags. The scripts are generated from the clap
+//! command tree, so they follow the CLI as it changes:
+//!
+//! ```bash
+//! llm-orchestrator completions bash > /etc/bash_completion.d/llm-orchestrator
+//! llm-orchestrator completions zsh > "${fpath[1]}/_llm-orchestrator"
+//! llm-orchestrator completions fish > ~/.config/fish/completions/llm-orchestrator.fish
+//! ```
+
+use clap::{Command, ValueEnum};
+use std::fmt::Write;
+
+/// A shell to print completions for.
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum Shell {
+    /// Bash 4 or later
+    Bash,
+    /// Zsh
+    Zsh,
+    /// Fish
+    Fish,
+}
+
+/// A command or subcommand, keyed by its path from the binary, e.g.
+/// `llm-orchestrator__workflows__publish`.
+struct Node {
+    key: String,
+    /// Names of the subcommands leading here, without the binary.
+    path: Vec<String>,
+    subcommands: Vec<(String, String)>,
+    flags: Vec<Flag>,
+    /// Whether the command takes positional arguments.
+    positional: bool,
+}
+
+struct Flag {
+    long: Option<String>,
+    short: Option<char>,
+    help: String,
+    takes_value: bool,
+    /// Accepted values, when enumerated.
+    values: Vec<String>,
+}
+
+impl Flag {
+    /// The spellings of the flag, e.g. `--verbose -v`.
+    fn names(&self) -> Vec<String> {
+        let long = self.long.iter().map(|l| format!("--{}", l));
+        long.chain(self.short.iter().map(|s| format!("-{}", s))).collect()
+    }
+}
+
+/// The completion script for `shell`.
+pub(crate) fn generate(shell: Shell, mut command: Command) -> String {
+    // Propagate global flags to the subcommands
+    command.build();
+    let bin = command.get_name().to_string();
+    let mut nodes = Vec::new();
+    collect(&command, bin.clone(), Vec::new(), &mut nodes);
+    match shell {
+        Shell::Bash => bash(&bin, &nodes),
+        Shell::Zsh => zsh(&bin, &nodes),
+        Shell::Fish => fish(&bin, &nodes),
+    }
+}
+
+fn collect(command: &Command, key: String, path: Vec<String>, nodes: &mut Vec<Node>) {
+    let flags = command
+        .get_arguments()
+        .filter(|arg| !arg.is_positional() && !arg.is_hide_set())
+        .map(|arg| Flag {
+            long: arg.get_long().map(str::to_string),
+            short: arg.get_short(),
+            help: arg.get_help().map(|h| first_line(&h.to_string())).unwrap_or_default(),
+            takes_value: arg.get_action().takes_values(),
+            values: arg
+                .get_possible_values()
+                .iter()
+                .filter(|value| !value.is_hide_set())
+                .map(|value| value.get_name().to_string())
+                .collect(),
+        })
+        .collect();
+    let subcommands: Vec<&Command> = command.get_subcommands().filter(|sub| !sub.is_hide_set()).collect();
+    nodes.push(Node {
+        key: key.clone(),
+        path: path.clone(),
+        subcommands: subcommands
+            .iter()
+            .map(|sub| {
+                let about = sub.get_about().map(|a| first_line(&a.to_string())).unwrap_or_default();
+                (sub.get_name().to_string(), about)
+            })
+            .collect(),
+        flags,
+        positional: command.get_positionals().next().is_some(),
+    });
+    for sub in subcommands {
+        let mut sub_path = path.clone();
+        sub_path.push(sub.get_name().to_string());
+        collect(sub, format!("{}__{}", key, sub.get_name()), sub_path, nodes);
+    }
+}
+
+fn first_line(text: &str) -> String {
+    text.lines().next().unwrap_or_default().trim().to_string()
+}
+
+/// `case` patterns of the transitions from a command to its subcommands.
+fn transitions(nodes: &[Node]) -> Vec<String> {
+    nodes
+        .iter()
+        .flat_map(|node| node.subcommands.iter().map(move |(name, _)| format!("{}__{}", node.key, name)))
+        .collect()
+}
+
+fn function_name(bin: &str) -> String {
+    format!("_{}", bin.replace('-', "_"))
+}
+
+fn bash(bin: &str, nodes: &[Node]) -> String {
+    let function = function_name(bin);
+    let mut script = String::new();
+    let _ = writeln!(script, "# bash completion for {}", bin);
+    let _ = writeln!(script, "{}() {{", function);
+    script.push_str("    local cur prev word key i opts\n");
+    script.push_str("    cur=\"${COMP_WORDS[COMP_CWORD]}\"\n");
+    script.push_str("    prev=\"${COMP_WORDS[COMP_CWORD-1]}\"\n");
+    let _ = writeln!(script, "    key=\"{}\"", bin);
+    script.push_str("    for ((i = 1; i < COMP_CWORD; i++)); do\n");
+    script.push_str("        word=\"${COMP_WORDS[i]}\"\n");
+    script.push_str("        case \"${key}__${word}\" in\n");
+    let _ = writeln!(script, "            {}) key=\"${{key}}__${{word}}\" ;;", transitions(nodes).join("|"));
+    script.push_str("        esac\n    done\n\n    case \"${key}\" in\n");
+    for node in nodes {
+        let _ = writeln!(script, "        {})", node.key);
+        let value_flags: Vec<&Flag> = node.flags.iter().filter(|f| f.takes_value).collect();
+        if !value_flags.is_empty() {
+            script.push_str("            case \"${prev}\" in\n");
+            for flag in value_flags {
+                let completion = if flag.values.is_empty() {
+                    "compgen -f -- \"${cur}\"".to_string()
+                } else {
+                    format!("compgen -W \"{}\" -- \"${{cur}}\"", flag.values.join(" "))
+                };
+                let _ = writeln!(script, "                {})\n                    COMPREPLY=($({}))\n                    return 0 ;;", flag.names().join("|"), completion);
+            }
+            script.push_str("            esac\n");
+        }
+        let words: Vec<String> = node
+            .subcommands
+            .iter()
+            .map(|(name, _)| name.clone())
+            .chain(node.flags.iter().flat_map(Flag::names))
+            .collect();
+        let _ = writeln!(script, "            opts=\"{}\" ;;", words.join(" "));
+    }
+    script.push_str("    esac\n\n");
+    script.push_str("    COMPREPLY=($(compgen -W \"${opts}\" -- \"${cur}\"))\n}\n\n");
+    let _ = writeln!(script, "complete -F {} -o bashdefault -o default {}", function, bin);
+    script
+}
+
+/// Quotes `text` for a single-quoted shell string.
+fn single_quoted(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "'\\''"))
+}
+
+fn zsh(bin: &str, nodes: &[Node]) -> String {
+    let function = function_name(bin);
+    let mut script = String::new();
+    let _ = writeln!(script, "#compdef {}\n", bin);
+    let _ = writeln!(script, "{}() {{", function);
+    script.push_str("    local word key i\n");
+    let _ = writeln!(script, "    key=\"{}\"", bin);
+    script.push_str("    for ((i = 2; i < CURRENT; i++)); do\n");
+    script.push_str("        word=\"${words[i]}\"\n");
+    script.push_str("        case \"${key}__${word}\" in\n");
+    let _ = writeln!(script, "            ({}) key=\"${{key}}__${{word}}\" ;;", transitions(nodes).join("|"));
+    script.push_str("        esac\n    done\n\n    case \"${key}\" in\n");
+    for node in nodes {
+        let _ = writeln!(script, "        ({})", node.key);
+        let value_flags: Vec<&Flag> = node.flags.iter().filter(|f| f.takes_value).collect();
+        if !value_flags.is_empty() {
+            script.push_str("            case \"${words[CURRENT-1]}\" in\n");
+            for flag in value_flags {
+                let completion = if flag.values.is_empty() {
+                    "_files".to_string()
+                } else {
+                    format!("compadd -- {}", flag.values.join(" "))
+                };
+                let _ = writeln!(script, "                ({})\n                    {}\n                    return ;;", flag.names().join("|"), completion);
+            }
+            script.push_str("            esac\n");
+        }
+        if !node.subcommands.is_empty() {
+            let commands: Vec<String> = node
+                .subcommands
+                .iter()
+                .map(|(name, about)| single_quoted(&format!("{}:{}", name, about.replace(':', "\\:"))))
+                .collect();
+            let _ = writeln!(script, "            local -a commands=({})", commands.join(" "));
+            script.push_str("            _describe -t commands 'command' commands\n");
+        }
+        let flags: Vec<String> = node
+            .flags
+            .iter()
+            .flat_map(|flag| {
+                let help = flag.help.replace(':', "\\:");
+                flag.names().into_iter().map(move |name| single_quoted(&format!("{}:{}", name, help)))
+            })
+            .collect();
+        let _ = writeln!(script, "            local -a flags=({})", flags.join(" "));
+        script.push_str("            _describe -t options 'option' flags");
+        if node.positional {
+            script.push_str("\n            _files");
+        }
+        script.push_str(" ;;\n");
+    }
+    script.push_str("    esac\n}\n\n");
+    let _ = writeln!(script, "if [ \"$funcstack[1]\" = \"{}\" ]; then\n    {} \"$@\"\nelse\n    compdef {} {}\nfi", function, function, function, bin);
+    script
+}
+
+fn fish(bin: &str, nodes: &[Node]) -> String {
+    let mut script = format!("# fish completion for {}\n", bin);
+    for node in nodes {
+        // Fish tracks the subcommands seen, not their nesting
+        let condition = if node.path.is_empty() {
+            "__fish_use_subcommand".to_string()
+        } else {
+            node.path
+                .iter()
+                .map(|name| format!("__fish_seen_subcommand_from {}", name))
+                .collect::<Vec<_>>()
+                .join("; and ")
+        };
+        for (name, about) in &node.subcommands {
+            let _ = writeln!(script, "complete -c {} -n {} -f -a {} -d {}", bin, single_quoted(&condition), name, single_quoted(about));
+        }
+        for flag in &node.flags {
+            let mut line = format!("complete -c {} -n {}", bin, single_quoted(&condition));
+            if let Some(long) = &flag.long {
+                let _ = write!(line, " -l {}", long);
+            }
+            if let Some(short) = flag.short {
+                let _ = write!(line, " -s {}", short);
+            }
+            if flag.takes_value {
+                if flag.values.is_empty() {
+                    line.push_str(" -r -F");
+                } else {
+                    let _ = write!(line, " -x -a {}", single_quoted(&flag.values.join(" ")));
+                }
+            }
+            let _ = writeln!(script, "{} -d {}", line, single_quoted(&flag.help));
+        }
+    }
+    script
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{Arg, ArgAction};
+
+    fn command() -> Command {
+        Command::new("orch")
+            .arg(Arg::new("verbose").long("verbose").short('v').action(ArgAction::SetTrue).help("Log more\nDetails"))
+            .subcommand(
+                Command::new("run")
+                    .about("Run a workflow")
+                    .arg(Arg::new("file"))
+                    .arg(Arg::new("format").long("format").value_parser(["text", "json"]).help("Output: it's text")),
+            )
+            .subcommand(Command::new("workflows").about("Manage workflows").subcommand(Command::new("publish")))
+            .subcommand(Command::new("internal").hide(true))
+    }
+
+    #[test]
+    fn test_bash() {
+        let script = generate(Shell::Bash, command());
+        // Subcommands are followed through nested commands
+        assert!(script.contains("            orch__run|orch__workflows|orch__help|orch__workflows__publish|"));
+        assert!(script.contains("--format)\n                    COMPREPLY=($(compgen -W \"text json\" -- \"${cur}\"))"));
+        assert!(script.contains("opts=\"run workflows help --verbose -v --help -h\" ;;"));
+        assert!(script.contains("complete -F _orch -o bashdefault -o default orch"));
+        assert!(!script.contains("internal"));
+    }
+
+    #[test]
+    fn test_zsh() {
+        let script = generate(Shell::Zsh, command());
+        assert!(script.starts_with("#compdef orch\n"));
+        assert!(script.contains("local -a commands=('run:Run a workflow' 'workflows:Manage workflows' 'help:"));
+        assert!(script.contains("(--format)\n                    compadd -- text json"));
+        // Help is cut to its first line and quotes are escaped
+        assert!(script.contains("'--verbose:Log more'"));
+        assert!(script.contains(r"'--format:Output\: it'\''s text'"));
+        // Commands taking positionals complete files
+        assert!(script.contains("_describe -t options 'option' flags\n            _files ;;"));
+    }
+
+    #[test]
+    fn test_fish() {
+        let script = generate(Shell::Fish, command());
+        assert!(script.contains("complete -c orch -n '__fish_use_subcommand' -f -a run -d 'Run a workflow'"));
+        assert!(script.contains("complete -c orch -n '__fish_use_subcommand' -l verbose -s v -d 'Log more'"));
+        assert!(script.contains("complete -c orch -n '__fish_seen_subcommand_from run' -l format -x -a 'text json'"));
+        assert!(script.contains("-n '__fish_seen_subcommand_from workflows' -f -a publish"));
+    }
+
+    #[test]
+    fn test_cli_completes_every_shell() {
+        use clap::CommandFactory;
+        for shell in Shell::value_variants() {
+            let script = generate(*shell, crate::Cli::command());
+            let run = match shell {
+                Shell::Fish => "-f -a run ",
+                Shell::Bash | Shell::Zsh => "llm-orchestrator__run",
+            };
+            assert!(script.contains(run));
+        }
+    }
+
+    #[test]
+    fn test_single_quoted() {
+        assert_eq!(single_quoted("it's"), "'it'\\''s'");
+    }
+}
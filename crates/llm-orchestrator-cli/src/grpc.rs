@@ -0,0 +1,450 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! gRPC API of serve mode.
+//!
+//! Implements the `llm_orchestrator.v1.Orchestrator` service of
+//! `proto/llm_orchestrator/v1/orchestrator.proto` on top of the same
+//! [`RunService`] as the webhook triggers, with server reflection so tools
+//! like `grpcurl` can call it without the proto file.
+//!
+//! Every call is authenticated from its `authorization` metadata by the
+//! [`AuthMiddleware`] serve mode builds for both APIs (see [`crate::auth`]).
+//! Publishing needs the `admin` scope, starting runs `submit`, and reading
+//! runs `read`. Runs are started under the tenant of the credentials, and
+//! runs of other tenants are not found.
+
+use crate::service::{RunRecord, RunService, ServiceError};
+use llm_orchestrator_auth::{AuthContext, AuthMiddleware, Permission};
+use llm_orchestrator_core::include::has_includes;
+use llm_orchestrator_core::worker::SubmitOptions;
+use llm_orchestrator_core::{CancelHandle, Workflow};
+use llm_orchestrator_state::{RunPriority, StepState, WorkflowDefinition, WorkflowStatus};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::Stream;
+use tonic::{Request, Response, Status};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+#[allow(clippy::all)]
+pub(crate) mod proto {
+    tonic::include_proto!("llm_orchestrator.v1");
+
+    /// Encoded descriptors of the protos, served by reflection.
+    pub(crate) const FILE_DESCRIPTOR_SET: &[u8] = tonic::include_file_descriptor_set!("orchestrator_descriptor");
+}
+
+use proto::orchestrator_server::{Orchestrator, OrchestratorServer};
+use proto::{
+    run_event, CancelRunRequest, CancelRunResponse, GetRunRequest, Priority, RunEvent, RunStatus, RunStatusChanged,
+    StartRunRequest, StartRunResponse, StepStatus, StepStatusChanged, StreamEventsRequest, SubmitWorkflowRequest,
+    SubmitWorkflowResponse,
+};
+
+/// How often `StreamEvents` checks a run for changes.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Builds the router serving the orchestrator and reflection services.
+///
+/// Event streams end when `stopping` is cancelled, so they do not hold up a
+/// graceful shutdown.
+pub(crate) fn router(
+    service: RunService,
+    auth: Arc<AuthMiddleware>,
+    stopping: CancelHandle,
+) -> anyhow::Result<tonic::service::Routes> {
+    let reflection = tonic_reflection::server::Builder::configure()
+        .register_encoded_file_descriptor_set(proto::FILE_DESCRIPTOR_SET)
+        .build_v1()?;
+    let orchestrator = OrchestratorServer::new(GrpcService { service, auth, stopping });
+    Ok(tonic::service::Routes::new(orchestrator).add_service(reflection))
+}
+
+/// The `Orchestrator` service.
+struct GrpcService {
+    service: RunService,
+    auth: Arc<AuthMiddleware>,
+    stopping: CancelHandle,
+}
+
+impl GrpcService {
+    /// Authenticates the caller from the `authorization` metadata and checks
+    /// it holds `permission`.
+    async fn authorize<T>(&self, request: &Request<T>, permission: Permission) -> Result<AuthContext, Status> {
+        let header = request.metadata().get("authorization").and_then(|value| value.to_str().ok());
+        let address = request.remote_addr().map(|address| address.ip().to_string());
+        let context = self
+            .auth
+            .authenticate_from(header, address.as_deref())
+            .await
+            .map_err(|e| Status::unauthenticated(e.to_string()))?;
+        context
+            .require_permission(&permission)
+            .map_err(|e| Status::permission_denied(e.to_string()))?;
+        Ok(context)
+    }
+
+    /// Loads a run of the caller's tenant; runs of other tenants are not found.
+    async fn run(&self, context: &AuthContext, run_id: Uuid) -> Result<RunRecord, Status> {
+        let record = self.service.run(run_id).await?;
+        if record.tenant() != context.tenant_id.as_deref() {
+            return Err(Status::not_found(format!("Run {} not found", run_id)));
+        }
+        Ok(record)
+    }
+}
+
+impl From<ServiceError> for Status {
+    fn from(error: ServiceError) -> Self {
+        match error {
+            ServiceError::NotFound(message) => Status::not_found(message),
+            ServiceError::Conflict(message) => Status::failed_precondition(message),
+            ServiceError::Saturated(rejection) => {
+                let mut status = Status::resource_exhausted(rejection.to_string());
+                // Same hint as the HTTP Retry-After header, in seconds
+                if let Ok(value) = rejection.retry_after.as_secs().to_string().parse() {
+                    status.metadata_mut().insert("retry-after", value);
+                }
+                status
+            }
+            ServiceError::Internal(message) => Status::internal(message),
+        }
+    }
+}
+
+fn parse_run_id(run_id: &str) -> Result<Uuid, Status> {
+    Uuid::parse_str(run_id).map_err(|_| Status::invalid_argument(format!("Invalid run ID: {}", run_id)))
+}
+
+fn timestamp(time: chrono::DateTime<chrono::Utc>) -> prost_types::Timestamp {
+    SystemTime::from(time).into()
+}
+
+fn run_status(status: &WorkflowStatus) -> RunStatus {
+    match status {
+        WorkflowStatus::Pending => RunStatus::Queued,
+        WorkflowStatus::Running => RunStatus::Running,
+        WorkflowStatus::Paused => RunStatus::Paused,
+        WorkflowStatus::Completed => RunStatus::Completed,
+        WorkflowStatus::Failed => RunStatus::Failed,
+        WorkflowStatus::Cancelled => RunStatus::Cancelled,
+    }
+}
+
+fn step(state: &StepState) -> proto::Step {
+    use llm_orchestrator_state::StepStatus as Status;
+    let status = match state.status {
+        Status::Pending => StepStatus::Pending,
+        Status::Running => StepStatus::Running,
+        Status::Completed => StepStatus::Completed,
+        Status::Failed => StepStatus::Failed,
+        Status::Skipped => StepStatus::Skipped,
+        Status::Cancelled => StepStatus::Cancelled,
+    };
+    proto::Step {
+        id: state.step_id.clone(),
+        status: status.into(),
+        error: state.error.clone().unwrap_or_default(),
+        outputs: match &state.outputs {
+            Value::Null => String::new(),
+            outputs => outputs.to_string(),
+        },
+        retry_count: state.retry_count,
+        started_at: state.started_at.map(timestamp),
+        completed_at: state.completed_at.map(timestamp),
+    }
+}
+
+/// Steps of a run, ordered by start time.
+fn steps(record: &RunRecord) -> Vec<proto::Step> {
+    let mut states: Vec<&StepState> = record.state.iter().flat_map(|state| state.steps.values()).collect();
+    states.sort_by_key(|state| (state.started_at, state.step_id.clone()));
+    states.into_iter().map(step).collect()
+}
+
+fn run(record: &RunRecord) -> proto::Run {
+    proto::Run {
+        id: record.id.to_string(),
+        workflow_name: record
+            .state
+            .as_ref()
+            .map(|state| state.workflow_name.clone())
+            .unwrap_or_default(),
+        status: run_status(&record.status()).into(),
+        error: record.error().unwrap_or_default().to_string(),
+        started_at: record.state.as_ref().map(|state| timestamp(state.started_at)),
+        completed_at: record.state.as_ref().and_then(|state| state.completed_at).map(timestamp),
+        steps: steps(record),
+    }
+}
+
+/// Events for what changed in a run since it was `seen`, updating `seen`.
+///
+/// Step changes come before the run's, so the last event of a finished run
+/// reports its final status.
+fn changes(record: &RunRecord, seen: &mut HashMap<String, i32>) -> Vec<RunEvent> {
+    let event = |event| RunEvent {
+        run_id: record.id.to_string(),
+        time: Some(SystemTime::now().into()),
+        event: Some(event),
+    };
+    let mut events = Vec::new();
+    for step in steps(record) {
+        if seen.insert(step.id.clone(), step.status) != Some(step.status) {
+            events.push(event(run_event::Event::Step(StepStatusChanged { step: Some(step) })));
+        }
+    }
+    // Step IDs cannot be empty, so the run's own status is kept under ""
+    let status = i32::from(run_status(&record.status()));
+    if seen.insert(String::new(), status) != Some(status) {
+        events.push(event(run_event::Event::Run(RunStatusChanged {
+            status,
+            error: record.error().unwrap_or_default().to_string(),
+        })));
+    }
+    events
+}
+
+type EventStream = Pin<Box<dyn Stream<Item = Result<RunEvent, Status>> + Send>>;
+
+#[tonic::async_trait]
+impl Orchestrator for GrpcService {
+    async fn submit_workflow(
+        &self,
+        request: Request<SubmitWorkflowRequest>,
+    ) -> Result<Response<SubmitWorkflowResponse>, Status> {
+        self.authorize(&request, Permission::WorkflowWrite).await?;
+        let request = request.into_inner();
+        // The registry keeps no other files to resolve includes against
+        if has_includes(&request.definition) {
+            return Err(Status::invalid_argument("Workflows with includes must be inlined before publishing"));
+        }
+        let workflow = Workflow::from_yaml(&request.definition)
+            .map_err(|e| Status::invalid_argument(format!("Failed to parse workflow YAML: {}", e)))?;
+        workflow
+            .validate()
+            .map_err(|e| Status::invalid_argument(format!("Workflow validation failed: {}", e)))?;
+
+        let mut definition = WorkflowDefinition::new(&workflow.name, &workflow.version, request.definition);
+        definition.created_by = Some(request.created_by).filter(|created_by| !created_by.is_empty());
+        let created = self.service.publish(&definition).await?;
+        if created {
+            info!(workflow = %definition.name, version = %definition.version, "Workflow published over gRPC");
+        }
+        Ok(Response::new(SubmitWorkflowResponse {
+            name: definition.name,
+            version: definition.version,
+            checksum: definition.checksum,
+            created,
+        }))
+    }
+
+    async fn start_run(&self, request: Request<StartRunRequest>) -> Result<Response<StartRunResponse>, Status> {
+        let context = self.authorize(&request, Permission::WorkflowExecute).await?;
+        let request = request.into_inner();
+        if request.workflow.is_empty() {
+            return Err(Status::invalid_argument("A workflow name is required"));
+        }
+        // The tenant comes from the credentials; naming another one is refused
+        if !request.tenant.is_empty() && context.tenant_id.as_deref() != Some(request.tenant.as_str()) {
+            return Err(Status::permission_denied(format!(
+                "Credentials are not scoped to tenant {}",
+                request.tenant
+            )));
+        }
+        let inputs: HashMap<String, Value> = if request.inputs.trim().is_empty() {
+            HashMap::new()
+        } else {
+            serde_json::from_str(&request.inputs)
+                .map_err(|e| Status::invalid_argument(format!("Inputs are not a JSON object: {}", e)))?
+        };
+        let priority = match request.priority() {
+            Priority::High => RunPriority::High,
+            Priority::Unspecified | Priority::Normal => RunPriority::Normal,
+            Priority::Low => RunPriority::Low,
+        };
+        let options = SubmitOptions {
+            priority,
+            tenant_id: context.tenant_id,
+        };
+        let version = Some(request.version.as_str()).filter(|version| !version.is_empty());
+        let workflow = self.service.published_workflow(&request.workflow, version).await?;
+        let run_id = self.service.start(&workflow, inputs, options).await?;
+
+        info!(%run_id, workflow_name = %workflow.name, "Run started over gRPC");
+        Ok(Response::new(StartRunResponse {
+            run_id: run_id.to_string(),
+        }))
+    }
+
+    async fn get_run(&self, request: Request<GetRunRequest>) -> Result<Response<proto::Run>, Status> {
+        let context = self.authorize(&request, Permission::ExecutionRead).await?;
+        let run_id = parse_run_id(&request.get_ref().run_id)?;
+        let record = self.run(&context, run_id).await?;
+        Ok(Response::new(run(&record)))
+    }
+
+    type StreamEventsStream = EventStream;
+
+    async fn stream_events(
+        &self,
+        request: Request<StreamEventsRequest>,
+    ) -> Result<Response<Self::StreamEventsStream>, Status> {
+        let context = self.authorize(&request, Permission::ExecutionRead).await?;
+        let run_id = parse_run_id(&request.get_ref().run_id)?;
+        // Unknown runs fail the call instead of the stream
+        let mut record = self.run(&context, run_id).await?;
+
+        let (tx, rx) = mpsc::channel(16);
+        let service = self.service.clone();
+        let stopping = self.stopping.clone();
+        tokio::spawn(async move {
+            let mut seen = HashMap::new();
+            loop {
+                for event in changes(&record, &mut seen) {
+                    if tx.send(Ok(event)).await.is_err() {
+                        return;
+                    }
+                }
+                if record.is_finished() {
+                    return;
+                }
+                tokio::select! {
+                    _ = tokio::time::sleep(POLL_INTERVAL) => {}
+                    _ = tx.closed() => return,
+                    _ = stopping.cancelled() => {
+                        let _ = tx.send(Err(Status::unavailable("Server shutting down"))).await;
+                        return;
+                    }
+                }
+                record = match service.run(run_id).await {
+                    Ok(record) => record,
+                    Err(e) => {
+                        warn!(%run_id, "Failed to poll run: {}", e);
+                        let _ = tx.send(Err(e.into())).await;
+                        return;
+                    }
+                };
+            }
+        });
+        Ok(Response::new(Box::pin(ReceiverStream::new(rx))))
+    }
+
+    async fn cancel_run(&self, request: Request<CancelRunRequest>) -> Result<Response<CancelRunResponse>, Status> {
+        let context = self.authorize(&request, Permission::ExecutionCancel).await?;
+        let run_id = parse_run_id(&request.get_ref().run_id)?;
+        self.run(&context, run_id).await?;
+        self.service.cancel(run_id).await?;
+        info!(%run_id, "Run cancelled over gRPC");
+        Ok(Response::new(CancelRunResponse {}))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use llm_orchestrator_auth::{ApiKeyManager, InMemoryApiKeyStore, JwtAuth, RbacEngine};
+    use tonic::Code;
+
+    const SECRET: &[u8] = b"grpc-test-signing-key-of-32-bytes!";
+
+    const WORKFLOW: &str = "name: greeting\nversion: '1.0'\nsteps:\n  \
+                            - {id: greet, type: llm, provider: openai, model: gpt-4, prompt: Hello}\n";
+
+    async fn grpc_service() -> GrpcService {
+        let database = crate::connect_database(":memory:").await.unwrap();
+        let auth = AuthMiddleware::new(
+            Arc::new(JwtAuth::new(SECRET.to_vec())),
+            Arc::new(ApiKeyManager::new(Arc::new(InMemoryApiKeyStore::new()))),
+            Arc::new(RbacEngine::new()),
+        );
+        GrpcService {
+            service: RunService::new(&database, None),
+            auth: Arc::new(auth),
+            stopping: CancelHandle::default(),
+        }
+    }
+
+    fn token(tenant: Option<&str>, role: &str) -> String {
+        let jwt = JwtAuth::new(SECRET.to_vec());
+        match tenant {
+            Some(tenant) => jwt.generate_tenant_token("caller", tenant, vec![role.to_string()]),
+            None => jwt.generate_token("caller", vec![role.to_string()]),
+        }
+        .unwrap()
+    }
+
+    fn authorized<T>(message: T, token: &str) -> Request<T> {
+        let mut request = Request::new(message);
+        let value = format!("Bearer {}", token).parse().unwrap();
+        request.metadata_mut().insert("authorization", value);
+        request
+    }
+
+    #[tokio::test]
+    async fn test_calls_without_credentials_are_unauthenticated() {
+        let grpc = grpc_service().await;
+        let submit = SubmitWorkflowRequest {
+            definition: WORKFLOW.to_string(),
+            ..Default::default()
+        };
+        assert_eq!(grpc.submit_workflow(Request::new(submit)).await.unwrap_err().code(), Code::Unauthenticated);
+        let start = StartRunRequest {
+            workflow: "greeting".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(grpc.start_run(Request::new(start)).await.unwrap_err().code(), Code::Unauthenticated);
+        let cancel = CancelRunRequest {
+            run_id: Uuid::new_v4().to_string(),
+        };
+        let error = grpc.cancel_run(authorized(cancel, "not-a-token")).await.unwrap_err();
+        assert_eq!(error.code(), Code::Unauthenticated);
+    }
+
+    #[tokio::test]
+    async fn test_runs_belong_to_the_tenant_of_the_credentials() {
+        let grpc = grpc_service().await;
+        let submit = SubmitWorkflowRequest {
+            definition: WORKFLOW.to_string(),
+            ..Default::default()
+        };
+        let acme = token(Some("acme"), "executor");
+        let error = grpc.submit_workflow(authorized(submit.clone(), &acme)).await.unwrap_err();
+        assert_eq!(error.code(), Code::PermissionDenied);
+        grpc.submit_workflow(authorized(submit, &token(None, "admin"))).await.unwrap();
+
+        // The request cannot pick another tenant than the token's
+        let start = StartRunRequest {
+            workflow: "greeting".to_string(),
+            tenant: "globex".to_string(),
+            ..Default::default()
+        };
+        let error = grpc.start_run(authorized(start.clone(), &acme)).await.unwrap_err();
+        assert_eq!(error.code(), Code::PermissionDenied);
+        let start = StartRunRequest {
+            tenant: String::new(),
+            ..start
+        };
+        let run_id = grpc.start_run(authorized(start, &acme)).await.unwrap().into_inner().run_id;
+        let record = grpc.service.run(parse_run_id(&run_id).unwrap()).await.unwrap();
+        assert_eq!(record.tenant(), Some("acme"));
+
+        let get = GetRunRequest { run_id: run_id.clone() };
+        grpc.get_run(authorized(get.clone(), &acme)).await.unwrap();
+        let globex = token(Some("globex"), "developer");
+        assert_eq!(grpc.get_run(authorized(get, &globex)).await.unwrap_err().code(), Code::NotFound);
+        let cancel = CancelRunRequest { run_id };
+        let error = grpc.cancel_run(authorized(cancel.clone(), &globex)).await.unwrap_err();
+        assert_eq!(error.code(), Code::NotFound);
+        // Cancelling needs more than the executor role
+        let error = grpc.cancel_run(authorized(cancel.clone(), &acme)).await.unwrap_err();
+        assert_eq!(error.code(), Code::PermissionDenied);
+        grpc.cancel_run(authorized(cancel, &token(Some("acme"), "developer"))).await.unwrap();
+    }
+}
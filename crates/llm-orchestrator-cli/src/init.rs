@@ -0,0 +1,315 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Interactive workflow scaffolding.
+//!
+//! `init [FILE]` asks for a workflow name, its inputs, a chain of LLM steps,
+//! a provider and model, and where the final outputs go, then writes a
+//! commented workflow that validates as is. With `--yes`, or when stdin is
+//! not a terminal, every question takes its default.
+
+use anyhow::{Context, Result};
+use colored::Colorize;
+use llm_orchestrator_core::result_sink::SinkConfig;
+use llm_orchestrator_core::Workflow;
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::Path;
+
+/// Answers to the wizard's questions.
+struct Answers {
+    name: String,
+    description: String,
+    inputs: Vec<String>,
+    steps: Vec<String>,
+    provider: String,
+    model: String,
+    sink: Option<SinkConfig>,
+}
+
+/// Asks questions on stdin, or answers them with their defaults.
+struct Prompter {
+    interactive: bool,
+}
+
+impl Prompter {
+    /// Ask `question` until `parse` accepts the answer; an empty answer takes `default`.
+    fn ask<T>(&self, question: &str, default: &str, parse: impl Fn(&str) -> Result<T, String>) -> Result<T> {
+        if !self.interactive {
+            return parse(default).map_err(|e| anyhow::anyhow!("Invalid default for '{}': {}", question, e));
+        }
+        let stdin = io::stdin();
+        loop {
+            if default.is_empty() {
+                print!("{} ", format!("{}:", question).bold());
+            } else {
+                print!("{} [{}] ", format!("{}:", question).bold(), default);
+            }
+            io::stdout().flush()?;
+
+            let mut line = String::new();
+            if stdin.lock().read_line(&mut line)? == 0 {
+                anyhow::bail!("Input ended before the workflow was complete");
+            }
+            let answer = match line.trim() {
+                "" => default,
+                answer => answer,
+            };
+            match parse(answer) {
+                Ok(value) => return Ok(value),
+                Err(e) => println!("  {} {}", "✗".red(), e),
+            }
+        }
+    }
+}
+
+/// Scaffold a new workflow at `path`.
+pub(crate) fn init_workflow(path: &Path, force: bool, use_defaults: bool) -> Result<()> {
+    if path.exists() && !force {
+        anyhow::bail!("{} already exists; pass --force to overwrite it", path.display());
+    }
+
+    let prompter = Prompter {
+        interactive: !use_defaults && io::stdin().is_terminal(),
+    };
+    if prompter.interactive {
+        println!("{}", "Scaffolding a new workflow; press Enter to take the default in brackets.".cyan());
+    }
+    let answers = ask_all(&prompter, path)?;
+    let yaml = render(&answers);
+
+    // The scaffold must be a valid workflow, whatever was answered
+    let workflow = Workflow::from_yaml(&yaml).with_context(|| "Scaffolded workflow does not parse")?;
+    workflow.validate().with_context(|| "Scaffolded workflow is invalid")?;
+
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    fs::write(path, yaml).with_context(|| format!("Failed to write workflow: {}", path.display()))?;
+
+    let example: serde_json::Map<String, serde_json::Value> =
+        answers.inputs.iter().map(|input| (input.clone(), serde_json::Value::from("..."))).collect();
+    println!("{} {}", "✓ Created".green().bold(), path.display());
+    println!("\nNext steps:");
+    println!("  llm-orchestrator validate {}", path.display());
+    println!(
+        "  llm-orchestrator run {} --input '{}'",
+        path.display(),
+        serde_json::Value::Object(example)
+    );
+    Ok(())
+}
+
+fn ask_all(prompter: &Prompter, path: &Path) -> Result<Answers> {
+    let default_name = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .filter(|stem| *stem != "workflow")
+        .unwrap_or("my-workflow")
+        .to_string();
+    let name = prompter.ask("Workflow name", &default_name, |answer| Ok(answer.to_string()))?;
+    let description = prompter.ask("Description", "", |answer| Ok(answer.to_string()))?;
+    let inputs = prompter.ask("Inputs (comma-separated names)", "text", |answer| identifiers(answer, "input"))?;
+    let steps = prompter.ask("Steps, run in order (comma-separated IDs)", "draft, review", |answer| {
+        identifiers(answer, "step")
+    })?;
+
+    // Prefer the provider whose API key is set
+    let default_provider = if std::env::var("OPENAI_API_KEY").is_err() && std::env::var("ANTHROPIC_API_KEY").is_ok() {
+        "anthropic"
+    } else {
+        "openai"
+    };
+    let provider = prompter.ask("Provider (openai or anthropic)", default_provider, |answer| match answer {
+        "openai" | "anthropic" => Ok(answer.to_string()),
+        other => Err(format!("Unknown provider '{}'; choose openai or anthropic", other)),
+    })?;
+    let default_model = match provider.as_str() {
+        "anthropic" => "claude-3-5-haiku-20241022",
+        _ => "gpt-4o-mini",
+    };
+    let model = prompter.ask("Model", default_model, |answer| Ok(answer.to_string()))?;
+
+    let sink = prompter.ask(
+        "Write final outputs to (file path, s3://BUCKET/KEY, or URL; empty for none)",
+        "",
+        |answer| match answer {
+            "" => Ok(None),
+            answer => answer.parse::<SinkConfig>().map(Some),
+        },
+    )?;
+
+    Ok(Answers {
+        name,
+        description,
+        inputs,
+        steps,
+        provider,
+        model,
+        sink,
+    })
+}
+
+/// Parse comma-separated identifiers usable in templates.
+fn identifiers(answer: &str, kind: &str) -> Result<Vec<String>, String> {
+    let mut names: Vec<String> = Vec::new();
+    for name in answer.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+        let valid = name.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid {
+            return Err(format!("'{}' is not a valid {} name; use letters, digits, and '_'", name, kind));
+        }
+        if names.iter().any(|n| n == name) {
+            return Err(format!("Duplicate {} name '{}'", kind, name));
+        }
+        names.push(name.to_string());
+    }
+    if names.is_empty() {
+        return Err(format!("Name at least one {}", kind));
+    }
+    Ok(names)
+}
+
+/// A YAML scalar for `text`; JSON strings are valid YAML.
+fn quoted(text: &str) -> String {
+    serde_json::Value::from(text).to_string()
+}
+
+/// The commented workflow for `answers`.
+fn render(answers: &Answers) -> String {
+    let mut yaml = String::new();
+    let _ = writeln!(yaml, "# {}", answers.name);
+    yaml.push_str("#\n# Scaffolded by `llm-orchestrator init`. Each step sends a prompt to the\n");
+    yaml.push_str("# model and stores the reply as its `result` output, which later steps\n");
+    yaml.push_str("# reference as {{ steps.<id>.result }}. Inputs are {{ inputs.<name> }}.\n\n");
+    let _ = writeln!(yaml, "name: {}", quoted(&answers.name));
+    yaml.push_str("version: \"1.0\"\n");
+    if !answers.description.is_empty() {
+        let _ = writeln!(yaml, "description: {}", quoted(&answers.description));
+    }
+
+    yaml.push_str("\nsteps:\n");
+    let mut previous: Option<&str> = None;
+    for step in &answers.steps {
+        match previous {
+            None => yaml.push_str("  # Works on the workflow inputs\n"),
+            Some(previous) => {
+                let _ = writeln!(yaml, "  # Builds on the reply of '{}'", previous);
+            }
+        }
+        let _ = writeln!(yaml, "  - id: {}", step);
+        yaml.push_str("    type: llm\n");
+        if let Some(previous) = previous {
+            let _ = writeln!(yaml, "    depends_on: [{}]", previous);
+        }
+        let _ = writeln!(yaml, "    provider: {}", answers.provider);
+        let _ = writeln!(yaml, "    model: {}", quoted(&answers.model));
+        yaml.push_str("    prompt: |\n");
+        match previous {
+            None => {
+                yaml.push_str("      TODO: say what to do with the inputs.\n");
+                for input in &answers.inputs {
+                    let _ = writeln!(yaml, "\n      {}: {{{{ inputs.{} }}}}", input, input);
+                }
+            }
+            Some(previous) => {
+                yaml.push_str("      TODO: say what to do with the previous reply.\n\n");
+                let _ = writeln!(yaml, "      {{{{ steps.{}.result }}}}", previous);
+            }
+        }
+        yaml.push_str("    # Lower is more deterministic\n");
+        yaml.push_str("    temperature: 0.7\n");
+        yaml.push_str("    max_tokens: 500\n");
+        yaml.push_str("    output: [result]\n");
+        yaml.push_str("    retry:\n      max_attempts: 3\n\n");
+        previous = Some(step);
+    }
+
+    yaml.push_str("# Where the final outputs go when a run finishes: a file, an S3 object, or a\n");
+    yaml.push_str("# webhook. Targets may use {{ run_id }}, {{ workflow }}, {{ timestamp }},\n");
+    yaml.push_str("# and {{ date }}.\n");
+    match &answers.sink {
+        Some(sink) => {
+            yaml.push_str("outputs:\n  sink:\n");
+            let sink = serde_yaml::to_string(sink).unwrap_or_default();
+            for line in sink.lines() {
+                let _ = writeln!(yaml, "    {}", line);
+            }
+        }
+        None => {
+            yaml.push_str("# outputs:\n#   sink:\n#     type: file\n");
+            yaml.push_str("#     path: \"results/{{ workflow }}-{{ timestamp }}.json\"\n");
+        }
+    }
+    yaml
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identifiers() {
+        assert_eq!(identifiers(" text, _doc2 ,", "input").unwrap(), ["text", "_doc2"]);
+        assert!(identifiers("2nd", "step").unwrap_err().contains("not a valid step name"));
+        assert!(identifiers("draft-1", "step").is_err());
+        assert!(identifiers("a, a", "input").unwrap_err().contains("Duplicate input name 'a'"));
+        assert!(identifiers(" , ", "step").unwrap_err().contains("at least one step"));
+    }
+
+    #[test]
+    fn test_defaults_scaffold_a_valid_chain() {
+        let prompter = Prompter { interactive: false };
+        let answers = ask_all(&prompter, Path::new("flows/support.yaml")).unwrap();
+        assert_eq!(answers.name, "support");
+        assert_eq!(answers.steps, ["draft", "review"]);
+
+        let yaml = render(&answers);
+        let workflow = Workflow::from_yaml(&yaml).unwrap();
+        workflow.validate().unwrap();
+        assert_eq!(workflow.steps[1].depends_on, ["draft"]);
+        assert!(yaml.contains("      text: {{ inputs.text }}\n"));
+        assert!(yaml.contains("      {{ steps.draft.result }}\n"));
+        assert!(workflow.outputs.is_none());
+
+        // A generic file name gets a generic workflow name
+        let answers = ask_all(&prompter, Path::new("workflow.yaml")).unwrap();
+        assert_eq!(answers.name, "my-workflow");
+    }
+
+    #[test]
+    fn test_render_quotes_answers_and_writes_the_sink() {
+        let answers = Answers {
+            name: "say \"hi\": now".to_string(),
+            description: "# not a comment".to_string(),
+            inputs: vec!["a".to_string(), "b".to_string()],
+            steps: vec!["only".to_string()],
+            provider: "anthropic".to_string(),
+            model: "claude-3-5-haiku-20241022".to_string(),
+            sink: Some("results/{{ run_id }}.json".parse().unwrap()),
+        };
+        let workflow = Workflow::from_yaml(&render(&answers)).unwrap();
+        workflow.validate().unwrap();
+        assert_eq!(workflow.name, "say \"hi\": now");
+        assert_eq!(workflow.description.as_deref(), Some("# not a comment"));
+        assert!(workflow.outputs.and_then(|outputs| outputs.sink).is_some());
+    }
+
+    #[test]
+    fn test_init_does_not_overwrite_without_force() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested/review.yaml");
+        init_workflow(&path, false, true).unwrap();
+        let written = fs::read_to_string(&path).unwrap();
+        assert!(written.starts_with("# review\n"));
+
+        fs::write(&path, "kept").unwrap();
+        let err = init_workflow(&path, false, true).unwrap_err();
+        assert!(err.to_string().contains("pass --force"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "kept");
+
+        init_workflow(&path, true, true).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), written);
+    }
+}
@@ -3,19 +3,68 @@
 
 //! LLM Orchestrator CLI.
 
+mod auth;
+mod completions;
+mod grpc;
+mod init;
+mod serve;
+mod service;
+
 use anyhow::{Context, Result};
-use clap::{Parser, Subcommand};
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
+use completions::Shell;
+use init::init_workflow;
 use colored::Colorize;
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use llm_orchestrator_audit::{
+    export_events, AuditEvent, AuditEventType, AuditLogger, AuditPage, AuditQuery, AuditResult,
+    AuditStorage, DatabaseAuditStorage, ExportFormat, FileAuditStorage, ResourceType,
+    RotationPolicy, TenantAuditStorage,
+};
+use llm_orchestrator_core::audit::AuditConfig;
 use llm_orchestrator_core::workflow::Workflow;
-use llm_orchestrator_core::{LLMProvider, WorkflowDAG, WorkflowExecutor};
-use llm_orchestrator_providers::{AnthropicProvider, OpenAIProvider};
+use llm_orchestrator_core::dead_letter::retry_dead_letter;
+use llm_orchestrator_core::executor_state::RerunScope;
+use llm_orchestrator_core::diagnostics::{check_yaml_with, Severity};
+use llm_orchestrator_core::document::{LoadDocumentAction, LOAD_ACTION};
+use llm_orchestrator_core::include::{has_includes, inline, FileResolver};
+use llm_orchestrator_core::inputs::{InputFormat, InputLoader, InputSource, DEFAULT_MAX_BYTES};
+use llm_orchestrator_core::lint::{lint_yaml_with, Level, LintConfig, LintRule};
+use llm_orchestrator_core::mcp::load_servers as load_mcp_servers;
+use llm_orchestrator_core::memory::{InMemoryMemoryStore, MemoryStore, StateMemoryStore};
+use llm_orchestrator_core::notify::{PagerDutyNotifier, SlackNotifier};
+use llm_orchestrator_core::prompts::{
+    diff_lines, parse_prompt_version, DiffLine, FilePromptStore, PromptRef, PromptRegistry,
+    PromptStore, StatePromptStore,
+};
+use llm_orchestrator_core::recovery::{RecoveryPolicy, RunRecovery};
+use llm_orchestrator_core::artifact::ArtifactStoreConfig;
+use llm_orchestrator_core::result_sink::SinkConfig;
+use llm_orchestrator_core::worker::{SubmitOptions, Worker};
+use llm_orchestrator_core::{
+    ActionHandler, AdmissionLimits, ArtifactSpill, ContextLimits, Diagnostic, DiagnosticCode, ExecutionReport, FailureNotifier, GoldenRecorder, GoldenRecording, HealthRegistry, HealthStatus, LLMProvider, McpClient, MessageSink, MessageSource, MockResponses, OrchestratorError, ReportFormat,
+    ResourceClass, StepStatus, Trigger, WorkflowDAG, WorkflowExecutor,
+};
+use llm_orchestrator_providers::{
+    AnthropicProvider, CreateIndexRequest, DistanceMetric, MetadataFieldType, OpenAIProvider, PineconeClient,
+    QdrantClient, VectorSearchProvider, WeaviateClient,
+};
+use llm_orchestrator_secrets::{EnvSecretStore, Redactor};
+use llm_orchestrator_state::{
+    DeadLetterQueue, OutputOffload, PostgresStateStore, PromptVersionStore, RetentionPolicy, RetentionStore, RunPriority,
+    RunQuery, RunSortField, SchemaMigrations, SessionMemoryStore, SqliteStateStore, StateEncryption, StateRetentionManager,
+    StateStore, TenantStateStore, UsageStore, WorkQueue, WorkflowDefinition, WorkflowDefinitionStore, WorkflowStatus,
+};
 use serde_json::Value;
+use service::RunService;
 use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 use tracing::{error, info};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use uuid::Uuid;
 
 #[derive(Parser)]
 #[command(name = "llm-orchestrator")]
@@ -27,8 +76,379 @@ struct Cli {
     /// Enable verbose logging
     #[arg(short, long, global = true)]
     verbose: bool,
+
+    /// Print only the result document (JSON unless `--format yaml`) and errors
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+
+    /// Export traces over OTLP/HTTP (e.g. http://localhost:4318/v1/traces)
+    #[cfg(feature = "otel")]
+    #[arg(long, global = true, value_name = "URL")]
+    otlp_endpoint: Option<String>,
+
+    /// Record runs and audit events under this tenant and confine queries to it
+    #[arg(long, global = true, value_name = "ID")]
+    tenant: Option<String>,
+
+    /// MCP servers for `mcp_tool` steps, in MCP host format ({"mcpServers": {...}})
+    #[arg(long, global = true, value_name = "FILE")]
+    mcp_config: Option<PathBuf>,
+
+    /// Serve the `document.load` action from files under this directory
+    #[arg(long, global = true, value_name = "DIR")]
+    documents_dir: Option<PathBuf>,
+
+    /// Seconds running steps and requests may take to finish after SIGINT or SIGTERM
+    #[arg(long, global = true, value_name = "SECONDS", default_value_t = 30)]
+    drain_timeout: u64,
+
+    #[command(flatten)]
+    audit: AuditArgs,
+
+    #[command(flatten)]
+    prompts: PromptArgs,
+
+    #[command(flatten)]
+    brokers: BrokerArgs,
+
+    #[command(flatten)]
+    memory: MemoryArgs,
+
+    #[command(flatten)]
+    notify: NotifyArgs,
+}
+
+#[derive(Args)]
+struct AuditArgs {
+    /// Append audit events as JSON lines to this file
+    #[arg(long, global = true, value_name = "FILE")]
+    audit_log: Option<PathBuf>,
+
+    /// User or service recorded as the actor of audit events and the user of persisted runs
+    #[arg(long, global = true, value_name = "NAME", default_value = "cli")]
+    audit_actor: String,
+
+    /// Record rendered prompts and completions in audit events
+    #[arg(long, global = true)]
+    audit_include_prompts: bool,
+}
+
+#[derive(Args)]
+struct PromptArgs {
+    /// Prompt registry directory
+    #[arg(long, global = true, value_name = "DIR", default_value = "prompts")]
+    prompts_dir: PathBuf,
+
+    /// Keep the prompt registry in this database instead (postgres:// URL or SQLite file path)
+    #[arg(long, global = true, value_name = "URL")]
+    prompts_database_url: Option<String>,
+}
+
+#[derive(Args)]
+struct BrokerArgs {
+    /// Kafka bootstrap servers, for `kafka` triggers and the `kafka` sink of `publish` actions
+    #[cfg(feature = "kafka")]
+    #[arg(long, global = true, value_name = "HOSTS")]
+    kafka_brokers: Option<String>,
+
+    /// NATS server URL, for `nats` triggers and the `nats` sink of `publish` actions
+    #[cfg(feature = "nats")]
+    #[arg(long, global = true, value_name = "URL")]
+    nats_url: Option<String>,
+}
+
+#[derive(Args, Default)]
+struct MemoryArgs {
+    /// Keep session memory in this database (postgres:// URL or SQLite file path)
+    #[arg(long, global = true, value_name = "URL")]
+    memory_database_url: Option<String>,
+
+    /// Keep session memory in Redis at this URL (e.g. redis://localhost:6379)
+    #[cfg(feature = "redis")]
+    #[arg(long, global = true, value_name = "URL", conflicts_with = "memory_database_url")]
+    memory_redis_url: Option<String>,
+}
+
+#[derive(Args)]
+struct NotifyArgs {
+    /// Alert these channels when a run fails (repeatable; configured through environment variables)
+    #[arg(long, global = true, value_name = "CHANNEL", value_delimiter = ',')]
+    notify_on_failure: Vec<NotifyChannel>,
+
+    /// Recipient of failure emails (repeatable)
+    #[arg(long, global = true, value_name = "ADDRESS")]
+    notify_email: Vec<String>,
+}
+
+#[derive(Args)]
+struct InputArgs {
+    /// Input JSON string or file, or `-` to read JSON from standard input
+    #[arg(short, long)]
+    input: Option<String>,
+
+    /// Read inputs from a file, glob, URL, or `-` for standard input; with NAME=
+    /// the data becomes that input, otherwise its fields become inputs; repeatable
+    #[arg(long = "input-file", value_name = "[NAME=]SOURCE")]
+    input_files: Vec<String>,
+
+    /// Format of --input-file data (json, yaml, jsonl, csv, text); inferred from extensions by default
+    #[arg(long, value_name = "FORMAT")]
+    input_format: Option<InputFormat>,
+
+    /// Maximum bytes read from each input source
+    #[arg(long, value_name = "BYTES", default_value_t = DEFAULT_MAX_BYTES)]
+    input_max_bytes: u64,
+}
+
+impl InputArgs {
+    /// Read the workflow inputs; `None` when no input is given.
+    async fn load(&self) -> Result<Option<HashMap<String, Value>>> {
+        if self.input.is_none() && self.input_files.is_empty() {
+            return Ok(None);
+        }
+
+        let mut inputs = match self.input.as_deref() {
+            Some("-") => {
+                let loader = InputLoader::new().with_max_bytes(self.input_max_bytes);
+                input_fields(loader.load(&InputSource::Stdin).await?, "-")?
+            }
+            Some(input) => parse_input(input)?,
+            None => HashMap::new(),
+        };
+
+        let mut loader = InputLoader::new().with_max_bytes(self.input_max_bytes);
+        if let Some(format) = self.input_format {
+            loader = loader.with_format(format);
+        }
+        for input_file in &self.input_files {
+            let (name, source) = match input_file.split_once('=') {
+                Some((name, source)) if is_input_name(name) => (Some(name), source),
+                _ => (None, input_file.as_str()),
+            };
+            // Errors name the source
+            let data = loader.load(&InputSource::from(source)).await?;
+            match name {
+                Some(name) => {
+                    inputs.insert(name.to_string(), data);
+                }
+                None => inputs.extend(input_fields(data, source)?),
+            }
+        }
+        Ok(Some(inputs))
+    }
+}
+
+/// Whether the text before `=` in `--input-file` names an input rather than being part of a path or URL.
+fn is_input_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+}
+
+/// The fields of input data read without a name, which must be an object.
+fn input_fields(data: Value, source: &str) -> Result<HashMap<String, Value>> {
+    match data {
+        Value::Object(fields) => Ok(fields.into_iter().collect()),
+        _ => anyhow::bail!("Input {} is not an object; name it with --input-file NAME={}", source, source),
+    }
+}
+
+#[derive(Args)]
+struct AuthArgs {
+    /// Also accept bearer tokens issued by this OpenID Connect provider
+    #[arg(long, value_name = "URL", requires = "oidc_audience")]
+    oidc_issuer: Option<String>,
+
+    /// Audience that tokens of the OpenID Connect provider must be issued to
+    #[arg(long, value_name = "AUDIENCE", requires = "oidc_issuer")]
+    oidc_audience: Option<String>,
+
+    /// Serve /metrics without credentials, e.g. to a Prometheus scraper
+    #[arg(long)]
+    public_metrics: bool,
+}
+
+#[derive(Args)]
+struct RecoveryArgs {
+    /// On startup, resume or fail queued runs orphaned by a dead worker
+    #[arg(long, value_name = "POLICY", default_value = "resume")]
+    orphaned_runs: OrphanedRuns,
+
+    /// Seconds an in-process run may go without an update before it is marked failed on startup
+    #[arg(long, value_name = "SECONDS", default_value = "3600")]
+    orphan_after: u64,
+}
+
+impl RecoveryArgs {
+    /// Resume or fail the runs left active by processes that died.
+    async fn recover(&self, database: &Database) -> Result<()> {
+        let policy = match self.orphaned_runs {
+            OrphanedRuns::Resume => RecoveryPolicy::Resume,
+            OrphanedRuns::Fail => RecoveryPolicy::Fail,
+        };
+        let report = RunRecovery::new(database.state.clone(), database.queue.clone())
+            .with_policy(policy)
+            .with_stale_after(Duration::from_secs(self.orphan_after))
+            .recover()
+            .await
+            .with_context(|| "Failed to recover orphaned runs")?;
+
+        if !report.is_empty() {
+            println!(
+                "{} {} resumed, {} failed",
+                "Recovered orphaned runs:".yellow().bold(),
+                report.resumed.len(),
+                report.failed.len()
+            );
+        }
+        Ok(())
+    }
+}
+
+/// What to do with orphaned queued runs.
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OrphanedRuns {
+    /// Queue them for a worker to resume from their latest checkpoint
+    Resume,
+    /// Cancel them and mark them failed
+    Fail,
+}
+
+/// Output format of `validate`, `lint`, and `run`.
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    /// Colored text for people: diagnostics with source excerpts, progress, and results
+    #[value(alias = "text")]
+    Table,
+    /// A JSON document for scripts, editors, and CI
+    Json,
+    /// The same document as YAML
+    Yaml,
+}
+
+/// How a command reports: human text, or a single JSON or YAML document on
+/// stdout for scripts.
+#[derive(Clone, Copy)]
+struct Output {
+    format: OutputFormat,
+    quiet: bool,
+}
+
+impl Output {
+    /// `--quiet` keeps only the result document, JSON unless YAML was asked for.
+    fn new(format: OutputFormat, quiet: bool) -> Self {
+        let format = match format {
+            OutputFormat::Table if quiet => OutputFormat::Json,
+            format => format,
+        };
+        Self { format, quiet }
+    }
+
+    /// Whether results are printed as text rather than a document.
+    fn is_text(self) -> bool {
+        self.format == OutputFormat::Table
+    }
+
+    /// Print a progress line: on stdout next to text results, on stderr next
+    /// to a document, and not at all when quiet.
+    fn status(self, line: impl std::fmt::Display) {
+        if self.quiet {
+            return;
+        }
+        if self.is_text() {
+            println!("{}", line);
+        } else {
+            eprintln!("{}", line);
+        }
+    }
+
+    /// Print the result document.
+    fn document(self, document: &Value) -> Result<()> {
+        match self.format {
+            OutputFormat::Yaml => print!("{}", serde_yaml::to_string(document)?),
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(document)?),
+            OutputFormat::Table => {
+                let mut text = String::new();
+                text_document(document, 0, &mut text);
+                print!("{}", text);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Renders a document as indented `key: value` lines, with list items
+/// marked by `-`.
+fn text_document(document: &Value, indent: usize, text: &mut String) {
+    let pad = " ".repeat(indent);
+    match document {
+        Value::Object(fields) => {
+            for (key, value) in fields {
+                text_entry(&format!("{}{}:", pad, key), value, indent, text);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                text_entry(&format!("{}-", pad), item, indent, text);
+            }
+        }
+        value => {
+            text.push_str(&pad);
+            text.push_str(&text_scalar(value));
+            text.push('\n');
+        }
+    }
+}
+
+/// Writes `label` with `value` after it, or below it when it has fields or items.
+fn text_entry(label: &str, value: &Value, indent: usize, text: &mut String) {
+    text.push_str(label);
+    let nested = match value {
+        Value::Array(items) => !items.is_empty(),
+        Value::Object(fields) => !fields.is_empty(),
+        _ => false,
+    };
+    if nested {
+        text.push('\n');
+        text_document(value, indent + 2, text);
+    } else {
+        text.push(' ');
+        text.push_str(&text_scalar(value));
+        text.push('\n');
+    }
+}
+
+fn text_scalar(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => "-".to_string(),
+        Value::Array(_) | Value::Object(_) => "(none)".to_string(),
+        value => value.to_string(),
+    }
 }
 
+/// A vector database managed by `index` commands or checked by `health`.
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum VectorDatabase {
+    /// Pinecone project at PINECONE_ENVIRONMENT, with the key PINECONE_API_KEY
+    Pinecone,
+    /// Weaviate instance at WEAVIATE_URL, with the optional key WEAVIATE_API_KEY
+    Weaviate,
+    /// Qdrant instance at QDRANT_URL, with the optional key QDRANT_API_KEY
+    Qdrant,
+}
+
+/// A notification channel, also the name of its action.
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum NotifyChannel {
+    /// Slack incoming webhook at SLACK_WEBHOOK_URL
+    Slack,
+    /// SMTP server at SMTP_URL, sending from SMTP_FROM
+    Email,
+    /// PagerDuty service with the integration key PAGERDUTY_ROUTING_KEY
+    Pagerduty,
+}
+
+// Parsed once per process, so the size of `run`'s many flags does not matter
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand)]
 enum Commands {
     /// Validate a workflow definition
@@ -36,186 +456,3413 @@ enum Commands {
         /// Path to workflow file
         #[arg(value_name = "FILE")]
         file: String,
+
+        /// Apply a named profile from the workflow's `profiles` section
+        #[arg(long, value_name = "NAME")]
+        profile: Option<String>,
+
+        /// Output format (table, json, or yaml)
+        #[arg(long, default_value = "table")]
+        format: OutputFormat,
     },
 
-    /// Run a workflow
-    Run {
+    /// Check a workflow for likely mistakes beyond validation
+    Lint {
         /// Path to workflow file
         #[arg(value_name = "FILE")]
         file: String,
 
-        /// Input JSON string or file
+        /// Lint configuration file setting rule levels
+        #[arg(short, long, value_name = "FILE")]
+        config: Option<PathBuf>,
+
+        /// Set the level of a rule (allow, warn, or deny); repeatable, overrides the config file
+        #[arg(long = "rule", value_name = "RULE=LEVEL", value_parser = parse_rule_level)]
+        rules: Vec<(LintRule, Level)>,
+
+        /// Fail on warnings too
+        #[arg(long)]
+        deny_warnings: bool,
+
+        /// Output format (table, json, or yaml)
+        #[arg(long, default_value = "table")]
+        format: OutputFormat,
+    },
+
+    /// Print the JSON Schema of workflow files, for editors and CI validation
+    Schema {
+        /// Write the schema to a file instead of stdout
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+
+    /// Create a new workflow file by answering a few questions
+    Init {
+        /// Path of the workflow file to create
+        #[arg(value_name = "FILE", default_value = "workflow.yaml")]
+        file: PathBuf,
+
+        /// Overwrite the file if it exists
+        #[arg(long)]
+        force: bool,
+
+        /// Take the default answer to every question
         #[arg(short, long)]
-        input: Option<String>,
+        yes: bool,
+    },
+
+    /// Print a shell completion script (bash, zsh, or fish)
+    Completions {
+        /// Shell to complete for
+        #[arg(value_name = "SHELL")]
+        shell: Shell,
+    },
+
+    /// Run a workflow
+    Run {
+        /// Path to workflow file
+        #[arg(value_name = "FILE", required_unless_present = "workflow")]
+        file: Option<String>,
+
+        /// Run a workflow published to the database instead of a file
+        #[arg(long, value_name = "NAME[@VERSION]", conflicts_with = "file", requires = "database_url")]
+        workflow: Option<String>,
+
+        #[command(flatten)]
+        inputs: InputArgs,
 
         /// Maximum concurrent steps
         #[arg(long, default_value = "4")]
         max_concurrency: usize,
+
+        /// Limit concurrent steps of a resource class (llm, embed, io, cpu); repeatable
+        #[arg(long = "resource-limit", value_name = "CLASS=N", value_parser = parse_resource_limit)]
+        resource_limits: Vec<(ResourceClass, usize)>,
+
+        /// Apply a named profile from the workflow's `profiles` section
+        #[arg(long, value_name = "NAME")]
+        profile: Option<String>,
+
+        /// Replay step outputs from a mock file instead of calling providers
+        #[arg(long, value_name = "FILE", conflicts_with = "record")]
+        mock: Option<String>,
+
+        /// Record completed step outputs into a mock file, or, given a directory
+        /// (e.g. `golden/`), provider calls and final outputs as a golden recording
+        #[arg(long, value_name = "PATH")]
+        record: Option<String>,
+
+        /// Re-run the golden recordings in DIR against their recorded provider
+        /// responses, failing when final outputs drift
+        #[arg(long, value_name = "DIR", conflicts_with_all = ["mock", "record"])]
+        replay: Option<PathBuf>,
+
+        /// Also write the final outputs to a file, s3://BUCKET/KEY, or webhook
+        /// URL; repeatable. Targets may use {{ run_id }}, {{ workflow }},
+        /// {{ timestamp }}, and {{ date }}
+        #[arg(long = "sink", value_name = "TARGET", conflicts_with = "replay")]
+        sinks: Vec<SinkConfig>,
+
+        /// Store files produced by steps in a directory or s3://BUCKET[/PREFIX],
+        /// in place of the workflow's artifacts section
+        #[arg(long, value_name = "TARGET", conflicts_with = "replay")]
+        artifacts: Option<ArtifactStoreConfig>,
+
+        /// Output format of the result (table, json, or yaml)
+        #[arg(long, default_value = "table", conflicts_with = "replay")]
+        format: OutputFormat,
+
+        /// Persist run state and failed steps (postgres:// URL or SQLite file path)
+        #[arg(long, value_name = "URL")]
+        database_url: Option<String>,
+
+        /// Re-run part of an earlier run persisted in the database, reusing the
+        /// outputs of the steps not selected by --from-step or --only-step
+        #[arg(long, value_name = "RUN_ID", requires_all = ["database_url", "rerun_step"], conflicts_with = "replay")]
+        rerun: Option<Uuid>,
+
+        /// With --rerun, run this step and every step downstream of it
+        #[arg(long, value_name = "STEP_ID", group = "rerun_step", requires = "rerun")]
+        from_step: Option<String>,
+
+        /// With --rerun, run only this step
+        #[arg(long, value_name = "STEP_ID", group = "rerun_step", requires = "rerun")]
+        only_step: Option<String>,
+
+        /// Keep step outputs larger than this many bytes as shared blobs
+        #[arg(long, value_name = "BYTES", default_value = "262144")]
+        blob_threshold: usize,
+
+        /// Spill blobs to temporary files once step outputs in memory would
+        /// exceed this many bytes
+        #[arg(long, value_name = "BYTES")]
+        context_memory_cap: Option<usize>,
+
+        /// Spill step outputs past --context-memory-cap to a directory or
+        /// s3://BUCKET[/PREFIX], reading them back for the steps that use them
+        #[arg(long, value_name = "TARGET", requires = "context_memory_cap")]
+        spill_to: Option<ArtifactStoreConfig>,
+
+        /// Send LLM calls with temperature 0 and a fixed seed, retry without
+        /// jitter, and record a reproducibility manifest in the results
+        #[arg(long)]
+        deterministic: bool,
+
+        /// Seed sent with every LLM call in deterministic mode
+        #[arg(long, default_value = "0", requires = "deterministic")]
+        seed: u64,
+
+        /// Allow `exec` actions to run local commands
+        #[arg(long)]
+        allow_exec: bool,
     },
-}
 
-#[tokio::main]
-async fn main() {
-    let cli = Cli::parse();
+    /// Submit a workflow run to the work queue
+    Submit {
+        /// Path to workflow file
+        #[arg(value_name = "FILE", required_unless_present = "workflow")]
+        file: Option<String>,
 
-    // Initialize tracing
-    let log_level = if cli.verbose {
-        tracing::Level::DEBUG
-    } else {
-        tracing::Level::INFO
-    };
+        /// Submit a workflow published to the database instead of a file
+        #[arg(long, value_name = "NAME[@VERSION]", conflicts_with = "file")]
+        workflow: Option<String>,
 
-    tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| format!("llm_orchestrator={}", log_level).into()),
-        )
-        .with(tracing_subscriber::fmt::layer())
-        .init();
+        #[command(flatten)]
+        inputs: InputArgs,
 
-    let result = match cli.command {
-        Commands::Validate { file } => validate_workflow(&file),
-        Commands::Run {
-            file,
-            input,
-            max_concurrency,
-        } => run_workflow(&file, input.as_deref(), max_concurrency).await,
-    };
+        /// Apply a named profile from the workflow's `profiles` section
+        #[arg(long, value_name = "NAME")]
+        profile: Option<String>,
 
-    if let Err(e) = result {
-        error!("{}", e);
-        eprintln!("{} {}", "Error:".red().bold(), e);
-        std::process::exit(1);
-    }
-}
+        /// Priority class workers claim the run in (high, normal, low)
+        #[arg(long, value_name = "CLASS", default_value = "normal")]
+        priority: RunPriority,
 
-fn validate_workflow(file_path: &str) -> Result<()> {
-    info!("Validating workflow: {}", file_path);
-    println!("{} {}", "Validating workflow:".cyan().bold(), file_path);
+        /// Queue database (postgres:// URL or SQLite file path)
+        #[arg(long, value_name = "URL")]
+        database_url: String,
+    },
 
-    // Read workflow file
-    let content = fs::read_to_string(file_path)
-        .with_context(|| format!("Failed to read workflow file: {}", file_path))?;
+    /// Cancel a queued or running run
+    Cancel {
+        /// Run ID returned by `submit`
+        #[arg(value_name = "RUN_ID")]
+        run_id: Uuid,
 
-    // Parse workflow
-    let workflow: Workflow = serde_yaml::from_str(&content)
-        .with_context(|| format!("Failed to parse workflow YAML: {}", file_path))?;
+        /// Queue database (postgres:// URL or SQLite file path)
+        #[arg(long, value_name = "URL")]
+        database_url: String,
+    },
 
-    info!("Parsed workflow: {} v{}", workflow.name, workflow.version);
+    /// Run a worker that executes runs from the work queue
+    Worker {
+        /// Queue database (postgres:// URL or SQLite file path)
+        #[arg(long, value_name = "URL")]
+        database_url: String,
 
-    // Validate workflow
-    workflow
-        .validate()
-        .with_context(|| "Workflow validation failed")?;
+        /// Worker ID (defaults to a random ID)
+        #[arg(long)]
+        worker_id: Option<String>,
 
-    // Build DAG to check for cycles
-    let _dag = WorkflowDAG::from_workflow(&workflow)
-        .with_context(|| "Failed to build workflow DAG (possible cycle detected)")?;
+        /// Lease duration in seconds before a silent worker's run is reassigned
+        #[arg(long, default_value = "30")]
+        lease_seconds: u64,
 
-    println!("{}", "✓ Workflow is valid".green().bold());
-    println!("  Name: {}", workflow.name);
-    println!("  Version: {}", workflow.version);
-    println!("  Steps: {}", workflow.steps.len());
+        /// Delay in milliseconds between polls of an empty queue
+        #[arg(long, default_value = "1000")]
+        poll_interval_ms: u64,
 
-    Ok(())
-}
+        /// Maximum concurrent steps per run
+        #[arg(long, default_value = "4")]
+        max_concurrency: usize,
 
-async fn run_workflow(
-    file_path: &str,
-    input: Option<&str>,
-    max_concurrency: usize,
-) -> Result<()> {
-    info!("Running workflow: {}", file_path);
-    println!("{} {}", "Running workflow:".cyan().bold(), file_path);
+        /// Limit concurrent steps of a resource class (llm, embed, io, cpu); repeatable
+        #[arg(long = "resource-limit", value_name = "CLASS=N", value_parser = parse_resource_limit)]
+        resource_limits: Vec<(ResourceClass, usize)>,
 
-    // Read workflow file
-    let content = fs::read_to_string(file_path)
-        .with_context(|| format!("Failed to read workflow file: {}", file_path))?;
+        /// Allow `exec` actions to run local commands
+        #[arg(long)]
+        allow_exec: bool,
 
-    // Parse workflow
-    let workflow: Workflow = serde_yaml::from_str(&content)
-        .with_context(|| format!("Failed to parse workflow YAML: {}", file_path))?;
+        #[command(flatten)]
+        recovery: RecoveryArgs,
+    },
 
-    info!("Parsed workflow: {} v{}", workflow.name, workflow.version);
+    /// Serve webhook, message, and chat triggers
+    Serve {
+        /// Workflow files whose triggers are served
+        #[arg(value_name = "FILE")]
+        files: Vec<String>,
 
-    // Validate workflow
-    workflow
-        .validate()
-        .with_context(|| "Workflow validation failed")?;
+        /// Also serve the triggers of the latest active version of every published workflow
+        #[arg(long)]
+        registry: bool,
 
-    // Parse input
-    let inputs = if let Some(input_str) = input {
-        parse_input(input_str)?
-    } else {
-        HashMap::new()
-    };
+        /// Address to listen on
+        #[arg(long, value_name = "ADDR", default_value = "127.0.0.1:8080")]
+        bind: std::net::SocketAddr,
 
-    info!("Workflow inputs: {:?}", inputs);
+        /// Also serve the gRPC API on this address
+        #[arg(long, value_name = "ADDR")]
+        grpc_bind: Option<std::net::SocketAddr>,
 
-    // Create providers
-    let mut providers: HashMap<String, Arc<dyn LLMProvider>> = HashMap::new();
+        /// Queue database (postgres:// URL or SQLite file path)
+        #[arg(long, value_name = "URL")]
+        database_url: String,
 
-    // Try to create OpenAI provider from environment
-    if let Ok(openai) = OpenAIProvider::from_env() {
-        info!("Registered OpenAI provider");
-        providers.insert("openai".to_string(), Arc::new(openai));
-    } else {
-        info!("OpenAI provider not available (OPENAI_API_KEY not set)");
-    }
+        /// Refuse chat requests while this many chat runs execute (default: unlimited)
+        #[arg(long, value_name = "N")]
+        max_concurrent_runs: Option<usize>,
 
-    // Try to create Anthropic provider from environment
-    if let Ok(anthropic) = AnthropicProvider::from_env() {
-        info!("Registered Anthropic provider");
-        providers.insert("anthropic".to_string(), Arc::new(anthropic));
-    } else {
-        info!("Anthropic provider not available (ANTHROPIC_API_KEY not set)");
-    }
+        /// Refuse new runs while this many runs wait in the queue (default: unlimited)
+        #[arg(long, value_name = "N")]
+        max_queued_runs: Option<u64>,
 
-    if providers.is_empty() {
-        anyhow::bail!(
-            "No LLM providers available. Please set OPENAI_API_KEY or ANTHROPIC_API_KEY environment variable."
-        );
-    }
+        /// Delay suggested to refused clients in the Retry-After header
+        #[arg(long, value_name = "SECONDS", default_value_t = 5)]
+        retry_after: u64,
 
-    // Create executor
-    let mut executor = WorkflowExecutor::new(workflow, inputs)
-        .with_context(|| "Failed to create workflow executor")?
-        .with_max_concurrency(max_concurrency);
+        #[command(flatten)]
+        auth: AuthArgs,
 
-    // Register providers
-    for (name, provider) in providers {
-        executor = executor.with_provider(name, provider);
-    }
+        #[command(flatten)]
+        recovery: RecoveryArgs,
+    },
 
-    println!("{}", "Executing workflow...".cyan());
+    /// Browse past and active runs
+    Runs {
+        #[command(subcommand)]
+        command: RunCommands,
+    },
 
-    // Execute workflow
-    let result = executor
-        .execute()
-        .await
-        .with_context(|| "Workflow execution failed")?;
+    /// Render a timing, token, and cost report of a run
+    Report {
+        /// Run ID
+        #[arg(value_name = "RUN_ID")]
+        id: Uuid,
 
-    println!("{}", "✓ Workflow completed successfully".green().bold());
-    println!("\n{}", "Results:".cyan().bold());
-    println!(
-        "{}",
-        serde_json::to_string_pretty(&result)
-            .unwrap_or_else(|_| format!("{:?}", result))
-    );
+        /// Output format: table, json, or html
+        #[arg(long, value_name = "FORMAT", default_value = "table")]
+        format: ReportFormat,
 
-    Ok(())
+        /// Write the report to a file instead of stdout
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+
+        /// Database (postgres:// URL or SQLite file path)
+        #[arg(long, value_name = "URL")]
+        database_url: String,
+    },
+
+    /// Summarize requests and tokens per provider and model over recorded runs
+    Usage {
+        /// Only runs of this workflow
+        #[arg(long, value_name = "NAME")]
+        workflow: Option<String>,
+
+        /// Only runs started at or after this time (RFC 3339 or YYYY-MM-DD)
+        #[arg(long, value_name = "TIME")]
+        since: Option<String>,
+
+        /// Only runs started at or before this time (RFC 3339 or YYYY-MM-DD, inclusive of the whole day)
+        #[arg(long, value_name = "TIME")]
+        until: Option<String>,
+
+        /// Print the summary as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Database (postgres:// URL or SQLite file path)
+        #[arg(long, value_name = "URL")]
+        database_url: String,
+    },
+
+    /// Check that providers, vector databases, and stores are reachable
+    Health {
+        /// Also check this database (postgres:// URL or SQLite file path)
+        #[arg(long, value_name = "URL")]
+        database_url: Option<String>,
+
+        /// Also check this vector database (repeatable)
+        #[arg(long = "vector-db", value_name = "DATABASE")]
+        vector_dbs: Vec<VectorDatabase>,
+
+        /// Seconds each check may take
+        #[arg(long, value_name = "SECONDS", default_value_t = 5)]
+        timeout: u64,
+
+        /// Print the result as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Inspect and re-drive dead-lettered steps
+    Dlq {
+        #[command(subcommand)]
+        command: DlqCommands,
+    },
+
+    /// Create, inspect, and delete vector database indexes
+    Index {
+        #[command(subcommand)]
+        command: IndexCommands,
+    },
+
+    /// Query audit events
+    Audit {
+        #[command(subcommand)]
+        command: AuditCommands,
+    },
+
+    /// Manage versioned prompt templates
+    Prompts {
+        #[command(subcommand)]
+        command: PromptCommands,
+    },
+
+    /// Manage workflow definitions published to the database
+    Workflows {
+        #[command(subcommand)]
+        command: WorkflowCommands,
+    },
+
+    /// Apply pending database schema migrations
+    Migrate {
+        /// Database to migrate (postgres:// URL or SQLite file path)
+        #[arg(long, value_name = "URL")]
+        database_url: String,
+
+        /// List pending migrations without applying them
+        #[arg(long)]
+        dry_run: bool,
+    },
 }
 
-fn parse_input(input_str: &str) -> Result<HashMap<String, Value>> {
-    // Check if input is a file path
-    if Path::new(input_str).exists() {
-        let content = fs::read_to_string(input_str)
-            .with_context(|| format!("Failed to read input file: {}", input_str))?;
-        serde_json::from_str(&content)
-            .with_context(|| format!("Failed to parse input JSON from file: {}", input_str))
-    } else {
-        // Try to parse as JSON string
-        serde_json::from_str(input_str)
+#[derive(Subcommand)]
+enum WorkflowCommands {
+    /// Publish a workflow file under its name and version
+    Publish {
+        /// Path to workflow file
+        #[arg(value_name = "FILE")]
+        file: String,
+
+        /// Database (postgres:// URL or SQLite file path)
+        #[arg(long, value_name = "URL")]
+        database_url: String,
+
+        /// User or service recorded as the publisher (defaults to --audit-actor)
+        #[arg(long, value_name = "NAME")]
+        created_by: Option<String>,
+    },
+
+    /// List published workflows
+    List {
+        /// Database (postgres:// URL or SQLite file path)
+        #[arg(long, value_name = "URL")]
+        database_url: String,
+
+        /// Include deprecated versions
+        #[arg(long)]
+        all: bool,
+    },
+
+    /// Print a published workflow definition
+    Get {
+        /// Workflow name, optionally pinned to a version (defaults to the latest)
+        #[arg(value_name = "NAME[@VERSION]")]
+        workflow: String,
+
+        /// Database (postgres:// URL or SQLite file path)
+        #[arg(long, value_name = "URL")]
+        database_url: String,
+    },
+
+    /// Deprecate a published version so it can no longer be run
+    Deprecate {
+        /// Workflow name
+        #[arg(value_name = "NAME")]
+        name: String,
+
+        /// Workflow version
+        #[arg(value_name = "VERSION")]
+        version: String,
+
+        /// Database (postgres:// URL or SQLite file path)
+        #[arg(long, value_name = "URL")]
+        database_url: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum PromptCommands {
+    /// List prompts with their versions
+    List,
+
+    /// Print the template a reference resolves to
+    Show {
+        /// Prompt reference (name, name@latest, or name@v3)
+        #[arg(value_name = "REF")]
+        reference: String,
+    },
+
+    /// Add a new version of a prompt
+    Add {
+        /// Prompt name
+        #[arg(value_name = "NAME")]
+        name: String,
+
+        /// File containing the template
+        #[arg(short, long, value_name = "FILE")]
+        file: PathBuf,
+
+        /// System prompt used with the template
+        #[arg(long)]
+        system: Option<String>,
+
+        /// What changed in this version
+        #[arg(short, long)]
+        description: Option<String>,
+
+        /// Promote the new version right away
+        #[arg(long)]
+        promote: bool,
+    },
+
+    /// Show the changes between two versions of a prompt
+    Diff {
+        /// Prompt name
+        #[arg(value_name = "NAME")]
+        name: String,
+
+        /// Old version (e.g. v2)
+        #[arg(value_name = "FROM")]
+        from: String,
+
+        /// New version (e.g. v3)
+        #[arg(value_name = "TO")]
+        to: String,
+    },
+
+    /// Make a version the one the prompt's bare name resolves to
+    Promote {
+        /// Prompt name
+        #[arg(value_name = "NAME")]
+        name: String,
+
+        /// Version to promote (e.g. v3)
+        #[arg(value_name = "VERSION")]
+        version: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum AuditCommands {
+    /// Search audit events, newest first
+    Search {
+        /// Only events at or after this time (RFC 3339 or YYYY-MM-DD)
+        #[arg(long, value_name = "TIME")]
+        since: Option<String>,
+
+        /// Only events at or before this time (RFC 3339 or YYYY-MM-DD, inclusive of the whole day)
+        #[arg(long, value_name = "TIME")]
+        until: Option<String>,
+
+        /// Only events of this type (e.g. provider_call, step_execution)
+        #[arg(long, value_name = "TYPE")]
+        event_type: Option<AuditEventType>,
+
+        /// Only events belonging to this workflow
+        #[arg(long, value_name = "ID")]
+        workflow_id: Option<String>,
+
+        /// Only events attributed to this user
+        #[arg(long, value_name = "USER")]
+        user: Option<String>,
+
+        /// Only events with this result (success, failure, partial_success)
+        #[arg(long, value_name = "RESULT")]
+        result: Option<AuditResult>,
+
+        /// Page number, starting at 1
+        #[arg(long, default_value = "1")]
+        page: usize,
+
+        /// Events per page
+        #[arg(long, default_value = "50")]
+        page_size: usize,
+
+        /// Print the page as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Audit database (postgres:// URL); defaults to the file given with --audit-log
+        #[arg(long, value_name = "URL")]
+        database_url: Option<String>,
+    },
+
+    /// Export audit events for SIEM ingestion, one per line
+    Export {
+        /// Only events at or after this time (RFC 3339 or YYYY-MM-DD)
+        #[arg(long, value_name = "TIME")]
+        since: Option<String>,
+
+        /// Only events at or before this time (RFC 3339 or YYYY-MM-DD, inclusive of the whole day)
+        #[arg(long, value_name = "TIME")]
+        until: Option<String>,
+
+        /// Line format (json or cef)
+        #[arg(long, default_value = "json")]
+        format: ExportFormat,
+
+        /// Write to this file instead of stdout
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+
+        /// Audit database (postgres:// URL); defaults to the file given with --audit-log
+        #[arg(long, value_name = "URL")]
+        database_url: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum RunCommands {
+    /// List runs, most recently started first
+    List {
+        /// Only runs of this workflow
+        #[arg(long, value_name = "NAME")]
+        workflow: Option<String>,
+
+        /// Only runs with this status (pending, running, paused, completed, failed, cancelled)
+        #[arg(long, value_name = "STATUS")]
+        status: Option<WorkflowStatus>,
+
+        /// Only runs started by this user
+        #[arg(long, value_name = "USER")]
+        user: Option<String>,
+
+        /// Only runs started at or after this time (RFC 3339 or YYYY-MM-DD)
+        #[arg(long, value_name = "TIME")]
+        since: Option<String>,
+
+        /// Only runs started at or before this time (RFC 3339 or YYYY-MM-DD, inclusive of the whole day)
+        #[arg(long, value_name = "TIME")]
+        until: Option<String>,
+
+        /// Sort by started_at, updated_at, completed_at, or workflow_name
+        #[arg(long, value_name = "FIELD", default_value = "started_at")]
+        sort: RunSortField,
+
+        /// Sort in ascending order
+        #[arg(long)]
+        asc: bool,
+
+        /// Page number, starting at 1
+        #[arg(long, default_value = "1")]
+        page: usize,
+
+        /// Runs per page
+        #[arg(long, default_value = "50")]
+        page_size: usize,
+
+        /// Print the page as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Database (postgres:// URL or SQLite file path)
+        #[arg(long, value_name = "URL")]
+        database_url: String,
+    },
+
+    /// Show a run with its step states
+    Show {
+        /// Run ID
+        #[arg(value_name = "RUN_ID")]
+        id: Uuid,
+
+        /// Print the full run state as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Database (postgres:// URL or SQLite file path)
+        #[arg(long, value_name = "URL")]
+        database_url: String,
+    },
+
+    /// Delete old finished runs and compress the contexts of kept ones, across all tenants
+    Purge {
+        /// Delete completed runs that finished more than this many days ago
+        #[arg(long, value_name = "DAYS")]
+        completed_days: Option<u32>,
+
+        /// Delete failed and cancelled runs that finished more than this many days ago
+        #[arg(long, value_name = "DAYS")]
+        failed_days: Option<u32>,
+
+        /// Compress the contexts of runs that finished more than this many days ago
+        #[arg(long, value_name = "DAYS")]
+        compress_after_days: Option<u32>,
+
+        /// Only report what would be deleted and compressed
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Database (postgres:// URL or SQLite file path)
+        #[arg(long, value_name = "URL")]
+        database_url: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum DlqCommands {
+    /// List dead-lettered steps
+    List {
+        /// Database (postgres:// URL or SQLite file path)
+        #[arg(long, value_name = "URL")]
+        database_url: String,
+
+        /// Include entries that were already retried successfully
+        #[arg(long)]
+        all: bool,
+    },
+
+    /// Show the full failure context of an entry
+    Show {
+        /// Dead letter ID
+        #[arg(value_name = "ID")]
+        id: Uuid,
+
+        /// Database (postgres:// URL or SQLite file path)
+        #[arg(long, value_name = "URL")]
+        database_url: String,
+    },
+
+    /// Re-run the failed step with its captured context
+    Retry {
+        /// Dead letter ID
+        #[arg(value_name = "ID")]
+        id: Uuid,
+
+        /// Database (postgres:// URL or SQLite file path)
+        #[arg(long, value_name = "URL")]
+        database_url: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum IndexCommands {
+    /// Create an index for vectors of a given length
+    Create {
+        /// Index name (a class in Weaviate, a collection in Qdrant)
+        #[arg(value_name = "NAME")]
+        name: String,
+
+        /// Database holding the index
+        #[arg(long, value_name = "DATABASE")]
+        database: VectorDatabase,
+
+        /// Length of the vectors the index stores
+        #[arg(long, value_name = "N")]
+        dimension: usize,
+
+        /// Similarity metric (cosine, euclidean, or dotproduct)
+        #[arg(long, value_name = "METRIC", default_value = "cosine")]
+        metric: DistanceMetric,
+
+        /// Index a metadata field for filtering, e.g. tenant=keyword; repeatable
+        #[arg(long = "metadata-index", value_name = "FIELD=TYPE", value_parser = parse_metadata_index)]
+        metadata_indexes: Vec<(String, MetadataFieldType)>,
+
+        /// Succeed without changes when the index already exists
+        #[arg(long)]
+        if_not_exists: bool,
+    },
+
+    /// Show an index's dimension, vector count, and metric
+    Describe {
+        /// Index name
+        #[arg(value_name = "NAME")]
+        name: String,
+
+        /// Database holding the index
+        #[arg(long, value_name = "DATABASE")]
+        database: VectorDatabase,
+
+        /// Print the stats as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Delete an index and every vector in it
+    Delete {
+        /// Index name
+        #[arg(value_name = "NAME")]
+        name: String,
+
+        /// Database holding the index
+        #[arg(long, value_name = "DATABASE")]
+        database: VectorDatabase,
+    },
+}
+
+#[tokio::main]
+async fn main() {
+    let cli = Cli::parse();
+
+    // Initialize tracing
+    let log_level = if cli.verbose {
+        tracing::Level::DEBUG
+    } else if cli.quiet {
+        tracing::Level::ERROR
+    } else {
+        tracing::Level::INFO
+    };
+
+    let registry = tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| format!("llm_orchestrator={}", log_level).into()),
+        )
+        // Log to stderr, keeping stdout for command output such as JSON reports
+        .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr));
+
+    // Optionally export spans to an OpenTelemetry collector
+    #[cfg(feature = "otel")]
+    let (registry, otel_guard) = {
+        use llm_orchestrator_core::telemetry::{otlp_layer, OtelConfig};
+
+        match cli.otlp_endpoint.as_deref() {
+            Some(endpoint) => match otlp_layer(&OtelConfig::default().with_endpoint(endpoint)) {
+                Ok((layer, guard)) => (registry.with(Some(layer)), Some(guard)),
+                Err(e) => {
+                    eprintln!("{} {}", "Error:".red().bold(), e);
+                    std::process::exit(1);
+                }
+            },
+            None => (registry.with(None), None),
+        }
+    };
+
+    registry.init();
+
+    let audit = match Audit::from_args(&cli.audit, cli.tenant.as_deref()) {
+        Ok(audit) => audit,
+        Err(e) => {
+            eprintln!("{} {}", "Error:".red().bold(), e);
+            std::process::exit(1);
+        }
+    };
+
+    let mut notifiers = match cli.notify.notifiers() {
+        Ok(notifiers) => notifiers,
+        Err(e) => {
+            eprintln!("{} {}", "Error:".red().bold(), e);
+            std::process::exit(1);
+        }
+    };
+
+    if let Some(dir) = &cli.documents_dir {
+        notifiers.handlers.push((LOAD_ACTION, Arc::new(LoadDocumentAction::new(dir))));
+    }
+
+    let mcp_servers = match open_mcp_servers(cli.mcp_config.as_deref()) {
+        Ok(servers) => servers,
+        Err(e) => {
+            eprintln!("{} {}", "Error:".red().bold(), e);
+            std::process::exit(1);
+        }
+    };
+
+    let drain_timeout = Duration::from_secs(cli.drain_timeout);
+    let result = match cli.command {
+        Commands::Validate { file, profile, format } => {
+            validate_workflow(&file, profile.as_deref(), Output::new(format, cli.quiet))
+        }
+        Commands::Lint {
+            file,
+            config,
+            rules,
+            deny_warnings,
+            format,
+        } => lint_workflow(&file, config.as_deref(), &rules, deny_warnings, Output::new(format, cli.quiet)),
+        Commands::Schema { output } => export_schema(output.as_deref()),
+        Commands::Init { file, force, yes } => init_workflow(&file, force, yes),
+        Commands::Completions { shell } => {
+            print!("{}", completions::generate(shell, Cli::command()));
+            Ok(())
+        }
+        Commands::Run {
+            file,
+            workflow,
+            inputs,
+            max_concurrency,
+            resource_limits,
+            profile,
+            mock,
+            record,
+            replay,
+            sinks,
+            artifacts,
+            format,
+            database_url,
+            rerun,
+            from_step,
+            only_step,
+            blob_threshold,
+            context_memory_cap,
+            spill_to,
+            deterministic,
+            seed,
+            allow_exec,
+        } => match replay {
+            Some(replay_dir) => {
+                replay_workflow(
+                    workflow_source(file.as_deref(), workflow.as_deref(), database_url.as_deref()),
+                    &inputs,
+                    profile.as_deref(),
+                    &replay_dir,
+                    allow_exec,
+                    audit.as_ref(),
+                    &cli.prompts,
+                    &cli.brokers,
+                    &notifiers,
+                    &mcp_servers,
+                )
+                .await
+            }
+            None => {
+                run_workflow(
+                    workflow_source(file.as_deref(), workflow.as_deref(), database_url.as_deref()),
+                    &inputs,
+                    max_concurrency,
+                    &resource_limits,
+                    profile.as_deref(),
+                    mock.as_deref(),
+                    record.as_deref(),
+                    &sinks,
+                    artifacts.as_ref(),
+                    spill_to.as_ref(),
+                    Output::new(format, cli.quiet),
+                    database_url.as_deref(),
+                    rerun.zip(from_step.map(RerunScope::From).or(only_step.map(RerunScope::Only))),
+                    ContextLimits {
+                        blob_threshold,
+                        memory_cap: context_memory_cap,
+                    },
+                    deterministic.then_some(seed),
+                    allow_exec,
+                    &cli.audit.audit_actor,
+                    cli.tenant.as_deref(),
+                    drain_timeout,
+                    audit.as_ref(),
+                    &cli.prompts,
+                    &cli.brokers,
+                    &cli.memory,
+                    &notifiers,
+                    &mcp_servers,
+                )
+                .await
+            }
+        }
+        Commands::Submit {
+            file,
+            workflow,
+            inputs,
+            profile,
+            priority,
+            database_url,
+        } => {
+            let options = SubmitOptions {
+                priority,
+                tenant_id: cli.tenant.clone(),
+            };
+            submit_workflow(
+                workflow_source(file.as_deref(), workflow.as_deref(), Some(&database_url)),
+                &inputs,
+                profile.as_deref(),
+                options,
+                &database_url,
+                audit.as_ref(),
+            )
+            .await
+        }
+        Commands::Cancel {
+            run_id,
+            database_url,
+        } => cancel_run(run_id, &database_url).await,
+        Commands::Worker {
+            database_url,
+            worker_id,
+            lease_seconds,
+            poll_interval_ms,
+            max_concurrency,
+            resource_limits,
+            allow_exec,
+            recovery,
+        } => {
+            run_worker(
+                &database_url,
+                worker_id,
+                Duration::from_secs(lease_seconds),
+                Duration::from_millis(poll_interval_ms),
+                max_concurrency,
+                resource_limits,
+                allow_exec,
+                drain_timeout,
+                &recovery,
+                audit,
+                &cli.prompts,
+                &cli.brokers,
+                &cli.memory,
+                &notifiers,
+                &mcp_servers,
+            )
+            .await
+        }
+        Commands::Serve {
+            files,
+            registry,
+            bind,
+            grpc_bind,
+            database_url,
+            max_concurrent_runs,
+            max_queued_runs,
+            retry_after,
+            auth,
+            recovery,
+        } => {
+            let admission = AdmissionLimits {
+                max_concurrent_runs,
+                max_queued_runs,
+                retry_after: Duration::from_secs(retry_after),
+            };
+            serve::serve(
+                &files,
+                registry,
+                bind,
+                grpc_bind,
+                &database_url,
+                drain_timeout,
+                admission,
+                &auth,
+                &recovery,
+                audit,
+                &cli.prompts,
+                &cli.brokers,
+                &cli.memory,
+                &notifiers,
+                &mcp_servers,
+            )
+            .await
+        }
+        Commands::Runs { command } => match command {
+            RunCommands::List {
+                workflow,
+                status,
+                user,
+                since,
+                until,
+                sort,
+                asc,
+                page,
+                page_size,
+                json,
+                database_url,
+            } => match build_run_query(
+                since.as_deref(),
+                until.as_deref(),
+                workflow,
+                status,
+                user,
+                cli.tenant.clone(),
+            ) {
+                Ok(query) => {
+                    list_runs(query.sort_by(sort, asc).page(page, page_size), json, &database_url).await
+                }
+                Err(e) => Err(e),
+            },
+            RunCommands::Show { id, json, database_url } => {
+                show_run(id, json, &database_url, cli.tenant.as_deref()).await
+            }
+            RunCommands::Purge {
+                completed_days,
+                failed_days,
+                compress_after_days,
+                dry_run,
+                json,
+                database_url,
+            } => {
+                let policy = RetentionPolicy {
+                    completed_days,
+                    failed_days,
+                    compress_after_days,
+                };
+                purge_runs(policy, dry_run, json, &database_url).await
+            }
+        },
+        Commands::Report {
+            id,
+            format,
+            output,
+            database_url,
+        } => report_run(id, format, output.as_deref(), &database_url, cli.tenant.as_deref()).await,
+        Commands::Usage {
+            workflow,
+            since,
+            until,
+            json,
+            database_url,
+        } => match build_run_query(since.as_deref(), until.as_deref(), workflow, None, None, cli.tenant.clone()) {
+            Ok(query) => show_usage(query, json, &database_url).await,
+            Err(e) => Err(e),
+        },
+        Commands::Health {
+            database_url,
+            vector_dbs,
+            timeout,
+            json,
+        } => check_health(database_url.as_deref(), &vector_dbs, Duration::from_secs(timeout), json).await,
+        Commands::Dlq { command } => match command {
+            DlqCommands::List { database_url, all } => list_dead_letters(&database_url, all).await,
+            DlqCommands::Show { id, database_url } => show_dead_letter(id, &database_url).await,
+            DlqCommands::Retry { id, database_url } => {
+                retry_dead_letter_step(id, &database_url, audit.as_ref(), &cli.prompts).await
+            }
+        },
+        Commands::Index { command } => match command {
+            IndexCommands::Create {
+                name,
+                database,
+                dimension,
+                metric,
+                metadata_indexes,
+                if_not_exists,
+            } => create_index(database, &name, dimension, metric, metadata_indexes, if_not_exists).await,
+            IndexCommands::Describe { name, database, json } => describe_index(database, &name, json).await,
+            IndexCommands::Delete { name, database } => delete_index(database, &name).await,
+        },
+        Commands::Audit { command } => match command {
+            AuditCommands::Search {
+                since,
+                until,
+                event_type,
+                workflow_id,
+                user,
+                result,
+                page,
+                page_size,
+                json,
+                database_url,
+            } => {
+                match build_audit_query(
+                    since.as_deref(),
+                    until.as_deref(),
+                    event_type,
+                    workflow_id,
+                    user,
+                    result,
+                ) {
+                    Ok(query) => {
+                        search_audit_events(
+                            query.page(page, page_size),
+                            json,
+                            database_url.as_deref(),
+                            cli.audit.audit_log.as_deref(),
+                            cli.tenant.as_deref(),
+                        )
+                        .await
+                    }
+                    Err(e) => Err(e),
+                }
+            }
+            AuditCommands::Export {
+                since,
+                until,
+                format,
+                output,
+                database_url,
+            } => match build_audit_query(since.as_deref(), until.as_deref(), None, None, None, None) {
+                Ok(query) => {
+                    export_audit_events(
+                        query,
+                        format,
+                        output.as_deref(),
+                        database_url.as_deref(),
+                        cli.audit.audit_log.as_deref(),
+                        cli.tenant.as_deref(),
+                    )
+                    .await
+                }
+                Err(e) => Err(e),
+            },
+        },
+        Commands::Prompts { command } => match command {
+            PromptCommands::List => list_prompts(&cli.prompts).await,
+            PromptCommands::Show { reference } => show_prompt(&reference, &cli.prompts).await,
+            PromptCommands::Add {
+                name,
+                file,
+                system,
+                description,
+                promote,
+            } => add_prompt(&name, &file, system, description, promote, &cli.prompts).await,
+            PromptCommands::Diff { name, from, to } => diff_prompt(&name, &from, &to, &cli.prompts).await,
+            PromptCommands::Promote { name, version } => {
+                promote_prompt(&name, &version, &cli.prompts).await
+            }
+        },
+        Commands::Workflows { command } => match command {
+            WorkflowCommands::Publish {
+                file,
+                database_url,
+                created_by,
+            } => {
+                let created_by = created_by.unwrap_or_else(|| cli.audit.audit_actor.clone());
+                publish_workflow(&file, &database_url, created_by, audit.as_ref()).await
+            }
+            WorkflowCommands::List { database_url, all } => list_workflows(&database_url, all).await,
+            WorkflowCommands::Get { workflow, database_url } => get_workflow(&workflow, &database_url).await,
+            WorkflowCommands::Deprecate {
+                name,
+                version,
+                database_url,
+            } => deprecate_workflow(&name, &version, &database_url, audit.as_ref()).await,
+        },
+        Commands::Migrate { database_url, dry_run } => migrate_database(&database_url, dry_run).await,
+    };
+
+    if let Err(e) = result {
+        error!("{}", e);
+        eprintln!("{} {}", "Error:".red().bold(), e);
+        #[cfg(feature = "otel")]
+        drop(otel_guard);
+        std::process::exit(exit_code(&e));
+    }
+}
+
+/// Signal that asked the process to stop.
+#[derive(Debug, Clone, Copy)]
+enum Shutdown {
+    Interrupt,
+    Terminate,
+}
+
+impl Shutdown {
+    /// Shell convention for a process stopped by a signal: 128 plus its number.
+    fn exit_code(self) -> i32 {
+        match self {
+            Self::Interrupt => 130,
+            Self::Terminate => 143,
+        }
+    }
+}
+
+impl std::fmt::Display for Shutdown {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Interrupt => write!(f, "Interrupted by SIGINT"),
+            Self::Terminate => write!(f, "Terminated by SIGTERM"),
+        }
+    }
+}
+
+impl std::error::Error for Shutdown {}
+
+/// A failure with its own exit code, so scripts can tell an invalid workflow
+/// from a failed run.
+#[derive(Debug)]
+enum Failure {
+    /// The workflow is invalid (exit code 3).
+    Invalid(String),
+    /// The run failed (exit code 4).
+    Execution(String),
+    /// The run finished, but some steps failed (exit code 5).
+    Partial(String),
+}
+
+impl Failure {
+    /// Mark an error as an invalid workflow.
+    fn invalid(error: anyhow::Error) -> anyhow::Error {
+        let message = error.to_string();
+        error.context(Self::Invalid(message))
+    }
+
+    fn exit_code(&self) -> i32 {
+        match self {
+            Self::Invalid(_) => 3,
+            Self::Execution(_) => 4,
+            Self::Partial(_) => 5,
+        }
+    }
+}
+
+impl std::fmt::Display for Failure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Invalid(message) | Self::Execution(message) | Self::Partial(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for Failure {}
+
+/// Exit code of a failed command: 128 plus the signal that stopped it, the
+/// code of its [`Failure`], 3 when a workflow did not parse or validate, or 1.
+fn exit_code(error: &anyhow::Error) -> i32 {
+    if let Some(signal) = error.downcast_ref::<Shutdown>() {
+        return signal.exit_code();
+    }
+    if let Some(failure) = error.downcast_ref::<Failure>() {
+        return failure.exit_code();
+    }
+    let invalid = error.chain().any(|cause| {
+        matches!(
+            cause.downcast_ref::<OrchestratorError>(),
+            Some(OrchestratorError::ParseError(_) | OrchestratorError::ValidationError(_))
+        )
+    });
+    if invalid {
+        3
+    } else {
+        1
+    }
+}
+
+/// Resolves on the next SIGINT or SIGTERM.
+async fn shutdown_signal() -> Shutdown {
+    let interrupt = async {
+        if tokio::signal::ctrl_c().await.is_err() {
+            std::future::pending::<()>().await;
+        }
+    };
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        if let Ok(mut terminate) = signal(SignalKind::terminate()) {
+            return tokio::select! {
+                _ = interrupt => Shutdown::Interrupt,
+                _ = terminate.recv() => Shutdown::Terminate,
+            };
+        }
+    }
+    interrupt.await;
+    Shutdown::Interrupt
+}
+
+fn validate_workflow(file_path: &str, profile: Option<&str>, output: Output) -> Result<()> {
+    info!("Validating workflow: {}", file_path);
+    if output.is_text() {
+        output.status(format_args!("{} {}", "Validating workflow:".cyan().bold(), file_path));
+    }
+
+    // Read workflow file
+    let content = fs::read_to_string(file_path)
+        .with_context(|| format!("Failed to read workflow file: {}", file_path))?;
+
+    // Parse and validate workflow, locating every problem
+    let workflow = match check_yaml_with(&content, &FileResolver::for_file(file_path)) {
+        Ok(workflow) => workflow,
+        Err(diagnostics) => {
+            if output.is_text() {
+                for diagnostic in &diagnostics {
+                    print_diagnostic(file_path, &content, diagnostic);
+                }
+            } else {
+                output.document(&serde_json::json!({"file": file_path, "valid": false, "diagnostics": diagnostics}))?;
+            }
+            return Err(Failure::Invalid(format!("Workflow validation failed with {} error(s)", diagnostics.len())).into());
+        }
+    };
+
+    info!("Parsed workflow: {} v{}", workflow.name, workflow.version);
+    let workflow = apply_profile(workflow, profile).map_err(Failure::invalid)?;
+
+    // Build DAG to check for cycles
+    let _dag = WorkflowDAG::from_workflow(&workflow)
+        .with_context(|| "Failed to build workflow DAG (possible cycle detected)")
+        .map_err(Failure::invalid)?;
+
+    if output.is_text() {
+        println!("{}", "✓ Workflow is valid".green().bold());
+        println!("  Name: {}", workflow.name);
+        println!("  Version: {}", workflow.version);
+        println!("  Steps: {}", workflow.steps.len());
+    } else {
+        output.document(&serde_json::json!({
+            "file": file_path,
+            "valid": true,
+            "name": workflow.name,
+            "version": workflow.version,
+            "steps": workflow.steps.len(),
+        }))?;
+    }
+
+    Ok(())
+}
+
+fn lint_workflow(
+    file_path: &str,
+    config_path: Option<&Path>,
+    rules: &[(LintRule, Level)],
+    deny_warnings: bool,
+    output: Output,
+) -> Result<()> {
+    info!("Linting workflow: {}", file_path);
+    if output.is_text() {
+        output.status(format_args!("{} {}", "Linting workflow:".cyan().bold(), file_path));
+    }
+
+    let mut config = match config_path {
+        Some(path) => {
+            let content = fs::read_to_string(path)
+                .with_context(|| format!("Failed to read lint config: {}", path.display()))?;
+            serde_yaml::from_str::<LintConfig>(&content)
+                .with_context(|| format!("Invalid lint config: {}", path.display()))?
+        }
+        None => LintConfig::default(),
+    };
+    config.rules.extend(rules.iter().copied());
+
+    let content = fs::read_to_string(file_path)
+        .with_context(|| format!("Failed to read workflow file: {}", file_path))?;
+    let diagnostics = lint_yaml_with(&content, &config, &FileResolver::for_file(file_path));
+    let errors = diagnostics.iter().filter(|d| d.severity == Severity::Error).count();
+    let warnings = diagnostics.len() - errors;
+
+    if output.is_text() {
+        for diagnostic in &diagnostics {
+            print_diagnostic(file_path, &content, diagnostic);
+        }
+        if diagnostics.is_empty() {
+            println!("{}", "✓ No lint findings".green().bold());
+        } else {
+            println!("{} error(s), {} warning(s)", errors, warnings);
+        }
+    } else {
+        output.document(&serde_json::json!({
+            "file": file_path,
+            "errors": errors,
+            "warnings": warnings,
+            "diagnostics": diagnostics,
+        }))?;
+    }
+
+    if errors > 0 || (deny_warnings && warnings > 0) {
+        return Err(Failure::Invalid(format!("Lint failed with {} error(s) and {} warning(s)", errors, warnings)).into());
+    }
+    Ok(())
+}
+
+/// Print a diagnostic with the source line it points at, in the style of compiler errors.
+fn print_diagnostic(file_path: &str, source: &str, diagnostic: &Diagnostic) {
+    let (label, code) = match diagnostic.severity {
+        Severity::Error => ("error".red().bold(), diagnostic.code.as_str().red().bold()),
+        Severity::Warning => ("warning".yellow().bold(), diagnostic.code.as_str().yellow().bold()),
+    };
+    println!("{}{}{}{} {}", label, "[".bold(), code, "]:".bold(), diagnostic.message.bold());
+    let (Some(line), Some(column)) = (diagnostic.line, diagnostic.column) else {
+        println!("  {} {}", "-->".blue().bold(), file_path);
+        print_notes(" ", diagnostic);
+        println!();
+        return;
+    };
+
+    let gutter = " ".repeat(line.to_string().len());
+    println!("{}{} {}:{}:{}", gutter, "-->".blue().bold(), file_path, line, column);
+    if let Some(text) = source.lines().nth(line - 1) {
+        println!("{} {}", gutter, "|".blue().bold());
+        println!("{} {} {}", line.to_string().blue().bold(), "|".blue().bold(), text);
+        let caret = match diagnostic.severity {
+            Severity::Error => "^".red().bold(),
+            Severity::Warning => "^".yellow().bold(),
+        };
+        println!("{} {} {}{}", gutter, "|".blue().bold(), " ".repeat(column - 1), caret);
+    }
+    print_notes(&gutter, diagnostic);
+    println!();
+}
+
+/// Print the suggestion of a diagnostic, and the rule behind a lint finding.
+fn print_notes(indent: &str, diagnostic: &Diagnostic) {
+    if let Some(suggestion) = &diagnostic.suggestion {
+        println!("{} {} {}", indent, "= help:".blue().bold(), suggestion);
+    }
+    if let DiagnosticCode::Lint(rule) = diagnostic.code {
+        println!("{} {} rule `{}`; silence it with --rule {}=allow", indent, "= note:".blue().bold(), rule, rule);
+    }
+}
+
+fn export_schema(output: Option<&Path>) -> Result<()> {
+    let mut schema = serde_json::to_string_pretty(&Workflow::json_schema())?;
+    schema.push('\n');
+
+    match output {
+        Some(path) => {
+            fs::write(path, schema).with_context(|| format!("Failed to write schema: {}", path.display()))?;
+            println!("{} {}", "✓ Schema written to".green().bold(), path.display());
+        }
+        None => print!("{}", schema),
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn run_workflow(
+    source: WorkflowSource<'_>,
+    inputs: &InputArgs,
+    max_concurrency: usize,
+    resource_limits: &[(ResourceClass, usize)],
+    profile: Option<&str>,
+    mock_file: Option<&str>,
+    record_file: Option<&str>,
+    sinks: &[SinkConfig],
+    artifacts: Option<&ArtifactStoreConfig>,
+    spill_to: Option<&ArtifactStoreConfig>,
+    output: Output,
+    database_url: Option<&str>,
+    rerun: Option<(Uuid, RerunScope)>,
+    context_limits: ContextLimits,
+    seed: Option<u64>,
+    allow_exec: bool,
+    user: &str,
+    tenant: Option<&str>,
+    drain_timeout: Duration,
+    audit: Option<&Audit>,
+    prompts: &PromptArgs,
+    brokers: &BrokerArgs,
+    memory: &MemoryArgs,
+    notifiers: &Notifiers,
+    mcp_servers: &[(String, Arc<McpClient>)],
+) -> Result<()> {
+    info!("Running workflow: {}", source);
+    output.status(format_args!("{} {}", "Running workflow:".cyan().bold(), source));
+
+    let workflow = source.load().await?;
+
+    info!("Parsed workflow: {} v{}", workflow.name, workflow.version);
+
+    // Validate workflow
+    workflow
+        .validate()
+        .with_context(|| "Workflow validation failed")?;
+    let workflow = apply_profile(workflow, profile)?;
+
+    let mut inputs = inputs.load().await?.unwrap_or_default();
+
+    let database = match database_url {
+        Some(database_url) => Some(connect_database(database_url).await?.for_tenant(tenant)),
+        None => None,
+    };
+
+    // A re-run starts from the state of the earlier run
+    let prior = match (&rerun, &database) {
+        (Some((prior_id, _)), Some(database)) => {
+            let prior = database
+                .state
+                .load_workflow_state(prior_id)
+                .await
+                .with_context(|| format!("Failed to load run {}", prior_id))?;
+            if prior.workflow_name != workflow.name {
+                anyhow::bail!("Run {} is of workflow '{}', not '{}'", prior_id, prior.workflow_name, workflow.name);
+            }
+            // Inputs not given again keep their earlier values
+            if let Some(prior_inputs) = prior.context.get("inputs").and_then(Value::as_object) {
+                for (name, value) in prior_inputs {
+                    inputs.entry(name.clone()).or_insert_with(|| value.clone());
+                }
+            }
+            Some(prior)
+        }
+        _ => None,
+    };
+    info!("Workflow inputs: {:?}", inputs);
+
+    // Create providers
+    let providers = providers_from_env();
+
+    // Capture provider calls when recording a golden run, masked like the run's other output
+    let redactor = run_redactor(audit);
+    let recorder = record_file
+        .filter(|path| is_golden_dir(path))
+        .map(|_| GoldenRecorder::new().with_redactor(redactor.clone()));
+    let workflow_name = workflow.name.clone();
+    let recorded_inputs = inputs.clone();
+
+    // Load canned step outputs for offline runs
+    let mocks = if let Some(mock_path) = mock_file {
+        let mocks = MockResponses::from_file(mock_path)
+            .with_context(|| format!("Failed to load mock file: {}", mock_path))?;
+        output.status(format_args!("{} {} ({} steps)", "Using mock file:".cyan(), mock_path, mocks.len()));
+        Some(mocks)
+    } else {
+        None
+    };
+
+    if providers.is_empty() && mocks.is_none() {
+        anyhow::bail!(
+            "No LLM providers available. Please set OPENAI_API_KEY or ANTHROPIC_API_KEY environment variable."
+        );
+    }
+
+    // Create executor
+    let mut executor = WorkflowExecutor::new(workflow, inputs)
+        .with_context(|| "Failed to create workflow executor")?
+        .with_max_concurrency(max_concurrency)
+        .with_context_limits(context_limits)
+        .with_exec_enabled(allow_exec)
+        .with_secret_store(Arc::new(EnvSecretStore::new()))
+        .with_redactor(redactor)
+        .with_prompt_registry(open_prompt_registry(prompts).await?);
+    for &(class, limit) in resource_limits {
+        executor = executor.with_resource_limit(class, limit);
+    }
+    for (name, sink) in brokers.sinks().await? {
+        executor = executor.with_message_sink(name, sink);
+    }
+    if let Some(store) = memory.store().await? {
+        executor = executor.with_memory_store(store);
+    }
+    executor = notifiers.attach(executor);
+    for (name, client) in mcp_servers {
+        executor = executor.with_mcp_server(name.clone(), client.clone());
+    }
+    for sink in sinks {
+        executor = executor.with_result_sink(sink.build().await?);
+    }
+    if let Some(config) = artifacts {
+        executor = executor.with_artifact_store(config.build().await?);
+    }
+    if let Some(config) = spill_to {
+        executor = executor.with_output_spill(Arc::new(ArtifactSpill::new(config.build().await?)));
+    }
+
+    // Register providers
+    for (name, provider) in providers {
+        let provider = match &recorder {
+            Some(recorder) => recorder.wrap(provider),
+            None => provider,
+        };
+        executor = executor.with_provider(name, provider);
+    }
+
+    if let Some(mocks) = mocks {
+        executor = executor.with_mock_responses(mocks);
+    }
+
+    if let Some(audit) = audit {
+        executor = audit.attach(executor);
+    }
+
+    if let Some(tenant) = tenant {
+        executor = executor.with_tenant_id(tenant);
+    }
+
+    if let Some(seed) = seed {
+        executor = executor.with_deterministic(seed);
+    }
+
+    if let (Some(prior), Some((_, scope))) = (&prior, &rerun) {
+        executor = executor.rerun(prior, scope)?;
+        output.status(format_args!("{} {}", "Re-running steps of run:".cyan(), prior.id));
+    }
+
+    if let Some(database) = database {
+        executor = executor
+            .with_user_id(user)
+            .with_state_store(database.state)
+            .with_dead_letter_queue(database.dead_letters);
+        output.status(format_args!("{} {}", "Run ID:".cyan(), executor.run_id()));
+    }
+
+    output.status("Executing workflow...".cyan());
+
+    // Drain the run on SIGINT or SIGTERM; a second signal cancels it
+    let shutdown = executor.shutdown_handle();
+    let cancel = executor.cancel_handle();
+    let signal = Arc::new(std::sync::OnceLock::new());
+    let received = signal.clone();
+    tokio::spawn(async move {
+        let _ = received.set(shutdown_signal().await);
+        eprintln!(
+            "{} waiting up to {}s for running steps; signal again to cancel them",
+            "Shutting down:".yellow().bold(),
+            drain_timeout.as_secs()
+        );
+        tokio::select! {
+            _ = shutdown.shutdown(drain_timeout) => {}
+            _ = shutdown_signal() => cancel.cancel(),
+        }
+    });
+
+    // Execute workflow
+    let run_id = executor.run_id();
+    let result = match executor.execute().await {
+        Ok(result) => result,
+        Err(OrchestratorError::Cancelled) => {
+            if output.is_text() {
+                println!("{}", "✗ Workflow cancelled".yellow().bold());
+            } else {
+                output.document(&run_document(run_id, &workflow_name, "cancelled", Value::Null))?;
+            }
+            return Ok(());
+        }
+        Err(OrchestratorError::Interrupted(unfinished)) => {
+            if output.is_text() {
+                println!("{} {}", "✗ Workflow interrupted; unfinished steps:".yellow().bold(), unfinished.join(", "));
+                if database_url.is_some() {
+                    println!("{} {}", "Run state saved as paused:".cyan(), run_id);
+                }
+            } else {
+                output.document(&run_document(run_id, &workflow_name, "interrupted", serde_json::json!({"unfinished_steps": unfinished})))?;
+            }
+            return Err(signal.get().copied().unwrap_or(Shutdown::Interrupt).into());
+        }
+        Err(OrchestratorError::StepsFailed(failures)) => {
+            if output.is_text() {
+                println!("{}", "✗ Workflow failed:".red().bold());
+                for failure in &failures {
+                    println!("  {} {}", failure.step_id.red(), failure.reason);
+                    if !failure.skipped.is_empty() {
+                        println!("    {} {}", "Skipped:".yellow(), failure.skipped.join(", "));
+                    }
+                }
+            } else {
+                let failures: Vec<Value> = failures
+                    .iter()
+                    .map(|f| serde_json::json!({"step_id": f.step_id, "reason": f.reason, "skipped": f.skipped}))
+                    .collect();
+                output.document(&run_document(run_id, &workflow_name, "failed", serde_json::json!({"failures": failures})))?;
+            }
+            return Err(Failure::Execution(format!("{} step(s) failed", failures.len())).into());
+        }
+        Err(e) => {
+            if !output.is_text() {
+                output.document(&run_document(run_id, &workflow_name, "failed", serde_json::json!({"error": e.to_string()})))?;
+            }
+            return Err(anyhow::Error::from(e).context(Failure::Execution("Workflow execution failed".to_string())));
+        }
+    };
+
+    if let (Some(record_dir), Some(recorder)) = (record_file, &recorder) {
+        let recording = GoldenRecording::new(workflow_name.as_str(), recorded_inputs, recorder, &result);
+        let path = recording
+            .save_in(record_dir)
+            .with_context(|| format!("Failed to write golden recording to: {}", record_dir))?;
+        output.status(format_args!(
+            "{} {} ({} provider calls)",
+            "Recorded golden run to:".cyan(),
+            path.display(),
+            recording.exchanges.len()
+        ));
+    } else if let Some(record_path) = record_file {
+        let recorded = MockResponses::from_results(&result);
+        recorded
+            .save(record_path)
+            .with_context(|| format!("Failed to write mock file: {}", record_path))?;
+        output.status(format_args!("{} {} ({} steps)", "Recorded step outputs to:".cyan(), record_path, recorded.len()));
+    }
+
+    let mut failed_steps: Vec<&str> = result
+        .values()
+        .filter(|r| r.status == StepStatus::Failed)
+        .map(|r| r.step_id.as_str())
+        .collect();
+    failed_steps.sort();
+
+    if output.is_text() {
+        if failed_steps.is_empty() {
+            println!("{}", "✓ Workflow completed successfully".green().bold());
+        } else {
+            println!(
+                "{} {}",
+                "✗ Workflow completed with failed steps:".red().bold(),
+                failed_steps.join(", ")
+            );
+        }
+        println!("\n{}", "Results:".cyan().bold());
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&result)
+                .unwrap_or_else(|_| format!("{:?}", result))
+        );
+        if let Some(manifest) = executor.manifest() {
+            println!("\n{}", "Reproducibility manifest:".cyan().bold());
+            println!("{}", serde_json::to_string_pretty(&manifest)?);
+        }
+    } else {
+        let status = if failed_steps.is_empty() { "completed" } else { "partial" };
+        let mut details = serde_json::json!({"failed_steps": failed_steps, "results": result});
+        if let Some(manifest) = executor.manifest() {
+            details["manifest"] = serde_json::to_value(manifest)?;
+        }
+        output.document(&run_document(run_id, &workflow_name, status, details))?;
+    }
+
+    if !failed_steps.is_empty() {
+        return Err(Failure::Partial(format!("{} step(s) failed", failed_steps.len())).into());
+    }
+
+    Ok(())
+}
+
+/// Result document of `run`: the run, how it ended (`completed`, `partial`,
+/// `failed`, `cancelled`, or `interrupted`), and the `details` fields.
+fn run_document(run_id: Uuid, workflow: &str, status: &str, details: Value) -> Value {
+    let mut document = serde_json::json!({"run_id": run_id, "workflow": workflow, "status": status});
+    if let (Some(document), Value::Object(details)) = (document.as_object_mut(), details) {
+        document.extend(details);
+    }
+    document
+}
+
+async fn submit_workflow(
+    source: WorkflowSource<'_>,
+    inputs: &InputArgs,
+    profile: Option<&str>,
+    options: SubmitOptions,
+    database_url: &str,
+    audit: Option<&Audit>,
+) -> Result<()> {
+    info!("Submitting workflow: {}", source);
+
+    let workflow = apply_profile(source.load().await?, profile)?;
+    let inputs = inputs.load().await?.unwrap_or_default();
+
+    let database = connect_database(database_url).await?;
+    let run_id = RunService::new(&database, audit.cloned()).start(&workflow, inputs, options).await?;
+
+    println!("{} {}", "✓ Run submitted:".green().bold(), run_id);
+    Ok(())
+}
+
+/// Whether `--record` names a golden recording directory rather than a mock file.
+fn is_golden_dir(path: &str) -> bool {
+    path.ends_with('/') || Path::new(path).is_dir()
+}
+
+/// Re-run the golden recordings of a workflow against their recorded provider
+/// responses and report the outputs that drifted.
+#[allow(clippy::too_many_arguments)]
+async fn replay_workflow(
+    source: WorkflowSource<'_>,
+    inputs: &InputArgs,
+    profile: Option<&str>,
+    replay_dir: &Path,
+    allow_exec: bool,
+    audit: Option<&Audit>,
+    prompts: &PromptArgs,
+    brokers: &BrokerArgs,
+    notifiers: &Notifiers,
+    mcp_servers: &[(String, Arc<McpClient>)],
+) -> Result<()> {
+    info!("Replaying workflow: {}", source);
+    println!("{} {}", "Replaying workflow:".cyan().bold(), source);
+
+    let workflow = source.load().await?;
+    workflow
+        .validate()
+        .with_context(|| "Workflow validation failed")?;
+    let mut workflow = apply_profile(workflow, profile)?;
+    // Replays only check for drift; they deliver no outputs
+    workflow.outputs = None;
+
+    let mut recordings = GoldenRecording::load_dir(replay_dir, &workflow.name)
+        .with_context(|| format!("Failed to load golden recordings: {}", replay_dir.display()))?;
+    // With inputs, replay only the recording of those inputs
+    if let Some(inputs) = inputs.load().await? {
+        recordings.retain(|(_, recording)| recording.inputs == inputs);
+    }
+    if recordings.is_empty() {
+        anyhow::bail!("No golden recordings of '{}' in {}", workflow.name, replay_dir.display());
+    }
+
+    let mut setup =
+        ExecutorSetup::new(audit.cloned(), prompts, brokers, &MemoryArgs::default(), notifiers, mcp_servers).await?;
+    let mut drifted = 0;
+    for (path, recording) in &recordings {
+        // Recordings are masked, so live requests are masked before matching
+        let redactor = run_redactor(audit);
+        setup.providers = recording.replay_providers_redacted(&redactor);
+        // Each recording starts from empty session memory
+        let executor = WorkflowExecutor::new(workflow.clone(), recording.inputs.clone())
+            .with_context(|| "Failed to create workflow executor")?
+            .with_redactor(redactor)
+            .with_exec_enabled(allow_exec)
+            .with_memory_store(Arc::new(InMemoryMemoryStore::new()));
+        let drift = match setup.apply(executor).execute().await {
+            Ok(result) => recording.diff(&result),
+            Err(OrchestratorError::StepsFailed(failures)) => {
+                drifted += 1;
+                println!("{} {}", "✗".red().bold(), path.display());
+                for failure in &failures {
+                    println!("  {} {}", failure.step_id.red(), failure.reason);
+                }
+                continue;
+            }
+            Err(e) => return Err(e).with_context(|| "Workflow execution failed"),
+        };
+
+        if drift.is_empty() {
+            println!("{} {}", "✓".green().bold(), path.display());
+            continue;
+        }
+        drifted += 1;
+        println!("{} {}", "✗".red().bold(), path.display());
+        for change in &drift {
+            let name = match &change.output {
+                Some(output) => format!("{}.{}", change.step_id, output),
+                None => change.step_id.clone(),
+            };
+            let show = |value: &Option<Value>| value.as_ref().map_or_else(|| "(missing)".to_string(), Value::to_string);
+            println!("  {}", name.red());
+            println!("    {} {}", "expected:".dimmed(), show(&change.expected));
+            println!("    {} {}", "actual:  ".dimmed(), show(&change.actual));
+        }
+    }
+
+    if drifted > 0 {
+        anyhow::bail!("{} of {} golden recording(s) drifted", drifted, recordings.len());
+    }
+    println!("{}", format!("✓ {} golden recording(s) match", recordings.len()).green().bold());
+    Ok(())
+}
+
+/// Where a workflow definition is loaded from.
+enum WorkflowSource<'a> {
+    File(&'a str),
+    /// `NAME[@VERSION]` published to the workflow registry.
+    Registry { reference: &'a str, database_url: &'a str },
+}
+
+impl std::fmt::Display for WorkflowSource<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WorkflowSource::File(path) => write!(f, "{}", path),
+            WorkflowSource::Registry { reference, .. } => write!(f, "{}", reference),
+        }
+    }
+}
+
+impl WorkflowSource<'_> {
+    /// Read and parse the workflow.
+    async fn load(&self) -> Result<Workflow> {
+        match self {
+            WorkflowSource::File(path) => {
+                Workflow::from_file(path).with_context(|| format!("Failed to load workflow file: {}", path))
+            }
+            WorkflowSource::Registry {
+                reference,
+                database_url,
+            } => {
+                let definition = load_workflow_definition(reference, database_url).await?;
+                if definition.is_deprecated() {
+                    anyhow::bail!("Workflow {}@{} is deprecated", definition.name, definition.version);
+                }
+                Workflow::from_yaml(&definition.definition).with_context(|| {
+                    format!("Failed to parse workflow YAML: {}@{}", definition.name, definition.version)
+                })
+            }
+        }
+    }
+}
+
+/// Pick the workflow source from the `FILE` and `--workflow` arguments.
+fn workflow_source<'a>(
+    file: Option<&'a str>,
+    workflow: Option<&'a str>,
+    database_url: Option<&'a str>,
+) -> WorkflowSource<'a> {
+    match (file, workflow, database_url) {
+        (Some(path), _, _) => WorkflowSource::File(path),
+        (None, Some(reference), Some(database_url)) => WorkflowSource::Registry {
+            reference,
+            database_url,
+        },
+        _ => unreachable!("clap requires FILE, or --workflow with --database-url"),
+    }
+}
+
+/// Split `NAME[@VERSION]` into its name and optional version.
+fn parse_workflow_ref(reference: &str) -> (&str, Option<&str>) {
+    match reference.rsplit_once('@') {
+        Some((name, version)) if !name.is_empty() && !version.is_empty() => (name, Some(version)),
+        _ => (reference, None),
+    }
+}
+
+/// Load a published workflow, verifying it was not modified since it was published.
+async fn load_workflow_definition(reference: &str, database_url: &str) -> Result<WorkflowDefinition> {
+    let (name, version) = parse_workflow_ref(reference);
+    let database = connect_database(database_url).await?;
+    Ok(RunService::new(&database, None).definition(name, version).await?)
+}
+
+async fn publish_workflow(
+    file_path: &str,
+    database_url: &str,
+    created_by: String,
+    audit: Option<&Audit>,
+) -> Result<()> {
+    let content = fs::read_to_string(file_path)
+        .with_context(|| format!("Failed to read workflow file: {}", file_path))?;
+    let workflow = Workflow::from_yaml_with(&content, &FileResolver::for_file(file_path))
+        .with_context(|| format!("Failed to parse workflow YAML: {}", file_path))?;
+    workflow
+        .validate()
+        .with_context(|| "Workflow validation failed")?;
+    // The registry keeps no other files, so included files are inlined
+    let content = if has_includes(&content) {
+        inline(&content, &FileResolver::for_file(file_path))?
+    } else {
+        content
+    };
+
+    let mut definition = WorkflowDefinition::new(&workflow.name, &workflow.version, content);
+    definition.created_by = Some(created_by);
+
+    let database = connect_database(database_url).await?;
+    // Re-publishing identical content is a no-op so deploy scripts can run repeatedly
+    if !RunService::new(&database, audit.cloned()).publish(&definition).await? {
+        println!(
+            "{} {}@{}",
+            "Already published:".yellow().bold(),
+            definition.name,
+            definition.version
+        );
+        return Ok(());
+    }
+
+    println!(
+        "{} {}@{} ({})",
+        "✓ Published".green().bold(),
+        definition.name,
+        definition.version,
+        &definition.checksum[..12]
+    );
+    Ok(())
+}
+
+async fn list_workflows(database_url: &str, include_deprecated: bool) -> Result<()> {
+    let database = connect_database(database_url).await?;
+    let definitions = database
+        .workflows
+        .list_workflow_definitions(include_deprecated)
+        .await
+        .with_context(|| "Failed to list workflows")?;
+
+    if definitions.is_empty() {
+        println!("{}", "No published workflows".yellow());
+        return Ok(());
+    }
+
+    for definition in definitions {
+        let status = if definition.is_deprecated() {
+            "deprecated".red()
+        } else {
+            "active".green()
+        };
+        println!(
+            "{}@{} {} {} checksum={} by={}",
+            definition.name.bold(),
+            definition.version,
+            definition.created_at.format("%Y-%m-%d %H:%M:%S"),
+            status,
+            &definition.checksum[..12],
+            definition.created_by.as_deref().unwrap_or("-")
+        );
+    }
+
+    Ok(())
+}
+
+async fn get_workflow(reference: &str, database_url: &str) -> Result<()> {
+    let definition = load_workflow_definition(reference, database_url).await?;
+    if definition.is_deprecated() {
+        eprintln!(
+            "{} {}@{} is deprecated",
+            "Warning:".yellow().bold(),
+            definition.name,
+            definition.version
+        );
+    }
+
+    print!("{}", definition.definition);
+    Ok(())
+}
+
+async fn deprecate_workflow(name: &str, version: &str, database_url: &str, audit: Option<&Audit>) -> Result<()> {
+    let database = connect_database(database_url).await?;
+    database
+        .workflows
+        .deprecate_workflow_definition(name, version)
+        .await
+        .with_context(|| format!("Failed to deprecate workflow {}@{}", name, version))?;
+
+    if let Some(audit) = audit {
+        let definition = database.workflows.get_workflow_definition(name, Some(version)).await?;
+        audit
+            .log_registry_change(AuditEventType::WorkflowUpdate, "Workflow deprecated", &definition)
+            .await;
+    }
+
+    println!("{} {}@{}", "✓ Deprecated".green().bold(), name, version);
+    Ok(())
+}
+
+async fn migrate_database(database_url: &str, dry_run: bool) -> Result<()> {
+    let migrations = connect_migrations(database_url).await?;
+    let status = migrations
+        .migration_status()
+        .await
+        .with_context(|| "Failed to read migration status")?;
+    let pending: Vec<_> = status.iter().filter(|m| !m.applied).collect();
+
+    if pending.is_empty() {
+        println!("{} ({} migrations applied)", "✓ Schema is up to date".green().bold(), status.len());
+        return Ok(());
+    }
+
+    if dry_run {
+        println!("{} pending migrations:", pending.len());
+        for migration in pending {
+            println!("  {:03} {}", migration.version, migration.description);
+        }
+        return Ok(());
+    }
+
+    let applied = migrations.migrate().await.with_context(|| "Failed to apply migrations")?;
+    for migration in &applied {
+        println!("  {:03} {}", migration.version, migration.description);
+    }
+    println!("{} {} migrations", "✓ Applied".green().bold(), applied.len());
+    Ok(())
+}
+
+/// Connects to a vector database configured through environment variables.
+fn vector_database_from_env(database: VectorDatabase) -> Result<Arc<dyn VectorSearchProvider>> {
+    Ok(match database {
+        VectorDatabase::Pinecone => Arc::new(PineconeClient::from_env()?),
+        VectorDatabase::Weaviate => Arc::new(WeaviateClient::from_env()?),
+        VectorDatabase::Qdrant => Arc::new(QdrantClient::from_env()?),
+    })
+}
+
+async fn create_index(
+    database: VectorDatabase,
+    name: &str,
+    dimension: usize,
+    metric: DistanceMetric,
+    metadata_indexes: Vec<(String, MetadataFieldType)>,
+    if_not_exists: bool,
+) -> Result<()> {
+    let client = vector_database_from_env(database)?;
+    if if_not_exists && matches!(client.index_stats(name).await, Ok(Some(_))) {
+        println!("{} {}", "✓ Index already exists:".green().bold(), name);
+        return Ok(());
+    }
+
+    client
+        .create_index(CreateIndexRequest {
+            index: name.to_string(),
+            dimension,
+            metric,
+            metadata_indexes: metadata_indexes.into_iter().collect(),
+        })
+        .await
+        .with_context(|| format!("Failed to create index '{}'", name))?;
+
+    println!("{} {} ({} dimensions, {})", "✓ Created index".green().bold(), name, dimension, metric);
+    Ok(())
+}
+
+async fn describe_index(database: VectorDatabase, name: &str, json: bool) -> Result<()> {
+    let client = vector_database_from_env(database)?;
+    let stats = client
+        .index_stats(name)
+        .await
+        .with_context(|| format!("Failed to describe index '{}'", name))?
+        .with_context(|| format!("{} does not describe indexes", client.name()))?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&stats)?);
+        return Ok(());
+    }
+
+    let unknown = || "unknown".dimmed().to_string();
+    println!("{} {}", "Index:".cyan().bold(), name);
+    println!("  Dimension: {}", stats.dimension.map(|d| d.to_string()).unwrap_or_else(unknown));
+    println!("  Vectors:   {}", stats.vector_count.map(|n| n.to_string()).unwrap_or_else(unknown));
+    println!("  Metric:    {}", stats.metric.map(|m| m.to_string()).unwrap_or_else(unknown));
+    Ok(())
+}
+
+async fn delete_index(database: VectorDatabase, name: &str) -> Result<()> {
+    vector_database_from_env(database)?
+        .delete_index(name)
+        .await
+        .with_context(|| format!("Failed to delete index '{}'", name))?;
+
+    println!("{} {}", "✓ Deleted index".green().bold(), name);
+    Ok(())
+}
+
+async fn check_health(
+    database_url: Option<&str>,
+    vector_dbs: &[VectorDatabase],
+    timeout: Duration,
+    json: bool,
+) -> Result<()> {
+    let mut registry = HealthRegistry::new().with_timeout(timeout);
+    for (name, provider) in providers_from_env() {
+        registry.register_provider(&name, provider);
+    }
+    for database in vector_dbs {
+        let client = vector_database_from_env(*database)?;
+        let name = client.name().to_string();
+        registry.register_vector_db(&name, client);
+    }
+    registry.register_secret_store("env", Arc::new(EnvSecretStore::new()));
+    if let Some(database_url) = database_url {
+        let database = connect_database(database_url).await?;
+        registry.register_state_store(database_kind(database_url), database.state);
+    }
+    if registry.components().is_empty() {
+        anyhow::bail!("Nothing to check");
+    }
+
+    let result = registry.readiness().await;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+    } else {
+        println!("{} {}", "Health:".cyan().bold(), colored_health(result.status));
+        let mut names: Vec<&String> = result.checks.keys().collect();
+        names.sort();
+        for name in names {
+            let check = &result.checks[name];
+            println!(
+                "  {:<10} {:<28} {:>7}  {}",
+                colored_health(check.status),
+                name,
+                check.response_time_ms.map(|ms| format!("{}ms", ms)).unwrap_or_default(),
+                check.error.as_deref().unwrap_or_default()
+            );
+        }
+    }
+
+    if !result.is_ready() {
+        anyhow::bail!("Unhealthy components");
+    }
+    Ok(())
+}
+
+fn colored_health(status: HealthStatus) -> colored::ColoredString {
+    match status {
+        HealthStatus::Healthy => "healthy".green(),
+        HealthStatus::Degraded => "degraded".yellow(),
+        HealthStatus::Unhealthy => "unhealthy".red(),
+    }
+}
+
+/// Apply the selected profile, if any, to a parsed workflow.
+fn apply_profile(workflow: Workflow, profile: Option<&str>) -> Result<Workflow> {
+    match profile {
+        Some(name) => {
+            info!("Applying profile: {}", name);
+            workflow
+                .with_profile(name)
+                .with_context(|| format!("Failed to apply profile '{}'", name))
+        }
+        None => Ok(workflow),
+    }
+}
+
+async fn cancel_run(run_id: Uuid, database_url: &str) -> Result<()> {
+    let database = connect_database(database_url).await?;
+    RunService::new(&database, None).cancel(run_id).await?;
+
+    println!("{} {}", "✓ Run cancelled:".green().bold(), run_id);
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn run_worker(
+    database_url: &str,
+    worker_id: Option<String>,
+    lease: Duration,
+    poll_interval: Duration,
+    max_concurrency: usize,
+    resource_limits: Vec<(ResourceClass, usize)>,
+    allow_exec: bool,
+    drain_timeout: Duration,
+    recovery: &RecoveryArgs,
+    audit: Option<Audit>,
+    prompts: &PromptArgs,
+    brokers: &BrokerArgs,
+    memory: &MemoryArgs,
+    notifiers: &Notifiers,
+    mcp_servers: &[(String, Arc<McpClient>)],
+) -> Result<()> {
+    let database = connect_database(database_url).await?;
+    let setup = ExecutorSetup::new(audit, prompts, brokers, memory, notifiers, mcp_servers).await?;
+    if setup.providers.is_empty() {
+        anyhow::bail!(
+            "No LLM providers available. Please set OPENAI_API_KEY or ANTHROPIC_API_KEY environment variable."
+        );
+    }
+    recovery.recover(&database).await?;
+
+    let dead_letters = database.dead_letters;
+    let mut worker = Worker::new(database.queue, database.state)
+        .with_lease(lease)
+        .with_poll_interval(poll_interval)
+        .with_drain_timeout(drain_timeout)
+        .with_executor_setup(move |executor| {
+            let mut executor = executor
+                .with_max_concurrency(max_concurrency)
+                .with_exec_enabled(allow_exec)
+                .with_dead_letter_queue(dead_letters.clone());
+            for &(class, limit) in &resource_limits {
+                executor = executor.with_resource_limit(class, limit);
+            }
+            setup.apply(executor)
+        });
+    if let Some(worker_id) = worker_id {
+        worker = worker.with_worker_id(worker_id);
+    }
+
+    println!("{} {}", "Worker started:".cyan().bold(), worker.worker_id());
+
+    // Stop claiming runs on SIGINT or SIGTERM and drain the current one
+    let shutdown = worker.shutdown_handle();
+    tokio::spawn(async move {
+        shutdown_signal().await;
+        eprintln!(
+            "{} waiting up to {}s for the current run",
+            "Worker shutting down:".yellow().bold(),
+            drain_timeout.as_secs()
+        );
+        shutdown.cancel();
+    });
+    worker.run().await.with_context(|| "Worker stopped")?;
+
+    println!("{}", "Worker stopped".cyan());
+    Ok(())
+}
+
+/// Build a run history query from `runs list` arguments.
+fn build_run_query(
+    since: Option<&str>,
+    until: Option<&str>,
+    workflow: Option<String>,
+    status: Option<WorkflowStatus>,
+    user: Option<String>,
+    tenant: Option<String>,
+) -> Result<RunQuery> {
+    let mut query = RunQuery::new();
+    if let Some(since) = since {
+        query = query.since(parse_time(since, NaiveTime::MIN)?);
+    }
+    if let Some(until) = until {
+        query = query.until(parse_time(until, end_of_day())?);
+    }
+    if let Some(workflow) = workflow {
+        query = query.workflow_name(workflow);
+    }
+    if let Some(status) = status {
+        query = query.status(status);
+    }
+    if let Some(user) = user {
+        query = query.user_id(user);
+    }
+    if let Some(tenant) = tenant {
+        query = query.tenant_id(tenant);
+    }
+    Ok(query)
+}
+
+fn colored_status(status: &WorkflowStatus) -> colored::ColoredString {
+    let label = status.to_string();
+    match status {
+        WorkflowStatus::Completed => label.green(),
+        WorkflowStatus::Failed => label.red(),
+        WorkflowStatus::Cancelled => label.yellow(),
+        WorkflowStatus::Pending | WorkflowStatus::Running | WorkflowStatus::Paused => label.cyan(),
+    }
+}
+
+async fn list_runs(query: RunQuery, json: bool, database_url: &str) -> Result<()> {
+    let database = connect_database(database_url).await?;
+    let page = database
+        .state
+        .list_workflow_runs(&query)
+        .await
+        .with_context(|| "Failed to list runs")?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&page)?);
+        return Ok(());
+    }
+
+    if page.runs.is_empty() {
+        println!("{}", "No matching runs".yellow());
+        return Ok(());
+    }
+
+    for run in &page.runs {
+        let duration = run
+            .duration()
+            .map(|d| format!("{}ms", d.num_milliseconds()))
+            .unwrap_or_else(|| "-".to_string());
+        println!(
+            "{}  {}  {:<10} {:<12} {:>9}  {}",
+            run.id,
+            run.started_at.format("%Y-%m-%d %H:%M:%S"),
+            colored_status(&run.status),
+            run.user_id.as_deref().unwrap_or("-"),
+            duration,
+            run.workflow_name.bold(),
+        );
+        if let Some(error) = &run.error {
+            println!("    {}", error.red());
+        }
+    }
+
+    println!(
+        "\n{} {}-{} of {}",
+        "Showing".cyan(),
+        page.offset + 1,
+        page.offset + page.runs.len(),
+        page.total
+    );
+    Ok(())
+}
+
+async fn report_run(
+    id: Uuid,
+    format: ReportFormat,
+    output: Option<&Path>,
+    database_url: &str,
+    tenant: Option<&str>,
+) -> Result<()> {
+    let database = connect_database(database_url).await?.for_tenant(tenant);
+    let state = database
+        .state
+        .load_workflow_state(&id)
+        .await
+        .with_context(|| format!("Failed to load run: {}", id))?;
+
+    let report: ExecutionReport = state
+        .context
+        .get("report")
+        .map(|report| serde_json::from_value(report.clone()))
+        .transpose()?
+        .with_context(|| format!("Run {} has no execution report", id))?;
+    let rendered = report.render(format)?;
+
+    match output {
+        Some(path) => {
+            fs::write(path, rendered).with_context(|| format!("Failed to write report: {}", path.display()))?;
+            println!("{} {}", "✓ Report written to".green().bold(), path.display());
+        }
+        None => print!("{}", rendered),
+    }
+    Ok(())
+}
+
+async fn show_usage(query: RunQuery, json: bool, database_url: &str) -> Result<()> {
+    let database = connect_database(database_url).await?;
+    let summary = database
+        .usage
+        .usage_summary(&query)
+        .await
+        .with_context(|| "Failed to summarize usage")?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&summary)?);
+        return Ok(());
+    }
+
+    if summary.is_empty() {
+        println!("{}", "No usage recorded for matching runs".yellow());
+        return Ok(());
+    }
+
+    println!(
+        "{:<12} {:<28} {:>6} {:>9} {:>7} {:>12} {:>12} {:>12} {:>10}",
+        "PROVIDER", "MODEL", "RUNS", "REQUESTS", "FAILED", "INPUT", "OUTPUT", "TOTAL", "COST"
+    );
+    let mut total_tokens = 0;
+    let mut total_cost = 0.0;
+    for row in &summary {
+        let usage = &row.usage;
+        println!(
+            "{:<12} {:<28} {:>6} {:>9} {:>7} {:>12} {:>12} {:>12} {:>10}",
+            usage.provider,
+            usage.model,
+            row.runs,
+            usage.requests,
+            usage.failed_requests,
+            usage.input_tokens,
+            usage.output_tokens,
+            usage.total_tokens,
+            format!("${:.4}", usage.cost_usd),
+        );
+        total_tokens += usage.total_tokens;
+        total_cost += usage.cost_usd;
+    }
+
+    println!("\n{} {} tokens, ${:.4}", "Total:".cyan().bold(), total_tokens, total_cost);
+    Ok(())
+}
+
+async fn purge_runs(policy: RetentionPolicy, dry_run: bool, json: bool, database_url: &str) -> Result<()> {
+    if policy == RetentionPolicy::default() {
+        anyhow::bail!("Nothing to do: set --completed-days, --failed-days, or --compress-after-days");
+    }
+
+    let database = connect_database(database_url).await?;
+    let manager = StateRetentionManager::new(database.retention, policy);
+    let report = if dry_run { manager.dry_run().await } else { manager.cleanup().await }
+        .with_context(|| "Failed to apply run retention")?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    let (purged, compressed) = if dry_run {
+        ("Would delete:", "Would compress:")
+    } else {
+        ("Deleted:", "Compressed:")
+    };
+    for run in &report.purged {
+        let finished = run.completed_at.unwrap_or(run.updated_at);
+        println!(
+            "  {} {:<24} {:<10} {}",
+            run.id,
+            run.workflow_name,
+            run.status.to_string(),
+            finished.format("%Y-%m-%d %H:%M")
+        );
+    }
+    println!("{} {} runs", purged.cyan().bold(), report.purged.len());
+    if policy.compress_after_days.is_some() {
+        println!("{} {} run contexts", compressed.cyan().bold(), report.compressed);
+    }
+    Ok(())
+}
+
+async fn show_run(id: Uuid, json: bool, database_url: &str, tenant: Option<&str>) -> Result<()> {
+    let database = connect_database(database_url).await?.for_tenant(tenant);
+    let state = database
+        .state
+        .load_workflow_state(&id)
+        .await
+        .with_context(|| format!("Failed to load run: {}", id))?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&state)?);
+        return Ok(());
+    }
+
+    println!("{} {}", "Run:".cyan().bold(), state.id);
+    println!("  Workflow: {} ({})", state.workflow_name.bold(), state.workflow_id);
+    println!("  Status:   {}", colored_status(&state.status));
+    println!("  User:     {}", state.user_id.as_deref().unwrap_or("-"));
+    println!("  Started:  {}", state.started_at.format("%Y-%m-%d %H:%M:%S"));
+    if let Some(completed_at) = state.completed_at {
+        println!("  Finished: {}", completed_at.format("%Y-%m-%d %H:%M:%S"));
+    }
+    if let Some(error) = &state.error {
+        println!("  Error:    {}", error.red());
+    }
+
+    let mut steps: Vec<_> = state.steps.values().collect();
+    steps.sort_by_key(|step| (step.started_at, step.step_id.clone()));
+    if !steps.is_empty() {
+        println!("\n{}", "Steps:".cyan().bold());
+    }
+    for step in steps {
+        let retries = if step.retry_count > 0 {
+            format!(" (retries: {})", step.retry_count)
+        } else {
+            String::new()
+        };
+        println!("  {:<24} {}{}", step.step_id, step.status, retries);
+        if let Some(error) = &step.error {
+            println!("      {}", error.red());
+        }
+    }
+
+    Ok(())
+}
+
+async fn list_dead_letters(database_url: &str, include_resolved: bool) -> Result<()> {
+    let database = connect_database(database_url).await?;
+    let entries = database
+        .dead_letters
+        .list_dead_letters(include_resolved)
+        .await
+        .with_context(|| "Failed to list dead letters")?;
+
+    if entries.is_empty() {
+        println!("{}", "No dead-lettered steps".green());
+        return Ok(());
+    }
+
+    for entry in entries {
+        let status = if entry.is_resolved() {
+            "resolved".green()
+        } else {
+            "failed".red()
+        };
+        println!(
+            "{} {} {} step={} run={} attempts={}",
+            entry.id,
+            entry.created_at.format("%Y-%m-%d %H:%M:%S"),
+            status,
+            entry.step_id.bold(),
+            entry.run_id,
+            entry.attempts.len()
+        );
+        println!("    {}", entry.error);
+    }
+
+    Ok(())
+}
+
+async fn show_dead_letter(id: Uuid, database_url: &str) -> Result<()> {
+    let database = connect_database(database_url).await?;
+    let entry = database
+        .dead_letters
+        .get_dead_letter(&id)
+        .await
+        .with_context(|| format!("Failed to load dead letter: {}", id))?;
+
+    println!("{}", serde_json::to_string_pretty(&entry)?);
+    Ok(())
+}
+
+async fn retry_dead_letter_step(
+    id: Uuid,
+    database_url: &str,
+    audit: Option<&Audit>,
+    prompts: &PromptArgs,
+) -> Result<()> {
+    let database = connect_database(database_url).await?;
+    let prompt_registry = open_prompt_registry(prompts).await?;
+
+    let providers = providers_from_env();
+    if providers.is_empty() {
+        anyhow::bail!(
+            "No LLM providers available. Please set OPENAI_API_KEY or ANTHROPIC_API_KEY environment variable."
+        );
+    }
+
+    println!("{} {}", "Retrying dead letter:".cyan().bold(), id);
+    let result = retry_dead_letter(database.dead_letters.as_ref(), &id, |executor| {
+        let mut executor = executor
+            .with_secret_store(Arc::new(EnvSecretStore::new()))
+            .with_prompt_registry(prompt_registry);
+        for (name, provider) in providers {
+            executor = executor.with_provider(name, provider);
+        }
+        match audit {
+            Some(audit) => audit.attach(executor),
+            None => executor,
+        }
+    })
+    .await
+    .with_context(|| "Retry failed")?;
+
+    println!("{}", serde_json::to_string_pretty(&result)?);
+    if result.status != StepStatus::Completed {
+        anyhow::bail!(
+            "Step '{}' failed again: {}",
+            result.step_id,
+            result.error.unwrap_or_default()
+        );
+    }
+
+    println!("{} {}", "✓ Step completed, dead letter resolved:".green().bold(), id);
+    Ok(())
+}
+
+/// Providers, prompts, sinks, notifiers, MCP servers, and audit logging
+/// shared by the executors of a long-running process.
+#[derive(Clone)]
+struct ExecutorSetup {
+    providers: HashMap<String, Arc<dyn LLMProvider>>,
+    prompt_registry: PromptRegistry,
+    sinks: Vec<(String, Arc<dyn MessageSink>)>,
+    memory: Option<Arc<dyn MemoryStore>>,
+    notifiers: Notifiers,
+    mcp_servers: Vec<(String, Arc<McpClient>)>,
+    audit: Option<Audit>,
+}
+
+impl ExecutorSetup {
+    async fn new(
+        audit: Option<Audit>,
+        prompts: &PromptArgs,
+        brokers: &BrokerArgs,
+        memory: &MemoryArgs,
+        notifiers: &Notifiers,
+        mcp_servers: &[(String, Arc<McpClient>)],
+    ) -> Result<Self> {
+        Ok(Self {
+            providers: providers_from_env(),
+            prompt_registry: open_prompt_registry(prompts).await?,
+            sinks: brokers.sinks().await?,
+            memory: memory.store().await?,
+            notifiers: notifiers.clone(),
+            mcp_servers: mcp_servers.to_vec(),
+            audit,
+        })
+    }
+
+    /// Register everything with an executor.
+    fn apply(&self, executor: WorkflowExecutor) -> WorkflowExecutor {
+        let mut executor = executor
+            .with_secret_store(Arc::new(EnvSecretStore::new()))
+            .with_prompt_registry(self.prompt_registry.clone());
+        for (name, sink) in &self.sinks {
+            executor = executor.with_message_sink(name.clone(), sink.clone());
+        }
+        if let Some(store) = &self.memory {
+            executor = executor.with_memory_store(store.clone());
+        }
+        executor = self.notifiers.attach(executor);
+        for (name, client) in &self.mcp_servers {
+            executor = executor.with_mcp_server(name.clone(), client.clone());
+        }
+        for (name, provider) in &self.providers {
+            executor = executor.with_provider(name.clone(), provider.clone());
+        }
+        match &self.audit {
+            Some(audit) => audit.attach(executor),
+            None => executor,
+        }
+    }
+}
+
+/// Redactor of a run: the audit log's when auditing, so both mask the same secrets.
+fn run_redactor(audit: Option<&Audit>) -> Redactor {
+    audit.map_or_else(|| Redactor::new().with_default_patterns(), |audit| audit.redactor.clone())
+}
+
+/// Audit logger and settings shared by every run of this process.
+#[derive(Clone)]
+struct Audit {
+    logger: Arc<AuditLogger>,
+    config: AuditConfig,
+    redactor: Redactor,
+}
+
+impl Audit {
+    /// Open the audit log if `--audit-log` was given.
+    fn from_args(args: &AuditArgs, tenant: Option<&str>) -> Result<Option<Self>> {
+        let Some(path) = &args.audit_log else {
+            return Ok(None);
+        };
+
+        let storage = FileAuditStorage::new(path.clone(), RotationPolicy::Never)
+            .with_context(|| format!("Failed to open audit log: {}", path.display()))?;
+        // Share the redactor so secrets resolved during a run are masked in its audit events
+        let redactor = Redactor::new().with_default_patterns();
+        let mut logger = AuditLogger::new(Arc::new(storage)).with_redactor(redactor.clone());
+        if let Some(tenant) = tenant {
+            logger = logger.with_tenant_id(tenant);
+        }
+
+        Ok(Some(Self {
+            logger: Arc::new(logger),
+            config: AuditConfig::new(args.audit_actor.clone()).with_prompts(args.audit_include_prompts),
+            redactor,
+        }))
+    }
+
+    /// Record audit events for the executor's run.
+    fn attach(&self, executor: WorkflowExecutor) -> WorkflowExecutor {
+        executor
+            .with_redactor(self.redactor.clone())
+            .with_audit_logger(self.logger.clone())
+            .with_audit_config(self.config.clone())
+    }
+
+    /// Record that a run was submitted to the work queue.
+    async fn log_submission(&self, workflow: &Workflow, run_id: Uuid) {
+        let event = AuditEvent::new(
+            AuditEventType::WorkflowExecution,
+            "Workflow submitted".to_string(),
+            ResourceType::Workflow,
+            workflow.id.to_string(),
+            AuditResult::Success,
+        )
+        .with_user_id(self.config.actor.clone())
+        .with_request_id(run_id.to_string())
+        .with_details(serde_json::json!({
+            "workflow_name": workflow.name,
+            "workflow_version": workflow.version,
+        }));
+
+        if let Err(e) = self.logger.log_event(event).await {
+            error!("Failed to write audit event: {}", e);
+        }
+    }
+
+    /// Record a change to the workflow registry.
+    async fn log_registry_change(&self, event_type: AuditEventType, action: &str, definition: &WorkflowDefinition) {
+        let event = AuditEvent::new(
+            event_type,
+            action.to_string(),
+            ResourceType::Workflow,
+            format!("{}@{}", definition.name, definition.version),
+            AuditResult::Success,
+        )
+        .with_user_id(self.config.actor.clone())
+        .with_details(serde_json::json!({
+            "workflow_name": definition.name,
+            "workflow_version": definition.version,
+            "checksum": definition.checksum,
+        }));
+
+        if let Err(e) = self.logger.log_event(event).await {
+            error!("Failed to write audit event: {}", e);
+        }
+    }
+}
+
+/// Build an audit query from `audit search` arguments.
+fn build_audit_query(
+    since: Option<&str>,
+    until: Option<&str>,
+    event_type: Option<AuditEventType>,
+    workflow_id: Option<String>,
+    user: Option<String>,
+    result: Option<AuditResult>,
+) -> Result<AuditQuery> {
+    let mut query = AuditQuery::new();
+    if let Some(since) = since {
+        query = query.since(parse_time(since, NaiveTime::MIN)?);
+    }
+    if let Some(until) = until {
+        query = query.until(parse_time(until, end_of_day())?);
+    }
+    if let Some(event_type) = event_type {
+        query = query.event_type(event_type);
+    }
+    if let Some(workflow_id) = workflow_id {
+        query = query.workflow_id(workflow_id);
+    }
+    if let Some(user) = user {
+        query = query.user_id(user);
+    }
+    if let Some(result) = result {
+        query = query.result(result);
+    }
+    Ok(query)
+}
+
+/// Last millisecond of a day, so date-only `--until` values include the whole day.
+fn end_of_day() -> NaiveTime {
+    NaiveTime::from_hms_milli_opt(23, 59, 59, 999).unwrap_or(NaiveTime::MIN)
+}
+
+/// Parse an RFC 3339 timestamp, or a date at `time_of_day` UTC.
+fn parse_time(value: &str, time_of_day: NaiveTime) -> Result<DateTime<Utc>> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .with_context(|| format!("Invalid time '{}': expected RFC 3339 or YYYY-MM-DD", value))?;
+    Ok(date.and_time(time_of_day).and_utc())
+}
+
+/// Open the audit database if given, else the audit log file, confined to `tenant` if set.
+async fn open_audit_storage(
+    database_url: Option<&str>,
+    audit_log: Option<&Path>,
+    tenant: Option<&str>,
+) -> Result<Box<dyn AuditStorage>> {
+    let storage: Box<dyn AuditStorage> = match (database_url, audit_log) {
+        (Some(database_url), _) => Box::new(
+            DatabaseAuditStorage::new(database_url)
+                .await
+                .with_context(|| "Failed to connect to audit database")?,
+        ),
+        (None, Some(path)) => Box::new(
+            FileAuditStorage::new(path.to_path_buf(), RotationPolicy::Never)
+                .with_context(|| format!("Failed to open audit log: {}", path.display()))?,
+        ),
+        (None, None) => anyhow::bail!("Specify the audit log with --audit-log or --database-url"),
+    };
+    match tenant {
+        Some(tenant) => Ok(Box::new(TenantAuditStorage::new(Arc::from(storage), tenant))),
+        None => Ok(storage),
+    }
+}
+
+async fn search_audit_events(
+    query: AuditQuery,
+    json: bool,
+    database_url: Option<&str>,
+    audit_log: Option<&Path>,
+    tenant: Option<&str>,
+) -> Result<()> {
+    let storage = open_audit_storage(database_url, audit_log, tenant).await?;
+    let page = storage
+        .search(query)
+        .await
+        .with_context(|| "Failed to search audit events")?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&page)?);
+    } else {
+        print_audit_page(&page);
+    }
+    Ok(())
+}
+
+async fn export_audit_events(
+    query: AuditQuery,
+    format: ExportFormat,
+    output: Option<&Path>,
+    database_url: Option<&str>,
+    audit_log: Option<&Path>,
+    tenant: Option<&str>,
+) -> Result<()> {
+    let storage = open_audit_storage(database_url, audit_log, tenant).await?;
+
+    let written = match output {
+        Some(path) => {
+            let file = fs::File::create(path)
+                .with_context(|| format!("Failed to create export file: {}", path.display()))?;
+            let mut writer = std::io::BufWriter::new(file);
+            export_events(storage.as_ref(), query, format, &mut writer).await
+        }
+        None => export_events(storage.as_ref(), query, format, &mut std::io::stdout().lock()).await,
+    }
+    .with_context(|| "Failed to export audit events")?;
+
+    // Keep stdout clean for piping into a collector
+    eprintln!("{} {} events", "Exported".green().bold(), written);
+    Ok(())
+}
+
+fn print_audit_page(page: &AuditPage) {
+    if page.events.is_empty() {
+        println!("{}", "No matching audit events".yellow());
+        return;
+    }
+
+    for event in &page.events {
+        let result = match &event.result {
+            AuditResult::Success => "success".green(),
+            AuditResult::PartialSuccess => "partial".yellow(),
+            AuditResult::Failure(_) => "failure".red(),
+        };
+        let model = event
+            .details
+            .get("model")
+            .and_then(|m| m.as_str())
+            .map(|m| format!(" model={}", m))
+            .unwrap_or_default();
+        println!(
+            "{}  {:<12} {:<18} {:<8} {}:{}{}  {}",
+            event.timestamp.format("%Y-%m-%d %H:%M:%S"),
+            event.user_id.as_deref().unwrap_or("-"),
+            event.event_type.as_str(),
+            result,
+            event.resource_type.as_str(),
+            event.resource_id,
+            model,
+            event.action,
+        );
+        if let Some(error) = event.result.error_message() {
+            println!("    {}", error.red());
+        }
+    }
+
+    println!(
+        "\n{} {}-{} of {}",
+        "Showing".cyan(),
+        page.offset + 1,
+        page.offset + page.events.len(),
+        page.total
+    );
+}
+
+/// Stores backed by the shared orchestration database.
+struct Database {
+    queue: Arc<dyn WorkQueue>,
+    state: Arc<dyn StateStore>,
+    dead_letters: Arc<dyn DeadLetterQueue>,
+    prompts: Arc<dyn PromptVersionStore>,
+    memory: Arc<dyn SessionMemoryStore>,
+    workflows: Arc<dyn WorkflowDefinitionStore>,
+    usage: Arc<dyn UsageStore>,
+    retention: Arc<dyn RetentionStore>,
+}
+
+impl Database {
+    /// Confine runs and checkpoints to `tenant`, if set.
+    fn for_tenant(mut self, tenant: Option<&str>) -> Self {
+        if let Some(tenant) = tenant {
+            self.state = Arc::new(TenantStateStore::new(self.state, tenant));
+        }
+        self
+    }
+}
+
+/// Secret holding the base64 state encryption key.
+const STATE_ENCRYPTION_SECRET: &str = "state/encryption_key";
+
+/// Environment variable the secret is read from.
+const STATE_ENCRYPTION_ENV: &str = "STATE_ENCRYPTION_KEY";
+
+/// Loads the state encryption key, if one is configured.
+async fn state_encryption() -> Result<Option<StateEncryption>> {
+    if std::env::var_os(STATE_ENCRYPTION_ENV).is_none() {
+        return Ok(None);
+    }
+    let encryption = StateEncryption::from_secret_store(&EnvSecretStore::new(), STATE_ENCRYPTION_SECRET)
+        .await
+        .with_context(|| format!("Invalid {}", STATE_ENCRYPTION_ENV))?;
+    Ok(Some(encryption))
+}
+
+/// Connects to a PostgreSQL URL or a SQLite file path.
+///
+/// Contexts, outputs, and snapshots are encrypted when `STATE_ENCRYPTION_KEY` is set,
+/// and step outputs over 256 KiB are kept in the `step_output_blobs` table.
+/// Name of the database behind a URL, as used in health checks.
+fn database_kind(database_url: &str) -> &'static str {
+    if database_url.starts_with("postgres://") || database_url.starts_with("postgresql://") {
+        "postgres"
+    } else {
+        "sqlite"
+    }
+}
+
+async fn connect_database(database_url: &str) -> Result<Database> {
+    let encryption = state_encryption().await?;
+    if database_url.starts_with("postgres://") || database_url.starts_with("postgresql://") {
+        let mut store = PostgresStateStore::new(database_url, None, None)
+            .await
+            .with_context(|| "Failed to connect to PostgreSQL")?
+            .with_output_offload(OutputOffload::default());
+        if let Some(encryption) = encryption {
+            store = store.with_encryption(encryption);
+        }
+        let store = Arc::new(store);
+        Ok(Database {
+            queue: store.clone(),
+            state: store.clone(),
+            dead_letters: store.clone(),
+            prompts: store.clone(),
+            memory: store.clone(),
+            workflows: store.clone(),
+            usage: store.clone(),
+            retention: store,
+        })
+    } else {
+        let mut store = SqliteStateStore::new(database_url)
+            .await
+            .with_context(|| format!("Failed to open SQLite database: {}", database_url))?
+            .with_output_offload(OutputOffload::default());
+        if let Some(encryption) = encryption {
+            store = store.with_encryption(encryption);
+        }
+        let store = Arc::new(store);
+        Ok(Database {
+            queue: store.clone(),
+            state: store.clone(),
+            dead_letters: store.clone(),
+            prompts: store.clone(),
+            memory: store.clone(),
+            workflows: store.clone(),
+            usage: store.clone(),
+            retention: store,
+        })
+    }
+}
+
+/// Connects to a database without applying migrations.
+async fn connect_migrations(database_url: &str) -> Result<Arc<dyn SchemaMigrations>> {
+    if database_url.starts_with("postgres://") || database_url.starts_with("postgresql://") {
+        Ok(Arc::new(
+            PostgresStateStore::connect(database_url, None, None)
+                .await
+                .with_context(|| "Failed to connect to PostgreSQL")?,
+        ))
+    } else {
+        Ok(Arc::new(
+            SqliteStateStore::connect(database_url)
+                .await
+                .with_context(|| format!("Failed to open SQLite database: {}", database_url))?,
+        ))
+    }
+}
+
+/// Opens the prompt registry selected by `--prompts-dir` or `--prompts-database-url`.
+async fn open_prompt_registry(args: &PromptArgs) -> Result<PromptRegistry> {
+    let store: Arc<dyn PromptStore> = match &args.prompts_database_url {
+        Some(database_url) => Arc::new(StatePromptStore::new(connect_database(database_url).await?.prompts)),
+        None => Arc::new(FilePromptStore::new(&args.prompts_dir)),
+    };
+    Ok(PromptRegistry::new(store))
+}
+
+impl MemoryArgs {
+    /// Store of session memory, if one is configured.
+    async fn store(&self) -> Result<Option<Arc<dyn MemoryStore>>> {
+        #[cfg(feature = "redis")]
+        if let Some(url) = &self.memory_redis_url {
+            let store = llm_orchestrator_core::memory::redis::RedisMemoryStore::connect(url).await?;
+            return Ok(Some(Arc::new(store)));
+        }
+        match &self.memory_database_url {
+            Some(database_url) => Ok(Some(Arc::new(StateMemoryStore::new(connect_database(database_url).await?.memory)))),
+            None => Ok(None),
+        }
+    }
+}
+
+impl BrokerArgs {
+    /// Sinks for `publish` actions, named after their broker (`kafka`, `nats`).
+    #[cfg_attr(not(any(feature = "kafka", feature = "nats")), allow(unused_mut))]
+    async fn sinks(&self) -> Result<Vec<(String, Arc<dyn MessageSink>)>> {
+        let mut sinks: Vec<(String, Arc<dyn MessageSink>)> = Vec::new();
+        #[cfg(feature = "kafka")]
+        if let Some(brokers) = &self.kafka_brokers {
+            let sink = llm_orchestrator_core::messaging::kafka::KafkaSink::new(brokers)?;
+            sinks.push(("kafka".to_string(), Arc::new(sink)));
+        }
+        #[cfg(feature = "nats")]
+        if let Some(url) = &self.nats_url {
+            let sink = llm_orchestrator_core::messaging::nats::NatsSink::connect(url).await?;
+            sinks.push(("nats".to_string(), Arc::new(sink)));
+        }
+        Ok(sinks)
+    }
+
+    /// Subscribe to the topic of a `kafka` or `nats` trigger, in the consumer group `group`.
+    #[cfg_attr(not(any(feature = "kafka", feature = "nats")), allow(unused_variables))]
+    async fn source(&self, trigger: &Trigger, group: &str) -> Result<Box<dyn MessageSource>> {
+        match trigger {
+            #[cfg(feature = "kafka")]
+            Trigger::Kafka(topic) => {
+                let brokers = self
+                    .kafka_brokers
+                    .as_deref()
+                    .context("--kafka-brokers is required to consume kafka triggers")?;
+                let source = llm_orchestrator_core::messaging::kafka::KafkaSource::new(brokers, group, &topic.topic)?;
+                Ok(Box::new(source))
+            }
+            #[cfg(feature = "nats")]
+            Trigger::Nats(topic) => {
+                let url = self
+                    .nats_url
+                    .as_deref()
+                    .context("--nats-url is required to consume nats triggers")?;
+                let source = llm_orchestrator_core::messaging::nats::NatsSource::connect(url, group, &topic.topic).await?;
+                Ok(Box::new(source))
+            }
+            #[cfg(not(feature = "kafka"))]
+            Trigger::Kafka(_) => anyhow::bail!("kafka triggers need a build with the `kafka` feature"),
+            #[cfg(not(feature = "nats"))]
+            Trigger::Nats(_) => anyhow::bail!("nats triggers need a build with the `nats` feature"),
+            Trigger::Webhook(_) | Trigger::Chat(_) => anyhow::bail!("Webhook and chat triggers are served over HTTP"),
+        }
+    }
+}
+
+/// Clients of the MCP servers in `--mcp-config`; they connect when first used.
+fn open_mcp_servers(path: Option<&Path>) -> Result<Vec<(String, Arc<McpClient>)>> {
+    let Some(path) = path else {
+        return Ok(Vec::new());
+    };
+    let servers = load_mcp_servers(path).with_context(|| format!("Failed to load MCP servers: {}", path.display()))?;
+    Ok(servers
+        .into_iter()
+        .map(|(name, config)| (name, Arc::new(McpClient::new(config))))
+        .collect())
+}
+
+/// Action handlers and failure alerts for executors.
+#[derive(Clone, Default)]
+struct Notifiers {
+    handlers: Vec<(&'static str, Arc<dyn ActionHandler>)>,
+    on_failure: Vec<Arc<dyn FailureNotifier>>,
+}
+
+impl Notifiers {
+    /// Register the actions and failure alerts with an executor.
+    fn attach(&self, mut executor: WorkflowExecutor) -> WorkflowExecutor {
+        for (name, handler) in &self.handlers {
+            executor = executor.with_action_handler(*name, handler.clone());
+        }
+        for notifier in &self.on_failure {
+            executor = executor.with_failure_notifier(notifier.clone());
+        }
+        executor
+    }
+}
+
+impl NotifyArgs {
+    /// Set up the channels configured through environment variables.
+    ///
+    /// Each configured channel serves the action named after it; the channels
+    /// given to `--notify-on-failure` must be configured.
+    fn notifiers(&self) -> Result<Notifiers> {
+        let mut notifiers = Notifiers::default();
+        let on_failure = |channel| self.notify_on_failure.contains(&channel);
+
+        if let Ok(url) = std::env::var("SLACK_WEBHOOK_URL") {
+            let slack = Arc::new(SlackNotifier::new(url));
+            notifiers.handlers.push(("slack", slack.clone()));
+            if on_failure(NotifyChannel::Slack) {
+                notifiers.on_failure.push(slack);
+            }
+        } else if on_failure(NotifyChannel::Slack) {
+            anyhow::bail!("SLACK_WEBHOOK_URL is required to notify Slack of failures");
+        }
+
+        if let Ok(routing_key) = std::env::var("PAGERDUTY_ROUTING_KEY") {
+            let pagerduty = Arc::new(PagerDutyNotifier::new(routing_key));
+            notifiers.handlers.push(("pagerduty", pagerduty.clone()));
+            if on_failure(NotifyChannel::Pagerduty) {
+                notifiers.on_failure.push(pagerduty);
+            }
+        } else if on_failure(NotifyChannel::Pagerduty) {
+            anyhow::bail!("PAGERDUTY_ROUTING_KEY is required to page on failures");
+        }
+
+        #[cfg(feature = "email")]
+        if let (Ok(url), Ok(from)) = (std::env::var("SMTP_URL"), std::env::var("SMTP_FROM")) {
+            let email = llm_orchestrator_core::notify::EmailNotifier::new(&url, &from)?
+                .with_failure_recipients(&self.notify_email)?;
+            let email = Arc::new(email);
+            notifiers.handlers.push(("email", email.clone()));
+            if on_failure(NotifyChannel::Email) {
+                if self.notify_email.is_empty() {
+                    anyhow::bail!("--notify-email is required to email failures");
+                }
+                notifiers.on_failure.push(email);
+            }
+        } else if on_failure(NotifyChannel::Email) {
+            anyhow::bail!("SMTP_URL and SMTP_FROM are required to email failures");
+        }
+        #[cfg(not(feature = "email"))]
+        if on_failure(NotifyChannel::Email) {
+            anyhow::bail!("Email notifications need a build with the `email` feature");
+        }
+
+        Ok(notifiers)
+    }
+}
+
+/// Parses a resource class limit given as `llm=2`.
+fn parse_resource_limit(value: &str) -> std::result::Result<(ResourceClass, usize), String> {
+    let (class, limit) = value
+        .split_once('=')
+        .ok_or_else(|| format!("Expected CLASS=N, got '{}'", value))?;
+    let limit = limit
+        .parse()
+        .map_err(|_| format!("Invalid limit for {}: '{}'", class, limit))?;
+    Ok((class.parse()?, limit))
+}
+
+fn parse_rule_level(value: &str) -> std::result::Result<(LintRule, Level), String> {
+    let (rule, level) = value
+        .split_once('=')
+        .ok_or_else(|| format!("Expected RULE=LEVEL, got '{}'", value))?;
+    Ok((rule.parse()?, level.parse()?))
+}
+
+fn parse_metadata_index(value: &str) -> std::result::Result<(String, MetadataFieldType), String> {
+    let (field, field_type) = value
+        .split_once('=')
+        .ok_or_else(|| format!("Expected FIELD=TYPE, got '{}'", value))?;
+    Ok((field.to_string(), field_type.parse()?))
+}
+
+/// Parses a prompt version given as `v3` or `3`.
+fn parse_version_arg(value: &str) -> Result<u32> {
+    parse_prompt_version(value).with_context(|| format!("Invalid prompt version: {}", value))
+}
+
+async fn list_prompts(args: &PromptArgs) -> Result<()> {
+    let registry = open_prompt_registry(args).await?;
+    let store = registry.store();
+    let names = store.names().await.with_context(|| "Failed to list prompts")?;
+
+    if names.is_empty() {
+        println!("{}", "No prompts in the registry".yellow());
+        return Ok(());
+    }
+
+    for name in names {
+        let versions = store.versions(&name).await?;
+        let promoted = store.promoted(&name).await?;
+        let listed: Vec<String> = versions
+            .iter()
+            .map(|v| {
+                if Some(v.version) == promoted {
+                    format!("v{}*", v.version).green().to_string()
+                } else {
+                    format!("v{}", v.version)
+                }
+            })
+            .collect();
+        println!("{} {}", name.bold(), listed.join(" "));
+        if let Some(description) = versions.last().and_then(|v| v.description.as_deref()) {
+            println!("    {}", description);
+        }
+    }
+    println!("\n{}", "* promoted version".cyan());
+
+    Ok(())
+}
+
+async fn show_prompt(reference: &str, args: &PromptArgs) -> Result<()> {
+    let reference: PromptRef = reference.parse()?;
+    let prompt = open_prompt_registry(args)
+        .await?
+        .resolve(&reference)
+        .await
+        .with_context(|| format!("Failed to load prompt: {}", reference))?;
+
+    println!("{}", serde_yaml::to_string(&prompt)?);
+    Ok(())
+}
+
+async fn add_prompt(
+    name: &str,
+    file: &Path,
+    system: Option<String>,
+    description: Option<String>,
+    promote: bool,
+    args: &PromptArgs,
+) -> Result<()> {
+    let template = fs::read_to_string(file)
+        .with_context(|| format!("Failed to read prompt file: {}", file.display()))?;
+    name.parse::<PromptRef>()?;
+
+    let registry = open_prompt_registry(args).await?;
+    let prompt = registry
+        .publish(name, template, system, description)
+        .await
+        .with_context(|| format!("Failed to add prompt: {}", name))?;
+    println!("{} {}@v{}", "✓ Added".green().bold(), prompt.name, prompt.version);
+
+    if promote {
+        registry.promote(name, prompt.version).await?;
+        println!("{} {}@v{}", "✓ Promoted".green().bold(), prompt.name, prompt.version);
+    }
+
+    Ok(())
+}
+
+async fn diff_prompt(name: &str, from: &str, to: &str, args: &PromptArgs) -> Result<()> {
+    let registry = open_prompt_registry(args).await?;
+    let (from, to) = (parse_version_arg(from)?, parse_version_arg(to)?);
+    let versions = registry.store().versions(name).await?;
+    let find = |version: u32| {
+        versions
+            .iter()
+            .find(|v| v.version == version)
+            .with_context(|| format!("Prompt {} version {} not found", name, version))
+    };
+    let (old, new) = (find(from)?, find(to)?);
+
+    println!("{}", format!("--- {}@v{}", name, from).red());
+    println!("{}", format!("+++ {}@v{}", name, to).green());
+    if old.system != new.system {
+        println!("{}", format!("- system: {}", old.system.as_deref().unwrap_or("")).red());
+        println!("{}", format!("+ system: {}", new.system.as_deref().unwrap_or("")).green());
+    }
+    for line in diff_lines(&old.template, &new.template) {
+        match line {
+            DiffLine::Same(_) => println!("{}", line),
+            DiffLine::Removed(_) => println!("{}", line.to_string().red()),
+            DiffLine::Added(_) => println!("{}", line.to_string().green()),
+        }
+    }
+
+    Ok(())
+}
+
+async fn promote_prompt(name: &str, version: &str, args: &PromptArgs) -> Result<()> {
+    let version = parse_version_arg(version)?;
+    open_prompt_registry(args)
+        .await?
+        .promote(name, version)
+        .await
+        .with_context(|| format!("Failed to promote {}@v{}", name, version))?;
+
+    println!("{} {}@v{}", "✓ Promoted".green().bold(), name, version);
+    Ok(())
+}
+
+/// Creates the LLM providers configured through environment variables.
+fn providers_from_env() -> HashMap<String, Arc<dyn LLMProvider>> {
+    let mut providers: HashMap<String, Arc<dyn LLMProvider>> = HashMap::new();
+
+    // Try to create OpenAI provider from environment
+    if let Ok(openai) = OpenAIProvider::from_env() {
+        info!("Registered OpenAI provider");
+        providers.insert("openai".to_string(), Arc::new(openai));
+    } else {
+        info!("OpenAI provider not available (OPENAI_API_KEY not set)");
+    }
+
+    // Try to create Anthropic provider from environment
+    if let Ok(anthropic) = AnthropicProvider::from_env() {
+        info!("Registered Anthropic provider");
+        providers.insert("anthropic".to_string(), Arc::new(anthropic));
+    } else {
+        info!("Anthropic provider not available (ANTHROPIC_API_KEY not set)");
+    }
+
+    providers
+}
+
+fn parse_input(input_str: &str) -> Result<HashMap<String, Value>> {
+    // Check if input is a file path
+    if Path::new(input_str).exists() {
+        let content = fs::read_to_string(input_str)
+            .with_context(|| format!("Failed to read input file: {}", input_str))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse input JSON from file: {}", input_str))
+    } else {
+        // Try to parse as JSON string
+        serde_json::from_str(input_str)
             .with_context(|| "Failed to parse input JSON string")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_document() {
+        let document = serde_json::json!({
+            "status": "partial",
+            "failed_steps": ["broken"],
+            "results": {"ok": {"text": "rust", "duration": 0}, "skipped": {}},
+            "manifest": null,
+        });
+        let mut text = String::new();
+        text_document(&document, 0, &mut text);
+        assert_eq!(
+            text,
+            "failed_steps:\n  - broken\nmanifest: -\nresults:\n  ok:\n    duration: 0\n    text: rust\n  skipped: (none)\nstatus: partial\n"
+        );
+    }
+}
@@ -0,0 +1,546 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Serve mode: starts runs from webhook, message, and chat triggers.
+//!
+//! `POST /hooks/{name}` verifies the request signature, maps the JSON
+//! payload into inputs of the workflow declaring the trigger, and queues a
+//! run for `worker` processes to pick up. Each `kafka` and `nats` trigger is
+//! consumed alongside, queueing a run per message.
+//!
+//! `POST /v1/chat/completions` speaks the OpenAI chat completions API: the
+//! requested model names a `chat` trigger, whose workflow is run in this
+//! process and answered with the rendered reply. `GET /v1/models` lists the
+//! served model names.
+//!
+//! `GET /health/live` answers as long as the process runs; `GET /health/ready`
+//! (also `/health`) checks the database, secret store, and chat providers and
+//! answers `503` when one of the stores is unhealthy.
+//!
+//! `GET /metrics` exports Prometheus metrics.
+//!
+//! Chat requests are [authenticated](crate::auth) and need the `submit`
+//! scope; their runs belong to the tenant of the credentials. `/v1/models`
+//! and `/metrics` need the `read` scope; `--public-metrics` leaves `/metrics`
+//! open to scrapers without credentials. Webhooks are verified by their signatures
+//! and the health probes stay open.
+//!
+//! `--max-concurrent-runs` caps the chat runs executed at once and
+//! `--max-queued-runs` the runs waiting in the work queue; requests over
+//! either limit are refused with `429` and `Retry-After`.
+//!
+//! With `--grpc-bind`, the [gRPC API](crate::grpc) is served alongside on its
+//! own address, authenticating callers the same way.
+
+use crate::auth::{self, Guard};
+use crate::service::{RunService, ServiceError};
+use crate::{connect_database, database_kind, grpc, shutdown_signal, Audit, AuthArgs, BrokerArgs, ExecutorSetup, MemoryArgs, Notifiers, PromptArgs, RecoveryArgs, WorkflowSource};
+use anyhow::{Context, Result};
+use axum::body::Bytes;
+use axum::extract::{Path, State};
+use axum::http::header::RETRY_AFTER;
+use axum::http::{HeaderMap, HeaderName, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Extension, Json, Router};
+use colored::Colorize;
+use llm_orchestrator_auth::{AuthContext, Permission};
+use llm_orchestrator_core::gateway::{self, ChatCompletionRequest, ChatTrigger};
+use llm_orchestrator_core::messaging::EventSourceRunner;
+use llm_orchestrator_core::worker::SubmitOptions;
+use llm_orchestrator_core::{
+    metrics, AdmissionController, AdmissionLimits, CancelHandle, HealthCheckResult, HealthRegistry, McpClient, Rejection,
+    Trigger, WebhookError, WebhookTrigger, Workflow, WorkflowExecutor,
+};
+use llm_orchestrator_secrets::{EnvSecretStore, SecretStore};
+use llm_orchestrator_state::{DeadLetterQueue, StateStore};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_stream::wrappers::TcpListenerStream;
+use tracing::{error, info, warn};
+
+/// A served webhook and the workflow it starts.
+struct Hook {
+    trigger: WebhookTrigger,
+    workflow: Workflow,
+}
+
+/// A served chat model and the workflow answering it.
+struct ChatModel {
+    trigger: ChatTrigger,
+    workflow: Workflow,
+}
+
+/// State shared by the request handlers.
+struct ServeState {
+    hooks: HashMap<String, Hook>,
+    models: HashMap<String, ChatModel>,
+    service: RunService,
+    admission: Arc<AdmissionController>,
+    state: Arc<dyn StateStore>,
+    dead_letters: Arc<dyn DeadLetterQueue>,
+    secrets: Arc<dyn SecretStore>,
+    // Only set up when chat models are served
+    setup: Option<ExecutorSetup>,
+    health: HealthRegistry,
+}
+
+/// Serve the triggers of the given workflows until interrupted.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn serve(
+    files: &[String],
+    registry: bool,
+    bind: SocketAddr,
+    grpc_bind: Option<SocketAddr>,
+    database_url: &str,
+    drain_timeout: Duration,
+    admission: AdmissionLimits,
+    auth_args: &AuthArgs,
+    recovery: &RecoveryArgs,
+    audit: Option<Audit>,
+    prompts: &PromptArgs,
+    brokers: &BrokerArgs,
+    memory: &MemoryArgs,
+    notifiers: &Notifiers,
+    mcp_servers: &[(String, Arc<McpClient>)],
+) -> Result<()> {
+    let database = connect_database(database_url).await?;
+    recovery.recover(&database).await?;
+
+    let mut workflows = Vec::new();
+    for file in files {
+        workflows.push(WorkflowSource::File(file).load().await?);
+    }
+    if registry {
+        // Definitions are listed oldest version first, so the newest version of each name wins
+        let mut latest = HashMap::new();
+        for definition in database
+            .workflows
+            .list_workflow_definitions(false)
+            .await
+            .with_context(|| "Failed to list published workflows")?
+        {
+            latest.insert(definition.name.clone(), definition);
+        }
+        for definition in latest.into_values() {
+            if !definition.verify_checksum() {
+                warn!(workflow = %definition.name, version = %definition.version, "Skipping workflow that does not match its checksum");
+                continue;
+            }
+            let workflow = Workflow::from_yaml(&definition.definition).with_context(|| {
+                format!("Failed to parse workflow YAML: {}@{}", definition.name, definition.version)
+            })?;
+            workflows.push(workflow);
+        }
+    }
+
+    let mut hooks = HashMap::new();
+    let mut models = HashMap::new();
+    let mut consumers = tokio::task::JoinSet::new();
+    for workflow in workflows {
+        workflow
+            .validate()
+            .with_context(|| format!("Workflow validation failed: {}", workflow.name))?;
+        for trigger in &workflow.triggers {
+            match trigger {
+                Trigger::Webhook(webhook) => {
+                    let hook = Hook {
+                        trigger: webhook.clone(),
+                        workflow: workflow.clone(),
+                    };
+                    if let Some(other) = hooks.insert(webhook.name.clone(), hook) {
+                        anyhow::bail!(
+                            "Webhook '{}' is declared by both {} and {}",
+                            webhook.name,
+                            other.workflow.name,
+                            workflow.name
+                        );
+                    }
+                }
+                Trigger::Kafka(topic) | Trigger::Nats(topic) => {
+                    let group = topic.group.as_deref().unwrap_or(&workflow.name);
+                    let source = brokers
+                        .source(trigger, group)
+                        .await
+                        .with_context(|| format!("Failed to subscribe to {}", topic.topic))?;
+                    println!("  {} {} -> {}", "Consuming".cyan(), topic.topic, workflow.name);
+                    let runner = EventSourceRunner::new(source, database.queue.clone(), workflow.clone(), topic.clone());
+                    let name = topic.topic.clone();
+                    consumers.spawn(async move { (name, runner.run().await) });
+                }
+                Trigger::Chat(chat) => {
+                    let model = ChatModel {
+                        trigger: chat.clone(),
+                        workflow: workflow.clone(),
+                    };
+                    if let Some(other) = models.insert(chat.model.clone(), model) {
+                        anyhow::bail!(
+                            "Chat model '{}' is declared by both {} and {}",
+                            chat.model,
+                            other.workflow.name,
+                            workflow.name
+                        );
+                    }
+                }
+            }
+        }
+    }
+    if hooks.is_empty() && models.is_empty() && consumers.is_empty() && grpc_bind.is_none() {
+        anyhow::bail!("No triggers to serve");
+    }
+
+    // Chat requests are answered by runs in this process
+    let setup = if models.is_empty() {
+        None
+    } else {
+        let setup = ExecutorSetup::new(audit.clone(), prompts, brokers, memory, notifiers, mcp_servers).await?;
+        if setup.providers.is_empty() {
+            anyhow::bail!(
+                "No LLM providers available. Please set OPENAI_API_KEY or ANTHROPIC_API_KEY environment variable."
+            );
+        }
+        Some(setup)
+    };
+
+    let mut names: Vec<_> = hooks.iter().map(|(name, hook)| (name.clone(), hook.workflow.name.clone())).collect();
+    names.sort();
+    let mut model_names: Vec<_> = models
+        .iter()
+        .map(|(name, model)| (name.clone(), model.workflow.name.clone()))
+        .collect();
+    model_names.sort();
+    let secrets: Arc<dyn SecretStore> = Arc::new(EnvSecretStore::new());
+    let mut health = HealthRegistry::new();
+    health.register_state_store(database_kind(database_url), database.state.clone());
+    health.register_secret_store("env", secrets.clone());
+    for (name, provider) in setup.iter().flat_map(|setup| &setup.providers) {
+        health.register_provider(name, provider.clone());
+    }
+    let admission = Arc::new(AdmissionController::new(admission));
+    let authenticator = Arc::new(auth::authenticator(secrets.as_ref(), auth_args, audit.as_ref()).await?);
+    let service = RunService::new(&database, audit).with_admission(admission.clone());
+    let state = Arc::new(ServeState {
+        hooks,
+        models,
+        service: service.clone(),
+        admission,
+        state: database.state,
+        dead_letters: database.dead_letters,
+        secrets,
+        setup,
+        health,
+    });
+    let guard = |permission| axum::middleware::from_fn_with_state(Guard::new(authenticator.clone(), permission), auth::require);
+    let mut metrics_route = get(export_metrics);
+    if !auth_args.public_metrics {
+        metrics_route = metrics_route.route_layer(guard(Permission::ExecutionRead));
+    }
+    let app = Router::new()
+        .route("/hooks/:name", post(receive_hook))
+        .route("/v1/chat/completions", post(chat_completions).route_layer(guard(Permission::WorkflowExecute)))
+        .route("/v1/models", get(list_models).route_layer(guard(Permission::WorkflowRead)))
+        .route("/health", get(readiness))
+        .route("/health/ready", get(readiness))
+        .route("/health/live", get(liveness))
+        .route("/metrics", metrics_route)
+        .with_state(state);
+
+    let listener = tokio::net::TcpListener::bind(bind)
+        .await
+        .with_context(|| format!("Failed to listen on {}", bind))?;
+    println!("{} http://{}", "Serving on".cyan().bold(), bind);
+    for (name, workflow) in names {
+        println!("  POST /hooks/{} -> {}", name, workflow);
+    }
+    for (model, workflow) in model_names {
+        println!("  POST /v1/chat/completions (model {}) -> {}", model, workflow);
+    }
+    println!("  GET  /health/live, /health/ready, /metrics");
+
+    // Stop accepting connections on SIGINT or SIGTERM and let in-flight requests finish
+    let stopping = CancelHandle::default();
+    let grpc_server = match grpc_bind {
+        Some(grpc_bind) => {
+            let routes = grpc::router(service, authenticator, stopping.clone())?;
+            let listener = tokio::net::TcpListener::bind(grpc_bind)
+                .await
+                .with_context(|| format!("Failed to listen on {}", grpc_bind))?;
+            println!("{} grpc://{}", "Serving gRPC on".cyan().bold(), grpc_bind);
+            println!("  llm_orchestrator.v1.Orchestrator, grpc.reflection.v1.ServerReflection");
+            let stop = stopping.clone();
+            Some(
+                tonic::transport::Server::builder()
+                    .add_routes(routes)
+                    .serve_with_incoming_shutdown(TcpListenerStream::new(listener), async move { stop.cancelled().await }),
+            )
+        }
+        None => None,
+    };
+    let stop = stopping.clone();
+    let server = axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).with_graceful_shutdown(async move {
+        shutdown_signal().await;
+        println!("{} waiting up to {}s for in-flight requests", "Server shutting down:".cyan(), drain_timeout.as_secs());
+        stop.cancel();
+    });
+    let grpc_server = async {
+        match grpc_server {
+            Some(server) => server.await.with_context(|| "gRPC server stopped"),
+            None => Ok(()),
+        }
+    };
+    tokio::select! {
+        result = async { tokio::try_join!(async { server.await.with_context(|| "Server stopped") }, grpc_server) } => result.map(|_| ()),
+        _ = async {
+            stopping.cancelled().await;
+            tokio::time::sleep(drain_timeout).await;
+        } => anyhow::bail!("In-flight requests did not finish within {}s", drain_timeout.as_secs()),
+        // Unacknowledged messages are redelivered once the consumer restarts
+        Some(finished) = consumers.join_next() => {
+            let (topic, result) = finished.with_context(|| "Consumer panicked")?;
+            result.with_context(|| format!("Consumer of {} stopped", topic))?;
+            anyhow::bail!("Consumer of {} stopped: the subscription ended", topic)
+        }
+    }
+}
+
+/// Why a webhook request was refused.
+enum HookError {
+    /// Refused with `status` and a JSON error message.
+    Refused(StatusCode, String),
+    /// An admission limit was reached.
+    Saturated(Rejection),
+}
+
+impl IntoResponse for HookError {
+    fn into_response(self) -> Response {
+        match self {
+            Self::Refused(status, message) => (status, Json(json!({ "error": message }))).into_response(),
+            Self::Saturated(rejection) => (
+                StatusCode::TOO_MANY_REQUESTS,
+                retry_after(&rejection),
+                Json(json!({ "error": rejection.to_string() })),
+            )
+                .into_response(),
+        }
+    }
+}
+
+/// The `Retry-After` header of a refused request, in seconds.
+fn retry_after(rejection: &Rejection) -> [(HeaderName, String); 1] {
+    [(RETRY_AFTER, rejection.retry_after.as_secs().to_string())]
+}
+
+impl From<WebhookError> for HookError {
+    fn from(error: WebhookError) -> Self {
+        let status = match error {
+            WebhookError::InvalidSignature(_) => StatusCode::UNAUTHORIZED,
+            WebhookError::InvalidPayload(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        };
+        Self::Refused(status, error.to_string())
+    }
+}
+
+/// Verify, map, and enqueue a webhook request.
+async fn receive_hook(
+    State(state): State<Arc<ServeState>>,
+    Path(name): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<(StatusCode, Json<Value>), HookError> {
+    let hook = state
+        .hooks
+        .get(&name)
+        .ok_or_else(|| HookError::Refused(StatusCode::NOT_FOUND, format!("Unknown webhook: {}", name)))?;
+
+    let secret = state.secrets.get_secret(&hook.trigger.secret).await.map_err(|e| {
+        error!(webhook = %name, "Failed to load webhook secret: {}", e);
+        HookError::Refused(StatusCode::INTERNAL_SERVER_ERROR, "Webhook secret is unavailable".to_string())
+    })?;
+    // HeaderMap names are already lowercase
+    let headers: HashMap<String, String> = headers
+        .iter()
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+        .collect();
+    if let Err(e) = hook
+        .trigger
+        .verify(secret.value.as_bytes(), &headers, &body, chrono::Utc::now().timestamp())
+    {
+        warn!(webhook = %name, "Rejected webhook request: {}", e);
+        return Err(e.into());
+    }
+
+    let payload: Value = serde_json::from_slice(&body)
+        .map_err(|e| HookError::Refused(StatusCode::BAD_REQUEST, format!("Payload is not JSON: {}", e)))?;
+    let inputs = hook.trigger.map_inputs(payload, &headers)?;
+
+    let run_id = state
+        .service
+        .start(&hook.workflow, inputs, SubmitOptions::default())
+        .await
+        .map_err(|e| match e {
+            ServiceError::Saturated(rejection) => {
+                warn!(webhook = %name, "Refused webhook request: {}", rejection);
+                HookError::Saturated(rejection)
+            }
+            e => {
+                error!(webhook = %name, "Failed to enqueue run: {}", e);
+                HookError::Refused(StatusCode::INTERNAL_SERVER_ERROR, "Failed to enqueue run".to_string())
+            }
+        })?;
+
+    info!(webhook = %name, %run_id, workflow_name = %hook.workflow.name, "Webhook run submitted");
+    Ok((StatusCode::ACCEPTED, Json(json!({ "run_id": run_id }))))
+}
+
+/// A chat request refused with `status` and an OpenAI-style error.
+struct ChatError {
+    status: StatusCode,
+    message: String,
+    kind: &'static str,
+    code: Option<&'static str>,
+    // Set when an admission limit was reached
+    rejection: Option<Rejection>,
+}
+
+impl ChatError {
+    fn new(status: StatusCode, message: impl Into<String>, kind: &'static str, code: Option<&'static str>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+            kind,
+            code,
+            rejection: None,
+        }
+    }
+
+    fn invalid_request(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, message, "invalid_request_error", None)
+    }
+
+    fn server_error(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, message, "server_error", None)
+    }
+
+    fn saturated(rejection: Rejection) -> Self {
+        Self {
+            rejection: Some(rejection.clone()),
+            ..Self::new(
+                StatusCode::TOO_MANY_REQUESTS,
+                rejection.to_string(),
+                "requests",
+                Some("rate_limit_exceeded"),
+            )
+        }
+    }
+}
+
+impl IntoResponse for ChatError {
+    fn into_response(self) -> Response {
+        let body = gateway::error_body(&self.message, self.kind, self.code);
+        match &self.rejection {
+            Some(rejection) => (self.status, retry_after(rejection), Json(body)).into_response(),
+            None => (self.status, Json(body)).into_response(),
+        }
+    }
+}
+
+/// Answer a chat completion request by running the model's workflow.
+async fn chat_completions(
+    State(state): State<Arc<ServeState>>,
+    Extension(context): Extension<AuthContext>,
+    body: Bytes,
+) -> Result<Response, ChatError> {
+    let request: ChatCompletionRequest = serde_json::from_slice(&body)
+        .map_err(|e| ChatError::invalid_request(format!("Invalid chat completion request: {}", e)))?;
+    let model = state.models.get(&request.model).ok_or_else(|| {
+        ChatError::new(
+            StatusCode::NOT_FOUND,
+            format!("The model '{}' does not exist", request.model),
+            "invalid_request_error",
+            Some("model_not_found"),
+        )
+    })?;
+    // Held until the reply is rendered
+    let _permit = state.admission.try_start_run().map_err(|rejection| {
+        warn!(model = %request.model, "Refused chat request: {}", rejection);
+        ChatError::saturated(rejection)
+    })?;
+
+    let inputs = model
+        .trigger
+        .map_inputs(&request)
+        .map_err(|e| ChatError::invalid_request(e.to_string()))?;
+    let setup = state.setup.as_ref().expect("executors are set up when chat models are served");
+    let mut executor = WorkflowExecutor::new(model.workflow.clone(), inputs.clone())
+        .map_err(|e| ChatError::server_error(e.to_string()))?
+        .with_state_store(state.state.clone())
+        .with_dead_letter_queue(state.dead_letters.clone());
+    // Runs belong to the tenant of the credentials, as runs started over gRPC do
+    if let Some(tenant_id) = context.tenant_id {
+        executor = executor.with_tenant_id(tenant_id);
+    }
+    let executor = setup.apply(executor);
+    let run_id = executor.run_id();
+    info!(model = %request.model, %run_id, workflow_name = %model.workflow.name, "Chat run started");
+
+    let results = executor.execute().await.map_err(|e| {
+        warn!(model = %request.model, %run_id, "Chat run failed: {}", e);
+        ChatError::server_error(format!("Run {} failed: {}", run_id, e))
+    })?;
+    let reply = model
+        .trigger
+        .render_reply(inputs, &results)
+        .map_err(|e| ChatError::server_error(format!("Failed to render reply of run {}: {}", run_id, e)))?;
+
+    let usage = executor.usage();
+    let created = chrono::Utc::now().timestamp();
+    if !request.stream {
+        let completion = gateway::chat_completion(run_id, &request.model, &reply, &usage, created);
+        return Ok(Json(completion).into_response());
+    }
+    let mut events = String::new();
+    for chunk in gateway::chat_completion_chunks(run_id, &request.model, &reply, &usage, created) {
+        events.push_str(&format!("data: {}\n\n", chunk));
+    }
+    events.push_str("data: [DONE]\n\n");
+    Ok(([("content-type", "text/event-stream"), ("cache-control", "no-cache")], events).into_response())
+}
+
+/// List the served chat models.
+async fn list_models(State(state): State<Arc<ServeState>>) -> Json<Value> {
+    let mut names: Vec<&String> = state.models.keys().collect();
+    names.sort();
+    let data: Vec<Value> = names
+        .into_iter()
+        .map(|name| json!({"id": name, "object": "model", "created": 0, "owned_by": "llm-orchestrator"}))
+        .collect();
+    Json(json!({"object": "list", "data": data}))
+}
+
+/// Report whether the stores and providers are reachable.
+async fn readiness(State(state): State<Arc<ServeState>>) -> (StatusCode, Json<HealthCheckResult>) {
+    let result = state.health.readiness().await;
+    let status = if result.is_ready() {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(result))
+}
+
+/// Report that the server is running.
+async fn liveness(State(state): State<Arc<ServeState>>) -> Json<HealthCheckResult> {
+    Json(state.health.liveness())
+}
+
+/// Export Prometheus metrics, refreshing the length of the work queue.
+async fn export_metrics(State(state): State<Arc<ServeState>>) -> Response {
+    match state.service.queued_runs().await {
+        Ok(queued) => state.admission.observe_queue(queued),
+        Err(e) => warn!("Failed to refresh queue length: {}", e),
+    }
+    ([("content-type", "text/plain; version=0.0.4")], metrics::gather_metrics()).into_response()
+}
@@ -0,0 +1,267 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Operations on published workflows and queued runs.
+//!
+//! The CLI commands, the webhook triggers of `serve`, and the gRPC API all go
+//! through [`RunService`], so a run is submitted, audited, and cancelled the
+//! same way whichever interface started it.
+
+use crate::{Audit, Database};
+use llm_orchestrator_audit::AuditEventType;
+use llm_orchestrator_core::worker::{submit_run_with, SubmitOptions};
+use llm_orchestrator_core::{AdmissionController, Rejection, Workflow};
+use llm_orchestrator_state::{
+    QueueTask, QueueTaskStatus, StateStore, StateStoreError, WorkQueue, WorkflowDefinition, WorkflowDefinitionStore,
+    WorkflowState, WorkflowStatus,
+};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Why an operation was refused.
+#[derive(Debug)]
+pub(crate) enum ServiceError {
+    /// The workflow or run does not exist.
+    NotFound(String),
+    /// The request conflicts with the current state (e.g. cancelling a finished run).
+    Conflict(String),
+    /// An admission limit was reached; the client should retry later.
+    Saturated(Rejection),
+    /// A store failed.
+    Internal(String),
+}
+
+impl std::fmt::Display for ServiceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotFound(message) | Self::Conflict(message) | Self::Internal(message) => {
+                f.write_str(message)
+            }
+            Self::Saturated(rejection) => write!(f, "{}", rejection),
+        }
+    }
+}
+
+impl std::error::Error for ServiceError {}
+
+impl ServiceError {
+    /// Wraps a store error, keeping missing records apart from failures.
+    fn store(context: impl std::fmt::Display, error: StateStoreError) -> Self {
+        match error {
+            StateStoreError::NotFound(_) => Self::NotFound(format!("{}: {}", context, error)),
+            _ => Self::Internal(format!("{}: {}", context, error)),
+        }
+    }
+}
+
+pub(crate) type ServiceResult<T> = std::result::Result<T, ServiceError>;
+
+/// A run as recorded by the work queue and the state store.
+///
+/// Queued runs have no state yet; runs started by `run` were never queued.
+pub(crate) struct RunRecord {
+    pub(crate) id: Uuid,
+    pub(crate) task: Option<QueueTask>,
+    pub(crate) state: Option<WorkflowState>,
+}
+
+impl RunRecord {
+    /// Status of the run; `Pending` while it waits for a worker.
+    pub(crate) fn status(&self) -> WorkflowStatus {
+        match (&self.state, &self.task) {
+            (Some(state), _) => state.status.clone(),
+            (None, Some(task)) => match task.status {
+                QueueTaskStatus::Queued => WorkflowStatus::Pending,
+                QueueTaskStatus::Running => WorkflowStatus::Running,
+                QueueTaskStatus::Completed => WorkflowStatus::Completed,
+                QueueTaskStatus::Failed => WorkflowStatus::Failed,
+                QueueTaskStatus::Cancelled => WorkflowStatus::Cancelled,
+            },
+            (None, None) => WorkflowStatus::Pending,
+        }
+    }
+
+    /// Error the run failed with, if any.
+    pub(crate) fn error(&self) -> Option<&str> {
+        self.state
+            .as_ref()
+            .and_then(|state| state.error.as_deref())
+            .or_else(|| self.task.as_ref().and_then(|task| task.error.as_deref()))
+    }
+
+    /// Tenant the run belongs to, if any.
+    pub(crate) fn tenant(&self) -> Option<&str> {
+        self.task
+            .as_ref()
+            .and_then(|task| task.tenant_id.as_deref())
+            .or_else(|| self.state.as_ref().and_then(|state| state.tenant_id.as_deref()))
+    }
+
+    /// Whether the run can no longer change.
+    pub(crate) fn is_finished(&self) -> bool {
+        matches!(
+            self.status(),
+            WorkflowStatus::Completed | WorkflowStatus::Failed | WorkflowStatus::Cancelled
+        )
+    }
+}
+
+/// Publishes workflows and submits, inspects, and cancels runs.
+#[derive(Clone)]
+pub(crate) struct RunService {
+    queue: Arc<dyn WorkQueue>,
+    state: Arc<dyn StateStore>,
+    workflows: Arc<dyn WorkflowDefinitionStore>,
+    audit: Option<Audit>,
+    admission: Option<Arc<AdmissionController>>,
+}
+
+impl RunService {
+    pub(crate) fn new(database: &Database, audit: Option<Audit>) -> Self {
+        Self {
+            queue: database.queue.clone(),
+            state: database.state.clone(),
+            workflows: database.workflows.clone(),
+            audit,
+            admission: None,
+        }
+    }
+
+    /// Refuses new runs while the work queue is at the controller's limit.
+    pub(crate) fn with_admission(mut self, admission: Arc<AdmissionController>) -> Self {
+        self.admission = Some(admission);
+        self
+    }
+
+    /// Number of runs waiting in the work queue.
+    pub(crate) async fn queued_runs(&self) -> ServiceResult<u64> {
+        self.queue
+            .count_tasks(QueueTaskStatus::Queued)
+            .await
+            .map_err(|e| ServiceError::Internal(format!("Failed to count queued runs: {}", e)))
+    }
+
+    /// Publishes a workflow definition. Returns `false` when the version is
+    /// already published with identical content, so deploy scripts can run
+    /// repeatedly.
+    pub(crate) async fn publish(&self, definition: &WorkflowDefinition) -> ServiceResult<bool> {
+        match self.workflows.publish_workflow_definition(definition).await {
+            Ok(()) => {}
+            Err(StateStoreError::InvalidState(_)) => {
+                let existing = self
+                    .workflows
+                    .get_workflow_definition(&definition.name, Some(&definition.version))
+                    .await
+                    .map_err(|e| ServiceError::store("Failed to load published workflow", e))?;
+                if existing.checksum != definition.checksum {
+                    return Err(ServiceError::Conflict(format!(
+                        "Workflow {}@{} is already published with different content; bump its version",
+                        definition.name, definition.version
+                    )));
+                }
+                return Ok(false);
+            }
+            Err(e) => return Err(ServiceError::Internal(format!("Failed to publish workflow: {}", e))),
+        }
+
+        if let Some(audit) = &self.audit {
+            audit
+                .log_registry_change(AuditEventType::WorkflowCreate, "Workflow published", definition)
+                .await;
+        }
+        Ok(true)
+    }
+
+    /// Loads a published workflow definition, verifying it was not modified
+    /// since it was published. The latest version is loaded without `version`.
+    pub(crate) async fn definition(&self, name: &str, version: Option<&str>) -> ServiceResult<WorkflowDefinition> {
+        let reference = match version {
+            Some(version) => format!("{}@{}", name, version),
+            None => name.to_string(),
+        };
+        let definition = self
+            .workflows
+            .get_workflow_definition(name, version)
+            .await
+            .map_err(|e| ServiceError::store(format_args!("Failed to load workflow {}", reference), e))?;
+
+        if !definition.verify_checksum() {
+            return Err(ServiceError::Internal(format!(
+                "Workflow {}@{} does not match its checksum {}",
+                definition.name, definition.version, definition.checksum
+            )));
+        }
+        Ok(definition)
+    }
+
+    /// Loads and parses a published workflow that is not deprecated.
+    pub(crate) async fn published_workflow(&self, name: &str, version: Option<&str>) -> ServiceResult<Workflow> {
+        let definition = self.definition(name, version).await?;
+        if definition.is_deprecated() {
+            return Err(ServiceError::Conflict(format!(
+                "Workflow {}@{} is deprecated",
+                definition.name, definition.version
+            )));
+        }
+        Workflow::from_yaml(&definition.definition).map_err(|e| {
+            ServiceError::Internal(format!(
+                "Failed to parse workflow YAML {}@{}: {}",
+                definition.name, definition.version, e
+            ))
+        })
+    }
+
+    /// Queues a run of `workflow` for a worker. Returns the run ID, or
+    /// `Saturated` when the work queue is at its admission limit.
+    pub(crate) async fn start(
+        &self,
+        workflow: &Workflow,
+        inputs: HashMap<String, Value>,
+        options: SubmitOptions,
+    ) -> ServiceResult<Uuid> {
+        if let Some(admission) = &self.admission {
+            let queued = self.queued_runs().await?;
+            admission.check_queue(queued).map_err(ServiceError::Saturated)?;
+        }
+        let run_id = submit_run_with(self.queue.as_ref(), workflow, inputs, options)
+            .await
+            .map_err(|e| ServiceError::Internal(format!("Failed to submit workflow run: {}", e)))?;
+        if let Some(audit) = &self.audit {
+            audit.log_submission(workflow, run_id).await;
+        }
+        Ok(run_id)
+    }
+
+    /// Loads a run from the work queue and the state store.
+    pub(crate) async fn run(&self, run_id: Uuid) -> ServiceResult<RunRecord> {
+        let task = match self.queue.get_task(&run_id).await {
+            Ok(task) => Some(task),
+            Err(StateStoreError::NotFound(_)) => None,
+            Err(e) => return Err(ServiceError::Internal(format!("Failed to load run {}: {}", run_id, e))),
+        };
+        let state = match self.state.load_workflow_state(&run_id).await {
+            Ok(state) => Some(state),
+            Err(StateStoreError::NotFound(_)) => None,
+            Err(e) => return Err(ServiceError::Internal(format!("Failed to load run {}: {}", run_id, e))),
+        };
+        if task.is_none() && state.is_none() {
+            return Err(ServiceError::NotFound(format!("Run {} not found", run_id)));
+        }
+        Ok(RunRecord { id: run_id, task, state })
+    }
+
+    /// Cancels a queued or running run.
+    pub(crate) async fn cancel(&self, run_id: Uuid) -> ServiceResult<()> {
+        let cancelled = self
+            .queue
+            .cancel_task(&run_id)
+            .await
+            .map_err(|e| ServiceError::store(format_args!("Failed to cancel run {}", run_id), e))?;
+        if !cancelled {
+            return Err(ServiceError::Conflict(format!("Run {} is not queued or running", run_id)));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,67 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Exit codes of the CLI, which scripts rely on to tell failures apart.
+
+use std::path::Path;
+use std::process::Command;
+
+/// Runs the CLI in `dir` without provider keys, returning its exit code.
+fn exit_code(dir: &Path, args: &[&str]) -> i32 {
+    let output = Command::new(env!("CARGO_BIN_EXE_llm-orchestrator"))
+        .args(args)
+        .current_dir(dir)
+        .env_remove("OPENAI_API_KEY")
+        .env_remove("ANTHROPIC_API_KEY")
+        .output()
+        .unwrap();
+    output.status.code().unwrap()
+}
+
+/// Writes a workflow whose `broken` step fails at run time, continuing with
+/// `on_failure`, and an empty mock file so no provider is needed.
+fn workflow(dir: &Path, on_failure: &str) {
+    let yaml = format!(
+        "name: exit-codes\nsteps:\n  \
+         - {{id: ok, type: transform, function: normalize, inputs: [topic]}}\n  \
+         - {{id: broken, type: transform, function: normalize, inputs: [doc], bind: {{doc: inputs.missing}}, on_failure: {}}}\n",
+        on_failure
+    );
+    std::fs::write(dir.join("workflow.yaml"), yaml).unwrap();
+    std::fs::write(dir.join("mocks.yaml"), "{}\n").unwrap();
+}
+
+const RUN: &[&str] = &["--quiet", "run", "workflow.yaml", "--mock", "mocks.yaml", "--input", r#"{"topic": "rust"}"#];
+
+#[test]
+fn test_valid_workflow_exits_0() {
+    let dir = tempfile::tempdir().unwrap();
+    workflow(dir.path(), "continue");
+    assert_eq!(exit_code(dir.path(), &["validate", "workflow.yaml", "--format", "json"]), 0);
+    // `table` is the human format, as for `report`; `text` is an alias
+    assert_eq!(exit_code(dir.path(), &["validate", "workflow.yaml", "--format", "table"]), 0);
+    assert_eq!(exit_code(dir.path(), &["validate", "workflow.yaml", "--format", "text"]), 0);
+}
+
+#[test]
+fn test_invalid_workflow_exits_3() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("workflow.yaml"), "name: invalid\nsteps:\n  - {id: a, type: transfrom}\n").unwrap();
+    assert_eq!(exit_code(dir.path(), &["validate", "workflow.yaml"]), 3);
+    assert_eq!(exit_code(dir.path(), &["validate", "workflow.yaml", "--format", "table"]), 3);
+    assert_eq!(exit_code(dir.path(), &["validate", "workflow.yaml", "--format", "json"]), 3);
+}
+
+#[test]
+fn test_failed_run_exits_4() {
+    let dir = tempfile::tempdir().unwrap();
+    workflow(dir.path(), "fail_workflow");
+    assert_eq!(exit_code(dir.path(), RUN), 4);
+}
+
+#[test]
+fn test_partially_failed_run_exits_5() {
+    let dir = tempfile::tempdir().unwrap();
+    workflow(dir.path(), "continue");
+    assert_eq!(exit_code(dir.path(), RUN), 5);
+}
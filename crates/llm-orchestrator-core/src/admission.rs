@@ -0,0 +1,221 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Admission control for runs started by a server.
+//!
+//! An [`AdmissionController`] caps how many runs an instance executes at once
+//! and how many runs may wait in the work queue. Requests over a limit are
+//! refused with a [`Rejection`] carrying a retry delay, which servers turn
+//! into `429 Too Many Requests` with `Retry-After`, so load beyond capacity
+//! is pushed back to clients instead of piling up in memory.
+//!
+//! Usage of each limit is exported as the `orchestrator_admission_saturation`
+//! gauge, the queue length as `orchestrator_queue_length`, and refusals as
+//! `orchestrator_admission_rejections_total`.
+
+use crate::metrics;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Limits enforced by an [`AdmissionController`]. `None` means unlimited.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdmissionLimits {
+    /// Runs executed in this process at once.
+    pub max_concurrent_runs: Option<usize>,
+    /// Runs waiting in the work queue before new ones are refused.
+    pub max_queued_runs: Option<u64>,
+    /// Delay suggested to refused clients.
+    pub retry_after: Duration,
+}
+
+impl Default for AdmissionLimits {
+    fn default() -> Self {
+        Self {
+            max_concurrent_runs: None,
+            max_queued_runs: None,
+            retry_after: Duration::from_secs(5),
+        }
+    }
+}
+
+/// The limit a request was refused by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdmissionLimit {
+    ConcurrentRuns,
+    QueuedRuns,
+}
+
+impl AdmissionLimit {
+    /// Label of the limit in metrics.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ConcurrentRuns => "concurrent_runs",
+            Self::QueuedRuns => "queued_runs",
+        }
+    }
+}
+
+/// A request refused because an admission limit was reached.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rejection {
+    pub limit: AdmissionLimit,
+    /// The configured value of the limit.
+    pub max: u64,
+    /// How long the client should wait before retrying.
+    pub retry_after: Duration,
+}
+
+impl fmt::Display for Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.limit {
+            AdmissionLimit::ConcurrentRuns => write!(f, "Too many concurrent runs (limit {})", self.max),
+            AdmissionLimit::QueuedRuns => write!(f, "Too many queued runs (limit {})", self.max),
+        }?;
+        write!(f, "; retry after {}s", self.retry_after.as_secs())
+    }
+}
+
+impl std::error::Error for Rejection {}
+
+/// Admits or refuses runs against [`AdmissionLimits`].
+#[derive(Debug)]
+pub struct AdmissionController {
+    limits: AdmissionLimits,
+    running: Arc<AtomicUsize>,
+}
+
+impl AdmissionController {
+    pub fn new(limits: AdmissionLimits) -> Self {
+        Self {
+            limits,
+            running: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    pub fn limits(&self) -> &AdmissionLimits {
+        &self.limits
+    }
+
+    /// Number of runs currently admitted by [`try_start_run`](Self::try_start_run).
+    pub fn running(&self) -> usize {
+        self.running.load(Ordering::SeqCst)
+    }
+
+    /// Admits a run to execute in this process. The run counts against
+    /// `max_concurrent_runs` until the returned permit is dropped.
+    pub fn try_start_run(&self) -> Result<RunPermit, Rejection> {
+        let max = self.limits.max_concurrent_runs;
+        let admitted = self.running.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |running| match max {
+            Some(max) if running >= max => None,
+            _ => Some(running + 1),
+        });
+        match admitted {
+            Ok(running) => {
+                record_concurrent_runs(running + 1, max);
+                Ok(RunPermit {
+                    running: self.running.clone(),
+                    max,
+                })
+            }
+            Err(_) => Err(self.reject(AdmissionLimit::ConcurrentRuns, max.unwrap_or_default() as u64)),
+        }
+    }
+
+    /// Admits a run into a work queue currently holding `queued` runs.
+    pub fn check_queue(&self, queued: u64) -> Result<(), Rejection> {
+        self.observe_queue(queued);
+        match self.limits.max_queued_runs {
+            Some(max) if queued >= max => Err(self.reject(AdmissionLimit::QueuedRuns, max)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Updates the queue length and saturation gauges without admitting anything.
+    pub fn observe_queue(&self, queued: u64) {
+        metrics::QUEUE_LENGTH.set(queued as f64);
+        if let Some(max) = self.limits.max_queued_runs {
+            metrics::record_admission_usage(AdmissionLimit::QueuedRuns.as_str(), queued, max);
+        }
+    }
+
+    fn reject(&self, limit: AdmissionLimit, max: u64) -> Rejection {
+        metrics::record_admission_rejection(limit.as_str());
+        Rejection {
+            limit,
+            max,
+            retry_after: self.limits.retry_after,
+        }
+    }
+}
+
+fn record_concurrent_runs(running: usize, max: Option<usize>) {
+    if let Some(max) = max {
+        metrics::record_admission_usage(AdmissionLimit::ConcurrentRuns.as_str(), running as u64, max as u64);
+    }
+}
+
+/// A run admitted by [`AdmissionController::try_start_run`]; releases its
+/// slot when dropped.
+#[derive(Debug)]
+pub struct RunPermit {
+    running: Arc<AtomicUsize>,
+    max: Option<usize>,
+}
+
+impl Drop for RunPermit {
+    fn drop(&mut self) {
+        let running = self.running.fetch_sub(1, Ordering::SeqCst) - 1;
+        record_concurrent_runs(running, self.max);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn controller(max_concurrent_runs: Option<usize>, max_queued_runs: Option<u64>) -> AdmissionController {
+        AdmissionController::new(AdmissionLimits {
+            max_concurrent_runs,
+            max_queued_runs,
+            retry_after: Duration::from_secs(3),
+        })
+    }
+
+    #[test]
+    fn test_concurrent_runs_are_limited_until_permits_drop() {
+        let admission = controller(Some(2), None);
+        let first = admission.try_start_run().unwrap();
+        let _second = admission.try_start_run().unwrap();
+        assert_eq!(admission.running(), 2);
+
+        let rejection = admission.try_start_run().unwrap_err();
+        assert_eq!(rejection.limit, AdmissionLimit::ConcurrentRuns);
+        assert_eq!(rejection.max, 2);
+        assert_eq!(rejection.retry_after, Duration::from_secs(3));
+        assert_eq!(rejection.to_string(), "Too many concurrent runs (limit 2); retry after 3s");
+
+        drop(first);
+        assert_eq!(admission.running(), 1);
+        assert!(admission.try_start_run().is_ok());
+    }
+
+    #[test]
+    fn test_queued_runs_are_limited() {
+        let admission = controller(None, Some(10));
+        assert!(admission.check_queue(9).is_ok());
+
+        let rejection = admission.check_queue(10).unwrap_err();
+        assert_eq!(rejection.limit, AdmissionLimit::QueuedRuns);
+        assert_eq!(rejection.max, 10);
+    }
+
+    #[test]
+    fn test_no_limits_admit_everything() {
+        let admission = AdmissionController::new(AdmissionLimits::default());
+        let permits: Vec<_> = (0..100).map(|_| admission.try_start_run().unwrap()).collect();
+        assert_eq!(admission.running(), permits.len());
+        assert!(admission.check_queue(u64::MAX).is_ok());
+    }
+}
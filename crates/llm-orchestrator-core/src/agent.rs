@@ -0,0 +1,447 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Agent steps.
+//!
+//! An `agent` step hands a task and a set of tools to a tool-calling model,
+//! runs the tools the model calls, and sends back what they returned, until
+//! the model answers without calling a tool:
+//!
+//! ```yaml
+//! - id: triage
+//!   type: agent
+//!   provider: openai
+//!   model: gpt-4o
+//!   prompt: "Find issues related to {{ inputs.report }} and label the duplicates."
+//!   tools:
+//!     - mcp: github                     # All tools of a registered MCP server
+//!       tools: [search_issues, add_label]
+//!     - action: notify.slack            # A registered action handler
+//!       name: notify
+//!       description: "Post a message to the triage channel"
+//!       parameters:
+//!         type: object
+//!         properties:
+//!           text: { type: string }
+//!         required: [text]
+//!   max_iterations: 8                   # Default 10
+//!   output: [answer, trajectory, stop_reason]
+//! ```
+//!
+//! Each model request is one iteration. The loop stops with `stop_reason`
+//! `answer` once the model answers, or `max_iterations` when it is still
+//! calling tools at the limit; the step then succeeds with the last text the
+//! model sent, so an `assert` on `stop_reason` can turn that into a failure.
+//! A tool that fails, or that the model made up, is reported back to the
+//! model as the observation, so it can try something else.
+//!
+//! The `trajectory` lists every iteration with the model's text and, for
+//! each tool call, its arguments and observation, for auditing what the
+//! agent did.
+
+use crate::deterministic::SentRequest;
+use crate::error::{OrchestratorError, Result};
+use crate::executor::WorkflowExecutor;
+use crate::mcp::McpClient;
+use crate::metrics;
+use crate::providers::{provider_error, CompletionRequest, CompletionResponse, ConversationTurn, LLMProvider, ToolCall, ToolSpec};
+use crate::usage::completion_usage;
+use crate::workflow::{AgentStepConfig, AgentTool, Step, StepConfig};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{debug, info};
+
+/// Default output names, in the order outputs are assigned.
+const DEFAULT_OUTPUTS: [&str; 3] = ["answer", "trajectory", "stop_reason"];
+
+/// Name a model calls an action tool by.
+pub(crate) fn action_tool_name(action: &str, name: Option<&str>) -> String {
+    match name {
+        Some(name) => name.to_string(),
+        None => action
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '_' || c == '-' { c } else { '_' })
+            .collect(),
+    }
+}
+
+/// What runs a tool the model calls.
+enum ToolTarget {
+    Action(String),
+    Mcp(Arc<McpClient>),
+}
+
+impl WorkflowExecutor {
+    /// Executes an agent step.
+    pub(crate) async fn execute_agent_step(&self, step: &Step) -> Result<HashMap<String, Value>> {
+        let config = match &step.config {
+            StepConfig::Agent(config) => config,
+            _ => {
+                return Err(OrchestratorError::InvalidStepConfig {
+                    step_id: step.id.clone(),
+                    reason: "Expected Agent step config".to_string(),
+                })
+            }
+        };
+        let provider = self
+            .providers
+            .get(&config.provider)
+            .map(|provider| provider.value().clone())
+            .ok_or_else(|| OrchestratorError::other(format!("Provider '{}' not registered", config.provider)))?;
+        let (specs, targets) = self.agent_tools(step, config).await?;
+
+        self.resolve_template_secrets(&config.prompt).await?;
+        let task = self.context.render_template(&config.prompt)?;
+        let system = match &config.system {
+            Some(template) => {
+                self.resolve_template_secrets(template).await?;
+                Some(self.context.render_template(template)?)
+            }
+            None => None,
+        };
+
+        let mut history = vec![ConversationTurn::new("user", task)];
+        let mut trajectory = Vec::new();
+        let mut answer = String::new();
+        let mut stop_reason = "max_iterations";
+        for iteration in 1..=config.max_iterations {
+            let response = self
+                .agent_completion(step, config, provider.as_ref(), system.clone(), &history, &specs)
+                .await?;
+            answer = response.text.clone();
+            if response.tool_calls.is_empty() {
+                trajectory.push(json!({"iteration": iteration, "text": response.text}));
+                stop_reason = "answer";
+                break;
+            }
+
+            history.push(ConversationTurn {
+                tool_calls: response.tool_calls.clone(),
+                ..ConversationTurn::new("assistant", response.text.clone())
+            });
+            let mut calls = Vec::new();
+            for call in &response.tool_calls {
+                let (observation, is_error) = match self.call_agent_tool(step, &targets, call).await {
+                    Ok(observation) => (observation, false),
+                    Err(e) => (self.redact(e.to_string()), true),
+                };
+                calls.push(json!({
+                    "tool": call.name,
+                    "arguments": call.arguments,
+                    "observation": observation,
+                    "error": is_error,
+                }));
+                history.push(ConversationTurn::tool_result(&call.id, observation));
+            }
+            trajectory.push(json!({"iteration": iteration, "text": response.text, "tool_calls": calls}));
+        }
+        info!(step_id = %step.id, iterations = trajectory.len(), stop_reason, "Agent completed");
+
+        let names: Vec<&str> = if step.output.is_empty() {
+            DEFAULT_OUTPUTS.to_vec()
+        } else {
+            step.output.iter().map(String::as_str).collect()
+        };
+        let values = [Value::String(answer), Value::Array(trajectory), json!(stop_reason)];
+        Ok(names.into_iter().map(String::from).zip(values).collect())
+    }
+
+    /// The tools offered to the model, and what runs each of them.
+    async fn agent_tools(
+        &self,
+        step: &Step,
+        config: &AgentStepConfig,
+    ) -> Result<(Vec<ToolSpec>, HashMap<String, ToolTarget>)> {
+        let invalid = |reason: String| OrchestratorError::InvalidStepConfig {
+            step_id: step.id.clone(),
+            reason,
+        };
+        let mut specs = Vec::new();
+        let mut targets = HashMap::new();
+        let mut offer = |spec: ToolSpec, target: ToolTarget| {
+            if targets.insert(spec.name.clone(), target).is_some() {
+                return Err(invalid(format!("Two tools are named '{}'", spec.name)));
+            }
+            specs.push(spec);
+            Ok(())
+        };
+
+        for tool in &config.tools {
+            match tool {
+                AgentTool::Action { action, name, description, parameters } => {
+                    if !self.action_handlers.contains_key(action) {
+                        return Err(invalid(format!("Action '{}' has no registered handler", action)));
+                    }
+                    let spec = ToolSpec {
+                        name: action_tool_name(action, name.as_deref()),
+                        description: description.clone(),
+                        parameters: parameters.clone().unwrap_or_else(|| json!({"type": "object"})),
+                    };
+                    offer(spec, ToolTarget::Action(action.clone()))?;
+                }
+                AgentTool::Mcp { mcp, tools } => {
+                    let client = self
+                        .mcp_servers
+                        .get(mcp)
+                        .map(|client| client.value().clone())
+                        .ok_or_else(|| invalid(format!("MCP server '{}' is not registered", mcp)))?;
+                    let definitions = client.tools().await.map_err(|e| OrchestratorError::ExecutionError {
+                        step_id: step.id.clone(),
+                        source: Box::new(e),
+                    })?;
+                    if let Some(missing) = tools.iter().find(|name| !definitions.iter().any(|d| &d.name == *name)) {
+                        return Err(invalid(format!("MCP server '{}' has no tool '{}'", mcp, missing)));
+                    }
+                    for definition in definitions {
+                        if !tools.is_empty() && !tools.contains(&definition.name) {
+                            continue;
+                        }
+                        let spec = ToolSpec {
+                            name: definition.name.clone(),
+                            description: definition.description.clone().unwrap_or_default(),
+                            parameters: definition.input_schema.clone(),
+                        };
+                        offer(spec, ToolTarget::Mcp(client.clone()))?;
+                    }
+                }
+            }
+        }
+        Ok((specs, targets))
+    }
+
+    /// Runs a tool the model called, returning the observation sent back to it.
+    async fn call_agent_tool(
+        &self,
+        step: &Step,
+        targets: &HashMap<String, ToolTarget>,
+        call: &ToolCall,
+    ) -> Result<String> {
+        debug!(step_id = %step.id, tool = %call.name, "Calling agent tool");
+        match targets.get(&call.name) {
+            Some(ToolTarget::Action(action)) => {
+                let handler = self
+                    .action_handlers
+                    .get(action)
+                    .map(|handler| handler.value().clone())
+                    .ok_or_else(|| OrchestratorError::other(format!("Action '{}' has no registered handler", action)))?;
+                let outputs = handler.execute(&step.id, call.arguments.clone()).await?;
+                Ok(serde_json::to_string(&outputs)?)
+            }
+            Some(ToolTarget::Mcp(client)) => {
+                let result = client
+                    .call_tool(&call.name, call.arguments.clone())
+                    .await
+                    .map_err(|e| OrchestratorError::other(e.to_string()))?;
+                Ok(match (result.text(), result.structured_content) {
+                    (text, Some(structured)) if text.is_empty() => structured.to_string(),
+                    (text, _) => text,
+                })
+            }
+            None => Err(OrchestratorError::other(format!("Unknown tool '{}'", call.name))),
+        }
+    }
+
+    /// Sends one iteration of the conversation to the model.
+    async fn agent_completion(
+        &self,
+        step: &Step,
+        config: &AgentStepConfig,
+        provider: &dyn LLMProvider,
+        system: Option<String>,
+        history: &[ConversationTurn],
+        tools: &[ToolSpec],
+    ) -> Result<CompletionResponse> {
+        let mut request = CompletionRequest {
+            model: config.model.clone(),
+            prompt: String::new(),
+            system,
+            history: history.to_vec(),
+            images: Vec::new(),
+            temperature: config.temperature,
+            max_tokens: config.max_tokens,
+            tools: tools.to_vec(),
+            extra: HashMap::new(),
+            headers: HashMap::new(),
+        };
+        self.pin_sampling(&mut request);
+        let sent = self.is_deterministic().then(|| SentRequest::of(&request));
+
+        self.record_request(&step.id, &request);
+        #[cfg(feature = "audit")]
+        let audit_prompt = history.last().map(|turn| turn.content.clone()).unwrap_or_default();
+        let start = std::time::Instant::now();
+        let response_result = provider.complete(request).await;
+        let duration = start.elapsed().as_secs_f64();
+        self.record_provider_latency(&step.id, start.elapsed());
+
+        #[cfg(feature = "audit")]
+        self.audit_provider_call(crate::audit::ProviderCall {
+            step_id: &step.id,
+            provider: &config.provider,
+            model: &config.model,
+            prompt: &audit_prompt,
+            completion: response_result.as_ref().ok().map(|r| r.text.as_str()),
+            tokens_used: response_result.as_ref().ok().and_then(|r| r.tokens_used),
+            error: response_result.as_ref().err().map(|e| e.to_string()),
+            duration: start.elapsed(),
+        })
+        .await;
+
+        metrics::record_llm_request(&config.provider, &config.model, duration, response_result.is_ok(), None, None);
+        self.usage
+            .record_completion(&config.provider, &config.model, response_result.as_ref().ok());
+        if let (Some(sent), Ok(response)) = (sent, &response_result) {
+            self.record_manifest(&step.id, &config.provider, sent, response);
+        }
+        if let Ok(response) = &response_result {
+            self.record_usage(&step.id, &completion_usage(response));
+        }
+        response_result.map_err(|e| provider_error(&config.provider, "Provider error", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::StepStatus;
+    use crate::notify::ActionHandler;
+    use crate::providers::ProviderError;
+    use crate::workflow::Workflow;
+    use async_trait::async_trait;
+    use parking_lot::Mutex;
+
+    /// Looks up the weather of a city named in the task, then answers with it.
+    #[derive(Default)]
+    struct WeatherAgent {
+        requests: Mutex<Vec<CompletionRequest>>,
+    }
+
+    #[async_trait]
+    impl LLMProvider for WeatherAgent {
+        async fn complete(&self, request: CompletionRequest) -> std::result::Result<CompletionResponse, ProviderError> {
+            self.requests.lock().push(request.clone());
+            let last = request.history.last().unwrap();
+            let (text, tool_calls) = match last.role.as_str() {
+                "user" if last.content.contains("Lisbon") => (
+                    "Checking the forecast.".to_string(),
+                    vec![
+                        ToolCall {
+                            id: "call_1".to_string(),
+                            name: "forecast".to_string(),
+                            arguments: json!({"city": "Lisbon"}),
+                        },
+                        ToolCall {
+                            id: "call_2".to_string(),
+                            name: "almanac".to_string(),
+                            arguments: json!({}),
+                        },
+                    ],
+                ),
+                "user" => ("Nothing to look up.".to_string(), Vec::new()),
+                _ => (format!("It is {}.", last.content), Vec::new()),
+            };
+            Ok(CompletionResponse {
+                text,
+                model: "m".to_string(),
+                tokens_used: None,
+                usage: None,
+                tool_calls,
+                metadata: HashMap::new(),
+            })
+        }
+
+        fn name(&self) -> &str {
+            "agent"
+        }
+    }
+
+    struct Forecast;
+
+    #[async_trait]
+    impl ActionHandler for Forecast {
+        async fn execute(&self, _step_id: &str, params: Value) -> Result<HashMap<String, Value>> {
+            Ok(HashMap::from([("sky".to_string(), json!(format!("sunny in {}", params["city"].as_str().unwrap())))]))
+        }
+    }
+
+    fn workflow(max_iterations: u32) -> Workflow {
+        Workflow::from_yaml(&format!(
+            r#"
+name: "weather"
+steps:
+  - id: "agent"
+    type: "agent"
+    provider: "agent"
+    model: "m"
+    prompt: "What is the weather in {{{{ inputs.city }}}}?"
+    tools:
+      - action: "weather.forecast"
+        name: "forecast"
+        description: "Forecast for a city"
+    max_iterations: {}
+"#,
+            max_iterations
+        ))
+        .unwrap()
+    }
+
+    async fn run(max_iterations: u32, city: &str) -> (HashMap<String, Value>, Arc<WeatherAgent>) {
+        let provider = Arc::new(WeatherAgent::default());
+        let inputs = HashMap::from([("city".to_string(), json!(city))]);
+        let results = WorkflowExecutor::new(workflow(max_iterations), inputs)
+            .unwrap()
+            .with_provider("agent", provider.clone())
+            .with_action_handler("weather.forecast", Arc::new(Forecast))
+            .execute()
+            .await
+            .unwrap();
+        let result = &results["agent"];
+        assert_eq!(result.status, StepStatus::Completed);
+        (result.outputs.clone(), provider)
+    }
+
+    #[tokio::test]
+    async fn test_agent_calls_tools_until_it_answers() {
+        let (outputs, provider) = run(10, "Lisbon").await;
+        assert_eq!(outputs["answer"], json!("It is Unknown tool 'almanac'."));
+        assert_eq!(outputs["stop_reason"], json!("answer"));
+
+        let trajectory = outputs["trajectory"].as_array().unwrap();
+        assert_eq!(trajectory.len(), 2);
+        let calls = trajectory[0]["tool_calls"].as_array().unwrap();
+        assert_eq!(calls[0]["arguments"], json!({"city": "Lisbon"}));
+        assert_eq!(calls[0]["observation"], json!(r#"{"sky":"sunny in Lisbon"}"#));
+        assert_eq!(calls[0]["error"], json!(false));
+        assert_eq!(calls[1]["error"], json!(true));
+
+        // The second request carries the calls and their results
+        let requests = provider.requests.lock();
+        assert_eq!(requests[0].tools.len(), 1);
+        assert_eq!(requests[0].tools[0].name, "forecast");
+        let history = &requests[1].history;
+        assert_eq!(history.len(), 4);
+        assert_eq!(history[1].tool_calls.len(), 2);
+        assert_eq!(history[2].tool_call_id.as_deref(), Some("call_1"));
+        assert_eq!(history[2].content, r#"{"sky":"sunny in Lisbon"}"#);
+    }
+
+    #[tokio::test]
+    async fn test_agent_stops_at_max_iterations() {
+        let (outputs, provider) = run(1, "Lisbon").await;
+        assert_eq!(outputs["stop_reason"], json!("max_iterations"));
+        assert_eq!(outputs["answer"], json!("Checking the forecast."));
+        assert_eq!(provider.requests.lock().len(), 1);
+
+        let (outputs, _) = run(1, "Oslo").await;
+        assert_eq!(outputs["stop_reason"], json!("answer"));
+        assert_eq!(outputs["answer"], json!("Nothing to look up."));
+    }
+
+    #[test]
+    fn test_action_tool_names() {
+        assert_eq!(action_tool_name("memory.read", None), "memory_read");
+        assert_eq!(action_tool_name("memory.read", Some("recall")), "recall");
+        assert!(workflow(0).diagnostics().iter().any(|d| d.code == crate::diagnostics::DiagnosticCode::InvalidAgent));
+    }
+}
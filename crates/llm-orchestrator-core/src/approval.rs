@@ -0,0 +1,197 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Human-in-the-loop approval gates.
+//!
+//! An approval step pauses its branch of the workflow until a reviewer submits
+//! an [`ApprovalDecision`] through [`WorkflowExecutor::submit_approval`](crate::WorkflowExecutor::submit_approval).
+//! While waiting, the request (including the rendered payload to review) is
+//! visible through [`WorkflowExecutor::pending_approvals`](crate::WorkflowExecutor::pending_approvals).
+
+use crate::error::{OrchestratorError, Result};
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use tokio::sync::oneshot;
+
+/// A decision waiting for a reviewer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalRequest {
+    /// ID of the approval step.
+    pub step_id: String,
+    /// Instructions for the reviewer.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// Rendered content to review.
+    pub payload: String,
+    /// When the approval was requested.
+    pub requested_at: DateTime<Utc>,
+}
+
+/// A reviewer's answer to an approval request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApprovalDecision {
+    /// Whether the request was approved.
+    pub approved: bool,
+    /// Who made the decision.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reviewer: Option<String>,
+    /// Free-form reviewer comment.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+}
+
+impl ApprovalDecision {
+    /// Create an approving decision.
+    pub fn approve() -> Self {
+        Self {
+            approved: true,
+            reviewer: None,
+            comment: None,
+        }
+    }
+
+    /// Create a rejecting decision.
+    pub fn reject() -> Self {
+        Self {
+            approved: false,
+            reviewer: None,
+            comment: None,
+        }
+    }
+
+    /// Set the reviewer.
+    pub fn with_reviewer(mut self, reviewer: impl Into<String>) -> Self {
+        self.reviewer = Some(reviewer.into());
+        self
+    }
+
+    /// Set the reviewer comment.
+    pub fn with_comment(mut self, comment: impl Into<String>) -> Self {
+        self.comment = Some(comment.into());
+        self
+    }
+}
+
+/// Pending approval with the channel used to resume the waiting step.
+struct PendingApproval {
+    request: ApprovalRequest,
+    responder: oneshot::Sender<ApprovalDecision>,
+}
+
+/// Registry of approval requests awaiting a decision.
+#[derive(Default)]
+pub(crate) struct ApprovalRegistry {
+    pending: DashMap<String, PendingApproval>,
+}
+
+impl ApprovalRegistry {
+    /// Register a request and return a waiter that resolves with the decision.
+    pub(crate) fn request(&self, request: ApprovalRequest) -> ApprovalWaiter<'_> {
+        let (responder, receiver) = oneshot::channel();
+        let step_id = request.step_id.clone();
+        self.pending.insert(
+            step_id.clone(),
+            PendingApproval { request, responder },
+        );
+        ApprovalWaiter {
+            registry: self,
+            step_id,
+            receiver,
+        }
+    }
+
+    /// List all pending requests.
+    pub(crate) fn pending(&self) -> Vec<ApprovalRequest> {
+        self.pending
+            .iter()
+            .map(|entry| entry.value().request.clone())
+            .collect()
+    }
+
+    /// Returns true if any request is waiting for a decision.
+    pub(crate) fn has_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Deliver a decision to the step waiting on it.
+    pub(crate) fn submit(&self, step_id: &str, decision: ApprovalDecision) -> Result<()> {
+        let (_, pending) = self.pending.remove(step_id).ok_or_else(|| {
+            OrchestratorError::other(format!("No pending approval for step '{}'", step_id))
+        })?;
+
+        pending.responder.send(decision).map_err(|_| {
+            OrchestratorError::other(format!("Approval step '{}' is no longer waiting", step_id))
+        })
+    }
+}
+
+/// Waits for the decision on one approval request.
+///
+/// Dropping the waiter (e.g. when the step times out) withdraws the request.
+pub(crate) struct ApprovalWaiter<'a> {
+    registry: &'a ApprovalRegistry,
+    step_id: String,
+    receiver: oneshot::Receiver<ApprovalDecision>,
+}
+
+impl ApprovalWaiter<'_> {
+    /// Wait for a reviewer to submit a decision.
+    pub(crate) async fn wait(mut self) -> Result<ApprovalDecision> {
+        (&mut self.receiver).await.map_err(|_| {
+            OrchestratorError::other(format!("Approval request for step '{}' was withdrawn", self.step_id))
+        })
+    }
+}
+
+impl Drop for ApprovalWaiter<'_> {
+    fn drop(&mut self) {
+        self.registry.pending.remove(&self.step_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(step_id: &str) -> ApprovalRequest {
+        ApprovalRequest {
+            step_id: step_id.to_string(),
+            message: None,
+            payload: "draft".to_string(),
+            requested_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_submit_resolves_waiting_receiver() {
+        let registry = ApprovalRegistry::default();
+        let waiter = registry.request(request("review"));
+        assert!(registry.has_pending());
+        assert_eq!(registry.pending()[0].payload, "draft");
+
+        registry
+            .submit("review", ApprovalDecision::reject().with_comment("needs work"))
+            .unwrap();
+
+        let decision = waiter.wait().await.unwrap();
+        assert!(!decision.approved);
+        assert_eq!(decision.comment.as_deref(), Some("needs work"));
+        assert!(!registry.has_pending());
+    }
+
+    #[test]
+    fn test_dropping_waiter_withdraws_request() {
+        let registry = ApprovalRegistry::default();
+        let waiter = registry.request(request("review"));
+        drop(waiter);
+        assert!(!registry.has_pending());
+        assert!(registry.submit("review", ApprovalDecision::approve()).is_err());
+    }
+
+    #[test]
+    fn test_submit_unknown_step_fails() {
+        let registry = ApprovalRegistry::default();
+        assert!(registry.submit("missing", ApprovalDecision::approve()).is_err());
+    }
+}
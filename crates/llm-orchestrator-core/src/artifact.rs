@@ -0,0 +1,329 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Artifact stores.
+//!
+//! Steps that produce files, such as synthesized audio, store them with
+//! [`WorkflowExecutor::store_artifact`] and put the returned URI in their
+//! outputs. Each run's artifacts are listed by [`WorkflowExecutor::artifacts`]
+//! and saved with its persisted state.
+//!
+//! Artifacts go to a local directory or an S3-compatible bucket (feature
+//! `s3`), configured in the workflow's `artifacts` section:
+//!
+//! ```yaml
+//! artifacts:
+//!   type: s3
+//!   bucket: pipeline-artifacts
+//!   prefix: voice-assistant
+//! ```
+//!
+//! or registered with [`WorkflowExecutor::with_artifact_store`]. Without
+//! either, they are written under `<temp dir>/llm-orchestrator/artifacts`.
+//! Each artifact is keyed `<run id>/<step id>/<name>`.
+
+use crate::error::{OrchestratorError, Result};
+use crate::executor::WorkflowExecutor;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use tracing::info;
+
+#[cfg(feature = "s3")]
+pub mod s3;
+
+#[cfg(feature = "s3")]
+pub use s3::S3ArtifactStore;
+
+/// A file stored by a step.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Artifact {
+    /// Step that stored the artifact.
+    pub step_id: String,
+    /// Name the step gave the artifact.
+    pub name: String,
+    /// Where the artifact is stored, e.g. `file:///tmp/…` or `s3://bucket/key`.
+    pub uri: String,
+    /// Size in bytes.
+    pub size: usize,
+    /// MIME type, from the name's extension.
+    pub content_type: String,
+    /// When the artifact was stored.
+    pub created_at: DateTime<Utc>,
+}
+
+/// Stores the files steps produce.
+#[async_trait]
+pub trait ArtifactStore: Send + Sync {
+    /// Stores `bytes` under `key`, returning the artifact's URI.
+    async fn put(&self, key: &str, bytes: Vec<u8>, content_type: &str) -> Result<String>;
+
+    /// Reads the artifact at a URI this store returned.
+    async fn get(&self, uri: &str) -> Result<Vec<u8>>;
+}
+
+/// Where a workflow's artifacts are stored.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ArtifactStoreConfig {
+    /// A local directory.
+    Local(LocalArtifactConfig),
+    /// An S3-compatible bucket (feature `s3`).
+    S3(S3ArtifactConfig),
+}
+
+/// A local artifact directory.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct LocalArtifactConfig {
+    /// Directory artifacts are written under; created when missing.
+    pub dir: String,
+}
+
+/// An S3 artifact bucket.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct S3ArtifactConfig {
+    /// Bucket name.
+    pub bucket: String,
+
+    /// Prefix of the object keys.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+
+    /// Endpoint of an S3-compatible service (MinIO, Ceph, R2), addressed path-style.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+
+    /// Region, by default from the environment.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+}
+
+impl ArtifactStoreConfig {
+    /// Checks that the store names a directory or bucket.
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            Self::Local(local) if local.dir.is_empty() => {
+                Err(OrchestratorError::validation("Local artifact store needs a dir"))
+            }
+            Self::S3(s3) if s3.bucket.is_empty() => Err(OrchestratorError::validation("S3 artifact store needs a bucket")),
+            _ => Ok(()),
+        }
+    }
+
+    /// Creates the store.
+    pub async fn build(&self) -> Result<Arc<dyn ArtifactStore>> {
+        match self {
+            Self::Local(local) => Ok(Arc::new(LocalArtifactStore::new(&local.dir)?)),
+            #[cfg(feature = "s3")]
+            Self::S3(s3) => Ok(Arc::new(S3ArtifactStore::new(s3.clone()).await)),
+            #[cfg(not(feature = "s3"))]
+            Self::S3(_) => Err(OrchestratorError::other("S3 artifact stores require the `s3` feature")),
+        }
+    }
+}
+
+impl FromStr for ArtifactStoreConfig {
+    type Err = String;
+
+    /// Parses `s3://BUCKET[/PREFIX]` or a directory path.
+    fn from_str(spec: &str) -> std::result::Result<Self, Self::Err> {
+        if let Some(location) = spec.strip_prefix("s3://") {
+            let (bucket, prefix) = location.split_once('/').unwrap_or((location, ""));
+            if bucket.is_empty() {
+                return Err(format!("Expected s3://BUCKET[/PREFIX], got '{}'", spec));
+            }
+            return Ok(Self::S3(S3ArtifactConfig {
+                bucket: bucket.to_string(),
+                prefix: (!prefix.is_empty()).then(|| prefix.to_string()),
+                endpoint: None,
+                region: None,
+            }));
+        }
+        if spec.is_empty() {
+            return Err("Artifact directory is empty".to_string());
+        }
+        Ok(Self::Local(LocalArtifactConfig { dir: spec.to_string() }))
+    }
+}
+
+/// Stores artifacts as files under a directory, with `file://` URIs.
+pub struct LocalArtifactStore {
+    root: PathBuf,
+}
+
+impl LocalArtifactStore {
+    /// Creates a store writing under `root`, relative to the working directory if not absolute.
+    pub fn new(root: impl AsRef<Path>) -> Result<Self> {
+        let root = std::env::current_dir()?.join(root);
+        Ok(Self { root })
+    }
+
+    /// The store used when none is configured: `<temp dir>/llm-orchestrator/artifacts`.
+    pub fn temporary() -> Self {
+        Self {
+            root: std::env::temp_dir().join("llm-orchestrator").join("artifacts"),
+        }
+    }
+}
+
+#[async_trait]
+impl ArtifactStore for LocalArtifactStore {
+    async fn put(&self, key: &str, bytes: Vec<u8>, _content_type: &str) -> Result<String> {
+        let path = self.root.join(key);
+        let failed = |e: std::io::Error| OrchestratorError::other(format!("Failed to write artifact {}: {}", path.display(), e));
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await.map_err(failed)?;
+        }
+        tokio::fs::write(&path, bytes).await.map_err(failed)?;
+        Ok(format!("file://{}", path.display()))
+    }
+
+    async fn get(&self, uri: &str) -> Result<Vec<u8>> {
+        let path = uri
+            .strip_prefix("file://")
+            .map(Path::new)
+            .filter(|path| path.starts_with(&self.root))
+            .ok_or_else(|| OrchestratorError::other(format!("Artifact '{}' is not in {}", uri, self.root.display())))?;
+        tokio::fs::read(path)
+            .await
+            .map_err(|e| OrchestratorError::other(format!("Failed to read artifact {}: {}", uri, e)))
+    }
+}
+
+/// MIME type of an artifact, from its name's extension.
+pub fn content_type(name: &str) -> &'static str {
+    let extension = Path::new(name).extension().and_then(|e| e.to_str()).unwrap_or_default();
+    match extension.to_ascii_lowercase().as_str() {
+        "mp3" => "audio/mpeg",
+        "wav" => "audio/wav",
+        "opus" | "ogg" => "audio/ogg",
+        "aac" => "audio/aac",
+        "flac" => "audio/flac",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "json" => "application/json",
+        "pdf" => "application/pdf",
+        "csv" => "text/csv",
+        "md" => "text/markdown",
+        "html" => "text/html",
+        "txt" => "text/plain",
+        _ => "application/octet-stream",
+    }
+}
+
+impl WorkflowExecutor {
+    /// Sets the store artifacts are written to, in place of the workflow's
+    /// `artifacts` section.
+    pub fn with_artifact_store(mut self, store: Arc<dyn ArtifactStore>) -> Self {
+        self.artifact_store = Arc::new(tokio::sync::OnceCell::new_with(Some(store)));
+        self
+    }
+
+    /// Artifacts stored so far in this run, in the order they were stored.
+    pub fn artifacts(&self) -> Vec<Artifact> {
+        self.artifacts.lock().clone()
+    }
+
+    /// Stores a file produced by a step, keyed `<run id>/<step id>/<name>`.
+    ///
+    /// The name may contain `/` but no `..` or root components.
+    pub async fn store_artifact(&self, step_id: &str, name: &str, bytes: Vec<u8>) -> Result<Artifact> {
+        let valid = !name.is_empty() && Path::new(name).components().all(|c| matches!(c, Component::Normal(_)));
+        if !valid {
+            return Err(OrchestratorError::InvalidStepConfig {
+                step_id: step_id.to_string(),
+                reason: format!("Invalid artifact name '{}'", name),
+            });
+        }
+        let store = self
+            .artifact_store
+            .get_or_try_init(|| async {
+                match &self.workflow.artifacts {
+                    Some(config) => config.build().await,
+                    None => Ok(Arc::new(LocalArtifactStore::temporary()) as Arc<dyn ArtifactStore>),
+                }
+            })
+            .await?;
+
+        let key = format!("{}/{}/{}", self.run_id, step_id, name);
+        let content_type = content_type(name);
+        let size = bytes.len();
+        let uri = store.put(&key, bytes, content_type).await?;
+        info!(run_id = %self.run_id, step_id = %step_id, uri = %uri, bytes = size, "Stored artifact");
+
+        let artifact = Artifact {
+            step_id: step_id.to_string(),
+            name: name.to_string(),
+            uri,
+            size,
+            content_type: content_type.to_string(),
+            created_at: Utc::now(),
+        };
+        self.artifacts.lock().push(artifact.clone());
+        Ok(artifact)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workflow::Workflow;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_parse_artifact_store_spec() {
+        let config: ArtifactStoreConfig = "s3://artifacts/voice".parse().unwrap();
+        let ArtifactStoreConfig::S3(s3) = config else { panic!("expected an S3 store") };
+        assert_eq!(s3.bucket, "artifacts");
+        assert_eq!(s3.prefix.as_deref(), Some("voice"));
+
+        assert!(matches!("s3://artifacts".parse(), Ok(ArtifactStoreConfig::S3(S3ArtifactConfig { prefix: None, .. }))));
+        assert!(matches!("out/artifacts".parse(), Ok(ArtifactStoreConfig::Local(_))));
+        assert!("s3://".parse::<ArtifactStoreConfig>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_store_artifact_in_configured_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let yaml = format!(
+            r#"
+name: artifacts
+artifacts:
+  type: local
+  dir: "{}"
+steps:
+  - id: noop
+    type: transform
+    function: identity
+    inputs: []
+"#,
+            dir.path().display()
+        );
+        let workflow = Workflow::from_yaml(&yaml).unwrap();
+        let executor = WorkflowExecutor::new(workflow, HashMap::new()).unwrap();
+
+        let artifact = executor.store_artifact("render", "charts/q3.png", b"\x89PNG".to_vec()).await.unwrap();
+        let path = dir.path().join(executor.run_id().to_string()).join("render/charts/q3.png");
+        assert_eq!(artifact.uri, format!("file://{}", path.display()));
+        assert_eq!(artifact.size, 4);
+        assert_eq!(artifact.content_type, "image/png");
+        assert_eq!(std::fs::read(&path).unwrap(), b"\x89PNG");
+        assert_eq!(executor.artifacts(), vec![artifact.clone()]);
+
+        let store = LocalArtifactStore::new(dir.path()).unwrap();
+        assert_eq!(store.get(&artifact.uri).await.unwrap(), b"\x89PNG");
+        assert!(store.get("file:///etc/passwd").await.is_err());
+
+        assert!(executor.store_artifact("render", "../escape.png", Vec::new()).await.is_err());
+        assert!(executor.store_artifact("render", "/abs.png", Vec::new()).await.is_err());
+        assert_eq!(executor.artifacts().len(), 1);
+    }
+}
@@ -0,0 +1,88 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! S3-compatible artifact store (requires the `s3` feature).
+
+use super::{ArtifactStore, S3ArtifactConfig};
+use crate::error::{OrchestratorError, Result};
+use async_trait::async_trait;
+use aws_config::{BehaviorVersion, Region};
+use aws_sdk_s3::error::DisplayErrorContext;
+use aws_sdk_s3::primitives::ByteStream;
+use aws_sdk_s3::Client;
+
+/// Stores artifacts as objects in an S3 bucket, with `s3://` URIs.
+///
+/// Credentials come from the environment; an `endpoint` selects an
+/// S3-compatible service (MinIO, Ceph, R2) with path-style addressing.
+pub struct S3ArtifactStore {
+    client: Client,
+    config: S3ArtifactConfig,
+}
+
+impl S3ArtifactStore {
+    /// Creates a store for `config.bucket`.
+    pub async fn new(config: S3ArtifactConfig) -> Self {
+        let mut loader = aws_config::defaults(BehaviorVersion::latest());
+        if let Some(region) = &config.region {
+            loader = loader.region(Region::new(region.clone()));
+        }
+        let shared = loader.load().await;
+        let mut builder = aws_sdk_s3::config::Builder::from(&shared);
+        if let Some(endpoint) = &config.endpoint {
+            builder = builder.endpoint_url(endpoint).force_path_style(true);
+        }
+        Self::with_client(Client::from_conf(builder.build()), config)
+    }
+
+    /// Creates a store from a configured client.
+    pub fn with_client(client: Client, config: S3ArtifactConfig) -> Self {
+        Self { client, config }
+    }
+
+    /// Object key of an artifact key, under the configured prefix.
+    fn object_key(&self, key: &str) -> String {
+        match self.config.prefix.as_deref().map(|prefix| prefix.trim_end_matches('/')) {
+            Some(prefix) if !prefix.is_empty() => format!("{}/{}", prefix, key),
+            _ => key.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl ArtifactStore for S3ArtifactStore {
+    async fn put(&self, key: &str, bytes: Vec<u8>, content_type: &str) -> Result<String> {
+        let key = self.object_key(key);
+        let uri = format!("s3://{}/{}", self.config.bucket, key);
+        self.client
+            .put_object()
+            .bucket(&self.config.bucket)
+            .key(&key)
+            .content_type(content_type)
+            .body(ByteStream::from(bytes))
+            .send()
+            .await
+            .map_err(|e| OrchestratorError::other(format!("Failed to upload artifact to {}: {}", uri, DisplayErrorContext(e))))?;
+        Ok(uri)
+    }
+
+    async fn get(&self, uri: &str) -> Result<Vec<u8>> {
+        let key = uri
+            .strip_prefix("s3://")
+            .and_then(|location| location.split_once('/'))
+            .filter(|(bucket, _)| *bucket == self.config.bucket)
+            .map(|(_, key)| key)
+            .ok_or_else(|| OrchestratorError::other(format!("Artifact '{}' is not in bucket {}", uri, self.config.bucket)))?;
+        let failed = |e: &dyn std::fmt::Display| OrchestratorError::other(format!("Failed to download artifact {}: {}", uri, e));
+        let object = self
+            .client
+            .get_object()
+            .bucket(&self.config.bucket)
+            .key(key)
+            .send()
+            .await
+            .map_err(|e| failed(&DisplayErrorContext(e)))?;
+        let body = object.body.collect().await.map_err(|e| failed(&e))?;
+        Ok(body.into_bytes().to_vec())
+    }
+}
@@ -0,0 +1,519 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Step output assertions.
+//!
+//! A step's `assert` list holds expressions that must hold for its outputs,
+//! so a provider call that succeeds with a useless answer fails the step:
+//!
+//! ```yaml
+//! - id: search
+//!   type: vector_search
+//!   # ...
+//!   assert:
+//!     - results | length > 0
+//!     - results[0].score >= 0.8
+//! ```
+//!
+//! Like conditions, assertions are rendered as templates first. Names then
+//! resolve against the step's outputs, then `inputs` and `steps`. Expressions
+//! support numbers, quoted strings, `true`, `false`, `null`, paths such as
+//! `results[0].score`, the filters `length`, `lower`, `upper`, and `trim`,
+//! the comparisons `==`, `!=`, `<`, `<=`, `>`, `>=`, and `contains`, and
+//! `not`, `and`, `or`, and parentheses.
+
+use crate::error::{OrchestratorError, Result};
+use crate::executor::WorkflowExecutor;
+use crate::workflow::Step;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+
+/// A parsed assertion expression.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expression {
+    root: Node,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Literal(Value),
+    Path(Path),
+    Filter(Box<Node>, Filter),
+    Compare(Box<Node>, Comparison, Box<Node>),
+    Not(Box<Node>),
+    And(Box<Node>, Box<Node>),
+    Or(Box<Node>, Box<Node>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Path {
+    text: String,
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Filter {
+    Length,
+    Lower,
+    Upper,
+    Trim,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Contains,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Str(String),
+    Word(String),
+    Symbol(&'static str),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Number(n) => write!(f, "{}", n),
+            Self::Str(s) => write!(f, "'{}'", s),
+            Self::Word(w) => f.write_str(w),
+            Self::Symbol(s) => f.write_str(s),
+        }
+    }
+}
+
+const SYMBOLS: [&str; 14] = ["==", "!=", "<=", ">=", "&&", "||", "<", ">", "!", "|", "(", ")", "=", "&"];
+
+fn tokenize(source: &str) -> std::result::Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut rest = source.trim_start();
+    while let Some(c) = rest.chars().next() {
+        if c == '\'' || c == '"' {
+            let end = rest[1..].find(c).ok_or_else(|| format!("Unterminated string starting at {}", rest))?;
+            tokens.push(Token::Str(rest[1..end + 1].to_string()));
+            rest = &rest[end + 2..];
+        } else if c.is_ascii_digit() || (c == '-' && rest[1..].starts_with(|d: char| d.is_ascii_digit())) {
+            let end = rest[1..].find(|d: char| !(d.is_ascii_digit() || d == '.')).map_or(rest.len(), |i| i + 1);
+            let number = rest[..end].parse().map_err(|_| format!("Invalid number '{}'", &rest[..end]))?;
+            tokens.push(Token::Number(number));
+            rest = &rest[end..];
+        } else if c.is_alphabetic() || c == '_' {
+            let end = rest
+                .find(|w: char| !(w.is_alphanumeric() || matches!(w, '_' | '.' | '[' | ']')))
+                .unwrap_or(rest.len());
+            tokens.push(Token::Word(rest[..end].to_string()));
+            rest = &rest[end..];
+        } else {
+            let symbol = SYMBOLS
+                .iter()
+                .find(|symbol| rest.starts_with(**symbol))
+                .ok_or_else(|| format!("Unexpected character '{}'", c))?;
+            if matches!(*symbol, "=" | "&") {
+                return Err(format!("Unexpected '{}'; did you mean '{}{}'?", symbol, symbol, symbol));
+            }
+            tokens.push(Token::Symbol(symbol));
+            rest = &rest[symbol.len()..];
+        }
+        rest = rest.trim_start();
+    }
+    Ok(tokens)
+}
+
+fn parse_path(text: &str) -> std::result::Result<Path, String> {
+    let invalid = || format!("Invalid path '{}'", text);
+    let mut segments = Vec::new();
+    for part in text.split('.') {
+        let (key, mut indexes) = part.split_once('[').map_or((part, ""), |(key, rest)| (key, rest));
+        if key.is_empty() && segments.is_empty() {
+            return Err(invalid());
+        }
+        if !key.is_empty() {
+            segments.push(Segment::Key(key.to_string()));
+        }
+        while !indexes.is_empty() {
+            let (index, rest) = indexes.split_once(']').ok_or_else(invalid)?;
+            segments.push(Segment::Index(index.parse().map_err(|_| invalid())?));
+            indexes = match rest {
+                "" => "",
+                rest => rest.strip_prefix('[').ok_or_else(invalid)?,
+            };
+        }
+    }
+    Ok(Path {
+        text: text.to_string(),
+        segments,
+    })
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    /// Consumes the next token if it is one of `options`.
+    fn accept(&mut self, options: &[&str]) -> bool {
+        let matched = match self.peek() {
+            Some(Token::Symbol(symbol)) => options.contains(symbol),
+            Some(Token::Word(word)) => options.contains(&word.as_str()),
+            _ => false,
+        };
+        if matched {
+            self.position += 1;
+        }
+        matched
+    }
+
+    fn or(&mut self) -> std::result::Result<Node, String> {
+        let mut node = self.and()?;
+        while self.accept(&["or", "||"]) {
+            node = Node::Or(Box::new(node), Box::new(self.and()?));
+        }
+        Ok(node)
+    }
+
+    fn and(&mut self) -> std::result::Result<Node, String> {
+        let mut node = self.not()?;
+        while self.accept(&["and", "&&"]) {
+            node = Node::And(Box::new(node), Box::new(self.not()?));
+        }
+        Ok(node)
+    }
+
+    fn not(&mut self) -> std::result::Result<Node, String> {
+        if self.accept(&["not", "!"]) {
+            return Ok(Node::Not(Box::new(self.not()?)));
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> std::result::Result<Node, String> {
+        let left = self.filtered()?;
+        let comparison = match self.peek() {
+            Some(Token::Symbol("==")) => Comparison::Eq,
+            Some(Token::Symbol("!=")) => Comparison::Ne,
+            Some(Token::Symbol("<")) => Comparison::Lt,
+            Some(Token::Symbol("<=")) => Comparison::Le,
+            Some(Token::Symbol(">")) => Comparison::Gt,
+            Some(Token::Symbol(">=")) => Comparison::Ge,
+            Some(Token::Word(word)) if word == "contains" => Comparison::Contains,
+            _ => return Ok(left),
+        };
+        self.position += 1;
+        Ok(Node::Compare(Box::new(left), comparison, Box::new(self.filtered()?)))
+    }
+
+    fn filtered(&mut self) -> std::result::Result<Node, String> {
+        let mut node = self.primary()?;
+        while self.accept(&["|"]) {
+            let filter = match self.next() {
+                Some(Token::Word(word)) => match word.as_str() {
+                    "length" => Filter::Length,
+                    "lower" => Filter::Lower,
+                    "upper" => Filter::Upper,
+                    "trim" => Filter::Trim,
+                    other => return Err(format!("Unknown filter '{}'; use length, lower, upper, or trim", other)),
+                },
+                _ => return Err("Expected a filter name after '|'".to_string()),
+            };
+            node = Node::Filter(Box::new(node), filter);
+        }
+        Ok(node)
+    }
+
+    fn primary(&mut self) -> std::result::Result<Node, String> {
+        match self.next() {
+            Some(Token::Number(n)) => Ok(Node::Literal(Value::from(n))),
+            Some(Token::Str(s)) => Ok(Node::Literal(Value::String(s))),
+            Some(Token::Word(word)) => match word.as_str() {
+                "true" => Ok(Node::Literal(Value::Bool(true))),
+                "false" => Ok(Node::Literal(Value::Bool(false))),
+                "null" => Ok(Node::Literal(Value::Null)),
+                "and" | "or" | "not" | "contains" => Err(format!("Expected a value before '{}'", word)),
+                _ => parse_path(&word).map(Node::Path),
+            },
+            Some(Token::Symbol("(")) => {
+                let node = self.or()?;
+                if !self.accept(&[")"]) {
+                    return Err("Expected ')'".to_string());
+                }
+                Ok(node)
+            }
+            Some(token) => Err(format!("Unexpected '{}'", token)),
+            None => Err("Unexpected end of expression".to_string()),
+        }
+    }
+}
+
+impl Expression {
+    /// Parses an expression.
+    pub fn parse(source: &str) -> std::result::Result<Self, String> {
+        let mut parser = Parser {
+            tokens: tokenize(source)?,
+            position: 0,
+        };
+        let root = parser.or()?;
+        match parser.peek() {
+            None => Ok(Self { root }),
+            Some(token) => Err(format!("Unexpected '{}'", token)),
+        }
+    }
+
+    /// Evaluates the expression against `scope`; paths missing from it are null.
+    pub fn evaluate(&self, scope: &Value) -> std::result::Result<bool, String> {
+        evaluate(&self.root, scope).map(|value| truthy(&value))
+    }
+
+    /// Values of the paths the expression reads, in order of appearance.
+    pub fn bindings(&self, scope: &Value) -> Vec<(String, Value)> {
+        let mut paths = Vec::new();
+        collect_paths(&self.root, &mut paths);
+        let mut bindings: Vec<(String, Value)> = Vec::new();
+        for path in paths {
+            if !bindings.iter().any(|(text, _)| *text == path.text) {
+                bindings.push((path.text.clone(), lookup(path, scope)));
+            }
+        }
+        bindings
+    }
+}
+
+fn collect_paths<'a>(node: &'a Node, paths: &mut Vec<&'a Path>) {
+    match node {
+        Node::Literal(_) => {}
+        Node::Path(path) => paths.push(path),
+        Node::Filter(node, _) | Node::Not(node) => collect_paths(node, paths),
+        Node::Compare(left, _, right) | Node::And(left, right) | Node::Or(left, right) => {
+            collect_paths(left, paths);
+            collect_paths(right, paths);
+        }
+    }
+}
+
+fn lookup(path: &Path, scope: &Value) -> Value {
+    let mut value = scope;
+    for segment in &path.segments {
+        let next = match segment {
+            Segment::Key(key) => value.get(key),
+            Segment::Index(index) => value.get(index),
+        };
+        match next {
+            Some(next) => value = next,
+            None => return Value::Null,
+        }
+    }
+    value.clone()
+}
+
+fn truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64() != Some(0.0),
+        Value::String(s) => !s.is_empty(),
+        Value::Array(a) => !a.is_empty(),
+        Value::Object(o) => !o.is_empty(),
+    }
+}
+
+fn evaluate(node: &Node, scope: &Value) -> std::result::Result<Value, String> {
+    Ok(match node {
+        Node::Literal(value) => value.clone(),
+        Node::Path(path) => lookup(path, scope),
+        Node::Filter(node, filter) => apply(*filter, evaluate(node, scope)?)?,
+        Node::Compare(left, comparison, right) => {
+            Value::Bool(compare(&evaluate(left, scope)?, *comparison, &evaluate(right, scope)?))
+        }
+        Node::Not(node) => Value::Bool(!truthy(&evaluate(node, scope)?)),
+        Node::And(left, right) => Value::Bool(truthy(&evaluate(left, scope)?) && truthy(&evaluate(right, scope)?)),
+        Node::Or(left, right) => Value::Bool(truthy(&evaluate(left, scope)?) || truthy(&evaluate(right, scope)?)),
+    })
+}
+
+fn apply(filter: Filter, value: Value) -> std::result::Result<Value, String> {
+    match (filter, &value) {
+        (Filter::Length, Value::Null) => Ok(Value::from(0)),
+        (Filter::Length, Value::String(s)) => Ok(Value::from(s.chars().count())),
+        (Filter::Length, Value::Array(a)) => Ok(Value::from(a.len())),
+        (Filter::Length, Value::Object(o)) => Ok(Value::from(o.len())),
+        (Filter::Lower, Value::String(s)) => Ok(Value::String(s.to_lowercase())),
+        (Filter::Upper, Value::String(s)) => Ok(Value::String(s.to_uppercase())),
+        (Filter::Trim, Value::String(s)) => Ok(Value::String(s.trim().to_string())),
+        (Filter::Lower | Filter::Upper | Filter::Trim, Value::Null) => Ok(Value::Null),
+        _ => Err(format!("Cannot apply '{}' to {}", format!("{:?}", filter).to_lowercase(), value)),
+    }
+}
+
+fn compare(left: &Value, comparison: Comparison, right: &Value) -> bool {
+    let ordering = match (left, right) {
+        (Value::Number(l), Value::Number(r)) => l.as_f64().zip(r.as_f64()).and_then(|(l, r)| l.partial_cmp(&r)),
+        (Value::String(l), Value::String(r)) => Some(l.cmp(r)),
+        _ => None,
+    };
+    match comparison {
+        Comparison::Eq => ordering.map_or(left == right, |o| o.is_eq()),
+        Comparison::Ne => !ordering.map_or(left == right, |o| o.is_eq()),
+        Comparison::Lt => ordering.is_some_and(|o| o.is_lt()),
+        Comparison::Le => ordering.is_some_and(|o| o.is_le()),
+        Comparison::Gt => ordering.is_some_and(|o| o.is_gt()),
+        Comparison::Ge => ordering.is_some_and(|o| o.is_ge()),
+        Comparison::Contains => match (left, right) {
+            (Value::String(l), Value::String(r)) => l.contains(r.as_str()),
+            (Value::Array(items), value) => items.iter().any(|item| compare(item, Comparison::Eq, value)),
+            (Value::Object(map), Value::String(key)) => map.contains_key(key),
+            _ => false,
+        },
+    }
+}
+
+impl WorkflowExecutor {
+    /// Fails a step whose outputs do not satisfy one of its assertions.
+    pub(crate) fn check_assertions(&self, step: &Step, outputs: &HashMap<String, Value>) -> Result<()> {
+        if step.assert.is_empty() {
+            return Ok(());
+        }
+        for assertion in &step.assert {
+            let scope = self.context.scope_with(outputs, assertion)?;
+            let failed = |reason: String| OrchestratorError::AssertionFailed {
+                step_id: step.id.clone(),
+                assertion: assertion.clone(),
+                reason,
+            };
+            let expression = Expression::parse(&self.context.render_template(assertion)?).map_err(failed)?;
+            if !expression.evaluate(&scope).map_err(failed)? {
+                let bindings: Vec<String> = expression
+                    .bindings(&scope)
+                    .into_iter()
+                    .map(|(path, value)| format!("{} = {}", path, value))
+                    .collect();
+                let reason = if bindings.is_empty() {
+                    "it is false".to_string()
+                } else {
+                    format!("got {}", bindings.join(", "))
+                };
+                return Err(failed(reason));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn holds(source: &str, scope: Value) -> bool {
+        Expression::parse(source).unwrap().evaluate(&scope).unwrap()
+    }
+
+    #[test]
+    fn test_comparisons_and_filters() {
+        let scope = json!({"score": 0.85, "label": "Spam ", "results": [{"id": "a"}], "tags": ["x"]});
+        assert!(holds("score >= 0.8", scope.clone()));
+        assert!(!holds("score > 0.9", scope.clone()));
+        assert!(holds("results | length > 0", scope.clone()));
+        assert!(holds("results[0].id == 'a'", scope.clone()));
+        assert!(holds("label | trim | lower == \"spam\"", scope.clone()));
+        assert!(holds("tags contains 'x' and not (missing | length > 0)", scope.clone()));
+        assert!(holds("score < 0.5 || label", scope.clone()));
+        assert!(!holds("missing >= 1", scope));
+    }
+
+    #[test]
+    fn test_parse_errors_name_the_problem() {
+        assert!(Expression::parse("score = 1").unwrap_err().contains("'=='"));
+        assert!(Expression::parse("results | size").unwrap_err().contains("Unknown filter 'size'"));
+        assert!(Expression::parse("(score > 1").unwrap_err().contains("')'"));
+        assert!(Expression::parse("score >").unwrap_err().contains("end of expression"));
+    }
+
+    #[tokio::test]
+    async fn test_failed_assertion_fails_the_step() {
+        let workflow = crate::Workflow::from_yaml(
+            r#"
+name: graded
+steps:
+  - id: grade
+    type: llm
+    provider: openai
+    model: m
+    prompt: "Grade {{ inputs.essay }}"
+    output: [score, label]
+    assert:
+      - label | length > 0
+      - score >= {{ inputs.pass_mark }}
+  - id: publish
+    type: llm
+    depends_on: [grade]
+    provider: openai
+    model: m
+    prompt: "{{ steps.grade.label }}"
+    output: [text]
+"#,
+        )
+        .unwrap();
+        workflow.validate().unwrap();
+        let mocks = crate::MockResponses::new()
+            .with_step("grade", HashMap::from([("score".to_string(), json!(0.5)), ("label".to_string(), json!("weak"))]))
+            .with_step("publish", HashMap::from([("text".to_string(), json!("ok"))]));
+        let inputs = HashMap::from([("essay".to_string(), json!("...")), ("pass_mark".to_string(), json!(0.8))]);
+
+        let executor = WorkflowExecutor::new(workflow, inputs).unwrap().with_mock_responses(mocks);
+        assert!(matches!(executor.execute().await, Err(OrchestratorError::StepsFailed(_))));
+        let grade = executor.step_results.get("grade").unwrap();
+        assert_eq!(grade.error.as_deref(), Some("Assertion 'score >= {{ inputs.pass_mark }}' failed for step 'grade': got score = 0.5"));
+        assert_eq!(grade.error_details.as_ref().unwrap().kind, crate::ErrorKind::Assertion);
+        assert_eq!(executor.step_results.get("publish").unwrap().status, crate::StepStatus::Skipped);
+    }
+
+    #[test]
+    fn test_invalid_assertion_is_reported_by_validation() {
+        let workflow = crate::Workflow::from_yaml(
+            r#"
+name: invalid
+steps:
+  - id: grade
+    type: transform
+    function: merge
+    inputs: [a]
+    output: [score]
+    assert: ["score = 1"]
+"#,
+        )
+        .unwrap();
+        let err = workflow.validate().unwrap_err().to_string();
+        assert!(err.contains("Step 'grade' has invalid assertion 'score = 1'"), "{}", err);
+    }
+
+    #[test]
+    fn test_bindings_report_the_values_read() {
+        let expression = Expression::parse("score >= 0.8 and score < 1 and label != 'x'").unwrap();
+        let bindings = expression.bindings(&json!({"score": 0.5}));
+        assert_eq!(bindings, vec![("score".to_string(), json!(0.5)), ("label".to_string(), Value::Null)]);
+    }
+}
@@ -0,0 +1,130 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Cooperative cancellation of workflow runs.
+//!
+//! Obtain a [`CancelHandle`] from
+//! [`WorkflowExecutor::cancel_handle`](crate::WorkflowExecutor::cancel_handle)
+//! and call [`CancelHandle::cancel`] from any task. In-flight steps are aborted
+//! (their provider calls are dropped), steps that have not finished are marked
+//! [`StepStatus::Cancelled`](crate::StepStatus::Cancelled), and `execute()`
+//! returns [`OrchestratorError::Cancelled`](crate::OrchestratorError::Cancelled).
+//!
+//! A [`ShutdownHandle`] from
+//! [`WorkflowExecutor::shutdown_handle`](crate::WorkflowExecutor::shutdown_handle)
+//! stops a run more gently, for process shutdown: no further steps start,
+//! steps in flight may finish within a drain timeout, and `execute()` returns
+//! [`OrchestratorError::Interrupted`](crate::OrchestratorError::Interrupted)
+//! with the run persisted as paused.
+//!
+//! An [`AbandonHandle`] from
+//! [`WorkflowExecutor::abandon_handle`](crate::WorkflowExecutor::abandon_handle)
+//! cancels a run that another process took over, such as a queued run whose
+//! lease passed to another worker. The run stops as if cancelled, but its
+//! state is no longer persisted, so the new owner's state is left alone.
+
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+
+/// Handle used to cancel a running workflow.
+///
+/// Cloning the handle is cheap; all clones cancel the same run.
+#[derive(Debug, Clone)]
+pub struct CancelHandle {
+    sender: Arc<watch::Sender<bool>>,
+}
+
+impl Default for CancelHandle {
+    fn default() -> Self {
+        let (sender, _) = watch::channel(false);
+        Self {
+            sender: Arc::new(sender),
+        }
+    }
+}
+
+impl CancelHandle {
+    /// Request cancellation. Calling this more than once has no further effect.
+    pub fn cancel(&self) {
+        self.sender.send_replace(true);
+    }
+
+    /// Returns true once cancellation has been requested.
+    pub fn is_cancelled(&self) -> bool {
+        *self.sender.borrow()
+    }
+
+    /// Resolves once cancellation has been requested.
+    pub async fn cancelled(&self) {
+        let mut receiver = self.sender.subscribe();
+        // The sender lives as long as `self`, so this only returns once cancelled
+        let _ = receiver.wait_for(|cancelled| *cancelled).await;
+    }
+}
+
+/// Handle used to drain a running workflow before the process exits.
+#[derive(Debug, Clone)]
+pub struct ShutdownHandle {
+    pub(crate) drain: CancelHandle,
+    pub(crate) cancel: CancelHandle,
+}
+
+impl ShutdownHandle {
+    /// Stops starting steps, then cancels the steps still running after
+    /// `drain_timeout`.
+    pub async fn shutdown(&self, drain_timeout: Duration) {
+        self.drain.cancel();
+        tokio::time::sleep(drain_timeout).await;
+        self.cancel.cancel();
+    }
+
+    /// Returns true once shutdown has been requested.
+    pub fn is_shutting_down(&self) -> bool {
+        self.drain.is_cancelled()
+    }
+}
+
+/// Handle used to stop a run that another process took over.
+#[derive(Debug, Clone)]
+pub struct AbandonHandle {
+    pub(crate) abandon: CancelHandle,
+    pub(crate) cancel: CancelHandle,
+}
+
+impl AbandonHandle {
+    /// Stops persisting the run's state, then cancels it.
+    pub fn abandon(&self) {
+        self.abandon.cancel();
+        self.cancel.cancel();
+    }
+
+    /// Returns true once the run has been abandoned.
+    pub fn is_abandoned(&self) -> bool {
+        self.abandon.is_cancelled()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_cancel_wakes_waiters() {
+        let handle = CancelHandle::default();
+        assert!(!handle.is_cancelled());
+
+        let waiter = handle.clone();
+        let task = tokio::spawn(async move { waiter.cancelled().await });
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        handle.cancel();
+
+        tokio::time::timeout(Duration::from_secs(1), task).await.unwrap().unwrap();
+        assert!(handle.is_cancelled());
+
+        // Already cancelled handles resolve immediately
+        handle.cancelled().await;
+    }
+}
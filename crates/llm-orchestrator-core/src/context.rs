@@ -2,13 +2,167 @@
 // SPDX-License-Identifier: Apache-2.0
 
 //! Execution context management for workflows.
+//!
+//! Step outputs larger than [`ContextLimits::blob_threshold`] are kept as
+//! [`Blob`]s: serialized once and shared rather than cloned, spilled to a
+//! temporary file once the outputs in memory reach
+//! [`ContextLimits::memory_cap`], and only loaded by templates that name
+//! their step.
+//!
+//! An executor with an [output spill store](crate::spill) moves outputs out of
+//! memory itself, leaving a reference here until it reads them back.
 
 use crate::error::{OrchestratorError, Result};
-use handlebars::Handlebars;
+use crate::spill;
+use handlebars::{Handlebars, RenderErrorReason};
+use serde::Serialize;
 use serde_json::Value;
 use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
 use std::sync::Arc;
 use parking_lot::RwLock;
+use tracing::warn;
+
+/// Secret values resolved for a run, keyed by secret key.
+///
+/// Debug output lists the keys only.
+#[derive(Default)]
+struct SecretValues(RwLock<HashMap<String, String>>);
+
+impl fmt::Debug for SecretValues {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.0.read().keys()).finish()
+    }
+}
+
+/// Limits on the memory step outputs take in a context.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContextLimits {
+    /// Outputs whose JSON is larger than this many bytes are kept as blobs.
+    pub blob_threshold: usize,
+    /// Bytes of outputs kept in memory; blobs that would exceed it are
+    /// spilled to temporary files. Unbounded when `None`.
+    pub memory_cap: Option<usize>,
+}
+
+impl Default for ContextLimits {
+    fn default() -> Self {
+        Self {
+            blob_threshold: 256 * 1024,
+            memory_cap: None,
+        }
+    }
+}
+
+/// Bytes held by the step outputs of a context, by JSON size.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ContextMemory {
+    /// Outputs held as JSON values.
+    pub inline_bytes: usize,
+    /// Blobs held in memory.
+    pub blob_bytes: usize,
+    /// Blobs spilled to temporary files.
+    pub spilled_bytes: usize,
+    /// Number of blobs, in memory or spilled.
+    pub blobs: usize,
+}
+
+/// A step output in a context.
+#[derive(Debug, Clone)]
+pub enum ContextValue {
+    /// A value small enough to keep as is.
+    Inline(Value),
+    /// A large value, loaded when read.
+    Blob(Blob),
+}
+
+impl ContextValue {
+    /// The value, loading it if it is a blob.
+    pub fn load(&self) -> Result<Value> {
+        match self {
+            Self::Inline(value) => Ok(value.clone()),
+            Self::Blob(blob) => blob.load(),
+        }
+    }
+}
+
+/// A large step output, serialized once and shared between readers.
+#[derive(Clone)]
+pub struct Blob {
+    size: usize,
+    data: BlobData,
+}
+
+#[derive(Clone)]
+enum BlobData {
+    Memory(Arc<[u8]>),
+    File(Arc<SpillFile>),
+    /// Spilled to the executor's spill store under this reference.
+    Store(Arc<str>),
+}
+
+/// A temporary file holding a spilled blob, removed when the last blob using it is dropped.
+struct SpillFile(PathBuf);
+
+impl Drop for SpillFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+impl fmt::Debug for Blob {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Blob").field("size", &self.size).field("spilled", &self.is_spilled()).finish()
+    }
+}
+
+impl Blob {
+    /// Size of the value's JSON in bytes.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Whether the blob was spilled to a temporary file or a spill store.
+    pub fn is_spilled(&self) -> bool {
+        matches!(self.data, BlobData::File(_) | BlobData::Store(_))
+    }
+
+    /// Deserialize the value.
+    pub fn load(&self) -> Result<Value> {
+        match &self.data {
+            BlobData::Memory(bytes) => Ok(serde_json::from_slice(bytes)?),
+            BlobData::File(file) => {
+                let bytes = fs::read(&file.0).map_err(|e| {
+                    OrchestratorError::other(format!("Failed to read spilled output {}: {}", file.0.display(), e))
+                })?;
+                Ok(serde_json::from_slice(&bytes)?)
+            }
+            BlobData::Store(reference) => Err(OrchestratorError::other(format!(
+                "Spilled output {} has not been read back",
+                reference
+            ))),
+        }
+    }
+
+    /// Write `bytes` to a temporary file.
+    fn spill(bytes: &[u8]) -> std::io::Result<Self> {
+        let path = std::env::temp_dir().join(format!("llm-orchestrator-{}.json", uuid::Uuid::new_v4()));
+        fs::write(&path, bytes)?;
+        Ok(Self {
+            size: bytes.len(),
+            data: BlobData::File(Arc::new(SpillFile(path))),
+        })
+    }
+}
+
+/// A step output with the size of its JSON.
+#[derive(Debug, Clone)]
+struct Output {
+    value: ContextValue,
+    size: usize,
+}
 
 /// Execution context for a workflow run.
 #[derive(Debug, Clone)]
@@ -17,13 +171,37 @@ pub struct ExecutionContext {
     inputs: Arc<RwLock<HashMap<String, Value>>>,
 
     /// Output values from completed steps.
-    outputs: Arc<RwLock<HashMap<String, Value>>>,
+    outputs: Arc<RwLock<HashMap<String, Output>>>,
+
+    /// When outputs become blobs and blobs are spilled.
+    limits: ContextLimits,
+
+    /// Whether the executor spills outputs to its spill store, in place of
+    /// spilling blobs to temporary files.
+    store_spill: bool,
 
     /// Template renderer.
     renderer: Arc<Handlebars<'static>>,
 
     /// Workflow metadata.
     metadata: Arc<RwLock<HashMap<String, Value>>>,
+
+    /// Facts about the run, such as its ID and working directory, rendered as `run`.
+    run: Arc<RwLock<serde_json::Map<String, Value>>>,
+
+    /// Secrets available to the `secret` template helper.
+    secrets: Arc<SecretValues>,
+
+    /// Dependency each `depends_on_any` step started after, whose outputs
+    /// are rendered as `joined.<step id>`.
+    joined: Arc<RwLock<HashMap<String, String>>>,
+
+    /// Paths rendered at the root under the names the step being rendered
+    /// binds them to.
+    bindings: HashMap<String, String>,
+
+    /// Templates of the registered partials, searched for the outputs they read.
+    partials: Arc<HashMap<String, String>>,
 }
 
 impl ExecutionContext {
@@ -33,24 +211,235 @@ impl ExecutionContext {
         // Disable HTML escaping for LLM prompts
         renderer.register_escape_fn(handlebars::no_escape);
 
+        // {{ secret "key" }} renders a secret resolved before rendering
+        let secrets = Arc::new(SecretValues::default());
+        let helper_secrets = secrets.clone();
+        renderer.register_helper(
+            "secret",
+            Box::new(
+                move |h: &handlebars::Helper<'_>,
+                      _: &Handlebars<'_>,
+                      _: &handlebars::Context,
+                      _: &mut handlebars::RenderContext<'_, '_>,
+                      out: &mut dyn handlebars::Output|
+                      -> handlebars::HelperResult {
+                    let key = h
+                        .param(0)
+                        .and_then(|p| p.value().as_str())
+                        .ok_or(RenderErrorReason::ParamNotFoundForIndex("secret", 0))?;
+                    let secrets = helper_secrets.0.read();
+                    let value = secrets.get(key).ok_or_else(|| {
+                        RenderErrorReason::Other(format!("Secret '{}' has not been resolved", key))
+                    })?;
+                    out.write(value)?;
+                    Ok(())
+                },
+            ),
+        );
+
         Self {
             inputs: Arc::new(RwLock::new(inputs)),
             outputs: Arc::new(RwLock::new(HashMap::new())),
             renderer: Arc::new(renderer),
             metadata: Arc::new(RwLock::new(HashMap::new())),
+            run: Arc::new(RwLock::new(serde_json::Map::new())),
+            secrets,
+            joined: Arc::new(RwLock::new(HashMap::new())),
+            bindings: HashMap::new(),
+            partials: Arc::default(),
+            limits: ContextLimits::default(),
+            store_spill: false,
         }
     }
 
+    /// Set when outputs become blobs and blobs are spilled to temporary files.
+    pub fn with_limits(mut self, limits: ContextLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Make `partials` available to templates as `{{> name }}`.
+    pub fn with_partials(mut self, partials: &HashMap<String, String>) -> Result<Self> {
+        let renderer = Arc::make_mut(&mut self.renderer);
+        for (name, template) in partials {
+            renderer
+                .register_partial(name, template)
+                .map_err(|e| OrchestratorError::template(format!("Partial '{}': {}", name, e)))?;
+        }
+        Arc::make_mut(&mut self.partials).extend(partials.iter().map(|(name, template)| (name.clone(), template.clone())));
+        Ok(self)
+    }
+
+    /// Leave spilling to the executor's spill store.
+    pub(crate) fn with_store_spill(mut self) -> Self {
+        self.store_spill = true;
+        self
+    }
+
+    /// Render the values at `bindings` paths under their names, for one step.
+    ///
+    /// Everything else is shared with the context this was made from.
+    pub(crate) fn with_bindings(mut self, bindings: HashMap<String, String>) -> Self {
+        self.bindings = bindings;
+        self
+    }
+
+    /// Fails if a bound path has no value.
+    pub(crate) fn check_bindings(&self) -> Result<()> {
+        if self.bindings.is_empty() {
+            return Ok(());
+        }
+        self.template_data(Some("")).map(drop)
+    }
+
+    pub(crate) fn limits(&self) -> ContextLimits {
+        self.limits
+    }
+
+    /// Make a secret available to the `secret` template helper.
+    pub fn set_secret(&self, key: impl Into<String>, value: impl Into<String>) {
+        self.secrets.0.write().insert(key.into(), value.into());
+    }
+
+    /// Returns true if the secret has been resolved for this run.
+    pub fn has_secret(&self, key: &str) -> bool {
+        self.secrets.0.read().contains_key(key)
+    }
+
+    /// Returns a resolved secret value.
+    #[cfg(feature = "secrets")]
+    pub(crate) fn secret(&self, key: &str) -> Option<String> {
+        self.secrets.0.read().get(key).cloned()
+    }
+
     /// Set an output value for a step.
+    ///
+    /// Values larger than the blob threshold are stored as blobs, spilled to a
+    /// temporary file if keeping them in memory would exceed the memory cap.
     pub fn set_output(&self, step_id: impl Into<String>, value: Value) {
+        let step_id = step_id.into();
+        let bytes = serde_json::to_vec(&value).unwrap_or_default();
+        let size = bytes.len();
         let mut outputs = self.outputs.write();
-        outputs.insert(step_id.into(), value);
+        if size <= self.limits.blob_threshold {
+            outputs.insert(step_id, Output { value: ContextValue::Inline(value), size });
+            return;
+        }
+        drop(value);
+
+        let in_memory: usize = outputs
+            .iter()
+            .filter(|(id, output)| **id != step_id && !matches!(&output.value, ContextValue::Blob(blob) if blob.is_spilled()))
+            .map(|(_, output)| output.size)
+            .sum();
+        let blob = match self.limits.memory_cap {
+            Some(cap) if !self.store_spill && in_memory + size > cap => Blob::spill(&bytes).unwrap_or_else(|e| {
+                warn!(step_id = %step_id, error = %e, "Failed to spill step output; keeping it in memory");
+                Blob { size, data: BlobData::Memory(bytes.into()) }
+            }),
+            _ => Blob { size, data: BlobData::Memory(bytes.into()) },
+        };
+        outputs.insert(step_id, Output { value: ContextValue::Blob(blob), size });
+    }
+
+    /// Render the outputs of `dependency` as `joined.<step_id>`.
+    pub(crate) fn set_joined(&self, step_id: impl Into<String>, dependency: impl Into<String>) {
+        self.joined.write().insert(step_id.into(), dependency.into());
     }
 
     /// Get an output value from a step.
     pub fn get_output(&self, step_id: &str) -> Option<Value> {
-        let outputs = self.outputs.read();
-        outputs.get(step_id).cloned()
+        let output = self.get_value(step_id)?;
+        output
+            .load()
+            .map_err(|e| warn!(step_id = %step_id, error = %e, "Failed to load step output"))
+            .ok()
+    }
+
+    /// Get the output of a step without loading it if it is a blob.
+    pub fn get_value(&self, step_id: &str) -> Option<ContextValue> {
+        self.outputs.read().get(step_id).map(|output| output.value.clone())
+    }
+
+    /// Steps whose outputs are held in memory, with their sizes.
+    pub(crate) fn resident_outputs(&self) -> Vec<(String, usize)> {
+        self.outputs
+            .read()
+            .iter()
+            .filter(|(_, output)| match &output.value {
+                ContextValue::Inline(value) => spill::spilled_reference(value).is_none(),
+                ContextValue::Blob(blob) => !blob.is_spilled(),
+            })
+            .map(|(step_id, output)| (step_id.clone(), output.size))
+            .collect()
+    }
+
+    /// The JSON of a step output held in memory.
+    pub(crate) fn output_bytes(&self, step_id: &str) -> Option<Vec<u8>> {
+        match &self.outputs.read().get(step_id)?.value {
+            ContextValue::Inline(value) => serde_json::to_vec(value).ok(),
+            ContextValue::Blob(Blob { data: BlobData::Memory(bytes), .. }) => Some(bytes.to_vec()),
+            ContextValue::Blob(_) => None,
+        }
+    }
+
+    /// Drops a step output from memory, keeping the reference it was spilled under.
+    pub(crate) fn mark_spilled(&self, step_id: &str, reference: &str) {
+        if let Some(output) = self.outputs.write().get_mut(step_id) {
+            output.value = ContextValue::Blob(Blob {
+                size: output.size,
+                data: BlobData::Store(reference.into()),
+            });
+        }
+    }
+
+    /// Steps whose outputs are in the spill store, with their references.
+    ///
+    /// Outputs restored from a checkpoint as references count as spilled too.
+    pub(crate) fn spilled_outputs(&self) -> Vec<(String, String)> {
+        self.outputs
+            .read()
+            .iter()
+            .filter_map(|(step_id, output)| {
+                let reference = match &output.value {
+                    ContextValue::Blob(Blob { data: BlobData::Store(reference), .. }) => reference.to_string(),
+                    ContextValue::Inline(value) => spill::spilled_reference(value)?.to_string(),
+                    ContextValue::Blob(_) => return None,
+                };
+                Some((step_id.clone(), reference))
+            })
+            .collect()
+    }
+
+    /// Puts back a step output read from the spill store.
+    pub(crate) fn restore_output(&self, step_id: &str, bytes: Vec<u8>) -> Result<()> {
+        let size = bytes.len();
+        let value = if size <= self.limits.blob_threshold {
+            ContextValue::Inline(serde_json::from_slice(&bytes)?)
+        } else {
+            ContextValue::Blob(Blob { size, data: BlobData::Memory(bytes.into()) })
+        };
+        self.outputs.write().insert(step_id.to_string(), Output { value, size });
+        Ok(())
+    }
+
+    /// Bytes held by step outputs.
+    pub fn memory_usage(&self) -> ContextMemory {
+        let mut memory = ContextMemory::default();
+        for output in self.outputs.read().values() {
+            match &output.value {
+                ContextValue::Inline(_) => memory.inline_bytes += output.size,
+                ContextValue::Blob(blob) => {
+                    memory.blobs += 1;
+                    if blob.is_spilled() {
+                        memory.spilled_bytes += output.size;
+                    } else {
+                        memory.blob_bytes += output.size;
+                    }
+                }
+            }
+        }
+        memory
     }
 
     /// Get an input value.
@@ -61,7 +450,25 @@ impl ExecutionContext {
 
     /// Render a template string with the current context.
     pub fn render_template(&self, template: &str) -> Result<String> {
-        // Build context data for rendering
+        self.renderer
+            .render_template(template, &self.template_data(Some(template))?)
+            .map_err(|e| OrchestratorError::template(e.to_string()))
+    }
+
+    /// Render a template, keeping the JSON value of a template that is a single `{{ path }}` expression.
+    ///
+    /// `{{ payload.labels }}` yields the labels array itself rather than its rendering.
+    pub fn render_value(&self, template: &str) -> Result<Value> {
+        match lookup_path(&self.template_data(Some(template))?, template) {
+            Some(value) => Ok(value),
+            None => self.render_template(template).map(Value::String),
+        }
+    }
+
+    /// Data templates are rendered against.
+    ///
+    /// Blobs are only loaded for steps `template` names; without a template all are.
+    fn template_data(&self, template: Option<&str>) -> Result<Value> {
         let mut context_data = serde_json::Map::new();
 
         // Add inputs (flat at root level for backward compatibility)
@@ -80,21 +487,76 @@ impl ExecutionContext {
 
         // Add outputs under both "outputs" and "steps" keys
         let outputs = self.outputs.read();
+        let joined = self.joined.read();
+        // Blobs read only by a partial are still loaded
+        let template = template.map(|template| crate::partials::expand(template, &self.partials));
+        let template = template.as_deref();
         if !outputs.is_empty() {
             let mut outputs_map = serde_json::Map::new();
-            for (step_id, value) in outputs.iter() {
-                outputs_map.insert(step_id.clone(), value.clone());
+            for (step_id, output) in outputs.iter() {
+                let named = match template {
+                    Some(template) => {
+                        template.contains(step_id.as_str())
+                            || joined.iter().any(|(join, dependency)| dependency == step_id && template.contains(join.as_str()))
+                            || self.bindings.values().any(|path| path.contains(step_id.as_str()))
+                    }
+                    None => true,
+                };
+                match &output.value {
+                    ContextValue::Inline(value) => {
+                        outputs_map.insert(step_id.clone(), value.clone());
+                    }
+                    ContextValue::Blob(blob) if named => {
+                        outputs_map.insert(step_id.clone(), blob.load()?);
+                    }
+                    ContextValue::Blob(_) => {}
+                }
             }
 
-            // Support both {{outputs.step_id}} (backward compat) and {{steps.step_id.field}} (new)
+            if !joined.is_empty() {
+                let joined_map = joined
+                    .iter()
+                    .filter_map(|(join, dependency)| Some((join.clone(), outputs_map.get(dependency)?.clone())))
+                    .collect();
+                context_data.insert("joined".to_string(), Value::Object(joined_map));
+            }
+            // Support both {{outputs.step_id}} (deprecated, see the `deprecated` lint) and {{steps.step_id.field}}
             context_data.insert("outputs".to_string(), Value::Object(outputs_map.clone()));
             context_data.insert("steps".to_string(), Value::Object(outputs_map));
         }
 
-        // Render template
-        self.renderer
-            .render_template(template, &Value::Object(context_data))
-            .map_err(|e| OrchestratorError::template(e.to_string()))
+        // Add facts about the run under "run"
+        let run = self.run.read();
+        if !run.is_empty() {
+            context_data.insert("run".to_string(), Value::Object(run.clone()));
+        }
+
+        // Add the step's bindings at the root, over inputs of the same name
+        let mut data = Value::Object(context_data);
+        if !self.bindings.is_empty() {
+            let mut bound = Vec::with_capacity(self.bindings.len());
+            for (name, path) in &self.bindings {
+                let value = lookup(&data, path).ok_or_else(|| {
+                    OrchestratorError::template(format!("Binding '{}' has no value: nothing at '{}'", name, path))
+                })?;
+                bound.push((name.clone(), value));
+            }
+            if let Value::Object(context_data) = &mut data {
+                context_data.extend(bound);
+            }
+        }
+
+        Ok(data)
+    }
+
+    /// Data `assertion` on a step's `outputs` is evaluated against: the
+    /// template data with the outputs at the root.
+    pub(crate) fn scope_with(&self, outputs: &HashMap<String, Value>, assertion: &str) -> Result<Value> {
+        let mut scope = self.template_data(Some(assertion))?;
+        if let Value::Object(map) = &mut scope {
+            map.extend(outputs.iter().map(|(k, v)| (k.clone(), v.clone())));
+        }
+        Ok(scope)
     }
 
     /// Evaluate a condition expression.
@@ -130,6 +592,11 @@ impl ExecutionContext {
         metadata.insert(key.into(), value);
     }
 
+    /// Set a value templates read as `run.<key>`.
+    pub fn set_run_value(&self, key: impl Into<String>, value: Value) {
+        self.run.write().insert(key.into(), value);
+    }
+
     /// Get metadata value.
     pub fn get_metadata(&self, key: &str) -> Option<Value> {
         let metadata = self.metadata.read();
@@ -137,8 +604,26 @@ impl ExecutionContext {
     }
 
     /// Get all outputs.
+    ///
+    /// Outputs in the spill store are given as references to where they were spilled.
     pub fn all_outputs(&self) -> HashMap<String, Value> {
-        self.outputs.read().clone()
+        let outputs: Vec<(String, Output)> =
+            self.outputs.read().iter().map(|(step_id, output)| (step_id.clone(), output.clone())).collect();
+        outputs
+            .into_iter()
+            .filter_map(|(step_id, output)| match &output.value {
+                ContextValue::Blob(Blob { data: BlobData::Store(reference), .. }) => {
+                    Some((step_id, spill::spilled_value(reference, output.size)))
+                }
+                value => match value.load() {
+                    Ok(value) => Some((step_id, value)),
+                    Err(e) => {
+                        warn!(step_id = %step_id, error = %e, "Failed to load step output");
+                        None
+                    }
+                },
+            })
+            .collect()
     }
 
     /// Get all inputs.
@@ -160,6 +645,25 @@ impl Default for ExecutionContext {
     }
 }
 
+/// The value named by a template that is a single `{{ path }}` expression.
+fn lookup_path(data: &Value, template: &str) -> Option<Value> {
+    lookup(data, template.trim().strip_prefix("{{")?.strip_suffix("}}")?.trim())
+}
+
+/// The value at a dotted path such as `steps.fetch.body`.
+fn lookup(data: &Value, path: &str) -> Option<Value> {
+    if path.is_empty() || path.contains(|c: char| c.is_whitespace() || c == '{' || c == '}') {
+        return None;
+    }
+    path.split('.')
+        .try_fold(data, |value, segment| match value {
+            Value::Object(fields) => fields.get(segment),
+            Value::Array(items) => items.get(segment.parse::<usize>().ok()?),
+            _ => None,
+        })
+        .cloned()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -186,6 +690,68 @@ mod tests {
         assert_eq!(ctx.get_output("step3"), None);
     }
 
+    #[test]
+    fn test_large_outputs_become_blobs_and_spill_past_the_cap() {
+        let ctx = ExecutionContext::default().with_limits(ContextLimits {
+            blob_threshold: 16,
+            memory_cap: Some(50),
+        });
+        ctx.set_output("small", json!({"ok": true}));
+        ctx.set_output("page", json!({"text": "a".repeat(20)}));
+        ctx.set_output("transcript", json!({"text": "b".repeat(20)}));
+
+        let memory = ctx.memory_usage();
+        assert_eq!(memory.inline_bytes, 11);
+        assert_eq!((memory.blobs, memory.blob_bytes, memory.spilled_bytes), (2, 31, 31));
+        let Some(ContextValue::Blob(spilled)) = ctx.get_value("transcript") else {
+            panic!("expected a blob");
+        };
+        assert!(spilled.is_spilled());
+
+        // Blobs load when read or named by a template
+        assert_eq!(ctx.get_output("transcript").unwrap()["text"], json!("b".repeat(20)));
+        assert_eq!(ctx.render_template("{{ steps.page.text }}").unwrap(), "a".repeat(20));
+        assert_eq!(ctx.all_outputs().len(), 3);
+
+        // Templates that do not name a blob's step never load it
+        let BlobData::File(file) = &spilled.data else { unreachable!() };
+        fs::remove_file(&file.0).unwrap();
+        assert_eq!(ctx.render_template("{{ steps.small.ok }}").unwrap(), "true");
+        assert!(ctx.render_template("{{ steps.transcript.text }}").is_err());
+    }
+
+    #[test]
+    fn test_spill_file_is_removed_with_the_output() {
+        let ctx = ExecutionContext::default().with_limits(ContextLimits {
+            blob_threshold: 0,
+            memory_cap: Some(0),
+        });
+        ctx.set_output("page", json!("large"));
+        let Some(ContextValue::Blob(Blob { data: BlobData::File(file), .. })) = ctx.get_value("page") else {
+            panic!("expected a spilled blob");
+        };
+        let path = file.0.clone();
+        drop(file);
+        assert!(path.exists());
+
+        ctx.set_output("page", json!("replaced"));
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_secret_helper() {
+        let ctx = ExecutionContext::default();
+        assert!(ctx.render_template(r#"key={{ secret "openai/api_key" }}"#).is_err());
+
+        ctx.set_secret("openai/api_key", "sk-test-123");
+        assert!(ctx.has_secret("openai/api_key"));
+        let rendered = ctx.render_template(r#"key={{ secret "openai/api_key" }}"#).unwrap();
+        assert_eq!(rendered, "key=sk-test-123");
+
+        // Secret values never appear in debug output
+        assert!(!format!("{:?}", ctx).contains("sk-test-123"));
+    }
+
     #[test]
     fn test_template_rendering_with_inputs() {
         let mut inputs = HashMap::new();
@@ -314,4 +880,16 @@ mod tests {
         let result3 = ctx.render_template("{{ inputs.age }}").unwrap();
         assert_eq!(result3, "30");
     }
+
+    #[test]
+    fn test_render_value_keeps_json_types() {
+        let mut inputs = HashMap::new();
+        inputs.insert("payload".to_string(), json!({"labels": ["bug", "p1"], "count": 2}));
+        let ctx = ExecutionContext::new(inputs);
+
+        assert_eq!(ctx.render_value("{{ payload.labels }}").unwrap(), json!(["bug", "p1"]));
+        assert_eq!(ctx.render_value("{{payload.count}}").unwrap(), json!(2));
+        assert_eq!(ctx.render_value("{{ payload.labels.1 }}").unwrap(), json!("p1"));
+        assert_eq!(ctx.render_value("{{ payload.count }} labels").unwrap(), json!("2 labels"));
+    }
 }
@@ -5,9 +5,10 @@
 
 use crate::error::{OrchestratorError, Result};
 use crate::workflow::Workflow;
-use petgraph::algo::toposort;
+use petgraph::algo::{tarjan_scc, toposort};
 use petgraph::graph::{DiGraph, NodeIndex};
-use std::collections::HashMap;
+use petgraph::visit::Dfs;
+use std::collections::{HashMap, HashSet, VecDeque};
 
 /// A DAG representation of a workflow.
 #[derive(Debug, Clone)]
@@ -40,7 +41,7 @@ impl WorkflowDAG {
         for step in &workflow.steps {
             let target_idx = step_to_node[&step.id];
 
-            for dep_id in &step.depends_on {
+            for dep_id in step.depends_on.iter().chain(&step.depends_on_any) {
                 let source_idx = step_to_node
                     .get(dep_id)
                     .ok_or_else(|| OrchestratorError::StepNotFound(dep_id.clone()))?;
@@ -65,15 +66,53 @@ impl WorkflowDAG {
     pub fn validate(&self) -> Result<()> {
         // Attempt topological sort - will fail if there's a cycle
         toposort(&self.graph, None)
-            .map_err(|_| OrchestratorError::CyclicDependency)?;
+            .map_err(|cycle| self.cycle_error(cycle.node_id()))?;
 
         Ok(())
     }
 
+    /// Error naming the steps of a cycle through `start`.
+    fn cycle_error(&self, start: NodeIndex) -> OrchestratorError {
+        // Walk dependencies breadth-first within the cycle's strongly connected component
+        let component: HashSet<NodeIndex> = tarjan_scc(&self.graph)
+            .into_iter()
+            .find(|component| component.contains(&start))
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+        let mut reached_from: HashMap<NodeIndex, NodeIndex> = HashMap::new();
+        let mut queue = VecDeque::from([start]);
+        'search: while let Some(node) = queue.pop_front() {
+            for dependency in self.graph.neighbors_directed(node, petgraph::Direction::Incoming) {
+                if !component.contains(&dependency) || reached_from.contains_key(&dependency) {
+                    continue;
+                }
+                reached_from.insert(dependency, node);
+                if dependency == start {
+                    break 'search;
+                }
+                queue.push_back(dependency);
+            }
+        }
+
+        // Follow the walk back from `start` to list the cycle in dependency order
+        let mut cycle = vec![start];
+        let mut node = start;
+        while let Some(&previous) = reached_from.get(&node) {
+            cycle.push(previous);
+            if previous == start {
+                break;
+            }
+            node = previous;
+        }
+        cycle.reverse();
+        OrchestratorError::CyclicDependency(cycle.into_iter().map(|idx| self.node_to_step[&idx].clone()).collect())
+    }
+
     /// Get execution order (topological sort).
     pub fn execution_order(&self) -> Result<Vec<String>> {
         let sorted_indices = toposort(&self.graph, None)
-            .map_err(|_| OrchestratorError::CyclicDependency)?;
+            .map_err(|cycle| self.cycle_error(cycle.node_id()))?;
 
         Ok(sorted_indices
             .into_iter()
@@ -113,6 +152,23 @@ impl WorkflowDAG {
         )
     }
 
+    /// Get all transitive dependents of a step (not including the step itself).
+    pub fn descendants(&self, step_id: &str) -> HashSet<String> {
+        let mut descendants = HashSet::new();
+        let Some(&start) = self.step_to_node.get(step_id) else {
+            return descendants;
+        };
+
+        let mut dfs = Dfs::new(&self.graph, start);
+        while let Some(idx) = dfs.next(&self.graph) {
+            if idx != start {
+                descendants.insert(self.node_to_step[&idx].clone());
+            }
+        }
+
+        descendants
+    }
+
     /// Get steps that are ready to execute (all dependencies completed).
     pub fn ready_steps(&self, completed: &std::collections::HashSet<String>) -> Vec<String> {
         self.step_to_node
@@ -167,20 +223,33 @@ mod tests {
             id: id.to_string(),
             step_type: StepType::Llm,
             depends_on: depends_on.into_iter().map(String::from).collect(),
+            depends_on_any: Vec::new(),
+            bind: HashMap::new(),
             condition: None,
             config: StepConfig::Llm(LlmStepConfig {
                 provider: "openai".to_string(),
                 model: "gpt-4".to_string(),
                 prompt: "test".to_string(),
+                prompt_ref: None,
                 temperature: None,
                 max_tokens: None,
                 system: None,
                 stream: false,
+                variants: Vec::new(),
+                variant_mode: Default::default(),
+                hedge: None,
+                memory: None,
+                images: Vec::new(),
                 extra: HashMap::new(),
             }),
             output: vec![],
             timeout_seconds: None,
+            on_failure: None,
             retry: None,
+            guardrails: None,
+            resource_class: None,
+            assert: Vec::new(),
+            allow_skipped_deps: false,
         }
     }
 
@@ -220,12 +289,22 @@ mod tests {
     #[test]
     fn test_cyclic_dependency_detection() {
         let mut workflow = Workflow::new("test");
-        workflow.steps.push(create_test_step("step1", vec!["step2"]));
+        workflow.steps.push(create_test_step("step1", vec!["step3"]));
         workflow.steps.push(create_test_step("step2", vec!["step1"]));
+        workflow.steps.push(create_test_step("step3", vec!["step2"]));
+        workflow.steps.push(create_test_step("step4", vec!["step3"]));
 
         let result = WorkflowDAG::from_workflow(&workflow);
         assert!(result.is_err());
-        assert!(matches!(result.unwrap_err(), OrchestratorError::CyclicDependency));
+        let OrchestratorError::CyclicDependency(cycle) = result.unwrap_err() else {
+            panic!("expected a cycle");
+        };
+        // The cycle starts and ends at the same step, each step depending on the next
+        assert_eq!(cycle.len(), 4);
+        assert_eq!(cycle.first(), cycle.last());
+        for pair in cycle.windows(2) {
+            assert!(workflow.get_step(&pair[0]).unwrap().depends_on.contains(&pair[1]));
+        }
     }
 
     #[test]
@@ -298,4 +377,22 @@ mod tests {
         assert!(dependents.contains(&"step2".to_string()));
         assert!(dependents.contains(&"step3".to_string()));
     }
+
+    #[test]
+    fn test_descendants() {
+        let mut workflow = Workflow::new("test");
+        workflow.steps.push(create_test_step("step1", vec![]));
+        workflow.steps.push(create_test_step("step2", vec!["step1"]));
+        workflow.steps.push(create_test_step("step3", vec!["step2"]));
+        workflow.steps.push(create_test_step("step4", vec![]));
+
+        let dag = WorkflowDAG::from_workflow(&workflow).unwrap();
+
+        let descendants = dag.descendants("step1");
+        assert_eq!(descendants.len(), 2);
+        assert!(descendants.contains("step2"));
+        assert!(descendants.contains("step3"));
+        assert!(dag.descendants("step3").is_empty());
+        assert!(dag.descendants("missing").is_empty());
+    }
 }
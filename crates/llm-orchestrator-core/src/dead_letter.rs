@@ -0,0 +1,154 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Re-driving dead-lettered steps (requires the `state-persistence` feature).
+//!
+//! When an executor has a dead-letter queue attached with
+//! [`WorkflowExecutor::with_dead_letter_queue`], every step that fails after
+//! exhausting its retries is stored together with its definition, the run
+//! context, the last provider request, and the error of every attempt.
+//! [`retry_dead_letter`] re-runs just that step with the captured context,
+//! without rerunning the rest of the workflow.
+
+use crate::error::{OrchestratorError, Result};
+use crate::executor::{StepResult, StepStatus, WorkflowExecutor};
+use crate::workflow::{Step, Workflow};
+use llm_orchestrator_state::DeadLetterQueue;
+use serde_json::Value;
+use std::collections::HashMap;
+use tracing::info;
+use uuid::Uuid;
+
+/// Re-run the step captured in a dead-letter entry.
+///
+/// `configure` sets up the executor (e.g. registers providers). The entry is
+/// marked resolved if the step completes.
+pub async fn retry_dead_letter(
+    dead_letters: &dyn DeadLetterQueue,
+    id: &Uuid,
+    configure: impl FnOnce(WorkflowExecutor) -> WorkflowExecutor,
+) -> Result<StepResult> {
+    let entry = dead_letters
+        .get_dead_letter(id)
+        .await
+        .map_err(|e| OrchestratorError::other(format!("Failed to load dead letter: {}", e)))?;
+    if entry.is_resolved() {
+        return Err(OrchestratorError::other(format!("Dead letter {} is already resolved", id)));
+    }
+
+    // Run the step on its own; its dependencies' outputs come from the captured context
+    let mut step: Step = serde_json::from_value(entry.step)?;
+    step.depends_on.clear();
+    step.condition = None;
+    step.on_failure = None;
+
+    let mut workflow = Workflow::new(entry.workflow_name);
+    workflow.steps = vec![step];
+
+    let executor = WorkflowExecutor::new(workflow, context_map(&entry.context, "inputs"))?;
+    for (step_id, outputs) in context_map(&entry.context, "outputs") {
+        executor.context.set_output(step_id, outputs);
+    }
+    let executor = configure(executor);
+
+    info!(dead_letter_id = %id, step_id = %entry.step_id, "Retrying dead-lettered step");
+    let mut results = executor.execute().await?;
+    let result = results
+        .remove(&entry.step_id)
+        .ok_or_else(|| OrchestratorError::StepNotFound(entry.step_id.clone()))?;
+
+    if result.status == StepStatus::Completed {
+        dead_letters
+            .resolve_dead_letter(id)
+            .await
+            .map_err(|e| OrchestratorError::other(format!("Failed to resolve dead letter: {}", e)))?;
+    }
+
+    Ok(result)
+}
+
+/// Extract one section (`inputs` or `outputs`) of a captured context.
+fn context_map(context: &Value, key: &str) -> HashMap<String, Value> {
+    context
+        .get(key)
+        .and_then(|v| v.as_object())
+        .map(|obj| obj.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::{CompletionRequest, CompletionResponse, LLMProvider, ProviderError};
+    use crate::MockResponses;
+    use llm_orchestrator_state::SqliteStateStore;
+    use std::sync::Arc;
+
+    struct RateLimitedProvider;
+
+    #[async_trait::async_trait]
+    impl LLMProvider for RateLimitedProvider {
+        async fn complete(&self, _request: CompletionRequest) -> std::result::Result<CompletionResponse, ProviderError> {
+            Err(ProviderError::RateLimitExceeded { retry_after: None })
+        }
+
+        fn name(&self) -> &str {
+            "rate-limited"
+        }
+    }
+
+    const WORKFLOW: &str = r#"
+name: "dlq-workflow"
+steps:
+  - id: "draft"
+    type: "transform"
+    function: "noop"
+    inputs: []
+  - id: "summarize"
+    type: "llm"
+    depends_on: ["draft"]
+    provider: "openai"
+    model: "gpt-4"
+    prompt: "Summarize {{ inputs.topic }}"
+    output: ["summary"]
+"#;
+
+    #[tokio::test]
+    async fn test_failed_step_is_captured_and_retried() {
+        let store = Arc::new(SqliteStateStore::new(":memory:").await.unwrap());
+
+        let workflow = Workflow::from_yaml(WORKFLOW).unwrap();
+        let inputs = HashMap::from([("topic".to_string(), serde_json::json!("rust"))]);
+        let executor = WorkflowExecutor::new(workflow, inputs)
+            .unwrap()
+            .with_provider("openai", Arc::new(RateLimitedProvider))
+            .with_dead_letter_queue(store.clone());
+        assert!(matches!(executor.execute().await, Err(OrchestratorError::StepsFailed(_))));
+        assert_eq!(executor.step_results.get("summarize").unwrap().status, StepStatus::Failed);
+
+        let entries = store.list_dead_letters(false).await.unwrap();
+        assert_eq!(entries.len(), 1);
+        let entry = &entries[0];
+        assert_eq!(entry.run_id, executor.run_id());
+        assert_eq!(entry.step_id, "summarize");
+        assert_eq!(entry.request.as_ref().unwrap()["prompt"], "Summarize rust");
+        assert_eq!(entry.context["inputs"]["topic"], "rust");
+        assert!(entry.attempts.last().unwrap().error.contains("Rate limit"));
+
+        // Re-drive only the failed step
+        let mocks = MockResponses::new().with_step(
+            "summarize",
+            HashMap::from([("summary".to_string(), serde_json::json!("Rust is fast"))]),
+        );
+        let result = retry_dead_letter(store.as_ref(), &entry.id, |executor| {
+            executor.with_mock_responses(mocks)
+        })
+        .await
+        .unwrap();
+        assert_eq!(result.status, StepStatus::Completed);
+        assert!(store.get_dead_letter(&entry.id).await.unwrap().is_resolved());
+
+        // Resolved entries cannot be retried again
+        assert!(retry_dead_letter(store.as_ref(), &entry.id, |executor| executor).await.is_err());
+    }
+}
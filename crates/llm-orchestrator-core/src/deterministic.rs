@@ -0,0 +1,232 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Deterministic execution.
+//!
+//! In deterministic mode every LLM call is sent with temperature 0 and a
+//! fixed seed (honoured by providers that support one, such as OpenAI),
+//! retries back off without jitter, and the run records a
+//! [`ReproducibilityManifest`]: per step, the model snapshot that answered,
+//! the provider's system fingerprint, and a hash of the prompt. Comparing the
+//! manifests of two runs tells whether their outputs can be compared at all.
+
+use crate::executor::WorkflowExecutor;
+use crate::providers::{CompletionRequest, CompletionResponse};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// What determined the outputs of a deterministic run.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReproducibilityManifest {
+    /// Seed sent with every LLM call.
+    pub seed: u64,
+    /// The LLM calls of each step, keyed by step ID.
+    pub steps: BTreeMap<String, StepManifest>,
+}
+
+/// The LLM call that produced a step's outputs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StepManifest {
+    /// Provider the step called.
+    pub provider: String,
+    /// Model the step asked for.
+    pub model: String,
+    /// Dated model version that answered, when the provider reports it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_snapshot: Option<String>,
+    /// Provider backend configuration, when the provider reports it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_fingerprint: Option<String>,
+    /// SHA-256 of the system prompt, prompt, and conversation history, hex-encoded.
+    pub prompt_sha256: String,
+    /// Temperature sent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    /// Seed sent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<Value>,
+}
+
+/// Collects the manifest of a deterministic run.
+#[derive(Debug)]
+pub(crate) struct ManifestRecorder {
+    manifest: Mutex<ReproducibilityManifest>,
+}
+
+/// What the manifest records of a request, taken before it is sent.
+pub(crate) struct SentRequest {
+    model: String,
+    prompt_sha256: String,
+    temperature: Option<f32>,
+    seed: Option<Value>,
+}
+
+impl SentRequest {
+    pub(crate) fn of(request: &CompletionRequest) -> Self {
+        Self {
+            model: request.model.clone(),
+            prompt_sha256: prompt_hash(request),
+            temperature: request.temperature,
+            seed: request.extra.get("seed").cloned(),
+        }
+    }
+}
+
+/// SHA-256 of a request's system prompt, prompt, conversation history, and images, hex-encoded.
+pub fn prompt_hash(request: &CompletionRequest) -> String {
+    let mut hasher = Sha256::new();
+    if let Some(system) = &request.system {
+        hasher.update(system.as_bytes());
+    }
+    hasher.update([0]);
+    hasher.update(request.prompt.as_bytes());
+    for turn in &request.history {
+        for part in [&turn.role, &turn.content] {
+            hasher.update([0]);
+            hasher.update(part.as_bytes());
+        }
+    }
+    for image in &request.images {
+        hasher.update([0]);
+        hasher.update(image.to_url().as_bytes());
+    }
+    hex::encode(hasher.finalize())
+}
+
+impl WorkflowExecutor {
+    /// Runs LLM calls with temperature 0 and `seed`, retries without jitter,
+    /// and records a [`ReproducibilityManifest`].
+    pub fn with_deterministic(mut self, seed: u64) -> Self {
+        self.manifest = Some(Arc::new(ManifestRecorder {
+            manifest: Mutex::new(ReproducibilityManifest {
+                seed,
+                steps: BTreeMap::new(),
+            }),
+        }));
+        self
+    }
+
+    /// Whether the run is deterministic.
+    pub fn is_deterministic(&self) -> bool {
+        self.manifest.is_some()
+    }
+
+    /// Seed of a deterministic run.
+    pub(crate) fn seed(&self) -> Option<u64> {
+        self.manifest.as_ref().map(|recorder| recorder.manifest.lock().seed)
+    }
+
+    /// The manifest of a deterministic run, covering the LLM calls made so far.
+    pub fn manifest(&self) -> Option<ReproducibilityManifest> {
+        self.manifest.as_ref().map(|recorder| recorder.manifest.lock().clone())
+    }
+
+    /// Pins the sampling of an LLM request in deterministic mode; a seed set on the step is kept.
+    pub(crate) fn pin_sampling(&self, request: &mut CompletionRequest) {
+        if let Some(recorder) = &self.manifest {
+            let seed = recorder.manifest.lock().seed;
+            request.temperature = Some(0.0);
+            request.extra.entry("seed".to_string()).or_insert_with(|| Value::from(seed));
+        }
+    }
+
+    /// Records the LLM call that produced a step's outputs.
+    pub(crate) fn record_manifest(&self, step_id: &str, provider: &str, request: SentRequest, response: &CompletionResponse) {
+        let Some(recorder) = &self.manifest else {
+            return;
+        };
+        let reported = |key: &str| response.metadata.get(key).and_then(Value::as_str).map(str::to_string);
+        recorder.manifest.lock().steps.insert(
+            step_id.to_string(),
+            StepManifest {
+                provider: provider.to_string(),
+                model: request.model,
+                model_snapshot: reported("model_snapshot"),
+                system_fingerprint: reported("system_fingerprint"),
+                prompt_sha256: request.prompt_sha256,
+                temperature: request.temperature,
+                seed: request.seed,
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::{LLMProvider, ProviderError};
+    use crate::workflow::Workflow;
+    use std::collections::HashMap;
+
+    /// Provider that remembers the last request it received and reports a snapshot.
+    #[derive(Default)]
+    struct CapturingProvider {
+        last_request: Mutex<Option<CompletionRequest>>,
+    }
+
+    #[async_trait::async_trait]
+    impl LLMProvider for CapturingProvider {
+        async fn complete(&self, request: CompletionRequest) -> std::result::Result<CompletionResponse, ProviderError> {
+            *self.last_request.lock() = Some(request);
+            Ok(CompletionResponse {
+                text: "ok".to_string(),
+                model: "gpt-4o".to_string(),
+                tokens_used: None,
+                usage: None,
+                tool_calls: Vec::new(),
+                metadata: HashMap::from([
+                    ("model_snapshot".to_string(), Value::from("gpt-4o-2024-08-06")),
+                    ("system_fingerprint".to_string(), Value::from("fp_1234")),
+                ]),
+            })
+        }
+
+        fn name(&self) -> &str {
+            "capturing"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_deterministic_run_pins_sampling_and_records_manifest() {
+        let workflow = Workflow::from_yaml(
+            r#"
+name: "deterministic"
+steps:
+  - id: "answer"
+    type: "llm"
+    provider: "openai"
+    model: "gpt-4o"
+    system: "Be brief."
+    prompt: "Summarize {{ inputs.topic }}"
+    temperature: 0.9
+    output: ["text"]
+"#,
+        )
+        .unwrap();
+        let provider = Arc::new(CapturingProvider::default());
+        let executor = WorkflowExecutor::new(workflow, HashMap::from([("topic".to_string(), Value::from("rust"))]))
+            .unwrap()
+            .with_provider("openai", provider.clone())
+            .with_deterministic(7);
+
+        executor.execute().await.unwrap();
+
+        let request = provider.last_request.lock().clone().unwrap();
+        assert_eq!(request.temperature, Some(0.0));
+        assert_eq!(request.extra["seed"], 7);
+
+        let manifest = executor.manifest().unwrap();
+        assert_eq!(manifest.seed, 7);
+        let step = &manifest.steps["answer"];
+        assert_eq!(step.model, "gpt-4o");
+        assert_eq!(step.model_snapshot.as_deref(), Some("gpt-4o-2024-08-06"));
+        assert_eq!(step.system_fingerprint.as_deref(), Some("fp_1234"));
+        assert_eq!(step.prompt_sha256, prompt_hash(&request));
+        assert_eq!(step.temperature, Some(0.0));
+        assert_eq!(step.seed, Some(Value::from(7)));
+    }
+}
@@ -0,0 +1,759 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Validation diagnostics located in the workflow source.
+//!
+//! [`Workflow::diagnostics`] reports every problem of a parsed workflow, with
+//! a stable [`DiagnosticCode`], the step it concerns, and the path of the
+//! offending field (e.g. `steps[2].depends_on[0]`). [`check_yaml`] parses a
+//! workflow file as well and resolves those paths to lines and columns, so
+//! editors and CI can point at the exact spot:
+//!
+//! ```
+//! use llm_orchestrator_core::diagnostics::check_yaml;
+//!
+//! let source = "name: demo\nsteps:\n  - id: a\n    type: transform\n    function: noop\n    inputs: []\n    depends_on: [b]\n";
+//! let diagnostics = check_yaml(source).unwrap_err();
+//! assert_eq!(diagnostics[0].code.as_str(), "E0102");
+//! assert_eq!((diagnostics[0].line, diagnostics[0].column), (Some(7), Some(18)));
+//! ```
+
+use crate::include::{FileResolver, IncludeResolver};
+use crate::lint::LintRule;
+use crate::workflow::{load_yaml, LoadError, Workflow};
+use serde::{Serialize, Serializer};
+use std::collections::{HashMap, HashSet};
+use yaml_rust2::parser::{Event, MarkedEventReceiver, Parser};
+use yaml_rust2::scanner::Marker;
+
+/// Kind of problem found in a workflow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DiagnosticCode {
+    /// The file is not valid YAML or does not match the workflow format.
+    Parse,
+    /// The workflow has no steps.
+    NoSteps,
+    /// Two steps share an ID.
+    DuplicateStepId,
+    /// A step depends on a step that does not exist.
+    UnknownDependency,
+    /// Steps depend on each other in a cycle.
+    DependencyCycle,
+    /// An approval's `on_reject` step does not depend on the approval.
+    InvalidRejectionBranch,
+    /// A `run_step` fallback does not depend on the step it replaces.
+    InvalidFallback,
+    /// A step template is invalid, unknown, or instantiated with wrong arguments.
+    InvalidTemplate,
+    /// An included file cannot be read or is included in a cycle.
+    InvalidInclude,
+    /// A step waits on a dependency cycle, so no root step leads to it.
+    UnreachableStep,
+    /// A step binds a name to a value it cannot read.
+    InvalidBinding,
+    /// Two variants of an LLM step share an ID.
+    DuplicateVariant,
+    /// A variant weight is negative or not finite.
+    InvalidVariantWeight,
+    /// No variant of an LLM step has a positive weight.
+    NoWeightedVariant,
+    /// A hedge percentile is outside (0, 100].
+    InvalidHedgePercentile,
+    /// A `prompt_ref` cannot be parsed.
+    InvalidPromptRef,
+    /// An LLM step sets both `prompt` and `prompt_ref`.
+    ConflictingPrompt,
+    /// An LLM step compacts its conversation with no budget or an invalid workflow.
+    InvalidCompaction,
+    /// A partial does not compile, cannot be read, or is rendered without being defined.
+    InvalidPartial,
+    /// An evaluate step has neither metrics nor a judge.
+    MissingEvaluation,
+    /// An HTTP step uses a method that is not supported.
+    UnsupportedHttpMethod,
+    /// An HTTP step sets both `body` and `form`.
+    ConflictingHttpBody,
+    /// An HTTP step extracts an output with an invalid JSONPath.
+    InvalidJsonPath,
+    /// A web fetch step has no concurrency, timeout, or size allowance.
+    InvalidWebFetch,
+    /// A trigger cannot be served.
+    InvalidTrigger,
+    /// Two triggers share a webhook name or chat model.
+    DuplicateTrigger,
+    /// An MCP tool step does not name a server and a tool.
+    IncompleteMcpTool,
+    /// An agent step has no tools or iterations, or names two tools alike.
+    InvalidAgent,
+    /// A vector search queries with embeddings of another dimension than its index.
+    DimensionMismatch,
+    /// A vector upsert step's batch size, concurrency, or rate is not positive.
+    InvalidUpsertLimit,
+    /// A keyword or hybrid vector search lacks its text or has invalid options.
+    InvalidSearchMode,
+    /// A result sink has an invalid target.
+    InvalidSink,
+    /// An artifact store names no directory or bucket.
+    InvalidArtifactStore,
+    /// A step assertion is not a valid expression.
+    InvalidAssertion,
+    /// A lint rule found a likely mistake.
+    Lint(LintRule),
+}
+
+impl DiagnosticCode {
+    /// The code, e.g. `E0102`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Parse => "E0001",
+            Self::NoSteps => "E0100",
+            Self::DuplicateStepId => "E0101",
+            Self::UnknownDependency => "E0102",
+            Self::DependencyCycle => "E0103",
+            Self::InvalidRejectionBranch => "E0104",
+            Self::InvalidFallback => "E0105",
+            Self::InvalidTemplate => "E0106",
+            Self::InvalidInclude => "E0107",
+            Self::UnreachableStep => "E0108",
+            Self::InvalidBinding => "E0109",
+            Self::DuplicateVariant => "E0110",
+            Self::InvalidVariantWeight => "E0111",
+            Self::NoWeightedVariant => "E0112",
+            Self::InvalidHedgePercentile => "E0113",
+            Self::InvalidPromptRef => "E0114",
+            Self::ConflictingPrompt => "E0115",
+            Self::InvalidCompaction => "E0116",
+            Self::InvalidPartial => "E0117",
+            Self::MissingEvaluation => "E0120",
+            Self::UnsupportedHttpMethod => "E0130",
+            Self::ConflictingHttpBody => "E0131",
+            Self::InvalidJsonPath => "E0132",
+            Self::InvalidWebFetch => "E0133",
+            Self::InvalidTrigger => "E0140",
+            Self::DuplicateTrigger => "E0141",
+            Self::IncompleteMcpTool => "E0150",
+            Self::InvalidAgent => "E0151",
+            Self::DimensionMismatch => "E0160",
+            Self::InvalidUpsertLimit => "E0161",
+            Self::InvalidSearchMode => "E0162",
+            Self::InvalidSink => "E0170",
+            Self::InvalidArtifactStore => "E0171",
+            Self::InvalidAssertion => "E0180",
+            Self::Lint(rule) => rule.code(),
+        }
+    }
+}
+
+impl std::fmt::Display for DiagnosticCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for DiagnosticCode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// How serious a diagnostic is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// The workflow cannot run as written.
+    Error,
+    /// The workflow runs, but likely not as intended.
+    Warning,
+}
+
+/// A problem found in a workflow.
+#[derive(Debug, Clone, Serialize)]
+pub struct Diagnostic {
+    /// Kind of problem.
+    pub code: DiagnosticCode,
+
+    /// Whether the problem fails the check.
+    pub severity: Severity,
+
+    /// What is wrong.
+    pub message: String,
+
+    /// Step the problem concerns.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub step_id: Option<String>,
+
+    /// Path of the offending field, e.g. `steps[2].depends_on[0]`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+
+    /// Line in the source, starting at 1.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+
+    /// Column in the source, starting at 1.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<usize>,
+
+    /// How the problem might be fixed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<String>,
+}
+
+impl Diagnostic {
+    pub(crate) fn new(code: DiagnosticCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            severity: Severity::Error,
+            message: message.into(),
+            step_id: None,
+            path: None,
+            line: None,
+            column: None,
+            suggestion: None,
+        }
+    }
+
+    pub(crate) fn at(mut self, path: impl Into<String>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    pub(crate) fn step(mut self, step_id: &str) -> Self {
+        self.step_id = Some(step_id.to_string());
+        self
+    }
+
+    pub(crate) fn suggest(mut self, suggestion: impl Into<String>) -> Self {
+        self.suggestion = Some(suggestion.into());
+        self
+    }
+
+    pub(crate) fn severity(mut self, severity: Severity) -> Self {
+        self.severity = severity;
+        self
+    }
+}
+
+/// Parses a workflow file and checks it.
+///
+/// Returns every problem found, located in `source`; dependency cycles and
+/// the steps they make unreachable are reported too, which
+/// [`Workflow::validate`] leaves to the DAG.
+///
+/// Included files are read relative to the working directory.
+pub fn check_yaml(source: &str) -> std::result::Result<Workflow, Vec<Diagnostic>> {
+    check_yaml_with(source, &FileResolver::current_dir())
+}
+
+/// Like [`check_yaml`], reading included files with `resolver`.
+pub fn check_yaml_with(source: &str, resolver: &dyn IncludeResolver) -> std::result::Result<Workflow, Vec<Diagnostic>> {
+    check(source, resolver).map(|(workflow, _)| workflow)
+}
+
+/// Checks a workflow file, also returning whether its includes or templates
+/// rewrote it, so that paths in the workflow no longer match the source.
+pub(crate) fn check(
+    source: &str,
+    resolver: &dyn IncludeResolver,
+) -> std::result::Result<(Workflow, bool), Vec<Diagnostic>> {
+    let (workflow, rewritten) = match load_yaml(source, resolver) {
+        Ok(loaded) => loaded,
+        Err(LoadError::Syntax(e)) => return Err(vec![parse_diagnostic(&e)]),
+        Err(LoadError::Invalid(diagnostic)) => {
+            let mut diagnostics = vec![*diagnostic];
+            locate(source, &mut diagnostics);
+            return Err(diagnostics);
+        }
+    };
+
+    let mut diagnostics = workflow.diagnostics();
+    diagnostics.extend(dependency_cycles(&workflow));
+    if diagnostics.is_empty() {
+        return Ok((workflow, rewritten));
+    }
+
+    if !rewritten {
+        locate(source, &mut diagnostics);
+    }
+    Err(diagnostics)
+}
+
+/// Resolves the paths of `diagnostics` to their lines and columns in `source`.
+pub(crate) fn locate(source: &str, diagnostics: &mut [Diagnostic]) {
+    let positions = SourceMap::parse(source);
+    for diagnostic in diagnostics {
+        if let Some((line, column)) = diagnostic.path.as_deref().and_then(|path| positions.locate(path)) {
+            diagnostic.line = Some(line);
+            diagnostic.column = Some(column);
+        }
+    }
+}
+
+/// Describes a YAML or deserialization error.
+fn parse_diagnostic(error: &serde_yaml::Error) -> Diagnostic {
+    // serde_yaml renders errors as `{path}: {message} at line {line} column {column}`
+    let mut message = error.to_string();
+    if let Some(location) = error.location() {
+        let suffix = format!(" at line {} column {}", location.line(), location.column());
+        if let Some(stripped) = message.strip_suffix(&suffix) {
+            message = stripped.to_string();
+        }
+    }
+    let mut path = None;
+    if let Some((prefix, rest)) = message.split_once(": ") {
+        if !prefix.contains(' ') {
+            path = Some(prefix.to_string());
+            message = rest.to_string();
+        }
+    }
+
+    let mut diagnostic = Diagnostic::new(DiagnosticCode::Parse, message.clone());
+    diagnostic.path = path;
+    diagnostic.line = error.location().map(|l| l.line());
+    diagnostic.column = error.location().map(|l| l.column());
+    if let Some(suggestion) = parse_suggestion(&message) {
+        diagnostic = diagnostic.suggest(suggestion);
+    }
+    diagnostic
+}
+
+/// Suggests a fix for a deserialization error.
+fn parse_suggestion(message: &str) -> Option<String> {
+    // `unknown variant `lm`, expected one of `llm`, `embed`, ...`
+    if let Some(rest) = message.strip_prefix("unknown variant `") {
+        let (word, expected) = rest.split_once('`')?;
+        let candidates: Vec<&str> = expected.split('`').skip(1).step_by(2).collect();
+        return did_you_mean(word, candidates);
+    }
+    if message.starts_with("data did not match any variant of untagged enum StepConfig") {
+        return Some("Check the fields required by the step's type (see `llm-orchestrator schema`)".to_string());
+    }
+    None
+}
+
+/// Reports every dependency cycle, naming its steps, then every step that
+/// waits on a cycle without being part of one.
+///
+/// Like the scheduler, both follow `depends_on_any` alternatives as well as
+/// `depends_on`.
+fn dependency_cycles(workflow: &Workflow) -> Vec<Diagnostic> {
+    let index: HashMap<&str, usize> = workflow.steps.iter().enumerate().map(|(i, s)| (s.id.as_str(), i)).collect();
+    // Dependencies of each step with the field and position listing them
+    let edges: Vec<Vec<(&str, usize, &str)>> = workflow
+        .steps
+        .iter()
+        .map(|step| {
+            let all = step.depends_on.iter().enumerate().map(|(j, dep)| ("depends_on", j, dep.as_str()));
+            let any = step.depends_on_any.iter().enumerate().map(|(j, dep)| ("depends_on_any", j, dep.as_str()));
+            all.chain(any).collect()
+        })
+        .collect();
+    let dependencies = |step: usize, field: &'static str| {
+        edges[step]
+            .iter()
+            .filter(move |&&(f, _, _)| f == field)
+            .filter_map(|&(_, j, dep)| index.get(dep).map(|&dependency| (j, dependency)))
+    };
+
+    let mut diagnostics = Vec::new();
+    let mut cycles: HashSet<Vec<usize>> = HashSet::new();
+    let mut in_cycle = HashSet::new();
+    let mut done = HashSet::new();
+    for start in 0..workflow.steps.len() {
+        // Depth-first search keeping the current chain of steps
+        let mut chain: Vec<(usize, usize)> = vec![(start, 0)];
+        while let Some(&(step, next)) = chain.last() {
+            if next == edges[step].len() || done.contains(&step) {
+                done.insert(step);
+                chain.pop();
+                continue;
+            }
+            chain.last_mut().expect("chain is not empty").1 += 1;
+            let (field, j, name) = edges[step][next];
+            let Some(&dependency) = index.get(name) else {
+                continue;
+            };
+            if let Some(position) = chain.iter().position(|&(s, _)| s == dependency) {
+                let mut members: Vec<usize> = chain[position..].iter().map(|&(s, _)| s).collect();
+                members.sort_unstable();
+                if !cycles.insert(members) {
+                    continue;
+                }
+                let mut ids: Vec<&str> = chain[position..].iter().map(|&(s, _)| workflow.steps[s].id.as_str()).collect();
+                ids.push(&workflow.steps[dependency].id);
+                in_cycle.extend(chain[position..].iter().map(|&(s, _)| s));
+                let id = &workflow.steps[step].id;
+                diagnostics.push(
+                    Diagnostic::new(
+                        DiagnosticCode::DependencyCycle,
+                        format!("Steps depend on each other in a cycle: {}", ids.join(" -> ")),
+                    )
+                    .step(id)
+                    .at(format!("steps[{}].{}[{}]", step, field, j))
+                    .suggest(format!("Remove '{}' from the dependencies of '{}'", name, id)),
+                );
+                continue;
+            }
+            chain.push((dependency, 0));
+        }
+    }
+    if cycles.is_empty() {
+        return diagnostics;
+    }
+
+    // Steps that never become ready once every runnable step has run wait on
+    // a cycle; a join is ready once one of its alternatives is
+    let mut ready: HashSet<usize> = HashSet::new();
+    loop {
+        let before = ready.len();
+        for step in 0..workflow.steps.len() {
+            let mut alternatives = dependencies(step, "depends_on_any").peekable();
+            if !ready.contains(&step)
+                && dependencies(step, "depends_on").all(|(_, dependency)| ready.contains(&dependency))
+                && (alternatives.peek().is_none() || alternatives.any(|(_, dependency)| ready.contains(&dependency)))
+            {
+                ready.insert(step);
+            }
+        }
+        if ready.len() == before {
+            break;
+        }
+    }
+    for step in (0..workflow.steps.len()).filter(|step| !ready.contains(step) && !in_cycle.contains(step)) {
+        let blocking = ["depends_on", "depends_on_any"].into_iter().find_map(|field| {
+            let (j, dependency) = dependencies(step, field).find(|(_, dependency)| !ready.contains(dependency))?;
+            Some((field, j, dependency))
+        });
+        let Some((field, j, dependency)) = blocking else {
+            continue;
+        };
+        let reason = if in_cycle.contains(&dependency) { "is part of" } else { "waits on" };
+        let id = &workflow.steps[step].id;
+        diagnostics.push(
+            Diagnostic::new(
+                DiagnosticCode::UnreachableStep,
+                format!(
+                    "Step '{}' is unreachable from any root step: it depends on '{}', which {} a dependency cycle",
+                    id, workflow.steps[dependency].id, reason
+                ),
+            )
+            .step(id)
+            .at(format!("steps[{}].{}[{}]", step, field, j)),
+        );
+    }
+    diagnostics
+}
+
+/// Suggests the candidate closest to `word`, if one is close enough to be a typo.
+pub(crate) fn did_you_mean<'a>(word: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<String> {
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(word, candidate), candidate))
+        .filter(|&(distance, candidate)| distance > 0 && distance <= (candidate.chars().count() / 3).max(1))
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, candidate)| format!("Did you mean '{}'?", candidate))
+}
+
+/// Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// Positions of the nodes of a YAML document, keyed by path.
+///
+/// A mapping value is positioned at its key, so a diagnostic about a field
+/// points at the field's name.
+#[derive(Default)]
+struct SourceMap {
+    positions: HashMap<String, (usize, usize)>,
+    // Open mappings and sequences: their path, and the key or index of the next value
+    stack: Vec<(String, Frame)>,
+}
+
+enum Frame {
+    Mapping(Option<String>),
+    Sequence(usize),
+}
+
+impl SourceMap {
+    /// Maps the nodes of `source`; a document that fails to parse is mapped up to the error.
+    fn parse(source: &str) -> Self {
+        let mut map = Self::default();
+        let _ = Parser::new_from_str(source).load(&mut map, false);
+        map
+    }
+
+    /// Position of the node at `path`, or of its closest mapped ancestor.
+    fn locate(&self, path: &str) -> Option<(usize, usize)> {
+        let mut path = path;
+        loop {
+            if let Some(&position) = self.positions.get(path) {
+                return Some(position);
+            }
+            path = &path[..path.rfind(['.', '['])?];
+        }
+    }
+
+    /// Path of the node starting at `mark`, recording its position; `None` for a mapping key.
+    fn enter(&mut self, mark: Marker, key: Option<&str>) -> Option<String> {
+        let position = (mark.line(), mark.col() + 1);
+        let Some((parent, frame)) = self.stack.last_mut() else {
+            self.positions.insert(String::new(), position);
+            return Some(String::new());
+        };
+        let path = match frame {
+            Frame::Mapping(pending) => match pending.take() {
+                Some(key) => join(parent, &key),
+                None => {
+                    // A key: the value that follows is positioned here
+                    let key = key.unwrap_or_default().to_string();
+                    self.positions.insert(join(parent, &key), position);
+                    *pending = Some(key);
+                    return None;
+                }
+            },
+            Frame::Sequence(index) => {
+                let path = format!("{}[{}]", parent, index);
+                *index += 1;
+                self.positions.insert(path.clone(), position);
+                path
+            }
+        };
+        Some(path)
+    }
+}
+
+fn join(parent: &str, key: &str) -> String {
+    if parent.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", parent, key)
+    }
+}
+
+impl MarkedEventReceiver for SourceMap {
+    fn on_event(&mut self, event: Event, mark: Marker) {
+        match event {
+            Event::Scalar(value, ..) => {
+                self.enter(mark, Some(&value));
+            }
+            Event::Alias(_) => {
+                self.enter(mark, None);
+            }
+            Event::MappingStart(..) => {
+                if let Some(path) = self.enter(mark, None) {
+                    self.stack.push((path, Frame::Mapping(None)));
+                }
+            }
+            Event::SequenceStart(..) => {
+                if let Some(path) = self.enter(mark, None) {
+                    self.stack.push((path, Frame::Sequence(0)));
+                }
+            }
+            Event::MappingEnd | Event::SequenceEnd => {
+                self.stack.pop();
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WORKFLOW: &str = r#"name: "diagnostics"
+steps:
+  - id: "fetch"
+    type: "http"
+    method: "FETCH"
+    url: "https://example.com"
+  - id: "summarize"
+    type: "transform"
+    function: "noop"
+    inputs: []
+    depends_on: ["fetch", "fetcch"]
+  - id: "fetch"
+    type: "transform"
+    function: "noop"
+    inputs: []
+"#;
+
+    #[test]
+    fn test_diagnostics_are_located() {
+        let diagnostics = check_yaml(WORKFLOW).unwrap_err();
+        let codes: Vec<&str> = diagnostics.iter().map(|d| d.code.as_str()).collect();
+        assert_eq!(codes, ["E0101", "E0102", "E0130"]);
+
+        let duplicate = &diagnostics[0];
+        assert_eq!(duplicate.step_id.as_deref(), Some("fetch"));
+        assert_eq!(duplicate.path.as_deref(), Some("steps[2].id"));
+        assert_eq!((duplicate.line, duplicate.column), (Some(12), Some(5)));
+
+        let dependency = &diagnostics[1];
+        assert_eq!(dependency.path.as_deref(), Some("steps[1].depends_on[1]"));
+        assert_eq!((dependency.line, dependency.column), (Some(11), Some(27)));
+        assert_eq!(dependency.suggestion.as_deref(), Some("Did you mean 'fetch'?"));
+
+        let method = &diagnostics[2];
+        assert_eq!((method.line, method.column), (Some(5), Some(5)));
+    }
+
+    #[test]
+    fn test_parse_errors_and_cycles() {
+        let source = "name: demo\nsteps:\n  - id: a\n    type: transfrom\n";
+        let diagnostic = &check_yaml(source).unwrap_err()[0];
+        assert_eq!(diagnostic.code, DiagnosticCode::Parse);
+        assert_eq!(diagnostic.path.as_deref(), Some("steps[0].type"));
+        assert_eq!(diagnostic.line, Some(4));
+        assert_eq!(diagnostic.suggestion.as_deref(), Some("Did you mean 'transform'?"));
+
+        let source = r#"
+name: cycle
+steps:
+  - { id: a, type: transform, function: noop, inputs: [], depends_on: [c] }
+  - { id: b, type: transform, function: noop, inputs: [], depends_on: [a] }
+  - { id: c, type: transform, function: noop, inputs: [], depends_on: [b] }
+"#;
+        let diagnostic = &check_yaml(source).unwrap_err()[0];
+        assert_eq!(diagnostic.code, DiagnosticCode::DependencyCycle);
+        assert!(diagnostic.message.ends_with("a -> c -> b -> a"), "{}", diagnostic.message);
+        assert_eq!(diagnostic.step_id.as_deref(), Some("b"));
+        assert_eq!(diagnostic.line, Some(5));
+    }
+
+    #[test]
+    fn test_graph_problems_are_reported_in_one_pass() {
+        let source = r#"
+name: graph
+steps:
+  - { id: a, type: transform, function: noop, inputs: [], depends_on: [b] }
+  - { id: b, type: transform, function: noop, inputs: [], depends_on: [a] }
+  - { id: c, type: transform, function: noop, inputs: [], depends_on: [d] }
+  - { id: d, type: transform, function: noop, inputs: [], depends_on: [c] }
+  - { id: after, type: transform, function: noop, inputs: [], depends_on: [root, a] }
+  - { id: later, type: transform, function: noop, inputs: [], depends_on: [after] }
+  - id: root
+    type: parallel
+    tasks:
+      - { id: c, type: transform, function: noop, inputs: [] }
+"#;
+        let diagnostics = check_yaml(source).unwrap_err();
+        let summary: Vec<(&str, &str)> = diagnostics
+            .iter()
+            .map(|d| (d.code.as_str(), d.message.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("E0101", "Step 'c' nested in 'root' has the same ID as a top-level step"),
+                ("E0103", "Steps depend on each other in a cycle: a -> b -> a"),
+                ("E0103", "Steps depend on each other in a cycle: c -> d -> c"),
+                ("E0108", "Step 'after' is unreachable from any root step: it depends on 'a', which is part of a dependency cycle"),
+                ("E0108", "Step 'later' is unreachable from any root step: it depends on 'after', which waits on a dependency cycle"),
+            ]
+        );
+        assert_eq!(diagnostics[0].path.as_deref(), Some("steps[6].tasks[0].id"));
+        assert_eq!(diagnostics[3].path.as_deref(), Some("steps[4].depends_on[1]"));
+        assert_eq!(diagnostics[3].line, Some(8));
+    }
+
+    #[test]
+    fn test_cycles_through_joins_are_reported() {
+        let source = r#"
+name: joins
+steps:
+  - { id: a, type: transform, function: noop, inputs: [], depends_on: [join] }
+  - { id: b, type: transform, function: noop, inputs: [] }
+  - { id: join, type: transform, function: noop, inputs: [], depends_on_any: [a, b] }
+  - { id: c, type: transform, function: noop, inputs: [], depends_on: [d] }
+  - { id: d, type: transform, function: noop, inputs: [], depends_on_any: [c] }
+  - { id: tail, type: transform, function: noop, inputs: [], depends_on_any: [c, d] }
+"#;
+        let diagnostics = check_yaml(source).unwrap_err();
+        let summary: Vec<(&str, &str, &str)> = diagnostics
+            .iter()
+            .map(|d| (d.code.as_str(), d.message.as_str(), d.path.as_deref().unwrap()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("E0103", "Steps depend on each other in a cycle: a -> join -> a", "steps[2].depends_on_any[0]"),
+                ("E0103", "Steps depend on each other in a cycle: c -> d -> c", "steps[4].depends_on_any[0]"),
+                (
+                    "E0108",
+                    "Step 'tail' is unreachable from any root step: it depends on 'c', which is part of a dependency cycle",
+                    "steps[5].depends_on_any[0]"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_embedding_dimension_mismatch() {
+        let source = r#"name: rag
+steps:
+  - id: embed
+    type: embed
+    provider: openai
+    model: text-embedding-3-large
+    input: "{{ inputs.query }}"
+  - id: search
+    type: vector_search
+    depends_on: [embed]
+    database: pinecone
+    index: docs
+    query: "{{ steps.embed.embedding }}"
+    dimensions: 1536
+"#;
+        let diagnostic = &check_yaml(source).unwrap_err()[0];
+        assert_eq!(diagnostic.code, DiagnosticCode::DimensionMismatch);
+        assert_eq!(diagnostic.path.as_deref(), Some("steps[1].dimensions"));
+        assert_eq!(diagnostic.line, Some(14));
+        assert!(diagnostic.message.contains("3072 dimensions"), "{}", diagnostic.message);
+
+        let shortened = source.replace("    input:", "    dimensions: 1536\n    input:");
+        assert!(check_yaml(&shortened).is_ok());
+    }
+
+    #[test]
+    fn test_hybrid_search_options() {
+        let source = r#"name: rag
+steps:
+  - id: search
+    type: vector_search
+    database: weaviate
+    index: Docs
+    query: "{{ inputs.vector }}"
+    mode: hybrid
+    alpha: 1.5
+"#;
+        let diagnostics = check_yaml(source).unwrap_err();
+        let paths: Vec<_> = diagnostics.iter().map(|d| (d.code, d.path.as_deref())).collect();
+        assert_eq!(
+            paths,
+            [
+                (DiagnosticCode::InvalidSearchMode, Some("steps[0]")),
+                (DiagnosticCode::InvalidSearchMode, Some("steps[0].alpha")),
+            ]
+        );
+
+        let fixed = source.replace("alpha: 1.5", "alpha: 0.5\n    text: \"{{ inputs.query }}\"");
+        assert!(check_yaml(&fixed).is_ok());
+
+        // Keyword searches take their text from `query` and skip the dimension check
+        let keyword = source.replace("mode: hybrid\n    alpha: 1.5", "mode: keyword");
+        assert!(check_yaml(&keyword).is_ok());
+    }
+}
@@ -0,0 +1,330 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Document loading for ingestion.
+//!
+//! A [`DocumentLoader`] turns the bytes of a file into normalized text and
+//! metadata, ready for the `split` transform. Plain text and Markdown are
+//! always supported; PDF, DOCX, and HTML need the `pdf`, `docx`, and `html`
+//! features, which pull in their parsers.
+//!
+//! Workflows load documents with the `document.load` action, served by a
+//! [`LoadDocumentAction`] that reads files under one directory:
+//!
+//! ```yaml
+//! - id: load
+//!   type: action
+//!   action: document.load
+//!   path: "{{ inputs.file }}"       # Relative to the action's directory
+//!   format: pdf                     # Defaults to the file extension
+//!
+//! - id: split
+//!   type: transform
+//!   function: split
+//!   inputs: [steps.load.text]
+//! ```
+
+use crate::error::{OrchestratorError, Result};
+use crate::notify::{action_params, ActionHandler};
+use crate::transform::text::{markdown_to_text, normalize_text, NormalizationForm};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use thiserror::Error;
+
+#[cfg(feature = "docx")]
+mod docx;
+#[cfg(feature = "html")]
+mod html;
+#[cfg(feature = "pdf")]
+mod pdf;
+
+#[cfg(feature = "docx")]
+pub use docx::DocxLoader;
+#[cfg(feature = "html")]
+pub use html::HtmlLoader;
+#[cfg(feature = "pdf")]
+pub use pdf::PdfLoader;
+
+/// Action that loads a document.
+pub const LOAD_ACTION: &str = "document.load";
+
+/// Text of a document and what is known about it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Document {
+    /// Text, with paragraphs separated by blank lines.
+    pub text: String,
+
+    /// Metadata such as `format`, `source`, `title`, or `pages`.
+    #[serde(default)]
+    pub metadata: Map<String, Value>,
+}
+
+impl Document {
+    /// A document with `text` and no metadata.
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            metadata: Map::new(),
+        }
+    }
+
+    /// Sets a metadata field.
+    pub fn with_metadata(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.metadata.insert(key.into(), value.into());
+        self
+    }
+}
+
+/// A document that could not be loaded.
+#[derive(Debug, Error)]
+pub enum DocumentError {
+    /// No loader handles the format.
+    #[error("Unsupported document format: {0}")]
+    Unsupported(String),
+
+    /// The file could not be read.
+    #[error("Failed to read document: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The file is not a valid document of its format.
+    #[error("Failed to parse {format} document: {message}")]
+    Parse { format: &'static str, message: String },
+}
+
+/// Parses documents of some formats.
+pub trait DocumentLoader: Send + Sync {
+    /// Formats loaded, as lowercase file extensions.
+    fn formats(&self) -> &[&'static str];
+
+    /// Parses the bytes of a document.
+    fn load(&self, bytes: &[u8]) -> std::result::Result<Document, DocumentError>;
+}
+
+/// Loads UTF-8 text, replacing invalid sequences.
+pub struct TextLoader;
+
+impl DocumentLoader for TextLoader {
+    fn formats(&self) -> &[&'static str] {
+        &["txt", "text"]
+    }
+
+    fn load(&self, bytes: &[u8]) -> std::result::Result<Document, DocumentError> {
+        Ok(Document::new(String::from_utf8_lossy(bytes)))
+    }
+}
+
+/// Loads Markdown as plain text, titled by its first heading.
+pub struct MarkdownLoader;
+
+impl DocumentLoader for MarkdownLoader {
+    fn formats(&self) -> &[&'static str] {
+        &["md", "markdown"]
+    }
+
+    fn load(&self, bytes: &[u8]) -> std::result::Result<Document, DocumentError> {
+        let markdown = String::from_utf8_lossy(bytes);
+        let title = markdown
+            .lines()
+            .find_map(|line| line.strip_prefix("# "))
+            .map(|title| title.trim().to_string());
+        let document = Document::new(markdown_to_text(&markdown));
+        Ok(match title {
+            Some(title) => document.with_metadata("title", title),
+            None => document,
+        })
+    }
+}
+
+/// Loaders by format.
+///
+/// [`DocumentLoaders::default`] has every loader compiled in; a loader added
+/// later takes over the formats it shares with earlier ones.
+#[derive(Clone)]
+pub struct DocumentLoaders {
+    loaders: Vec<Arc<dyn DocumentLoader>>,
+}
+
+impl Default for DocumentLoaders {
+    fn default() -> Self {
+        let loaders = Self::new().with_loader(Arc::new(TextLoader)).with_loader(Arc::new(MarkdownLoader));
+        #[cfg(feature = "pdf")]
+        let loaders = loaders.with_loader(Arc::new(PdfLoader));
+        #[cfg(feature = "docx")]
+        let loaders = loaders.with_loader(Arc::new(DocxLoader));
+        #[cfg(feature = "html")]
+        let loaders = loaders.with_loader(Arc::new(HtmlLoader));
+        loaders
+    }
+}
+
+impl DocumentLoaders {
+    /// No loaders.
+    pub fn new() -> Self {
+        Self { loaders: Vec::new() }
+    }
+
+    /// Adds a loader.
+    pub fn with_loader(mut self, loader: Arc<dyn DocumentLoader>) -> Self {
+        self.loaders.push(loader);
+        self
+    }
+
+    /// Formats some loader handles.
+    pub fn formats(&self) -> Vec<&'static str> {
+        let mut formats: Vec<_> = self.loaders.iter().flat_map(|loader| loader.formats().iter().copied()).collect();
+        formats.sort_unstable();
+        formats.dedup();
+        formats
+    }
+
+    /// Parses a document of `format`, normalizing its text to NFC with tidy whitespace.
+    pub fn load(&self, bytes: &[u8], format: &str) -> std::result::Result<Document, DocumentError> {
+        let format = format.trim_start_matches('.').to_ascii_lowercase();
+        let loader = self
+            .loaders
+            .iter()
+            .rev()
+            .find(|loader| loader.formats().contains(&format.as_str()))
+            .ok_or_else(|| DocumentError::Unsupported(format.clone()))?;
+        let mut document = loader.load(bytes)?;
+        document.text = normalize_text(&document.text, NormalizationForm::Nfc);
+        document.metadata.insert("format".to_string(), json!(format));
+        document.metadata.insert("characters".to_string(), json!(document.text.chars().count()));
+        Ok(document)
+    }
+
+    /// Reads and parses a file, of the format of its extension unless `format` is given.
+    pub async fn load_file(&self, path: &Path, format: Option<&str>) -> std::result::Result<Document, DocumentError> {
+        let format = match format {
+            Some(format) => format.to_string(),
+            None => path
+                .extension()
+                .and_then(|extension| extension.to_str())
+                .ok_or_else(|| DocumentError::Unsupported(path.display().to_string()))?
+                .to_string(),
+        };
+        let bytes = tokio::fs::read(path).await?;
+        let mut document = self.load(&bytes, &format)?;
+        document.metadata.insert("source".to_string(), json!(path.display().to_string()));
+        Ok(document)
+    }
+}
+
+/// Parameters of the `document.load` action.
+#[derive(Deserialize)]
+struct LoadParams {
+    path: String,
+    format: Option<String>,
+}
+
+/// Serves the `document.load` action from files under one directory.
+///
+/// Outputs the document's `text` and `metadata`. Paths are relative to the
+/// directory, and may not lead out of it.
+pub struct LoadDocumentAction {
+    root: PathBuf,
+    loaders: DocumentLoaders,
+}
+
+impl LoadDocumentAction {
+    /// Loads documents under `root` with the default loaders.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            loaders: DocumentLoaders::default(),
+        }
+    }
+
+    /// Uses other loaders.
+    pub fn with_loaders(mut self, loaders: DocumentLoaders) -> Self {
+        self.loaders = loaders;
+        self
+    }
+
+    /// The file `path` names, if it is under the root.
+    async fn resolve(&self, path: &str) -> Result<PathBuf> {
+        let root = tokio::fs::canonicalize(&self.root)
+            .await
+            .map_err(|e| OrchestratorError::other(format!("Document directory {}: {}", self.root.display(), e)))?;
+        let file = tokio::fs::canonicalize(root.join(path))
+            .await
+            .map_err(|e| OrchestratorError::other(format!("Document '{}': {}", path, e)))?;
+        if !file.starts_with(&root) {
+            return Err(OrchestratorError::other(format!("Document '{}' is outside the document directory", path)));
+        }
+        Ok(file)
+    }
+}
+
+#[async_trait]
+impl ActionHandler for LoadDocumentAction {
+    async fn execute(&self, step_id: &str, params: Value) -> Result<HashMap<String, Value>> {
+        let params: LoadParams = action_params(step_id, LOAD_ACTION, params)?;
+        let path = self.resolve(&params.path).await?;
+        let mut document = self
+            .loaders
+            .load_file(&path, params.format.as_deref())
+            .await
+            .map_err(|e| OrchestratorError::other(e.to_string()))?;
+        // Report the path as given, not where the directory happens to be
+        document.metadata.insert("source".to_string(), json!(params.path));
+        Ok(HashMap::from([
+            ("text".to_string(), Value::String(document.text)),
+            ("metadata".to_string(), Value::Object(document.metadata)),
+        ]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::WorkflowExecutor;
+    use crate::workflow::Workflow;
+
+    #[test]
+    fn test_load_by_format() {
+        let loaders = DocumentLoaders::default();
+        let document = loaders.load(b"# Guide\n\nCafe\xCC\x81  menu\n", "MD").unwrap();
+        assert_eq!(document.text, "Guide\n\nCaf\u{e9} menu");
+        assert_eq!(document.metadata["title"], json!("Guide"));
+        assert_eq!(document.metadata["format"], json!("md"));
+        assert!(matches!(loaders.load(b"", "xyz"), Err(DocumentError::Unsupported(_))));
+    }
+
+    #[tokio::test]
+    async fn test_load_action_stays_in_its_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("docs");
+        std::fs::create_dir(&root).unwrap();
+        std::fs::write(root.join("notes.txt"), "First line\r\nSecond   line").unwrap();
+        std::fs::write(dir.path().join("secret.txt"), "hidden").unwrap();
+
+        let workflow = Workflow::from_yaml(
+            r#"
+name: "ingest"
+steps:
+  - id: "load"
+    type: "action"
+    action: "document.load"
+    path: "{{ inputs.file }}"
+"#,
+        )
+        .unwrap();
+        let run = |file: &str| {
+            let inputs = HashMap::from([("file".to_string(), json!(file))]);
+            let executor = WorkflowExecutor::new(workflow.clone(), inputs)
+                .unwrap()
+                .with_action_handler(LOAD_ACTION, Arc::new(LoadDocumentAction::new(&root)));
+            async move { executor.execute().await }
+        };
+
+        let results = run("notes.txt").await.unwrap();
+        assert_eq!(results["load"].outputs["text"], json!("First line\nSecond line"));
+        assert_eq!(results["load"].outputs["metadata"]["source"], json!("notes.txt"));
+        assert!(run("../secret.txt").await.is_err());
+    }
+}
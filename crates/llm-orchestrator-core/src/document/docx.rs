@@ -0,0 +1,124 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Word (DOCX) documents.
+
+use super::{Document, DocumentError, DocumentLoader};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::io::{Cursor, Read};
+use zip::ZipArchive;
+
+/// Loads the text of Word documents, a paragraph per line, with the title
+/// and author of their properties.
+pub struct DocxLoader;
+
+fn parse_error(message: impl ToString) -> DocumentError {
+    DocumentError::Parse {
+        format: "DOCX",
+        message: message.to_string(),
+    }
+}
+
+/// Reads a part of the package, if it exists.
+fn read_part(archive: &mut ZipArchive<Cursor<&[u8]>>, name: &str) -> std::result::Result<Option<String>, DocumentError> {
+    let mut file = match archive.by_name(name) {
+        Ok(file) => file,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(parse_error(e)),
+    };
+    let mut xml = String::new();
+    file.read_to_string(&mut xml)?;
+    Ok(Some(xml))
+}
+
+/// Text of the body of `word/document.xml`.
+fn body_text(xml: &str) -> std::result::Result<String, DocumentError> {
+    let mut reader = Reader::from_str(xml);
+    let mut text = String::new();
+    let mut in_text = false;
+    loop {
+        match reader.read_event().map_err(parse_error)? {
+            Event::Start(element) if element.name().as_ref() == b"w:t" => in_text = true,
+            Event::End(element) => match element.name().as_ref() {
+                b"w:t" => in_text = false,
+                b"w:p" => text.push('\n'),
+                _ => {}
+            },
+            Event::Empty(element) => match element.name().as_ref() {
+                b"w:tab" => text.push('\t'),
+                b"w:br" | b"w:cr" => text.push('\n'),
+                _ => {}
+            },
+            Event::Text(content) if in_text => text.push_str(&content.unescape().map_err(parse_error)?),
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(text)
+}
+
+/// Text of the first element named `name` in `xml`.
+fn element_text(xml: &str, name: &[u8]) -> Option<String> {
+    let mut reader = Reader::from_str(xml);
+    let mut inside = false;
+    loop {
+        match reader.read_event().ok()? {
+            Event::Start(element) if element.name().as_ref() == name => inside = true,
+            Event::Text(content) if inside => {
+                return content.unescape().ok().map(|text| text.trim().to_string()).filter(|text| !text.is_empty())
+            }
+            Event::End(element) if element.name().as_ref() == name => inside = false,
+            Event::Eof => return None,
+            _ => {}
+        }
+    }
+}
+
+impl DocumentLoader for DocxLoader {
+    fn formats(&self) -> &[&'static str] {
+        &["docx"]
+    }
+
+    fn load(&self, bytes: &[u8]) -> std::result::Result<Document, DocumentError> {
+        let mut archive = ZipArchive::new(Cursor::new(bytes)).map_err(parse_error)?;
+        let body = read_part(&mut archive, "word/document.xml")?.ok_or_else(|| parse_error("no word/document.xml"))?;
+        let mut document = Document::new(body_text(&body)?);
+        if let Some(properties) = read_part(&mut archive, "docProps/core.xml")? {
+            for (key, name) in [("title", b"dc:title".as_slice()), ("author", b"dc:creator".as_slice())] {
+                if let Some(value) = element_text(&properties, name) {
+                    document = document.with_metadata(key, value);
+                }
+            }
+        }
+        Ok(document)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    #[test]
+    fn test_docx_text_and_properties() {
+        let mut package = Cursor::new(Vec::new());
+        let mut zip = zip::ZipWriter::new(&mut package);
+        zip.start_file("word/document.xml", SimpleFileOptions::default()).unwrap();
+        zip.write_all(
+            br#"<w:document><w:body><w:p><w:r><w:t>Fish &amp; chips</w:t></w:r></w:p><w:p><w:r><w:t>a</w:t><w:tab/><w:t>b</w:t></w:r></w:p></w:body></w:document>"#,
+        )
+        .unwrap();
+        zip.start_file("docProps/core.xml", SimpleFileOptions::default()).unwrap();
+        zip.write_all(br#"<cp:coreProperties><dc:title>Menu</dc:title><dc:creator>Ana</dc:creator></cp:coreProperties>"#)
+            .unwrap();
+        zip.finish().unwrap();
+
+        let document = DocxLoader.load(package.get_ref()).unwrap();
+        assert_eq!(document.text, "Fish & chips\na\tb\n");
+        assert_eq!(document.metadata["title"], "Menu");
+        assert_eq!(document.metadata["author"], "Ana");
+        assert!(DocxLoader.load(b"not a zip").is_err());
+    }
+}
@@ -0,0 +1,86 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! HTML documents.
+
+use super::{Document, DocumentError, DocumentLoader};
+use crate::transform::text::tidy_lines;
+use scraper::{ElementRef, Html, Node, Selector};
+
+/// Elements whose content is not shown.
+const HIDDEN: [&str; 5] = ["script", "style", "head", "noscript", "template"];
+
+/// Elements that start a new line.
+const BLOCKS: [&str; 24] = [
+    "address", "article", "aside", "blockquote", "dd", "div", "dl", "dt", "footer", "h1", "h2", "h3", "h4", "h5",
+    "h6", "header", "li", "nav", "ol", "p", "pre", "section", "table", "tr",
+];
+
+/// Loads the visible text of HTML pages, with their title and language.
+pub struct HtmlLoader;
+
+/// Appends the visible text of `element`.
+fn visible_text(element: ElementRef, text: &mut String) {
+    let name = element.value().name();
+    if HIDDEN.contains(&name) {
+        return;
+    }
+    let block = BLOCKS.contains(&name);
+    if block || name == "br" {
+        text.push('\n');
+    }
+    for child in element.children() {
+        match child.value() {
+            Node::Text(content) => text.push_str(content),
+            Node::Element(_) => {
+                if let Some(child) = ElementRef::wrap(child) {
+                    visible_text(child, text);
+                }
+            }
+            _ => {}
+        }
+    }
+    if block {
+        text.push('\n');
+    }
+}
+
+impl DocumentLoader for HtmlLoader {
+    fn formats(&self) -> &[&'static str] {
+        &["html", "htm"]
+    }
+
+    fn load(&self, bytes: &[u8]) -> std::result::Result<Document, DocumentError> {
+        let html = Html::parse_document(&String::from_utf8_lossy(bytes));
+        let mut text = String::new();
+        visible_text(html.root_element(), &mut text);
+        let mut document = Document::new(tidy_lines(&text));
+
+        let title = Selector::parse("title").expect("valid selector");
+        if let Some(title) = html.select(&title).next() {
+            let title = title.text().collect::<String>().trim().to_string();
+            if !title.is_empty() {
+                document = document.with_metadata("title", title);
+            }
+        }
+        if let Some(language) = html.root_element().value().attr("lang") {
+            document = document.with_metadata("language", language);
+        }
+        Ok(document)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_html_visible_text() {
+        let page = br#"<html lang="en"><head><title> Menu </title><style>p{}</style></head>
+            <body><h1>Today</h1><p>Fish &amp; chips<br>Peas</p><script>track()</script></body></html>"#;
+        let document = HtmlLoader.load(page).unwrap();
+        assert_eq!(document.text, "Today\n\nFish & chips\nPeas");
+        assert_eq!(document.metadata["title"], "Menu");
+        assert_eq!(document.metadata["language"], "en");
+    }
+}
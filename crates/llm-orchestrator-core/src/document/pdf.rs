@@ -0,0 +1,28 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! PDF documents.
+
+use super::{Document, DocumentError, DocumentLoader};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+/// Loads the text of PDF documents, a page per paragraph.
+///
+/// Scanned pages without a text layer come out empty.
+pub struct PdfLoader;
+
+impl DocumentLoader for PdfLoader {
+    fn formats(&self) -> &[&'static str] {
+        &["pdf"]
+    }
+
+    fn load(&self, bytes: &[u8]) -> std::result::Result<Document, DocumentError> {
+        let parse_error = |message: String| DocumentError::Parse { format: "PDF", message };
+        // The parser panics on some malformed files
+        let pages = catch_unwind(AssertUnwindSafe(|| pdf_extract::extract_text_from_mem_by_pages(bytes)))
+            .map_err(|_| parse_error("malformed document".to_string()))?
+            .map_err(|e| parse_error(e.to_string()))?;
+        let text = pages.iter().map(|page| page.trim()).collect::<Vec<_>>().join("\n\n");
+        Ok(Document::new(text).with_metadata("pages", pages.len()))
+    }
+}
@@ -3,6 +3,8 @@
 
 //! Error types for the LLM Orchestrator core.
 
+use crate::providers::ProviderError;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 /// Result type alias for orchestrator operations.
@@ -19,9 +21,10 @@ pub enum OrchestratorError {
     #[error("Workflow validation failed: {0}")]
     ValidationError(String),
 
-    /// Cyclic dependency detected in workflow DAG.
-    #[error("Cyclic dependency detected in workflow")]
-    CyclicDependency,
+    /// Cyclic dependency detected in workflow DAG; lists the steps of the
+    /// cycle, each depending on the next, ending where it started.
+    #[error("Cyclic dependency detected in workflow: {}", .0.join(" -> "))]
+    CyclicDependency(Vec<String>),
 
     /// Step not found in workflow.
     #[error("Step '{0}' not found in workflow")]
@@ -58,13 +61,74 @@ pub enum OrchestratorError {
     #[error("Operation timed out after {duration:?}")]
     Timeout { duration: std::time::Duration },
 
+    /// A step with the `fail_workflow` policy failed.
+    #[error("Workflow failed at step '{step_id}': {reason}")]
+    WorkflowFailed { step_id: String, reason: String },
+
+    /// Steps failed under the default failure policy; their dependents were skipped.
+    #[error("{}", describe_failures(.0))]
+    StepsFailed(Vec<StepFailure>),
+
+    /// A guardrail blocked a step.
+    #[error("Guardrail '{filter}' blocked step '{step_id}': {reason}")]
+    GuardrailBlocked {
+        step_id: String,
+        filter: String,
+        reason: String,
+    },
+
+    /// A step's outputs did not satisfy one of its assertions.
+    #[error("Assertion '{assertion}' failed for step '{step_id}': {reason}")]
+    AssertionFailed {
+        step_id: String,
+        assertion: String,
+        reason: String,
+    },
+
+    /// Execution was cancelled.
+    #[error("Workflow execution was cancelled")]
+    Cancelled,
+
+    /// The process shut down before these steps finished; the run can be resumed.
+    #[error("Workflow execution was interrupted by shutdown before {} step(s) finished: {}", .0.len(), .0.join(", "))]
+    Interrupted(Vec<String>),
+
+    /// A step task panicked; the message is the panic payload.
+    #[error("Step '{step_id}' panicked: {message}")]
+    StepPanicked { step_id: String, message: String },
+
     /// Concurrency limit exceeded.
     #[error("Concurrency limit exceeded: {limit}")]
     ConcurrencyLimitExceeded { limit: usize },
 
     /// Provider error (LLM API errors, rate limits, etc).
     #[error("Provider '{provider}' error: {message}")]
-    ProviderError { provider: String, message: String },
+    ProviderError {
+        provider: String,
+        message: String,
+        /// How long the provider asked callers to wait before retrying.
+        retry_after: Option<std::time::Duration>,
+    },
+
+    /// An HTTP step got an unsuccessful response, or none at all.
+    #[error("HTTP request of step '{step_id}' failed: {message}")]
+    HttpRequest {
+        step_id: String,
+        /// Response status; `None` when the request could not be sent.
+        status: Option<u16>,
+        message: String,
+        /// How long the service asked callers to wait before retrying.
+        retry_after: Option<std::time::Duration>,
+    },
+
+    /// A provider call failed.
+    #[error("{context}: {source}")]
+    ProviderCall {
+        provider: String,
+        context: String,
+        #[source]
+        source: ProviderError,
+    },
 
     /// IO error.
     #[error("IO error: {0}")]
@@ -79,6 +143,31 @@ pub enum OrchestratorError {
     Other(String),
 }
 
+/// A step that failed under the default failure policy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StepFailure {
+    /// ID of the failed step.
+    pub step_id: String,
+    /// Error the step failed with.
+    pub reason: String,
+    /// Steps skipped because they depend on the failed step.
+    pub skipped: Vec<String>,
+}
+
+fn describe_failures(failures: &[StepFailure]) -> String {
+    let steps: Vec<String> = failures
+        .iter()
+        .map(|f| {
+            let mut description = format!("'{}': {}", f.step_id, f.reason);
+            if !f.skipped.is_empty() {
+                description.push_str(&format!(" (skipped {})", f.skipped.join(", ")));
+            }
+            description
+        })
+        .collect();
+    format!("{} step(s) failed: {}", failures.len(), steps.join("; "))
+}
+
 impl OrchestratorError {
     /// Create a new parse error.
     pub fn parse<S: Into<String>>(msg: S) -> Self {
@@ -122,13 +211,155 @@ impl OrchestratorError {
     /// - Timeout errors
     /// - Concurrency limit errors
     /// - Provider errors (rate limits, temporary API failures, etc.)
+    /// - HTTP step requests that got no response, a 429, or a 5xx
     pub fn is_retryable(&self) -> bool {
-        matches!(
-            self,
-            Self::Timeout { .. }
-                | Self::ConcurrencyLimitExceeded { .. }
-                | Self::ProviderError { .. }
-        )
+        match self {
+            Self::HttpRequest { status, .. } => !matches!(status, Some(s) if *s != 429 && *s < 500),
+            _ => matches!(
+                self,
+                Self::Timeout { .. }
+                    | Self::ConcurrencyLimitExceeded { .. }
+                    | Self::ProviderError { .. }
+                    | Self::ProviderCall { source: ProviderError::RateLimitExceeded { .. }, .. }
+            ),
+        }
+    }
+
+    /// How long to wait before retrying, when the provider sent a hint.
+    pub fn retry_after(&self) -> Option<std::time::Duration> {
+        match self {
+            Self::ProviderError { retry_after, .. } | Self::HttpRequest { retry_after, .. } => *retry_after,
+            Self::ProviderCall { source, .. } => source.retry_after(),
+            _ => None,
+        }
+    }
+
+    /// Category of the error, for consumers reacting to failure types.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Self::ParseError(_)
+            | Self::ValidationError(_)
+            | Self::CyclicDependency(_)
+            | Self::StepNotFound(_)
+            | Self::InvalidStepConfig { .. } => ErrorKind::Validation,
+            Self::TemplateError(_) | Self::ContextVariableNotFound(_) => ErrorKind::Template,
+            Self::Timeout { .. } => ErrorKind::Timeout,
+            Self::Cancelled | Self::Interrupted(_) => ErrorKind::Cancelled,
+            Self::GuardrailBlocked { .. } => ErrorKind::Guardrail,
+            Self::AssertionFailed { .. } => ErrorKind::Assertion,
+            Self::StepPanicked { .. } => ErrorKind::Panicked,
+            Self::ProviderError { .. } => ErrorKind::Provider,
+            Self::ProviderCall { source, .. } => match source {
+                ProviderError::AuthError(_) => ErrorKind::Authentication,
+                ProviderError::RateLimitExceeded { .. } => ErrorKind::RateLimited,
+                ProviderError::InvalidRequest(_) => ErrorKind::InvalidRequest,
+                ProviderError::Timeout => ErrorKind::Timeout,
+                ProviderError::SerializationError(_) => ErrorKind::Serialization,
+                _ => ErrorKind::Provider,
+            },
+            Self::HttpRequest { status, .. } => match status {
+                Some(429) => ErrorKind::RateLimited,
+                Some(401 | 403) => ErrorKind::Authentication,
+                Some(400..=499) => ErrorKind::InvalidRequest,
+                _ => ErrorKind::Other,
+            },
+            Self::SerializationError(_) => ErrorKind::Serialization,
+            _ => ErrorKind::Other,
+        }
+    }
+
+    /// Name of the provider the error came from, if any.
+    pub fn provider(&self) -> Option<&str> {
+        match self {
+            Self::ProviderError { provider, .. } | Self::ProviderCall { provider, .. } => Some(provider),
+            _ => None,
+        }
+    }
+
+    /// HTTP status of the provider or HTTP step response the error came from, when known.
+    pub fn http_status(&self) -> Option<u16> {
+        match self {
+            Self::ProviderCall { source, .. } => source.http_status(),
+            Self::HttpRequest { status, .. } => *status,
+            _ => None,
+        }
+    }
+}
+
+/// Category of a step failure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    /// The step or a provider call timed out.
+    Timeout,
+    /// The provider rate limited the request.
+    RateLimited,
+    /// The provider rejected the credentials.
+    Authentication,
+    /// The provider rejected the request as invalid.
+    InvalidRequest,
+    /// Any other provider failure.
+    Provider,
+    /// A template could not be rendered.
+    Template,
+    /// The workflow or step configuration is invalid.
+    Validation,
+    /// A guardrail blocked the step.
+    Guardrail,
+    /// The step's outputs did not satisfy an assertion.
+    Assertion,
+    /// A reviewer rejected an approval step.
+    Rejected,
+    /// The step panicked.
+    Panicked,
+    /// The run was cancelled.
+    Cancelled,
+    /// A value could not be serialized or deserialized.
+    Serialization,
+    /// Any other failure.
+    Other,
+}
+
+/// Structured description of why a step failed, recorded in its result.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StepError {
+    /// Category of the failure.
+    pub kind: ErrorKind,
+    /// Error message, as in `StepResult::error`.
+    pub message: String,
+    /// Provider the failure came from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    /// HTTP status of the provider response, when known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http_status: Option<u16>,
+    /// Whether the error is one the executor retries.
+    pub retryable: bool,
+    /// Number of attempts made, including retries.
+    pub attempts: u32,
+    /// Messages of the underlying errors, outermost first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub causes: Vec<String>,
+}
+
+impl StepError {
+    /// Describes the error a step failed with after `attempts` attempts.
+    pub fn from_error(err: &OrchestratorError, attempts: u32) -> Self {
+        let mut causes = Vec::new();
+        let mut source = std::error::Error::source(err);
+        while let Some(cause) = source {
+            causes.push(cause.to_string());
+            source = cause.source();
+        }
+        Self {
+            kind: err.kind(),
+            message: err.to_string(),
+            provider: err.provider().map(str::to_string),
+            http_status: err.http_status(),
+            retryable: err.is_retryable(),
+            attempts,
+            causes,
+        }
     }
 }
 
@@ -174,4 +405,38 @@ mod tests {
         let parse_err = OrchestratorError::parse("test");
         assert!(!parse_err.is_retryable());
     }
+
+    #[test]
+    fn test_step_error_from_provider_call() {
+        let err = OrchestratorError::ProviderCall {
+            provider: "openai".to_string(),
+            context: "Provider error".to_string(),
+            source: ProviderError::Api {
+                status: 503,
+                message: "overloaded".to_string(),
+            },
+        };
+        let step_error = StepError::from_error(&err, 2);
+        assert_eq!(step_error.kind, ErrorKind::Provider);
+        assert_eq!(step_error.message, "Provider error: API error (HTTP 503): overloaded");
+        assert_eq!(step_error.provider.as_deref(), Some("openai"));
+        assert_eq!(step_error.http_status, Some(503));
+        assert!(!step_error.retryable);
+        assert_eq!(step_error.attempts, 2);
+        assert_eq!(step_error.causes, vec!["API error (HTTP 503): overloaded"]);
+
+        let err = OrchestratorError::ProviderCall {
+            provider: "openai".to_string(),
+            context: "Provider error".to_string(),
+            source: ProviderError::RateLimitExceeded { retry_after: None },
+        };
+        let step_error = StepError::from_error(&err, 4);
+        assert_eq!(step_error.kind, ErrorKind::RateLimited);
+        assert_eq!(step_error.http_status, Some(429));
+        assert!(step_error.retryable);
+
+        let json = serde_json::to_value(StepError::from_error(&OrchestratorError::template("bad"), 1)).unwrap();
+        assert_eq!(json["kind"], "template");
+        assert!(json.get("provider").is_none());
+    }
 }
@@ -0,0 +1,587 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Output evaluation.
+//!
+//! Scores a step's output for the `evaluate` step type. Built-in metrics
+//! (exact match, regex, JSON Schema validity, embedding similarity) and an
+//! LLM judge each produce a score between 0.0 and 1.0; the step reports
+//! their weighted mean and whether it reached the configured threshold.
+
+use crate::error::{OrchestratorError, Result};
+use crate::deterministic::SentRequest;
+use crate::executor::WorkflowExecutor;
+use crate::metrics;
+use crate::providers::{provider_error, CompletionRequest, EmbeddingInput, EmbeddingRequest};
+use crate::usage::{completion_usage, embedding_usage};
+use crate::workflow::{EvalMetricKind, JudgeConfig, Step, StepConfig};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use tracing::debug;
+
+lazy_static::lazy_static! {
+    /// First number in a judge reply without a JSON score.
+    static ref NUMBER: regex::Regex = regex::Regex::new(r"\d+(?:\.\d+)?").unwrap();
+}
+
+/// Score awarded by one metric or the judge.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricScore {
+    /// Metric name (`exact_match`, `regex`, `json_schema`,
+    /// `embedding_similarity`, or `judge`).
+    pub metric: String,
+
+    /// Score between 0.0 and 1.0.
+    pub score: f64,
+
+    /// Weight of the score in the overall score.
+    pub weight: f64,
+
+    /// Why the score was given (schema violation, judge reasoning).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+/// Returns 1.0 when `target` equals `reference`, ignoring surrounding whitespace.
+pub fn exact_match(target: &str, reference: &str, ignore_case: bool) -> f64 {
+    let (target, reference) = (target.trim(), reference.trim());
+    let equal = if ignore_case {
+        target.to_lowercase() == reference.to_lowercase()
+    } else {
+        target == reference
+    };
+    if equal {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+/// Returns 1.0 when `pattern` matches anywhere in `target`.
+pub fn regex_match(target: &str, pattern: &str) -> std::result::Result<f64, regex::Error> {
+    let regex = regex::Regex::new(pattern)?;
+    Ok(if regex.is_match(target) { 1.0 } else { 0.0 })
+}
+
+/// Lists the ways `target` fails to be JSON valid against `schema`.
+///
+/// Returns an error when the schema itself is invalid.
+pub fn json_schema_errors(target: &str, schema: &Value) -> std::result::Result<Vec<String>, String> {
+    let validator = jsonschema::validator_for(schema).map_err(|e| e.to_string())?;
+    let instance: Value = match serde_json::from_str(target.trim()) {
+        Ok(instance) => instance,
+        Err(e) => return Ok(vec![format!("not valid JSON: {}", e)]),
+    };
+    Ok(validator.iter_errors(&instance).map(|e| e.to_string()).collect())
+}
+
+/// Cosine similarity of two vectors, or 0.0 when either is empty or zero.
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f64 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    let mut dot = 0.0;
+    let mut norm_a = 0.0;
+    let mut norm_b = 0.0;
+    for (x, y) in a.iter().zip(b) {
+        let (x, y) = (f64::from(*x), f64::from(*y));
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a.sqrt() * norm_b.sqrt())
+}
+
+/// Weighted mean of `scores`, or 0.0 when their weights sum to zero.
+pub fn weighted_score(scores: &[MetricScore]) -> f64 {
+    let total_weight: f64 = scores.iter().map(|s| s.weight).sum();
+    if total_weight <= 0.0 {
+        return 0.0;
+    }
+    scores.iter().map(|s| s.score * s.weight).sum::<f64>() / total_weight
+}
+
+/// Builds the prompt asking the judge to grade `target` against `rubric`.
+fn judge_prompt(rubric: &str, target: &str, reference: Option<&str>, scale: u32) -> String {
+    let mut prompt = format!(
+        "You are an impartial evaluator. Grade the response below against the rubric.\n\nRubric:\n{}\n\n",
+        rubric
+    );
+    if let Some(reference) = reference {
+        prompt.push_str(&format!("Reference answer:\n{}\n\n", reference));
+    }
+    prompt.push_str(&format!(
+        "Response:\n{}\n\nReply with only a JSON object of the form \
+         {{\"score\": <number from 0 to {}>, \"reasoning\": \"<one sentence>\"}}.",
+        target, scale
+    ));
+    prompt
+}
+
+/// Extracts the score and reasoning from a judge's reply, normalized by `scale`.
+///
+/// Accepts the requested JSON object, possibly wrapped in prose or a code
+/// fence, and falls back to the first number in the reply.
+pub fn parse_judge_reply(reply: &str, scale: u32) -> Option<(f64, Option<String>)> {
+    let scale = f64::from(scale.max(1));
+    let normalize = |score: f64| (score / scale).clamp(0.0, 1.0);
+
+    if let (Some(start), Some(end)) = (reply.find('{'), reply.rfind('}')) {
+        if let Ok(Value::Object(object)) = serde_json::from_str::<Value>(&reply[start..=end]) {
+            let score = object.get("score").and_then(|s| match s {
+                Value::Number(n) => n.as_f64(),
+                Value::String(s) => s.trim().parse().ok(),
+                _ => None,
+            });
+            if let Some(score) = score {
+                let reasoning = object.get("reasoning").and_then(Value::as_str).map(str::to_string);
+                return Some((normalize(score), reasoning));
+            }
+        }
+    }
+
+    let score: f64 = NUMBER.find(reply)?.as_str().parse().ok()?;
+    Some((normalize(score), None))
+}
+
+impl WorkflowExecutor {
+    /// Executes an evaluation step.
+    pub(crate) async fn execute_evaluate_step(&self, step: &Step) -> Result<HashMap<String, Value>> {
+        let eval_config = match &step.config {
+            StepConfig::Evaluate(config) => config,
+            _ => {
+                return Err(OrchestratorError::InvalidStepConfig {
+                    step_id: step.id.clone(),
+                    reason: "Expected Evaluate step config".to_string(),
+                })
+            }
+        };
+
+        let target = self.context.render_template(&eval_config.target)?;
+        let reference = eval_config
+            .reference
+            .as_deref()
+            .map(|r| self.context.render_template(r))
+            .transpose()?;
+        let invalid = |reason: String| OrchestratorError::InvalidStepConfig {
+            step_id: step.id.clone(),
+            reason,
+        };
+        let require_reference = |metric: &EvalMetricKind| {
+            reference
+                .as_deref()
+                .ok_or_else(|| invalid(format!("{} metric requires a reference", metric.name())))
+        };
+
+        let mut scores = Vec::new();
+        for metric in &eval_config.metrics {
+            let (score, detail) = match &metric.kind {
+                EvalMetricKind::ExactMatch { ignore_case } => {
+                    (exact_match(&target, require_reference(&metric.kind)?, *ignore_case), None)
+                }
+                EvalMetricKind::Regex { pattern } => {
+                    let score = regex_match(&target, pattern)
+                        .map_err(|e| invalid(format!("Invalid regex '{}': {}", pattern, e)))?;
+                    (score, None)
+                }
+                EvalMetricKind::JsonSchema { schema } => {
+                    let errors = json_schema_errors(&target, schema)
+                        .map_err(|e| invalid(format!("Invalid JSON schema: {}", e)))?;
+                    (if errors.is_empty() { 1.0 } else { 0.0 }, errors.into_iter().next())
+                }
+                EvalMetricKind::EmbeddingSimilarity { provider, model } => {
+                    let reference = require_reference(&metric.kind)?;
+                    let similarity = self
+                        .embedding_similarity(step, provider, model, &target, reference)
+                        .await?;
+                    (similarity.clamp(0.0, 1.0), None)
+                }
+            };
+            scores.push(MetricScore {
+                metric: metric.kind.name().to_string(),
+                score,
+                weight: metric.weight,
+                detail,
+            });
+        }
+
+        if let Some(judge) = &eval_config.judge {
+            scores.push(self.judge_output(step, judge, &target, reference.as_deref()).await?);
+        }
+
+        let score = weighted_score(&scores);
+        let passed = score >= eval_config.threshold;
+        debug!(step_id = %step.id, score, passed, "Evaluation completed");
+        self.record_experiment_score(step, score);
+
+        if !passed && eval_config.fail_below_threshold {
+            return Err(OrchestratorError::other(format!(
+                "Evaluation score {:.2} is below threshold {:.2}",
+                score, eval_config.threshold
+            )));
+        }
+
+        // Key per-metric scores by name, numbering repeated metrics
+        let mut by_metric = Map::new();
+        for entry in &scores {
+            let mut key = entry.metric.clone();
+            let mut n = 1;
+            while by_metric.contains_key(&key) {
+                n += 1;
+                key = format!("{}_{}", entry.metric, n);
+            }
+            by_metric.insert(key, json!(entry.score));
+        }
+
+        let output_key = |index: usize, default: &str| {
+            step.output.get(index).cloned().unwrap_or_else(|| default.to_string())
+        };
+        let mut outputs = HashMap::new();
+        outputs.insert(output_key(0, "score"), json!(score));
+        outputs.insert(output_key(1, "passed"), json!(passed));
+        outputs.insert(output_key(2, "scores"), Value::Object(by_metric));
+
+        // Always store the full breakdown under a special key for debugging
+        outputs.insert(
+            "_evaluation".to_string(),
+            json!({ "threshold": eval_config.threshold, "scores": scores }),
+        );
+
+        Ok(outputs)
+    }
+
+    /// Embeds `target` and `reference` in one request and compares them.
+    async fn embedding_similarity(
+        &self,
+        step: &Step,
+        provider_name: &str,
+        model: &str,
+        target: &str,
+        reference: &str,
+    ) -> Result<f64> {
+        let provider = self
+            .embedding_providers
+            .get(provider_name)
+            .ok_or_else(|| OrchestratorError::other(format!(
+                "Embedding provider '{}' not registered",
+                provider_name
+            )))?;
+
+        let request = EmbeddingRequest {
+            model: model.to_string(),
+            input: EmbeddingInput::Batch {
+                input: vec![target.to_string(), reference.to_string()],
+            },
+            dimensions: None,
+            extra: HashMap::new(),
+        };
+
+        self.record_request(&step.id, &request);
+        let embed_start = std::time::Instant::now();
+        let response_result = provider.embed(request).await;
+        self.record_provider_latency(&step.id, embed_start.elapsed());
+
+        #[cfg(feature = "audit")]
+        self.audit_provider_call(crate::audit::ProviderCall {
+            step_id: &step.id,
+            provider: provider_name,
+            model,
+            prompt: target,
+            completion: None,
+            tokens_used: response_result.as_ref().ok().and_then(|r| r.tokens_used),
+            error: response_result.as_ref().err().map(|e| e.to_string()),
+            duration: embed_start.elapsed(),
+        })
+        .await;
+
+        self.usage.record_embedding(provider_name, model, response_result.as_ref().ok());
+        if let Ok(response) = &response_result {
+            self.record_usage(&step.id, &embedding_usage(response));
+        }
+        let response = response_result
+            .map_err(|e| provider_error(provider_name, "Embedding provider error", e))?;
+        match response.embeddings.as_slice() {
+            [target, reference] => Ok(cosine_similarity(target, reference)),
+            embeddings => Err(OrchestratorError::other(format!(
+                "Embedding provider returned {} embeddings, expected 2",
+                embeddings.len()
+            ))),
+        }
+    }
+
+    /// Asks the judge model to grade `target` against the rubric.
+    async fn judge_output(
+        &self,
+        step: &Step,
+        judge: &JudgeConfig,
+        target: &str,
+        reference: Option<&str>,
+    ) -> Result<MetricScore> {
+        let provider = self
+            .providers
+            .get(&judge.provider)
+            .ok_or_else(|| OrchestratorError::other(format!(
+                "Provider '{}' not registered",
+                judge.provider
+            )))?;
+
+        self.resolve_template_secrets(&judge.rubric).await?;
+        let rubric = self.context.render_template(&judge.rubric)?;
+        let mut request = CompletionRequest {
+            model: judge.model.clone(),
+            prompt: judge_prompt(&rubric, target, reference, judge.scale),
+            system: None,
+            history: Vec::new(),
+            images: Vec::new(),
+            temperature: Some(0.0),
+            max_tokens: None,
+            tools: Vec::new(),
+            extra: HashMap::new(),
+            headers: HashMap::new(),
+        };
+        self.pin_sampling(&mut request);
+        let sent = self.is_deterministic().then(|| SentRequest::of(&request));
+
+        debug!(
+            step_id = %step.id,
+            provider = %judge.provider,
+            model = %judge.model,
+            "Calling judge model"
+        );
+        self.record_request(&step.id, &request);
+        #[cfg(feature = "audit")]
+        let audit_prompt = request.prompt.clone();
+        let judge_start = std::time::Instant::now();
+        let response_result = provider.complete(request).await;
+        let judge_duration = judge_start.elapsed().as_secs_f64();
+        self.record_provider_latency(&step.id, judge_start.elapsed());
+
+        #[cfg(feature = "audit")]
+        self.audit_provider_call(crate::audit::ProviderCall {
+            step_id: &step.id,
+            provider: &judge.provider,
+            model: &judge.model,
+            prompt: &audit_prompt,
+            completion: response_result.as_ref().ok().map(|r| r.text.as_str()),
+            tokens_used: response_result.as_ref().ok().and_then(|r| r.tokens_used),
+            error: response_result.as_ref().err().map(|e| e.to_string()),
+            duration: judge_start.elapsed(),
+        })
+        .await;
+
+        metrics::record_llm_request(
+            &judge.provider,
+            &judge.model,
+            judge_duration,
+            response_result.is_ok(),
+            None,
+            None,
+        );
+        self.usage
+            .record_completion(&judge.provider, &judge.model, response_result.as_ref().ok());
+        if let (Some(sent), Ok(response)) = (sent, &response_result) {
+            self.record_manifest(&step.id, &judge.provider, sent, response);
+        }
+        if let Ok(response) = &response_result {
+            self.record_usage(&step.id, &completion_usage(response));
+        }
+        let response = response_result.map_err(|e| provider_error(&judge.provider, "Provider error", e))?;
+
+        let (score, reasoning) = parse_judge_reply(&response.text, judge.scale).ok_or_else(|| {
+            OrchestratorError::other(format!("Judge reply has no score: {}", response.text))
+        })?;
+        Ok(MetricScore {
+            metric: "judge".to_string(),
+            score,
+            weight: judge.weight,
+            detail: reasoning,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::StepStatus;
+    use crate::providers::{
+        CompletionResponse, EmbeddingProvider, EmbeddingResponse, LLMProvider, ProviderError,
+    };
+    use crate::workflow::Workflow;
+    use std::sync::Arc;
+
+    /// Grades every response 8 out of 10.
+    struct GenerousJudge;
+
+    #[async_trait::async_trait]
+    impl LLMProvider for GenerousJudge {
+        async fn complete(&self, request: CompletionRequest) -> std::result::Result<CompletionResponse, ProviderError> {
+            assert!(request.prompt.contains("Rubric:\nMentions Paris"));
+            Ok(CompletionResponse {
+                text: r#"```json
+{"score": 8, "reasoning": "Names the capital."}
+```"#
+                    .to_string(),
+                model: request.model,
+                tokens_used: None,
+                usage: None,
+                tool_calls: Vec::new(),
+                metadata: HashMap::new(),
+            })
+        }
+
+        fn name(&self) -> &str {
+            "judge"
+        }
+    }
+
+    /// Embeds text by character class counts.
+    struct CountingEmbedder;
+
+    #[async_trait::async_trait]
+    impl EmbeddingProvider for CountingEmbedder {
+        async fn embed(&self, request: EmbeddingRequest) -> std::result::Result<EmbeddingResponse, ProviderError> {
+            let EmbeddingInput::Batch { input } = request.input else {
+                panic!("expected a batch request");
+            };
+            let embeddings = input
+                .iter()
+                .map(|text| {
+                    vec![
+                        text.chars().filter(|c| c.is_alphabetic()).count() as f32,
+                        text.chars().filter(|c| c.is_numeric()).count() as f32,
+                    ]
+                })
+                .collect();
+            Ok(EmbeddingResponse {
+                embeddings,
+                model: request.model,
+                tokens_used: None,
+                metadata: HashMap::new(),
+            })
+        }
+
+        fn name(&self) -> &str {
+            "counting"
+        }
+    }
+
+    async fn run(yaml: &str) -> crate::executor::StepResult {
+        let workflow = Workflow::from_yaml(yaml).unwrap();
+        let inputs = HashMap::from([
+            ("answer".to_string(), json!("The capital is Paris")),
+            ("expected".to_string(), json!("the capital is paris")),
+        ]);
+        let executor = WorkflowExecutor::new(workflow, inputs)
+            .unwrap()
+            .with_provider("judge", Arc::new(GenerousJudge))
+            .with_embedding_provider("counting", Arc::new(CountingEmbedder));
+        // Failed steps fail the run; tests check the step result either way
+        let _ = executor.execute().await;
+        executor.step_results.remove("grade").unwrap().1
+    }
+
+    #[test]
+    fn test_exact_match_and_regex() {
+        assert_eq!(exact_match(" yes\n", "yes", false), 1.0);
+        assert_eq!(exact_match("Yes", "yes", false), 0.0);
+        assert_eq!(exact_match("Yes", "yes", true), 1.0);
+        assert_eq!(regex_match("order #123", r"#\d+").unwrap(), 1.0);
+        assert_eq!(regex_match("order", r"#\d+").unwrap(), 0.0);
+        assert!(regex_match("order", "(").is_err());
+    }
+
+    #[test]
+    fn test_json_schema_errors() {
+        let schema = json!({
+            "type": "object",
+            "required": ["name"],
+            "properties": {"name": {"type": "string"}}
+        });
+        assert!(json_schema_errors(r#"{"name": "Ada"}"#, &schema).unwrap().is_empty());
+        assert_eq!(json_schema_errors(r#"{"name": 1}"#, &schema).unwrap().len(), 1);
+        assert!(json_schema_errors("not json", &schema).unwrap()[0].starts_with("not valid JSON"));
+        assert!(json_schema_errors("{}", &json!({"type": "no-such-type"})).is_err());
+    }
+
+    #[test]
+    fn test_cosine_similarity() {
+        assert!((cosine_similarity(&[1.0, 0.0], &[2.0, 0.0]) - 1.0).abs() < 1e-9);
+        assert!(cosine_similarity(&[1.0, 0.0], &[0.0, 1.0]).abs() < 1e-9);
+        assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 0.0]), 0.0);
+        assert_eq!(cosine_similarity(&[1.0], &[1.0, 0.0]), 0.0);
+    }
+
+    #[test]
+    fn test_parse_judge_reply() {
+        assert_eq!(
+            parse_judge_reply(r#"{"score": 7, "reasoning": "Mostly right"}"#, 10),
+            Some((0.7, Some("Mostly right".to_string())))
+        );
+        assert_eq!(parse_judge_reply("I'd give it 4.", 5), Some((0.8, None)));
+        assert_eq!(parse_judge_reply(r#"{"score": 12}"#, 10), Some((1.0, None)));
+        assert_eq!(parse_judge_reply("No idea", 10), None);
+    }
+
+    #[tokio::test]
+    async fn test_evaluate_step_scores_output() {
+        let result = run(r#"
+name: "graded"
+steps:
+  - id: "grade"
+    type: "evaluate"
+    target: "{{ answer }}"
+    reference: "{{ expected }}"
+    threshold: 0.8
+    metrics:
+      - type: "exact_match"
+        ignore_case: true
+      - type: "regex"
+        pattern: "London"
+      - type: "embedding_similarity"
+        provider: "counting"
+        model: "test"
+    judge:
+      provider: "judge"
+      model: "test"
+      rubric: "Mentions Paris"
+      weight: 2
+"#)
+        .await;
+
+        assert_eq!(result.status, StepStatus::Completed);
+        let outputs = &result.outputs;
+        assert_eq!(outputs["scores"]["exact_match"], json!(1.0));
+        assert_eq!(outputs["scores"]["regex"], json!(0.0));
+        assert_eq!(outputs["scores"]["embedding_similarity"], json!(1.0));
+        assert_eq!(outputs["scores"]["judge"], json!(0.8));
+        // (1 + 0 + 1 + 2 * 0.8) / 5
+        assert!((outputs["score"].as_f64().unwrap() - 0.72).abs() < 1e-9);
+        assert_eq!(outputs["passed"], json!(false));
+        assert_eq!(outputs["_evaluation"]["scores"][3]["detail"], json!("Names the capital."));
+    }
+
+    #[tokio::test]
+    async fn test_evaluate_step_fails_below_threshold() {
+        let result = run(r#"
+name: "graded"
+steps:
+  - id: "grade"
+    type: "evaluate"
+    target: "{{ answer }}"
+    fail_below_threshold: true
+    metrics:
+      - type: "json_schema"
+        schema:
+          type: "object"
+    output: ["quality"]
+"#)
+        .await;
+
+        assert_eq!(result.status, StepStatus::Failed);
+        assert!(result.error.unwrap().contains("below threshold"));
+    }
+}
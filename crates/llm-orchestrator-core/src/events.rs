@@ -0,0 +1,337 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Execution lifecycle hooks.
+//!
+//! Implement [`WorkflowEventListener`] and register it with
+//! [`WorkflowExecutor::with_event_listener`](crate::WorkflowExecutor::with_event_listener)
+//! to observe a run as it progresses. All methods have empty default
+//! implementations, so listeners only override the events they care about.
+//!
+//! Hooks are called synchronously from the executor, so they should return
+//! quickly; forward events to a channel if heavier processing is needed.
+//!
+//! For a channel-based view of the same events, call
+//! [`WorkflowExecutor::subscribe`](crate::WorkflowExecutor::subscribe) to get a
+//! broadcast receiver of serializable [`ExecutionEvent`]s.
+
+use crate::error::OrchestratorError;
+use crate::executor::{StepResult, StepStatus};
+use crate::workflow::{Step, Workflow};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+/// Number of events buffered per subscriber before old events are dropped.
+const EVENT_CHANNEL_CAPACITY: usize = 1024;
+
+/// Receives workflow execution events.
+pub trait WorkflowEventListener: Send + Sync {
+    /// Called once before any step runs.
+    fn on_workflow_start(&self, _run_id: Uuid, _workflow: &Workflow) {}
+
+    /// Called when a step begins executing.
+    fn on_step_start(&self, _run_id: Uuid, _step: &Step) {}
+
+    /// Called when a step is skipped (condition false or branch not taken).
+    fn on_step_skipped(&self, _run_id: Uuid, _step_id: &str) {}
+
+    /// Called as a long-running step works through its items, such as the
+    /// records of a vector upsert.
+    fn on_step_progress(&self, _run_id: Uuid, _step: &Step, _completed: usize, _total: usize) {}
+
+    /// Called when a step completes successfully.
+    fn on_step_complete(&self, _run_id: Uuid, _result: &StepResult) {}
+
+    /// Called when a step fails after exhausting its retries.
+    fn on_step_failed(&self, _run_id: Uuid, _result: &StepResult) {}
+
+    /// Called before a failed attempt is retried.
+    ///
+    /// `attempt` is the number of the failed attempt (starting at 1).
+    fn on_retry(&self, _run_id: Uuid, _step: &Step, _attempt: u32, _error: &OrchestratorError) {}
+
+    /// Called once after all steps have finished.
+    fn on_workflow_complete(&self, _run_id: Uuid, _results: &HashMap<String, StepResult>) {}
+
+    /// Called instead of `on_workflow_complete` when the run is cancelled.
+    fn on_workflow_cancelled(&self, _run_id: Uuid, _results: &HashMap<String, StepResult>) {}
+}
+
+/// A serializable execution event, as delivered to subscribers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ExecutionEvent {
+    /// The run started.
+    WorkflowStarted {
+        /// Run ID.
+        run_id: Uuid,
+        /// Workflow ID.
+        workflow_id: Uuid,
+        /// Workflow name.
+        workflow_name: String,
+        /// When the event occurred.
+        timestamp: DateTime<Utc>,
+    },
+    /// A step started executing.
+    StepStarted {
+        /// Run ID.
+        run_id: Uuid,
+        /// Step ID.
+        step_id: String,
+        /// When the event occurred.
+        timestamp: DateTime<Utc>,
+    },
+    /// A step was skipped.
+    StepSkipped {
+        /// Run ID.
+        run_id: Uuid,
+        /// Step ID.
+        step_id: String,
+        /// When the event occurred.
+        timestamp: DateTime<Utc>,
+    },
+    /// A failed attempt is about to be retried.
+    StepRetrying {
+        /// Run ID.
+        run_id: Uuid,
+        /// Step ID.
+        step_id: String,
+        /// Number of the failed attempt (starting at 1).
+        attempt: u32,
+        /// Error of the failed attempt.
+        error: String,
+        /// When the event occurred.
+        timestamp: DateTime<Utc>,
+    },
+    /// A step worked through more of its items.
+    StepProgress {
+        /// Run ID.
+        run_id: Uuid,
+        /// Step ID.
+        step_id: String,
+        /// Items done so far.
+        completed: usize,
+        /// Items in all.
+        total: usize,
+        /// When the event occurred.
+        timestamp: DateTime<Utc>,
+    },
+    /// A step completed successfully.
+    StepCompleted {
+        /// Run ID.
+        run_id: Uuid,
+        /// Step result, including outputs.
+        result: StepResult,
+        /// When the event occurred.
+        timestamp: DateTime<Utc>,
+    },
+    /// A step failed.
+    StepFailed {
+        /// Run ID.
+        run_id: Uuid,
+        /// Step result, including the error.
+        result: StepResult,
+        /// When the event occurred.
+        timestamp: DateTime<Utc>,
+    },
+    /// All steps finished.
+    WorkflowCompleted {
+        /// Run ID.
+        run_id: Uuid,
+        /// IDs of the steps that failed.
+        failed_steps: Vec<String>,
+        /// When the event occurred.
+        timestamp: DateTime<Utc>,
+    },
+    /// The run was cancelled.
+    WorkflowCancelled {
+        /// Run ID.
+        run_id: Uuid,
+        /// IDs of the steps that were cancelled.
+        cancelled_steps: Vec<String>,
+        /// When the event occurred.
+        timestamp: DateTime<Utc>,
+    },
+}
+
+impl ExecutionEvent {
+    /// Returns the run this event belongs to.
+    pub fn run_id(&self) -> Uuid {
+        match self {
+            Self::WorkflowStarted { run_id, .. }
+            | Self::StepStarted { run_id, .. }
+            | Self::StepSkipped { run_id, .. }
+            | Self::StepRetrying { run_id, .. }
+            | Self::StepProgress { run_id, .. }
+            | Self::StepCompleted { run_id, .. }
+            | Self::StepFailed { run_id, .. }
+            | Self::WorkflowCompleted { run_id, .. }
+            | Self::WorkflowCancelled { run_id, .. } => *run_id,
+        }
+    }
+}
+
+/// Listener that republishes events on a broadcast channel.
+pub(crate) struct EventBroadcaster {
+    sender: broadcast::Sender<ExecutionEvent>,
+}
+
+impl Default for EventBroadcaster {
+    fn default() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+        Self { sender }
+    }
+}
+
+impl EventBroadcaster {
+    /// Create a new receiver for subsequent events.
+    pub(crate) fn subscribe(&self) -> broadcast::Receiver<ExecutionEvent> {
+        self.sender.subscribe()
+    }
+
+    fn publish(&self, event: ExecutionEvent) {
+        // Sending only fails when nobody is subscribed
+        let _ = self.sender.send(event);
+    }
+}
+
+impl WorkflowEventListener for EventBroadcaster {
+    fn on_workflow_start(&self, run_id: Uuid, workflow: &Workflow) {
+        self.publish(ExecutionEvent::WorkflowStarted {
+            run_id,
+            workflow_id: workflow.id,
+            workflow_name: workflow.name.clone(),
+            timestamp: Utc::now(),
+        });
+    }
+
+    fn on_step_start(&self, run_id: Uuid, step: &Step) {
+        self.publish(ExecutionEvent::StepStarted {
+            run_id,
+            step_id: step.id.clone(),
+            timestamp: Utc::now(),
+        });
+    }
+
+    fn on_step_skipped(&self, run_id: Uuid, step_id: &str) {
+        self.publish(ExecutionEvent::StepSkipped {
+            run_id,
+            step_id: step_id.to_string(),
+            timestamp: Utc::now(),
+        });
+    }
+
+    fn on_step_progress(&self, run_id: Uuid, step: &Step, completed: usize, total: usize) {
+        self.publish(ExecutionEvent::StepProgress {
+            run_id,
+            step_id: step.id.clone(),
+            completed,
+            total,
+            timestamp: Utc::now(),
+        });
+    }
+
+    fn on_step_complete(&self, run_id: Uuid, result: &StepResult) {
+        self.publish(ExecutionEvent::StepCompleted {
+            run_id,
+            result: result.clone(),
+            timestamp: Utc::now(),
+        });
+    }
+
+    fn on_step_failed(&self, run_id: Uuid, result: &StepResult) {
+        self.publish(ExecutionEvent::StepFailed {
+            run_id,
+            result: result.clone(),
+            timestamp: Utc::now(),
+        });
+    }
+
+    fn on_retry(&self, run_id: Uuid, step: &Step, attempt: u32, error: &OrchestratorError) {
+        self.publish(ExecutionEvent::StepRetrying {
+            run_id,
+            step_id: step.id.clone(),
+            attempt,
+            error: error.to_string(),
+            timestamp: Utc::now(),
+        });
+    }
+
+    fn on_workflow_complete(&self, run_id: Uuid, results: &HashMap<String, StepResult>) {
+        self.publish(ExecutionEvent::WorkflowCompleted {
+            run_id,
+            failed_steps: steps_with_status(results, StepStatus::Failed),
+            timestamp: Utc::now(),
+        });
+    }
+
+    fn on_workflow_cancelled(&self, run_id: Uuid, results: &HashMap<String, StepResult>) {
+        self.publish(ExecutionEvent::WorkflowCancelled {
+            run_id,
+            cancelled_steps: steps_with_status(results, StepStatus::Cancelled),
+            timestamp: Utc::now(),
+        });
+    }
+}
+
+/// Sorted IDs of the steps with the given status.
+fn steps_with_status(results: &HashMap<String, StepResult>, status: StepStatus) -> Vec<String> {
+    let mut step_ids: Vec<String> = results
+        .values()
+        .filter(|r| r.status == status)
+        .map(|r| r.step_id.clone())
+        .collect();
+    step_ids.sort();
+    step_ids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_event_serialization_is_tagged() {
+        let run_id = Uuid::new_v4();
+        let event = ExecutionEvent::StepStarted {
+            run_id,
+            step_id: "step1".to_string(),
+            timestamp: Utc::now(),
+        };
+
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["type"], "step_started");
+        assert_eq!(json["step_id"], "step1");
+        assert_eq!(event.run_id(), run_id);
+    }
+
+    #[tokio::test]
+    async fn test_broadcaster_publishes_to_subscribers() {
+        let broadcaster = EventBroadcaster::default();
+        // Publishing without subscribers is a no-op
+        broadcaster.on_step_skipped(Uuid::new_v4(), "ignored");
+
+        let mut receiver = broadcaster.subscribe();
+        let result = StepResult {
+            step_id: "step1".to_string(),
+            status: StepStatus::Failed,
+            outputs: HashMap::new(),
+            error: Some("boom".to_string()),
+            skip_reason: None,
+            error_details: None,
+            duration: Duration::from_millis(5),
+            usage: None,
+        };
+        broadcaster.on_workflow_complete(Uuid::new_v4(), &HashMap::from([("step1".to_string(), result)]));
+
+        match receiver.recv().await.unwrap() {
+            ExecutionEvent::WorkflowCompleted { failed_steps, .. } => {
+                assert_eq!(failed_steps, vec!["step1"]);
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,241 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! External command actions.
+//!
+//! An `exec` action runs a local program and captures its stdout, stderr,
+//! and exit code:
+//!
+//! ```yaml
+//! - id: wordcount
+//!   type: action
+//!   action: exec
+//!   command: wc
+//!   args: ["-w"]
+//!   stdin: "{{ steps.draft.text }}"
+//!   env: [PATH, LANG]
+//!   timeout_seconds: 10
+//!   output: [count]
+//! ```
+//!
+//! Running commands is disabled unless the executor opts in with
+//! [`WorkflowExecutor::with_exec_enabled`]. The program runs directly, not
+//! through a shell, so rendered arguments cannot inject further commands;
+//! the command and working directory are taken literally, while `args` and
+//! `stdin` are templates. Without a `cwd`, the command runs in the run's
+//! [working directory](crate::workdir). The child starts with an empty environment plus the
+//! variables named in `env`, and is killed if the step times out.
+
+use crate::error::{OrchestratorError, Result};
+use crate::executor::WorkflowExecutor;
+use crate::workflow::{ActionConfig, Step};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tracing::info;
+
+/// Name of the action that runs an external command.
+pub const EXEC_ACTION: &str = "exec";
+
+/// Default output names, in the order outputs are assigned.
+const DEFAULT_OUTPUTS: [&str; 3] = ["stdout", "stderr", "exit_code"];
+
+/// Parameters of an `exec` action.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExecConfig {
+    /// Program to run, looked up on the `PATH` passed to it when not a path.
+    pub command: String,
+
+    /// Argument templates.
+    #[serde(default)]
+    pub args: Vec<String>,
+
+    /// Template written to the program's stdin.
+    #[serde(default)]
+    pub stdin: Option<String>,
+
+    /// Working directory; the run's working directory when unset.
+    #[serde(default)]
+    pub cwd: Option<String>,
+
+    /// Environment variables passed through from the orchestrator.
+    #[serde(default)]
+    pub env: Vec<String>,
+
+    /// Fail the step when the program exits with a non-zero code.
+    #[serde(default = "default_check")]
+    pub check: bool,
+}
+
+fn default_check() -> bool {
+    true
+}
+
+impl ExecConfig {
+    /// Reads the parameters of an `exec` action.
+    pub fn from_action(step_id: &str, config: &ActionConfig) -> Result<Self> {
+        let params = Value::Object(config.params.clone().into_iter().collect());
+        serde_json::from_value(params).map_err(|e| OrchestratorError::InvalidStepConfig {
+            step_id: step_id.to_string(),
+            reason: format!("Invalid exec action: {}", e),
+        })
+    }
+}
+
+impl WorkflowExecutor {
+    /// Allows `exec` actions to run local commands.
+    ///
+    /// Off by default: a workflow that can run commands can do anything the
+    /// orchestrator's user can, so only enable it for trusted workflows.
+    pub fn with_exec_enabled(mut self, enabled: bool) -> Self {
+        self.exec_enabled = enabled;
+        self
+    }
+
+    /// Runs the command of an `exec` action.
+    pub(crate) async fn execute_exec_action(
+        &self,
+        step: &Step,
+        config: &ActionConfig,
+    ) -> Result<HashMap<String, Value>> {
+        if !self.exec_enabled {
+            return Err(OrchestratorError::InvalidStepConfig {
+                step_id: step.id.clone(),
+                reason: "exec actions are disabled for this executor".to_string(),
+            });
+        }
+        let exec = ExecConfig::from_action(&step.id, config)?;
+
+        let args = exec
+            .args
+            .iter()
+            .map(|arg| self.context.render_template(arg))
+            .collect::<Result<Vec<_>>>()?;
+        let stdin = exec
+            .stdin
+            .as_deref()
+            .map(|s| self.context.render_template(s))
+            .transpose()?;
+
+        let mut command = Command::new(&exec.command);
+        command
+            .args(&args)
+            .env_clear()
+            .envs(exec.env.iter().filter_map(|name| std::env::var_os(name).map(|v| (name, v))))
+            .stdin(if stdin.is_some() { Stdio::piped() } else { Stdio::null() })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        match &exec.cwd {
+            Some(cwd) => command.current_dir(cwd),
+            None => command.current_dir(self.workdir()),
+        };
+
+        info!(step_id = %step.id, command = %exec.command, "Running exec action");
+        let mut child = command.spawn().map_err(|e| OrchestratorError::execution(&step.id, e))?;
+        if let (Some(input), Some(mut pipe)) = (stdin, child.stdin.take()) {
+            // Written concurrently with reading the output, so a large input cannot deadlock
+            tokio::spawn(async move {
+                let _ = pipe.write_all(input.as_bytes()).await;
+            });
+        }
+        let output = child
+            .wait_with_output()
+            .await
+            .map_err(|e| OrchestratorError::execution(&step.id, e))?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+        let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+        // A program killed by a signal has no exit code
+        let exit_code = output.status.code();
+
+        if exec.check && !output.status.success() {
+            let message = format!("command '{}' {}: {}", exec.command, output.status, stderr.trim());
+            return Err(OrchestratorError::execution(&step.id, std::io::Error::other(message)));
+        }
+
+        let values = [Value::String(stdout), Value::String(stderr), exit_code.map_or(Value::Null, Value::from)];
+        let names: Vec<&str> = if step.output.is_empty() {
+            DEFAULT_OUTPUTS.to_vec()
+        } else {
+            step.output.iter().map(String::as_str).collect()
+        };
+        Ok(names.into_iter().map(String::from).zip(values).collect())
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::workflow::Workflow;
+
+    fn executor(yaml: &str, enabled: bool) -> WorkflowExecutor {
+        let workflow = Workflow::from_yaml(yaml).unwrap();
+        let inputs = HashMap::from([("name".to_string(), Value::from("World"))]);
+        WorkflowExecutor::new(workflow, inputs).unwrap().with_exec_enabled(enabled)
+    }
+
+    #[tokio::test]
+    async fn test_exec_captures_output() {
+        let yaml = r#"
+name: exec
+steps:
+  - id: greet
+    type: action
+    action: exec
+    command: sh
+    args: ["-c", "cat; echo \" $0\"; echo oops >&2", "{{ inputs.name }}"]
+    stdin: "Hello"
+    env: [PATH]
+    output: [text, errors, code]
+"#;
+        let results = executor(yaml, true).execute().await.unwrap();
+        let outputs = &results["greet"].outputs;
+        assert_eq!(outputs["text"], "Hello World\n");
+        assert_eq!(outputs["errors"], "oops\n");
+        assert_eq!(outputs["code"], 0);
+    }
+
+    #[tokio::test]
+    async fn test_exec_environment_and_exit_code() {
+        std::env::set_var("EXEC_TEST_PASSED", "yes");
+        std::env::set_var("EXEC_TEST_HIDDEN", "yes");
+        let yaml = r#"
+name: exec
+steps:
+  - id: env
+    type: action
+    action: exec
+    command: /bin/sh
+    args: ["-c", "echo \"${EXEC_TEST_PASSED:-no} ${EXEC_TEST_HIDDEN:-no}\"; exit 3"]
+    env: [EXEC_TEST_PASSED]
+    check: false
+"#;
+        let results = executor(yaml, true).execute().await.unwrap();
+        let outputs = &results["env"].outputs;
+        assert_eq!(outputs["stdout"], "yes no\n");
+        assert_eq!(outputs["exit_code"], 3);
+    }
+
+    #[tokio::test]
+    async fn test_exec_failures() {
+        let yaml = r#"
+name: exec
+steps:
+  - id: fail
+    type: action
+    action: exec
+    command: /bin/sh
+    args: ["-c", "echo broken >&2; exit 1"]
+"#;
+        // Disabled unless the executor opts in
+        assert!(executor(yaml, false).execute().await.is_err());
+
+        let err = executor(yaml, true).execute().await.unwrap_err().to_string();
+        assert!(err.contains("broken"), "{}", err);
+    }
+}
@@ -6,25 +6,48 @@
 //! This module provides the core execution engine for running workflows
 //! with support for parallel execution, retry logic, and error handling.
 
-use crate::context::ExecutionContext;
+use crate::approval::{ApprovalDecision, ApprovalRegistry, ApprovalRequest};
+use crate::artifact::{Artifact, ArtifactStore};
+use crate::workdir::WorkdirConfig;
+use crate::cancel::{AbandonHandle, CancelHandle, ShutdownHandle};
+use crate::context::{ContextLimits, ContextMemory, ExecutionContext};
 use crate::dag::WorkflowDAG;
-use crate::error::{OrchestratorError, Result};
+use crate::deterministic::{ManifestRecorder, SentRequest};
+use crate::error::{ErrorKind, OrchestratorError, Result, StepError, StepFailure};
+use crate::events::{EventBroadcaster, ExecutionEvent, WorkflowEventListener};
+use crate::experiment::ExperimentTracker;
+use crate::guardrails::{ContentFilter, GuardrailPolicy, GuardrailStage};
+use crate::health::HealthRegistry;
+use crate::hedge::LatencyTracker;
+use crate::mcp::McpClient;
+use crate::memory::{MemoryStore, PendingTurns};
+use crate::messaging::MessageSink;
+use crate::notify::{ActionHandler, FailureNotifier};
+use crate::usage::{completion_usage, embedding_usage, ModelUsage, UsageTracker};
 use crate::metrics;
+use crate::mock::MockResponses;
+use crate::prompts::PromptRegistry;
 use crate::providers::{
-    CompletionRequest, EmbeddingInput, EmbeddingProvider, EmbeddingRequest, LLMProvider,
-    VectorSearchProvider, VectorSearchRequest,
+    embedding_dimensions, provider_error, CompletionRequest, EmbeddingInput, EmbeddingProvider,
+    EmbeddingRequest, IndexStats, LLMProvider, SearchMode, SpeechProvider, TranscriptionProvider, Usage, VectorSearchProvider,
+    VectorSearchRequest,
 };
-use crate::retry::{RetryExecutor, RetryPolicy};
-use crate::workflow::{BackoffStrategy, Step, StepConfig, StepType, Workflow};
-use dashmap::DashMap;
-use futures::future::select_all;
+use crate::report::{ExecutionReport, StepTiming};
+use crate::result_sink::ResultSink;
+use crate::retry::{RetryBudget, RetryExecutor, RetryPolicy};
+use crate::spill::SpillState;
+use crate::workflow::{BackoffStrategy, FailurePolicy, ResourceClass, Step, StepConfig, StepType, Workflow};
+use chrono::{DateTime, Utc};
+use dashmap::{DashMap, DashSet};
 use serde_json::Value;
-use std::collections::{HashMap, HashSet};
-use std::sync::Arc;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, OnceLock};
 use std::time::Duration;
-use tokio::sync::{Notify, RwLock};
+use tokio::sync::{broadcast, OnceCell, Semaphore};
+use tokio::task::JoinSet;
 use tokio::time::timeout;
-use tracing::{debug, error, info, warn, instrument};
+use tracing::{debug, error, info, warn, instrument, Instrument, Span};
+use uuid::Uuid;
 
 /// Execution status for a step.
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
@@ -37,8 +60,38 @@ pub enum StepStatus {
     Completed,
     /// Step failed with an error.
     Failed,
-    /// Step was skipped due to condition.
+    /// Step was skipped; [`StepResult::skip_reason`] tells why.
     Skipped,
+    /// Step was aborted or never started because the run was cancelled.
+    Cancelled,
+}
+
+/// Why a step was skipped.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum SkipReason {
+    /// The step's condition evaluated to false.
+    Condition,
+    /// The step is on a branch ruled out by an approval decision or failure policy.
+    PrunedBranch,
+    /// The step is a fallback and the step it replaces did not fail.
+    UnusedFallback,
+    /// A partial re-run did not select the step, and it had not completed in the earlier run.
+    NotRerun,
+    /// A step upstream failed under the default failure policy.
+    UpstreamFailed {
+        /// Steps leading back to the failure, nearest first; the last one failed.
+        chain: Vec<String>,
+    },
+    /// A dependency was skipped by its condition, and the step does not set
+    /// `allow_skipped_deps`.
+    UpstreamSkipped {
+        /// Steps leading back to the skip, nearest first; the last one's
+        /// condition evaluated to false.
+        chain: Vec<String>,
+    },
+    /// None of the step's `depends_on_any` alternatives completed.
+    NoAlternativeCompleted,
 }
 
 /// Result of a step execution.
@@ -52,9 +105,18 @@ pub struct StepResult {
     pub outputs: HashMap<String, Value>,
     /// Error message if failed.
     pub error: Option<String>,
+    /// Kind, provider, attempts, and causes of the failure.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_details: Option<StepError>,
+    /// Reason the step was skipped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skip_reason: Option<SkipReason>,
     /// Execution duration in milliseconds.
     #[serde(serialize_with = "serialize_duration", deserialize_with = "deserialize_duration")]
     pub duration: Duration,
+    /// Tokens, cost, and provider latency summed over the step's provider calls.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<Usage>,
 }
 
 fn serialize_duration<S>(duration: &Duration, serializer: S) -> std::result::Result<S::Ok, S::Error>
@@ -75,26 +137,121 @@ where
 
 /// Workflow execution engine.
 pub struct WorkflowExecutor {
+    /// Unique identifier for this run.
+    pub(crate) run_id: Uuid,
+    /// User who started the run, recorded with persisted state.
+    pub(crate) user_id: Option<String>,
+    /// Tenant the run belongs to, recorded with persisted state and audit events.
+    pub(crate) tenant_id: Option<String>,
     /// The workflow to execute.
-    workflow: Workflow,
+    pub(crate) workflow: Workflow,
     /// DAG representation of the workflow.
-    dag: WorkflowDAG,
+    pub(crate) dag: WorkflowDAG,
     /// Execution context.
-    context: Arc<ExecutionContext>,
+    pub(crate) context: Arc<ExecutionContext>,
     /// Step statuses.
-    step_statuses: Arc<DashMap<String, StepStatus>>,
+    pub(crate) step_statuses: Arc<DashMap<String, StepStatus>>,
     /// Step results.
-    step_results: Arc<DashMap<String, StepResult>>,
+    pub(crate) step_results: Arc<DashMap<String, StepResult>>,
+    /// Start, end, attempts, and provider latency of each step.
+    pub(crate) step_timings: Arc<DashMap<String, StepTiming>>,
     /// Maximum concurrent steps (0 = unlimited).
     max_concurrency: usize,
+    /// Concurrency limits per resource class.
+    resource_limits: HashMap<ResourceClass, Arc<Semaphore>>,
+    /// Retries left to all steps of the run.
+    pub(crate) retry_budget: Option<Arc<RetryBudget>>,
+    /// Recent LLM latencies, used to decide when to hedge a request.
+    pub(crate) latency_tracker: Arc<LatencyTracker>,
+    /// Requests and tokens per provider and model.
+    pub(crate) usage: Arc<UsageTracker>,
     /// LLM provider registry.
-    providers: Arc<DashMap<String, Arc<dyn LLMProvider>>>,
+    pub(crate) providers: Arc<DashMap<String, Arc<dyn LLMProvider>>>,
     /// Embedding provider registry.
-    embedding_providers: Arc<DashMap<String, Arc<dyn EmbeddingProvider>>>,
+    pub(crate) embedding_providers: Arc<DashMap<String, Arc<dyn EmbeddingProvider>>>,
+    /// Transcription provider registry.
+    pub(crate) transcription_providers: Arc<DashMap<String, Arc<dyn TranscriptionProvider>>>,
+    /// Speech provider registry.
+    pub(crate) speech_providers: Arc<DashMap<String, Arc<dyn SpeechProvider>>>,
     /// Vector database registry.
-    vector_dbs: Arc<DashMap<String, Arc<dyn VectorSearchProvider>>>,
-    /// Notification for step completion (for event-driven dependency waiting).
-    step_completion_notify: Arc<Notify>,
+    pub(crate) vector_dbs: Arc<DashMap<String, Arc<dyn VectorSearchProvider>>>,
+    /// Canned step outputs replayed instead of executing the step.
+    mock_responses: Option<Arc<MockResponses>>,
+    /// Whether `exec` actions may run local commands.
+    pub(crate) exec_enabled: bool,
+    /// Brokers `publish` actions send messages to.
+    pub(crate) message_sinks: Arc<DashMap<String, Arc<dyn MessageSink>>>,
+    /// Session memory of `memory.*` actions and LLM steps with `memory`.
+    pub(crate) memory_store: Option<Arc<dyn MemoryStore>>,
+    /// Conversation turns of LLM steps waiting for their step to succeed.
+    pub(crate) pending_turns: Arc<DashMap<String, PendingTurns>>,
+    /// MCP servers `mcp_tool` steps call.
+    pub(crate) mcp_servers: Arc<DashMap<String, Arc<McpClient>>>,
+    /// Handlers of actions, keyed by action name.
+    pub(crate) action_handlers: Arc<DashMap<String, Arc<dyn ActionHandler>>>,
+    /// Alerted when the run fails.
+    pub(crate) failure_notifiers: Vec<Arc<dyn FailureNotifier>>,
+    /// Receive the final outputs when the run finishes.
+    pub(crate) result_sinks: Vec<Arc<dyn ResultSink>>,
+    /// Store of the files steps produce, created on first use.
+    pub(crate) artifact_store: Arc<OnceCell<Arc<dyn ArtifactStore>>>,
+    /// Files steps stored in this run.
+    pub(crate) artifacts: Arc<parking_lot::Mutex<Vec<Artifact>>>,
+    /// Store step outputs are spilled to past the context's memory cap.
+    pub(crate) output_spill: Option<Arc<SpillState>>,
+    /// Working directory settings replacing the workflow's.
+    pub(crate) workdir_config: Option<WorkdirConfig>,
+    /// Records the manifest of a deterministic run.
+    pub(crate) manifest: Option<Arc<ManifestRecorder>>,
+    /// Approval requests waiting for a reviewer.
+    pub(crate) approvals: Arc<ApprovalRegistry>,
+    /// Steps ruled out by an approval decision or failure policy.
+    pruned_steps: Arc<DashSet<String>>,
+    /// Steps a partial re-run executes; the others keep the outputs of the earlier run.
+    pub(crate) rerun_steps: Option<Arc<HashSet<String>>>,
+    /// First step whose `fail_workflow` policy aborted the run.
+    fatal_step: Arc<OnceLock<String>>,
+    /// Panic messages of steps whose task panicked; a run with any is poisoned.
+    panicked_steps: Arc<DashMap<String, String>>,
+    /// Signalled when a `fail_workflow` step aborts the run.
+    abort: CancelHandle,
+    /// Listeners notified of execution events.
+    event_listeners: Vec<Arc<dyn WorkflowEventListener>>,
+    /// Broadcast channel for event subscribers.
+    event_broadcaster: Arc<EventBroadcaster>,
+    /// Cancellation signal for this run.
+    pub(crate) cancel: CancelHandle,
+    /// Set on shutdown; no further steps start once it is.
+    pub(crate) drain: CancelHandle,
+    /// Set once another process owns the run; its state is no longer written.
+    pub(crate) abandon: CancelHandle,
+    /// Content filters applied to every LLM step.
+    pub(crate) content_filters: Vec<(Arc<dyn ContentFilter>, GuardrailPolicy)>,
+    /// Aggregates per-variant metrics of experiment steps.
+    pub(crate) experiment_tracker: Option<Arc<ExperimentTracker>>,
+    /// Resolves `prompt_ref` of LLM steps.
+    pub(crate) prompt_registry: Option<PromptRegistry>,
+    /// State store for persisting run state.
+    #[cfg(feature = "state-persistence")]
+    pub(crate) state_store: Option<Arc<dyn llm_orchestrator_state::StateStore>>,
+    /// Dead-letter queue for steps that exhaust their retries.
+    #[cfg(feature = "state-persistence")]
+    pub(crate) dead_letters: Option<Arc<dyn llm_orchestrator_state::DeadLetterQueue>>,
+    /// Last provider request sent by each step, for dead-letter capture.
+    #[cfg(feature = "state-persistence")]
+    pub(crate) last_requests: Arc<DashMap<String, Value>>,
+    /// Secret store used to resolve secret references.
+    #[cfg(feature = "secrets")]
+    pub(crate) secret_store: Option<Arc<dyn llm_orchestrator_secrets::SecretStore>>,
+    /// Masks secrets in step outputs, errors, and persisted state.
+    #[cfg(feature = "secrets")]
+    pub(crate) redactor: llm_orchestrator_secrets::Redactor,
+    /// Records audit events for this run.
+    #[cfg(feature = "audit")]
+    pub(crate) audit_logger: Option<Arc<llm_orchestrator_audit::AuditLogger>>,
+    /// Actor and prompt policy of audit events.
+    #[cfg(feature = "audit")]
+    pub(crate) audit_config: crate::audit::AuditConfig,
 }
 
 impl WorkflowExecutor {
@@ -107,7 +264,7 @@ impl WorkflowExecutor {
         let dag = WorkflowDAG::from_workflow(&workflow)?;
 
         // Create execution context
-        let context = Arc::new(ExecutionContext::new(inputs));
+        let context = Arc::new(ExecutionContext::new(inputs).with_partials(&workflow.partials)?);
 
         // Initialize step statuses
         let step_statuses = Arc::new(DashMap::new());
@@ -115,17 +272,75 @@ impl WorkflowExecutor {
             step_statuses.insert(step.id.clone(), StepStatus::Pending);
         }
 
+        let retry_budget = workflow.retry_budget.as_ref().map(|budget| {
+            Arc::new(RetryBudget::new(
+                budget.max_retries,
+                budget.max_extra_latency_ms.map(Duration::from_millis),
+            ))
+        });
+
         Ok(Self {
+            run_id: Uuid::new_v4(),
+            user_id: None,
+            tenant_id: None,
             workflow,
             dag,
             context,
             step_statuses,
             step_results: Arc::new(DashMap::new()),
+            step_timings: Arc::new(DashMap::new()),
             max_concurrency: 0, // Unlimited by default
+            resource_limits: HashMap::new(),
+            retry_budget,
+            latency_tracker: Arc::new(LatencyTracker::new()),
+            usage: Arc::new(UsageTracker::new()),
             providers: Arc::new(DashMap::new()),
             embedding_providers: Arc::new(DashMap::new()),
+            transcription_providers: Arc::new(DashMap::new()),
+            speech_providers: Arc::new(DashMap::new()),
             vector_dbs: Arc::new(DashMap::new()),
-            step_completion_notify: Arc::new(Notify::new()),
+            mock_responses: None,
+            exec_enabled: false,
+            message_sinks: Arc::new(DashMap::new()),
+            memory_store: None,
+            pending_turns: Arc::new(DashMap::new()),
+            mcp_servers: Arc::new(DashMap::new()),
+            action_handlers: Arc::new(DashMap::new()),
+            failure_notifiers: Vec::new(),
+            result_sinks: Vec::new(),
+            artifact_store: Arc::new(OnceCell::new()),
+            artifacts: Arc::new(parking_lot::Mutex::new(Vec::new())),
+            output_spill: None,
+            workdir_config: None,
+            manifest: None,
+            approvals: Arc::new(ApprovalRegistry::default()),
+            pruned_steps: Arc::new(DashSet::new()),
+            rerun_steps: None,
+            fatal_step: Arc::new(OnceLock::new()),
+            panicked_steps: Arc::new(DashMap::new()),
+            abort: CancelHandle::default(),
+            event_listeners: Vec::new(),
+            event_broadcaster: Arc::new(EventBroadcaster::default()),
+            cancel: CancelHandle::default(),
+            drain: CancelHandle::default(),
+            abandon: CancelHandle::default(),
+            content_filters: Vec::new(),
+            experiment_tracker: None,
+            prompt_registry: None,
+            #[cfg(feature = "state-persistence")]
+            state_store: None,
+            #[cfg(feature = "state-persistence")]
+            dead_letters: None,
+            #[cfg(feature = "state-persistence")]
+            last_requests: Arc::new(DashMap::new()),
+            #[cfg(feature = "secrets")]
+            secret_store: None,
+            #[cfg(feature = "secrets")]
+            redactor: llm_orchestrator_secrets::Redactor::new().with_default_patterns(),
+            #[cfg(feature = "audit")]
+            audit_logger: None,
+            #[cfg(feature = "audit")]
+            audit_config: crate::audit::AuditConfig::default(),
         })
     }
 
@@ -135,6 +350,72 @@ impl WorkflowExecutor {
         self
     }
 
+    /// Keeps step outputs larger than `limits.blob_threshold` as shared blobs,
+    /// spilling them to temporary files beyond `limits.memory_cap`, or to the
+    /// store given to [`with_output_spill`](Self::with_output_spill).
+    pub fn with_context_limits(mut self, limits: ContextLimits) -> Self {
+        self.context = Arc::new(self.context.as_ref().clone().with_limits(limits));
+        self
+    }
+
+    /// Limits how many steps of a resource class run at once (0 = unlimited).
+    ///
+    /// Steps waiting for their class do not take a `max_concurrency` slot, so
+    /// steps of other classes keep running.
+    pub fn with_resource_limit(mut self, class: ResourceClass, limit: usize) -> Self {
+        if limit == 0 {
+            self.resource_limits.remove(&class);
+        } else {
+            self.resource_limits.insert(class, Arc::new(Semaphore::new(limit)));
+        }
+        self
+    }
+
+    /// Replaces the workflow's `retry_budget` with the given budget.
+    pub fn with_retry_budget(mut self, budget: RetryBudget) -> Self {
+        self.retry_budget = Some(Arc::new(budget));
+        self
+    }
+
+    /// Returns the retry budget of the run, if it has one.
+    pub fn retry_budget(&self) -> Option<&RetryBudget> {
+        self.retry_budget.as_deref()
+    }
+
+    /// Returns the requests and tokens used so far, per provider and model.
+    pub fn usage(&self) -> Vec<ModelUsage> {
+        self.usage.snapshot()
+    }
+
+    /// Returns the bytes step outputs hold in the context.
+    pub fn context_memory(&self) -> ContextMemory {
+        self.context.memory_usage()
+    }
+
+    /// Returns a health registry checking the registered providers, vector
+    /// databases, and secret and state stores.
+    pub fn health_registry(&self) -> HealthRegistry {
+        let mut registry = HealthRegistry::new();
+        for entry in self.providers.iter() {
+            registry.register_provider(entry.key(), entry.value().clone());
+        }
+        for entry in self.embedding_providers.iter() {
+            registry.register_embedding_provider(entry.key(), entry.value().clone());
+        }
+        for entry in self.vector_dbs.iter() {
+            registry.register_vector_db(entry.key(), entry.value().clone());
+        }
+        #[cfg(feature = "secrets")]
+        if let Some(store) = &self.secret_store {
+            registry.register_secret_store("default", store.clone());
+        }
+        #[cfg(feature = "state-persistence")]
+        if let Some(store) = &self.state_store {
+            registry.register_state_store("default", store.clone());
+        }
+        registry
+    }
+
     /// Registers an LLM provider.
     pub fn with_provider(self, name: impl Into<String>, provider: Arc<dyn LLMProvider>) -> Self {
         self.providers.insert(name.into(), provider);
@@ -147,12 +428,155 @@ impl WorkflowExecutor {
         self
     }
 
+    /// Registers a transcription provider.
+    pub fn with_transcription_provider(self, name: impl Into<String>, provider: Arc<dyn TranscriptionProvider>) -> Self {
+        self.transcription_providers.insert(name.into(), provider);
+        self
+    }
+
+    /// Registers a speech provider.
+    pub fn with_speech_provider(self, name: impl Into<String>, provider: Arc<dyn SpeechProvider>) -> Self {
+        self.speech_providers.insert(name.into(), provider);
+        self
+    }
+
     /// Registers a vector database.
     pub fn with_vector_db(self, name: impl Into<String>, vector_db: Arc<dyn VectorSearchProvider>) -> Self {
         self.vector_dbs.insert(name.into(), vector_db);
         self
     }
 
+    /// Replays canned outputs for the mapped steps instead of executing them.
+    ///
+    /// Steps without an entry in `mocks` execute normally.
+    pub fn with_mock_responses(mut self, mocks: MockResponses) -> Self {
+        self.mock_responses = Some(Arc::new(mocks));
+        self
+    }
+
+    /// Registers a listener for execution events.
+    pub fn with_event_listener(mut self, listener: Arc<dyn WorkflowEventListener>) -> Self {
+        self.event_listeners.push(listener);
+        self
+    }
+
+    /// Subscribes to execution events for this run.
+    ///
+    /// Only events emitted after subscribing are received. Slow receivers
+    /// that fall too far behind observe a `Lagged` error and skip ahead.
+    pub fn subscribe(&self) -> broadcast::Receiver<ExecutionEvent> {
+        self.event_broadcaster.subscribe()
+    }
+
+    /// Sets the run identifier (e.g. to resume or track a queued run).
+    pub fn with_run_id(mut self, run_id: Uuid) -> Self {
+        self.run_id = run_id;
+        self
+    }
+
+    /// Sets the user recorded as having started the run.
+    pub fn with_user_id(mut self, user_id: impl Into<String>) -> Self {
+        self.user_id = Some(user_id.into());
+        self
+    }
+
+    /// Sets the tenant the run belongs to.
+    pub fn with_tenant_id(mut self, tenant_id: impl Into<String>) -> Self {
+        self.tenant_id = Some(tenant_id.into());
+        self
+    }
+
+    /// Applies a workflow profile (see [`Workflow::with_profile`]).
+    ///
+    /// Profiles only override existing steps, so step statuses are unchanged;
+    /// the DAG is rebuilt because overrides may change dependencies.
+    pub fn with_profile(mut self, profile: &str) -> Result<Self> {
+        let workflow = self.workflow.with_profile(profile)?;
+        self.dag = WorkflowDAG::from_workflow(&workflow)?;
+        self.workflow = workflow;
+        Ok(self)
+    }
+
+    /// Returns the unique identifier of this run.
+    pub fn run_id(&self) -> Uuid {
+        self.run_id
+    }
+
+    /// Builds a timing, usage, and critical path report of the run so far.
+    pub fn report(&self) -> ExecutionReport {
+        let statuses = self.step_statuses.iter().map(|e| (e.key().clone(), e.value().clone())).collect();
+        let results = self.step_results.iter().map(|e| (e.key().clone(), e.value().clone())).collect();
+        let timings = self.step_timings.iter().map(|e| (e.key().clone(), e.value().clone())).collect();
+        ExecutionReport::build(self.run_id, &self.workflow, &statuses, &results, &timings)
+    }
+
+    /// Adds time spent waiting on a provider to a step's timing.
+    pub(crate) fn record_provider_latency(&self, step_id: &str, latency: Duration) {
+        if let Some(mut timing) = self.step_timings.get_mut(step_id) {
+            timing.provider_latency += latency;
+        }
+    }
+
+    /// Adds the tokens and cost of a provider call to a step's timing.
+    pub(crate) fn record_usage(&self, step_id: &str, usage: &Usage) {
+        if let Some(mut timing) = self.step_timings.get_mut(step_id) {
+            timing.usage.get_or_insert_with(Usage::default).add(usage);
+        }
+    }
+
+    /// Returns a handle that cancels this run.
+    ///
+    /// Cancelling aborts in-flight steps, marks unfinished steps as
+    /// [`StepStatus::Cancelled`], and makes `execute()` return
+    /// [`OrchestratorError::Cancelled`].
+    pub fn cancel_handle(&self) -> CancelHandle {
+        self.cancel.clone()
+    }
+
+    /// Returns a handle that drains this run on shutdown.
+    ///
+    /// Once shutdown is requested no further steps start. If steps remain
+    /// unfinished when the in-flight ones end or are cancelled, the run is
+    /// persisted as paused and `execute()` returns
+    /// [`OrchestratorError::Interrupted`] listing them.
+    pub fn shutdown_handle(&self) -> ShutdownHandle {
+        ShutdownHandle {
+            drain: self.drain.clone(),
+            cancel: self.cancel.clone(),
+        }
+    }
+
+    /// Returns a handle that stops this run once another process owns it.
+    ///
+    /// Abandoning cancels the run like [`cancel_handle`](Self::cancel_handle),
+    /// but neither its state nor a checkpoint is persisted from then on, so
+    /// the new owner's state is left alone.
+    pub fn abandon_handle(&self) -> AbandonHandle {
+        AbandonHandle {
+            abandon: self.abandon.clone(),
+            cancel: self.cancel.clone(),
+        }
+    }
+
+    /// Lists approval steps currently waiting for a decision.
+    pub fn pending_approvals(&self) -> Vec<ApprovalRequest> {
+        self.approvals.pending()
+    }
+
+    /// Returns true while any approval step is waiting for a decision.
+    pub fn is_awaiting_approval(&self) -> bool {
+        self.approvals.has_pending()
+    }
+
+    /// Submits a reviewer decision for a waiting approval step.
+    ///
+    /// The executor is typically shared behind an `Arc` so decisions can be
+    /// submitted from another task while `execute()` is running.
+    pub fn submit_approval(&self, step_id: &str, decision: ApprovalDecision) -> Result<()> {
+        info!(step_id = %step_id, approved = decision.approved, "Approval decision received");
+        self.approvals.submit(step_id, decision)
+    }
+
     /// Executes the workflow.
     ///
     /// Returns a map of step results indexed by step ID.
@@ -162,12 +586,90 @@ impl WorkflowExecutor {
             self.workflow.timeout_seconds.unwrap_or(3600) // Default: 1 hour
         );
 
-        match timeout(timeout_duration, self.execute_inner()).await {
-            Ok(result) => result,
-            Err(_) => Err(OrchestratorError::Timeout {
-                duration: timeout_duration,
-            }),
+        #[cfg(feature = "audit")]
+        self.audit_workflow_started().await;
+        #[cfg(feature = "audit")]
+        let start = std::time::Instant::now();
+
+        let result = match self.create_workdir().await {
+            Ok(()) => match timeout(timeout_duration, self.execute_inner()).await {
+                Ok(result) => result,
+                Err(_) => Err(OrchestratorError::Timeout {
+                    duration: timeout_duration,
+                }),
+            },
+            Err(e) => Err(e),
+        };
+        // A run whose outputs cannot be delivered has failed; spilled outputs are delivered read back
+        let result = match result {
+            Ok(mut results) => match self.read_back_results(&mut results).await {
+                Ok(()) => self.write_results(&results).await.map(|_| results),
+                Err(e) => Err(e),
+            },
+            Err(e) => Err(e),
+        };
+        self.finish_workdir(result.is_ok()).await;
+
+        #[cfg(feature = "audit")]
+        self.audit_workflow_finished(&result, start.elapsed()).await;
+
+        if let Err(e) = &result {
+            self.notify_failure(e).await;
         }
+
+        result
+    }
+
+    /// Checks that each vector search queries with embeddings as long as its
+    /// index's vectors.
+    ///
+    /// The index dimension comes from the step's `dimensions` or, failing that,
+    /// from the database's index stats; the embedding dimension from the embed
+    /// step's `dimensions`, its provider, or the known model table. Searches
+    /// where either side is unknown are left to fail at query time.
+    async fn check_embedding_dimensions(&self) -> Result<()> {
+        for step in &self.workflow.steps {
+            let StepConfig::VectorSearch(search) = &step.config else {
+                continue;
+            };
+            let Some((embed_step, embed)) = self.workflow.query_embedding(search) else {
+                continue;
+            };
+
+            let mut index_dimensions = search.dimensions;
+            if let Some(vector_db) = self.vector_dbs.get(&search.database).map(|db| db.value().clone()) {
+                match vector_db.index_stats(&search.index).await {
+                    Ok(Some(IndexStats { dimension: Some(actual), .. })) => match index_dimensions {
+                        Some(declared) if declared != actual => {
+                            return Err(OrchestratorError::validation(format!(
+                                "Vector search step '{}' declares {} dimensions but index '{}' holds {}-dimensional vectors",
+                                step.id, declared, search.index, actual
+                            )));
+                        }
+                        _ => index_dimensions = Some(actual),
+                    },
+                    Ok(_) => {}
+                    Err(e) => warn!(step_id = %step.id, index = %search.index, error = %e, "Could not read index stats"),
+                }
+            }
+            let Some(index_dimensions) = index_dimensions else {
+                continue;
+            };
+
+            let embed_dimensions = embed.dimensions.or_else(|| {
+                self.embedding_providers
+                    .get(&embed.provider)
+                    .and_then(|provider| provider.dimensions(&embed.model))
+                    .or_else(|| embedding_dimensions(&embed.model))
+            });
+            if let Some(embed_dimensions) = embed_dimensions.filter(|d| *d != index_dimensions) {
+                return Err(OrchestratorError::validation(format!(
+                    "Vector search step '{}' queries index '{}' of {}-dimensional vectors with step '{}' embeddings of {} dimensions (model '{}')",
+                    step.id, search.index, index_dimensions, embed_step.id, embed_dimensions, embed.model
+                )));
+            }
+        }
+        Ok(())
     }
 
     /// Internal execution logic (without timeout wrapper).
@@ -179,84 +681,224 @@ impl WorkflowExecutor {
             "Starting workflow execution"
         );
 
+        // Fail before any step runs if a search would query with the wrong vector length
+        self.check_embedding_dimensions().await?;
+
         // Record workflow start metrics
         metrics::record_workflow_start();
+        self.emit(|l| l.on_workflow_start(self.run_id, &self.workflow));
         let workflow_start = std::time::Instant::now();
+        // Record the run as started so it shows up in run history while in progress
+        self.persist_state().await;
 
-        // Get execution order from DAG
-        let execution_order = self.dag.execution_order()?;
-        debug!("Execution order: {:?}", execution_order);
+        // Steps start as soon as their own dependencies finish, so a slow step
+        // only delays the steps downstream of it
+        let mut schedule = ReadySet::new(&self.workflow.steps);
+        // Dropping the set aborts the step tasks, so a run that times out or
+        // is dropped leaves no step calling providers behind
+        let mut running = JoinSet::new();
+        let mut running_steps = HashMap::new();
 
-        // Track completed steps
-        let completed_steps = Arc::new(RwLock::new(HashSet::new()));
+        loop {
+            // Fill free slots from the ready set; skipped steps finish immediately
+            // without taking a slot
+            while !self.is_stopped()
+                && !self.drain.is_cancelled()
+                && (self.max_concurrency == 0 || running.len() < self.max_concurrency)
+            {
+                let Some(step) = schedule.pop() else {
+                    break;
+                };
 
-        // Execute steps according to DAG dependencies
-        let mut tasks = Vec::new();
+                // Steps restored from a checkpoint already ran
+                if self.is_completed(&step.id) {
+                    schedule.succeed(&step.id);
+                    schedule.complete(&step.id);
+                    continue;
+                }
 
-        for step_id in execution_order {
-            let step = self
-                .workflow
-                .steps
-                .iter()
-                .find(|s| s.id == step_id)
-                .ok_or_else(|| OrchestratorError::StepNotFound(step_id.clone()))?;
+                // A partial re-run leaves the steps it does not select alone
+                if self.rerun_steps.as_ref().is_some_and(|steps| !steps.contains(&step.id)) {
+                    self.mark_skipped(&step.id, SkipReason::NotRerun);
+                    schedule.complete(&step.id);
+                    continue;
+                }
 
-            // Wait for dependencies
-            self.wait_for_dependencies(step, &completed_steps).await?;
+                // Skip steps on a branch ruled out by an approval decision or failure policy
+                if self.pruned_steps.contains(&step.id) {
+                    info!(step_id = %step.id, "Skipping step on pruned branch");
+                    self.mark_skipped(&step.id, SkipReason::PrunedBranch);
+                    schedule.complete(&step.id);
+                    continue;
+                }
 
-            // Check if we should execute based on condition
-            if !self.should_execute(step)? {
-                info!(step_id = %step.id, "Skipping step due to condition");
-                self.mark_skipped(&step.id);
-                continue;
-            }
+                // Fallback steps only run when the step they replace failed
+                if self.is_unused_fallback(step) {
+                    info!(step_id = %step.id, "Skipping fallback step");
+                    self.mark_skipped(&step.id, SkipReason::UnusedFallback);
+                    schedule.complete(&step.id);
+                    continue;
+                }
 
-            // Execute step
-            let executor = self.clone_executor_context();
-            let step_clone = step.clone();
-            let completed = completed_steps.clone();
-            let notify = self.step_completion_notify.clone();
+                // Steps after a step skipped by its condition would read outputs that do not exist
+                if let Some(chain) = self.skipped_upstream(step) {
+                    info!(step_id = %step.id, skipped_step = %chain[0], "Skipping step after upstream skip");
+                    self.mark_skipped(&step.id, SkipReason::UpstreamSkipped { chain });
+                    schedule.complete(&step.id);
+                    continue;
+                }
 
-            let task = tokio::spawn(async move {
-                let result = executor.execute_step(&step_clone).await;
+                // Join steps read the outputs of the first alternative to complete
+                if !step.depends_on_any.is_empty() {
+                    let Some(first) = self.first_completed(&step.depends_on_any) else {
+                        info!(step_id = %step.id, "Skipping step as no alternative completed");
+                        self.mark_skipped(&step.id, SkipReason::NoAlternativeCompleted);
+                        schedule.complete(&step.id);
+                        continue;
+                    };
+                    debug!(step_id = %step.id, joined = %first, "Joining first completed alternative");
+                    self.context.set_joined(&step.id, first);
+                }
 
-                // Mark as completed
-                let mut completed_guard = completed.write().await;
-                completed_guard.insert(step_clone.id.clone());
-                drop(completed_guard);
+                // Read back the spilled outputs the step names; they stay in memory until it finishes
+                let restored = self.restore_outputs(step).await?;
 
-                // Notify all waiting tasks that a step completed
-                notify.notify_waiters();
+                // Check if we should execute based on condition
+                if !self.should_execute(step)? {
+                    info!(step_id = %step.id, "Skipping step due to condition");
+                    self.mark_skipped(&step.id, SkipReason::Condition);
+                    schedule.complete(&step.id);
+                    continue;
+                }
 
-                result
-            });
+                // Hold the step back while its resource class is at its limit
+                let permit = match step.resource_class().and_then(|class| self.resource_limits.get(&class)) {
+                    Some(limit) => match limit.clone().try_acquire_owned() {
+                        Ok(permit) => Some(permit),
+                        Err(_) => {
+                            schedule.defer(step);
+                            continue;
+                        }
+                    },
+                    None => None,
+                };
 
-            tasks.push(task);
+                // Execute step, rendering its templates with its bindings
+                let mut executor = self.clone_executor_context();
+                if !step.bind.is_empty() {
+                    executor.context = Arc::new((*self.context).clone().with_bindings(step.bind.clone()));
+                }
+                let step_clone = step.clone();
+
+                let task = running.spawn(async move {
+                    let _permit = permit;
+                    // Dropping the step future aborts any in-flight provider call
+                    let result = tokio::select! {
+                        biased;
+                        _ = executor.stopped() => Err(OrchestratorError::Cancelled),
+                        result = executor.execute_step(&step_clone) => result,
+                    };
+                    drop(restored);
+                    executor.spill_outputs().await;
+                    result
+                }
+                // Keep step spans nested under the workflow span
+                .in_current_span());
+                running_steps.insert(task.id(), step.id.clone());
+            }
 
-            // Enforce concurrency limit
-            if self.max_concurrency > 0 && tasks.len() >= self.max_concurrency {
-                // Wait for the first task to complete (any task, not just first in vec)
-                let (result, _index, remaining_tasks) = select_all(tasks).await;
-                tasks = remaining_tasks;
+            if running.is_empty() {
+                break;
+            }
 
-                // Log the completed task result
-                if let Err(e) = result {
-                    error!("Task failed: {:?}", e);
-                }
+            // Wait for any running step, stopping early if the run is cancelled or aborted
+            let (task_id, result) = tokio::select! {
+                biased;
+                _ = self.stopped() => break,
+                Some(finished) = running.join_next_with_id() => match finished {
+                    Ok((task_id, result)) => (task_id, Ok(result)),
+                    Err(e) => (e.id(), Err(e)),
+                },
+            };
+            let step_id = running_steps.remove(&task_id).expect("every task runs a step");
+            match result {
+                Err(e) if e.is_panic() => self.record_panic(&step_id, e.into_panic()).await,
+                Err(e) => error!(step_id = %step_id, "Task failed: {:?}", e),
+                Ok(_) => {}
+            }
+            if self.is_completed(&step_id) {
+                schedule.succeed(&step_id);
+            }
+            schedule.complete(&step_id);
+            schedule.resume_deferred();
+
+            // A run that dies from here on resumes after this step
+            if self.is_completed(&step_id) {
+                self.checkpoint(&step_id).await;
+            }
+
+            // Nothing downstream of a failure can run, so skip it all right away
+            if self.fails_fast(&step_id) {
+                self.skip_downstream(&step_id, &mut schedule);
+            }
+        }
+
+        // Wait for in-flight steps to observe the stop and record their status
+        while running.join_next().await.is_some() {}
+
+        // Leave a drained run resumable rather than cancelled
+        if self.drain.is_cancelled() {
+            let unfinished = self.unfinished_steps();
+            if !unfinished.is_empty() {
+                warn!(workflow_name = %self.workflow.name, unfinished = unfinished.len(), "Workflow interrupted by shutdown");
+
+                self.persist_state().await;
+                self.checkpoint("shutdown").await;
+                self.emit(|l| l.on_workflow_cancelled(self.run_id, &self.collect_results()));
+                return Err(OrchestratorError::Interrupted(unfinished));
             }
         }
 
-        // Wait for all remaining tasks
-        for task in tasks {
-            let _ = task.await;
+        if self.cancel.is_cancelled() {
+            self.mark_unfinished_cancelled();
+            let results = self.collect_results();
+            warn!(workflow_name = %self.workflow.name, "Workflow cancelled");
+
+            self.persist_state().await;
+            self.emit(|l| l.on_workflow_cancelled(self.run_id, &results));
+            return Err(OrchestratorError::Cancelled);
+        }
+
+        if let Some(step_id) = self.fatal_step.get() {
+            self.mark_unfinished_cancelled();
+            let results = self.collect_results();
+            let reason = results
+                .get(step_id)
+                .and_then(|r| r.error.clone())
+                .unwrap_or_default();
+            error!(step_id = %step_id, "Workflow aborted by failed step");
+
+            self.persist_state().await;
+            self.emit(|l| l.on_workflow_complete(self.run_id, &results));
+            return Err(OrchestratorError::WorkflowFailed {
+                step_id: step_id.clone(),
+                reason,
+            });
+        }
+
+        // Failures under the default policy fail the run once unaffected steps finish
+        let failures = self.step_failures();
+        if !failures.is_empty() {
+            let results = self.collect_results();
+            error!(failed_steps = failures.len(), "Workflow failed");
+
+            self.persist_state().await;
+            self.emit(|l| l.on_workflow_complete(self.run_id, &results));
+            return Err(OrchestratorError::StepsFailed(failures));
         }
 
         // Collect results
-        let results: HashMap<String, StepResult> = self
-            .step_results
-            .iter()
-            .map(|entry| (entry.key().clone(), entry.value().clone()))
-            .collect();
+        let results = self.collect_results();
 
         // Check for failures
         let failures: Vec<_> = results
@@ -283,39 +925,10 @@ impl WorkflowExecutor {
             info!("Workflow completed successfully");
         }
 
-        Ok(results)
-    }
-
-    /// Waits for all dependencies of a step to complete.
-    ///
-    /// Uses event-driven notifications instead of polling for efficiency.
-    async fn wait_for_dependencies(
-        &self,
-        step: &Step,
-        completed: &Arc<RwLock<HashSet<String>>>,
-    ) -> Result<()> {
-        // If no dependencies, return immediately
-        if step.depends_on.is_empty() {
-            return Ok(());
-        }
-
-        loop {
-            // Check if all dependencies are complete
-            {
-                let completed_guard = completed.read().await;
-                let all_deps_complete = step
-                    .depends_on
-                    .iter()
-                    .all(|dep| completed_guard.contains(dep));
+        self.persist_state().await;
+        self.emit(|l| l.on_workflow_complete(self.run_id, &results));
 
-                if all_deps_complete {
-                    return Ok(());
-                }
-            } // Drop read lock
-
-            // Wait for notification that a step completed
-            self.step_completion_notify.notified().await;
-        }
+        Ok(results)
     }
 
     /// Checks if a step should execute based on its condition.
@@ -328,7 +941,21 @@ impl WorkflowExecutor {
     }
 
     /// Marks a step as skipped.
-    fn mark_skipped(&self, step_id: &str) {
+    fn mark_skipped(&self, step_id: &str, reason: SkipReason) {
+        // Point steps skipped after a failure at the error that caused it
+        let error = match &reason {
+            SkipReason::UpstreamFailed { chain } => chain.last().map(|failed| {
+                let cause = self
+                    .step_results
+                    .get(failed)
+                    .and_then(|r| r.error.clone())
+                    .unwrap_or_default();
+                format!("Upstream step '{}' failed: {}", failed, cause)
+            }),
+            _ => None,
+        };
+
+        self.emit(|l| l.on_step_skipped(self.run_id, step_id));
         self.step_statuses
             .insert(step_id.to_string(), StepStatus::Skipped);
         self.step_results.insert(
@@ -337,30 +964,389 @@ impl WorkflowExecutor {
                 step_id: step_id.to_string(),
                 status: StepStatus::Skipped,
                 outputs: HashMap::new(),
-                error: None,
+                error,
+                skip_reason: Some(reason),
+                error_details: None,
                 duration: Duration::from_secs(0),
+                usage: None,
             },
         );
     }
 
+    /// The chain of steps back to a condition skip, if a dependency of the
+    /// step was skipped by its condition or after one.
+    fn skipped_upstream(&self, step: &Step) -> Option<Vec<String>> {
+        if step.allow_skipped_deps {
+            return None;
+        }
+        step.depends_on.iter().find_map(|dep| {
+            let result = self.step_results.get(dep)?;
+            let mut chain = vec![dep.clone()];
+            match &result.skip_reason {
+                Some(SkipReason::Condition | SkipReason::NoAlternativeCompleted) => {}
+                Some(SkipReason::UpstreamSkipped { chain: upstream }) => chain.extend(upstream.iter().cloned()),
+                _ => return None,
+            }
+            Some(chain)
+        })
+    }
+
+    /// The step among `alternatives` that completed first; steps restored
+    /// from a checkpoint count as completing before the others.
+    fn first_completed(&self, alternatives: &[String]) -> Option<String> {
+        alternatives
+            .iter()
+            .enumerate()
+            .filter(|(_, step_id)| self.is_completed(step_id))
+            .min_by_key(|(i, step_id)| {
+                let completed_at = self.step_timings.get(step_id.as_str()).and_then(|timing| timing.completed_at);
+                (completed_at, *i)
+            })
+            .map(|(_, step_id)| step_id.clone())
+    }
+
+    /// Returns true once the run is cancelled or aborted by a failed step.
+    fn is_stopped(&self) -> bool {
+        self.cancel.is_cancelled() || self.abort.is_cancelled()
+    }
+
+    /// Resolves once the run is cancelled or aborted by a failed step.
+    async fn stopped(&self) {
+        tokio::select! {
+            _ = self.cancel.cancelled() => {}
+            _ = self.abort.cancelled() => {}
+        }
+    }
+
+    /// Returns true if the step is a fallback whose primary step did not fail.
+    fn is_unused_fallback(&self, step: &Step) -> bool {
+        let fallback_for: Vec<&Step> = self
+            .workflow
+            .steps
+            .iter()
+            .filter(|s| matches!(&s.on_failure, Some(FailurePolicy::RunStep(target)) if *target == step.id))
+            .collect();
+
+        !fallback_for.is_empty()
+            && fallback_for.iter().all(|primary| {
+                self.step_statuses
+                    .get(&primary.id)
+                    .map(|status| *status != StepStatus::Failed)
+                    .unwrap_or(true)
+            })
+    }
+
+    /// Applies the step's failure policy after it failed.
+    fn apply_failure_policy(&self, step: &Step) {
+        match &step.on_failure {
+            Some(FailurePolicy::FailWorkflow) => {
+                let _ = self.fatal_step.set(step.id.clone());
+                self.abort.cancel();
+            }
+            Some(FailurePolicy::SkipDependents) => {
+                for step_id in self.dag.descendants(&step.id) {
+                    self.pruned_steps.insert(step_id);
+                }
+            }
+            Some(FailurePolicy::RunStep(target)) => {
+                info!(step_id = %step.id, fallback = %target, "Running fallback step");
+            }
+            Some(FailurePolicy::Continue) | None => {}
+        }
+    }
+
+    /// Fails a step whose task panicked and marks the run as poisoned.
+    ///
+    /// The step's failure policy applies as if it had returned an error, so
+    /// the results stay complete instead of missing the step.
+    async fn record_panic(&self, step_id: &str, payload: Box<dyn std::any::Any + Send>) {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "Box<dyn Any>".to_string());
+        let message = self.redact(message);
+        error!(step_id = %step_id, panic = %message, "Step panicked; run is poisoned");
+        metrics::record_error("panic", "executor");
+        self.panicked_steps.insert(step_id.to_string(), message.clone());
+
+        let Some(step) = self.workflow.steps.iter().find(|s| s.id == step_id) else {
+            return;
+        };
+        let mut duration = Duration::ZERO;
+        if let Some(mut timing) = self.step_timings.get_mut(step_id) {
+            let completed_at = Utc::now();
+            duration = (completed_at - timing.started_at).to_std().unwrap_or_default();
+            timing.completed_at = Some(completed_at);
+        }
+        let err = OrchestratorError::StepPanicked {
+            step_id: step_id.to_string(),
+            message,
+        };
+        let step_result = StepResult {
+            step_id: step_id.to_string(),
+            status: StepStatus::Failed,
+            outputs: HashMap::new(),
+            error: Some(err.to_string()),
+            skip_reason: None,
+            error_details: Some(StepError::from_error(&err, 1)),
+            duration,
+            usage: None,
+        };
+        self.step_statuses.insert(step_id.to_string(), StepStatus::Failed);
+        self.step_results.insert(step_id.to_string(), step_result.clone());
+
+        #[cfg(feature = "audit")]
+        self.audit_step(step, &step_result).await;
+
+        self.emit(|l| l.on_step_failed(self.run_id, &step_result));
+        self.apply_failure_policy(step);
+    }
+
+    /// Returns true once a step task of this run has panicked.
+    pub fn is_poisoned(&self) -> bool {
+        !self.panicked_steps.is_empty()
+    }
+
+    /// Steps whose task panicked, with the panic message, sorted by step ID.
+    pub fn panicked_steps(&self) -> Vec<(String, String)> {
+        let mut panicked: Vec<_> = self
+            .panicked_steps
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .collect();
+        panicked.sort();
+        panicked
+    }
+
+    /// Returns true if the step failed under the default failure policy.
+    ///
+    /// Rejected approvals route to their `on_reject` branch instead.
+    fn fails_fast(&self, step_id: &str) -> bool {
+        let failed = self
+            .step_statuses
+            .get(step_id)
+            .is_some_and(|status| *status == StepStatus::Failed);
+        failed
+            && self
+                .workflow
+                .steps
+                .iter()
+                .find(|s| s.id == step_id)
+                .is_some_and(|s| s.on_failure.is_none() && s.step_type != StepType::Approval)
+    }
+
+    /// Skips every step downstream of a failed step, recording the chain of
+    /// steps leading back to the failure.
+    fn skip_downstream(&self, failed: &str, schedule: &mut ReadySet<'_>) {
+        let mut queue = VecDeque::from([(failed.to_string(), vec![failed.to_string()])]);
+        while let Some((step_id, chain)) = queue.pop_front() {
+            for dependent in schedule.dependents(&step_id) {
+                if schedule.is_done(&dependent.id) {
+                    continue;
+                }
+                info!(step_id = %dependent.id, failed_step = %failed, "Skipping step after upstream failure");
+                self.mark_skipped(&dependent.id, SkipReason::UpstreamFailed { chain: chain.clone() });
+                schedule.complete(&dependent.id);
+
+                let mut dependent_chain = vec![dependent.id.clone()];
+                dependent_chain.extend(chain.iter().cloned());
+                queue.push_back((dependent.id.clone(), dependent_chain));
+            }
+        }
+    }
+
+    /// Steps that failed under the default failure policy, in workflow order.
+    fn step_failures(&self) -> Vec<StepFailure> {
+        self.workflow
+            .steps
+            .iter()
+            .filter(|step| self.fails_fast(&step.id))
+            .map(|step| StepFailure {
+                step_id: step.id.clone(),
+                reason: self
+                    .step_results
+                    .get(&step.id)
+                    .and_then(|r| r.error.clone())
+                    .unwrap_or_default(),
+                skipped: self
+                    .workflow
+                    .steps
+                    .iter()
+                    .filter(|s| {
+                        self.step_results.get(&s.id).is_some_and(|r| {
+                            matches!(&r.skip_reason, Some(SkipReason::UpstreamFailed { chain }) if chain.last() == Some(&step.id))
+                        })
+                    })
+                    .map(|s| s.id.clone())
+                    .collect(),
+            })
+            .collect()
+    }
+
+    /// Marks every step that has not finished as cancelled.
+    fn mark_unfinished_cancelled(&self) {
+        for mut entry in self.step_statuses.iter_mut() {
+            if !matches!(entry.value(), StepStatus::Pending | StepStatus::Running) {
+                continue;
+            }
+            *entry.value_mut() = StepStatus::Cancelled;
+            self.step_results.insert(
+                entry.key().clone(),
+                StepResult {
+                    step_id: entry.key().clone(),
+                    status: StepStatus::Cancelled,
+                    outputs: HashMap::new(),
+                    error: Some(OrchestratorError::Cancelled.to_string()),
+                    skip_reason: None,
+                    error_details: None,
+                    duration: Duration::from_secs(0),
+                    usage: None,
+                },
+            );
+        }
+    }
+
+    /// IDs of the steps without a final result, in workflow order.
+    fn unfinished_steps(&self) -> Vec<String> {
+        self.workflow
+            .steps
+            .iter()
+            .filter(|step| {
+                self.step_results
+                    .get(&step.id)
+                    .map_or(true, |r| matches!(r.status, StepStatus::Pending | StepStatus::Running))
+            })
+            .map(|step| step.id.clone())
+            .collect()
+    }
+
+    /// Snapshots the step results.
+    fn collect_results(&self) -> HashMap<String, StepResult> {
+        self.step_results
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .collect()
+    }
+
     /// Clones the executor context for parallel execution.
     fn clone_executor_context(&self) -> Self {
         Self {
+            run_id: self.run_id,
+            user_id: self.user_id.clone(),
+            tenant_id: self.tenant_id.clone(),
             workflow: self.workflow.clone(),
             dag: self.dag.clone(),
             context: self.context.clone(),
             step_statuses: self.step_statuses.clone(),
             step_results: self.step_results.clone(),
+            step_timings: self.step_timings.clone(),
             max_concurrency: self.max_concurrency,
+            resource_limits: self.resource_limits.clone(),
+            retry_budget: self.retry_budget.clone(),
+            latency_tracker: self.latency_tracker.clone(),
+            usage: self.usage.clone(),
             providers: self.providers.clone(),
             embedding_providers: self.embedding_providers.clone(),
+            transcription_providers: self.transcription_providers.clone(),
+            speech_providers: self.speech_providers.clone(),
             vector_dbs: self.vector_dbs.clone(),
-            step_completion_notify: self.step_completion_notify.clone(),
+            mock_responses: self.mock_responses.clone(),
+            exec_enabled: self.exec_enabled,
+            message_sinks: self.message_sinks.clone(),
+            memory_store: self.memory_store.clone(),
+            pending_turns: self.pending_turns.clone(),
+            mcp_servers: self.mcp_servers.clone(),
+            action_handlers: self.action_handlers.clone(),
+            failure_notifiers: self.failure_notifiers.clone(),
+            result_sinks: self.result_sinks.clone(),
+            artifact_store: self.artifact_store.clone(),
+            artifacts: self.artifacts.clone(),
+            output_spill: self.output_spill.clone(),
+            workdir_config: self.workdir_config.clone(),
+            manifest: self.manifest.clone(),
+            approvals: self.approvals.clone(),
+            pruned_steps: self.pruned_steps.clone(),
+            rerun_steps: self.rerun_steps.clone(),
+            fatal_step: self.fatal_step.clone(),
+            panicked_steps: self.panicked_steps.clone(),
+            abort: self.abort.clone(),
+            event_listeners: self.event_listeners.clone(),
+            event_broadcaster: self.event_broadcaster.clone(),
+            cancel: self.cancel.clone(),
+            drain: self.drain.clone(),
+            abandon: self.abandon.clone(),
+            content_filters: self.content_filters.clone(),
+            experiment_tracker: self.experiment_tracker.clone(),
+            prompt_registry: self.prompt_registry.clone(),
+            #[cfg(feature = "state-persistence")]
+            state_store: self.state_store.clone(),
+            #[cfg(feature = "state-persistence")]
+            dead_letters: self.dead_letters.clone(),
+            #[cfg(feature = "state-persistence")]
+            last_requests: self.last_requests.clone(),
+            #[cfg(feature = "secrets")]
+            secret_store: self.secret_store.clone(),
+            #[cfg(feature = "secrets")]
+            redactor: self.redactor.clone(),
+            #[cfg(feature = "audit")]
+            audit_logger: self.audit_logger.clone(),
+            #[cfg(feature = "audit")]
+            audit_config: self.audit_config.clone(),
         }
     }
 
+    /// Creates an executor for a workflow run on behalf of this one.
+    ///
+    /// It shares the providers, stores, and handlers of this executor, counts
+    /// its usage toward this run, and stops when this run is cancelled. It is
+    /// not persisted and reports no events.
+    pub(crate) fn sub_executor(&self, workflow: Workflow, inputs: HashMap<String, Value>) -> Result<Self> {
+        let mut executor = Self::new(workflow, inputs)?;
+        executor.user_id = self.user_id.clone();
+        executor.tenant_id = self.tenant_id.clone();
+        executor.max_concurrency = self.max_concurrency;
+        executor.latency_tracker = self.latency_tracker.clone();
+        executor.usage = self.usage.clone();
+        executor.providers = self.providers.clone();
+        executor.embedding_providers = self.embedding_providers.clone();
+        executor.transcription_providers = self.transcription_providers.clone();
+        executor.speech_providers = self.speech_providers.clone();
+        executor.artifact_store = self.artifact_store.clone();
+        executor.artifacts = self.artifacts.clone();
+        executor.workdir_config = self.workdir_config.clone();
+        executor.vector_dbs = self.vector_dbs.clone();
+        executor.exec_enabled = self.exec_enabled;
+        executor.message_sinks = self.message_sinks.clone();
+        executor.memory_store = self.memory_store.clone();
+        executor.mcp_servers = self.mcp_servers.clone();
+        executor.action_handlers = self.action_handlers.clone();
+        executor.cancel = self.cancel.clone();
+        executor.drain = self.drain.clone();
+        executor.content_filters = self.content_filters.clone();
+        executor.prompt_registry = self.prompt_registry.clone();
+        #[cfg(feature = "secrets")]
+        {
+            executor.secret_store = self.secret_store.clone();
+            executor.redactor = self.redactor.clone();
+        }
+        Ok(executor)
+    }
+
     /// Executes a single step with retry logic.
-    #[instrument(skip(self, step), fields(step_id = %step.id, step_type = ?step.step_type))]
+    #[instrument(
+        skip(self, step),
+        fields(
+            step_id = %step.id,
+            step_type = ?step.step_type,
+            provider = tracing::field::Empty,
+            model = tracing::field::Empty,
+            input_tokens = tracing::field::Empty,
+            output_tokens = tracing::field::Empty,
+            total_tokens = tracing::field::Empty,
+            cost_usd = tracing::field::Empty,
+        )
+    )]
     async fn execute_step(&self, step: &Step) -> Result<StepResult> {
         let start = std::time::Instant::now();
 
@@ -369,16 +1355,26 @@ impl WorkflowExecutor {
         // Update status to running
         self.step_statuses
             .insert(step.id.clone(), StepStatus::Running);
+        self.step_timings.insert(step.id.clone(), StepTiming::start());
+        self.emit(|l| l.on_step_start(self.run_id, step));
 
         // Get retry policy from step config or use default
-        let retry_policy = self.get_retry_policy(step);
-        let retry_executor = RetryExecutor::new(retry_policy);
+        let mut retry_policy = self.get_retry_policy(step);
+        // Deterministic runs wait the same between attempts every time
+        if self.is_deterministic() {
+            retry_policy.jitter = false;
+        }
+        let mut retry_executor = RetryExecutor::new(retry_policy);
+        if let Some(budget) = &self.retry_budget {
+            retry_executor = retry_executor.with_budget(budget.clone());
+        }
 
-        // Execute with retry
+        // Execute with retry, keeping the error of every failed attempt
+        let mut failed_attempts = Vec::new();
         let result = retry_executor
-            .execute(|| async {
+            .execute_with_retry_hook(|| async {
                 // Apply timeout if configured
-                if let Some(timeout_secs) = step.timeout_seconds {
+                let outputs = if let Some(timeout_secs) = step.timeout_seconds {
                     let timeout_duration = Duration::from_secs(timeout_secs);
                     match timeout(timeout_duration, self.execute_step_inner(step)).await {
                         Ok(result) => result,
@@ -388,17 +1384,34 @@ impl WorkflowExecutor {
                     }
                 } else {
                     self.execute_step_inner(step).await
-                }
+                }?;
+                self.check_assertions(step, &outputs)?;
+                self.record_conversation_turns(step).await?;
+                Ok(outputs)
+            }, |attempt, err| {
+                let message = self.redact(err.to_string());
+                warn!(step_id = %step.id, attempt, error = %message, "Retrying step");
+                failed_attempts.push((attempt, message, Utc::now()));
+                self.emit(|l| l.on_retry(self.run_id, step, attempt, err));
             })
             .await;
 
         let duration = start.elapsed();
+        let mut usage = None;
+        if let Some(mut timing) = self.step_timings.get_mut(&step.id) {
+            timing.completed_at = Some(Utc::now());
+            timing.attempts = failed_attempts.len() as u32 + 1;
+            usage = timing.usage.map(|usage| Usage {
+                latency_ms: Some(timing.provider_latency.as_millis() as u64),
+                ..usage
+            });
+        }
 
         // Get step type string for metrics
         let _step_type_str = format!("{:?}", step.step_type).to_lowercase();
 
         let step_result = match result {
-            Ok(outputs) => {
+            Ok(mut outputs) => {
                 info!(step_id = %step.id, duration_ms = duration.as_millis(), "Step completed successfully");
                 self.step_statuses
                     .insert(step.id.clone(), StepStatus::Completed);
@@ -407,6 +1420,9 @@ impl WorkflowExecutor {
                 // TODO: Implement metrics module
                 // metrics::record_step_execution(&step_type_str, duration.as_secs_f64(), "success");
 
+                // Mask secrets before outputs reach the context, results, and persisted state
+                outputs.values_mut().for_each(|value| self.redact_json(value));
+
                 // Store outputs in context as a JSON object
                 let outputs_json = serde_json::to_value(&outputs)
                     .unwrap_or_else(|_| Value::Object(serde_json::Map::new()));
@@ -417,11 +1433,15 @@ impl WorkflowExecutor {
                     status: StepStatus::Completed,
                     outputs,
                     error: None,
+                    skip_reason: None,
+                    error_details: None,
                     duration,
+                    usage,
                 }
             }
             Err(err) => {
-                error!(step_id = %step.id, error = %err, "Step failed");
+                let message = self.redact(err.to_string());
+                error!(step_id = %step.id, error = %message, "Step failed");
                 self.step_statuses
                     .insert(step.id.clone(), StepStatus::Failed);
 
@@ -440,12 +1460,27 @@ impl WorkflowExecutor {
                 // TODO: Implement metrics module
                 // metrics::record_error(error_type, "step_executor");
 
+                let mut details = StepError::from_error(&err, failed_attempts.len() as u32 + 1);
+                details.message = message.clone();
+                details.causes = details.causes.into_iter().map(|c| self.redact(c)).collect();
+
+                // Approval rejections are decisions, not failures worth re-driving
+                if step.step_type == StepType::Approval {
+                    details.kind = ErrorKind::Rejected;
+                } else {
+                    failed_attempts.push((failed_attempts.len() as u32 + 1, message.clone(), Utc::now()));
+                    self.capture_dead_letter(step, &message, failed_attempts).await;
+                }
+
                 StepResult {
                     step_id: step.id.clone(),
                     status: StepStatus::Failed,
                     outputs: HashMap::new(),
-                    error: Some(err.to_string()),
+                    error: Some(message),
+                    skip_reason: None,
+                    error_details: Some(details),
                     duration,
+                    usage,
                 }
             }
         };
@@ -454,19 +1489,50 @@ impl WorkflowExecutor {
         self.step_results
             .insert(step.id.clone(), step_result.clone());
 
+        #[cfg(feature = "audit")]
+        self.audit_step(step, &step_result).await;
+
+        if step_result.status == StepStatus::Completed {
+            self.emit(|l| l.on_step_complete(self.run_id, &step_result));
+        } else {
+            self.emit(|l| l.on_step_failed(self.run_id, &step_result));
+            self.apply_failure_policy(step);
+        }
+
         Ok(step_result)
     }
 
     /// Inner step execution logic (actual work).
     async fn execute_step_inner(&self, step: &Step) -> Result<HashMap<String, Value>> {
+        if let Some(outputs) = self.mock_responses.as_ref().and_then(|m| m.get(&step.id)) {
+            debug!(step_id = %step.id, "Replaying mocked step outputs");
+            return Ok(outputs.clone());
+        }
+        self.context.check_bindings()?;
+
         match &step.step_type {
-            StepType::Llm => self.execute_llm_step(step).await,
+            StepType::Llm => match &step.config {
+                StepConfig::Llm(config) if !config.variants.is_empty() => {
+                    self.execute_llm_variants(step).await
+                }
+                _ => self.execute_llm_step(step).await,
+            },
             StepType::Embed => self.execute_embed_step(step).await,
             StepType::VectorSearch => self.execute_vector_search_step(step).await,
+            StepType::VectorUpsert => self.execute_vector_upsert_step(step).await,
+            StepType::VectorFetch => self.execute_vector_fetch_step(step).await,
             StepType::Transform => self.execute_transform_step(step).await,
             StepType::Action => self.execute_action_step(step).await,
             StepType::Parallel => self.execute_parallel_step(step).await,
             StepType::Branch => self.execute_branch_step(step).await,
+            StepType::Approval => self.execute_approval_step(step).await,
+            StepType::Evaluate => self.execute_evaluate_step(step).await,
+            StepType::Http => self.execute_http_step(step).await,
+            StepType::McpTool => self.execute_mcp_tool_step(step).await,
+            StepType::Agent => self.execute_agent_step(step).await,
+            StepType::WebFetch => self.execute_web_fetch_step(step).await,
+            StepType::Transcribe => self.execute_transcribe_step(step).await,
+            StepType::Speak => self.execute_speak_step(step).await,
         }
     }
 
@@ -492,7 +1558,7 @@ impl WorkflowExecutor {
     }
 
     /// Executes an LLM step using the registered provider.
-    async fn execute_llm_step(&self, step: &Step) -> Result<HashMap<String, Value>> {
+    pub(crate) async fn execute_llm_step(&self, step: &Step) -> Result<HashMap<String, Value>> {
         // Extract LLM config
         let llm_config = match &step.config {
             StepConfig::Llm(config) => config,
@@ -513,18 +1579,44 @@ impl WorkflowExecutor {
                 llm_config.provider
             )))?;
 
-        // Render prompt template
-        let rendered_prompt = self.context.render_template(&llm_config.prompt)?;
+        // Render prompt template, from the registry if the step references one
+        let registry_prompt = self.resolve_prompt_ref(step, llm_config).await?;
+        let prompt_template = registry_prompt
+            .as_ref()
+            .map_or(llm_config.prompt.as_str(), |p| p.template.as_str());
+        self.resolve_template_secrets(prompt_template).await?;
+        let rendered_prompt = self.context.render_template(prompt_template)?;
+        let mut verdicts = Vec::new();
+        let rendered_prompt = self
+            .apply_guardrails(step, GuardrailStage::Input, rendered_prompt, &mut verdicts)
+            .await?;
+
+        // Continue the session's conversation, if the step keeps one
+        let conversation = match &llm_config.memory {
+            Some(memory) => Some(self.conversation_history(step, memory).await?),
+            None => None,
+        };
+        let (session_id, history) = conversation.unzip();
 
         // Build completion request
-        let request = CompletionRequest {
+        let mut request = CompletionRequest {
             model: llm_config.model.clone(),
             prompt: rendered_prompt,
-            system: llm_config.system.clone(),
+            system: llm_config
+                .system
+                .clone()
+                .or_else(|| registry_prompt.as_ref().and_then(|p| p.system.clone())),
+            history: history.unwrap_or_default(),
+            images: self.resolve_images(&step.id, &llm_config.images).await?,
             temperature: llm_config.temperature,
             max_tokens: llm_config.max_tokens,
+            tools: Vec::new(),
             extra: llm_config.extra.clone(),
+            headers: HashMap::new(),
         };
+        self.resolve_request_secrets(&mut request.extra).await?;
+        self.pin_sampling(&mut request);
+        let sent = self.is_deterministic().then(|| SentRequest::of(&request));
 
         // Call provider with metrics
         debug!(
@@ -533,20 +1625,64 @@ impl WorkflowExecutor {
             model = %llm_config.model,
             "Calling LLM provider"
         );
-
+        let span = Span::current();
+        span.record("provider", llm_config.provider.as_str());
+        span.record("model", llm_config.model.as_str());
+
+        self.record_request(&step.id, &request);
+        #[cfg(feature = "audit")]
+        let audit_prompt = request.prompt.clone();
+        let recorded_prompt = session_id.as_ref().map(|_| request.prompt.clone());
         let llm_start = std::time::Instant::now();
-        let response_result = provider.complete(request).await;
+        let response_result = self.complete_llm(step, llm_config, provider.as_ref(), request).await;
         let llm_duration = llm_start.elapsed().as_secs_f64();
+        self.record_provider_latency(&step.id, llm_start.elapsed());
+
+        // Guard the completion before it is audited or stored
+        let guarded_text = match &response_result {
+            Ok(resp) => Some(
+                self.apply_guardrails(step, GuardrailStage::Output, resp.text.clone(), &mut verdicts)
+                    .await,
+            ),
+            Err(_) => None,
+        };
 
-        let response = match response_result {
+        #[cfg(feature = "audit")]
+        self.audit_provider_call(crate::audit::ProviderCall {
+            step_id: &step.id,
+            provider: &llm_config.provider,
+            model: &llm_config.model,
+            prompt: &audit_prompt,
+            completion: guarded_text.as_ref().and_then(|r| r.as_ref().ok()).map(String::as_str),
+            tokens_used: response_result.as_ref().ok().and_then(|r| r.tokens_used),
+            error: response_result.as_ref().err().map(|e| e.to_string()),
+            duration: llm_start.elapsed(),
+        })
+        .await;
+
+        let mut response = match response_result {
             Ok(resp) => {
                 // Record successful LLM request
-                let input_tokens = resp.metadata.get("input_tokens")
-                    .and_then(|v| v.as_u64())
-                    .map(|t| t as u32);
-                let output_tokens = resp.metadata.get("output_tokens")
-                    .and_then(|v| v.as_u64())
-                    .map(|t| t as u32);
+                let usage = completion_usage(&resp);
+                self.record_usage(&step.id, &usage);
+                let (input_tokens, output_tokens) = if usage.input_tokens + usage.output_tokens > 0 {
+                    (Some(usage.input_tokens as u32), Some(usage.output_tokens as u32))
+                } else {
+                    (None, None)
+                };
+
+                if let Some(tokens) = input_tokens {
+                    span.record("input_tokens", tokens);
+                }
+                if let Some(tokens) = output_tokens {
+                    span.record("output_tokens", tokens);
+                }
+                if usage.total_tokens > 0 {
+                    span.record("total_tokens", usage.total_tokens);
+                }
+                if let Some(cost) = usage.cost_usd {
+                    span.record("cost_usd", cost);
+                }
 
                 metrics::record_llm_request(
                     &llm_config.provider,
@@ -556,6 +1692,10 @@ impl WorkflowExecutor {
                     input_tokens,
                     output_tokens,
                 );
+                self.usage.record_completion(&llm_config.provider, &llm_config.model, Some(&resp));
+                if let Some(sent) = sent {
+                    self.record_manifest(&step.id, &llm_config.provider, sent, &resp);
+                }
 
                 resp
             }
@@ -569,10 +1709,14 @@ impl WorkflowExecutor {
                     None,
                     None,
                 );
+                self.usage.record_completion(&llm_config.provider, &llm_config.model, None);
 
-                return Err(OrchestratorError::other(format!("Provider error: {}", e)));
+                return Err(provider_error(&llm_config.provider, "Provider error", e));
             }
         };
+        if let Some(text) = guarded_text {
+            response.text = text?;
+        }
 
         // Build output
         let mut outputs = HashMap::new();
@@ -621,6 +1765,26 @@ impl WorkflowExecutor {
         // Always store full response metadata under special key for debugging
         outputs.insert("_response".to_string(), serde_json::to_value(&response)?);
 
+        // Add the exchange to the conversation once the step succeeds
+        if let (Some(memory), Some(session_id), Some(prompt)) = (&llm_config.memory, session_id, recorded_prompt) {
+            if memory.record {
+                self.stage_conversation_turns(step, session_id, memory, &prompt, &response.text);
+            }
+        }
+
+        // Record which registry prompt version was used
+        if let Some(prompt) = &registry_prompt {
+            outputs.insert(
+                "_prompt".to_string(),
+                serde_json::json!({ "name": prompt.name, "version": prompt.version }),
+            );
+        }
+
+        // Record what the guardrails found, if anything
+        if !verdicts.is_empty() {
+            outputs.insert("_guardrails".to_string(), serde_json::to_value(&verdicts)?);
+        }
+
         debug!(step_id = %step.id, "LLM step completed successfully");
 
         Ok(outputs)
@@ -649,7 +1813,10 @@ impl WorkflowExecutor {
             )))?;
 
         // Render input template
+        self.resolve_template_secrets(&embed_config.input).await?;
         let rendered_input = self.context.render_template(&embed_config.input)?;
+        #[cfg(feature = "audit")]
+        let audit_input = rendered_input.clone();
 
         // Build embedding request
         let request = EmbeddingRequest {
@@ -668,11 +1835,35 @@ impl WorkflowExecutor {
             model = %embed_config.model,
             "Calling embedding provider"
         );
+        let span = Span::current();
+        span.record("provider", embed_config.provider.as_str());
+        span.record("model", embed_config.model.as_str());
+
+        self.record_request(&step.id, &request);
+        let embed_start = std::time::Instant::now();
+        let response_result = provider.embed(request).await;
+        self.record_provider_latency(&step.id, embed_start.elapsed());
+
+        #[cfg(feature = "audit")]
+        self.audit_provider_call(crate::audit::ProviderCall {
+            step_id: &step.id,
+            provider: &embed_config.provider,
+            model: &embed_config.model,
+            prompt: &audit_input,
+            completion: None,
+            tokens_used: response_result.as_ref().ok().and_then(|r| r.tokens_used),
+            error: response_result.as_ref().err().map(|e| e.to_string()),
+            duration: embed_start.elapsed(),
+        })
+        .await;
 
-        let response = provider
-            .embed(request)
-            .await
-            .map_err(|e| OrchestratorError::other(format!("Embedding provider error: {}", e)))?;
+        self.usage
+            .record_embedding(&embed_config.provider, &embed_config.model, response_result.as_ref().ok());
+        if let Ok(response) = &response_result {
+            self.record_usage(&step.id, &embedding_usage(response));
+        }
+        let response = response_result
+            .map_err(|e| provider_error(&embed_config.provider, "Embedding provider error", e))?;
 
         // Build output
         let mut outputs = HashMap::new();
@@ -733,15 +1924,29 @@ impl WorkflowExecutor {
                 search_config.database
             )))?;
 
-        // Render query template to get the vector
+        // Render query template to get the vector, or the text of a keyword search
         let rendered_query = self.context.render_template(&search_config.query)?;
-
-        // Parse the query - it should be a JSON array of floats (the embedding vector)
-        let query_vector: Vec<f32> = serde_json::from_str(&rendered_query)
-            .map_err(|e| OrchestratorError::other(format!(
-                "Failed to parse query vector: {}. Expected JSON array of floats, got: {}",
-                e, rendered_query
-            )))?;
+        let (query_vector, text) = if search_config.mode == SearchMode::Keyword {
+            (Vec::new(), Some(rendered_query))
+        } else {
+            // Parse the query - it should be a JSON array of floats (the embedding vector)
+            let query_vector: Vec<f32> = serde_json::from_str(&rendered_query)
+                .map_err(|e| OrchestratorError::other(format!(
+                    "Failed to parse query vector: {}. Expected JSON array of floats, got: {}",
+                    e, rendered_query
+                )))?;
+            if let Some(dimensions) = search_config.dimensions.filter(|d| *d != query_vector.len()) {
+                return Err(OrchestratorError::InvalidStepConfig {
+                    step_id: step.id.clone(),
+                    reason: format!(
+                        "Query vector has {} dimensions but index '{}' expects {}",
+                        query_vector.len(), search_config.index, dimensions
+                    ),
+                });
+            }
+            let text = search_config.text.as_deref().map(|text| self.context.render_template(text)).transpose()?;
+            (query_vector, text)
+        };
 
         // Build search request
         let request = VectorSearchRequest {
@@ -752,6 +1957,10 @@ impl WorkflowExecutor {
             filter: search_config.filter.clone(),
             include_metadata: search_config.include_metadata,
             include_vectors: search_config.include_vectors,
+            mode: search_config.mode,
+            text,
+            alpha: search_config.alpha,
+            fusion: search_config.fusion,
         };
 
         // Call vector database
@@ -763,10 +1972,12 @@ impl WorkflowExecutor {
             "Calling vector database"
         );
 
-        let response = vector_db
-            .search(request)
-            .await
-            .map_err(|e| OrchestratorError::other(format!("Vector search error: {}", e)))?;
+        self.record_request(&step.id, &request);
+        let search_start = std::time::Instant::now();
+        let response = vector_db.search(request).await;
+        self.record_provider_latency(&step.id, search_start.elapsed());
+        let response = response
+            .map_err(|e| provider_error(&search_config.database, "Vector search error", e))?;
 
         // Build output
         let mut outputs = HashMap::new();
@@ -822,19 +2033,25 @@ impl WorkflowExecutor {
         Ok(outputs)
     }
 
-    /// Executes a transform step.
-    async fn execute_transform_step(&self, step: &Step) -> Result<HashMap<String, Value>> {
-        debug!(step_id = %step.id, "Transform step execution");
-
-        // For now, just return empty outputs
-        // This will be expanded with actual transform functions
-        Ok(HashMap::new())
-    }
-
     /// Executes an action step.
     async fn execute_action_step(&self, step: &Step) -> Result<HashMap<String, Value>> {
         debug!(step_id = %step.id, "Action step execution");
 
+        if let StepConfig::Action(config) = &step.config {
+            if config.action == crate::exec::EXEC_ACTION {
+                return self.execute_exec_action(step, config).await;
+            }
+            if config.action == crate::messaging::PUBLISH_ACTION {
+                return self.execute_publish_action(step, config).await;
+            }
+            if crate::memory::is_memory_action(&config.action) {
+                return self.execute_memory_action(step, config).await;
+            }
+            if let Some(result) = self.execute_handler_action(step, config).await {
+                return result;
+            }
+        }
+
         // For now, just log and return empty outputs
         // This will be expanded with actual actions
         Ok(HashMap::new())
@@ -857,6 +2074,332 @@ impl WorkflowExecutor {
         // For now, return empty outputs
         Ok(HashMap::new())
     }
+
+    /// Executes an approval step, waiting for a reviewer decision.
+    async fn execute_approval_step(&self, step: &Step) -> Result<HashMap<String, Value>> {
+        let approval_config = match &step.config {
+            StepConfig::Approval(config) => config,
+            _ => {
+                return Err(OrchestratorError::InvalidStepConfig {
+                    step_id: step.id.clone(),
+                    reason: "Expected Approval step config".to_string(),
+                })
+            }
+        };
+
+        let payload = self.context.render_template(&approval_config.payload)?;
+        let message = approval_config
+            .message
+            .as_deref()
+            .map(|m| self.context.render_template(m))
+            .transpose()?;
+
+        let waiter = self.approvals.request(ApprovalRequest {
+            step_id: step.id.clone(),
+            message,
+            payload,
+            requested_at: chrono::Utc::now(),
+        });
+
+        info!(step_id = %step.id, "Waiting for approval");
+        self.persist_state().await;
+
+        let decision = waiter.wait().await?;
+        self.persist_state().await;
+
+        // Route to the rejection branch or skip it
+        let mut reject_branch = HashSet::new();
+        if let Some(target) = &approval_config.on_reject {
+            reject_branch = self.dag.descendants(target);
+            reject_branch.insert(target.clone());
+        }
+        let pruned: Vec<String> = if decision.approved {
+            reject_branch.into_iter().collect()
+        } else {
+            self.dag
+                .descendants(&step.id)
+                .into_iter()
+                .filter(|id| !reject_branch.contains(id))
+                .collect()
+        };
+        for step_id in pruned {
+            self.pruned_steps.insert(step_id);
+        }
+
+        if !decision.approved {
+            let mut reason = "Approval rejected".to_string();
+            if let Some(reviewer) = &decision.reviewer {
+                reason.push_str(&format!(" by {}", reviewer));
+            }
+            if let Some(comment) = &decision.comment {
+                reason.push_str(&format!(": {}", comment));
+            }
+            return Err(OrchestratorError::other(reason));
+        }
+
+        let output_key = step.output.first().cloned().unwrap_or_else(|| "approval".to_string());
+        let mut outputs = HashMap::new();
+        outputs.insert(output_key, serde_json::to_value(&decision)?);
+
+        Ok(outputs)
+    }
+
+    /// Notifies all registered event listeners.
+    pub(crate) fn emit(&self, event: impl Fn(&dyn WorkflowEventListener)) {
+        event(self.event_broadcaster.as_ref());
+        for listener in &self.event_listeners {
+            event(listener.as_ref());
+        }
+    }
+
+    /// Fetches the secrets a template references before it is rendered.
+    #[cfg_attr(not(feature = "secrets"), allow(unused_variables))]
+    pub(crate) async fn resolve_template_secrets(&self, template: &str) -> Result<()> {
+        #[cfg(feature = "secrets")]
+        for key in crate::secrets::template_secret_keys(template) {
+            self.resolve_secret(&key).await?;
+        }
+        Ok(())
+    }
+
+    /// Replaces `secretRef` objects in provider parameters with their values.
+    #[cfg_attr(not(feature = "secrets"), allow(unused_variables))]
+    pub(crate) async fn resolve_request_secrets(&self, params: &mut HashMap<String, Value>) -> Result<()> {
+        #[cfg(feature = "secrets")]
+        for value in params.values_mut() {
+            self.resolve_secret_refs(value).await?;
+        }
+        Ok(())
+    }
+
+    /// Masks registered secrets and credential patterns in text.
+    pub(crate) fn redact(&self, text: String) -> String {
+        #[cfg(feature = "secrets")]
+        {
+            self.redactor.redact(&text).into_owned()
+        }
+        #[cfg(not(feature = "secrets"))]
+        {
+            text
+        }
+    }
+
+    /// Masks registered secrets and credential patterns in a JSON value.
+    #[cfg_attr(not(feature = "secrets"), allow(unused_variables))]
+    pub(crate) fn redact_json(&self, value: &mut Value) {
+        #[cfg(feature = "secrets")]
+        self.redactor.redact_json(value);
+    }
+
+    /// Remembers the provider request a step sent, for dead-letter capture.
+    #[cfg_attr(not(feature = "state-persistence"), allow(unused_variables))]
+    pub(crate) fn record_request(&self, step_id: &str, request: &impl serde::Serialize) {
+        #[cfg(feature = "state-persistence")]
+        if self.dead_letters.is_some() {
+            if let Ok(mut request) = serde_json::to_value(request) {
+                self.redact_json(&mut request);
+                self.last_requests.insert(step_id.to_string(), request);
+            }
+        }
+    }
+
+    /// Stores the failure context of a step if a dead-letter queue is attached.
+    #[cfg_attr(not(feature = "state-persistence"), allow(unused_variables))]
+    async fn capture_dead_letter(
+        &self,
+        step: &Step,
+        error: &str,
+        failed_attempts: Vec<(u32, String, DateTime<Utc>)>,
+    ) {
+        #[cfg(feature = "state-persistence")]
+        if let Some(dead_letters) = &self.dead_letters {
+            let mut context = serde_json::json!({
+                "inputs": self.context.all_inputs(),
+                "outputs": self.context.all_outputs(),
+            });
+            self.redact_json(&mut context);
+            let mut entry = llm_orchestrator_state::DeadLetter::new(
+                self.run_id,
+                self.workflow.name.clone(),
+                step.id.clone(),
+                serde_json::to_value(step).unwrap_or(Value::Null),
+                context,
+                error.to_string(),
+            );
+            entry.request = self.last_requests.get(&step.id).map(|r| r.value().clone());
+            entry.attempts = failed_attempts
+                .into_iter()
+                .map(|(attempt, error, failed_at)| llm_orchestrator_state::DeadLetterAttempt {
+                    attempt,
+                    error,
+                    failed_at,
+                })
+                .collect();
+
+            if let Err(e) = dead_letters.add_dead_letter(&entry).await {
+                warn!(step_id = %step.id, error = %e, "Failed to store dead letter");
+            }
+        }
+    }
+
+    /// Persists the current run state if a state store is attached and the run was not abandoned.
+    async fn persist_state(&self) {
+        #[cfg(feature = "state-persistence")]
+        if let Some(state_store) = self.state_store.as_ref().filter(|_| !self.abandon.is_cancelled()) {
+            if let Err(e) = self.save_state(state_store, self.user_id.clone()).await {
+                warn!(error = %e, "Failed to persist workflow state");
+            }
+        }
+    }
+
+    /// Records a checkpoint of the completed steps if a state store is attached
+    /// and the run was not abandoned.
+    ///
+    /// Only the latest checkpoint is kept, as that is the one a run resumes from.
+    async fn checkpoint(&self, label: &str) {
+        #[cfg(feature = "state-persistence")]
+        if let Some(state_store) = self.state_store.as_ref().filter(|_| !self.abandon.is_cancelled()) {
+            if let Err(e) = self.create_checkpoint(state_store, self.run_id, label).await {
+                warn!(error = %e, "Failed to checkpoint workflow");
+                return;
+            }
+            if let Err(e) = state_store.cleanup_old_checkpoints(&self.run_id, 1).await {
+                warn!(error = %e, "Failed to clean up old checkpoints");
+            }
+        }
+        #[cfg(not(feature = "state-persistence"))]
+        let _ = label;
+    }
+
+    /// Whether a step has completed, in this run or one it resumed.
+    fn is_completed(&self, step_id: &str) -> bool {
+        self.step_statuses.get(step_id).is_some_and(|s| *s == StepStatus::Completed)
+    }
+}
+
+/// Dependency counters for scheduling steps as their dependencies finish.
+struct ReadySet<'a> {
+    /// Unfinished dependencies per step.
+    pending: HashMap<&'a str, usize>,
+    /// Steps depending on each step, in workflow order.
+    dependents: HashMap<&'a str, Vec<&'a Step>>,
+    /// Steps listing each step in `depends_on_any`, in workflow order.
+    joins: HashMap<&'a str, Vec<&'a Step>>,
+    /// Unfinished alternatives per join step whose join is still pending.
+    alternatives: HashMap<&'a str, usize>,
+    /// Steps whose dependencies have all finished, in the order they became ready.
+    ready: VecDeque<&'a Step>,
+    /// Ready steps held back by their resource class limit.
+    deferred: Vec<&'a Step>,
+    /// Steps that finished or were skipped.
+    done: HashSet<&'a str>,
+}
+
+impl<'a> ReadySet<'a> {
+    fn new(steps: &'a [Step]) -> Self {
+        let mut dependents: HashMap<&str, Vec<&Step>> = HashMap::new();
+        let mut joins: HashMap<&str, Vec<&Step>> = HashMap::new();
+        for step in steps {
+            for dep in &step.depends_on {
+                dependents.entry(dep.as_str()).or_default().push(step);
+            }
+            for dep in &step.depends_on_any {
+                joins.entry(dep.as_str()).or_default().push(step);
+            }
+        }
+        // Alternatives count as one dependency, met by the first to complete
+        let pending_count = |s: &Step| s.depends_on.len() + usize::from(!s.depends_on_any.is_empty());
+
+        Self {
+            pending: steps.iter().map(|s| (s.id.as_str(), pending_count(s))).collect(),
+            dependents,
+            joins,
+            alternatives: steps
+                .iter()
+                .filter(|s| !s.depends_on_any.is_empty())
+                .map(|s| (s.id.as_str(), s.depends_on_any.len()))
+                .collect(),
+            ready: steps.iter().filter(|s| pending_count(s) == 0).collect(),
+            deferred: Vec::new(),
+            done: HashSet::new(),
+        }
+    }
+
+    /// Holds back a ready step until a running step finishes.
+    fn defer(&mut self, step: &'a Step) {
+        self.deferred.push(step);
+    }
+
+    /// Returns deferred steps to the front of the ready queue, in their original order.
+    fn resume_deferred(&mut self) {
+        for step in self.deferred.drain(..).rev() {
+            self.ready.push_front(step);
+        }
+    }
+
+    /// Takes the next ready step that has not already been skipped.
+    fn pop(&mut self) -> Option<&'a Step> {
+        while let Some(step) = self.ready.pop_front() {
+            if !self.done.contains(step.id.as_str()) {
+                return Some(step);
+            }
+        }
+        None
+    }
+
+    /// Steps depending directly on a step.
+    fn dependents(&self, step_id: &str) -> Vec<&'a Step> {
+        self.dependents.get(step_id).cloned().unwrap_or_default()
+    }
+
+    /// Returns true if the step finished or was skipped.
+    fn is_done(&self, step_id: &str) -> bool {
+        self.done.contains(step_id)
+    }
+
+    /// Records a step that completed, meeting the joins waiting on it as an alternative.
+    ///
+    /// Call before [`complete`](Self::complete).
+    fn succeed(&mut self, step_id: &str) {
+        for step in self.joins.get(step_id).cloned().unwrap_or_default() {
+            if self.alternatives.remove(step.id.as_str()).is_some() {
+                self.satisfy(step);
+            }
+        }
+    }
+
+    /// Records a finished or skipped step, readying dependents with no other pending dependency.
+    fn complete(&mut self, step_id: &str) {
+        let Some((&id, _)) = self.pending.get_key_value(step_id) else {
+            return;
+        };
+        self.done.insert(id);
+
+        for step in self.dependents(step_id) {
+            self.satisfy(step);
+        }
+        // A join whose alternatives all finished without completing runs only to be skipped
+        for step in self.joins.get(step_id).cloned().unwrap_or_default() {
+            let Some(unfinished) = self.alternatives.get_mut(step.id.as_str()) else {
+                continue;
+            };
+            *unfinished -= 1;
+            if *unfinished == 0 {
+                self.alternatives.remove(step.id.as_str());
+                self.satisfy(step);
+            }
+        }
+    }
+
+    /// Meets one pending dependency of a step, readying it once none are left.
+    fn satisfy(&mut self, step: &'a Step) {
+        if let Some(pending) = self.pending.get_mut(step.id.as_str()) {
+            *pending -= 1;
+            if *pending == 0 && !self.done.contains(step.id.as_str()) {
+                self.ready.push_back(step);
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -876,25 +2419,40 @@ mod tests {
                     id: "step1".to_string(),
                     step_type: StepType::Llm,
                     depends_on: vec![],
+                    depends_on_any: Vec::new(),
+                    bind: HashMap::new(),
                     condition: None,
                     config: StepConfig::Llm(LlmStepConfig {
                         provider: "openai".to_string(),
                         model: "gpt-4".to_string(),
                         prompt: "Test prompt".to_string(),
+                        prompt_ref: None,
                         temperature: Some(0.7),
                         max_tokens: Some(100),
                         system: None,
                         stream: false,
+                        variants: Vec::new(),
+                        variant_mode: Default::default(),
+                        hedge: None,
+                        memory: None,
+                        images: Vec::new(),
                         extra: HashMap::new(),
                     }),
                     output: vec!["result".to_string()],
                     timeout_seconds: None,
+                    on_failure: None,
                     retry: None,
+                    guardrails: None,
+                    resource_class: None,
+                    assert: Vec::new(),
+                    allow_skipped_deps: false,
                 },
                 Step {
                     id: "step2".to_string(),
                     step_type: StepType::Transform,
                     depends_on: vec!["step1".to_string()],
+                    depends_on_any: Vec::new(),
+                    bind: HashMap::new(),
                     condition: None,
                     config: StepConfig::Transform(crate::workflow::TransformConfig {
                         function: "test".to_string(),
@@ -903,10 +2461,23 @@ mod tests {
                     }),
                     output: vec!["transformed".to_string()],
                     timeout_seconds: None,
+                    on_failure: None,
                     retry: None,
+                    guardrails: None,
+                    resource_class: None,
+                    assert: Vec::new(),
+                    allow_skipped_deps: false,
                 },
             ],
             metadata: HashMap::new(),
+            profiles: HashMap::new(),
+            guardrails: None,
+            retry_budget: None,
+            triggers: Vec::new(),
+            outputs: None,
+            artifacts: None,
+            workdir: None,
+            partials: HashMap::new(),
         }
     }
 
@@ -941,19 +2512,32 @@ mod tests {
             id: "test".to_string(),
             step_type: StepType::Llm,
             depends_on: vec![],
+            depends_on_any: Vec::new(),
+            bind: HashMap::new(),
             condition: None,
             config: StepConfig::Llm(LlmStepConfig {
                 provider: "openai".to_string(),
                 model: "gpt-4".to_string(),
                 prompt: "Test".to_string(),
+                prompt_ref: None,
                 temperature: None,
                 max_tokens: None,
                 system: None,
                 stream: false,
+                variants: Vec::new(),
+                variant_mode: Default::default(),
+                hedge: None,
+                memory: None,
+                images: Vec::new(),
                 extra: HashMap::new(),
             }),
             output: vec![],
             timeout_seconds: None,
+            on_failure: None,
+            guardrails: None,
+            resource_class: None,
+            assert: Vec::new(),
+            allow_skipped_deps: false,
             retry: Some(RetryConfig {
                 max_attempts: 5,
                 backoff: BackoffStrategy::Exponential,
@@ -981,6 +2565,8 @@ mod tests {
                 id: "transform1".to_string(),
                 step_type: StepType::Transform,
                 depends_on: vec![],
+                depends_on_any: Vec::new(),
+                bind: HashMap::new(),
                 condition: None,
                 config: StepConfig::Transform(crate::workflow::TransformConfig {
                     function: "test".to_string(),
@@ -989,9 +2575,22 @@ mod tests {
                 }),
                 output: vec!["result".to_string()],
                 timeout_seconds: None,
+                on_failure: None,
                 retry: None,
+                guardrails: None,
+                resource_class: None,
+                assert: Vec::new(),
+                allow_skipped_deps: false,
             }],
             metadata: HashMap::new(),
+            profiles: HashMap::new(),
+            guardrails: None,
+            retry_budget: None,
+            triggers: Vec::new(),
+            outputs: None,
+            artifacts: None,
+            workdir: None,
+            partials: HashMap::new(),
         };
 
         let inputs = HashMap::new();
@@ -1019,6 +2618,8 @@ mod tests {
                 id: "conditional".to_string(),
                 step_type: StepType::Action,
                 depends_on: vec![],
+                depends_on_any: Vec::new(),
+                bind: HashMap::new(),
                 condition: Some("false".to_string()), // Always false
                 config: StepConfig::Action(crate::workflow::ActionConfig {
                     action: "test".to_string(),
@@ -1026,9 +2627,22 @@ mod tests {
                 }),
                 output: vec![],
                 timeout_seconds: None,
+                on_failure: None,
                 retry: None,
+                guardrails: None,
+                resource_class: None,
+                assert: Vec::new(),
+                allow_skipped_deps: false,
             }],
             metadata: HashMap::new(),
+            profiles: HashMap::new(),
+            guardrails: None,
+            retry_budget: None,
+            triggers: Vec::new(),
+            outputs: None,
+            artifacts: None,
+            workdir: None,
+            partials: HashMap::new(),
         };
 
         let inputs = HashMap::new();
@@ -1038,6 +2652,701 @@ mod tests {
         assert_eq!(results["conditional"].status, StepStatus::Skipped);
     }
 
+    #[tokio::test]
+    async fn test_condition_skip_propagates_to_dependents() {
+        let workflow = Workflow::from_yaml(
+            "name: skips\nsteps:\n  \
+             - {id: gated, type: transform, function: noop, inputs: [], condition: 'false'}\n  \
+             - {id: uses, type: transform, function: noop, inputs: [], depends_on: [gated]}\n  \
+             - {id: after, type: transform, function: noop, inputs: [], depends_on: [uses]}\n  \
+             - {id: join, type: transform, function: noop, inputs: [], depends_on: [uses], allow_skipped_deps: true}\n",
+        )
+        .unwrap();
+        let executor = WorkflowExecutor::new(workflow, HashMap::new()).unwrap();
+
+        let results = executor.execute().await.unwrap();
+        assert_eq!(results["gated"].skip_reason, Some(SkipReason::Condition));
+        assert_eq!(results["uses"].status, StepStatus::Skipped);
+        assert_eq!(
+            results["after"].skip_reason,
+            Some(SkipReason::UpstreamSkipped {
+                chain: vec!["uses".to_string(), "gated".to_string()],
+            })
+        );
+        assert!(results["after"].error.is_none());
+        assert_eq!(results["join"].status, StepStatus::Completed);
+    }
+
+    fn create_approval_workflow() -> Workflow {
+        let transform = |id: &str, depends_on: &str| Step {
+            id: id.to_string(),
+            step_type: StepType::Transform,
+            depends_on: vec![depends_on.to_string()],
+            depends_on_any: Vec::new(),
+            bind: HashMap::new(),
+            condition: None,
+            config: StepConfig::Transform(crate::workflow::TransformConfig {
+                function: "noop".to_string(),
+                inputs: vec![],
+                params: HashMap::new(),
+            }),
+            output: vec![],
+            timeout_seconds: None,
+            on_failure: None,
+            retry: None,
+            guardrails: None,
+            resource_class: None,
+            assert: Vec::new(),
+            allow_skipped_deps: false,
+        };
+
+        let mut workflow = Workflow::new("approval-test");
+        workflow.steps = vec![
+            Step {
+                id: "review".to_string(),
+                step_type: StepType::Approval,
+                depends_on: vec![],
+                depends_on_any: Vec::new(),
+                bind: HashMap::new(),
+                condition: None,
+                config: StepConfig::Approval(crate::workflow::ApprovalConfig {
+                    payload: "Draft for {{ inputs.topic }}".to_string(),
+                    message: Some("Publish?".to_string()),
+                    on_reject: Some("revise".to_string()),
+                }),
+                output: vec!["decision".to_string()],
+                timeout_seconds: None,
+                on_failure: None,
+                retry: None,
+                guardrails: None,
+                resource_class: None,
+                assert: Vec::new(),
+                allow_skipped_deps: false,
+            },
+            transform("publish", "review"),
+            transform("revise", "review"),
+        ];
+        workflow
+    }
+
+    async fn run_with_decision(decision: ApprovalDecision) -> HashMap<String, StepResult> {
+        let inputs = HashMap::from([("topic".to_string(), serde_json::json!("rust"))]);
+        let executor = Arc::new(WorkflowExecutor::new(create_approval_workflow(), inputs).unwrap());
+
+        let handle = tokio::spawn({
+            let executor = executor.clone();
+            async move { executor.execute().await }
+        });
+
+        while !executor.is_awaiting_approval() {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        let pending = executor.pending_approvals();
+        assert_eq!(pending[0].step_id, "review");
+        assert_eq!(pending[0].payload, "Draft for rust");
+
+        executor.submit_approval("review", decision).unwrap();
+        handle.await.unwrap().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_approval_step_approved() {
+        let results = run_with_decision(ApprovalDecision::approve().with_reviewer("alice")).await;
+
+        assert_eq!(results["review"].status, StepStatus::Completed);
+        assert_eq!(results["review"].outputs["decision"]["reviewer"], "alice");
+        assert_eq!(results["publish"].status, StepStatus::Completed);
+        assert_eq!(results["revise"].status, StepStatus::Skipped);
+    }
+
+    #[tokio::test]
+    async fn test_approval_step_rejected() {
+        let results = run_with_decision(ApprovalDecision::reject().with_comment("too short")).await;
+
+        assert_eq!(results["review"].status, StepStatus::Failed);
+        assert!(results["review"].error.as_deref().unwrap().contains("too short"));
+        assert_eq!(results["publish"].status, StepStatus::Skipped);
+        assert_eq!(results["revise"].status, StepStatus::Completed);
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_limit_does_not_block_independent_branches() {
+        // review waits for a reviewer while holding one of the two slots;
+        // the independent prepare -> summarize branch must still run
+        let mut workflow = create_approval_workflow();
+        for (id, depends_on) in [("prepare", None), ("summarize", Some("prepare"))] {
+            let mut step = workflow.steps[1].clone();
+            step.id = id.to_string();
+            step.depends_on = depends_on.into_iter().map(String::from).collect();
+            workflow.steps.push(step);
+        }
+        let inputs = HashMap::from([("topic".to_string(), serde_json::json!("rust"))]);
+        let executor = Arc::new(
+            WorkflowExecutor::new(workflow, inputs)
+                .unwrap()
+                .with_max_concurrency(2),
+        );
+
+        let handle = tokio::spawn({
+            let executor = executor.clone();
+            async move { executor.execute().await }
+        });
+
+        let summarized = || {
+            executor
+                .step_statuses
+                .get("summarize")
+                .is_some_and(|s| *s == StepStatus::Completed)
+        };
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while !summarized() {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .expect("independent branch was blocked");
+        assert!(executor.is_awaiting_approval());
+
+        executor.submit_approval("review", ApprovalDecision::approve()).unwrap();
+        let results = handle.await.unwrap().unwrap();
+        assert_eq!(results["publish"].status, StepStatus::Completed);
+        assert_eq!(results["revise"].status, StepStatus::Skipped);
+    }
+
+    /// LLM provider recording the most completions it served at once.
+    #[derive(Default)]
+    struct ConcurrencyProbe {
+        active: std::sync::atomic::AtomicUsize,
+        peak: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl LLMProvider for ConcurrencyProbe {
+        async fn complete(
+            &self,
+            request: CompletionRequest,
+        ) -> std::result::Result<crate::providers::CompletionResponse, crate::providers::ProviderError> {
+            use std::sync::atomic::Ordering::SeqCst;
+            let active = self.active.fetch_add(1, SeqCst) + 1;
+            self.peak.fetch_max(active, SeqCst);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            self.active.fetch_sub(1, SeqCst);
+
+            Ok(crate::providers::CompletionResponse {
+                text: request.prompt,
+                model: request.model,
+                tokens_used: None,
+                usage: None,
+                tool_calls: Vec::new(),
+                metadata: HashMap::new(),
+            })
+        }
+
+        fn name(&self) -> &str {
+            "probe"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resource_limit_throttles_only_its_class() {
+        let mut yaml = "name: classes\nsteps:\n".to_string();
+        for i in 0..3 {
+            yaml.push_str(&format!(
+                "  - {{id: ask{i}, type: llm, provider: probe, model: m, prompt: hi, output: [reply]}}\n  \
+                 - {{id: prep{i}, type: transform, function: noop, inputs: []}}\n"
+            ));
+        }
+        let probe = Arc::new(ConcurrencyProbe::default());
+        let executor = WorkflowExecutor::new(Workflow::from_yaml(&yaml).unwrap(), HashMap::new())
+            .unwrap()
+            .with_provider("probe", probe.clone())
+            .with_resource_limit(ResourceClass::Llm, 1);
+
+        let results = executor.execute().await.unwrap();
+        assert!(results.values().all(|r| r.status == StepStatus::Completed));
+        assert_eq!(probe.peak.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        // Transforms are not queued behind the throttled LLM steps
+        let timings = &executor.step_timings;
+        let last_prep = timings.get("prep2").unwrap().completed_at.unwrap();
+        assert!(last_prep <= timings.get("ask1").unwrap().started_at);
+    }
+
+    struct Panicking;
+
+    #[async_trait::async_trait]
+    impl LLMProvider for Panicking {
+        async fn complete(
+            &self,
+            _request: CompletionRequest,
+        ) -> std::result::Result<crate::providers::CompletionResponse, crate::providers::ProviderError> {
+            panic!("provider bug");
+        }
+
+        fn name(&self) -> &str {
+            "panicking"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_panicking_step_fails_and_poisons_the_run() {
+        let workflow = Workflow::from_yaml(
+            "name: poisoned\nsteps:\n  \
+             - {id: ask, type: llm, provider: panicking, model: m, prompt: hi, output: [reply], on_failure: continue}\n  \
+             - {id: next, type: transform, function: noop, inputs: []}\n",
+        )
+        .unwrap();
+        let executor = WorkflowExecutor::new(workflow, HashMap::new())
+            .unwrap()
+            .with_provider("panicking", Arc::new(Panicking));
+
+        let results = executor.execute().await.unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results["ask"].status, StepStatus::Failed);
+        assert_eq!(results["ask"].error.as_deref(), Some("Step 'ask' panicked: provider bug"));
+        assert_eq!(results["ask"].error_details.as_ref().unwrap().kind, ErrorKind::Panicked);
+        assert_eq!(results["next"].status, StepStatus::Completed);
+
+        assert!(executor.is_poisoned());
+        assert_eq!(
+            executor.panicked_steps(),
+            vec![("ask".to_string(), "provider bug".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_join_runs_after_first_alternative() {
+        let workflow = Workflow::from_yaml(
+            "name: join\nsteps:\n  \
+             - {id: slow, type: llm, provider: probe, model: m, prompt: slow, output: [text]}\n  \
+             - {id: fast, type: transform, function: normalize, inputs: [answer]}\n  \
+             - {id: join, type: transform, function: normalize, inputs: [joined.join.text], depends_on_any: [slow, fast]}\n",
+        )
+        .unwrap();
+        let inputs = HashMap::from([("answer".to_string(), serde_json::json!("fast"))]);
+        let executor = WorkflowExecutor::new(workflow, inputs)
+            .unwrap()
+            .with_provider("probe", Arc::new(ConcurrencyProbe::default()));
+
+        let results = executor.execute().await.unwrap();
+        assert_eq!(results["join"].outputs["text"], serde_json::json!("fast"));
+        assert_eq!(results["slow"].status, StepStatus::Completed);
+        let timings = &executor.step_timings;
+        assert!(timings.get("join").unwrap().started_at <= timings.get("slow").unwrap().completed_at.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_bindings_are_read_as_local_variables() {
+        let workflow = Workflow::from_yaml(
+            "name: bind\nsteps:\n  \
+             - {id: fetch, type: transform, function: normalize, inputs: [body]}\n  \
+             - {id: write, type: llm, provider: probe, model: m, prompt: '{{ doc }} in a {{ style }} tone', output: [text], depends_on: [fetch], bind: {doc: steps.fetch.text, style: inputs.tone}}\n  \
+             - {id: broken, type: llm, provider: probe, model: m, prompt: '{{ doc }}', depends_on: [fetch], bind: {doc: inputs.missing}}\n",
+        )
+        .unwrap();
+        let inputs = HashMap::from([
+            ("body".to_string(), serde_json::json!("Notes")),
+            ("tone".to_string(), serde_json::json!("dry")),
+        ]);
+        let executor = WorkflowExecutor::new(workflow, inputs)
+            .unwrap()
+            .with_provider("probe", Arc::new(ConcurrencyProbe::default()));
+
+        assert!(executor.execute().await.is_err());
+        let write = executor.step_results.get("write").unwrap();
+        assert_eq!(write.outputs["text"], serde_json::json!("Notes in a dry tone"));
+        let broken = executor.step_results.get("broken").unwrap();
+        assert_eq!(broken.status, StepStatus::Failed);
+        assert!(broken.error.as_ref().unwrap().contains("Binding 'doc' has no value: nothing at 'inputs.missing'"));
+    }
+
+    #[tokio::test]
+    async fn test_join_is_skipped_when_no_alternative_completes() {
+        let workflow = Workflow::from_yaml(
+            "name: join\nsteps:\n  \
+             - {id: a, type: transform, function: noop, inputs: [], condition: 'false'}\n  \
+             - {id: b, type: transform, function: noop, inputs: [], condition: 'false'}\n  \
+             - {id: join, type: transform, function: noop, inputs: [], depends_on_any: [a, b]}\n  \
+             - {id: after, type: transform, function: noop, inputs: [], depends_on: [join]}\n",
+        )
+        .unwrap();
+        let executor = WorkflowExecutor::new(workflow, HashMap::new()).unwrap();
+
+        let results = executor.execute().await.unwrap();
+        assert_eq!(results["join"].skip_reason, Some(SkipReason::NoAlternativeCompleted));
+        assert_eq!(
+            results["after"].skip_reason,
+            Some(SkipReason::UpstreamSkipped { chain: vec!["join".to_string()] })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dropping_the_run_aborts_running_steps() {
+        let workflow = Workflow::from_yaml(
+            "name: dropped\nsteps:\n  - {id: ask, type: llm, provider: probe, model: m, prompt: hi, output: [reply]}\n",
+        )
+        .unwrap();
+        let probe = Arc::new(ConcurrencyProbe::default());
+        let executor = WorkflowExecutor::new(workflow, HashMap::new())
+            .unwrap()
+            .with_provider("probe", probe.clone());
+
+        let result = timeout(Duration::from_millis(5), executor.execute()).await;
+        assert!(result.is_err());
+        assert_eq!(probe.active.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        // The completion was aborted with the run, so it never returns
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(probe.active.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_drains_running_steps() {
+        let workflow = Workflow::from_yaml(
+            "name: drain\nsteps:\n  - {id: ask, type: llm, provider: probe, model: m, prompt: hi, output: [reply]}\n  \
+             - {id: next, type: transform, depends_on: [ask], function: noop, inputs: []}\n",
+        )
+        .unwrap();
+        let executor = WorkflowExecutor::new(workflow, HashMap::new())
+            .unwrap()
+            .with_provider("probe", Arc::new(ConcurrencyProbe::default()));
+        let shutdown = executor.shutdown_handle();
+        let drain = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            shutdown.shutdown(Duration::from_secs(60)).await;
+        });
+
+        let result = executor.execute().await;
+        drain.abort();
+        match result {
+            Err(OrchestratorError::Interrupted(unfinished)) => assert_eq!(unfinished, vec!["next"]),
+            other => panic!("expected an interrupted run, got {:?}", other.map(|_| ())),
+        }
+        // The step in flight finished; the one after it never started
+        assert_eq!(executor.step_results.get("ask").unwrap().status, StepStatus::Completed);
+        assert!(executor.step_results.get("next").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_step_result_reports_usage() {
+        struct Metered;
+
+        #[async_trait::async_trait]
+        impl LLMProvider for Metered {
+            async fn complete(
+                &self,
+                request: CompletionRequest,
+            ) -> std::result::Result<crate::providers::CompletionResponse, crate::providers::ProviderError> {
+                Ok(crate::providers::CompletionResponse {
+                    text: request.prompt,
+                    model: request.model,
+                    tokens_used: Some(12),
+                    usage: Some(Usage { cost_usd: Some(0.5), ..Usage::new(9, 3) }),
+                    tool_calls: Vec::new(),
+                    metadata: HashMap::new(),
+                })
+            }
+
+            fn name(&self) -> &str {
+                "metered"
+            }
+        }
+
+        let workflow = Workflow::from_yaml(
+            "name: usage\nsteps:\n  - {id: ask, type: llm, provider: metered, model: m, prompt: hi, output: [reply]}\n  \
+             - {id: prep, type: transform, function: noop, inputs: []}\n",
+        )
+        .unwrap();
+        let executor = WorkflowExecutor::new(workflow, HashMap::new())
+            .unwrap()
+            .with_provider("metered", Arc::new(Metered));
+
+        let results = executor.execute().await.unwrap();
+        let usage = results["ask"].usage.unwrap();
+        assert_eq!((usage.input_tokens, usage.output_tokens, usage.total_tokens), (9, 3, 12));
+        assert_eq!(usage.cost_usd, Some(0.5));
+        assert!(usage.latency_ms.is_some());
+        assert!(results["prep"].usage.is_none());
+
+        let report = executor.report();
+        assert_eq!(report.steps[0].input_tokens, Some(9));
+        assert_eq!(report.steps[0].cost_usd, Some(0.5));
+    }
+
+    #[tokio::test]
+    async fn test_fail_workflow_policy_aborts_run() {
+        // step1 fails (no provider registered)
+        let mut workflow = create_test_workflow();
+        workflow.steps[0].on_failure = Some(FailurePolicy::FailWorkflow);
+
+        let executor = WorkflowExecutor::new(workflow, HashMap::new()).unwrap();
+        match executor.execute().await {
+            Err(OrchestratorError::WorkflowFailed { step_id, reason }) => {
+                assert_eq!(step_id, "step1");
+                assert!(reason.contains("openai"));
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert_eq!(executor.step_results.get("step2").unwrap().status, StepStatus::Cancelled);
+    }
+
+    #[tokio::test]
+    async fn test_failed_step_skips_downstream_and_fails_run() {
+        // step1 fails (no provider registered); step3 hangs off step2, "other" is independent
+        let mut workflow = create_test_workflow();
+        let mut step3 = workflow.steps[1].clone();
+        step3.id = "step3".to_string();
+        step3.depends_on = vec!["step2".to_string()];
+        let mut other = workflow.steps[1].clone();
+        other.id = "other".to_string();
+        other.depends_on = vec![];
+        workflow.steps.extend([step3, other]);
+
+        let executor = WorkflowExecutor::new(workflow, HashMap::new()).unwrap();
+        match executor.execute().await {
+            Err(OrchestratorError::StepsFailed(failures)) => {
+                assert_eq!(failures.len(), 1);
+                assert_eq!(failures[0].step_id, "step1");
+                assert_eq!(failures[0].skipped, vec!["step2", "step3"]);
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        let step3 = executor.step_results.get("step3").unwrap();
+        assert_eq!(step3.status, StepStatus::Skipped);
+        assert_eq!(
+            step3.skip_reason,
+            Some(SkipReason::UpstreamFailed {
+                chain: vec!["step2".to_string(), "step1".to_string()],
+            })
+        );
+        assert!(step3.error.as_deref().unwrap().starts_with("Upstream step 'step1' failed"));
+        assert_eq!(executor.step_results.get("other").unwrap().status, StepStatus::Completed);
+    }
+
+    /// LLM provider that is always overloaded.
+    struct Overloaded;
+
+    #[async_trait::async_trait]
+    impl LLMProvider for Overloaded {
+        async fn complete(
+            &self,
+            _request: CompletionRequest,
+        ) -> std::result::Result<crate::providers::CompletionResponse, crate::providers::ProviderError> {
+            Err(crate::providers::ProviderError::Api {
+                status: 503,
+                message: "overloaded".to_string(),
+            })
+        }
+
+        fn name(&self) -> &str {
+            "overloaded"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failed_step_records_error_details() {
+        let mut workflow = create_test_workflow();
+        workflow.steps[0].on_failure = Some(FailurePolicy::Continue);
+
+        let executor = WorkflowExecutor::new(workflow, HashMap::new())
+            .unwrap()
+            .with_provider("openai", Arc::new(Overloaded));
+        let results = executor.execute().await.unwrap();
+
+        let details = results["step1"].error_details.clone().unwrap();
+        assert_eq!(details.kind, ErrorKind::Provider);
+        assert_eq!(details.provider.as_deref(), Some("openai"));
+        assert_eq!(details.http_status, Some(503));
+        assert!(!details.retryable);
+        assert_eq!(details.attempts, 1);
+        assert_eq!(details.causes, vec!["API error (HTTP 503): overloaded"]);
+        assert_eq!(Some(details.message), results["step1"].error);
+        assert!(results["step2"].error_details.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_skip_dependents_policy() {
+        let mut workflow = create_test_workflow();
+        workflow.steps[0].on_failure = Some(FailurePolicy::SkipDependents);
+
+        let executor = WorkflowExecutor::new(workflow, HashMap::new()).unwrap();
+        let results = executor.execute().await.unwrap();
+        assert_eq!(results["step1"].status, StepStatus::Failed);
+        assert_eq!(results["step2"].status, StepStatus::Skipped);
+    }
+
+    fn create_fallback_workflow() -> Workflow {
+        let mut workflow = create_test_workflow();
+        workflow.steps[0].on_failure = Some(FailurePolicy::RunStep("fallback".to_string()));
+        let mut fallback = workflow.steps[1].clone();
+        fallback.id = "fallback".to_string();
+        workflow.steps.push(fallback);
+        workflow
+    }
+
+    #[tokio::test]
+    async fn test_run_step_policy_runs_fallback_on_failure() {
+        let executor = WorkflowExecutor::new(create_fallback_workflow(), HashMap::new()).unwrap();
+        let results = executor.execute().await.unwrap();
+        assert_eq!(results["step1"].status, StepStatus::Failed);
+        assert_eq!(results["fallback"].status, StepStatus::Completed);
+        assert_eq!(results["step2"].status, StepStatus::Completed);
+    }
+
+    #[tokio::test]
+    async fn test_run_step_policy_skips_fallback_on_success() {
+        let mocks = MockResponses::new().with_step(
+            "step1",
+            HashMap::from([("result".to_string(), serde_json::json!("ok"))]),
+        );
+        let executor = WorkflowExecutor::new(create_fallback_workflow(), HashMap::new())
+            .unwrap()
+            .with_mock_responses(mocks);
+
+        let results = executor.execute().await.unwrap();
+        assert_eq!(results["step1"].status, StepStatus::Completed);
+        assert_eq!(results["fallback"].status, StepStatus::Skipped);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_aborts_running_and_pending_steps() {
+        let inputs = HashMap::from([("topic".to_string(), serde_json::json!("rust"))]);
+        let executor = Arc::new(WorkflowExecutor::new(create_approval_workflow(), inputs).unwrap());
+        let mut events = executor.subscribe();
+
+        let handle = tokio::spawn({
+            let executor = executor.clone();
+            async move { executor.execute().await }
+        });
+
+        while !executor.is_awaiting_approval() {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        executor.cancel_handle().cancel();
+
+        let result = handle.await.unwrap();
+        assert!(matches!(result, Err(OrchestratorError::Cancelled)));
+        assert!(!executor.is_awaiting_approval());
+        for step_id in ["review", "publish", "revise"] {
+            assert_eq!(executor.step_results.get(step_id).unwrap().status, StepStatus::Cancelled);
+        }
+
+        let mut last = None;
+        while let Ok(event) = events.try_recv() {
+            last = Some(event);
+        }
+        match last.unwrap() {
+            ExecutionEvent::WorkflowCancelled { cancelled_steps, .. } => {
+                assert_eq!(cancelled_steps, vec!["publish", "review", "revise"]);
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingListener {
+        events: std::sync::Mutex<Vec<String>>,
+    }
+
+    impl WorkflowEventListener for RecordingListener {
+        fn on_workflow_start(&self, _run_id: Uuid, workflow: &Workflow) {
+            self.events.lock().unwrap().push(format!("workflow_start:{}", workflow.name));
+        }
+
+        fn on_step_start(&self, _run_id: Uuid, step: &Step) {
+            self.events.lock().unwrap().push(format!("step_start:{}", step.id));
+        }
+
+        fn on_step_complete(&self, _run_id: Uuid, result: &StepResult) {
+            self.events.lock().unwrap().push(format!("step_complete:{}", result.step_id));
+        }
+
+        fn on_step_failed(&self, _run_id: Uuid, result: &StepResult) {
+            self.events.lock().unwrap().push(format!("step_failed:{}", result.step_id));
+        }
+
+        fn on_workflow_complete(&self, _run_id: Uuid, results: &HashMap<String, StepResult>) {
+            self.events.lock().unwrap().push(format!("workflow_complete:{}", results.len()));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_event_listener_receives_lifecycle_events() {
+        // step1 fails (no provider registered), step2 still runs under `continue`
+        let mut workflow = create_test_workflow();
+        workflow.steps[0].on_failure = Some(FailurePolicy::Continue);
+        let listener = Arc::new(RecordingListener::default());
+        let executor = WorkflowExecutor::new(workflow, HashMap::new())
+            .unwrap()
+            .with_event_listener(listener.clone());
+
+        executor.execute().await.unwrap();
+
+        let events = listener.events.lock().unwrap().clone();
+        assert_eq!(
+            events,
+            vec![
+                "workflow_start:test-workflow",
+                "step_start:step1",
+                "step_failed:step1",
+                "step_start:step2",
+                "step_complete:step2",
+                "workflow_complete:2",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_streams_execution_events() {
+        let mut workflow = create_test_workflow();
+        workflow.steps[0].on_failure = Some(FailurePolicy::Continue);
+        workflow.steps[1].condition = Some("false".to_string());
+
+        let executor = WorkflowExecutor::new(workflow, HashMap::new()).unwrap();
+        let mut events = executor.subscribe();
+        executor.execute().await.unwrap();
+
+        let mut types = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            assert_eq!(event.run_id(), executor.run_id());
+            types.push(serde_json::to_value(&event).unwrap()["type"].as_str().unwrap().to_string());
+        }
+        assert_eq!(
+            types,
+            vec![
+                "workflow_started",
+                "step_started",
+                "step_failed",
+                "step_skipped",
+                "workflow_completed",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mocked_llm_step_without_provider() {
+        let workflow = create_test_workflow();
+        let mocks = MockResponses::new().with_step(
+            "step1",
+            HashMap::from([("result".to_string(), serde_json::json!("mocked answer"))]),
+        );
+
+        // No provider registered: step1 must be served from the mock file
+        let executor = WorkflowExecutor::new(workflow, HashMap::new())
+            .unwrap()
+            .with_mock_responses(mocks);
+
+        let results = executor.execute().await.unwrap();
+        assert_eq!(results["step1"].status, StepStatus::Completed);
+        assert_eq!(results["step1"].outputs["result"], serde_json::json!("mocked answer"));
+        assert_eq!(results["step2"].status, StepStatus::Completed);
+        assert_eq!(
+            executor.context.get_output("step1").unwrap()["result"],
+            serde_json::json!("mocked answer")
+        );
+    }
+
     // RAG Pipeline Integration Tests
 
     /// Mock embedding provider for testing
@@ -1112,11 +3421,55 @@ mod tests {
             })
         }
 
+        async fn index_stats(&self, _index: &str) -> std::result::Result<Option<crate::providers::IndexStats>, crate::providers::ProviderError> {
+            Ok(Some(crate::providers::IndexStats {
+                dimension: Some(384),
+                vector_count: Some(2),
+                metric: None,
+            }))
+        }
+
         fn name(&self) -> &str {
             "mock_vectordb"
         }
     }
 
+    #[tokio::test]
+    async fn test_embedding_dimension_mismatch_fails_before_run() {
+        let workflow = Workflow::from_yaml(
+            r#"
+name: dimension-mismatch
+steps:
+  - id: embed_query
+    type: embed
+    provider: mock
+    model: text-embedding-3-small
+    input: "{{ inputs.query }}"
+    output: [query_vector]
+  - id: search_docs
+    type: vector_search
+    depends_on: [embed_query]
+    database: mock
+    index: knowledge-base
+    query: "{{ steps.embed_query.query_vector }}"
+    output: [search_results]
+"#,
+        )
+        .unwrap();
+
+        let mut inputs = HashMap::new();
+        inputs.insert("query".to_string(), serde_json::json!("What is Rust?"));
+        let executor = WorkflowExecutor::new(workflow, inputs)
+            .unwrap()
+            .with_embedding_provider("mock", Arc::new(MockEmbeddingProvider))
+            .with_vector_db("mock", Arc::new(MockVectorSearchProvider));
+
+        let err = executor.execute().await.unwrap_err();
+        assert!(matches!(err, OrchestratorError::ValidationError(_)));
+        assert!(err.to_string().contains("384-dimensional"), "{}", err);
+        assert!(executor.context.get_output("embed_query").is_none());
+    }
+
     #[tokio::test]
     async fn test_embed_step_execution() {
         use crate::workflow::EmbedStepConfig;
@@ -1131,6 +3484,8 @@ mod tests {
                 id: "embed1".to_string(),
                 step_type: StepType::Embed,
                 depends_on: vec![],
+                depends_on_any: Vec::new(),
+                bind: HashMap::new(),
                 condition: None,
                 config: StepConfig::Embed(EmbedStepConfig {
                     provider: "mock".to_string(),
@@ -1141,9 +3496,22 @@ mod tests {
                 }),
                 output: vec!["embedding".to_string(), "metadata".to_string()],
                 timeout_seconds: None,
+                on_failure: None,
                 retry: None,
+                guardrails: None,
+                resource_class: None,
+                assert: Vec::new(),
+                allow_skipped_deps: false,
             }],
             metadata: HashMap::new(),
+            profiles: HashMap::new(),
+            guardrails: None,
+            retry_budget: None,
+            triggers: Vec::new(),
+            outputs: None,
+            artifacts: None,
+            workdir: None,
+            partials: HashMap::new(),
         };
 
         let mut inputs = HashMap::new();
@@ -1166,6 +3534,40 @@ mod tests {
         assert!(outputs.contains_key("metadata"), "Should have metadata output");
     }
 
+    #[tokio::test]
+    async fn test_profile_overrides_step_provider() {
+        let workflow = Workflow::from_yaml(
+            r#"
+name: "profiled-embed"
+steps:
+  - id: "embed1"
+    type: "embed"
+    provider: "openai"
+    model: "text-embedding-3-small"
+    input: "{{ inputs.query }}"
+    output: ["embedding"]
+profiles:
+  dev:
+    steps:
+      embed1:
+        provider: "mock"
+"#,
+        )
+        .unwrap();
+        let inputs = HashMap::from([("query".to_string(), serde_json::json!("what is rust?"))]);
+
+        // Only the dev provider is registered
+        let executor = WorkflowExecutor::new(workflow.clone(), inputs.clone())
+            .unwrap()
+            .with_profile("dev")
+            .unwrap()
+            .with_embedding_provider("mock", Arc::new(MockEmbeddingProvider));
+        let results = executor.execute().await.unwrap();
+        assert_eq!(results["embed1"].status, StepStatus::Completed);
+
+        assert!(WorkflowExecutor::new(workflow, inputs).unwrap().with_profile("prod").is_err());
+    }
+
     #[tokio::test]
     async fn test_vector_search_step_execution() {
         use crate::workflow::VectorSearchConfig;
@@ -1180,6 +3582,8 @@ mod tests {
                 id: "search1".to_string(),
                 step_type: StepType::VectorSearch,
                 depends_on: vec![],
+                depends_on_any: Vec::new(),
+                bind: HashMap::new(),
                 condition: None,
                 config: StepConfig::VectorSearch(VectorSearchConfig {
                     database: "mock".to_string(),
@@ -1190,12 +3594,30 @@ mod tests {
                     namespace: None,
                     include_metadata: true,
                     include_vectors: false,
+                    dimensions: None,
+                    mode: SearchMode::Vector,
+                    text: None,
+                    alpha: None,
+                    fusion: None,
                 }),
                 output: vec!["results".to_string(), "metadata".to_string()],
                 timeout_seconds: None,
+                on_failure: None,
                 retry: None,
+                guardrails: None,
+                resource_class: None,
+                assert: Vec::new(),
+                allow_skipped_deps: false,
             }],
             metadata: HashMap::new(),
+            profiles: HashMap::new(),
+            guardrails: None,
+            retry_budget: None,
+            triggers: Vec::new(),
+            outputs: None,
+            artifacts: None,
+            workdir: None,
+            partials: HashMap::new(),
         };
 
         let inputs = HashMap::new();
@@ -1222,6 +3644,79 @@ mod tests {
         }
     }
 
+    /// Remembers the search requests it receives.
+    #[derive(Default)]
+    struct RecordingVectorSearchProvider {
+        requests: parking_lot::Mutex<Vec<VectorSearchRequest>>,
+    }
+
+    #[async_trait::async_trait]
+    impl crate::providers::VectorSearchProvider for RecordingVectorSearchProvider {
+        async fn search(&self, request: VectorSearchRequest) -> std::result::Result<crate::providers::VectorSearchResponse, crate::providers::ProviderError> {
+            self.requests.lock().push(request);
+            Ok(crate::providers::VectorSearchResponse {
+                results: Vec::new(),
+                metadata: HashMap::new(),
+            })
+        }
+
+        async fn upsert(&self, _request: crate::providers::UpsertRequest) -> std::result::Result<crate::providers::UpsertResponse, crate::providers::ProviderError> {
+            unimplemented!()
+        }
+
+        async fn delete(&self, _request: crate::providers::DeleteRequest) -> std::result::Result<crate::providers::DeleteResponse, crate::providers::ProviderError> {
+            unimplemented!()
+        }
+
+        fn name(&self) -> &str {
+            "recording"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_keyword_and_hybrid_search_requests() {
+        let workflow = Workflow::from_yaml(
+            r#"
+name: search
+steps:
+  - id: keyword
+    type: vector_search
+    database: db
+    index: Docs
+    query: "{{ inputs.question }}"
+    mode: keyword
+    output: [results]
+  - id: hybrid
+    type: vector_search
+    database: db
+    index: Docs
+    query: "[0.5, 0.25]"
+    mode: hybrid
+    text: "{{ inputs.question }}"
+    alpha: 0.3
+    fusion: relative_score
+    output: [results]
+"#,
+        )
+        .unwrap();
+        let db = Arc::new(RecordingVectorSearchProvider::default());
+        let inputs = HashMap::from([("question".to_string(), Value::from("borrow checker"))]);
+        let executor = WorkflowExecutor::new(workflow, inputs).unwrap().with_vector_db("db", db.clone());
+
+        executor.execute().await.unwrap();
+
+        let mut requests = db.requests.lock().clone();
+        requests.sort_by_key(|r| r.mode.as_str());
+        let (hybrid, keyword) = (&requests[0], &requests[1]);
+        assert_eq!(keyword.mode, SearchMode::Keyword);
+        assert!(keyword.query.is_empty());
+        assert_eq!(keyword.text.as_deref(), Some("borrow checker"));
+        assert_eq!(hybrid.query, [0.5, 0.25]);
+        assert_eq!(hybrid.text.as_deref(), Some("borrow checker"));
+        assert_eq!(hybrid.alpha, Some(0.3));
+        assert_eq!(hybrid.fusion, Some(crate::providers::HybridFusion::RelativeScore));
+    }
+
     #[tokio::test]
     async fn test_rag_pipeline_integration() {
         use crate::workflow::{EmbedStepConfig, VectorSearchConfig};
@@ -1238,6 +3733,8 @@ mod tests {
                     id: "embed_query".to_string(),
                     step_type: StepType::Embed,
                     depends_on: vec![],
+                    depends_on_any: Vec::new(),
+                    bind: HashMap::new(),
                     condition: None,
                     config: StepConfig::Embed(EmbedStepConfig {
                         provider: "mock".to_string(),
@@ -1248,12 +3745,19 @@ mod tests {
                     }),
                     output: vec!["query_vector".to_string()],
                     timeout_seconds: None,
+                    on_failure: None,
                     retry: None,
+                    guardrails: None,
+                    resource_class: None,
+                    assert: Vec::new(),
+                    allow_skipped_deps: false,
                 },
                 Step {
                     id: "search_docs".to_string(),
                     step_type: StepType::VectorSearch,
                     depends_on: vec!["embed_query".to_string()],
+                    depends_on_any: Vec::new(),
+                    bind: HashMap::new(),
                     condition: None,
                     config: StepConfig::VectorSearch(VectorSearchConfig {
                         database: "mock".to_string(),
@@ -1264,13 +3768,31 @@ mod tests {
                         namespace: None,
                         include_metadata: true,
                         include_vectors: false,
+                        dimensions: None,
+                        mode: SearchMode::Vector,
+                        text: None,
+                        alpha: None,
+                        fusion: None,
                     }),
                     output: vec!["search_results".to_string()],
                     timeout_seconds: None,
+                    on_failure: None,
                     retry: None,
+                    guardrails: None,
+                    resource_class: None,
+                    assert: Vec::new(),
+                    allow_skipped_deps: false,
                 },
             ],
             metadata: HashMap::new(),
+            profiles: HashMap::new(),
+            guardrails: None,
+            retry_budget: None,
+            triggers: Vec::new(),
+            outputs: None,
+            artifacts: None,
+            workdir: None,
+            partials: HashMap::new(),
         };
 
         let mut inputs = HashMap::new();
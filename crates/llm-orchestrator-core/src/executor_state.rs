@@ -6,18 +6,59 @@
 //! This module provides extensions to the WorkflowExecutor to support
 //! database-backed state persistence and automatic checkpointing.
 
+#[cfg(feature = "state-persistence")]
+use crate::error::{OrchestratorError, Result};
+#[cfg(feature = "state-persistence")]
+use crate::executor::{StepResult, StepStatus, WorkflowExecutor};
 #[cfg(feature = "state-persistence")]
 use llm_orchestrator_state::{
-    Checkpoint, StateStore, StepState as PersistentStepState, WorkflowState, WorkflowStatus,
+    Checkpoint, DeadLetterQueue, ModelUsage as PersistentModelUsage, StateStore, StepState as PersistentStepState,
+    WorkflowState, WorkflowStatus,
 };
+#[cfg(feature = "state-persistence")]
+use serde_json::Value;
+#[cfg(feature = "state-persistence")]
+use std::collections::{HashMap, HashSet};
+#[cfg(feature = "state-persistence")]
+use std::sync::Arc;
+#[cfg(feature = "state-persistence")]
+use std::time::Duration;
+#[cfg(feature = "state-persistence")]
+use tracing::{debug, info};
 
+/// The steps a partial re-run executes.
+#[cfg(feature = "state-persistence")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RerunScope {
+    /// The step and every step downstream of it.
+    From(String),
+    /// Only the step.
+    Only(String),
+}
+
+#[cfg(feature = "state-persistence")]
+impl RerunScope {
+    fn step_id(&self) -> &str {
+        match self {
+            Self::From(step_id) | Self::Only(step_id) => step_id,
+        }
+    }
+}
 
 #[cfg(feature = "state-persistence")]
 impl WorkflowExecutor {
     /// Attach a state store to this executor for automatic persistence.
+    ///
+    /// The run is saved whenever it pauses on an approval step and again when it finishes.
     pub fn with_state_store(mut self, state_store: Arc<dyn StateStore>) -> Self {
-        // In a real implementation, we'd add a state_store field to WorkflowExecutor
-        // For now, we'll document the pattern
+        self.state_store = Some(state_store);
+        self
+    }
+
+    /// Attach a dead-letter queue that captures the failure context of steps
+    /// that exhaust their retries.
+    pub fn with_dead_letter_queue(mut self, dead_letters: Arc<dyn DeadLetterQueue>) -> Self {
+        self.dead_letters = Some(dead_letters);
         self
     }
 
@@ -31,10 +72,27 @@ impl WorkflowExecutor {
         debug!("Saving workflow state to database");
 
         // Create workflow state
-        let context_json = serde_json::json!({
+        let mut context_json = serde_json::json!({
             "inputs": self.context.all_inputs(),
             "outputs": self.context.all_outputs(),
+            "report": self.report(),
+            "artifacts": self.artifacts(),
+            "workdir": self.workdir(),
         });
+        if let Some(manifest) = self.manifest() {
+            context_json["manifest"] = serde_json::to_value(manifest)?;
+        }
+        // A panicking step may have left shared state half-updated
+        if self.is_poisoned() {
+            context_json["poisoned"] = serde_json::json!({
+                "panicked_steps": self
+                    .panicked_steps()
+                    .into_iter()
+                    .map(|(step_id, message)| (step_id, serde_json::Value::String(message)))
+                    .collect::<serde_json::Map<_, _>>(),
+            });
+        }
+        self.redact_json(&mut context_json);
 
         let mut workflow_state = WorkflowState::new(
             self.workflow.id.to_string(),
@@ -42,23 +100,34 @@ impl WorkflowExecutor {
             user_id,
             context_json,
         );
+        workflow_state.id = self.run_id;
+        workflow_state.tenant_id = self.tenant_id.clone();
 
         // Determine overall status
         let has_failures = self.step_results.iter().any(|r| r.value().status == StepStatus::Failed);
         let all_completed = self.step_statuses.iter().all(|s| {
             matches!(
                 s.value(),
-                StepStatus::Completed | StepStatus::Failed | StepStatus::Skipped
+                StepStatus::Completed | StepStatus::Failed | StepStatus::Skipped | StepStatus::Cancelled
             )
         });
 
-        if has_failures {
+        if self.drain.is_cancelled() && !all_completed {
+            workflow_state.status = WorkflowStatus::Paused;
+        } else if self.cancel.is_cancelled() {
+            workflow_state.status = WorkflowStatus::Cancelled;
+        } else if has_failures {
             workflow_state.status = WorkflowStatus::Failed;
         } else if all_completed {
             workflow_state.status = WorkflowStatus::Completed;
+        } else if self.approvals.has_pending() {
+            workflow_state.status = WorkflowStatus::Paused;
         } else {
             workflow_state.status = WorkflowStatus::Running;
         }
+        if !workflow_state.is_active() {
+            workflow_state.completed_at = Some(workflow_state.updated_at);
+        }
 
         // Convert step results to persistent step states
         for entry in self.step_results.iter() {
@@ -66,13 +135,7 @@ impl WorkflowExecutor {
             let step_result = entry.value();
 
             let mut step_state = PersistentStepState::new(step_id);
-            step_state.status = match step_result.status {
-                StepStatus::Pending => llm_orchestrator_state::StepStatus::Pending,
-                StepStatus::Running => llm_orchestrator_state::StepStatus::Running,
-                StepStatus::Completed => llm_orchestrator_state::StepStatus::Completed,
-                StepStatus::Failed => llm_orchestrator_state::StepStatus::Failed,
-                StepStatus::Skipped => llm_orchestrator_state::StepStatus::Skipped,
-            };
+            step_state.status = convert_step_status(&step_result.status);
 
             step_state.outputs = serde_json::to_value(&step_result.outputs)
                 .unwrap_or(Value::Null);
@@ -81,9 +144,30 @@ impl WorkflowExecutor {
                 step_state.error = Some(error.clone());
             }
 
+            if let Some(timing) = self.step_timings.get(step_id) {
+                step_state.started_at = Some(timing.started_at);
+                step_state.completed_at = timing.completed_at;
+                step_state.retry_count = timing.attempts.saturating_sub(1) as i32;
+            }
+
             workflow_state.steps.insert(step_id.clone(), step_state);
         }
 
+        workflow_state.usage = self
+            .usage()
+            .into_iter()
+            .map(|usage| PersistentModelUsage {
+                provider: usage.provider,
+                model: usage.model,
+                requests: usage.requests,
+                failed_requests: usage.failed_requests,
+                input_tokens: usage.input_tokens,
+                output_tokens: usage.output_tokens,
+                total_tokens: usage.total_tokens,
+                cost_usd: usage.cost_usd,
+            })
+            .collect();
+
         let state_id = workflow_state.id;
 
         // Save to database
@@ -107,7 +191,7 @@ impl WorkflowExecutor {
         debug!("Creating checkpoint for workflow_state_id={}", workflow_state_id);
 
         // Create snapshot
-        let snapshot = serde_json::json!({
+        let mut snapshot = serde_json::json!({
             "workflow": {
                 "id": self.workflow.id,
                 "name": &self.workflow.name,
@@ -122,6 +206,8 @@ impl WorkflowExecutor {
                 .collect::<Vec<_>>(),
         });
 
+        self.redact_json(&mut snapshot);
+
         let checkpoint = Checkpoint::new(workflow_state_id, step_id, snapshot);
         let checkpoint_id = checkpoint.id;
 
@@ -134,6 +220,129 @@ impl WorkflowExecutor {
         Ok(checkpoint_id)
     }
 
+    /// Resume the run persisted under this executor's run ID from its latest checkpoint.
+    ///
+    /// Steps completed in the checkpoint are not executed again; their outputs
+    /// are restored for the steps downstream of them. Without a state store or
+    /// a checkpoint the run starts from the beginning.
+    pub async fn resume_from_latest_checkpoint(self) -> Result<Self> {
+        let Some(state_store) = &self.state_store else {
+            return Ok(self);
+        };
+        let checkpoint = state_store
+            .get_latest_checkpoint(&self.run_id)
+            .await
+            .map_err(|e| OrchestratorError::other(format!("Failed to load checkpoint: {}", e)))?;
+        let Some(checkpoint) = checkpoint else {
+            return Ok(self);
+        };
+
+        let outputs = checkpoint.snapshot.pointer("/context/outputs");
+        let completed = checkpoint
+            .snapshot
+            .get("completed_steps")
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|v| v.as_str());
+        let mut restored = 0;
+        for step_id in completed {
+            // Skip steps the workflow no longer declares
+            if !self.step_statuses.contains_key(step_id) {
+                continue;
+            }
+            let step_outputs = outputs
+                .and_then(|outputs| outputs.get(step_id))
+                .cloned()
+                .unwrap_or_else(|| Value::Object(Default::default()));
+            self.context.set_output(step_id, step_outputs.clone());
+            self.step_statuses.insert(step_id.to_string(), StepStatus::Completed);
+            self.step_results.insert(
+                step_id.to_string(),
+                StepResult {
+                    step_id: step_id.to_string(),
+                    status: StepStatus::Completed,
+                    outputs: serde_json::from_value(step_outputs).unwrap_or_default(),
+                    error: None,
+                    error_details: None,
+                    skip_reason: None,
+                    duration: Duration::ZERO,
+                    usage: None,
+                },
+            );
+            restored += 1;
+        }
+
+        info!(run_id = %self.run_id, checkpoint_id = %checkpoint.id, restored_steps = restored, "Resuming workflow from checkpoint");
+        Ok(self)
+    }
+
+    /// Re-run part of the workflow on top of an earlier run.
+    ///
+    /// Steps outside `scope` are not executed: those that completed in `prior`
+    /// keep their outputs, which the selected steps read as usual, and the
+    /// rest are skipped. Every step a selected step depends on must either be
+    /// selected too or have completed in `prior`.
+    pub fn rerun(mut self, prior: &WorkflowState, scope: &RerunScope) -> Result<Self> {
+        let target = scope.step_id();
+        if self.workflow.get_step(target).is_none() {
+            return Err(OrchestratorError::validation(format!("Unknown step '{}'", target)));
+        }
+        let mut selected = HashSet::from([target.to_string()]);
+        if let RerunScope::From(_) = scope {
+            selected.extend(self.dag.descendants(target));
+        }
+
+        let completed = |step_id: &str| {
+            prior
+                .steps
+                .get(step_id)
+                .filter(|state| state.status == llm_orchestrator_state::StepStatus::Completed)
+        };
+        for step in self.workflow.steps.iter().filter(|step| selected.contains(&step.id)) {
+            if let Some(missing) = step.depends_on.iter().find(|dep| !selected.contains(*dep) && completed(dep).is_none()) {
+                return Err(OrchestratorError::validation(format!(
+                    "Cannot re-run step '{}': step '{}' it depends on did not complete in run {}",
+                    step.id, missing, prior.id
+                )));
+            }
+            let alternative_available = |dep: &String| selected.contains(dep) || completed(dep).is_some();
+            if !step.depends_on_any.is_empty() && !step.depends_on_any.iter().any(alternative_available) {
+                return Err(OrchestratorError::validation(format!(
+                    "Cannot re-run step '{}': none of the steps in its depends_on_any completed in run {}",
+                    step.id, prior.id
+                )));
+            }
+        }
+
+        let mut restored = 0;
+        for step in self.workflow.steps.iter().filter(|step| !selected.contains(&step.id)) {
+            let Some(state) = completed(&step.id) else {
+                continue;
+            };
+            self.context.set_output(&step.id, state.outputs.clone());
+            self.step_statuses.insert(step.id.clone(), StepStatus::Completed);
+            self.step_results.insert(
+                step.id.clone(),
+                StepResult {
+                    step_id: step.id.clone(),
+                    status: StepStatus::Completed,
+                    outputs: serde_json::from_value(state.outputs.clone()).unwrap_or_default(),
+                    error: None,
+                    error_details: None,
+                    skip_reason: None,
+                    duration: Duration::ZERO,
+                    usage: None,
+                },
+            );
+            restored += 1;
+        }
+
+        info!(run_id = %self.run_id, prior_run_id = %prior.id, rerun_steps = selected.len(), restored_steps = restored, "Re-running workflow steps");
+        self.rerun_steps = Some(Arc::new(selected));
+        Ok(self)
+    }
+
     /// Restore workflow execution from a checkpoint.
     #[cfg(feature = "state-persistence")]
     pub async fn restore_from_checkpoint(
@@ -160,7 +369,7 @@ impl WorkflowExecutor {
             .unwrap_or_default();
 
         // Extract outputs from context to populate execution context
-        let _outputs = workflow_state
+        let _outputs: HashMap<String, Value> = workflow_state
             .context
             .get("outputs")
             .and_then(|v| v.as_object())
@@ -172,7 +381,7 @@ impl WorkflowExecutor {
             .unwrap_or_default();
 
         // Get list of completed steps
-        let completed_steps = workflow_state
+        let completed_steps: Vec<String> = workflow_state
             .context
             .get("completed_steps")
             .and_then(|v| v.as_array())
@@ -217,6 +426,7 @@ fn convert_step_status(status: &StepStatus) -> llm_orchestrator_state::StepStatu
         StepStatus::Completed => llm_orchestrator_state::StepStatus::Completed,
         StepStatus::Failed => llm_orchestrator_state::StepStatus::Failed,
         StepStatus::Skipped => llm_orchestrator_state::StepStatus::Skipped,
+        StepStatus::Cancelled => llm_orchestrator_state::StepStatus::Cancelled,
     }
 }
 
@@ -224,8 +434,8 @@ fn convert_step_status(status: &StepStatus) -> llm_orchestrator_state::StepStatu
 #[cfg(feature = "state-persistence")]
 mod tests {
     use super::*;
-    use crate::workflow::{Workflow, Step, StepType, StepConfig, LlmStepConfig};
-    use llm_orchestrator_state::{SqliteStateStore, StateStore};
+    use crate::workflow::{Workflow, Step, StepType, StepConfig};
+    use llm_orchestrator_state::SqliteStateStore;
     use std::collections::HashMap;
 
     #[tokio::test]
@@ -249,6 +459,8 @@ mod tests {
                     id: "step1".to_string(),
                     step_type: StepType::Transform,
                     depends_on: vec![],
+                    depends_on_any: Vec::new(),
+                    bind: HashMap::new(),
                     condition: None,
                     config: StepConfig::Transform(crate::workflow::TransformConfig {
                         function: "test".to_string(),
@@ -257,10 +469,23 @@ mod tests {
                     }),
                     output: vec!["result".to_string()],
                     timeout_seconds: None,
+                    on_failure: None,
                     retry: None,
+                    guardrails: None,
+                    resource_class: None,
+                    assert: Vec::new(),
+                    allow_skipped_deps: false,
                 },
             ],
             metadata: HashMap::new(),
+            profiles: HashMap::new(),
+            guardrails: None,
+            retry_budget: None,
+            triggers: Vec::new(),
+            outputs: None,
+            artifacts: None,
+            workdir: None,
+            partials: HashMap::new(),
         };
 
         let inputs = HashMap::new();
@@ -289,10 +514,171 @@ mod tests {
             .await
             .expect("Failed to list resumable workflows");
 
-        // Should have at least our workflow (might be completed by now)
-        // We're just testing the integration works
-        assert!(resumable.len() >= 0); // May be 0 if already completed
+        // The run completed, so it is not resumable
+        assert!(resumable.iter().all(|state| state.id != state_id));
+    }
+
+    #[tokio::test]
+    async fn test_persisted_run_records_user_and_completion() {
+        let state_store: Arc<dyn StateStore> = Arc::new(SqliteStateStore::new(":memory:").await.unwrap());
+        let workflow = Workflow::from_yaml(
+            r#"
+name: history
+steps:
+  - id: merge
+    type: transform
+    function: merge
+    inputs: [a]
+    output: [merged]
+"#,
+        )
+        .unwrap();
+
+        let executor = WorkflowExecutor::new(workflow, HashMap::from([("a".to_string(), serde_json::json!("x"))]))
+            .unwrap()
+            .with_user_id("alice")
+            .with_tenant_id("acme")
+            .with_state_store(state_store.clone());
+        executor.execute().await.unwrap();
+
+        let state = state_store.load_workflow_state(&executor.run_id()).await.unwrap();
+        assert_eq!(state.status, WorkflowStatus::Completed);
+        assert_eq!(state.user_id.as_deref(), Some("alice"));
+        assert_eq!(state.tenant_id.as_deref(), Some("acme"));
+        let completed_at = state.completed_at.expect("finished runs record their completion time");
+        assert!(completed_at >= state.started_at);
+
+        // Step timing and the execution report are persisted with the run
+        assert!(state.steps["merge"].started_at.is_some());
+        assert!(state.steps["merge"].completed_at.is_some());
+        let report: crate::ExecutionReport = serde_json::from_value(state.context["report"].clone()).unwrap();
+        assert_eq!(report.critical_path, vec!["merge"]);
+        assert_eq!(report.steps[0].attempts, 1);
+    }
+
+    struct CountingProvider;
+
+    #[async_trait::async_trait]
+    impl crate::providers::LLMProvider for CountingProvider {
+        async fn complete(
+            &self,
+            request: crate::providers::CompletionRequest,
+        ) -> std::result::Result<crate::providers::CompletionResponse, crate::providers::ProviderError> {
+            Ok(crate::providers::CompletionResponse {
+                text: "ok".to_string(),
+                model: request.model,
+                tokens_used: Some(12),
+                usage: None,
+                tool_calls: Vec::new(),
+                metadata: HashMap::from([
+                    ("input_tokens".to_string(), serde_json::json!(9)),
+                    ("output_tokens".to_string(), serde_json::json!(3)),
+                ]),
+            })
+        }
+
+        fn name(&self) -> &str {
+            "counting"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_usage_is_persisted_with_the_run() {
+        let state_store: Arc<dyn StateStore> = Arc::new(SqliteStateStore::new(":memory:").await.unwrap());
+        let workflow = Workflow::from_yaml(
+            r#"
+name: usage
+steps:
+  - id: first
+    type: llm
+    provider: counting
+    model: small
+    prompt: "One"
+    output: [a]
+  - id: second
+    type: llm
+    provider: counting
+    model: small
+    prompt: "Two"
+    output: [b]
+"#,
+        )
+        .unwrap();
+
+        let executor = WorkflowExecutor::new(workflow, HashMap::new())
+            .unwrap()
+            .with_provider("counting", Arc::new(CountingProvider))
+            .with_state_store(state_store.clone());
+        executor.execute().await.unwrap();
+
+        let usage = executor.usage();
+        assert_eq!(usage.len(), 1);
+        assert_eq!((usage[0].requests, usage[0].input_tokens, usage[0].total_tokens), (2, 18, 24));
+
+        let state = state_store.load_workflow_state(&executor.run_id()).await.unwrap();
+        assert_eq!(state.usage.len(), 1);
+        assert_eq!(state.usage[0].provider, "counting");
+        assert_eq!(state.usage[0].model, "small");
+        assert_eq!((state.usage[0].requests, state.usage[0].output_tokens), (2, 6));
+    }
 
-        println!("✅ State persistence integration test passed");
+    #[tokio::test]
+    async fn test_rerun_executes_only_the_selected_steps() {
+        let state_store: Arc<dyn StateStore> = Arc::new(SqliteStateStore::new(":memory:").await.unwrap());
+        let workflow = Workflow::from_yaml(
+            r#"
+name: rerun
+steps:
+  - id: draft
+    type: llm
+    provider: counting
+    model: small
+    prompt: "Draft"
+    output: [text]
+  - id: review
+    type: llm
+    depends_on: [draft]
+    provider: counting
+    model: small
+    prompt: "Review {{ steps.draft.text }}"
+    output: [text]
+  - id: publish
+    type: llm
+    depends_on: [review]
+    provider: counting
+    model: small
+    prompt: "Publish {{ steps.review.text }}"
+    output: [text]
+"#,
+        )
+        .unwrap();
+
+        let executor = || {
+            WorkflowExecutor::new(workflow.clone(), HashMap::new())
+                .unwrap()
+                .with_provider("counting", Arc::new(CountingProvider))
+        };
+        let first = executor().with_state_store(state_store.clone());
+        first.execute().await.unwrap();
+        let mut prior = state_store.load_workflow_state(&first.run_id()).await.unwrap();
+
+        // From a step: it and everything downstream run again
+        let rerun = executor().rerun(&prior, &RerunScope::From("review".to_string())).unwrap();
+        let results = rerun.execute().await.unwrap();
+        assert_eq!(rerun.usage()[0].requests, 2);
+        assert!(results.values().all(|r| r.status == StepStatus::Completed));
+        assert_eq!(results["draft"].outputs["text"], "ok");
+
+        // Only a step: downstream keeps its earlier outputs
+        let rerun = executor().rerun(&prior, &RerunScope::Only("review".to_string())).unwrap();
+        rerun.execute().await.unwrap();
+        assert_eq!(rerun.usage()[0].requests, 1);
+
+        // A dependency that did not complete cannot be restored
+        prior.steps.remove("draft");
+        let err = executor().rerun(&prior, &RerunScope::Only("review".to_string())).err().unwrap();
+        assert!(err.to_string().contains("'draft'"));
+        let err = executor().rerun(&prior, &RerunScope::From("missing".to_string())).err().unwrap();
+        assert!(err.to_string().contains("Unknown step 'missing'"));
     }
 }
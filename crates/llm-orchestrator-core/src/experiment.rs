@@ -0,0 +1,485 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! A/B experiments across LLM step variants.
+//!
+//! An LLM step with `variants:` runs one of them per run, picked by weight
+//! from the run ID so retries and resumed runs keep the same variant. In
+//! `compare` mode every variant runs. Results are tagged with the variant
+//! ID, and an [`ExperimentTracker`] shared between runs aggregates latency,
+//! cost, and evaluation scores per variant.
+
+use crate::error::{OrchestratorError, Result};
+use crate::executor::WorkflowExecutor;
+use crate::workflow::{LlmStepConfig, LlmVariant, Step, StepConfig, VariantMode};
+use dashmap::DashMap;
+use futures::future::join_all;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// Running totals for one variant of one step.
+#[derive(Debug, Clone, Default)]
+struct VariantStats {
+    runs: u64,
+    failures: u64,
+    total_latency: Duration,
+    total_tokens: u64,
+    total_cost_usd: f64,
+    scores: u64,
+    score_sum: f64,
+}
+
+/// Aggregated results of one variant of one step.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VariantSummary {
+    /// Step the variant belongs to.
+    pub step_id: String,
+
+    /// Variant identifier.
+    pub variant: String,
+
+    /// Number of provider calls made with the variant.
+    pub runs: u64,
+
+    /// Number of those calls that failed.
+    pub failures: u64,
+
+    /// Mean call latency in milliseconds.
+    pub mean_latency_ms: f64,
+
+    /// Tokens used across all calls.
+    pub total_tokens: u64,
+
+    /// Cost across all calls, for providers that report it.
+    pub total_cost_usd: f64,
+
+    /// Mean score given by evaluate steps depending on the step.
+    pub mean_score: Option<f64>,
+
+    /// Number of scores behind `mean_score`.
+    pub scored_runs: u64,
+}
+
+/// Aggregates per-variant metrics across runs for offline analysis.
+///
+/// Share one tracker between executors with
+/// [`WorkflowExecutor::with_experiment_tracker`].
+#[derive(Debug, Default)]
+pub struct ExperimentTracker {
+    stats: DashMap<(String, String), VariantStats>,
+}
+
+impl ExperimentTracker {
+    /// Creates an empty tracker.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records one provider call made with `variant`.
+    pub fn record_call(
+        &self,
+        step_id: &str,
+        variant: &str,
+        latency: Duration,
+        tokens_used: Option<u32>,
+        cost_usd: Option<f64>,
+        success: bool,
+    ) {
+        let mut stats = self
+            .stats
+            .entry((step_id.to_string(), variant.to_string()))
+            .or_default();
+        stats.runs += 1;
+        if !success {
+            stats.failures += 1;
+        }
+        stats.total_latency += latency;
+        stats.total_tokens += u64::from(tokens_used.unwrap_or(0));
+        stats.total_cost_usd += cost_usd.unwrap_or(0.0);
+    }
+
+    /// Records an evaluation score for output produced by `variant`.
+    pub fn record_score(&self, step_id: &str, variant: &str, score: f64) {
+        let mut stats = self
+            .stats
+            .entry((step_id.to_string(), variant.to_string()))
+            .or_default();
+        stats.scores += 1;
+        stats.score_sum += score;
+    }
+
+    /// Summarizes every variant seen so far, ordered by step and variant.
+    pub fn summary(&self) -> Vec<VariantSummary> {
+        let mut summary: Vec<VariantSummary> = self
+            .stats
+            .iter()
+            .map(|entry| {
+                let ((step_id, variant), stats) = entry.pair();
+                VariantSummary {
+                    step_id: step_id.clone(),
+                    variant: variant.clone(),
+                    runs: stats.runs,
+                    failures: stats.failures,
+                    mean_latency_ms: if stats.runs == 0 {
+                        0.0
+                    } else {
+                        stats.total_latency.as_secs_f64() * 1000.0 / stats.runs as f64
+                    },
+                    total_tokens: stats.total_tokens,
+                    total_cost_usd: stats.total_cost_usd,
+                    mean_score: (stats.scores > 0).then(|| stats.score_sum / stats.scores as f64),
+                    scored_runs: stats.scores,
+                }
+            })
+            .collect();
+        summary.sort_by(|a, b| (&a.step_id, &a.variant).cmp(&(&b.step_id, &b.variant)));
+        summary
+    }
+}
+
+/// Picks the variant a run uses for a step, by weight.
+///
+/// The pick depends only on the run and step IDs, so it is the same on
+/// every attempt and when the run is resumed.
+pub fn select_variant<'a>(variants: &'a [LlmVariant], run_id: Uuid, step_id: &str) -> Option<&'a LlmVariant> {
+    pick_variant(variants, run_id.as_bytes(), step_id)
+}
+
+/// Picks the variant a deterministic run uses for a step, by weight.
+///
+/// The pick depends only on the seed and step ID, so runs with the same
+/// seed use the same variants.
+pub fn select_seeded_variant<'a>(variants: &'a [LlmVariant], seed: u64, step_id: &str) -> Option<&'a LlmVariant> {
+    pick_variant(variants, &seed.to_be_bytes(), step_id)
+}
+
+fn pick_variant<'a>(variants: &'a [LlmVariant], key: &[u8], step_id: &str) -> Option<&'a LlmVariant> {
+    // FNV-1a, which unlike the std hasher is stable across releases
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in key.iter().chain(step_id.as_bytes()) {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+
+    let total: f64 = variants.iter().map(|v| v.weight).sum();
+    let mut point = (hash >> 11) as f64 / (1u64 << 53) as f64 * total;
+    for variant in variants {
+        if point < variant.weight {
+            return Some(variant);
+        }
+        point -= variant.weight;
+    }
+    variants.iter().rev().find(|v| v.weight > 0.0)
+}
+
+/// Applies a variant's overrides to the step's configuration.
+fn variant_config(base: &LlmStepConfig, variant: &LlmVariant) -> LlmStepConfig {
+    // A variant's own prompt or prompt_ref replaces both of the step's
+    let (prompt, prompt_ref) = if variant.prompt.is_some() || variant.prompt_ref.is_some() {
+        (variant.prompt.clone().unwrap_or_default(), variant.prompt_ref.clone())
+    } else {
+        (base.prompt.clone(), base.prompt_ref.clone())
+    };
+    LlmStepConfig {
+        provider: variant.provider.clone().unwrap_or_else(|| base.provider.clone()),
+        model: variant.model.clone().unwrap_or_else(|| base.model.clone()),
+        prompt,
+        prompt_ref,
+        temperature: variant.temperature.or(base.temperature),
+        max_tokens: variant.max_tokens.or(base.max_tokens),
+        system: variant.system.clone().or_else(|| base.system.clone()),
+        stream: base.stream,
+        variants: Vec::new(),
+        variant_mode: VariantMode::Split,
+        hedge: base.hedge.clone(),
+        memory: base.memory.clone(),
+        images: base.images.clone(),
+        extra: base.extra.clone(),
+    }
+}
+
+impl WorkflowExecutor {
+    /// Aggregates per-variant metrics of experiment steps into `tracker`.
+    pub fn with_experiment_tracker(mut self, tracker: Arc<ExperimentTracker>) -> Self {
+        self.experiment_tracker = Some(tracker);
+        self
+    }
+
+    /// Executes an LLM step that defines variants.
+    ///
+    /// Outputs come from the variant picked for this run and carry its ID
+    /// under `_variant`. In compare mode the other variants run alongside
+    /// and every variant's result is stored under `_variants`.
+    pub(crate) async fn execute_llm_variants(&self, step: &Step) -> Result<HashMap<String, Value>> {
+        let llm_config = match &step.config {
+            StepConfig::Llm(config) => config,
+            _ => {
+                return Err(OrchestratorError::InvalidStepConfig {
+                    step_id: step.id.clone(),
+                    reason: "Expected LLM step config".to_string(),
+                })
+            }
+        };
+        let chosen = match self.seed() {
+            Some(seed) => select_seeded_variant(&llm_config.variants, seed, &step.id),
+            None => select_variant(&llm_config.variants, self.run_id, &step.id),
+        };
+        let chosen = chosen.ok_or_else(|| {
+            OrchestratorError::InvalidStepConfig {
+                step_id: step.id.clone(),
+                reason: "No variant has a positive weight".to_string(),
+            }
+        })?;
+
+        if llm_config.variant_mode == VariantMode::Split {
+            return self.execute_variant(step, llm_config, chosen).await;
+        }
+
+        let results = join_all(
+            llm_config
+                .variants
+                .iter()
+                .map(|variant| self.execute_variant(step, llm_config, variant)),
+        )
+        .await;
+
+        let mut comparison = Map::new();
+        let mut chosen_outputs = None;
+        for (variant, result) in llm_config.variants.iter().zip(results) {
+            let entry = match &result {
+                Ok(outputs) => {
+                    let response = outputs.get("_response").cloned().unwrap_or(Value::Null);
+                    json!({
+                        "text": response.get("text"),
+                        "model": response.get("model"),
+                        "tokens_used": response.get("tokens_used"),
+                        "cost_usd": response.get("cost_usd"),
+                        "latency_ms": outputs.get("_latency_ms"),
+                    })
+                }
+                Err(e) => json!({ "error": e.to_string() }),
+            };
+            comparison.insert(variant.id.clone(), entry);
+            if variant.id == chosen.id {
+                chosen_outputs = Some(result);
+            }
+        }
+
+        let mut outputs = chosen_outputs.expect("chosen variant is one of the step's variants")?;
+        outputs.insert("_variants".to_string(), Value::Object(comparison));
+        Ok(outputs)
+    }
+
+    /// Runs the step with one variant's configuration and records the call.
+    async fn execute_variant(
+        &self,
+        step: &Step,
+        base: &LlmStepConfig,
+        variant: &LlmVariant,
+    ) -> Result<HashMap<String, Value>> {
+        let mut variant_step = step.clone();
+        variant_step.config = StepConfig::Llm(variant_config(base, variant));
+
+        let start = Instant::now();
+        let result = self.execute_llm_step(&variant_step).await;
+        let latency = start.elapsed();
+
+        if let Some(tracker) = &self.experiment_tracker {
+            let response = result.as_ref().ok().and_then(|o| o.get("_response"));
+            tracker.record_call(
+                &step.id,
+                &variant.id,
+                latency,
+                response
+                    .and_then(|r| r.get("tokens_used"))
+                    .and_then(Value::as_u64)
+                    .map(|t| t as u32),
+                response.and_then(|r| r.get("cost_usd")).and_then(Value::as_f64),
+                result.is_ok(),
+            );
+        }
+
+        let mut outputs = result?;
+        outputs.insert("_variant".to_string(), json!(variant.id));
+        outputs.insert("_latency_ms".to_string(), json!(latency.as_millis() as u64));
+        Ok(outputs)
+    }
+
+    /// Credits an evaluation score to the variants of the steps it depends on.
+    pub(crate) fn record_experiment_score(&self, step: &Step, score: f64) {
+        let Some(tracker) = &self.experiment_tracker else {
+            return;
+        };
+        for dep in &step.depends_on {
+            let variant = self.step_results.get(dep).and_then(|r| {
+                r.outputs.get("_variant").and_then(Value::as_str).map(str::to_string)
+            });
+            if let Some(variant) = variant {
+                tracker.record_score(dep, &variant, score);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::StepStatus;
+    use crate::providers::{CompletionRequest, CompletionResponse, LLMProvider, ProviderError};
+    use crate::workflow::Workflow;
+
+    /// Replies with the model name and reports a fixed cost.
+    struct ModelEcho;
+
+    #[async_trait::async_trait]
+    impl LLMProvider for ModelEcho {
+        async fn complete(&self, request: CompletionRequest) -> std::result::Result<CompletionResponse, ProviderError> {
+            Ok(CompletionResponse {
+                text: format!("{}: {}", request.model, request.prompt),
+                model: request.model,
+                tokens_used: Some(10),
+                usage: None,
+                tool_calls: Vec::new(),
+                metadata: HashMap::from([("cost_usd".to_string(), json!(0.5))]),
+            })
+        }
+
+        fn name(&self) -> &str {
+            "echo"
+        }
+    }
+
+    const EXPERIMENT: &str = r#"
+name: "experiment"
+steps:
+  - id: "ask"
+    type: "llm"
+    provider: "echo"
+    model: "base"
+    prompt: "Hello"
+    output: ["answer"]
+    variants:
+      - id: "a"
+        weight: 3
+      - id: "b"
+        model: "challenger"
+        prompt: "Hi"
+  - id: "grade"
+    type: "evaluate"
+    depends_on: ["ask"]
+    target: "{{ steps.ask.answer }}"
+    metrics:
+      - type: "regex"
+        pattern: "^challenger"
+"#;
+
+    async fn run(yaml: &str, tracker: &Arc<ExperimentTracker>) -> HashMap<String, crate::executor::StepResult> {
+        let workflow = Workflow::from_yaml(yaml).unwrap();
+        WorkflowExecutor::new(workflow, HashMap::new())
+            .unwrap()
+            .with_provider("echo", Arc::new(ModelEcho))
+            .with_experiment_tracker(tracker.clone())
+            .execute()
+            .await
+            .unwrap()
+    }
+
+    fn variants(weights: &[f64]) -> Vec<LlmVariant> {
+        weights
+            .iter()
+            .enumerate()
+            .map(|(i, weight)| LlmVariant {
+                id: i.to_string(),
+                weight: *weight,
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_select_variant_is_stable_and_weighted() {
+        let variants = variants(&[3.0, 1.0, 0.0]);
+        let run_id = Uuid::new_v4();
+        let first = select_variant(&variants, run_id, "ask").unwrap();
+        assert_eq!(select_variant(&variants, run_id, "ask").unwrap().id, first.id);
+
+        let mut counts = [0; 3];
+        for _ in 0..2000 {
+            let variant = select_variant(&variants, Uuid::new_v4(), "ask").unwrap();
+            counts[variant.id.parse::<usize>().unwrap()] += 1;
+        }
+        assert_eq!(counts[2], 0);
+        assert!((1300..1700).contains(&counts[0]), "counts: {:?}", counts);
+
+        assert!(select_variant(&[], run_id, "ask").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_deterministic_runs_pick_the_same_variants() {
+        let workflow = Workflow::from_yaml(EXPERIMENT).unwrap();
+        let mut picked = std::collections::HashSet::new();
+        for _ in 0..10 {
+            let results = WorkflowExecutor::new(workflow.clone(), HashMap::new())
+                .unwrap()
+                .with_provider("echo", Arc::new(ModelEcho))
+                .with_deterministic(7)
+                .execute()
+                .await
+                .unwrap();
+            picked.insert(results["ask"].outputs["_variant"].as_str().unwrap().to_string());
+        }
+        assert_eq!(picked.len(), 1, "picked: {:?}", picked);
+
+        // Seeds still split runs between variants by weight
+        let variants = variants(&[3.0, 1.0]);
+        let picks: Vec<_> = (0..200).map(|seed| &select_seeded_variant(&variants, seed, "ask").unwrap().id).collect();
+        assert!(picks.iter().any(|id| *id == "0") && picks.iter().any(|id| *id == "1"));
+    }
+
+    #[tokio::test]
+    async fn test_split_mode_tags_results_and_tracks_variants() {
+        let tracker = Arc::new(ExperimentTracker::new());
+        for _ in 0..20 {
+            let results = run(EXPERIMENT, &tracker).await;
+            let ask = &results["ask"];
+            assert_eq!(ask.status, StepStatus::Completed);
+            let expected = match ask.outputs["_variant"].as_str().unwrap() {
+                "a" => "base: Hello",
+                _ => "challenger: Hi",
+            };
+            assert_eq!(ask.outputs["answer"], json!(expected));
+        }
+
+        let summary = tracker.summary();
+        assert_eq!(summary.iter().map(|s| s.runs).sum::<u64>(), 20);
+        for variant in &summary {
+            assert_eq!(variant.failures, 0);
+            assert_eq!(variant.total_tokens, variant.runs * 10);
+            assert_eq!(variant.total_cost_usd, variant.runs as f64 * 0.5);
+            assert_eq!(variant.scored_runs, variant.runs);
+            let expected_score = if variant.variant == "b" { 1.0 } else { 0.0 };
+            assert_eq!(variant.mean_score, Some(expected_score));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_compare_mode_runs_every_variant() {
+        let tracker = Arc::new(ExperimentTracker::new());
+        let yaml = EXPERIMENT.replace("    variants:", "    variant_mode: \"compare\"\n    variants:");
+        let results = run(&yaml, &tracker).await;
+
+        let compared = results["ask"].outputs["_variants"].as_object().unwrap();
+        assert_eq!(compared["a"]["text"], json!("base: Hello"));
+        assert_eq!(compared["b"]["text"], json!("challenger: Hi"));
+        assert_eq!(compared["b"]["cost_usd"], json!(0.5));
+
+        let summary = tracker.summary();
+        assert_eq!(summary.len(), 2);
+        assert!(summary.iter().all(|s| s.runs == 1));
+        assert_eq!(summary.iter().map(|s| s.scored_runs).sum::<u64>(), 1);
+    }
+}
@@ -0,0 +1,213 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Vector record lookups by ID.
+//!
+//! A `vector_fetch` step reads records from a vector database registered with
+//! [`WorkflowExecutor::with_vector_db`], for grounding on known documents or
+//! for skipping records that are already stored:
+//!
+//! ```yaml
+//! - id: existing
+//!   type: vector_fetch
+//!   depends_on: [chunk]
+//!   database: pinecone
+//!   index: knowledge-base
+//!   namespace: "{{ inputs.tenant }}"
+//!   ids: "{{ steps.chunk.ids }}"
+//!   output: [records, missing]
+//! ```
+//!
+//! The first output lists the records found in the order requested; the
+//! second lists the requested IDs with no record.
+
+use crate::error::{OrchestratorError, Result};
+use crate::executor::WorkflowExecutor;
+use crate::providers::{provider_error, FetchRequest};
+use crate::workflow::{Step, StepConfig};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use tracing::{debug, info};
+
+/// Output names used when a step declares none.
+const DEFAULT_OUTPUTS: [&str; 2] = ["records", "missing"];
+
+impl WorkflowExecutor {
+    /// Executes a vector fetch step.
+    pub(crate) async fn execute_vector_fetch_step(&self, step: &Step) -> Result<HashMap<String, Value>> {
+        let config = match &step.config {
+            StepConfig::VectorFetch(config) => config,
+            _ => {
+                return Err(OrchestratorError::InvalidStepConfig {
+                    step_id: step.id.clone(),
+                    reason: "Expected VectorFetch step config".to_string(),
+                })
+            }
+        };
+        let vector_db = self
+            .vector_dbs
+            .get(&config.database)
+            .map(|db| db.value().clone())
+            .ok_or_else(|| OrchestratorError::other(format!("Vector database '{}' not registered", config.database)))?;
+
+        let mut ids = config.ids.clone();
+        let mut templates = Vec::new();
+        crate::http::collect_strings(&ids, &mut templates);
+        for template in templates {
+            self.resolve_template_secrets(&template).await?;
+        }
+        self.render_values(&mut ids)?;
+        let ids = parse_ids(&step.id, ids)?;
+        let namespace = config.namespace.as_deref().map(|ns| self.context.render_template(ns)).transpose()?;
+        debug!(step_id = %step.id, database = %config.database, index = %config.index, ids = ids.len(), "Fetching vectors");
+
+        let request = FetchRequest {
+            index: config.index.clone(),
+            ids: ids.clone(),
+            namespace,
+        };
+        self.record_request(&step.id, &request);
+        let started = std::time::Instant::now();
+        let response = vector_db.fetch(request).await;
+        self.record_provider_latency(&step.id, started.elapsed());
+        let response = response.map_err(|e| provider_error(&config.database, "Vector fetch error", e))?;
+
+        let found: HashSet<&str> = response.records.iter().map(|r| r.id.as_str()).collect();
+        let missing: Vec<&String> = ids.iter().filter(|id| !found.contains(id.as_str())).collect();
+        info!(step_id = %step.id, found = found.len(), missing = missing.len(), "Vector fetch completed");
+
+        let names: Vec<&str> = if step.output.is_empty() {
+            DEFAULT_OUTPUTS.to_vec()
+        } else {
+            step.output.iter().map(String::as_str).collect()
+        };
+        let values = [serde_json::to_value(&response.records)?, serde_json::to_value(missing)?];
+        Ok(names.into_iter().map(String::from).zip(values).collect())
+    }
+}
+
+/// Reads rendered IDs, accepting numbers.
+fn parse_ids(step_id: &str, ids: Value) -> Result<Vec<String>> {
+    let invalid = |ids: &Value| OrchestratorError::InvalidStepConfig {
+        step_id: step_id.to_string(),
+        reason: format!("IDs must be a list of strings or numbers, got: {}", ids),
+    };
+    let Value::Array(list) = &ids else {
+        return Err(invalid(&ids));
+    };
+    list.iter()
+        .map(|id| match id {
+            Value::String(id) => Ok(id.clone()),
+            Value::Number(id) => Ok(id.to_string()),
+            _ => Err(invalid(&ids)),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::{
+        DeleteRequest, DeleteResponse, FetchResponse, ProviderError, UpsertRequest, UpsertResponse,
+        VectorRecord, VectorSearchProvider, VectorSearchRequest, VectorSearchResponse,
+    };
+    use crate::workflow::Workflow;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    /// Holds records "1" and "3" in namespace "acme".
+    struct StoredVectorDb;
+
+    #[async_trait::async_trait]
+    impl VectorSearchProvider for StoredVectorDb {
+        async fn search(&self, _request: VectorSearchRequest) -> std::result::Result<VectorSearchResponse, ProviderError> {
+            unimplemented!()
+        }
+
+        async fn upsert(&self, _request: UpsertRequest) -> std::result::Result<UpsertResponse, ProviderError> {
+            unimplemented!()
+        }
+
+        async fn delete(&self, _request: DeleteRequest) -> std::result::Result<DeleteResponse, ProviderError> {
+            unimplemented!()
+        }
+
+        async fn fetch(&self, request: FetchRequest) -> std::result::Result<FetchResponse, ProviderError> {
+            assert_eq!(request.namespace.as_deref(), Some("acme"));
+            let records = request
+                .ids
+                .into_iter()
+                .filter(|id| id == "1" || id == "3")
+                .map(|id| VectorRecord {
+                    metadata: Some(json!({"text": format!("chunk {}", id)})),
+                    id,
+                    vector: vec![0.5, 0.5],
+                })
+                .collect();
+            Ok(FetchResponse { records })
+        }
+
+        fn name(&self) -> &str {
+            "stored"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_reports_found_and_missing_ids() {
+        let workflow = Workflow::from_yaml(
+            r#"
+name: lookup
+steps:
+  - id: lookup
+    type: vector_fetch
+    database: db
+    index: docs
+    namespace: "{{ inputs.tenant }}"
+    ids: [1, "2", "{{ inputs.last }}"]
+"#,
+        )
+        .unwrap();
+        let inputs = HashMap::from([("tenant".to_string(), json!("acme")), ("last".to_string(), json!("3"))]);
+        let executor = WorkflowExecutor::new(workflow, inputs)
+            .unwrap()
+            .with_vector_db("db", Arc::new(StoredVectorDb));
+
+        let results = executor.execute().await.unwrap();
+        let outputs = &results["lookup"].outputs;
+        assert_eq!(outputs["records"][0], json!({"id": "1", "vector": [0.5, 0.5], "metadata": {"text": "chunk 1"}}));
+        assert_eq!(outputs["records"][1]["id"], json!("3"));
+        assert_eq!(outputs["missing"], json!(["2"]));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_fails_when_database_cannot_fetch() {
+        struct SearchOnly;
+
+        #[async_trait::async_trait]
+        impl VectorSearchProvider for SearchOnly {
+            async fn search(&self, _request: VectorSearchRequest) -> std::result::Result<VectorSearchResponse, ProviderError> {
+                unimplemented!()
+            }
+
+            async fn upsert(&self, _request: UpsertRequest) -> std::result::Result<UpsertResponse, ProviderError> {
+                unimplemented!()
+            }
+
+            async fn delete(&self, _request: DeleteRequest) -> std::result::Result<DeleteResponse, ProviderError> {
+                unimplemented!()
+            }
+
+            fn name(&self) -> &str {
+                "search-only"
+            }
+        }
+
+        let workflow = Workflow::from_yaml("name: lookup\nsteps:\n  - {id: lookup, type: vector_fetch, database: db, index: docs, ids: [a]}\n").unwrap();
+        let executor = WorkflowExecutor::new(workflow, HashMap::new())
+            .unwrap()
+            .with_vector_db("db", Arc::new(SearchOnly));
+
+        let results = executor.execute().await;
+        assert!(matches!(results, Err(OrchestratorError::StepsFailed(_))));
+    }
+}
@@ -0,0 +1,278 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! OpenAI-compatible chat triggers.
+//!
+//! A workflow with a `chat` trigger answers `POST /v1/chat/completions`
+//! requests for its model name on a server started with
+//! `llm-orchestrator serve`, so apps written against the OpenAI SDK can use
+//! it by pointing their base URL at the server:
+//!
+//! ```yaml
+//! name: support-rag
+//! triggers:
+//!   - type: chat
+//!     model: support-bot
+//!     reply: "{{ steps.answer.text }}"
+//! steps:
+//!   - id: context
+//!     type: vector_search
+//!     query: "{{ inputs.prompt }}"
+//!     ...
+//!   - id: answer
+//!     type: llm
+//!     prompt: "{{ inputs.prompt }}\n\nContext: {{ steps.context.results }}"
+//!     ...
+//! ```
+//!
+//! Without `inputs`, a run receives the request `messages`, the text of the
+//! last user message as `prompt`, and the system messages as `system`;
+//! `inputs` templates see the same values and the whole `request`. The reply
+//! is rendered from the finished run and returned as the assistant message,
+//! or as a single chunk when the client asks for a stream.
+
+use crate::context::ExecutionContext;
+use crate::error::{OrchestratorError, Result};
+use crate::executor::{StepResult, StepStatus};
+use crate::usage::ModelUsage;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// A model name served by running the workflow.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ChatTrigger {
+    /// Model name clients request.
+    pub model: String,
+
+    /// Workflow input templates, keyed by input name.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub inputs: HashMap<String, String>,
+
+    /// Template of the assistant reply, rendered against the finished run.
+    pub reply: String,
+}
+
+/// A chat completion request, as sent by OpenAI clients.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatCompletionRequest {
+    /// Requested model.
+    pub model: String,
+
+    /// Conversation so far.
+    pub messages: Vec<ChatMessage>,
+
+    /// Whether to answer with a server-sent event stream.
+    #[serde(default)]
+    pub stream: bool,
+
+    /// Other parameters (`temperature`, `user`, ...), available to `inputs` templates.
+    #[serde(flatten)]
+    pub params: serde_json::Map<String, Value>,
+}
+
+/// A message of a chat completion request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatMessage {
+    /// `system`, `user`, `assistant`, or `tool`.
+    pub role: String,
+
+    /// Text, or an array of content parts.
+    #[serde(default)]
+    pub content: Value,
+}
+
+impl ChatMessage {
+    /// The text of the message, joining its text parts.
+    pub fn text(&self) -> String {
+        match &self.content {
+            Value::String(text) => text.clone(),
+            Value::Array(parts) => parts
+                .iter()
+                .filter(|part| part["type"] == "text")
+                .filter_map(|part| part["text"].as_str())
+                .collect::<Vec<_>>()
+                .join("\n"),
+            _ => String::new(),
+        }
+    }
+}
+
+impl ChatTrigger {
+    /// Checks that the trigger can be served.
+    pub fn validate(&self) -> Result<()> {
+        if self.model.is_empty() {
+            return Err(OrchestratorError::validation("Chat trigger needs a model name"));
+        }
+        if self.reply.is_empty() {
+            return Err(OrchestratorError::validation(format!("Chat trigger '{}' needs a reply template", self.model)));
+        }
+        Ok(())
+    }
+
+    /// Maps a chat request into workflow inputs.
+    pub fn map_inputs(&self, request: &ChatCompletionRequest) -> Result<HashMap<String, Value>> {
+        let prompt = request
+            .messages
+            .iter()
+            .rev()
+            .find(|m| m.role == "user")
+            .map(ChatMessage::text)
+            .unwrap_or_default();
+        let system: Vec<String> = request
+            .messages
+            .iter()
+            .filter(|m| m.role == "system" || m.role == "developer")
+            .map(ChatMessage::text)
+            .collect();
+        let values = HashMap::from([
+            ("messages".to_string(), serde_json::to_value(&request.messages)?),
+            ("prompt".to_string(), Value::String(prompt)),
+            ("system".to_string(), Value::String(system.join("\n"))),
+        ]);
+        if self.inputs.is_empty() {
+            return Ok(values);
+        }
+
+        let mut data = values;
+        data.insert("request".to_string(), serde_json::to_value(request)?);
+        let context = ExecutionContext::new(data);
+        let mut inputs = HashMap::new();
+        for (input, template) in &self.inputs {
+            inputs.insert(input.clone(), context.render_value(template)?);
+        }
+        Ok(inputs)
+    }
+
+    /// Renders the reply from the inputs and results of a finished run.
+    pub fn render_reply(&self, inputs: HashMap<String, Value>, results: &HashMap<String, StepResult>) -> Result<String> {
+        let context = ExecutionContext::new(inputs);
+        for result in results.values().filter(|r| r.status == StepStatus::Completed) {
+            context.set_output(&result.step_id, serde_json::to_value(&result.outputs)?);
+        }
+        context.render_template(&self.reply)
+    }
+}
+
+/// Token counts of a run, in the shape of an OpenAI `usage` object.
+fn usage_json(usage: &[ModelUsage]) -> Value {
+    let prompt: u64 = usage.iter().map(|u| u.input_tokens).sum();
+    let completion: u64 = usage.iter().map(|u| u.output_tokens).sum();
+    json!({
+        "prompt_tokens": prompt,
+        "completion_tokens": completion,
+        "total_tokens": prompt + completion,
+    })
+}
+
+/// A `chat.completion` response carrying the reply of run `run_id`.
+pub fn chat_completion(run_id: Uuid, model: &str, reply: &str, usage: &[ModelUsage], created: i64) -> Value {
+    json!({
+        "id": format!("chatcmpl-{}", run_id.simple()),
+        "object": "chat.completion",
+        "created": created,
+        "model": model,
+        "choices": [{
+            "index": 0,
+            "message": {"role": "assistant", "content": reply},
+            "finish_reason": "stop",
+        }],
+        "usage": usage_json(usage),
+    })
+}
+
+/// The `chat.completion.chunk` events streaming the reply of run `run_id`.
+///
+/// The reply is sent whole, in the first chunk; the last chunk carries the
+/// finish reason and usage.
+pub fn chat_completion_chunks(run_id: Uuid, model: &str, reply: &str, usage: &[ModelUsage], created: i64) -> Vec<Value> {
+    let chunk = |delta: Value, finish_reason: Value| {
+        json!({
+            "id": format!("chatcmpl-{}", run_id.simple()),
+            "object": "chat.completion.chunk",
+            "created": created,
+            "model": model,
+            "choices": [{"index": 0, "delta": delta, "finish_reason": finish_reason}],
+        })
+    };
+    let mut last = chunk(json!({}), json!("stop"));
+    last["usage"] = usage_json(usage);
+    vec![chunk(json!({"role": "assistant", "content": reply}), Value::Null), last]
+}
+
+/// An OpenAI-style error body.
+pub fn error_body(message: &str, kind: &str, code: Option<&str>) -> Value {
+    json!({"error": {"message": message, "type": kind, "param": null, "code": code}})
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn request(body: Value) -> ChatCompletionRequest {
+        serde_json::from_value(body).unwrap()
+    }
+
+    #[test]
+    fn test_map_inputs() {
+        let body = json!({
+            "model": "support-bot",
+            "temperature": 0.2,
+            "messages": [
+                {"role": "system", "content": "Be brief."},
+                {"role": "user", "content": "Hi"},
+                {"role": "assistant", "content": "Hello!"},
+                {"role": "user", "content": [{"type": "text", "text": "Reset my password"}]},
+            ],
+        });
+        let trigger: ChatTrigger = serde_yaml::from_str("model: support-bot\nreply: x").unwrap();
+        let inputs = trigger.map_inputs(&request(body.clone())).unwrap();
+        assert_eq!(inputs["prompt"], "Reset my password");
+        assert_eq!(inputs["system"], "Be brief.");
+        assert_eq!(inputs["messages"].as_array().unwrap().len(), 4);
+
+        let mapped: ChatTrigger = serde_yaml::from_str(
+            "model: support-bot\nreply: x\ninputs:\n  question: '{{ prompt }}'\n  temperature: '{{ request.temperature }}'",
+        )
+        .unwrap();
+        let inputs = mapped.map_inputs(&request(body)).unwrap();
+        assert_eq!(inputs["question"], "Reset my password");
+        assert_eq!(inputs["temperature"], 0.2);
+    }
+
+    #[test]
+    fn test_reply_and_responses() {
+        let trigger: ChatTrigger = serde_yaml::from_str("model: bot\nreply: '{{ steps.answer.text }}'").unwrap();
+        let result = StepResult {
+            step_id: "answer".to_string(),
+            status: StepStatus::Completed,
+            outputs: HashMap::from([("text".to_string(), json!("Use the reset link."))]),
+            error: None,
+            error_details: None,
+            skip_reason: None,
+            duration: Duration::ZERO,
+            usage: None,
+        };
+        let results = HashMap::from([("answer".to_string(), result)]);
+        let reply = trigger.render_reply(HashMap::new(), &results).unwrap();
+        assert_eq!(reply, "Use the reset link.");
+
+        let usage = [ModelUsage {
+            input_tokens: 12,
+            output_tokens: 5,
+            ..Default::default()
+        }];
+        let run_id = Uuid::new_v4();
+        let completion = chat_completion(run_id, "bot", &reply, &usage, 0);
+        assert_eq!(completion["choices"][0]["message"]["content"], reply);
+        assert_eq!(completion["usage"]["total_tokens"], 17);
+
+        let chunks = chat_completion_chunks(run_id, "bot", &reply, &usage, 0);
+        assert_eq!(chunks[0]["choices"][0]["delta"]["content"], reply);
+        assert_eq!(chunks[1]["choices"][0]["finish_reason"], "stop");
+        assert_eq!(chunks[0]["id"], completion["id"]);
+    }
+}
@@ -0,0 +1,551 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Golden-file regression testing.
+//!
+//! A [`GoldenRecorder`] wraps the LLM providers of a real run and captures
+//! every request and response. Together with the inputs and final step
+//! outputs, they are saved as a [`GoldenRecording`], one JSON file per
+//! workflow and input set. Replaying the recording answers each provider
+//! request from the file instead of the provider, and [`GoldenRecording::diff`]
+//! lists the outputs that no longer match, so template, transform, and
+//! wiring changes can be checked offline:
+//!
+//! ```no_run
+//! # async fn example(workflow: llm_orchestrator_core::Workflow) -> llm_orchestrator_core::Result<()> {
+//! use llm_orchestrator_core::golden::GoldenRecording;
+//! use llm_orchestrator_core::WorkflowExecutor;
+//!
+//! for (path, recording) in GoldenRecording::load_dir("golden", &workflow.name)? {
+//!     let mut executor = WorkflowExecutor::new(workflow.clone(), recording.inputs.clone())?;
+//!     for (name, provider) in recording.replay_providers() {
+//!         executor = executor.with_provider(name, provider);
+//!     }
+//!     let drift = recording.diff(&executor.execute().await?);
+//!     assert!(drift.is_empty(), "{} drifted: {:?}", path.display(), drift);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Only LLM provider calls are replayed; other steps (HTTP, MCP tools,
+//! vector search) run live.
+//!
+//! With the `secrets` feature, recorded requests and responses pass through
+//! a [`Redactor`] before they are kept, so resolved secrets and API keys are
+//! never written to the file. Replays mask live requests with the same
+//! redactor before matching them against the recording.
+
+use crate::error::{OrchestratorError, Result};
+use crate::executor::{StepResult, StepStatus};
+use crate::providers::{CompletionRequest, CompletionResponse, LLMProvider, ProviderError};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+#[cfg(feature = "secrets")]
+use llm_orchestrator_secrets::Redactor;
+use llm_orchestrator_providers::{MiddlewareProvider, ProviderMiddleware};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// A provider request and the response it got.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderExchange {
+    /// Name the provider is registered under.
+    pub provider: String,
+
+    /// Request sent.
+    pub request: CompletionRequest,
+
+    /// Response received.
+    pub response: CompletionResponse,
+}
+
+/// Captures the successful provider calls of a run.
+#[derive(Debug, Clone)]
+pub struct GoldenRecorder {
+    exchanges: Arc<Mutex<Vec<ProviderExchange>>>,
+    #[cfg(feature = "secrets")]
+    redactor: Redactor,
+}
+
+#[cfg_attr(not(feature = "secrets"), allow(clippy::derivable_impls))]
+impl Default for GoldenRecorder {
+    fn default() -> Self {
+        Self {
+            exchanges: Arc::default(),
+            #[cfg(feature = "secrets")]
+            redactor: Redactor::new().with_default_patterns(),
+        }
+    }
+}
+
+impl GoldenRecorder {
+    /// Create a recorder with nothing captured.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mask captured calls with the run's redactor, so secrets it resolves are not recorded.
+    ///
+    /// Replaces the default redactor, which masks common API key formats.
+    #[cfg(feature = "secrets")]
+    pub fn with_redactor(mut self, redactor: Redactor) -> Self {
+        self.redactor = redactor;
+        self
+    }
+
+    /// Wraps a provider so its calls are captured.
+    pub fn wrap(&self, provider: Arc<dyn LLMProvider>) -> Arc<dyn LLMProvider> {
+        Arc::new(MiddlewareProvider::new(provider).layer(Arc::new(self.clone())))
+    }
+
+    /// Calls captured so far, in the order they completed.
+    pub fn exchanges(&self) -> Vec<ProviderExchange> {
+        self.exchanges.lock().clone()
+    }
+}
+
+#[async_trait]
+impl ProviderMiddleware for GoldenRecorder {
+    async fn after_response(
+        &self,
+        provider: &str,
+        request: &CompletionRequest,
+        result: &std::result::Result<CompletionResponse, ProviderError>,
+        _latency: Duration,
+    ) {
+        if let Ok(response) = result {
+            let (request, response) = (request.clone(), response.clone());
+            #[cfg(feature = "secrets")]
+            let (request, response) = (redacted(&self.redactor, request), redacted(&self.redactor, response));
+            self.exchanges.lock().push(ProviderExchange {
+                provider: provider.to_string(),
+                request,
+                response,
+            });
+        }
+    }
+}
+
+/// Masks secrets in every string of `value`, keeping it as is if it does not round-trip.
+#[cfg(feature = "secrets")]
+fn redacted<T: Serialize + serde::de::DeserializeOwned>(redactor: &Redactor, value: T) -> T {
+    let Ok(mut json) = serde_json::to_value(&value) else {
+        return value;
+    };
+    redactor.redact_json(&mut json);
+    serde_json::from_value(json).unwrap_or(value)
+}
+
+/// LLM provider answering requests from recorded exchanges.
+///
+/// A request is answered by the first recorded exchange with an identical
+/// request, preferring ones not replayed yet; a request that was never
+/// recorded fails, since its prompt or parameters drifted.
+pub struct ReplayProvider {
+    name: String,
+    // Recorded requests (as JSON, for comparison), responses, and whether each was replayed
+    exchanges: Mutex<Vec<(Value, CompletionResponse, bool)>>,
+    #[cfg(feature = "secrets")]
+    redactor: Redactor,
+}
+
+impl ReplayProvider {
+    /// Replays the exchanges recorded for provider `name`.
+    pub fn new(name: impl Into<String>, exchanges: &[ProviderExchange]) -> Self {
+        let name = name.into();
+        let exchanges = exchanges
+            .iter()
+            .filter(|exchange| exchange.provider == name)
+            .map(|exchange| (request_key(&exchange.request), exchange.response.clone(), false))
+            .collect();
+        Self {
+            name,
+            exchanges: Mutex::new(exchanges),
+            #[cfg(feature = "secrets")]
+            redactor: Redactor::new().with_default_patterns(),
+        }
+    }
+
+    /// Mask live requests with the replaying run's redactor before matching them.
+    ///
+    /// Recorded requests are masked, so requests carrying resolved secrets
+    /// only match once the same secrets are masked in them.
+    #[cfg(feature = "secrets")]
+    pub fn with_redactor(mut self, redactor: Redactor) -> Self {
+        self.redactor = redactor;
+        self
+    }
+
+    fn key(&self, request: &CompletionRequest) -> Value {
+        #[cfg_attr(not(feature = "secrets"), allow(unused_mut))]
+        let mut key = request_key(request);
+        #[cfg(feature = "secrets")]
+        self.redactor.redact_json(&mut key);
+        key
+    }
+}
+
+#[async_trait]
+impl LLMProvider for ReplayProvider {
+    async fn complete(&self, request: CompletionRequest) -> std::result::Result<CompletionResponse, ProviderError> {
+        let key = self.key(&request);
+        let mut exchanges = self.exchanges.lock();
+        let matching = exchanges.iter().position(|(recorded, _, replayed)| *recorded == key && !replayed)
+            .or_else(|| exchanges.iter().position(|(recorded, _, _)| *recorded == key));
+        let Some(index) = matching else {
+            return Err(ProviderError::InvalidRequest(format!(
+                "No recorded response for this request to model '{}'; the prompt or parameters changed since recording",
+                request.model
+            )));
+        };
+        exchanges[index].2 = true;
+        Ok(exchanges[index].1.clone())
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+fn request_key(request: &CompletionRequest) -> Value {
+    serde_json::to_value(request).unwrap_or_default()
+}
+
+/// A recorded run: its inputs, provider calls, and final outputs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GoldenRecording {
+    /// Workflow name.
+    pub workflow: String,
+
+    /// Workflow inputs.
+    pub inputs: HashMap<String, Value>,
+
+    /// When the run was recorded.
+    pub recorded_at: DateTime<Utc>,
+
+    /// Provider calls of the run.
+    pub exchanges: Vec<ProviderExchange>,
+
+    /// Outputs of the completed steps, keyed by step ID.
+    pub outputs: BTreeMap<String, BTreeMap<String, Value>>,
+}
+
+/// An output that differs from its recording.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OutputDrift {
+    /// Step the output belongs to.
+    pub step_id: String,
+
+    /// Output name, or `None` when the whole step is missing from one side.
+    pub output: Option<String>,
+
+    /// Recorded value.
+    pub expected: Option<Value>,
+
+    /// Value of the replay.
+    pub actual: Option<Value>,
+}
+
+impl GoldenRecording {
+    /// Builds a recording of a finished run.
+    pub fn new(
+        workflow: impl Into<String>,
+        inputs: HashMap<String, Value>,
+        recorder: &GoldenRecorder,
+        results: &HashMap<String, StepResult>,
+    ) -> Self {
+        Self {
+            workflow: workflow.into(),
+            inputs,
+            recorded_at: Utc::now(),
+            exchanges: recorder.exchanges(),
+            outputs: completed_outputs(results),
+        }
+    }
+
+    /// File name of the recording: the workflow name and a hash of the inputs.
+    pub fn file_name(&self) -> String {
+        let name: String = self
+            .workflow
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+            .collect();
+        // Sorted, so the same inputs always hash the same
+        let inputs: BTreeMap<&String, &Value> = self.inputs.iter().collect();
+        let digest = Sha256::digest(serde_json::to_vec(&inputs).unwrap_or_default());
+        format!("{}-{}.json", name, &hex::encode(digest)[..12])
+    }
+
+    /// Writes the recording into `dir`, replacing an earlier recording of the same inputs.
+    pub fn save_in(&self, dir: impl AsRef<Path>) -> Result<PathBuf> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir)?;
+        let path = dir.join(self.file_name());
+        fs::write(&path, serde_json::to_string_pretty(self)?)?;
+        Ok(path)
+    }
+
+    /// Loads the recordings of workflow `workflow` in `dir`, ordered by file name.
+    pub fn load_dir(dir: impl AsRef<Path>, workflow: &str) -> Result<Vec<(PathBuf, Self)>> {
+        let mut recordings = Vec::new();
+        for entry in fs::read_dir(dir.as_ref())? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            let content = fs::read_to_string(&path)?;
+            let recording: Self = serde_json::from_str(&content).map_err(|e| {
+                OrchestratorError::parse(format!("Invalid golden recording {}: {}", path.display(), e))
+            })?;
+            if recording.workflow == workflow {
+                recordings.push((path, recording));
+            }
+        }
+        recordings.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(recordings)
+    }
+
+    /// Providers answering from this recording, keyed by provider name.
+    pub fn replay_providers(&self) -> HashMap<String, Arc<dyn LLMProvider>> {
+        self.replay_providers_with(|provider| provider)
+    }
+
+    /// Providers answering from this recording, masking live requests with `redactor`.
+    ///
+    /// Pass the redactor of the replaying run (see
+    /// [`WorkflowExecutor::with_redactor`](crate::WorkflowExecutor::with_redactor))
+    /// so requests carrying resolved secrets match their masked recordings.
+    #[cfg(feature = "secrets")]
+    pub fn replay_providers_redacted(&self, redactor: &Redactor) -> HashMap<String, Arc<dyn LLMProvider>> {
+        self.replay_providers_with(|provider| provider.with_redactor(redactor.clone()))
+    }
+
+    fn replay_providers_with(
+        &self,
+        configure: impl Fn(ReplayProvider) -> ReplayProvider,
+    ) -> HashMap<String, Arc<dyn LLMProvider>> {
+        let mut providers: HashMap<String, Arc<dyn LLMProvider>> = HashMap::new();
+        for exchange in &self.exchanges {
+            if !providers.contains_key(&exchange.provider) {
+                let provider = configure(ReplayProvider::new(exchange.provider.clone(), &self.exchanges));
+                providers.insert(exchange.provider.clone(), Arc::new(provider));
+            }
+        }
+        providers
+    }
+
+    /// Outputs of a replay that differ from the recording, ordered by step and output.
+    ///
+    /// Internal outputs (named with a leading `_`, like `_response`) are not compared.
+    pub fn diff(&self, results: &HashMap<String, StepResult>) -> Vec<OutputDrift> {
+        let actual = completed_outputs(results);
+        let mut drift = Vec::new();
+        let step_ids: std::collections::BTreeSet<&String> = self.outputs.keys().chain(actual.keys()).collect();
+        for step_id in step_ids {
+            let (expected, actual) = match (self.outputs.get(step_id), actual.get(step_id)) {
+                (Some(expected), Some(actual)) => (expected, actual),
+                (expected, actual) => {
+                    drift.push(OutputDrift {
+                        step_id: step_id.clone(),
+                        output: None,
+                        expected: expected.map(|outputs| serde_json::to_value(outputs).unwrap_or_default()),
+                        actual: actual.map(|outputs| serde_json::to_value(outputs).unwrap_or_default()),
+                    });
+                    continue;
+                }
+            };
+            let names: std::collections::BTreeSet<&String> = expected.keys().chain(actual.keys()).collect();
+            for name in names {
+                if expected.get(name) != actual.get(name) {
+                    drift.push(OutputDrift {
+                        step_id: step_id.clone(),
+                        output: Some(name.clone()),
+                        expected: expected.get(name).cloned(),
+                        actual: actual.get(name).cloned(),
+                    });
+                }
+            }
+        }
+        drift
+    }
+}
+
+/// Outputs of completed steps, without internal outputs.
+pub(crate) fn completed_outputs(results: &HashMap<String, StepResult>) -> BTreeMap<String, BTreeMap<String, Value>> {
+    results
+        .values()
+        .filter(|result| result.status == StepStatus::Completed)
+        .map(|result| {
+            let outputs = result
+                .outputs
+                .iter()
+                .filter(|(name, _)| !name.starts_with('_'))
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect();
+            (result.step_id.clone(), outputs)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workflow::Workflow;
+    use crate::WorkflowExecutor;
+    use serde_json::json;
+
+    struct Echo;
+
+    #[async_trait]
+    impl LLMProvider for Echo {
+        async fn complete(&self, request: CompletionRequest) -> std::result::Result<CompletionResponse, ProviderError> {
+            Ok(CompletionResponse {
+                text: request.prompt.to_uppercase(),
+                model: request.model,
+                tokens_used: Some(3),
+                usage: None,
+                tool_calls: Vec::new(),
+                metadata: HashMap::new(),
+            })
+        }
+
+        fn name(&self) -> &str {
+            "echo"
+        }
+    }
+
+    fn workflow(prompt: &str) -> Workflow {
+        Workflow::from_yaml(&format!(
+            r#"
+name: "golden"
+steps:
+  - id: "shout"
+    type: "llm"
+    provider: "echo"
+    model: "echo-1"
+    prompt: "{}"
+    output: ["text"]
+"#,
+            prompt
+        ))
+        .unwrap()
+    }
+
+    async fn run(workflow: Workflow, inputs: &HashMap<String, Value>, providers: HashMap<String, Arc<dyn LLMProvider>>) -> Result<HashMap<String, StepResult>> {
+        let mut executor = WorkflowExecutor::new(workflow, inputs.clone())?;
+        for (name, provider) in providers {
+            executor = executor.with_provider(name, provider);
+        }
+        executor.execute().await
+    }
+
+    #[tokio::test]
+    async fn test_record_and_replay() {
+        let inputs = HashMap::from([("name".to_string(), json!("ada"))]);
+        let recorder = GoldenRecorder::new();
+        let providers = HashMap::from([("echo".to_string(), recorder.wrap(Arc::new(Echo)))]);
+        let results = run(workflow("hello {{ inputs.name }}"), &inputs, providers).await.unwrap();
+
+        let recording = GoldenRecording::new("golden", inputs.clone(), &recorder, &results);
+        assert_eq!(recording.exchanges.len(), 1);
+        assert_eq!(recording.outputs["shout"]["text"], json!("HELLO ADA"));
+
+        let dir = std::env::temp_dir().join(format!("llm_orchestrator_golden_{}", uuid::Uuid::new_v4().simple()));
+        let path = recording.save_in(&dir).unwrap();
+        let loaded = GoldenRecording::load_dir(&dir, "golden").unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].0, path);
+        assert!(GoldenRecording::load_dir(&dir, "other").unwrap().is_empty());
+        let _ = fs::remove_dir_all(&dir);
+
+        // The same workflow replays without drift
+        let results = run(workflow("hello {{ inputs.name }}"), &inputs, recording.replay_providers()).await.unwrap();
+        assert!(recording.diff(&results).is_empty());
+
+        // A changed prompt has no recording
+        let err = run(workflow("hi {{ inputs.name }}"), &inputs, recording.replay_providers()).await.unwrap_err();
+        assert!(matches!(err, OrchestratorError::StepsFailed(_)), "{}", err);
+    }
+
+    #[cfg(feature = "secrets")]
+    #[tokio::test]
+    async fn test_recordings_mask_secrets() {
+        // Upper case, so the echoed response carries it verbatim
+        std::env::set_var("GOLDEN_TEST_PARTNER_TOKEN", "TOK-GOLDEN-123");
+        let store = Arc::new(llm_orchestrator_secrets::EnvSecretStore::with_prefix("GOLDEN_TEST_".to_string()));
+        let prompt = "key {{ secret 'partner/token' }}";
+        let inputs = HashMap::new();
+
+        let redactor = Redactor::new().with_default_patterns();
+        let recorder = GoldenRecorder::new().with_redactor(redactor.clone());
+        let executor = WorkflowExecutor::new(workflow(prompt), inputs.clone())
+            .unwrap()
+            .with_provider("echo", recorder.wrap(Arc::new(Echo)))
+            .with_secret_store(store.clone())
+            .with_redactor(redactor);
+        let results = executor.execute().await.unwrap();
+
+        let recording = GoldenRecording::new("golden", inputs.clone(), &recorder, &results);
+        let saved = serde_json::to_string(&recording).unwrap();
+        assert!(!saved.contains("TOK-GOLDEN-123"), "{}", saved);
+        assert_eq!(recording.exchanges[0].request.prompt, "key [REDACTED]");
+        assert_eq!(recording.exchanges[0].response.text, "KEY [REDACTED]");
+
+        // A replay resolving the same secret matches the masked recording
+        let redactor = Redactor::new().with_default_patterns();
+        let mut executor = WorkflowExecutor::new(workflow(prompt), inputs)
+            .unwrap()
+            .with_secret_store(store)
+            .with_redactor(redactor.clone());
+        for (name, provider) in recording.replay_providers_redacted(&redactor) {
+            executor = executor.with_provider(name, provider);
+        }
+        let results = executor.execute().await.unwrap();
+        assert!(recording.diff(&results).is_empty());
+    }
+
+    #[test]
+    fn test_diff_and_file_name() {
+        let recording = GoldenRecording {
+            workflow: "support bot".to_string(),
+            inputs: HashMap::from([("a".to_string(), json!(1)), ("b".to_string(), json!(2))]),
+            recorded_at: Utc::now(),
+            exchanges: Vec::new(),
+            outputs: BTreeMap::from([
+                ("classify".to_string(), BTreeMap::from([("label".to_string(), json!("billing"))])),
+                ("reply".to_string(), BTreeMap::from([("text".to_string(), json!("Hi"))])),
+            ]),
+        };
+        assert!(recording.file_name().starts_with("support_bot-"));
+        assert_eq!(recording.file_name(), recording.clone().file_name());
+
+        let result = |step_id: &str, outputs: Value| StepResult {
+            step_id: step_id.to_string(),
+            status: StepStatus::Completed,
+            outputs: serde_json::from_value(outputs).unwrap(),
+            error: None,
+            error_details: None,
+            skip_reason: None,
+            duration: Duration::ZERO,
+            usage: None,
+        };
+        let results = HashMap::from([
+            ("classify".to_string(), result("classify", json!({"label": "refund", "_response": {}}))),
+            ("extra".to_string(), result("extra", json!({"x": 1}))),
+        ]);
+        let drift = recording.diff(&results);
+        let summary: Vec<(&str, Option<&str>)> = drift.iter().map(|d| (d.step_id.as_str(), d.output.as_deref())).collect();
+        assert_eq!(summary, [("classify", Some("label")), ("extra", None), ("reply", None)]);
+        assert_eq!(drift[0].expected, Some(json!("billing")));
+        assert_eq!(drift[0].actual, Some(json!("refund")));
+        assert_eq!(drift[2].actual, None);
+    }
+}
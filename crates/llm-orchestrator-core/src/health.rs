@@ -5,10 +5,18 @@
 //!
 //! This module provides health check endpoints for Kubernetes readiness/liveness
 //! probes and general system health monitoring.
+//!
+//! A [`HealthRegistry`] checks the providers, vector databases, secret stores,
+//! and state stores a process depends on, concurrently and each under a
+//! timeout. A failing provider or vector database only degrades the process,
+//! since other steps can still run; a failing store makes it unhealthy.
 
+use crate::providers::{EmbeddingProvider, LLMProvider, VectorSearchProvider};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::future::Future;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use async_trait::async_trait;
 
 /// Overall health status.
@@ -37,6 +45,40 @@ pub struct HealthCheckResult {
     pub message: Option<String>,
 }
 
+impl HealthCheckResult {
+    /// Builds a result whose status is the worst of the component statuses.
+    fn from_checks(checks: HashMap<String, ComponentHealth>) -> Self {
+        let status = checks
+            .values()
+            .map(|check| check.status)
+            .fold(HealthStatus::Healthy, |overall, status| match (overall, status) {
+                (HealthStatus::Unhealthy, _) | (_, HealthStatus::Unhealthy) => HealthStatus::Unhealthy,
+                (HealthStatus::Degraded, _) | (_, HealthStatus::Degraded) => HealthStatus::Degraded,
+                _ => HealthStatus::Healthy,
+            });
+        Self {
+            status,
+            timestamp: chrono::Utc::now(),
+            checks,
+            message: None,
+        }
+    }
+
+    fn alive() -> Self {
+        Self {
+            status: HealthStatus::Healthy,
+            timestamp: chrono::Utc::now(),
+            checks: HashMap::new(),
+            message: Some("Application is alive".to_string()),
+        }
+    }
+
+    /// Whether the process can serve traffic, i.e. is not unhealthy.
+    pub fn is_ready(&self) -> bool {
+        self.status != HealthStatus::Unhealthy
+    }
+}
+
 /// Health status of an individual component.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ComponentHealth {
@@ -127,9 +169,6 @@ impl HealthChecker {
     ///
     /// Returns an overall health status based on all component checks.
     pub async fn check_all(&self) -> HealthCheckResult {
-        let mut checks = HashMap::new();
-        let mut overall_status = HealthStatus::Healthy;
-
         // Run all checks in parallel
         let futures: Vec<_> = self.checks.iter().map(|check| {
             async move {
@@ -139,31 +178,7 @@ impl HealthChecker {
             }
         }).collect();
 
-        let results = futures::future::join_all(futures).await;
-
-        for (name, result) in results {
-            // Update overall status based on component status
-            match result.status {
-                HealthStatus::Unhealthy => {
-                    overall_status = HealthStatus::Unhealthy;
-                }
-                HealthStatus::Degraded => {
-                    if overall_status != HealthStatus::Unhealthy {
-                        overall_status = HealthStatus::Degraded;
-                    }
-                }
-                HealthStatus::Healthy => {}
-            }
-
-            checks.insert(name, result);
-        }
-
-        HealthCheckResult {
-            status: overall_status,
-            timestamp: chrono::Utc::now(),
-            checks,
-            message: None,
-        }
+        HealthCheckResult::from_checks(futures::future::join_all(futures).await.into_iter().collect())
     }
 
     /// Performs a simple liveness check.
@@ -171,12 +186,7 @@ impl HealthChecker {
     /// This is a lightweight check that verifies the application is running.
     /// Unlike `check_all()`, it doesn't check dependencies.
     pub fn liveness(&self) -> HealthCheckResult {
-        HealthCheckResult {
-            status: HealthStatus::Healthy,
-            timestamp: chrono::Utc::now(),
-            checks: HashMap::new(),
-            message: Some("Application is alive".to_string()),
-        }
+        HealthCheckResult::alive()
     }
 
     /// Performs a readiness check.
@@ -193,6 +203,171 @@ impl Default for HealthChecker {
     }
 }
 
+/// Time a component check may take before it is reported unhealthy.
+pub const DEFAULT_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Health check backed by an async function.
+struct ComponentCheck<F> {
+    name: String,
+    check: F,
+}
+
+#[async_trait]
+impl<F, Fut> HealthCheck for ComponentCheck<F>
+where
+    F: Fn() -> Fut + Send + Sync,
+    Fut: Future<Output = std::result::Result<(), String>> + Send,
+{
+    async fn check_health(&self) -> ComponentHealth {
+        match (self.check)().await {
+            Ok(()) => ComponentHealth::healthy(),
+            Err(error) => ComponentHealth::unhealthy(error),
+        }
+    }
+
+    fn component_name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Registered check and whether its failure makes the process unhealthy.
+struct RegisteredCheck {
+    check: Arc<dyn HealthCheck>,
+    critical: bool,
+}
+
+/// Readiness and liveness of the components a process depends on.
+///
+/// Components are named `<kind>:<name>`, e.g. `provider:openai` or
+/// `state:sqlite`.
+pub struct HealthRegistry {
+    checks: Vec<RegisteredCheck>,
+    timeout: Duration,
+}
+
+impl HealthRegistry {
+    /// Creates an empty registry using [`DEFAULT_CHECK_TIMEOUT`].
+    pub fn new() -> Self {
+        Self {
+            checks: Vec::new(),
+            timeout: DEFAULT_CHECK_TIMEOUT,
+        }
+    }
+
+    /// Sets how long each component check may take.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Registers a component whose failure makes the process unhealthy.
+    pub fn register(&mut self, check: Arc<dyn HealthCheck>) {
+        self.checks.push(RegisteredCheck { check, critical: true });
+    }
+
+    /// Registers a component whose failure only degrades the process.
+    pub fn register_optional(&mut self, check: Arc<dyn HealthCheck>) {
+        self.checks.push(RegisteredCheck { check, critical: false });
+    }
+
+    /// Registers an LLM provider.
+    pub fn register_provider(&mut self, name: &str, provider: Arc<dyn LLMProvider>) {
+        self.register_optional(Arc::new(ComponentCheck {
+            name: format!("provider:{}", name),
+            check: move || {
+                let provider = provider.clone();
+                async move { provider.health_check().await.map_err(|e| e.to_string()) }
+            },
+        }));
+    }
+
+    /// Registers an embedding provider.
+    pub fn register_embedding_provider(&mut self, name: &str, provider: Arc<dyn EmbeddingProvider>) {
+        self.register_optional(Arc::new(ComponentCheck {
+            name: format!("embedding:{}", name),
+            check: move || {
+                let provider = provider.clone();
+                async move { provider.health_check().await.map_err(|e| e.to_string()) }
+            },
+        }));
+    }
+
+    /// Registers a vector database.
+    pub fn register_vector_db(&mut self, name: &str, vector_db: Arc<dyn VectorSearchProvider>) {
+        self.register_optional(Arc::new(ComponentCheck {
+            name: format!("vector_db:{}", name),
+            check: move || {
+                let vector_db = vector_db.clone();
+                async move { vector_db.health_check().await.map_err(|e| e.to_string()) }
+            },
+        }));
+    }
+
+    /// Registers a secret store.
+    #[cfg(feature = "secrets")]
+    pub fn register_secret_store(&mut self, name: &str, store: Arc<dyn llm_orchestrator_secrets::SecretStore>) {
+        self.register(Arc::new(ComponentCheck {
+            name: format!("secrets:{}", name),
+            check: move || {
+                let store = store.clone();
+                async move { store.health_check().await.map_err(|e| e.to_string()) }
+            },
+        }));
+    }
+
+    /// Registers a state store.
+    #[cfg(feature = "state-persistence")]
+    pub fn register_state_store(&mut self, name: &str, store: Arc<dyn llm_orchestrator_state::StateStore>) {
+        self.register(Arc::new(ComponentCheck {
+            name: format!("state:{}", name),
+            check: move || {
+                let store = store.clone();
+                async move { store.health_check().await.map_err(|e| e.to_string()) }
+            },
+        }));
+    }
+
+    /// Names of the registered components.
+    pub fn components(&self) -> Vec<&str> {
+        self.checks.iter().map(|c| c.check.component_name()).collect()
+    }
+
+    /// Checks every component concurrently.
+    ///
+    /// A check that outlasts the timeout is reported unhealthy; a failed
+    /// optional component is reported degraded.
+    pub async fn readiness(&self) -> HealthCheckResult {
+        let futures = self.checks.iter().map(|registered| async move {
+            let start = Instant::now();
+            let mut health = match tokio::time::timeout(self.timeout, registered.check.check_health()).await {
+                Ok(health) => health,
+                Err(_) => ComponentHealth::unhealthy(format!(
+                    "Health check timed out after {}ms",
+                    self.timeout.as_millis()
+                )),
+            };
+            if !registered.critical && health.status == HealthStatus::Unhealthy {
+                health.status = HealthStatus::Degraded;
+            }
+            health.response_time_ms.get_or_insert(start.elapsed().as_millis() as u64);
+            (registered.check.component_name().to_string(), health)
+        });
+
+        HealthCheckResult::from_checks(futures::future::join_all(futures).await.into_iter().collect())
+    }
+
+    /// Reports that the process is running, without checking components.
+    pub fn liveness(&self) -> HealthCheckResult {
+        HealthCheckResult::alive()
+    }
+}
+
+impl Default for HealthRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Memory usage health check.
 pub struct MemoryHealthCheck {
     /// Maximum memory usage threshold (bytes).
@@ -323,6 +498,70 @@ mod tests {
         assert_eq!(result.checks["memory"].status, HealthStatus::Healthy);
     }
 
+    /// Provider whose health check always fails.
+    struct DownProvider;
+
+    #[async_trait]
+    impl LLMProvider for DownProvider {
+        async fn complete(
+            &self,
+            _request: crate::providers::CompletionRequest,
+        ) -> std::result::Result<crate::providers::CompletionResponse, crate::providers::ProviderError> {
+            unimplemented!()
+        }
+
+        fn name(&self) -> &str {
+            "down"
+        }
+
+        async fn health_check(&self) -> std::result::Result<(), crate::providers::ProviderError> {
+            Err(crate::providers::ProviderError::AuthError("invalid key".to_string()))
+        }
+    }
+
+    /// Check that never finishes within a test timeout.
+    struct HangingCheck;
+
+    #[async_trait]
+    impl HealthCheck for HangingCheck {
+        async fn check_health(&self) -> ComponentHealth {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            ComponentHealth::healthy()
+        }
+
+        fn component_name(&self) -> &str {
+            "hanging"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_registry_degrades_on_provider_failure() {
+        let mut registry = HealthRegistry::new();
+        registry.register(Arc::new(MemoryHealthCheck::new(1024)));
+        registry.register_provider("openai", Arc::new(DownProvider));
+
+        let result = registry.readiness().await;
+        assert_eq!(result.status, HealthStatus::Degraded);
+        assert!(result.is_ready());
+        let provider = &result.checks["provider:openai"];
+        assert_eq!(provider.status, HealthStatus::Degraded);
+        assert!(provider.error.as_deref().unwrap().contains("invalid key"));
+        assert!(provider.response_time_ms.is_some());
+        assert_eq!(result.checks["memory"].status, HealthStatus::Healthy);
+    }
+
+    #[tokio::test]
+    async fn test_registry_times_out_critical_checks() {
+        let mut registry = HealthRegistry::new().with_timeout(Duration::from_millis(20));
+        registry.register(Arc::new(HangingCheck));
+
+        let result = registry.readiness().await;
+        assert_eq!(result.status, HealthStatus::Unhealthy);
+        assert!(!result.is_ready());
+        assert!(result.checks["hanging"].error.as_deref().unwrap().contains("timed out"));
+        assert_eq!(registry.liveness().status, HealthStatus::Healthy);
+    }
+
     #[test]
     fn test_health_check_result_serialization() {
         let mut checks = HashMap::new();
@@ -0,0 +1,229 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Hedged LLM requests.
+//!
+//! An LLM step with `hedge:` sends a second, identical request when the first
+//! has not answered after a latency percentile of recent calls to the same
+//! provider and model, and uses whichever response succeeds first. Latencies
+//! are recorded by a [`LatencyTracker`], which can be shared between runs so
+//! that percentiles are known from the first step of a new run. Hedged
+//! requests count against the run's retry budget.
+
+use crate::executor::WorkflowExecutor;
+use crate::providers::{CompletionRequest, CompletionResponse, LLMProvider, ProviderError};
+use crate::workflow::{LlmStepConfig, Step};
+use dashmap::DashMap;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+/// Number of recent latencies kept per provider and model.
+const LATENCY_WINDOW: usize = 100;
+
+/// Latencies needed before percentiles are used.
+const MIN_SAMPLES: usize = 20;
+
+/// Recent completion latencies per provider and model.
+#[derive(Debug, Default)]
+pub struct LatencyTracker {
+    windows: DashMap<(String, String), VecDeque<Duration>>,
+}
+
+impl LatencyTracker {
+    /// Creates an empty tracker.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the latency of a successful completion.
+    pub fn record(&self, provider: &str, model: &str, latency: Duration) {
+        let mut window = self
+            .windows
+            .entry((provider.to_string(), model.to_string()))
+            .or_default();
+        if window.len() == LATENCY_WINDOW {
+            window.pop_front();
+        }
+        window.push_back(latency);
+    }
+
+    /// Latency below which `percentile` percent of recent completions finished.
+    ///
+    /// Returns `None` until enough completions have been recorded.
+    pub fn percentile(&self, provider: &str, model: &str, percentile: f64) -> Option<Duration> {
+        let window = self.windows.get(&(provider.to_string(), model.to_string()))?;
+        if window.len() < MIN_SAMPLES {
+            return None;
+        }
+        let mut latencies: Vec<Duration> = window.iter().copied().collect();
+        latencies.sort();
+        // Nearest-rank percentile
+        let rank = (percentile / 100.0 * latencies.len() as f64).ceil() as usize;
+        Some(latencies[rank.clamp(1, latencies.len()) - 1])
+    }
+}
+
+impl WorkflowExecutor {
+    /// Shares a latency tracker with other runs, so hedging uses their latencies.
+    pub fn with_latency_tracker(mut self, tracker: Arc<LatencyTracker>) -> Self {
+        self.latency_tracker = tracker;
+        self
+    }
+
+    /// Sends a completion request, hedging it when the step asks for it.
+    pub(crate) async fn complete_llm(
+        &self,
+        step: &Step,
+        llm_config: &LlmStepConfig,
+        provider: &dyn LLMProvider,
+        request: CompletionRequest,
+    ) -> std::result::Result<CompletionResponse, ProviderError> {
+        let start = Instant::now();
+        let result = match self.hedge_delay(llm_config) {
+            Some(delay) => self.complete_hedged(step, provider, request, delay).await,
+            None => provider.complete(request).await,
+        };
+        if result.is_ok() {
+            self.latency_tracker
+                .record(&llm_config.provider, &llm_config.model, start.elapsed());
+        }
+        result
+    }
+
+    /// Delay after which a step's request is hedged, if it is.
+    fn hedge_delay(&self, llm_config: &LlmStepConfig) -> Option<Duration> {
+        let hedge = llm_config.hedge.as_ref()?;
+        self.latency_tracker
+            .percentile(&llm_config.provider, &llm_config.model, hedge.percentile)
+            .or_else(|| hedge.initial_delay_ms.map(Duration::from_millis))
+    }
+
+    async fn complete_hedged(
+        &self,
+        step: &Step,
+        provider: &dyn LLMProvider,
+        request: CompletionRequest,
+        delay: Duration,
+    ) -> std::result::Result<CompletionResponse, ProviderError> {
+        let mut primary = provider.complete(request.clone());
+        tokio::select! {
+            result = &mut primary => return result,
+            _ = tokio::time::sleep(delay) => {}
+        }
+
+        // A hedged request costs as much as a retry, but adds no latency
+        if let Some(budget) = &self.retry_budget {
+            if !budget.try_spend(Duration::ZERO) {
+                debug!(step_id = %step.id, "Retry budget exhausted, not hedging");
+                return primary.await;
+            }
+        }
+        debug!(step_id = %step.id, delay_ms = delay.as_millis() as u64, "Hedging slow LLM request");
+
+        // Take the first success; if one request fails, wait for the other
+        let mut hedge = provider.complete(request);
+        let (first, rest) = tokio::select! {
+            result = &mut primary => (result, hedge),
+            result = &mut hedge => (result, primary),
+        };
+        match first {
+            Ok(response) => Ok(response),
+            Err(_) => rest.await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workflow::Workflow;
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// Hangs on its first call and answers later calls at once.
+    #[derive(Default)]
+    struct SlowFirstCall {
+        calls: AtomicU32,
+    }
+
+    #[async_trait::async_trait]
+    impl LLMProvider for SlowFirstCall {
+        async fn complete(&self, request: CompletionRequest) -> std::result::Result<CompletionResponse, ProviderError> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            if call == 0 {
+                tokio::time::sleep(Duration::from_secs(30)).await;
+            }
+            Ok(CompletionResponse {
+                text: format!("call {}", call),
+                model: request.model,
+                tokens_used: None,
+                usage: None,
+                tool_calls: Vec::new(),
+                metadata: HashMap::new(),
+            })
+        }
+
+        fn name(&self) -> &str {
+            "slow-first"
+        }
+    }
+
+    const WORKFLOW: &str = r#"
+name: "hedged"
+steps:
+  - id: "ask"
+    type: "llm"
+    provider: "slow"
+    model: "m"
+    prompt: "Hello"
+    output: ["answer"]
+    hedge:
+      initial_delay_ms: 20
+"#;
+
+    #[test]
+    fn test_latency_percentile() {
+        let tracker = LatencyTracker::new();
+        for ms in 1..MIN_SAMPLES as u64 {
+            tracker.record("p", "m", Duration::from_millis(ms));
+        }
+        assert_eq!(tracker.percentile("p", "m", 50.0), None);
+
+        tracker.record("p", "m", Duration::from_millis(20));
+        assert_eq!(tracker.percentile("p", "m", 50.0), Some(Duration::from_millis(10)));
+        assert_eq!(tracker.percentile("p", "m", 95.0), Some(Duration::from_millis(19)));
+        assert_eq!(tracker.percentile("p", "m", 100.0), Some(Duration::from_millis(20)));
+        assert_eq!(tracker.percentile("p", "other", 50.0), None);
+    }
+
+    #[tokio::test]
+    async fn test_slow_request_is_hedged() {
+        let provider = Arc::new(SlowFirstCall::default());
+        let workflow = Workflow::from_yaml(WORKFLOW).unwrap();
+        let executor = WorkflowExecutor::new(workflow, HashMap::new())
+            .unwrap()
+            .with_provider("slow", provider.clone());
+
+        let results = tokio::time::timeout(Duration::from_secs(5), executor.execute())
+            .await
+            .expect("hedged request should not wait for the slow one")
+            .unwrap();
+        assert_eq!(results["ask"].outputs["answer"], "call 1");
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_exhausted_budget_prevents_hedging() {
+        let yaml = WORKFLOW.replace("steps:", "retry_budget:\n  max_retries: 0\nsteps:");
+        let provider = Arc::new(SlowFirstCall::default());
+        let workflow = Workflow::from_yaml(&yaml).unwrap();
+        let executor = WorkflowExecutor::new(workflow, HashMap::new())
+            .unwrap()
+            .with_provider("slow", provider.clone());
+
+        assert!(tokio::time::timeout(Duration::from_millis(200), executor.execute()).await.is_err());
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,332 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! HTTP request steps.
+//!
+//! An `http` step calls a REST service mid-workflow:
+//!
+//! ```yaml
+//! - id: create_ticket
+//!   type: http
+//!   method: POST
+//!   url: "https://tickets.internal/api/{{ inputs.project }}/tickets"
+//!   headers:
+//!     Authorization: 'Bearer {{ secret "tickets/token" }}'
+//!   body:
+//!     title: "{{ steps.summarize.title }}"
+//!     body: "{{ steps.summarize.text }}"
+//!   extract:
+//!     ticket_id: "$.data.id"
+//!   success_status: [201]
+//!   retry:
+//!     max_attempts: 3
+//!   output: [response, status]
+//! ```
+//!
+//! Failures the service may recover from (no response, 429, 5xx) are
+//! retryable errors, so the step's retry policy and the run's retry budget
+//! apply as to any other step.
+
+use crate::error::{OrchestratorError, Result};
+use crate::executor::WorkflowExecutor;
+use crate::workflow::{HttpConfig, Step, StepConfig};
+use jsonpath_rust::JsonPath;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::Method;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use std::time::Duration;
+use tracing::debug;
+
+/// Default output names, in the order outputs are assigned.
+const DEFAULT_OUTPUTS: [&str; 3] = ["body", "status", "headers"];
+
+/// Longest response body quoted in an error message.
+const MAX_ERROR_BODY: usize = 500;
+
+/// Client shared by all HTTP steps, so connections are pooled.
+pub(crate) fn client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT.get_or_init(reqwest::Client::new)
+}
+
+/// Delay requested by a `retry-after` header given in seconds.
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let seconds: f64 = headers.get(RETRY_AFTER)?.to_str().ok()?.trim().parse().ok()?;
+    Duration::try_from_secs_f64(seconds).ok()
+}
+
+/// Values picked from `body` by `path`: the value itself when exactly one matches.
+fn extract(body: &Value, path: &str) -> Option<Value> {
+    let mut found = body.query(path).ok()?;
+    match found.len() {
+        0 => None,
+        1 => found.pop().cloned(),
+        _ => Some(Value::Array(found.into_iter().cloned().collect())),
+    }
+}
+
+/// Strings nested in a JSON value, e.g. the templates of a request body.
+pub(crate) fn collect_strings(value: &Value, strings: &mut Vec<String>) {
+    match value {
+        Value::String(s) => strings.push(s.clone()),
+        Value::Array(items) => items.iter().for_each(|item| collect_strings(item, strings)),
+        Value::Object(fields) => fields.values().for_each(|field| collect_strings(field, strings)),
+        _ => {}
+    }
+}
+
+impl WorkflowExecutor {
+    /// Executes an HTTP request step.
+    pub(crate) async fn execute_http_step(&self, step: &Step) -> Result<HashMap<String, Value>> {
+        let http_config = match &step.config {
+            StepConfig::Http(config) => config,
+            _ => {
+                return Err(OrchestratorError::InvalidStepConfig {
+                    step_id: step.id.clone(),
+                    reason: "Expected HTTP step config".to_string(),
+                })
+            }
+        };
+
+        let request = self.build_http_request(step, http_config).await?;
+        debug!(step_id = %step.id, method = %http_config.method, "Sending HTTP request");
+
+        let request_error = |status: Option<u16>, message: String, retry_after: Option<Duration>| {
+            OrchestratorError::HttpRequest {
+                step_id: step.id.clone(),
+                status,
+                message,
+                retry_after,
+            }
+        };
+        let response = request
+            .send()
+            .await
+            .map_err(|e| request_error(e.status().map(|s| s.as_u16()), e.to_string(), None))?;
+
+        let status = response.status().as_u16();
+        let headers: Map<String, Value> = response
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| Some((name.to_string(), Value::from(value.to_str().ok()?))))
+            .collect();
+        let delay = retry_after(response.headers());
+        let text = response
+            .text()
+            .await
+            .map_err(|e| request_error(Some(status), e.to_string(), None))?;
+
+        let succeeded = if http_config.success_status.is_empty() {
+            (200..300).contains(&status)
+        } else {
+            http_config.success_status.contains(&status)
+        };
+        if !succeeded {
+            let excerpt: String = text.chars().take(MAX_ERROR_BODY).collect();
+            return Err(request_error(Some(status), format!("status {}: {}", status, excerpt), delay));
+        }
+
+        let body = serde_json::from_str(&text).unwrap_or(Value::String(text));
+        let mut outputs = HashMap::new();
+        for (output, path) in &http_config.extract {
+            outputs.insert(output.clone(), extract(&body, path).unwrap_or(Value::Null));
+        }
+
+        let values = [body, Value::from(status), Value::Object(headers)];
+        let names: Vec<&str> = if step.output.is_empty() {
+            DEFAULT_OUTPUTS.to_vec()
+        } else {
+            step.output.iter().map(String::as_str).collect()
+        };
+        outputs.extend(names.into_iter().map(String::from).zip(values));
+        Ok(outputs)
+    }
+
+    /// Renders the URL, headers, and body of an HTTP step into a request.
+    async fn build_http_request(&self, step: &Step, config: &HttpConfig) -> Result<reqwest::RequestBuilder> {
+        let invalid = |reason: String| OrchestratorError::InvalidStepConfig {
+            step_id: step.id.clone(),
+            reason,
+        };
+        let method = Method::from_bytes(config.method.to_uppercase().as_bytes())
+            .map_err(|_| invalid(format!("Unsupported HTTP method '{}'", config.method)))?;
+        let url = self.render_http_template(&config.url).await?;
+        let url = reqwest::Url::parse(&url).map_err(|e| invalid(format!("Invalid URL '{}': {}", url, e)))?;
+        let mut request = client().request(method, url);
+
+        // Secret references are resolved after rendering, so secret values are never parsed as templates
+        for (name, value) in &config.headers {
+            let value = match value {
+                Value::String(template) => self.render_http_template(template).await?,
+                other => {
+                    let mut value = other.clone();
+                    self.resolve_http_secret_refs(&mut value).await?;
+                    match value {
+                        Value::String(secret) => secret,
+                        other => return Err(invalid(format!("Header '{}' must be a string, not {}", name, other))),
+                    }
+                }
+            };
+            request = request.header(name.as_str(), value);
+        }
+
+        if let Some(body) = &config.body {
+            let mut body = self.render_json(body.clone()).await?;
+            self.resolve_http_secret_refs(&mut body).await?;
+            request = request.json(&body);
+        } else if let Some(form) = &config.form {
+            let mut fields = HashMap::new();
+            for (name, template) in form {
+                fields.insert(name.as_str(), self.render_http_template(template).await?);
+            }
+            request = request.form(&fields);
+        }
+
+        Ok(request)
+    }
+
+    /// Renders a template after fetching the secrets it references.
+    async fn render_http_template(&self, template: &str) -> Result<String> {
+        self.resolve_template_secrets(template).await?;
+        self.context.render_template(template)
+    }
+
+    /// Renders every string in a JSON value as a template.
+    pub(crate) async fn render_json(&self, mut body: Value) -> Result<Value> {
+        let mut templates = Vec::new();
+        collect_strings(&body, &mut templates);
+        for template in templates {
+            self.resolve_template_secrets(&template).await?;
+        }
+        self.render_strings(&mut body)?;
+        Ok(body)
+    }
+
+    fn render_strings(&self, value: &mut Value) -> Result<()> {
+        match value {
+            Value::String(template) => *template = self.context.render_template(template)?,
+            Value::Array(items) => items.iter_mut().try_for_each(|item| self.render_strings(item))?,
+            Value::Object(fields) => fields.values_mut().try_for_each(|field| self.render_strings(field))?,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Replaces `{ secretRef: "key" }` objects with their secret values.
+    #[cfg_attr(not(feature = "secrets"), allow(unused_variables))]
+    async fn resolve_http_secret_refs(&self, value: &mut Value) -> Result<()> {
+        #[cfg(feature = "secrets")]
+        self.resolve_secret_refs(value).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workflow::Workflow;
+    use mockito::Matcher;
+
+    fn executor(yaml: &str) -> WorkflowExecutor {
+        let workflow = Workflow::from_yaml(yaml).unwrap();
+        let inputs = HashMap::from([("project".to_string(), Value::from("core"))]);
+        WorkflowExecutor::new(workflow, inputs).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_http_post_with_extract() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/core/tickets")
+            .match_header("x-project", "core")
+            .match_body(Matcher::Json(serde_json::json!({"title": "Ticket for core", "labels": ["core"]})))
+            .with_status(201)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"data": {"id": 42, "tags": [{"name": "a"}, {"name": "b"}]}}"#)
+            .create_async()
+            .await;
+
+        let yaml = format!(
+            r#"
+name: http
+steps:
+  - id: create
+    type: http
+    method: post
+    url: "{}/{{{{ inputs.project }}}}/tickets"
+    headers:
+      X-Project: "{{{{ inputs.project }}}}"
+    body:
+      title: "Ticket for {{{{ inputs.project }}}}"
+      labels: ["{{{{ inputs.project }}}}"]
+    extract:
+      ticket_id: "$.data.id"
+      tags: "$.data.tags[*].name"
+      missing: "$.data.owner"
+    success_status: [201]
+    output: [response, code]
+"#,
+            server.url()
+        );
+        let results = executor(&yaml).execute().await.unwrap();
+        let outputs = &results["create"].outputs;
+        assert_eq!(outputs["code"], 201);
+        assert_eq!(outputs["response"]["data"]["id"], 42);
+        assert_eq!(outputs["ticket_id"], 42);
+        assert_eq!(outputs["tags"], serde_json::json!(["a", "b"]));
+        assert_eq!(outputs["missing"], Value::Null);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_http_status_errors() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/busy")
+            .with_status(503)
+            .with_header("retry-after", "2")
+            .with_body("try later")
+            .create_async()
+            .await;
+        server.mock("GET", "/missing").with_status(404).create_async().await;
+
+        let yaml = format!(
+            r#"
+name: http
+steps:
+  - id: busy
+    type: http
+    url: "{url}/busy"
+  - id: missing
+    type: http
+    url: "{url}/missing"
+"#,
+            url = server.url()
+        );
+        let executor = executor(&yaml);
+
+        let err = executor.execute_http_step(&executor.workflow.steps[0]).await.unwrap_err();
+        assert!(err.is_retryable());
+        assert_eq!(err.http_status(), Some(503));
+        assert_eq!(err.retry_after(), Some(Duration::from_secs(2)));
+        assert!(err.to_string().contains("try later"), "{}", err);
+
+        let err = executor.execute_http_step(&executor.workflow.steps[1]).await.unwrap_err();
+        assert!(!err.is_retryable());
+        assert_eq!(err.http_status(), Some(404));
+    }
+
+    #[test]
+    fn test_http_validation() {
+        let invalid = |config: &str| {
+            let yaml = format!("name: http\nsteps:\n  - id: call\n    type: http\n    url: http://localhost\n{}", config);
+            Workflow::from_yaml(&yaml).unwrap().validate().is_err()
+        };
+        assert!(!invalid("    method: PATCH\n"));
+        assert!(invalid("    method: FETCH\n"));
+        assert!(invalid("    extract:\n      id: \"data.id[\"\n"));
+        assert!(invalid("    body: {a: 1}\n    form: {b: \"2\"}\n"));
+    }
+}
@@ -0,0 +1,268 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Images sent with the prompts of multimodal LLM steps.
+//!
+//! An LLM step lists images by file path, URL, or base64 data from the
+//! context, for OCR-style extraction or visual question answering:
+//!
+//! ```yaml
+//! - id: read_receipt
+//!   type: llm
+//!   provider: anthropic
+//!   model: claude-3-5-sonnet-20241022
+//!   prompt: "List the items and prices on this receipt as JSON."
+//!   images:
+//!     - path: "scans/{{ inputs.receipt }}.jpg"
+//!     - url: "https://example.com/logo.png"
+//!     - data: "{{ steps.capture.image }}"
+//!       media_type: image/png
+//! ```
+//!
+//! Files and data are sent inline, after checking their size and that their
+//! bytes are a PNG, JPEG, GIF, or WebP image; providers download URLs
+//! themselves.
+
+use crate::error::{OrchestratorError, Result};
+use crate::executor::WorkflowExecutor;
+use crate::providers::ImageInput;
+use crate::workflow::ImageAttachment;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde_json::Value;
+
+/// Largest image sent inline, the lowest limit of the supported providers.
+pub const MAX_IMAGE_BYTES: usize = 5 * 1024 * 1024;
+
+/// Media types of the images providers accept.
+pub const IMAGE_MEDIA_TYPES: [&str; 4] = ["image/png", "image/jpeg", "image/gif", "image/webp"];
+
+/// Media type of image bytes, if they are of a supported format.
+pub fn sniff_media_type(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("image/png")
+    } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("image/jpeg")
+    } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        Some("image/gif")
+    } else if bytes.len() >= 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        Some("image/webp")
+    } else {
+        None
+    }
+}
+
+impl WorkflowExecutor {
+    /// Renders and checks the images of an LLM step.
+    pub(crate) async fn resolve_images(&self, step_id: &str, images: &[ImageAttachment]) -> Result<Vec<ImageInput>> {
+        let mut resolved = Vec::with_capacity(images.len());
+        for (index, image) in images.iter().enumerate() {
+            let invalid = |reason: String| OrchestratorError::InvalidStepConfig {
+                step_id: step_id.to_string(),
+                reason: format!("Image {}: {}", index + 1, reason),
+            };
+            self.resolve_template_secrets(image.source()).await?;
+            let input = match image {
+                ImageAttachment::Path { path, media_type } => {
+                    let path = self.context.render_template(path)?;
+                    let size = tokio::fs::metadata(&path)
+                        .await
+                        .map_err(|e| invalid(format!("Failed to read '{}': {}", path, e)))?
+                        .len();
+                    if size > MAX_IMAGE_BYTES as u64 {
+                        return Err(invalid(format!("'{}' is larger than {} bytes", path, MAX_IMAGE_BYTES)));
+                    }
+                    let bytes = tokio::fs::read(&path)
+                        .await
+                        .map_err(|e| invalid(format!("Failed to read '{}': {}", path, e)))?;
+                    inline_image(&bytes, media_type.as_deref()).map_err(invalid)?
+                }
+                ImageAttachment::Url { url } => {
+                    let url = self.context.render_template(url)?;
+                    if url.starts_with("data:") {
+                        data_url_image(&url).map_err(invalid)?
+                    } else {
+                        let parsed = reqwest::Url::parse(&url).map_err(|e| invalid(format!("Invalid URL '{}': {}", url, e)))?;
+                        if !matches!(parsed.scheme(), "http" | "https") {
+                            return Err(invalid(format!("Unsupported URL scheme '{}'", parsed.scheme())));
+                        }
+                        ImageInput::Url { url }
+                    }
+                }
+                ImageAttachment::Data { data, media_type } => match self.context.render_value(data)? {
+                    Value::String(data) if data.starts_with("data:") => data_url_image(&data).map_err(invalid)?,
+                    Value::String(data) => {
+                        let bytes = decode_base64(&data).map_err(invalid)?;
+                        inline_image(&bytes, media_type.as_deref()).map_err(invalid)?
+                    }
+                    other => return Err(invalid(format!("Data must be a base64 string, got: {}", other))),
+                },
+            };
+            resolved.push(input);
+        }
+        Ok(resolved)
+    }
+}
+
+/// Checks image bytes and encodes them for sending.
+///
+/// A declared media type must be supported and agree with the bytes.
+fn inline_image(bytes: &[u8], media_type: Option<&str>) -> std::result::Result<ImageInput, String> {
+    if bytes.len() > MAX_IMAGE_BYTES {
+        return Err(format!("Image is larger than {} bytes", MAX_IMAGE_BYTES));
+    }
+    let sniffed = sniff_media_type(bytes);
+    let media_type = match media_type.map(|m| m.trim().to_ascii_lowercase()) {
+        Some(declared) if !IMAGE_MEDIA_TYPES.contains(&declared.as_str()) => {
+            return Err(format!(
+                "Unsupported media type '{}', expected one of: {}",
+                declared,
+                IMAGE_MEDIA_TYPES.join(", ")
+            ))
+        }
+        Some(declared) => match sniffed {
+            Some(sniffed) if sniffed != declared => {
+                return Err(format!("Declared as {} but the bytes are {}", declared, sniffed))
+            }
+            _ => declared,
+        },
+        None => sniffed
+            .ok_or_else(|| "Not a PNG, JPEG, GIF, or WebP image; set media_type if it is one".to_string())?
+            .to_string(),
+    };
+    Ok(ImageInput::Base64 {
+        media_type,
+        data: STANDARD.encode(bytes),
+    })
+}
+
+/// Image of a `data:<media type>;base64,<data>` URL.
+fn data_url_image(url: &str) -> std::result::Result<ImageInput, String> {
+    let (header, data) = url
+        .strip_prefix("data:")
+        .and_then(|rest| rest.split_once(','))
+        .ok_or_else(|| "Malformed data URL".to_string())?;
+    let media_type = header
+        .strip_suffix(";base64")
+        .ok_or_else(|| "Data URLs must be base64-encoded".to_string())?;
+    let bytes = decode_base64(data)?;
+    inline_image(&bytes, (!media_type.is_empty()).then_some(media_type))
+}
+
+/// Decodes base64, ignoring whitespace such as line breaks.
+fn decode_base64(data: &str) -> std::result::Result<Vec<u8>, String> {
+    let data: String = data.chars().filter(|c| !c.is_ascii_whitespace()).collect();
+    if data.len() / 4 * 3 > MAX_IMAGE_BYTES + 3 {
+        return Err(format!("Image is larger than {} bytes", MAX_IMAGE_BYTES));
+    }
+    STANDARD.decode(data).map_err(|e| format!("Invalid base64 data: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::{CompletionRequest, CompletionResponse, LLMProvider, ProviderError};
+    use crate::workflow::Workflow;
+    use async_trait::async_trait;
+    use parking_lot::Mutex;
+    use serde_json::json;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+
+    #[test]
+    fn test_inline_image_checks_format() {
+        assert_eq!(sniff_media_type(PNG), Some("image/png"));
+        assert_eq!(sniff_media_type(b"RIFF\0\0\0\0WEBPVP8 "), Some("image/webp"));
+        assert_eq!(sniff_media_type(b"%PDF-1.7"), None);
+
+        let image = inline_image(PNG, None).unwrap();
+        assert_eq!(image, ImageInput::Base64 { media_type: "image/png".to_string(), data: STANDARD.encode(PNG) });
+        assert!(inline_image(PNG, Some("image/jpeg")).unwrap_err().contains("image/png"));
+        assert!(inline_image(PNG, Some("image/tiff")).unwrap_err().contains("Unsupported"));
+        assert!(inline_image(b"%PDF-1.7", None).is_err());
+        assert!(inline_image(&vec![0; MAX_IMAGE_BYTES + 1], Some("image/png")).is_err());
+        assert!(data_url_image(&format!("data:image/png;base64,{}", STANDARD.encode(PNG))).is_ok());
+        assert!(data_url_image("data:image/png,raw").is_err());
+    }
+
+    /// Records the images of each request.
+    #[derive(Default)]
+    struct Vision {
+        images: Mutex<Vec<ImageInput>>,
+    }
+
+    #[async_trait]
+    impl LLMProvider for Vision {
+        async fn complete(&self, request: CompletionRequest) -> std::result::Result<CompletionResponse, ProviderError> {
+            self.images.lock().extend(request.images);
+            Ok(CompletionResponse {
+                text: "A receipt.".to_string(),
+                model: request.model,
+                tokens_used: None,
+                usage: None,
+                tool_calls: Vec::new(),
+                metadata: HashMap::new(),
+            })
+        }
+
+        fn name(&self) -> &str {
+            "vision"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_llm_step_sends_images() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("receipt.png"), PNG).unwrap();
+        let workflow = Workflow::from_yaml(
+            r#"
+name: "vision"
+steps:
+  - id: "describe"
+    type: "llm"
+    provider: "vision"
+    model: "m"
+    prompt: "What is this?"
+    images:
+      - path: "{{ inputs.dir }}/receipt.png"
+      - url: "https://example.com/logo.jpg"
+      - data: "{{ inputs.capture }}"
+    output: ["description"]
+"#,
+        )
+        .unwrap();
+        let provider = Arc::new(Vision::default());
+        let inputs = HashMap::from([
+            ("dir".to_string(), json!(dir.path().display().to_string())),
+            ("capture".to_string(), json!(STANDARD.encode(PNG))),
+        ]);
+        WorkflowExecutor::new(workflow.clone(), inputs)
+            .unwrap()
+            .with_provider("vision", provider.clone())
+            .execute()
+            .await
+            .unwrap();
+
+        {
+            let images = provider.images.lock();
+            assert_eq!(images.len(), 3);
+            assert_eq!(images[0], images[2]);
+            assert_eq!(images[1], ImageInput::Url { url: "https://example.com/logo.jpg".to_string() });
+        }
+
+        // Data that is not an image fails the step before the provider is called
+        let inputs = HashMap::from([
+            ("dir".to_string(), json!(dir.path().display().to_string())),
+            ("capture".to_string(), json!(STANDARD.encode("plain text"))),
+        ]);
+        let result = WorkflowExecutor::new(workflow, inputs)
+            .unwrap()
+            .with_provider("vision", provider.clone())
+            .execute()
+            .await;
+        assert!(result.is_err());
+        assert_eq!(provider.images.lock().len(), 3);
+    }
+}
@@ -0,0 +1,411 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Splitting workflow files with includes.
+//!
+//! A workflow can pull shared definitions from other files in two ways:
+//!
+//! - `includes` lists files that are merged into the workflow, in order. Their
+//!   steps come before the workflow's own; everything else is deep-merged, with
+//!   mappings merged key by key and the including file winning. Included files
+//!   hold any part of a workflow, e.g. `templates`, `profiles` or `metadata`.
+//! - A `$include` mapping is replaced by the contents of a file anywhere in the
+//!   workflow. YAML and JSON files are parsed, and a list included into a list
+//!   is spliced into it; other files, e.g. prompt blocks, are included as text.
+//!
+//! ```yaml
+//! includes: [shared/templates.yaml, shared/profiles.yaml]
+//!
+//! steps:
+//!   - $include: shared/fetch-steps.yaml
+//!   - id: answer
+//!     type: llm
+//!     provider: openai
+//!     model: gpt-4
+//!     prompt:
+//!       $include: prompts/answer.md
+//! ```
+//!
+//! Paths are relative to the including file; included files can include
+//! further files, and cycles are rejected. An [`IncludeResolver`] reads the
+//! files, which lets them come from somewhere other than the file system.
+
+use crate::diagnostics::{Diagnostic, DiagnosticCode};
+use crate::error::OrchestratorError;
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
+
+/// Workflow field listing the files merged into it.
+pub(crate) const INCLUDES_FIELD: &str = "includes";
+
+/// Key of a mapping replaced by the contents of a file.
+const INCLUDE_KEY: &str = "$include";
+
+/// Reads the files included by workflows.
+pub trait IncludeResolver: Send + Sync {
+    /// Location of `path` as included from the file at `parent`, or from the
+    /// workflow being loaded if `parent` is `None`.
+    ///
+    /// Locations identify files: including a location twice within one chain
+    /// of includes is a cycle.
+    fn locate(&self, path: &str, parent: Option<&Path>) -> std::io::Result<PathBuf>;
+
+    /// Read the file at a location returned by [`locate`](Self::locate).
+    fn read(&self, location: &Path) -> std::io::Result<String>;
+
+    /// Locations of the files in the directory at a location returned by
+    /// [`locate`](Self::locate), sorted; used for `partials_dir`.
+    fn list(&self, location: &Path) -> std::io::Result<Vec<PathBuf>> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            format!("cannot list {}", location.display()),
+        ))
+    }
+}
+
+/// Reads included files from the file system.
+#[derive(Debug, Clone)]
+pub struct FileResolver {
+    base: PathBuf,
+}
+
+impl FileResolver {
+    /// Create a resolver reading the workflow's includes relative to `base`.
+    pub fn new(base: impl Into<PathBuf>) -> Self {
+        Self { base: base.into() }
+    }
+
+    /// Create a resolver reading the workflow's includes relative to the working directory.
+    pub fn current_dir() -> Self {
+        Self::new(".")
+    }
+
+    /// Create a resolver reading the includes of the workflow file at `path` relative to it.
+    pub fn for_file(path: impl AsRef<Path>) -> Self {
+        Self::new(path.as_ref().parent().unwrap_or(Path::new(".")))
+    }
+}
+
+impl IncludeResolver for FileResolver {
+    fn locate(&self, path: &str, parent: Option<&Path>) -> std::io::Result<PathBuf> {
+        let directory = parent.and_then(Path::parent).unwrap_or(&self.base);
+        directory.join(path).canonicalize()
+    }
+
+    fn read(&self, location: &Path) -> std::io::Result<String> {
+        std::fs::read_to_string(location)
+    }
+
+    fn list(&self, location: &Path) -> std::io::Result<Vec<PathBuf>> {
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(location)? {
+            let entry = entry?;
+            if entry.file_type()?.is_file() {
+                files.push(entry.path());
+            }
+        }
+        files.sort();
+        Ok(files)
+    }
+}
+
+/// Whether the workflow YAML in `source` includes other files, or reads partials from a directory.
+pub fn has_includes(source: &str) -> bool {
+    serde_yaml::from_str::<serde_yaml::Value>(source).is_ok_and(|document| {
+        document_has_includes(&document) || document.get(crate::partials::PARTIALS_DIR_FIELD).is_some()
+    })
+}
+
+/// Whether a workflow document includes other files.
+pub(crate) fn document_has_includes(document: &serde_yaml::Value) -> bool {
+    fn has_include_node(value: &serde_yaml::Value) -> bool {
+        match value {
+            serde_yaml::Value::Mapping(fields) => {
+                fields.contains_key(INCLUDE_KEY) || fields.values().any(has_include_node)
+            }
+            serde_yaml::Value::Sequence(items) => items.iter().any(has_include_node),
+            _ => false,
+        }
+    }
+    document.get(INCLUDES_FIELD).is_some() || has_include_node(document)
+}
+
+/// Inlines the files included by the workflow YAML in `source`.
+///
+/// Returns the workflow as a single YAML document, e.g. to store it where the
+/// included files are not available. The files of its `partials_dir` are
+/// inlined into `partials` too (see [`crate::partials`]). Step templates are
+/// left as they are.
+pub fn inline(source: &str, resolver: &dyn IncludeResolver) -> crate::error::Result<String> {
+    let document: serde_yaml::Value =
+        serde_yaml::from_str(source).map_err(|e| OrchestratorError::parse(e.to_string()))?;
+    let resolved = resolve(document, resolver).map_err(|diagnostic| OrchestratorError::validation(diagnostic.message))?;
+    let mut resolved = serde_yaml::to_value(resolved).map_err(|e| OrchestratorError::serialization(e.to_string()))?;
+    crate::partials::read_dir(&mut resolved, resolver).map_err(|diagnostic| OrchestratorError::validation(diagnostic.message))?;
+    serde_yaml::to_string(&resolved).map_err(|e| OrchestratorError::serialization(e.to_string()))
+}
+
+/// Resolves the includes of a workflow document.
+pub(crate) fn resolve(document: serde_yaml::Value, resolver: &dyn IncludeResolver) -> Result<Value, Box<Diagnostic>> {
+    let document = serde_json::to_value(document)
+        .map_err(|e| Diagnostic::new(DiagnosticCode::Parse, e.to_string()))?;
+    Includer {
+        resolver,
+        chain: Vec::new(),
+        site: String::new(),
+    }
+    .document(document, None)
+}
+
+struct Includer<'a> {
+    resolver: &'a dyn IncludeResolver,
+    // Files being included, outermost first
+    chain: Vec<PathBuf>,
+    // Path of the outermost include in the workflow, where errors are reported
+    site: String,
+}
+
+impl Includer<'_> {
+    /// Resolves the includes of a document read from `origin`.
+    fn document(&mut self, mut document: Value, origin: Option<&Path>) -> Result<Value, Box<Diagnostic>> {
+        let includes = document.as_object_mut().and_then(|fields| fields.remove(INCLUDES_FIELD));
+        self.nodes(&mut document, origin, "")?;
+        let Some(includes) = includes else {
+            return Ok(document);
+        };
+
+        let Value::Array(paths) = includes else {
+            return Err(self.error(INCLUDES_FIELD, "`includes` must be a list of paths".to_string()));
+        };
+        let mut merged = Value::Object(Map::new());
+        for (index, path) in paths.iter().enumerate() {
+            let site = format!("{}[{}]", INCLUDES_FIELD, index);
+            let Some(path) = path.as_str() else {
+                return Err(self.error(&site, "`includes` must be a list of paths".to_string()));
+            };
+            let included = self.include(path, origin, &site)?;
+            if !included.is_object() {
+                return Err(self.error(&site, format!("Included file '{}' must be a mapping", path)));
+            }
+            merge(&mut merged, included);
+        }
+        merge(&mut merged, document);
+        Ok(merged)
+    }
+
+    /// Replaces the `$include` mappings within `value`.
+    fn nodes(&mut self, value: &mut Value, origin: Option<&Path>, path: &str) -> Result<(), Box<Diagnostic>> {
+        match value {
+            Value::Object(fields) => {
+                if let Some(include) = include_path(fields) {
+                    let include = include.to_string();
+                    *value = self.include(&include, origin, path)?;
+                    return Ok(());
+                }
+                for (key, field) in fields.iter_mut() {
+                    let field_path = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+                    self.nodes(field, origin, &field_path)?;
+                }
+            }
+            Value::Array(items) => {
+                let mut spliced = Vec::with_capacity(items.len());
+                for (index, mut item) in std::mem::take(items).into_iter().enumerate() {
+                    let spliceable = item.as_object().is_some_and(|fields| include_path(fields).is_some());
+                    self.nodes(&mut item, origin, &format!("{}[{}]", path, index))?;
+                    match item {
+                        Value::Array(included) if spliceable => spliced.extend(included),
+                        item => spliced.push(item),
+                    }
+                }
+                *items = spliced;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Reads and resolves the file at `path`, included at `site` from `origin`.
+    fn include(&mut self, path: &str, origin: Option<&Path>, site: &str) -> Result<Value, Box<Diagnostic>> {
+        if self.chain.is_empty() {
+            self.site = site.to_string();
+        }
+        let location = self
+            .resolver
+            .locate(path, origin)
+            .map_err(|e| self.error(site, format!("Cannot include '{}': {}", path, e)))?;
+        if self.chain.contains(&location) {
+            let cycle: Vec<String> = self
+                .chain
+                .iter()
+                .chain([&location])
+                .map(|location| location.display().to_string())
+                .collect();
+            return Err(self.error(site, format!("Include cycle: {}", cycle.join(" -> "))));
+        }
+        let content = self
+            .resolver
+            .read(&location)
+            .map_err(|e| self.error(site, format!("Cannot include '{}': {}", path, e)))?;
+
+        let data = matches!(
+            location.extension().and_then(|extension| extension.to_str()),
+            Some("yaml" | "yml" | "json")
+        );
+        if !data {
+            return Ok(Value::String(content));
+        }
+        let document: Value = serde_yaml::from_str(&content)
+            .map_err(|e| self.error(site, format!("Invalid included file '{}': {}", path, e)))?;
+
+        self.chain.push(location.clone());
+        let resolved = self.document(document, Some(&location));
+        self.chain.pop();
+        resolved
+    }
+
+    /// An include error at `site` in the workflow, or at the outermost include
+    /// for errors in included files.
+    fn error(&self, site: &str, message: String) -> Box<Diagnostic> {
+        let site = if self.chain.is_empty() { site } else { &self.site };
+        let diagnostic = Diagnostic::new(DiagnosticCode::InvalidInclude, message);
+        Box::new(if site.is_empty() { diagnostic } else { diagnostic.at(site) })
+    }
+}
+
+/// Path of a `$include` mapping.
+fn include_path(fields: &Map<String, Value>) -> Option<&str> {
+    match fields.get(INCLUDE_KEY) {
+        Some(Value::String(path)) if fields.len() == 1 => Some(path),
+        _ => None,
+    }
+}
+
+/// Merges an included document `overlay` onto `base`: steps are appended and
+/// everything else is deep-merged.
+fn merge(base: &mut Value, overlay: Value) {
+    let (Value::Object(base), Value::Object(overlay)) = (base, overlay) else {
+        return;
+    };
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Array(steps)), Value::Array(more)) if key == "steps" => steps.extend(more),
+            (Some(existing), value) => crate::workflow::deep_merge(existing, &value),
+            (None, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workflow::{StepConfig, Workflow};
+    use std::fs;
+
+    fn write(dir: &Path, path: &str, content: &str) {
+        let path = dir.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    const SHARED: &str = r#"
+metadata:
+  team: "search"
+  owner: "shared"
+templates:
+  answer:
+    params:
+      question: ~
+    steps:
+      - id: "reply"
+        type: "llm"
+        provider: "openai"
+        model: "gpt-4"
+        prompt:
+          $include: "../prompts/answer.md"
+        output: ["text"]
+steps:
+  - id: "fetch"
+    type: "transform"
+    function: "noop"
+    inputs: []
+"#;
+
+    #[test]
+    fn test_includes_compose_workflow() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "shared/base.yaml", SHARED);
+        write(dir.path(), "prompts/answer.md", "Answer {{ params.question }}");
+        write(
+            dir.path(),
+            "shared/steps.yaml",
+            "- id: \"clean\"\n  type: \"transform\"\n  depends_on: [\"fetch\"]\n  function: \"noop\"\n  inputs: []\n",
+        );
+        write(
+            dir.path(),
+            "flows/support.yaml",
+            r#"
+name: "support"
+includes: ["../shared/base.yaml"]
+metadata:
+  owner: "support"
+steps:
+  - $include: "../shared/steps.yaml"
+  - id: "faq"
+    use: "answer"
+    depends_on: ["clean"]
+    with:
+      question: "{{ inputs.question }}"
+"#,
+        );
+
+        let path = dir.path().join("flows/support.yaml");
+        let workflow = Workflow::from_file(&path).unwrap();
+        workflow.validate().unwrap();
+        let ids: Vec<&str> = workflow.steps.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec!["fetch", "clean", "faq_reply"]);
+        assert_eq!(workflow.metadata["team"], "search");
+        assert_eq!(workflow.metadata["owner"], "support");
+        match &workflow.steps[2].config {
+            StepConfig::Llm(config) => assert_eq!(config.prompt, "Answer {{ inputs.question }}"),
+            _ => panic!("faq_reply is not an LLM step"),
+        }
+
+        // The inlined workflow loads the same without its files
+        let inlined = inline(&fs::read_to_string(&path).unwrap(), &FileResolver::for_file(&path)).unwrap();
+        assert!(!has_includes(&inlined));
+        assert_eq!(inlined, inline(&inlined, &resolver_without_files()).unwrap());
+        let reloaded = Workflow::from_yaml_with(&inlined, &resolver_without_files()).unwrap();
+        assert_eq!(reloaded.steps.len(), 3);
+    }
+
+    fn resolver_without_files() -> FileResolver {
+        FileResolver::new("/nonexistent")
+    }
+
+    #[test]
+    fn test_include_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "a.yaml", "includes: [\"b.yaml\"]\n");
+        write(dir.path(), "b.yaml", "includes: [\"a.yaml\"]\n");
+        let resolver = FileResolver::new(dir.path());
+
+        let cyclic = "name: \"cyclic\"\nincludes: [\"a.yaml\"]\nsteps: []\n";
+        let diagnostics = crate::diagnostics::check_yaml_with(cyclic, &resolver).unwrap_err();
+        assert_eq!(diagnostics[0].code, DiagnosticCode::InvalidInclude);
+        assert!(diagnostics[0].message.starts_with("Include cycle: "), "{}", diagnostics[0].message);
+        assert_eq!(diagnostics[0].path.as_deref(), Some("includes[0]"));
+        assert_eq!(diagnostics[0].line, Some(2));
+
+        let missing = "name: \"missing\"\nsteps:\n  - $include: \"nope.yaml\"\n";
+        let diagnostics = crate::diagnostics::check_yaml_with(missing, &resolver).unwrap_err();
+        assert!(diagnostics[0].message.starts_with("Cannot include 'nope.yaml'"));
+        assert_eq!(diagnostics[0].path.as_deref(), Some("steps[0]"));
+
+        assert!(Workflow::from_yaml_with(missing, &resolver).is_err());
+        assert!(inline(missing, &resolver).is_err());
+        assert!(has_includes(missing));
+        assert!(!has_includes("name: \"plain\"\nsteps: []\n"));
+    }
+}
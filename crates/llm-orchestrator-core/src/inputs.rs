@@ -0,0 +1,426 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Workflow inputs read from files, standard input, and URLs.
+//!
+//! An [`InputSource`] names where input data comes from: `-` for standard
+//! input, an `http://` or `https://` URL, a glob pattern such as
+//! `docs/**/*.md`, or a file. [`InputLoader`] reads a source into a JSON value:
+//!
+//! - JSON, YAML and plain text are read as a single value.
+//! - CSV is read as a list of objects keyed by the header row, with every
+//!   field a string.
+//! - JSON Lines is read as a list of the values on each line.
+//! - A glob is read as a list of the values of the matching files, sorted by
+//!   path.
+//!
+//! The format of a file or URL is inferred from its extension (and a URL's
+//! content type) unless one is set with [`InputLoader::with_format`]; anything
+//! unrecognized is read as JSON. Every source is limited in size, so a wrong
+//! path cannot pull gigabytes into a run.
+
+use crate::error::{OrchestratorError, Result};
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::io::AsyncReadExt;
+
+/// Default limit of the bytes read from one source.
+pub const DEFAULT_MAX_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Format of input data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputFormat {
+    /// A JSON value.
+    Json,
+    /// A YAML value.
+    Yaml,
+    /// One JSON value per line, read as a list.
+    Jsonl,
+    /// Comma-separated values with a header row, read as a list of objects.
+    Csv,
+    /// Plain text, read as a string.
+    Text,
+}
+
+impl InputFormat {
+    /// Format of a file, inferred from its extension.
+    pub fn from_path(path: &str) -> Option<Self> {
+        let (_, extension) = path.rsplit_once('.')?;
+        match extension.to_ascii_lowercase().as_str() {
+            "json" => Some(Self::Json),
+            "yaml" | "yml" => Some(Self::Yaml),
+            "jsonl" | "ndjson" => Some(Self::Jsonl),
+            "csv" => Some(Self::Csv),
+            "txt" | "md" | "text" => Some(Self::Text),
+            _ => None,
+        }
+    }
+
+    /// Format of an HTTP response, inferred from its content type.
+    fn from_content_type(content_type: &str) -> Option<Self> {
+        let mime = content_type.split(';').next().unwrap_or_default().trim();
+        match mime {
+            "application/json" => Some(Self::Json),
+            "application/yaml" | "application/x-yaml" | "text/yaml" => Some(Self::Yaml),
+            "application/x-ndjson" | "application/jsonl" => Some(Self::Jsonl),
+            "text/csv" => Some(Self::Csv),
+            _ if mime.starts_with("text/") => Some(Self::Text),
+            _ => None,
+        }
+    }
+
+    /// Parse `content` in this format.
+    pub fn decode(self, content: &str) -> Result<Value> {
+        match self {
+            Self::Json => Ok(serde_json::from_str(content)?),
+            Self::Yaml => serde_yaml::from_str(content)
+                .map_err(|e| OrchestratorError::serialization(format!("Invalid YAML input: {}", e))),
+            Self::Jsonl => content
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| !line.trim().is_empty())
+                .map(|(index, line)| {
+                    serde_json::from_str::<Value>(line).map_err(|e| {
+                        OrchestratorError::serialization(format!("Invalid JSON on line {}: {}", index + 1, e))
+                    })
+                })
+                .collect::<Result<Vec<_>>>()
+                .map(Value::Array),
+            Self::Csv => parse_csv(content),
+            Self::Text => Ok(Value::String(content.to_string())),
+        }
+    }
+}
+
+impl std::fmt::Display for InputFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Json => "json",
+            Self::Yaml => "yaml",
+            Self::Jsonl => "jsonl",
+            Self::Csv => "csv",
+            Self::Text => "text",
+        })
+    }
+}
+
+impl std::str::FromStr for InputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(Self::Json),
+            "yaml" | "yml" => Ok(Self::Yaml),
+            "jsonl" | "ndjson" => Ok(Self::Jsonl),
+            "csv" => Ok(Self::Csv),
+            "text" => Ok(Self::Text),
+            other => Err(format!(
+                "unknown input format '{}' (expected json, yaml, jsonl, csv or text)",
+                other
+            )),
+        }
+    }
+}
+
+/// Where input data comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    /// Standard input.
+    Stdin,
+    /// An `http://` or `https://` URL.
+    Url(String),
+    /// Files matching a glob pattern.
+    Glob(String),
+    /// A file.
+    File(PathBuf),
+}
+
+impl From<&str> for InputSource {
+    /// `-` is standard input; a pattern with `*`, `?` or `[` that is not an
+    /// existing file is a glob.
+    fn from(source: &str) -> Self {
+        if source == "-" {
+            Self::Stdin
+        } else if source.starts_with("http://") || source.starts_with("https://") {
+            Self::Url(source.to_string())
+        } else if source.contains(['*', '?', '[']) && !Path::new(source).exists() {
+            Self::Glob(source.to_string())
+        } else {
+            Self::File(PathBuf::from(source))
+        }
+    }
+}
+
+impl std::fmt::Display for InputSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Stdin => f.write_str("standard input"),
+            Self::Url(url) => f.write_str(url),
+            Self::Glob(pattern) => f.write_str(pattern),
+            Self::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// Reads input data from its sources.
+#[derive(Debug, Clone)]
+pub struct InputLoader {
+    format: Option<InputFormat>,
+    max_bytes: u64,
+    client: reqwest::Client,
+}
+
+impl Default for InputLoader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InputLoader {
+    /// Create a loader inferring formats and reading up to [`DEFAULT_MAX_BYTES`] per source.
+    pub fn new() -> Self {
+        Self {
+            format: None,
+            max_bytes: DEFAULT_MAX_BYTES,
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(30))
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Read every source in `format` instead of inferring it.
+    pub fn with_format(mut self, format: InputFormat) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    /// Limit the bytes read from one source; a glob's files count together.
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// Read the data of `source`.
+    pub async fn load(&self, source: &InputSource) -> Result<Value> {
+        match source {
+            InputSource::Stdin => {
+                let mut content = String::new();
+                tokio::io::stdin()
+                    .take(self.max_bytes + 1)
+                    .read_to_string(&mut content)
+                    .await?;
+                self.check_size(source, content.len() as u64)?;
+                self.format.unwrap_or(InputFormat::Json).decode(&content)
+            }
+            InputSource::Url(url) => self.fetch(url).await,
+            InputSource::File(path) => {
+                let content = self.read_file(path, self.max_bytes).await?;
+                self.file_format(path).decode(&content).map_err(|e| in_source(source, e))
+            }
+            InputSource::Glob(pattern) => {
+                let paths = expand_glob(pattern)?;
+                if paths.is_empty() {
+                    return Err(OrchestratorError::other(format!("No files match '{}'", pattern)));
+                }
+                let mut remaining = self.max_bytes;
+                let mut values = Vec::with_capacity(paths.len());
+                for path in paths {
+                    let content = self
+                        .read_file(&path, remaining)
+                        .await
+                        .map_err(|e| in_source(source, e))?;
+                    remaining -= content.len() as u64;
+                    let value = self.file_format(&path).decode(&content);
+                    values.push(value.map_err(|e| in_source(&InputSource::File(path), e))?);
+                }
+                Ok(Value::Array(values))
+            }
+        }
+    }
+
+    fn file_format(&self, path: &Path) -> InputFormat {
+        self.format
+            .or_else(|| InputFormat::from_path(&path.to_string_lossy()))
+            .unwrap_or(InputFormat::Json)
+    }
+
+    /// Reads a file of at most `max_bytes`.
+    ///
+    /// The limit is enforced while reading, so it also holds for pipes and
+    /// files that grow.
+    async fn read_file(&self, path: &Path, max_bytes: u64) -> Result<String> {
+        let source = InputSource::File(path.to_path_buf());
+        let failed = |e: std::io::Error| OrchestratorError::other(format!("Failed to read input {}: {}", source, e));
+        let file = tokio::fs::File::open(path).await.map_err(failed)?;
+        let mut content = Vec::new();
+        file.take(max_bytes + 1).read_to_end(&mut content).await.map_err(failed)?;
+        if content.len() as u64 > max_bytes {
+            return Err(too_large(&source, self.max_bytes));
+        }
+        String::from_utf8(content).map_err(|_| OrchestratorError::other(format!("Input {} is not UTF-8 text", source)))
+    }
+
+    async fn fetch(&self, url: &str) -> Result<Value> {
+        let source = InputSource::Url(url.to_string());
+        let failed = |e: reqwest::Error| OrchestratorError::other(format!("Failed to fetch input {}: {}", url, e));
+        let mut response = self.client.get(url).send().await.map_err(failed)?.error_for_status().map_err(failed)?;
+        if response.content_length().is_some_and(|length| length > self.max_bytes) {
+            return Err(too_large(&source, self.max_bytes));
+        }
+
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await.map_err(failed)? {
+            body.extend_from_slice(&chunk);
+            self.check_size(&source, body.len() as u64)?;
+        }
+        let content = String::from_utf8(body)
+            .map_err(|_| OrchestratorError::other(format!("Input {} is not UTF-8 text", url)))?;
+
+        let path = url.split(['?', '#']).next().unwrap_or(url);
+        let content_type = response.headers().get(reqwest::header::CONTENT_TYPE).and_then(|v| v.to_str().ok());
+        let format = self
+            .format
+            .or_else(|| InputFormat::from_path(path.rsplit('/').next().unwrap_or_default()))
+            .or_else(|| content_type.and_then(InputFormat::from_content_type))
+            .unwrap_or(InputFormat::Json);
+        format.decode(&content).map_err(|e| in_source(&source, e))
+    }
+
+    fn check_size(&self, source: &InputSource, size: u64) -> Result<()> {
+        if size > self.max_bytes {
+            return Err(too_large(source, self.max_bytes));
+        }
+        Ok(())
+    }
+}
+
+fn too_large(source: &InputSource, max_bytes: u64) -> OrchestratorError {
+    OrchestratorError::other(format!("Input {} exceeds the limit of {} bytes", source, max_bytes))
+}
+
+/// Names the source of a parse error.
+fn in_source(source: &InputSource, error: OrchestratorError) -> OrchestratorError {
+    match error {
+        OrchestratorError::SerializationError(message) => {
+            OrchestratorError::serialization(format!("Invalid input {}: {}", source, message))
+        }
+        error => error,
+    }
+}
+
+/// Parses CSV with a header row into a list of objects, skipping blank lines.
+fn parse_csv(content: &str) -> Result<Value> {
+    let invalid = |e: csv::Error| OrchestratorError::serialization(format!("Invalid CSV: {}", e));
+    let mut reader = csv::Reader::from_reader(content.as_bytes());
+    let header = reader.headers().map_err(invalid)?.clone();
+    reader
+        .records()
+        .map(|record| {
+            let record = record.map_err(invalid)?;
+            let row: Map<String, Value> = header
+                .iter()
+                .zip(&record)
+                .map(|(name, field)| (name.to_string(), Value::String(field.to_string())))
+                .collect();
+            Ok(Value::Object(row))
+        })
+        .collect()
+}
+
+/// Files matching a glob pattern, sorted by path.
+///
+/// `*`, `?` and `[...]` match within one path component, and a `**`
+/// component matches any number of directories. Hidden files only match
+/// patterns starting with a dot.
+pub fn expand_glob(pattern: &str) -> Result<Vec<PathBuf>> {
+    let options = glob::MatchOptions {
+        require_literal_leading_dot: true,
+        ..Default::default()
+    };
+    let paths = glob::glob_with(pattern, options)
+        .map_err(|e| OrchestratorError::other(format!("Invalid glob '{}': {}", pattern, e)))?;
+    // Unreadable directories are skipped
+    let mut files: Vec<PathBuf> = paths.flatten().filter(|path| path.is_file()).collect();
+    files.sort();
+    files.dedup();
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_csv_input() {
+        let csv = "\u{feff}name,notes\r\nada,\"likes \"\"math\"\", logic\"\r\n\r\nalan,\"multi\nline\"\n";
+        assert_eq!(
+            InputFormat::Csv.decode(csv).unwrap(),
+            json!([
+                {"name": "ada", "notes": "likes \"math\", logic"},
+                {"name": "alan", "notes": "multi\nline"},
+            ])
+        );
+        assert!(InputFormat::Csv.decode("a,b\n1\n").is_err());
+        // An unterminated quoted field runs to the end of the input
+        assert_eq!(InputFormat::Csv.decode("a\n\"open\n").unwrap(), json!([{"a": "open\n"}]));
+        assert_eq!(InputFormat::Jsonl.decode("{\"a\": 1}\n\n[2]\n").unwrap(), json!([{"a": 1}, [2]]));
+    }
+
+    #[tokio::test]
+    async fn test_glob_input() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("docs/nested")).unwrap();
+        std::fs::write(root.join("docs/a.json"), "{\"id\": 1}").unwrap();
+        std::fs::write(root.join("docs/nested/b.json"), "{\"id\": 2}").unwrap();
+        std::fs::write(root.join("docs/notes.txt"), "skip").unwrap();
+        std::fs::write(root.join("docs/.hidden.json"), "{}").unwrap();
+
+        let pattern = format!("{}/docs/**/*.json", root.display());
+        let source = InputSource::from(pattern.as_str());
+        assert_eq!(source, InputSource::Glob(pattern.clone()));
+        assert_eq!(InputLoader::new().load(&source).await.unwrap(), json!([{"id": 1}, {"id": 2}]));
+
+        let single = format!("{}/docs/[ab].json", root.display());
+        assert_eq!(expand_glob(&single).unwrap(), vec![root.join("docs/a.json")]);
+
+        // The limit counts the files of a glob together
+        let error = InputLoader::new().with_max_bytes(12).load(&source).await.unwrap_err();
+        assert!(error.to_string().contains("exceeds the limit of 12 bytes"), "{}", error);
+
+        let text = InputSource::from(format!("{}/docs/notes.txt", root.display()).as_str());
+        assert_eq!(InputLoader::new().load(&text).await.unwrap(), json!("skip"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_limit_holds_for_files_without_a_size() {
+        // Reports a size of 0 and never ends
+        let source = InputSource::File(PathBuf::from("/dev/zero"));
+        let error = InputLoader::new().with_max_bytes(16).load(&source).await.unwrap_err();
+        assert!(error.to_string().contains("exceeds the limit of 16 bytes"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn test_url_input() {
+        let mut server = mockito::Server::new_async().await;
+        let _csv = server
+            .mock("GET", "/export")
+            .match_query(mockito::Matcher::Any)
+            .with_header("content-type", "text/csv; charset=utf-8")
+            .with_body("id,title\n7,Intro\n")
+            .create_async()
+            .await;
+        let _large = server.mock("GET", "/large.json").with_body(format!("\"{}\"", "x".repeat(64))).create_async().await;
+
+        let loader = InputLoader::new();
+        let source = InputSource::from(format!("{}/export?page=1", server.url()).as_str());
+        assert_eq!(loader.load(&source).await.unwrap(), json!([{"id": "7", "title": "Intro"}]));
+
+        let large = InputSource::Url(format!("{}/large.json", server.url()));
+        assert!(loader.with_max_bytes(32).load(&large).await.is_err());
+    }
+}
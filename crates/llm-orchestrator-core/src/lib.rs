@@ -43,30 +43,118 @@
 //! # }
 //! ```
 
+pub mod admission;
+pub mod agent;
+pub mod approval;
+pub mod artifact;
+pub mod assertion;
+#[cfg(feature = "audit")]
+pub mod audit;
+pub mod cancel;
 pub mod context;
 pub mod dag;
+#[cfg(feature = "state-persistence")]
+pub mod dead_letter;
+pub mod deterministic;
+pub mod diagnostics;
+pub mod document;
 pub mod error;
+pub mod evaluate;
+pub mod exec;
+pub mod events;
 pub mod executor;
 pub mod executor_state;
+pub mod experiment;
+pub mod fetch;
+pub mod gateway;
+pub mod golden;
+pub mod guardrails;
+pub mod hedge;
+pub mod memory;
+pub mod messaging;
+pub mod mcp;
+pub mod http;
+pub mod image;
+pub mod include;
+pub mod inputs;
+pub mod lint;
 pub mod health;
 pub mod metrics;
+pub mod mock;
+pub mod notify;
+pub mod partials;
+pub mod prompts;
 pub mod providers;
+#[cfg(feature = "state-persistence")]
+pub mod recovery;
+pub mod report;
+pub mod result_sink;
 pub mod retry;
+#[cfg(feature = "secrets")]
+pub mod secrets;
+pub mod speak;
+pub mod spill;
+pub mod step_template;
+#[cfg(feature = "otel")]
+pub mod telemetry;
+pub mod transcribe;
+pub mod transform;
+pub mod upsert;
+pub mod usage;
+pub mod web_fetch;
+pub mod workdir;
+pub mod webhook;
 pub mod workflow;
+#[cfg(feature = "state-persistence")]
+pub mod worker;
 
 // Re-export commonly used types
-pub use context::ExecutionContext;
+pub use admission::{AdmissionController, AdmissionLimits, Rejection};
+pub use approval::{ApprovalDecision, ApprovalRequest};
+pub use artifact::{Artifact, ArtifactStore, ArtifactStoreConfig, LocalArtifactStore};
+pub use cancel::{AbandonHandle, CancelHandle, ShutdownHandle};
+pub use context::{ContextLimits, ContextMemory, ContextValue, ExecutionContext};
 pub use dag::WorkflowDAG;
-pub use error::{OrchestratorError, Result};
-pub use executor::{StepResult, StepStatus, WorkflowExecutor};
+pub use deterministic::{ReproducibilityManifest, StepManifest};
+pub use diagnostics::{Diagnostic, DiagnosticCode, Severity};
+pub use document::{Document, DocumentError, DocumentLoader, DocumentLoaders, LoadDocumentAction};
+pub use error::{ErrorKind, OrchestratorError, Result, StepError, StepFailure};
+pub use evaluate::MetricScore;
+pub use events::{ExecutionEvent, WorkflowEventListener};
+pub use executor::{SkipReason, StepResult, StepStatus, WorkflowExecutor};
+pub use experiment::{ExperimentTracker, VariantSummary};
+pub use gateway::{ChatCompletionRequest, ChatMessage, ChatTrigger};
+pub use golden::{GoldenRecorder, GoldenRecording, OutputDrift};
+pub use guardrails::{
+    ContentFilter, Finding, GuardrailAction, GuardrailConfig, GuardrailPolicy, GuardrailStage,
+    GuardrailVerdict, InjectionDetector, PiiDetector, PiiKind,
+};
+pub use health::{ComponentHealth, HealthCheck, HealthCheckResult, HealthRegistry, HealthStatus};
+pub use hedge::LatencyTracker;
+pub use lint::{LintConfig, LintRule};
+pub use memory::{InMemoryMemoryStore, MemoryStore};
+pub use messaging::{Delivery, Message, MessageSink, MessageSource, TopicTrigger};
+pub use mcp::{McpClient, McpServerConfig, ToolDefinition};
+pub use mock::MockResponses;
+pub use notify::{ActionHandler, FailureNotifier, WorkflowFailure};
+pub use prompts::{FilePromptStore, PromptRef, PromptRegistry, PromptStore, PromptTemplate};
 pub use providers::{CompletionRequest, CompletionResponse, LLMProvider, ProviderError};
-pub use retry::{RetryExecutor, RetryPolicy};
+pub use report::{ExecutionReport, ReportFormat, StepReport};
+pub use result_sink::{ResultSink, RunOutputs, SinkConfig, SinkFormat};
+pub use spill::{ArtifactSpill, OutputSpill};
+pub use upsert::UpsertFailure;
+pub use retry::{RetryBudget, RetryExecutor, RetryPolicy};
+pub use usage::{ModelUsage, UsageTracker};
 pub use workflow::{
     Workflow, Step, StepType, StepConfig,
-    LlmStepConfig, EmbedStepConfig, VectorSearchConfig,
-    TransformConfig, ActionConfig, ParallelConfig, BranchConfig,
-    RetryConfig, BackoffStrategy,
+    LlmStepConfig, LlmVariant, VariantMode, ImageAttachment, EmbedStepConfig, VectorSearchConfig, VectorUpsertConfig, VectorFetchConfig,
+    TransformConfig, ActionConfig, ParallelConfig, BranchConfig, ApprovalConfig,
+    EvaluateConfig, EvalMetric, EvalMetricKind, JudgeConfig, HttpConfig, McpToolConfig, AgentStepConfig, AgentTool, WebFetchConfig, TranscribeConfig, SpeakConfig,
+    FailurePolicy, RetryConfig, RetryBudgetConfig, BackoffStrategy, ResourceClass,
+    HedgeConfig, Trigger,
 };
+pub use webhook::{SignatureScheme, WebhookError, WebhookTrigger};
+pub use workdir::{WorkdirConfig, WorkdirRetention};
 
 /// Library version.
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -0,0 +1,581 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Lint rules for workflows.
+//!
+//! Linting goes beyond validation: it flags workflows that run, but likely
+//! not as intended. Each [`LintRule`] reports a [`Diagnostic`] with an `L`
+//! code at a configurable [`Level`]; rules warn unless a [`LintConfig`] says
+//! otherwise:
+//!
+//! ```yaml
+//! # lint.yaml
+//! rules:
+//!   missing-timeout: deny
+//!   deprecated: allow
+//! max_json_temperature: 0.2
+//! ```
+//!
+//! | Rule | Code | Flags |
+//! |------|------|-------|
+//! | `missing-retry` | L0001 | Provider calls without a `retry` policy |
+//! | `missing-timeout` | L0002 | Remote calls without a step or workflow timeout |
+//! | `unused-output` | L0003 | Outputs of intermediate steps no template reads |
+//! | `unreachable-branch` | L0004 | Steps and branches whose condition is constant |
+//! | `json-temperature` | L0005 | High temperatures on LLM steps expected to return JSON |
+//! | `deprecated` | L0006 | `{{ outputs.<step> }}` references |
+
+use crate::context::ExecutionContext;
+use crate::diagnostics::{check, did_you_mean, locate, Diagnostic, DiagnosticCode, Severity};
+use crate::include::{FileResolver, IncludeResolver};
+use crate::workflow::{EvalMetricKind, LlmStepConfig, Step, StepConfig, Workflow};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+
+lazy_static::lazy_static! {
+    /// A template reference to step outputs: root, step ID, and field.
+    static ref REFERENCE: Regex =
+        Regex::new(r"\b(steps|outputs)\.([A-Za-z0-9_-]+)(?:\.([A-Za-z0-9_-]+))?").unwrap();
+}
+
+/// A lint rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LintRule {
+    /// An LLM, embed, or judged evaluate step has no `retry` policy and
+    /// falls back to the default of 3 attempts.
+    MissingRetry,
+    /// A step calling a provider or remote service has no timeout, and the
+    /// workflow has none either.
+    MissingTimeout,
+    /// A step that others depend on declares an output no template reads.
+    UnusedOutput,
+    /// A step or branch can never run because its condition is constant.
+    UnreachableBranch,
+    /// An LLM step expected to return JSON samples above the configured temperature.
+    JsonTemperature,
+    /// A template uses the deprecated `outputs.<step>` form.
+    Deprecated,
+}
+
+impl LintRule {
+    /// Every rule, in code order.
+    pub const ALL: [LintRule; 6] = [
+        Self::MissingRetry,
+        Self::MissingTimeout,
+        Self::UnusedOutput,
+        Self::UnreachableBranch,
+        Self::JsonTemperature,
+        Self::Deprecated,
+    ];
+
+    /// The rule name used in configuration, e.g. `missing-retry`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::MissingRetry => "missing-retry",
+            Self::MissingTimeout => "missing-timeout",
+            Self::UnusedOutput => "unused-output",
+            Self::UnreachableBranch => "unreachable-branch",
+            Self::JsonTemperature => "json-temperature",
+            Self::Deprecated => "deprecated",
+        }
+    }
+
+    /// The diagnostic code, e.g. `L0001`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::MissingRetry => "L0001",
+            Self::MissingTimeout => "L0002",
+            Self::UnusedOutput => "L0003",
+            Self::UnreachableBranch => "L0004",
+            Self::JsonTemperature => "L0005",
+            Self::Deprecated => "L0006",
+        }
+    }
+}
+
+impl std::fmt::Display for LintRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl std::str::FromStr for LintRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        if let Some(rule) = Self::ALL.into_iter().find(|rule| rule.name() == s || rule.code() == s) {
+            return Ok(rule);
+        }
+        let mut message = format!("Unknown lint rule '{}'", s);
+        if let Some(suggestion) = did_you_mean(s, Self::ALL.iter().map(|rule| rule.name())) {
+            message = format!("{}. {}", message, suggestion);
+        }
+        Err(message)
+    }
+}
+
+/// What a rule does with its findings.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    /// Drop the findings.
+    Allow,
+    /// Report the findings as warnings.
+    #[default]
+    Warn,
+    /// Report the findings as errors.
+    Deny,
+}
+
+impl std::str::FromStr for Level {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "allow" | "off" => Ok(Self::Allow),
+            "warn" => Ok(Self::Warn),
+            "deny" | "error" => Ok(Self::Deny),
+            other => Err(format!("Unknown lint level '{}' (expected allow, warn, or deny)", other)),
+        }
+    }
+}
+
+/// Lint configuration, usually read from a YAML file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LintConfig {
+    /// Level of each rule; rules not listed warn.
+    pub rules: HashMap<LintRule, Level>,
+
+    /// Highest temperature allowed for LLM steps expected to return JSON.
+    pub max_json_temperature: f32,
+}
+
+impl Default for LintConfig {
+    fn default() -> Self {
+        Self {
+            rules: HashMap::new(),
+            max_json_temperature: 0.3,
+        }
+    }
+}
+
+impl LintConfig {
+    /// Level of `rule`.
+    pub fn level(&self, rule: LintRule) -> Level {
+        self.rules.get(&rule).copied().unwrap_or_default()
+    }
+}
+
+/// Lints a parsed workflow.
+///
+/// Findings carry the path of the offending field but no location; use
+/// [`lint_yaml`] to lint a workflow file.
+pub fn lint(workflow: &Workflow, config: &LintConfig) -> Vec<Diagnostic> {
+    let mut linter = Linter::new(workflow, config);
+    linter.missing_retry();
+    linter.missing_timeout();
+    linter.unused_output();
+    linter.unreachable_branch();
+    linter.json_temperature();
+    linter.deprecated();
+    linter.diagnostics
+}
+
+/// Parses, checks, and lints a workflow file.
+///
+/// An invalid workflow is not linted: its validation errors are returned
+/// instead. Findings are located in `source` and ordered by position.
+/// Included files are read relative to the working directory.
+pub fn lint_yaml(source: &str, config: &LintConfig) -> Vec<Diagnostic> {
+    lint_yaml_with(source, config, &FileResolver::current_dir())
+}
+
+/// Like [`lint_yaml`], reading included files with `resolver`.
+///
+/// Findings in a workflow rewritten by includes or step templates are not located.
+pub fn lint_yaml_with(source: &str, config: &LintConfig, resolver: &dyn IncludeResolver) -> Vec<Diagnostic> {
+    let (workflow, rewritten) = match check(source, resolver) {
+        Ok(checked) => checked,
+        Err(diagnostics) => return diagnostics,
+    };
+    let mut diagnostics = lint(&workflow, config);
+    if !rewritten {
+        locate(source, &mut diagnostics);
+    }
+    diagnostics.sort_by_key(|d| (d.line.unwrap_or(usize::MAX), d.column));
+    diagnostics
+}
+
+struct Linter<'a> {
+    workflow: &'a Workflow,
+    config: &'a LintConfig,
+    // Every step with its path, nested steps included
+    steps: Vec<(String, &'a Step)>,
+    // Every template string with its path
+    templates: Vec<(String, String)>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> Linter<'a> {
+    fn new(workflow: &'a Workflow, config: &'a LintConfig) -> Self {
+        let mut steps = Vec::new();
+        for (i, step) in workflow.steps.iter().enumerate() {
+            collect_steps(format!("steps[{}]", i), step, &mut steps);
+        }
+        let mut templates = Vec::new();
+        for (path, value) in [("steps", serde_json::to_value(&workflow.steps)), ("triggers", serde_json::to_value(&workflow.triggers))] {
+            collect_templates(path.to_string(), &value.unwrap_or_default(), &mut templates);
+        }
+        Self {
+            workflow,
+            config,
+            steps,
+            templates,
+            diagnostics: Vec::new(),
+        }
+    }
+
+    fn report(&mut self, rule: LintRule, diagnostic: Diagnostic) {
+        let severity = match self.config.level(rule) {
+            Level::Allow => return,
+            Level::Warn => Severity::Warning,
+            Level::Deny => Severity::Error,
+        };
+        self.diagnostics.push(diagnostic.severity(severity));
+    }
+
+    /// Step whose definition contains `path`.
+    fn owner(&self, path: &str) -> Option<&'a Step> {
+        self.steps
+            .iter()
+            .filter(|(prefix, _)| path.strip_prefix(prefix.as_str()).is_some_and(|rest| rest.starts_with(['.', '['])))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|&(_, step)| step)
+    }
+
+    fn missing_retry(&mut self) {
+        for (path, step) in self.steps.clone() {
+            let Some(provider) = provider(step).filter(|_| step.retry.is_none()) else {
+                continue;
+            };
+            let diagnostic = Diagnostic::new(DiagnosticCode::Lint(LintRule::MissingRetry), format!("Step '{}' calls provider '{}' without a retry policy", step.id, provider))
+                .step(&step.id)
+                .at(path)
+                .suggest("Set `retry` to the attempts and backoff this call needs; the default is 3 attempts");
+            self.report(LintRule::MissingRetry, diagnostic);
+        }
+    }
+
+    fn missing_timeout(&mut self) {
+        if self.workflow.timeout_seconds.is_some() {
+            return;
+        }
+        for (path, step) in self.steps.clone() {
+            let remote = provider(step).is_some() || matches!(step.config, StepConfig::VectorSearch(_) | StepConfig::VectorUpsert(_) | StepConfig::VectorFetch(_) | StepConfig::Http(_) | StepConfig::McpTool(_) | StepConfig::WebFetch(_));
+            if !remote || step.timeout_seconds.is_some() {
+                continue;
+            }
+            let diagnostic = Diagnostic::new(DiagnosticCode::Lint(LintRule::MissingTimeout), format!("Step '{}' has no timeout", step.id))
+                .step(&step.id)
+                .at(path)
+                .suggest("Set `timeout_seconds` on the step or the workflow; otherwise only the one-hour run limit stops a hung call");
+            self.report(LintRule::MissingTimeout, diagnostic);
+        }
+    }
+
+    fn unused_output(&mut self) {
+        // Partials read outputs for the templates calling them
+        let texts: Vec<Cow<str>> = self
+            .templates
+            .iter()
+            .map(|(_, text)| crate::partials::expand(text, &self.workflow.partials))
+            // Bindings are paths such as `steps.fetch.body`
+            .chain(self.steps.iter().flat_map(|(_, step)| step.bind.values().map(|path| Cow::Borrowed(path.as_str()))))
+            .collect();
+        // Step IDs, and fields unless a template reads the whole output
+        let referenced: HashSet<(&str, Option<&str>)> = texts
+            .iter()
+            .flat_map(|text| REFERENCE.captures_iter(text))
+            .filter_map(|c| Some((c.get(2)?.as_str(), c.get(3).map(|m| m.as_str()))))
+            .collect();
+        let dependencies: HashSet<&str> = self.steps.iter().flat_map(|(_, step)| step.depends_on.iter().chain(&step.depends_on_any).map(String::as_str)).collect();
+        // Transform steps name the outputs they read
+        let transform_inputs: HashSet<&str> = self
+            .steps
+            .iter()
+            .filter_map(|(_, step)| match &step.config {
+                StepConfig::Transform(config) => Some(config.inputs.iter().map(String::as_str)),
+                _ => None,
+            })
+            .flatten()
+            .collect();
+
+        let mut findings = Vec::new();
+        for (path, step) in &self.steps {
+            // Outputs of the last steps are the results of the run
+            if !dependencies.contains(step.id.as_str()) || referenced.contains(&(step.id.as_str(), None)) {
+                continue;
+            }
+            for (k, output) in step.output.iter().enumerate() {
+                if referenced.contains(&(step.id.as_str(), Some(output.as_str()))) || transform_inputs.contains(output.as_str()) {
+                    continue;
+                }
+                findings.push(
+                    Diagnostic::new(DiagnosticCode::Lint(LintRule::UnusedOutput), format!("Output '{}' of step '{}' is never used", output, step.id))
+                        .step(&step.id)
+                        .at(format!("{}.output[{}]", path, k))
+                        .suggest(format!("Reference it as `{{{{ steps.{}.{} }}}}` or remove it from `output`", step.id, output)),
+                );
+            }
+        }
+        for diagnostic in findings {
+            self.report(LintRule::UnusedOutput, diagnostic);
+        }
+    }
+
+    fn unreachable_branch(&mut self) {
+        let context = ExecutionContext::new(HashMap::new());
+        for (path, step) in self.steps.clone() {
+            if let Some(condition) = step.condition.as_deref().filter(|c| !c.contains("{{")) {
+                if matches!(context.evaluate_condition(condition), Ok(false)) {
+                    let diagnostic = Diagnostic::new(DiagnosticCode::Lint(LintRule::UnreachableBranch), format!("Step '{}' never runs: its condition '{}' is always false", step.id, condition))
+                        .step(&step.id)
+                        .at(format!("{}.condition", path))
+                        .suggest("Remove the step, or base the condition on inputs or step outputs");
+                    self.report(LintRule::UnreachableBranch, diagnostic);
+                }
+            }
+
+            let StepConfig::Branch(config) = &step.config else {
+                continue;
+            };
+            if config.condition.contains("{{") {
+                continue;
+            }
+            let taken = config.condition.trim();
+            let mut branches: Vec<&String> = config.branches.keys().filter(|key| key.as_str() != taken).collect();
+            branches.sort();
+            for branch in branches {
+                let diagnostic = Diagnostic::new(DiagnosticCode::Lint(LintRule::UnreachableBranch), format!("Branch '{}' of step '{}' is never taken: the condition is always '{}'", branch, step.id, taken))
+                    .step(&step.id)
+                    .at(format!("{}.branches.{}", path, branch))
+                    .suggest("Base the condition on inputs or step outputs");
+                self.report(LintRule::UnreachableBranch, diagnostic);
+            }
+        }
+    }
+
+    fn json_temperature(&mut self) {
+        let max = self.config.max_json_temperature;
+        // Steps whose output an evaluate step checks against a JSON Schema
+        let mut schema_checked = HashSet::new();
+        for (_, step) in &self.steps {
+            if let StepConfig::Evaluate(config) = &step.config {
+                if config.metrics.iter().any(|m| matches!(m.kind, EvalMetricKind::JsonSchema { .. })) {
+                    schema_checked.extend(referenced_steps(&config.target).map(str::to_string));
+                }
+            }
+        }
+
+        for (path, step) in self.steps.clone() {
+            let StepConfig::Llm(config) = &step.config else {
+                continue;
+            };
+            if !schema_checked.contains(&step.id) && !expects_json(config) {
+                continue;
+            }
+            let temperatures = std::iter::once((format!("{}.temperature", path), config.temperature))
+                .chain(config.variants.iter().enumerate().map(|(k, v)| (format!("{}.variants[{}].temperature", path, k), v.temperature)));
+            for (field, temperature) in temperatures {
+                let Some(temperature) = temperature.filter(|t| *t > max) else {
+                    continue;
+                };
+                let diagnostic = Diagnostic::new(DiagnosticCode::Lint(LintRule::JsonTemperature), format!("Step '{}' expects JSON output but samples at temperature {}", step.id, temperature))
+                    .step(&step.id)
+                    .at(field)
+                    .suggest(format!("Lower `temperature` to {} or less so replies stay parseable", max));
+                self.report(LintRule::JsonTemperature, diagnostic);
+            }
+        }
+    }
+
+    fn deprecated(&mut self) {
+        let mut seen = HashSet::new();
+        let mut findings = Vec::new();
+        for (path, text) in &self.templates {
+            for captures in REFERENCE.captures_iter(text).filter(|c| &c[1] == "outputs") {
+                let target = &captures[2];
+                if !seen.insert((path.as_str(), target.to_string())) {
+                    continue;
+                }
+                let mut diagnostic = Diagnostic::new(DiagnosticCode::Lint(LintRule::Deprecated), format!("`outputs.{}` is deprecated", target))
+                    .at(path.clone())
+                    .suggest(format!("Use `steps.{}` instead", target));
+                if let Some(step) = self.owner(path) {
+                    diagnostic = diagnostic.step(&step.id);
+                }
+                findings.push(diagnostic);
+            }
+        }
+        for diagnostic in findings {
+            self.report(LintRule::Deprecated, diagnostic);
+        }
+    }
+}
+
+/// IDs of the steps whose outputs `text` references.
+pub(crate) fn referenced_steps(text: &str) -> impl Iterator<Item = &str> {
+    REFERENCE.captures_iter(text).filter_map(|c| Some(c.get(2)?.as_str()))
+}
+
+/// Provider a step calls, if any.
+fn provider(step: &Step) -> Option<&str> {
+    match &step.config {
+        StepConfig::Llm(config) => Some(&config.provider),
+        StepConfig::Agent(config) => Some(&config.provider),
+        StepConfig::Embed(config) => Some(&config.provider),
+        StepConfig::Transcribe(config) => Some(&config.provider),
+        StepConfig::Speak(config) => Some(&config.provider),
+        StepConfig::Evaluate(config) => config.judge.as_ref().map(|judge| judge.provider.as_str()).or_else(|| {
+            config.metrics.iter().find_map(|metric| match &metric.kind {
+                EvalMetricKind::EmbeddingSimilarity { provider, .. } => Some(provider.as_str()),
+                _ => None,
+            })
+        }),
+        _ => None,
+    }
+}
+
+/// Whether an LLM step asks for JSON, through `response_format` or its prompt.
+fn expects_json(config: &LlmStepConfig) -> bool {
+    config.extra.get("response_format").is_some_and(|format| format.to_string().to_lowercase().contains("json"))
+        || config.prompt.contains("JSON")
+        || config.system.as_deref().is_some_and(|system| system.contains("JSON"))
+}
+
+/// Adds `step` and the steps nested in it, with their paths.
+fn collect_steps<'a>(path: String, step: &'a Step, steps: &mut Vec<(String, &'a Step)>) {
+    match &step.config {
+        StepConfig::Parallel(config) => {
+            for (j, task) in config.tasks.iter().enumerate() {
+                collect_steps(format!("{}.tasks[{}]", path, j), task, steps);
+            }
+        }
+        StepConfig::Branch(config) => {
+            let mut branches: Vec<_> = config.branches.iter().collect();
+            branches.sort_by_key(|(key, _)| *key);
+            for (key, branch) in branches {
+                for (j, nested) in branch.iter().enumerate() {
+                    collect_steps(format!("{}.branches.{}[{}]", path, key, j), nested, steps);
+                }
+            }
+        }
+        _ => {}
+    }
+    steps.push((path, step));
+}
+
+/// Adds the template strings in `value`, with their paths.
+pub(crate) fn collect_templates(path: String, value: &Value, templates: &mut Vec<(String, String)>) {
+    match value {
+        Value::String(text) if text.contains("{{") => templates.push((path, text.clone())),
+        Value::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                collect_templates(format!("{}[{}]", path, i), item, templates);
+            }
+        }
+        Value::Object(fields) => {
+            for (key, field) in fields {
+                collect_templates(format!("{}.{}", path, key), field, templates);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WORKFLOW: &str = r#"name: "lint"
+steps:
+  - id: "extract"
+    type: "llm"
+    provider: "openai"
+    model: "gpt-4"
+    prompt: "Return the fields of {{ inputs.text }} as JSON"
+    temperature: 0.9
+    output: ["fields", "model"]
+  - id: "store"
+    type: "http"
+    depends_on: ["extract"]
+    method: "POST"
+    url: "https://example.com/records"
+    body: "{{ steps.extract.fields }}"
+    timeout_seconds: 10
+  - id: "archive"
+    type: "transform"
+    depends_on: ["store"]
+    condition: "false"
+    function: "noop"
+    inputs: []
+  - id: "log"
+    type: "action"
+    depends_on: ["store"]
+    action: "log"
+    message: "Stored {{ outputs.store }}"
+"#;
+
+    #[test]
+    fn test_lint_rules() {
+        let diagnostics = lint_yaml(WORKFLOW, &LintConfig::default());
+        let found: Vec<(&str, Option<&str>, Option<usize>)> = diagnostics
+            .iter()
+            .map(|d| (d.code.as_str(), d.step_id.as_deref(), d.line))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("L0001", Some("extract"), Some(3)),
+                ("L0002", Some("extract"), Some(3)),
+                ("L0005", Some("extract"), Some(8)),
+                ("L0003", Some("extract"), Some(9)),
+                ("L0004", Some("archive"), Some(20)),
+                ("L0006", Some("log"), Some(27)),
+            ]
+        );
+        assert!(diagnostics.iter().all(|d| d.severity == Severity::Warning));
+        assert_eq!(diagnostics[3].path.as_deref(), Some("steps[0].output[1]"));
+        assert_eq!(diagnostics[5].suggestion.as_deref(), Some("Use `steps.store` instead"));
+    }
+
+    #[test]
+    fn test_lint_config() {
+        let config: LintConfig = serde_yaml::from_str("rules:\n  missing-retry: allow\n  json-temperature: deny\nmax_json_temperature: 0.95\n").unwrap();
+        let codes: Vec<&str> = lint_yaml(WORKFLOW, &config).iter().map(|d| d.code.as_str()).collect();
+        assert_eq!(codes, ["L0002", "L0003", "L0004", "L0006"]);
+
+        let config = LintConfig {
+            rules: HashMap::from([(LintRule::JsonTemperature, Level::Deny)]),
+            ..Default::default()
+        };
+        let diagnostics = lint_yaml(WORKFLOW, &config);
+        let denied = diagnostics.iter().find(|d| d.code == DiagnosticCode::Lint(LintRule::JsonTemperature)).unwrap();
+        assert_eq!(denied.severity, Severity::Error);
+
+        // Invalid workflows report their validation errors instead
+        let invalid = WORKFLOW.replace("depends_on: [\"store\"]\n    condition", "depends_on: [\"stor\"]\n    condition");
+        let diagnostics = lint_yaml(&invalid, &config);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, DiagnosticCode::UnknownDependency);
+
+        assert_eq!("unused-output".parse(), Ok(LintRule::UnusedOutput));
+        assert_eq!("L0004".parse(), Ok(LintRule::UnreachableBranch));
+        assert_eq!("missing-retries".parse::<LintRule>().unwrap_err(), "Unknown lint rule 'missing-retries'. Did you mean 'missing-retry'?");
+    }
+}
@@ -0,0 +1,525 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Model Context Protocol (MCP) tools.
+//!
+//! An `mcp_tool` step calls a tool on an MCP server registered with
+//! [`WorkflowExecutor::with_mcp_server`]:
+//!
+//! ```yaml
+//! - id: issues
+//!   type: mcp_tool
+//!   server: github
+//!   tool: search_issues
+//!   arguments:
+//!     query: "repo:acme/core {{ inputs.keywords }}"
+//!     per_page: "{{ inputs.limit }}"
+//!   output: [issues]
+//! ```
+//!
+//! Servers are described the way MCP hosts describe them: a command started
+//! with stdio transport, or the URL of a Streamable HTTP endpoint. An
+//! [`McpClient`] connects on first use and discovers the server's tools, so
+//! a step fails with a clear error when the tool is missing or its arguments
+//! do not match the tool's input schema. The same [`ToolDefinition`]s can be
+//! handed to function-calling models with [`ToolDefinition::to_openai_tool`]
+//! or [`ToolDefinition::to_anthropic_tool`].
+
+use crate::error::{OrchestratorError, Result};
+use crate::executor::WorkflowExecutor;
+use crate::workflow::{Step, StepConfig};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use thiserror::Error;
+use tokio::sync::OnceCell;
+use tracing::{debug, info};
+
+mod transport;
+
+use transport::{HttpTransport, StdioTransport, Transport};
+
+/// Protocol revision requested when connecting.
+pub const PROTOCOL_VERSION: &str = "2025-03-26";
+
+/// Default output names, in the order outputs are assigned.
+const DEFAULT_OUTPUTS: [&str; 2] = ["text", "structured"];
+
+/// An MCP call that failed.
+#[derive(Debug, Error)]
+pub enum McpError {
+    /// The server could not be reached or sent something unreadable.
+    #[error("MCP transport error: {0}")]
+    Transport(String),
+
+    /// The server answered with a JSON-RPC error.
+    #[error("MCP server error {code}: {message}")]
+    Server { code: i64, message: String },
+
+    /// The server does not offer the tool.
+    #[error("MCP server has no tool '{0}'")]
+    UnknownTool(String),
+
+    /// The arguments do not match the tool's input schema.
+    #[error("Invalid arguments for tool '{tool}': {reason}")]
+    InvalidArguments { tool: String, reason: String },
+
+    /// The tool ran and reported an error.
+    #[error("Tool '{tool}' failed: {message}")]
+    ToolFailed { tool: String, message: String },
+}
+
+/// How to reach an MCP server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum McpServerConfig {
+    /// A local process speaking MCP over stdin and stdout.
+    Stdio {
+        /// Program to start.
+        command: String,
+        /// Program arguments.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        args: Vec<String>,
+        /// Variables added to the inherited environment.
+        #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+        env: HashMap<String, String>,
+    },
+
+    /// A Streamable HTTP endpoint.
+    Http {
+        /// Endpoint URL.
+        url: String,
+        /// Headers sent with every request, e.g. `Authorization`.
+        #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+        headers: HashMap<String, String>,
+    },
+}
+
+/// Reads servers from an MCP host configuration file (`{"mcpServers": {...}}`).
+pub fn load_servers(path: impl AsRef<Path>) -> Result<HashMap<String, McpServerConfig>> {
+    #[derive(Deserialize)]
+    struct HostConfig {
+        #[serde(rename = "mcpServers")]
+        servers: HashMap<String, McpServerConfig>,
+    }
+
+    let path = path.as_ref();
+    let content = std::fs::read_to_string(path)?;
+    let config: HostConfig = serde_json::from_str(&content).map_err(|e| {
+        OrchestratorError::ParseError(format!("Invalid MCP configuration {}: {}", path.display(), e))
+    })?;
+    Ok(config.servers)
+}
+
+/// A tool offered by an MCP server.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolDefinition {
+    /// Tool name.
+    pub name: String,
+
+    /// What the tool does.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// JSON Schema of the tool arguments.
+    #[serde(rename = "inputSchema")]
+    pub input_schema: Value,
+}
+
+impl ToolDefinition {
+    /// The tool as an OpenAI function-calling tool.
+    pub fn to_openai_tool(&self) -> Value {
+        json!({
+            "type": "function",
+            "function": {
+                "name": self.name,
+                "description": self.description.as_deref().unwrap_or_default(),
+                "parameters": self.input_schema,
+            },
+        })
+    }
+
+    /// The tool as an Anthropic tool-use tool.
+    pub fn to_anthropic_tool(&self) -> Value {
+        json!({
+            "name": self.name,
+            "description": self.description.as_deref().unwrap_or_default(),
+            "input_schema": self.input_schema,
+        })
+    }
+}
+
+/// The result of a tool call.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolResult {
+    /// Content blocks (`text`, `image`, `resource`, ...).
+    #[serde(default)]
+    pub content: Vec<Value>,
+
+    /// Structured result, for tools that declare an output schema.
+    #[serde(default)]
+    pub structured_content: Option<Value>,
+
+    /// Whether the tool reported an error.
+    #[serde(default)]
+    pub is_error: bool,
+}
+
+impl ToolResult {
+    /// The text blocks of the content, joined by newlines.
+    pub fn text(&self) -> String {
+        self.content
+            .iter()
+            .filter(|block| block["type"] == "text")
+            .filter_map(|block| block["text"].as_str())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// A client of one MCP server.
+///
+/// The client connects on first use, and keeps the connection (and a stdio
+/// server process) until it is dropped.
+pub struct McpClient {
+    config: McpServerConfig,
+    transport: OnceCell<Box<dyn Transport>>,
+    tools: OnceCell<Vec<ToolDefinition>>,
+    next_id: AtomicU64,
+}
+
+impl McpClient {
+    /// Creates a client of the server described by `config`.
+    pub fn new(config: McpServerConfig) -> Self {
+        Self {
+            config,
+            transport: OnceCell::new(),
+            tools: OnceCell::new(),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    /// Connects and completes the protocol handshake, unless already connected.
+    async fn connection(&self) -> std::result::Result<&dyn Transport, McpError> {
+        let transport = self
+            .transport
+            .get_or_try_init(|| async {
+                let transport: Box<dyn Transport> = match &self.config {
+                    McpServerConfig::Stdio { command, args, env } => {
+                        Box::new(StdioTransport::spawn(command, args, env)?)
+                    }
+                    McpServerConfig::Http { url, headers } => Box::new(HttpTransport::new(url, headers)),
+                };
+                let params = json!({
+                    "protocolVersion": PROTOCOL_VERSION,
+                    "capabilities": {},
+                    "clientInfo": {"name": "llm-orchestrator", "version": env!("CARGO_PKG_VERSION")},
+                });
+                let server = call(transport.as_ref(), self.next_id(), "initialize", params).await?;
+                debug!(server = %server["serverInfo"]["name"], "Connected to MCP server");
+                transport
+                    .notify(json!({"jsonrpc": "2.0", "method": "notifications/initialized"}))
+                    .await?;
+                Ok::<_, McpError>(transport)
+            })
+            .await?;
+        Ok(transport.as_ref())
+    }
+
+    fn next_id(&self) -> u64 {
+        self.next_id.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// Sends a request and returns its result.
+    async fn request(&self, method: &str, params: Value) -> std::result::Result<Value, McpError> {
+        let transport = self.connection().await?;
+        call(transport, self.next_id(), method, params).await
+    }
+
+    /// Lists the tools the server offers.
+    pub async fn list_tools(&self) -> std::result::Result<Vec<ToolDefinition>, McpError> {
+        let mut tools = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let params = match &cursor {
+                Some(cursor) => json!({ "cursor": cursor }),
+                None => json!({}),
+            };
+            let page = self.request("tools/list", params).await?;
+            let listed: Vec<ToolDefinition> = serde_json::from_value(page["tools"].clone())
+                .map_err(|e| McpError::Transport(format!("invalid tool list: {}", e)))?;
+            tools.extend(listed);
+            match page["nextCursor"].as_str() {
+                Some(next) => cursor = Some(next.to_string()),
+                None => return Ok(tools),
+            }
+        }
+    }
+
+    /// The tools the server offers, discovered on first use.
+    pub async fn tools(&self) -> std::result::Result<&[ToolDefinition], McpError> {
+        let tools = self.tools.get_or_try_init(|| self.list_tools()).await?;
+        Ok(tools)
+    }
+
+    /// Calls a tool after checking `arguments` against its input schema.
+    ///
+    /// A result the tool marks as an error is returned as [`McpError::ToolFailed`].
+    pub async fn call_tool(&self, tool: &str, arguments: Value) -> std::result::Result<ToolResult, McpError> {
+        let definition = self
+            .tools()
+            .await?
+            .iter()
+            .find(|t| t.name == tool)
+            .ok_or_else(|| McpError::UnknownTool(tool.to_string()))?;
+        check_arguments(definition, &arguments)?;
+
+        let result = self
+            .request("tools/call", json!({ "name": tool, "arguments": arguments }))
+            .await?;
+        let result: ToolResult = serde_json::from_value(result)
+            .map_err(|e| McpError::Transport(format!("invalid tool result: {}", e)))?;
+        if result.is_error {
+            return Err(McpError::ToolFailed {
+                tool: tool.to_string(),
+                message: result.text(),
+            });
+        }
+        Ok(result)
+    }
+}
+
+/// Sends a JSON-RPC request over `transport` and returns its result.
+async fn call(transport: &dyn Transport, id: u64, method: &str, params: Value) -> std::result::Result<Value, McpError> {
+    let response = transport
+        .request(id, json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params}))
+        .await?;
+    if let Some(error) = response.get("error") {
+        return Err(McpError::Server {
+            code: error["code"].as_i64().unwrap_or_default(),
+            message: error["message"].as_str().unwrap_or_default().to_string(),
+        });
+    }
+    Ok(response.get("result").cloned().unwrap_or(Value::Null))
+}
+
+/// Checks tool arguments against the tool's input schema.
+fn check_arguments(tool: &ToolDefinition, arguments: &Value) -> std::result::Result<(), McpError> {
+    let invalid = |reason: String| McpError::InvalidArguments {
+        tool: tool.name.clone(),
+        reason,
+    };
+    let validator = jsonschema::validator_for(&tool.input_schema).map_err(|e| invalid(format!("unusable schema: {}", e)))?;
+    let errors: Vec<String> = validator.iter_errors(arguments).map(|e| e.to_string()).collect();
+    if !errors.is_empty() {
+        return Err(invalid(errors.join("; ")));
+    }
+    Ok(())
+}
+
+impl WorkflowExecutor {
+    /// Registers an MCP server that `mcp_tool` steps can name as their `server`.
+    pub fn with_mcp_server(self, name: impl Into<String>, client: Arc<McpClient>) -> Self {
+        self.mcp_servers.insert(name.into(), client);
+        self
+    }
+
+    /// Executes an MCP tool step.
+    pub(crate) async fn execute_mcp_tool_step(&self, step: &Step) -> Result<HashMap<String, Value>> {
+        let config = match &step.config {
+            StepConfig::McpTool(config) => config,
+            _ => {
+                return Err(OrchestratorError::InvalidStepConfig {
+                    step_id: step.id.clone(),
+                    reason: "Expected MCP tool step config".to_string(),
+                })
+            }
+        };
+        let client = self
+            .mcp_servers
+            .get(&config.server)
+            .map(|client| client.value().clone())
+            .ok_or_else(|| OrchestratorError::InvalidStepConfig {
+                step_id: step.id.clone(),
+                reason: format!("MCP server '{}' is not registered", config.server),
+            })?;
+
+        let mut arguments = Value::Object(config.arguments.clone().into_iter().collect());
+        let mut templates = Vec::new();
+        crate::http::collect_strings(&arguments, &mut templates);
+        for template in templates {
+            self.resolve_template_secrets(&template).await?;
+        }
+        self.render_values(&mut arguments)?;
+
+        debug!(step_id = %step.id, server = %config.server, tool = %config.tool, "Calling MCP tool");
+        let result = client
+            .call_tool(&config.tool, arguments)
+            .await
+            .map_err(|e| OrchestratorError::ExecutionError {
+                step_id: step.id.clone(),
+                source: Box::new(e),
+            })?;
+        info!(step_id = %step.id, server = %config.server, tool = %config.tool, "MCP tool call completed");
+
+        let names: Vec<&str> = if step.output.is_empty() {
+            DEFAULT_OUTPUTS.to_vec()
+        } else {
+            step.output.iter().map(String::as_str).collect()
+        };
+        let values = [Value::String(result.text()), result.structured_content.unwrap_or(Value::Null)];
+        Ok(names.into_iter().map(String::from).zip(values).collect())
+    }
+
+    /// Renders every string in a JSON value, keeping the type of single-expression templates.
+    pub(crate) fn render_values(&self, value: &mut Value) -> Result<()> {
+        match value {
+            Value::String(template) => *value = self.context.render_value(template)?,
+            Value::Array(items) => items.iter_mut().try_for_each(|item| self.render_values(item))?,
+            Value::Object(fields) => fields.values_mut().try_for_each(|field| self.render_values(field))?,
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workflow::Workflow;
+
+    fn search_tool() -> ToolDefinition {
+        serde_json::from_value(json!({
+            "name": "search",
+            "description": "Search issues",
+            "inputSchema": {
+                "type": "object",
+                "properties": {"query": {"type": "string"}, "limit": {"type": "integer"}},
+                "required": ["query"],
+            },
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_check_arguments() {
+        let tool = search_tool();
+        assert!(check_arguments(&tool, &json!({"query": "crash", "limit": 5})).is_ok());
+        assert!(matches!(
+            check_arguments(&tool, &json!({"limit": "5"})),
+            Err(McpError::InvalidArguments { .. })
+        ));
+        assert_eq!(tool.to_openai_tool()["function"]["parameters"], tool.input_schema);
+        assert_eq!(tool.to_anthropic_tool()["input_schema"], tool.input_schema);
+    }
+
+    #[test]
+    fn test_load_servers() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mcp.json");
+        std::fs::write(
+            &path,
+            r#"{"mcpServers": {
+                "files": {"command": "mcp-files", "args": ["/srv"]},
+                "github": {"url": "https://mcp.example.com/mcp", "headers": {"Authorization": "Bearer t"}}
+            }}"#,
+        )
+        .unwrap();
+
+        let servers = load_servers(&path).unwrap();
+        assert!(matches!(&servers["files"], McpServerConfig::Stdio { args, .. } if args == &["/srv"]));
+        assert!(matches!(&servers["github"], McpServerConfig::Http { url, .. } if url.ends_with("/mcp")));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_stdio_tool_error() {
+        // Answers the handshake, the tool list, and one call, with a notification first
+        let script = r#"
+read l; echo '{"jsonrpc":"2.0","id":1,"result":{"protocolVersion":"2025-03-26"}}'
+read l
+read l; echo '{"jsonrpc":"2.0","id":2,"result":{"tools":[{"name":"lookup","inputSchema":{"type":"object"}}]}}'
+read l; echo '{"jsonrpc":"2.0","method":"notifications/message","params":{}}'
+echo '{"jsonrpc":"2.0","id":3,"result":{"content":[{"type":"text","text":"not found"}],"isError":true}}'
+"#;
+        let client = McpClient::new(McpServerConfig::Stdio {
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), script.to_string()],
+            env: HashMap::new(),
+        });
+
+        let error = client.call_tool("lookup", json!({})).await.unwrap_err();
+        assert!(matches!(error, McpError::ToolFailed { ref message, .. } if message == "not found"));
+        // The tool list was discovered once, so an unknown tool fails without a request
+        assert!(matches!(client.call_tool("other", json!({})).await, Err(McpError::UnknownTool(_))));
+    }
+
+    #[tokio::test]
+    async fn test_mcp_tool_step_over_http() {
+        let mut server = mockito::Server::new_async().await;
+        let rpc = |method: &str, result: Value| {
+            let body = json!({"jsonrpc": "2.0", "id": 0, "result": result});
+            (method.to_string(), body)
+        };
+        for (method, body) in [
+            rpc("initialize", json!({"protocolVersion": PROTOCOL_VERSION, "serverInfo": {"name": "test"}})),
+            rpc("tools/list", json!({"tools": [search_tool()]})),
+        ] {
+            server
+                .mock("POST", "/mcp")
+                .match_body(mockito::Matcher::PartialJson(json!({ "method": method })))
+                .with_header("content-type", "application/json")
+                .with_body(body.to_string())
+                .create_async()
+                .await;
+        }
+        // Streamed responses are picked out by request ID
+        let call = server
+            .mock("POST", "/mcp")
+            .match_body(mockito::Matcher::PartialJson(
+                json!({"method": "tools/call", "params": {"arguments": {"query": "crash", "limit": 5}}}),
+            ))
+            .with_header("content-type", "text/event-stream")
+            .with_body("event: message\ndata: {\"jsonrpc\": \"2.0\", \"id\": 3, \"result\": {\"content\": [{\"type\": \"text\", \"text\": \"2 issues\"}], \"structuredContent\": {\"count\": 2}}}\n\n")
+            .create_async()
+            .await;
+        server
+            .mock("POST", "/mcp")
+            .match_body(mockito::Matcher::PartialJson(json!({"method": "notifications/initialized"})))
+            .with_status(202)
+            .create_async()
+            .await;
+
+        let workflow = Workflow::from_yaml(
+            r#"
+name: mcp
+steps:
+  - id: issues
+    type: mcp_tool
+    server: tracker
+    tool: search
+    arguments:
+      query: "{{ inputs.keywords }}"
+      limit: "{{ inputs.limit }}"
+"#,
+        )
+        .unwrap();
+        let inputs = HashMap::from([("keywords".to_string(), json!("crash")), ("limit".to_string(), json!(5))]);
+        let config = McpServerConfig::Http {
+            url: format!("{}/mcp", server.url()),
+            headers: HashMap::new(),
+        };
+        let executor = WorkflowExecutor::new(workflow, inputs)
+            .unwrap()
+            .with_mcp_server("tracker", Arc::new(McpClient::new(config)));
+
+        let results = executor.execute().await.unwrap();
+        call.assert_async().await;
+        assert_eq!(results["issues"].outputs["text"], "2 issues");
+        assert_eq!(results["issues"].outputs["structured"], json!({"count": 2}));
+    }
+}
@@ -0,0 +1,228 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! MCP transports: stdio and Streamable HTTP.
+
+use super::McpError;
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::process::Stdio;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStdin, Command};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tracing::debug;
+
+/// Header carrying the session ID of a Streamable HTTP connection.
+const SESSION_HEADER: &str = "mcp-session-id";
+
+/// Carries JSON-RPC messages to a server.
+#[async_trait]
+pub(crate) trait Transport: Send + Sync {
+    /// Sends the request `id` and waits for its response message.
+    async fn request(&self, id: u64, message: Value) -> Result<Value, McpError>;
+
+    /// Sends a notification, which has no response.
+    async fn notify(&self, message: Value) -> Result<(), McpError>;
+}
+
+fn transport_error(context: &str, error: impl std::fmt::Display) -> McpError {
+    McpError::Transport(format!("{}: {}", context, error))
+}
+
+/// Requests waiting for their response, by ID.
+type Pending = Arc<Mutex<HashMap<u64, oneshot::Sender<Value>>>>;
+
+/// A server process exchanging newline-delimited messages over stdin and stdout.
+pub(crate) struct StdioTransport {
+    stdin: Arc<tokio::sync::Mutex<ChildStdin>>,
+    pending: Pending,
+    reader: JoinHandle<()>,
+    // Killed when the transport is dropped
+    _child: Child,
+}
+
+impl StdioTransport {
+    /// Starts the server process.
+    pub(crate) fn spawn(command: &str, args: &[String], env: &HashMap<String, String>) -> Result<Self, McpError> {
+        let mut child = Command::new(command)
+            .args(args)
+            .envs(env)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| transport_error(&format!("Failed to start MCP server '{}'", command), e))?;
+        let stdin = Arc::new(tokio::sync::Mutex::new(child.stdin.take().expect("stdin is piped")));
+        let stdout = child.stdout.take().expect("stdout is piped");
+
+        let pending: Pending = Arc::default();
+        let reader = tokio::spawn({
+            let pending = pending.clone();
+            let stdin = stdin.clone();
+            async move {
+                let mut lines = BufReader::new(stdout).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    let Ok(message) = serde_json::from_str::<Value>(&line) else {
+                        debug!("Ignoring non-JSON output of MCP server: {}", line);
+                        continue;
+                    };
+                    match (message["id"].as_u64(), message.get("method")) {
+                        // A request from the server; only `ping` needs an answer from a client without capabilities
+                        (_, Some(method)) if message.get("id").is_some() => {
+                            let reply = if method == "ping" {
+                                json!({"jsonrpc": "2.0", "id": message["id"], "result": {}})
+                            } else {
+                                json!({"jsonrpc": "2.0", "id": message["id"], "error": {"code": -32601, "message": "Method not found"}})
+                            };
+                            let _ = write_line(&stdin, &reply).await;
+                        }
+                        (Some(id), None) => {
+                            if let Some(waiter) = pending.lock().remove(&id) {
+                                let _ = waiter.send(message);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                // The server exited: fail whatever is still waiting
+                pending.lock().clear();
+            }
+        });
+
+        Ok(Self {
+            stdin,
+            pending,
+            reader,
+            _child: child,
+        })
+    }
+}
+
+async fn write_line(stdin: &tokio::sync::Mutex<ChildStdin>, message: &Value) -> Result<(), McpError> {
+    let mut line = message.to_string();
+    line.push('\n');
+    let mut stdin = stdin.lock().await;
+    stdin
+        .write_all(line.as_bytes())
+        .await
+        .map_err(|e| transport_error("Failed to write to MCP server", e))?;
+    stdin.flush().await.map_err(|e| transport_error("Failed to write to MCP server", e))
+}
+
+impl Drop for StdioTransport {
+    fn drop(&mut self) {
+        self.reader.abort();
+    }
+}
+
+#[async_trait]
+impl Transport for StdioTransport {
+    async fn request(&self, id: u64, message: Value) -> Result<Value, McpError> {
+        let (sender, receiver) = oneshot::channel();
+        self.pending.lock().insert(id, sender);
+        if let Err(e) = write_line(&self.stdin, &message).await {
+            self.pending.lock().remove(&id);
+            return Err(e);
+        }
+        receiver
+            .await
+            .map_err(|_| McpError::Transport("MCP server exited before responding".to_string()))
+    }
+
+    async fn notify(&self, message: Value) -> Result<(), McpError> {
+        write_line(&self.stdin, &message).await
+    }
+}
+
+/// A Streamable HTTP endpoint, answering with JSON or a server-sent event stream.
+pub(crate) struct HttpTransport {
+    client: reqwest::Client,
+    url: String,
+    headers: HashMap<String, String>,
+    session: Mutex<Option<String>>,
+}
+
+impl HttpTransport {
+    pub(crate) fn new(url: &str, headers: &HashMap<String, String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: url.to_string(),
+            headers: headers.clone(),
+            session: Mutex::new(None),
+        }
+    }
+
+    async fn post(&self, message: &Value) -> Result<reqwest::Response, McpError> {
+        let mut request = self
+            .client
+            .post(&self.url)
+            .header("accept", "application/json, text/event-stream")
+            .json(message);
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+        if let Some(session) = self.session.lock().clone() {
+            request = request.header(SESSION_HEADER, session);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| transport_error("Failed to reach MCP server", e))?;
+        if let Some(session) = response.headers().get(SESSION_HEADER).and_then(|v| v.to_str().ok()) {
+            *self.session.lock() = Some(session.to_string());
+        }
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(McpError::Transport(format!("MCP server answered {}: {}", status, body)));
+        }
+        Ok(response)
+    }
+}
+
+#[async_trait]
+impl Transport for HttpTransport {
+    async fn request(&self, id: u64, message: Value) -> Result<Value, McpError> {
+        let response = self.post(&message).await?;
+        let streamed = response
+            .headers()
+            .get("content-type")
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.starts_with("text/event-stream"));
+        let body = response
+            .text()
+            .await
+            .map_err(|e| transport_error("Failed to read MCP response", e))?;
+
+        if !streamed {
+            return serde_json::from_str(&body).map_err(|e| transport_error("Invalid MCP response", e));
+        }
+        // The stream may carry server requests and notifications before the response
+        let response = sse_data(&body)
+            .filter_map(|data| serde_json::from_str::<Value>(&data).ok())
+            .find(|message| message["id"].as_u64() == Some(id) && message.get("method").is_none());
+        response.ok_or_else(|| McpError::Transport("MCP server closed the stream without responding".to_string()))
+    }
+
+    async fn notify(&self, message: Value) -> Result<(), McpError> {
+        self.post(&message).await.map(|_| ())
+    }
+}
+
+/// The data of each event in a server-sent event stream.
+fn sse_data(body: &str) -> impl Iterator<Item = String> + '_ {
+    body.split("\n\n").filter_map(|event| {
+        let data: Vec<&str> = event
+            .lines()
+            .filter_map(|line| line.strip_prefix("data:"))
+            .map(|data| data.strip_prefix(' ').unwrap_or(data))
+            .collect();
+        (!data.is_empty()).then(|| data.join("\n"))
+    })
+}
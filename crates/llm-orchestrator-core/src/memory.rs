@@ -0,0 +1,487 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Session memory.
+//!
+//! Keeps values across runs of a workflow, per session ID and key, so a
+//! multi-turn assistant can be built from a workflow that answers one message
+//! per run. Actions read and change memory:
+//!
+//! ```yaml
+//! steps:
+//!   - id: profile
+//!     type: action
+//!     action: memory.read
+//!     session: "{{ inputs.session_id }}"
+//!     key: profile
+//!     output: [profile]
+//!   - id: remember
+//!     type: action
+//!     action: memory.append
+//!     session: "{{ inputs.session_id }}"
+//!     key: topics
+//!     entries: ["{{ inputs.topic }}"]
+//! ```
+//!
+//! `memory.write` replaces a key's value with `value`. An LLM step with
+//! `memory` sends the conversation stored under its key before the prompt and,
+//! once the step succeeds, appends the prompt and the reply to it:
+//!
+//! ```yaml
+//!   - id: reply
+//!     type: llm
+//!     provider: openai
+//!     model: gpt-4o
+//!     prompt: "{{ inputs.message }}"
+//!     memory:
+//!       session: "{{ inputs.session_id }}"
+//!       last: 20
+//!     output: [text]
+//! ```
+//!
+//! With `compact`, long conversations are summarized; see [`compact`].
+//!
+//! Memory is kept by a [`MemoryStore`]: [`InMemoryMemoryStore`] within one
+//! process, [`StateMemoryStore`] in the state database (feature
+//! `state-persistence`), or [`redis::RedisMemoryStore`] (feature `redis`).
+
+use crate::error::{OrchestratorError, Result};
+use crate::executor::WorkflowExecutor;
+use crate::providers::ConversationTurn;
+use crate::workflow::{ActionConfig, LlmMemoryConfig, MemoryCompaction, Step};
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+pub mod compact;
+#[cfg(feature = "redis")]
+pub mod redis;
+
+/// Name of the action that reads a memory key.
+pub const MEMORY_READ_ACTION: &str = "memory.read";
+
+/// Name of the action that appends entries to a memory key.
+pub const MEMORY_APPEND_ACTION: &str = "memory.append";
+
+/// Name of the action that replaces a memory key's value.
+pub const MEMORY_WRITE_ACTION: &str = "memory.write";
+
+/// Whether `action` names a memory action.
+pub fn is_memory_action(action: &str) -> bool {
+    matches!(action, MEMORY_READ_ACTION | MEMORY_APPEND_ACTION | MEMORY_WRITE_ACTION)
+}
+
+/// Storage for session memory.
+#[async_trait]
+pub trait MemoryStore: Send + Sync {
+    /// Value stored under `key` in a session, if any.
+    async fn read(&self, session_id: &str, key: &str) -> Result<Option<Value>>;
+
+    /// Stores `value` under `key` in a session, replacing what was there.
+    async fn write(&self, session_id: &str, key: &str, value: Value) -> Result<()>;
+
+    /// Appends `entries` to the list stored under `key` in a session, starting
+    /// one if the key is unset, and returns the length of the list.
+    ///
+    /// Fails if the key holds something other than a list.
+    async fn append(&self, session_id: &str, key: &str, entries: Vec<Value>) -> Result<usize>;
+}
+
+fn not_a_list(session_id: &str, key: &str) -> OrchestratorError {
+    OrchestratorError::other(format!("Memory key '{}' of session '{}' does not hold a list", key, session_id))
+}
+
+/// Session memory held in this process, lost when it exits.
+#[derive(Debug, Default)]
+pub struct InMemoryMemoryStore {
+    values: Mutex<HashMap<(String, String), Value>>,
+}
+
+impl InMemoryMemoryStore {
+    /// Creates an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl MemoryStore for InMemoryMemoryStore {
+    async fn read(&self, session_id: &str, key: &str) -> Result<Option<Value>> {
+        Ok(self.values.lock().get(&(session_id.to_string(), key.to_string())).cloned())
+    }
+
+    async fn write(&self, session_id: &str, key: &str, value: Value) -> Result<()> {
+        self.values.lock().insert((session_id.to_string(), key.to_string()), value);
+        Ok(())
+    }
+
+    async fn append(&self, session_id: &str, key: &str, entries: Vec<Value>) -> Result<usize> {
+        let mut values = self.values.lock();
+        let value = values
+            .entry((session_id.to_string(), key.to_string()))
+            .or_insert_with(|| Value::Array(Vec::new()));
+        let Value::Array(list) = value else {
+            return Err(not_a_list(session_id, key));
+        };
+        list.extend(entries);
+        Ok(list.len())
+    }
+}
+
+/// Session memory kept in the state database (requires the `state-persistence` feature).
+#[cfg(feature = "state-persistence")]
+pub struct StateMemoryStore {
+    store: Arc<dyn llm_orchestrator_state::SessionMemoryStore>,
+}
+
+#[cfg(feature = "state-persistence")]
+impl StateMemoryStore {
+    /// Wraps a state store.
+    pub fn new(store: Arc<dyn llm_orchestrator_state::SessionMemoryStore>) -> Self {
+        Self { store }
+    }
+}
+
+#[cfg(feature = "state-persistence")]
+fn state_error(e: llm_orchestrator_state::StateStoreError) -> OrchestratorError {
+    OrchestratorError::other(e.to_string())
+}
+
+#[cfg(feature = "state-persistence")]
+#[async_trait]
+impl MemoryStore for StateMemoryStore {
+    async fn read(&self, session_id: &str, key: &str) -> Result<Option<Value>> {
+        self.store.get_session_memory(session_id, key).await.map_err(state_error)
+    }
+
+    async fn write(&self, session_id: &str, key: &str, value: Value) -> Result<()> {
+        self.store.put_session_memory(session_id, key, &value).await.map_err(state_error)
+    }
+
+    async fn append(&self, session_id: &str, key: &str, entries: Vec<Value>) -> Result<usize> {
+        self.store.append_session_memory(session_id, key, &entries).await.map_err(state_error)
+    }
+}
+
+/// Parameters of a `memory.read`, `memory.append`, or `memory.write` action.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MemoryActionConfig {
+    /// Session ID template.
+    pub session: String,
+
+    /// Memory key.
+    pub key: String,
+
+    /// Entries `memory.append` adds: JSON whose strings are templates.
+    #[serde(default)]
+    pub entries: Vec<Value>,
+
+    /// Value `memory.write` stores: JSON whose strings are templates.
+    #[serde(default)]
+    pub value: Option<Value>,
+
+    /// For `memory.read` of a list, return only this many of the most recent entries.
+    #[serde(default)]
+    pub last: Option<usize>,
+}
+
+impl MemoryActionConfig {
+    /// Reads the parameters of a memory action.
+    pub fn from_action(step_id: &str, config: &ActionConfig) -> Result<Self> {
+        let invalid = |reason: String| OrchestratorError::InvalidStepConfig {
+            step_id: step_id.to_string(),
+            reason: format!("Invalid {} action: {}", config.action, reason),
+        };
+        let params = Value::Object(config.params.clone().into_iter().collect());
+        let memory: Self = serde_json::from_value(params).map_err(|e| invalid(e.to_string()))?;
+        match config.action.as_str() {
+            MEMORY_APPEND_ACTION if memory.entries.is_empty() => Err(invalid("needs `entries`".to_string())),
+            MEMORY_WRITE_ACTION if memory.value.is_none() => Err(invalid("needs a `value`".to_string())),
+            _ => Ok(memory),
+        }
+    }
+}
+
+/// Keeps the most recent `last` entries of a list.
+fn most_recent(value: Value, last: Option<usize>) -> Value {
+    match (value, last) {
+        (Value::Array(mut list), Some(last)) if list.len() > last => Value::Array(list.split_off(list.len() - last)),
+        (value, _) => value,
+    }
+}
+
+/// Conversation turns an LLM step adds to memory once it succeeds.
+pub(crate) struct PendingTurns {
+    session_id: String,
+    key: String,
+    turns: Vec<Value>,
+    compact: Option<MemoryCompaction>,
+}
+
+impl WorkflowExecutor {
+    /// Keeps session memory for `memory.*` actions and LLM steps with `memory`.
+    pub fn with_memory_store(mut self, store: Arc<dyn MemoryStore>) -> Self {
+        self.memory_store = Some(store);
+        self
+    }
+
+    fn memory_store(&self, step: &Step) -> Result<&Arc<dyn MemoryStore>> {
+        self.memory_store.as_ref().ok_or_else(|| OrchestratorError::InvalidStepConfig {
+            step_id: step.id.clone(),
+            reason: "Step uses session memory but no memory store is configured".to_string(),
+        })
+    }
+
+    /// Renders a session ID template, which must not render empty.
+    async fn render_session_id(&self, step: &Step, template: &str) -> Result<String> {
+        self.resolve_template_secrets(template).await?;
+        let session_id = self.context.render_template(template)?;
+        if session_id.trim().is_empty() {
+            return Err(OrchestratorError::InvalidStepConfig {
+                step_id: step.id.clone(),
+                reason: format!("Memory session '{}' rendered empty", template),
+            });
+        }
+        Ok(session_id)
+    }
+
+    /// Runs a `memory.read`, `memory.append`, or `memory.write` action.
+    pub(crate) async fn execute_memory_action(
+        &self,
+        step: &Step,
+        config: &ActionConfig,
+    ) -> Result<HashMap<String, Value>> {
+        let memory = MemoryActionConfig::from_action(&step.id, config)?;
+        let store = self.memory_store(step)?;
+        let session_id = self.render_session_id(step, &memory.session).await?;
+
+        let (default_output, value) = match config.action.as_str() {
+            MEMORY_APPEND_ACTION => {
+                let Value::Array(entries) = self.render_json(Value::Array(memory.entries)).await? else {
+                    unreachable!("rendering keeps the shape of its input");
+                };
+                let len = store.append(&session_id, &memory.key, entries).await?;
+                info!(step_id = %step.id, key = %memory.key, len, "Appended to session memory");
+                ("length", Value::from(len))
+            }
+            MEMORY_WRITE_ACTION => {
+                let value = self.render_json(memory.value.unwrap_or_default()).await?;
+                store.write(&session_id, &memory.key, value.clone()).await?;
+                info!(step_id = %step.id, key = %memory.key, "Wrote session memory");
+                ("value", value)
+            }
+            _ => {
+                let value = store.read(&session_id, &memory.key).await?.unwrap_or(Value::Null);
+                ("value", most_recent(value, memory.last))
+            }
+        };
+
+        let name = step.output.first().map_or(default_output, String::as_str);
+        Ok(HashMap::from([(name.to_string(), value)]))
+    }
+
+    /// Reads the conversation an LLM step continues, as `(session ID, turns)`.
+    ///
+    /// Entries that are not `{role, content}` objects are left out.
+    pub(crate) async fn conversation_history(
+        &self,
+        step: &Step,
+        memory: &LlmMemoryConfig,
+    ) -> Result<(String, Vec<ConversationTurn>)> {
+        let store = self.memory_store(step)?;
+        let session_id = self.render_session_id(step, &memory.session).await?;
+        let stored = match store.read(&session_id, &memory.key).await? {
+            Some(Value::Array(entries)) => entries,
+            Some(_) => return Err(not_a_list(&session_id, &memory.key)),
+            None => Vec::new(),
+        };
+        let mut turns: Vec<ConversationTurn> = stored
+            .into_iter()
+            .filter_map(|entry| serde_json::from_value(entry).ok())
+            .collect();
+        if let Some(last) = memory.last {
+            turns.drain(..turns.len().saturating_sub(last));
+        }
+        Ok((session_id, turns))
+    }
+
+    /// Holds the prompt and reply of an LLM step until the step succeeds.
+    pub(crate) fn stage_conversation_turns(
+        &self,
+        step: &Step,
+        session_id: String,
+        memory: &LlmMemoryConfig,
+        prompt: &str,
+        reply: &str,
+    ) {
+        let turns = [("user", prompt), ("assistant", reply)]
+            .into_iter()
+            .map(|(role, content)| serde_json::json!({"role": role, "content": content}))
+            .collect();
+        self.pending_turns.insert(
+            step.id.clone(),
+            PendingTurns {
+                session_id,
+                key: memory.key.clone(),
+                turns,
+                compact: memory.compact.clone(),
+            },
+        );
+    }
+
+    /// Appends the turns staged by a step's successful attempt to its conversation,
+    /// then compacts the conversation if it outgrew its budget.
+    ///
+    /// A failed compaction leaves the conversation whole, to be compacted after
+    /// a later turn.
+    pub(crate) async fn record_conversation_turns(&self, step: &Step) -> Result<()> {
+        let Some((_, pending)) = self.pending_turns.remove(&step.id) else {
+            return Ok(());
+        };
+        let store = self.memory_store(step)?;
+        store.append(&pending.session_id, &pending.key, pending.turns).await?;
+        if let Some(compact) = &pending.compact {
+            if let Err(e) = self
+                .compact_conversation(store.as_ref(), &pending.session_id, &pending.key, compact)
+                .await
+            {
+                warn!(step_id = %step.id, key = %pending.key, error = %self.redact(e.to_string()), "Failed to compact conversation");
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::{CompletionRequest, CompletionResponse, LLMProvider, ProviderError};
+    use crate::workflow::Workflow;
+    use serde_json::json;
+
+    /// Provider that answers with the number of earlier turns it was sent.
+    #[derive(Default)]
+    struct CountingProvider {
+        requests: Mutex<Vec<CompletionRequest>>,
+    }
+
+    #[async_trait]
+    impl LLMProvider for CountingProvider {
+        async fn complete(&self, request: CompletionRequest) -> std::result::Result<CompletionResponse, ProviderError> {
+            let text = format!("seen {}", request.history.len());
+            self.requests.lock().push(request);
+            Ok(CompletionResponse {
+                text,
+                model: "gpt-4o".to_string(),
+                tokens_used: None,
+                usage: None,
+                tool_calls: Vec::new(),
+                metadata: HashMap::new(),
+            })
+        }
+
+        fn name(&self) -> &str {
+            "counting"
+        }
+    }
+
+    const CHAT: &str = r#"
+name: "chat"
+steps:
+  - id: "reply"
+    type: "llm"
+    provider: "openai"
+    model: "gpt-4o"
+    prompt: "{{ inputs.message }}"
+    memory:
+      session: "{{ inputs.session_id }}"
+      last: 2
+    output: ["text"]
+"#;
+
+    #[tokio::test]
+    async fn test_llm_step_continues_the_session_conversation() {
+        let store = Arc::new(InMemoryMemoryStore::new());
+        let provider = Arc::new(CountingProvider::default());
+        for message in ["Hi", "How are you?", "Bye"] {
+            let inputs = HashMap::from([
+                ("session_id".to_string(), json!("s1")),
+                ("message".to_string(), json!(message)),
+            ]);
+            WorkflowExecutor::new(Workflow::from_yaml(CHAT).unwrap(), inputs)
+                .unwrap()
+                .with_provider("openai", provider.clone())
+                .with_memory_store(store.clone())
+                .execute()
+                .await
+                .unwrap();
+        }
+
+        // Each run sees the turns of the runs before it, up to `last`
+        let requests = provider.requests.lock().clone();
+        let seen: Vec<usize> = requests.iter().map(|r| r.history.len()).collect();
+        assert_eq!(seen, vec![0, 2, 2]);
+        assert_eq!(requests[2].history[0].content, "How are you?");
+        assert_eq!(requests[2].history[1].content, "seen 2");
+
+        let conversation = store.read("s1", "conversation").await.unwrap().unwrap();
+        assert_eq!(conversation.as_array().unwrap().len(), 6);
+        assert_eq!(conversation[4], json!({"role": "user", "content": "Bye"}));
+    }
+
+    #[tokio::test]
+    async fn test_memory_actions() {
+        let workflow = Workflow::from_yaml(
+            r#"
+name: "remember"
+steps:
+  - id: "profile"
+    type: "action"
+    action: "memory.write"
+    session: "{{ inputs.user }}"
+    key: "profile"
+    value:
+      name: "{{ inputs.name }}"
+  - id: "remember"
+    type: "action"
+    action: "memory.append"
+    session: "{{ inputs.user }}"
+    key: "topics"
+    entries: ["{{ inputs.topic }}"]
+  - id: "recall"
+    type: "action"
+    action: "memory.read"
+    depends_on: ["profile", "remember"]
+    session: "{{ inputs.user }}"
+    key: "topics"
+    last: 1
+    output: ["topics"]
+"#,
+        )
+        .unwrap();
+        let store = Arc::new(InMemoryMemoryStore::new());
+        store.append("u1", "topics", vec![json!("rust")]).await.unwrap();
+        let inputs = HashMap::from([
+            ("user".to_string(), json!("u1")),
+            ("name".to_string(), json!("Ada")),
+            ("topic".to_string(), json!("go")),
+        ]);
+
+        let results = WorkflowExecutor::new(workflow, inputs)
+            .unwrap()
+            .with_memory_store(store.clone())
+            .execute()
+            .await
+            .unwrap();
+
+        assert_eq!(results["remember"].outputs["length"], json!(2));
+        assert_eq!(results["recall"].outputs["topics"], json!(["go"]));
+        assert_eq!(store.read("u1", "profile").await.unwrap(), Some(json!({"name": "Ada"})));
+        assert!(store.append("u1", "profile", vec![json!(1)]).await.is_err());
+    }
+}
@@ -0,0 +1,264 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Compaction of conversations kept in session memory.
+//!
+//! A long-lived session would send an ever longer conversation. With
+//! `compact`, once the stored conversation is estimated above `max_tokens`,
+//! the turns before the `keep_last` most recent ones are summarized by a
+//! sub-workflow and replaced with the summary:
+//!
+//! ```yaml
+//! memory:
+//!   session: "{{ inputs.session_id }}"
+//!   compact:
+//!     max_tokens: 3000
+//!     keep_last: 6                  # Default
+//!     workflow:
+//!       name: summarize
+//!       steps:
+//!         - id: summarize
+//!           type: llm
+//!           provider: openai
+//!           model: gpt-4o-mini
+//!           prompt: "Summarize this conversation:\n\n{{ inputs.transcript }}"
+//!           output: [summary]
+//! ```
+//!
+//! The workflow receives the `turns` to summarize and their `transcript`, one
+//! `role: content` line per turn. The first output of its last step is the
+//! summary, stored as a pair of turns in which the assistant recounts the
+//! conversation so far. Earlier summaries are summarized along with the turns
+//! after them, so the conversation stays near its budget however long the
+//! session runs.
+
+use crate::error::{OrchestratorError, Result};
+use crate::executor::WorkflowExecutor;
+use crate::memory::MemoryStore;
+use crate::workflow::MemoryCompaction;
+use futures::future::BoxFuture;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use tracing::info;
+
+/// Request that opens a summary in a compacted conversation.
+pub const SUMMARY_REQUEST: &str = "Summarize our conversation so far.";
+
+/// Approximate token count of conversation entries, at four characters a token.
+///
+/// Turns count their content; other entries count their JSON.
+pub fn estimate_tokens(entries: &[Value]) -> usize {
+    let chars: usize = entries
+        .iter()
+        .map(|entry| match entry.get("content") {
+            Some(Value::String(content)) => content.chars().count(),
+            _ => entry.to_string().chars().count(),
+        })
+        .sum();
+    chars.div_ceil(4)
+}
+
+/// One `role: content` line per turn.
+pub fn transcript(entries: &[Value]) -> String {
+    entries
+        .iter()
+        .map(|entry| match (entry.get("role").and_then(Value::as_str), entry.get("content")) {
+            (Some(role), Some(Value::String(content))) => format!("{}: {}", role, content),
+            _ => entry.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Number of leading entries to summarize, keeping at least `keep_last` and
+/// starting the kept ones at a user turn.
+fn summarized_len(entries: &[Value], keep_last: usize) -> usize {
+    let mut split = entries.len().saturating_sub(keep_last);
+    while split > 0 && entries[split].get("role").and_then(Value::as_str) == Some("assistant") {
+        split -= 1;
+    }
+    split
+}
+
+impl WorkflowExecutor {
+    /// Summarizes the older turns of a conversation that outgrew its budget.
+    ///
+    /// Returns whether the conversation was compacted. A conversation changed
+    /// while it was being summarized is left for a later compaction.
+    ///
+    /// Boxed, as the summarization run may itself compact a conversation.
+    pub(crate) fn compact_conversation<'a>(
+        &'a self,
+        store: &'a dyn MemoryStore,
+        session_id: &'a str,
+        key: &'a str,
+        compact: &'a MemoryCompaction,
+    ) -> BoxFuture<'a, Result<bool>> {
+        Box::pin(async move {
+            let Some(Value::Array(entries)) = store.read(session_id, key).await? else {
+                return Ok(false);
+            };
+            let tokens = estimate_tokens(&entries);
+            if tokens <= compact.max_tokens {
+                return Ok(false);
+            }
+            let older = &entries[..summarized_len(&entries, compact.keep_last)];
+            if older.is_empty() {
+                return Ok(false);
+            }
+
+            let inputs = HashMap::from([
+                ("turns".to_string(), Value::Array(older.to_vec())),
+                ("transcript".to_string(), Value::String(transcript(older))),
+            ]);
+            let results = self.sub_executor(compact.workflow.as_ref().clone(), inputs)?.execute().await?;
+            let summary = compact
+                .workflow
+                .steps
+                .last()
+                .and_then(|last| results.get(&last.id)?.outputs.get(last.output.first()?))
+                .ok_or_else(|| OrchestratorError::other("Summarization workflow produced no summary"))?;
+            let summary = match summary {
+                Value::String(text) => text.clone(),
+                other => other.to_string(),
+            };
+
+            // Keep turns appended while summarizing, unless the summarized ones changed
+            let Some(Value::Array(current)) = store.read(session_id, key).await? else {
+                return Ok(false);
+            };
+            if !current.starts_with(older) {
+                return Ok(false);
+            }
+            let mut compacted = vec![
+                json!({"role": "user", "content": SUMMARY_REQUEST}),
+                json!({"role": "assistant", "content": summary}),
+            ];
+            compacted.extend_from_slice(&current[older.len()..]);
+            let compacted_tokens = estimate_tokens(&compacted);
+            store.write(session_id, key, Value::Array(compacted)).await?;
+            info!(
+                key,
+                turns = older.len(),
+                tokens,
+                compacted_tokens,
+                "Compacted conversation"
+            );
+            Ok(true)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::InMemoryMemoryStore;
+    use crate::providers::{CompletionRequest, CompletionResponse, LLMProvider, ProviderError};
+    use crate::workflow::Workflow;
+    use async_trait::async_trait;
+    use parking_lot::Mutex;
+    use std::sync::Arc;
+
+    /// Provider that echoes the prompt, or summarizes by counting transcript lines.
+    #[derive(Default)]
+    struct EchoProvider {
+        summaries: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl LLMProvider for EchoProvider {
+        async fn complete(&self, request: CompletionRequest) -> std::result::Result<CompletionResponse, ProviderError> {
+            let text = match request.prompt.strip_prefix("Summarize:\n") {
+                Some(transcript) => {
+                    self.summaries.lock().push(transcript.to_string());
+                    format!("{} turns", transcript.lines().count())
+                }
+                None => request.prompt.clone(),
+            };
+            Ok(CompletionResponse {
+                text,
+                model: "m".to_string(),
+                tokens_used: None,
+                usage: None,
+                tool_calls: Vec::new(),
+                metadata: HashMap::new(),
+            })
+        }
+
+        fn name(&self) -> &str {
+            "echo"
+        }
+    }
+
+    const CHAT: &str = r#"
+name: "chat"
+steps:
+  - id: "reply"
+    type: "llm"
+    provider: "echo"
+    model: "m"
+    prompt: "{{ inputs.message }}"
+    memory:
+      session: "s1"
+      compact:
+        max_tokens: 10
+        keep_last: 2
+        workflow:
+          name: "summarize"
+          steps:
+            - id: "summarize"
+              type: "llm"
+              provider: "echo"
+              model: "m"
+              prompt: "Summarize:\n{{ inputs.transcript }}"
+              output: ["summary"]
+    output: ["text"]
+"#;
+
+    #[tokio::test]
+    async fn test_conversation_over_budget_is_summarized() {
+        let store = Arc::new(InMemoryMemoryStore::new());
+        let provider = Arc::new(EchoProvider::default());
+        for message in ["first message", "second message", "third message"] {
+            let inputs = HashMap::from([("message".to_string(), json!(message))]);
+            WorkflowExecutor::new(Workflow::from_yaml(CHAT).unwrap(), inputs)
+                .unwrap()
+                .with_provider("echo", provider.clone())
+                .with_memory_store(store.clone())
+                .execute()
+                .await
+                .unwrap();
+        }
+
+        // The first exchange fits; each later one pushes the older turns into a summary
+        let summaries = provider.summaries.lock().clone();
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(summaries[0], "user: first message\nassistant: first message");
+        assert!(summaries[1].starts_with("user: Summarize our conversation so far.\nassistant: 2 turns\n"));
+
+        let conversation = store.read("s1", "conversation").await.unwrap().unwrap();
+        assert_eq!(
+            conversation,
+            json!([
+                {"role": "user", "content": SUMMARY_REQUEST},
+                {"role": "assistant", "content": "4 turns"},
+                {"role": "user", "content": "third message"},
+                {"role": "assistant", "content": "third message"},
+            ])
+        );
+    }
+
+    #[test]
+    fn test_kept_turns_start_at_a_user_turn() {
+        let entries = vec![
+            json!({"role": "user", "content": "a"}),
+            json!({"role": "assistant", "content": "b"}),
+            json!({"role": "user", "content": "c"}),
+            json!({"role": "assistant", "content": "d"}),
+        ];
+        assert_eq!(summarized_len(&entries, 2), 2);
+        assert_eq!(summarized_len(&entries, 1), 2);
+        assert_eq!(summarized_len(&entries, 4), 0);
+        assert_eq!(estimate_tokens(&entries), 1);
+    }
+}
@@ -0,0 +1,147 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Redis session memory (requires the `redis` feature).
+
+use super::{not_a_list, MemoryStore};
+use crate::error::{OrchestratorError, Result};
+use async_trait::async_trait;
+use redis::aio::MultiplexedConnection;
+use redis::{AsyncCommands, ErrorKind, RedisError};
+use serde_json::Value;
+use std::time::Duration;
+
+fn redis_error(context: &str, error: impl std::fmt::Display) -> OrchestratorError {
+    OrchestratorError::other(format!("{}: {}", context, error))
+}
+
+/// Session memory kept in Redis.
+///
+/// Each key of a session is a Redis key named `{prefix}{session_id}:{key}`,
+/// holding a list of JSON entries for a list and a JSON string otherwise.
+/// Writing an empty list removes the key.
+#[derive(Clone)]
+pub struct RedisMemoryStore {
+    connection: MultiplexedConnection,
+    prefix: String,
+    ttl: Option<Duration>,
+}
+
+impl RedisMemoryStore {
+    /// Connects to the Redis server at `url`, e.g. `redis://localhost:6379/0`.
+    pub async fn connect(url: &str) -> Result<Self> {
+        let client = redis::Client::open(url).map_err(|e| redis_error("Invalid Redis URL", e))?;
+        let connection = client
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| redis_error("Failed to connect to Redis", e))?;
+        Ok(Self {
+            connection,
+            prefix: "llm-orchestrator:memory:".to_string(),
+            ttl: None,
+        })
+    }
+
+    /// Prefixes the Redis keys of session memory with `prefix`.
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// Expires a memory key once it has not been changed for `ttl`.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    fn redis_key(&self, session_id: &str, key: &str) -> String {
+        format!("{}{}:{}", self.prefix, session_id, key)
+    }
+
+    async fn refresh_ttl(&self, connection: &mut MultiplexedConnection, redis_key: &str) -> Result<()> {
+        if let Some(ttl) = self.ttl {
+            let _: bool = connection
+                .expire(redis_key, ttl.as_secs().max(1) as i64)
+                .await
+                .map_err(|e| redis_error("Failed to set Redis key expiry", e))?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_entry(raw: &str) -> Result<Value> {
+    serde_json::from_str(raw).map_err(|e| redis_error("Invalid JSON in Redis session memory", e))
+}
+
+fn is_wrong_type(error: &RedisError) -> bool {
+    error.kind() == ErrorKind::TypeError || error.code() == Some("WRONGTYPE")
+}
+
+#[async_trait]
+impl MemoryStore for RedisMemoryStore {
+    async fn read(&self, session_id: &str, key: &str) -> Result<Option<Value>> {
+        let redis_key = self.redis_key(session_id, key);
+        let mut connection = self.connection.clone();
+        let kind: String = redis::cmd("TYPE")
+            .arg(&redis_key)
+            .query_async(&mut connection)
+            .await
+            .map_err(|e| redis_error("Failed to read Redis session memory", e))?;
+        match kind.as_str() {
+            "none" => Ok(None),
+            "list" => {
+                let entries: Vec<String> = connection
+                    .lrange(&redis_key, 0, -1)
+                    .await
+                    .map_err(|e| redis_error("Failed to read Redis session memory", e))?;
+                let entries = entries.iter().map(|raw| parse_entry(raw)).collect::<Result<_>>()?;
+                Ok(Some(Value::Array(entries)))
+            }
+            _ => {
+                let raw: Option<String> = connection
+                    .get(&redis_key)
+                    .await
+                    .map_err(|e| redis_error("Failed to read Redis session memory", e))?;
+                raw.as_deref().map(parse_entry).transpose()
+            }
+        }
+    }
+
+    async fn write(&self, session_id: &str, key: &str, value: Value) -> Result<()> {
+        let redis_key = self.redis_key(session_id, key);
+        let mut connection = self.connection.clone();
+        let mut pipe = redis::pipe();
+        pipe.atomic().del(&redis_key).ignore();
+        match value {
+            Value::Array(entries) if entries.is_empty() => {}
+            Value::Array(entries) => {
+                let entries: Vec<String> = entries.iter().map(Value::to_string).collect();
+                pipe.rpush(&redis_key, entries).ignore();
+            }
+            value => {
+                pipe.set(&redis_key, value.to_string()).ignore();
+            }
+        }
+        if let Some(ttl) = self.ttl {
+            pipe.expire(&redis_key, ttl.as_secs().max(1) as i64).ignore();
+        }
+        pipe.query_async::<()>(&mut connection)
+            .await
+            .map_err(|e| redis_error("Failed to write Redis session memory", e))
+    }
+
+    async fn append(&self, session_id: &str, key: &str, entries: Vec<Value>) -> Result<usize> {
+        let redis_key = self.redis_key(session_id, key);
+        let mut connection = self.connection.clone();
+        let entries: Vec<String> = entries.iter().map(Value::to_string).collect();
+        let len: usize = connection.rpush(&redis_key, entries).await.map_err(|e| {
+            if is_wrong_type(&e) {
+                not_a_list(session_id, key)
+            } else {
+                redis_error("Failed to append to Redis session memory", e)
+            }
+        })?;
+        self.refresh_ttl(&mut connection, &redis_key).await?;
+        Ok(len)
+    }
+}
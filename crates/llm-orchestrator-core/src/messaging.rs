@@ -0,0 +1,487 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Message broker integration.
+//!
+//! A workflow with a `kafka` or `nats` trigger is started for every message
+//! on a topic (a NATS JetStream subject), with the message as input:
+//!
+//! ```yaml
+//! name: triage-ticket
+//! triggers:
+//!   - type: kafka
+//!     topic: support-tickets
+//!     group: triage
+//!     inputs:
+//!       ticket: "{{ message.ticket }}"
+//!       priority: "{{ headers.priority }}"
+//! steps:
+//!   - id: notify
+//!     type: action
+//!     action: publish
+//!     sink: kafka
+//!     topic: triaged-tickets
+//!     key: "{{ inputs.ticket.id }}"
+//!     message:
+//!       ticket: "{{ inputs.ticket.id }}"
+//!       summary: "{{ steps.summarize.text }}"
+//! ```
+//!
+//! Delivery is at least once. An [`EventSourceRunner`] acknowledges a
+//! message only after its run is queued, and the run ID is derived from the
+//! message's position in the topic, so a redelivered message maps to the run
+//! already queued instead of starting another. A `publish` action stamps its
+//! message with the idempotency key `{run_id}/{step_id}`, which stays the same
+//! when the step is retried or the run resumed, so consumers (and NATS, which
+//! deduplicates on `Nats-Msg-Id`) can discard repeats.
+//!
+//! Brokers are reached through the [`MessageSource`] and [`MessageSink`]
+//! traits; Kafka (feature `kafka`) and NATS JetStream (feature `nats`)
+//! implementations are included.
+
+use crate::context::ExecutionContext;
+use crate::error::{OrchestratorError, Result};
+use crate::executor::WorkflowExecutor;
+use crate::workflow::{ActionConfig, Step};
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::info;
+
+#[cfg(feature = "kafka")]
+pub mod kafka;
+#[cfg(feature = "nats")]
+pub mod nats;
+
+/// Name of the action that publishes a message.
+pub const PUBLISH_ACTION: &str = "publish";
+
+/// Header carrying the idempotency key of a published message.
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// Default output names, in the order outputs are assigned.
+const DEFAULT_OUTPUTS: [&str; 1] = ["message_id"];
+
+/// A message received from a broker.
+#[derive(Debug, Clone)]
+pub struct Message {
+    /// Position of the message in its topic, unique per broker (e.g. `orders/2/1041`).
+    pub id: String,
+    /// Topic or subject the message was received on.
+    pub topic: String,
+    /// Message key, if the broker has keys.
+    pub key: Option<String>,
+    /// Message body.
+    pub payload: Vec<u8>,
+    /// Message headers.
+    pub headers: HashMap<String, String>,
+}
+
+/// Acknowledges a received message to its broker.
+#[async_trait]
+pub trait Acknowledge: Send {
+    /// Marks the message as processed so it is not delivered again.
+    async fn ack(self: Box<Self>) -> Result<()>;
+}
+
+/// A received message waiting to be acknowledged.
+pub struct Delivery {
+    /// The message.
+    pub message: Message,
+    acker: Box<dyn Acknowledge>,
+}
+
+impl Delivery {
+    /// Creates a delivery acknowledged by `acker`.
+    pub fn new(message: Message, acker: Box<dyn Acknowledge>) -> Self {
+        Self { message, acker }
+    }
+
+    /// Acknowledges the message.
+    pub async fn ack(self) -> Result<()> {
+        self.acker.ack().await
+    }
+}
+
+/// A subscription delivering messages from a broker.
+#[async_trait]
+pub trait MessageSource: Send {
+    /// Waits for the next message. Returns `None` once the subscription ends.
+    async fn next(&mut self) -> Result<Option<Delivery>>;
+}
+
+/// A broker that `publish` actions send messages to.
+#[async_trait]
+pub trait MessageSink: Send + Sync {
+    /// Publishes `payload` to `topic`, waiting until the broker has accepted it.
+    async fn publish(&self, topic: &str, key: Option<&str>, payload: Vec<u8>, idempotency_key: &str) -> Result<()>;
+}
+
+/// A topic whose messages start runs of the workflow.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TopicTrigger {
+    /// Kafka topic or NATS subject.
+    #[serde(alias = "subject")]
+    pub topic: String,
+
+    /// Consumer group (Kafka) or durable consumer (NATS); defaults to the workflow name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+
+    /// Workflow input templates, keyed by input name.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub inputs: HashMap<String, String>,
+}
+
+impl TopicTrigger {
+    /// Checks that the trigger can be consumed.
+    pub fn validate(&self) -> Result<()> {
+        if self.topic.is_empty() {
+            return Err(OrchestratorError::validation("Message trigger needs a topic"));
+        }
+        Ok(())
+    }
+
+    /// Maps a message into workflow inputs.
+    ///
+    /// Templates see the `message` body (parsed as JSON when possible), its
+    /// `topic`, `key`, and `headers`. Without `inputs`, the body is passed as
+    /// the `message` input.
+    pub fn map_inputs(&self, message: &Message) -> Result<HashMap<String, Value>> {
+        let body = serde_json::from_slice(&message.payload)
+            .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(&message.payload).into_owned()));
+        if self.inputs.is_empty() {
+            return Ok(HashMap::from([("message".to_string(), body)]));
+        }
+
+        let headers: serde_json::Map<String, Value> = message
+            .headers
+            .iter()
+            .map(|(name, value)| (name.clone(), Value::String(value.clone())))
+            .collect();
+        let context = ExecutionContext::new(HashMap::from([
+            ("message".to_string(), body),
+            ("topic".to_string(), Value::String(message.topic.clone())),
+            ("key".to_string(), message.key.clone().map_or(Value::Null, Value::String)),
+            ("headers".to_string(), Value::Object(headers)),
+        ]));
+
+        let mut inputs = HashMap::new();
+        for (input, template) in &self.inputs {
+            inputs.insert(input.clone(), context.render_value(template)?);
+        }
+        Ok(inputs)
+    }
+}
+
+/// Parameters of a `publish` action.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PublishConfig {
+    /// Name the sink was registered under with [`WorkflowExecutor::with_message_sink`].
+    pub sink: String,
+
+    /// Topic or subject template.
+    pub topic: String,
+
+    /// Message key template (Kafka only).
+    #[serde(default)]
+    pub key: Option<String>,
+
+    /// Message body: a template, or JSON whose strings are templates.
+    pub message: Value,
+}
+
+impl PublishConfig {
+    /// Reads the parameters of a `publish` action.
+    pub fn from_action(step_id: &str, config: &ActionConfig) -> Result<Self> {
+        let params = Value::Object(config.params.clone().into_iter().collect());
+        serde_json::from_value(params).map_err(|e| OrchestratorError::InvalidStepConfig {
+            step_id: step_id.to_string(),
+            reason: format!("Invalid publish action: {}", e),
+        })
+    }
+}
+
+impl WorkflowExecutor {
+    /// Registers a broker that `publish` actions can name as their `sink`.
+    pub fn with_message_sink(self, name: impl Into<String>, sink: Arc<dyn MessageSink>) -> Self {
+        self.message_sinks.insert(name.into(), sink);
+        self
+    }
+
+    /// Publishes the message of a `publish` action.
+    pub(crate) async fn execute_publish_action(
+        &self,
+        step: &Step,
+        config: &ActionConfig,
+    ) -> Result<HashMap<String, Value>> {
+        let publish = PublishConfig::from_action(&step.id, config)?;
+        let sink = self
+            .message_sinks
+            .get(&publish.sink)
+            .map(|sink| sink.value().clone())
+            .ok_or_else(|| OrchestratorError::InvalidStepConfig {
+                step_id: step.id.clone(),
+                reason: format!("No message sink registered as '{}'", publish.sink),
+            })?;
+
+        let topic = self.context.render_template(&publish.topic)?;
+        let key = publish
+            .key
+            .as_deref()
+            .map(|key| self.context.render_template(key))
+            .transpose()?;
+        let payload = match publish.message {
+            Value::String(template) => self.context.render_template(&template)?.into_bytes(),
+            message => serde_json::to_vec(&self.render_json(message).await?)?,
+        };
+
+        let idempotency_key = format!("{}/{}", self.run_id, step.id);
+        info!(step_id = %step.id, sink = %publish.sink, topic = %topic, "Publishing message");
+        sink.publish(&topic, key.as_deref(), payload, &idempotency_key).await?;
+
+        let names: Vec<&str> = if step.output.is_empty() {
+            DEFAULT_OUTPUTS.to_vec()
+        } else {
+            step.output.iter().map(String::as_str).collect()
+        };
+        Ok(names.into_iter().map(String::from).zip([Value::String(idempotency_key)]).collect())
+    }
+}
+
+/// Queues a run of the workflow for every message of a source (requires the `state-persistence` feature).
+#[cfg(feature = "state-persistence")]
+pub struct EventSourceRunner {
+    source: Box<dyn MessageSource>,
+    queue: Arc<dyn llm_orchestrator_state::WorkQueue>,
+    workflow: crate::workflow::Workflow,
+    trigger: TopicTrigger,
+}
+
+#[cfg(feature = "state-persistence")]
+impl EventSourceRunner {
+    /// Creates a runner queueing runs of `workflow` for the messages `trigger` describes.
+    pub fn new(
+        source: Box<dyn MessageSource>,
+        queue: Arc<dyn llm_orchestrator_state::WorkQueue>,
+        workflow: crate::workflow::Workflow,
+        trigger: TopicTrigger,
+    ) -> Self {
+        Self {
+            source,
+            queue,
+            workflow,
+            trigger,
+        }
+    }
+
+    /// Consumes messages until the source ends or a run cannot be queued.
+    ///
+    /// A message that cannot be mapped into inputs is logged and acknowledged,
+    /// so it does not block the messages behind it.
+    pub async fn run(self) -> Result<()> {
+        // Sources need not be `Sync`, so the source is split off to keep the future `Send`
+        let Self { mut source, queue, workflow, trigger } = self;
+        while let Some(delivery) = source.next().await? {
+            queue_run(queue.as_ref(), &workflow, &trigger, delivery).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Queues the run of one message, then acknowledges it.
+#[cfg(feature = "state-persistence")]
+async fn queue_run(
+    queue: &dyn llm_orchestrator_state::WorkQueue,
+    workflow: &crate::workflow::Workflow,
+    trigger: &TopicTrigger,
+    delivery: Delivery,
+) -> Result<()> {
+    let message = &delivery.message;
+    match trigger.map_inputs(message) {
+        Ok(inputs) => {
+            let key = format!("{}:{}", workflow.name, message.id);
+            crate::worker::submit_run_idempotent(queue, workflow, inputs, &key).await?;
+        }
+        Err(e) => {
+            tracing::warn!(message_id = %message.id, topic = %message.topic, "Dropping message: {}", e);
+        }
+    }
+    delivery.ack().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workflow::Workflow;
+    use parking_lot::Mutex;
+    use serde_json::json;
+
+    /// Topic, key, body, and idempotency key of a published message.
+    type Published = (String, Option<String>, Value, String);
+
+    /// Records published messages.
+    #[derive(Default)]
+    struct RecordingSink(Mutex<Vec<Published>>);
+
+    #[async_trait]
+    impl MessageSink for RecordingSink {
+        async fn publish(&self, topic: &str, key: Option<&str>, payload: Vec<u8>, idempotency_key: &str) -> Result<()> {
+            let payload = serde_json::from_slice(&payload).unwrap_or_else(|_| json!(String::from_utf8(payload).unwrap()));
+            self.0
+                .lock()
+                .push((topic.to_string(), key.map(String::from), payload, idempotency_key.to_string()));
+            Ok(())
+        }
+    }
+
+    fn message(payload: &str) -> Message {
+        Message {
+            id: "tickets/0/7".to_string(),
+            topic: "tickets".to_string(),
+            key: Some("t-1".to_string()),
+            payload: payload.as_bytes().to_vec(),
+            headers: HashMap::from([("priority".to_string(), "high".to_string())]),
+        }
+    }
+
+    #[test]
+    fn test_map_inputs() {
+        let trigger: TopicTrigger = serde_yaml::from_str(
+            r#"
+subject: tickets
+inputs:
+  ticket: "{{ message.ticket }}"
+  line: "{{ key }} on {{ topic }} ({{ headers.priority }})"
+"#,
+        )
+        .unwrap();
+        let inputs = trigger.map_inputs(&message(r#"{"ticket": {"id": 1}}"#)).unwrap();
+        assert_eq!(inputs["ticket"], json!({"id": 1}));
+        assert_eq!(inputs["line"], "t-1 on tickets (high)");
+
+        // Without a mapping, the body is the `message` input, as text when it is not JSON
+        let trigger: TopicTrigger = serde_yaml::from_str("topic: tickets").unwrap();
+        assert_eq!(trigger.map_inputs(&message("plain text")).unwrap()["message"], "plain text");
+    }
+
+    #[tokio::test]
+    async fn test_publish_action() {
+        let yaml = r#"
+name: publish
+steps:
+  - id: notify
+    type: action
+    action: publish
+    sink: events
+    topic: "tickets.{{ inputs.team }}"
+    key: "{{ inputs.team }}"
+    message:
+      team: "{{ inputs.team }}"
+      tags: ["{{ inputs.team }}", "new"]
+  - id: raw
+    type: action
+    action: publish
+    sink: events
+    topic: raw
+    message: "hello {{ inputs.team }}"
+    output: [id]
+"#;
+        let sink = Arc::new(RecordingSink::default());
+        let inputs = HashMap::from([("team".to_string(), json!("core"))]);
+        let executor = WorkflowExecutor::new(Workflow::from_yaml(yaml).unwrap(), inputs)
+            .unwrap()
+            .with_message_sink("events", sink.clone());
+        let run_id = executor.run_id();
+        let results = executor.execute().await.unwrap();
+
+        let published = sink.0.lock();
+        let notify = published.iter().find(|p| p.0 == "tickets.core").unwrap();
+        assert_eq!(notify.1.as_deref(), Some("core"));
+        assert_eq!(notify.2, json!({"team": "core", "tags": ["core", "new"]}));
+        assert_eq!(notify.3, format!("{}/notify", run_id));
+        assert_eq!(results["notify"].outputs["message_id"], json!(notify.3));
+
+        let raw = published.iter().find(|p| p.0 == "raw").unwrap();
+        assert_eq!(raw.2, json!("hello core"));
+        assert_eq!(results["raw"].outputs["id"], json!(format!("{}/raw", run_id)));
+    }
+
+    #[tokio::test]
+    async fn test_publish_requires_registered_sink() {
+        let yaml = r#"
+name: publish
+steps:
+  - id: notify
+    type: action
+    action: publish
+    sink: missing
+    topic: t
+    message: "x"
+"#;
+        let executor = WorkflowExecutor::new(Workflow::from_yaml(yaml).unwrap(), HashMap::new()).unwrap();
+        assert!(executor.execute().await.is_err());
+    }
+
+    #[cfg(feature = "state-persistence")]
+    #[tokio::test]
+    async fn test_runner_queues_each_message_once() {
+        use llm_orchestrator_state::SqliteStateStore;
+        use std::collections::VecDeque;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        struct Replay(VecDeque<Message>, Arc<AtomicUsize>);
+
+        struct CountAck(Arc<AtomicUsize>);
+
+        #[async_trait]
+        impl Acknowledge for CountAck {
+            async fn ack(self: Box<Self>) -> Result<()> {
+                self.0.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            }
+        }
+
+        #[async_trait]
+        impl MessageSource for Replay {
+            async fn next(&mut self) -> Result<Option<Delivery>> {
+                Ok(self
+                    .0
+                    .pop_front()
+                    .map(|m| Delivery::new(m, Box::new(CountAck(self.1.clone())))))
+            }
+        }
+
+        let store = Arc::new(SqliteStateStore::new(":memory:").await.unwrap());
+        let workflow = Workflow::from_yaml(
+            "name: consume\nsteps:\n  - id: s\n    type: transform\n    function: noop\n    inputs: []\n",
+        )
+        .unwrap();
+        let trigger: TopicTrigger = serde_yaml::from_str("topic: tickets").unwrap();
+
+        // The first message is redelivered, as after a crash before its acknowledgement
+        let mut second = message("{}");
+        second.id = "tickets/0/8".to_string();
+        let acks = Arc::new(AtomicUsize::new(0));
+        let source = Replay(VecDeque::from([message("{}"), message("{}"), second]), acks.clone());
+        EventSourceRunner::new(Box::new(source), store.clone(), workflow, trigger)
+            .run()
+            .await
+            .unwrap();
+
+        assert_eq!(acks.load(Ordering::SeqCst), 3);
+        let mut runs = 0;
+        while llm_orchestrator_state::WorkQueue::claim_task(store.as_ref(), "w", std::time::Duration::from_secs(30))
+            .await
+            .unwrap()
+            .is_some()
+        {
+            runs += 1;
+        }
+        assert_eq!(runs, 2);
+    }
+}
@@ -0,0 +1,141 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Kafka source and sink (requires the `kafka` feature).
+
+use super::{Acknowledge, Delivery, Message, MessageSink, MessageSource, IDEMPOTENCY_KEY_HEADER};
+use crate::error::{OrchestratorError, Result};
+use async_trait::async_trait;
+use rdkafka::config::ClientConfig;
+use rdkafka::consumer::{CommitMode, Consumer, StreamConsumer};
+use rdkafka::message::{Header, Headers, OwnedHeaders};
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use rdkafka::{Message as _, Offset, TopicPartitionList};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How long a message may wait in the producer queue before publishing fails.
+const PUBLISH_TIMEOUT: Duration = Duration::from_secs(30);
+
+fn kafka_error(context: &str, error: impl std::fmt::Display) -> OrchestratorError {
+    OrchestratorError::other(format!("{}: {}", context, error))
+}
+
+/// Consumes a topic as a member of a consumer group.
+///
+/// Offsets are committed only when a message is acknowledged; a group with no
+/// committed offset starts from the earliest message.
+pub struct KafkaSource {
+    consumer: Arc<StreamConsumer>,
+}
+
+impl KafkaSource {
+    /// Subscribes to `topic` on the comma-separated `brokers`.
+    pub fn new(brokers: &str, group: &str, topic: &str) -> Result<Self> {
+        let consumer: StreamConsumer = ClientConfig::new()
+            .set("bootstrap.servers", brokers)
+            .set("group.id", group)
+            .set("enable.auto.commit", "false")
+            .set("auto.offset.reset", "earliest")
+            .create()
+            .map_err(|e| kafka_error("Failed to create Kafka consumer", e))?;
+        consumer
+            .subscribe(&[topic])
+            .map_err(|e| kafka_error("Failed to subscribe to Kafka topic", e))?;
+        Ok(Self {
+            consumer: Arc::new(consumer),
+        })
+    }
+}
+
+/// Commits the offset after an acknowledged message.
+struct KafkaAck {
+    consumer: Arc<StreamConsumer>,
+    topic: String,
+    partition: i32,
+    offset: i64,
+}
+
+#[async_trait]
+impl Acknowledge for KafkaAck {
+    async fn ack(self: Box<Self>) -> Result<()> {
+        let mut offsets = TopicPartitionList::new();
+        offsets
+            .add_partition_offset(&self.topic, self.partition, Offset::Offset(self.offset + 1))
+            .map_err(|e| kafka_error("Invalid Kafka offset", e))?;
+        self.consumer
+            .commit(&offsets, CommitMode::Async)
+            .map_err(|e| kafka_error("Failed to commit Kafka offset", e))
+    }
+}
+
+#[async_trait]
+impl MessageSource for KafkaSource {
+    async fn next(&mut self) -> Result<Option<Delivery>> {
+        let received = self
+            .consumer
+            .recv()
+            .await
+            .map_err(|e| kafka_error("Failed to receive Kafka message", e))?;
+
+        let headers = received
+            .headers()
+            .map(|headers| {
+                headers
+                    .iter()
+                    .filter_map(|h| Some((h.key.to_string(), String::from_utf8(h.value?.to_vec()).ok()?)))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let message = Message {
+            id: format!("{}/{}/{}", received.topic(), received.partition(), received.offset()),
+            topic: received.topic().to_string(),
+            key: received.key().map(|key| String::from_utf8_lossy(key).into_owned()),
+            payload: received.payload().unwrap_or_default().to_vec(),
+            headers,
+        };
+        let acker = KafkaAck {
+            consumer: self.consumer.clone(),
+            topic: message.topic.clone(),
+            partition: received.partition(),
+            offset: received.offset(),
+        };
+        Ok(Some(Delivery::new(message, Box::new(acker))))
+    }
+}
+
+/// Publishes to Kafka with an idempotent producer.
+pub struct KafkaSink {
+    producer: FutureProducer,
+}
+
+impl KafkaSink {
+    /// Connects to the comma-separated `brokers`.
+    pub fn new(brokers: &str) -> Result<Self> {
+        let producer = ClientConfig::new()
+            .set("bootstrap.servers", brokers)
+            .set("enable.idempotence", "true")
+            .create()
+            .map_err(|e| kafka_error("Failed to create Kafka producer", e))?;
+        Ok(Self { producer })
+    }
+}
+
+#[async_trait]
+impl MessageSink for KafkaSink {
+    async fn publish(&self, topic: &str, key: Option<&str>, payload: Vec<u8>, idempotency_key: &str) -> Result<()> {
+        let headers = OwnedHeaders::new().insert(Header {
+            key: IDEMPOTENCY_KEY_HEADER,
+            value: Some(idempotency_key),
+        });
+        let mut record = FutureRecord::to(topic).payload(&payload).headers(headers);
+        if let Some(key) = key {
+            record = record.key(key);
+        }
+        self.producer
+            .send(record, PUBLISH_TIMEOUT)
+            .await
+            .map_err(|(e, _)| kafka_error("Failed to publish Kafka message", e))?;
+        Ok(())
+    }
+}
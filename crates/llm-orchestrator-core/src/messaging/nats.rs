@@ -0,0 +1,135 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! NATS JetStream source and sink (requires the `nats` feature).
+
+use super::{Acknowledge, Delivery, Message, MessageSink, MessageSource, IDEMPOTENCY_KEY_HEADER};
+use crate::error::{OrchestratorError, Result};
+use async_nats::jetstream::{self, consumer::pull, consumer::AckPolicy};
+use async_nats::HeaderMap;
+use async_trait::async_trait;
+use futures::StreamExt;
+
+/// Header NATS deduplicates published messages on.
+const MSG_ID_HEADER: &str = "Nats-Msg-Id";
+
+fn nats_error(context: &str, error: impl std::fmt::Display) -> OrchestratorError {
+    OrchestratorError::other(format!("{}: {}", context, error))
+}
+
+/// Consumes a subject through a durable JetStream pull consumer.
+///
+/// The subject must belong to a stream. Messages are acknowledged explicitly,
+/// so unacknowledged messages are redelivered after the ack wait.
+pub struct NatsSource {
+    messages: pull::Stream,
+}
+
+impl NatsSource {
+    /// Consumes `subject` on the server at `url` as the durable consumer `durable`.
+    pub async fn connect(url: &str, durable: &str, subject: &str) -> Result<Self> {
+        let client = async_nats::connect(url)
+            .await
+            .map_err(|e| nats_error("Failed to connect to NATS", e))?;
+        let jetstream = jetstream::new(client);
+        let stream_name = jetstream
+            .stream_by_subject(subject)
+            .await
+            .map_err(|e| nats_error(&format!("No JetStream stream holds subject '{}'", subject), e))?;
+        let stream = jetstream
+            .get_stream(&stream_name)
+            .await
+            .map_err(|e| nats_error("Failed to open JetStream stream", e))?;
+        let consumer = stream
+            .get_or_create_consumer(
+                durable,
+                pull::Config {
+                    durable_name: Some(durable.to_string()),
+                    filter_subject: subject.to_string(),
+                    ack_policy: AckPolicy::Explicit,
+                    ..Default::default()
+                },
+            )
+            .await
+            .map_err(|e| nats_error("Failed to create JetStream consumer", e))?;
+        let messages = consumer
+            .messages()
+            .await
+            .map_err(|e| nats_error("Failed to consume JetStream messages", e))?;
+        Ok(Self { messages })
+    }
+}
+
+/// Acknowledges a JetStream message.
+struct NatsAck(jetstream::Message);
+
+#[async_trait]
+impl Acknowledge for NatsAck {
+    async fn ack(self: Box<Self>) -> Result<()> {
+        self.0.ack().await.map_err(|e| nats_error("Failed to acknowledge NATS message", e))
+    }
+}
+
+#[async_trait]
+impl MessageSource for NatsSource {
+    async fn next(&mut self) -> Result<Option<Delivery>> {
+        let Some(received) = self.messages.next().await else {
+            return Ok(None);
+        };
+        let received = received.map_err(|e| nats_error("Failed to receive NATS message", e))?;
+
+        let info = received.info().map_err(|e| nats_error("Invalid JetStream message", e))?;
+        let id = format!("{}/{}", info.stream, info.stream_sequence);
+        let headers = received
+            .headers
+            .as_ref()
+            .map(|headers| {
+                headers
+                    .iter()
+                    .filter_map(|(name, values)| Some((name.to_string(), values.first()?.to_string())))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let message = Message {
+            id,
+            topic: received.subject.to_string(),
+            key: None,
+            payload: received.payload.to_vec(),
+            headers,
+        };
+        Ok(Some(Delivery::new(message, Box::new(NatsAck(received)))))
+    }
+}
+
+/// Publishes to JetStream, which deduplicates on the idempotency key.
+pub struct NatsSink {
+    jetstream: jetstream::Context,
+}
+
+impl NatsSink {
+    /// Connects to the server at `url`.
+    pub async fn connect(url: &str) -> Result<Self> {
+        let client = async_nats::connect(url)
+            .await
+            .map_err(|e| nats_error("Failed to connect to NATS", e))?;
+        Ok(Self {
+            jetstream: jetstream::new(client),
+        })
+    }
+}
+
+#[async_trait]
+impl MessageSink for NatsSink {
+    async fn publish(&self, subject: &str, _key: Option<&str>, payload: Vec<u8>, idempotency_key: &str) -> Result<()> {
+        let mut headers = HeaderMap::new();
+        headers.insert(MSG_ID_HEADER, idempotency_key);
+        headers.insert(IDEMPOTENCY_KEY_HEADER, idempotency_key);
+        self.jetstream
+            .publish_with_headers(subject.to_string(), headers, payload.into())
+            .await
+            .map_err(|e| nats_error("Failed to publish NATS message", e))?
+            .await
+            .map_err(|e| nats_error("NATS did not acknowledge the message", e))?;
+        Ok(())
+    }
+}
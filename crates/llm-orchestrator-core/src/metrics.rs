@@ -8,8 +8,8 @@
 
 use lazy_static::lazy_static;
 use prometheus::{
-    register_counter_vec, register_gauge, register_histogram_vec, CounterVec, Gauge, HistogramVec,
-    TextEncoder, Encoder, Registry,
+    register_counter_vec, register_gauge, register_gauge_vec, register_histogram_vec, CounterVec, Gauge,
+    GaugeVec, HistogramVec, TextEncoder, Encoder, Registry,
 };
 
 lazy_static! {
@@ -93,6 +93,39 @@ lazy_static! {
     )
     .expect("Failed to create llm_request_duration_seconds metric");
 
+    // ============================================================================
+    // Admission Metrics
+    // ============================================================================
+
+    /// Number of runs waiting in the work queue.
+    pub static ref QUEUE_LENGTH: Gauge = register_gauge!(
+        "orchestrator_queue_length",
+        "Number of runs waiting in the work queue"
+    )
+    .expect("Failed to create queue_length metric");
+
+    /// Fraction of an admission limit in use, from 0 to 1.
+    ///
+    /// Labels:
+    /// - limit: "concurrent_runs" | "queued_runs"
+    pub static ref ADMISSION_SATURATION: GaugeVec = register_gauge_vec!(
+        "orchestrator_admission_saturation",
+        "Fraction of an admission limit in use",
+        &["limit"]
+    )
+    .expect("Failed to create admission_saturation metric");
+
+    /// Total requests rejected because an admission limit was reached.
+    ///
+    /// Labels:
+    /// - limit: "concurrent_runs" | "queued_runs"
+    pub static ref ADMISSION_REJECTIONS_TOTAL: CounterVec = register_counter_vec!(
+        "orchestrator_admission_rejections_total",
+        "Total requests rejected by admission control",
+        &["limit"]
+    )
+    .expect("Failed to create admission_rejections_total metric");
+
     // ============================================================================
     // Error Metrics
     // ============================================================================
@@ -239,6 +272,31 @@ pub fn record_error(error_type: &str, component: &str) {
         .inc();
 }
 
+/// Records how much of an admission limit is in use.
+///
+/// # Arguments
+/// * `limit` - Limit name ("concurrent_runs", "queued_runs")
+/// * `used` - Current usage
+/// * `max` - Configured limit
+#[inline]
+pub fn record_admission_usage(limit: &str, used: u64, max: u64) {
+    let saturation = if max == 0 { 1.0 } else { (used as f64 / max as f64).min(1.0) };
+    ADMISSION_SATURATION
+        .with_label_values(&[limit])
+        .set(saturation);
+}
+
+/// Records a request rejected because an admission limit was reached.
+///
+/// # Arguments
+/// * `limit` - Limit name ("concurrent_runs", "queued_runs")
+#[inline]
+pub fn record_admission_rejection(limit: &str) {
+    ADMISSION_REJECTIONS_TOTAL
+        .with_label_values(&[limit])
+        .inc();
+}
+
 /// Gathers and encodes all metrics in Prometheus text format.
 ///
 /// Returns a string containing all metrics in Prometheus exposition format.
@@ -288,6 +346,12 @@ pub fn create_registry() -> Registry {
         .expect("Failed to register step_executions_total");
     registry.register(Box::new(STEP_DURATION_SECONDS.clone()))
         .expect("Failed to register step_duration_seconds");
+    registry.register(Box::new(QUEUE_LENGTH.clone()))
+        .expect("Failed to register queue_length");
+    registry.register(Box::new(ADMISSION_SATURATION.clone()))
+        .expect("Failed to register admission_saturation");
+    registry.register(Box::new(ADMISSION_REJECTIONS_TOTAL.clone()))
+        .expect("Failed to register admission_rejections_total");
 
     registry
 }
@@ -338,6 +402,18 @@ mod tests {
         assert!(count >= 1.0);
     }
 
+    #[test]
+    fn test_admission_metrics() {
+        record_admission_usage("test_limit", 3, 4);
+        assert_eq!(ADMISSION_SATURATION.with_label_values(&["test_limit"]).get(), 0.75);
+
+        record_admission_rejection("concurrent_runs");
+        let count = ADMISSION_REJECTIONS_TOTAL
+            .with_label_values(&["concurrent_runs"])
+            .get();
+        assert!(count >= 1.0);
+    }
+
     #[test]
     fn test_gather_metrics() {
         record_workflow_start();
@@ -353,10 +429,11 @@ mod tests {
         let registry = create_registry();
         let families = registry.gather();
 
-        // Should have all our custom metrics (9 total)
+        // Should have all our custom metrics (12 total)
         // The registry may not return all metrics if they haven't been used
         // We have: workflow_executions, workflow_duration, active_workflows,
-        // llm_requests, llm_tokens, llm_duration, errors, step_executions, step_duration
-        assert!(families.len() <= 9, "Registered metrics count should not exceed 9");
+        // llm_requests, llm_tokens, llm_duration, errors, step_executions, step_duration,
+        // queue_length, admission_saturation, admission_rejections
+        assert!(families.len() <= 12, "Registered metrics count should not exceed 12");
     }
 }
@@ -0,0 +1,176 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Canned step outputs for offline workflow execution.
+//!
+//! A mock file maps step IDs to the outputs that step should produce. When
+//! attached to a [`WorkflowExecutor`](crate::WorkflowExecutor), mapped steps are
+//! replayed from the file instead of calling providers, so whole workflows can
+//! run in CI without API keys. Steps without an entry execute normally.
+//!
+//! # Example
+//!
+//! ```yaml
+//! analyze:
+//!   sentiment: "positive"
+//! summarize:
+//!   summary: "A short summary"
+//! ```
+
+use crate::error::{OrchestratorError, Result};
+use crate::executor::{StepResult, StepStatus};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Canned outputs keyed by step ID.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct MockResponses {
+    steps: HashMap<String, HashMap<String, Value>>,
+}
+
+impl MockResponses {
+    /// Create an empty set of mock responses.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add canned outputs for a step.
+    pub fn with_step(mut self, step_id: impl Into<String>, outputs: HashMap<String, Value>) -> Self {
+        self.insert(step_id, outputs);
+        self
+    }
+
+    /// Insert canned outputs for a step, replacing any existing entry.
+    pub fn insert(&mut self, step_id: impl Into<String>, outputs: HashMap<String, Value>) {
+        self.steps.insert(step_id.into(), outputs);
+    }
+
+    /// Get the canned outputs for a step.
+    pub fn get(&self, step_id: &str) -> Option<&HashMap<String, Value>> {
+        self.steps.get(step_id)
+    }
+
+    /// Number of mocked steps.
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// Returns true if no steps are mocked.
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Build a mock set from the results of a real run (record mode).
+    ///
+    /// Only completed steps are captured; failed and skipped steps are left
+    /// out so they execute normally on replay.
+    pub fn from_results(results: &HashMap<String, StepResult>) -> Self {
+        let steps = results
+            .iter()
+            .filter(|(_, result)| result.status == StepStatus::Completed)
+            .map(|(step_id, result)| (step_id.clone(), result.outputs.clone()))
+            .collect();
+
+        Self { steps }
+    }
+
+    /// Load mock responses from a YAML or JSON string.
+    pub fn from_yaml(content: &str) -> Result<Self> {
+        // YAML is a superset of JSON, so one parser handles both formats
+        serde_yaml::from_str(content)
+            .map_err(|e| OrchestratorError::parse(format!("Invalid mock file: {}", e)))
+    }
+
+    /// Load mock responses from a file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let content = fs::read_to_string(path.as_ref())?;
+        Self::from_yaml(&content)
+    }
+
+    /// Write mock responses to a file.
+    ///
+    /// Files with a `.json` extension are written as JSON, everything else as YAML.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let content = match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => serde_json::to_string_pretty(self)?,
+            _ => serde_yaml::to_string(self)?,
+        };
+        fs::write(path, content)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::time::Duration;
+
+    #[test]
+    fn test_parse_yaml_mock_file() {
+        let yaml = r#"
+analyze:
+  sentiment: "positive"
+  score: 0.9
+"#;
+        let mocks = MockResponses::from_yaml(yaml).unwrap();
+        assert_eq!(mocks.len(), 1);
+        assert_eq!(mocks.get("analyze").unwrap()["sentiment"], json!("positive"));
+        assert!(mocks.get("missing").is_none());
+    }
+
+    #[test]
+    fn test_from_results_only_captures_completed_steps() {
+        let mut results = HashMap::new();
+        results.insert(
+            "ok".to_string(),
+            StepResult {
+                step_id: "ok".to_string(),
+                status: StepStatus::Completed,
+                outputs: HashMap::from([("text".to_string(), json!("hello"))]),
+                error: None,
+                skip_reason: None,
+                error_details: None,
+                duration: Duration::from_millis(5),
+                usage: None,
+            },
+        );
+        results.insert(
+            "bad".to_string(),
+            StepResult {
+                step_id: "bad".to_string(),
+                status: StepStatus::Failed,
+                outputs: HashMap::new(),
+                error: Some("boom".to_string()),
+                skip_reason: None,
+                error_details: None,
+                duration: Duration::from_millis(5),
+                usage: None,
+            },
+        );
+
+        let mocks = MockResponses::from_results(&results);
+        assert_eq!(mocks.len(), 1);
+        assert_eq!(mocks.get("ok").unwrap()["text"], json!("hello"));
+    }
+
+    #[test]
+    fn test_save_and_reload_round_trip() {
+        let mocks = MockResponses::new()
+            .with_step("step1", HashMap::from([("out".to_string(), json!("value"))]));
+
+        let dir = std::env::temp_dir();
+        for name in ["llm_orchestrator_mock_test.json", "llm_orchestrator_mock_test.yaml"] {
+            let path = dir.join(name);
+            mocks.save(&path).unwrap();
+            let loaded = MockResponses::from_file(&path).unwrap();
+            assert_eq!(loaded, mocks);
+            let _ = fs::remove_file(&path);
+        }
+    }
+}
@@ -0,0 +1,242 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Notification actions and failure alerts.
+//!
+//! An [`ActionHandler`] registered with
+//! [`WorkflowExecutor::with_action_handler`] runs every action step naming it.
+//! Its parameters are rendered first: every string is a template, and
+//! `{{ secret "key" }}` references are resolved.
+//!
+//! ```yaml
+//! - id: announce
+//!   type: action
+//!   action: slack
+//!   text: "Release notes for {{ inputs.version }} are ready"
+//!   blocks:
+//!     - type: section
+//!       text: { type: mrkdwn, text: "{{ steps.draft.text }}" }
+//! - id: page
+//!   type: action
+//!   action: pagerduty
+//!   summary: "Nightly evaluation regressed: {{ steps.evaluate.score }}"
+//!   severity: warning
+//! ```
+//!
+//! Slack incoming webhooks ([`SlackNotifier`]), PagerDuty Events v2
+//! ([`PagerDutyNotifier`]), and SMTP email (`EmailNotifier`, feature `email`)
+//! are included. Each is also a [`FailureNotifier`]: register it with
+//! [`WorkflowExecutor::with_failure_notifier`] to be alerted whenever a run
+//! fails, without adding alerting steps to every workflow.
+
+use crate::error::{OrchestratorError, Result};
+use crate::executor::WorkflowExecutor;
+use crate::workflow::{ActionConfig, Step};
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+#[cfg(feature = "email")]
+pub mod email;
+pub mod pagerduty;
+pub mod slack;
+
+#[cfg(feature = "email")]
+pub use email::EmailNotifier;
+pub use pagerduty::PagerDutyNotifier;
+pub use slack::SlackNotifier;
+
+/// Runs action steps that name it.
+#[async_trait]
+pub trait ActionHandler: Send + Sync {
+    /// Performs the action with its rendered parameters.
+    ///
+    /// Returns the step outputs, keyed by name.
+    async fn execute(&self, step_id: &str, params: Value) -> Result<HashMap<String, Value>>;
+}
+
+/// A failed run, as reported to failure notifiers.
+#[derive(Debug, Clone)]
+pub struct WorkflowFailure {
+    /// Run that failed.
+    pub run_id: Uuid,
+    /// Name of the workflow.
+    pub workflow: String,
+    /// Why the run failed.
+    pub error: String,
+    /// Steps that failed, sorted by ID.
+    pub failed_steps: Vec<String>,
+}
+
+impl WorkflowFailure {
+    /// One-line description, e.g. `Workflow 'nightly' failed: Step 'fetch' failed: ...`.
+    pub fn summary(&self) -> String {
+        format!("Workflow '{}' failed: {}", self.workflow, self.error)
+    }
+}
+
+/// Alerts someone when a run fails.
+#[async_trait]
+pub trait FailureNotifier: Send + Sync {
+    /// Sends the alert.
+    async fn notify_failure(&self, failure: &WorkflowFailure) -> Result<()>;
+}
+
+/// Reads the parameters of an action step, rejecting unknown fields.
+pub(crate) fn action_params<T: DeserializeOwned>(step_id: &str, action: &str, params: Value) -> Result<T> {
+    serde_json::from_value(params).map_err(|e| OrchestratorError::InvalidStepConfig {
+        step_id: step_id.to_string(),
+        reason: format!("Invalid {} action: {}", action, e),
+    })
+}
+
+impl WorkflowExecutor {
+    /// Registers a handler for action steps whose `action` is `name`.
+    pub fn with_action_handler(self, name: impl Into<String>, handler: Arc<dyn ActionHandler>) -> Self {
+        self.action_handlers.insert(name.into(), handler);
+        self
+    }
+
+    /// Registers a notifier alerted when a run fails.
+    ///
+    /// Cancelled and interrupted runs are not reported.
+    pub fn with_failure_notifier(mut self, notifier: Arc<dyn FailureNotifier>) -> Self {
+        self.failure_notifiers.push(notifier);
+        self
+    }
+
+    /// Runs an action step with its registered handler, if there is one.
+    pub(crate) async fn execute_handler_action(
+        &self,
+        step: &Step,
+        config: &ActionConfig,
+    ) -> Option<Result<HashMap<String, Value>>> {
+        let handler = self.action_handlers.get(&config.action).map(|h| h.value().clone())?;
+        let params = Value::Object(config.params.clone().into_iter().collect());
+        let result = match self.render_json(params).await {
+            Ok(params) => handler.execute(&step.id, params).await,
+            Err(e) => Err(e),
+        };
+        if result.is_ok() {
+            info!(step_id = %step.id, action = %config.action, "Action completed");
+        }
+        Some(result)
+    }
+
+    /// Alerts the failure notifiers that the run failed with `error`.
+    ///
+    /// Notifier errors are logged; they do not change the outcome of the run.
+    pub(crate) async fn notify_failure(&self, error: &OrchestratorError) {
+        if self.failure_notifiers.is_empty() || matches!(error, OrchestratorError::Cancelled | OrchestratorError::Interrupted(_)) {
+            return;
+        }
+
+        let mut failed_steps: Vec<String> = self
+            .step_results
+            .iter()
+            .filter(|r| r.status == crate::executor::StepStatus::Failed)
+            .map(|r| r.step_id.clone())
+            .collect();
+        failed_steps.sort_unstable();
+        let failure = WorkflowFailure {
+            run_id: self.run_id,
+            workflow: self.workflow.name.clone(),
+            error: self.redact(error.to_string()),
+            failed_steps,
+        };
+
+        for notifier in &self.failure_notifiers {
+            if let Err(e) = notifier.notify_failure(&failure).await {
+                warn!(run_id = %self.run_id, "Failed to send failure notification: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workflow::Workflow;
+    use parking_lot::Mutex;
+
+    /// Records the parameters and failures it receives.
+    #[derive(Default)]
+    struct Recorder {
+        params: Mutex<Vec<Value>>,
+        failures: Mutex<Vec<WorkflowFailure>>,
+    }
+
+    #[async_trait]
+    impl ActionHandler for Recorder {
+        async fn execute(&self, _step_id: &str, params: Value) -> Result<HashMap<String, Value>> {
+            self.params.lock().push(params);
+            Ok(HashMap::from([("sent".to_string(), Value::Bool(true))]))
+        }
+    }
+
+    #[async_trait]
+    impl FailureNotifier for Recorder {
+        async fn notify_failure(&self, failure: &WorkflowFailure) -> Result<()> {
+            self.failures.lock().push(failure.clone());
+            Ok(())
+        }
+    }
+
+    fn executor(yaml: &str) -> WorkflowExecutor {
+        let workflow = Workflow::from_yaml(yaml).unwrap();
+        let inputs = HashMap::from([("version".to_string(), Value::from("1.2"))]);
+        WorkflowExecutor::new(workflow, inputs).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_handler_receives_rendered_params() {
+        let recorder = Arc::new(Recorder::default());
+        let executor = executor(
+            r#"
+name: notify
+steps:
+  - id: announce
+    type: action
+    action: chat
+    text: "Released {{ inputs.version }}"
+    blocks: [{ text: "v{{ inputs.version }}" }]
+"#,
+        )
+        .with_action_handler("chat", recorder.clone());
+
+        let results = executor.execute().await.unwrap();
+        assert_eq!(results["announce"].outputs["sent"], true);
+        assert_eq!(
+            recorder.params.lock()[0],
+            serde_json::json!({"text": "Released 1.2", "blocks": [{"text": "v1.2"}]})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failure_notifier() {
+        let recorder = Arc::new(Recorder::default());
+        let executor = executor(
+            r#"
+name: nightly
+steps:
+  - id: fetch
+    type: action
+    action: exec
+    command: "true"
+"#,
+        )
+        .with_failure_notifier(recorder.clone());
+
+        // `exec` is disabled, so the step fails
+        assert!(executor.execute().await.is_err());
+        let failures = recorder.failures.lock();
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].workflow, "nightly");
+        assert_eq!(failures[0].run_id, executor.run_id());
+        assert_eq!(failures[0].failed_steps, vec!["fetch"]);
+    }
+}
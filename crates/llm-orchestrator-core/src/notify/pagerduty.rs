@@ -0,0 +1,198 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! PagerDuty Events API v2 alerts.
+
+use super::{action_params, ActionHandler, FailureNotifier, WorkflowFailure};
+use crate::error::{OrchestratorError, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Events API v2 endpoint.
+pub const DEFAULT_EVENTS_URL: &str = "https://events.pagerduty.com/v2/enqueue";
+
+/// Source reported on events that do not set one.
+const DEFAULT_SOURCE: &str = "llm-orchestrator";
+
+/// What an event does to its alert.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventAction {
+    /// Opens an alert, or adds to the open alert with the same `dedup_key`.
+    #[default]
+    Trigger,
+    /// Acknowledges the alert with the given `dedup_key`.
+    Acknowledge,
+    /// Resolves the alert with the given `dedup_key`.
+    Resolve,
+}
+
+/// Severity of a triggered alert.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// Needs immediate attention.
+    Critical,
+    /// Something is broken.
+    #[default]
+    Error,
+    /// Something may break.
+    Warning,
+    /// For information only.
+    Info,
+}
+
+/// Parameters of a `pagerduty` action.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PagerDutyEvent {
+    /// What the event does.
+    #[serde(default)]
+    pub event_action: EventAction,
+
+    /// Alert summary, required to trigger.
+    #[serde(default)]
+    pub summary: Option<String>,
+
+    /// Alert severity.
+    #[serde(default)]
+    pub severity: Severity,
+
+    /// Affected system; defaults to `llm-orchestrator`.
+    #[serde(default)]
+    pub source: Option<String>,
+
+    /// Groups events into one alert, and names the alert to acknowledge or resolve.
+    #[serde(default)]
+    pub dedup_key: Option<String>,
+
+    /// Extra details shown on the alert.
+    #[serde(default)]
+    pub custom_details: Option<Value>,
+}
+
+/// Sends events to a PagerDuty service integration.
+pub struct PagerDutyNotifier {
+    client: reqwest::Client,
+    routing_key: String,
+    events_url: String,
+}
+
+impl PagerDutyNotifier {
+    /// Sends events with the integration key `routing_key`.
+    pub fn new(routing_key: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            routing_key: routing_key.into(),
+            events_url: DEFAULT_EVENTS_URL.to_string(),
+        }
+    }
+
+    /// Sends events to another endpoint, such as a proxy.
+    pub fn with_events_url(mut self, url: impl Into<String>) -> Self {
+        self.events_url = url.into();
+        self
+    }
+
+    /// Sends an event, returning the `dedup_key` of its alert.
+    pub async fn send(&self, event: &PagerDutyEvent) -> Result<String> {
+        let mut body = json!({
+            "routing_key": self.routing_key,
+            "event_action": event.event_action,
+        });
+        if let Some(dedup_key) = &event.dedup_key {
+            body["dedup_key"] = json!(dedup_key);
+        }
+        if event.event_action == EventAction::Trigger {
+            let summary = event
+                .summary
+                .as_deref()
+                .ok_or_else(|| OrchestratorError::other("A PagerDuty trigger event needs a summary"))?;
+            body["payload"] = json!({
+                "summary": summary,
+                "severity": event.severity,
+                "source": event.source.as_deref().unwrap_or(DEFAULT_SOURCE),
+                "custom_details": event.custom_details,
+            });
+        }
+
+        let response = self
+            .client
+            .post(&self.events_url)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| OrchestratorError::other(format!("Failed to send PagerDuty event: {}", e)))?;
+        let status = response.status();
+        let reply: Value = response.json().await.unwrap_or_default();
+        if !status.is_success() {
+            return Err(OrchestratorError::other(format!(
+                "PagerDuty rejected the event ({}): {}",
+                status,
+                reply["message"].as_str().unwrap_or_default()
+            )));
+        }
+        Ok(reply["dedup_key"].as_str().unwrap_or_default().to_string())
+    }
+}
+
+#[async_trait]
+impl ActionHandler for PagerDutyNotifier {
+    async fn execute(&self, step_id: &str, params: Value) -> Result<HashMap<String, Value>> {
+        let event: PagerDutyEvent = action_params(step_id, "pagerduty", params)?;
+        let dedup_key = self.send(&event).await?;
+        Ok(HashMap::from([("dedup_key".to_string(), Value::String(dedup_key))]))
+    }
+}
+
+#[async_trait]
+impl FailureNotifier for PagerDutyNotifier {
+    async fn notify_failure(&self, failure: &WorkflowFailure) -> Result<()> {
+        let event = PagerDutyEvent {
+            summary: Some(failure.summary()),
+            // One alert per run, however many notifiers or retries report it
+            dedup_key: Some(format!("{}/{}", failure.workflow, failure.run_id)),
+            custom_details: Some(json!({
+                "run_id": failure.run_id,
+                "failed_steps": failure.failed_steps,
+            })),
+            ..Default::default()
+        };
+        self.send(&event).await.map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Matcher;
+
+    #[tokio::test]
+    async fn test_trigger_event() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/enqueue")
+            .match_body(Matcher::PartialJson(json!({
+                "routing_key": "R0UT1NG",
+                "event_action": "trigger",
+                "payload": {"summary": "Score regressed", "severity": "warning", "source": "llm-orchestrator"},
+            })))
+            .with_status(202)
+            .with_body(r#"{"status": "success", "message": "Event processed", "dedup_key": "abc"}"#)
+            .create_async()
+            .await;
+        let pagerduty = PagerDutyNotifier::new("R0UT1NG").with_events_url(format!("{}/enqueue", server.url()));
+
+        let outputs = pagerduty
+            .execute("page", json!({"summary": "Score regressed", "severity": "warning"}))
+            .await
+            .unwrap();
+        mock.assert_async().await;
+        assert_eq!(outputs["dedup_key"], "abc");
+
+        // Nothing to describe the alert with
+        assert!(pagerduty.execute("page", json!({})).await.is_err());
+    }
+}
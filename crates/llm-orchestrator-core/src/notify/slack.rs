@@ -0,0 +1,120 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Slack incoming webhook messages.
+
+use super::{action_params, ActionHandler, FailureNotifier, WorkflowFailure};
+use crate::error::{OrchestratorError, Result};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Parameters of a `slack` action.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SlackMessage {
+    /// Message text, also the fallback shown in notifications when `blocks` are set.
+    pub text: String,
+
+    /// Block Kit layout blocks.
+    #[serde(default)]
+    pub blocks: Option<Value>,
+}
+
+/// Posts messages to a Slack incoming webhook.
+pub struct SlackNotifier {
+    client: reqwest::Client,
+    webhook_url: String,
+}
+
+impl SlackNotifier {
+    /// Posts to the incoming webhook at `webhook_url`.
+    pub fn new(webhook_url: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            webhook_url: webhook_url.into(),
+        }
+    }
+
+    /// Posts a message.
+    pub async fn send(&self, message: &SlackMessage) -> Result<()> {
+        let mut body = json!({ "text": message.text });
+        if let Some(blocks) = &message.blocks {
+            body["blocks"] = blocks.clone();
+        }
+        let response = self
+            .client
+            .post(&self.webhook_url)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| OrchestratorError::other(format!("Failed to post Slack message: {}", e)))?;
+        let status = response.status();
+        if !status.is_success() {
+            let reason = response.text().await.unwrap_or_default();
+            return Err(OrchestratorError::other(format!("Slack rejected the message ({}): {}", status, reason)));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ActionHandler for SlackNotifier {
+    async fn execute(&self, step_id: &str, params: Value) -> Result<HashMap<String, Value>> {
+        let message: SlackMessage = action_params(step_id, "slack", params)?;
+        self.send(&message).await?;
+        Ok(HashMap::new())
+    }
+}
+
+#[async_trait]
+impl FailureNotifier for SlackNotifier {
+    async fn notify_failure(&self, failure: &WorkflowFailure) -> Result<()> {
+        let mut text = format!(":rotating_light: {}\nRun: `{}`", failure.summary(), failure.run_id);
+        if !failure.failed_steps.is_empty() {
+            text.push_str(&format!("\nFailed steps: {}", failure.failed_steps.join(", ")));
+        }
+        self.send(&SlackMessage { text, blocks: None }).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Matcher;
+
+    #[tokio::test]
+    async fn test_slack_message() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/hook")
+            .match_body(Matcher::Json(json!({"text": "Done", "blocks": [{"type": "divider"}]})))
+            .with_body("ok")
+            .create_async()
+            .await;
+        let slack = SlackNotifier::new(format!("{}/hook", server.url()));
+
+        let params = json!({"text": "Done", "blocks": [{"type": "divider"}]});
+        slack.execute("announce", params).await.unwrap();
+        mock.assert_async().await;
+
+        let unknown = slack.execute("announce", json!({"text": "Done", "channel": "#ops"})).await;
+        assert!(matches!(unknown, Err(OrchestratorError::InvalidStepConfig { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_slack_rejection() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/hook")
+            .with_status(404)
+            .with_body("no_service")
+            .create_async()
+            .await;
+        let slack = SlackNotifier::new(format!("{}/hook", server.url()));
+
+        let error = slack.execute("announce", json!({"text": "Done"})).await.unwrap_err();
+        assert!(error.to_string().contains("no_service"));
+    }
+}
@@ -0,0 +1,223 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Shared prompt fragments.
+//!
+//! A workflow's `partials` are Handlebars templates that any template of the
+//! workflow renders with `{{> name }}`, e.g. tone guidelines or an output
+//! format block kept in one place:
+//!
+//! ```yaml
+//! partials_dir: prompts/partials   # tone.md, json_format.hbs, ...
+//! partials:
+//!   sign_off: "Reply in {{ inputs.language }}."
+//!
+//! steps:
+//!   - id: answer
+//!     type: llm
+//!     provider: openai
+//!     model: gpt-4
+//!     prompt: |
+//!       {{> tone }}
+//!       Answer: {{ inputs.question }}
+//!       {{> json_format }}
+//!       {{> sign_off }}
+//! ```
+//!
+//! Each file directly in `partials_dir` is a partial named after the file
+//! without its extension; hidden files and subdirectories are ignored. The
+//! directory is relative to the workflow file and is read when the workflow is
+//! loaded, so the loaded workflow holds its files in `partials`, with inline
+//! partials winning over files of the same name. Partials render with the data
+//! of the template that uses them.
+
+use crate::diagnostics::{Diagnostic, DiagnosticCode};
+use crate::include::IncludeResolver;
+use regex::Regex;
+use serde_yaml::{Mapping, Value};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Workflow field naming the directory partials are read from.
+pub(crate) const PARTIALS_DIR_FIELD: &str = "partials_dir";
+
+const PARTIALS_FIELD: &str = "partials";
+
+lazy_static::lazy_static! {
+    /// A partial call, `{{> name }}` or `{{~> name }}`, capturing the name.
+    static ref PARTIAL_CALL: Regex = Regex::new(r"\{\{~?>\s*([A-Za-z0-9_./-]+)").unwrap();
+}
+
+/// Names of the partials `template` renders.
+pub(crate) fn called(template: &str) -> impl Iterator<Item = &str> {
+    PARTIAL_CALL.captures_iter(template).filter_map(|c| Some(c.get(1)?.as_str()))
+}
+
+/// `text` followed by the partials it calls, and those the partials call.
+///
+/// Outputs a template reads are found by searching it for step IDs, which
+/// must also see what its partials read.
+pub(crate) fn expand<'a>(text: &'a str, partials: &HashMap<String, String>) -> Cow<'a, str> {
+    if partials.is_empty() || called(text).next().is_none() {
+        return Cow::Borrowed(text);
+    }
+    let mut expanded = text.to_string();
+    let mut seen = HashSet::new();
+    let mut pending: Vec<&str> = called(text).collect();
+    while let Some(name) = pending.pop() {
+        let Some(body) = partials.get(name).filter(|_| seen.insert(name)) else {
+            continue;
+        };
+        expanded.push('\n');
+        expanded.push_str(body);
+        pending.extend(called(body));
+    }
+    Cow::Owned(expanded)
+}
+
+/// Moves the files of the workflow document's `partials_dir` into its `partials`.
+pub(crate) fn read_dir(document: &mut Value, resolver: &dyn IncludeResolver) -> Result<(), Box<Diagnostic>> {
+    let Some(fields) = document.as_mapping_mut() else {
+        return Ok(());
+    };
+    let Some(dir) = fields.remove(PARTIALS_DIR_FIELD) else {
+        return Ok(());
+    };
+    let error = |message: String| Box::new(Diagnostic::new(DiagnosticCode::InvalidPartial, message).at(PARTIALS_DIR_FIELD));
+    let Value::String(dir) = dir else {
+        return Err(error("`partials_dir` must be a path".to_string()));
+    };
+
+    let files = resolver
+        .locate(&dir, None)
+        .and_then(|location| resolver.list(&location))
+        .map_err(|e| error(format!("Cannot read partials from '{}': {}", dir, e)))?;
+    let mut partials = HashMap::new();
+    for file in files {
+        let Some(name) = file.file_stem().and_then(|stem| stem.to_str()).filter(|name| !name.starts_with('.')) else {
+            continue;
+        };
+        let template = resolver
+            .read(&file)
+            .map_err(|e| error(format!("Cannot read partial '{}': {}", file.display(), e)))?;
+        if let Some(other) = partials.insert(name.to_string(), (file.clone(), template)).map(|(other, _)| other) {
+            return Err(error(format!("Partial '{}' is defined by both {} and {}", name, file_name(&other), file_name(&file))));
+        }
+    }
+
+    let inline = fields
+        .entry(Value::String(PARTIALS_FIELD.to_string()))
+        .or_insert_with(|| Value::Mapping(Mapping::new()));
+    let Value::Mapping(inline) = inline else {
+        return Err(error("`partials` must map names to templates".to_string()));
+    };
+    for (name, (_, template)) in partials {
+        inline.entry(Value::String(name)).or_insert(Value::String(template));
+    }
+    Ok(())
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::context::{ContextLimits, ExecutionContext};
+    use crate::diagnostics::DiagnosticCode;
+    use crate::include::FileResolver;
+    use crate::lint::{lint_yaml, LintConfig, LintRule};
+    use crate::workflow::Workflow;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_partials_render_with_the_template_data() {
+        let partials = HashMap::from([("greeting".to_string(), "Hello, {{ inputs.name }}.".to_string())]);
+        let context = ExecutionContext::new(HashMap::from([("name".to_string(), json!("Ada"))]))
+            .with_partials(&partials)
+            .unwrap();
+        assert_eq!(context.render_template("{{> greeting }} Bye.").unwrap(), "Hello, Ada. Bye.");
+    }
+
+    #[test]
+    fn test_blobs_read_through_partials_are_loaded() {
+        let partials = HashMap::from([
+            ("quote".to_string(), "> {{> body }}".to_string()),
+            ("body".to_string(), "{{ steps.page.text }}".to_string()),
+        ]);
+        let context = ExecutionContext::default()
+            .with_limits(ContextLimits {
+                blob_threshold: 16,
+                memory_cap: Some(0),
+            })
+            .with_partials(&partials)
+            .unwrap();
+        context.set_output("page", json!({"text": "a".repeat(20)}));
+        assert_eq!(context.render_template("{{> quote }}").unwrap(), format!("> {}", "a".repeat(20)));
+    }
+
+    #[test]
+    fn test_outputs_read_through_partials_are_used() {
+        let yaml = "name: partials
+partials:
+  quote: '{{ steps.fetch.text }}'
+steps:
+  \
+                    - {id: fetch, type: llm, provider: openai, model: gpt-4, prompt: Fetch, output: [text]}
+  \
+                    - {id: answer, type: llm, provider: openai, model: gpt-4, prompt: '{{> quote}}', depends_on: [fetch]}
+";
+        let diagnostics = lint_yaml(yaml, &LintConfig::default());
+        assert!(
+            diagnostics.iter().all(|d| d.code != DiagnosticCode::Lint(LintRule::UnusedOutput)),
+            "{:?}",
+            diagnostics
+        );
+    }
+
+    #[test]
+    fn test_invalid_and_undefined_partials_are_reported() {
+        let yaml = "name: partials\npartials:\n  tone: Be brief.\n  broken: '{{#if x}}'\nsteps:\n  \
+                    - {id: a, type: llm, provider: openai, model: gpt-4, prompt: '{{> tone}} {{> tones}}'}\n";
+        let workflow = Workflow::from_yaml(yaml).unwrap();
+        let problems: Vec<_> = workflow
+            .diagnostics()
+            .into_iter()
+            .map(|d| (d.code.as_str(), d.path.unwrap(), d.suggestion))
+            .collect();
+        assert_eq!(
+            problems,
+            [
+                ("E0117", "partials.broken".to_string(), None),
+                ("E0117", "steps[0].prompt".to_string(), Some("Did you mean 'tone'?".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_partials_dir_is_read_on_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let partials = dir.path().join("partials");
+        std::fs::create_dir(&partials).unwrap();
+        std::fs::write(partials.join("tone.md"), "Be brief.").unwrap();
+        std::fs::write(partials.join("format.hbs"), "Reply as JSON.").unwrap();
+        std::fs::write(partials.join(".draft.md"), "ignored").unwrap();
+        let yaml = "name: partials\npartials_dir: partials\npartials:\n  format: Reply as YAML.\nsteps:\n  \
+                    - {id: a, type: llm, provider: openai, model: gpt-4, prompt: '{{> tone}} {{> format}}'}\n";
+
+        let workflow = Workflow::from_yaml_with(yaml, &FileResolver::new(dir.path())).unwrap();
+        let mut loaded: Vec<_> = workflow.partials.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+        loaded.sort();
+        assert_eq!(loaded, [("format", "Reply as YAML."), ("tone", "Be brief.")]);
+        assert!(crate::include::has_includes(yaml));
+        let inlined = crate::include::inline(yaml, &FileResolver::new(dir.path())).unwrap();
+        assert!(!crate::include::has_includes(&inlined));
+        assert_eq!(Workflow::from_yaml(&inlined).unwrap().partials, workflow.partials);
+
+        std::fs::write(partials.join("tone.txt"), "Be terse.").unwrap();
+        let error = Workflow::from_yaml_with(yaml, &FileResolver::new(dir.path())).unwrap_err();
+        assert!(error.to_string().contains("Partial 'tone' is defined by both"));
+    }
+}
@@ -0,0 +1,597 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Prompt template registry.
+//!
+//! Keeps named, versioned prompt templates outside workflow files so they
+//! can be reused across workflows and reviewed on their own. LLM steps
+//! reference them with `prompt_ref`:
+//!
+//! - `summarize` uses the promoted version, or the latest if none was promoted
+//! - `summarize@latest` uses the newest version
+//! - `summarize@v3` uses version 3
+//!
+//! Versions are immutable once added. Templates live in a directory
+//! ([`FilePromptStore`]) or, with the `state-persistence` feature, in the
+//! state database ([`StatePromptStore`]).
+
+use crate::error::{OrchestratorError, Result};
+use crate::executor::WorkflowExecutor;
+use crate::workflow::{LlmStepConfig, Step};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// One version of a named prompt template.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PromptTemplate {
+    /// Prompt name.
+    pub name: String,
+
+    /// Version number, starting at 1.
+    pub version: u32,
+
+    /// Prompt template (supports Handlebars syntax).
+    pub template: String,
+
+    /// System prompt used when the step does not set one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system: Option<String>,
+
+    /// What changed in this version.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// When the version was added.
+    pub created_at: DateTime<Utc>,
+}
+
+/// Which version of a prompt a reference selects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromptVersionSelector {
+    /// The promoted version, or the latest if none was promoted.
+    Promoted,
+
+    /// The newest version.
+    Latest,
+
+    /// A specific version.
+    Version(u32),
+}
+
+/// Reference to a registry prompt, written `name`, `name@latest`, or `name@v3`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromptRef {
+    /// Prompt name.
+    pub name: String,
+
+    /// Version to use.
+    pub version: PromptVersionSelector,
+}
+
+/// Parses a version written as `v3` or `3`.
+pub fn parse_prompt_version(value: &str) -> Option<u32> {
+    let digits = value.strip_prefix('v').unwrap_or(value);
+    digits.parse().ok().filter(|v| *v > 0)
+}
+
+fn valid_prompt_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        && !name.starts_with('.')
+}
+
+impl FromStr for PromptRef {
+    type Err = OrchestratorError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (name, version) = match s.split_once('@') {
+            None => (s, PromptVersionSelector::Promoted),
+            Some((name, "latest")) => (name, PromptVersionSelector::Latest),
+            Some((name, version)) => {
+                let version = parse_prompt_version(version).ok_or_else(|| {
+                    OrchestratorError::validation(format!("Invalid prompt version in '{}'", s))
+                })?;
+                (name, PromptVersionSelector::Version(version))
+            }
+        };
+        if !valid_prompt_name(name) {
+            return Err(OrchestratorError::validation(format!(
+                "Invalid prompt name in '{}': use letters, digits, '-', '_' and '.'",
+                s
+            )));
+        }
+        Ok(Self {
+            name: name.to_string(),
+            version,
+        })
+    }
+}
+
+impl fmt::Display for PromptRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.version {
+            PromptVersionSelector::Promoted => write!(f, "{}", self.name),
+            PromptVersionSelector::Latest => write!(f, "{}@latest", self.name),
+            PromptVersionSelector::Version(version) => write!(f, "{}@v{}", self.name, version),
+        }
+    }
+}
+
+/// Storage backend for prompt templates.
+#[async_trait]
+pub trait PromptStore: Send + Sync {
+    /// Names of all stored prompts, in alphabetical order.
+    async fn names(&self) -> Result<Vec<String>>;
+
+    /// Versions of a prompt, oldest first.
+    async fn versions(&self, name: &str) -> Result<Vec<PromptTemplate>>;
+
+    /// Adds a new version. Fails if the version already exists.
+    async fn add(&self, prompt: &PromptTemplate) -> Result<()>;
+
+    /// Makes `version` the one the prompt's bare name resolves to.
+    async fn promote(&self, name: &str, version: u32) -> Result<()>;
+
+    /// The promoted version of a prompt, if any.
+    async fn promoted(&self, name: &str) -> Result<Option<u32>>;
+}
+
+/// Prompt store keeping each prompt in its own directory.
+///
+/// Versions are YAML files named `<root>/<name>/v<N>.yaml`; the promoted
+/// version number is kept in `<root>/<name>/promoted`.
+pub struct FilePromptStore {
+    root: PathBuf,
+}
+
+impl FilePromptStore {
+    /// Creates a store rooted at `root`, which is created on first write.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn prompt_dir(&self, name: &str) -> Result<PathBuf> {
+        if !valid_prompt_name(name) {
+            return Err(OrchestratorError::validation(format!("Invalid prompt name '{}'", name)));
+        }
+        Ok(self.root.join(name))
+    }
+}
+
+/// Reads a directory, treating a missing one as empty.
+async fn read_dir_names(dir: &Path) -> Result<Vec<(String, bool)>> {
+    let mut entries = match tokio::fs::read_dir(dir).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let mut names = Vec::new();
+    while let Some(entry) = entries.next_entry().await? {
+        let is_dir = entry.file_type().await?.is_dir();
+        names.push((entry.file_name().to_string_lossy().into_owned(), is_dir));
+    }
+    Ok(names)
+}
+
+#[async_trait]
+impl PromptStore for FilePromptStore {
+    async fn names(&self) -> Result<Vec<String>> {
+        let mut names: Vec<String> = read_dir_names(&self.root)
+            .await?
+            .into_iter()
+            .filter(|(name, is_dir)| *is_dir && valid_prompt_name(name))
+            .map(|(name, _)| name)
+            .collect();
+        names.sort();
+        Ok(names)
+    }
+
+    async fn versions(&self, name: &str) -> Result<Vec<PromptTemplate>> {
+        let dir = self.prompt_dir(name)?;
+        let mut numbers: Vec<u32> = read_dir_names(&dir)
+            .await?
+            .into_iter()
+            .filter_map(|(file, _)| file.strip_suffix(".yaml").and_then(parse_prompt_version))
+            .collect();
+        numbers.sort_unstable();
+
+        let mut versions = Vec::with_capacity(numbers.len());
+        for number in numbers {
+            let path = dir.join(format!("v{}.yaml", number));
+            let content = tokio::fs::read_to_string(&path).await?;
+            let prompt: PromptTemplate = serde_yaml::from_str(&content).map_err(|e| {
+                OrchestratorError::parse(format!("Invalid prompt file {}: {}", path.display(), e))
+            })?;
+            versions.push(prompt);
+        }
+        Ok(versions)
+    }
+
+    async fn add(&self, prompt: &PromptTemplate) -> Result<()> {
+        let dir = self.prompt_dir(&prompt.name)?;
+        tokio::fs::create_dir_all(&dir).await?;
+        let yaml = serde_yaml::to_string(prompt)
+            .map_err(|e| OrchestratorError::serialization(e.to_string()))?;
+
+        // create_new keeps published versions immutable
+        let path = dir.join(format!("v{}.yaml", prompt.version));
+        let mut file = match tokio::fs::OpenOptions::new().write(true).create_new(true).open(&path).await {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                return Err(OrchestratorError::other(format!(
+                    "Prompt {} version {} already exists",
+                    prompt.name, prompt.version
+                )))
+            }
+            Err(e) => return Err(e.into()),
+        };
+        tokio::io::AsyncWriteExt::write_all(&mut file, yaml.as_bytes()).await?;
+        Ok(())
+    }
+
+    async fn promote(&self, name: &str, version: u32) -> Result<()> {
+        let dir = self.prompt_dir(name)?;
+        if !tokio::fs::try_exists(dir.join(format!("v{}.yaml", version))).await? {
+            return Err(OrchestratorError::other(format!("Prompt {} version {} not found", name, version)));
+        }
+        tokio::fs::write(dir.join("promoted"), format!("{}\n", version)).await?;
+        Ok(())
+    }
+
+    async fn promoted(&self, name: &str) -> Result<Option<u32>> {
+        let path = self.prompt_dir(name)?.join("promoted");
+        match tokio::fs::read_to_string(&path).await {
+            Ok(content) => parse_prompt_version(content.trim()).map(Some).ok_or_else(|| {
+                OrchestratorError::parse(format!("Invalid promoted version in {}", path.display()))
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Prompt store backed by the state database.
+#[cfg(feature = "state-persistence")]
+pub struct StatePromptStore {
+    store: Arc<dyn llm_orchestrator_state::PromptVersionStore>,
+}
+
+#[cfg(feature = "state-persistence")]
+impl StatePromptStore {
+    /// Wraps a state store.
+    pub fn new(store: Arc<dyn llm_orchestrator_state::PromptVersionStore>) -> Self {
+        Self { store }
+    }
+}
+
+#[cfg(feature = "state-persistence")]
+fn state_error(e: llm_orchestrator_state::StateStoreError) -> OrchestratorError {
+    OrchestratorError::other(e.to_string())
+}
+
+#[cfg(feature = "state-persistence")]
+#[async_trait]
+impl PromptStore for StatePromptStore {
+    async fn names(&self) -> Result<Vec<String>> {
+        self.store.list_prompt_names().await.map_err(state_error)
+    }
+
+    async fn versions(&self, name: &str) -> Result<Vec<PromptTemplate>> {
+        let versions = self.store.list_prompt_versions(name).await.map_err(state_error)?;
+        Ok(versions
+            .into_iter()
+            .map(|v| PromptTemplate {
+                name: v.name,
+                version: v.version,
+                template: v.template,
+                system: v.system,
+                description: v.description,
+                created_at: v.created_at,
+            })
+            .collect())
+    }
+
+    async fn add(&self, prompt: &PromptTemplate) -> Result<()> {
+        let version = llm_orchestrator_state::PromptVersion {
+            name: prompt.name.clone(),
+            version: prompt.version,
+            template: prompt.template.clone(),
+            system: prompt.system.clone(),
+            description: prompt.description.clone(),
+            created_at: prompt.created_at,
+        };
+        self.store.add_prompt_version(&version).await.map_err(state_error)
+    }
+
+    async fn promote(&self, name: &str, version: u32) -> Result<()> {
+        self.store.promote_prompt_version(name, version).await.map_err(state_error)
+    }
+
+    async fn promoted(&self, name: &str) -> Result<Option<u32>> {
+        self.store.promoted_prompt_version(name).await.map_err(state_error)
+    }
+}
+
+/// Resolves prompt references and publishes new versions.
+#[derive(Clone)]
+pub struct PromptRegistry {
+    store: Arc<dyn PromptStore>,
+}
+
+impl PromptRegistry {
+    /// Creates a registry over `store`.
+    pub fn new(store: Arc<dyn PromptStore>) -> Self {
+        Self { store }
+    }
+
+    /// The underlying store.
+    pub fn store(&self) -> &dyn PromptStore {
+        self.store.as_ref()
+    }
+
+    /// Loads the version a reference selects.
+    pub async fn resolve(&self, reference: &PromptRef) -> Result<PromptTemplate> {
+        let mut versions = self.store.versions(&reference.name).await?;
+        let wanted = match reference.version {
+            PromptVersionSelector::Version(version) => Some(version),
+            PromptVersionSelector::Promoted => self.store.promoted(&reference.name).await?,
+            PromptVersionSelector::Latest => None,
+        };
+        let found = match wanted {
+            Some(version) => versions.into_iter().find(|v| v.version == version),
+            None => versions.pop(),
+        };
+        found.ok_or_else(|| OrchestratorError::other(format!("Prompt '{}' not found", reference)))
+    }
+
+    /// Adds `template` as the next version of `name`.
+    pub async fn publish(
+        &self,
+        name: &str,
+        template: impl Into<String>,
+        system: Option<String>,
+        description: Option<String>,
+    ) -> Result<PromptTemplate> {
+        let latest = self.store.versions(name).await?.last().map_or(0, |v| v.version);
+        let prompt = PromptTemplate {
+            name: name.to_string(),
+            version: latest + 1,
+            template: template.into(),
+            system,
+            description,
+            created_at: Utc::now(),
+        };
+        self.store.add(&prompt).await?;
+        Ok(prompt)
+    }
+
+    /// Makes `version` the one the prompt's bare name resolves to.
+    pub async fn promote(&self, name: &str, version: u32) -> Result<()> {
+        self.store.promote(name, version).await
+    }
+}
+
+/// A line of a [`diff_lines`] result.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine<'a> {
+    /// Present in both texts.
+    Same(&'a str),
+
+    /// Only in the old text.
+    Removed(&'a str),
+
+    /// Only in the new text.
+    Added(&'a str),
+}
+
+impl fmt::Display for DiffLine<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Same(line) => write!(f, "  {}", line),
+            Self::Removed(line) => write!(f, "- {}", line),
+            Self::Added(line) => write!(f, "+ {}", line),
+        }
+    }
+}
+
+/// Line diff of two texts, from their longest common subsequence.
+pub fn diff_lines<'a>(old: &'a str, new: &'a str) -> Vec<DiffLine<'a>> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // lcs[i][j] is the LCS length of old[i..] and new[j..]
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut diff = Vec::new();
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            diff.push(DiffLine::Same(old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            diff.push(DiffLine::Removed(old[i]));
+            i += 1;
+        } else {
+            diff.push(DiffLine::Added(new[j]));
+            j += 1;
+        }
+    }
+    diff
+}
+
+impl WorkflowExecutor {
+    /// Resolves `prompt_ref` of LLM steps against `registry`.
+    pub fn with_prompt_registry(mut self, registry: PromptRegistry) -> Self {
+        self.prompt_registry = Some(registry);
+        self
+    }
+
+    /// Loads the registry prompt an LLM step references, if any.
+    pub(crate) async fn resolve_prompt_ref(
+        &self,
+        step: &Step,
+        config: &LlmStepConfig,
+    ) -> Result<Option<PromptTemplate>> {
+        let Some(prompt_ref) = &config.prompt_ref else {
+            return Ok(None);
+        };
+        let registry = self.prompt_registry.as_ref().ok_or_else(|| {
+            OrchestratorError::InvalidStepConfig {
+                step_id: step.id.clone(),
+                reason: format!("prompt_ref '{}' needs a prompt registry", prompt_ref),
+            }
+        })?;
+        let reference: PromptRef = prompt_ref.parse()?;
+        registry.resolve(&reference).await.map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workflow::Workflow;
+    use crate::providers::{CompletionRequest, CompletionResponse, LLMProvider, ProviderError};
+    use std::collections::HashMap;
+
+    /// Echoes the system and user prompts back as the completion.
+    struct EchoProvider;
+
+    #[async_trait]
+    impl LLMProvider for EchoProvider {
+        async fn complete(&self, request: CompletionRequest) -> std::result::Result<CompletionResponse, ProviderError> {
+            Ok(CompletionResponse {
+                text: format!("[{}] {}", request.system.unwrap_or_default(), request.prompt),
+                model: request.model,
+                tokens_used: None,
+                usage: None,
+                tool_calls: Vec::new(),
+                metadata: HashMap::new(),
+            })
+        }
+
+        fn name(&self) -> &str {
+            "echo"
+        }
+    }
+
+    #[test]
+    fn test_prompt_ref_parsing() {
+        let reference: PromptRef = "summarize@v3".parse().unwrap();
+        assert_eq!(reference.version, PromptVersionSelector::Version(3));
+        assert_eq!(reference.to_string(), "summarize@v3");
+        assert_eq!("summarize@3".parse::<PromptRef>().unwrap(), reference);
+        assert_eq!(
+            "summarize@latest".parse::<PromptRef>().unwrap().version,
+            PromptVersionSelector::Latest
+        );
+        assert_eq!("summarize".parse::<PromptRef>().unwrap().version, PromptVersionSelector::Promoted);
+
+        assert!("summarize@v0".parse::<PromptRef>().is_err());
+        assert!("summarize@next".parse::<PromptRef>().is_err());
+        assert!("../secrets".parse::<PromptRef>().is_err());
+        assert!("@v1".parse::<PromptRef>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_file_store_publish_promote_resolve() {
+        let dir = tempfile::tempdir().unwrap();
+        let registry = PromptRegistry::new(Arc::new(FilePromptStore::new(dir.path())));
+
+        let v1 = registry.publish("summarize", "Summarize {{ text }}", None, None).await.unwrap();
+        let v2 = registry
+            .publish("summarize", "Summarize {{ text }} briefly", None, Some("Shorter".to_string()))
+            .await
+            .unwrap();
+        assert_eq!((v1.version, v2.version), (1, 2));
+        assert_eq!(registry.store().names().await.unwrap(), vec!["summarize"]);
+
+        let bare: PromptRef = "summarize".parse().unwrap();
+        assert_eq!(registry.resolve(&bare).await.unwrap().version, 2);
+        registry.promote("summarize", 1).await.unwrap();
+        assert_eq!(registry.resolve(&bare).await.unwrap(), v1);
+        assert_eq!(registry.resolve(&"summarize@latest".parse().unwrap()).await.unwrap(), v2);
+
+        assert!(registry.promote("summarize", 9).await.is_err());
+        assert!(registry.resolve(&"summarize@v9".parse().unwrap()).await.is_err());
+        assert!(registry.resolve(&"missing".parse().unwrap()).await.is_err());
+
+        // Published versions cannot be overwritten
+        assert!(registry.store().add(&v1).await.is_err());
+    }
+
+    #[test]
+    fn test_diff_lines() {
+        let diff = diff_lines("a\nb\nc", "a\nc\nd");
+        assert_eq!(
+            diff,
+            vec![
+                DiffLine::Same("a"),
+                DiffLine::Removed("b"),
+                DiffLine::Same("c"),
+                DiffLine::Added("d"),
+            ]
+        );
+        assert_eq!(diff[1].to_string(), "- b");
+    }
+
+    #[tokio::test]
+    async fn test_llm_step_uses_prompt_ref() {
+        let dir = tempfile::tempdir().unwrap();
+        let registry = PromptRegistry::new(Arc::new(FilePromptStore::new(dir.path())));
+        registry
+            .publish("greet", "Hello {{ name }}", Some("Be warm.".to_string()), None)
+            .await
+            .unwrap();
+
+        let workflow = Workflow::from_yaml(
+            r#"
+name: "registry"
+steps:
+  - id: "ask"
+    type: "llm"
+    provider: "echo"
+    model: "test"
+    prompt_ref: "greet@v1"
+    output: ["reply"]
+"#,
+        )
+        .unwrap();
+        let inputs = HashMap::from([("name".to_string(), serde_json::json!("Ada"))]);
+        let results = WorkflowExecutor::new(workflow.clone(), inputs.clone())
+            .unwrap()
+            .with_provider("echo", Arc::new(EchoProvider))
+            .with_prompt_registry(registry)
+            .execute()
+            .await
+            .unwrap();
+        let outputs = &results["ask"].outputs;
+        assert_eq!(outputs["reply"], serde_json::json!("[Be warm.] Hello Ada"));
+        assert_eq!(outputs["_prompt"], serde_json::json!({"name": "greet", "version": 1}));
+
+        // Without a registry the step cannot run
+        let err = WorkflowExecutor::new(workflow, inputs)
+            .unwrap()
+            .with_provider("echo", Arc::new(EchoProvider))
+            .execute()
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("needs a prompt registry"));
+    }
+}
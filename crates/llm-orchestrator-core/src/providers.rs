@@ -5,9 +5,25 @@
 
 // Re-export all provider traits from the providers crate
 pub use llm_orchestrator_providers::{
-    CompletionRequest, CompletionResponse, LLMProvider, ProviderError,
-    EmbeddingProvider, EmbeddingRequest, EmbeddingResponse, EmbeddingInput,
-    VectorSearchProvider, VectorSearchRequest, VectorSearchResponse, SearchResult,
-    UpsertRequest, UpsertResponse, VectorRecord,
+    CompletionRequest, CompletionResponse, ConversationTurn, ImageInput, LLMProvider, ProviderError, ToolCall, ToolSpec, Usage,
+    EmbeddingProvider, EmbeddingRequest, EmbeddingResponse, EmbeddingInput, embedding_dimensions,
+    TranscriptionProvider, TranscriptionRequest, TranscriptionResponse, TranscriptSegment,
+    SpeechProvider, SpeechRequest, SpeechResponse,
+    VectorSearchProvider, VectorSearchRequest, VectorSearchResponse, SearchResult, SearchMode, HybridFusion, IndexStats,
+    CreateIndexRequest, DistanceMetric, MetadataFieldType,
+    UpsertRequest, UpsertResponse, VectorRecord, FetchRequest, FetchResponse, ListIdsRequest, ListIdsResponse,
     DeleteRequest, DeleteResponse,
 };
+
+use crate::error::OrchestratorError;
+
+/// Convert a provider failure into an orchestrator error reported as `"<context>: <error>"`.
+///
+/// Rate limit errors are retryable and keep the provider's retry-after hint.
+pub(crate) fn provider_error(provider: &str, context: &str, err: ProviderError) -> OrchestratorError {
+    OrchestratorError::ProviderCall {
+        provider: provider.to_string(),
+        context: context.to_string(),
+        source: err,
+    }
+}
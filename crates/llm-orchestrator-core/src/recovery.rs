@@ -0,0 +1,296 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Startup recovery of orphaned runs (requires the `state-persistence` feature).
+//!
+//! A run is orphaned when the process executing it dies without recording its
+//! outcome, leaving the run active in the state store. [`RunRecovery`]
+//! reconciles the active runs when a worker or server starts:
+//!
+//! - Queued runs (see [`crate::worker`]) are owned by the worker holding their
+//!   lease. Once the lease expires the run is queued again. Under
+//!   [`RecoveryPolicy::Resume`] the next worker resumes it from its latest
+//!   checkpoint; under [`RecoveryPolicy::Fail`] it is cancelled and marked failed.
+//! - Runs executed in-process (e.g. `llm-orchestrator run`) hold no lease and
+//!   their workflow definition is not persisted, so they cannot be resumed.
+//!   They are marked failed once they have gone without an update for longer
+//!   than the stale threshold.
+
+use crate::error::{OrchestratorError, Result};
+use chrono::Utc;
+use llm_orchestrator_state::{QueueTaskStatus, StateStore, StateStoreError, WorkQueue, WorkflowState};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// What to do with an orphaned queued run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RecoveryPolicy {
+    /// Leave the run queued for a worker to resume from its latest checkpoint.
+    #[default]
+    Resume,
+    /// Cancel the run and mark it failed.
+    Fail,
+}
+
+/// Orphaned runs found by [`RunRecovery::recover`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecoveryReport {
+    /// Runs queued again for a worker to resume.
+    pub resumed: Vec<Uuid>,
+    /// Runs marked failed.
+    pub failed: Vec<Uuid>,
+}
+
+impl RecoveryReport {
+    /// Whether no orphaned runs were found.
+    pub fn is_empty(&self) -> bool {
+        self.resumed.is_empty() && self.failed.is_empty()
+    }
+}
+
+/// Finds runs left active by a dead process and resumes or fails them.
+pub struct RunRecovery {
+    state_store: Arc<dyn StateStore>,
+    queue: Arc<dyn WorkQueue>,
+    policy: RecoveryPolicy,
+    stale_after: Duration,
+}
+
+impl RunRecovery {
+    /// Create a recovery that resumes queued runs and fails in-process runs
+    /// not updated for an hour, the default workflow timeout.
+    pub fn new(state_store: Arc<dyn StateStore>, queue: Arc<dyn WorkQueue>) -> Self {
+        Self {
+            state_store,
+            queue,
+            policy: RecoveryPolicy::default(),
+            stale_after: Duration::from_secs(3600),
+        }
+    }
+
+    /// Set what to do with orphaned queued runs.
+    pub fn with_policy(mut self, policy: RecoveryPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Set how long an in-process run may go without an update before it is
+    /// considered orphaned. Should exceed the longest workflow timeout.
+    pub fn with_stale_after(mut self, stale_after: Duration) -> Self {
+        self.stale_after = stale_after;
+        self
+    }
+
+    /// Reconcile the active runs in the state store.
+    pub async fn recover(&self) -> Result<RecoveryReport> {
+        self.queue
+            .recover_expired_tasks()
+            .await
+            .map_err(|e| OrchestratorError::other(format!("Failed to recover expired runs: {}", e)))?;
+
+        let runs = self
+            .state_store
+            .list_active_workflows()
+            .await
+            .map_err(|e| OrchestratorError::other(format!("Failed to list active workflows: {}", e)))?;
+
+        let mut report = RecoveryReport::default();
+        for run in runs {
+            match self.queue.get_task(&run.id).await {
+                Ok(task) => match task.status {
+                    // Its worker still holds the lease
+                    QueueTaskStatus::Running => {}
+                    // Runs only have state once started, so a queued one lost its worker
+                    QueueTaskStatus::Queued => match self.policy {
+                        RecoveryPolicy::Resume => {
+                            info!(run_id = %run.id, workflow_name = %run.workflow_name, "Orphaned run queued for resumption");
+                            report.resumed.push(run.id);
+                        }
+                        RecoveryPolicy::Fail => {
+                            self.queue
+                                .cancel_task(&run.id)
+                                .await
+                                .map_err(|e| OrchestratorError::other(format!("Failed to cancel run: {}", e)))?;
+                            report.failed.push(self.fail(run, "Run was orphaned by its worker").await?);
+                        }
+                    },
+                    _ => {
+                        let reason = format!("Run was left active after its task became {}", task.status);
+                        report.failed.push(self.fail(run, &reason).await?);
+                    }
+                },
+                Err(StateStoreError::NotFound(_)) => {
+                    if self.is_stale(&run) {
+                        report.failed.push(self.fail(run, "Run was orphaned by the process executing it").await?);
+                    }
+                }
+                Err(e) => return Err(OrchestratorError::other(format!("Failed to load run task: {}", e))),
+            }
+        }
+
+        if !report.is_empty() {
+            info!(resumed = report.resumed.len(), failed = report.failed.len(), "Recovered orphaned runs");
+        }
+        Ok(report)
+    }
+
+    /// Whether an in-process run has gone without an update for too long.
+    fn is_stale(&self, run: &WorkflowState) -> bool {
+        (Utc::now() - run.updated_at)
+            .to_std()
+            .is_ok_and(|age| age > self.stale_after)
+    }
+
+    /// Mark an orphaned run failed. Returns its ID.
+    async fn fail(&self, mut run: WorkflowState, reason: &str) -> Result<Uuid> {
+        warn!(run_id = %run.id, workflow_name = %run.workflow_name, reason, "Marking orphaned run failed");
+        run.mark_failed(reason);
+        self.state_store
+            .save_workflow_state(&run)
+            .await
+            .map_err(|e| OrchestratorError::other(format!("Failed to save workflow state: {}", e)))?;
+        Ok(run.id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::{CompletionRequest, CompletionResponse, LLMProvider, ProviderError};
+    use crate::worker::{submit_run, Worker};
+    use crate::workflow::Workflow;
+    use crate::WorkflowExecutor;
+    use llm_orchestrator_state::{QueueTask, SqliteStateStore, WorkflowStatus};
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    /// Counts calls; hangs while `blocked` is set.
+    #[derive(Default)]
+    struct GatedProvider {
+        calls: AtomicUsize,
+        blocked: AtomicBool,
+    }
+
+    #[async_trait::async_trait]
+    impl LLMProvider for GatedProvider {
+        async fn complete(&self, request: CompletionRequest) -> std::result::Result<CompletionResponse, ProviderError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if self.blocked.load(Ordering::SeqCst) && request.prompt == "Second" {
+                std::future::pending::<()>().await;
+            }
+            Ok(CompletionResponse {
+                text: request.prompt,
+                model: request.model,
+                tokens_used: None,
+                usage: None,
+                tool_calls: Vec::new(),
+                metadata: HashMap::new(),
+            })
+        }
+
+        fn name(&self) -> &str {
+            "gated"
+        }
+    }
+
+    const WORKFLOW: &str = r#"
+name: "recoverable"
+steps:
+  - id: "first"
+    type: "llm"
+    provider: "gated"
+    model: "small"
+    prompt: "First"
+    output: ["a"]
+  - id: "second"
+    type: "llm"
+    depends_on: ["first"]
+    provider: "gated"
+    model: "small"
+    prompt: "Second"
+    output: ["b"]
+"#;
+
+    fn started_run(id: Uuid, updated_ago: chrono::Duration) -> WorkflowState {
+        let mut state = WorkflowState::new(Uuid::new_v4().to_string(), "recoverable", None, serde_json::json!({}));
+        state.id = id;
+        state.mark_running();
+        state.updated_at = Utc::now() - updated_ago;
+        state
+    }
+
+    #[tokio::test]
+    async fn test_orphaned_queued_run_resumes_from_checkpoint() {
+        let store = Arc::new(SqliteStateStore::new(":memory:").await.unwrap());
+        let provider = Arc::new(GatedProvider::default());
+        provider.blocked.store(true, Ordering::SeqCst);
+
+        // A worker claims the run and dies during the second step
+        let workflow = Workflow::from_yaml(WORKFLOW).unwrap();
+        let run_id = submit_run(store.as_ref(), &workflow, HashMap::new()).await.unwrap();
+        store.claim_task("dead-worker", Duration::from_millis(20)).await.unwrap().unwrap();
+        let executor = WorkflowExecutor::new(workflow, HashMap::new())
+            .unwrap()
+            .with_run_id(run_id)
+            .with_state_store(store.clone())
+            .with_provider("gated", provider.clone());
+        let crashed = tokio::spawn(async move { executor.execute().await });
+        while store.get_latest_checkpoint(&run_id).await.unwrap().is_none() {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        crashed.abort();
+        tokio::time::sleep(Duration::from_millis(30)).await;
+
+        let report = RunRecovery::new(store.clone(), store.clone()).recover().await.unwrap();
+        assert_eq!(report.resumed, vec![run_id]);
+        assert!(report.failed.is_empty());
+        assert_eq!(store.get_task(&run_id).await.unwrap().status, QueueTaskStatus::Queued);
+
+        // The next worker runs only the step that did not finish
+        provider.blocked.store(false, Ordering::SeqCst);
+        let setup_provider = provider.clone();
+        let worker = Worker::new(store.clone(), store.clone())
+            .with_executor_setup(move |executor| executor.with_provider("gated", setup_provider.clone()));
+        assert_eq!(worker.run_once().await.unwrap(), Some(run_id));
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 3);
+
+        let state = store.load_workflow_state(&run_id).await.unwrap();
+        assert_eq!(state.status, WorkflowStatus::Completed);
+        assert_eq!(state.context["outputs"]["first"]["a"], "First");
+    }
+
+    #[tokio::test]
+    async fn test_fail_policy_cancels_orphaned_queued_run() {
+        let store = Arc::new(SqliteStateStore::new(":memory:").await.unwrap());
+        let task = QueueTask::new(serde_json::json!({}));
+        store.enqueue_task(&task).await.unwrap();
+        store.save_workflow_state(&started_run(task.id, chrono::Duration::zero())).await.unwrap();
+
+        let report = RunRecovery::new(store.clone(), store.clone())
+            .with_policy(RecoveryPolicy::Fail)
+            .recover()
+            .await
+            .unwrap();
+        assert_eq!(report.failed, vec![task.id]);
+        assert_eq!(store.get_task(&task.id).await.unwrap().status, QueueTaskStatus::Cancelled);
+        let state = store.load_workflow_state(&task.id).await.unwrap();
+        assert_eq!(state.status, WorkflowStatus::Failed);
+        assert!(state.error.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_stale_in_process_run_is_failed() {
+        let store = Arc::new(SqliteStateStore::new(":memory:").await.unwrap());
+        let stale = started_run(Uuid::new_v4(), chrono::Duration::hours(2));
+        let live = started_run(Uuid::new_v4(), chrono::Duration::minutes(1));
+        store.save_workflow_state(&stale).await.unwrap();
+        store.save_workflow_state(&live).await.unwrap();
+
+        let report = RunRecovery::new(store.clone(), store.clone()).recover().await.unwrap();
+        assert_eq!(report.failed, vec![stale.id]);
+        assert_eq!(store.load_workflow_state(&stale.id).await.unwrap().status, WorkflowStatus::Failed);
+        assert_eq!(store.load_workflow_state(&live.id).await.unwrap().status, WorkflowStatus::Running);
+    }
+}
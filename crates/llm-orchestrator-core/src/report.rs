@@ -0,0 +1,616 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Post-run execution reports.
+//!
+//! [`WorkflowExecutor::report`](crate::WorkflowExecutor::report) summarizes a
+//! run step by step: when each step started and how long it took, how much of
+//! that was spent waiting on providers, tokens and cost, retries, and the
+//! critical path, i.e. the chain of dependent steps that determined the run's
+//! wall-clock time. Reports render as a terminal table with a Gantt-style
+//! timeline, as JSON, or as a standalone HTML page.
+
+use crate::error::{OrchestratorError, Result};
+use crate::executor::{StepResult, StepStatus};
+use crate::providers::Usage;
+use crate::workflow::{Step, StepConfig, Workflow};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::str::FromStr;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Width of the timeline column in terminal reports.
+const TIMELINE_WIDTH: usize = 40;
+
+/// Timing recorded by the executor while a step runs.
+#[derive(Debug, Clone)]
+pub(crate) struct StepTiming {
+    /// When the step started.
+    pub started_at: DateTime<Utc>,
+    /// When the step finished, if it has.
+    pub completed_at: Option<DateTime<Utc>>,
+    /// Attempts made, including the first.
+    pub attempts: u32,
+    /// Time spent waiting on providers, summed across calls and attempts.
+    pub provider_latency: Duration,
+    /// Tokens and cost of the step's provider calls.
+    pub usage: Option<Usage>,
+}
+
+impl StepTiming {
+    pub(crate) fn start() -> Self {
+        Self {
+            started_at: Utc::now(),
+            completed_at: None,
+            attempts: 1,
+            provider_latency: Duration::ZERO,
+            usage: None,
+        }
+    }
+}
+
+/// Output format of an [`ExecutionReport`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReportFormat {
+    /// Terminal table with a Gantt-style timeline.
+    #[default]
+    Table,
+    /// Pretty-printed JSON.
+    Json,
+    /// Standalone HTML page.
+    Html,
+}
+
+impl FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "table" => Ok(Self::Table),
+            "json" => Ok(Self::Json),
+            "html" => Ok(Self::Html),
+            other => Err(format!("Unknown report format '{}' (expected table, json, or html)", other)),
+        }
+    }
+}
+
+/// Timing and usage of one step.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepReport {
+    /// Step ID.
+    pub step_id: String,
+    /// Step type, e.g. `llm`.
+    pub step_type: String,
+    /// Final status; steps that never ran are `Pending`.
+    pub status: StepStatus,
+    /// Steps this step depends on.
+    pub depends_on: Vec<String>,
+    /// When the step started.
+    pub started_at: Option<DateTime<Utc>>,
+    /// When the step finished.
+    pub completed_at: Option<DateTime<Utc>>,
+    /// Start of the step relative to the start of the run, in milliseconds.
+    pub offset_ms: u64,
+    /// Wall-clock duration in milliseconds.
+    pub duration_ms: u64,
+    /// Time spent waiting on providers in milliseconds.
+    pub provider_latency_ms: u64,
+    /// Attempts made, including the first.
+    pub attempts: u32,
+    /// Provider used by the step.
+    pub provider: Option<String>,
+    /// Model that produced the response.
+    pub model: Option<String>,
+    /// Prompt tokens.
+    pub input_tokens: Option<u64>,
+    /// Completion tokens.
+    pub output_tokens: Option<u64>,
+    /// Total tokens.
+    pub total_tokens: Option<u64>,
+    /// Cost in USD, if the provider reports it.
+    pub cost_usd: Option<f64>,
+    /// Whether the step is on the critical path.
+    pub critical: bool,
+}
+
+impl StepReport {
+    /// Retries made after the first attempt.
+    pub fn retries(&self) -> u32 {
+        self.attempts.saturating_sub(1)
+    }
+}
+
+/// Post-run report of a workflow execution.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecutionReport {
+    /// Run ID.
+    pub run_id: Uuid,
+    /// Workflow name.
+    pub workflow_name: String,
+    /// When the first step started.
+    pub started_at: Option<DateTime<Utc>>,
+    /// When the last step finished.
+    pub completed_at: Option<DateTime<Utc>>,
+    /// Wall-clock time from the first step start to the last step end, in milliseconds.
+    pub wall_clock_ms: u64,
+    /// Time spent waiting on providers across all steps, in milliseconds.
+    pub provider_latency_ms: u64,
+    /// Total tokens across all steps.
+    pub total_tokens: u64,
+    /// Total cost in USD of the steps that report it.
+    pub cost_usd: f64,
+    /// Chain of dependent steps that determined the wall-clock time.
+    pub critical_path: Vec<String>,
+    /// Summed duration of the critical path steps, in milliseconds.
+    pub critical_path_ms: u64,
+    /// Steps in workflow order.
+    pub steps: Vec<StepReport>,
+}
+
+impl ExecutionReport {
+    /// Build a report from the executor's records of a run.
+    pub(crate) fn build(
+        run_id: Uuid,
+        workflow: &Workflow,
+        statuses: &HashMap<String, StepStatus>,
+        results: &HashMap<String, StepResult>,
+        timings: &HashMap<String, StepTiming>,
+    ) -> Self {
+        let started_at = timings.values().map(|t| t.started_at).min();
+        let completed_at = timings.values().filter_map(|t| t.completed_at).max();
+
+        let mut steps: Vec<StepReport> = workflow
+            .steps
+            .iter()
+            .map(|step| {
+                let status = statuses.get(&step.id).cloned().unwrap_or(StepStatus::Pending);
+                step_report(step, status, results.get(&step.id), timings.get(&step.id), started_at)
+            })
+            .collect();
+
+        let critical_path = critical_path(&steps);
+        for step in steps.iter_mut() {
+            step.critical = critical_path.contains(&step.step_id);
+        }
+        let critical_path_ms = steps.iter().filter(|s| s.critical).map(|s| s.duration_ms).sum();
+
+        Self {
+            run_id,
+            workflow_name: workflow.name.clone(),
+            started_at,
+            completed_at,
+            wall_clock_ms: match (started_at, completed_at) {
+                (Some(start), Some(end)) => millis_between(start, end),
+                _ => 0,
+            },
+            provider_latency_ms: steps.iter().map(|s| s.provider_latency_ms).sum(),
+            total_tokens: steps.iter().filter_map(|s| s.total_tokens).sum(),
+            cost_usd: steps.iter().filter_map(|s| s.cost_usd).fold(0.0, |total, cost| total + cost),
+            critical_path,
+            critical_path_ms,
+            steps,
+        }
+    }
+
+    /// Render the report in the given format.
+    pub fn render(&self, format: ReportFormat) -> Result<String> {
+        match format {
+            ReportFormat::Table => Ok(self.render_table()),
+            ReportFormat::Json => self.to_json(),
+            ReportFormat::Html => Ok(self.render_html()),
+        }
+    }
+
+    /// Serialize the report as pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(OrchestratorError::from)
+    }
+
+    /// Render the report as a terminal table with a Gantt-style timeline.
+    ///
+    /// Critical path steps are marked with `*`.
+    pub fn render_table(&self) -> String {
+        let id_width = self.steps.iter().map(|s| s.step_id.len()).max().unwrap_or(4).max(4);
+        let mut out = String::new();
+
+        let _ = writeln!(out, "Workflow: {} (run {})", self.workflow_name, self.run_id);
+        let _ = writeln!(
+            out,
+            "Wall clock: {}   Provider latency: {}   Tokens: {}   Cost: {}",
+            format_ms(self.wall_clock_ms),
+            format_ms(self.provider_latency_ms),
+            self.total_tokens,
+            format_cost(Some(self.cost_usd)),
+        );
+        let _ = writeln!(
+            out,
+            "Critical path: {} ({})",
+            if self.critical_path.is_empty() { "-".to_string() } else { self.critical_path.join(" -> ") },
+            format_ms(self.critical_path_ms),
+        );
+        let _ = writeln!(out);
+
+        let _ = writeln!(
+            out,
+            "  {:<id_width$}  {:<9}  {:>8}  {:>8}  {:>8}  {:>7}  {:>9}  {:>7}  TIMELINE",
+            "STEP", "STATUS", "START", "DURATION", "LATENCY", "TOKENS", "COST", "RETRIES",
+        );
+        for step in &self.steps {
+            let _ = writeln!(
+                out,
+                "{} {:<id_width$}  {:<9}  {:>8}  {:>8}  {:>8}  {:>7}  {:>9}  {:>7}  {}",
+                if step.critical { '*' } else { ' ' },
+                step.step_id,
+                format!("{:?}", step.status).to_lowercase(),
+                step.started_at.map_or("-".to_string(), |_| format!("+{}", format_ms(step.offset_ms))),
+                step.started_at.map_or("-".to_string(), |_| format_ms(step.duration_ms)),
+                format_optional_ms(step.provider_latency_ms),
+                step.total_tokens.map_or("-".to_string(), |t| t.to_string()),
+                format_cost(step.cost_usd),
+                step.retries(),
+                self.timeline_bar(step),
+            );
+        }
+        out
+    }
+
+    /// Render the report as a standalone HTML page.
+    pub fn render_html(&self) -> String {
+        let mut rows = String::new();
+        for step in &self.steps {
+            let (left, width) = self.timeline_span(step);
+            let _ = writeln!(
+                rows,
+                r#"<tr class="{critical}"><td>{id}</td><td class="status-{status}">{status}</td><td>{start}</td><td>{duration}</td><td>{latency}</td><td>{model}</td><td>{tokens}</td><td>{cost}</td><td>{retries}</td><td class="timeline"><div class="bar" style="left:{left:.2}%;width:{width:.2}%"></div></td></tr>"#,
+                critical = if step.critical { "critical" } else { "" },
+                id = escape_html(&step.step_id),
+                status = format!("{:?}", step.status).to_lowercase(),
+                start = step.started_at.map_or("-".to_string(), |_| format!("+{}", format_ms(step.offset_ms))),
+                duration = step.started_at.map_or("-".to_string(), |_| format_ms(step.duration_ms)),
+                latency = format_optional_ms(step.provider_latency_ms),
+                model = escape_html(step.model.as_deref().unwrap_or("-")),
+                tokens = step.total_tokens.map_or("-".to_string(), |t| t.to_string()),
+                cost = format_cost(step.cost_usd),
+                retries = step.retries(),
+            );
+        }
+
+        format!(
+            r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{name} run report</title>
+<style>
+body {{ font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", sans-serif; margin: 2rem; color: #1f2328; }}
+table {{ border-collapse: collapse; width: 100%; font-size: 14px; }}
+th, td {{ padding: 6px 10px; border-bottom: 1px solid #d0d7de; text-align: left; white-space: nowrap; }}
+td.timeline {{ position: relative; width: 40%; }}
+.bar {{ position: absolute; top: 8px; height: 12px; min-width: 2px; background: #54aeff; border-radius: 2px; }}
+tr.critical .bar {{ background: #cf222e; }}
+tr.critical td:first-child {{ font-weight: 600; }}
+.status-failed {{ color: #cf222e; }}
+.status-completed {{ color: #1a7f37; }}
+.summary span {{ margin-right: 2rem; }}
+</style>
+</head>
+<body>
+<h1>{name}</h1>
+<p>Run <code>{run_id}</code></p>
+<p class="summary"><span>Wall clock: <b>{wall_clock}</b></span><span>Provider latency: <b>{latency}</b></span><span>Tokens: <b>{tokens}</b></span><span>Cost: <b>{cost}</b></span></p>
+<p>Critical path ({critical_ms}): {critical_path}</p>
+<table>
+<thead><tr><th>Step</th><th>Status</th><th>Start</th><th>Duration</th><th>Provider latency</th><th>Model</th><th>Tokens</th><th>Cost</th><th>Retries</th><th>Timeline</th></tr></thead>
+<tbody>
+{rows}</tbody>
+</table>
+</body>
+</html>
+"#,
+            name = escape_html(&self.workflow_name),
+            run_id = self.run_id,
+            wall_clock = format_ms(self.wall_clock_ms),
+            latency = format_ms(self.provider_latency_ms),
+            tokens = self.total_tokens,
+            cost = format_cost(Some(self.cost_usd)),
+            critical_ms = format_ms(self.critical_path_ms),
+            critical_path = escape_html(&self.critical_path.join(" → ")),
+            rows = rows,
+        )
+    }
+
+    /// Start and width of a step's bar as percentages of the run.
+    fn timeline_span(&self, step: &StepReport) -> (f64, f64) {
+        if step.started_at.is_none() || self.wall_clock_ms == 0 {
+            return (0.0, 0.0);
+        }
+        let total = self.wall_clock_ms as f64;
+        let left = step.offset_ms as f64 / total * 100.0;
+        let width = (step.duration_ms as f64 / total * 100.0).min(100.0 - left);
+        (left, width)
+    }
+
+    fn timeline_bar(&self, step: &StepReport) -> String {
+        if step.started_at.is_none() {
+            return String::new();
+        }
+        let (left, width) = self.timeline_span(step);
+        let start = ((left / 100.0) * TIMELINE_WIDTH as f64).round() as usize;
+        let len = (((width / 100.0) * TIMELINE_WIDTH as f64).round() as usize).max(1);
+        let start = start.min(TIMELINE_WIDTH - 1);
+        let len = len.min(TIMELINE_WIDTH - start);
+        format!(
+            "|{}{}{}|",
+            " ".repeat(start),
+            "█".repeat(len),
+            " ".repeat(TIMELINE_WIDTH - start - len)
+        )
+    }
+}
+
+fn step_report(
+    step: &Step,
+    status: StepStatus,
+    result: Option<&StepResult>,
+    timing: Option<&StepTiming>,
+    run_start: Option<DateTime<Utc>>,
+) -> StepReport {
+    let response = result.and_then(|r| r.outputs.get("_response"));
+    let usage = result.and_then(|r| r.usage).or_else(|| timing.and_then(|t| t.usage));
+    let field = |field: &str| response.and_then(|r| r.get(field)).and_then(Value::as_u64);
+
+    let started_at = timing.map(|t| t.started_at);
+    let completed_at = timing.and_then(|t| t.completed_at);
+    let duration_ms = match (started_at, completed_at) {
+        (Some(start), Some(end)) => millis_between(start, end),
+        _ => result.map_or(0, |r| r.duration.as_millis() as u64),
+    };
+
+    StepReport {
+        step_id: step.id.clone(),
+        step_type: serde_json::to_value(&step.step_type)
+            .ok()
+            .and_then(|v| v.as_str().map(str::to_string))
+            .unwrap_or_default(),
+        status,
+        depends_on: step.depends_on.clone(),
+        started_at,
+        completed_at,
+        offset_ms: match (run_start, started_at) {
+            (Some(run_start), Some(start)) => millis_between(run_start, start),
+            _ => 0,
+        },
+        duration_ms,
+        provider_latency_ms: timing.map_or(0, |t| t.provider_latency.as_millis() as u64),
+        attempts: timing.map_or(0, |t| t.attempts),
+        provider: step_provider(step, result),
+        model: response.and_then(|r| r.get("model")).and_then(Value::as_str).map(str::to_string),
+        input_tokens: usage.map(|u| u.input_tokens).or_else(|| field("input_tokens")),
+        output_tokens: usage.map(|u| u.output_tokens).or_else(|| field("output_tokens")),
+        total_tokens: usage
+            .map(|u| u.total_tokens)
+            .or_else(|| field("tokens_used"))
+            .or_else(|| field("total_tokens")),
+        cost_usd: usage.and_then(|u| u.cost_usd).or_else(|| response.and_then(|r| r.get("cost_usd")).and_then(Value::as_f64)),
+        critical: false,
+    }
+}
+
+/// Provider of a step, taking the chosen experiment variant into account.
+fn step_provider(step: &Step, result: Option<&StepResult>) -> Option<String> {
+    match &step.config {
+        StepConfig::Llm(config) => {
+            let variant = result
+                .and_then(|r| r.outputs.get("_variant"))
+                .and_then(Value::as_str)
+                .and_then(|id| config.variants.iter().find(|v| v.id == id));
+            Some(variant.and_then(|v| v.provider.clone()).unwrap_or_else(|| config.provider.clone()))
+        }
+        StepConfig::Embed(config) => Some(config.provider.clone()),
+        StepConfig::VectorSearch(config) => Some(config.database.clone()),
+        StepConfig::VectorUpsert(config) => Some(config.database.clone()),
+        StepConfig::VectorFetch(config) => Some(config.database.clone()),
+        _ => None,
+    }
+}
+
+/// Walk back from the last step to finish through the dependency that finished last.
+fn critical_path(steps: &[StepReport]) -> Vec<String> {
+    let by_id: HashMap<&str, &StepReport> = steps.iter().map(|s| (s.step_id.as_str(), s)).collect();
+    let mut current = steps.iter().filter(|s| s.completed_at.is_some()).max_by_key(|s| s.completed_at);
+
+    let mut path = Vec::new();
+    while let Some(step) = current {
+        path.push(step.step_id.clone());
+        current = step
+            .depends_on
+            .iter()
+            .filter_map(|dep| by_id.get(dep.as_str()).copied())
+            .filter(|dep| dep.completed_at.is_some())
+            .max_by_key(|dep| dep.completed_at);
+    }
+    path.reverse();
+    path
+}
+
+fn millis_between(start: DateTime<Utc>, end: DateTime<Utc>) -> u64 {
+    (end - start).num_milliseconds().max(0) as u64
+}
+
+fn format_ms(ms: u64) -> String {
+    if ms < 1000 {
+        format!("{}ms", ms)
+    } else if ms < 60_000 {
+        format!("{:.2}s", ms as f64 / 1000.0)
+    } else {
+        format!("{}m{:02}s", ms / 60_000, (ms % 60_000) / 1000)
+    }
+}
+
+fn format_optional_ms(ms: u64) -> String {
+    if ms == 0 {
+        "-".to_string()
+    } else {
+        format_ms(ms)
+    }
+}
+
+fn format_cost(cost: Option<f64>) -> String {
+    cost.map_or("-".to_string(), |c| format!("${:.4}", c))
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn timing(start_ms: i64, end_ms: i64, attempts: u32, latency_ms: u64) -> StepTiming {
+        let base = DateTime::parse_from_rfc3339("2025-06-01T12:00:00Z").unwrap().with_timezone(&Utc);
+        StepTiming {
+            started_at: base + chrono::Duration::milliseconds(start_ms),
+            completed_at: Some(base + chrono::Duration::milliseconds(end_ms)),
+            attempts,
+            provider_latency: Duration::from_millis(latency_ms),
+            usage: None,
+        }
+    }
+
+    fn result(step_id: &str, outputs: HashMap<String, Value>) -> StepResult {
+        StepResult {
+            step_id: step_id.to_string(),
+            status: StepStatus::Completed,
+            outputs,
+            error: None,
+            skip_reason: None,
+            error_details: None,
+            duration: Duration::ZERO,
+            usage: None,
+        }
+    }
+
+    fn sample_report() -> ExecutionReport {
+        let workflow = Workflow::from_yaml(
+            r#"
+name: "report-test"
+steps:
+  - id: "fetch"
+    type: "transform"
+    function: "identity"
+    inputs: ["query"]
+    output: ["doc"]
+  - id: "summarize"
+    type: "llm"
+    depends_on: ["fetch"]
+    provider: "openai"
+    model: "gpt-4"
+    prompt: "Summarize {{ doc }}"
+    output: ["summary"]
+  - id: "tag"
+    type: "transform"
+    depends_on: ["fetch"]
+    function: "identity"
+    inputs: ["query"]
+    output: ["tags"]
+  - id: "publish"
+    type: "transform"
+    depends_on: ["summarize", "tag"]
+    function: "identity"
+    inputs: ["query"]
+    output: ["post"]
+  - id: "notify"
+    type: "transform"
+    depends_on: ["publish"]
+    condition: "{{ false }}"
+    function: "identity"
+    inputs: ["query"]
+    output: ["sent"]
+"#,
+        )
+        .unwrap();
+
+        let statuses = HashMap::from([
+            ("fetch".to_string(), StepStatus::Completed),
+            ("summarize".to_string(), StepStatus::Completed),
+            ("tag".to_string(), StepStatus::Completed),
+            ("publish".to_string(), StepStatus::Completed),
+            ("notify".to_string(), StepStatus::Skipped),
+        ]);
+        let summary_outputs = HashMap::from([(
+            "_response".to_string(),
+            json!({"text": "...", "model": "gpt-4-0613", "tokens_used": 150, "input_tokens": 100, "output_tokens": 50, "cost_usd": 0.0045}),
+        )]);
+        let results = HashMap::from([
+            ("fetch".to_string(), result("fetch", HashMap::new())),
+            ("summarize".to_string(), result("summarize", summary_outputs)),
+            ("tag".to_string(), result("tag", HashMap::new())),
+            ("publish".to_string(), result("publish", HashMap::new())),
+        ]);
+        let timings = HashMap::from([
+            ("fetch".to_string(), timing(0, 100, 1, 0)),
+            ("summarize".to_string(), timing(100, 1600, 2, 1300)),
+            ("tag".to_string(), timing(100, 300, 1, 0)),
+            ("publish".to_string(), timing(1600, 2000, 1, 0)),
+        ]);
+
+        ExecutionReport::build(Uuid::nil(), &workflow, &statuses, &results, &timings)
+    }
+
+    #[test]
+    fn test_report_totals_and_critical_path() {
+        let report = sample_report();
+
+        assert_eq!(report.wall_clock_ms, 2000);
+        assert_eq!(report.provider_latency_ms, 1300);
+        assert_eq!(report.total_tokens, 150);
+        assert!((report.cost_usd - 0.0045).abs() < 1e-9);
+        assert_eq!(report.critical_path, vec!["fetch", "summarize", "publish"]);
+        assert_eq!(report.critical_path_ms, 2000);
+
+        let summarize = &report.steps[1];
+        assert_eq!(summarize.offset_ms, 100);
+        assert_eq!(summarize.duration_ms, 1500);
+        assert_eq!(summarize.retries(), 1);
+        assert_eq!(summarize.provider.as_deref(), Some("openai"));
+        assert_eq!(summarize.model.as_deref(), Some("gpt-4-0613"));
+        assert_eq!(summarize.input_tokens, Some(100));
+        assert!(summarize.critical);
+        assert!(!report.steps[2].critical);
+
+        let notify = &report.steps[4];
+        assert_eq!(notify.status, StepStatus::Skipped);
+        assert!(notify.started_at.is_none());
+    }
+
+    #[test]
+    fn test_report_rendering() {
+        let report = sample_report();
+
+        let table = report.render(ReportFormat::Table).unwrap();
+        assert!(table.contains("Critical path: fetch -> summarize -> publish (2.00s)"));
+        assert!(table.contains("* summarize"));
+        assert!(table.contains("$0.0045"));
+
+        let parsed: ExecutionReport = serde_json::from_str(&report.render(ReportFormat::Json).unwrap()).unwrap();
+        assert_eq!(parsed.critical_path, report.critical_path);
+
+        let mut report = report;
+        report.workflow_name = "<script>".to_string();
+        let html = report.render(ReportFormat::Html).unwrap();
+        assert!(html.contains("&lt;script&gt;"));
+        assert!(html.contains(r#"class="bar" style="left:5.00%;width:75.00%""#));
+
+        assert_eq!("HTML".parse::<ReportFormat>().unwrap(), ReportFormat::Html);
+        assert!("pdf".parse::<ReportFormat>().is_err());
+    }
+}
@@ -0,0 +1,482 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Result sinks.
+//!
+//! When a run finishes, its final outputs can be written to a local file,
+//! uploaded to S3-compatible storage (feature `s3`), or posted to a webhook.
+//! Configure a sink in the workflow's `outputs` section:
+//!
+//! ```yaml
+//! outputs:
+//!   sink:
+//!     type: s3
+//!     bucket: reports
+//!     key: "{{ workflow }}/{{ date }}/{{ run_id }}.md"
+//! ```
+//!
+//! or register one with [`WorkflowExecutor::with_result_sink`]. Paths, keys,
+//! and URLs are templates over `run_id`, `workflow`, `timestamp`
+//! (`20250102T030405Z`), and `date` (`2025-01-02`). Outputs are written as
+//! JSON, YAML, or Markdown, following `format` or else the extension of the
+//! path or key.
+
+use crate::error::{OrchestratorError, Result};
+use crate::executor::{StepResult, StepStatus, WorkflowExecutor};
+use crate::golden::completed_outputs;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use handlebars::Handlebars;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+use tracing::info;
+use uuid::Uuid;
+
+#[cfg(feature = "s3")]
+pub mod s3;
+
+#[cfg(feature = "s3")]
+pub use s3::S3Sink;
+
+/// Final outputs of a run, as written to result sinks.
+#[derive(Debug, Clone, Serialize)]
+pub struct RunOutputs {
+    /// Run the outputs belong to.
+    pub run_id: Uuid,
+    /// Name of the workflow.
+    pub workflow: String,
+    /// When the run finished.
+    pub finished_at: DateTime<Utc>,
+    /// Steps that failed, sorted by ID.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub failed_steps: Vec<String>,
+    /// Outputs of the completed steps, keyed by step ID.
+    pub outputs: BTreeMap<String, BTreeMap<String, Value>>,
+}
+
+impl RunOutputs {
+    /// Collects the outputs of a finished run.
+    pub fn new(run_id: Uuid, workflow: impl Into<String>, results: &HashMap<String, StepResult>) -> Self {
+        let mut failed_steps: Vec<String> = results
+            .values()
+            .filter(|r| r.status == StepStatus::Failed)
+            .map(|r| r.step_id.clone())
+            .collect();
+        failed_steps.sort_unstable();
+        Self {
+            run_id,
+            workflow: workflow.into(),
+            finished_at: Utc::now(),
+            failed_steps,
+            outputs: completed_outputs(results),
+        }
+    }
+
+    /// Renders a path, key, or URL template for this run.
+    pub fn render_target(&self, template: &str) -> Result<String> {
+        let mut renderer = Handlebars::new();
+        renderer.register_escape_fn(handlebars::no_escape);
+        renderer.set_strict_mode(true);
+        let vars = json!({
+            "run_id": self.run_id,
+            "workflow": self.workflow,
+            "timestamp": self.finished_at.format("%Y%m%dT%H%M%SZ").to_string(),
+            "date": self.finished_at.format("%Y-%m-%d").to_string(),
+        });
+        renderer
+            .render_template(template, &vars)
+            .map_err(|e| OrchestratorError::TemplateError(format!("Invalid sink target '{}': {}", template, e)))
+    }
+
+    /// Serializes the outputs in `format`.
+    pub fn render(&self, format: SinkFormat) -> Result<Vec<u8>> {
+        match format {
+            SinkFormat::Json => Ok(serde_json::to_vec_pretty(self)?),
+            SinkFormat::Yaml => serde_yaml::to_string(self)
+                .map(String::into_bytes)
+                .map_err(|e| OrchestratorError::serialization(e.to_string())),
+            SinkFormat::Markdown => Ok(self.to_markdown().into_bytes()),
+        }
+    }
+
+    /// A Markdown document with a section per step; text outputs are inlined
+    /// and other values shown as JSON.
+    fn to_markdown(&self) -> String {
+        let mut doc = format!("# {}\n\nRun `{}` finished at {}.\n", self.workflow, self.run_id, self.finished_at.to_rfc3339());
+        if !self.failed_steps.is_empty() {
+            let _ = write!(doc, "\nFailed steps: {}\n", self.failed_steps.join(", "));
+        }
+        for (step_id, outputs) in &self.outputs {
+            let _ = write!(doc, "\n## {}\n", step_id);
+            for (name, value) in outputs {
+                match value {
+                    Value::String(text) => {
+                        let _ = write!(doc, "\n### {}\n\n{}\n", name, text.trim_end());
+                    }
+                    value => {
+                        let json = serde_json::to_string_pretty(value).unwrap_or_default();
+                        let _ = write!(doc, "\n### {}\n\n```json\n{}\n```\n", name, json);
+                    }
+                }
+            }
+        }
+        doc
+    }
+}
+
+/// Receives the final outputs of runs.
+#[async_trait]
+pub trait ResultSink: Send + Sync {
+    /// Writes the outputs of a finished run.
+    async fn write(&self, outputs: &RunOutputs) -> Result<()>;
+}
+
+/// Serialization of the outputs written to a sink.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SinkFormat {
+    /// Pretty-printed JSON.
+    #[default]
+    Json,
+    /// YAML.
+    Yaml,
+    /// A Markdown document with a section per step.
+    Markdown,
+}
+
+impl SinkFormat {
+    /// Format named by a path's extension: `.yaml`/`.yml`, `.md`/`.markdown`, or JSON.
+    pub fn from_path(path: &str) -> Self {
+        match Path::new(path).extension().and_then(|e| e.to_str()) {
+            Some("yaml" | "yml") => Self::Yaml,
+            Some("md" | "markdown") => Self::Markdown,
+            _ => Self::Json,
+        }
+    }
+
+    /// MIME type of the serialized outputs.
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::Yaml => "application/yaml",
+            Self::Markdown => "text/markdown; charset=utf-8",
+        }
+    }
+}
+
+/// Where a workflow's final outputs go.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct OutputsConfig {
+    /// Sink written to when a run finishes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sink: Option<SinkConfig>,
+}
+
+/// A result sink.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SinkConfig {
+    /// A local file.
+    File(FileSinkConfig),
+    /// An object in an S3-compatible bucket (feature `s3`).
+    S3(S3SinkConfig),
+    /// A POST request to a URL.
+    Webhook(WebhookSinkConfig),
+}
+
+/// A local file sink.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct FileSinkConfig {
+    /// File path template; missing directories are created.
+    pub path: String,
+
+    /// Output format, by default from the path's extension.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<SinkFormat>,
+}
+
+/// An S3 object sink.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct S3SinkConfig {
+    /// Bucket name.
+    pub bucket: String,
+
+    /// Object key template.
+    pub key: String,
+
+    /// Endpoint of an S3-compatible service (MinIO, Ceph, R2), addressed path-style.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+
+    /// Region, by default from the environment.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+
+    /// Output format, by default from the key's extension.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<SinkFormat>,
+}
+
+/// A webhook sink.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct WebhookSinkConfig {
+    /// URL template the outputs are posted to.
+    pub url: String,
+
+    /// Extra request headers.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+
+    /// Output format, JSON by default.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<SinkFormat>,
+}
+
+impl SinkConfig {
+    /// Checks that the sink's templates compile and its URL is HTTP.
+    pub fn validate(&self) -> Result<()> {
+        let target = match self {
+            Self::File(file) => &file.path,
+            Self::S3(s3) => {
+                if s3.bucket.is_empty() {
+                    return Err(OrchestratorError::validation("S3 sink needs a bucket"));
+                }
+                &s3.key
+            }
+            Self::Webhook(webhook) => {
+                if !webhook.url.starts_with("http://") && !webhook.url.starts_with("https://") {
+                    return Err(OrchestratorError::validation(format!("Webhook sink URL '{}' must be http(s)", webhook.url)));
+                }
+                &webhook.url
+            }
+        };
+        handlebars::Template::compile(target)
+            .map(|_| ())
+            .map_err(|e| OrchestratorError::validation(format!("Invalid sink target '{}': {}", target, e)))
+    }
+
+    /// Creates the sink.
+    pub async fn build(&self) -> Result<Arc<dyn ResultSink>> {
+        match self {
+            Self::File(file) => Ok(Arc::new(FileSink::new(file.clone()))),
+            Self::Webhook(webhook) => Ok(Arc::new(WebhookSink::new(webhook.clone()))),
+            #[cfg(feature = "s3")]
+            Self::S3(s3) => Ok(Arc::new(S3Sink::new(s3.clone()).await)),
+            #[cfg(not(feature = "s3"))]
+            Self::S3(_) => Err(OrchestratorError::other("S3 result sinks require the `s3` feature")),
+        }
+    }
+}
+
+impl FromStr for SinkConfig {
+    type Err = String;
+
+    /// Parses `s3://BUCKET/KEY`, an `http(s)://` webhook URL, or a file path.
+    fn from_str(spec: &str) -> std::result::Result<Self, Self::Err> {
+        if let Some(location) = spec.strip_prefix("s3://") {
+            return match location.split_once('/') {
+                Some((bucket, key)) if !bucket.is_empty() && !key.is_empty() => Ok(Self::S3(S3SinkConfig {
+                    bucket: bucket.to_string(),
+                    key: key.to_string(),
+                    endpoint: None,
+                    region: None,
+                    format: None,
+                })),
+                _ => Err(format!("Expected s3://BUCKET/KEY, got '{}'", spec)),
+            };
+        }
+        if spec.starts_with("http://") || spec.starts_with("https://") {
+            return Ok(Self::Webhook(WebhookSinkConfig {
+                url: spec.to_string(),
+                headers: HashMap::new(),
+                format: None,
+            }));
+        }
+        if spec.is_empty() {
+            return Err("Sink path is empty".to_string());
+        }
+        Ok(Self::File(FileSinkConfig {
+            path: spec.to_string(),
+            format: None,
+        }))
+    }
+}
+
+/// Writes outputs to a local file.
+pub struct FileSink {
+    config: FileSinkConfig,
+}
+
+impl FileSink {
+    /// Creates a sink writing to `config.path`.
+    pub fn new(config: FileSinkConfig) -> Self {
+        Self { config }
+    }
+}
+
+#[async_trait]
+impl ResultSink for FileSink {
+    async fn write(&self, outputs: &RunOutputs) -> Result<()> {
+        let path = outputs.render_target(&self.config.path)?;
+        let format = self.config.format.unwrap_or_else(|| SinkFormat::from_path(&path));
+        let body = outputs.render(format)?;
+        if let Some(parent) = Path::new(&path).parent().filter(|p| !p.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(&path, body)
+            .await
+            .map_err(|e| OrchestratorError::other(format!("Failed to write outputs to {}: {}", path, e)))?;
+        info!(run_id = %outputs.run_id, path = %path, "Wrote run outputs");
+        Ok(())
+    }
+}
+
+/// Posts outputs to a webhook.
+pub struct WebhookSink {
+    client: reqwest::Client,
+    config: WebhookSinkConfig,
+}
+
+impl WebhookSink {
+    /// Creates a sink posting to `config.url`.
+    pub fn new(config: WebhookSinkConfig) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            config,
+        }
+    }
+}
+
+#[async_trait]
+impl ResultSink for WebhookSink {
+    async fn write(&self, outputs: &RunOutputs) -> Result<()> {
+        let url = outputs.render_target(&self.config.url)?;
+        let format = self.config.format.unwrap_or_default();
+        let mut request = self
+            .client
+            .post(&url)
+            .header(reqwest::header::CONTENT_TYPE, format.content_type())
+            .body(outputs.render(format)?);
+        for (name, value) in &self.config.headers {
+            request = request.header(name.as_str(), value.as_str());
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| OrchestratorError::other(format!("Failed to post outputs to {}: {}", url, e)))?;
+        let status = response.status();
+        if !status.is_success() {
+            let reason = response.text().await.unwrap_or_default();
+            return Err(OrchestratorError::other(format!("Webhook {} rejected the outputs ({}): {}", url, status, reason)));
+        }
+        info!(run_id = %outputs.run_id, url = %url, "Posted run outputs");
+        Ok(())
+    }
+}
+
+impl WorkflowExecutor {
+    /// Registers a sink written to when the run finishes, in addition to the
+    /// workflow's own `outputs.sink`.
+    pub fn with_result_sink(mut self, sink: Arc<dyn ResultSink>) -> Self {
+        self.result_sinks.push(sink);
+        self
+    }
+
+    /// Writes the final outputs to the workflow's sink and the registered sinks.
+    pub(crate) async fn write_results(&self, results: &HashMap<String, StepResult>) -> Result<()> {
+        let configured = self.workflow.outputs.as_ref().and_then(|o| o.sink.as_ref());
+        if configured.is_none() && self.result_sinks.is_empty() {
+            return Ok(());
+        }
+
+        let mut sinks = Vec::new();
+        if let Some(config) = configured {
+            sinks.push(config.build().await?);
+        }
+        sinks.extend(self.result_sinks.iter().cloned());
+
+        let outputs = RunOutputs::new(self.run_id, &self.workflow.name, results);
+        for sink in sinks {
+            sink.write(&outputs).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockResponses;
+    use crate::workflow::Workflow;
+
+    #[test]
+    fn test_parse_sink_spec() {
+        let sink: SinkConfig = "s3://reports/{{ workflow }}/{{ run_id }}.md".parse().unwrap();
+        let SinkConfig::S3(s3) = sink else { panic!("expected an S3 sink") };
+        assert_eq!(s3.bucket, "reports");
+        assert_eq!(s3.key, "{{ workflow }}/{{ run_id }}.md");
+
+        assert!(matches!("https://example.com/hook".parse(), Ok(SinkConfig::Webhook(_))));
+        assert!(matches!("out/result.yaml".parse(), Ok(SinkConfig::File(_))));
+        assert!("s3://reports".parse::<SinkConfig>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_file_and_webhook_sinks() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut server = mockito::Server::new_async().await;
+        let hook = server
+            .mock("POST", "/results/outputs")
+            .match_header("content-type", "application/json")
+            .match_body(mockito::Matcher::PartialJson(json!({
+                "workflow": "sinks",
+                "outputs": { "greet": { "result": "Hello, Ada" } }
+            })))
+            .with_status(204)
+            .create_async()
+            .await;
+
+        let yaml = format!(
+            r#"
+name: sinks
+outputs:
+  sink:
+    type: file
+    path: "{}/{{{{ workflow }}}}/{{{{ run_id }}}}.md"
+steps:
+  - id: greet
+    type: llm
+    provider: openai
+    model: gpt-4
+    prompt: "Greet {{{{ inputs.name }}}}"
+"#,
+            dir.path().display()
+        );
+        let workflow = Workflow::from_yaml(&yaml).unwrap();
+        workflow.validate().unwrap();
+        let mocks = MockResponses::new().with_step("greet", HashMap::from([("result".to_string(), json!("Hello, Ada"))]));
+        let webhook: SinkConfig = format!("{}/results/outputs", server.url()).parse().unwrap();
+        let webhook = webhook.build().await.unwrap();
+        let executor = WorkflowExecutor::new(workflow, HashMap::new())
+            .unwrap()
+            .with_mock_responses(mocks)
+            .with_result_sink(webhook);
+        executor.execute().await.unwrap();
+        hook.assert_async().await;
+
+        let path = dir.path().join("sinks").join(format!("{}.md", executor.run_id()));
+        let markdown = std::fs::read_to_string(path).unwrap();
+        assert!(markdown.starts_with("# sinks\n"));
+        assert!(markdown.contains("## greet\n\n### result\n\nHello, Ada\n"));
+    }
+}
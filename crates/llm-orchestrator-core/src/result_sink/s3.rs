@@ -0,0 +1,69 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! S3-compatible result sink (requires the `s3` feature).
+
+use super::{ResultSink, RunOutputs, S3SinkConfig, SinkFormat};
+use crate::error::{OrchestratorError, Result};
+use async_trait::async_trait;
+use aws_config::{BehaviorVersion, Region};
+use aws_sdk_s3::error::DisplayErrorContext;
+use aws_sdk_s3::primitives::ByteStream;
+use aws_sdk_s3::Client;
+use tracing::info;
+
+/// Uploads outputs as objects in an S3 bucket.
+///
+/// Credentials come from the environment; an `endpoint` selects an
+/// S3-compatible service (MinIO, Ceph, R2) with path-style addressing.
+pub struct S3Sink {
+    client: Client,
+    config: S3SinkConfig,
+}
+
+impl S3Sink {
+    /// Creates a sink for `config.bucket`.
+    pub async fn new(config: S3SinkConfig) -> Self {
+        let mut loader = aws_config::defaults(BehaviorVersion::latest());
+        if let Some(region) = &config.region {
+            loader = loader.region(Region::new(region.clone()));
+        }
+        let shared = loader.load().await;
+        let mut builder = aws_sdk_s3::config::Builder::from(&shared);
+        if let Some(endpoint) = &config.endpoint {
+            builder = builder.endpoint_url(endpoint).force_path_style(true);
+        }
+        Self::with_client(Client::from_conf(builder.build()), config)
+    }
+
+    /// Creates a sink from a configured client.
+    pub fn with_client(client: Client, config: S3SinkConfig) -> Self {
+        Self { client, config }
+    }
+}
+
+#[async_trait]
+impl ResultSink for S3Sink {
+    async fn write(&self, outputs: &RunOutputs) -> Result<()> {
+        let key = outputs.render_target(&self.config.key)?;
+        let format = self.config.format.unwrap_or_else(|| SinkFormat::from_path(&key));
+        self.client
+            .put_object()
+            .bucket(&self.config.bucket)
+            .key(&key)
+            .content_type(format.content_type())
+            .body(ByteStream::from(outputs.render(format)?))
+            .send()
+            .await
+            .map_err(|e| {
+                OrchestratorError::other(format!(
+                    "Failed to upload outputs to s3://{}/{}: {}",
+                    self.config.bucket,
+                    key,
+                    DisplayErrorContext(e)
+                ))
+            })?;
+        info!(run_id = %outputs.run_id, bucket = %self.config.bucket, key = %key, "Uploaded run outputs");
+        Ok(())
+    }
+}
@@ -6,9 +6,11 @@
 //! This module provides configurable retry policies for handling transient failures
 //! in LLM API calls and other operations.
 
-use crate::error::Result;
+use crate::error::{OrchestratorError, Result};
 use rand::Rng;
-use std::time::Duration;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::warn;
 
 /// Retry policy configuration.
 #[derive(Debug, Clone)]
@@ -112,21 +114,105 @@ impl RetryPolicy {
         Duration::from_millis(jittered_ms)
     }
 
+    /// Calculates the delay after a provider asked to wait for `hint`.
+    ///
+    /// The hint is a lower bound: it is not capped by `max_delay`, and jitter
+    /// only lengthens it (by up to 25%) so that waiting callers do not all
+    /// retry at the same moment.
+    pub fn delay_for_hint(&self, hint: Duration) -> Duration {
+        if self.jitter {
+            hint + hint.mul_f64(rand::thread_rng().gen_range(0.0..=0.25))
+        } else {
+            hint
+        }
+    }
+
+    /// Calculates the delay before retrying after `err` on the given attempt (0-indexed),
+    /// preferring a retry-after hint carried by the error over computed backoff.
+    fn delay_after_error(&self, attempt: u32, err: &OrchestratorError) -> Duration {
+        match err.retry_after() {
+            Some(hint) => self.delay_for_hint(hint),
+            None => self.delay_for_attempt(attempt),
+        }
+    }
+
     /// Returns true if retries are enabled.
     pub fn is_enabled(&self) -> bool {
         self.max_attempts > 0
     }
 }
 
+/// Retries available to a whole run, shared by the retry executors of its steps.
+#[derive(Debug, Default)]
+pub struct RetryBudget {
+    max_retries: Option<u32>,
+    max_extra_latency: Option<Duration>,
+    spent: Mutex<(u32, Duration)>,
+}
+
+impl RetryBudget {
+    /// Creates a budget; `None` leaves that dimension unlimited.
+    pub fn new(max_retries: Option<u32>, max_extra_latency: Option<Duration>) -> Self {
+        Self {
+            max_retries,
+            max_extra_latency,
+            spent: Mutex::new((0, Duration::ZERO)),
+        }
+    }
+
+    /// Spends one retry that adds `latency` to the run.
+    ///
+    /// Returns false, spending nothing, when the retry would exceed the budget.
+    pub fn try_spend(&self, latency: Duration) -> bool {
+        let mut spent = self.spent.lock().unwrap();
+        let (retries, extra_latency) = (spent.0 + 1, spent.1 + latency);
+        if self.max_retries.is_some_and(|max| retries > max)
+            || self.max_extra_latency.is_some_and(|max| extra_latency > max)
+        {
+            return false;
+        }
+        *spent = (retries, extra_latency);
+        true
+    }
+
+    /// Number of retries spent so far.
+    pub fn retries_used(&self) -> u32 {
+        self.spent.lock().unwrap().0
+    }
+
+    /// Latency added by retries so far.
+    pub fn extra_latency(&self) -> Duration {
+        self.spent.lock().unwrap().1
+    }
+}
+
 /// Retry executor that handles retry logic with async functions.
 pub struct RetryExecutor {
     policy: RetryPolicy,
+    budget: Option<Arc<RetryBudget>>,
 }
 
 impl RetryExecutor {
     /// Creates a new retry executor with the given policy.
     pub fn new(policy: RetryPolicy) -> Self {
-        Self { policy }
+        Self { policy, budget: None }
+    }
+
+    /// Draws every retry from a shared budget; retrying stops once it is spent.
+    pub fn with_budget(mut self, budget: Arc<RetryBudget>) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    /// Spends a retry from the budget, if any; false when the budget is exhausted.
+    fn spend_budget(&self, latency: Duration) -> bool {
+        match &self.budget {
+            Some(budget) if !budget.try_spend(latency) => {
+                warn!("Retry budget exhausted, not retrying");
+                false
+            }
+            _ => true,
+        }
     }
 
     /// Executes an async operation with retries according to the policy.
@@ -135,6 +221,9 @@ impl RetryExecutor {
     /// - It returns a retryable error (determined by `OrchestratorError::is_retryable()`)
     /// - The maximum number of attempts has not been reached
     ///
+    /// When the error carries a retry-after hint (see `OrchestratorError::retry_after()`),
+    /// the hint is used instead of the policy's backoff.
+    ///
     /// # Examples
     ///
     /// ```
@@ -152,10 +241,22 @@ impl RetryExecutor {
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn execute<F, Fut, T>(&self, mut operation: F) -> Result<T>
+    pub async fn execute<F, Fut, T>(&self, operation: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = Result<T>>,
+    {
+        self.execute_with_retry_hook(operation, |_, _| {}).await
+    }
+
+    /// Executes an async operation with retries, calling `on_retry` before each retry.
+    ///
+    /// The hook receives the number of the failed attempt (starting at 1) and its error.
+    pub async fn execute_with_retry_hook<F, Fut, T, H>(&self, mut operation: F, mut on_retry: H) -> Result<T>
     where
         F: FnMut() -> Fut,
         Fut: std::future::Future<Output = Result<T>>,
+        H: FnMut(u32, &OrchestratorError),
     {
         let mut attempt = 0;
         let max_attempts = if self.policy.is_enabled() {
@@ -165,6 +266,7 @@ impl RetryExecutor {
         };
 
         loop {
+            let started = Instant::now();
             match operation().await {
                 Ok(result) => return Ok(result),
                 Err(err) => {
@@ -175,8 +277,14 @@ impl RetryExecutor {
                         return Err(err);
                     }
 
-                    // Calculate delay and wait before retrying
-                    let delay = self.policy.delay_for_attempt(attempt - 1);
+                    // Calculate delay, charge the retry to the budget, and wait before retrying
+                    let delay = self.policy.delay_after_error(attempt - 1, &err);
+                    if !self.spend_budget(started.elapsed() + delay) {
+                        return Err(err);
+                    }
+
+                    on_retry(attempt, &err);
+
                     if delay > Duration::from_millis(0) {
                         tokio::time::sleep(delay).await;
                     }
@@ -202,6 +310,7 @@ impl RetryExecutor {
         };
 
         loop {
+            let started = Instant::now();
             match operation(attempt).await {
                 Ok(result) => return Ok(result),
                 Err(err) => {
@@ -211,7 +320,10 @@ impl RetryExecutor {
                         return Err(err);
                     }
 
-                    let delay = self.policy.delay_for_attempt(attempt - 1);
+                    let delay = self.policy.delay_after_error(attempt - 1, &err);
+                    if !self.spend_budget(started.elapsed() + delay) {
+                        return Err(err);
+                    }
                     if delay > Duration::from_millis(0) {
                         tokio::time::sleep(delay).await;
                     }
@@ -224,7 +336,6 @@ impl RetryExecutor {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::error::OrchestratorError;
     use std::sync::atomic::{AtomicU32, Ordering};
     use std::sync::Arc;
 
@@ -318,6 +429,49 @@ mod tests {
         assert!(delay3.as_millis() >= 750 && delay3.as_millis() <= 1250);
     }
 
+    #[test]
+    fn test_delay_for_hint_never_shortens_hint() {
+        let hint = Duration::from_secs(60);
+        let policy = RetryPolicy::fixed_delay(3, Duration::from_millis(100));
+        assert_eq!(policy.delay_for_hint(hint), hint);
+
+        // Jitter only lengthens the hint, and max_delay does not cap it
+        let policy = RetryPolicy::default();
+        for _ in 0..10 {
+            let delay = policy.delay_for_hint(hint);
+            assert!(delay >= hint && delay <= Duration::from_secs(75));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retry_executor_honors_retry_after_hint() {
+        let policy = RetryPolicy::fixed_delay(1, Duration::from_millis(1));
+        let executor = RetryExecutor::new(policy);
+        let counter = Arc::new(AtomicU32::new(0));
+
+        let start = std::time::Instant::now();
+        let result = executor
+            .execute(|| {
+                let counter = counter.clone();
+                async move {
+                    if counter.fetch_add(1, Ordering::SeqCst) == 0 {
+                        Err(OrchestratorError::ProviderError {
+                            provider: "test".to_string(),
+                            message: "Rate limit exceeded".to_string(),
+                            retry_after: Some(Duration::from_millis(200)),
+                        })
+                    } else {
+                        Ok::<i32, OrchestratorError>(42)
+                    }
+                }
+            })
+            .await;
+
+        assert_eq!(result.unwrap(), 42);
+        assert_eq!(counter.load(Ordering::SeqCst), 2);
+        assert!(start.elapsed() >= Duration::from_millis(200));
+    }
+
     #[tokio::test]
     async fn test_retry_executor_success_on_first_attempt() {
         let policy = RetryPolicy::default();
@@ -364,6 +518,7 @@ mod tests {
                         Err(OrchestratorError::ProviderError {
                             provider: "test".to_string(),
                             message: "retryable error".to_string(),
+                            retry_after: None,
                         })
                     } else {
                         // Succeed on 3rd attempt
@@ -399,6 +554,7 @@ mod tests {
                     Err::<i32, OrchestratorError>(OrchestratorError::ProviderError {
                         provider: "test".to_string(),
                         message: "persistent error".to_string(),
+                        retry_after: None,
                     })
                 }
             })
@@ -458,6 +614,7 @@ mod tests {
                         Err(OrchestratorError::ProviderError {
                             provider: "test".to_string(),
                             message: "retry".to_string(),
+                            retry_after: None,
                         })
                     } else {
                         Ok::<i32, OrchestratorError>(42)
@@ -487,6 +644,7 @@ mod tests {
                     Err::<i32, OrchestratorError>(OrchestratorError::ProviderError {
                         provider: "test".to_string(),
                         message: "error".to_string(),
+                        retry_after: None,
                     })
                 }
             })
@@ -495,4 +653,74 @@ mod tests {
         assert!(result.is_err());
         assert_eq!(counter.load(Ordering::SeqCst), 1); // No retries
     }
+
+    #[test]
+    fn test_retry_budget_limits() {
+        let budget = RetryBudget::new(Some(2), None);
+        assert!(budget.try_spend(Duration::from_secs(10)));
+        assert!(budget.try_spend(Duration::from_secs(10)));
+        assert!(!budget.try_spend(Duration::ZERO));
+        assert_eq!(budget.retries_used(), 2);
+        assert_eq!(budget.extra_latency(), Duration::from_secs(20));
+
+        // A retry that would exceed the latency budget is not charged
+        let budget = RetryBudget::new(None, Some(Duration::from_secs(1)));
+        assert!(budget.try_spend(Duration::from_millis(600)));
+        assert!(!budget.try_spend(Duration::from_millis(600)));
+        assert!(budget.try_spend(Duration::from_millis(400)));
+        assert_eq!(budget.retries_used(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_retry_budget_is_shared_between_executors() {
+        let budget = Arc::new(RetryBudget::new(Some(3), None));
+        let counter = Arc::new(AtomicU32::new(0));
+
+        for _ in 0..2 {
+            let executor = RetryExecutor::new(RetryPolicy::fixed_delay(5, Duration::from_millis(1)))
+                .with_budget(budget.clone());
+            let result = executor
+                .execute(|| {
+                    let counter = counter.clone();
+                    async move {
+                        counter.fetch_add(1, Ordering::SeqCst);
+                        Err::<i32, OrchestratorError>(OrchestratorError::ProviderError {
+                            provider: "test".to_string(),
+                            message: "down".to_string(),
+                            retry_after: None,
+                        })
+                    }
+                })
+                .await;
+            assert!(result.is_err());
+        }
+
+        // Two initial attempts plus the three retries of the budget
+        assert_eq!(counter.load(Ordering::SeqCst), 5);
+        assert_eq!(budget.retries_used(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_retry_executor_with_retry_hook() {
+        let policy = RetryPolicy::fixed_delay(2, Duration::from_millis(1));
+        let executor = RetryExecutor::new(policy);
+        let mut retries = Vec::new();
+
+        let result: Result<()> = executor
+            .execute_with_retry_hook(
+                || async { Err(OrchestratorError::ProviderError {
+                    provider: "test".to_string(),
+                    message: "flaky".to_string(),
+                    retry_after: None,
+                }) },
+                |attempt, err| retries.push((attempt, err.to_string())),
+            )
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(retries.len(), 2);
+        assert_eq!(retries[0].0, 1);
+        assert_eq!(retries[1].0, 2);
+        assert!(retries[0].1.contains("flaky"));
+    }
 }
@@ -0,0 +1,313 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Secret references in workflow definitions (requires the `secrets` feature).
+//!
+//! LLM prompts and embedding inputs can reference secrets as
+//! `{{ secret "openai/api_key" }}`, and provider parameters of LLM steps can
+//! be given as `{ secretRef: "key" }`.
+//! References are resolved through the [`SecretStore`] attached with
+//! [`WorkflowExecutor::with_secret_store`] just before the provider call.
+//! Resolved values are cached for the run separately from its inputs and
+//! outputs and registered with the run's [`Redactor`], which masks them (and
+//! common API key formats) in step outputs, errors, dead letters, and
+//! persisted state.
+
+use crate::context::ExecutionContext;
+use crate::error::{OrchestratorError, Result};
+use crate::executor::WorkflowExecutor;
+use llm_orchestrator_secrets::{Redactor, SecretStore};
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::debug;
+
+/// Key of the object form of a secret reference.
+const SECRET_REF: &str = "secretRef";
+
+impl WorkflowExecutor {
+    /// Attach the secret store used to resolve secret references.
+    pub fn with_secret_store(mut self, secret_store: Arc<dyn SecretStore>) -> Self {
+        self.secret_store = Some(secret_store);
+        self
+    }
+
+    /// Use a shared redactor, e.g. one that is also attached to the audit logger.
+    ///
+    /// Replaces the default redactor, which masks common API key formats.
+    pub fn with_redactor(mut self, redactor: Redactor) -> Self {
+        self.redactor = redactor;
+        self
+    }
+
+    /// Returns the redactor masking this run's secrets.
+    pub fn redactor(&self) -> &Redactor {
+        &self.redactor
+    }
+
+    /// Fetch a secret into the run context unless it is already resolved.
+    pub(crate) async fn resolve_secret(&self, key: &str) -> Result<()> {
+        if self.context.has_secret(key) {
+            return Ok(());
+        }
+
+        let store = self.secret_store.as_ref().ok_or_else(|| {
+            OrchestratorError::other(format!(
+                "Secret '{}' is referenced but no secret store is registered",
+                key
+            ))
+        })?;
+        let result = store.get_secret(key).await;
+        #[cfg(feature = "audit")]
+        self.audit_secret_access(key, result.as_ref().err().map(|e| e.to_string()))
+            .await;
+        let secret = result
+            .map_err(|e| OrchestratorError::other(format!("Failed to resolve secret '{}': {}", key, e)))?;
+
+        debug!(secret_key = %key, "Resolved secret");
+        self.redactor.add_value(secret.value.as_str());
+        self.context.set_secret(key, secret.value);
+        Ok(())
+    }
+
+    /// Replace every `{ secretRef: "key" }` object in `value` with the secret value.
+    pub(crate) async fn resolve_secret_refs(&self, value: &mut Value) -> Result<()> {
+        let mut keys = Vec::new();
+        collect_secret_refs(value, &mut keys);
+        if keys.is_empty() {
+            return Ok(());
+        }
+
+        for key in &keys {
+            self.resolve_secret(key).await?;
+        }
+        replace_secret_refs(value, &self.context);
+        Ok(())
+    }
+}
+
+/// Returns the keys referenced with the `secret` helper in a template.
+pub(crate) fn template_secret_keys(template: &str) -> Vec<String> {
+    let mut keys = Vec::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start..].find("}}") else {
+            break;
+        };
+        let expression = rest[start + 2..start + end].trim_matches(|c: char| c == '~' || c.is_whitespace());
+        rest = &rest[start + end + 2..];
+
+        let Some(argument) = expression.strip_prefix("secret") else {
+            continue;
+        };
+        if !argument.starts_with(char::is_whitespace) {
+            continue;
+        }
+        let argument = argument.trim();
+        let Some(quote) = argument.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+            continue;
+        };
+        if let Some(key) = argument[1..].split(quote).next() {
+            if !keys.iter().any(|k| k == key) {
+                keys.push(key.to_string());
+            }
+        }
+    }
+
+    keys
+}
+
+/// Returns the key of a `{ secretRef: "key" }` object.
+fn secret_ref(value: &Value) -> Option<&str> {
+    match value {
+        Value::Object(fields) if fields.len() == 1 => fields.get(SECRET_REF)?.as_str(),
+        _ => None,
+    }
+}
+
+fn collect_secret_refs(value: &Value, keys: &mut Vec<String>) {
+    if let Some(key) = secret_ref(value) {
+        keys.push(key.to_string());
+        return;
+    }
+    match value {
+        Value::Array(items) => items.iter().for_each(|item| collect_secret_refs(item, keys)),
+        Value::Object(fields) => fields.values().for_each(|field| collect_secret_refs(field, keys)),
+        _ => {}
+    }
+}
+
+fn replace_secret_refs(value: &mut Value, context: &ExecutionContext) {
+    if let Some(key) = secret_ref(value) {
+        *value = Value::String(context.secret(key).unwrap_or_default());
+        return;
+    }
+    match value {
+        Value::Array(items) => items.iter_mut().for_each(|item| replace_secret_refs(item, context)),
+        Value::Object(fields) => fields.values_mut().for_each(|field| replace_secret_refs(field, context)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::StepStatus;
+    use crate::providers::{CompletionRequest, CompletionResponse, LLMProvider, ProviderError};
+    use crate::workflow::Workflow;
+    use llm_orchestrator_secrets::EnvSecretStore;
+    use parking_lot::Mutex;
+    use std::collections::HashMap;
+
+    /// Provider that remembers the last request it received.
+    #[derive(Default)]
+    struct CapturingProvider {
+        last_request: Mutex<Option<CompletionRequest>>,
+    }
+
+    #[async_trait::async_trait]
+    impl LLMProvider for CapturingProvider {
+        async fn complete(&self, request: CompletionRequest) -> std::result::Result<CompletionResponse, ProviderError> {
+            *self.last_request.lock() = Some(request);
+            Ok(CompletionResponse {
+                text: "ok".to_string(),
+                model: "gpt-4".to_string(),
+                tokens_used: None,
+                usage: None,
+                tool_calls: Vec::new(),
+                metadata: HashMap::new(),
+            })
+        }
+
+        fn name(&self) -> &str {
+            "capturing"
+        }
+    }
+
+    const WORKFLOW: &str = r#"
+name: "secret-workflow"
+steps:
+  - id: "call"
+    type: "llm"
+    provider: "openai"
+    model: "gpt-4"
+    prompt: 'Token: {{ secret "partner/token" }}'
+    organization:
+      secretRef: "partner/org"
+    output: ["answer"]
+"#;
+
+    #[test]
+    fn test_template_secret_keys() {
+        let keys = template_secret_keys(
+            r#"{{ secret "a/key" }} {{~secret 'b'~}} {{ secretive }} {{ name }} {{ secret "a/key" }}"#,
+        );
+        assert_eq!(keys, vec!["a/key".to_string(), "b".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_secrets_resolved_for_provider_only() {
+        std::env::set_var("SECRETS_TEST_PARTNER_TOKEN", "tok-123");
+        std::env::set_var("SECRETS_TEST_PARTNER_ORG", "org-456");
+
+        let provider = Arc::new(CapturingProvider::default());
+        let store = Arc::new(EnvSecretStore::with_prefix("SECRETS_TEST_".to_string()));
+        let executor = WorkflowExecutor::new(Workflow::from_yaml(WORKFLOW).unwrap(), HashMap::new())
+            .unwrap()
+            .with_provider("openai", provider.clone())
+            .with_secret_store(store);
+
+        let results = executor.execute().await.unwrap();
+        assert_eq!(results["call"].status, StepStatus::Completed);
+
+        let request = provider.last_request.lock().clone().unwrap();
+        assert_eq!(request.prompt, "Token: tok-123");
+        assert_eq!(request.extra["organization"], "org-456");
+
+        // Values stay out of results and the run context
+        let results = serde_json::to_string(&results).unwrap();
+        let outputs = serde_json::to_string(&executor.context.all_outputs()).unwrap();
+        for text in [results, outputs] {
+            assert!(!text.contains("tok-123") && !text.contains("org-456"));
+        }
+    }
+
+    /// Provider that echoes the prompt, or fails with a key in the error if asked.
+    struct LeakyProvider;
+
+    #[async_trait::async_trait]
+    impl LLMProvider for LeakyProvider {
+        async fn complete(&self, request: CompletionRequest) -> std::result::Result<CompletionResponse, ProviderError> {
+            if request.prompt.contains("fail") {
+                return Err(ProviderError::AuthError(
+                    "invalid key sk-live-abcdefghijklmnopqrstuvwx".to_string(),
+                ));
+            }
+            Ok(CompletionResponse {
+                text: request.prompt,
+                model: "gpt-4".to_string(),
+                tokens_used: None,
+                usage: None,
+                tool_calls: Vec::new(),
+                metadata: HashMap::new(),
+            })
+        }
+
+        fn name(&self) -> &str {
+            "leaky"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_secrets_redacted_from_outputs_and_errors() {
+        std::env::set_var("REDACT_TEST_PARTNER_TOKEN", "tok-789-abc");
+
+        let workflow = Workflow::from_yaml(
+            r#"
+name: "leaky-workflow"
+steps:
+  - id: "echo"
+    type: "llm"
+    provider: "leaky"
+    model: "gpt-4"
+    prompt: 'Echo {{ secret "partner/token" }}'
+    output: ["answer"]
+  - id: "fail"
+    type: "llm"
+    provider: "leaky"
+    model: "gpt-4"
+    prompt: "fail"
+    output: ["answer"]
+    retry:
+      max_attempts: 1
+"#,
+        )
+        .unwrap();
+        let executor = WorkflowExecutor::new(workflow, HashMap::new())
+            .unwrap()
+            .with_provider("leaky", Arc::new(LeakyProvider))
+            .with_secret_store(Arc::new(EnvSecretStore::with_prefix("REDACT_TEST_".to_string())));
+
+        let err = executor.execute().await.unwrap_err();
+        assert!(err.to_string().ends_with("invalid key [REDACTED]"));
+        let results = &executor.step_results;
+        assert_eq!(results.get("echo").unwrap().outputs["answer"], "Echo [REDACTED]");
+        assert_eq!(
+            executor.context.get_output("echo").unwrap()["answer"],
+            "Echo [REDACTED]"
+        );
+        assert!(results.get("fail").unwrap().error.as_ref().unwrap().ends_with("invalid key [REDACTED]"));
+    }
+
+    #[tokio::test]
+    async fn test_secret_without_store_fails_step() {
+        let executor = WorkflowExecutor::new(Workflow::from_yaml(WORKFLOW).unwrap(), HashMap::new())
+            .unwrap()
+            .with_provider("openai", Arc::new(CapturingProvider::default()));
+
+        let err = executor.execute().await.unwrap_err();
+        assert!(err.to_string().contains("no secret store"));
+        let result = executor.step_results.get("call").unwrap();
+        assert_eq!(result.status, StepStatus::Failed);
+    }
+}
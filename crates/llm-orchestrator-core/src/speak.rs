@@ -0,0 +1,217 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Text-to-speech synthesis.
+//!
+//! A `speak` step renders text and has a provider registered with
+//! [`WorkflowExecutor::with_speech_provider`] read it aloud, so the answer of
+//! a voice assistant can be played back:
+//!
+//! ```yaml
+//! - id: answer
+//!   type: llm
+//!   provider: anthropic
+//!   model: claude-3-5-haiku-20241022
+//!   prompt: "Answer briefly: {{ steps.transcribe.transcript }}"
+//!   output: [answer]
+//!
+//! - id: reply
+//!   type: speak
+//!   depends_on: [answer]
+//!   provider: openai
+//!   model: tts-1
+//!   voice: alloy
+//!   text: "{{ steps.answer.answer }}"
+//!   format: mp3
+//!   path: "replies/{{ inputs.call_id }}.mp3"   # Optional
+//!   output: [audio_path, audio_bytes]
+//! ```
+//!
+//! Without a `path`, the audio is stored in the run's artifact store as
+//! `speech.<format>` and its URI emitted in place of the path.
+
+use crate::error::{OrchestratorError, Result};
+use crate::executor::WorkflowExecutor;
+use crate::providers::{provider_error, SpeechRequest};
+use crate::workflow::{Step, StepConfig};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tracing::{debug, info, Span};
+
+/// Output names used when a step declares none.
+const DEFAULT_OUTPUTS: [&str; 2] = ["path", "bytes"];
+
+impl WorkflowExecutor {
+    /// Executes a speak step.
+    pub(crate) async fn execute_speak_step(&self, step: &Step) -> Result<HashMap<String, Value>> {
+        let config = match &step.config {
+            StepConfig::Speak(config) => config,
+            _ => {
+                return Err(OrchestratorError::InvalidStepConfig {
+                    step_id: step.id.clone(),
+                    reason: "Expected Speak step config".to_string(),
+                })
+            }
+        };
+        let provider = self
+            .speech_providers
+            .get(&config.provider)
+            .map(|provider| provider.value().clone())
+            .ok_or_else(|| OrchestratorError::other(format!("Speech provider '{}' not registered", config.provider)))?;
+
+        self.resolve_template_secrets(&config.text).await?;
+        let text = self.context.render_template(&config.text)?;
+        if text.trim().is_empty() {
+            return Err(OrchestratorError::InvalidStepConfig {
+                step_id: step.id.clone(),
+                reason: "Text to speak is empty".to_string(),
+            });
+        }
+        let path = match &config.path {
+            Some(template) => Some(PathBuf::from(self.context.render_template(template)?)),
+            None => None,
+        };
+
+        let mut request = SpeechRequest {
+            model: config.model.clone(),
+            text,
+            voice: config.voice.clone(),
+            format: config.format.clone(),
+            speed: config.speed,
+            extra: config.extra.clone(),
+        };
+        self.resolve_request_secrets(&mut request.extra).await?;
+
+        debug!(
+            step_id = %step.id,
+            provider = %config.provider,
+            model = %config.model,
+            characters = request.text.chars().count(),
+            "Calling speech provider"
+        );
+        let span = Span::current();
+        span.record("provider", config.provider.as_str());
+        span.record("model", config.model.as_str());
+
+        self.record_request(&step.id, &request);
+        #[cfg(feature = "audit")]
+        let text = request.text.clone();
+        let started = std::time::Instant::now();
+        let response_result = provider.synthesize(request).await;
+        self.record_provider_latency(&step.id, started.elapsed());
+
+        #[cfg(feature = "audit")]
+        self.audit_provider_call(crate::audit::ProviderCall {
+            step_id: &step.id,
+            provider: &config.provider,
+            model: &config.model,
+            prompt: &text,
+            completion: None,
+            tokens_used: None,
+            error: response_result.as_ref().err().map(|e| e.to_string()),
+            duration: started.elapsed(),
+        })
+        .await;
+
+        let response = response_result.map_err(|e| provider_error(&config.provider, "Speech provider error", e))?;
+
+        let bytes = response.audio.len();
+        let location = match path {
+            Some(path) => {
+                let failed = |e: std::io::Error| OrchestratorError::other(format!("Failed to write audio '{}': {}", path.display(), e));
+                if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+                    tokio::fs::create_dir_all(parent).await.map_err(failed)?;
+                }
+                tokio::fs::write(&path, &response.audio).await.map_err(failed)?;
+                path.display().to_string()
+            }
+            None => {
+                let name = format!("speech.{}", config.format);
+                self.store_artifact(&step.id, &name, response.audio).await?.uri
+            }
+        };
+        info!(step_id = %step.id, location = %location, bytes, "Speech synthesized");
+
+        let names: Vec<&str> = if step.output.is_empty() {
+            DEFAULT_OUTPUTS.to_vec()
+        } else {
+            step.output.iter().map(String::as_str).collect()
+        };
+        let values = [Value::String(location), Value::from(bytes)];
+        Ok(names.into_iter().map(String::from).zip(values).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::{ProviderError, SpeechProvider, SpeechResponse};
+    use crate::workflow::Workflow;
+    use async_trait::async_trait;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    /// Speaks any text as its voice, format, and text.
+    struct Announcer;
+
+    #[async_trait]
+    impl SpeechProvider for Announcer {
+        async fn synthesize(&self, request: SpeechRequest) -> std::result::Result<SpeechResponse, ProviderError> {
+            Ok(SpeechResponse {
+                audio: format!("{}/{}: {}", request.voice, request.format, request.text).into_bytes(),
+                content_type: None,
+                model: request.model,
+            })
+        }
+
+        fn name(&self) -> &str {
+            "announcer"
+        }
+    }
+
+    async fn speak(path: Option<&str>) -> Result<(WorkflowExecutor, HashMap<String, Value>)> {
+        let path = path.map(|path| format!("\n    path: \"{}\"", path)).unwrap_or_default();
+        let workflow = Workflow::from_yaml(&format!(
+            r#"
+name: "assistant"
+steps:
+  - id: "reply"
+    type: "speak"
+    provider: "announcer"
+    model: "tts-1"
+    voice: "alloy"
+    text: "Hello, {{{{ inputs.name }}}}."{}
+"#,
+            path
+        ))
+        .unwrap();
+        assert!(matches!(workflow.steps[0].config, StepConfig::Speak(_)));
+        let inputs = HashMap::from([("name".to_string(), json!("Ada"))]);
+        let executor = WorkflowExecutor::new(workflow, inputs)?.with_speech_provider("announcer", Arc::new(Announcer));
+        let results = executor.execute().await?;
+        let outputs = results["reply"].outputs.clone();
+        Ok((executor, outputs))
+    }
+
+    #[tokio::test]
+    async fn test_speak_writes_audio_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("replies/ada.mp3");
+        let (_, outputs) = speak(Some(path.to_str().unwrap())).await.unwrap();
+        assert_eq!(outputs["path"], json!(path.display().to_string()));
+        assert_eq!(outputs["bytes"], json!(22));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "alloy/mp3: Hello, Ada.");
+
+        // Without a path, the audio is stored as an artifact of the run
+        let (executor, outputs) = speak(None).await.unwrap();
+        let artifacts = executor.artifacts();
+        assert_eq!(artifacts.len(), 1);
+        assert_eq!(outputs["path"], json!(artifacts[0].uri));
+        assert_eq!(artifacts[0].name, "speech.mp3");
+        assert_eq!(artifacts[0].content_type, "audio/mpeg");
+        let path = PathBuf::from(artifacts[0].uri.strip_prefix("file://").unwrap());
+        assert!(path.ends_with(format!("{}/reply/speech.mp3", executor.run_id())));
+        std::fs::remove_dir_all(path.parent().unwrap().parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,424 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Spilling step outputs out of memory during a run.
+//!
+//! An executor given an [`OutputSpill`] store with
+//! [`WorkflowExecutor::with_output_spill`] keeps the step outputs it holds in
+//! memory under [`ContextLimits::memory_cap`](crate::ContextLimits::memory_cap).
+//! Each time a step finishes, the outputs of the steps that finished first
+//! are written to the store, keyed `spill/<run id>/<step id>.json`, and
+//! dropped from the context and the step results. Before a step starts, the spilled outputs its definition names
+//! are read back, so its templates render as if they had never left; outputs
+//! a running step names are not spilled until it finishes.
+//!
+//! Outputs are spilled to an [`ArtifactStore`] with [`ArtifactSpill`] or, with
+//! the `state-persistence` feature, to a state store blob backend with
+//! [`BlobSpill`]. The persisted state of a spilled step holds a reference in
+//! place of its outputs:
+//!
+//! ```json
+//! {"$spilled": {"reference": "file:///var/spill/spill/<run id>/<step id>.json", "size": 1048576}}
+//! ```
+//!
+//! The results [`WorkflowExecutor::execute`] returns and writes to result
+//! sinks hold the outputs read back, the same as in a run that spilled
+//! nothing. While the run executes, [`WorkflowExecutor::load_outputs`] reads
+//! the outputs of a spilled step back.
+
+use crate::artifact::ArtifactStore;
+use crate::error::Result;
+use crate::executor::{StepResult, WorkflowExecutor};
+use crate::workflow::Step;
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+/// Field marking a spilled output.
+const SPILLED_FIELD: &str = "$spilled";
+
+/// Storage for step outputs spilled out of memory.
+#[async_trait]
+pub trait OutputSpill: Send + Sync {
+    /// Stores the JSON of a step output under `key`, returning the reference
+    /// to read it back with.
+    async fn put(&self, key: &str, bytes: Vec<u8>) -> Result<String>;
+
+    /// Reads an output stored under `reference`.
+    async fn get(&self, reference: &str) -> Result<Vec<u8>>;
+}
+
+/// Spills outputs to an artifact store; references are artifact URIs.
+pub struct ArtifactSpill {
+    store: Arc<dyn ArtifactStore>,
+}
+
+impl ArtifactSpill {
+    pub fn new(store: Arc<dyn ArtifactStore>) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl OutputSpill for ArtifactSpill {
+    async fn put(&self, key: &str, bytes: Vec<u8>) -> Result<String> {
+        self.store.put(key, bytes, "application/json").await
+    }
+
+    async fn get(&self, reference: &str) -> Result<Vec<u8>> {
+        self.store.get(reference).await
+    }
+}
+
+/// Spills outputs to a state store blob backend; references are blob keys.
+#[cfg(feature = "state-persistence")]
+pub struct BlobSpill {
+    store: Arc<dyn llm_orchestrator_state::BlobStore>,
+}
+
+#[cfg(feature = "state-persistence")]
+impl BlobSpill {
+    pub fn new(store: Arc<dyn llm_orchestrator_state::BlobStore>) -> Self {
+        Self { store }
+    }
+}
+
+#[cfg(feature = "state-persistence")]
+#[async_trait]
+impl OutputSpill for BlobSpill {
+    async fn put(&self, key: &str, bytes: Vec<u8>) -> Result<String> {
+        self.store
+            .put(key, &bytes)
+            .await
+            .map_err(|e| crate::error::OrchestratorError::other(format!("Failed to spill output {}: {}", key, e)))?;
+        Ok(key.to_string())
+    }
+
+    async fn get(&self, reference: &str) -> Result<Vec<u8>> {
+        self.store
+            .get(reference)
+            .await
+            .map_err(|e| crate::error::OrchestratorError::other(format!("Failed to read spilled output {}: {}", reference, e)))
+    }
+}
+
+/// The value standing in for an output spilled under `reference`.
+pub fn spilled_value(reference: &str, size: usize) -> Value {
+    json!({ SPILLED_FIELD: { "reference": reference, "size": size } })
+}
+
+/// The reference of a value standing in for a spilled output.
+pub fn spilled_reference(value: &Value) -> Option<&str> {
+    let object = value.as_object().filter(|object| object.len() == 1)?;
+    object.get(SPILLED_FIELD)?.get("reference")?.as_str()
+}
+
+/// The reference of step outputs replaced by their spill reference.
+fn spilled_outputs_reference(outputs: &HashMap<String, Value>) -> Option<&str> {
+    if outputs.len() != 1 {
+        return None;
+    }
+    outputs.get(SPILLED_FIELD)?.get("reference")?.as_str()
+}
+
+/// An executor's spill store and the outputs running steps name.
+pub(crate) struct SpillState {
+    store: Arc<dyn OutputSpill>,
+    /// Steps named by running steps, with the number of steps naming them.
+    pinned: Mutex<HashMap<String, usize>>,
+    /// Held while spilling or reading back, so each happens once.
+    busy: tokio::sync::Mutex<()>,
+}
+
+impl SpillState {
+    pub(crate) fn new(store: Arc<dyn OutputSpill>) -> Self {
+        Self {
+            store,
+            pinned: Mutex::new(HashMap::new()),
+            busy: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    fn is_pinned(&self, step_id: &str) -> bool {
+        self.pinned.lock().contains_key(step_id)
+    }
+}
+
+/// Keeps the outputs a step names in memory until dropped.
+pub(crate) struct PinnedOutputs {
+    spill: Arc<SpillState>,
+    step_ids: Vec<String>,
+}
+
+impl PinnedOutputs {
+    fn new(spill: Arc<SpillState>, step_ids: Vec<String>) -> Self {
+        let mut pinned = spill.pinned.lock();
+        for step_id in &step_ids {
+            *pinned.entry(step_id.clone()).or_default() += 1;
+        }
+        drop(pinned);
+        Self { spill, step_ids }
+    }
+}
+
+impl Drop for PinnedOutputs {
+    fn drop(&mut self) {
+        let mut pinned = self.spill.pinned.lock();
+        for step_id in &self.step_ids {
+            if let Some(count) = pinned.get_mut(step_id) {
+                *count -= 1;
+                if *count == 0 {
+                    pinned.remove(step_id);
+                }
+            }
+        }
+    }
+}
+
+impl WorkflowExecutor {
+    /// Spills step outputs to `store` once those in memory exceed the
+    /// context's `memory_cap`, in place of spilling blobs to temporary files.
+    pub fn with_output_spill(mut self, store: Arc<dyn OutputSpill>) -> Self {
+        self.output_spill = Some(Arc::new(SpillState::new(store)));
+        self.context = Arc::new(self.context.as_ref().clone().with_store_spill());
+        self
+    }
+
+    /// Outputs of a step, read back from the spill store if they were spilled.
+    pub async fn load_outputs(&self, step_id: &str) -> Result<Option<HashMap<String, Value>>> {
+        let Some(outputs) = self.step_results.get(step_id).map(|result| result.outputs.clone()) else {
+            return Ok(None);
+        };
+        match (spilled_outputs_reference(&outputs), &self.output_spill) {
+            (Some(reference), Some(spill)) => {
+                let bytes = spill.store.get(reference).await?;
+                Ok(Some(serde_json::from_slice(&bytes)?))
+            }
+            _ => Ok(Some(outputs)),
+        }
+    }
+
+    /// Replaces the spill references in `results` with the outputs they stand for.
+    pub(crate) async fn read_back_results(&self, results: &mut HashMap<String, StepResult>) -> Result<()> {
+        let Some(spill) = &self.output_spill else {
+            return Ok(());
+        };
+        for result in results.values_mut() {
+            let Some(reference) = spilled_outputs_reference(&result.outputs) else {
+                continue;
+            };
+            let bytes = spill.store.get(reference).await?;
+            result.outputs = serde_json::from_slice(&bytes)?;
+        }
+        Ok(())
+    }
+
+    /// Reads back the spilled outputs `step` names, keeping every output it
+    /// names in memory until the returned guard is dropped.
+    pub(crate) async fn restore_outputs(&self, step: &Step) -> Result<Option<PinnedOutputs>> {
+        let Some(spill) = &self.output_spill else {
+            return Ok(None);
+        };
+        // Templates name steps by ID, as the context assumes when it loads blobs
+        let definition = serde_json::to_string(step)?;
+        let definition = crate::partials::expand(&definition, &self.workflow.partials);
+        let named: Vec<String> = self
+            .workflow
+            .steps
+            .iter()
+            .filter(|other| other.id != step.id && definition.contains(other.id.as_str()))
+            .map(|other| other.id.clone())
+            .collect();
+        let pinned = PinnedOutputs::new(spill.clone(), named);
+
+        let _busy = spill.busy.lock().await;
+        for (step_id, reference) in self.context.spilled_outputs() {
+            if !pinned.step_ids.contains(&step_id) {
+                continue;
+            }
+            let bytes = spill.store.get(&reference).await?;
+            debug!(step_id = %step.id, spilled_step = %step_id, bytes = bytes.len(), "Read back spilled output");
+            self.context.restore_output(&step_id, bytes)?;
+        }
+        Ok(Some(pinned))
+    }
+
+    /// Spills the outputs held in memory, oldest first and other than those
+    /// running steps name, until the rest fit the context's memory cap.
+    pub(crate) async fn spill_outputs(&self) {
+        let (Some(spill), Some(cap)) = (&self.output_spill, self.context.limits().memory_cap) else {
+            return;
+        };
+        let _busy = spill.busy.lock().await;
+        let mut resident = self.context.resident_outputs();
+        // Outputs restored from a checkpoint have no timing and go first
+        resident.sort_by_cached_key(|(step_id, _)| {
+            let completed_at = self.step_timings.get(step_id).and_then(|timing| timing.completed_at);
+            (completed_at, step_id.clone())
+        });
+        let mut in_memory: usize = resident.iter().map(|(_, size)| size).sum();
+        for (step_id, size) in resident {
+            if in_memory <= cap {
+                break;
+            }
+            if spill.is_pinned(&step_id) {
+                continue;
+            }
+            let Some(bytes) = self.context.output_bytes(&step_id) else {
+                continue;
+            };
+            let key = format!("spill/{}/{}.json", self.run_id, step_id);
+            let reference = match spill.store.put(&key, bytes).await {
+                Ok(reference) => reference,
+                Err(e) => {
+                    warn!(step_id = %step_id, error = %e, "Failed to spill step output; keeping it in memory");
+                    continue;
+                }
+            };
+            self.context.mark_spilled(&step_id, &reference);
+            if let Some(mut result) = self.step_results.get_mut(&step_id) {
+                let Value::Object(marker) = spilled_value(&reference, size) else {
+                    unreachable!("spilled values are objects");
+                };
+                result.outputs = marker.into_iter().collect();
+            }
+            in_memory -= size;
+            info!(step_id = %step_id, reference = %reference, bytes = size, "Spilled step output");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::artifact::LocalArtifactStore;
+    use crate::context::ContextLimits;
+    use crate::providers::{CompletionRequest, CompletionResponse, LLMProvider, ProviderError};
+    use crate::workflow::Workflow;
+
+    #[test]
+    fn test_spilled_reference_round_trip() {
+        let value = spilled_value("file:///spill/a.json", 42);
+        assert_eq!(spilled_reference(&value), Some("file:///spill/a.json"));
+        assert_eq!(spilled_reference(&json!({"text": "hello"})), None);
+        assert_eq!(spilled_reference(&json!({"$spilled": {"reference": "x"}, "other": 1})), None);
+    }
+
+    #[tokio::test]
+    async fn test_outputs_spill_past_the_cap_and_are_read_back_by_steps_naming_them() {
+        let dir = tempfile::tempdir().unwrap();
+        let yaml = r#"
+name: spill
+steps:
+  - id: first
+    type: transform
+    function: normalize
+    inputs: [doc]
+  - id: second
+    type: transform
+    function: normalize
+    inputs: [doc]
+  - id: combined
+    type: transform
+    function: normalize
+    depends_on: [first]
+    inputs: ["steps.first.text"]
+"#;
+        let doc = "x".repeat(2000);
+        let workflow = Workflow::from_yaml(yaml).unwrap();
+        let store = Arc::new(LocalArtifactStore::new(dir.path()).unwrap());
+        let executor = WorkflowExecutor::new(workflow, HashMap::from([("doc".to_string(), json!(doc))]))
+            .unwrap()
+            .with_max_concurrency(1)
+            .with_context_limits(ContextLimits {
+                memory_cap: Some(3000),
+                ..ContextLimits::default()
+            })
+            .with_output_spill(Arc::new(ArtifactSpill::new(store)));
+
+        let results = executor.execute().await.unwrap();
+
+        // `combined` read `first` back, then the two older outputs were spilled again
+        assert_eq!(results["combined"].outputs["text"], json!(doc));
+        for step_id in ["first", "second"] {
+            let outputs = executor.step_results.get(step_id).unwrap().outputs.clone();
+            let reference = spilled_outputs_reference(&outputs).unwrap();
+            assert!(reference.ends_with(&format!("spill/{}/{}.json", executor.run_id(), step_id)));
+            let outputs = executor.load_outputs(step_id).await.unwrap().unwrap();
+            assert_eq!(outputs["text"], json!(doc));
+            // The returned results match a run that spilled nothing
+            assert_eq!(results[step_id].outputs, outputs);
+        }
+
+        let memory = executor.context_memory();
+        assert!(memory.inline_bytes <= 3000);
+        assert_eq!(memory.spilled_bytes, 2 * memory.inline_bytes);
+    }
+
+    /// Answers with the prompt it was sent.
+    struct EchoProvider;
+
+    #[async_trait::async_trait]
+    impl LLMProvider for EchoProvider {
+        async fn complete(&self, request: CompletionRequest) -> std::result::Result<CompletionResponse, ProviderError> {
+            Ok(CompletionResponse {
+                text: request.prompt,
+                model: request.model,
+                tokens_used: None,
+                usage: None,
+                tool_calls: Vec::new(),
+                metadata: HashMap::new(),
+            })
+        }
+
+        fn name(&self) -> &str {
+            "echo"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_spilled_outputs_read_through_partials_are_read_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let yaml = r#"
+name: spill
+partials:
+  quote: "Quote: {{> body }}"
+  body: "{{ steps.first.text }}"
+steps:
+  - id: first
+    type: transform
+    function: normalize
+    inputs: [doc]
+  - id: second
+    type: transform
+    function: normalize
+    inputs: [doc]
+    depends_on: [first]
+  - id: answer
+    type: llm
+    provider: echo
+    model: echo
+    prompt: "{{> quote }}"
+    depends_on: [second]
+    output: [text]
+"#;
+        let doc = "x".repeat(2000);
+        let workflow = Workflow::from_yaml(yaml).unwrap();
+        let store = Arc::new(LocalArtifactStore::new(dir.path()).unwrap());
+        let executor = WorkflowExecutor::new(workflow, HashMap::from([("doc".to_string(), json!(doc))]))
+            .unwrap()
+            .with_provider("echo", Arc::new(EchoProvider))
+            .with_max_concurrency(1)
+            .with_context_limits(ContextLimits {
+                memory_cap: Some(3000),
+                ..ContextLimits::default()
+            })
+            .with_output_spill(Arc::new(ArtifactSpill::new(store)));
+
+        let results = executor.execute().await.unwrap();
+        assert_eq!(results["answer"].outputs["text"], json!(format!("Quote: {}", doc)));
+    }
+}
@@ -0,0 +1,498 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reusable step templates.
+//!
+//! A workflow can define groups of steps once under `templates` and
+//! instantiate them as often as it needs. Templates are expanded when the
+//! workflow is loaded, before it is validated:
+//!
+//! ```yaml
+//! templates:
+//!   summarize_doc:
+//!     params:
+//!       doc: ~        # required
+//!       lang: en      # optional, with a default
+//!     steps:
+//!       - id: translate
+//!         type: llm
+//!         provider: openai
+//!         model: gpt-4
+//!         prompt: "Translate into {{ params.lang }}: {{ params.doc }}"
+//!         output: [text]
+//!       - id: summarize
+//!         type: llm
+//!         depends_on: [translate]
+//!         provider: openai
+//!         model: gpt-4
+//!         prompt: "Summarize: {{ steps.translate.text }}"
+//!         output: [text]
+//!
+//! steps:
+//!   - id: french
+//!     use: summarize_doc
+//!     with: {doc: "{{ inputs.doc }}", lang: fr}
+//! ```
+//!
+//! Each instance adds the template's steps, with the instance ID prefixed to
+//! their IDs (`french_translate`, `french_summarize`) and to the references
+//! between them. `{{ params.<name> }}` placeholders are replaced by the
+//! instance's arguments; a string holding nothing but a placeholder takes the
+//! argument as is, so numbers and lists can be passed too.
+//!
+//! The instance's `depends_on` applies to the template's first steps (those
+//! depending on no other step of the template), and steps depending on the
+//! instance depend on its last steps (those no other step of the template
+//! depends on). Other steps read an instance's outputs by the expanded step
+//! IDs, e.g. `{{ steps.french_summarize.text }}`.
+
+use crate::diagnostics::{did_you_mean, Diagnostic, DiagnosticCode};
+use crate::workflow::Workflow;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+
+/// Workflow field defining the templates.
+pub(crate) const TEMPLATES_FIELD: &str = "templates";
+
+/// Step field naming the template a step instantiates.
+const USE_FIELD: &str = "use";
+
+/// A reusable group of steps.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct StepTemplate {
+    /// Description of what the template does.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Parameters and their defaults; a parameter without a default (`~`) is required.
+    #[serde(default)]
+    pub params: HashMap<String, Value>,
+
+    /// Steps added by each instance, which may use `{{ params.<name> }}` placeholders.
+    ///
+    /// Step IDs are local to the template.
+    pub steps: Vec<Value>,
+}
+
+/// A step instantiating a template.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct TemplateInstance {
+    /// Instance identifier, prefixed to the IDs of the template's steps.
+    pub id: String,
+
+    /// Name of the template.
+    #[serde(rename = "use")]
+    pub template: String,
+
+    /// Arguments for the template's parameters.
+    #[serde(rename = "with", default, skip_serializing_if = "HashMap::is_empty")]
+    pub args: HashMap<String, Value>,
+
+    /// Dependencies of the template's first steps.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+}
+
+/// Parses a workflow document, expanding its step templates.
+pub(crate) fn instantiate(document: impl Serialize) -> Result<Workflow, Box<Diagnostic>> {
+    let mut document = serde_json::to_value(document).map_err(parse_error)?;
+    expand(&mut document)?;
+    Ok(serde_json::from_value(document).map_err(parse_error)?)
+}
+
+fn parse_error(error: serde_json::Error) -> Diagnostic {
+    Diagnostic::new(DiagnosticCode::Parse, error.to_string())
+}
+
+/// Replaces the template instances among the steps of `document` by the
+/// steps they add, and removes the templates.
+fn expand(document: &mut Value) -> Result<(), Box<Diagnostic>> {
+    let Some(templates) = document.as_object_mut().and_then(|fields| fields.remove(TEMPLATES_FIELD)) else {
+        return Ok(());
+    };
+    let templates: HashMap<String, StepTemplate> = serde_json::from_value(templates).map_err(|e| {
+        Diagnostic::new(DiagnosticCode::InvalidTemplate, format!("Invalid templates: {}", e)).at(TEMPLATES_FIELD)
+    })?;
+    let Some(Value::Array(steps)) = document.get_mut("steps") else {
+        return Ok(());
+    };
+
+    let mut expanded = Vec::with_capacity(steps.len());
+    // Instance ID -> IDs of its last steps
+    let mut instances: HashMap<String, Vec<String>> = HashMap::new();
+    for (index, step) in std::mem::take(steps).into_iter().enumerate() {
+        if step.get(USE_FIELD).is_none() {
+            expanded.push(step);
+            continue;
+        }
+        let path = format!("steps[{}]", index);
+        let instance: TemplateInstance = serde_json::from_value(step).map_err(|e| {
+            Diagnostic::new(DiagnosticCode::InvalidTemplate, format!("Invalid template instance: {}", e)).at(&path)
+        })?;
+        let Some(template) = templates.get(&instance.template) else {
+            let diagnostic = Diagnostic::new(
+                DiagnosticCode::InvalidTemplate,
+                format!("Step '{}' uses unknown template '{}'", instance.id, instance.template),
+            )
+            .step(&instance.id)
+            .at(format!("{}.{}", path, USE_FIELD));
+            return Err(Box::new(match did_you_mean(&instance.template, templates.keys().map(String::as_str)) {
+                Some(suggestion) => diagnostic.suggest(suggestion),
+                None => diagnostic,
+            }));
+        };
+        let last = add_instance(template, &instance, &path, &mut expanded)?;
+        instances.insert(instance.id, last);
+    }
+
+    // Steps depending on an instance depend on its last steps
+    for step in &mut expanded {
+        let Some(Value::Array(depends_on)) = step.get_mut("depends_on") else {
+            continue;
+        };
+        let mut resolved: Vec<Value> = Vec::with_capacity(depends_on.len());
+        for dependency in depends_on.drain(..) {
+            let ids = dependency.as_str().and_then(|id| instances.get(id));
+            for value in ids.map_or_else(|| vec![dependency], |ids| ids.iter().cloned().map(Value::String).collect()) {
+                if !resolved.contains(&value) {
+                    resolved.push(value);
+                }
+            }
+        }
+        *depends_on = resolved;
+    }
+
+    *steps = expanded;
+    Ok(())
+}
+
+/// Adds the steps of an instance of `template` to `steps`. Returns the IDs of its last steps.
+fn add_instance(
+    template: &StepTemplate,
+    instance: &TemplateInstance,
+    path: &str,
+    steps: &mut Vec<Value>,
+) -> Result<Vec<String>, Box<Diagnostic>> {
+    let invalid = |message: String| Diagnostic::new(DiagnosticCode::InvalidTemplate, message).step(&instance.id);
+
+    let mut names: Vec<&str> = template.params.keys().map(String::as_str).collect();
+    names.sort();
+    for name in instance.args.keys() {
+        if !template.params.contains_key(name) {
+            let diagnostic = invalid(format!("Template '{}' has no parameter '{}'", instance.template, name))
+                .at(format!("{}.with.{}", path, name));
+            return Err(Box::new(match did_you_mean(name, names.iter().copied()) {
+                Some(suggestion) => diagnostic.suggest(suggestion),
+                None => diagnostic,
+            }));
+        }
+    }
+    let mut args = HashMap::new();
+    for name in names {
+        let value = match instance.args.get(name) {
+            Some(value) => value,
+            None if !template.params[name].is_null() => &template.params[name],
+            None => {
+                return Err(invalid(format!(
+                    "Step '{}' is missing argument '{}' of template '{}'",
+                    instance.id, name, instance.template
+                ))
+                .at(path)
+                .into());
+            }
+        };
+        args.insert(name, value);
+    }
+
+    let local: HashSet<&str> = template.steps.iter().filter_map(|step| step.get("id")?.as_str()).collect();
+    let prefixed = |id: &str| format!("{}_{}", instance.id, id);
+    let mut depended_on = HashSet::new();
+    for (index, step) in template.steps.iter().enumerate() {
+        let step_path = format!("{}.{}.steps[{}]", TEMPLATES_FIELD, instance.template, index);
+        if step.get(USE_FIELD).is_some() {
+            return Err(invalid(format!("Template '{}' cannot use another template", instance.template))
+                .at(step_path)
+                .into());
+        }
+
+        let mut step = step.clone();
+        rename_references(&mut step, &local, &instance.id);
+        substitute(&mut step, &args).map_err(|name| {
+            invalid(format!("Template '{}' has no parameter '{}'", instance.template, name)).at(&step_path)
+        })?;
+
+        let Value::Object(fields) = &mut step else {
+            steps.push(step);
+            continue;
+        };
+        if let Some(Value::String(id)) = fields.get_mut("id") {
+            *id = prefixed(id);
+        }
+        let mut first = true;
+        if let Some(Value::Array(depends_on)) = fields.get_mut("depends_on") {
+            for dependency in depends_on.iter_mut() {
+                if let Value::String(id) = dependency {
+                    if local.contains(id.as_str()) {
+                        depended_on.insert(id.clone());
+                        *id = prefixed(id);
+                        first = false;
+                    }
+                }
+            }
+        }
+        if first && !instance.depends_on.is_empty() {
+            if let Value::Array(depends_on) = fields.entry("depends_on").or_insert_with(|| Value::Array(Vec::new())) {
+                depends_on.extend(instance.depends_on.iter().cloned().map(Value::String));
+            }
+        }
+        // Fallback and rejection steps
+        let fallback = fields.get_mut("on_failure").and_then(|policy| policy.get_mut("run_step"));
+        if let Some(Value::String(id)) = fallback {
+            if local.contains(id.as_str()) {
+                *id = prefixed(id);
+            }
+        }
+        if let Some(Value::String(id)) = fields.get_mut("on_reject") {
+            if local.contains(id.as_str()) {
+                *id = prefixed(id);
+            }
+        }
+        steps.push(step);
+    }
+
+    Ok(template
+        .steps
+        .iter()
+        .filter_map(|step| step.get("id")?.as_str())
+        .filter(|id| !depended_on.contains(*id))
+        .map(prefixed)
+        .collect())
+}
+
+/// Prefixes `instance` to the `steps.<id>` and `outputs.<id>` references to
+/// the `local` steps in the strings of `value`.
+fn rename_references(value: &mut Value, local: &HashSet<&str>, instance: &str) {
+    match value {
+        Value::String(text) => {
+            let mut renamed = String::with_capacity(text.len());
+            let mut rest = text.as_str();
+            while let Some(start) = ["steps.", "outputs."].iter().filter_map(|namespace| rest.find(namespace)).min() {
+                let id_start = start + rest[start..].find('.').expect("namespace ends with a dot") + 1;
+                let id_end = rest[id_start..].find(|c| !is_identifier_char(c)).map_or(rest.len(), |end| id_start + end);
+                let id = &rest[id_start..id_end];
+                renamed.push_str(&rest[..id_start]);
+                if !rest[..start].ends_with(is_identifier_char) && local.contains(id) {
+                    renamed.push_str(instance);
+                    renamed.push('_');
+                }
+                renamed.push_str(id);
+                rest = &rest[id_end..];
+            }
+            renamed.push_str(rest);
+            *text = renamed;
+        }
+        Value::Array(items) => items.iter_mut().for_each(|item| rename_references(item, local, instance)),
+        Value::Object(fields) => fields.values_mut().for_each(|field| rename_references(field, local, instance)),
+        _ => {}
+    }
+}
+
+/// Replaces the `{{ params.<name> }}` placeholders in the strings of `value`.
+///
+/// Returns the name of a placeholder without an argument.
+fn substitute(value: &mut Value, args: &HashMap<&str, &Value>) -> Result<(), String> {
+    match value {
+        Value::String(text) => {
+            if let Some(name) = sole_placeholder(text) {
+                let arg = args.get(name).ok_or_else(|| name.to_string())?;
+                *value = (*arg).clone();
+                return Ok(());
+            }
+
+            let mut substituted = String::with_capacity(text.len());
+            let mut rest = text.as_str();
+            while let Some(start) = rest.find("{{") {
+                let Some(length) = rest[start..].find("}}") else {
+                    break;
+                };
+                let end = start + length + 2;
+                substituted.push_str(&rest[..start]);
+                match placeholder(&rest[start + 2..end - 2]) {
+                    Some(name) => match args.get(name).ok_or_else(|| name.to_string())? {
+                        Value::String(arg) => substituted.push_str(arg),
+                        arg => substituted.push_str(&arg.to_string()),
+                    },
+                    None => substituted.push_str(&rest[start..end]),
+                }
+                rest = &rest[end..];
+            }
+            substituted.push_str(rest);
+            *text = substituted;
+            Ok(())
+        }
+        Value::Array(items) => items.iter_mut().try_for_each(|item| substitute(item, args)),
+        Value::Object(fields) => fields.values_mut().try_for_each(|field| substitute(field, args)),
+        _ => Ok(()),
+    }
+}
+
+/// Parameter named by a placeholder's expression, e.g. ` params.lang `.
+fn placeholder(expression: &str) -> Option<&str> {
+    let name = expression.trim().strip_prefix("params.")?;
+    (!name.is_empty() && name.chars().all(is_identifier_char)).then_some(name)
+}
+
+/// Parameter named by a string that is a single placeholder.
+fn sole_placeholder(text: &str) -> Option<&str> {
+    let expression = text.strip_prefix("{{")?.strip_suffix("}}")?;
+    if expression.contains("{{") || expression.contains("}}") {
+        return None;
+    }
+    placeholder(expression)
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '-'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workflow::{FailurePolicy, StepConfig};
+
+    const WORKFLOW: &str = r#"
+name: "templated"
+templates:
+  summarize_doc:
+    params:
+      doc: ~
+      lang: "en"
+      max_tokens: 200
+    steps:
+      - id: "translate"
+        type: "llm"
+        provider: "openai"
+        model: "gpt-4"
+        prompt: "Translate into {{ params.lang }}: {{ params.doc }}"
+        output: ["text"]
+      - id: "summarize"
+        type: "llm"
+        depends_on: ["translate"]
+        provider: "openai"
+        model: "gpt-4"
+        prompt: "Summarize {{ steps.translate.text }} in {{params.lang}}"
+        max_tokens: "{{ params.max_tokens }}"
+        on_failure:
+          run_step: "fallback"
+        output: ["text"]
+      - id: "fallback"
+        type: "transform"
+        depends_on: ["summarize"]
+        function: "noop"
+        inputs: []
+steps:
+  - id: "fetch"
+    type: "transform"
+    function: "noop"
+    inputs: []
+  - id: "french"
+    use: "summarize_doc"
+    depends_on: ["fetch"]
+    with:
+      doc: "{{ steps.fetch.text }}"
+      lang: "fr"
+  - id: "german"
+    use: "summarize_doc"
+    with:
+      doc: "{{ inputs.doc }}"
+      max_tokens: 50
+  - id: "report"
+    type: "transform"
+    depends_on: ["french", "german"]
+    function: "concat"
+    inputs: ["{{ steps.french_summarize.text }}"]
+"#;
+
+    fn llm_step<'a>(workflow: &'a Workflow, id: &str) -> &'a crate::workflow::LlmStepConfig {
+        let step = workflow.steps.iter().find(|s| s.id == id).unwrap();
+        match &step.config {
+            StepConfig::Llm(config) => config,
+            _ => panic!("{} is not an LLM step", id),
+        }
+    }
+
+    #[test]
+    fn test_templates_expand_per_instance() {
+        let workflow = Workflow::from_yaml(WORKFLOW).unwrap();
+        workflow.validate().unwrap();
+
+        let ids: Vec<&str> = workflow.steps.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec![
+                "fetch",
+                "french_translate",
+                "french_summarize",
+                "french_fallback",
+                "german_translate",
+                "german_summarize",
+                "german_fallback",
+                "report"
+            ]
+        );
+
+        // Arguments and defaults are substituted; a sole placeholder keeps the argument's type
+        assert_eq!(llm_step(&workflow, "french_translate").prompt, "Translate into fr: {{ steps.fetch.text }}");
+        assert_eq!(llm_step(&workflow, "german_translate").prompt, "Translate into en: {{ inputs.doc }}");
+        assert_eq!(llm_step(&workflow, "french_summarize").max_tokens, Some(200));
+        assert_eq!(llm_step(&workflow, "german_summarize").max_tokens, Some(50));
+
+        // References between the template's steps follow the instance
+        assert_eq!(llm_step(&workflow, "german_summarize").prompt, "Summarize {{ steps.german_translate.text }} in en");
+        let summarize = workflow.steps.iter().find(|s| s.id == "german_summarize").unwrap();
+        assert_eq!(summarize.depends_on, vec!["german_translate"]);
+        assert!(matches!(&summarize.on_failure, Some(FailurePolicy::RunStep(step)) if step == "german_fallback"));
+
+        // The instance's dependencies apply to its first steps, and its dependents wait for its last steps
+        let depends_on = |id: &str| workflow.steps.iter().find(|s| s.id == id).unwrap().depends_on.clone();
+        assert_eq!(depends_on("french_translate"), vec!["fetch"]);
+        assert!(depends_on("german_translate").is_empty());
+        assert_eq!(depends_on("report"), vec!["french_fallback", "german_fallback"]);
+
+        // The expanded workflow round-trips without its templates
+        let reloaded = Workflow::from_yaml(&workflow.to_yaml().unwrap()).unwrap();
+        assert_eq!(reloaded.steps.len(), workflow.steps.len());
+    }
+
+    #[test]
+    fn test_template_errors() {
+        let error = |yaml: String| crate::diagnostics::check_yaml(&yaml).unwrap_err().remove(0);
+        let instance = |with: &str| WORKFLOW.replace("      doc: \"{{ inputs.doc }}\"\n      max_tokens: 50", with);
+
+        let unknown = error(WORKFLOW.replace("use: \"summarize_doc\"\n    depends_on", "use: \"summarise_doc\"\n    depends_on"));
+        assert_eq!(unknown.code, DiagnosticCode::InvalidTemplate);
+        assert_eq!(unknown.step_id.as_deref(), Some("french"));
+        assert_eq!(unknown.suggestion.as_deref(), Some("Did you mean 'summarize_doc'?"));
+        assert_eq!(unknown.line, Some(37));
+
+        let missing = error(instance("      lang: \"de\""));
+        assert_eq!(missing.message, "Step 'german' is missing argument 'doc' of template 'summarize_doc'");
+
+        let extra = error(instance("      doc: \"x\"\n      langs: \"de\""));
+        assert_eq!(extra.message, "Template 'summarize_doc' has no parameter 'langs'");
+        assert_eq!(extra.suggestion.as_deref(), Some("Did you mean 'lang'?"));
+
+        let undeclared = error(WORKFLOW.replace("{{params.lang}}", "{{ params.tone }}"));
+        assert_eq!(undeclared.message, "Template 'summarize_doc' has no parameter 'tone'");
+        assert_eq!(undeclared.path.as_deref(), Some("templates.summarize_doc.steps[1]"));
+
+        assert!(matches!(
+            Workflow::from_yaml(&WORKFLOW.replace("use: \"summarize_doc\"\n    with", "use: \"nope\"\n    with")),
+            Err(crate::error::OrchestratorError::ValidationError(_))
+        ));
+    }
+}
@@ -0,0 +1,131 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! OpenTelemetry trace export (requires the `otel` feature).
+//!
+//! [`otlp_layer`] builds a `tracing-subscriber` layer that exports the
+//! executor's spans over OTLP/HTTP. Each run produces a workflow root span
+//! with one child span per step; LLM and embedding steps carry `provider`,
+//! `model` and token usage attributes. The W3C trace context propagator is
+//! installed globally so provider HTTP requests carry a `traceparent` header.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use llm_orchestrator_core::telemetry::{otlp_layer, OtelConfig};
+//! use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let config = OtelConfig::new("my-service").with_endpoint("http://localhost:4318/v1/traces");
+//! let (layer, _guard) = otlp_layer(&config)?;
+//! tracing_subscriber::registry().with(layer).init();
+//! // Keep `_guard` alive until shutdown so buffered spans are flushed.
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{OrchestratorError, Result};
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::{SdkTracerProvider, Tracer};
+use opentelemetry_sdk::Resource;
+use tracing::Subscriber;
+use tracing_opentelemetry::OpenTelemetryLayer;
+use tracing_subscriber::registry::LookupSpan;
+
+/// OTLP exporter configuration.
+#[derive(Debug, Clone)]
+pub struct OtelConfig {
+    /// Service name reported on every span.
+    pub service_name: String,
+    /// OTLP/HTTP traces endpoint. Falls back to `OTEL_EXPORTER_OTLP_ENDPOINT` when unset.
+    pub endpoint: Option<String>,
+}
+
+impl Default for OtelConfig {
+    fn default() -> Self {
+        Self::new("llm-orchestrator")
+    }
+}
+
+impl OtelConfig {
+    /// Create a configuration for the given service name.
+    pub fn new(service_name: impl Into<String>) -> Self {
+        Self {
+            service_name: service_name.into(),
+            endpoint: None,
+        }
+    }
+
+    /// Set the OTLP/HTTP traces endpoint.
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = Some(endpoint.into());
+        self
+    }
+}
+
+/// Flushes and shuts down the tracer provider when dropped.
+pub struct OtelGuard {
+    provider: SdkTracerProvider,
+}
+
+impl Drop for OtelGuard {
+    fn drop(&mut self) {
+        if let Err(e) = self.provider.shutdown() {
+            eprintln!("Failed to shut down OpenTelemetry tracer provider: {}", e);
+        }
+    }
+}
+
+/// Build a tracing layer that exports spans over OTLP.
+///
+/// Also registers the tracer provider and the W3C trace context propagator
+/// globally.
+pub fn otlp_layer<S>(config: &OtelConfig) -> Result<(OpenTelemetryLayer<S, Tracer>, OtelGuard)>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    let mut exporter = SpanExporter::builder().with_http();
+    if let Some(endpoint) = &config.endpoint {
+        exporter = exporter.with_endpoint(endpoint.clone());
+    }
+    let exporter = exporter
+        .build()
+        .map_err(|e| OrchestratorError::other(format!("Failed to create OTLP exporter: {}", e)))?;
+
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(
+            Resource::builder()
+                .with_service_name(config.service_name.clone())
+                .build(),
+        )
+        .build();
+
+    let tracer = provider.tracer(crate::NAME);
+    opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
+    opentelemetry::global::set_tracer_provider(provider.clone());
+
+    Ok((tracing_opentelemetry::layer().with_tracer(tracer), OtelGuard { provider }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn test_provider_requests_carry_trace_context() {
+        let config = OtelConfig::default().with_endpoint("http://127.0.0.1:9/v1/traces");
+        let (layer, _guard) = otlp_layer(&config).unwrap();
+        let subscriber = tracing_subscriber::registry().with(layer);
+
+        tracing::subscriber::with_default(subscriber, || {
+            let _span = tracing::info_span!("step").entered();
+            let headers = llm_orchestrator_providers::trace_context::headers();
+            let traceparent = headers.get("traceparent").unwrap().to_str().unwrap();
+            assert!(traceparent.starts_with("00-"));
+        });
+    }
+}
@@ -0,0 +1,284 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Speech-to-text transcription.
+//!
+//! A `transcribe` step sends an audio file to a provider registered with
+//! [`WorkflowExecutor::with_transcription_provider`], so a recording can be
+//! summarized by the steps after it:
+//!
+//! ```yaml
+//! - id: transcribe
+//!   type: transcribe
+//!   provider: openai
+//!   model: whisper-1
+//!   audio: "{{ inputs.recording }}"      # File path or http(s) URL
+//!   language: en
+//!   prompt: "Standup of the Atlas team"
+//!   output: [transcript, segments]
+//!
+//! - id: summarize
+//!   type: llm
+//!   depends_on: [transcribe]
+//!   provider: anthropic
+//!   model: claude-3-5-haiku-20241022
+//!   prompt: "Summarize the decisions in: {{ steps.transcribe.transcript }}"
+//!   output: [summary]
+//! ```
+//!
+//! Segments are `{start, end, text}` objects with times in seconds.
+
+use crate::error::{OrchestratorError, Result};
+use crate::executor::WorkflowExecutor;
+use crate::providers::{provider_error, TranscriptionRequest};
+use crate::workflow::{Step, StepConfig};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+use tracing::{debug, info, Span};
+
+/// Output names used when a step declares none.
+const DEFAULT_OUTPUTS: [&str; 4] = ["transcript", "segments", "language", "duration"];
+
+/// Largest audio file read when the provider sets no limit.
+const MAX_AUDIO_BYTES: usize = 100 * 1024 * 1024;
+
+impl WorkflowExecutor {
+    /// Executes a transcribe step.
+    pub(crate) async fn execute_transcribe_step(&self, step: &Step) -> Result<HashMap<String, Value>> {
+        let config = match &step.config {
+            StepConfig::Transcribe(config) => config,
+            _ => {
+                return Err(OrchestratorError::InvalidStepConfig {
+                    step_id: step.id.clone(),
+                    reason: "Expected Transcribe step config".to_string(),
+                })
+            }
+        };
+        let provider = self
+            .transcription_providers
+            .get(&config.provider)
+            .map(|provider| provider.value().clone())
+            .ok_or_else(|| OrchestratorError::other(format!("Transcription provider '{}' not registered", config.provider)))?;
+
+        self.resolve_template_secrets(&config.audio).await?;
+        let source = self.context.render_template(&config.audio)?;
+        let prompt = match &config.prompt {
+            Some(template) => {
+                self.resolve_template_secrets(template).await?;
+                Some(self.context.render_template(template)?)
+            }
+            None => None,
+        };
+        let max_bytes = provider.max_audio_bytes().unwrap_or(MAX_AUDIO_BYTES);
+        let (audio, file_name) = load_audio(&source, max_bytes).await?;
+
+        let mut request = TranscriptionRequest {
+            model: config.model.clone(),
+            audio,
+            file_name,
+            language: config.language.clone(),
+            prompt,
+            extra: config.extra.clone(),
+        };
+        self.resolve_request_secrets(&mut request.extra).await?;
+
+        debug!(
+            step_id = %step.id,
+            provider = %config.provider,
+            model = %config.model,
+            bytes = request.audio.len(),
+            "Calling transcription provider"
+        );
+        let span = Span::current();
+        span.record("provider", config.provider.as_str());
+        span.record("model", config.model.as_str());
+
+        self.record_request(&step.id, &request);
+        let started = std::time::Instant::now();
+        let response_result = provider.transcribe(request).await;
+        self.record_provider_latency(&step.id, started.elapsed());
+
+        #[cfg(feature = "audit")]
+        self.audit_provider_call(crate::audit::ProviderCall {
+            step_id: &step.id,
+            provider: &config.provider,
+            model: &config.model,
+            prompt: &source,
+            completion: response_result.as_ref().ok().map(|r| r.text.as_str()),
+            tokens_used: None,
+            error: response_result.as_ref().err().map(|e| e.to_string()),
+            duration: started.elapsed(),
+        })
+        .await;
+
+        let response = response_result.map_err(|e| provider_error(&config.provider, "Transcription provider error", e))?;
+        info!(step_id = %step.id, segments = response.segments.len(), "Transcription completed");
+
+        let names: Vec<&str> = if step.output.is_empty() {
+            DEFAULT_OUTPUTS.to_vec()
+        } else {
+            step.output.iter().map(String::as_str).collect()
+        };
+        let values = [
+            Value::String(response.text),
+            serde_json::to_value(&response.segments)?,
+            serde_json::to_value(&response.language)?,
+            serde_json::to_value(response.duration_seconds)?,
+        ];
+        Ok(names.into_iter().map(String::from).zip(values).collect())
+    }
+}
+
+/// Reads the audio at a path or `http(s)` URL, with the file name that tells its format.
+async fn load_audio(source: &str, max_bytes: usize) -> Result<(Vec<u8>, String)> {
+    let too_large = || OrchestratorError::other(format!("Audio '{}' is larger than {} bytes", source, max_bytes));
+    if source.starts_with("http://") || source.starts_with("https://") {
+        let failed = |e: reqwest::Error| OrchestratorError::other(format!("Failed to download audio '{}': {}", source, e));
+        let mut response = crate::http::client()
+            .get(source)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(failed)?;
+        if response.content_length().is_some_and(|length| length > max_bytes as u64) {
+            return Err(too_large());
+        }
+        let file_name = url_file_name(response.url(), response.headers().get(reqwest::header::CONTENT_TYPE));
+        let mut audio = Vec::new();
+        while let Some(chunk) = response.chunk().await.map_err(failed)? {
+            if audio.len() + chunk.len() > max_bytes {
+                return Err(too_large());
+            }
+            audio.extend_from_slice(&chunk);
+        }
+        return Ok((audio, file_name));
+    }
+
+    let path = Path::new(source);
+    let failed = |e: std::io::Error| OrchestratorError::other(format!("Failed to read audio '{}': {}", source, e));
+    if tokio::fs::metadata(path).await.map_err(failed)?.len() > max_bytes as u64 {
+        return Err(too_large());
+    }
+    let audio = tokio::fs::read(path).await.map_err(failed)?;
+    let file_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("audio")
+        .to_string();
+    Ok((audio, file_name))
+}
+
+/// File name of downloaded audio: the URL's last segment if it has an
+/// extension, else one for its content type.
+fn url_file_name(url: &reqwest::Url, content_type: Option<&reqwest::header::HeaderValue>) -> String {
+    let last = url.path_segments().and_then(|mut segments| segments.next_back()).unwrap_or_default();
+    if last.contains('.') {
+        return last.to_string();
+    }
+    let media_type = content_type
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .unwrap_or_default()
+        .trim();
+    let extension = match media_type {
+        "audio/mpeg" | "audio/mp3" => "mp3",
+        "audio/mp4" | "audio/x-m4a" | "audio/m4a" => "m4a",
+        "audio/wav" | "audio/x-wav" | "audio/wave" => "wav",
+        "audio/webm" => "webm",
+        "audio/ogg" => "ogg",
+        "audio/flac" | "audio/x-flac" => "flac",
+        _ => return "audio".to_string(),
+    };
+    format!("audio.{}", extension)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::{ProviderError, TranscriptSegment, TranscriptionProvider, TranscriptionResponse};
+    use crate::workflow::Workflow;
+    use async_trait::async_trait;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    /// Transcribes any audio as the length and name of its file.
+    struct Stenographer;
+
+    #[async_trait]
+    impl TranscriptionProvider for Stenographer {
+        async fn transcribe(
+            &self,
+            request: TranscriptionRequest,
+        ) -> std::result::Result<TranscriptionResponse, ProviderError> {
+            let text = format!("{} bytes of {}", request.audio.len(), request.file_name);
+            Ok(TranscriptionResponse {
+                segments: vec![TranscriptSegment { start: 0.0, end: 1.0, text: text.clone() }],
+                text,
+                language: request.language,
+                duration_seconds: Some(1.0),
+                model: request.model,
+            })
+        }
+
+        fn name(&self) -> &str {
+            "stenographer"
+        }
+
+        fn max_audio_bytes(&self) -> Option<usize> {
+            Some(8)
+        }
+    }
+
+    fn workflow() -> Workflow {
+        Workflow::from_yaml(
+            r#"
+name: "meeting"
+steps:
+  - id: "transcribe"
+    type: "transcribe"
+    provider: "steno"
+    audio: "{{ inputs.recording }}"
+    language: "en"
+"#,
+        )
+        .unwrap()
+    }
+
+    async fn transcribe(recording: &str) -> Result<HashMap<String, Value>> {
+        let inputs = HashMap::from([("recording".to_string(), json!(recording))]);
+        let results = WorkflowExecutor::new(workflow(), inputs)?
+            .with_transcription_provider("steno", Arc::new(Stenographer))
+            .execute()
+            .await?;
+        Ok(results["transcribe"].outputs.clone())
+    }
+
+    #[tokio::test]
+    async fn test_transcribe_file_and_url() {
+        assert!(matches!(workflow().steps[0].config, StepConfig::Transcribe(_)));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("standup.wav");
+        std::fs::write(&path, b"RIFF").unwrap();
+        let outputs = transcribe(path.to_str().unwrap()).await.unwrap();
+        assert_eq!(outputs["transcript"], json!("4 bytes of standup.wav"));
+        assert_eq!(outputs["segments"], json!([{"start": 0.0, "end": 1.0, "text": "4 bytes of standup.wav"}]));
+        assert_eq!(outputs["language"], json!("en"));
+        assert_eq!(outputs["duration"], json!(1.0));
+
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/recordings/42")
+            .with_header("content-type", "audio/mpeg")
+            .with_body("ID3")
+            .create_async()
+            .await;
+        server.mock("GET", "/long.mp3").with_body("ID3 and much more").create_async().await;
+        let outputs = transcribe(&format!("{}/recordings/42", server.url())).await.unwrap();
+        assert_eq!(outputs["transcript"], json!("3 bytes of audio.mp3"));
+
+        // Audio over the provider's limit is not sent
+        assert!(transcribe(&format!("{}/long.mp3", server.url())).await.is_err());
+    }
+}
@@ -0,0 +1,424 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Transform functions.
+//!
+//! A `transform` step applies a built-in `function` to its `inputs`, each a
+//! path into the context such as `steps.embed.embedding` or a template:
+//!
+//! ```yaml
+//! - id: unique_chunks
+//!   type: transform
+//!   function: dedup
+//!   inputs: [steps.split.chunks, steps.embed_chunks.embeddings]
+//!   threshold: 0.95
+//!   output: [chunks, removed]
+//! ```
+//!
+//! The embedding functions do small-scale vector math on embeddings already
+//! in the context, without a vector database:
+//!
+//! - `cosine_similarity`: the similarity of two vectors.
+//! - `nearest`: the `top_k` (default 1) items most similar to a query vector,
+//!   each as `{index, score, item}`, most similar first.
+//! - `dedup`: the items left after dropping each item at least `threshold`
+//!   (default 0.95) similar to an earlier kept one, and the number dropped.
+//!
+//! Items are vectors themselves, objects holding their vector in the field
+//! named by `embedding_field` (default `embedding`), or any values paired
+//! with the vectors of an extra input, such as the embeddings of an embed
+//! step that embedded them.
+//!
+//! The text functions prepare documents for ingestion. They take a string,
+//! or a list of strings to apply to each:
+//!
+//! - `split`: chunks of at most `chunk_size` characters (default 1000)
+//!   overlapping by up to `chunk_overlap` (default 200), split recursively
+//!   on `separators` (default paragraphs, lines, words, then characters).
+//! - `markdown_to_text`, `html_to_text`: the plain text of a document.
+//! - `normalize`: Unicode normalization to `form` (default `nfkc`), without
+//!   control characters and runs of whitespace.
+//! - `detect_language`: the ISO 639-1 `language` code (`und` when unknown)
+//!   and a `confidence` from 0 to 1.
+//! - `remove_stopwords`: the text without the common words of `language`
+//!   (default `en`, or `auto` to detect it), or of a `stopwords` list.
+
+use crate::error::{OrchestratorError, Result};
+use crate::evaluate::cosine_similarity;
+use crate::executor::WorkflowExecutor;
+use crate::workflow::{Step, StepConfig, TransformConfig};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::collections::HashSet;
+use tracing::debug;
+
+pub mod text;
+
+use text::NormalizationForm;
+
+/// Default `dedup` similarity threshold.
+const DEFAULT_DEDUP_THRESHOLD: f64 = 0.95;
+
+/// Field holding an item's vector, unless `embedding_field` is set.
+const DEFAULT_EMBEDDING_FIELD: &str = "embedding";
+
+/// Reads a JSON array of numbers as a vector.
+fn vector(value: &Value) -> Option<Vec<f32>> {
+    value
+        .as_array()?
+        .iter()
+        .map(|x| x.as_f64().map(|x| x as f32))
+        .collect()
+}
+
+/// Pairs each item with its vector.
+///
+/// Vectors come from `embeddings` when given, one per item; otherwise an
+/// item is a vector or an object with one in `field`.
+fn embedded_items(items: &Value, embeddings: Option<&Value>, field: &str) -> std::result::Result<Vec<(Value, Vec<f32>)>, String> {
+    let items = items.as_array().ok_or("items must be a list")?;
+    match embeddings {
+        Some(embeddings) => {
+            let embeddings = embeddings.as_array().ok_or("embeddings must be a list")?;
+            if embeddings.len() != items.len() {
+                return Err(format!("{} items but {} embeddings", items.len(), embeddings.len()));
+            }
+            items
+                .iter()
+                .zip(embeddings)
+                .enumerate()
+                .map(|(i, (item, embedding))| {
+                    let embedding = vector(embedding).ok_or_else(|| format!("embedding {} is not a vector", i))?;
+                    Ok((item.clone(), embedding))
+                })
+                .collect()
+        }
+        None => items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| {
+                let embedding = vector(item)
+                    .or_else(|| item.get(field).and_then(vector))
+                    .ok_or_else(|| format!("item {} is not a vector and has no '{}' vector", i, field))?;
+                Ok((item.clone(), embedding))
+            })
+            .collect(),
+    }
+}
+
+/// The `top_k` items most similar to `query`, most similar first.
+pub fn nearest(query: &[f32], items: &[(Value, Vec<f32>)], top_k: usize) -> Vec<Value> {
+    let mut scored: Vec<(usize, f64)> = items
+        .iter()
+        .enumerate()
+        .map(|(i, (_, embedding))| (i, cosine_similarity(query, embedding)))
+        .collect();
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    scored
+        .into_iter()
+        .take(top_k)
+        .map(|(i, score)| json!({"index": i, "score": score, "item": items[i].0}))
+        .collect()
+}
+
+/// Items not at least `threshold` similar to an earlier kept one, in order.
+pub fn dedup(items: Vec<(Value, Vec<f32>)>, threshold: f64) -> Vec<Value> {
+    let mut kept: Vec<(Value, Vec<f32>)> = Vec::new();
+    for (item, embedding) in items {
+        if kept.iter().all(|(_, other)| cosine_similarity(&embedding, other) < threshold) {
+            kept.push((item, embedding));
+        }
+    }
+    kept.into_iter().map(|(item, _)| item).collect()
+}
+
+impl WorkflowExecutor {
+    /// Executes a transform step.
+    pub(crate) async fn execute_transform_step(&self, step: &Step) -> Result<HashMap<String, Value>> {
+        debug!(step_id = %step.id, "Transform step execution");
+        let config = match &step.config {
+            StepConfig::Transform(config) => config,
+            _ => {
+                return Err(OrchestratorError::InvalidStepConfig {
+                    step_id: step.id.clone(),
+                    reason: "Expected Transform step config".to_string(),
+                })
+            }
+        };
+        let invalid = |reason: String| OrchestratorError::InvalidStepConfig {
+            step_id: step.id.clone(),
+            reason: format!("{}: {}", config.function, reason),
+        };
+        let output_key = |index: usize, default: &str| {
+            step.output.get(index).cloned().unwrap_or_else(|| default.to_string())
+        };
+
+        let mut outputs = HashMap::new();
+        match config.function.as_str() {
+            "cosine_similarity" => {
+                let inputs = self.transform_inputs(config, 2, 2).map_err(invalid)?;
+                let (Some(a), Some(b)) = (vector(&inputs[0]), vector(&inputs[1])) else {
+                    return Err(invalid("inputs must be two vectors".to_string()));
+                };
+                if a.len() != b.len() {
+                    return Err(invalid(format!("vectors have {} and {} dimensions", a.len(), b.len())));
+                }
+                outputs.insert(output_key(0, "similarity"), json!(cosine_similarity(&a, &b)));
+            }
+            "nearest" => {
+                let inputs = self.transform_inputs(config, 2, 3).map_err(invalid)?;
+                let query = vector(&inputs[0]).ok_or_else(|| invalid("query must be a vector".to_string()))?;
+                let items = embedded_items(&inputs[1], inputs.get(2), embedding_field(config)).map_err(invalid)?;
+                let top_k = match config.params.get("top_k") {
+                    Some(top_k) => top_k
+                        .as_u64()
+                        .filter(|&k| k > 0)
+                        .ok_or_else(|| invalid("top_k must be a positive integer".to_string()))? as usize,
+                    None => 1,
+                };
+                outputs.insert(output_key(0, "matches"), Value::Array(nearest(&query, &items, top_k)));
+            }
+            "dedup" => {
+                let inputs = self.transform_inputs(config, 1, 2).map_err(invalid)?;
+                let items = embedded_items(&inputs[0], inputs.get(1), embedding_field(config)).map_err(invalid)?;
+                let threshold = match config.params.get("threshold") {
+                    Some(threshold) => threshold.as_f64().ok_or_else(|| invalid("threshold must be a number".to_string()))?,
+                    None => DEFAULT_DEDUP_THRESHOLD,
+                };
+                let count = items.len();
+                let kept = dedup(items, threshold);
+                outputs.insert(output_key(1, "removed"), json!(count - kept.len()));
+                outputs.insert(output_key(0, "items"), Value::Array(kept));
+            }
+            "split" => {
+                let inputs = self.transform_inputs(config, 1, 1).map_err(invalid)?;
+                let chunk_size = usize_param(config, "chunk_size", 1000).map_err(invalid)?;
+                let chunk_overlap = usize_param(config, "chunk_overlap", 200).map_err(invalid)?;
+                let separators: Vec<String> = match config.params.get("separators") {
+                    Some(separators) => serde_json::from_value(separators.clone())
+                        .map_err(|_| invalid("separators must be a list of strings".to_string()))?,
+                    None => text::DEFAULT_SEPARATORS.iter().map(|s| s.to_string()).collect(),
+                };
+                let texts = match &inputs[0] {
+                    Value::Array(texts) => texts.iter().map(|text| text.as_str()).collect::<Option<Vec<_>>>(),
+                    text => text.as_str().map(|text| vec![text]),
+                }
+                .ok_or_else(|| invalid("input must be a string or a list of strings".to_string()))?;
+                let chunks: Vec<String> = texts
+                    .into_iter()
+                    .flat_map(|text| text::split_text(text, chunk_size, chunk_overlap, &separators))
+                    .collect();
+                outputs.insert(output_key(0, "chunks"), json!(chunks));
+            }
+            "markdown_to_text" | "html_to_text" | "normalize" | "remove_stopwords" => {
+                let inputs = self.transform_inputs(config, 1, 1).map_err(invalid)?;
+                let clean: Box<dyn Fn(&str) -> String> = match config.function.as_str() {
+                    "markdown_to_text" => Box::new(text::markdown_to_text),
+                    "html_to_text" => Box::new(text::html_to_text),
+                    "normalize" => {
+                        let form = match config.params.get("form") {
+                            Some(form) => form
+                                .as_str()
+                                .and_then(NormalizationForm::parse)
+                                .ok_or_else(|| invalid("form must be nfc, nfd, nfkc, or nfkd".to_string()))?,
+                            None => NormalizationForm::Nfkc,
+                        };
+                        Box::new(move |input| text::normalize_text(input, form))
+                    }
+                    _ => {
+                        let stopwords = stopword_param(config).map_err(invalid)?;
+                        Box::new(move |input| match &stopwords {
+                            Some(stopwords) => text::remove_stopwords(input, stopwords),
+                            None => {
+                                let (language, _) = text::detect_language(input);
+                                let stopwords = text::stopwords(language).unwrap_or_default();
+                                text::remove_stopwords(input, &stopwords.iter().map(|s| s.to_string()).collect())
+                            }
+                        })
+                    }
+                };
+                let cleaned = map_texts(&inputs[0], |input| clean(input)).map_err(invalid)?;
+                outputs.insert(output_key(0, "text"), cleaned);
+            }
+            "detect_language" => {
+                let inputs = self.transform_inputs(config, 1, 1).map_err(invalid)?;
+                let input = inputs[0].as_str().ok_or_else(|| invalid("input must be a string".to_string()))?;
+                let (language, confidence) = text::detect_language(input);
+                outputs.insert(output_key(0, "language"), json!(language));
+                outputs.insert(output_key(1, "confidence"), json!(confidence));
+            }
+            function => {
+                debug!(step_id = %step.id, function, "No built-in transform function");
+            }
+        }
+        Ok(outputs)
+    }
+
+    /// Resolves a transform's inputs, of which there must be `min` to `max`.
+    ///
+    /// An input is a template, or a path whose value is looked up.
+    fn transform_inputs(&self, config: &TransformConfig, min: usize, max: usize) -> std::result::Result<Vec<Value>, String> {
+        if config.inputs.len() < min || config.inputs.len() > max {
+            let expected = if min == max { min.to_string() } else { format!("{} to {}", min, max) };
+            return Err(format!("expected {} inputs, got {}", expected, config.inputs.len()));
+        }
+        config
+            .inputs
+            .iter()
+            .map(|input| {
+                let template = if input.contains("{{") { input.clone() } else { format!("{{{{ {} }}}}", input) };
+                self.context.render_value(&template).map_err(|e| e.to_string())
+            })
+            .collect()
+    }
+}
+
+/// A non-negative integer parameter.
+fn usize_param(config: &TransformConfig, name: &str, default: usize) -> std::result::Result<usize, String> {
+    match config.params.get(name) {
+        Some(value) => value
+            .as_u64()
+            .map(|value| value as usize)
+            .ok_or_else(|| format!("{} must be a non-negative integer", name)),
+        None => Ok(default),
+    }
+}
+
+/// Stopwords to remove: the `stopwords` list, those of `language`, or `None`
+/// to use those of the detected language.
+fn stopword_param(config: &TransformConfig) -> std::result::Result<Option<HashSet<String>>, String> {
+    if let Some(stopwords) = config.params.get("stopwords") {
+        let stopwords: Vec<String> =
+            serde_json::from_value(stopwords.clone()).map_err(|_| "stopwords must be a list of strings".to_string())?;
+        return Ok(Some(stopwords.into_iter().map(|word| word.to_lowercase()).collect()));
+    }
+    let language = config.params.get("language").and_then(Value::as_str).unwrap_or("en");
+    if language == "auto" {
+        return Ok(None);
+    }
+    let stopwords = text::stopwords(language).ok_or_else(|| format!("no stopwords known for language '{}'", language))?;
+    Ok(Some(stopwords.iter().map(|word| word.to_string()).collect()))
+}
+
+/// Applies `f` to a string, or to each string of a list.
+fn map_texts(value: &Value, f: impl Fn(&str) -> String) -> std::result::Result<Value, String> {
+    match value {
+        Value::String(text) => Ok(Value::String(f(text))),
+        Value::Array(texts) => texts
+            .iter()
+            .map(|text| text.as_str().map(|text| Value::String(f(text))))
+            .collect::<Option<Vec<_>>>()
+            .map(Value::Array)
+            .ok_or_else(|| "input must be a string or a list of strings".to_string()),
+        _ => Err("input must be a string or a list of strings".to_string()),
+    }
+}
+
+/// Field holding an item's vector.
+fn embedding_field(config: &TransformConfig) -> &str {
+    config
+        .params
+        .get("embedding_field")
+        .and_then(Value::as_str)
+        .unwrap_or(DEFAULT_EMBEDDING_FIELD)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workflow::Workflow;
+
+    const VECTORS: &str = r#"
+name: "vectors"
+steps:
+  - id: "similarity"
+    type: "transform"
+    function: "cosine_similarity"
+    inputs: ["inputs.query", "inputs.docs.0.embedding"]
+  - id: "nearest"
+    type: "transform"
+    function: "nearest"
+    inputs: ["inputs.query", "inputs.docs"]
+    top_k: 2
+  - id: "unique"
+    type: "transform"
+    function: "dedup"
+    inputs: ["inputs.chunks", "inputs.embeddings"]
+    threshold: 0.9
+    output: ["chunks", "removed"]
+"#;
+
+    #[tokio::test]
+    async fn test_embedding_functions() {
+        let inputs = HashMap::from([
+            ("query".to_string(), json!([1.0, 0.0])),
+            (
+                "docs".to_string(),
+                json!([
+                    {"id": "a", "embedding": [0.0, 1.0]},
+                    {"id": "b", "embedding": [1.0, 0.1]},
+                    {"id": "c", "embedding": [1.0, 1.0]},
+                ]),
+            ),
+            ("chunks".to_string(), json!(["one", "one again", "two"])),
+            ("embeddings".to_string(), json!([[1.0, 0.0], [0.99, 0.05], [0.0, 1.0]])),
+        ]);
+        let results = WorkflowExecutor::new(Workflow::from_yaml(VECTORS).unwrap(), inputs)
+            .unwrap()
+            .execute()
+            .await
+            .unwrap();
+
+        assert_eq!(results["similarity"].outputs["similarity"], json!(0.0));
+
+        let matches = results["nearest"].outputs["matches"].as_array().unwrap();
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0]["index"], json!(1));
+        assert_eq!(matches[0]["item"]["id"], json!("b"));
+        assert_eq!(matches[1]["item"]["id"], json!("c"));
+
+        assert_eq!(results["unique"].outputs["chunks"], json!(["one", "two"]));
+        assert_eq!(results["unique"].outputs["removed"], json!(1));
+    }
+
+    #[tokio::test]
+    async fn test_text_functions() {
+        let workflow = Workflow::from_yaml(
+            r#"
+name: "ingest"
+steps:
+  - id: "clean"
+    type: "transform"
+    function: "html_to_text"
+    inputs: ["inputs.page"]
+  - id: "split"
+    type: "transform"
+    depends_on: ["clean"]
+    function: "split"
+    inputs: ["steps.clean.text"]
+    chunk_size: 12
+    chunk_overlap: 0
+  - id: "language"
+    type: "transform"
+    depends_on: ["clean"]
+    function: "detect_language"
+    inputs: ["steps.clean.text"]
+"#,
+        )
+        .unwrap();
+        let inputs = HashMap::from([("page".to_string(), json!("<p>The cat is on the mat</p>"))]);
+        let results = WorkflowExecutor::new(workflow, inputs).unwrap().execute().await.unwrap();
+
+        assert_eq!(results["split"].outputs["chunks"], json!(["The cat is", "on the mat"]));
+        assert_eq!(results["language"].outputs["language"], json!("en"));
+    }
+
+    #[test]
+    fn test_embedded_items() {
+        let items = embedded_items(&json!([[1.0, 2.0], {"vec": [3.0]}]), None, "vec").unwrap();
+        assert_eq!(items[0].1, vec![1.0, 2.0]);
+        assert_eq!(items[1].1, vec![3.0]);
+        assert!(embedded_items(&json!(["text"]), None, "vec").is_err());
+        assert!(embedded_items(&json!(["a", "b"]), Some(&json!([[1.0]])), "vec").is_err());
+    }
+}
@@ -0,0 +1,386 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Text splitting and cleaning.
+//!
+//! Lengths are counted in characters. Language detection and stopword
+//! filtering know a short list of common words for English, Spanish,
+//! French, German, Italian, Portuguese, and Dutch; other languages are told
+//! apart by their script only.
+
+use lazy_static::lazy_static;
+use pulldown_cmark::{Event, Parser, Tag, TagEnd};
+use regex::Regex;
+use std::collections::{HashSet, VecDeque};
+use unicode_normalization::UnicodeNormalization;
+
+lazy_static! {
+    /// Comments and elements whose content is not shown.
+    static ref HIDDEN: Regex = Regex::new(
+        r"(?is)<!--.*?-->|<(script|style|head|noscript|template)\b[^>]*>.*?</(script|style|head|noscript|template)\s*>"
+    )
+    .unwrap();
+    /// Line breaks and tags of block elements.
+    static ref BLOCK: Regex = Regex::new(
+        r"(?i)<(br|/?(p|div|h[1-6]|li|ul|ol|tr|table|section|article|header|footer|nav|aside|blockquote|pre|hr|dt|dd))\b[^>]*>"
+    )
+    .unwrap();
+    static ref TAG: Regex = Regex::new(r"(?s)<[^>]*>").unwrap();
+    static ref ENTITY: Regex = Regex::new(r"&(#[0-9]{1,7}|#[xX][0-9a-fA-F]{1,6}|[a-zA-Z]+);").unwrap();
+}
+
+/// Separators tried in turn by [`split_text`] unless others are given.
+pub const DEFAULT_SEPARATORS: [&str; 4] = ["\n\n", "\n", " ", ""];
+
+/// Common words of the languages detected by their vocabulary.
+const STOPWORDS: [(&str, &[&str]); 7] = [
+    ("en", &[
+        "a", "about", "an", "and", "are", "as", "at", "be", "but", "by", "for", "from", "has", "have", "he",
+        "i", "in", "is", "it", "its", "not", "of", "on", "or", "she", "that", "the", "their", "there", "they",
+        "this", "to", "was", "we", "were", "which", "will", "with", "you",
+    ]),
+    ("es", &[
+        "al", "como", "con", "de", "del", "el", "en", "es", "esta", "este", "la", "las", "lo", "los", "más",
+        "no", "para", "pero", "por", "que", "se", "sin", "su", "sus", "un", "una", "y", "ya",
+    ]),
+    ("fr", &[
+        "au", "aux", "avec", "ce", "cette", "dans", "de", "des", "du", "elle", "en", "est", "et", "il", "je",
+        "la", "le", "les", "leur", "mais", "ne", "nous", "ou", "par", "pas", "pour", "qui", "sont", "sur",
+        "un", "une", "vous",
+    ]),
+    ("de", &[
+        "auf", "aus", "bei", "das", "dass", "dem", "den", "der", "des", "die", "ein", "eine", "einer", "es",
+        "für", "hat", "ich", "im", "in", "ist", "mit", "nicht", "sich", "sie", "sind", "und", "von", "wir",
+        "zu", "zum", "zur",
+    ]),
+    ("it", &[
+        "che", "chi", "con", "da", "del", "della", "di", "e", "gli", "ha", "il", "in", "la", "le", "lo",
+        "ma", "non", "per", "più", "si", "sono", "su", "sua", "suo", "un", "una", "uno",
+    ]),
+    ("pt", &[
+        "ao", "as", "com", "como", "da", "das", "de", "do", "dos", "e", "ela", "ele", "em", "na", "não",
+        "no", "nos", "o", "os", "para", "pela", "pelo", "por", "que", "se", "sua", "seu", "um", "uma",
+    ]),
+    ("nl", &[
+        "aan", "als", "bij", "dat", "de", "die", "dit", "een", "en", "er", "het", "hij", "in", "is", "met",
+        "niet", "nog", "om", "ook", "op", "te", "van", "voor", "was", "wat", "ze", "zijn",
+    ]),
+];
+
+/// Splits `text` into chunks of at most `chunk_size` characters.
+///
+/// Splits on the first of `separators` found in the text, and splits pieces
+/// still too long on the next ones; the empty separator splits between
+/// characters. Pieces are merged back into chunks that overlap by up to
+/// `chunk_overlap` characters.
+pub fn split_text(text: &str, chunk_size: usize, chunk_overlap: usize, separators: &[String]) -> Vec<String> {
+    let chunk_size = chunk_size.max(1);
+    let mut chunks = Vec::new();
+    split_recursive(text, chunk_size, chunk_overlap.min(chunk_size - 1), separators, &mut chunks);
+    chunks
+}
+
+fn split_recursive(text: &str, chunk_size: usize, chunk_overlap: usize, separators: &[String], chunks: &mut Vec<String>) {
+    let position = separators
+        .iter()
+        .position(|separator| separator.is_empty() || text.contains(separator.as_str()))
+        .unwrap_or(separators.len().saturating_sub(1));
+    let separator = separators.get(position).map(String::as_str).unwrap_or("");
+    let remaining = separators.get(position + 1..).unwrap_or_default();
+
+    let pieces: Vec<&str> = if separator.is_empty() {
+        text.char_indices().map(|(i, c)| &text[i..i + c.len_utf8()]).collect()
+    } else {
+        text.split(separator).filter(|piece| !piece.is_empty()).collect()
+    };
+
+    let mut short = Vec::new();
+    for piece in pieces {
+        if piece.chars().count() <= chunk_size {
+            short.push(piece);
+            continue;
+        }
+        merge_pieces(&short, separator, chunk_size, chunk_overlap, chunks);
+        short.clear();
+        if remaining.is_empty() {
+            chunks.push(piece.trim().to_string());
+        } else {
+            split_recursive(piece, chunk_size, chunk_overlap, remaining, chunks);
+        }
+    }
+    merge_pieces(&short, separator, chunk_size, chunk_overlap, chunks);
+}
+
+/// Joins pieces with `separator` into chunks, carrying over up to `chunk_overlap` characters.
+fn merge_pieces(pieces: &[&str], separator: &str, chunk_size: usize, chunk_overlap: usize, chunks: &mut Vec<String>) {
+    let separator_len = separator.chars().count();
+    let mut current: VecDeque<(&str, usize)> = VecDeque::new();
+    let mut total = 0;
+    let mut emit = |current: &VecDeque<(&str, usize)>| {
+        let chunk = current.iter().map(|(piece, _)| *piece).collect::<Vec<_>>().join(separator);
+        let chunk = chunk.trim();
+        if !chunk.is_empty() {
+            chunks.push(chunk.to_string());
+        }
+    };
+
+    for piece in pieces {
+        let len = piece.chars().count();
+        let joined_len = |current: &VecDeque<_>| if current.is_empty() { len } else { len + separator_len };
+        if total + joined_len(&current) > chunk_size && !current.is_empty() {
+            emit(&current);
+            // Drop pieces from the front until what is left fits as overlap
+            while total > chunk_overlap || (total > 0 && total + joined_len(&current) > chunk_size) {
+                let Some((_, front_len)) = current.pop_front() else {
+                    break;
+                };
+                total -= front_len + if current.is_empty() { 0 } else { separator_len };
+            }
+        }
+        total += joined_len(&current);
+        current.push_back((piece, len));
+    }
+    emit(&current);
+}
+
+/// Plain text of a Markdown document, one block per paragraph.
+pub fn markdown_to_text(markdown: &str) -> String {
+    let mut text = String::new();
+    for event in Parser::new(markdown) {
+        match event {
+            Event::Text(content) | Event::Code(content) => text.push_str(&content),
+            Event::SoftBreak | Event::HardBreak => text.push('\n'),
+            Event::Start(Tag::Item) => text.push_str("- "),
+            Event::End(TagEnd::Item) => text.push('\n'),
+            Event::End(TagEnd::Paragraph | TagEnd::Heading(_) | TagEnd::CodeBlock | TagEnd::BlockQuote(_) | TagEnd::List(_) | TagEnd::Table) => {
+                text.push_str("\n\n")
+            }
+            Event::End(TagEnd::TableCell) => text.push('\t'),
+            Event::End(TagEnd::TableRow | TagEnd::TableHead) => text.push('\n'),
+            _ => {}
+        }
+    }
+    tidy_lines(&text)
+}
+
+/// Visible text of an HTML document, with block elements on their own lines.
+pub fn html_to_text(html: &str) -> String {
+    let text = HIDDEN.replace_all(html, "");
+    let text = BLOCK.replace_all(&text, "\n");
+    let text = TAG.replace_all(&text, "");
+    tidy_lines(&decode_entities(&text))
+}
+
+/// Decodes character references and the common named entities.
+fn decode_entities(text: &str) -> String {
+    ENTITY
+        .replace_all(text, |captures: &regex::Captures| {
+            let name = &captures[1];
+            let decoded = match name {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                _ => name
+                    .strip_prefix("#x")
+                    .or_else(|| name.strip_prefix("#X"))
+                    .map(|hex| u32::from_str_radix(hex, 16))
+                    .or_else(|| name.strip_prefix('#').map(str::parse))
+                    .and_then(|code| char::from_u32(code.ok()?)),
+            };
+            decoded.map_or_else(|| captures[0].to_string(), String::from)
+        })
+        .into_owned()
+}
+
+/// Collapses spaces within lines, trims them, and keeps at most one blank line in a row.
+pub(crate) fn tidy_lines(text: &str) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines() {
+        let line = line.split_whitespace().collect::<Vec<_>>().join(" ");
+        if !line.is_empty() || lines.last().is_some_and(|last| !last.is_empty()) {
+            lines.push(line);
+        }
+    }
+    while lines.last().is_some_and(String::is_empty) {
+        lines.pop();
+    }
+    lines.join("\n")
+}
+
+/// Unicode normalization form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NormalizationForm {
+    Nfc,
+    Nfd,
+    Nfkc,
+    Nfkd,
+}
+
+impl NormalizationForm {
+    /// Form named `nfc`, `nfd`, `nfkc`, or `nfkd`, in any case.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "nfc" => Some(Self::Nfc),
+            "nfd" => Some(Self::Nfd),
+            "nfkc" => Some(Self::Nfkc),
+            "nfkd" => Some(Self::Nfkd),
+            _ => None,
+        }
+    }
+}
+
+/// Normalizes `text` to `form`, drops control characters, and tidies whitespace.
+pub fn normalize_text(text: &str, form: NormalizationForm) -> String {
+    let normalized: String = match form {
+        NormalizationForm::Nfc => text.nfc().collect(),
+        NormalizationForm::Nfd => text.nfd().collect(),
+        NormalizationForm::Nfkc => text.nfkc().collect(),
+        NormalizationForm::Nfkd => text.nfkd().collect(),
+    };
+    let visible: String = normalized
+        .replace("\r\n", "\n")
+        .chars()
+        .filter(|c| !c.is_control() || matches!(c, '\n' | '\t'))
+        .filter(|c| !matches!(c, '\u{200B}' | '\u{200C}' | '\u{200D}' | '\u{FEFF}'))
+        .collect();
+    tidy_lines(&visible)
+}
+
+/// Words of `text`, lowercased, without surrounding punctuation.
+fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split_whitespace()
+        .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase())
+        .filter(|word| !word.is_empty())
+}
+
+/// ISO 639-1 code of the language `text` is most likely in, with a confidence
+/// from 0 to 1, or `und` with 0 when it cannot tell.
+pub fn detect_language(text: &str) -> (&'static str, f64) {
+    // Scripts written mostly in one language; Cyrillic is taken as Russian
+    let mut scripts: Vec<(&'static str, usize)> = Vec::new();
+    let mut letters = 0;
+    for c in text.chars().filter(|c| c.is_alphabetic()) {
+        letters += 1;
+        let language = match c as u32 {
+            0x3040..=0x30FF => "ja",
+            0xAC00..=0xD7AF | 0x1100..=0x11FF => "ko",
+            0x4E00..=0x9FFF => "zh",
+            0x0400..=0x04FF => "ru",
+            0x0600..=0x06FF => "ar",
+            0x0900..=0x097F => "hi",
+            0x0370..=0x03FF => "el",
+            0x0590..=0x05FF => "he",
+            0x0E00..=0x0E7F => "th",
+            _ => continue,
+        };
+        match scripts.iter_mut().find(|(l, _)| *l == language) {
+            Some((_, count)) => *count += 1,
+            None => scripts.push((language, 1)),
+        }
+    }
+    if letters == 0 {
+        return ("und", 0.0);
+    }
+    // Kana marks Japanese even among Han characters
+    let count = |language: &str| scripts.iter().find(|(l, _)| *l == language).map_or(0, |(_, n)| *n);
+    if count("ja") > 0 {
+        return ("ja", (count("ja") + count("zh")) as f64 / letters as f64);
+    }
+    if let Some((language, count)) = scripts.iter().max_by_key(|(_, count)| *count) {
+        if *count * 2 >= letters {
+            return (language, *count as f64 / letters as f64);
+        }
+    }
+
+    let mut hits = [0usize; STOPWORDS.len()];
+    let mut total = 0;
+    for word in words(text) {
+        total += 1;
+        for (i, (_, stopwords)) in STOPWORDS.iter().enumerate() {
+            if stopwords.contains(&word.as_str()) {
+                hits[i] += 1;
+            }
+        }
+    }
+    let matched: usize = hits.iter().sum();
+    match hits.iter().enumerate().max_by_key(|(i, hits)| (**hits, std::cmp::Reverse(*i))) {
+        Some((i, &best)) if best > 0 && total > 0 => (STOPWORDS[i].0, best as f64 / matched as f64),
+        _ => ("und", 0.0),
+    }
+}
+
+/// Stopwords known for a language code.
+pub fn stopwords(language: &str) -> Option<&'static [&'static str]> {
+    STOPWORDS.iter().find(|(code, _)| *code == language).map(|(_, words)| *words)
+}
+
+/// `text` without the words in `stopwords`, compared case-insensitively;
+/// the remaining words are joined by single spaces.
+pub fn remove_stopwords(text: &str, stopwords: &HashSet<String>) -> String {
+    text.split_whitespace()
+        .filter(|word| {
+            let bare = word.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase();
+            bare.is_empty() || !stopwords.contains(&bare)
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn separators() -> Vec<String> {
+        DEFAULT_SEPARATORS.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_split_text() {
+        let text = "One two three.\n\nFour five six seven eight nine ten.";
+        assert_eq!(split_text(text, 100, 10, &separators()), vec![text]);
+        assert_eq!(split_text(text, 40, 10, &separators()), vec!["One two three.", "Four five six seven eight nine ten."]);
+        let chunks = split_text(text, 20, 10, &separators());
+        assert_eq!(chunks, vec!["One two three.", "Four five six seven", "six seven eight nine", "eight nine ten."]);
+        assert!(chunks.iter().all(|chunk| chunk.chars().count() <= 20));
+        assert_eq!(split_text("abcdefg", 3, 1, &separators()), vec!["abc", "cde", "efg"]);
+        assert!(split_text("", 10, 0, &separators()).is_empty());
+    }
+
+    #[test]
+    fn test_markup_to_text() {
+        let markdown = "# Title\n\nSome *emphasis* and `code`.\n\n- one\n- two\n";
+        assert_eq!(markdown_to_text(markdown), "Title\n\nSome emphasis and code.\n\n- one\n- two");
+
+        let html = "<html><head><title>T</title><style>p{}</style></head><body>\
+            <h1>Title</h1><p>Fish &amp; chips&nbsp;&#x2014; <b>hot</b></p><script>x()</script>\
+            <ul><li>one</li><li>two</li></ul></body></html>";
+        assert_eq!(html_to_text(html), "Title\n\nFish & chips \u{2014} hot\n\none\n\ntwo");
+    }
+
+    #[test]
+    fn test_normalize_text() {
+        assert_eq!(normalize_text("ﬁle  \u{200B}name\r\n\n\n\nＡ\u{7}", NormalizationForm::Nfkc), "file name\n\nA");
+        assert_eq!(normalize_text("e\u{301}", NormalizationForm::Nfc), "\u{e9}");
+        assert_eq!(NormalizationForm::parse("NFD"), Some(NormalizationForm::Nfd));
+    }
+
+    #[test]
+    fn test_detect_language() {
+        assert_eq!(detect_language("The cat is on the mat and it is happy").0, "en");
+        assert_eq!(detect_language("Le chat est sur la table et il dort").0, "fr");
+        assert_eq!(detect_language("Der Hund ist nicht in dem Haus").0, "de");
+        assert_eq!(detect_language("El perro está en la casa con los niños").0, "es");
+        assert_eq!(detect_language("Привет, как дела?").0, "ru");
+        assert_eq!(detect_language("東京は日本の首都です").0, "ja");
+        assert_eq!(detect_language("12345"), ("und", 0.0));
+    }
+
+    #[test]
+    fn test_remove_stopwords() {
+        let stopwords = stopwords("en").unwrap().iter().map(|s| s.to_string()).collect();
+        assert_eq!(remove_stopwords("The cat is on the Mat.", &stopwords), "cat Mat.");
+    }
+}
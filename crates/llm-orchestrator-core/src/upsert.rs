@@ -0,0 +1,291 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Batched vector upserts.
+//!
+//! A `vector_upsert` step writes records to a vector database registered with
+//! [`WorkflowExecutor::with_vector_db`]:
+//!
+//! ```yaml
+//! - id: store
+//!   type: vector_upsert
+//!   depends_on: [embed_chunks]
+//!   database: pinecone
+//!   index: knowledge-base
+//!   namespace: "{{ inputs.tenant }}"
+//!   records: "{{ steps.embed_chunks.records }}"
+//!   batch_size: 200
+//!   concurrency: 4
+//!   requests_per_second: 10
+//! ```
+//!
+//! Records are split into batches no larger than the database accepts, and
+//! [`WorkflowEventListener::on_step_progress`](crate::WorkflowEventListener::on_step_progress)
+//! reports each finished batch. A rejected batch does not stop the others:
+//! its record IDs and error are listed in the step's second output as
+//! [`UpsertFailure`]s.
+
+use crate::error::{OrchestratorError, Result};
+use crate::executor::WorkflowExecutor;
+use crate::providers::{provider_error, ProviderError, UpsertRequest, VectorRecord};
+use crate::workflow::{Step, StepConfig};
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+/// Output names used when a step declares none.
+const DEFAULT_OUTPUTS: [&str; 2] = ["upserted", "failures"];
+
+/// A batch of records the vector database rejected.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UpsertFailure {
+    /// IDs of the records in the batch.
+    pub ids: Vec<String>,
+
+    /// Error the database returned.
+    pub error: String,
+}
+
+impl WorkflowExecutor {
+    /// Executes a vector upsert step.
+    pub(crate) async fn execute_vector_upsert_step(&self, step: &Step) -> Result<HashMap<String, Value>> {
+        let config = match &step.config {
+            StepConfig::VectorUpsert(config) => config,
+            _ => {
+                return Err(OrchestratorError::InvalidStepConfig {
+                    step_id: step.id.clone(),
+                    reason: "Expected VectorUpsert step config".to_string(),
+                })
+            }
+        };
+        let vector_db = self
+            .vector_dbs
+            .get(&config.database)
+            .map(|db| db.value().clone())
+            .ok_or_else(|| OrchestratorError::other(format!("Vector database '{}' not registered", config.database)))?;
+
+        let mut records = config.records.clone();
+        let mut templates = Vec::new();
+        crate::http::collect_strings(&records, &mut templates);
+        for template in templates {
+            self.resolve_template_secrets(&template).await?;
+        }
+        self.render_values(&mut records)?;
+        let records = parse_records(&step.id, records)?;
+        let namespace = config.namespace.as_deref().map(|ns| self.context.render_template(ns)).transpose()?;
+
+        let batch_size = vector_db.max_upsert_batch().map_or(config.batch_size, |max| config.batch_size.min(max)).max(1);
+        let batches: Vec<Vec<VectorRecord>> = records.chunks(batch_size).map(<[VectorRecord]>::to_vec).collect();
+        let batch_count = batches.len();
+        let spacing = config.requests_per_second.map(|rate| Duration::from_secs_f64(1.0 / rate));
+        debug!(
+            step_id = %step.id,
+            database = %config.database,
+            index = %config.index,
+            records = records.len(),
+            batches = batch_count,
+            "Upserting vectors"
+        );
+
+        // Batch `i` starts no earlier than `i` request intervals after the first
+        let start = tokio::time::Instant::now();
+        let mut responses = stream::iter(batches.into_iter().enumerate())
+            .map(|(i, vectors)| {
+                let vector_db = vector_db.clone();
+                let request = UpsertRequest {
+                    index: config.index.clone(),
+                    vectors,
+                    namespace: namespace.clone(),
+                };
+                async move {
+                    if let Some(spacing) = spacing {
+                        tokio::time::sleep_until(start + spacing.mul_f64(i as f64)).await;
+                    }
+                    let ids: Vec<String> = request.vectors.iter().map(|v| v.id.clone()).collect();
+                    let started = std::time::Instant::now();
+                    let response = vector_db.upsert(request).await;
+                    (ids, response, started.elapsed())
+                }
+            })
+            .buffer_unordered(config.concurrency.max(1));
+
+        let mut upserted = 0;
+        let mut completed = 0;
+        let mut failures = Vec::new();
+        let mut last_error: Option<ProviderError> = None;
+        while let Some((ids, response, latency)) = responses.next().await {
+            self.record_provider_latency(&step.id, latency);
+            completed += ids.len();
+            match response {
+                Ok(response) => upserted += response.upserted_count,
+                Err(e) => {
+                    warn!(step_id = %step.id, records = ids.len(), error = %e, "Vector upsert batch failed");
+                    failures.push(UpsertFailure { ids, error: e.to_string() });
+                    last_error = Some(e);
+                }
+            }
+            self.emit(|l| l.on_step_progress(self.run_id, step, completed, records.len()));
+        }
+
+        if let Some(e) = last_error.filter(|_| failures.len() == batch_count) {
+            return Err(provider_error(&config.database, "Vector upsert error", e));
+        }
+        info!(step_id = %step.id, upserted, failed_batches = failures.len(), "Vector upsert completed");
+
+        let names: Vec<&str> = if step.output.is_empty() {
+            DEFAULT_OUTPUTS.to_vec()
+        } else {
+            step.output.iter().map(String::as_str).collect()
+        };
+        let failures = serde_json::to_value(failures).map_err(|e| OrchestratorError::serialization(e.to_string()))?;
+        let values = [Value::from(upserted), failures];
+        Ok(names.into_iter().map(String::from).zip(values).collect())
+    }
+}
+
+/// Reads rendered records, accepting numeric IDs.
+fn parse_records(step_id: &str, records: Value) -> Result<Vec<VectorRecord>> {
+    let Value::Array(records) = records else {
+        return Err(OrchestratorError::InvalidStepConfig {
+            step_id: step_id.to_string(),
+            reason: format!("Records must be a list, got: {}", records),
+        });
+    };
+    records
+        .into_iter()
+        .enumerate()
+        .map(|(i, mut record)| {
+            if let Some(id @ Value::Number(_)) = record.get_mut("id") {
+                *id = Value::String(id.to_string());
+            }
+            serde_json::from_value(record).map_err(|e| OrchestratorError::InvalidStepConfig {
+                step_id: step_id.to_string(),
+                reason: format!("Record {} is invalid: {}", i, e),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::ExecutionEvent;
+    use crate::providers::{
+        DeleteRequest, DeleteResponse, UpsertResponse, VectorSearchProvider, VectorSearchRequest,
+        VectorSearchResponse,
+    };
+    use crate::workflow::Workflow;
+    use parking_lot::Mutex;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    /// Rejects batches holding the record with ID "bad", and remembers the rest.
+    #[derive(Default)]
+    struct RecordingVectorDb {
+        batches: Mutex<Vec<UpsertRequest>>,
+    }
+
+    #[async_trait::async_trait]
+    impl VectorSearchProvider for RecordingVectorDb {
+        async fn search(&self, _request: VectorSearchRequest) -> std::result::Result<VectorSearchResponse, ProviderError> {
+            unimplemented!()
+        }
+
+        async fn upsert(&self, request: UpsertRequest) -> std::result::Result<UpsertResponse, ProviderError> {
+            if request.vectors.iter().any(|v| v.id == "bad") {
+                return Err(ProviderError::InvalidRequest("vector has the wrong dimension".to_string()));
+            }
+            let upserted_count = request.vectors.len();
+            self.batches.lock().push(request);
+            Ok(UpsertResponse {
+                upserted_count,
+                metadata: HashMap::new(),
+            })
+        }
+
+        async fn delete(&self, _request: DeleteRequest) -> std::result::Result<DeleteResponse, ProviderError> {
+            unimplemented!()
+        }
+
+        fn max_upsert_batch(&self) -> Option<usize> {
+            Some(2)
+        }
+
+        fn name(&self) -> &str {
+            "recording"
+        }
+    }
+
+    fn workflow(records: &str) -> Workflow {
+        Workflow::from_yaml(&format!(
+            r#"
+name: ingest
+steps:
+  - id: store
+    type: vector_upsert
+    database: db
+    index: docs
+    namespace: "{{{{ inputs.tenant }}}}"
+    records: {}
+    batch_size: 10
+    concurrency: 2
+"#,
+            records
+        ))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_upsert_batches_and_reports_failures() {
+        let db = Arc::new(RecordingVectorDb::default());
+        let inputs = HashMap::from([
+            ("tenant".to_string(), json!("acme")),
+            (
+                "chunks".to_string(),
+                json!([
+                    {"id": 1, "vector": [0.1, 0.2], "metadata": {"text": "one"}},
+                    {"id": 2, "vector": [0.3, 0.4]},
+                    {"id": "bad", "vector": [0.5]},
+                    {"id": 4, "vector": [0.7, 0.8]},
+                    {"id": 5, "vector": [0.9, 1.0]},
+                ]),
+            ),
+        ]);
+        let executor = WorkflowExecutor::new(workflow("\"{{ inputs.chunks }}\""), inputs)
+            .unwrap()
+            .with_vector_db("db", db.clone());
+        let mut events = executor.subscribe();
+
+        let results = executor.execute().await.unwrap();
+        let outputs = &results["store"].outputs;
+        assert_eq!(outputs["upserted"], json!(3));
+        assert_eq!(outputs["failures"][0]["ids"], json!(["bad", "4"]));
+
+        // Batches are capped at the database's limit and keep the namespace
+        let batches = db.batches.lock();
+        assert_eq!(batches.len(), 2);
+        assert!(batches.iter().all(|b| b.vectors.len() <= 2 && b.namespace.as_deref() == Some("acme")));
+        assert_eq!(batches.iter().find(|b| b.vectors[0].id == "1").unwrap().vectors[0].metadata, Some(json!({"text": "one"})));
+
+        let mut progress = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            if let ExecutionEvent::StepProgress { completed, total, .. } = event {
+                progress.push((completed, total));
+            }
+        }
+        assert_eq!(progress, [(2, 5), (4, 5), (5, 5)]);
+    }
+
+    #[tokio::test]
+    async fn test_upsert_fails_when_every_batch_fails() {
+        let executor = WorkflowExecutor::new(workflow(r#"[{id: "bad", vector: [0.1]}]"#), HashMap::new())
+            .unwrap()
+            .with_vector_db("db", Arc::new(RecordingVectorDb::default()));
+
+        let results = executor.execute().await;
+        assert!(matches!(results, Err(OrchestratorError::StepsFailed(_))));
+    }
+}
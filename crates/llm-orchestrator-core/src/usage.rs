@@ -0,0 +1,191 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Usage accounting for provider calls.
+//!
+//! Every completion, judge and embedding call of a run is counted by a
+//! [`UsageTracker`], per provider and model. The totals are available from
+//! [`WorkflowExecutor::usage`](crate::WorkflowExecutor::usage) once the run
+//! has finished and are saved with the workflow state, where the
+//! `llm-orchestrator usage` command sums them over many runs.
+
+use crate::providers::{CompletionResponse, EmbeddingResponse, Usage};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+
+/// Requests and tokens of one provider and model.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModelUsage {
+    /// Provider name as configured in the workflow.
+    pub provider: String,
+    /// Model name.
+    pub model: String,
+    /// Requests sent, including failed ones.
+    pub requests: u64,
+    /// Requests that returned an error.
+    pub failed_requests: u64,
+    /// Prompt tokens reported by the provider.
+    pub input_tokens: u64,
+    /// Completion tokens reported by the provider.
+    pub output_tokens: u64,
+    /// Total tokens reported by the provider.
+    pub total_tokens: u64,
+    /// Cost in US dollars, for providers that report it.
+    pub cost_usd: f64,
+}
+
+/// Per-provider, per-model usage of one run.
+#[derive(Debug, Default)]
+pub struct UsageTracker {
+    models: DashMap<(String, String), ModelUsage>,
+}
+
+impl UsageTracker {
+    /// Creates an empty tracker.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a completion request; `response` is `None` when it failed.
+    pub fn record_completion(&self, provider: &str, model: &str, response: Option<&CompletionResponse>) {
+        let usage = response.map(completion_usage).unwrap_or_default();
+        self.add(
+            provider,
+            model,
+            response.is_some(),
+            usage.input_tokens,
+            usage.output_tokens,
+            usage.total_tokens,
+            usage.cost_usd.unwrap_or(0.0),
+        );
+    }
+
+    /// Records an embedding request; `response` is `None` when it failed.
+    pub fn record_embedding(&self, provider: &str, model: &str, response: Option<&EmbeddingResponse>) {
+        let usage = response.map(embedding_usage).unwrap_or_default();
+        self.add(provider, model, response.is_some(), usage.input_tokens, 0, usage.total_tokens, 0.0);
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn add(
+        &self,
+        provider: &str,
+        model: &str,
+        succeeded: bool,
+        input_tokens: u64,
+        output_tokens: u64,
+        total_tokens: u64,
+        cost_usd: f64,
+    ) {
+        let mut usage = self
+            .models
+            .entry((provider.to_string(), model.to_string()))
+            .or_insert_with(|| ModelUsage {
+                provider: provider.to_string(),
+                model: model.to_string(),
+                ..Default::default()
+            });
+        usage.requests += 1;
+        if !succeeded {
+            usage.failed_requests += 1;
+        }
+        usage.input_tokens += input_tokens;
+        usage.output_tokens += output_tokens;
+        usage.total_tokens += total_tokens;
+        usage.cost_usd += cost_usd;
+    }
+
+    /// Usage recorded so far, sorted by provider and model.
+    pub fn snapshot(&self) -> Vec<ModelUsage> {
+        let mut usage: Vec<ModelUsage> = self.models.iter().map(|entry| entry.value().clone()).collect();
+        usage.sort_by(|a, b| (&a.provider, &a.model).cmp(&(&b.provider, &b.model)));
+        usage
+    }
+}
+
+/// Usage of a completion, read from the provider's `usage` when set and from
+/// the `input_tokens`, `output_tokens` and `cost_usd` metadata otherwise.
+pub(crate) fn completion_usage(response: &CompletionResponse) -> Usage {
+    if let Some(usage) = response.usage {
+        return usage;
+    }
+    let metadata_tokens = |key: &str| response.metadata.get(key).and_then(|v| v.as_u64()).unwrap_or(0);
+    let input_tokens = metadata_tokens("input_tokens");
+    let output_tokens = metadata_tokens("output_tokens");
+    Usage {
+        input_tokens,
+        output_tokens,
+        total_tokens: response.tokens_used.map_or(input_tokens + output_tokens, u64::from),
+        cost_usd: response.metadata.get("cost_usd").and_then(|v| v.as_f64()),
+        latency_ms: None,
+    }
+}
+
+/// Usage of an embedding request, whose tokens are all input tokens.
+pub(crate) fn embedding_usage(response: &EmbeddingResponse) -> Usage {
+    let tokens = response.tokens_used.map_or(0, u64::from);
+    Usage {
+        input_tokens: tokens,
+        total_tokens: tokens,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    fn completion(tokens_used: Option<u32>, metadata: serde_json::Value) -> CompletionResponse {
+        CompletionResponse {
+            text: "ok".to_string(),
+            model: "m".to_string(),
+            tokens_used,
+            usage: None,
+            tool_calls: Vec::new(),
+            metadata: serde_json::from_value(metadata).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_usage_is_summed_per_model() {
+        let tracker = UsageTracker::new();
+        tracker.record_completion(
+            "openai",
+            "gpt-4",
+            Some(&completion(Some(15), json!({"input_tokens": 10, "output_tokens": 5, "cost_usd": 0.25}))),
+        );
+        tracker.record_completion(
+            "openai",
+            "gpt-4",
+            Some(&completion(None, json!({"input_tokens": 3, "output_tokens": 4}))),
+        );
+        tracker.record_completion("openai", "gpt-4", None);
+        tracker.record_embedding(
+            "openai",
+            "text-embedding-3-small",
+            Some(&EmbeddingResponse {
+                embeddings: vec![vec![0.0]],
+                model: "text-embedding-3-small".to_string(),
+                tokens_used: Some(8),
+                metadata: HashMap::new(),
+            }),
+        );
+        tracker.record_completion("anthropic", "claude", Some(&completion(Some(2), json!({}))));
+
+        let usage = tracker.snapshot();
+        assert_eq!(
+            usage.iter().map(|u| (u.provider.as_str(), u.model.as_str())).collect::<Vec<_>>(),
+            vec![("anthropic", "claude"), ("openai", "gpt-4"), ("openai", "text-embedding-3-small")]
+        );
+
+        let gpt = &usage[1];
+        assert_eq!((gpt.requests, gpt.failed_requests), (3, 1));
+        assert_eq!((gpt.input_tokens, gpt.output_tokens, gpt.total_tokens), (13, 9, 22));
+        assert_eq!(gpt.cost_usd, 0.25);
+
+        assert_eq!((usage[2].input_tokens, usage[2].total_tokens), (8, 8));
+        assert_eq!((usage[0].requests, usage[0].total_tokens), (1, 2));
+    }
+}
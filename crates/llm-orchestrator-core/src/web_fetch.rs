@@ -0,0 +1,362 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Web page fetching for research workflows.
+//!
+//! A `web_fetch` step fetches a list of URLs concurrently, politely, and
+//! within limits:
+//!
+//! ```yaml
+//! - id: pages
+//!   type: web_fetch
+//!   depends_on: [search]
+//!   urls: "{{ steps.search.links }}"
+//!   concurrency: 4
+//!   max_bytes: 1048576
+//!   content_types: ["text/html", "text/plain"]
+//!   output: [pages, failed]
+//! ```
+//!
+//! Each site's robots.txt is fetched once per step: disallowed URLs fail,
+//! and requests to the site are spaced by its crawl delay. Responses with
+//! another content type, or bodies over `max_bytes`, fail too. A failed URL
+//! does not fail the step; the second output lists them with their errors.
+
+mod robots;
+
+pub use robots::Robots;
+
+use crate::error::{OrchestratorError, Result};
+use crate::executor::WorkflowExecutor;
+use crate::transform::text::html_to_text;
+use crate::workflow::{Step, StepConfig, WebFetchConfig};
+use futures::stream::{self, StreamExt};
+use lazy_static::lazy_static;
+use regex::Regex;
+use reqwest::{header, Client, StatusCode, Url};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::OnceCell;
+use tokio::time::Instant;
+use tracing::{debug, info};
+
+/// Output names used when a step declares none.
+const DEFAULT_OUTPUTS: [&str; 2] = ["pages", "failed"];
+
+/// Largest robots.txt read; the rest is ignored.
+const MAX_ROBOTS_BYTES: usize = 512 * 1024;
+
+lazy_static! {
+    static ref TITLE: Regex = Regex::new(r"(?is)<title[^>]*>(.*?)</title>").unwrap();
+}
+
+impl WorkflowExecutor {
+    /// Executes a web fetch step.
+    pub(crate) async fn execute_web_fetch_step(&self, step: &Step) -> Result<HashMap<String, Value>> {
+        let config = match &step.config {
+            StepConfig::WebFetch(config) => config,
+            _ => {
+                return Err(OrchestratorError::InvalidStepConfig {
+                    step_id: step.id.clone(),
+                    reason: "Expected WebFetch step config".to_string(),
+                })
+            }
+        };
+
+        let mut urls = config.urls.clone();
+        let mut templates = Vec::new();
+        crate::http::collect_strings(&urls, &mut templates);
+        for template in templates {
+            self.resolve_template_secrets(&template).await?;
+        }
+        self.render_values(&mut urls)?;
+        let urls = parse_urls(&step.id, urls)?;
+        debug!(step_id = %step.id, urls = urls.len(), "Fetching web pages");
+
+        let client = Client::builder()
+            .user_agent(&config.user_agent)
+            .timeout(Duration::from_secs(config.timeout_seconds))
+            .build()
+            .map_err(|e| OrchestratorError::other(format!("Failed to build HTTP client: {}", e)))?;
+        let fetcher = Fetcher {
+            client,
+            config,
+            sites: Mutex::new(HashMap::new()),
+        };
+        // Collected first: a stream mapping borrowed URLs is not provably Send
+        let fetches: Vec<_> = urls.iter().map(|url| fetcher.fetch(url)).collect();
+        let results: Vec<_> = stream::iter(fetches).buffered(config.concurrency.max(1)).collect().await;
+
+        let mut pages = Vec::new();
+        let mut failed = Vec::new();
+        for (url, result) in urls.iter().zip(results) {
+            match result {
+                Ok(page) => pages.push(page),
+                Err(error) => {
+                    debug!(step_id = %step.id, url = %url, error = %error, "Web fetch failed");
+                    failed.push(json!({ "url": url, "error": error }));
+                }
+            }
+        }
+        info!(step_id = %step.id, fetched = pages.len(), failed = failed.len(), "Web fetch completed");
+
+        let names: Vec<&str> = if step.output.is_empty() {
+            DEFAULT_OUTPUTS.to_vec()
+        } else {
+            step.output.iter().map(String::as_str).collect()
+        };
+        let values = [Value::Array(pages), Value::Array(failed)];
+        Ok(names.into_iter().map(String::from).zip(values).collect())
+    }
+}
+
+/// Reads rendered URLs: one string or a list of them.
+fn parse_urls(step_id: &str, urls: Value) -> Result<Vec<String>> {
+    let invalid = |urls: &Value| OrchestratorError::InvalidStepConfig {
+        step_id: step_id.to_string(),
+        reason: format!("URLs must be a string or a list of strings, got: {}", urls),
+    };
+    match &urls {
+        Value::String(url) => Ok(vec![url.clone()]),
+        Value::Array(list) => list
+            .iter()
+            .map(|url| url.as_str().map(String::from).ok_or_else(|| invalid(&urls)))
+            .collect(),
+        _ => Err(invalid(&urls)),
+    }
+}
+
+/// What is known about a site during a step.
+struct Site {
+    robots: Robots,
+
+    /// Earliest time the next request may be sent, under the crawl delay.
+    next_request: tokio::sync::Mutex<Instant>,
+}
+
+/// Fetches the pages of one step.
+struct Fetcher<'a> {
+    client: Client,
+    config: &'a WebFetchConfig,
+    sites: Mutex<HashMap<String, Arc<OnceCell<Arc<Site>>>>>,
+}
+
+impl Fetcher<'_> {
+    /// Fetches a page, or says why it could not be.
+    async fn fetch(&self, url: &str) -> std::result::Result<Value, String> {
+        let parsed = Url::parse(url).map_err(|e| format!("Invalid URL: {}", e))?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return Err(format!("Unsupported URL scheme '{}'", parsed.scheme()));
+        }
+        if self.config.respect_robots {
+            let site = self.site(&parsed).await;
+            let path = match parsed.query() {
+                Some(query) => format!("{}?{}", parsed.path(), query),
+                None => parsed.path().to_string(),
+            };
+            if !site.robots.is_allowed(&path) {
+                return Err("Disallowed by robots.txt".to_string());
+            }
+            if let Some(delay) = site.robots.crawl_delay() {
+                let mut next_request = site.next_request.lock().await;
+                tokio::time::sleep_until(*next_request).await;
+                *next_request = Instant::now() + delay;
+            }
+        }
+
+        let mut response = self.client.get(parsed).send().await.map_err(|e| e.to_string())?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(format!("HTTP {}", status));
+        }
+        let content_type = response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(';').next())
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+        if !accepts(&self.config.content_types, &content_type) {
+            return Err(format!("Content type '{}' is not accepted", content_type));
+        }
+        let final_url = response.url().to_string();
+        let body = read_body(&mut response, self.config.max_bytes)
+            .await?
+            .ok_or_else(|| format!("Body is larger than {} bytes", self.config.max_bytes))?;
+        let body = String::from_utf8_lossy(&body);
+
+        let mut page = json!({
+            "url": url,
+            "final_url": final_url,
+            "status": status.as_u16(),
+            "content_type": content_type,
+        });
+        if matches!(content_type.as_str(), "text/html" | "application/xhtml+xml") {
+            if let Some(title) = TITLE.captures(&body) {
+                page["title"] = json!(html_to_text(&title[1]));
+            }
+            if self.config.extract_text {
+                page["text"] = json!(html_to_text(&body));
+                return Ok(page);
+            }
+        }
+        page["text"] = json!(body);
+        Ok(page)
+    }
+
+    /// The site of `url`, fetching its robots.txt the first time.
+    async fn site(&self, url: &Url) -> Arc<Site> {
+        let origin = url.origin().ascii_serialization();
+        let cell = self.sites.lock().unwrap().entry(origin.clone()).or_default().clone();
+        cell.get_or_init(|| async {
+            Arc::new(Site {
+                robots: self.robots(&origin).await,
+                next_request: tokio::sync::Mutex::new(Instant::now()),
+            })
+        })
+        .await
+        .clone()
+    }
+
+    /// A site's robots.txt rules for our user agent.
+    ///
+    /// A missing robots.txt allows everything; one that cannot be read
+    /// disallows everything.
+    async fn robots(&self, origin: &str) -> Robots {
+        let response = match self.client.get(format!("{}/robots.txt", origin)).send().await {
+            Ok(response) => response,
+            Err(_) => return Robots::disallow_all(),
+        };
+        let status = response.status();
+        if status.is_client_error() {
+            return Robots::allow_all();
+        }
+        if status != StatusCode::OK {
+            return Robots::disallow_all();
+        }
+        let mut response = response;
+        let mut body = Vec::new();
+        while body.len() < MAX_ROBOTS_BYTES {
+            match response.chunk().await {
+                Ok(Some(chunk)) => body.extend_from_slice(&chunk),
+                Ok(None) => break,
+                Err(_) => return Robots::disallow_all(),
+            }
+        }
+        body.truncate(MAX_ROBOTS_BYTES);
+        Robots::parse(&String::from_utf8_lossy(&body), &self.config.user_agent)
+    }
+}
+
+/// Whether `content_type` is among `accepted`, where `type/*` accepts a whole type.
+fn accepts(accepted: &[String], content_type: &str) -> bool {
+    accepted.iter().any(|accepted| {
+        let accepted = accepted.trim().to_ascii_lowercase();
+        match accepted.strip_suffix("/*") {
+            Some(kind) => content_type.split('/').next() == Some(kind),
+            None => accepted == content_type,
+        }
+    })
+}
+
+/// Reads a response body of at most `max_bytes`, or `None` if it is longer.
+async fn read_body(response: &mut reqwest::Response, max_bytes: usize) -> std::result::Result<Option<Vec<u8>>, String> {
+    if response.content_length().is_some_and(|length| length > max_bytes as u64) {
+        return Ok(None);
+    }
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+        if body.len() + chunk.len() > max_bytes {
+            return Ok(None);
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(Some(body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workflow::Workflow;
+
+    #[test]
+    fn test_accepts() {
+        let accepted = vec!["text/*".to_string(), "application/json".to_string()];
+        assert!(accepts(&accepted, "text/html"));
+        assert!(accepts(&accepted, "application/json"));
+        assert!(!accepts(&accepted, "application/pdf"));
+        assert!(!accepts(&accepted, ""));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_within_limits() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/robots.txt")
+            .with_body("User-agent: *\nDisallow: /private\n")
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/article")
+            .with_header("content-type", "text/html; charset=utf-8")
+            .with_body("<html><head><title>News &amp; views</title></head><body><p>Rain today.</p></body></html>")
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/report.pdf")
+            .with_header("content-type", "application/pdf")
+            .with_body("%PDF")
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/large")
+            .with_header("content-type", "text/plain")
+            .with_body("x".repeat(2048))
+            .create_async()
+            .await;
+        let private = server.mock("GET", "/private/page").expect(0).create_async().await;
+
+        let workflow = Workflow::from_yaml(
+            r#"
+name: "research"
+steps:
+  - id: "pages"
+    type: "web_fetch"
+    urls: "{{ inputs.urls }}"
+    max_bytes: 1024
+"#,
+        )
+        .unwrap();
+        let urls: Vec<String> = ["/article", "/private/page", "/report.pdf", "/large"]
+            .iter()
+            .map(|path| format!("{}{}", server.url(), path))
+            .collect();
+        let inputs = HashMap::from([("urls".to_string(), json!(urls))]);
+        let results = WorkflowExecutor::new(workflow, inputs).unwrap().execute().await.unwrap();
+
+        let outputs = &results["pages"].outputs;
+        let pages = outputs["pages"].as_array().unwrap();
+        assert_eq!(pages.len(), 1);
+        assert_eq!(pages[0]["url"], json!(urls[0]));
+        assert_eq!(pages[0]["title"], json!("News & views"));
+        assert_eq!(pages[0]["text"], json!("Rain today."));
+        let failed: Vec<(&str, &str)> = outputs["failed"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|f| (f["url"].as_str().unwrap(), f["error"].as_str().unwrap()))
+            .collect();
+        assert_eq!(
+            failed,
+            vec![
+                (urls[1].as_str(), "Disallowed by robots.txt"),
+                (urls[2].as_str(), "Content type 'application/pdf' is not accepted"),
+                (urls[3].as_str(), "Body is larger than 1024 bytes"),
+            ]
+        );
+        private.assert_async().await;
+    }
+}
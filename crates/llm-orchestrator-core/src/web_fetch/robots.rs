@@ -0,0 +1,180 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! robots.txt rules, as specified by RFC 9309.
+
+use std::time::Duration;
+
+/// The rules of a robots.txt file that apply to one user agent.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Robots {
+    /// `(allow, pattern)` pairs.
+    rules: Vec<(bool, String)>,
+    crawl_delay: Option<Duration>,
+}
+
+/// A group of lines of a robots.txt file.
+#[derive(Default)]
+struct Group {
+    agents: Vec<String>,
+    rules: Vec<(bool, String)>,
+    crawl_delay: Option<Duration>,
+}
+
+impl Robots {
+    /// Rules that allow everything, for sites without a robots.txt.
+    pub fn allow_all() -> Self {
+        Self::default()
+    }
+
+    /// Rules that disallow everything, for sites whose robots.txt is unreachable.
+    pub fn disallow_all() -> Self {
+        Self {
+            rules: vec![(false, "/".to_string())],
+            crawl_delay: None,
+        }
+    }
+
+    /// The rules of `robots_txt` for `user_agent`.
+    ///
+    /// Groups naming the user agent's product token apply, or else the `*`
+    /// group; rules of groups naming the same agent are combined.
+    pub fn parse(robots_txt: &str, user_agent: &str) -> Self {
+        let mut groups: Vec<Group> = Vec::new();
+        let mut in_agents = false;
+        for line in robots_txt.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            match key.trim().to_ascii_lowercase().as_str() {
+                "user-agent" => {
+                    if !in_agents {
+                        groups.push(Group::default());
+                        in_agents = true;
+                    }
+                    if let Some(group) = groups.last_mut() {
+                        group.agents.push(value.to_ascii_lowercase());
+                    }
+                }
+                key @ ("allow" | "disallow") => {
+                    in_agents = false;
+                    if let Some(group) = groups.last_mut() {
+                        // An empty disallow rule disallows nothing
+                        if !value.is_empty() {
+                            group.rules.push((key == "allow", value.to_string()));
+                        }
+                    }
+                }
+                "crawl-delay" => {
+                    in_agents = false;
+                    if let Some(group) = groups.last_mut() {
+                        group.crawl_delay = value.parse::<f64>().ok().and_then(|s| Duration::try_from_secs_f64(s).ok());
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let token = user_agent.split('/').next().unwrap_or_default().trim().to_ascii_lowercase();
+        let named = |agent: &str| agent != "*" && !agent.is_empty() && token.starts_with(agent);
+        let applies: Vec<&Group> = if groups.iter().any(|g| g.agents.iter().any(|a| named(a))) {
+            groups.iter().filter(|g| g.agents.iter().any(|a| named(a))).collect()
+        } else {
+            groups.iter().filter(|g| g.agents.iter().any(|a| a == "*")).collect()
+        };
+        Self {
+            rules: applies.iter().flat_map(|g| g.rules.iter().cloned()).collect(),
+            crawl_delay: applies.iter().find_map(|g| g.crawl_delay),
+        }
+    }
+
+    /// Whether a path, with its query, may be fetched.
+    ///
+    /// The longest matching rule decides; an allow rule wins a tie.
+    pub fn is_allowed(&self, path: &str) -> bool {
+        let mut best: Option<(usize, bool)> = None;
+        for (allow, pattern) in &self.rules {
+            if matches(pattern, path) {
+                let candidate = (pattern.len(), *allow);
+                if best.map_or(true, |best| candidate > best) {
+                    best = Some(candidate);
+                }
+            }
+        }
+        best.map_or(true, |(_, allow)| allow)
+    }
+
+    /// Delay the site asks for between requests.
+    pub fn crawl_delay(&self) -> Option<Duration> {
+        self.crawl_delay
+    }
+}
+
+/// Whether `path` starts with `pattern`, where `*` matches any characters
+/// and a final `$` anchors the end.
+fn matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(pattern) => (pattern, true),
+        None => (pattern, false),
+    };
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = path.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    for (i, part) in parts.iter().enumerate() {
+        let last = i + 1 == parts.len();
+        if last && anchored {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(at) => rest = &rest[at + part.len()..],
+            None => return false,
+        }
+    }
+    !anchored || rest.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROBOTS: &str = "
+# Example
+User-agent: *
+Disallow: /private/
+Allow: /private/public
+Disallow: /*.pdf$
+
+User-agent: llm-orchestrator
+User-agent: other
+Disallow: /drafts
+Crawl-delay: 1.5
+";
+
+    #[test]
+    fn test_rules_for_user_agent() {
+        let robots = Robots::parse(ROBOTS, "Mozilla/5.0");
+        assert!(robots.is_allowed("/"));
+        assert!(!robots.is_allowed("/private/x"));
+        assert!(robots.is_allowed("/private/public/page"));
+        assert!(!robots.is_allowed("/docs/a.pdf"));
+        assert!(robots.is_allowed("/docs/a.pdf?download=1"));
+        assert_eq!(robots.crawl_delay(), None);
+
+        let robots = Robots::parse(ROBOTS, "LLM-Orchestrator/0.1");
+        assert!(robots.is_allowed("/private/x"));
+        assert!(!robots.is_allowed("/drafts/1"));
+        assert_eq!(robots.crawl_delay(), Some(Duration::from_millis(1500)));
+    }
+
+    #[test]
+    fn test_defaults() {
+        assert!(Robots::allow_all().is_allowed("/anything"));
+        assert!(!Robots::disallow_all().is_allowed("/"));
+        assert!(Robots::parse("User-agent: *\nDisallow:\n", "bot").is_allowed("/"));
+    }
+}
@@ -0,0 +1,289 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Webhook triggers.
+//!
+//! A workflow with a webhook trigger is started by POSTs to
+//! `/hooks/{name}` on a server started with `llm-orchestrator serve`:
+//!
+//! ```yaml
+//! name: triage-issue
+//! triggers:
+//!   - type: webhook
+//!     name: github-issues
+//!     secret: github/webhook_secret
+//!     inputs:
+//!       repository: "{{ payload.repository.full_name }}"
+//!       title: "{{ payload.issue.title }}"
+//!       labels: "{{ payload.issue.labels }}"
+//!       event: "{{ headers.x-github-event }}"
+//! steps: [...]
+//! ```
+//!
+//! Requests must be signed with HMAC-SHA256 over the raw body using the
+//! value of `secret` (a key in the secret store). The default `hmac` scheme
+//! reads a hex digest, optionally prefixed with `sha256=`, from
+//! `X-Hub-Signature-256` as GitHub sends it; `slack` checks Slack's
+//! `v0` signatures and rejects requests more than five minutes old.
+//!
+//! Each `inputs` entry is a template rendered against the JSON `payload` and
+//! the request `headers` (lowercase names). An entry that is a single
+//! `{{ path }}` expression keeps the JSON type of the value it names; without
+//! `inputs`, the whole payload is passed as the `payload` input.
+
+use crate::context::ExecutionContext;
+use hmac::{Hmac, Mac};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::Sha256;
+use std::collections::HashMap;
+use thiserror::Error;
+
+/// Header carrying the signature under the `hmac` scheme, unless overridden.
+pub const DEFAULT_SIGNATURE_HEADER: &str = "x-hub-signature-256";
+
+/// Header carrying the signature under the `slack` scheme.
+const SLACK_SIGNATURE_HEADER: &str = "x-slack-signature";
+
+/// Header carrying the request time under the `slack` scheme.
+const SLACK_TIMESTAMP_HEADER: &str = "x-slack-request-timestamp";
+
+/// Oldest Slack request accepted, in seconds, to limit replays.
+const SLACK_MAX_AGE_SECS: i64 = 300;
+
+/// Why a webhook request was refused.
+#[derive(Debug, Error)]
+pub enum WebhookError {
+    /// The request is missing a valid signature.
+    #[error("Invalid webhook signature: {0}")]
+    InvalidSignature(String),
+
+    /// The payload could not be mapped into workflow inputs.
+    #[error("Invalid webhook payload: {0}")]
+    InvalidPayload(String),
+}
+
+/// How webhook requests are signed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SignatureScheme {
+    /// Hex HMAC-SHA256 of the body, optionally prefixed with `sha256=` (GitHub and most services).
+    #[default]
+    Hmac,
+    /// Slack's `v0=` HMAC-SHA256 of `v0:{timestamp}:{body}`.
+    Slack,
+}
+
+/// A webhook that starts a run of the workflow.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WebhookTrigger {
+    /// Name in the trigger path, `/hooks/{name}`.
+    pub name: String,
+
+    /// Secret store key of the HMAC signing secret.
+    pub secret: String,
+
+    /// How requests are signed.
+    #[serde(default)]
+    pub scheme: SignatureScheme,
+
+    /// Header carrying the signature under the `hmac` scheme.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature_header: Option<String>,
+
+    /// Workflow input templates, keyed by input name.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub inputs: HashMap<String, String>,
+}
+
+impl WebhookTrigger {
+    /// Checks that the trigger can be served.
+    pub fn validate(&self) -> crate::error::Result<()> {
+        let valid_name = !self.name.is_empty()
+            && self
+                .name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+        if !valid_name {
+            return Err(crate::error::OrchestratorError::validation(format!(
+                "Webhook name '{}' must be non-empty and use only letters, digits, '-', '_', and '.'",
+                self.name
+            )));
+        }
+        if self.secret.is_empty() {
+            return Err(crate::error::OrchestratorError::validation(format!(
+                "Webhook '{}' needs a signing secret",
+                self.name
+            )));
+        }
+        Ok(())
+    }
+
+    /// Verifies the request signature.
+    ///
+    /// `headers` are keyed by lowercase name and `now` is the current Unix time.
+    pub fn verify(
+        &self,
+        key: &[u8],
+        headers: &HashMap<String, String>,
+        body: &[u8],
+        now: i64,
+    ) -> Result<(), WebhookError> {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .map(|v| v.trim())
+                .ok_or_else(|| WebhookError::InvalidSignature(format!("missing {} header", name)))
+        };
+
+        match self.scheme {
+            SignatureScheme::Hmac => {
+                let name = self
+                    .signature_header
+                    .as_deref()
+                    .map(str::to_ascii_lowercase)
+                    .unwrap_or_else(|| DEFAULT_SIGNATURE_HEADER.to_string());
+                let signature = header(&name)?;
+                let signature = signature.strip_prefix("sha256=").unwrap_or(signature);
+                check_mac(key, &[body], signature)
+            }
+            SignatureScheme::Slack => {
+                let timestamp = header(SLACK_TIMESTAMP_HEADER)?;
+                let sent: i64 = timestamp
+                    .parse()
+                    .map_err(|_| WebhookError::InvalidSignature("invalid request timestamp".to_string()))?;
+                if (now - sent).abs() > SLACK_MAX_AGE_SECS {
+                    return Err(WebhookError::InvalidSignature("request timestamp is too old".to_string()));
+                }
+                let signature = header(SLACK_SIGNATURE_HEADER)?;
+                let signature = signature
+                    .strip_prefix("v0=")
+                    .ok_or_else(|| WebhookError::InvalidSignature("unsupported signature version".to_string()))?;
+                check_mac(key, &[b"v0:", timestamp.as_bytes(), b":", body], signature)
+            }
+        }
+    }
+
+    /// Maps a payload and its request headers into workflow inputs.
+    pub fn map_inputs(
+        &self,
+        payload: Value,
+        headers: &HashMap<String, String>,
+    ) -> Result<HashMap<String, Value>, WebhookError> {
+        if self.inputs.is_empty() {
+            return Ok(HashMap::from([("payload".to_string(), payload)]));
+        }
+
+        let headers: serde_json::Map<String, Value> = headers
+            .iter()
+            .map(|(name, value)| (name.clone(), Value::String(value.clone())))
+            .collect();
+        let context = ExecutionContext::new(HashMap::from([
+            ("payload".to_string(), payload),
+            ("headers".to_string(), Value::Object(headers)),
+        ]));
+
+        let mut inputs = HashMap::new();
+        for (input, template) in &self.inputs {
+            let value = context
+                .render_value(template)
+                .map_err(|e| WebhookError::InvalidPayload(format!("input '{}': {}", input, e)))?;
+            inputs.insert(input.clone(), value);
+        }
+        Ok(inputs)
+    }
+}
+
+/// Compares the hex `signature` with the HMAC-SHA256 of the concatenated `parts`.
+fn check_mac(key: &[u8], parts: &[&[u8]], signature: &str) -> Result<(), WebhookError> {
+    let expected = hex::decode(signature)
+        .map_err(|_| WebhookError::InvalidSignature("signature is not hex".to_string()))?;
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    for part in parts {
+        mac.update(part);
+    }
+    // Constant-time comparison
+    mac.verify_slice(&expected)
+        .map_err(|_| WebhookError::InvalidSignature("signature does not match".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn trigger(yaml: &str) -> WebhookTrigger {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    fn sign(key: &[u8], message: &[u8]) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(key).unwrap();
+        mac.update(message);
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    #[test]
+    fn test_hmac_signature() {
+        let hook = trigger("name: push\nsecret: hooks/push");
+        let body = br#"{"ref": "main"}"#;
+        let signed = |value: String| HashMap::from([(DEFAULT_SIGNATURE_HEADER.to_string(), value)]);
+
+        assert!(hook.verify(b"key", &signed(format!("sha256={}", sign(b"key", body))), body, 0).is_ok());
+        assert!(hook.verify(b"key", &signed(sign(b"key", body)), body, 0).is_ok());
+        assert!(hook.verify(b"other", &signed(sign(b"key", body)), body, 0).is_err());
+        assert!(hook.verify(b"key", &signed("sha256=zz".to_string()), body, 0).is_err());
+        assert!(hook.verify(b"key", &HashMap::new(), body, 0).is_err());
+
+        let custom = trigger("name: push\nsecret: s\nsignature_header: X-Signature");
+        let headers = HashMap::from([("x-signature".to_string(), sign(b"key", body))]);
+        assert!(custom.verify(b"key", &headers, body, 0).is_ok());
+    }
+
+    #[test]
+    fn test_slack_signature() {
+        let hook = trigger("name: slack\nsecret: slack/signing_secret\nscheme: slack");
+        let body = b"{}";
+        let headers = HashMap::from([
+            (SLACK_TIMESTAMP_HEADER.to_string(), "1000".to_string()),
+            (SLACK_SIGNATURE_HEADER.to_string(), format!("v0={}", sign(b"key", b"v0:1000:{}"))),
+        ]);
+
+        assert!(hook.verify(b"key", &headers, body, 1100).is_ok());
+        // Replayed long after it was signed
+        assert!(hook.verify(b"key", &headers, body, 1000 + SLACK_MAX_AGE_SECS + 1).is_err());
+    }
+
+    #[test]
+    fn test_map_inputs() {
+        let hook = trigger(
+            r#"
+name: issues
+secret: s
+inputs:
+  repository: "{{ payload.repository.name }}"
+  labels: "{{ payload.labels }}"
+  first_label: "{{payload.labels.0}}"
+  summary: "{{ headers.x-event }}: {{ payload.title }}"
+"#,
+        );
+        let payload = json!({"repository": {"name": "core"}, "labels": ["bug", "p1"], "title": "Crash"});
+        let headers = HashMap::from([("x-event".to_string(), "issues".to_string())]);
+
+        let inputs = hook.map_inputs(payload.clone(), &headers).unwrap();
+        assert_eq!(inputs["repository"], "core");
+        assert_eq!(inputs["labels"], json!(["bug", "p1"]));
+        assert_eq!(inputs["first_label"], "bug");
+        assert_eq!(inputs["summary"], "issues: Crash");
+
+        let unmapped = trigger("name: raw\nsecret: s").map_inputs(payload.clone(), &headers).unwrap();
+        assert_eq!(unmapped["payload"], payload);
+    }
+
+    #[test]
+    fn test_validate_name() {
+        assert!(trigger("name: github-push\nsecret: s").validate().is_ok());
+        assert!(trigger("name: a/b\nsecret: s").validate().is_err());
+        assert!(trigger("name: push\nsecret: ''").validate().is_err());
+    }
+}
@@ -0,0 +1,204 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Per-run working directories.
+//!
+//! Each run gets its own directory, `<root>/<run id>`, for the scratch files
+//! of its steps. Templates reach it as `{{ run.workdir }}`, and `exec`
+//! actions without a `cwd` run in it:
+//!
+//! ```yaml
+//! workdir:
+//!   root: /var/lib/orchestrator/runs   # Default: <temp dir>/llm-orchestrator/runs
+//!   retention: keep_failed             # keep_failed (default), keep, or delete
+//!
+//! steps:
+//!   - id: render
+//!     type: action
+//!     action: exec
+//!     command: pandoc
+//!     args: ["-o", "{{ run.workdir }}/report.pdf", "-"]
+//!     stdin: "{{ steps.draft.text }}"
+//!     env: [PATH]
+//! ```
+//!
+//! When the run finishes, the directory is deleted or kept following the
+//! retention policy; by default it is kept only when the run failed, so its
+//! files can be inspected. Files meant to outlive the run belong in the
+//! [artifact store](crate::artifact).
+
+use crate::error::{OrchestratorError, Result};
+use crate::executor::WorkflowExecutor;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::PathBuf;
+use tracing::{debug, info, warn};
+
+/// Whether a run's working directory is kept when the run finishes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkdirRetention {
+    /// Keep the directory of failed, cancelled, or interrupted runs; delete it
+    /// after successful ones.
+    #[default]
+    KeepFailed,
+    /// Always keep the directory.
+    Keep,
+    /// Always delete the directory.
+    Delete,
+}
+
+impl WorkdirRetention {
+    /// Whether a run that finished with `succeeded` keeps its directory.
+    pub fn keeps(self, succeeded: bool) -> bool {
+        match self {
+            Self::KeepFailed => !succeeded,
+            Self::Keep => true,
+            Self::Delete => false,
+        }
+    }
+}
+
+/// Where runs' working directories are created and how long they are kept.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct WorkdirConfig {
+    /// Directory the working directories of runs are created in; by default
+    /// `<temp dir>/llm-orchestrator/runs`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub root: Option<String>,
+
+    /// When a run's directory is deleted.
+    #[serde(default)]
+    pub retention: WorkdirRetention,
+}
+
+impl WorkdirConfig {
+    /// Directory the working directories of runs are created in.
+    pub fn root(&self) -> PathBuf {
+        match &self.root {
+            Some(root) => PathBuf::from(root),
+            None => std::env::temp_dir().join("llm-orchestrator").join("runs"),
+        }
+    }
+}
+
+impl WorkflowExecutor {
+    /// Sets where the run's working directory is created and how long it is
+    /// kept, in place of the workflow's `workdir` section.
+    pub fn with_workdir_config(mut self, config: WorkdirConfig) -> Self {
+        self.workdir_config = Some(config);
+        self
+    }
+
+    /// Working directory settings of the run.
+    fn workdir_settings(&self) -> WorkdirConfig {
+        self.workdir_config
+            .clone()
+            .or_else(|| self.workflow.workdir.clone())
+            .unwrap_or_default()
+    }
+
+    /// The run's working directory, `<root>/<run id>`; it exists while the run executes.
+    pub fn workdir(&self) -> PathBuf {
+        self.workdir_settings().root().join(self.run_id.to_string())
+    }
+
+    /// Creates the run's working directory and exposes it to templates as
+    /// `run.workdir`, along with `run.id`.
+    ///
+    /// A resumed run keeps the files already in the directory.
+    pub(crate) async fn create_workdir(&self) -> Result<()> {
+        let workdir = self.workdir();
+        tokio::fs::create_dir_all(&workdir).await.map_err(|e| {
+            OrchestratorError::other(format!("Failed to create working directory {}: {}", workdir.display(), e))
+        })?;
+        debug!(run_id = %self.run_id, workdir = %workdir.display(), "Created working directory");
+        self.context.set_run_value("id", Value::String(self.run_id.to_string()));
+        self.context.set_run_value("workdir", Value::String(workdir.display().to_string()));
+        Ok(())
+    }
+
+    /// Deletes the run's working directory unless the retention policy keeps it.
+    pub(crate) async fn finish_workdir(&self, succeeded: bool) {
+        let workdir = self.workdir();
+        if self.workdir_settings().retention.keeps(succeeded) {
+            info!(run_id = %self.run_id, workdir = %workdir.display(), "Keeping working directory");
+            return;
+        }
+        match tokio::fs::remove_dir_all(&workdir).await {
+            Ok(()) => debug!(run_id = %self.run_id, workdir = %workdir.display(), "Removed working directory"),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => warn!(run_id = %self.run_id, workdir = %workdir.display(), error = %e, "Failed to remove working directory"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workflow::Workflow;
+    use std::collections::HashMap;
+
+    fn workflow(root: &std::path::Path, retention: &str, exit_code: u8) -> Workflow {
+        Workflow::from_yaml(&format!(
+            r#"
+name: workdir
+workdir:
+  root: "{}"
+  retention: {}
+steps:
+  - id: write
+    type: action
+    action: exec
+    command: sh
+    args: ["-c", "echo draft > notes.txt; cat {{{{ run.workdir }}}}/notes.txt; exit {}"]
+    output: [text]
+"#,
+            root.display(),
+            retention,
+            exit_code
+        ))
+        .unwrap()
+    }
+
+    async fn run(workflow: Workflow) -> (WorkflowExecutor, Result<HashMap<String, crate::executor::StepResult>>) {
+        let executor = WorkflowExecutor::new(workflow, HashMap::new()).unwrap().with_exec_enabled(true);
+        let result = executor.execute().await;
+        (executor, result)
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_workdir_retention() {
+        let root = tempfile::tempdir().unwrap();
+
+        // Commands run in the directory, which a successful run deletes
+        let (executor, result) = run(workflow(root.path(), "keep_failed", 0)).await;
+        assert_eq!(result.unwrap()["write"].outputs["text"], "draft\n");
+        assert!(executor.workdir().starts_with(root.path()));
+        assert!(!executor.workdir().exists());
+
+        // A failed run keeps its files
+        let (executor, result) = run(workflow(root.path(), "keep_failed", 1)).await;
+        assert!(result.is_err());
+        assert_eq!(std::fs::read_to_string(executor.workdir().join("notes.txt")).unwrap(), "draft\n");
+
+        let (executor, result) = run(workflow(root.path(), "keep", 0)).await;
+        assert!(result.is_ok());
+        assert!(executor.workdir().join("notes.txt").exists());
+
+        let (executor, result) = run(workflow(root.path(), "delete", 1)).await;
+        assert!(result.is_err());
+        assert!(!executor.workdir().exists());
+    }
+
+    #[test]
+    fn test_retention_keeps() {
+        assert!(WorkdirRetention::default().keeps(false));
+        assert!(!WorkdirRetention::default().keeps(true));
+        assert!(WorkdirRetention::Keep.keeps(true));
+        assert!(!WorkdirRetention::Delete.keeps(false));
+    }
+}
@@ -0,0 +1,651 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Queue-backed distributed execution (requires the `state-persistence` feature).
+//!
+//! Runs are submitted to a shared [`WorkQueue`] with [`submit_run`] and picked
+//! up by any number of [`Worker`]s. [`submit_run_with`] sets the run's
+//! [`RunPriority`] and tenant: workers claim high-priority runs first and,
+//! within a priority, share capacity fairly between tenants. A worker leases one run at a time, renews
+//! the lease with periodic heartbeats while the run executes, and persists run
+//! state through the state store. If a worker dies, its lease expires and the
+//! run is returned to the queue for another worker, which resumes it from its
+//! latest checkpoint. A worker that finds its lease taken over, e.g. after a
+//! long pause, abandons the run without writing its state again. Cancelling
+//! a task with [`WorkQueue::cancel_task`] stops the run on its next heartbeat.
+//!
+//! [`Worker::shutdown_handle`] stops a worker gracefully: it claims no more
+//! runs and drains the current one. A run interrupted by the drain timeout is
+//! persisted as paused and its lease left to expire, so another worker picks
+//! it up.
+
+use crate::cancel::{AbandonHandle, CancelHandle, ShutdownHandle};
+use crate::error::{OrchestratorError, Result};
+use crate::executor::WorkflowExecutor;
+use crate::workflow::Workflow;
+use llm_orchestrator_state::{QueueTask, QueueTaskStatus, RunPriority, StateStore, WorkQueue};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+/// Payload of a queued workflow run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RunPayload {
+    workflow: Workflow,
+    inputs: HashMap<String, Value>,
+}
+
+/// Namespace of run IDs derived from idempotency keys.
+const IDEMPOTENCY_NAMESPACE: Uuid = Uuid::from_u128(0x6c6c_6d2d_6f72_6368_6573_7472_6174_6f72);
+
+/// How a run is queued.
+#[derive(Debug, Clone, Default)]
+pub struct SubmitOptions {
+    /// Priority class the run is claimed in.
+    pub priority: RunPriority,
+    /// Tenant the run is recorded under.
+    pub tenant_id: Option<String>,
+}
+
+/// Enqueue a workflow run with normal priority. Returns the run ID.
+pub async fn submit_run(
+    queue: &dyn WorkQueue,
+    workflow: &Workflow,
+    inputs: HashMap<String, Value>,
+) -> Result<Uuid> {
+    submit_run_with(queue, workflow, inputs, SubmitOptions::default()).await
+}
+
+/// Enqueue a workflow run with a priority and tenant. Returns the run ID.
+pub async fn submit_run_with(
+    queue: &dyn WorkQueue,
+    workflow: &Workflow,
+    inputs: HashMap<String, Value>,
+    options: SubmitOptions,
+) -> Result<Uuid> {
+    let task = run_task(workflow, inputs, options)?;
+    queue
+        .enqueue_task(&task)
+        .await
+        .map_err(|e| OrchestratorError::other(format!("Failed to enqueue run: {}", e)))?;
+
+    info!(run_id = %task.id, workflow_name = %workflow.name, priority = %task.priority, "Run submitted");
+    Ok(task.id)
+}
+
+/// Enqueue a workflow run at most once per idempotency key.
+///
+/// The run ID is derived from `key`, so submitting the same key again (e.g.
+/// for a redelivered message) returns the existing run instead of queueing
+/// another. Returns the run ID and whether this call queued it.
+pub async fn submit_run_idempotent(
+    queue: &dyn WorkQueue,
+    workflow: &Workflow,
+    inputs: HashMap<String, Value>,
+    key: &str,
+) -> Result<(Uuid, bool)> {
+    let mut task = run_task(workflow, inputs, SubmitOptions::default())?;
+    task.id = Uuid::new_v5(&IDEMPOTENCY_NAMESPACE, key.as_bytes());
+
+    if queue.get_task(&task.id).await.is_ok() {
+        info!(run_id = %task.id, idempotency_key = key, "Run already submitted");
+        return Ok((task.id, false));
+    }
+    if let Err(e) = queue.enqueue_task(&task).await {
+        // Lost a race with another submitter of the same key
+        if queue.get_task(&task.id).await.is_ok() {
+            return Ok((task.id, false));
+        }
+        return Err(OrchestratorError::other(format!("Failed to enqueue run: {}", e)));
+    }
+
+    info!(run_id = %task.id, workflow_name = %workflow.name, idempotency_key = key, "Run submitted");
+    Ok((task.id, true))
+}
+
+/// Queue task running `workflow` with `inputs`.
+fn run_task(workflow: &Workflow, inputs: HashMap<String, Value>, options: SubmitOptions) -> Result<QueueTask> {
+    workflow.validate()?;
+
+    let payload = serde_json::to_value(RunPayload {
+        workflow: workflow.clone(),
+        inputs,
+    })?;
+    let mut task = QueueTask::new(payload).with_priority(options.priority);
+    task.tenant_id = options.tenant_id;
+    Ok(task)
+}
+
+/// Hook used to configure each executor (e.g. register providers).
+type ExecutorSetup = dyn Fn(WorkflowExecutor) -> WorkflowExecutor + Send + Sync;
+
+/// Claims queued runs and executes them.
+pub struct Worker {
+    queue: Arc<dyn WorkQueue>,
+    state_store: Arc<dyn StateStore>,
+    worker_id: String,
+    lease: Duration,
+    poll_interval: Duration,
+    drain_timeout: Duration,
+    shutdown: CancelHandle,
+    setup: Arc<ExecutorSetup>,
+}
+
+impl Worker {
+    /// Create a worker with a random ID, a 30 second lease, a 1 second poll
+    /// interval, and a 30 second drain timeout.
+    pub fn new(queue: Arc<dyn WorkQueue>, state_store: Arc<dyn StateStore>) -> Self {
+        Self {
+            queue,
+            state_store,
+            worker_id: format!("worker-{}", Uuid::new_v4()),
+            lease: Duration::from_secs(30),
+            poll_interval: Duration::from_secs(1),
+            drain_timeout: Duration::from_secs(30),
+            shutdown: CancelHandle::default(),
+            setup: Arc::new(|executor| executor),
+        }
+    }
+
+    /// Set the worker ID recorded on claimed runs.
+    pub fn with_worker_id(mut self, worker_id: impl Into<String>) -> Self {
+        self.worker_id = worker_id.into();
+        self
+    }
+
+    /// Set the lease duration. Heartbeats are sent every third of the lease.
+    pub fn with_lease(mut self, lease: Duration) -> Self {
+        self.lease = lease;
+        self
+    }
+
+    /// Set how long to wait before polling an empty queue again.
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Set how long the steps of the current run may take to finish on shutdown.
+    pub fn with_drain_timeout(mut self, drain_timeout: Duration) -> Self {
+        self.drain_timeout = drain_timeout;
+        self
+    }
+
+    /// Configure each executor before it runs (e.g. register providers).
+    pub fn with_executor_setup(
+        mut self,
+        setup: impl Fn(WorkflowExecutor) -> WorkflowExecutor + Send + Sync + 'static,
+    ) -> Self {
+        self.setup = Arc::new(setup);
+        self
+    }
+
+    /// Returns the worker ID.
+    pub fn worker_id(&self) -> &str {
+        &self.worker_id
+    }
+
+    /// Returns a handle that shuts the worker down.
+    ///
+    /// Cancelling it stops claiming runs and drains the current run, after
+    /// which [`run`](Self::run) returns.
+    pub fn shutdown_handle(&self) -> CancelHandle {
+        self.shutdown.clone()
+    }
+
+    /// Poll the queue and execute runs until shut down.
+    pub async fn run(&self) -> Result<()> {
+        info!(worker_id = %self.worker_id, "Worker started");
+        while !self.shutdown.is_cancelled() {
+            let idle = match self.run_once().await {
+                Ok(Some(_)) => false,
+                Ok(None) => true,
+                Err(e) => {
+                    error!(worker_id = %self.worker_id, error = %e, "Worker iteration failed");
+                    true
+                }
+            };
+            if idle {
+                tokio::select! {
+                    _ = tokio::time::sleep(self.poll_interval) => {}
+                    _ = self.shutdown.cancelled() => {}
+                }
+            }
+        }
+        info!(worker_id = %self.worker_id, "Worker stopped");
+        Ok(())
+    }
+
+    /// Recover expired leases, then claim and execute at most one run.
+    ///
+    /// Returns the ID of the executed run, or `None` if the queue was empty.
+    pub async fn run_once(&self) -> Result<Option<Uuid>> {
+        if self.shutdown.is_cancelled() {
+            return Ok(None);
+        }
+        self.queue
+            .recover_expired_tasks()
+            .await
+            .map_err(|e| OrchestratorError::other(format!("Failed to recover expired runs: {}", e)))?;
+
+        let task = match self
+            .queue
+            .claim_task(&self.worker_id, self.lease)
+            .await
+            .map_err(|e| OrchestratorError::other(format!("Failed to claim run: {}", e)))?
+        {
+            Some(task) => task,
+            None => return Ok(None),
+        };
+
+        info!(worker_id = %self.worker_id, run_id = %task.id, attempt = task.attempts, "Claimed run");
+
+        let outcome = match self.build_executor(&task).await {
+            Ok(executor) => {
+                let abandon = executor.abandon_handle();
+                let heartbeat = self.spawn_heartbeat(task.id, executor.cancel_handle(), abandon.clone());
+                let drain = self.spawn_drain(executor.shutdown_handle());
+                let outcome = executor.execute().await.map(|_| ());
+                heartbeat.abort();
+                drain.abort();
+                if abandon.is_abandoned() {
+                    // Another worker owns the run now; its outcome is theirs to record
+                    warn!(run_id = %task.id, worker_id = %self.worker_id, "Run taken over by another worker");
+                    return Ok(Some(task.id));
+                }
+                outcome
+            }
+            Err(e) => Err(e),
+        };
+
+        let update = match &outcome {
+            Ok(()) => self.queue.complete_task(&task.id, &self.worker_id).await,
+            Err(OrchestratorError::Cancelled) => {
+                // The executor persisted the run as cancelled; the task already is
+                info!(run_id = %task.id, "Run cancelled");
+                return Ok(Some(task.id));
+            }
+            Err(OrchestratorError::Interrupted(unfinished)) => {
+                // Once the lease expires the run is queued again
+                warn!(run_id = %task.id, unfinished = unfinished.len(), "Run interrupted by shutdown");
+                return Ok(Some(task.id));
+            }
+            Err(e) => self.queue.fail_task(&task.id, &self.worker_id, &e.to_string()).await,
+        };
+        if let Err(e) = update {
+            warn!(run_id = %task.id, error = %e, "Failed to record run outcome");
+        }
+
+        outcome.map(|_| Some(task.id))
+    }
+
+    /// Create the executor for the workflow carried by a task, resuming
+    /// earlier attempts of the run from their latest checkpoint.
+    async fn build_executor(&self, task: &QueueTask) -> Result<WorkflowExecutor> {
+        let payload: RunPayload = serde_json::from_value(task.payload.clone())?;
+
+        let mut executor = WorkflowExecutor::new(payload.workflow, payload.inputs)?
+            .with_run_id(task.id)
+            .with_state_store(self.state_store.clone());
+        if let Some(tenant_id) = &task.tenant_id {
+            executor = executor.with_tenant_id(tenant_id);
+        }
+        (self.setup)(executor).resume_from_latest_checkpoint().await
+    }
+
+    /// Drain the current run once the worker shuts down, until aborted.
+    fn spawn_drain(&self, run: ShutdownHandle) -> tokio::task::JoinHandle<()> {
+        let shutdown = self.shutdown.clone();
+        let drain_timeout = self.drain_timeout;
+
+        tokio::spawn(async move {
+            shutdown.cancelled().await;
+            run.shutdown(drain_timeout).await;
+        })
+    }
+
+    /// Renew the lease on a task until aborted.
+    ///
+    /// Cancels the run if the task was cancelled, and abandons it if the
+    /// lease passed to another worker, so this worker no longer persists its
+    /// state over theirs.
+    fn spawn_heartbeat(&self, task_id: Uuid, cancel: CancelHandle, abandon: AbandonHandle) -> tokio::task::JoinHandle<()> {
+        let queue = self.queue.clone();
+        let worker_id = self.worker_id.clone();
+        let lease = self.lease;
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(lease / 3);
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                match queue.heartbeat_task(&task_id, &worker_id, lease).await {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        let cancelled = matches!(
+                            queue.get_task(&task_id).await,
+                            Ok(task) if task.status == QueueTaskStatus::Cancelled
+                        );
+                        if cancelled {
+                            info!(run_id = %task_id, worker_id = %worker_id, "Run cancelled, stopping it");
+                            cancel.cancel();
+                        } else {
+                            warn!(run_id = %task_id, worker_id = %worker_id, "Lost lease on run, abandoning it");
+                            abandon.abandon();
+                        }
+                        return;
+                    }
+                    Err(e) => warn!(run_id = %task_id, error = %e, "Heartbeat failed"),
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::{CompletionRequest, CompletionResponse, LLMProvider, ProviderError};
+    use llm_orchestrator_state::{SqliteStateStore, StateStoreResult, StepStatus, WorkflowStatus};
+
+    const WORKFLOW: &str = r#"
+name: "queued-workflow"
+steps:
+  - id: "prepare"
+    type: "transform"
+    function: "noop"
+    inputs: []
+  - id: "finish"
+    type: "transform"
+    depends_on: ["prepare"]
+    function: "noop"
+    inputs: []
+"#;
+
+    #[tokio::test]
+    async fn test_idempotent_submission() {
+        let store = Arc::new(SqliteStateStore::new(":memory:").await.unwrap());
+        let workflow = Workflow::from_yaml(WORKFLOW).unwrap();
+
+        let (run_id, queued) = submit_run_idempotent(store.as_ref(), &workflow, HashMap::new(), "orders/0/42")
+            .await
+            .unwrap();
+        assert!(queued);
+        let (again, queued) = submit_run_idempotent(store.as_ref(), &workflow, HashMap::new(), "orders/0/42")
+            .await
+            .unwrap();
+        assert_eq!((again, queued), (run_id, false));
+
+        let (other, queued) = submit_run_idempotent(store.as_ref(), &workflow, HashMap::new(), "orders/0/43")
+            .await
+            .unwrap();
+        assert!(queued);
+        assert_ne!(other, run_id);
+    }
+
+    #[tokio::test]
+    async fn test_worker_executes_submitted_run() {
+        let store = Arc::new(SqliteStateStore::new(":memory:").await.unwrap());
+        let workflow = Workflow::from_yaml(WORKFLOW).unwrap();
+
+        let run_id = submit_run(store.as_ref(), &workflow, HashMap::new()).await.unwrap();
+
+        let worker = Worker::new(store.clone(), store.clone()).with_worker_id("test-worker");
+        assert_eq!(worker.run_once().await.unwrap(), Some(run_id));
+        assert_eq!(worker.run_once().await.unwrap(), None);
+
+        let task = store.get_task(&run_id).await.unwrap();
+        assert_eq!(task.status, QueueTaskStatus::Completed);
+        assert_eq!(task.worker_id.as_deref(), Some("test-worker"));
+
+        // The run was persisted under the queued run ID
+        let state = store.load_workflow_state(&run_id).await.unwrap();
+        assert_eq!(state.status, WorkflowStatus::Completed);
+        assert_eq!(state.steps.len(), 2);
+        assert_eq!(state.steps["finish"].status, StepStatus::Completed);
+    }
+
+    #[tokio::test]
+    async fn test_worker_claims_by_priority_and_records_tenant() {
+        let store = Arc::new(SqliteStateStore::new(":memory:").await.unwrap());
+        let workflow = Workflow::from_yaml(WORKFLOW).unwrap();
+
+        let batch = SubmitOptions {
+            priority: RunPriority::Low,
+            tenant_id: Some("acme".to_string()),
+        };
+        let low = submit_run_with(store.as_ref(), &workflow, HashMap::new(), batch).await.unwrap();
+        let normal = submit_run(store.as_ref(), &workflow, HashMap::new()).await.unwrap();
+
+        let worker = Worker::new(store.clone(), store.clone());
+        assert_eq!(worker.run_once().await.unwrap(), Some(normal));
+        assert_eq!(worker.run_once().await.unwrap(), Some(low));
+
+        let state = store.load_workflow_state(&low).await.unwrap();
+        assert_eq!(state.tenant_id.as_deref(), Some("acme"));
+        assert_eq!(store.load_workflow_state(&normal).await.unwrap().tenant_id, None);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_task_stops_running_workflow() {
+        let store = Arc::new(SqliteStateStore::new(":memory:").await.unwrap());
+        // The approval step blocks until the run is cancelled
+        let workflow = Workflow::from_yaml(
+            r#"
+name: "blocked-workflow"
+steps:
+  - id: "review"
+    type: "approval"
+    payload: "draft"
+"#,
+        )
+        .unwrap();
+        let run_id = submit_run(store.as_ref(), &workflow, HashMap::new()).await.unwrap();
+
+        let worker = Worker::new(store.clone(), store.clone()).with_lease(Duration::from_millis(30));
+        let handle = tokio::spawn(async move { worker.run_once().await });
+
+        while store.get_task(&run_id).await.unwrap().status != QueueTaskStatus::Running {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        assert!(store.cancel_task(&run_id).await.unwrap());
+
+        assert_eq!(handle.await.unwrap().unwrap(), Some(run_id));
+        assert_eq!(store.get_task(&run_id).await.unwrap().status, QueueTaskStatus::Cancelled);
+
+        let state = store.load_workflow_state(&run_id).await.unwrap();
+        assert_eq!(state.status, WorkflowStatus::Cancelled);
+        assert_eq!(state.steps["review"].status, StepStatus::Cancelled);
+    }
+
+    /// Queue whose heartbeats do not renew leases until resumed, like a worker
+    /// paused long enough for its lease to expire.
+    struct StalledHeartbeats {
+        store: Arc<SqliteStateStore>,
+        stalled: std::sync::atomic::AtomicBool,
+    }
+
+    #[async_trait::async_trait]
+    impl WorkQueue for StalledHeartbeats {
+        async fn enqueue_task(&self, task: &QueueTask) -> StateStoreResult<()> {
+            self.store.enqueue_task(task).await
+        }
+
+        async fn claim_task(&self, worker_id: &str, lease: Duration) -> StateStoreResult<Option<QueueTask>> {
+            self.store.claim_task(worker_id, lease).await
+        }
+
+        async fn heartbeat_task(&self, task_id: &Uuid, worker_id: &str, lease: Duration) -> StateStoreResult<bool> {
+            if self.stalled.load(std::sync::atomic::Ordering::SeqCst) {
+                return Ok(true);
+            }
+            self.store.heartbeat_task(task_id, worker_id, lease).await
+        }
+
+        async fn complete_task(&self, task_id: &Uuid, worker_id: &str) -> StateStoreResult<()> {
+            self.store.complete_task(task_id, worker_id).await
+        }
+
+        async fn fail_task(&self, task_id: &Uuid, worker_id: &str, error: &str) -> StateStoreResult<()> {
+            self.store.fail_task(task_id, worker_id, error).await
+        }
+
+        async fn cancel_task(&self, task_id: &Uuid) -> StateStoreResult<bool> {
+            self.store.cancel_task(task_id).await
+        }
+
+        async fn recover_expired_tasks(&self) -> StateStoreResult<u64> {
+            self.store.recover_expired_tasks().await
+        }
+
+        async fn get_task(&self, task_id: &Uuid) -> StateStoreResult<QueueTask> {
+            self.store.get_task(task_id).await
+        }
+
+        async fn count_tasks(&self, status: QueueTaskStatus) -> StateStoreResult<u64> {
+            self.store.count_tasks(status).await
+        }
+    }
+
+    /// Answers once `release` is notified, or right away without one.
+    struct GatedProvider {
+        release: Option<Arc<tokio::sync::Notify>>,
+    }
+
+    #[async_trait::async_trait]
+    impl LLMProvider for GatedProvider {
+        async fn complete(&self, request: CompletionRequest) -> std::result::Result<CompletionResponse, ProviderError> {
+            if let Some(release) = &self.release {
+                release.notified().await;
+            }
+            Ok(CompletionResponse {
+                text: "done".to_string(),
+                model: request.model,
+                tokens_used: None,
+                usage: None,
+                tool_calls: Vec::new(),
+                metadata: HashMap::new(),
+            })
+        }
+
+        fn name(&self) -> &str {
+            "gated"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_worker_that_lost_its_lease_leaves_the_new_owners_state_alone() {
+        let store = Arc::new(SqliteStateStore::new(":memory:").await.unwrap());
+        let workflow = Workflow::from_yaml(
+            r#"
+name: "taken-over"
+steps:
+  - id: "answer"
+    type: "llm"
+    provider: "gated"
+    model: "m"
+    prompt: "hi"
+    output: ["text"]
+"#,
+        )
+        .unwrap();
+        let run_id = submit_run(store.as_ref(), &workflow, HashMap::new()).await.unwrap();
+
+        // Worker A stalls mid-step without renewing its lease
+        let queue = Arc::new(StalledHeartbeats {
+            store: store.clone(),
+            stalled: std::sync::atomic::AtomicBool::new(true),
+        });
+        let release = Arc::new(tokio::sync::Notify::new());
+        let gate = release.clone();
+        let worker_a = Worker::new(queue.clone(), store.clone())
+            .with_worker_id("a")
+            .with_lease(Duration::from_millis(30))
+            .with_executor_setup(move |executor| {
+                executor.with_provider("gated", Arc::new(GatedProvider { release: Some(gate.clone()) }))
+            });
+        let a = tokio::spawn(async move { worker_a.run_once().await });
+        while store.get_task(&run_id).await.unwrap().status != QueueTaskStatus::Running {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        // Worker B recovers the expired lease and finishes the run
+        let worker_b = Worker::new(store.clone(), store.clone())
+            .with_worker_id("b")
+            .with_executor_setup(|executor| executor.with_provider("gated", Arc::new(GatedProvider { release: None })));
+        assert_eq!(worker_b.run_once().await.unwrap(), Some(run_id));
+        let finished = store.load_workflow_state(&run_id).await.unwrap();
+        assert_eq!(finished.status, WorkflowStatus::Completed);
+
+        // A resumes, finds its lease gone, and stops without writing its state
+        queue.stalled.store(false, std::sync::atomic::Ordering::SeqCst);
+        assert_eq!(
+            tokio::time::timeout(Duration::from_secs(5), a).await.unwrap().unwrap().unwrap(),
+            Some(run_id)
+        );
+
+        let state = store.load_workflow_state(&run_id).await.unwrap();
+        assert_eq!(state.status, WorkflowStatus::Completed);
+        assert_eq!(state.steps["answer"].status, StepStatus::Completed);
+        assert_eq!(state.updated_at, finished.updated_at);
+        let task = store.get_task(&run_id).await.unwrap();
+        assert_eq!(task.status, QueueTaskStatus::Completed);
+        assert_eq!(task.worker_id.as_deref(), Some("b"));
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_interrupts_run_after_drain_timeout() {
+        let store = Arc::new(SqliteStateStore::new(":memory:").await.unwrap());
+        let workflow = Workflow::from_yaml(
+            r#"
+name: "blocked-workflow"
+steps:
+  - id: "review"
+    type: "approval"
+    payload: "draft"
+  - id: "publish"
+    type: "transform"
+    depends_on: ["review"]
+    function: "noop"
+    inputs: []
+"#,
+        )
+        .unwrap();
+        let run_id = submit_run(store.as_ref(), &workflow, HashMap::new()).await.unwrap();
+
+        let worker = Worker::new(store.clone(), store.clone()).with_drain_timeout(Duration::from_millis(20));
+        let shutdown = worker.shutdown_handle();
+        let handle = tokio::spawn(async move { worker.run().await });
+
+        while store.get_task(&run_id).await.unwrap().status != QueueTaskStatus::Running {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        shutdown.cancel();
+        tokio::time::timeout(Duration::from_secs(5), handle).await.unwrap().unwrap().unwrap();
+
+        // The lease is left to expire so the run is queued again
+        assert_eq!(store.get_task(&run_id).await.unwrap().status, QueueTaskStatus::Running);
+        let state = store.load_workflow_state(&run_id).await.unwrap();
+        assert_eq!(state.status, WorkflowStatus::Paused);
+        assert!(store.get_latest_checkpoint(&run_id).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_worker_fails_invalid_payload() {
+        let store = Arc::new(SqliteStateStore::new(":memory:").await.unwrap());
+        let task = QueueTask::new(serde_json::json!({"not": "a run"}));
+        store.enqueue_task(&task).await.unwrap();
+
+        let worker = Worker::new(store.clone(), store.clone());
+        assert!(worker.run_once().await.is_err());
+
+        let task = store.get_task(&task.id).await.unwrap();
+        assert_eq!(task.status, QueueTaskStatus::Failed);
+        assert!(task.error.is_some());
+    }
+}
@@ -3,15 +3,30 @@
 
 //! Workflow definition types.
 
+use crate::diagnostics::{did_you_mean, Diagnostic, DiagnosticCode};
+use crate::guardrails::GuardrailConfig;
+use crate::gateway::ChatTrigger;
+use crate::messaging::TopicTrigger;
+use crate::providers::{HybridFusion, SearchMode};
+use crate::include::{self, FileResolver, IncludeResolver};
+use crate::partials;
+use crate::step_template::{self, StepTemplate, TemplateInstance};
+use crate::artifact::ArtifactStoreConfig;
+use crate::result_sink::OutputsConfig;
+use crate::workdir::WorkdirConfig;
+use crate::webhook::WebhookTrigger;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use uuid::Uuid;
 
 /// A complete workflow definition.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Workflow {
     /// Unique workflow identifier.
     #[serde(default = "Uuid::new_v4")]
+    // A generated ID is no default worth suggesting
+    #[schemars(skip_serializing_if = "is_generated")]
     pub id: Uuid,
 
     /// Workflow name.
@@ -35,14 +50,86 @@ pub struct Workflow {
     /// Workflow metadata.
     #[serde(default)]
     pub metadata: HashMap<String, serde_json::Value>,
+
+    /// Named configuration overlays (e.g. `dev`, `prod`).
+    ///
+    /// Each profile is deep-merged onto the workflow by [`Workflow::with_profile`].
+    /// Its `steps` entry maps step IDs to overrides for those steps.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub profiles: HashMap<String, serde_json::Value>,
+
+    /// Guardrails applied to every step without its own.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub guardrails: Option<GuardrailConfig>,
+
+    /// Limits on the retries of all steps of a run combined.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_budget: Option<RetryBudgetConfig>,
+
+    /// Events that start runs of the workflow.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub triggers: Vec<Trigger>,
+
+    /// Where the final outputs of runs are written.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub outputs: Option<OutputsConfig>,
+
+    /// Where files produced by steps are stored.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub artifacts: Option<ArtifactStoreConfig>,
+
+    /// Where each run's working directory is created and how long it is kept.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workdir: Option<WorkdirConfig>,
+
+    /// Handlebars templates any template of the workflow renders with
+    /// `{{> name }}`, by name (see [`crate::partials`]).
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub partials: HashMap<String, String>,
+}
+
+/// Steps nested in a parallel or branch step, at any depth, with their paths
+/// under `path`, the path of `step`.
+fn nested_steps(step: &Step, path: String) -> Vec<(String, &Step)> {
+    let children: Vec<(String, &Step)> = match &step.config {
+        StepConfig::Parallel(config) => config
+            .tasks
+            .iter()
+            .enumerate()
+            .map(|(k, task)| (format!("{}.tasks[{}]", path, k), task))
+            .collect(),
+        StepConfig::Branch(config) => {
+            let mut branches: Vec<_> = config.branches.iter().collect();
+            branches.sort_by_key(|(value, _)| value.as_str());
+            branches
+                .into_iter()
+                .flat_map(|(value, steps)| {
+                    let path = &path;
+                    steps.iter().enumerate().map(move |(k, nested)| (format!("{}.branches.{}[{}]", path, value, k), nested))
+                })
+                .collect()
+        }
+        _ => Vec::new(),
+    };
+    children
+        .into_iter()
+        .flat_map(|(path, nested)| {
+            let deeper = nested_steps(nested, path.clone());
+            std::iter::once((path, nested)).chain(deeper)
+        })
+        .collect()
 }
 
 fn default_version() -> String {
     "1.0".to_string()
 }
 
+fn is_generated(_: &Uuid) -> bool {
+    true
+}
+
 /// A single step in a workflow.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Step {
     /// Unique step identifier within the workflow.
     pub id: String,
@@ -55,6 +142,22 @@ pub struct Step {
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub depends_on: Vec<String>,
 
+    /// Alternative steps, of which the first to complete starts this step.
+    ///
+    /// Templates read that step's outputs as `joined.<this step's id>`. The
+    /// step is skipped if none of them completes.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on_any: Vec<String>,
+
+    /// Values the step's templates read by name, e.g. `doc: steps.fetch.body`
+    /// makes `{{ doc }}` the `body` output of step `fetch`.
+    ///
+    /// Values are paths into the template data: `steps.<id>.<output>`,
+    /// `inputs.<name>`, `joined.<id>`, or `run.<fact>`. The step fails before
+    /// doing any work when one has no value.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub bind: HashMap<String, String>,
+
     /// Conditional execution (template expression).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub condition: Option<String>,
@@ -71,13 +174,103 @@ pub struct Step {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub timeout_seconds: Option<u64>,
 
+    /// What to do when the step fails after exhausting its retries.
+    ///
+    /// Without a policy, every step downstream is skipped and the run returns
+    /// [`OrchestratorError::StepsFailed`](crate::OrchestratorError::StepsFailed)
+    /// once the remaining steps finish.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_failure: Option<FailurePolicy>,
+
     /// Retry configuration for this step.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub retry: Option<RetryConfig>,
+
+    /// Guardrails for this step, replacing the workflow's.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub guardrails: Option<GuardrailConfig>,
+
+    /// Resource class limiting how many such steps run at once, replacing the
+    /// default for the step type.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_class: Option<ResourceClass>,
+
+    /// Expressions that must hold for the step's outputs, e.g.
+    /// `results | length > 0`; the step fails when one does not.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub assert: Vec<String>,
+
+    /// Run even when a dependency was skipped by its condition.
+    ///
+    /// By default such a step is skipped too, as are the steps after it, since
+    /// the outputs it would read do not exist.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub allow_skipped_deps: bool,
+}
+
+impl Step {
+    /// Resource class this step is throttled by.
+    ///
+    /// Defaults by step type; parallel, branch, and approval steps only
+    /// coordinate other work and have no class unless one is set.
+    pub fn resource_class(&self) -> Option<ResourceClass> {
+        self.resource_class.or(match self.step_type {
+            StepType::Llm | StepType::Evaluate | StepType::Agent | StepType::Transcribe | StepType::Speak => Some(ResourceClass::Llm),
+            StepType::Embed => Some(ResourceClass::Embed),
+            StepType::VectorSearch | StepType::VectorUpsert | StepType::VectorFetch | StepType::Action | StepType::Http | StepType::McpTool | StepType::WebFetch => Some(ResourceClass::Io),
+            StepType::Transform => Some(ResourceClass::Cpu),
+            StepType::Parallel | StepType::Branch | StepType::Approval => None,
+        })
+    }
+}
+
+/// Kind of resource a step mostly consumes.
+///
+/// Each class can be given its own concurrency limit on the executor, so
+/// expensive LLM calls are throttled without holding back cheap transforms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ResourceClass {
+    /// LLM completions, including evaluation judges.
+    Llm,
+
+    /// Embedding generation.
+    Embed,
+
+    /// Vector searches and other calls to external services.
+    Io,
+
+    /// Local computation such as transforms.
+    Cpu,
+}
+
+impl std::fmt::Display for ResourceClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Llm => "llm",
+            Self::Embed => "embed",
+            Self::Io => "io",
+            Self::Cpu => "cpu",
+        })
+    }
+}
+
+impl std::str::FromStr for ResourceClass {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "llm" => Ok(Self::Llm),
+            "embed" => Ok(Self::Embed),
+            "io" => Ok(Self::Io),
+            "cpu" => Ok(Self::Cpu),
+            other => Err(format!("Unknown resource class '{}' (expected llm, embed, io, or cpu)", other)),
+        }
+    }
 }
 
 /// Step type enumeration.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum StepType {
     /// LLM completion step.
@@ -89,6 +282,12 @@ pub enum StepType {
     /// Vector database search.
     VectorSearch,
 
+    /// Batched write of records to a vector database.
+    VectorUpsert,
+
+    /// Lookup of vector database records by ID.
+    VectorFetch,
+
     /// Data transformation step.
     Transform,
 
@@ -100,12 +299,61 @@ pub enum StepType {
 
     /// Conditional branch.
     Branch,
+
+    /// Human approval gate.
+    Approval,
+
+    /// Output scoring against metrics or an LLM judge.
+    Evaluate,
+
+    /// HTTP request to an external service.
+    Http,
+
+    /// Tool call on an MCP server.
+    McpTool,
+
+    /// Model that calls tools in a loop until it has an answer.
+    Agent,
+
+    /// Retrieval of web pages.
+    WebFetch,
+
+    /// Speech-to-text transcription of an audio file.
+    Transcribe,
+
+    /// Text-to-speech synthesis to an audio file.
+    Speak,
 }
 
+/// Schema definition of the configuration of each step type.
+const STEP_CONFIG_SCHEMAS: [(&str, &str); 17] = [
+    ("llm", "LlmStepConfig"),
+    ("embed", "EmbedStepConfig"),
+    ("vector_search", "VectorSearchConfig"),
+    ("vector_upsert", "VectorUpsertConfig"),
+    ("vector_fetch", "VectorFetchConfig"),
+    ("transform", "TransformConfig"),
+    ("action", "ActionConfig"),
+    ("parallel", "ParallelConfig"),
+    ("branch", "BranchConfig"),
+    ("approval", "ApprovalConfig"),
+    ("evaluate", "EvaluateConfig"),
+    ("http", "HttpConfig"),
+    ("mcp_tool", "McpToolConfig"),
+    ("agent", "AgentStepConfig"),
+    ("web_fetch", "WebFetchConfig"),
+    ("transcribe", "TranscribeConfig"),
+    ("speak", "SpeakConfig"),
+];
+
 /// Step configuration.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(untagged)]
 pub enum StepConfig {
+    /// Agent step configuration; before LLM, which it would also match
+    /// without its `tools`.
+    Agent(AgentStepConfig),
+
     /// LLM step configuration.
     Llm(LlmStepConfig),
 
@@ -115,6 +363,12 @@ pub enum StepConfig {
     /// Vector search configuration.
     VectorSearch(VectorSearchConfig),
 
+    /// Vector upsert configuration.
+    VectorUpsert(VectorUpsertConfig),
+
+    /// Vector fetch configuration.
+    VectorFetch(VectorFetchConfig),
+
     /// Transform configuration.
     Transform(TransformConfig),
 
@@ -126,10 +380,34 @@ pub enum StepConfig {
 
     /// Branch configuration.
     Branch(BranchConfig),
+
+    /// Approval configuration.
+    Approval(ApprovalConfig),
+
+    /// Evaluation configuration.
+    Evaluate(EvaluateConfig),
+
+    /// HTTP request configuration.
+    Http(HttpConfig),
+
+    /// MCP tool call configuration.
+    McpTool(McpToolConfig),
+
+    /// Web fetch configuration.
+    WebFetch(WebFetchConfig),
+
+    /// Transcription configuration.
+    Transcribe(TranscribeConfig),
+
+    /// Speech synthesis configuration.
+    Speak(SpeakConfig),
 }
 
 /// LLM step configuration.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Needs either an inline `prompt` or a `prompt_ref` to the prompt registry.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(try_from = "LlmStepConfigRepr")]
 pub struct LlmStepConfig {
     /// LLM provider (openai, anthropic, cohere, etc.).
     pub provider: String,
@@ -138,8 +416,18 @@ pub struct LlmStepConfig {
     pub model: String,
 
     /// Prompt template (supports Handlebars syntax).
+    ///
+    /// Empty when the prompt comes from `prompt_ref`.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    #[schemars(default)]
     pub prompt: String,
 
+    /// Registry prompt used instead of `prompt`: `name`, `name@latest`, or `name@v3`.
+    ///
+    /// A registry prompt's system prompt applies when `system` is unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt_ref: Option<String>,
+
     /// Temperature parameter (0.0 - 2.0).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub temperature: Option<f32>,
@@ -156,13 +444,261 @@ pub struct LlmStepConfig {
     #[serde(default)]
     pub stream: bool,
 
+    /// Alternative provider/model/prompt combinations to experiment with.
+    ///
+    /// Each run uses one variant, picked by weight, unless `variant_mode`
+    /// is `compare`. Fields a variant leaves out come from this step.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub variants: Vec<LlmVariant>,
+
+    /// How the variants are run.
+    #[serde(default)]
+    pub variant_mode: VariantMode,
+
+    /// Send a second request when the first is slow, keeping the first response.
+    ///
+    /// Only enable this for calls that are safe to issue twice.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hedge: Option<HedgeConfig>,
+
+    /// Session memory holding the conversation the prompt continues.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory: Option<LlmMemoryConfig>,
+
+    /// Images sent with the prompt, for models that accept them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub images: Vec<ImageAttachment>,
+
     /// Additional provider-specific parameters.
     #[serde(flatten)]
     pub extra: HashMap<String, serde_json::Value>,
 }
 
+/// Deserialized form of [`LlmStepConfig`].
+///
+/// Rejecting configs without a prompt keeps other step configs with a
+/// `provider` and `model` (such as embed steps) from matching as LLM steps.
+#[derive(Deserialize)]
+struct LlmStepConfigRepr {
+    provider: String,
+    model: String,
+    prompt: Option<String>,
+    prompt_ref: Option<String>,
+    temperature: Option<f32>,
+    max_tokens: Option<u32>,
+    system: Option<String>,
+    #[serde(default)]
+    stream: bool,
+    #[serde(default)]
+    variants: Vec<LlmVariant>,
+    #[serde(default)]
+    variant_mode: VariantMode,
+    hedge: Option<HedgeConfig>,
+    memory: Option<LlmMemoryConfig>,
+    #[serde(default)]
+    images: Vec<ImageAttachment>,
+    #[serde(flatten)]
+    extra: HashMap<String, serde_json::Value>,
+}
+
+impl TryFrom<LlmStepConfigRepr> for LlmStepConfig {
+    type Error = String;
+
+    fn try_from(repr: LlmStepConfigRepr) -> Result<Self, Self::Error> {
+        if repr.prompt.is_none() && repr.prompt_ref.is_none() {
+            return Err("LLM step needs a prompt or prompt_ref".to_string());
+        }
+        Ok(Self {
+            provider: repr.provider,
+            model: repr.model,
+            prompt: repr.prompt.unwrap_or_default(),
+            prompt_ref: repr.prompt_ref,
+            temperature: repr.temperature,
+            max_tokens: repr.max_tokens,
+            system: repr.system,
+            stream: repr.stream,
+            variants: repr.variants,
+            variant_mode: repr.variant_mode,
+            hedge: repr.hedge,
+            memory: repr.memory,
+            images: repr.images,
+            extra: repr.extra,
+        })
+    }
+}
+
+/// An image sent with an LLM step's prompt.
+///
+/// Paths, URLs, and data support Handlebars syntax, so images can come from
+/// inputs or earlier steps.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum ImageAttachment {
+    /// An image file, read when the step runs.
+    Path {
+        /// File path.
+        path: String,
+
+        /// Media type, detected from the file's bytes when unset.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        media_type: Option<String>,
+    },
+
+    /// An image the provider downloads, or a `data:` URL.
+    Url {
+        /// `http`, `https`, or `data:` URL.
+        url: String,
+    },
+
+    /// Base64-encoded image bytes.
+    Data {
+        /// Base64 data, usually a template such as `{{ steps.capture.image }}`.
+        data: String,
+
+        /// Media type, detected from the bytes when unset.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        media_type: Option<String>,
+    },
+}
+
+impl ImageAttachment {
+    /// The path, URL, or data template.
+    pub fn source(&self) -> &str {
+        match self {
+            Self::Path { path, .. } => path,
+            Self::Url { url } => url,
+            Self::Data { data, .. } => data,
+        }
+    }
+}
+
+/// One arm of an LLM step experiment.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct LlmVariant {
+    /// Variant identifier, recorded with the step's results.
+    pub id: String,
+
+    /// Share of runs using this variant, relative to the other variants.
+    #[serde(default = "default_weight")]
+    pub weight: f64,
+
+    /// LLM provider override.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+
+    /// Model override.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+
+    /// Prompt template override.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt: Option<String>,
+
+    /// Registry prompt override, e.g. to compare two versions of a prompt.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt_ref: Option<String>,
+
+    /// System prompt override.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system: Option<String>,
+
+    /// Temperature override.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+
+    /// Maximum tokens override.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+}
+
+/// How an LLM step runs its variants.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VariantMode {
+    /// Run one variant per run, picked by weight.
+    #[default]
+    Split,
+
+    /// Run every variant; the weighted pick supplies the step's outputs.
+    Compare,
+}
+
+/// Hedged requests of an LLM step.
+///
+/// When the provider has not answered after the given latency percentile of
+/// recent calls to the same provider and model, a second identical request is
+/// sent and whichever succeeds first is used.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct HedgeConfig {
+    /// Latency percentile (0-100] after which the second request is sent.
+    #[serde(default = "default_hedge_percentile")]
+    pub percentile: f64,
+
+    /// Delay in milliseconds used until enough latencies have been recorded.
+    ///
+    /// Without it, requests are not hedged until then.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub initial_delay_ms: Option<u64>,
+}
+
+fn default_hedge_percentile() -> f64 {
+    95.0
+}
+
+/// Conversation history of an LLM step, kept in session memory.
+///
+/// The turns stored under `key` are sent before the prompt. Unless `record`
+/// is off, the prompt and the reply are appended once the step succeeds.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct LlmMemoryConfig {
+    /// Session ID template, e.g. `{{ inputs.session_id }}`.
+    pub session: String,
+
+    /// Memory key holding the conversation.
+    #[serde(default = "default_memory_key")]
+    pub key: String,
+
+    /// Send only this many of the most recent turns.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last: Option<usize>,
+
+    /// Append the prompt and the reply to the conversation.
+    #[serde(default = "default_true")]
+    pub record: bool,
+
+    /// Summarize older turns once the conversation outgrows a token budget.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compact: Option<MemoryCompaction>,
+}
+
+fn default_memory_key() -> String {
+    "conversation".to_string()
+}
+
+/// Compaction of a conversation kept in session memory.
+///
+/// Once the stored conversation is estimated at more than `max_tokens`, all
+/// but the `keep_last` most recent turns are passed to `workflow`, and the
+/// first output of its last step replaces them as a summary.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MemoryCompaction {
+    /// Token budget of the conversation, estimated at four characters a token.
+    pub max_tokens: usize,
+
+    /// Most recent turns kept as they are.
+    #[serde(default = "default_keep_last")]
+    pub keep_last: usize,
+
+    /// Summarization workflow, run with the `turns` to summarize and their `transcript`.
+    pub workflow: Box<Workflow>,
+}
+
+fn default_keep_last() -> usize {
+    6
+}
+
 /// Embedding step configuration.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct EmbedStepConfig {
     /// Embedding provider.
     pub provider: String,
@@ -182,8 +718,15 @@ pub struct EmbedStepConfig {
     pub batch_size: Option<usize>,
 }
 
+impl EmbedStepConfig {
+    /// Length of the vectors this step produces, when known up front.
+    pub fn expected_dimensions(&self) -> Option<usize> {
+        self.dimensions.or_else(|| crate::providers::embedding_dimensions(&self.model))
+    }
+}
+
 /// Vector database search configuration.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct VectorSearchConfig {
     /// Vector database provider (pinecone, weaviate, etc.).
     pub database: String,
@@ -191,7 +734,7 @@ pub struct VectorSearchConfig {
     /// Index/collection name.
     pub index: String,
 
-    /// Query embedding (from previous step).
+    /// Query embedding (from previous step), or the search text in keyword mode.
     pub query: String,
 
     /// Number of results to return.
@@ -213,18 +756,113 @@ pub struct VectorSearchConfig {
     /// Include vector embeddings in results.
     #[serde(default)]
     pub include_vectors: bool,
+
+    /// Length of the index's vectors, checked against the query embedding
+    /// before the run starts and against each query vector.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dimensions: Option<usize>,
+
+    /// How documents are matched: `vector` (default), `keyword` (BM25 on the
+    /// `query` text), or `hybrid` (the `query` vector fused with `text`).
+    #[serde(default, skip_serializing_if = "is_vector_mode")]
+    #[schemars(with = "Option<String>")]
+    pub mode: SearchMode,
+
+    /// Keyword query text for hybrid mode.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+
+    /// Weight of vector against keyword scores in hybrid mode, from 0.0
+    /// (keyword only) to 1.0 (vector only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alpha: Option<f32>,
+
+    /// How hybrid mode merges vector and keyword results: `ranked` or
+    /// `relative_score`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schemars(with = "Option<String>")]
+    pub fusion: Option<HybridFusion>,
+}
+
+fn is_vector_mode(mode: &SearchMode) -> bool {
+    *mode == SearchMode::Vector
 }
 
 fn default_top_k() -> usize {
     5
 }
 
+/// Vector database upsert configuration.
+///
+/// Writes `records` in batches of `batch_size`, lowered to the database's own
+/// limit, with up to `concurrency` batches in flight. Outputs the number of
+/// vectors upserted and the batches the database rejected, with their record
+/// IDs and errors, in that order. The step only fails when every batch fails.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct VectorUpsertConfig {
+    /// Vector database provider (pinecone, weaviate, etc.).
+    pub database: String,
+
+    /// Index/collection name.
+    pub index: String,
+
+    /// Records to write: a list of `{id, vector, metadata}` objects whose
+    /// strings support Handlebars syntax, or a template yielding such a list.
+    pub records: serde_json::Value,
+
+    /// Namespace/partition to write to (supports Handlebars syntax).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+
+    /// Records per upsert request.
+    #[serde(default = "default_upsert_batch_size")]
+    pub batch_size: usize,
+
+    /// Upsert requests in flight at once.
+    #[serde(default = "default_upsert_concurrency")]
+    pub concurrency: usize,
+
+    /// Most upsert requests started per second.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub requests_per_second: Option<f64>,
+}
+
+/// Vector database fetch configuration.
+///
+/// Looks up records by ID. Outputs the records found, as `{id, vector,
+/// metadata}` objects in the order requested, and the IDs with no record, in
+/// that order.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct VectorFetchConfig {
+    /// Vector database provider (pinecone, weaviate, etc.).
+    pub database: String,
+
+    /// Index/collection name.
+    pub index: String,
+
+    /// IDs to fetch: a list whose strings support Handlebars syntax, or a
+    /// template yielding such a list.
+    pub ids: serde_json::Value,
+
+    /// Namespace/partition to read from (supports Handlebars syntax).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+}
+
+fn default_upsert_batch_size() -> usize {
+    100
+}
+
+fn default_upsert_concurrency() -> usize {
+    4
+}
+
 fn default_true() -> bool {
     true
 }
 
 /// Transform configuration.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TransformConfig {
     /// Transform function name (concat, merge, filter, etc.).
     pub function: String,
@@ -238,7 +876,7 @@ pub struct TransformConfig {
 }
 
 /// Action configuration.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ActionConfig {
     /// Action type (log, notify, publish, etc.).
     pub action: String,
@@ -249,7 +887,7 @@ pub struct ActionConfig {
 }
 
 /// Parallel execution configuration.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ParallelConfig {
     /// Parallel tasks.
     pub tasks: Vec<Step>,
@@ -260,7 +898,7 @@ pub struct ParallelConfig {
 }
 
 /// Branch configuration.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct BranchConfig {
     /// Condition to evaluate.
     pub condition: String,
@@ -269,77 +907,707 @@ pub struct BranchConfig {
     pub branches: HashMap<String, Vec<Step>>,
 }
 
-/// Retry configuration.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct RetryConfig {
-    /// Maximum retry attempts.
-    #[serde(default = "default_max_attempts")]
-    pub max_attempts: u32,
+/// Approval gate configuration.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ApprovalConfig {
+    /// Content presented to the reviewer (supports Handlebars syntax).
+    pub payload: String,
 
-    /// Backoff strategy.
+    /// Instructions shown to the reviewer.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+
+    /// Step to run when the request is rejected.
+    ///
+    /// The step must depend on the approval step. It is skipped on approval;
+    /// on rejection it runs while the other dependents are skipped.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_reject: Option<String>,
+}
+
+/// Output evaluation configuration.
+///
+/// Scores `target` between 0.0 and 1.0 with each metric and the judge, and
+/// combines them into a weighted mean. Later steps can branch on the score
+/// with a `condition`, or set `fail_below_threshold` so a low score fails the
+/// step and its `on_failure` policy (e.g. a regenerating fallback) applies.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct EvaluateConfig {
+    /// Output to score (supports Handlebars syntax).
+    pub target: String,
+
+    /// Expected output, used by `exact_match` and `embedding_similarity`
+    /// (supports Handlebars syntax).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reference: Option<String>,
+
+    /// Built-in metrics.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub metrics: Vec<EvalMetric>,
+
+    /// LLM-as-judge scoring.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub judge: Option<JudgeConfig>,
+
+    /// Minimum overall score for the output to pass.
+    #[serde(default = "default_eval_threshold")]
+    pub threshold: f64,
+
+    /// Fail the step when the overall score is below `threshold`.
     #[serde(default)]
-    pub backoff: BackoffStrategy,
+    pub fail_below_threshold: bool,
+}
 
-    /// Initial delay in milliseconds.
-    #[serde(default = "default_initial_delay_ms")]
-    pub initial_delay_ms: u64,
+fn default_eval_threshold() -> f64 {
+    0.5
+}
 
-    /// Maximum delay in milliseconds.
-    #[serde(default = "default_max_delay_ms")]
-    pub max_delay_ms: u64,
+/// HTTP request step configuration.
+///
+/// Sends one request and stores the response body (parsed as JSON when
+/// possible), status code, and headers in the step outputs, in that order.
+/// `extract` adds outputs picked out of the JSON body with JSONPath. Failed
+/// requests are retried according to the step's `retry` policy when the
+/// service is unreachable, overloaded (429), or erroring (5xx).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct HttpConfig {
+    /// HTTP method (GET, POST, PUT, PATCH, DELETE, HEAD, or OPTIONS).
+    #[serde(default = "default_http_method")]
+    pub method: String,
+
+    /// URL (supports Handlebars syntax).
+    pub url: String,
+
+    /// Request headers: templates, or `{ secretRef: "key" }` objects.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, serde_json::Value>,
+
+    /// JSON body; its strings support Handlebars syntax.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<serde_json::Value>,
+
+    /// URL-encoded form body; its values support Handlebars syntax.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub form: Option<HashMap<String, String>>,
+
+    /// Outputs to pick from the JSON response, as output name -> JSONPath.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub extract: HashMap<String, String>,
+
+    /// Status codes that count as success (default: any 2xx).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub success_status: Vec<u16>,
 }
 
-fn default_max_attempts() -> u32 {
-    3
+fn default_http_method() -> String {
+    "GET".to_string()
 }
 
-fn default_initial_delay_ms() -> u64 {
-    100
+/// Methods an HTTP step may use.
+pub const HTTP_METHODS: [&str; 7] = ["GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS"];
+
+/// MCP tool call step configuration.
+///
+/// Calls `tool` on the MCP server registered as `server` and stores the
+/// text of the result and its structured content in the step outputs, in
+/// that order. Arguments are checked against the input schema the server
+/// publishes for the tool before the call.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct McpToolConfig {
+    /// Name the server was registered under.
+    pub server: String,
+
+    /// Tool name.
+    pub tool: String,
+
+    /// Tool arguments; strings support Handlebars syntax, and a string that is
+    /// a single `{{ path }}` expression keeps the JSON type of its value.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub arguments: HashMap<String, serde_json::Value>,
 }
 
-fn default_max_delay_ms() -> u64 {
-    30000
+/// Web fetch step configuration.
+///
+/// Fetches each of `urls` with a GET request and stores the pages fetched
+/// and the URLs that could not be, in that order. HTML pages are reduced to
+/// their text unless `extract_text` is off.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WebFetchConfig {
+    /// URLs to fetch: a URL or list whose strings support Handlebars syntax,
+    /// or a template yielding a list.
+    pub urls: serde_json::Value,
+
+    /// Most requests in flight at once.
+    #[serde(default = "default_fetch_concurrency")]
+    pub concurrency: usize,
+
+    /// Seconds each request may take.
+    #[serde(default = "default_fetch_timeout")]
+    pub timeout_seconds: u64,
+
+    /// Largest response body, in bytes.
+    #[serde(default = "default_fetch_max_bytes")]
+    pub max_bytes: usize,
+
+    /// Accepted media types; `text/*` accepts every text type.
+    #[serde(default = "default_fetch_content_types")]
+    pub content_types: Vec<String>,
+
+    /// Skip URLs the site's robots.txt disallows, and wait its crawl delay
+    /// between requests to the site.
+    #[serde(default = "default_true")]
+    pub respect_robots: bool,
+
+    /// User agent sent, and matched against robots.txt groups.
+    #[serde(default = "default_fetch_user_agent")]
+    pub user_agent: String,
+
+    /// Reduce HTML pages to their visible text.
+    #[serde(default = "default_true")]
+    pub extract_text: bool,
 }
 
-/// Backoff strategy for retries.
-#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
-pub enum BackoffStrategy {
-    /// Exponential backoff (2^n * initial_delay).
-    #[default]
-    Exponential,
+fn default_fetch_concurrency() -> usize {
+    4
+}
 
-    /// Linear backoff (n * initial_delay).
-    Linear,
+fn default_fetch_timeout() -> u64 {
+    30
+}
 
-    /// Constant delay.
-    Constant,
+fn default_fetch_max_bytes() -> usize {
+    5 * 1024 * 1024
 }
 
-impl Workflow {
-    /// Create a new workflow.
-    pub fn new(name: impl Into<String>) -> Self {
-        Self {
-            id: Uuid::new_v4(),
-            name: name.into(),
+fn default_fetch_content_types() -> Vec<String> {
+    ["text/*", "application/json", "application/xhtml+xml"].map(String::from).to_vec()
+}
+
+fn default_fetch_user_agent() -> String {
+    "llm-orchestrator".to_string()
+}
+
+/// Transcribe step configuration.
+///
+/// Transcribes an audio file with a registered transcription provider and
+/// stores the transcript, its timed segments, the language spoken, and the
+/// audio's duration, in that order.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TranscribeConfig {
+    /// Transcription provider, as registered with the executor.
+    pub provider: String,
+
+    /// Model name.
+    #[serde(default = "default_transcription_model")]
+    pub model: String,
+
+    /// Audio file path or `http(s)` URL (supports Handlebars syntax).
+    pub audio: String,
+
+    /// Spoken language as an ISO-639-1 code; detected when unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+
+    /// Text guiding the transcript's spelling or style, such as names and
+    /// jargon (supports Handlebars syntax).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt: Option<String>,
+
+    /// Additional provider-specific parameters.
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+fn default_transcription_model() -> String {
+    "whisper-1".to_string()
+}
+
+/// Speak step configuration.
+///
+/// Synthesizes speech with a registered speech provider, writes it to an
+/// audio file, and stores the file's path or artifact URI and its size in
+/// bytes, in that order.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SpeakConfig {
+    /// Speech provider, as registered with the executor.
+    pub provider: String,
+
+    /// Model name.
+    pub model: String,
+
+    /// Voice name or ID.
+    pub voice: String,
+
+    /// Text to speak (supports Handlebars syntax).
+    pub text: String,
+
+    /// Audio format, such as `mp3`, `wav`, `opus`, or `pcm`.
+    #[serde(default = "default_speech_format")]
+    pub format: String,
+
+    /// Speaking rate, 1.0 being normal.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speed: Option<f32>,
+
+    /// File the audio is written to (supports Handlebars syntax); stored in
+    /// the run's artifact store when unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+
+    /// Additional provider-specific parameters.
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+fn default_speech_format() -> String {
+    "mp3".to_string()
+}
+
+/// Agent step configuration.
+///
+/// The model is given the task in `prompt` and the `tools`, and calls tools
+/// until it answers without one or has made `max_iterations` requests. The
+/// answer, the trajectory of calls and observations, and why the loop
+/// stopped are the step outputs, in that order.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AgentStepConfig {
+    /// LLM provider; it must support tool calling.
+    pub provider: String,
+
+    /// Model name.
+    pub model: String,
+
+    /// Task template (supports Handlebars syntax).
+    pub prompt: String,
+
+    /// System prompt template.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system: Option<String>,
+
+    /// Tools the model may call.
+    pub tools: Vec<AgentTool>,
+
+    /// Most model requests before the loop stops without an answer.
+    #[serde(default = "default_max_iterations")]
+    pub max_iterations: u32,
+
+    /// Sampling temperature.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+
+    /// Maximum tokens per model request.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+}
+
+fn default_max_iterations() -> u32 {
+    10
+}
+
+/// A tool offered to an agent.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum AgentTool {
+    /// A registered action handler, called with the model's arguments as
+    /// its params. Arguments are not rendered as templates.
+    Action {
+        /// Action handler name.
+        action: String,
+
+        /// Tool name shown to the model; defaults to the action name with
+        /// characters other than letters, digits, `_`, and `-` replaced by `_`.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+
+        /// What the tool does.
+        #[serde(default, skip_serializing_if = "String::is_empty")]
+        description: String,
+
+        /// JSON Schema of the arguments; any object when unset.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        parameters: Option<serde_json::Value>,
+    },
+
+    /// Tools of a registered MCP server, as the server describes them.
+    Mcp {
+        /// Name the server was registered under.
+        mcp: String,
+
+        /// Tools to offer; all of the server's tools when empty.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        tools: Vec<String>,
+    },
+}
+
+fn default_weight() -> f64 {
+    1.0
+}
+
+/// A built-in evaluation metric and its weight in the overall score.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct EvalMetric {
+    /// What the metric measures.
+    #[serde(flatten)]
+    pub kind: EvalMetricKind,
+
+    /// Weight of this metric in the overall score.
+    #[serde(default = "default_weight")]
+    pub weight: f64,
+}
+
+/// Built-in evaluation metrics, written in YAML with a `type` tag.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EvalMetricKind {
+    /// 1.0 when the target equals the reference, ignoring surrounding whitespace.
+    ExactMatch {
+        /// Compare case-insensitively.
+        #[serde(default)]
+        ignore_case: bool,
+    },
+
+    /// 1.0 when the target matches a regular expression.
+    Regex {
+        /// Pattern to search for.
+        pattern: String,
+    },
+
+    /// 1.0 when the target is JSON valid against a schema.
+    JsonSchema {
+        /// JSON Schema the target must satisfy.
+        schema: serde_json::Value,
+    },
+
+    /// Cosine similarity between the target and reference embeddings,
+    /// clamped to 0.0 - 1.0.
+    EmbeddingSimilarity {
+        /// Embedding provider.
+        provider: String,
+
+        /// Embedding model.
+        model: String,
+    },
+}
+
+impl EvalMetricKind {
+    /// Metric name used as its key in the step's scores.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::ExactMatch { .. } => "exact_match",
+            Self::Regex { .. } => "regex",
+            Self::JsonSchema { .. } => "json_schema",
+            Self::EmbeddingSimilarity { .. } => "embedding_similarity",
+        }
+    }
+}
+
+/// LLM-as-judge configuration.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct JudgeConfig {
+    /// LLM provider.
+    pub provider: String,
+
+    /// Model name.
+    pub model: String,
+
+    /// Grading criteria given to the judge (supports Handlebars syntax).
+    pub rubric: String,
+
+    /// Highest score the judge can award; scores are divided by it.
+    #[serde(default = "default_judge_scale")]
+    pub scale: u32,
+
+    /// Weight of the judge's score in the overall score.
+    #[serde(default = "default_weight")]
+    pub weight: f64,
+}
+
+fn default_judge_scale() -> u32 {
+    10
+}
+
+/// Failure handling policy for a step.
+///
+/// Written in YAML as `fail_workflow`, `continue`, `skip_dependents`, or
+/// `{ run_step: <step id> }`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "FailurePolicyRepr", into = "FailurePolicyRepr")]
+pub enum FailurePolicy {
+    /// Abort the run: in-flight steps are cancelled and `execute()` returns
+    /// [`OrchestratorError::WorkflowFailed`](crate::OrchestratorError::WorkflowFailed).
+    FailWorkflow,
+
+    /// Record the failure and keep running, including dependent steps.
+    Continue,
+
+    /// Skip every step that depends (directly or transitively) on the failed step.
+    SkipDependents,
+
+    /// Run a fallback step, which must depend on the failing step.
+    ///
+    /// The fallback is skipped when the step succeeds.
+    RunStep(String),
+}
+
+/// Serialized form of [`FailurePolicy`].
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+enum FailurePolicyRepr {
+    Named(NamedFailurePolicy),
+    RunStep { run_step: String },
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+enum NamedFailurePolicy {
+    FailWorkflow,
+    Continue,
+    SkipDependents,
+}
+
+impl From<FailurePolicyRepr> for FailurePolicy {
+    fn from(repr: FailurePolicyRepr) -> Self {
+        match repr {
+            FailurePolicyRepr::Named(NamedFailurePolicy::FailWorkflow) => Self::FailWorkflow,
+            FailurePolicyRepr::Named(NamedFailurePolicy::Continue) => Self::Continue,
+            FailurePolicyRepr::Named(NamedFailurePolicy::SkipDependents) => Self::SkipDependents,
+            FailurePolicyRepr::RunStep { run_step } => Self::RunStep(run_step),
+        }
+    }
+}
+
+impl From<FailurePolicy> for FailurePolicyRepr {
+    fn from(policy: FailurePolicy) -> Self {
+        match policy {
+            FailurePolicy::FailWorkflow => Self::Named(NamedFailurePolicy::FailWorkflow),
+            FailurePolicy::Continue => Self::Named(NamedFailurePolicy::Continue),
+            FailurePolicy::SkipDependents => Self::Named(NamedFailurePolicy::SkipDependents),
+            FailurePolicy::RunStep(run_step) => Self::RunStep { run_step },
+        }
+    }
+}
+
+// The schema follows the serialized form
+impl JsonSchema for FailurePolicy {
+    fn schema_name() -> String {
+        "FailurePolicy".to_string()
+    }
+
+    fn json_schema(generator: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        let mut schema = FailurePolicyRepr::json_schema(generator).into_object();
+        schema.metadata().description =
+            Some("`fail_workflow`, `continue`, `skip_dependents`, or `{ run_step: <step id> }`.".to_string());
+        schema.into()
+    }
+}
+
+/// Retry configuration.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RetryConfig {
+    /// Maximum retry attempts.
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+
+    /// Backoff strategy.
+    #[serde(default)]
+    pub backoff: BackoffStrategy,
+
+    /// Initial delay in milliseconds.
+    #[serde(default = "default_initial_delay_ms")]
+    pub initial_delay_ms: u64,
+
+    /// Maximum delay in milliseconds.
+    #[serde(default = "default_max_delay_ms")]
+    pub max_delay_ms: u64,
+}
+
+fn default_max_attempts() -> u32 {
+    3
+}
+
+fn default_initial_delay_ms() -> u64 {
+    100
+}
+
+fn default_max_delay_ms() -> u64 {
+    30000
+}
+
+/// Limits on the retries of a run, shared by all of its steps.
+///
+/// Once either limit is reached, failing steps are not retried again, so
+/// cascading retries cannot multiply the cost of a run.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct RetryBudgetConfig {
+    /// Maximum number of retries, including hedged requests.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_retries: Option<u32>,
+
+    /// Maximum latency retries may add to the run, in milliseconds.
+    ///
+    /// A retry adds the duration of the failed attempt plus its backoff delay.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_extra_latency_ms: Option<u64>,
+}
+
+/// An event that starts a run of the workflow.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Trigger {
+    /// A signed HTTP request to `/hooks/{name}` in serve mode.
+    Webhook(WebhookTrigger),
+    /// A message on a Kafka topic.
+    Kafka(TopicTrigger),
+    /// A message on a NATS JetStream subject.
+    Nats(TopicTrigger),
+    /// An OpenAI-compatible chat completion request for a model name in serve mode.
+    Chat(ChatTrigger),
+}
+
+/// Backoff strategy for retries.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BackoffStrategy {
+    /// Exponential backoff (2^n * initial_delay).
+    #[default]
+    Exponential,
+
+    /// Linear backoff (n * initial_delay).
+    Linear,
+
+    /// Constant delay.
+    Constant,
+}
+
+impl Workflow {
+    /// Create a new workflow.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            name: name.into(),
             version: default_version(),
             description: None,
             steps: Vec::new(),
             timeout_seconds: None,
             metadata: HashMap::new(),
+            profiles: HashMap::new(),
+            guardrails: None,
+            retry_budget: None,
+            triggers: Vec::new(),
+            outputs: None,
+            artifacts: None,
+            workdir: None,
+            partials: HashMap::new(),
         }
     }
 
     /// Load workflow from YAML string.
+    ///
+    /// Included files and partials are read relative to the working directory
+    /// (see [`crate::include`] and [`crate::partials`]) and step templates are
+    /// expanded (see [`crate::step_template`]).
     pub fn from_yaml(yaml: &str) -> crate::error::Result<Self> {
-        serde_yaml::from_str(yaml).map_err(|e| crate::error::OrchestratorError::parse(e.to_string()))
+        Self::from_yaml_with(yaml, &FileResolver::current_dir())
+    }
+
+    /// Load workflow from YAML string, reading included files with `resolver`.
+    pub fn from_yaml_with(yaml: &str, resolver: &dyn IncludeResolver) -> crate::error::Result<Self> {
+        match load_yaml(yaml, resolver) {
+            Ok((workflow, _)) => Ok(workflow),
+            Err(LoadError::Syntax(e)) => Err(crate::error::OrchestratorError::parse(e.to_string())),
+            Err(LoadError::Invalid(diagnostic)) => Err(template_error(*diagnostic)),
+        }
+    }
+
+    /// Load workflow from a YAML file, reading included files relative to it.
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> crate::error::Result<Self> {
+        let path = path.as_ref();
+        let yaml = std::fs::read_to_string(path)?;
+        Self::from_yaml_with(&yaml, &FileResolver::for_file(path))
     }
 
     /// Load workflow from JSON string.
+    ///
+    /// Step templates are expanded (see [`crate::step_template`]).
     pub fn from_json(json: &str) -> crate::error::Result<Self> {
+        let document: serde_json::Value =
+            serde_json::from_str(json).map_err(|e| crate::error::OrchestratorError::parse(e.to_string()))?;
+        if document.get(step_template::TEMPLATES_FIELD).is_some() {
+            return step_template::instantiate(document).map_err(|diagnostic| template_error(*diagnostic));
+        }
         serde_json::from_str(json).map_err(|e| crate::error::OrchestratorError::parse(e.to_string()))
     }
 
+    /// JSON Schema of workflow files, for editor validation and completion.
+    ///
+    /// Each step is checked against the configuration of its `type`.
+    pub fn json_schema() -> schemars::schema::RootSchema {
+        let mut schema = schemars::schema_for!(Workflow);
+        // Serde tells step configurations apart by their fields alone
+        if let Some(schemars::schema::Schema::Object(step)) = schema.definitions.get_mut("Step") {
+            let rules = STEP_CONFIG_SCHEMAS
+                .iter()
+                .map(|(step_type, config)| {
+                    serde_json::from_value(serde_json::json!({
+                        "if": {"properties": {"type": {"const": step_type}}},
+                        "then": {"$ref": format!("#/definitions/{}", config)},
+                    }))
+                    .expect("step rules are valid schemas")
+                })
+                .collect();
+            step.subschemas().all_of = Some(rules);
+        }
+
+        // Includes, step templates, and the partials directory are resolved on load, so the workflow type lacks them
+        for (name, template_schema) in [
+            ("StepTemplate", schemars::schema_for!(StepTemplate)),
+            ("TemplateInstance", schemars::schema_for!(TemplateInstance)),
+        ] {
+            schema.definitions.extend(template_schema.definitions);
+            schema.definitions.insert(name.to_string(), template_schema.schema.into());
+        }
+        let properties = &mut schema.schema.object().properties;
+        properties.insert(
+            step_template::TEMPLATES_FIELD.to_string(),
+            serde_json::from_value(serde_json::json!({
+                "description": "Reusable groups of steps, instantiated by steps with `use`.",
+                "type": "object",
+                "additionalProperties": {"$ref": "#/definitions/StepTemplate"},
+            }))
+            .expect("templates schema is valid"),
+        );
+        properties.insert(
+            include::INCLUDES_FIELD.to_string(),
+            serde_json::from_value(serde_json::json!({
+                "description": "Files merged into the workflow, relative to it.",
+                "type": "array",
+                "items": {"type": "string"},
+            }))
+            .expect("includes schema is valid"),
+        );
+        properties.insert(
+            partials::PARTIALS_DIR_FIELD.to_string(),
+            serde_json::from_value(serde_json::json!({
+                "description": "Directory whose files are partials named after them, relative to the workflow.",
+                "type": "string",
+            }))
+            .expect("partials_dir schema is valid"),
+        );
+        if let Some(schemars::schema::Schema::Object(steps)) = properties.get_mut("steps") {
+            steps.array().items = Some(
+                serde_json::from_value::<schemars::schema::Schema>(serde_json::json!({
+                    "anyOf": [
+                        {"$ref": "#/definitions/Step"},
+                        {"$ref": "#/definitions/TemplateInstance"},
+                    ],
+                }))
+                .expect("step items schema is valid")
+                .into(),
+            );
+        }
+        schema
+    }
+
     /// Convert workflow to YAML string.
     pub fn to_yaml(&self) -> crate::error::Result<String> {
         serde_yaml::to_string(self).map_err(|e| crate::error::OrchestratorError::serialization(e.to_string()))
@@ -350,6 +1618,56 @@ impl Workflow {
         serde_json::to_string_pretty(self).map_err(|e| crate::error::OrchestratorError::serialization(e.to_string()))
     }
 
+    /// Returns a copy of the workflow with the named profile applied.
+    ///
+    /// Objects are merged recursively and all other values (including arrays)
+    /// are replaced. Step overrides are matched by step ID. The merged
+    /// workflow is validated before it is returned.
+    pub fn with_profile(&self, profile: &str) -> crate::error::Result<Self> {
+        let overlay = self.profiles.get(profile).ok_or_else(|| {
+            let mut available: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+            available.sort();
+            crate::error::OrchestratorError::validation(format!(
+                "Unknown profile '{}' (available: {})",
+                profile,
+                available.join(", ")
+            ))
+        })?;
+        let serde_json::Value::Object(overlay) = overlay else {
+            return Err(crate::error::OrchestratorError::validation(format!(
+                "Profile '{}' must be a mapping",
+                profile
+            )));
+        };
+
+        // The applied workflow does not carry the profiles itself
+        let mut merged = serde_json::to_value(self)?;
+        if let Some(fields) = merged.as_object_mut() {
+            fields.remove("profiles");
+        }
+        for (key, value) in overlay {
+            if key == "steps" {
+                merge_step_overrides(&mut merged["steps"], value, profile)?;
+            } else if key == "profiles" {
+                return Err(crate::error::OrchestratorError::validation(format!(
+                    "Profile '{}' cannot define profiles",
+                    profile
+                )));
+            } else {
+                deep_merge(&mut merged[key.as_str()], value);
+            }
+        }
+
+        let workflow: Workflow = serde_json::from_value(merged).map_err(|e| {
+            crate::error::OrchestratorError::validation(format!(
+                "Profile '{}' produced an invalid workflow: {}",
+                profile, e
+            ))
+        })?;
+        workflow.validate()?;
+        Ok(workflow)
+    }
+
     /// Get a step by ID.
     pub fn get_step(&self, id: &str) -> Option<&Step> {
         self.steps.iter().find(|s| s.id == id)
@@ -360,31 +1678,501 @@ impl Workflow {
         self.steps.iter().map(|s| s.id.clone()).collect()
     }
 
-    /// Validate workflow structure.
+    /// Validate the workflow, returning its first problem.
+    ///
+    /// Use [`Workflow::diagnostics`] to get every problem, or
+    /// [`check_yaml`](crate::diagnostics::check_yaml) to locate them in a file.
     pub fn validate(&self) -> crate::error::Result<()> {
+        match self.diagnostics().into_iter().next() {
+            Some(diagnostic) => Err(crate::error::OrchestratorError::validation(diagnostic.message)),
+            None => Ok(()),
+        }
+    }
+
+    /// Every problem of the workflow, in the order `validate` checks them.
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        let mut report = |diagnostic| diagnostics.push(diagnostic);
+
         // Check for empty steps
         if self.steps.is_empty() {
-            return Err(crate::error::OrchestratorError::validation("Workflow has no steps"));
+            report(Diagnostic::new(DiagnosticCode::NoSteps, "Workflow has no steps").at("steps"));
         }
 
         // Check for duplicate step IDs
         let mut seen = std::collections::HashSet::new();
-        for step in &self.steps {
+        for (i, step) in self.steps.iter().enumerate() {
             if !seen.insert(&step.id) {
-                return Err(crate::error::OrchestratorError::validation(format!("Duplicate step ID: {}", step.id)));
+                report(Diagnostic::new(DiagnosticCode::DuplicateStepId, format!("Duplicate step ID: {}", step.id)).step(&step.id).at(format!("steps[{}].id", i)).suggest("Give each step a unique ID"));
+            }
+        }
+
+        // Check that steps nested in parallel and branch steps do not reuse top-level IDs
+        for (i, step) in self.steps.iter().enumerate() {
+            for (path, nested) in nested_steps(step, format!("steps[{}]", i)) {
+                if seen.contains(&nested.id) {
+                    report(Diagnostic::new(DiagnosticCode::DuplicateStepId, format!("Step '{}' nested in '{}' has the same ID as a top-level step", nested.id, step.id)).step(&step.id).at(format!("{}.id", path)).suggest("Give each step a unique ID"));
+                }
             }
         }
 
         // Check that dependencies reference valid steps
-        for step in &self.steps {
-            for dep in &step.depends_on {
+        for (i, step) in self.steps.iter().enumerate() {
+            let dependencies = step.depends_on.iter().enumerate().map(|(j, dep)| (format!("depends_on[{}]", j), dep));
+            let alternatives = step.depends_on_any.iter().enumerate().map(|(j, dep)| (format!("depends_on_any[{}]", j), dep));
+            for (field, dep) in dependencies.chain(alternatives) {
                 if !seen.contains(dep) {
-                    return Err(crate::error::OrchestratorError::validation(format!("Step '{}' depends on non-existent step '{}'", step.id, dep)));
+                    let mut diagnostic = Diagnostic::new(DiagnosticCode::UnknownDependency, format!("Step '{}' depends on non-existent step '{}'", step.id, dep)).step(&step.id).at(format!("steps[{}].{}", i, field));
+                    if let Some(suggestion) = did_you_mean(dep, self.steps.iter().map(|s| s.id.as_str())) {
+                        diagnostic = diagnostic.suggest(suggestion);
+                    }
+                    report(diagnostic);
+                }
+            }
+        }
+
+        // Check that bindings name values the step can read
+        for (i, step) in self.steps.iter().enumerate() {
+            let mut bindings: Vec<_> = step.bind.iter().collect();
+            bindings.sort();
+            for (name, path) in bindings {
+                let problem = if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+                    Some(("templates cannot read it by that name".to_string(), Some("Name it with letters, digits, '_' and '-' only".to_string())))
+                } else {
+                    self.binding_problem(step, path)
+                };
+                if let Some((problem, suggestion)) = problem {
+                    let mut diagnostic = Diagnostic::new(DiagnosticCode::InvalidBinding, format!("Binding '{}' of step '{}': {}", name, step.id, problem)).step(&step.id).at(format!("steps[{}].bind.{}", i, name));
+                    if let Some(suggestion) = suggestion {
+                        diagnostic = diagnostic.suggest(suggestion);
+                    }
+                    report(diagnostic);
                 }
             }
         }
 
-        Ok(())
+        // Check that approval rejection branches hang off their approval step
+        for (i, step) in self.steps.iter().enumerate() {
+            if let StepConfig::Approval(ApprovalConfig { on_reject: Some(target), .. }) = &step.config {
+                let depends_on_approval = self
+                    .get_step(target)
+                    .map(|t| t.depends_on.contains(&step.id))
+                    .unwrap_or(false);
+                if !depends_on_approval {
+                    report(Diagnostic::new(DiagnosticCode::InvalidRejectionBranch, format!("Step '{}' rejection branch '{}' must be a step that depends on it", step.id, target)).step(&step.id).at(format!("steps[{}].on_reject", i)).suggest(format!("Add '{}' to the depends_on of '{}'", step.id, target)));
+                }
+            }
+        }
+
+        // Check that experiment variants are distinct and can be picked
+        for (i, step) in self.steps.iter().enumerate() {
+            if let StepConfig::Llm(config) = &step.config {
+                if config.variants.is_empty() {
+                    continue;
+                }
+                let mut ids = std::collections::HashSet::new();
+                for (k, variant) in config.variants.iter().enumerate() {
+                    if !ids.insert(variant.id.as_str()) {
+                        report(Diagnostic::new(DiagnosticCode::DuplicateVariant, format!("Step '{}' has duplicate variant '{}'", step.id, variant.id)).step(&step.id).at(format!("steps[{}].variants[{}].id", i, k)));
+                    }
+                    if !(variant.weight >= 0.0 && variant.weight.is_finite()) {
+                        report(Diagnostic::new(DiagnosticCode::InvalidVariantWeight, format!("Step '{}' variant '{}' has an invalid weight", step.id, variant.id)).step(&step.id).at(format!("steps[{}].variants[{}].weight", i, k)).suggest("Use a finite weight of 0 or more"));
+                    }
+                }
+                if config.variants.iter().all(|v| v.weight == 0.0) {
+                    report(Diagnostic::new(DiagnosticCode::NoWeightedVariant, format!("Step '{}' needs a variant with a positive weight", step.id)).step(&step.id).at(format!("steps[{}].variants", i)));
+                }
+            }
+        }
+
+        // Check that hedged LLM steps hedge at a valid percentile
+        for (i, step) in self.steps.iter().enumerate() {
+            if let StepConfig::Llm(LlmStepConfig { hedge: Some(hedge), .. }) = &step.config {
+                if !(hedge.percentile > 0.0 && hedge.percentile <= 100.0) {
+                    report(Diagnostic::new(DiagnosticCode::InvalidHedgePercentile, format!("Step '{}' hedge percentile must be in (0, 100]", step.id)).step(&step.id).at(format!("steps[{}].hedge.percentile", i)));
+                }
+            }
+        }
+
+        // Check that LLM steps name one valid prompt source
+        for (i, step) in self.steps.iter().enumerate() {
+            if let StepConfig::Llm(config) = &step.config {
+                let refs = std::iter::once((format!("steps[{}].prompt_ref", i), config.prompt_ref.as_deref()))
+                    .chain(config.variants.iter().enumerate().map(|(k, v)| (format!("steps[{}].variants[{}].prompt_ref", i, k), v.prompt_ref.as_deref())));
+                for (path, prompt_ref) in refs {
+                    if let Some(Err(e)) = prompt_ref.map(str::parse::<crate::prompts::PromptRef>) {
+                        report(Diagnostic::new(DiagnosticCode::InvalidPromptRef, error_message(e)).step(&step.id).at(path).suggest("Use `name`, `name@latest`, or `name@v3`"));
+                    }
+                }
+                if config.prompt_ref.is_some() && !config.prompt.is_empty() {
+                    report(Diagnostic::new(DiagnosticCode::ConflictingPrompt, format!("Step '{}' sets both prompt and prompt_ref", step.id)).step(&step.id).at(format!("steps[{}].prompt_ref", i)).suggest("Remove either prompt or prompt_ref"));
+                }
+            }
+        }
+
+        // Check that partials compile and that templates only render defined ones
+        let mut partial_names: Vec<&String> = self.partials.keys().collect();
+        partial_names.sort();
+        let mut templates = Vec::new();
+        for name in partial_names {
+            let path = format!("partials.{}", name);
+            if let Err(e) = handlebars::Template::compile(&self.partials[name]) {
+                report(Diagnostic::new(DiagnosticCode::InvalidPartial, format!("Partial '{}' does not compile: {}", name, e)).at(&path));
+            }
+            templates.push((None, path, self.partials[name].clone()));
+        }
+        for (i, step) in self.steps.iter().enumerate() {
+            let mut step_templates = Vec::new();
+            crate::lint::collect_templates(format!("steps[{}]", i), &serde_json::to_value(step).unwrap_or_default(), &mut step_templates);
+            templates.extend(step_templates.into_iter().map(|(path, text)| (Some(&step.id), path, text)));
+        }
+        for (step_id, path, text) in &templates {
+            for name in partials::called(text).filter(|name| !self.partials.contains_key(*name)) {
+                let mut diagnostic = Diagnostic::new(DiagnosticCode::InvalidPartial, format!("Template renders undefined partial '{}'", name)).at(path);
+                if let Some(step_id) = step_id {
+                    diagnostic = diagnostic.step(step_id);
+                }
+                if let Some(suggestion) = did_you_mean(name, self.partials.keys().map(String::as_str)) {
+                    diagnostic = diagnostic.suggest(suggestion);
+                }
+                report(diagnostic);
+            }
+        }
+
+        // Check that conversation compaction has a budget and a valid summarization workflow
+        for (i, step) in self.steps.iter().enumerate() {
+            let StepConfig::Llm(LlmStepConfig { memory: Some(LlmMemoryConfig { compact: Some(compact), .. }), .. }) = &step.config else {
+                continue;
+            };
+            let path = format!("steps[{}].memory.compact", i);
+            if compact.max_tokens == 0 {
+                report(Diagnostic::new(DiagnosticCode::InvalidCompaction, format!("Step '{}' compacts memory with a token budget of 0", step.id)).step(&step.id).at(format!("{}.max_tokens", path)).suggest("Use a positive max_tokens"));
+            }
+            for diagnostic in compact.workflow.diagnostics() {
+                report(Diagnostic::new(DiagnosticCode::InvalidCompaction, format!("Step '{}' summarization workflow: {}", step.id, diagnostic.message)).step(&step.id).at(format!("{}.workflow", path)));
+            }
+            if compact.workflow.steps.last().is_some_and(|last| last.output.is_empty()) {
+                report(Diagnostic::new(DiagnosticCode::InvalidCompaction, format!("Step '{}' summarization workflow has no summary output", step.id)).step(&step.id).at(format!("{}.workflow.steps", path)).suggest("Declare the summary as the first output of the last step"));
+            }
+        }
+
+        // Check that evaluation steps have something to score with
+        for (i, step) in self.steps.iter().enumerate() {
+            if let StepConfig::Evaluate(config) = &step.config {
+                if config.metrics.is_empty() && config.judge.is_none() {
+                    report(Diagnostic::new(DiagnosticCode::MissingEvaluation, format!("Evaluate step '{}' needs at least one metric or a judge", step.id)).step(&step.id).at(format!("steps[{}]", i)));
+                }
+            }
+        }
+
+        // Check that HTTP steps send a valid request and extract with valid paths
+        for (i, step) in self.steps.iter().enumerate() {
+            if let StepConfig::Http(config) = &step.config {
+                if !HTTP_METHODS.contains(&config.method.to_uppercase().as_str()) {
+                    report(Diagnostic::new(DiagnosticCode::UnsupportedHttpMethod, format!("HTTP step '{}' has unsupported method '{}'", step.id, config.method)).step(&step.id).at(format!("steps[{}].method", i)).suggest(format!("Use one of {}", HTTP_METHODS.join(", "))));
+                }
+                if config.body.is_some() && config.form.is_some() {
+                    report(Diagnostic::new(DiagnosticCode::ConflictingHttpBody, format!("HTTP step '{}' sets both body and form", step.id)).step(&step.id).at(format!("steps[{}].form", i)).suggest("Remove either body or form"));
+                }
+                let mut extract: Vec<_> = config.extract.iter().collect();
+                extract.sort();
+                for (output, path) in extract {
+                    if let Err(e) = jsonpath_rust::parser::parse_json_path(path) {
+                        report(Diagnostic::new(DiagnosticCode::InvalidJsonPath, format!("HTTP step '{}' output '{}' has invalid JSONPath '{}': {}", step.id, output, path, e)).step(&step.id).at(format!("steps[{}].extract.{}", i, output)));
+                    }
+                }
+            }
+        }
+
+        // Check that assertions parse; templated ones are only known once rendered
+        for (i, step) in self.steps.iter().enumerate() {
+            for (j, assertion) in step.assert.iter().enumerate() {
+                if assertion.contains("{{") {
+                    continue;
+                }
+                if let Err(e) = crate::assertion::Expression::parse(assertion) {
+                    report(Diagnostic::new(DiagnosticCode::InvalidAssertion, format!("Step '{}' has invalid assertion '{}': {}", step.id, assertion, e)).step(&step.id).at(format!("steps[{}].assert[{}]", i, j)));
+                }
+            }
+        }
+
+        // Check that triggers are servable and webhooks and chat models have distinct names
+        let mut hooks = std::collections::HashSet::new();
+        let mut models = std::collections::HashSet::new();
+        for (t, trigger) in self.triggers.iter().enumerate() {
+            let (result, duplicate) = match trigger {
+                Trigger::Webhook(hook) => (hook.validate(), (!hooks.insert(hook.name.as_str())).then(|| ("name", format!("Duplicate webhook trigger: {}", hook.name)))),
+                Trigger::Kafka(topic) | Trigger::Nats(topic) => (topic.validate(), None),
+                Trigger::Chat(chat) => (chat.validate(), (!models.insert(chat.model.as_str())).then(|| ("model", format!("Duplicate chat trigger model: {}", chat.model)))),
+            };
+            if let Err(e) = result {
+                report(Diagnostic::new(DiagnosticCode::InvalidTrigger, error_message(e)).at(format!("triggers[{}]", t)));
+            }
+            if let Some((field, message)) = duplicate {
+                report(Diagnostic::new(DiagnosticCode::DuplicateTrigger, message).at(format!("triggers[{}].{}", t, field)));
+            }
+        }
+
+        // Check that MCP tool steps name a server and a tool
+        for (i, step) in self.steps.iter().enumerate() {
+            if let StepConfig::McpTool(config) = &step.config {
+                if config.server.is_empty() || config.tool.is_empty() {
+                    report(Diagnostic::new(DiagnosticCode::IncompleteMcpTool, format!("MCP tool step '{}' needs a server and a tool", step.id)).step(&step.id).at(format!("steps[{}]", i)));
+                }
+            }
+        }
+
+        // Check that web fetches can make progress
+        for (i, step) in self.steps.iter().enumerate() {
+            if let StepConfig::WebFetch(config) = &step.config {
+                if config.concurrency == 0 || config.timeout_seconds == 0 || config.max_bytes == 0 {
+                    report(Diagnostic::new(DiagnosticCode::InvalidWebFetch, format!("Web fetch step '{}' needs a positive concurrency, timeout, and max_bytes", step.id)).step(&step.id).at(format!("steps[{}]", i)));
+                }
+            }
+        }
+
+        // Check that agent steps can act, and name their action tools apart
+        for (i, step) in self.steps.iter().enumerate() {
+            let StepConfig::Agent(config) = &step.config else {
+                continue;
+            };
+            if config.tools.is_empty() || config.max_iterations == 0 {
+                report(Diagnostic::new(DiagnosticCode::InvalidAgent, format!("Agent step '{}' needs at least one tool and one iteration", step.id)).step(&step.id).at(format!("steps[{}]", i)));
+            }
+            let mut names = std::collections::HashSet::new();
+            for (t, tool) in config.tools.iter().enumerate() {
+                if let AgentTool::Action { action, name, .. } = tool {
+                    let name = crate::agent::action_tool_name(action, name.as_deref());
+                    if !names.insert(name.clone()) {
+                        report(Diagnostic::new(DiagnosticCode::InvalidAgent, format!("Agent step '{}' offers two tools named '{}'", step.id, name)).step(&step.id).at(format!("steps[{}].tools[{}]", i, t)).suggest("Give one of the actions another `name`"));
+                    }
+                }
+            }
+        }
+
+        // Check that vector searches query with embeddings of the index's dimension
+        for (i, step) in self.steps.iter().enumerate() {
+            let StepConfig::VectorSearch(config) = &step.config else {
+                continue;
+            };
+            let (Some(declared), Some((embed_step, embed))) = (config.dimensions, self.query_embedding(config)) else {
+                continue;
+            };
+            if let Some(dimensions) = embed.expected_dimensions().filter(|d| *d != declared) {
+                report(Diagnostic::new(DiagnosticCode::DimensionMismatch, format!("Vector search step '{}' expects {}-dimensional vectors but step '{}' embeds with {} dimensions (model '{}')", step.id, declared, embed_step.id, dimensions, embed.model)).step(&step.id).at(format!("steps[{}].dimensions", i)).suggest(format!("Use an embedding model with {} dimensions, or set `dimensions: {}` on step '{}' if its model can shorten embeddings", declared, declared, embed_step.id)));
+            }
+        }
+
+        // Check that keyword and hybrid searches have what they need
+        for (i, step) in self.steps.iter().enumerate() {
+            let StepConfig::VectorSearch(config) = &step.config else {
+                continue;
+            };
+            if config.mode == SearchMode::Hybrid && config.text.is_none() {
+                report(Diagnostic::new(DiagnosticCode::InvalidSearchMode, format!("Hybrid search step '{}' has no keyword text", step.id)).step(&step.id).at(format!("steps[{}]", i)).suggest("Set `text` to the query the keyword search should match"));
+            }
+            if config.alpha.is_some_and(|alpha| !(0.0..=1.0).contains(&alpha)) {
+                report(Diagnostic::new(DiagnosticCode::InvalidSearchMode, format!("Vector search step '{}' has an alpha outside 0.0 to 1.0", step.id)).step(&step.id).at(format!("steps[{}].alpha", i)));
+            }
+            if config.mode != SearchMode::Hybrid && (config.text.is_some() || config.alpha.is_some() || config.fusion.is_some()) {
+                report(Diagnostic::new(DiagnosticCode::InvalidSearchMode, format!("Vector search step '{}' sets hybrid options in {} mode", step.id, config.mode)).step(&step.id).at(format!("steps[{}].mode", i)).suggest("Set `mode: hybrid`, or remove `text`, `alpha`, and `fusion`"));
+            }
+        }
+
+        // Check that vector upserts can make progress
+        for (i, step) in self.steps.iter().enumerate() {
+            let StepConfig::VectorUpsert(config) = &step.config else {
+                continue;
+            };
+            for (field, valid) in [
+                ("batch_size", config.batch_size > 0),
+                ("concurrency", config.concurrency > 0),
+                ("requests_per_second", config.requests_per_second.map_or(true, |rate| rate.is_finite() && rate > 0.0)),
+            ] {
+                if !valid {
+                    report(Diagnostic::new(DiagnosticCode::InvalidUpsertLimit, format!("Vector upsert step '{}' has a {} that is not positive", step.id, field)).step(&step.id).at(format!("steps[{}].{}", i, field)));
+                }
+            }
+        }
+
+        // Check that the result sink can be written to
+        if let Some(sink) = self.outputs.as_ref().and_then(|o| o.sink.as_ref()) {
+            if let Err(e) = sink.validate() {
+                report(Diagnostic::new(DiagnosticCode::InvalidSink, error_message(e)).at("outputs.sink"));
+            }
+        }
+
+        // Check that the artifact store names a location
+        if let Some(Err(e)) = self.artifacts.as_ref().map(ArtifactStoreConfig::validate) {
+            report(Diagnostic::new(DiagnosticCode::InvalidArtifactStore, error_message(e)).at("artifacts"));
+        }
+
+        // Check that fallback steps hang off the step they replace
+        for (i, step) in self.steps.iter().enumerate() {
+            if let Some(FailurePolicy::RunStep(target)) = &step.on_failure {
+                let depends_on_step = self
+                    .get_step(target)
+                    .map(|t| t.depends_on.contains(&step.id))
+                    .unwrap_or(false);
+                if !depends_on_step {
+                    report(Diagnostic::new(DiagnosticCode::InvalidFallback, format!("Step '{}' fallback step '{}' must be a step that depends on it", step.id, target)).step(&step.id).at(format!("steps[{}].on_failure.run_step", i)).suggest(format!("Add '{}' to the depends_on of '{}'", step.id, target)));
+                }
+            }
+        }
+
+        diagnostics
+    }
+
+    /// Why `path`, bound by `step`, cannot be read, with a suggestion.
+    fn binding_problem(&self, step: &Step, path: &str) -> Option<(String, Option<String>)> {
+        let segments: Vec<&str> = path.split('.').collect();
+        if segments.iter().any(|segment| segment.is_empty() || segment.contains(|c: char| c.is_whitespace() || c == '{' || c == '}')) {
+            return Some((format!("'{}' is not a path", path), Some("Write a dotted path such as `steps.fetch.body`, without braces".to_string())));
+        }
+        match segments.as_slice() {
+            [root @ ("inputs" | "run" | "steps")] => Some((format!("'{}' names no value", path), Some(format!("Name a value under it, such as `{}.<name>`", root)))),
+            ["inputs" | "run", _, ..] => None,
+            ["joined", id, ..] if *id == step.id && !step.depends_on_any.is_empty() => None,
+            ["joined", ..] => Some((format!("'{}' is not a join of this step", path), Some(format!("Bind `joined.{}` on a step with `depends_on_any`", step.id)))),
+            ["steps", id, rest @ ..] => {
+                let Some(source) = self.get_step(id) else {
+                    let suggestion = did_you_mean(id, self.steps.iter().map(|s| s.id.as_str()));
+                    return Some((format!("step '{}' does not exist", id), suggestion));
+                };
+                if step.depends_on_any.iter().any(|alternative| alternative == id) && !self.upstream(step, false).contains(id) {
+                    return Some((format!("step '{}' is an alternative that may not complete", id), Some(format!("Bind `joined.{}` to the outputs of whichever alternative completed first", step.id))));
+                }
+                if !self.upstream(step, true).contains(id) {
+                    return Some((format!("step '{}' does not run before it", id), Some(format!("Add '{}' to the depends_on of '{}'", id, step.id))));
+                }
+                let mut declared: Vec<&str> = source.output.iter().map(String::as_str).collect();
+                if let StepConfig::Http(config) = &source.config {
+                    declared.extend(config.extract.keys().map(String::as_str));
+                }
+                match rest.first() {
+                    Some(output) if !source.output.is_empty() && !declared.contains(output) => {
+                        declared.sort();
+                        Some((format!("step '{}' has no output '{}'", id, output), Some(format!("Use one of {}", declared.join(", ")))))
+                    }
+                    _ => None,
+                }
+            }
+            _ => Some((format!("'{}' does not start with steps, inputs, joined, or run", path), Some(format!("Use `inputs.{}` for a workflow input", path)))),
+        }
+    }
+
+    /// IDs of the steps that finish before `step` starts, through `depends_on`
+    /// and, if `alternatives`, `depends_on_any`.
+    fn upstream<'a>(&'a self, step: &'a Step, alternatives: bool) -> std::collections::HashSet<&'a str> {
+        let mut upstream = std::collections::HashSet::new();
+        let mut pending = vec![step];
+        while let Some(step) = pending.pop() {
+            let alternatives = if alternatives { step.depends_on_any.as_slice() } else { &[] };
+            for dependency in step.depends_on.iter().chain(alternatives) {
+                if upstream.insert(dependency.as_str()) {
+                    pending.extend(self.get_step(dependency));
+                }
+            }
+        }
+        upstream
+    }
+
+    /// The embed step whose output a vector search queries with, if any.
+    pub fn query_embedding(&self, search: &VectorSearchConfig) -> Option<(&Step, &EmbedStepConfig)> {
+        if search.mode == SearchMode::Keyword {
+            return None;
+        }
+        crate::lint::referenced_steps(&search.query).find_map(|id| {
+            let step = self.get_step(id)?;
+            match &step.config {
+                StepConfig::Embed(config) => Some((step, config)),
+                _ => None,
+            }
+        })
+    }
+}
+
+/// Why a workflow YAML document could not be loaded.
+pub(crate) enum LoadError {
+    /// The document does not parse as a workflow.
+    Syntax(serde_yaml::Error),
+    /// An include or step template is invalid.
+    Invalid(Box<Diagnostic>),
+}
+
+/// Parses a workflow YAML document, resolving its includes and expanding its
+/// step templates.
+///
+/// Also returns whether the document was rewritten, in which case paths in
+/// the workflow no longer match the source.
+pub(crate) fn load_yaml(yaml: &str, resolver: &dyn IncludeResolver) -> std::result::Result<(Workflow, bool), LoadError> {
+    let mut document: serde_yaml::Value = serde_yaml::from_str(yaml).map_err(LoadError::Syntax)?;
+    if include::document_has_includes(&document) {
+        let resolved = include::resolve(document, resolver).map_err(LoadError::Invalid)?;
+        document = serde_yaml::to_value(resolved).map_err(LoadError::Syntax)?;
+    } else if document.get(step_template::TEMPLATES_FIELD).is_none() && document.get(partials::PARTIALS_DIR_FIELD).is_none() {
+        // Parsed from the source, so errors keep their location
+        return serde_yaml::from_str(yaml).map(|workflow| (workflow, false)).map_err(LoadError::Syntax);
+    }
+    partials::read_dir(&mut document, resolver).map_err(LoadError::Invalid)?;
+    step_template::instantiate(document)
+        .map(|workflow| (workflow, true))
+        .map_err(LoadError::Invalid)
+}
+
+/// Error of a workflow whose includes or step templates cannot be resolved.
+fn template_error(diagnostic: Diagnostic) -> crate::error::OrchestratorError {
+    match diagnostic.code {
+        DiagnosticCode::Parse => crate::error::OrchestratorError::parse(diagnostic.message),
+        _ => crate::error::OrchestratorError::validation(diagnostic.message),
+    }
+}
+
+/// Message of a validation error, without the error kind.
+fn error_message(error: crate::error::OrchestratorError) -> String {
+    match error {
+        crate::error::OrchestratorError::ValidationError(message) => message,
+        other => other.to_string(),
+    }
+}
+
+/// Apply a profile's `steps` overrides (step ID -> overlay) to the serialized steps.
+fn merge_step_overrides(
+    steps: &mut serde_json::Value,
+    overrides: &serde_json::Value,
+    profile: &str,
+) -> crate::error::Result<()> {
+    let serde_json::Value::Object(overrides) = overrides else {
+        return Err(crate::error::OrchestratorError::validation(format!(
+            "Profile '{}' steps must map step IDs to overrides",
+            profile
+        )));
+    };
+
+    for (step_id, overlay) in overrides {
+        let step = steps
+            .as_array_mut()
+            .and_then(|steps| steps.iter_mut().find(|s| s["id"] == step_id.as_str()))
+            .ok_or_else(|| {
+                crate::error::OrchestratorError::validation(format!(
+                    "Profile '{}' overrides unknown step '{}'",
+                    profile, step_id
+                ))
+            })?;
+        deep_merge(step, overlay);
+    }
+    Ok(())
+}
+
+/// Merge `overlay` into `base`, recursing into objects and replacing everything else.
+pub(crate) fn deep_merge(base: &mut serde_json::Value, overlay: &serde_json::Value) {
+    match (base, overlay) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                deep_merge(base.entry(key.clone()).or_insert(serde_json::Value::Null), value);
+            }
+        }
+        (base, overlay) => *base = overlay.clone(),
     }
 }
 
@@ -430,20 +2218,33 @@ steps:
             id: "step1".to_string(),
             step_type: StepType::Llm,
             depends_on: vec![],
+            depends_on_any: Vec::new(),
+            bind: HashMap::new(),
             condition: None,
             config: StepConfig::Llm(LlmStepConfig {
                 provider: "openai".to_string(),
                 model: "gpt-4".to_string(),
                 prompt: "test".to_string(),
+                prompt_ref: None,
                 temperature: None,
                 max_tokens: None,
                 system: None,
                 stream: false,
+                variants: Vec::new(),
+                variant_mode: Default::default(),
+                hedge: None,
+                memory: None,
+                images: Vec::new(),
                 extra: HashMap::new(),
             }),
             output: vec!["result".to_string()],
             timeout_seconds: None,
+            on_failure: None,
             retry: None,
+            guardrails: None,
+            resource_class: None,
+            assert: Vec::new(),
+            allow_skipped_deps: false,
         });
 
         let result = workflow.validate();
@@ -457,20 +2258,33 @@ steps:
             id: "step1".to_string(),
             step_type: StepType::Llm,
             depends_on: vec![],
+            depends_on_any: Vec::new(),
+            bind: HashMap::new(),
             condition: None,
             config: StepConfig::Llm(LlmStepConfig {
                 provider: "openai".to_string(),
                 model: "gpt-4".to_string(),
                 prompt: "test".to_string(),
+                prompt_ref: None,
                 temperature: None,
                 max_tokens: None,
                 system: None,
                 stream: false,
+                variants: Vec::new(),
+                variant_mode: Default::default(),
+                hedge: None,
+                memory: None,
+                images: Vec::new(),
                 extra: HashMap::new(),
             }),
             output: vec![],
             timeout_seconds: None,
+            on_failure: None,
             retry: None,
+            guardrails: None,
+            resource_class: None,
+            assert: Vec::new(),
+            allow_skipped_deps: false,
         };
 
         workflow.steps.push(step.clone());
@@ -487,23 +2301,348 @@ steps:
             id: "step1".to_string(),
             step_type: StepType::Llm,
             depends_on: vec!["nonexistent".to_string()],
+            depends_on_any: Vec::new(),
+            bind: HashMap::new(),
             condition: None,
             config: StepConfig::Llm(LlmStepConfig {
                 provider: "openai".to_string(),
                 model: "gpt-4".to_string(),
                 prompt: "test".to_string(),
+                prompt_ref: None,
                 temperature: None,
                 max_tokens: None,
                 system: None,
                 stream: false,
+                variants: Vec::new(),
+                variant_mode: Default::default(),
+                hedge: None,
+                memory: None,
+                images: Vec::new(),
                 extra: HashMap::new(),
             }),
             output: vec![],
             timeout_seconds: None,
+            on_failure: None,
             retry: None,
+            guardrails: None,
+            resource_class: None,
+            assert: Vec::new(),
+            allow_skipped_deps: false,
         });
 
         let result = workflow.validate();
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_approval_step_parsing_and_validation() {
+        let yaml = r#"
+name: "review-workflow"
+steps:
+  - id: "review"
+    type: "approval"
+    payload: "{{ steps.draft.text }}"
+    message: "Publish this draft?"
+    on_reject: "revise"
+  - id: "revise"
+    type: "transform"
+    depends_on: ["review"]
+    function: "noop"
+    inputs: []
+"#;
+
+        let mut workflow = Workflow::from_yaml(yaml).unwrap();
+        assert!(matches!(
+            &workflow.steps[0].config,
+            StepConfig::Approval(config) if config.on_reject.as_deref() == Some("revise")
+        ));
+        assert!(workflow.validate().is_ok());
+
+        // The rejection branch must depend on the approval step
+        workflow.steps[1].depends_on.clear();
+        assert!(workflow.validate().is_err());
+    }
+
+    #[test]
+    fn test_evaluate_step_parsing_and_validation() {
+        let yaml = r#"
+name: "graded-workflow"
+steps:
+  - id: "grade"
+    type: "evaluate"
+    target: "{{ steps.draft.text }}"
+    reference: "{{ expected }}"
+    threshold: 0.8
+    metrics:
+      - type: "exact_match"
+        ignore_case: true
+      - type: "regex"
+        pattern: "^Dear"
+        weight: 0.5
+    judge:
+      provider: "openai"
+      model: "gpt-4"
+      rubric: "Is the letter polite?"
+    output: ["score", "passed"]
+"#;
+
+        let mut workflow = Workflow::from_yaml(yaml).unwrap();
+        let StepConfig::Evaluate(config) = &workflow.steps[0].config else {
+            panic!("expected evaluate config");
+        };
+        assert_eq!(config.metrics.len(), 2);
+        assert!(matches!(config.metrics[0].kind, EvalMetricKind::ExactMatch { ignore_case: true }));
+        assert_eq!(config.metrics[1].weight, 0.5);
+        assert_eq!(config.judge.as_ref().unwrap().scale, 10);
+        assert!(!config.fail_below_threshold);
+        assert!(workflow.validate().is_ok());
+
+        // An evaluation needs a metric or a judge
+        if let StepConfig::Evaluate(config) = &mut workflow.steps[0].config {
+            config.metrics.clear();
+            config.judge = None;
+        }
+        assert!(workflow.validate().is_err());
+    }
+
+    #[test]
+    fn test_prompt_ref_parsing_and_validation() {
+        let yaml = r#"
+name: "registry-workflow"
+steps:
+  - id: "ask"
+    type: "llm"
+    provider: "openai"
+    model: "gpt-4"
+    prompt_ref: "summarize@v3"
+  - id: "vectorize"
+    type: "embed"
+    provider: "openai"
+    model: "text-embedding-3-small"
+    input: "{{ text }}"
+"#;
+
+        let mut workflow = Workflow::from_yaml(yaml).unwrap();
+        let StepConfig::Llm(config) = &workflow.steps[0].config else {
+            panic!("expected LLM config");
+        };
+        assert_eq!(config.prompt_ref.as_deref(), Some("summarize@v3"));
+        assert!(config.prompt.is_empty());
+        assert!(matches!(workflow.steps[1].config, StepConfig::Embed(_)));
+        assert!(workflow.validate().is_ok());
+
+        if let StepConfig::Llm(config) = &mut workflow.steps[0].config {
+            config.prompt_ref = Some("summarize@next".to_string());
+        }
+        assert!(workflow.validate().is_err());
+    }
+
+    #[test]
+    fn test_failure_policy_parsing_and_validation() {
+        let yaml = r#"
+name: "degrading-workflow"
+steps:
+  - id: "primary"
+    type: "transform"
+    function: "noop"
+    inputs: []
+    on_failure:
+      run_step: "fallback"
+  - id: "fallback"
+    type: "transform"
+    depends_on: ["primary"]
+    function: "noop"
+    inputs: []
+    on_failure: "skip_dependents"
+"#;
+
+        let mut workflow = Workflow::from_yaml(yaml).unwrap();
+        assert_eq!(
+            workflow.steps[0].on_failure,
+            Some(FailurePolicy::RunStep("fallback".to_string()))
+        );
+        assert_eq!(workflow.steps[1].on_failure, Some(FailurePolicy::SkipDependents));
+        let json = serde_json::to_value(&workflow.steps[0]).unwrap();
+        assert_eq!(json["on_failure"], serde_json::json!({"run_step": "fallback"}));
+        assert!(workflow.validate().is_ok());
+
+        // The fallback step must depend on the step it replaces
+        workflow.steps[1].depends_on.clear();
+        assert!(workflow.validate().is_err());
+    }
+
+    #[test]
+    fn test_binding_validation() {
+        let yaml = r#"
+name: "bindings"
+steps:
+  - id: "fetch"
+    type: "http"
+    url: "https://example.com"
+    output: ["body"]
+    extract:
+      title: "$.title"
+  - id: "draft"
+    type: "transform"
+    function: "noop"
+    inputs: []
+  - id: "write"
+    type: "llm"
+    provider: "openai"
+    model: "gpt-4"
+    prompt: "{{ doc }}"
+    depends_on: ["fetch"]
+    bind:
+      doc: "steps.fetch.body"
+      title: "steps.fetch.title"
+      tone: "inputs.tone"
+      headline: "steps.fetch.headline"
+      notes: "steps.draft.text"
+      other: "steps.fecth.body"
+      style: "tone"
+"#;
+
+        let workflow = Workflow::from_yaml(yaml).unwrap();
+        let problems: Vec<_> = workflow
+            .diagnostics()
+            .into_iter()
+            .map(|d| (d.path.unwrap(), d.message))
+            .collect();
+        assert_eq!(
+            problems,
+            [
+                ("steps[2].bind.headline".to_string(), "Binding 'headline' of step 'write': step 'fetch' has no output 'headline'".to_string()),
+                ("steps[2].bind.notes".to_string(), "Binding 'notes' of step 'write': step 'draft' does not run before it".to_string()),
+                ("steps[2].bind.other".to_string(), "Binding 'other' of step 'write': step 'fecth' does not exist".to_string()),
+                ("steps[2].bind.style".to_string(), "Binding 'style' of step 'write': 'tone' does not start with steps, inputs, joined, or run".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_profile_overlays() {
+        let yaml = r#"
+name: "profiled-workflow"
+timeout_seconds: 60
+metadata:
+  team: "search"
+  tags: ["a", "b"]
+steps:
+  - id: "answer"
+    type: "llm"
+    provider: "openai"
+    model: "gpt-4"
+    prompt: "Answer {{ inputs.question }}"
+    temperature: 0.2
+    output: ["answer"]
+profiles:
+  dev:
+    timeout_seconds: 10
+    metadata:
+      tags: ["dev"]
+    steps:
+      answer:
+        model: "gpt-3.5-turbo"
+  broken:
+    steps:
+      answer:
+        depends_on: ["missing"]
+  typo:
+    steps:
+      anwser:
+        model: "gpt-4o"
+"#;
+
+        let workflow = Workflow::from_yaml(yaml).unwrap();
+        let dev = workflow.with_profile("dev").unwrap();
+        assert_eq!(dev.timeout_seconds, Some(10));
+        assert!(dev.profiles.is_empty());
+
+        // Objects merge, arrays are replaced
+        assert_eq!(dev.metadata["team"], "search");
+        assert_eq!(dev.metadata["tags"], serde_json::json!(["dev"]));
+
+        // Step overrides only touch the keys they set
+        match &dev.steps[0].config {
+            StepConfig::Llm(config) => {
+                assert_eq!(config.model, "gpt-3.5-turbo");
+                assert_eq!(config.provider, "openai");
+                assert_eq!(config.temperature, Some(0.2));
+            }
+            other => panic!("Unexpected config: {:?}", other),
+        }
+
+        // The merged workflow must still be valid
+        assert!(workflow.with_profile("broken").is_err());
+        let err = workflow.with_profile("typo").unwrap_err();
+        assert!(err.to_string().contains("anwser"));
+        let err = workflow.with_profile("prod").unwrap_err();
+        assert!(err.to_string().contains("broken, dev, typo"));
+    }
+
+    #[test]
+    fn test_webhook_trigger_parsing_and_validation() {
+        let yaml = r#"
+name: "hooks"
+triggers:
+  - type: webhook
+    name: github-push
+    secret: github/webhook_secret
+    inputs:
+      branch: "{{ payload.ref }}"
+  - type: webhook
+    name: slack-events
+    secret: slack/signing_secret
+    scheme: slack
+steps:
+  - id: "step1"
+    type: "transform"
+    function: "noop"
+    inputs: []
+"#;
+
+        let mut workflow = Workflow::from_yaml(yaml).unwrap();
+        assert!(workflow.validate().is_ok());
+        match &workflow.triggers[1] {
+            Trigger::Webhook(hook) => assert_eq!(hook.scheme, crate::webhook::SignatureScheme::Slack),
+            other => panic!("Unexpected trigger: {:?}", other),
+        }
+
+        let duplicate = workflow.triggers[0].clone();
+        workflow.triggers.push(duplicate);
+        let err = workflow.validate().unwrap_err();
+        assert!(err.to_string().contains("github-push"));
+    }
+
+    #[test]
+    fn test_json_schema_validates_workflows() {
+        let schema = serde_json::to_value(Workflow::json_schema()).unwrap();
+        for (step_type, config) in STEP_CONFIG_SCHEMAS {
+            assert!(serde_json::from_value::<StepType>(serde_json::json!(step_type)).is_ok());
+            assert!(schema["definitions"][config].is_object(), "{} is not in the schema", config);
+        }
+        let validator = jsonschema::validator_for(&schema).unwrap();
+
+        let examples = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../../examples");
+        for entry in std::fs::read_dir(examples).unwrap() {
+            let path = entry.unwrap().path();
+            let workflow: serde_json::Value = serde_yaml::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+            let errors: Vec<String> = validator.iter_errors(&workflow).map(|e| e.to_string()).collect();
+            assert!(errors.is_empty(), "{}: {:?}", path.display(), errors);
+        }
+
+        // An LLM step configured like a transform
+        let mismatched: serde_json::Value = serde_yaml::from_str(
+            r#"
+name: "mismatched"
+steps:
+  - id: "step1"
+    type: "llm"
+    function: "noop"
+    inputs: []
+"#,
+        )
+        .unwrap();
+        assert!(!validator.is_valid(&mismatched));
+    }
 }
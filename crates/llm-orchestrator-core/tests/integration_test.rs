@@ -54,6 +54,8 @@ impl LLMProvider for MockProvider {
             text,
             model: request.model,
             tokens_used: Some(15),
+            usage: None,
+            tool_calls: Vec::new(),
             metadata,
         })
     }
@@ -72,20 +74,33 @@ async fn test_simple_workflow_execution() {
         id: "greet".to_string(),
         step_type: StepType::Llm,
         depends_on: vec![],
+        depends_on_any: Vec::new(),
+        bind: HashMap::new(),
         condition: None,
         config: StepConfig::Llm(LlmStepConfig {
             provider: "mock".to_string(),
             model: "gpt-4".to_string(),
             prompt: "Say hello".to_string(),
+            prompt_ref: None,
             temperature: Some(0.7),
             max_tokens: Some(100),
             system: None,
             stream: false,
+            variants: Vec::new(),
+            variant_mode: Default::default(),
+            hedge: None,
+            memory: None,
+            images: Vec::new(),
             extra: HashMap::new(),
         }),
         output: vec!["greeting".to_string()],
         timeout_seconds: None,
+        on_failure: None,
         retry: None,
+        guardrails: None,
+        resource_class: None,
+        assert: Vec::new(),
+        allow_skipped_deps: false,
     });
 
     // Create inputs
@@ -122,40 +137,66 @@ async fn test_workflow_with_dependencies() {
         id: "step1".to_string(),
         step_type: StepType::Llm,
         depends_on: vec![],
+        depends_on_any: Vec::new(),
+        bind: HashMap::new(),
         condition: None,
         config: StepConfig::Llm(LlmStepConfig {
             provider: "mock".to_string(),
             model: "gpt-4".to_string(),
             prompt: "Step 1".to_string(),
+            prompt_ref: None,
             temperature: None,
             max_tokens: Some(50),
             system: None,
             stream: false,
+            variants: Vec::new(),
+            variant_mode: Default::default(),
+            hedge: None,
+            memory: None,
+            images: Vec::new(),
             extra: HashMap::new(),
         }),
         output: vec!["result1".to_string()],
         timeout_seconds: None,
+        on_failure: None,
         retry: None,
+        guardrails: None,
+        resource_class: None,
+        assert: Vec::new(),
+        allow_skipped_deps: false,
     });
 
     workflow.steps.push(Step {
         id: "step2".to_string(),
         step_type: StepType::Llm,
         depends_on: vec!["step1".to_string()],
+        depends_on_any: Vec::new(),
+        bind: HashMap::new(),
         condition: None,
         config: StepConfig::Llm(LlmStepConfig {
             provider: "mock".to_string(),
             model: "gpt-3.5-turbo".to_string(),
             prompt: "Step 2 using {{steps.step1.result1}}".to_string(),
+            prompt_ref: None,
             temperature: None,
             max_tokens: Some(50),
             system: None,
             stream: false,
+            variants: Vec::new(),
+            variant_mode: Default::default(),
+            hedge: None,
+            memory: None,
+            images: Vec::new(),
             extra: HashMap::new(),
         }),
         output: vec!["result2".to_string()],
         timeout_seconds: None,
+        on_failure: None,
         retry: None,
+        guardrails: None,
+        resource_class: None,
+        assert: Vec::new(),
+        allow_skipped_deps: false,
     });
 
     let inputs = HashMap::new();
@@ -187,20 +228,33 @@ async fn test_workflow_with_parallel_steps() {
             id: format!("parallel_{}", i),
             step_type: StepType::Llm,
             depends_on: vec![],
+            depends_on_any: Vec::new(),
+            bind: HashMap::new(),
             condition: None,
             config: StepConfig::Llm(LlmStepConfig {
                 provider: "mock".to_string(),
                 model: "gpt-4".to_string(),
                 prompt: format!("Parallel step {}", i),
+                prompt_ref: None,
                 temperature: None,
                 max_tokens: Some(50),
                 system: None,
                 stream: false,
+                variants: Vec::new(),
+                variant_mode: Default::default(),
+                hedge: None,
+                memory: None,
+                images: Vec::new(),
                 extra: HashMap::new(),
             }),
             output: vec![format!("result{}", i)],
             timeout_seconds: None,
+            on_failure: None,
             retry: None,
+            guardrails: None,
+            resource_class: None,
+            assert: Vec::new(),
+            allow_skipped_deps: false,
         });
     }
 
@@ -233,20 +287,33 @@ async fn test_workflow_with_condition() {
         id: "conditional".to_string(),
         step_type: StepType::Llm,
         depends_on: vec![],
+        depends_on_any: Vec::new(),
+        bind: HashMap::new(),
         condition: Some("inputs.execute == true".to_string()),
         config: StepConfig::Llm(LlmStepConfig {
             provider: "mock".to_string(),
             model: "gpt-4".to_string(),
             prompt: "Conditional step".to_string(),
+            prompt_ref: None,
             temperature: None,
             max_tokens: Some(50),
             system: None,
             stream: false,
+            variants: Vec::new(),
+            variant_mode: Default::default(),
+            hedge: None,
+            memory: None,
+            images: Vec::new(),
             extra: HashMap::new(),
         }),
         output: vec!["result".to_string()],
         timeout_seconds: None,
+        on_failure: None,
         retry: None,
+        guardrails: None,
+        resource_class: None,
+        assert: Vec::new(),
+        allow_skipped_deps: false,
     });
 
     // Test with condition true
@@ -2,23 +2,37 @@
 // SPDX-License-Identifier: Apache-2.0
 
 //! Anthropic (Claude) provider implementation.
-
-use crate::traits::{CompletionRequest, CompletionResponse, LLMProvider, ProviderError};
+//!
+//! Rate-limited (429), overloaded (529), and server error responses, as well
+//! as timeouts and connection failures, are retried with exponential backoff,
+//! waiting as long as the `retry-after` header asks when one is sent.
+
+use crate::middleware;
+use crate::retry::{self, RetryConfig};
+use crate::trace_context;
+use crate::traits::{
+    CompletionRequest, CompletionResponse, ConversationTurn, ImageInput, LLMProvider, ProviderError, ToolCall,
+};
 use async_trait::async_trait;
+use reqwest::header::HeaderMap;
 use reqwest::{Client, StatusCode};
 use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
 use std::time::Duration;
+use tracing::warn;
 
 /// Anthropic API provider.
 pub struct AnthropicProvider {
     /// HTTP client.
     client: Client,
-    /// API key.
-    api_key: String,
+    /// API key, shared so a rotated key can be swapped in place.
+    api_key: Arc<RwLock<String>>,
     /// API base URL.
     base_url: String,
     /// Default API version.
     api_version: String,
+    /// Retry behavior for failed requests.
+    retry: RetryConfig,
 }
 
 /// Anthropic messages request.
@@ -37,13 +51,41 @@ struct MessagesRequest {
     top_k: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     stop_sequences: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tools: Vec<Tool>,
 }
 
 /// Message in the conversation.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize)]
 struct Message {
     role: String,
-    content: String,
+    content: MessageContent,
+}
+
+/// Plain text, or content blocks for images and tool use.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+enum MessageContent {
+    Text(String),
+    Blocks(Vec<RequestBlock>),
+}
+
+/// Content block in a request.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum RequestBlock {
+    Text { text: String },
+    Image { source: ImageInput },
+    ToolUse { id: String, name: String, input: serde_json::Value },
+    ToolResult { tool_use_id: String, content: String },
+}
+
+/// Tool offered to the model.
+#[derive(Debug, Clone, Serialize)]
+struct Tool {
+    name: String,
+    description: String,
+    input_schema: serde_json::Value,
 }
 
 /// Anthropic messages response.
@@ -65,11 +107,12 @@ struct MessagesResponse {
 
 /// Content block in response.
 #[derive(Debug, Deserialize)]
-struct ContentBlock {
-    #[serde(rename = "type")]
-    #[allow(dead_code)]
-    content_type: String,
-    text: String,
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ContentBlock {
+    Text { text: String },
+    ToolUse { id: String, name: String, input: serde_json::Value },
+    #[serde(other)]
+    Other,
 }
 
 /// Token usage information.
@@ -79,6 +122,25 @@ struct Usage {
     output_tokens: u32,
 }
 
+/// A failed request attempt.
+struct AttemptError {
+    error: ProviderError,
+    /// Whether sending the request again may succeed.
+    retryable: bool,
+    /// Delay asked for by the `retry-after` header.
+    retry_after: Option<Duration>,
+}
+
+impl AttemptError {
+    fn fatal(error: ProviderError) -> Self {
+        Self {
+            error,
+            retryable: false,
+            retry_after: None,
+        }
+    }
+}
+
 /// Anthropic error response.
 #[derive(Debug, Deserialize)]
 struct AnthropicErrorResponse {
@@ -102,9 +164,12 @@ impl AnthropicProvider {
                 if status == 401 || status == 403 {
                     ProviderError::AuthError(err.to_string())
                 } else if status == 429 {
-                    ProviderError::RateLimitExceeded
+                    ProviderError::RateLimitExceeded { retry_after: None }
                 } else {
-                    ProviderError::HttpError(err.to_string())
+                    ProviderError::Api {
+                        status: status.as_u16(),
+                        message: err.to_string(),
+                    }
                 }
             } else {
                 ProviderError::HttpError(err.to_string())
@@ -144,12 +209,19 @@ impl AnthropicProvider {
 
         Ok(Self {
             client,
-            api_key,
+            api_key: Arc::new(RwLock::new(api_key)),
             base_url,
             api_version,
+            retry: RetryConfig::default(),
         })
     }
 
+    /// Sets the retry behavior for failed requests.
+    pub fn with_retry_config(mut self, retry: RetryConfig) -> Self {
+        self.retry = retry;
+        self
+    }
+
     /// Creates a new Anthropic provider from environment variable.
     ///
     /// Reads the API key from `ANTHROPIC_API_KEY` environment variable.
@@ -200,13 +272,75 @@ impl AnthropicProvider {
         Self::new(secret.value)
     }
 
+    /// Creates a new Anthropic provider that follows rotations of its API key.
+    ///
+    /// The key is read through the cache and replaced whenever the cache
+    /// observes a new version, so long-running workers pick up a rotated key
+    /// without restarting.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # #[cfg(feature = "secrets")]
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// use llm_orchestrator_providers::AnthropicProvider;
+    /// use llm_orchestrator_secrets::{EnvSecretStore, SecretCache};
+    /// use std::sync::Arc;
+    ///
+    /// let cache = Arc::new(SecretCache::with_default_ttl(Arc::new(EnvSecretStore::new())));
+    /// let provider = AnthropicProvider::from_secret_cache(&cache, "anthropic/api_key").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "secrets")]
+    pub async fn from_secret_cache<S>(
+        cache: &llm_orchestrator_secrets::SecretCache<S>,
+        secret_key: &str,
+    ) -> Result<Self, ProviderError>
+    where
+        S: llm_orchestrator_secrets::SecretStore + ?Sized,
+    {
+        let secret = cache
+            .get(secret_key)
+            .await
+            .map_err(|e| ProviderError::InvalidRequest(format!("Failed to retrieve secret: {}", e)))?;
+
+        let provider = Self::new(secret.value)?;
+        let api_key = Arc::downgrade(&provider.api_key);
+        cache.subscribe(secret_key, move |secret| {
+            if let Some(api_key) = api_key.upgrade() {
+                *api_key.write().unwrap_or_else(|e| e.into_inner()) = secret.value.clone();
+            }
+        });
+
+        Ok(provider)
+    }
+
+    /// Returns the current API key.
+    fn api_key(&self) -> String {
+        self.api_key.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
     /// Converts a provider completion request to Anthropic format.
     fn to_anthropic_request(&self, request: &CompletionRequest) -> MessagesRequest {
-        // Build messages array
-        let messages = vec![Message {
-            role: "user".to_string(),
-            content: request.prompt.clone(),
-        }];
+        // Build messages array, earlier turns of the conversation first
+        let mut messages = history_messages(&request.history);
+        if !request.images.is_empty() {
+            // Images go before the text that asks about them
+            let images = request.images.iter().map(|image| RequestBlock::Image { source: image.clone() });
+            let text = (!request.prompt.is_empty()).then(|| RequestBlock::Text {
+                text: request.prompt.clone(),
+            });
+            messages.push(Message {
+                role: "user".to_string(),
+                content: MessageContent::Blocks(images.chain(text).collect()),
+            });
+        } else if !request.prompt.is_empty() {
+            messages.push(Message {
+                role: "user".to_string(),
+                content: MessageContent::Text(request.prompt.clone()),
+            });
+        }
 
         // Extract optional parameters from extra
         let top_p = request
@@ -240,6 +374,85 @@ impl AnthropicProvider {
             top_p,
             top_k,
             stop_sequences,
+            tools: request
+                .tools
+                .iter()
+                .map(|tool| Tool {
+                    name: tool.name.clone(),
+                    description: tool.description.clone(),
+                    input_schema: tool.parameters.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns true for statuses worth retrying: timeouts, lock conflicts,
+    /// rate limits, and server errors including 529 (overloaded).
+    fn is_retryable_status(status: StatusCode) -> bool {
+        matches!(status.as_u16(), 408 | 409 | 429) || status.is_server_error()
+    }
+
+    /// Sends one request.
+    async fn send(&self, request: &MessagesRequest, headers: &HeaderMap) -> Result<MessagesResponse, AttemptError> {
+        let response = self
+            .client
+            .post(format!("{}/messages", self.base_url))
+            .header("x-api-key", self.api_key())
+            .header("anthropic-version", &self.api_version)
+            .header("Content-Type", "application/json")
+            .json(request)
+            .headers(trace_context::headers())
+            .headers(headers.clone())
+            .send()
+            .await
+            .map_err(|e| AttemptError {
+                retryable: e.is_timeout() || e.is_connect(),
+                retry_after: None,
+                error: Self::convert_reqwest_error(e),
+            })?;
+
+        let status = response.status();
+        let retry_after = retry::retry_after(response.headers());
+        let body = response
+            .text()
+            .await
+            .unwrap_or_else(|_| String::from("Failed to read response body"));
+
+        // Handle errors
+        if !status.is_success() {
+            return Err(AttemptError {
+                error: self.parse_error(status, &body).with_retry_after(retry_after),
+                retryable: Self::is_retryable_status(status),
+                retry_after,
+            });
+        }
+
+        serde_json::from_str(&body).map_err(|e| AttemptError::fatal(e.into()))
+    }
+
+    /// Sends a request, retrying transient failures.
+    async fn send_with_retry(
+        &self,
+        request: &MessagesRequest,
+        headers: &HeaderMap,
+    ) -> Result<MessagesResponse, ProviderError> {
+        let mut retries = 0;
+        loop {
+            match self.send(request, headers).await {
+                Ok(response) => return Ok(response),
+                Err(failure) if failure.retryable && retries < self.retry.max_retries => {
+                    retries += 1;
+                    let delay = failure.retry_after.unwrap_or_else(|| self.retry.backoff(retries));
+                    warn!(
+                        retry = retries,
+                        delay_ms = delay.as_millis() as u64,
+                        error = %failure.error,
+                        "Retrying Anthropic request"
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                Err(failure) => return Err(failure.error),
+            }
         }
     }
 
@@ -251,7 +464,7 @@ impl AnthropicProvider {
 
             // Detect rate limiting
             if status == StatusCode::TOO_MANY_REQUESTS || error.error_type == "rate_limit_error" {
-                return ProviderError::RateLimitExceeded;
+                return ProviderError::RateLimitExceeded { retry_after: None };
             }
 
             // Detect authentication errors
@@ -269,17 +482,63 @@ impl AnthropicProvider {
             }
 
             // Generic API error
-            return ProviderError::ProviderSpecific(format!(
-                "[{}] {}: {}",
-                status.as_u16(),
-                error.error_type,
-                error.message
-            ));
+            return ProviderError::Api {
+                status: status.as_u16(),
+                message: format!("{}: {}", error.error_type, error.message),
+            };
         }
 
         // Fallback to generic error
-        ProviderError::HttpError(format!("[{}] {}", status.as_u16(), body))
+        ProviderError::Api {
+            status: status.as_u16(),
+            message: body.to_string(),
+        }
+    }
+}
+
+/// Messages of earlier conversation turns.
+///
+/// Tool calls become `tool_use` blocks of the assistant message, and the
+/// results of consecutive `tool` turns one user message of `tool_result` blocks.
+fn history_messages(history: &[ConversationTurn]) -> Vec<Message> {
+    let mut messages: Vec<Message> = Vec::new();
+    for turn in history {
+        if let Some(call_id) = &turn.tool_call_id {
+            let result = RequestBlock::ToolResult {
+                tool_use_id: call_id.clone(),
+                content: turn.content.clone(),
+            };
+            match messages.last_mut() {
+                Some(Message {
+                    role,
+                    content: MessageContent::Blocks(blocks),
+                }) if role == "user" => blocks.push(result),
+                _ => messages.push(Message {
+                    role: "user".to_string(),
+                    content: MessageContent::Blocks(vec![result]),
+                }),
+            }
+            continue;
+        }
+        let content = if turn.tool_calls.is_empty() {
+            MessageContent::Text(turn.content.clone())
+        } else {
+            let text = (!turn.content.is_empty()).then(|| RequestBlock::Text {
+                text: turn.content.clone(),
+            });
+            let calls = turn.tool_calls.iter().map(|call| RequestBlock::ToolUse {
+                id: call.id.clone(),
+                name: call.name.clone(),
+                input: call.arguments.clone(),
+            });
+            MessageContent::Blocks(text.into_iter().chain(calls).collect())
+        };
+        messages.push(Message {
+            role: turn.role.clone(),
+            content,
+        });
     }
+    messages
 }
 
 #[async_trait]
@@ -289,57 +548,32 @@ impl LLMProvider for AnthropicProvider {
         request: CompletionRequest,
     ) -> Result<CompletionResponse, ProviderError> {
         let anthropic_request = self.to_anthropic_request(&request);
-
-        // Make API request
-        let response = self
-            .client
-            .post(format!("{}/messages", self.base_url))
-            .header("x-api-key", &self.api_key)
-            .header("anthropic-version", &self.api_version)
-            .header("Content-Type", "application/json")
-            .json(&anthropic_request)
-            .send()
-            .await
-            .map_err(Self::convert_reqwest_error)?;
-
-        let status = response.status();
-        let body = response
-            .text()
-            .await
-            .unwrap_or_else(|_| String::from("Failed to read response body"));
-
-        // Handle errors
-        if !status.is_success() {
-            return Err(self.parse_error(status, &body));
+        let headers = middleware::request_headers(&request.headers);
+        let messages_response = self.send_with_retry(&anthropic_request, &headers).await?;
+
+        // Extract text and tool calls from content blocks
+        let mut text = String::new();
+        let mut tool_calls = Vec::new();
+        for block in messages_response.content {
+            match block {
+                ContentBlock::Text { text: part } => text.push_str(&part),
+                ContentBlock::ToolUse { id, name, input } => tool_calls.push(ToolCall {
+                    id,
+                    name,
+                    arguments: input,
+                }),
+                ContentBlock::Other => {}
+            }
         }
 
-        // Parse success response
-        let messages_response: MessagesResponse = serde_json::from_str(&body)?;
-
-        // Extract text from content blocks
-        let text = messages_response
-            .content
-            .iter()
-            .map(|block| block.text.clone())
-            .collect::<Vec<_>>()
-            .join("");
-
-        // Build metadata with usage and stop reason
+        // Build metadata with stop reason
         let mut metadata = std::collections::HashMap::new();
-        metadata.insert(
-            "usage".to_string(),
-            serde_json::json!({
-                "input_tokens": messages_response.usage.input_tokens,
-                "output_tokens": messages_response.usage.output_tokens,
-                "total_tokens": messages_response.usage.input_tokens + messages_response.usage.output_tokens,
-            }),
-        );
-
         if let Some(stop_reason) = &messages_response.stop_reason {
             metadata.insert("stop_reason".to_string(), serde_json::json!(stop_reason));
         }
 
         metadata.insert("id".to_string(), serde_json::json!(messages_response.id));
+        metadata.insert("model_snapshot".to_string(), serde_json::json!(messages_response.model));
 
         Ok(CompletionResponse {
             text,
@@ -347,6 +581,11 @@ impl LLMProvider for AnthropicProvider {
             tokens_used: Some(
                 messages_response.usage.input_tokens + messages_response.usage.output_tokens,
             ),
+            usage: Some(crate::traits::Usage::new(
+                u64::from(messages_response.usage.input_tokens),
+                u64::from(messages_response.usage.output_tokens),
+            )),
+            tool_calls,
             metadata,
         })
     }
@@ -362,9 +601,13 @@ impl LLMProvider for AnthropicProvider {
             model: "claude-3-haiku-20240307".to_string(),
             prompt: "Hi".to_string(),
             system: None,
+            history: Vec::new(),
+            images: Vec::new(),
             temperature: None,
             max_tokens: Some(5),
+            tools: Vec::new(),
             extra: std::collections::HashMap::new(),
+            headers: std::collections::HashMap::new(),
         };
 
         self.complete(test_request).await?;
@@ -402,9 +645,13 @@ mod tests {
             model: "claude-3-opus-20240229".to_string(),
             prompt: "Hello, world!".to_string(),
             system: Some("You are a helpful assistant".to_string()),
+            history: Vec::new(),
+            images: Vec::new(),
             temperature: Some(0.7),
             max_tokens: Some(100),
+            tools: Vec::new(),
             extra: std::collections::HashMap::new(),
+            headers: std::collections::HashMap::new(),
         };
 
         let anthropic_req = provider.to_anthropic_request(&request);
@@ -412,7 +659,7 @@ mod tests {
         assert_eq!(anthropic_req.model, "claude-3-opus-20240229");
         assert_eq!(anthropic_req.messages.len(), 1);
         assert_eq!(anthropic_req.messages[0].role, "user");
-        assert_eq!(anthropic_req.messages[0].content, "Hello, world!");
+        assert_eq!(anthropic_req.messages[0].content, MessageContent::Text("Hello, world!".to_string()));
         assert_eq!(
             anthropic_req.system,
             Some("You are a helpful assistant".to_string())
@@ -421,6 +668,28 @@ mod tests {
         assert_eq!(anthropic_req.max_tokens, 100);
     }
 
+    #[test]
+    fn test_to_anthropic_request_with_images() {
+        let provider = AnthropicProvider::new("test-key".to_string()).unwrap();
+        let request = CompletionRequest {
+            prompt: "Transcribe the receipt.".to_string(),
+            images: vec![ImageInput::Base64 {
+                media_type: "image/jpeg".to_string(),
+                data: "/9j/4AAQ".to_string(),
+            }],
+            ..test_request()
+        };
+
+        let body = serde_json::to_value(provider.to_anthropic_request(&request).messages).unwrap();
+        assert_eq!(
+            body[0]["content"],
+            serde_json::json!([
+                {"type": "image", "source": {"type": "base64", "media_type": "image/jpeg", "data": "/9j/4AAQ"}},
+                {"type": "text", "text": "Transcribe the receipt."},
+            ])
+        );
+    }
+
     #[test]
     fn test_parse_rate_limit_error() {
         let provider = AnthropicProvider::new("test-key".to_string()).unwrap();
@@ -435,7 +704,7 @@ mod tests {
         let error = provider.parse_error(StatusCode::TOO_MANY_REQUESTS, error_json);
 
         match error {
-            ProviderError::RateLimitExceeded => {} // Success
+            ProviderError::RateLimitExceeded { .. } => {} // Success
             _ => panic!("Expected RateLimitExceeded error"),
         }
     }
@@ -477,4 +746,167 @@ mod tests {
             _ => panic!("Expected InvalidRequest error"),
         }
     }
+
+    const SUCCESS_BODY: &str = r#"{
+        "id": "msg_1",
+        "type": "message",
+        "role": "assistant",
+        "content": [{"type": "text", "text": "Hello"}],
+        "model": "claude-3-haiku-20240307",
+        "stop_reason": "end_turn",
+        "stop_sequence": null,
+        "usage": {"input_tokens": 3, "output_tokens": 1}
+    }"#;
+
+    fn test_request() -> CompletionRequest {
+        CompletionRequest {
+            model: "claude-3-haiku-20240307".to_string(),
+            prompt: "Hi".to_string(),
+            system: None,
+            history: Vec::new(),
+            images: Vec::new(),
+            temperature: None,
+            max_tokens: Some(5),
+            tools: Vec::new(),
+            extra: std::collections::HashMap::new(),
+            headers: std::collections::HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tool_use() {
+        let mut server = mockito::Server::new_async().await;
+        let call = ToolCall {
+            id: "toolu_1".to_string(),
+            name: "search".to_string(),
+            arguments: serde_json::json!({"query": "rust"}),
+        };
+        // Results of the earlier calls go back as one user message of tool_result blocks
+        let mock = server
+            .mock("POST", "/messages")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "messages": [
+                    {"role": "user", "content": "Find rust news"},
+                    {"role": "assistant", "content": [{"type": "tool_use", "id": "toolu_1", "name": "search", "input": {"query": "rust"}}]},
+                    {"role": "user", "content": [{"type": "tool_result", "tool_use_id": "toolu_1", "content": "3 results"}]},
+                ],
+                "tools": [{"name": "search", "description": "Search the news", "input_schema": {"type": "object"}}],
+            })))
+            .with_status(200)
+            .with_body(r#"{
+                "id": "msg_2", "type": "message", "role": "assistant", "model": "claude-3-haiku-20240307",
+                "content": [{"type": "text", "text": "Reading more."}, {"type": "tool_use", "id": "toolu_2", "name": "read", "input": {"id": 7}}],
+                "stop_reason": "tool_use", "stop_sequence": null,
+                "usage": {"input_tokens": 30, "output_tokens": 12}
+            }"#)
+            .create_async()
+            .await;
+
+        let request = CompletionRequest {
+            prompt: String::new(),
+            history: vec![
+                ConversationTurn::new("user", "Find rust news"),
+                ConversationTurn {
+                    tool_calls: vec![call],
+                    ..ConversationTurn::new("assistant", "")
+                },
+                ConversationTurn::tool_result("toolu_1", "3 results"),
+            ],
+            tools: vec![crate::traits::ToolSpec {
+                name: "search".to_string(),
+                description: "Search the news".to_string(),
+                parameters: serde_json::json!({"type": "object"}),
+            }],
+            ..test_request()
+        };
+        let response = test_provider(&server, 0).complete(request).await.unwrap();
+        mock.assert_async().await;
+        assert_eq!(response.text, "Reading more.");
+        assert_eq!(response.tool_calls.len(), 1);
+        assert_eq!(response.tool_calls[0].name, "read");
+        assert_eq!(response.tool_calls[0].arguments, serde_json::json!({"id": 7}));
+    }
+
+    fn test_provider(server: &mockito::Server, max_retries: u32) -> AnthropicProvider {
+        AnthropicProvider::with_base_url("test-key".to_string(), server.url(), "2023-06-01".to_string())
+            .unwrap()
+            .with_retry_config(RetryConfig::new(max_retries).with_initial_delay(Duration::from_millis(1)))
+    }
+
+    #[tokio::test]
+    async fn test_retries_rate_limit_after_retry_after_delay() {
+        let mut server = mockito::Server::new_async().await;
+        let rate_limited = server
+            .mock("POST", "/messages")
+            .with_status(429)
+            .with_header("retry-after", "0")
+            .with_body(r#"{"error": {"type": "rate_limit_error", "message": "Slow down"}}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let success = server
+            .mock("POST", "/messages")
+            .with_status(200)
+            .with_body(SUCCESS_BODY)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let response = test_provider(&server, 2).complete(test_request()).await.unwrap();
+        assert_eq!(response.text, "Hello");
+        assert_eq!(response.usage, Some(crate::traits::Usage::new(3, 1)));
+
+        rate_limited.assert_async().await;
+        success.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_overloaded_retries_are_bounded() {
+        let mut server = mockito::Server::new_async().await;
+        let overloaded = server
+            .mock("POST", "/messages")
+            .with_status(529)
+            .with_body(r#"{"error": {"type": "overloaded_error", "message": "Overloaded"}}"#)
+            .expect(3)
+            .create_async()
+            .await;
+
+        let error = test_provider(&server, 2).complete(test_request()).await.unwrap_err();
+        assert!(error.to_string().contains("overloaded_error"));
+        overloaded.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_sends_request_headers() {
+        let mut server = mockito::Server::new_async().await;
+        let success = server
+            .mock("POST", "/messages")
+            .match_header("x-team", "search")
+            .with_status(200)
+            .with_body(SUCCESS_BODY)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let mut request = test_request();
+        request.headers.insert("x-team".to_string(), "search".to_string());
+        test_provider(&server, 0).complete(request).await.unwrap();
+        success.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_invalid_request_is_not_retried() {
+        let mut server = mockito::Server::new_async().await;
+        let invalid = server
+            .mock("POST", "/messages")
+            .with_status(400)
+            .with_body(r#"{"error": {"type": "invalid_request_error", "message": "Bad model"}}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let error = test_provider(&server, 3).complete(test_request()).await.unwrap_err();
+        assert!(matches!(error, ProviderError::InvalidRequest(_)));
+        invalid.assert_async().await;
+    }
 }
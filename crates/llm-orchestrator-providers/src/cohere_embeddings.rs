@@ -9,6 +9,8 @@
 //! - Input types: search_document, search_query, classification, clustering
 //! - Automatic retries with exponential backoff
 
+use crate::retry;
+use crate::trace_context;
 use crate::traits::*;
 use async_trait::async_trait;
 use reqwest::Client;
@@ -90,7 +92,11 @@ impl CohereEmbeddingProvider {
 
         for attempt in 0..=self.max_retries {
             if attempt > 0 {
-                let delay = Duration::from_millis(INITIAL_RETRY_DELAY_MS * 2_u64.pow(attempt - 1));
+                // Wait as long as a rate limit response asked, else back off exponentially
+                let delay = last_error
+                    .as_ref()
+                    .and_then(ProviderError::retry_after)
+                    .unwrap_or_else(|| Duration::from_millis(INITIAL_RETRY_DELAY_MS * 2_u64.pow(attempt - 1)));
                 warn!("Retry attempt {} after {}ms", attempt, delay.as_millis());
                 tokio::time::sleep(delay).await;
             }
@@ -103,6 +109,7 @@ impl CohereEmbeddingProvider {
                 .header("Authorization", format!("Bearer {}", self.api_key))
                 .header("Content-Type", "application/json")
                 .json(&api_request)
+                .headers(trace_context::headers())
                 .send()
                 .await
             {
@@ -114,6 +121,8 @@ impl CohereEmbeddingProvider {
             };
 
             let status = response.status();
+
+            let retry_after = retry::retry_after(response.headers());
             if !status.is_success() {
                 let error_text = response
                     .text()
@@ -124,16 +133,22 @@ impl CohereEmbeddingProvider {
                     401 => ProviderError::AuthError(error_text),
                     429 => {
                         // Rate limit - always retry
-                        last_error = Some(ProviderError::RateLimitExceeded);
+                        last_error = Some(ProviderError::RateLimitExceeded { retry_after });
                         continue;
                     }
                     400..=499 => ProviderError::InvalidRequest(error_text),
                     500..=599 => {
                         // Server error - retry
-                        last_error = Some(ProviderError::ProviderSpecific(error_text));
+                        last_error = Some(ProviderError::Api {
+                            status: status.as_u16(),
+                            message: error_text,
+                        });
                         continue;
                     }
-                    _ => ProviderError::ProviderSpecific(error_text),
+                    _ => ProviderError::Api {
+                        status: status.as_u16(),
+                        message: error_text,
+                    },
                 };
 
                 return Err(error);
@@ -401,7 +416,7 @@ mod tests {
         let mock = server
             .mock("POST", "/embed")
             .match_body(mockito::Matcher::PartialJsonString(
-                r#""input_type":"search_query""#.to_string(),
+                r#"{"input_type":"search_query"}"#.to_string(),
             ))
             .with_status(200)
             .with_body(
@@ -570,7 +585,7 @@ mod tests {
         let mock = server
             .mock("POST", "/embed")
             .match_body(mockito::Matcher::PartialJsonString(
-                r#""model":"embed-multilingual-v3.0""#.to_string(),
+                r#"{"model":"embed-multilingual-v3.0"}"#.to_string(),
             ))
             .with_status(200)
             .with_body(
@@ -0,0 +1,180 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! ElevenLabs text-to-speech provider implementation.
+//!
+//! Supports:
+//! - Models: eleven_multilingual_v2, eleven_turbo_v2_5, eleven_flash_v2_5, and others
+//! - Voices by ID, from the ElevenLabs voice library
+//! - Formats: mp3, opus, pcm, ulaw, or an ElevenLabs `output_format` such as `mp3_22050_32`
+
+use crate::retry::{self, RetryConfig};
+use crate::traits::*;
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::time::Duration;
+use tracing::debug;
+
+/// ElevenLabs text-to-speech provider.
+pub struct ElevenLabsProvider {
+    client: Client,
+    api_key: String,
+    base_url: String,
+    retry: RetryConfig,
+}
+
+impl ElevenLabsProvider {
+    /// Create a new ElevenLabs provider.
+    pub fn new(api_key: String) -> Result<Self, ProviderError> {
+        Self::with_base_url(api_key, "https://api.elevenlabs.io".to_string())
+    }
+
+    /// Create a provider with a custom base URL.
+    pub fn with_base_url(api_key: String, base_url: String) -> Result<Self, ProviderError> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(120))
+            .build()
+            .map_err(|e| ProviderError::HttpError(format!("Failed to create HTTP client: {}", e)))?;
+
+        Ok(Self {
+            client,
+            api_key,
+            base_url,
+            retry: RetryConfig::default(),
+        })
+    }
+
+    /// Sets the retry behavior for failed requests.
+    pub fn with_retry_config(mut self, retry: RetryConfig) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Create from environment variables.
+    pub fn from_env() -> Result<Self, ProviderError> {
+        let api_key = std::env::var("ELEVENLABS_API_KEY")
+            .map_err(|_| ProviderError::AuthError("ELEVENLABS_API_KEY not set".to_string()))?;
+        Self::new(api_key)
+    }
+}
+
+/// ElevenLabs `output_format` of an audio format.
+fn output_format(format: &str) -> Result<&str, ProviderError> {
+    match format {
+        "mp3" => Ok("mp3_44100_128"),
+        "opus" => Ok("opus_48000_128"),
+        "pcm" => Ok("pcm_44100"),
+        "ulaw" => Ok("ulaw_8000"),
+        // Already an ElevenLabs format, with its sample rate
+        format if format.contains('_') => Ok(format),
+        format => Err(ProviderError::InvalidRequest(format!(
+            "Unsupported audio format '{}', expected mp3, opus, pcm, ulaw, or an ElevenLabs output format",
+            format
+        ))),
+    }
+}
+
+#[async_trait]
+impl SpeechProvider for ElevenLabsProvider {
+    async fn synthesize(&self, request: SpeechRequest) -> Result<SpeechResponse, ProviderError> {
+        let output_format = output_format(&request.format)?;
+
+        debug!(
+            "Synthesizing {} characters with model {} and voice {}",
+            request.text.chars().count(),
+            request.model,
+            request.voice
+        );
+
+        let mut body = json!({
+            "text": request.text,
+            "model_id": request.model,
+        });
+        for (key, value) in &request.extra {
+            body[key] = value.clone();
+        }
+        if let Some(speed) = request.speed {
+            if !body["voice_settings"].is_object() {
+                body["voice_settings"] = Value::Object(Default::default());
+            }
+            body["voice_settings"]["speed"] = json!(speed);
+        }
+
+        let url = format!("{}/v1/text-to-speech/{}", self.base_url, request.voice);
+        let response = retry::send_with_retry(&self.retry, "ElevenLabs", || {
+            self.client
+                .post(&url)
+                .query(&[("output_format", output_format)])
+                .header("xi-api-key", &self.api_key)
+                .json(&body)
+        })
+        .await?;
+
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(String::from);
+        let audio = response.bytes().await.map_err(|e| ProviderError::HttpError(e.to_string()))?;
+
+        Ok(SpeechResponse {
+            audio: audio.to_vec(),
+            content_type,
+            model: request.model,
+        })
+    }
+
+    fn name(&self) -> &str {
+        "elevenlabs"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Matcher;
+    use std::collections::HashMap;
+
+    fn request(format: &str) -> SpeechRequest {
+        SpeechRequest {
+            model: "eleven_multilingual_v2".to_string(),
+            text: "Bonjour à tous.".to_string(),
+            voice: "21m00Tcm4TlvDq8ikWAM".to_string(),
+            format: format.to_string(),
+            speed: Some(1.1),
+            extra: HashMap::from([("voice_settings".to_string(), json!({"stability": 0.5}))]),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_synthesize() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/v1/text-to-speech/21m00Tcm4TlvDq8ikWAM")
+            .match_query(Matcher::UrlEncoded("output_format".to_string(), "opus_48000_128".to_string()))
+            .match_header("xi-api-key", "test-key")
+            .match_body(Matcher::Json(json!({
+                "text": "Bonjour à tous.",
+                "model_id": "eleven_multilingual_v2",
+                "voice_settings": {"stability": 0.5, "speed": 1.100000023841858},
+            })))
+            .with_header("content-type", "audio/ogg")
+            .with_body("OggS")
+            .create_async()
+            .await;
+        let provider = ElevenLabsProvider::with_base_url("test-key".to_string(), server.url()).unwrap();
+
+        let response = provider.synthesize(request("opus")).await.unwrap();
+        mock.assert_async().await;
+        assert_eq!(response.audio, b"OggS");
+        assert_eq!(response.content_type.as_deref(), Some("audio/ogg"));
+    }
+
+    #[test]
+    fn test_output_format() {
+        assert_eq!(output_format("mp3").unwrap(), "mp3_44100_128");
+        assert_eq!(output_format("mp3_22050_32").unwrap(), "mp3_22050_32");
+        assert!(output_format("wav").is_err());
+    }
+}
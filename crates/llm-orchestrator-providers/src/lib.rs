@@ -11,6 +11,13 @@ pub mod openai;
 pub mod openai_embeddings;
 pub mod cohere_embeddings;
 
+// Transcription providers
+pub mod openai_transcription;
+
+// Speech providers
+pub mod openai_speech;
+pub mod elevenlabs;
+
 // Vector database clients
 pub mod pinecone;
 pub mod weaviate;
@@ -19,19 +26,39 @@ pub mod qdrant;
 // Traits
 pub mod traits;
 
+// Retry settings
+pub mod retry;
+
+// Trace context propagation
+pub mod trace_context;
+
+// Request/response hooks
+pub mod middleware;
+
+// Form bodies for file uploads
+mod multipart;
+
 // Re-exports
 pub use anthropic::AnthropicProvider;
 pub use openai::OpenAIProvider;
 pub use openai_embeddings::OpenAIEmbeddingProvider;
 pub use cohere_embeddings::CohereEmbeddingProvider;
+pub use openai_transcription::OpenAITranscriptionProvider;
+pub use openai_speech::OpenAISpeechProvider;
+pub use elevenlabs::ElevenLabsProvider;
 pub use pinecone::PineconeClient;
 pub use weaviate::WeaviateClient;
-pub use qdrant::QdrantClient;
+pub use qdrant::{QdrantClient, ScrollPage, ScrollRequest, ScrolledPoint};
+pub use middleware::{MiddlewareProvider, ProviderMiddleware};
+pub use retry::RetryConfig;
 pub use traits::{
-    CompletionRequest, CompletionResponse, LLMProvider, ProviderError,
-    EmbeddingProvider, EmbeddingRequest, EmbeddingResponse, EmbeddingInput,
-    VectorSearchProvider, VectorSearchRequest, VectorSearchResponse, SearchResult,
-    UpsertRequest, UpsertResponse, VectorRecord,
+    CompletionRequest, CompletionResponse, ConversationTurn, ImageInput, LLMProvider, ProviderError, ToolCall, ToolSpec, Usage,
+    EmbeddingProvider, EmbeddingRequest, EmbeddingResponse, EmbeddingInput, embedding_dimensions,
+    TranscriptionProvider, TranscriptionRequest, TranscriptionResponse, TranscriptSegment,
+    SpeechProvider, SpeechRequest, SpeechResponse,
+    VectorSearchProvider, VectorSearchRequest, VectorSearchResponse, SearchResult, SearchMode, HybridFusion, IndexStats,
+    CreateIndexRequest, DistanceMetric, MetadataFieldType,
+    UpsertRequest, UpsertResponse, VectorRecord, FetchRequest, FetchResponse, ListIdsRequest, ListIdsResponse,
     DeleteRequest, DeleteResponse,
 };
 
@@ -0,0 +1,352 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Middleware stacked on LLM providers.
+//!
+//! A [`ProviderMiddleware`] sees every request before it is sent and every
+//! outcome after the provider answers, without changing the provider itself.
+//! Wrap any [`LLMProvider`] in a [`MiddlewareProvider`] and add layers:
+//!
+//! ```no_run
+//! use llm_orchestrator_providers::middleware::{JsonlCapture, MiddlewareProvider, StaticHeaders};
+//! use llm_orchestrator_providers::OpenAIProvider;
+//! use std::sync::Arc;
+//!
+//! # fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let provider = MiddlewareProvider::new(Arc::new(OpenAIProvider::new("sk-...".to_string())?))
+//!     .layer(Arc::new(StaticHeaders::new().header("x-team", "search")))
+//!     .layer(Arc::new(JsonlCapture::new("captures/openai.jsonl")));
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Layers run `before_request` in the order they were added and
+//! `after_response` in reverse order, like nested wrappers.
+
+use crate::traits::{CompletionRequest, CompletionResponse, LLMProvider, ProviderError};
+use async_trait::async_trait;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tracing::warn;
+
+/// Hooks around the requests of an LLM provider.
+#[async_trait]
+pub trait ProviderMiddleware: Send + Sync {
+    /// Called before the request is sent.
+    ///
+    /// The request may be modified, for example to add `headers`. Returning an
+    /// error fails the call without reaching the provider.
+    async fn before_request(&self, _provider: &str, _request: &mut CompletionRequest) -> Result<(), ProviderError> {
+        Ok(())
+    }
+
+    /// Called with the outcome of a request and how long the provider took.
+    async fn after_response(
+        &self,
+        _provider: &str,
+        _request: &CompletionRequest,
+        _result: &Result<CompletionResponse, ProviderError>,
+        _latency: Duration,
+    ) {
+    }
+}
+
+/// LLM provider running middleware around another provider.
+pub struct MiddlewareProvider {
+    inner: Arc<dyn LLMProvider>,
+    layers: Vec<Arc<dyn ProviderMiddleware>>,
+}
+
+impl MiddlewareProvider {
+    /// Wraps a provider without any middleware.
+    pub fn new(inner: Arc<dyn LLMProvider>) -> Self {
+        Self {
+            inner,
+            layers: Vec::new(),
+        }
+    }
+
+    /// Adds a middleware layer inside the ones added before it.
+    pub fn layer(mut self, middleware: Arc<dyn ProviderMiddleware>) -> Self {
+        self.layers.push(middleware);
+        self
+    }
+}
+
+#[async_trait]
+impl LLMProvider for MiddlewareProvider {
+    async fn complete(&self, mut request: CompletionRequest) -> Result<CompletionResponse, ProviderError> {
+        let name = self.inner.name();
+        for layer in &self.layers {
+            layer.before_request(name, &mut request).await?;
+        }
+
+        let start = Instant::now();
+        let result = self.inner.complete(request.clone()).await;
+        let latency = start.elapsed();
+
+        for layer in self.layers.iter().rev() {
+            layer.after_response(name, &request, &result, latency).await;
+        }
+        result
+    }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn health_check(&self) -> Result<(), ProviderError> {
+        self.inner.health_check().await
+    }
+}
+
+/// Adds fixed HTTP headers to every request.
+#[derive(Debug, Clone, Default)]
+pub struct StaticHeaders {
+    headers: HashMap<String, String>,
+}
+
+impl StaticHeaders {
+    /// Creates a layer without headers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a header, replacing any earlier value for the same name.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.insert(name.into(), value.into());
+        self
+    }
+}
+
+#[async_trait]
+impl ProviderMiddleware for StaticHeaders {
+    async fn before_request(&self, _provider: &str, request: &mut CompletionRequest) -> Result<(), ProviderError> {
+        request
+            .headers
+            .extend(self.headers.iter().map(|(name, value)| (name.clone(), value.clone())));
+        Ok(())
+    }
+}
+
+/// Appends every request and its outcome to a JSON Lines file.
+///
+/// Each line holds the provider name, the request, the response or error,
+/// and the latency in milliseconds, which makes the file usable both for
+/// debugging and as a prompt/completion dataset for evaluations. Failing to
+/// write a line is logged and does not fail the call.
+#[derive(Debug)]
+pub struct JsonlCapture {
+    path: PathBuf,
+    lock: Mutex<()>,
+}
+
+impl JsonlCapture {
+    /// Captures to `path`, creating the file and its directory if needed.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            lock: Mutex::new(()),
+        }
+    }
+
+    async fn append(&self, line: &[u8]) -> std::io::Result<()> {
+        // Keep concurrent calls from interleaving their lines
+        let _guard = self.lock.lock().await;
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await?;
+        file.write_all(line).await
+    }
+}
+
+#[async_trait]
+impl ProviderMiddleware for JsonlCapture {
+    async fn after_response(
+        &self,
+        provider: &str,
+        request: &CompletionRequest,
+        result: &Result<CompletionResponse, ProviderError>,
+        latency: Duration,
+    ) {
+        let record = serde_json::json!({
+            "provider": provider,
+            "request": request,
+            "response": result.as_ref().ok(),
+            "error": result.as_ref().err().map(|e| e.to_string()),
+            "latency_ms": latency.as_millis() as u64,
+        });
+        let mut line = record.to_string().into_bytes();
+        line.push(b'\n');
+        if let Err(e) = self.append(&line).await {
+            warn!(path = %self.path.display(), error = %e, "Failed to capture provider request");
+        }
+    }
+}
+
+/// Converts the `headers` of a request into HTTP headers, skipping invalid ones.
+pub(crate) fn request_headers(headers: &HashMap<String, String>) -> HeaderMap {
+    let mut map = HeaderMap::new();
+    for (name, value) in headers {
+        match (HeaderName::from_bytes(name.as_bytes()), HeaderValue::from_str(value)) {
+            (Ok(name), Ok(value)) => {
+                map.insert(name, value);
+            }
+            _ => warn!(header = %name, "Skipping invalid request header"),
+        }
+    }
+    map
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex as StdMutex;
+
+    /// Replies with the request's headers, sorted.
+    struct HeaderEcho;
+
+    #[async_trait]
+    impl LLMProvider for HeaderEcho {
+        async fn complete(&self, request: CompletionRequest) -> Result<CompletionResponse, ProviderError> {
+            let mut headers: Vec<String> = request.headers.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+            headers.sort();
+            Ok(CompletionResponse {
+                text: headers.join(","),
+                model: request.model,
+                tokens_used: None,
+                usage: None,
+                tool_calls: Vec::new(),
+                metadata: HashMap::new(),
+            })
+        }
+
+        fn name(&self) -> &str {
+            "echo"
+        }
+    }
+
+    /// Records the order its hooks are called in.
+    struct Recorder {
+        id: &'static str,
+        calls: Arc<StdMutex<Vec<String>>>,
+        reject: bool,
+    }
+
+    #[async_trait]
+    impl ProviderMiddleware for Recorder {
+        async fn before_request(&self, provider: &str, _request: &mut CompletionRequest) -> Result<(), ProviderError> {
+            self.calls.lock().unwrap().push(format!("before {} {}", self.id, provider));
+            if self.reject {
+                return Err(ProviderError::InvalidRequest("rejected".to_string()));
+            }
+            Ok(())
+        }
+
+        async fn after_response(
+            &self,
+            _provider: &str,
+            _request: &CompletionRequest,
+            result: &Result<CompletionResponse, ProviderError>,
+            _latency: Duration,
+        ) {
+            let text = result.as_ref().map(|r| r.text.clone()).unwrap_or_default();
+            self.calls.lock().unwrap().push(format!("after {} {}", self.id, text));
+        }
+    }
+
+    fn request() -> CompletionRequest {
+        CompletionRequest {
+            model: "m".to_string(),
+            prompt: "Hi".to_string(),
+            system: None,
+            history: Vec::new(),
+            images: Vec::new(),
+            temperature: None,
+            max_tokens: None,
+            tools: Vec::new(),
+            extra: HashMap::new(),
+            headers: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_layers_wrap_the_provider_in_order() {
+        let calls = Arc::new(StdMutex::new(Vec::new()));
+        let recorder = |id, reject| {
+            Arc::new(Recorder {
+                id,
+                calls: calls.clone(),
+                reject,
+            })
+        };
+        let provider = MiddlewareProvider::new(Arc::new(HeaderEcho))
+            .layer(recorder("outer", false))
+            .layer(Arc::new(StaticHeaders::new().header("x-team", "search")))
+            .layer(recorder("inner", false));
+
+        let response = provider.complete(request()).await.unwrap();
+        assert_eq!(response.text, "x-team=search");
+        assert_eq!(provider.name(), "echo");
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec![
+                "before outer echo",
+                "before inner echo",
+                "after inner x-team=search",
+                "after outer x-team=search",
+            ]
+        );
+
+        // A failing before_request stops the call before the provider
+        calls.lock().unwrap().clear();
+        let provider = MiddlewareProvider::new(Arc::new(HeaderEcho)).layer(recorder("guard", true));
+        assert!(matches!(provider.complete(request()).await, Err(ProviderError::InvalidRequest(_))));
+        assert_eq!(*calls.lock().unwrap(), vec!["before guard echo"]);
+    }
+
+    #[tokio::test]
+    async fn test_jsonl_capture_appends_requests() {
+        let path = std::env::temp_dir()
+            .join(format!("llm-orchestrator-{}", uuid::Uuid::new_v4()))
+            .join("capture.jsonl");
+        let provider = MiddlewareProvider::new(Arc::new(HeaderEcho))
+            .layer(Arc::new(StaticHeaders::new().header("authorization", "secret")))
+            .layer(Arc::new(JsonlCapture::new(&path)));
+
+        provider.complete(request()).await.unwrap();
+        provider.complete(request()).await.unwrap();
+
+        let contents = tokio::fs::read_to_string(&path).await.unwrap();
+        let lines: Vec<serde_json::Value> = contents.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["provider"], "echo");
+        assert_eq!(lines[0]["request"]["prompt"], "Hi");
+        assert_eq!(lines[0]["response"]["text"], "authorization=secret");
+        assert!(lines[0]["error"].is_null());
+        assert!(!contents.contains("\"headers\""));
+
+        tokio::fs::remove_dir_all(path.parent().unwrap()).await.unwrap();
+    }
+
+    #[test]
+    fn test_request_headers_skip_invalid_names() {
+        let headers = HashMap::from([
+            ("x-team".to_string(), "search".to_string()),
+            ("bad header".to_string(), "x".to_string()),
+        ]);
+        let map = request_headers(&headers);
+        assert_eq!(map.len(), 1);
+        assert_eq!(map["x-team"], "search");
+    }
+}
@@ -0,0 +1,105 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! `multipart/form-data` request bodies.
+//!
+//! Built by hand: the few forms providers take do not warrant reqwest's
+//! multipart support and the MIME database it pulls in.
+
+/// A form being built.
+pub(crate) struct Form {
+    boundary: String,
+    body: Vec<u8>,
+}
+
+impl Form {
+    /// An empty form.
+    pub(crate) fn new() -> Self {
+        Self {
+            boundary: format!("llm-orchestrator-{}", uuid::Uuid::new_v4().simple()),
+            body: Vec::new(),
+        }
+    }
+
+    /// Adds a text field.
+    pub(crate) fn text(mut self, name: &str, value: &str) -> Self {
+        self.part_header(name, None, None);
+        self.body.extend_from_slice(value.as_bytes());
+        self.body.extend_from_slice(b"\r\n");
+        self
+    }
+
+    /// Adds a file field.
+    pub(crate) fn file(mut self, name: &str, file_name: &str, content_type: &str, bytes: &[u8]) -> Self {
+        self.part_header(name, Some(file_name), Some(content_type));
+        self.body.extend_from_slice(bytes);
+        self.body.extend_from_slice(b"\r\n");
+        self
+    }
+
+    /// Value of the request's `Content-Type` header.
+    pub(crate) fn content_type(&self) -> String {
+        format!("multipart/form-data; boundary={}", self.boundary)
+    }
+
+    /// The request body.
+    pub(crate) fn finish(mut self) -> Vec<u8> {
+        self.body.extend_from_slice(format!("--{}--\r\n", self.boundary).as_bytes());
+        self.body
+    }
+
+    fn part_header(&mut self, name: &str, file_name: Option<&str>, content_type: Option<&str>) {
+        let mut header = format!("--{}\r\nContent-Disposition: form-data; name=\"{}\"", self.boundary, quote(name));
+        if let Some(file_name) = file_name {
+            header.push_str(&format!("; filename=\"{}\"", quote(file_name)));
+        }
+        if let Some(content_type) = content_type {
+            header.push_str(&format!("\r\nContent-Type: {}", content_type));
+        }
+        header.push_str("\r\n\r\n");
+        self.body.extend_from_slice(header.as_bytes());
+    }
+}
+
+/// Escapes a quoted header parameter as browsers do.
+fn quote(value: &str) -> String {
+    value.replace('"', "%22").replace('\r', "%0D").replace('\n', "%0A")
+}
+
+/// Media type of an audio file, from its extension.
+pub(crate) fn audio_content_type(file_name: &str) -> &'static str {
+    let extension = file_name.rsplit_once('.').map(|(_, extension)| extension.to_ascii_lowercase());
+    match extension.as_deref() {
+        Some("mp3" | "mpeg" | "mpga") => "audio/mpeg",
+        Some("mp4" | "m4a") => "audio/mp4",
+        Some("wav") => "audio/wav",
+        Some("webm") => "audio/webm",
+        Some("ogg" | "oga" | "opus") => "audio/ogg",
+        Some("flac") => "audio/flac",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_form_body() {
+        let form = Form::new().text("model", "whisper-1").file("file", "a\"b.wav", "audio/wav", b"RIFF");
+        let boundary = form.boundary.clone();
+        assert_eq!(form.content_type(), format!("multipart/form-data; boundary={}", boundary));
+        let body = String::from_utf8(form.finish()).unwrap();
+        assert_eq!(
+            body,
+            format!(
+                "--{b}\r\nContent-Disposition: form-data; name=\"model\"\r\n\r\nwhisper-1\r\n\
+                 --{b}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"a%22b.wav\"\r\nContent-Type: audio/wav\r\n\r\nRIFF\r\n\
+                 --{b}--\r\n",
+                b = boundary
+            )
+        );
+        assert_eq!(audio_content_type("meeting.M4A"), "audio/mp4");
+        assert_eq!(audio_content_type("notes"), "application/octet-stream");
+    }
+}
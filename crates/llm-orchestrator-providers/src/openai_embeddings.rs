@@ -9,6 +9,8 @@
 //! - Dimension reduction: optional parameter for text-embedding-3-* models
 //! - Automatic retries with exponential backoff
 
+use crate::retry;
+use crate::trace_context;
 use crate::traits::*;
 use async_trait::async_trait;
 use reqwest::Client;
@@ -72,7 +74,11 @@ impl OpenAIEmbeddingProvider {
 
         for attempt in 0..=self.max_retries {
             if attempt > 0 {
-                let delay = Duration::from_millis(INITIAL_RETRY_DELAY_MS * 2_u64.pow(attempt - 1));
+                // Wait as long as a rate limit response asked, else back off exponentially
+                let delay = last_error
+                    .as_ref()
+                    .and_then(ProviderError::retry_after)
+                    .unwrap_or_else(|| Duration::from_millis(INITIAL_RETRY_DELAY_MS * 2_u64.pow(attempt - 1)));
                 warn!("Retry attempt {} after {}ms", attempt, delay.as_millis());
                 tokio::time::sleep(delay).await;
             }
@@ -85,6 +91,7 @@ impl OpenAIEmbeddingProvider {
                 .header("Authorization", format!("Bearer {}", self.api_key))
                 .header("Content-Type", "application/json")
                 .json(&api_request)
+                .headers(trace_context::headers())
                 .send()
                 .await
             {
@@ -96,6 +103,8 @@ impl OpenAIEmbeddingProvider {
             };
 
             let status = response.status();
+
+            let retry_after = retry::retry_after(response.headers());
             if !status.is_success() {
                 let error_text = response
                     .text()
@@ -106,16 +115,22 @@ impl OpenAIEmbeddingProvider {
                     401 => ProviderError::AuthError(error_text),
                     429 => {
                         // Rate limit - always retry
-                        last_error = Some(ProviderError::RateLimitExceeded);
+                        last_error = Some(ProviderError::RateLimitExceeded { retry_after });
                         continue;
                     }
                     400..=499 => ProviderError::InvalidRequest(error_text),
                     500..=599 => {
                         // Server error - retry
-                        last_error = Some(ProviderError::ProviderSpecific(error_text));
+                        last_error = Some(ProviderError::Api {
+                            status: status.as_u16(),
+                            message: error_text,
+                        });
                         continue;
                     }
-                    _ => ProviderError::ProviderSpecific(error_text),
+                    _ => ProviderError::Api {
+                        status: status.as_u16(),
+                        message: error_text,
+                    },
                 };
 
                 return Err(error);
@@ -516,6 +531,34 @@ mod tests {
         mock_success.assert_async().await;
     }
 
+    #[tokio::test]
+    async fn test_rate_limit_carries_retry_after() {
+        let mut server = Server::new_async().await;
+        let _mock = server
+            .mock("POST", "/embeddings")
+            .with_status(429)
+            .with_header("retry-after", "7")
+            .with_body(r#"{"error": {"message": "Rate limit exceeded"}}"#)
+            .create_async()
+            .await;
+
+        let provider = OpenAIEmbeddingProvider::with_base_url("test-key".to_string(), server.url())
+            .unwrap()
+            .with_max_retries(0);
+
+        let request = EmbeddingRequest {
+            model: "text-embedding-3-small".to_string(),
+            input: EmbeddingInput::Single {
+                input: "test".to_string(),
+            },
+            dimensions: None,
+            extra: HashMap::new(),
+        };
+
+        let err = provider.embed(request).await.unwrap_err();
+        assert_eq!(err.retry_after(), Some(Duration::from_secs(7)));
+    }
+
     #[tokio::test]
     async fn test_batch_size_validation() {
         let provider = OpenAIEmbeddingProvider::new("test-key".to_string()).unwrap();
@@ -0,0 +1,195 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! OpenAI text-to-speech provider implementation.
+//!
+//! Supports:
+//! - Models: tts-1, tts-1-hd, gpt-4o-mini-tts
+//! - Voices: alloy, ash, coral, echo, fable, nova, onyx, sage, shimmer, and others
+//! - Formats: mp3, opus, aac, flac, wav, pcm
+//! - Up to 4096 characters of text per request
+
+use crate::retry::{self, RetryConfig};
+use crate::traits::*;
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::Duration;
+use tracing::debug;
+
+/// Longest text the OpenAI speech API accepts, in characters.
+pub const OPENAI_MAX_SPEECH_CHARS: usize = 4096;
+
+/// Audio formats the OpenAI speech API returns.
+const FORMATS: [&str; 6] = ["mp3", "opus", "aac", "flac", "wav", "pcm"];
+
+/// OpenAI text-to-speech provider.
+pub struct OpenAISpeechProvider {
+    client: Client,
+    api_key: String,
+    base_url: String,
+    retry: RetryConfig,
+}
+
+impl OpenAISpeechProvider {
+    /// Create a new OpenAI speech provider.
+    pub fn new(api_key: String) -> Result<Self, ProviderError> {
+        Self::with_base_url(api_key, "https://api.openai.com/v1".to_string())
+    }
+
+    /// Create a provider with a custom base URL.
+    pub fn with_base_url(api_key: String, base_url: String) -> Result<Self, ProviderError> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(120))
+            .build()
+            .map_err(|e| ProviderError::HttpError(format!("Failed to create HTTP client: {}", e)))?;
+
+        Ok(Self {
+            client,
+            api_key,
+            base_url,
+            retry: RetryConfig::default(),
+        })
+    }
+
+    /// Sets the retry behavior for failed requests.
+    pub fn with_retry_config(mut self, retry: RetryConfig) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Create from environment variables.
+    pub fn from_env() -> Result<Self, ProviderError> {
+        let api_key = std::env::var("OPENAI_API_KEY")
+            .map_err(|_| ProviderError::AuthError("OPENAI_API_KEY not set".to_string()))?;
+        Self::new(api_key)
+    }
+}
+
+#[async_trait]
+impl SpeechProvider for OpenAISpeechProvider {
+    async fn synthesize(&self, request: SpeechRequest) -> Result<SpeechResponse, ProviderError> {
+        let characters = request.text.chars().count();
+        if characters > OPENAI_MAX_SPEECH_CHARS {
+            return Err(ProviderError::InvalidRequest(format!(
+                "Text of {} characters exceeds OpenAI maximum of {}",
+                characters, OPENAI_MAX_SPEECH_CHARS
+            )));
+        }
+        if !FORMATS.contains(&request.format.as_str()) {
+            return Err(ProviderError::InvalidRequest(format!(
+                "Unsupported audio format '{}', expected one of: {}",
+                request.format,
+                FORMATS.join(", ")
+            )));
+        }
+
+        debug!(
+            "Synthesizing {} characters with model {} and voice {}",
+            characters, request.model, request.voice
+        );
+
+        let api_request = OpenAISpeechRequest {
+            model: &request.model,
+            input: &request.text,
+            voice: &request.voice,
+            response_format: &request.format,
+            speed: request.speed,
+            extra: &request.extra,
+        };
+        let url = format!("{}/audio/speech", self.base_url);
+        let response = retry::send_with_retry(&self.retry, "OpenAI speech", || {
+            self.client
+                .post(&url)
+                .header("Authorization", format!("Bearer {}", self.api_key))
+                .json(&api_request)
+        })
+        .await?;
+
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(String::from);
+        let audio = response.bytes().await.map_err(|e| ProviderError::HttpError(e.to_string()))?;
+
+        Ok(SpeechResponse {
+            audio: audio.to_vec(),
+            content_type,
+            model: request.model,
+        })
+    }
+
+    fn name(&self) -> &str {
+        "openai_speech"
+    }
+}
+
+// OpenAI-specific request types
+
+#[derive(Debug, Serialize)]
+struct OpenAISpeechRequest<'a> {
+    model: &'a str,
+    input: &'a str,
+    voice: &'a str,
+    response_format: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    speed: Option<f32>,
+    #[serde(flatten)]
+    extra: &'a HashMap<String, serde_json::Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Matcher;
+
+    fn request(text: &str, format: &str) -> SpeechRequest {
+        SpeechRequest {
+            model: "tts-1".to_string(),
+            text: text.to_string(),
+            voice: "alloy".to_string(),
+            format: format.to_string(),
+            speed: Some(1.25),
+            extra: HashMap::from([("instructions".to_string(), serde_json::json!("Cheerful"))]),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_synthesize() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/audio/speech")
+            .match_header("authorization", "Bearer test-key")
+            .match_body(Matcher::Json(serde_json::json!({
+                "model": "tts-1",
+                "input": "Your meeting starts in five minutes.",
+                "voice": "alloy",
+                "response_format": "mp3",
+                "speed": 1.25,
+                "instructions": "Cheerful",
+            })))
+            .with_header("content-type", "audio/mpeg")
+            .with_body("ID3frames")
+            .create_async()
+            .await;
+        let provider = OpenAISpeechProvider::with_base_url("test-key".to_string(), server.url()).unwrap();
+
+        let response = provider
+            .synthesize(request("Your meeting starts in five minutes.", "mp3"))
+            .await
+            .unwrap();
+        mock.assert_async().await;
+        assert_eq!(response.audio, b"ID3frames");
+        assert_eq!(response.content_type.as_deref(), Some("audio/mpeg"));
+    }
+
+    #[tokio::test]
+    async fn test_synthesize_rejects_bad_requests() {
+        let provider = OpenAISpeechProvider::new("test-key".to_string()).unwrap();
+        let long = "a".repeat(OPENAI_MAX_SPEECH_CHARS + 1);
+        assert!(matches!(provider.synthesize(request(&long, "mp3")).await, Err(ProviderError::InvalidRequest(_))));
+        assert!(matches!(provider.synthesize(request("Hi", "ogg")).await, Err(ProviderError::InvalidRequest(_))));
+    }
+}
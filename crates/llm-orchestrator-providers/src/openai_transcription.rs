@@ -0,0 +1,316 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! OpenAI transcription provider implementation.
+//!
+//! Supports:
+//! - Models: whisper-1, gpt-4o-transcribe, gpt-4o-mini-transcribe
+//! - Segment timestamps from whisper-1; the other models return text only
+//! - Audio files up to 25 MB
+//! - Automatic retries with exponential backoff
+
+use crate::multipart::{audio_content_type, Form};
+use crate::retry;
+use crate::trace_context;
+use crate::traits::*;
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Deserialize;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// Largest audio file the OpenAI transcription API accepts.
+pub const OPENAI_MAX_AUDIO_BYTES: usize = 25 * 1024 * 1024;
+
+/// Default retry configuration.
+const MAX_RETRIES: u32 = 3;
+const INITIAL_RETRY_DELAY_MS: u64 = 1000;
+
+/// OpenAI transcription provider.
+pub struct OpenAITranscriptionProvider {
+    client: Client,
+    api_key: String,
+    base_url: String,
+    max_retries: u32,
+}
+
+impl OpenAITranscriptionProvider {
+    /// Create a new OpenAI transcription provider.
+    pub fn new(api_key: String) -> Result<Self, ProviderError> {
+        Self::with_base_url(api_key, "https://api.openai.com/v1".to_string())
+    }
+
+    /// Create a provider with a custom base URL.
+    pub fn with_base_url(api_key: String, base_url: String) -> Result<Self, ProviderError> {
+        // Long recordings take minutes to transcribe
+        let client = Client::builder()
+            .timeout(Duration::from_secs(600))
+            .build()
+            .map_err(|e| ProviderError::HttpError(format!("Failed to create HTTP client: {}", e)))?;
+
+        Ok(Self {
+            client,
+            api_key,
+            base_url,
+            max_retries: MAX_RETRIES,
+        })
+    }
+
+    /// Set maximum number of retries for failed requests.
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Create from environment variables.
+    pub fn from_env() -> Result<Self, ProviderError> {
+        let api_key = std::env::var("OPENAI_API_KEY")
+            .map_err(|_| ProviderError::AuthError("OPENAI_API_KEY not set".to_string()))?;
+        Self::new(api_key)
+    }
+
+    /// Builds the form of a request.
+    fn form(request: &TranscriptionRequest) -> Form {
+        // Only whisper-1 reports segment timestamps
+        let verbose = request.model.starts_with("whisper");
+        let mut form = Form::new()
+            .text("model", &request.model)
+            .text("response_format", if verbose { "verbose_json" } else { "json" });
+        if verbose {
+            form = form.text("timestamp_granularities[]", "segment");
+        }
+        if let Some(language) = &request.language {
+            form = form.text("language", language);
+        }
+        if let Some(prompt) = &request.prompt {
+            form = form.text("prompt", prompt);
+        }
+        for (key, value) in &request.extra {
+            let value = match value {
+                serde_json::Value::String(value) => value.clone(),
+                other => other.to_string(),
+            };
+            form = form.text(key, &value);
+        }
+        form.file(
+            "file",
+            &request.file_name,
+            audio_content_type(&request.file_name),
+            &request.audio,
+        )
+    }
+
+    /// Perform a transcription request with retries.
+    async fn transcribe_with_retry(&self, request: &TranscriptionRequest) -> Result<OpenAITranscription, ProviderError> {
+        let form = Self::form(request);
+        let content_type = form.content_type();
+        let body = form.finish();
+        let mut last_error = None;
+
+        for attempt in 0..=self.max_retries {
+            if attempt > 0 {
+                // Wait as long as a rate limit response asked, else back off exponentially
+                let delay = last_error
+                    .as_ref()
+                    .and_then(ProviderError::retry_after)
+                    .unwrap_or_else(|| Duration::from_millis(INITIAL_RETRY_DELAY_MS * 2_u64.pow(attempt - 1)));
+                warn!("Retry attempt {} after {}ms", attempt, delay.as_millis());
+                tokio::time::sleep(delay).await;
+            }
+
+            let url = format!("{}/audio/transcriptions", self.base_url);
+
+            let response = match self
+                .client
+                .post(&url)
+                .header("Authorization", format!("Bearer {}", self.api_key))
+                .header("Content-Type", &content_type)
+                .body(body.clone())
+                .headers(trace_context::headers())
+                .send()
+                .await
+            {
+                Ok(resp) => resp,
+                Err(e) => {
+                    last_error = Some(ProviderError::HttpError(e.to_string()));
+                    continue;
+                }
+            };
+
+            let status = response.status();
+
+            let retry_after = retry::retry_after(response.headers());
+            if !status.is_success() {
+                let error_text = response
+                    .text()
+                    .await
+                    .unwrap_or_else(|_| "Unknown error".to_string());
+
+                let error = match status.as_u16() {
+                    401 => ProviderError::AuthError(error_text),
+                    429 => {
+                        // Rate limit - always retry
+                        last_error = Some(ProviderError::RateLimitExceeded { retry_after });
+                        continue;
+                    }
+                    400..=499 => ProviderError::InvalidRequest(error_text),
+                    500..=599 => {
+                        // Server error - retry
+                        last_error = Some(ProviderError::Api {
+                            status: status.as_u16(),
+                            message: error_text,
+                        });
+                        continue;
+                    }
+                    _ => ProviderError::Api {
+                        status: status.as_u16(),
+                        message: error_text,
+                    },
+                };
+
+                return Err(error);
+            }
+
+            match response.json::<OpenAITranscription>().await {
+                Ok(transcription) => return Ok(transcription),
+                Err(e) => {
+                    last_error = Some(ProviderError::SerializationError(e.to_string()));
+                    continue;
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| ProviderError::Unknown("Max retries exceeded".to_string())))
+    }
+}
+
+#[async_trait]
+impl TranscriptionProvider for OpenAITranscriptionProvider {
+    async fn transcribe(&self, request: TranscriptionRequest) -> Result<TranscriptionResponse, ProviderError> {
+        if request.audio.len() > OPENAI_MAX_AUDIO_BYTES {
+            return Err(ProviderError::InvalidRequest(format!(
+                "Audio of {} bytes exceeds OpenAI maximum of {}",
+                request.audio.len(),
+                OPENAI_MAX_AUDIO_BYTES
+            )));
+        }
+
+        debug!(
+            "Transcribing {} bytes of {} with model {}",
+            request.audio.len(),
+            request.file_name,
+            request.model
+        );
+
+        let transcription = self.transcribe_with_retry(&request).await?;
+
+        Ok(TranscriptionResponse {
+            text: transcription.text,
+            segments: transcription
+                .segments
+                .into_iter()
+                .map(|segment| TranscriptSegment {
+                    start: segment.start,
+                    end: segment.end,
+                    text: segment.text.trim().to_string(),
+                })
+                .collect(),
+            language: transcription.language,
+            duration_seconds: transcription.duration,
+            model: request.model,
+        })
+    }
+
+    fn name(&self) -> &str {
+        "openai_transcription"
+    }
+
+    fn max_audio_bytes(&self) -> Option<usize> {
+        Some(OPENAI_MAX_AUDIO_BYTES)
+    }
+}
+
+// OpenAI-specific response types
+
+#[derive(Debug, Deserialize)]
+struct OpenAITranscription {
+    text: String,
+    #[serde(default)]
+    language: Option<String>,
+    #[serde(default)]
+    duration: Option<f64>,
+    #[serde(default)]
+    segments: Vec<OpenAISegment>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAISegment {
+    start: f64,
+    end: f64,
+    text: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Matcher;
+    use std::collections::HashMap;
+
+    fn request(model: &str) -> TranscriptionRequest {
+        TranscriptionRequest {
+            model: model.to_string(),
+            audio: b"ID3audio".to_vec(),
+            file_name: "standup.mp3".to_string(),
+            language: Some("en".to_string()),
+            prompt: None,
+            extra: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_transcribe_with_segments() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/audio/transcriptions")
+            .match_header("authorization", "Bearer test-key")
+            .match_header("content-type", Matcher::Regex("^multipart/form-data; boundary=".to_string()))
+            .match_body(Matcher::AllOf(vec![
+                Matcher::Regex("name=\"model\"\r\n\r\nwhisper-1\r\n".to_string()),
+                Matcher::Regex("name=\"response_format\"\r\n\r\nverbose_json\r\n".to_string()),
+                Matcher::Regex("name=\"language\"\r\n\r\nen\r\n".to_string()),
+                Matcher::Regex("filename=\"standup.mp3\"\r\nContent-Type: audio/mpeg\r\n\r\nID3audio\r\n".to_string()),
+            ]))
+            .with_body(
+                r#"{"task": "transcribe", "language": "english", "duration": 4.2, "text": "Hello team. Shipping today.",
+                    "segments": [{"id": 0, "start": 0.0, "end": 1.5, "text": " Hello team."},
+                                 {"id": 1, "start": 1.5, "end": 4.2, "text": " Shipping today."}]}"#,
+            )
+            .create_async()
+            .await;
+        let provider = OpenAITranscriptionProvider::with_base_url("test-key".to_string(), server.url()).unwrap();
+
+        let response = provider.transcribe(request("whisper-1")).await.unwrap();
+        mock.assert_async().await;
+        assert_eq!(response.text, "Hello team. Shipping today.");
+        assert_eq!(response.language.as_deref(), Some("english"));
+        assert_eq!(response.duration_seconds, Some(4.2));
+        assert_eq!(
+            response.segments[1],
+            TranscriptSegment {
+                start: 1.5,
+                end: 4.2,
+                text: "Shipping today.".to_string()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_transcribe_rejects_large_audio() {
+        let provider = OpenAITranscriptionProvider::new("test-key".to_string()).unwrap();
+        let request = TranscriptionRequest {
+            audio: vec![0; OPENAI_MAX_AUDIO_BYTES + 1],
+            ..request("gpt-4o-transcribe")
+        };
+        assert!(matches!(provider.transcribe(request).await, Err(ProviderError::InvalidRequest(_))));
+    }
+}
@@ -3,6 +3,8 @@
 
 //! Pinecone vector database client implementation.
 
+use crate::retry;
+use crate::trace_context;
 use crate::traits::*;
 use async_trait::async_trait;
 use reqwest::Client;
@@ -36,15 +38,37 @@ impl PineconeClient {
         })
     }
 
+    /// Create a Pinecone client from the `PINECONE_API_KEY` and
+    /// `PINECONE_ENVIRONMENT` environment variables.
+    pub fn from_env() -> Result<Self, ProviderError> {
+        let api_key = std::env::var("PINECONE_API_KEY").map_err(|_| {
+            ProviderError::InvalidRequest("PINECONE_API_KEY environment variable not set".to_string())
+        })?;
+        let environment = std::env::var("PINECONE_ENVIRONMENT").map_err(|_| {
+            ProviderError::InvalidRequest("PINECONE_ENVIRONMENT environment variable not set".to_string())
+        })?;
+
+        Self::new(api_key, environment)
+    }
+
     /// Get the base URL for an index.
     fn get_index_url(&self, index: &str) -> String {
         format!("https://{}-{}.svc.{}.pinecone.io", index, "default", self.environment)
     }
+
+    /// Get the URL of the controller that manages indexes.
+    fn get_controller_url(&self) -> String {
+        format!("https://controller.{}.pinecone.io", self.environment)
+    }
 }
 
 #[async_trait]
 impl VectorSearchProvider for PineconeClient {
     async fn search(&self, request: VectorSearchRequest) -> Result<VectorSearchResponse, ProviderError> {
+        if request.mode != SearchMode::Vector {
+            return Err(ProviderError::InvalidRequest(format!("Pinecone does not support {} search", request.mode)));
+        }
+
         // Build Pinecone query request
         let api_request = PineconeQueryRequest {
             vector: request.query,
@@ -63,11 +87,14 @@ impl VectorSearchProvider for PineconeClient {
             .header("Api-Key", &self.api_key)
             .header("Content-Type", "application/json")
             .json(&api_request)
+            .headers(trace_context::headers())
             .send()
             .await
             .map_err(|e| ProviderError::HttpError(e.to_string()))?;
 
         let status = response.status();
+
+        let retry_after = retry::retry_after(response.headers());
         if !status.is_success() {
             let error_text = response
                 .text()
@@ -75,9 +102,12 @@ impl VectorSearchProvider for PineconeClient {
                 .unwrap_or_else(|_| "Unknown error".to_string());
             return Err(match status.as_u16() {
                 401 => ProviderError::AuthError(error_text),
-                429 => ProviderError::RateLimitExceeded,
+                429 => ProviderError::RateLimitExceeded { retry_after },
                 400..=499 => ProviderError::InvalidRequest(error_text),
-                _ => ProviderError::ProviderSpecific(error_text),
+                _ => ProviderError::Api {
+                    status: status.as_u16(),
+                    message: error_text,
+                },
             });
         }
 
@@ -137,11 +167,14 @@ impl VectorSearchProvider for PineconeClient {
             .header("Api-Key", &self.api_key)
             .header("Content-Type", "application/json")
             .json(&api_request)
+            .headers(trace_context::headers())
             .send()
             .await
             .map_err(|e| ProviderError::HttpError(e.to_string()))?;
 
         let status = response.status();
+
+        let retry_after = retry::retry_after(response.headers());
         if !status.is_success() {
             let error_text = response
                 .text()
@@ -149,9 +182,12 @@ impl VectorSearchProvider for PineconeClient {
                 .unwrap_or_else(|_| "Unknown error".to_string());
             return Err(match status.as_u16() {
                 401 => ProviderError::AuthError(error_text),
-                429 => ProviderError::RateLimitExceeded,
+                429 => ProviderError::RateLimitExceeded { retry_after },
                 400..=499 => ProviderError::InvalidRequest(error_text),
-                _ => ProviderError::ProviderSpecific(error_text),
+                _ => ProviderError::Api {
+                    status: status.as_u16(),
+                    message: error_text,
+                },
             });
         }
 
@@ -192,11 +228,14 @@ impl VectorSearchProvider for PineconeClient {
             .header("Api-Key", &self.api_key)
             .header("Content-Type", "application/json")
             .json(&api_request)
+            .headers(trace_context::headers())
             .send()
             .await
             .map_err(|e| ProviderError::HttpError(e.to_string()))?;
 
         let status = response.status();
+
+        let retry_after = retry::retry_after(response.headers());
         if !status.is_success() {
             let error_text = response
                 .text()
@@ -204,9 +243,12 @@ impl VectorSearchProvider for PineconeClient {
                 .unwrap_or_else(|_| "Unknown error".to_string());
             return Err(match status.as_u16() {
                 401 => ProviderError::AuthError(error_text),
-                429 => ProviderError::RateLimitExceeded,
+                429 => ProviderError::RateLimitExceeded { retry_after },
                 400..=499 => ProviderError::InvalidRequest(error_text),
-                _ => ProviderError::ProviderSpecific(error_text),
+                _ => ProviderError::Api {
+                    status: status.as_u16(),
+                    message: error_text,
+                },
             });
         }
 
@@ -217,6 +259,226 @@ impl VectorSearchProvider for PineconeClient {
         })
     }
 
+    async fn fetch(&self, request: FetchRequest) -> Result<FetchResponse, ProviderError> {
+        let mut query: Vec<(&str, &str)> = request.ids.iter().map(|id| ("ids", id.as_str())).collect();
+        if let Some(namespace) = &request.namespace {
+            query.push(("namespace", namespace));
+        }
+
+        let url = format!("{}/vectors/fetch", self.get_index_url(&request.index));
+
+        let response = self
+            .client
+            .get(&url)
+            .header("Api-Key", &self.api_key)
+            .query(&query)
+            .headers(trace_context::headers())
+            .send()
+            .await
+            .map_err(|e| ProviderError::HttpError(e.to_string()))?;
+
+        let status = response.status();
+
+        let retry_after = retry::retry_after(response.headers());
+        if !status.is_success() {
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(match status.as_u16() {
+                401 => ProviderError::AuthError(error_text),
+                429 => ProviderError::RateLimitExceeded { retry_after },
+                400..=499 => ProviderError::InvalidRequest(error_text),
+                _ => ProviderError::Api {
+                    status: status.as_u16(),
+                    message: error_text,
+                },
+            });
+        }
+
+        let api_response: PineconeFetchResponse = response
+            .json()
+            .await
+            .map_err(|e| ProviderError::SerializationError(e.to_string()))?;
+
+        Ok(api_response.into_response(&request.ids))
+    }
+
+    async fn list_ids(&self, request: ListIdsRequest) -> Result<ListIdsResponse, ProviderError> {
+        let limit = request.limit.map(|limit| limit.to_string());
+        let query: Vec<(&str, &str)> = [
+            ("namespace", request.namespace.as_deref()),
+            ("prefix", request.prefix.as_deref()),
+            ("limit", limit.as_deref()),
+            ("paginationToken", request.pagination_token.as_deref()),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| Some((name, value?)))
+        .collect();
+
+        let url = format!("{}/vectors/list", self.get_index_url(&request.index));
+
+        let response = self
+            .client
+            .get(&url)
+            .header("Api-Key", &self.api_key)
+            .query(&query)
+            .headers(trace_context::headers())
+            .send()
+            .await
+            .map_err(|e| ProviderError::HttpError(e.to_string()))?;
+
+        let status = response.status();
+
+        let retry_after = retry::retry_after(response.headers());
+        if !status.is_success() {
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(match status.as_u16() {
+                401 => ProviderError::AuthError(error_text),
+                429 => ProviderError::RateLimitExceeded { retry_after },
+                400..=499 => ProviderError::InvalidRequest(error_text),
+                _ => ProviderError::Api {
+                    status: status.as_u16(),
+                    message: error_text,
+                },
+            });
+        }
+
+        let api_response: PineconeListResponse = response
+            .json()
+            .await
+            .map_err(|e| ProviderError::SerializationError(e.to_string()))?;
+
+        Ok(api_response.into())
+    }
+
+    fn max_upsert_batch(&self) -> Option<usize> {
+        Some(1000)
+    }
+
+    async fn index_stats(&self, index: &str) -> Result<Option<IndexStats>, ProviderError> {
+        let url = format!("{}/describe_index_stats", self.get_index_url(index));
+
+        let response = self
+            .client
+            .post(&url)
+            .header("Api-Key", &self.api_key)
+            .header("Content-Type", "application/json")
+            .json(&serde_json::json!({}))
+            .headers(trace_context::headers())
+            .send()
+            .await
+            .map_err(|e| ProviderError::HttpError(e.to_string()))?;
+
+        let status = response.status();
+
+        let retry_after = retry::retry_after(response.headers());
+        if !status.is_success() {
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(match status.as_u16() {
+                401 => ProviderError::AuthError(error_text),
+                429 => ProviderError::RateLimitExceeded { retry_after },
+                400..=499 => ProviderError::InvalidRequest(error_text),
+                _ => ProviderError::Api {
+                    status: status.as_u16(),
+                    message: error_text,
+                },
+            });
+        }
+
+        let api_response: PineconeIndexStats = response
+            .json()
+            .await
+            .map_err(|e| ProviderError::SerializationError(e.to_string()))?;
+
+        Ok(Some(api_response.into()))
+    }
+
+    async fn create_index(&self, request: CreateIndexRequest) -> Result<(), ProviderError> {
+        let api_request = PineconeCreateIndexRequest {
+            name: request.index,
+            dimension: request.dimension,
+            metric: request.metric.as_str(),
+            metadata_config: (!request.metadata_indexes.is_empty()).then(|| PineconeMetadataConfig {
+                indexed: request.metadata_indexes.into_keys().collect(),
+            }),
+        };
+
+        let url = format!("{}/databases", self.get_controller_url());
+
+        let response = self
+            .client
+            .post(&url)
+            .header("Api-Key", &self.api_key)
+            .header("Content-Type", "application/json")
+            .json(&api_request)
+            .headers(trace_context::headers())
+            .send()
+            .await
+            .map_err(|e| ProviderError::HttpError(e.to_string()))?;
+
+        let status = response.status();
+
+        let retry_after = retry::retry_after(response.headers());
+        if !status.is_success() {
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(match status.as_u16() {
+                401 => ProviderError::AuthError(error_text),
+                429 => ProviderError::RateLimitExceeded { retry_after },
+                400..=499 => ProviderError::InvalidRequest(error_text),
+                _ => ProviderError::Api {
+                    status: status.as_u16(),
+                    message: error_text,
+                },
+            });
+        }
+
+        Ok(())
+    }
+
+    async fn delete_index(&self, index: &str) -> Result<(), ProviderError> {
+        let url = format!("{}/databases/{}", self.get_controller_url(), index);
+
+        let response = self
+            .client
+            .delete(&url)
+            .header("Api-Key", &self.api_key)
+            .headers(trace_context::headers())
+            .send()
+            .await
+            .map_err(|e| ProviderError::HttpError(e.to_string()))?;
+
+        let status = response.status();
+
+        let retry_after = retry::retry_after(response.headers());
+        if !status.is_success() {
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(match status.as_u16() {
+                401 => ProviderError::AuthError(error_text),
+                429 => ProviderError::RateLimitExceeded { retry_after },
+                400..=499 => ProviderError::InvalidRequest(error_text),
+                _ => ProviderError::Api {
+                    status: status.as_u16(),
+                    message: error_text,
+                },
+            });
+        }
+
+        Ok(())
+    }
+
     fn name(&self) -> &str {
         "pinecone"
     }
@@ -224,6 +486,96 @@ impl VectorSearchProvider for PineconeClient {
 
 // Pinecone-specific request/response types
 
+#[derive(Debug, Serialize)]
+struct PineconeCreateIndexRequest {
+    name: String,
+    dimension: usize,
+    metric: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    metadata_config: Option<PineconeMetadataConfig>,
+}
+
+#[derive(Debug, Serialize)]
+struct PineconeMetadataConfig {
+    indexed: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PineconeIndexStats {
+    dimension: Option<usize>,
+    #[serde(rename = "totalVectorCount")]
+    total_vector_count: Option<u64>,
+}
+
+impl From<PineconeIndexStats> for IndexStats {
+    fn from(stats: PineconeIndexStats) -> Self {
+        Self {
+            dimension: stats.dimension,
+            vector_count: stats.total_vector_count,
+            metric: None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct PineconeFetchResponse {
+    #[serde(default)]
+    vectors: HashMap<String, PineconeFetchedVector>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PineconeFetchedVector {
+    #[serde(default)]
+    values: Vec<f32>,
+    #[serde(default)]
+    metadata: Option<serde_json::Value>,
+}
+
+impl PineconeFetchResponse {
+    /// Orders the fetched records as requested; Pinecone returns them keyed by ID.
+    fn into_response(mut self, ids: &[String]) -> FetchResponse {
+        let records = ids
+            .iter()
+            .filter_map(|id| {
+                let vector = self.vectors.remove(id)?;
+                Some(VectorRecord {
+                    id: id.clone(),
+                    vector: vector.values,
+                    metadata: vector.metadata,
+                })
+            })
+            .collect();
+        FetchResponse { records }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct PineconeListResponse {
+    #[serde(default)]
+    vectors: Vec<PineconeListedVector>,
+    #[serde(default)]
+    pagination: Option<PineconePagination>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PineconeListedVector {
+    id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PineconePagination {
+    next: Option<String>,
+}
+
+impl From<PineconeListResponse> for ListIdsResponse {
+    fn from(response: PineconeListResponse) -> Self {
+        Self {
+            ids: response.vectors.into_iter().map(|v| v.id).collect(),
+            next_token: response.pagination.and_then(|p| p.next),
+        }
+    }
+}
+
 #[derive(Debug, Serialize)]
 struct PineconeQueryRequest {
     vector: Vec<f32>,
@@ -328,6 +680,10 @@ mod tests {
             filter: Some(json!({"genre": "action"})),
             include_metadata: true,
             include_vectors: false,
+            mode: SearchMode::Vector,
+            text: None,
+            alpha: None,
+            fusion: None,
         };
 
         // URL should be correctly formatted
@@ -366,6 +722,28 @@ mod tests {
         assert!(json_str.contains("id2"));
     }
 
+    #[test]
+    fn test_create_index_request_serialization() {
+        let create_req = PineconeCreateIndexRequest {
+            name: "docs".to_string(),
+            dimension: 1536,
+            metric: DistanceMetric::DotProduct.as_str(),
+            metadata_config: None,
+        };
+
+        let value = serde_json::to_value(&create_req).unwrap();
+        assert_eq!(value, json!({"name": "docs", "dimension": 1536, "metric": "dotproduct"}));
+
+        let create_req = PineconeCreateIndexRequest {
+            metadata_config: Some(PineconeMetadataConfig {
+                indexed: vec!["tenant".to_string()],
+            }),
+            ..create_req
+        };
+        let value = serde_json::to_value(&create_req).unwrap();
+        assert_eq!(value["metadata_config"], json!({"indexed": ["tenant"]}));
+    }
+
     #[test]
     fn test_delete_all_request_serialization() {
         let delete_req = PineconeDeleteRequest {
@@ -377,4 +755,47 @@ mod tests {
         let json_str = serde_json::to_string(&delete_req).unwrap();
         assert!(json_str.contains("deleteAll"));
     }
+
+    #[test]
+    fn test_index_stats_deserialization() {
+        let stats: PineconeIndexStats = serde_json::from_str(
+            r#"{"namespaces": {"": {"vectorCount": 50}}, "dimension": 1024, "indexFullness": 0.0, "totalVectorCount": 50}"#,
+        )
+        .unwrap();
+        let stats = IndexStats::from(stats);
+        assert_eq!(stats.dimension, Some(1024));
+        assert_eq!(stats.vector_count, Some(50));
+    }
+
+    #[test]
+    fn test_fetch_response_keeps_request_order() {
+        let response: PineconeFetchResponse = serde_json::from_str(
+            r#"{"vectors": {
+                "b": {"id": "b", "values": [0.3, 0.4]},
+                "a": {"id": "a", "values": [0.1, 0.2], "metadata": {"title": "A"}}
+            }, "namespace": "docs"}"#,
+        )
+        .unwrap();
+
+        let ids = ["a", "missing", "b"].map(String::from);
+        let response = response.into_response(&ids);
+        let fetched: Vec<&str> = response.records.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(fetched, ["a", "b"]);
+        assert_eq!(response.records[0].metadata, Some(json!({"title": "A"})));
+        assert_eq!(response.records[1].vector, [0.3, 0.4]);
+    }
+
+    #[test]
+    fn test_list_response_deserialization() {
+        let response: PineconeListResponse = serde_json::from_str(
+            r#"{"vectors": [{"id": "doc1#1"}, {"id": "doc1#2"}], "pagination": {"next": "Tm90aGluZw=="}, "namespace": "", "usage": {"readUnits": 1}}"#,
+        )
+        .unwrap();
+        let response = ListIdsResponse::from(response);
+        assert_eq!(response.ids, ["doc1#1", "doc1#2"]);
+        assert_eq!(response.next_token.as_deref(), Some("Tm90aGluZw=="));
+
+        let last: PineconeListResponse = serde_json::from_str(r#"{"vectors": [], "namespace": ""}"#).unwrap();
+        assert_eq!(ListIdsResponse::from(last).next_token, None);
+    }
 }
@@ -2,7 +2,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
 //! Qdrant vector database client implementation.
+//!
+//! The client talks to Qdrant's REST API by default. With the `qdrant-grpc`
+//! feature, [`QdrantClient::grpc`] connects over gRPC instead.
 
+use crate::retry;
+use crate::trace_context;
 use crate::traits::*;
 use async_trait::async_trait;
 use reqwest::Client;
@@ -10,11 +15,16 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::time::Duration;
 
+#[cfg(feature = "qdrant-grpc")]
+mod grpc;
+
 /// Qdrant vector database client.
 pub struct QdrantClient {
     client: Client,
     base_url: String,
     api_key: Option<String>,
+    #[cfg(feature = "qdrant-grpc")]
+    grpc: Option<grpc::GrpcTransport>,
 }
 
 impl QdrantClient {
@@ -33,13 +43,261 @@ impl QdrantClient {
             client,
             base_url,
             api_key,
+            #[cfg(feature = "qdrant-grpc")]
+            grpc: None,
+        })
+    }
+
+    /// Create a Qdrant client that uses the gRPC API.
+    ///
+    /// # Arguments
+    /// * `grpc_url` - Qdrant gRPC endpoint (e.g., "http://localhost:6334")
+    /// * `api_key` - Optional API key for authentication
+    #[cfg(feature = "qdrant-grpc")]
+    pub fn grpc(grpc_url: String, api_key: Option<String>) -> Result<Self, ProviderError> {
+        let transport = grpc::GrpcTransport::new(&grpc_url, api_key.clone())?;
+        let mut client = Self::new(grpc_url, api_key)?;
+        client.grpc = Some(transport);
+        Ok(client)
+    }
+
+    /// Create a Qdrant client from the `QDRANT_URL` and optional
+    /// `QDRANT_API_KEY` environment variables.
+    ///
+    /// With the `qdrant-grpc` feature, `QDRANT_GRPC_URL` takes precedence
+    /// over `QDRANT_URL` and selects the gRPC API.
+    pub fn from_env() -> Result<Self, ProviderError> {
+        #[cfg(feature = "qdrant-grpc")]
+        if let Ok(grpc_url) = std::env::var("QDRANT_GRPC_URL") {
+            return Self::grpc(grpc_url, std::env::var("QDRANT_API_KEY").ok());
+        }
+
+        let base_url = std::env::var("QDRANT_URL").map_err(|_| {
+            ProviderError::InvalidRequest("QDRANT_URL environment variable not set".to_string())
+        })?;
+
+        Self::new(base_url, std::env::var("QDRANT_API_KEY").ok())
+    }
+
+    async fn create_collection(&self, request: &CreateIndexRequest) -> Result<(), ProviderError> {
+        #[cfg(feature = "qdrant-grpc")]
+        if let Some(grpc) = &self.grpc {
+            return grpc.create_collection(request).await;
+        }
+
+        let index = &request.index;
+        let api_request = QdrantCreateCollectionRequest {
+            vectors: QdrantVectorParams {
+                size: request.dimension,
+                distance: match request.metric {
+                    DistanceMetric::Cosine => "Cosine",
+                    DistanceMetric::Euclidean => "Euclid",
+                    DistanceMetric::DotProduct => "Dot",
+                },
+            },
+        };
+
+        let url = format!("{}/collections/{}", self.base_url, index);
+
+        let mut req_builder = self.client
+            .put(&url)
+            .header("Content-Type", "application/json")
+            .json(&api_request);
+
+        if let Some(api_key) = &self.api_key {
+            req_builder = req_builder.header("api-key", api_key);
+        }
+
+        let response = req_builder
+            .headers(trace_context::headers())
+            .send()
+            .await
+            .map_err(|e| ProviderError::HttpError(e.to_string()))?;
+
+        let status = response.status();
+
+        let retry_after = retry::retry_after(response.headers());
+        if !status.is_success() {
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(match status.as_u16() {
+                401 => ProviderError::AuthError(error_text),
+                429 => ProviderError::RateLimitExceeded { retry_after },
+                400..=499 => ProviderError::InvalidRequest(error_text),
+                _ => ProviderError::Api {
+                    status: status.as_u16(),
+                    message: error_text,
+                },
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Index a payload field so filters on it stay fast on large collections.
+    pub async fn create_payload_index(
+        &self,
+        collection: &str,
+        field: &str,
+        field_type: MetadataFieldType,
+    ) -> Result<(), ProviderError> {
+        #[cfg(feature = "qdrant-grpc")]
+        if let Some(grpc) = &self.grpc {
+            return grpc.create_payload_index(collection, field, field_type).await;
+        }
+
+        let api_request = QdrantPayloadIndexRequest {
+            field_name: field,
+            field_schema: field_type.as_str(),
+        };
+
+        let url = format!("{}/collections/{}/index?wait=true", self.base_url, collection);
+
+        let mut req_builder = self.client
+            .put(&url)
+            .header("Content-Type", "application/json")
+            .json(&api_request);
+
+        if let Some(api_key) = &self.api_key {
+            req_builder = req_builder.header("api-key", api_key);
+        }
+
+        let response = req_builder
+            .headers(trace_context::headers())
+            .send()
+            .await
+            .map_err(|e| ProviderError::HttpError(e.to_string()))?;
+
+        let status = response.status();
+
+        let retry_after = retry::retry_after(response.headers());
+        if !status.is_success() {
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(match status.as_u16() {
+                401 => ProviderError::AuthError(error_text),
+                429 => ProviderError::RateLimitExceeded { retry_after },
+                400..=499 => ProviderError::InvalidRequest(error_text),
+                _ => ProviderError::Api {
+                    status: status.as_u16(),
+                    message: error_text,
+                },
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Fetch one page of points matching a filter, in ID order.
+    ///
+    /// Pass the returned `next_offset` as the next request's `offset` to
+    /// continue; it is `None` once every point has been read.
+    pub async fn scroll(&self, request: &ScrollRequest) -> Result<ScrollPage, ProviderError> {
+        #[cfg(feature = "qdrant-grpc")]
+        if let Some(grpc) = &self.grpc {
+            return grpc.scroll(request).await;
+        }
+
+        let api_request = QdrantScrollRequest {
+            filter: request.filter.clone(),
+            limit: request.limit,
+            offset: request.offset.as_deref().map(rest_point_id),
+            with_payload: request.with_payload,
+            with_vector: request.with_vectors,
+        };
+
+        let url = format!("{}/collections/{}/points/scroll", self.base_url, request.collection);
+
+        let mut req_builder = self.client
+            .post(&url)
+            .header("Content-Type", "application/json")
+            .json(&api_request);
+
+        if let Some(api_key) = &self.api_key {
+            req_builder = req_builder.header("api-key", api_key);
+        }
+
+        let response = req_builder
+            .headers(trace_context::headers())
+            .send()
+            .await
+            .map_err(|e| ProviderError::HttpError(e.to_string()))?;
+
+        let status = response.status();
+
+        let retry_after = retry::retry_after(response.headers());
+        if !status.is_success() {
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(match status.as_u16() {
+                401 => ProviderError::AuthError(error_text),
+                429 => ProviderError::RateLimitExceeded { retry_after },
+                400..=499 => ProviderError::InvalidRequest(error_text),
+                _ => ProviderError::Api {
+                    status: status.as_u16(),
+                    message: error_text,
+                },
+            });
+        }
+
+        let api_response: QdrantScrollResponse = response
+            .json()
+            .await
+            .map_err(|e| ProviderError::SerializationError(e.to_string()))?;
+
+        let points = api_response
+            .result
+            .points
+            .into_iter()
+            .map(|p| ScrolledPoint {
+                id: p.id.to_string(),
+                payload: p.payload,
+                vector: p.vector,
+            })
+            .collect();
+
+        Ok(ScrollPage {
+            points,
+            next_offset: api_response.result.next_page_offset.map(|id| id.to_string()),
         })
     }
+
+    /// Read every point matching a filter by following scroll pages.
+    ///
+    /// `request.limit` sets the page size. Use [`scroll`](Self::scroll)
+    /// directly to process very large collections page by page.
+    pub async fn scroll_all(&self, request: &ScrollRequest) -> Result<Vec<ScrolledPoint>, ProviderError> {
+        let mut request = request.clone();
+        let mut points = Vec::new();
+        loop {
+            let page = self.scroll(&request).await?;
+            points.extend(page.points);
+            match page.next_offset {
+                Some(offset) => request.offset = Some(offset),
+                None => return Ok(points),
+            }
+        }
+    }
 }
 
 #[async_trait]
 impl VectorSearchProvider for QdrantClient {
     async fn search(&self, request: VectorSearchRequest) -> Result<VectorSearchResponse, ProviderError> {
+        if request.mode != SearchMode::Vector {
+            return Err(ProviderError::InvalidRequest(format!("Qdrant does not support {} search", request.mode)));
+        }
+
+        #[cfg(feature = "qdrant-grpc")]
+        if let Some(grpc) = &self.grpc {
+            return grpc.search(request).await;
+        }
+
         // Build Qdrant search request
         let api_request = QdrantSearchRequest {
             vector: request.query,
@@ -61,11 +319,14 @@ impl VectorSearchProvider for QdrantClient {
         }
 
         let response = req_builder
+            .headers(trace_context::headers())
             .send()
             .await
             .map_err(|e| ProviderError::HttpError(e.to_string()))?;
 
         let status = response.status();
+
+        let retry_after = retry::retry_after(response.headers());
         if !status.is_success() {
             let error_text = response
                 .text()
@@ -73,9 +334,12 @@ impl VectorSearchProvider for QdrantClient {
                 .unwrap_or_else(|_| "Unknown error".to_string());
             return Err(match status.as_u16() {
                 401 => ProviderError::AuthError(error_text),
-                429 => ProviderError::RateLimitExceeded,
+                429 => ProviderError::RateLimitExceeded { retry_after },
                 400..=499 => ProviderError::InvalidRequest(error_text),
-                _ => ProviderError::ProviderSpecific(error_text),
+                _ => ProviderError::Api {
+                    status: status.as_u16(),
+                    message: error_text,
+                },
             });
         }
 
@@ -118,6 +382,11 @@ impl VectorSearchProvider for QdrantClient {
     }
 
     async fn upsert(&self, request: UpsertRequest) -> Result<UpsertResponse, ProviderError> {
+        #[cfg(feature = "qdrant-grpc")]
+        if let Some(grpc) = &self.grpc {
+            return grpc.upsert(request).await;
+        }
+
         // Save the count before moving request.vectors
         let vectors_count = request.vectors.len();
 
@@ -146,11 +415,14 @@ impl VectorSearchProvider for QdrantClient {
         }
 
         let response = req_builder
+            .headers(trace_context::headers())
             .send()
             .await
             .map_err(|e| ProviderError::HttpError(e.to_string()))?;
 
         let status = response.status();
+
+        let retry_after = retry::retry_after(response.headers());
         if !status.is_success() {
             let error_text = response
                 .text()
@@ -158,9 +430,12 @@ impl VectorSearchProvider for QdrantClient {
                 .unwrap_or_else(|_| "Unknown error".to_string());
             return Err(match status.as_u16() {
                 401 => ProviderError::AuthError(error_text),
-                429 => ProviderError::RateLimitExceeded,
+                429 => ProviderError::RateLimitExceeded { retry_after },
                 400..=499 => ProviderError::InvalidRequest(error_text),
-                _ => ProviderError::ProviderSpecific(error_text),
+                _ => ProviderError::Api {
+                    status: status.as_u16(),
+                    message: error_text,
+                },
             });
         }
 
@@ -183,6 +458,11 @@ impl VectorSearchProvider for QdrantClient {
     }
 
     async fn delete(&self, request: DeleteRequest) -> Result<DeleteResponse, ProviderError> {
+        #[cfg(feature = "qdrant-grpc")]
+        if let Some(grpc) = &self.grpc {
+            return grpc.delete(request).await;
+        }
+
         let api_request = QdrantDeleteRequest {
             points: request.ids.clone(),
         };
@@ -199,11 +479,14 @@ impl VectorSearchProvider for QdrantClient {
         }
 
         let response = req_builder
+            .headers(trace_context::headers())
             .send()
             .await
             .map_err(|e| ProviderError::HttpError(e.to_string()))?;
 
         let status = response.status();
+
+        let retry_after = retry::retry_after(response.headers());
         if !status.is_success() {
             let error_text = response
                 .text()
@@ -211,9 +494,12 @@ impl VectorSearchProvider for QdrantClient {
                 .unwrap_or_else(|_| "Unknown error".to_string());
             return Err(match status.as_u16() {
                 401 => ProviderError::AuthError(error_text),
-                429 => ProviderError::RateLimitExceeded,
+                429 => ProviderError::RateLimitExceeded { retry_after },
                 400..=499 => ProviderError::InvalidRequest(error_text),
-                _ => ProviderError::ProviderSpecific(error_text),
+                _ => ProviderError::Api {
+                    status: status.as_u16(),
+                    message: error_text,
+                },
             });
         }
 
@@ -235,11 +521,194 @@ impl VectorSearchProvider for QdrantClient {
         })
     }
 
+    async fn index_stats(&self, index: &str) -> Result<Option<IndexStats>, ProviderError> {
+        #[cfg(feature = "qdrant-grpc")]
+        if let Some(grpc) = &self.grpc {
+            return grpc.index_stats(index).await;
+        }
+
+        let url = format!("{}/collections/{}", self.base_url, index);
+
+        let mut req_builder = self.client.get(&url);
+
+        if let Some(api_key) = &self.api_key {
+            req_builder = req_builder.header("api-key", api_key);
+        }
+
+        let response = req_builder
+            .headers(trace_context::headers())
+            .send()
+            .await
+            .map_err(|e| ProviderError::HttpError(e.to_string()))?;
+
+        let status = response.status();
+
+        let retry_after = retry::retry_after(response.headers());
+        if !status.is_success() {
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(match status.as_u16() {
+                401 => ProviderError::AuthError(error_text),
+                429 => ProviderError::RateLimitExceeded { retry_after },
+                400..=499 => ProviderError::InvalidRequest(error_text),
+                _ => ProviderError::Api {
+                    status: status.as_u16(),
+                    message: error_text,
+                },
+            });
+        }
+
+        let api_response: QdrantCollectionResponse = response
+            .json()
+            .await
+            .map_err(|e| ProviderError::SerializationError(e.to_string()))?;
+
+        // Collections with named vectors have no single dimension or metric
+        let vectors = &api_response.result.config.params.vectors;
+        let dimension = vectors.get("size").and_then(|size| size.as_u64());
+        let metric = vectors.get("distance").and_then(|distance| match distance.as_str()? {
+            "Cosine" => Some(DistanceMetric::Cosine),
+            "Euclid" => Some(DistanceMetric::Euclidean),
+            "Dot" => Some(DistanceMetric::DotProduct),
+            _ => None,
+        });
+        Ok(Some(IndexStats {
+            dimension: dimension.map(|size| size as usize),
+            vector_count: api_response.result.points_count,
+            metric,
+        }))
+    }
+
+    async fn create_index(&self, request: CreateIndexRequest) -> Result<(), ProviderError> {
+        self.create_collection(&request).await?;
+        for (field, field_type) in &request.metadata_indexes {
+            self.create_payload_index(&request.index, field, *field_type).await?;
+        }
+        Ok(())
+    }
+
+    async fn delete_index(&self, index: &str) -> Result<(), ProviderError> {
+        #[cfg(feature = "qdrant-grpc")]
+        if let Some(grpc) = &self.grpc {
+            return grpc.delete_collection(index).await;
+        }
+
+        let url = format!("{}/collections/{}", self.base_url, index);
+
+        let mut req_builder = self.client.delete(&url);
+
+        if let Some(api_key) = &self.api_key {
+            req_builder = req_builder.header("api-key", api_key);
+        }
+
+        let response = req_builder
+            .headers(trace_context::headers())
+            .send()
+            .await
+            .map_err(|e| ProviderError::HttpError(e.to_string()))?;
+
+        let status = response.status();
+
+        let retry_after = retry::retry_after(response.headers());
+        if !status.is_success() {
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(match status.as_u16() {
+                401 => ProviderError::AuthError(error_text),
+                429 => ProviderError::RateLimitExceeded { retry_after },
+                400..=499 => ProviderError::InvalidRequest(error_text),
+                _ => ProviderError::Api {
+                    status: status.as_u16(),
+                    message: error_text,
+                },
+            });
+        }
+
+        Ok(())
+    }
+
     fn name(&self) -> &str {
         "qdrant"
     }
 }
 
+/// A page request for [`QdrantClient::scroll`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScrollRequest {
+    /// Collection to read.
+    pub collection: String,
+
+    /// Qdrant filter, in the REST API's JSON form.
+    #[serde(default)]
+    pub filter: Option<serde_json::Value>,
+
+    /// Maximum number of points per page.
+    pub limit: usize,
+
+    /// Point ID to start from, taken from the previous page's `next_offset`.
+    #[serde(default)]
+    pub offset: Option<String>,
+
+    /// Include point payloads.
+    #[serde(default = "default_true")]
+    pub with_payload: bool,
+
+    /// Include point vectors.
+    #[serde(default)]
+    pub with_vectors: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl ScrollRequest {
+    /// Request the first page of a collection, with payloads and without vectors.
+    pub fn new(collection: impl Into<String>, limit: usize) -> Self {
+        Self {
+            collection: collection.into(),
+            filter: None,
+            limit,
+            offset: None,
+            with_payload: true,
+            with_vectors: false,
+        }
+    }
+}
+
+/// One page of points returned by [`QdrantClient::scroll`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScrollPage {
+    /// Points on this page.
+    pub points: Vec<ScrolledPoint>,
+
+    /// Offset of the next page, or `None` on the last page.
+    pub next_offset: Option<String>,
+}
+
+/// A point read by scrolling.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScrolledPoint {
+    /// Point ID.
+    pub id: String,
+
+    /// Point payload, if requested.
+    pub payload: Option<serde_json::Value>,
+
+    /// Point vector, if requested.
+    pub vector: Option<Vec<f32>>,
+}
+
+/// Numeric IDs are sent as integers, as Qdrant only accepts unsigned
+/// integers and UUIDs.
+fn rest_point_id(id: &str) -> serde_json::Value {
+    id.parse::<u64>().map_or_else(|_| serde_json::Value::from(id), serde_json::Value::from)
+}
+
 // Qdrant-specific request/response types
 
 #[derive(Debug, Serialize)]
@@ -252,6 +721,75 @@ struct QdrantSearchRequest {
     with_vector: bool,
 }
 
+#[derive(Debug, Serialize)]
+struct QdrantCreateCollectionRequest {
+    vectors: QdrantVectorParams,
+}
+
+#[derive(Debug, Serialize)]
+struct QdrantVectorParams {
+    size: usize,
+    distance: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+struct QdrantPayloadIndexRequest<'a> {
+    field_name: &'a str,
+    field_schema: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+struct QdrantScrollRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    filter: Option<serde_json::Value>,
+    limit: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    offset: Option<serde_json::Value>,
+    with_payload: bool,
+    with_vector: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct QdrantScrollResponse {
+    result: QdrantScrollResult,
+}
+
+#[derive(Debug, Deserialize)]
+struct QdrantScrollResult {
+    points: Vec<QdrantScrolledPoint>,
+    next_page_offset: Option<QdrantPointId>,
+}
+
+#[derive(Debug, Deserialize)]
+struct QdrantScrolledPoint {
+    id: QdrantPointId,
+    #[serde(default)]
+    payload: Option<serde_json::Value>,
+    #[serde(default)]
+    vector: Option<Vec<f32>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct QdrantCollectionResponse {
+    result: QdrantCollectionInfo,
+}
+
+#[derive(Debug, Deserialize)]
+struct QdrantCollectionInfo {
+    points_count: Option<u64>,
+    config: QdrantCollectionConfig,
+}
+
+#[derive(Debug, Deserialize)]
+struct QdrantCollectionConfig {
+    params: QdrantCollectionParams,
+}
+
+#[derive(Debug, Deserialize)]
+struct QdrantCollectionParams {
+    vectors: serde_json::Value,
+}
+
 #[derive(Debug, Deserialize)]
 struct QdrantSearchResponse {
     result: Vec<QdrantPoint>,
@@ -401,10 +939,157 @@ mod tests {
             filter: None,
             include_metadata: true,
             include_vectors: false,
+            mode: SearchMode::Vector,
+            text: None,
+            alpha: None,
+            fusion: None,
         };
 
         // Verify client and request are correctly structured
         assert_eq!(client.name(), "qdrant");
         assert_eq!(request.top_k, 10);
     }
+
+    #[tokio::test]
+    async fn test_index_stats() {
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("GET", "/collections/docs")
+            .with_status(200)
+            .with_body(
+                r#"{"result": {"status": "green", "points_count": 42, "config": {"params": {"vectors": {"size": 1536, "distance": "Cosine"}}}}, "status": "ok"}"#,
+            )
+            .create_async()
+            .await;
+        let _named = server
+            .mock("GET", "/collections/multi")
+            .with_status(200)
+            .with_body(r#"{"result": {"points_count": 0, "config": {"params": {"vectors": {"text": {"size": 768}}}}}}"#)
+            .create_async()
+            .await;
+
+        let client = QdrantClient::new(server.url(), None).unwrap();
+        let stats = client.index_stats("docs").await.unwrap().unwrap();
+        assert_eq!(stats.dimension, Some(1536));
+        assert_eq!(stats.vector_count, Some(42));
+        assert_eq!(stats.metric, Some(DistanceMetric::Cosine));
+
+        let stats = client.index_stats("multi").await.unwrap().unwrap();
+        assert_eq!(stats.dimension, None);
+    }
+
+    #[tokio::test]
+    async fn test_create_and_delete_index() {
+        let mut server = mockito::Server::new_async().await;
+        let create = server
+            .mock("PUT", "/collections/docs")
+            .match_body(mockito::Matcher::Json(serde_json::json!({
+                "vectors": {"size": 768, "distance": "Euclid"}
+            })))
+            .with_status(200)
+            .with_body(r#"{"result": true, "status": "ok"}"#)
+            .create_async()
+            .await;
+        let delete = server
+            .mock("DELETE", "/collections/docs")
+            .with_status(200)
+            .with_body(r#"{"result": true, "status": "ok"}"#)
+            .create_async()
+            .await;
+
+        let client = QdrantClient::new(server.url(), None).unwrap();
+        client
+            .create_index(CreateIndexRequest {
+                index: "docs".to_string(),
+                dimension: 768,
+                metric: DistanceMetric::Euclidean,
+                metadata_indexes: Default::default(),
+            })
+            .await
+            .unwrap();
+        create.assert_async().await;
+
+        client.delete_index("docs").await.unwrap();
+        delete.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_create_index_with_payload_indexes() {
+        let mut server = mockito::Server::new_async().await;
+        let create = server
+            .mock("PUT", "/collections/docs")
+            .with_status(200)
+            .with_body(r#"{"result": true, "status": "ok"}"#)
+            .create_async()
+            .await;
+        let payload_index = server
+            .mock("PUT", "/collections/docs/index")
+            .match_query(mockito::Matcher::UrlEncoded("wait".into(), "true".into()))
+            .match_body(mockito::Matcher::Json(serde_json::json!({
+                "field_name": "tenant",
+                "field_schema": "keyword"
+            })))
+            .with_status(200)
+            .with_body(r#"{"result": {"status": "completed"}, "status": "ok"}"#)
+            .create_async()
+            .await;
+
+        let client = QdrantClient::new(server.url(), None).unwrap();
+        client
+            .create_index(CreateIndexRequest {
+                index: "docs".to_string(),
+                dimension: 4,
+                metric: DistanceMetric::Cosine,
+                metadata_indexes: [("tenant".to_string(), MetadataFieldType::Keyword)].into(),
+            })
+            .await
+            .unwrap();
+        create.assert_async().await;
+        payload_index.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_scroll_all_follows_pages() {
+        let mut server = mockito::Server::new_async().await;
+        let filter = serde_json::json!({"must": [{"key": "tenant", "match": {"value": "acme"}}]});
+        let first = server
+            .mock("POST", "/collections/docs/points/scroll")
+            .match_body(mockito::Matcher::Json(serde_json::json!({
+                "filter": filter,
+                "limit": 2,
+                "with_payload": true,
+                "with_vector": false
+            })))
+            .with_status(200)
+            .with_body(
+                r#"{"result": {"points": [{"id": 1, "payload": {"tenant": "acme"}}, {"id": 2, "payload": {"tenant": "acme"}}], "next_page_offset": 3}, "status": "ok"}"#,
+            )
+            .expect(2)
+            .create_async()
+            .await;
+        let second = server
+            .mock("POST", "/collections/docs/points/scroll")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({"offset": 3})))
+            .with_status(200)
+            .with_body(r#"{"result": {"points": [{"id": 3, "payload": {"tenant": "acme"}}], "next_page_offset": null}, "status": "ok"}"#)
+            .create_async()
+            .await;
+
+        let client = QdrantClient::new(server.url(), None).unwrap();
+        let request = ScrollRequest {
+            filter: Some(filter),
+            ..ScrollRequest::new("docs", 2)
+        };
+
+        let page = client.scroll(&request).await.unwrap();
+        assert_eq!(page.points.len(), 2);
+        assert_eq!(page.next_offset.as_deref(), Some("3"));
+
+        let points = client.scroll_all(&request).await.unwrap();
+        let ids: Vec<&str> = points.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(ids, ["1", "2", "3"]);
+        assert_eq!(points[2].payload, Some(serde_json::json!({"tenant": "acme"})));
+        first.assert_async().await;
+        second.assert_async().await;
+    }
 }
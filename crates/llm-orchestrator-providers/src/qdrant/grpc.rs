@@ -0,0 +1,379 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! gRPC transport for the Qdrant client, built on `qdrant-client`.
+
+use super::{ScrollPage, ScrollRequest, ScrolledPoint};
+use crate::traits::*;
+use qdrant_client::qdrant::point_id::PointIdOptions;
+use qdrant_client::qdrant::vector_output::Vector;
+use qdrant_client::qdrant::vectors_config::Config;
+use qdrant_client::qdrant::{
+    Condition, CreateCollectionBuilder, CreateFieldIndexCollectionBuilder, DeletePointsBuilder, Distance, FieldType,
+    Filter, PointId, PointStruct, PointsIdsList, Range, ScrollPointsBuilder, SearchPointsBuilder,
+    UpsertPointsBuilder, VectorParamsBuilder, VectorsOutput,
+};
+use qdrant_client::{Payload, Qdrant, QdrantError};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Qdrant connection over gRPC.
+pub(super) struct GrpcTransport {
+    client: Qdrant,
+}
+
+impl GrpcTransport {
+    pub(super) fn new(url: &str, api_key: Option<String>) -> Result<Self, ProviderError> {
+        let client = Qdrant::from_url(url)
+            .api_key(api_key)
+            .timeout(Duration::from_secs(30))
+            .build()
+            .map_err(|e| ProviderError::HttpError(format!("Failed to create gRPC client: {}", e)))?;
+
+        Ok(Self { client })
+    }
+
+    pub(super) async fn search(&self, request: VectorSearchRequest) -> Result<VectorSearchResponse, ProviderError> {
+        let mut search = SearchPointsBuilder::new(&request.index, request.query, request.top_k as u64)
+            .with_payload(request.include_metadata)
+            .with_vectors(request.include_vectors);
+        if let Some(filter) = &request.filter {
+            search = search.filter(filter_from_json(filter)?);
+        }
+
+        let response = self.client.search_points(search).await.map_err(provider_error)?;
+
+        let results = response
+            .result
+            .into_iter()
+            .map(|point| SearchResult {
+                id: point.id.map(id_to_string).unwrap_or_default(),
+                score: point.score,
+                metadata: request.include_metadata.then(|| Payload::from(point.payload).into()),
+                vector: if request.include_vectors {
+                    point.vectors.and_then(dense_vector)
+                } else {
+                    None
+                },
+            })
+            .collect();
+
+        Ok(VectorSearchResponse {
+            results,
+            metadata: HashMap::new(),
+        })
+    }
+
+    pub(super) async fn upsert(&self, request: UpsertRequest) -> Result<UpsertResponse, ProviderError> {
+        let vectors_count = request.vectors.len();
+
+        let points = request
+            .vectors
+            .into_iter()
+            .map(|v| {
+                let payload = match v.metadata {
+                    Some(metadata) => Payload::try_from(metadata).map_err(provider_error)?,
+                    None => Payload::new(),
+                };
+                Ok(PointStruct::new(point_id(&v.id), v.vector, payload))
+            })
+            .collect::<Result<Vec<_>, ProviderError>>()?;
+
+        self.client
+            .upsert_points(UpsertPointsBuilder::new(&request.index, points).wait(true))
+            .await
+            .map_err(provider_error)?;
+
+        Ok(UpsertResponse {
+            upserted_count: vectors_count,
+            metadata: HashMap::new(),
+        })
+    }
+
+    pub(super) async fn delete(&self, request: DeleteRequest) -> Result<DeleteResponse, ProviderError> {
+        let ids: Vec<PointId> = request.ids.iter().map(|id| point_id(id)).collect();
+
+        self.client
+            .delete_points(DeletePointsBuilder::new(&request.index).points(PointsIdsList { ids }).wait(true))
+            .await
+            .map_err(provider_error)?;
+
+        Ok(DeleteResponse {
+            deleted_count: request.ids.len(),
+            metadata: HashMap::new(),
+        })
+    }
+
+    pub(super) async fn index_stats(&self, index: &str) -> Result<Option<IndexStats>, ProviderError> {
+        let response = self.client.collection_info(index).await.map_err(provider_error)?;
+        let Some(info) = response.result else {
+            return Ok(None);
+        };
+
+        // Collections with named vectors have no single dimension or metric
+        let params = info
+            .config
+            .and_then(|config| config.params)
+            .and_then(|params| params.vectors_config)
+            .and_then(|vectors| vectors.config)
+            .and_then(|config| match config {
+                Config::Params(params) => Some(params),
+                Config::ParamsMap(_) => None,
+            });
+        let metric = params.as_ref().and_then(|params| match Distance::try_from(params.distance).ok()? {
+            Distance::Cosine => Some(DistanceMetric::Cosine),
+            Distance::Euclid => Some(DistanceMetric::Euclidean),
+            Distance::Dot => Some(DistanceMetric::DotProduct),
+            _ => None,
+        });
+        Ok(Some(IndexStats {
+            dimension: params.map(|params| params.size as usize),
+            vector_count: info.points_count,
+            metric,
+        }))
+    }
+
+    pub(super) async fn create_collection(&self, request: &CreateIndexRequest) -> Result<(), ProviderError> {
+        let distance = match request.metric {
+            DistanceMetric::Cosine => Distance::Cosine,
+            DistanceMetric::Euclidean => Distance::Euclid,
+            DistanceMetric::DotProduct => Distance::Dot,
+        };
+
+        self.client
+            .create_collection(
+                CreateCollectionBuilder::new(&request.index)
+                    .vectors_config(VectorParamsBuilder::new(request.dimension as u64, distance)),
+            )
+            .await
+            .map_err(provider_error)?;
+
+        Ok(())
+    }
+
+    pub(super) async fn delete_collection(&self, index: &str) -> Result<(), ProviderError> {
+        self.client.delete_collection(index).await.map_err(provider_error)?;
+        Ok(())
+    }
+
+    pub(super) async fn create_payload_index(
+        &self,
+        collection: &str,
+        field: &str,
+        field_type: MetadataFieldType,
+    ) -> Result<(), ProviderError> {
+        let field_type = match field_type {
+            MetadataFieldType::Keyword => FieldType::Keyword,
+            MetadataFieldType::Integer => FieldType::Integer,
+            MetadataFieldType::Float => FieldType::Float,
+            MetadataFieldType::Bool => FieldType::Bool,
+            MetadataFieldType::Text => FieldType::Text,
+            MetadataFieldType::Datetime => FieldType::Datetime,
+        };
+
+        self.client
+            .create_field_index(CreateFieldIndexCollectionBuilder::new(collection, field, field_type).wait(true))
+            .await
+            .map_err(provider_error)?;
+
+        Ok(())
+    }
+
+    pub(super) async fn scroll(&self, request: &ScrollRequest) -> Result<ScrollPage, ProviderError> {
+        let mut scroll = ScrollPointsBuilder::new(&request.collection)
+            .limit(request.limit as u32)
+            .with_payload(request.with_payload)
+            .with_vectors(request.with_vectors);
+        if let Some(filter) = &request.filter {
+            scroll = scroll.filter(filter_from_json(filter)?);
+        }
+        if let Some(offset) = &request.offset {
+            scroll = scroll.offset(point_id(offset));
+        }
+
+        let response = self.client.scroll(scroll).await.map_err(provider_error)?;
+
+        let points = response
+            .result
+            .into_iter()
+            .map(|point| ScrolledPoint {
+                id: point.id.map(id_to_string).unwrap_or_default(),
+                payload: request.with_payload.then(|| Payload::from(point.payload).into()),
+                vector: point.vectors.and_then(dense_vector),
+            })
+            .collect();
+
+        Ok(ScrollPage {
+            points,
+            next_offset: response.next_page_offset.map(id_to_string),
+        })
+    }
+}
+
+/// Numeric IDs become integer point IDs; anything else is sent as a UUID.
+fn point_id(id: &str) -> PointId {
+    id.parse::<u64>().map_or_else(|_| PointId::from(id), PointId::from)
+}
+
+fn id_to_string(id: PointId) -> String {
+    match id.point_id_options {
+        Some(PointIdOptions::Num(num)) => num.to_string(),
+        Some(PointIdOptions::Uuid(uuid)) => uuid,
+        None => String::new(),
+    }
+}
+
+/// Returns the unnamed dense vector, if the point has one.
+fn dense_vector(vectors: VectorsOutput) -> Option<Vec<f32>> {
+    match vectors.get_vector()? {
+        Vector::Dense(dense) => Some(dense.data),
+        _ => None,
+    }
+}
+
+/// Converts a REST-style JSON filter into its gRPC form.
+///
+/// Supports `must`, `should` and `must_not` clauses made of nested filters,
+/// `has_id` conditions, and `key` conditions with `match` (`value` or `any`)
+/// or `range`.
+fn filter_from_json(filter: &Value) -> Result<Filter, ProviderError> {
+    let clause = |name: &str| -> Result<Vec<Condition>, ProviderError> {
+        match filter.get(name) {
+            None | Some(Value::Null) => Ok(Vec::new()),
+            Some(Value::Array(conditions)) => conditions.iter().map(condition_from_json).collect(),
+            Some(condition) => Ok(vec![condition_from_json(condition)?]),
+        }
+    };
+
+    Ok(Filter {
+        must: clause("must")?,
+        should: clause("should")?,
+        must_not: clause("must_not")?,
+        ..Default::default()
+    })
+}
+
+fn condition_from_json(condition: &Value) -> Result<Condition, ProviderError> {
+    let unsupported = || ProviderError::InvalidRequest(format!("Unsupported Qdrant filter condition: {}", condition));
+
+    if let Some(ids) = condition.get("has_id").and_then(Value::as_array) {
+        let ids = ids
+            .iter()
+            .map(|id| match id {
+                Value::Number(n) => n.as_u64().map(PointId::from),
+                Value::String(s) => Some(PointId::from(s.as_str())),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(unsupported)?;
+        return Ok(Condition::has_id(ids));
+    }
+
+    if ["must", "should", "must_not"].iter().any(|clause| condition.get(clause).is_some()) {
+        return Ok(Condition::from(filter_from_json(condition)?));
+    }
+
+    let key = condition.get("key").and_then(Value::as_str).ok_or_else(unsupported)?;
+    if let Some(matches) = condition.get("match") {
+        if let Some(value) = matches.get("value") {
+            return match value {
+                Value::Bool(b) => Ok(Condition::matches(key, *b)),
+                Value::String(s) => Ok(Condition::matches(key, s.clone())),
+                Value::Number(n) => n.as_i64().map(|n| Condition::matches(key, n)).ok_or_else(unsupported),
+                _ => Err(unsupported()),
+            };
+        }
+        if let Some(Value::Array(any)) = matches.get("any") {
+            if let Some(strings) = any.iter().map(|v| v.as_str().map(String::from)).collect::<Option<Vec<_>>>() {
+                return Ok(Condition::matches(key, strings));
+            }
+            if let Some(ints) = any.iter().map(Value::as_i64).collect::<Option<Vec<_>>>() {
+                return Ok(Condition::matches(key, ints));
+            }
+        }
+        return Err(unsupported());
+    }
+    if let Some(range) = condition.get("range") {
+        let bound = |name: &str| range.get(name).and_then(Value::as_f64);
+        return Ok(Condition::range(
+            key,
+            Range {
+                gt: bound("gt"),
+                gte: bound("gte"),
+                lt: bound("lt"),
+                lte: bound("lte"),
+            },
+        ));
+    }
+
+    Err(unsupported())
+}
+
+fn provider_error(error: QdrantError) -> ProviderError {
+    match error {
+        QdrantError::ResourceExhaustedError {
+            retry_after_seconds, ..
+        } => ProviderError::RateLimitExceeded {
+            retry_after: Some(Duration::from_secs(retry_after_seconds)),
+        },
+        QdrantError::ResponseError { status } => match status.code() {
+            tonic::Code::Unauthenticated | tonic::Code::PermissionDenied => {
+                ProviderError::AuthError(status.message().to_string())
+            }
+            tonic::Code::ResourceExhausted => ProviderError::RateLimitExceeded { retry_after: None },
+            tonic::Code::InvalidArgument | tonic::Code::NotFound | tonic::Code::AlreadyExists => {
+                ProviderError::InvalidRequest(status.message().to_string())
+            }
+            tonic::Code::DeadlineExceeded => ProviderError::Timeout,
+            code => ProviderError::ProviderSpecific(format!("Qdrant gRPC error ({}): {}", code, status.message())),
+        },
+        QdrantError::ConversionError(message) => ProviderError::InvalidRequest(message),
+        QdrantError::JsonToPayload(value) => {
+            ProviderError::InvalidRequest(format!("Metadata must be a JSON object, got: {}", value))
+        }
+        other => ProviderError::ProviderSpecific(other.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use qdrant_client::qdrant::condition::ConditionOneOf;
+    use serde_json::json;
+
+    #[test]
+    fn test_filter_from_json() {
+        let filter = filter_from_json(&json!({
+            "must": [
+                {"key": "lang", "match": {"value": "en"}},
+                {"key": "year", "range": {"gte": 2020}},
+                {"should": [{"key": "tag", "match": {"any": ["a", "b"]}}]}
+            ],
+            "must_not": {"has_id": [7, "5c56c793-69f3-4fbf-87e6-c4bf54c28c26"]}
+        }))
+        .unwrap();
+
+        assert_eq!(filter.must.len(), 3);
+        assert!(filter.should.is_empty());
+        assert_eq!(filter.must[0], Condition::matches("lang", "en".to_string()));
+        assert_eq!(
+            filter.must[1],
+            Condition::range(
+                "year",
+                Range {
+                    gte: Some(2020.0),
+                    ..Default::default()
+                }
+            )
+        );
+        assert!(matches!(filter.must[2].condition_one_of, Some(ConditionOneOf::Filter(_))));
+        assert!(matches!(filter.must_not[0].condition_one_of, Some(ConditionOneOf::HasId(_))));
+    }
+
+    #[test]
+    fn test_filter_from_json_rejects_unsupported_conditions() {
+        let error = filter_from_json(&json!({"must": [{"key": "loc", "geo_radius": {}}]})).unwrap_err();
+        assert!(matches!(error, ProviderError::InvalidRequest(_)));
+    }
+}
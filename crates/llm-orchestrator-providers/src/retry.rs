@@ -0,0 +1,166 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Retry settings shared by provider clients.
+
+use crate::trace_context;
+use crate::traits::ProviderError;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{RequestBuilder, Response};
+use std::time::Duration;
+use tracing::warn;
+
+/// Retry behavior for provider requests.
+///
+/// Retries back off exponentially from `initial_delay`, capped at
+/// `max_delay`. A `retry-after` header sent by the provider takes precedence
+/// over the computed delay.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryConfig {
+    /// Retries after the first attempt; 0 disables retrying.
+    pub max_retries: u32,
+
+    /// Delay before the first retry, doubled for each one after it.
+    pub initial_delay: Duration,
+
+    /// Upper bound for the backoff delay.
+    pub max_delay: Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Retry up to `max_retries` times with the default delays.
+    pub fn new(max_retries: u32) -> Self {
+        Self {
+            max_retries,
+            ..Self::default()
+        }
+    }
+
+    /// Never retry.
+    pub fn disabled() -> Self {
+        Self::new(0)
+    }
+
+    /// Set the delay before the first retry.
+    pub fn with_initial_delay(mut self, delay: Duration) -> Self {
+        self.initial_delay = delay;
+        self
+    }
+
+    /// Set the upper bound for the backoff delay.
+    pub fn with_max_delay(mut self, delay: Duration) -> Self {
+        self.max_delay = delay;
+        self
+    }
+
+    /// Backoff delay before the given retry, counting from 1.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2_u32.saturating_pow(retry.saturating_sub(1));
+        self.initial_delay.saturating_mul(factor).min(self.max_delay)
+    }
+}
+
+/// Delay requested by a `retry-after` header given in seconds.
+pub(crate) fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let seconds: f64 = headers.get(RETRY_AFTER)?.to_str().ok()?.trim().parse().ok()?;
+    Duration::try_from_secs_f64(seconds).ok()
+}
+
+/// Sends the request `build` makes, retrying connection failures, rate
+/// limits, and server errors; other error statuses fail at once.
+///
+/// Returns the successful response, its body unread.
+pub(crate) async fn send_with_retry(
+    retry: &RetryConfig,
+    provider: &str,
+    build: impl Fn() -> RequestBuilder,
+) -> Result<Response, ProviderError> {
+    let mut retries = 0;
+    loop {
+        let (error, retryable, retry_after) = match build().headers(trace_context::headers()).send().await {
+            Ok(response) if response.status().is_success() => return Ok(response),
+            Ok(response) => {
+                let status = response.status();
+                let retry_after = retry_after(response.headers());
+                let message = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+                let error = match status.as_u16() {
+                    401 | 403 => ProviderError::AuthError(message),
+                    429 => ProviderError::RateLimitExceeded { retry_after },
+                    400..=499 => ProviderError::InvalidRequest(message),
+                    _ => ProviderError::Api {
+                        status: status.as_u16(),
+                        message,
+                    },
+                };
+                (error, status.as_u16() == 429 || status.is_server_error(), retry_after)
+            }
+            Err(e) => (ProviderError::HttpError(e.to_string()), true, None),
+        };
+        if !retryable || retries >= retry.max_retries {
+            return Err(error);
+        }
+        retries += 1;
+        let delay = retry_after.unwrap_or_else(|| retry.backoff(retries));
+        warn!(
+            retry = retries,
+            delay_ms = delay.as_millis() as u64,
+            error = %error,
+            "Retrying {} request",
+            provider
+        );
+        tokio::time::sleep(delay).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_backoff_doubles_up_to_max_delay() {
+        let config = RetryConfig::new(5)
+            .with_initial_delay(Duration::from_millis(500))
+            .with_max_delay(Duration::from_secs(3));
+
+        let delays: Vec<_> = (1..=5).map(|retry| config.backoff(retry)).collect();
+        assert_eq!(
+            delays,
+            vec![
+                Duration::from_millis(500),
+                Duration::from_secs(1),
+                Duration::from_secs(2),
+                Duration::from_secs(3),
+                Duration::from_secs(3),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_retry_after_header() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after(&headers), None);
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("2"));
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(2)));
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("0.5"));
+        assert_eq!(retry_after(&headers), Some(Duration::from_millis(500)));
+
+        // HTTP dates and negative values fall back to the backoff delay
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"));
+        assert_eq!(retry_after(&headers), None);
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("-1"));
+        assert_eq!(retry_after(&headers), None);
+    }
+}
@@ -0,0 +1,45 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Trace context propagation for outgoing provider requests.
+//!
+//! With the `otel` feature enabled, [`headers`] injects the W3C trace context
+//! (`traceparent`/`tracestate`) of the current `tracing` span using the globally
+//! registered propagator, so provider calls join the caller's distributed trace.
+//! Without the feature it returns an empty header map.
+
+use reqwest::header::HeaderMap;
+
+/// Returns the headers carrying the current trace context.
+#[cfg(feature = "otel")]
+pub fn headers() -> HeaderMap {
+    use opentelemetry::propagation::Injector;
+    use reqwest::header::{HeaderName, HeaderValue};
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+    struct HeaderInjector<'a>(&'a mut HeaderMap);
+
+    impl Injector for HeaderInjector<'_> {
+        fn set(&mut self, key: &str, value: String) {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::from_bytes(key.as_bytes()),
+                HeaderValue::from_str(&value),
+            ) {
+                self.0.insert(name, value);
+            }
+        }
+    }
+
+    let mut headers = HeaderMap::new();
+    let context = tracing::Span::current().context();
+    opentelemetry::global::get_text_map_propagator(|propagator| {
+        propagator.inject_context(&context, &mut HeaderInjector(&mut headers));
+    });
+    headers
+}
+
+/// Returns the headers carrying the current trace context.
+#[cfg(not(feature = "otel"))]
+pub fn headers() -> HeaderMap {
+    HeaderMap::new()
+}
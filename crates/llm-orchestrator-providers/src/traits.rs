@@ -5,7 +5,8 @@
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
 
 /// LLM provider trait.
 #[async_trait]
@@ -29,20 +30,138 @@ pub struct CompletionRequest {
     pub model: String,
 
     /// Prompt or messages.
+    ///
+    /// May be empty when `history` already ends where the model should
+    /// continue, e.g. with the results of its tool calls.
     pub prompt: String,
 
     /// System prompt (optional).
     pub system: Option<String>,
 
+    /// Earlier turns of the conversation, oldest first, sent before the prompt.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub history: Vec<ConversationTurn>,
+
+    /// Images sent with the prompt, for models that accept them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub images: Vec<ImageInput>,
+
     /// Temperature (0.0 - 2.0).
     pub temperature: Option<f32>,
 
     /// Maximum tokens to generate.
     pub max_tokens: Option<u32>,
 
+    /// Tools the model may call instead of answering.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<ToolSpec>,
+
     /// Additional parameters.
     #[serde(flatten)]
     pub extra: HashMap<String, serde_json::Value>,
+
+    /// Extra HTTP headers sent with the request.
+    ///
+    /// Not serialized, so headers never end up in captured or persisted requests.
+    #[serde(skip)]
+    pub headers: HashMap<String, String>,
+}
+
+/// A turn of an earlier conversation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConversationTurn {
+    /// Who spoke: `user`, `assistant`, or `tool` for the result of a tool call.
+    pub role: String,
+
+    /// What was said.
+    pub content: String,
+
+    /// Tools an assistant turn called.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<ToolCall>,
+
+    /// Call a `tool` turn answers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+}
+
+impl ConversationTurn {
+    /// A turn of `role` saying `content`.
+    pub fn new(role: impl Into<String>, content: impl Into<String>) -> Self {
+        Self {
+            role: role.into(),
+            content: content.into(),
+            tool_calls: Vec::new(),
+            tool_call_id: None,
+        }
+    }
+
+    /// The result of the tool call `call_id`.
+    pub fn tool_result(call_id: impl Into<String>, content: impl Into<String>) -> Self {
+        Self {
+            tool_call_id: Some(call_id.into()),
+            ..Self::new("tool", content)
+        }
+    }
+}
+
+/// An image sent with the prompt.
+///
+/// Serializes as the `source` of an Anthropic image block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ImageInput {
+    /// An image the provider downloads.
+    Url {
+        /// `http` or `https` URL of the image.
+        url: String,
+    },
+
+    /// Image bytes sent inline.
+    Base64 {
+        /// `image/png`, `image/jpeg`, `image/gif`, or `image/webp`.
+        media_type: String,
+
+        /// Base64-encoded bytes.
+        data: String,
+    },
+}
+
+impl ImageInput {
+    /// The image as a URL: its own, or a `data:` URL of its bytes.
+    pub fn to_url(&self) -> String {
+        match self {
+            Self::Url { url } => url.clone(),
+            Self::Base64 { media_type, data } => format!("data:{};base64,{}", media_type, data),
+        }
+    }
+}
+
+/// A tool offered to the model.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolSpec {
+    /// Tool name, made of letters, digits, `_`, and `-`.
+    pub name: String,
+
+    /// What the tool does, for the model to decide when to call it.
+    #[serde(default)]
+    pub description: String,
+
+    /// JSON Schema of the tool arguments.
+    pub parameters: serde_json::Value,
+}
+
+/// A tool call the model asked for.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolCall {
+    /// ID the result of the call refers to.
+    pub id: String,
+
+    /// Tool name.
+    pub name: String,
+
+    /// Arguments, a JSON object.
+    pub arguments: serde_json::Value,
 }
 
 /// Completion response.
@@ -57,11 +176,70 @@ pub struct CompletionResponse {
     /// Tokens used.
     pub tokens_used: Option<u32>,
 
+    /// Token counts and cost, when the provider reports them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<Usage>,
+
+    /// Tools the model called instead of, or besides, answering.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<ToolCall>,
+
     /// Additional metadata.
     #[serde(flatten)]
     pub metadata: HashMap<String, serde_json::Value>,
 }
 
+/// Tokens, cost, and latency of one or more provider calls.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Usage {
+    /// Prompt tokens.
+    #[serde(default)]
+    pub input_tokens: u64,
+
+    /// Completion tokens.
+    #[serde(default)]
+    pub output_tokens: u64,
+
+    /// Total tokens, which some providers report without a breakdown.
+    #[serde(default)]
+    pub total_tokens: u64,
+
+    /// Estimated cost in US dollars, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_usd: Option<f64>,
+
+    /// Time spent waiting on the provider, in milliseconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u64>,
+}
+
+impl Usage {
+    /// Usage of a call with the given prompt and completion tokens.
+    pub fn new(input_tokens: u64, output_tokens: u64) -> Self {
+        Self {
+            input_tokens,
+            output_tokens,
+            total_tokens: input_tokens + output_tokens,
+            ..Default::default()
+        }
+    }
+
+    /// Adds the usage of another call.
+    pub fn add(&mut self, other: &Usage) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.total_tokens += other.total_tokens;
+        self.cost_usd = match (self.cost_usd, other.cost_usd) {
+            (None, None) => None,
+            (a, b) => Some(a.unwrap_or(0.0) + b.unwrap_or(0.0)),
+        };
+        self.latency_ms = match (self.latency_ms, other.latency_ms) {
+            (None, None) => None,
+            (a, b) => Some(a.unwrap_or(0) + b.unwrap_or(0)),
+        };
+    }
+}
+
 /// Provider error.
 #[derive(Debug, thiserror::Error)]
 pub enum ProviderError {
@@ -75,12 +253,19 @@ pub enum ProviderError {
 
     /// Rate limit exceeded.
     #[error("Rate limit exceeded")]
-    RateLimitExceeded,
+    RateLimitExceeded {
+        /// How long the provider asked callers to wait, from its `retry-after` header.
+        retry_after: Option<Duration>,
+    },
 
     /// Invalid request.
     #[error("Invalid request: {0}")]
     InvalidRequest(String),
 
+    /// The provider answered with an HTTP error status not covered above.
+    #[error("API error (HTTP {status}): {message}")]
+    Api { status: u16, message: String },
+
     /// Provider-specific error.
     #[error("Provider error: {0}")]
     ProviderSpecific(String),
@@ -98,6 +283,35 @@ pub enum ProviderError {
     Unknown(String),
 }
 
+impl ProviderError {
+    /// Delay the provider asked for before retrying, if any.
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Self::RateLimitExceeded { retry_after } => *retry_after,
+            _ => None,
+        }
+    }
+
+    /// HTTP status of the provider response the error came from, when known.
+    pub fn http_status(&self) -> Option<u16> {
+        match self {
+            Self::Api { status, .. } => Some(*status),
+            Self::RateLimitExceeded { .. } => Some(429),
+            _ => None,
+        }
+    }
+
+    /// Attaches a `retry-after` hint to a rate limit error.
+    pub(crate) fn with_retry_after(self, hint: Option<Duration>) -> Self {
+        match self {
+            Self::RateLimitExceeded { retry_after } => Self::RateLimitExceeded {
+                retry_after: hint.or(retry_after),
+            },
+            other => other,
+        }
+    }
+}
+
 impl From<serde_json::Error> for ProviderError {
     fn from(err: serde_json::Error) -> Self {
         Self::SerializationError(err.to_string())
@@ -113,12 +327,29 @@ pub trait EmbeddingProvider: Send + Sync {
     /// Get provider name.
     fn name(&self) -> &str;
 
+    /// Length of the vectors `model` returns by default, when known.
+    fn dimensions(&self, model: &str) -> Option<usize> {
+        embedding_dimensions(model)
+    }
+
     /// Check if provider is healthy.
     async fn health_check(&self) -> Result<(), ProviderError> {
         Ok(())
     }
 }
 
+/// Default output dimension of well-known embedding models.
+pub fn embedding_dimensions(model: &str) -> Option<usize> {
+    match model {
+        "text-embedding-3-small" | "text-embedding-ada-002" | "embed-v4.0" => Some(1536),
+        "text-embedding-3-large" => Some(3072),
+        "embed-english-v3.0" | "embed-multilingual-v3.0" => Some(1024),
+        "embed-english-light-v3.0" | "embed-multilingual-light-v3.0" => Some(384),
+        "embed-english-v2.0" => Some(4096),
+        _ => None,
+    }
+}
+
 /// Embedding request.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EmbeddingRequest {
@@ -166,6 +397,141 @@ pub struct EmbeddingResponse {
     pub metadata: HashMap<String, serde_json::Value>,
 }
 
+/// Speech-to-text provider trait.
+#[async_trait]
+pub trait TranscriptionProvider: Send + Sync {
+    /// Transcribe audio.
+    async fn transcribe(&self, request: TranscriptionRequest) -> Result<TranscriptionResponse, ProviderError>;
+
+    /// Get provider name.
+    fn name(&self) -> &str;
+
+    /// Largest audio file accepted, in bytes, when limited.
+    fn max_audio_bytes(&self) -> Option<usize> {
+        None
+    }
+
+    /// Check if provider is healthy.
+    async fn health_check(&self) -> Result<(), ProviderError> {
+        Ok(())
+    }
+}
+
+/// Transcription request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptionRequest {
+    /// Model name.
+    pub model: String,
+
+    /// Audio file contents.
+    ///
+    /// Not serialized, so audio never ends up in captured or persisted requests.
+    #[serde(skip)]
+    pub audio: Vec<u8>,
+
+    /// File name of the audio, whose extension tells its format.
+    pub file_name: String,
+
+    /// Spoken language as an ISO-639-1 code, detected when unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+
+    /// Text that guides the style or spelling of the transcript.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt: Option<String>,
+
+    /// Additional parameters.
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+/// Transcription response.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TranscriptionResponse {
+    /// Full transcript.
+    pub text: String,
+
+    /// Timed segments of the transcript, in order.
+    #[serde(default)]
+    pub segments: Vec<TranscriptSegment>,
+
+    /// Language spoken, as the provider reports it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+
+    /// Length of the audio, in seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_seconds: Option<f64>,
+
+    /// Model used.
+    pub model: String,
+}
+
+/// A timed part of a transcript.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptSegment {
+    /// Start, in seconds from the beginning of the audio.
+    pub start: f64,
+
+    /// End, in seconds from the beginning of the audio.
+    pub end: f64,
+
+    /// What was said.
+    pub text: String,
+}
+
+/// Text-to-speech provider trait.
+#[async_trait]
+pub trait SpeechProvider: Send + Sync {
+    /// Synthesize speech.
+    async fn synthesize(&self, request: SpeechRequest) -> Result<SpeechResponse, ProviderError>;
+
+    /// Get provider name.
+    fn name(&self) -> &str;
+
+    /// Check if provider is healthy.
+    async fn health_check(&self) -> Result<(), ProviderError> {
+        Ok(())
+    }
+}
+
+/// Speech synthesis request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpeechRequest {
+    /// Model name.
+    pub model: String,
+
+    /// Text to speak.
+    pub text: String,
+
+    /// Voice name or ID.
+    pub voice: String,
+
+    /// Audio format, such as `mp3`, `wav`, `opus`, or `pcm`.
+    pub format: String,
+
+    /// Speaking rate, 1.0 being normal.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speed: Option<f32>,
+
+    /// Additional parameters.
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+/// Synthesized speech.
+#[derive(Debug, Clone)]
+pub struct SpeechResponse {
+    /// Audio file contents, in the requested format.
+    pub audio: Vec<u8>,
+
+    /// Media type of the audio, as the provider reports it.
+    pub content_type: Option<String>,
+
+    /// Model used.
+    pub model: String,
+}
+
 /// Vector search provider trait.
 #[async_trait]
 pub trait VectorSearchProvider: Send + Sync {
@@ -178,6 +544,52 @@ pub trait VectorSearchProvider: Send + Sync {
     /// Delete vectors by ID.
     async fn delete(&self, request: DeleteRequest) -> Result<DeleteResponse, ProviderError>;
 
+    /// Fetch records by ID. IDs with no record are left out of the response.
+    async fn fetch(&self, request: FetchRequest) -> Result<FetchResponse, ProviderError> {
+        Err(ProviderError::ProviderSpecific(format!(
+            "{} cannot fetch records from index '{}'",
+            self.name(),
+            request.index
+        )))
+    }
+
+    /// List the IDs of records in an index, one page at a time.
+    async fn list_ids(&self, request: ListIdsRequest) -> Result<ListIdsResponse, ProviderError> {
+        Err(ProviderError::ProviderSpecific(format!(
+            "{} cannot list records in index '{}'",
+            self.name(),
+            request.index
+        )))
+    }
+
+    /// Most vectors a single upsert request may carry, if the database limits it.
+    fn max_upsert_batch(&self) -> Option<usize> {
+        None
+    }
+
+    /// Describe an index, or `None` when the database cannot.
+    async fn index_stats(&self, _index: &str) -> Result<Option<IndexStats>, ProviderError> {
+        Ok(None)
+    }
+
+    /// Create an index for vectors of the given length.
+    async fn create_index(&self, request: CreateIndexRequest) -> Result<(), ProviderError> {
+        Err(ProviderError::ProviderSpecific(format!(
+            "{} cannot create index '{}'",
+            self.name(),
+            request.index
+        )))
+    }
+
+    /// Delete an index and every vector in it.
+    async fn delete_index(&self, index: &str) -> Result<(), ProviderError> {
+        Err(ProviderError::ProviderSpecific(format!(
+            "{} cannot delete index '{}'",
+            self.name(),
+            index
+        )))
+    }
+
     /// Get provider name.
     fn name(&self) -> &str;
 
@@ -187,6 +599,132 @@ pub trait VectorSearchProvider: Send + Sync {
     }
 }
 
+/// Size and shape of a vector index.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IndexStats {
+    /// Length of the index's vectors.
+    pub dimension: Option<usize>,
+
+    /// Number of vectors stored.
+    pub vector_count: Option<u64>,
+
+    /// Similarity metric the index ranks results by.
+    pub metric: Option<DistanceMetric>,
+}
+
+/// Request to create a vector index.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateIndexRequest {
+    /// Index/collection name.
+    pub index: String,
+
+    /// Length of the vectors the index stores.
+    pub dimension: usize,
+
+    /// Similarity metric to rank results by.
+    #[serde(default)]
+    pub metric: DistanceMetric,
+
+    /// Metadata fields to index for filtering, with their types. Databases
+    /// that index all metadata ignore it.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata_indexes: BTreeMap<String, MetadataFieldType>,
+}
+
+/// Type of an indexed metadata field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MetadataFieldType {
+    /// Exact-match strings.
+    Keyword,
+    /// Integers, matched exactly or by range.
+    Integer,
+    /// Floating-point numbers, matched by range.
+    Float,
+    /// Booleans.
+    Bool,
+    /// Full-text searchable strings.
+    Text,
+    /// RFC 3339 timestamps, matched by range.
+    Datetime,
+}
+
+impl MetadataFieldType {
+    /// Name of the type as accepted by [`FromStr`](std::str::FromStr).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Keyword => "keyword",
+            Self::Integer => "integer",
+            Self::Float => "float",
+            Self::Bool => "bool",
+            Self::Text => "text",
+            Self::Datetime => "datetime",
+        }
+    }
+}
+
+impl std::str::FromStr for MetadataFieldType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "keyword" => Ok(Self::Keyword),
+            "integer" => Ok(Self::Integer),
+            "float" => Ok(Self::Float),
+            "bool" => Ok(Self::Bool),
+            "text" => Ok(Self::Text),
+            "datetime" => Ok(Self::Datetime),
+            _ => Err(format!(
+                "Unknown metadata field type '{}' (expected keyword, integer, float, bool, text, or datetime)",
+                s
+            )),
+        }
+    }
+}
+
+/// Similarity metric of a vector index.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DistanceMetric {
+    /// Cosine similarity.
+    #[default]
+    Cosine,
+    /// Euclidean distance.
+    Euclidean,
+    /// Dot product.
+    DotProduct,
+}
+
+impl DistanceMetric {
+    /// Name of the metric as accepted by [`FromStr`](std::str::FromStr).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Cosine => "cosine",
+            Self::Euclidean => "euclidean",
+            Self::DotProduct => "dotproduct",
+        }
+    }
+}
+
+impl std::fmt::Display for DistanceMetric {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for DistanceMetric {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "cosine" => Ok(Self::Cosine),
+            "euclidean" | "euclid" | "l2" => Ok(Self::Euclidean),
+            "dotproduct" | "dot" => Ok(Self::DotProduct),
+            _ => Err(format!("Unknown distance metric '{}' (expected cosine, euclidean, or dotproduct)", s)),
+        }
+    }
+}
+
 /// Vector search request.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VectorSearchRequest {
@@ -214,12 +752,70 @@ pub struct VectorSearchRequest {
     /// Include vector embeddings in results.
     #[serde(default)]
     pub include_vectors: bool,
+
+    /// How documents are matched. Only Weaviate supports keyword and hybrid
+    /// search; other databases reject them.
+    #[serde(default)]
+    pub mode: SearchMode,
+
+    /// Text to match in keyword and hybrid modes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+
+    /// Weight of vector against keyword scores in hybrid mode, from 0.0
+    /// (keyword only) to 1.0 (vector only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alpha: Option<f32>,
+
+    /// How hybrid mode merges vector and keyword results.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fusion: Option<HybridFusion>,
 }
 
 fn default_true_vs() -> bool {
     true
 }
 
+/// How a vector search matches documents.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchMode {
+    /// Nearest neighbours of the query vector.
+    #[default]
+    Vector,
+    /// BM25 ranking of the query text.
+    Keyword,
+    /// Vector and keyword results fused into one ranking.
+    Hybrid,
+}
+
+impl SearchMode {
+    /// Name used in workflow files, e.g. `hybrid`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Vector => "vector",
+            Self::Keyword => "keyword",
+            Self::Hybrid => "hybrid",
+        }
+    }
+}
+
+impl std::fmt::Display for SearchMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// How a hybrid search merges its vector and keyword results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HybridFusion {
+    /// Score each result by its rank in either list.
+    Ranked,
+    /// Score each result by its normalized scores in either list.
+    RelativeScore,
+}
+
 /// Vector search response.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VectorSearchResponse {
@@ -277,6 +873,61 @@ pub struct VectorRecord {
     pub metadata: Option<serde_json::Value>,
 }
 
+/// Request for records by ID.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FetchRequest {
+    /// Index/collection name.
+    pub index: String,
+
+    /// IDs of the records to fetch.
+    pub ids: Vec<String>,
+
+    /// Namespace/partition (optional).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+}
+
+/// Records found by ID.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FetchResponse {
+    /// Records found, in the order they were requested.
+    pub records: Vec<VectorRecord>,
+}
+
+/// Request for a page of record IDs.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ListIdsRequest {
+    /// Index/collection name.
+    pub index: String,
+
+    /// Namespace/partition (optional).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+
+    /// Only list IDs starting with this prefix.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+
+    /// Most IDs to return; the database's default when unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+
+    /// Token from the previous page's response.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pagination_token: Option<String>,
+}
+
+/// A page of record IDs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListIdsResponse {
+    /// Record IDs on this page.
+    pub ids: Vec<String>,
+
+    /// Token for the next page, or `None` on the last page.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_token: Option<String>,
+}
+
 /// Upsert response.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpsertResponse {
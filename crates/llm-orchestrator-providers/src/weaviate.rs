@@ -3,6 +3,8 @@
 
 //! Weaviate vector database client implementation.
 
+use crate::retry;
+use crate::trace_context;
 use crate::traits::*;
 use async_trait::async_trait;
 use reqwest::Client;
@@ -36,24 +38,33 @@ impl WeaviateClient {
             api_key,
         })
     }
+
+    /// Create a Weaviate client from the `WEAVIATE_URL` and optional
+    /// `WEAVIATE_API_KEY` environment variables.
+    pub fn from_env() -> Result<Self, ProviderError> {
+        let base_url = std::env::var("WEAVIATE_URL").map_err(|_| {
+            ProviderError::InvalidRequest("WEAVIATE_URL environment variable not set".to_string())
+        })?;
+
+        Self::new(base_url, std::env::var("WEAVIATE_API_KEY").ok())
+    }
 }
 
 #[async_trait]
 impl VectorSearchProvider for WeaviateClient {
     async fn search(&self, request: VectorSearchRequest) -> Result<VectorSearchResponse, ProviderError> {
-        // Build Weaviate GraphQL query
+        // Build Weaviate GraphQL query; keyword and hybrid searches report a score, not a distance
+        let additional = match request.mode {
+            SearchMode::Vector => "_additional { id distance }",
+            SearchMode::Keyword | SearchMode::Hybrid => "_additional { id score }",
+        };
         let fields = if request.include_metadata {
-            "_additional { id distance } ... on * { * }"
+            format!("{} ... on * {{ * }}", additional)
         } else {
-            "_additional { id distance }"
+            additional.to_string()
         };
 
-        let vector_str = format!("[{}]",
-            request.query.iter()
-                .map(|v| v.to_string())
-                .collect::<Vec<_>>()
-                .join(",")
-        );
+        let operator = search_operator(&request)?;
 
         let where_clause = if let Some(filter) = &request.filter {
             format!(", where: {}", serde_json::to_string(filter)
@@ -66,7 +77,7 @@ impl VectorSearchProvider for WeaviateClient {
             r#"{{
                 Get {{
                     {} (
-                        nearVector: {{ vector: {} }}
+                        {}
                         limit: {}
                         {}
                     ) {{
@@ -75,7 +86,7 @@ impl VectorSearchProvider for WeaviateClient {
                 }}
             }}"#,
             request.index,
-            vector_str,
+            operator,
             request.top_k,
             where_clause,
             fields
@@ -97,11 +108,14 @@ impl VectorSearchProvider for WeaviateClient {
         }
 
         let response = req_builder
+            .headers(trace_context::headers())
             .send()
             .await
             .map_err(|e| ProviderError::HttpError(e.to_string()))?;
 
         let status = response.status();
+
+        let retry_after = retry::retry_after(response.headers());
         if !status.is_success() {
             let error_text = response
                 .text()
@@ -109,9 +123,12 @@ impl VectorSearchProvider for WeaviateClient {
                 .unwrap_or_else(|_| "Unknown error".to_string());
             return Err(match status.as_u16() {
                 401 => ProviderError::AuthError(error_text),
-                429 => ProviderError::RateLimitExceeded,
+                429 => ProviderError::RateLimitExceeded { retry_after },
                 400..=499 => ProviderError::InvalidRequest(error_text),
-                _ => ProviderError::ProviderSpecific(error_text),
+                _ => ProviderError::Api {
+                    status: status.as_u16(),
+                    message: error_text,
+                },
             });
         }
 
@@ -139,10 +156,15 @@ impl VectorSearchProvider for WeaviateClient {
                 let obj = item.as_object()?;
                 let additional = obj.get("_additional")?.as_object()?;
                 let id = additional.get("id")?.as_str()?.to_string();
-                let distance = additional.get("distance")?.as_f64()? as f32;
-
-                // Convert distance to similarity score (Weaviate uses cosine distance)
-                let score = 1.0 - distance;
+                let score = match request.mode {
+                    // Convert distance to similarity score (Weaviate uses cosine distance)
+                    SearchMode::Vector => 1.0 - additional.get("distance")?.as_f64()? as f32,
+                    // Weaviate returns BM25 and fused scores as strings
+                    SearchMode::Keyword | SearchMode::Hybrid => match additional.get("score")? {
+                        serde_json::Value::String(score) => score.parse().ok()?,
+                        score => score.as_f64()? as f32,
+                    },
+                };
 
                 // Extract metadata (everything except _additional)
                 let mut metadata = serde_json::Map::new();
@@ -205,11 +227,14 @@ impl VectorSearchProvider for WeaviateClient {
         }
 
         let response = req_builder
+            .headers(trace_context::headers())
             .send()
             .await
             .map_err(|e| ProviderError::HttpError(e.to_string()))?;
 
         let status = response.status();
+
+        let retry_after = retry::retry_after(response.headers());
         if !status.is_success() {
             let error_text = response
                 .text()
@@ -217,9 +242,12 @@ impl VectorSearchProvider for WeaviateClient {
                 .unwrap_or_else(|_| "Unknown error".to_string());
             return Err(match status.as_u16() {
                 401 => ProviderError::AuthError(error_text),
-                429 => ProviderError::RateLimitExceeded,
+                429 => ProviderError::RateLimitExceeded { retry_after },
                 400..=499 => ProviderError::InvalidRequest(error_text),
-                _ => ProviderError::ProviderSpecific(error_text),
+                _ => ProviderError::Api {
+                    status: status.as_u16(),
+                    message: error_text,
+                },
             });
         }
 
@@ -253,6 +281,7 @@ impl VectorSearchProvider for WeaviateClient {
             }
 
             let response = req_builder
+                .headers(trace_context::headers())
                 .send()
                 .await
                 .map_err(|e| ProviderError::HttpError(e.to_string()))?;
@@ -268,6 +297,198 @@ impl VectorSearchProvider for WeaviateClient {
         })
     }
 
+    async fn index_stats(&self, index: &str) -> Result<Option<IndexStats>, ProviderError> {
+        let url = format!("{}/v1/schema/{}", self.base_url, index);
+
+        let mut req_builder = self.client.get(&url);
+
+        if let Some(api_key) = &self.api_key {
+            req_builder = req_builder.header("Authorization", format!("Bearer {}", api_key));
+        }
+
+        let response = req_builder
+            .headers(trace_context::headers())
+            .send()
+            .await
+            .map_err(|e| ProviderError::HttpError(e.to_string()))?;
+
+        let status = response.status();
+
+        let retry_after = retry::retry_after(response.headers());
+        if !status.is_success() {
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(match status.as_u16() {
+                401 => ProviderError::AuthError(error_text),
+                429 => ProviderError::RateLimitExceeded { retry_after },
+                400..=499 => ProviderError::InvalidRequest(error_text),
+                _ => ProviderError::Api {
+                    status: status.as_u16(),
+                    message: error_text,
+                },
+            });
+        }
+
+        let class: WeaviateClass = response
+            .json()
+            .await
+            .map_err(|e| ProviderError::SerializationError(e.to_string()))?;
+
+        // Object counts are only available through an aggregate query
+        let count_request = json!({
+            "query": format!("{{ Aggregate {{ {} {{ meta {{ count }} }} }} }}", index)
+        });
+
+        let url = format!("{}/v1/graphql", self.base_url);
+
+        let mut req_builder = self.client
+            .post(&url)
+            .header("Content-Type", "application/json")
+            .json(&count_request);
+
+        if let Some(api_key) = &self.api_key {
+            req_builder = req_builder.header("Authorization", format!("Bearer {}", api_key));
+        }
+
+        let response = req_builder
+            .headers(trace_context::headers())
+            .send()
+            .await
+            .map_err(|e| ProviderError::HttpError(e.to_string()))?;
+
+        let status = response.status();
+
+        let retry_after = retry::retry_after(response.headers());
+        if !status.is_success() {
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(match status.as_u16() {
+                401 => ProviderError::AuthError(error_text),
+                429 => ProviderError::RateLimitExceeded { retry_after },
+                400..=499 => ProviderError::InvalidRequest(error_text),
+                _ => ProviderError::Api {
+                    status: status.as_u16(),
+                    message: error_text,
+                },
+            });
+        }
+
+        let api_response: WeaviateQueryResponse = response
+            .json()
+            .await
+            .map_err(|e| ProviderError::SerializationError(e.to_string()))?;
+        let vector_count = api_response
+            .data
+            .as_ref()
+            .and_then(|d| d.pointer(&format!("/Aggregate/{}/0/meta/count", index)))
+            .and_then(|count| count.as_u64());
+
+        // Weaviate sizes a class by the first vector stored, and does not report it
+        Ok(Some(IndexStats {
+            dimension: None,
+            vector_count,
+            metric: class.vector_index_config.and_then(|config| config.distance).and_then(|distance| match distance.as_str() {
+                "cosine" => Some(DistanceMetric::Cosine),
+                "l2-squared" => Some(DistanceMetric::Euclidean),
+                "dot" => Some(DistanceMetric::DotProduct),
+                _ => None,
+            }),
+        }))
+    }
+
+    async fn create_index(&self, request: CreateIndexRequest) -> Result<(), ProviderError> {
+        // Vectors are supplied by the caller, and the class takes the length of the first one
+        let distance = match request.metric {
+            DistanceMetric::Cosine => "cosine",
+            DistanceMetric::Euclidean => "l2-squared",
+            DistanceMetric::DotProduct => "dot",
+        };
+        let api_request = json!({
+            "class": request.index,
+            "vectorizer": "none",
+            "vectorIndexConfig": { "distance": distance },
+        });
+
+        let url = format!("{}/v1/schema", self.base_url);
+
+        let mut req_builder = self.client
+            .post(&url)
+            .header("Content-Type", "application/json")
+            .json(&api_request);
+
+        if let Some(api_key) = &self.api_key {
+            req_builder = req_builder.header("Authorization", format!("Bearer {}", api_key));
+        }
+
+        let response = req_builder
+            .headers(trace_context::headers())
+            .send()
+            .await
+            .map_err(|e| ProviderError::HttpError(e.to_string()))?;
+
+        let status = response.status();
+
+        let retry_after = retry::retry_after(response.headers());
+        if !status.is_success() {
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(match status.as_u16() {
+                401 => ProviderError::AuthError(error_text),
+                429 => ProviderError::RateLimitExceeded { retry_after },
+                400..=499 => ProviderError::InvalidRequest(error_text),
+                _ => ProviderError::Api {
+                    status: status.as_u16(),
+                    message: error_text,
+                },
+            });
+        }
+
+        Ok(())
+    }
+
+    async fn delete_index(&self, index: &str) -> Result<(), ProviderError> {
+        let url = format!("{}/v1/schema/{}", self.base_url, index);
+
+        let mut req_builder = self.client.delete(&url);
+
+        if let Some(api_key) = &self.api_key {
+            req_builder = req_builder.header("Authorization", format!("Bearer {}", api_key));
+        }
+
+        let response = req_builder
+            .headers(trace_context::headers())
+            .send()
+            .await
+            .map_err(|e| ProviderError::HttpError(e.to_string()))?;
+
+        let status = response.status();
+
+        let retry_after = retry::retry_after(response.headers());
+        if !status.is_success() {
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(match status.as_u16() {
+                401 => ProviderError::AuthError(error_text),
+                429 => ProviderError::RateLimitExceeded { retry_after },
+                400..=499 => ProviderError::InvalidRequest(error_text),
+                _ => ProviderError::Api {
+                    status: status.as_u16(),
+                    message: error_text,
+                },
+            });
+        }
+
+        Ok(())
+    }
+
     fn name(&self) -> &str {
         "weaviate"
     }
@@ -283,6 +504,17 @@ struct WeaviateQueryResponse {
     errors: Option<Vec<serde_json::Value>>,
 }
 
+#[derive(Debug, Deserialize)]
+struct WeaviateClass {
+    #[serde(rename = "vectorIndexConfig")]
+    vector_index_config: Option<WeaviateVectorIndexConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WeaviateVectorIndexConfig {
+    distance: Option<String>,
+}
+
 #[derive(Debug, Serialize)]
 struct WeaviateBatchRequest {
     objects: Vec<WeaviateObject>,
@@ -308,6 +540,47 @@ struct WeaviateBatchResult {
     status: String,
 }
 
+/// GraphQL search operator for the request's mode, e.g. `nearVector: { ... }`.
+fn search_operator(request: &VectorSearchRequest) -> Result<String, ProviderError> {
+    let vector = || {
+        format!(
+            "[{}]",
+            request.query.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(",")
+        )
+    };
+    let text = || {
+        let text = request.text.as_deref().ok_or_else(|| {
+            ProviderError::InvalidRequest(format!("Weaviate {} search needs query text", request.mode))
+        })?;
+        serde_json::to_string(text).map_err(|e| ProviderError::SerializationError(e.to_string()))
+    };
+
+    Ok(match request.mode {
+        SearchMode::Vector => format!("nearVector: {{ vector: {} }}", vector()),
+        SearchMode::Keyword => format!("bm25: {{ query: {} }}", text()?),
+        SearchMode::Hybrid => {
+            let mut arguments = vec![format!("query: {}", text()?)];
+            // Without a vector, Weaviate embeds the query text with the class's vectorizer
+            if !request.query.is_empty() {
+                arguments.push(format!("vector: {}", vector()));
+            }
+            if let Some(alpha) = request.alpha {
+                arguments.push(format!("alpha: {}", alpha));
+            }
+            if let Some(fusion) = request.fusion {
+                arguments.push(format!(
+                    "fusionType: {}",
+                    match fusion {
+                        HybridFusion::Ranked => "rankedFusion",
+                        HybridFusion::RelativeScore => "relativeScoreFusion",
+                    }
+                ));
+            }
+            format!("hybrid: {{ {} }}", arguments.join(", "))
+        }
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -376,4 +649,126 @@ mod tests {
         assert!(json_str.contains("key1"));
         assert!(json_str.contains("value1"));
     }
+
+    #[tokio::test]
+    async fn test_index_lifecycle() {
+        let mut server = mockito::Server::new_async().await;
+        let create = server
+            .mock("POST", "/v1/schema")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "class": "Docs",
+                "vectorIndexConfig": {"distance": "dot"}
+            })))
+            .with_status(200)
+            .with_body(r#"{"class": "Docs"}"#)
+            .create_async()
+            .await;
+        let _schema = server
+            .mock("GET", "/v1/schema/Docs")
+            .with_status(200)
+            .with_body(r#"{"class": "Docs", "vectorIndexConfig": {"distance": "dot"}}"#)
+            .create_async()
+            .await;
+        let _count = server
+            .mock("POST", "/v1/graphql")
+            .with_status(200)
+            .with_body(r#"{"data": {"Aggregate": {"Docs": [{"meta": {"count": 7}}]}}}"#)
+            .create_async()
+            .await;
+        let delete = server
+            .mock("DELETE", "/v1/schema/Docs")
+            .with_status(200)
+            .create_async()
+            .await;
+
+        let client = WeaviateClient::new(server.url(), None).unwrap();
+        client
+            .create_index(CreateIndexRequest {
+                index: "Docs".to_string(),
+                dimension: 384,
+                metric: DistanceMetric::DotProduct,
+                metadata_indexes: Default::default(),
+            })
+            .await
+            .unwrap();
+        create.assert_async().await;
+
+        let stats = client.index_stats("Docs").await.unwrap().unwrap();
+        assert_eq!(stats.vector_count, Some(7));
+        assert_eq!(stats.metric, Some(DistanceMetric::DotProduct));
+
+        client.delete_index("Docs").await.unwrap();
+        delete.assert_async().await;
+    }
+
+    fn search_request(mode: SearchMode) -> VectorSearchRequest {
+        VectorSearchRequest {
+            index: "Docs".to_string(),
+            query: vec![0.5, 0.25],
+            top_k: 3,
+            namespace: None,
+            filter: None,
+            include_metadata: false,
+            include_vectors: false,
+            mode,
+            text: Some("rust \"async\"".to_string()),
+            alpha: None,
+            fusion: None,
+        }
+    }
+
+    #[test]
+    fn test_search_operator() {
+        let request = search_request(SearchMode::Vector);
+        assert_eq!(search_operator(&request).unwrap(), "nearVector: { vector: [0.5,0.25] }");
+
+        let request = search_request(SearchMode::Keyword);
+        assert_eq!(search_operator(&request).unwrap(), r#"bm25: { query: "rust \"async\"" }"#);
+
+        let request = VectorSearchRequest {
+            alpha: Some(0.25),
+            fusion: Some(HybridFusion::RelativeScore),
+            ..search_request(SearchMode::Hybrid)
+        };
+        assert_eq!(
+            search_operator(&request).unwrap(),
+            r#"hybrid: { query: "rust \"async\"", vector: [0.5,0.25], alpha: 0.25, fusionType: relativeScoreFusion }"#
+        );
+
+        let request = VectorSearchRequest {
+            text: None,
+            ..search_request(SearchMode::Hybrid)
+        };
+        assert!(matches!(search_operator(&request), Err(ProviderError::InvalidRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_hybrid_search_reads_scores() {
+        let mut server = mockito::Server::new_async().await;
+        let search = server
+            .mock("POST", "/v1/graphql")
+            .match_body(mockito::Matcher::Regex("hybrid: \\{ query: .*fusionType: rankedFusion".to_string()))
+            .with_status(200)
+            .with_body(
+                r#"{"data": {"Get": {"Docs": [
+                    {"_additional": {"id": "a", "score": "0.032"}},
+                    {"_additional": {"id": "b", "score": "0.016"}}
+                ]}}}"#,
+            )
+            .create_async()
+            .await;
+
+        let client = WeaviateClient::new(server.url(), None).unwrap();
+        let response = client
+            .search(VectorSearchRequest {
+                fusion: Some(HybridFusion::Ranked),
+                ..search_request(SearchMode::Hybrid)
+            })
+            .await
+            .unwrap();
+        search.assert_async().await;
+
+        let scores: Vec<(&str, f32)> = response.results.iter().map(|r| (r.id.as_str(), r.score)).collect();
+        assert_eq!(scores, [("a", 0.032), ("b", 0.016)]);
+    }
 }
@@ -0,0 +1,412 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! The `WorkflowBuilder`, `StepHandle`, and `Output` Python classes.
+//!
+//! Steps are configured with keyword arguments rather than closures, and
+//! every method returns the builder so calls chain as in Rust:
+//!
+//! ```python
+//! retrieve = lo.StepHandle("retrieve")
+//! docs = retrieve.output("docs")
+//!
+//! workflow = (
+//!     lo.WorkflowBuilder("rag")
+//!     .transform_step(retrieve, "merge", inputs=["corpus"], produces=[docs])
+//!     .llm_step("answer", provider="openai", model="gpt-4", prompt=f"Using {docs}")
+//!     .build()
+//! )
+//! ```
+
+use crate::workflow::PyWorkflow;
+use crate::{py_to_json, to_py_err, OrchestratorError};
+use llm_orchestrator_sdk::builder::{StepBuilder, StepKind};
+use llm_orchestrator_sdk::{Output, StepHandle, WorkflowBuilder};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Names a step in one place; pass it as the step ID and derive outputs from it.
+#[pyclass(name = "StepHandle", module = "llm_orchestrator", frozen)]
+#[derive(Clone)]
+pub struct PyStepHandle {
+    inner: StepHandle,
+}
+
+#[pymethods]
+impl PyStepHandle {
+    #[new]
+    fn new(id: String) -> Self {
+        Self {
+            inner: StepHandle::new(id),
+        }
+    }
+
+    /// Step ID.
+    #[getter]
+    fn id(&self) -> &str {
+        self.inner.id()
+    }
+
+    /// Refers to output `name` of this step.
+    fn output(&self, name: String) -> PyOutput {
+        PyOutput {
+            inner: self.inner.output::<Value>(name),
+        }
+    }
+
+    fn __repr__(&self) -> String {
+        format!("StepHandle({:?})", self.inner.id())
+    }
+}
+
+/// An output of a step; `str()` renders it as `{{steps.<step>.<name>}}`.
+#[pyclass(name = "Output", module = "llm_orchestrator", frozen)]
+#[derive(Clone)]
+pub struct PyOutput {
+    inner: Output,
+}
+
+#[pymethods]
+impl PyOutput {
+    #[new]
+    fn new(step_id: String, name: String) -> Self {
+        Self {
+            inner: Output::new(step_id, name),
+        }
+    }
+
+    /// ID of the producing step.
+    #[getter]
+    fn step_id(&self) -> &str {
+        self.inner.step_id()
+    }
+
+    /// Output name.
+    #[getter]
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn __str__(&self) -> String {
+        self.inner.to_string()
+    }
+
+    fn __format__(&self, _spec: &str) -> String {
+        self.inner.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Output({:?}, {:?})",
+            self.inner.step_id(),
+            self.inner.name()
+        )
+    }
+}
+
+/// A step ID given as a string or a `StepHandle`.
+#[derive(FromPyObject)]
+enum StepId {
+    Handle(PyStepHandle),
+    Name(String),
+}
+
+impl From<StepId> for String {
+    fn from(id: StepId) -> Self {
+        match id {
+            StepId::Handle(handle) => handle.inner.id().to_string(),
+            StepId::Name(name) => name,
+        }
+    }
+}
+
+/// Keyword arguments shared by all step types.
+struct Common {
+    depends_on: Vec<String>,
+    condition: Option<String>,
+    output: Vec<String>,
+    produces: Vec<PyOutput>,
+    timeout_seconds: Option<u64>,
+}
+
+impl Common {
+    fn apply<C: StepKind>(self, mut step: StepBuilder<C>) -> StepBuilder<C> {
+        for dependency in self.depends_on {
+            step = step.depends_on(dependency);
+        }
+        if let Some(condition) = self.condition {
+            step = step.condition(condition);
+        }
+        for name in self.output {
+            step = step.output(name);
+        }
+        for output in &self.produces {
+            step = step.produces(&output.inner);
+        }
+        if let Some(seconds) = self.timeout_seconds {
+            step = step.timeout(Duration::from_secs(seconds));
+        }
+        step
+    }
+}
+
+/// Converts a dict of parameters into JSON values.
+fn params(params: Option<&Bound<'_, PyDict>>) -> PyResult<HashMap<String, Value>> {
+    match params.map(|p| py_to_json(p.as_any())).transpose()? {
+        Some(Value::Object(map)) => Ok(map.into_iter().collect()),
+        _ => Ok(HashMap::new()),
+    }
+}
+
+/// Builds a validated `Workflow` step by step.
+#[pyclass(name = "WorkflowBuilder", module = "llm_orchestrator")]
+pub struct PyWorkflowBuilder {
+    inner: Option<WorkflowBuilder>,
+}
+
+impl PyWorkflowBuilder {
+    fn update(&mut self, f: impl FnOnce(WorkflowBuilder) -> WorkflowBuilder) -> PyResult<()> {
+        let builder = self
+            .inner
+            .take()
+            .ok_or_else(|| OrchestratorError::new_err("WorkflowBuilder was already built"))?;
+        self.inner = Some(f(builder));
+        Ok(())
+    }
+}
+
+#[pymethods]
+impl PyWorkflowBuilder {
+    #[new]
+    #[pyo3(signature = (name, *, version=None, description=None, timeout_seconds=None))]
+    fn new(
+        name: String,
+        version: Option<String>,
+        description: Option<String>,
+        timeout_seconds: Option<u64>,
+    ) -> Self {
+        let mut builder = WorkflowBuilder::new(name);
+        if let Some(version) = version {
+            builder = builder.version(version);
+        }
+        if let Some(description) = description {
+            builder = builder.description(description);
+        }
+        if let Some(seconds) = timeout_seconds {
+            builder = builder.timeout(Duration::from_secs(seconds));
+        }
+        Self {
+            inner: Some(builder),
+        }
+    }
+
+    /// Adds a metadata entry.
+    fn metadata<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        key: String,
+        value: &Bound<'py, PyAny>,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        let value = py_to_json(value)?;
+        slf.update(|b| b.metadata(key, value))?;
+        Ok(slf)
+    }
+
+    /// Adds an LLM completion step; it needs a prompt or a prompt_ref.
+    #[pyo3(signature = (
+        id, *, provider, model, prompt=None, prompt_ref=None, system=None, temperature=None, max_tokens=None,
+        params=None, depends_on=Vec::new(), condition=None, output=Vec::new(), produces=Vec::new(),
+        timeout_seconds=None
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn llm_step<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        id: StepId,
+        provider: String,
+        model: String,
+        prompt: Option<String>,
+        prompt_ref: Option<String>,
+        system: Option<String>,
+        temperature: Option<f32>,
+        max_tokens: Option<u32>,
+        params: Option<&Bound<'py, PyDict>>,
+        depends_on: Vec<String>,
+        condition: Option<String>,
+        output: Vec<String>,
+        produces: Vec<PyOutput>,
+        timeout_seconds: Option<u64>,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        let params = self::params(params)?;
+        let common = Common {
+            depends_on,
+            condition,
+            output,
+            produces,
+            timeout_seconds,
+        };
+        slf.update(|b| {
+            b.llm_step(String::from(id), |mut s| {
+                s = s.provider(provider).model(model);
+                if let Some(prompt) = prompt {
+                    s = s.prompt(prompt);
+                }
+                if let Some(prompt_ref) = prompt_ref {
+                    s = s.prompt_ref(prompt_ref);
+                }
+                if let Some(system) = system {
+                    s = s.system(system);
+                }
+                if let Some(temperature) = temperature {
+                    s = s.temperature(temperature);
+                }
+                if let Some(max_tokens) = max_tokens {
+                    s = s.max_tokens(max_tokens);
+                }
+                for (key, value) in params {
+                    s = s.param(key, value);
+                }
+                common.apply(s)
+            })
+        })?;
+        Ok(slf)
+    }
+
+    /// Adds an embedding step.
+    #[pyo3(signature = (
+        id, *, provider, model, input, dimensions=None, depends_on=Vec::new(), condition=None, output=Vec::new(),
+        produces=Vec::new(), timeout_seconds=None
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn embed_step<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        id: StepId,
+        provider: String,
+        model: String,
+        input: String,
+        dimensions: Option<usize>,
+        depends_on: Vec<String>,
+        condition: Option<String>,
+        output: Vec<String>,
+        produces: Vec<PyOutput>,
+        timeout_seconds: Option<u64>,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        let common = Common {
+            depends_on,
+            condition,
+            output,
+            produces,
+            timeout_seconds,
+        };
+        slf.update(|b| {
+            b.embed_step(String::from(id), |mut s| {
+                s = s.provider(provider).model(model).input(input);
+                if let Some(dimensions) = dimensions {
+                    s = s.dimensions(dimensions);
+                }
+                common.apply(s)
+            })
+        })?;
+        Ok(slf)
+    }
+
+    /// Adds a step applying the transform `function`.
+    #[pyo3(signature = (
+        id, function, *, inputs=Vec::new(), params=None, depends_on=Vec::new(), condition=None, output=Vec::new(),
+        produces=Vec::new(), timeout_seconds=None
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn transform_step<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        id: StepId,
+        function: String,
+        inputs: Vec<String>,
+        params: Option<&Bound<'py, PyDict>>,
+        depends_on: Vec<String>,
+        condition: Option<String>,
+        output: Vec<String>,
+        produces: Vec<PyOutput>,
+        timeout_seconds: Option<u64>,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        let params = self::params(params)?;
+        let common = Common {
+            depends_on,
+            condition,
+            output,
+            produces,
+            timeout_seconds,
+        };
+        slf.update(|b| {
+            b.transform_step(String::from(id), function, |mut s| {
+                for input in inputs {
+                    s = s.input(input);
+                }
+                for (key, value) in params {
+                    s = s.param(key, value);
+                }
+                common.apply(s)
+            })
+        })?;
+        Ok(slf)
+    }
+
+    /// Adds a step performing `action` (log, notify, etc.).
+    #[pyo3(signature = (
+        id, action, *, params=None, depends_on=Vec::new(), condition=None, output=Vec::new(), produces=Vec::new(),
+        timeout_seconds=None
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn action_step<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        id: StepId,
+        action: String,
+        params: Option<&Bound<'py, PyDict>>,
+        depends_on: Vec<String>,
+        condition: Option<String>,
+        output: Vec<String>,
+        produces: Vec<PyOutput>,
+        timeout_seconds: Option<u64>,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        let params = self::params(params)?;
+        let common = Common {
+            depends_on,
+            condition,
+            output,
+            produces,
+            timeout_seconds,
+        };
+        slf.update(|b| {
+            b.action_step(String::from(id), action, |mut s| {
+                for (key, value) in params {
+                    s = s.param(key, value);
+                }
+                common.apply(s)
+            })
+        })?;
+        Ok(slf)
+    }
+
+    /// Adds a step given as a dict shaped like its YAML, for step types without a method.
+    fn step<'py>(
+        mut slf: PyRefMut<'py, Self>,
+        step: &Bound<'py, PyAny>,
+    ) -> PyResult<PyRefMut<'py, Self>> {
+        let step = serde_json::from_value(py_to_json(step)?)
+            .map_err(|e| OrchestratorError::new_err(format!("Invalid step: {}", e)))?;
+        slf.update(|b| b.step(step))?;
+        Ok(slf)
+    }
+
+    /// Links template references to their steps and validates the workflow.
+    ///
+    /// Raises `ValidationError` if the workflow is invalid. The builder cannot
+    /// be used afterwards.
+    fn build(&mut self) -> PyResult<PyWorkflow> {
+        let builder = self
+            .inner
+            .take()
+            .ok_or_else(|| OrchestratorError::new_err("WorkflowBuilder was already built"))?;
+        builder.build().map(PyWorkflow::from).map_err(to_py_err)
+    }
+}
@@ -0,0 +1,141 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Async workflow execution from Python.
+
+use crate::workflow::PyWorkflow;
+use crate::{json_to_py, py_to_json, to_py_err, OrchestratorError};
+use llm_orchestrator_core::WorkflowExecutor;
+use llm_orchestrator_providers::{AnthropicProvider, LLMProvider, OpenAIProvider};
+use llm_orchestrator_secrets::{SecretManagerBuilder, SecretStore, SecretStoreType, VaultConfig};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+use tokio::runtime::Runtime;
+
+/// Runtime driving executions; Python's event loop only awaits their completion.
+fn runtime() -> &'static Runtime {
+    static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+    RUNTIME.get_or_init(|| Runtime::new().expect("Failed to start the Tokio runtime"))
+}
+
+/// Parses the `secret_store` argument of [`execute`].
+fn parse_store_type(name: &str) -> PyResult<SecretStoreType> {
+    match name {
+        "env" => Ok(SecretStoreType::Environment),
+        "vault" => Ok(SecretStoreType::Vault),
+        "aws" => Ok(SecretStoreType::AwsSecretsManager),
+        "gcp" => Ok(SecretStoreType::Gcp),
+        "azure" => Ok(SecretStoreType::AzureKeyVault),
+        "file" => Ok(SecretStoreType::File),
+        other => Err(OrchestratorError::new_err(format!(
+            "Unknown secret store '{}' (expected env, vault, aws, gcp, azure, or file)",
+            other
+        ))),
+    }
+}
+
+/// Opens the secret store, configured from its usual environment variables.
+async fn open_secret_store(store_type: SecretStoreType) -> PyResult<Arc<dyn SecretStore>> {
+    let mut builder = SecretManagerBuilder::new(store_type);
+    if store_type == SecretStoreType::Vault {
+        let config =
+            VaultConfig::from_env().map_err(|e| OrchestratorError::new_err(e.to_string()))?;
+        builder = builder.with_vault_config(config);
+    }
+    builder
+        .build()
+        .await
+        .map_err(|e| OrchestratorError::new_err(format!("Failed to open secret store: {}", e)))
+}
+
+/// Creates the providers whose API key (`<provider>/api_key`) is in the secret store.
+async fn providers_from_store(
+    store: &Arc<dyn SecretStore>,
+) -> HashMap<String, Arc<dyn LLMProvider>> {
+    let mut providers: HashMap<String, Arc<dyn LLMProvider>> = HashMap::new();
+    if let Ok(openai) = OpenAIProvider::from_secret_store(store.clone(), "openai/api_key").await {
+        providers.insert("openai".to_string(), Arc::new(openai));
+    }
+    if let Ok(anthropic) =
+        AnthropicProvider::from_secret_store(store.clone(), "anthropic/api_key").await
+    {
+        providers.insert("anthropic".to_string(), Arc::new(anthropic));
+    }
+    providers
+}
+
+/// Runs a workflow on the Rust engine and returns its step results.
+///
+/// Providers are created from the API keys in `secret_store` (`env` reads
+/// `OPENAI_API_KEY` and `ANTHROPIC_API_KEY`), which also resolves secret
+/// references in the workflow. Returns a dict mapping each step ID to its
+/// result (`status`, `outputs`, `error`, `duration`).
+#[pyfunction]
+#[pyo3(signature = (workflow, inputs=None, *, secret_store="env".to_string(), max_concurrency=None, profile=None))]
+pub async fn execute(
+    workflow: PyWorkflow,
+    inputs: Option<Py<PyDict>>,
+    secret_store: String,
+    max_concurrency: Option<usize>,
+    profile: Option<String>,
+) -> PyResult<PyObject> {
+    let inputs =
+        Python::with_gil(|py| inputs.map(|i| py_to_json(i.bind(py).as_any())).transpose())?;
+    let inputs: HashMap<String, Value> = match inputs {
+        Some(Value::Object(map)) => map.into_iter().collect(),
+        _ => HashMap::new(),
+    };
+    let workflow = match profile {
+        Some(profile) => workflow.inner.with_profile(&profile).map_err(to_py_err)?,
+        None => workflow.inner,
+    };
+    let store_type = parse_store_type(&secret_store)?;
+
+    let run = runtime().spawn(async move {
+        let store = open_secret_store(store_type).await?;
+        let providers = providers_from_store(&store).await;
+        if providers.is_empty() {
+            return Err(OrchestratorError::new_err(
+                "No LLM providers available. Please set OPENAI_API_KEY or ANTHROPIC_API_KEY in the secret store.",
+            ));
+        }
+
+        let mut executor = WorkflowExecutor::new(workflow, inputs)
+            .map_err(to_py_err)?
+            .with_secret_store(store);
+        if let Some(max) = max_concurrency {
+            executor = executor.with_max_concurrency(max);
+        }
+        for (name, provider) in providers {
+            executor = executor.with_provider(name, provider);
+        }
+
+        let results = executor.execute().await.map_err(to_py_err)?;
+        serde_json::to_value(&results).map_err(|e| OrchestratorError::new_err(e.to_string()))
+    });
+    let results = run
+        .await
+        .map_err(|e| OrchestratorError::new_err(format!("Execution task failed: {}", e)))??;
+    Python::with_gil(|py| json_to_py(py, &results))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_store_type() {
+        assert_eq!(
+            parse_store_type("env").unwrap(),
+            SecretStoreType::Environment
+        );
+        assert_eq!(
+            parse_store_type("aws").unwrap(),
+            SecretStoreType::AwsSecretsManager
+        );
+        assert!(parse_store_type("keychain").is_err());
+    }
+}
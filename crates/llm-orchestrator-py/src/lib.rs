@@ -0,0 +1,81 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Python bindings for LLM Orchestrator.
+//!
+//! Exposes workflow loading and validation, the SDK builder, and async
+//! execution as the `llm_orchestrator` Python module:
+//!
+//! ```python
+//! import asyncio
+//! import llm_orchestrator as lo
+//!
+//! workflow = lo.Workflow.from_file("workflow.yaml")
+//! workflow.validate()
+//! results = asyncio.run(lo.execute(workflow, {"question": "What is RAG?"}))
+//! print(results["answer"]["outputs"])
+//! ```
+//!
+//! Workflows run on the Rust engine; Python only sees the inputs going in
+//! and the step results coming out, both as plain dicts.
+
+pub mod builder;
+pub mod execute;
+pub mod workflow;
+
+use llm_orchestrator_core::OrchestratorError as CoreError;
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+use serde_json::Value;
+
+pyo3::create_exception!(
+    llm_orchestrator,
+    OrchestratorError,
+    PyException,
+    "Raised when loading, validating, or executing a workflow fails."
+);
+pyo3::create_exception!(
+    llm_orchestrator,
+    ValidationError,
+    OrchestratorError,
+    "Raised when a workflow is invalid."
+);
+
+/// Converts an engine error into the matching Python exception.
+pub(crate) fn to_py_err(error: CoreError) -> PyErr {
+    match error {
+        CoreError::ValidationError(msg) => ValidationError::new_err(msg),
+        other => OrchestratorError::new_err(other.to_string()),
+    }
+}
+
+/// Converts a JSON value into the equivalent Python object.
+pub(crate) fn json_to_py(py: Python<'_>, value: &Value) -> PyResult<PyObject> {
+    let json =
+        serde_json::to_string(value).map_err(|e| OrchestratorError::new_err(e.to_string()))?;
+    Ok(py.import("json")?.call_method1("loads", (json,))?.unbind())
+}
+
+/// Converts a JSON-serializable Python object into a JSON value.
+pub(crate) fn py_to_json(obj: &Bound<'_, PyAny>) -> PyResult<Value> {
+    let json: String = obj
+        .py()
+        .import("json")?
+        .call_method1("dumps", (obj,))?
+        .extract()?;
+    serde_json::from_str(&json).map_err(|e| OrchestratorError::new_err(e.to_string()))
+}
+
+/// The `llm_orchestrator` Python module.
+#[pymodule]
+fn llm_orchestrator(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("__version__", llm_orchestrator_sdk::VERSION)?;
+    m.add("OrchestratorError", m.py().get_type::<OrchestratorError>())?;
+    m.add("ValidationError", m.py().get_type::<ValidationError>())?;
+    m.add_class::<workflow::PyWorkflow>()?;
+    m.add_class::<builder::PyWorkflowBuilder>()?;
+    m.add_class::<builder::PyStepHandle>()?;
+    m.add_class::<builder::PyOutput>()?;
+    m.add_function(wrap_pyfunction!(execute::execute, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,126 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! The `Workflow` Python class.
+
+use crate::{json_to_py, py_to_json, to_py_err, OrchestratorError};
+use llm_orchestrator_core::{Workflow, WorkflowDAG};
+use pyo3::prelude::*;
+use std::path::PathBuf;
+
+/// A workflow definition, loaded from YAML or JSON or built with `WorkflowBuilder`.
+#[pyclass(name = "Workflow", module = "llm_orchestrator", frozen)]
+#[derive(Clone)]
+pub struct PyWorkflow {
+    pub(crate) inner: Workflow,
+}
+
+impl From<Workflow> for PyWorkflow {
+    fn from(inner: Workflow) -> Self {
+        Self { inner }
+    }
+}
+
+#[pymethods]
+impl PyWorkflow {
+    /// Parses a workflow from YAML.
+    #[staticmethod]
+    fn from_yaml(yaml: &str) -> PyResult<Self> {
+        Workflow::from_yaml(yaml).map(Self::from).map_err(to_py_err)
+    }
+
+    /// Parses a workflow from JSON.
+    #[staticmethod]
+    fn from_json(json: &str) -> PyResult<Self> {
+        Workflow::from_json(json).map(Self::from).map_err(to_py_err)
+    }
+
+    /// Reads a workflow from a YAML (or JSON) file.
+    #[staticmethod]
+    fn from_file(path: PathBuf) -> PyResult<Self> {
+        let yaml = std::fs::read_to_string(&path).map_err(|e| {
+            OrchestratorError::new_err(format!("Failed to read {}: {}", path.display(), e))
+        })?;
+        Self::from_yaml(&yaml)
+    }
+
+    /// Builds a workflow from a dict shaped like its YAML.
+    #[staticmethod]
+    fn from_dict(data: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let workflow: Workflow = serde_json::from_value(py_to_json(data)?)
+            .map_err(|e| OrchestratorError::new_err(format!("Invalid workflow: {}", e)))?;
+        Ok(Self::from(workflow))
+    }
+
+    /// Checks the workflow structure and that its dependencies form no cycle.
+    ///
+    /// Raises `ValidationError` describing the first problem found.
+    fn validate(&self) -> PyResult<()> {
+        self.inner.validate().map_err(to_py_err)?;
+        WorkflowDAG::from_workflow(&self.inner).map_err(to_py_err)?;
+        Ok(())
+    }
+
+    /// Returns a copy with the named profile applied.
+    fn with_profile(&self, profile: &str) -> PyResult<Self> {
+        self.inner
+            .with_profile(profile)
+            .map(Self::from)
+            .map_err(to_py_err)
+    }
+
+    /// Serializes the workflow to YAML.
+    fn to_yaml(&self) -> PyResult<String> {
+        self.inner.to_yaml().map_err(to_py_err)
+    }
+
+    /// Serializes the workflow to JSON.
+    fn to_json(&self) -> PyResult<String> {
+        self.inner.to_json().map_err(to_py_err)
+    }
+
+    /// Returns the workflow as a dict shaped like its YAML.
+    fn to_dict(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let value = serde_json::to_value(&self.inner)
+            .map_err(|e| OrchestratorError::new_err(e.to_string()))?;
+        json_to_py(py, &value)
+    }
+
+    /// Workflow ID.
+    #[getter]
+    fn id(&self) -> String {
+        self.inner.id.to_string()
+    }
+
+    /// Workflow name.
+    #[getter]
+    fn name(&self) -> &str {
+        &self.inner.name
+    }
+
+    /// Workflow version.
+    #[getter]
+    fn version(&self) -> &str {
+        &self.inner.version
+    }
+
+    /// Workflow description.
+    #[getter]
+    fn description(&self) -> Option<&str> {
+        self.inner.description.as_deref()
+    }
+
+    /// IDs of the steps, in definition order.
+    fn step_ids(&self) -> Vec<String> {
+        self.inner.step_ids()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Workflow(name={:?}, version={:?}, steps={})",
+            self.inner.name,
+            self.inner.version,
+            self.inner.steps.len()
+        )
+    }
+}
@@ -0,0 +1,1046 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Fluent construction of workflows.
+//!
+//! [`WorkflowBuilder`] assembles a [`Workflow`] step by step, each step
+//! configured by a closure over its [`StepBuilder`]:
+//!
+//! ```
+//! use llm_orchestrator_sdk::{Input, StepHandle, WorkflowBuilder};
+//!
+//! let question = Input::new("question");
+//! let retrieve = StepHandle::new("retrieve");
+//! let docs = retrieve.output::<Vec<String>>("docs");
+//!
+//! let workflow = WorkflowBuilder::new("rag")
+//!     .transform_step(&retrieve, "merge", |s| s.input("corpus").produces(&docs))
+//!     .llm_step("summarize", |s| {
+//!         s.provider("openai")
+//!             .model("gpt-4")
+//!             .prompt(format!("Answer {question} using {docs}"))
+//!             .output("summary")
+//!     })
+//!     .build()
+//!     .unwrap();
+//!
+//! // Referencing the handle made `summarize` depend on `retrieve`
+//! assert_eq!(workflow.get_step("summarize").unwrap().depends_on, vec!["retrieve"]);
+//! ```
+//!
+//! [`Input`] and [`Output`] handles render as template expressions, so they
+//! can be formatted into prompts; outputs come from a [`StepHandle`], which
+//! names its step in one place. [`build`](WorkflowBuilder::build) checks
+//! every `{{steps.<id>.<output>}}` reference in the workflow's templates,
+//! whether written by a handle or by hand: the step must exist and declare
+//! the output, and it becomes a dependency of the referencing step. The
+//! finished workflow is then validated like one loaded from YAML.
+
+use llm_orchestrator_core::workflow::{
+    ActionConfig, EmbedStepConfig, FailurePolicy, ImageAttachment, LlmStepConfig, ResourceClass, RetryBudgetConfig,
+    RetryConfig, Step, StepConfig, StepType, TransformConfig, Workflow,
+};
+use llm_orchestrator_core::{OrchestratorError, Result, StepResult, WorkflowDAG};
+use regex::Regex;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// A workflow input, rendering as `{{inputs.<name>}}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Input {
+    name: String,
+}
+
+impl Input {
+    /// Refers to the input `name`.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self { name: name.into() }
+    }
+
+    /// Input name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl fmt::Display for Input {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{{{{inputs.{}}}}}", self.name)
+    }
+}
+
+/// A step of the workflow being built, naming it in one place.
+///
+/// Pass it as the ID of the step it names and derive that step's outputs
+/// from it, so renaming the step renames every reference:
+///
+/// ```
+/// use llm_orchestrator_sdk::{StepHandle, WorkflowBuilder};
+///
+/// let retrieve = StepHandle::new("retrieve");
+/// let docs = retrieve.output::<Vec<String>>("docs");
+///
+/// let workflow = WorkflowBuilder::new("rag")
+///     .transform_step(&retrieve, "merge", |s| s.produces(&docs))
+///     .llm_step("answer", |s| {
+///         s.provider("openai").model("gpt-4").prompt(format!("Using {docs}"))
+///     })
+///     .build()
+///     .unwrap();
+/// # assert_eq!(workflow.get_step("answer").unwrap().depends_on, vec!["retrieve"]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StepHandle {
+    id: String,
+}
+
+impl StepHandle {
+    /// Names the step `id`.
+    pub fn new(id: impl Into<String>) -> Self {
+        Self { id: id.into() }
+    }
+
+    /// Step ID.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Refers to output `name` of this step, holding values of type `T`.
+    pub fn output<T>(&self, name: impl Into<String>) -> Output<T> {
+        Output {
+            step_id: self.id.clone(),
+            name: name.into(),
+            value_type: PhantomData,
+        }
+    }
+}
+
+impl From<&StepHandle> for String {
+    fn from(handle: &StepHandle) -> Self {
+        handle.id.clone()
+    }
+}
+
+/// An output of a step holding values of type `T`, rendering as
+/// `{{steps.<step>.<name>}}`.
+///
+/// Declare it on the producing step with [`StepBuilder::produces`], format it
+/// into the templates of later steps, and [`read`](Output::read) its value
+/// from the results of a run.
+pub struct Output<T = Value> {
+    step_id: String,
+    name: String,
+    value_type: PhantomData<fn() -> T>,
+}
+
+impl Output {
+    /// Refers to output `name` of step `step_id`, holding any JSON value.
+    ///
+    /// Prefer [`StepHandle::output`], which follows the step if it is renamed.
+    pub fn new(step_id: impl Into<String>, name: impl Into<String>) -> Self {
+        StepHandle::new(step_id).output(name)
+    }
+}
+
+impl<T> Output<T> {
+    /// ID of the producing step.
+    pub fn step_id(&self) -> &str {
+        &self.step_id
+    }
+
+    /// Output name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<T: DeserializeOwned> Output<T> {
+    /// Reads the output from the results of a run.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the step has no result, the result lacks the
+    /// output, or its value is not a `T`.
+    pub fn read(&self, results: &HashMap<String, StepResult>) -> Result<T> {
+        let result = results
+            .get(&self.step_id)
+            .ok_or_else(|| OrchestratorError::StepNotFound(self.step_id.clone()))?;
+        let value = result.outputs.get(&self.name).ok_or_else(|| {
+            OrchestratorError::ContextVariableNotFound(format!(
+                "steps.{}.{}",
+                self.step_id, self.name
+            ))
+        })?;
+        serde_json::from_value(value.clone()).map_err(|e| {
+            OrchestratorError::serialization(format!(
+                "Output '{}' of step '{}' has an unexpected type: {}",
+                self.name, self.step_id, e
+            ))
+        })
+    }
+}
+
+// Implemented by hand so that `T` needs none of these traits
+impl<T> Clone for Output<T> {
+    fn clone(&self) -> Self {
+        Self {
+            step_id: self.step_id.clone(),
+            name: self.name.clone(),
+            value_type: PhantomData,
+        }
+    }
+}
+
+impl<T> PartialEq for Output<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.step_id == other.step_id && self.name == other.name
+    }
+}
+
+impl<T> Eq for Output<T> {}
+
+impl<T> fmt::Debug for Output<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Output")
+            .field("step_id", &self.step_id)
+            .field("name", &self.name)
+            .field("type", &std::any::type_name::<T>())
+            .finish()
+    }
+}
+
+impl<T> fmt::Display for Output<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{{{{steps.{}.{}}}}}", self.step_id, self.name)
+    }
+}
+
+/// Builds a validated [`Workflow`].
+#[derive(Debug)]
+pub struct WorkflowBuilder {
+    workflow: Workflow,
+    errors: Vec<String>,
+}
+
+impl WorkflowBuilder {
+    /// Starts a workflow named `name`.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            workflow: Workflow::new(name),
+            errors: Vec::new(),
+        }
+    }
+
+    /// Sets the version.
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.workflow.version = version.into();
+        self
+    }
+
+    /// Sets the description.
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.workflow.description = Some(description.into());
+        self
+    }
+
+    /// Sets the timeout of the whole run.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.workflow.timeout_seconds = Some(timeout.as_secs());
+        self
+    }
+
+    /// Adds a metadata entry.
+    pub fn metadata(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.workflow.metadata.insert(key.into(), value.into());
+        self
+    }
+
+    /// Limits the retries of all steps combined.
+    pub fn retry_budget(mut self, budget: RetryBudgetConfig) -> Self {
+        self.workflow.retry_budget = Some(budget);
+        self
+    }
+
+    /// Adds an LLM completion step; it needs a provider, a model, and a prompt.
+    pub fn llm_step(
+        self,
+        id: impl Into<String>,
+        configure: impl FnOnce(LlmStepBuilder) -> LlmStepBuilder,
+    ) -> Self {
+        self.add(configure(StepBuilder::new(id, LlmStep::default())))
+    }
+
+    /// Adds an embedding step; it needs a provider, a model, and an input.
+    pub fn embed_step(
+        self,
+        id: impl Into<String>,
+        configure: impl FnOnce(EmbedStepBuilder) -> EmbedStepBuilder,
+    ) -> Self {
+        self.add(configure(StepBuilder::new(id, EmbedStep::default())))
+    }
+
+    /// Adds a step applying the transform `function`.
+    pub fn transform_step(
+        self,
+        id: impl Into<String>,
+        function: impl Into<String>,
+        configure: impl FnOnce(TransformStepBuilder) -> TransformStepBuilder,
+    ) -> Self {
+        let config = TransformStep {
+            function: function.into(),
+            ..TransformStep::default()
+        };
+        self.add(configure(StepBuilder::new(id, config)))
+    }
+
+    /// Adds a step performing `action` (log, notify, etc.).
+    pub fn action_step(
+        self,
+        id: impl Into<String>,
+        action: impl Into<String>,
+        configure: impl FnOnce(ActionStepBuilder) -> ActionStepBuilder,
+    ) -> Self {
+        let config = ActionStep {
+            action: action.into(),
+            ..ActionStep::default()
+        };
+        self.add(configure(StepBuilder::new(id, config)))
+    }
+
+    /// Adds a step defined directly, for step types without a builder.
+    pub fn step(mut self, step: Step) -> Self {
+        self.workflow.steps.push(step);
+        self
+    }
+
+    fn add<C: StepKind>(mut self, builder: StepBuilder<C>) -> Self {
+        match builder.into_step() {
+            Ok(step) => self.workflow.steps.push(step),
+            Err(e) => self.errors.push(e),
+        }
+        self
+    }
+
+    /// Links template references to their steps and validates the workflow.
+    ///
+    /// # Errors
+    ///
+    /// Returns a validation error if a step is incomplete, a template refers
+    /// to an unknown step or undeclared output, or the workflow is invalid
+    /// (e.g. duplicate step IDs or a dependency cycle).
+    pub fn build(mut self) -> Result<Workflow> {
+        if let Some(error) = self.errors.into_iter().next() {
+            return Err(OrchestratorError::validation(error));
+        }
+        link_references(&mut self.workflow.steps)?;
+        self.workflow.validate()?;
+        WorkflowDAG::from_workflow(&self.workflow)?;
+        Ok(self.workflow)
+    }
+}
+
+/// Configuration specific to one type of step.
+pub trait StepKind {
+    /// Type of the built step.
+    fn step_type(&self) -> StepType;
+
+    /// Builds the step configuration, naming what is missing on failure.
+    fn into_config(self) -> std::result::Result<StepConfig, String>;
+}
+
+/// Builds one step; the type parameter holds what is specific to its type.
+#[derive(Debug)]
+pub struct StepBuilder<C> {
+    id: String,
+    depends_on: Vec<String>,
+    depends_on_any: Vec<String>,
+    bind: HashMap<String, String>,
+    condition: Option<String>,
+    output: Vec<String>,
+    timeout_seconds: Option<u64>,
+    retry: Option<RetryConfig>,
+    on_failure: Option<FailurePolicy>,
+    resource_class: Option<ResourceClass>,
+    assert: Vec<String>,
+    allow_skipped_deps: bool,
+    errors: Vec<String>,
+    config: C,
+}
+
+impl<C: StepKind> StepBuilder<C> {
+    fn new(id: impl Into<String>, config: C) -> Self {
+        Self {
+            id: id.into(),
+            depends_on: Vec::new(),
+            depends_on_any: Vec::new(),
+            bind: HashMap::new(),
+            condition: None,
+            output: Vec::new(),
+            timeout_seconds: None,
+            retry: None,
+            on_failure: None,
+            resource_class: None,
+            assert: Vec::new(),
+            allow_skipped_deps: false,
+            errors: Vec::new(),
+            config,
+        }
+    }
+
+    /// Runs the step after `step_id`, even if no template refers to it.
+    pub fn depends_on(mut self, step_id: impl Into<String>) -> Self {
+        let step_id = step_id.into();
+        if !self.depends_on.contains(&step_id) {
+            self.depends_on.push(step_id);
+        }
+        self
+    }
+
+    /// Runs the step once `step_id` or another alternative completes, reading
+    /// the outputs of the first to complete as `joined.<this step's id>`.
+    pub fn depends_on_any(mut self, step_id: impl Into<String>) -> Self {
+        let step_id = step_id.into();
+        if !self.depends_on_any.contains(&step_id) {
+            self.depends_on_any.push(step_id);
+        }
+        self
+    }
+
+    /// Lets the step's templates read the value at `path`, such as
+    /// `steps.fetch.body`, as `{{ name }}`.
+    pub fn bind(mut self, name: impl Into<String>, path: impl Into<String>) -> Self {
+        self.bind.insert(name.into(), path.into());
+        self
+    }
+
+    /// Runs the step only when the template expression is true.
+    pub fn condition(mut self, condition: impl Into<String>) -> Self {
+        self.condition = Some(condition.into());
+        self
+    }
+
+    /// Declares an output variable.
+    pub fn output(mut self, name: impl Into<String>) -> Self {
+        self.output.push(name.into());
+        self
+    }
+
+    /// Declares the output a handle refers to; the handle must name this step.
+    pub fn produces<T>(mut self, output: &Output<T>) -> Self {
+        if output.step_id != self.id {
+            self.errors.push(format!(
+                "Step '{}' cannot produce output '{}' of step '{}'",
+                self.id, output.name, output.step_id
+            ));
+        }
+        self.output(output.name.clone())
+    }
+
+    /// Sets the step timeout.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout_seconds = Some(timeout.as_secs());
+        self
+    }
+
+    /// Sets how the step is retried.
+    pub fn retry(mut self, retry: RetryConfig) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    /// Sets what happens when the step fails after its retries.
+    pub fn on_failure(mut self, policy: FailurePolicy) -> Self {
+        self.on_failure = Some(policy);
+        self
+    }
+
+    /// Throttles the step with another resource class than its type's default.
+    pub fn resource_class(mut self, class: ResourceClass) -> Self {
+        self.resource_class = Some(class);
+        self
+    }
+
+    /// Fails the step unless the expression holds for its outputs, e.g. `score >= 0.8`.
+    pub fn assert(mut self, expression: impl Into<String>) -> Self {
+        self.assert.push(expression.into());
+        self
+    }
+
+    /// Runs the step even when a dependency was skipped by its condition.
+    pub fn allow_skipped_deps(mut self) -> Self {
+        self.allow_skipped_deps = true;
+        self
+    }
+
+    fn into_step(self) -> std::result::Result<Step, String> {
+        if let Some(error) = self.errors.into_iter().next() {
+            return Err(error);
+        }
+        let step_type = self.config.step_type();
+        let config = self
+            .config
+            .into_config()
+            .map_err(|missing| format!("Step '{}' {}", self.id, missing))?;
+        Ok(Step {
+            id: self.id,
+            step_type,
+            depends_on: self.depends_on,
+            depends_on_any: self.depends_on_any,
+            bind: self.bind,
+            condition: self.condition,
+            config,
+            output: self.output,
+            timeout_seconds: self.timeout_seconds,
+            on_failure: self.on_failure,
+            retry: self.retry,
+            guardrails: None,
+            resource_class: self.resource_class,
+            assert: self.assert,
+            allow_skipped_deps: self.allow_skipped_deps,
+        })
+    }
+}
+
+/// Builder of an LLM completion step.
+pub type LlmStepBuilder = StepBuilder<LlmStep>;
+
+/// What is specific to an LLM completion step.
+#[derive(Debug, Default)]
+pub struct LlmStep {
+    provider: Option<String>,
+    model: Option<String>,
+    prompt: Option<String>,
+    prompt_ref: Option<String>,
+    system: Option<String>,
+    temperature: Option<f32>,
+    max_tokens: Option<u32>,
+    images: Vec<ImageAttachment>,
+    extra: HashMap<String, Value>,
+}
+
+impl StepBuilder<LlmStep> {
+    /// Sets the provider, as registered with the executor.
+    pub fn provider(mut self, provider: impl Into<String>) -> Self {
+        self.config.provider = Some(provider.into());
+        self
+    }
+
+    /// Sets the model.
+    pub fn model(mut self, model: impl Into<String>) -> Self {
+        self.config.model = Some(model.into());
+        self
+    }
+
+    /// Sets the prompt template.
+    pub fn prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.config.prompt = Some(prompt.into());
+        self
+    }
+
+    /// Uses a prompt from the registry (`name`, `name@latest`, or `name@v3`) instead of an inline prompt.
+    pub fn prompt_ref(mut self, prompt_ref: impl Into<String>) -> Self {
+        self.config.prompt_ref = Some(prompt_ref.into());
+        self
+    }
+
+    /// Sets the system prompt.
+    pub fn system(mut self, system: impl Into<String>) -> Self {
+        self.config.system = Some(system.into());
+        self
+    }
+
+    /// Sets the sampling temperature.
+    pub fn temperature(mut self, temperature: f32) -> Self {
+        self.config.temperature = Some(temperature);
+        self
+    }
+
+    /// Limits the tokens generated.
+    pub fn max_tokens(mut self, max_tokens: u32) -> Self {
+        self.config.max_tokens = Some(max_tokens);
+        self
+    }
+
+    /// Sends an image with the prompt.
+    pub fn image(mut self, image: ImageAttachment) -> Self {
+        self.config.images.push(image);
+        self
+    }
+
+    /// Passes a provider-specific parameter.
+    pub fn param(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.config.extra.insert(key.into(), value.into());
+        self
+    }
+}
+
+impl StepKind for LlmStep {
+    fn step_type(&self) -> StepType {
+        StepType::Llm
+    }
+
+    fn into_config(self) -> std::result::Result<StepConfig, String> {
+        let provider = self.provider.ok_or("needs a provider")?;
+        let model = self.model.ok_or("needs a model")?;
+        if self.prompt.is_none() && self.prompt_ref.is_none() {
+            return Err("needs a prompt or prompt_ref".to_string());
+        }
+        Ok(StepConfig::Llm(LlmStepConfig {
+            provider,
+            model,
+            prompt: self.prompt.unwrap_or_default(),
+            prompt_ref: self.prompt_ref,
+            temperature: self.temperature,
+            max_tokens: self.max_tokens,
+            system: self.system,
+            stream: false,
+            variants: Vec::new(),
+            variant_mode: Default::default(),
+            hedge: None,
+            memory: None,
+            images: self.images,
+            extra: self.extra,
+        }))
+    }
+}
+
+/// Builder of an embedding step.
+pub type EmbedStepBuilder = StepBuilder<EmbedStep>;
+
+/// What is specific to an embedding step.
+#[derive(Debug, Default)]
+pub struct EmbedStep {
+    provider: Option<String>,
+    model: Option<String>,
+    input: Option<String>,
+    dimensions: Option<usize>,
+}
+
+impl StepBuilder<EmbedStep> {
+    /// Sets the provider, as registered with the executor.
+    pub fn provider(mut self, provider: impl Into<String>) -> Self {
+        self.config.provider = Some(provider.into());
+        self
+    }
+
+    /// Sets the embedding model.
+    pub fn model(mut self, model: impl Into<String>) -> Self {
+        self.config.model = Some(model.into());
+        self
+    }
+
+    /// Sets the template of the text to embed.
+    pub fn input(mut self, input: impl Into<String>) -> Self {
+        self.config.input = Some(input.into());
+        self
+    }
+
+    /// Requests embeddings with fewer dimensions, where the model supports it.
+    pub fn dimensions(mut self, dimensions: usize) -> Self {
+        self.config.dimensions = Some(dimensions);
+        self
+    }
+}
+
+impl StepKind for EmbedStep {
+    fn step_type(&self) -> StepType {
+        StepType::Embed
+    }
+
+    fn into_config(self) -> std::result::Result<StepConfig, String> {
+        Ok(StepConfig::Embed(EmbedStepConfig {
+            provider: self.provider.ok_or("needs a provider")?,
+            model: self.model.ok_or("needs a model")?,
+            input: self.input.ok_or("needs an input")?,
+            dimensions: self.dimensions,
+            batch_size: None,
+        }))
+    }
+}
+
+/// Builder of a transform step.
+pub type TransformStepBuilder = StepBuilder<TransformStep>;
+
+/// What is specific to a transform step.
+#[derive(Debug, Default)]
+pub struct TransformStep {
+    function: String,
+    inputs: Vec<String>,
+    params: HashMap<String, Value>,
+}
+
+impl StepBuilder<TransformStep> {
+    /// Adds an input variable.
+    pub fn input(mut self, name: impl Into<String>) -> Self {
+        self.config.inputs.push(name.into());
+        self
+    }
+
+    /// Sets a parameter of the transform function.
+    pub fn param(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.config.params.insert(key.into(), value.into());
+        self
+    }
+}
+
+impl StepKind for TransformStep {
+    fn step_type(&self) -> StepType {
+        StepType::Transform
+    }
+
+    fn into_config(self) -> std::result::Result<StepConfig, String> {
+        Ok(StepConfig::Transform(TransformConfig {
+            function: self.function,
+            inputs: self.inputs,
+            params: self.params,
+        }))
+    }
+}
+
+/// Builder of an action step.
+pub type ActionStepBuilder = StepBuilder<ActionStep>;
+
+/// What is specific to an action step.
+#[derive(Debug, Default)]
+pub struct ActionStep {
+    action: String,
+    params: HashMap<String, Value>,
+}
+
+impl StepBuilder<ActionStep> {
+    /// Sets a parameter of the action.
+    pub fn param(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.config.params.insert(key.into(), value.into());
+        self
+    }
+}
+
+impl StepKind for ActionStep {
+    fn step_type(&self) -> StepType {
+        StepType::Action
+    }
+
+    fn into_config(self) -> std::result::Result<StepConfig, String> {
+        Ok(StepConfig::Action(ActionConfig {
+            action: self.action,
+            params: self.params,
+        }))
+    }
+}
+
+/// Templates of a step that may refer to other steps' outputs.
+fn templates(step: &Step) -> Vec<&str> {
+    let mut templates: Vec<&str> = step.condition.iter().map(String::as_str).collect();
+    match &step.config {
+        StepConfig::Llm(config) => {
+            templates.push(&config.prompt);
+            templates.extend(config.system.as_deref());
+            templates.extend(config.images.iter().map(ImageAttachment::source));
+        }
+        StepConfig::Agent(config) => {
+            templates.push(&config.prompt);
+            templates.extend(config.system.as_deref());
+        }
+        StepConfig::Embed(config) => templates.push(&config.input),
+        StepConfig::VectorSearch(config) => {
+            templates.push(&config.query);
+            templates.extend(config.text.as_deref());
+        }
+        StepConfig::VectorUpsert(config) => {
+            strings(&config.records, &mut templates);
+            templates.extend(config.namespace.as_deref());
+        }
+        StepConfig::VectorFetch(config) => {
+            strings(&config.ids, &mut templates);
+            templates.extend(config.namespace.as_deref());
+        }
+        StepConfig::Approval(config) => templates.push(&config.payload),
+        StepConfig::Evaluate(config) => {
+            templates.push(&config.target);
+            templates.extend(config.reference.as_deref());
+        }
+        StepConfig::Action(config) => config
+            .params
+            .values()
+            .for_each(|v| strings(v, &mut templates)),
+        StepConfig::Http(config) => {
+            templates.push(&config.url);
+            config
+                .headers
+                .values()
+                .for_each(|v| strings(v, &mut templates));
+            config.body.iter().for_each(|v| strings(v, &mut templates));
+            templates.extend(
+                config
+                    .form
+                    .iter()
+                    .flat_map(|f| f.values().map(String::as_str)),
+            );
+        }
+        StepConfig::McpTool(config) => config
+            .arguments
+            .values()
+            .for_each(|v| strings(v, &mut templates)),
+        StepConfig::WebFetch(config) => strings(&config.urls, &mut templates),
+        StepConfig::Transcribe(config) => {
+            templates.push(&config.audio);
+            templates.extend(config.prompt.as_deref());
+        }
+        StepConfig::Speak(config) => {
+            templates.push(&config.text);
+            templates.extend(config.path.as_deref());
+        }
+        StepConfig::Transform(_) | StepConfig::Parallel(_) | StepConfig::Branch(_) => {}
+    }
+    templates
+}
+
+/// Strings nested in a JSON value, e.g. templated arguments of an action.
+fn strings<'a>(value: &'a Value, found: &mut Vec<&'a str>) {
+    match value {
+        Value::String(s) => found.push(s),
+        Value::Array(items) => items.iter().for_each(|item| strings(item, found)),
+        Value::Object(fields) => fields.values().for_each(|field| strings(field, found)),
+        _ => {}
+    }
+}
+
+/// Outputs a step declares; empty when it declares none, so any may be referenced.
+fn declared_outputs(step: &Step) -> Vec<String> {
+    let mut outputs = step.output.clone();
+    if let (false, StepConfig::Http(config)) = (outputs.is_empty(), &step.config) {
+        outputs.extend(config.extract.keys().cloned());
+    }
+    outputs
+}
+
+/// `(step ID, output)` pairs referenced by `{{steps.<id>.<output>}}` or `{{outputs.<id>}}` expressions.
+fn references(template: &str) -> Vec<(String, Option<String>)> {
+    static EXPRESSION: OnceLock<Regex> = OnceLock::new();
+    static REFERENCE: OnceLock<Regex> = OnceLock::new();
+    let expression = EXPRESSION.get_or_init(|| Regex::new(r"(?s)\{\{(.*?)\}\}").unwrap());
+    let reference = REFERENCE.get_or_init(|| {
+        Regex::new(r"\b(?:steps|outputs)\.([A-Za-z0-9_-]+)(?:\.([A-Za-z0-9_]+))?").unwrap()
+    });
+
+    expression
+        .captures_iter(template)
+        .flat_map(|expr| {
+            reference
+                .captures_iter(expr.get(1).map_or("", |m| m.as_str()))
+                .map(|r| (r[1].to_string(), r.get(2).map(|m| m.as_str().to_string())))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Checks every output reference and makes the referenced step a dependency.
+fn link_references(steps: &mut [Step]) -> Result<()> {
+    let declared: HashMap<String, Vec<String>> = steps
+        .iter()
+        .map(|s| (s.id.clone(), declared_outputs(s)))
+        .collect();
+
+    for step in steps.iter_mut() {
+        let mut referenced = Vec::new();
+        for template in templates(step) {
+            for (step_id, output) in references(template) {
+                let Some(outputs) = declared.get(&step_id) else {
+                    return Err(OrchestratorError::validation(format!(
+                        "Step '{}' refers to unknown step '{}'",
+                        step.id, step_id
+                    )));
+                };
+                if step_id == step.id {
+                    return Err(OrchestratorError::validation(format!(
+                        "Step '{}' refers to its own output",
+                        step.id
+                    )));
+                }
+                // Underscored outputs (e.g. `_response`) are recorded by the executor itself
+                if let Some(output) = output.filter(|o| !o.starts_with('_') && !outputs.is_empty())
+                {
+                    if !outputs.contains(&output) {
+                        return Err(OrchestratorError::validation(format!(
+                            "Step '{}' refers to output '{}' that step '{}' does not declare",
+                            step.id, output, step_id
+                        )));
+                    }
+                }
+                referenced.push(step_id);
+            }
+        }
+        for step_id in referenced {
+            if !step.depends_on.contains(&step_id) {
+                step.depends_on.push(step_id);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use llm_orchestrator_core::StepStatus;
+
+    fn summarize(s: LlmStepBuilder) -> LlmStepBuilder {
+        s.provider("openai").model("gpt-4").output("summary")
+    }
+
+    #[test]
+    fn test_handles_render_as_templates() {
+        assert_eq!(Input::new("query").to_string(), "{{inputs.query}}");
+        assert_eq!(
+            Output::new("retrieve", "docs").to_string(),
+            "{{steps.retrieve.docs}}"
+        );
+    }
+
+    #[test]
+    fn test_build_links_references() {
+        let docs = Output::new("retrieve", "docs");
+        let workflow = WorkflowBuilder::new("rag")
+            .version("2.0")
+            .transform_step("retrieve", "merge", |s| s.input("corpus").produces(&docs))
+            .llm_step("summarize", |s| {
+                summarize(s)
+                    .prompt(format!("Summarize {docs}"))
+                    .condition("{{ outputs.retrieve }}")
+                    .temperature(0.2)
+            })
+            .action_step("notify", "log", |s| {
+                s.depends_on("summarize").param("level", "info")
+            })
+            .build()
+            .unwrap();
+
+        assert_eq!(workflow.version, "2.0");
+        assert_eq!(workflow.step_ids(), vec!["retrieve", "summarize", "notify"]);
+        let step = workflow.get_step("summarize").unwrap();
+        assert_eq!(step.depends_on, vec!["retrieve"]);
+        assert_eq!(step.output, vec!["summary"]);
+        let StepConfig::Llm(config) = &step.config else {
+            panic!("summarize should be an LLM step");
+        };
+        assert_eq!(config.prompt, "Summarize {{steps.retrieve.docs}}");
+        assert_eq!(config.temperature, Some(0.2));
+
+        // The built workflow round-trips through YAML
+        let yaml = workflow.to_yaml().unwrap();
+        assert_eq!(
+            Workflow::from_yaml(&yaml)
+                .unwrap()
+                .get_step("summarize")
+                .unwrap()
+                .depends_on,
+            vec!["retrieve"]
+        );
+    }
+
+    #[test]
+    fn test_step_handles_follow_renames() {
+        let build = |id: &str| {
+            let retrieve = StepHandle::new(id);
+            let docs = retrieve.output::<Vec<String>>("docs");
+            WorkflowBuilder::new("rag")
+                .transform_step(&retrieve, "merge", |s| s.produces(&docs))
+                .llm_step("summarize", |s| {
+                    summarize(s).prompt(format!("Summarize {docs}"))
+                })
+                .build()
+                .unwrap()
+        };
+
+        for id in ["retrieve", "fetch"] {
+            let workflow = build(id);
+            assert_eq!(workflow.get_step("summarize").unwrap().depends_on, vec![id]);
+        }
+    }
+
+    #[test]
+    fn test_output_read() {
+        let retrieve = StepHandle::new("retrieve");
+        let docs = retrieve.output::<Vec<String>>("docs");
+        let result = StepResult {
+            step_id: "retrieve".to_string(),
+            status: StepStatus::Completed,
+            outputs: HashMap::from([
+                ("docs".to_string(), serde_json::json!(["a", "b"])),
+                ("count".to_string(), serde_json::json!("two")),
+            ]),
+            error: None,
+            error_details: None,
+            skip_reason: None,
+            duration: Duration::from_millis(3),
+            usage: None,
+        };
+        let results = HashMap::from([("retrieve".to_string(), result)]);
+
+        assert_eq!(docs.read(&results).unwrap(), vec!["a", "b"]);
+        assert!(retrieve.output::<u64>("count").read(&results).is_err());
+        assert!(retrieve.output::<Value>("missing").read(&results).is_err());
+        assert!(Output::new("other", "docs").read(&results).is_err());
+    }
+
+    #[test]
+    fn test_build_rejects_bad_references() {
+        let error = |builder: WorkflowBuilder| builder.build().unwrap_err().to_string();
+
+        let unknown_step = WorkflowBuilder::new("wf")
+            .llm_step("a", |s| summarize(s).prompt("{{steps.missing.text}}"));
+        assert!(error(unknown_step).contains("unknown step 'missing'"));
+
+        let undeclared = WorkflowBuilder::new("wf")
+            .llm_step("a", |s| summarize(s).prompt("hi"))
+            .llm_step("b", |s| summarize(s).prompt("{{steps.a.sumary}}"));
+        assert!(error(undeclared).contains("output 'sumary'"));
+
+        let wrong_step = WorkflowBuilder::new("wf")
+            .transform_step("a", "merge", |s| s.produces(&Output::new("b", "docs")));
+        assert!(error(wrong_step).contains("cannot produce"));
+
+        let cycle = WorkflowBuilder::new("wf")
+            .llm_step("a", |s| summarize(s).prompt("{{steps.b.summary}}"))
+            .llm_step("b", |s| summarize(s).prompt("{{steps.a.summary}}"));
+        assert!(cycle.build().is_err());
+    }
+
+    #[test]
+    fn test_build_rejects_incomplete_steps() {
+        let no_model = WorkflowBuilder::new("wf")
+            .llm_step("a", |s| s.provider("openai").prompt("hi").output("x"));
+        assert!(no_model
+            .build()
+            .unwrap_err()
+            .to_string()
+            .contains("Step 'a' needs a model"));
+
+        let no_prompt = WorkflowBuilder::new("wf")
+            .llm_step("a", |s| s.provider("openai").model("gpt-4").output("x"));
+        assert!(no_prompt
+            .build()
+            .unwrap_err()
+            .to_string()
+            .contains("prompt"));
+
+        let no_input = WorkflowBuilder::new("wf").embed_step("a", |s| {
+            s.provider("openai").model("text-embedding-3-small")
+        });
+        assert!(no_input
+            .build()
+            .unwrap_err()
+            .to_string()
+            .contains("needs an input"));
+
+        let duplicate = WorkflowBuilder::new("wf")
+            .llm_step("a", |s| summarize(s).prompt("hi"))
+            .llm_step("a", |s| summarize(s).prompt("hi"));
+        assert!(duplicate.build().is_err());
+    }
+}
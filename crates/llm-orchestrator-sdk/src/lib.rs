@@ -3,8 +3,12 @@
 
 //! LLM Orchestrator SDK for programmatic workflow construction.
 
+pub mod builder;
+
 // Re-exports from core
 pub use llm_orchestrator_core::*;
 
+pub use builder::{Input, Output, StepBuilder, StepHandle, WorkflowBuilder};
+
 /// Library version.
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
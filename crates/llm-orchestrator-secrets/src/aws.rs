@@ -29,7 +29,7 @@ use tracing::{debug, error, info};
 /// # Example
 ///
 /// ```no_run
-/// use llm_orchestrator_secrets::AwsSecretStore;
+/// use llm_orchestrator_secrets::{AwsSecretStore, SecretStore};
 /// use aws_sdk_secretsmanager::config::Region;
 ///
 /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
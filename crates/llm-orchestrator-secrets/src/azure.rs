@@ -0,0 +1,694 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Azure Key Vault secret store implementation.
+//!
+//! Talks to the Key Vault REST API and authenticates with Azure AD, using
+//! either a service principal (client credentials) or a managed identity.
+
+use crate::models::{Secret, SecretMetadata, SecretVersion};
+use crate::traits::{Result, SecretError, SecretStore};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use tokio::sync::Mutex;
+use tracing::{debug, error, info, warn};
+
+/// Key Vault REST API version.
+const API_VERSION: &str = "7.4";
+
+/// Default Azure AD authority.
+const DEFAULT_AUTHORITY_HOST: &str = "https://login.microsoftonline.com";
+
+/// Resource (and scope prefix) for Key Vault tokens.
+const VAULT_RESOURCE: &str = "https://vault.azure.net";
+
+/// Instance Metadata Service token endpoint used on Azure VMs and AKS.
+const IMDS_TOKEN_URL: &str = "http://169.254.169.254/metadata/identity/oauth2/token";
+
+/// Tag holding the original secret key, since Key Vault names only allow
+/// alphanumerics and dashes.
+const KEY_TAG: &str = "orchestrator-key";
+
+/// Azure AD credentials used to obtain access tokens.
+#[derive(Clone)]
+enum Credentials {
+    /// Service principal with a client secret.
+    ClientSecret {
+        tenant_id: String,
+        client_id: String,
+        client_secret: String,
+    },
+    /// System- or user-assigned managed identity.
+    ManagedIdentity { client_id: Option<String> },
+    /// A fixed access token.
+    AccessToken(String),
+}
+
+/// Access token with its expiry.
+struct CachedToken {
+    token: String,
+    expires_at: DateTime<Utc>,
+}
+
+/// Azure Key Vault secret store.
+///
+/// # Features
+///
+/// - Azure AD authentication with client credentials or managed identity
+/// - Secret versions
+/// - Tags exposed as secret metadata
+///
+/// Key Vault secret names may only contain alphanumerics and dashes, so other
+/// characters are replaced with `-` (e.g. `openai/api_key` is stored as
+/// `openai-api-key`). The original key is kept in a tag for listing.
+///
+/// # Example
+///
+/// ```no_run
+/// use llm_orchestrator_secrets::{AzureKeyVaultStore, SecretStore};
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let store = AzureKeyVaultStore::with_client_credentials(
+///     "https://my-vault.vault.azure.net".to_string(),
+///     "tenant-id".to_string(),
+///     "client-id".to_string(),
+///     "client-secret".to_string(),
+/// );
+///
+/// let secret = store.get_secret("openai/api_key").await?;
+/// println!("Retrieved secret: {}", secret.key);
+/// # Ok(())
+/// # }
+/// ```
+pub struct AzureKeyVaultStore {
+    /// HTTP client.
+    client: Client,
+    /// Vault URL (e.g. `https://my-vault.vault.azure.net`).
+    vault_url: String,
+    /// Azure AD authority host.
+    authority_host: String,
+    /// Credentials used to obtain access tokens.
+    credentials: Credentials,
+    /// Cached access token.
+    token: Mutex<Option<CachedToken>>,
+}
+
+impl AzureKeyVaultStore {
+    /// Create a store authenticated as a service principal.
+    pub fn with_client_credentials(
+        vault_url: String,
+        tenant_id: String,
+        client_id: String,
+        client_secret: String,
+    ) -> Self {
+        Self::with_credentials(
+            vault_url,
+            Credentials::ClientSecret {
+                tenant_id,
+                client_id,
+                client_secret,
+            },
+        )
+    }
+
+    /// Create a store authenticated with a managed identity.
+    ///
+    /// Pass a client ID to select a user-assigned identity.
+    pub fn with_managed_identity(vault_url: String, client_id: Option<String>) -> Self {
+        Self::with_credentials(vault_url, Credentials::ManagedIdentity { client_id })
+    }
+
+    /// Create a store that authenticates with a fixed access token.
+    pub fn with_access_token(vault_url: String, token: String) -> Self {
+        Self::with_credentials(vault_url, Credentials::AccessToken(token))
+    }
+
+    /// Create a store from environment variables.
+    ///
+    /// Reads:
+    /// - `AZURE_KEYVAULT_URL` - Vault URL
+    /// - `AZURE_TENANT_ID`, `AZURE_CLIENT_ID`, `AZURE_CLIENT_SECRET` - Service
+    ///   principal; when the secret is absent a managed identity is used,
+    ///   selected by `AZURE_CLIENT_ID` if set
+    pub fn from_env() -> Result<Self> {
+        let vault_url = std::env::var("AZURE_KEYVAULT_URL")
+            .map_err(|_| SecretError::EnvVarNotFound("AZURE_KEYVAULT_URL".to_string()))?;
+
+        let client_id = std::env::var("AZURE_CLIENT_ID").ok();
+        let store = match (
+            std::env::var("AZURE_TENANT_ID").ok(),
+            client_id.clone(),
+            std::env::var("AZURE_CLIENT_SECRET").ok(),
+        ) {
+            (Some(tenant_id), Some(client_id), Some(client_secret)) => {
+                Self::with_client_credentials(vault_url, tenant_id, client_id, client_secret)
+            }
+            _ => Self::with_managed_identity(vault_url, client_id),
+        };
+
+        Ok(store)
+    }
+
+    /// Set the Azure AD authority host (e.g. for sovereign clouds).
+    pub fn with_authority_host(mut self, authority_host: String) -> Self {
+        self.authority_host = authority_host.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Returns the vault URL.
+    pub fn vault_url(&self) -> &str {
+        &self.vault_url
+    }
+
+    fn with_credentials(vault_url: String, credentials: Credentials) -> Self {
+        let vault_url = vault_url.trim_end_matches('/').to_string();
+        debug!("Initialized Azure Key Vault client for {}", vault_url);
+        Self {
+            client: Client::new(),
+            vault_url,
+            authority_host: DEFAULT_AUTHORITY_HOST.to_string(),
+            credentials,
+            token: Mutex::new(None),
+        }
+    }
+
+    /// Returns a valid access token, refreshing it when close to expiry.
+    async fn access_token(&self) -> Result<String> {
+        if let Credentials::AccessToken(token) = &self.credentials {
+            return Ok(token.clone());
+        }
+
+        let mut cached = self.token.lock().await;
+        if let Some(token) = cached.as_ref() {
+            if token.expires_at > Utc::now() + Duration::seconds(60) {
+                return Ok(token.token.clone());
+            }
+        }
+
+        debug!("Fetching Azure AD access token");
+        let (token, expires_in) = self.request_token().await?;
+        *cached = Some(CachedToken {
+            token: token.clone(),
+            expires_at: Utc::now() + Duration::seconds(expires_in),
+        });
+        Ok(token)
+    }
+
+    /// Request a Key Vault access token from Azure AD.
+    async fn request_token(&self) -> Result<(String, i64)> {
+        let request = match &self.credentials {
+            Credentials::ClientSecret {
+                tenant_id,
+                client_id,
+                client_secret,
+            } => {
+                let scope = format!("{}/.default", VAULT_RESOURCE);
+                self.client
+                    .post(format!(
+                        "{}/{}/oauth2/v2.0/token",
+                        self.authority_host, tenant_id
+                    ))
+                    .form(&[
+                        ("grant_type", "client_credentials"),
+                        ("client_id", client_id.as_str()),
+                        ("client_secret", client_secret.as_str()),
+                        ("scope", scope.as_str()),
+                    ])
+            }
+            Credentials::ManagedIdentity { client_id } => {
+                // App Service and Functions expose their own identity endpoint
+                let mut request = match (
+                    std::env::var("IDENTITY_ENDPOINT"),
+                    std::env::var("IDENTITY_HEADER"),
+                ) {
+                    (Ok(endpoint), Ok(header)) => self
+                        .client
+                        .get(endpoint)
+                        .header("X-IDENTITY-HEADER", header)
+                        .query(&[("api-version", "2019-08-01")]),
+                    _ => self
+                        .client
+                        .get(IMDS_TOKEN_URL)
+                        .header("Metadata", "true")
+                        .query(&[("api-version", "2018-02-01")]),
+                };
+                request = request.query(&[("resource", VAULT_RESOURCE)]);
+                if let Some(client_id) = client_id {
+                    request = request.query(&[("client_id", client_id)]);
+                }
+                request
+            }
+            Credentials::AccessToken(_) => unreachable!("fixed tokens are never refreshed"),
+        };
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| SecretError::AuthenticationFailed(format!("Token request failed: {}", e)))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            error!("Azure AD token request failed with status {}", status);
+            return Err(SecretError::AuthenticationFailed(format!(
+                "Token request failed with status {}",
+                status
+            )));
+        }
+
+        let body: Value = response
+            .json()
+            .await
+            .map_err(|e| SecretError::AuthenticationFailed(format!("Invalid token response: {}", e)))?;
+        let token = body["access_token"].as_str().ok_or_else(|| {
+            SecretError::AuthenticationFailed("Token response has no access_token".to_string())
+        })?;
+        // Managed identity endpoints return expires_in as a string
+        let expires_in = match &body["expires_in"] {
+            Value::Number(n) => n.as_i64(),
+            Value::String(s) => s.parse().ok(),
+            _ => None,
+        }
+        .unwrap_or(3600);
+
+        Ok((token.to_string(), expires_in))
+    }
+
+    /// Convert a secret key to a Key Vault secret name.
+    fn secret_name(key: &str) -> String {
+        key.chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect()
+    }
+
+    fn secret_url(&self, key: &str) -> String {
+        format!("{}/secrets/{}", self.vault_url, Self::secret_name(key))
+    }
+
+    /// Send an authenticated request and map error statuses.
+    async fn send(&self, key: &str, request: RequestBuilder) -> Result<Response> {
+        let token = self.access_token().await?;
+        let response = request
+            .query(&[("api-version", API_VERSION)])
+            .bearer_auth(token)
+            .send()
+            .await
+            .map_err(|e| SecretError::NetworkError(e.to_string()))?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+
+        let message = response
+            .json::<Value>()
+            .await
+            .ok()
+            .and_then(|body| body["error"]["message"].as_str().map(str::to_string))
+            .unwrap_or_else(|| status.to_string());
+        Err(Self::convert_status(key, status, message))
+    }
+
+    /// Convert an API error status to a SecretError.
+    fn convert_status(key: &str, status: StatusCode, message: String) -> SecretError {
+        match status {
+            StatusCode::NOT_FOUND => SecretError::NotFound(key.to_string()),
+            StatusCode::UNAUTHORIZED => SecretError::AuthenticationFailed(message),
+            StatusCode::FORBIDDEN => SecretError::PermissionDenied(message),
+            StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE => {
+                SecretError::BackendUnavailable(message)
+            }
+            _ => SecretError::Other(format!("Azure Key Vault error {}: {}", status, message)),
+        }
+    }
+
+    /// Read a secret bundle, or its current version if `version` is `None`.
+    async fn get_bundle(&self, key: &str, version: Option<&str>) -> Result<Secret> {
+        let url = match version {
+            Some(version) => format!("{}/{}", self.secret_url(key), version),
+            None => self.secret_url(key),
+        };
+        let response = self.send(key, self.client.get(url)).await?;
+        let bundle: SecretBundle = Self::parse(response).await?;
+
+        let mut tags = bundle.tags;
+        tags.remove(KEY_TAG);
+
+        let mut secret = Secret::new(key.to_string(), bundle.value)
+            .with_metadata(tags)
+            .add_metadata("source".to_string(), "azure".to_string());
+        if let Some(content_type) = bundle.content_type {
+            secret = secret.add_metadata("content_type".to_string(), content_type);
+        }
+        if let Some(version) = version_id(&bundle.id) {
+            secret = secret.with_version(version);
+        }
+        if let Some(created_at) = bundle.attributes.created.and_then(timestamp) {
+            secret.created_at = created_at;
+        }
+        Ok(secret)
+    }
+
+    /// Fetch every page of a list endpoint.
+    async fn list_all(&self, key: &str, url: String) -> Result<Vec<SecretItem>> {
+        let mut items = Vec::new();
+        let mut request = self.client.get(url);
+
+        loop {
+            let page: SecretListResponse = Self::parse(self.send(key, request).await?).await?;
+            items.extend(page.value);
+
+            match page.next_link {
+                Some(link) => request = self.client.get(without_api_version(&link)?),
+                None => break,
+            }
+        }
+
+        Ok(items)
+    }
+
+    async fn parse<T: serde::de::DeserializeOwned>(response: Response) -> Result<T> {
+        response
+            .json()
+            .await
+            .map_err(|e| SecretError::SerializationError(e.to_string()))
+    }
+}
+
+/// Returns the version segment of a secret ID URL.
+fn version_id(id: &str) -> Option<String> {
+    let mut segments = id.trim_end_matches('/').rsplit('/');
+    let version = segments.next()?;
+    let name = segments.next()?;
+    (segments.next() == Some("secrets") && !name.is_empty()).then(|| version.to_string())
+}
+
+/// Strip the api-version from a nextLink, since [`AzureKeyVaultStore::send`] adds it.
+fn without_api_version(link: &str) -> Result<reqwest::Url> {
+    let mut url = reqwest::Url::parse(link)
+        .map_err(|e| SecretError::SerializationError(format!("Invalid nextLink: {}", e)))?;
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(name, _)| name != "api-version")
+        .map(|(name, value)| (name.into_owned(), value.into_owned()))
+        .collect();
+    url.query_pairs_mut().clear().extend_pairs(pairs);
+    Ok(url)
+}
+
+fn timestamp(seconds: i64) -> Option<DateTime<Utc>> {
+    Utc.timestamp_opt(seconds, 0).single()
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SecretBundle {
+    value: String,
+    id: String,
+    content_type: Option<String>,
+    #[serde(default)]
+    attributes: SecretAttributes,
+    #[serde(default)]
+    tags: HashMap<String, String>,
+}
+
+#[derive(Default, Deserialize)]
+struct SecretAttributes {
+    #[serde(default = "default_enabled")]
+    enabled: bool,
+    created: Option<i64>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SecretListResponse {
+    #[serde(default)]
+    value: Vec<SecretItem>,
+    next_link: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SecretItem {
+    id: String,
+    #[serde(default)]
+    attributes: SecretAttributes,
+    #[serde(default)]
+    tags: HashMap<String, String>,
+}
+
+#[async_trait]
+impl SecretStore for AzureKeyVaultStore {
+    async fn get_secret(&self, key: &str) -> Result<Secret> {
+        debug!("Retrieving secret from Azure Key Vault: {}", key);
+        let secret = self.get_bundle(key, None).await?;
+        debug!("Successfully retrieved secret: {}", key);
+        Ok(secret)
+    }
+
+    async fn put_secret(
+        &self,
+        key: &str,
+        value: &str,
+        metadata: Option<SecretMetadata>,
+    ) -> Result<()> {
+        debug!("Storing secret in Azure Key Vault: {}", key);
+
+        let mut tags = metadata.as_ref().map(|m| m.tags.clone()).unwrap_or_default();
+        if let Some(description) = metadata.as_ref().and_then(|m| m.description.clone()) {
+            tags.insert("description".to_string(), description);
+        }
+        tags.insert(KEY_TAG.to_string(), key.to_string());
+
+        // Every write creates a new version
+        let request = self
+            .client
+            .put(self.secret_url(key))
+            .json(&json!({ "value": value, "tags": tags }));
+        self.send(key, request).await?;
+
+        info!("Successfully stored secret: {}", key);
+        Ok(())
+    }
+
+    async fn delete_secret(&self, key: &str) -> Result<()> {
+        debug!("Deleting secret from Azure Key Vault: {}", key);
+        self.send(key, self.client.delete(self.secret_url(key))).await?;
+        info!("Successfully deleted secret: {}", key);
+        Ok(())
+    }
+
+    async fn list_secrets(&self, prefix: &str) -> Result<Vec<String>> {
+        debug!("Listing secrets with prefix: {}", prefix);
+
+        let items = self
+            .list_all(prefix, format!("{}/secrets", self.vault_url))
+            .await?;
+        let keys: Vec<String> = items
+            .into_iter()
+            .filter_map(|item| {
+                let mut tags = item.tags;
+                tags.remove(KEY_TAG)
+                    .or_else(|| item.id.rsplit('/').next().map(str::to_string))
+            })
+            .filter(|key| key.starts_with(prefix))
+            .collect();
+
+        debug!("Found {} secrets with prefix {}", keys.len(), prefix);
+        Ok(keys)
+    }
+
+    async fn rotate_secret(&self, key: &str) -> Result<Secret> {
+        debug!("Rotating secret: {}", key);
+
+        // Rotation adds a new version; the new value must be generated externally
+        let current = self.get_secret(key).await?;
+        warn!(
+            "Secret rotation for {} - new value should be generated externally",
+            key
+        );
+        let tags = current
+            .metadata
+            .iter()
+            .filter(|(k, _)| !matches!(k.as_str(), "source" | "content_type"))
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        self.put_secret(key, &current.value, Some(SecretMetadata::new().with_tags(tags)))
+            .await?;
+
+        self.get_secret(key).await
+    }
+
+    async fn health_check(&self) -> Result<()> {
+        debug!("Performing Azure Key Vault health check");
+
+        let request = self
+            .client
+            .get(format!("{}/secrets", self.vault_url))
+            .query(&[("maxresults", "1")]);
+        self.send(&self.vault_url, request).await.map_err(|e| {
+            error!("Azure Key Vault health check failed: {}", e);
+            SecretError::BackendUnavailable(format!("Health check failed: {}", e))
+        })?;
+
+        debug!("Azure Key Vault health check: OK");
+        Ok(())
+    }
+
+    async fn get_secret_versions(&self, key: &str) -> Result<Vec<SecretVersion>> {
+        debug!("Retrieving versions for secret: {}", key);
+
+        let items = self
+            .list_all(key, format!("{}/versions", self.secret_url(key)))
+            .await?;
+
+        let mut versions: Vec<(SecretVersion, bool)> = items
+            .iter()
+            .filter_map(|item| {
+                let version = version_id(&item.id)?;
+                let created_at = item.attributes.created.and_then(timestamp).unwrap_or_default();
+                Some((SecretVersion::new(version, created_at), item.attributes.enabled))
+            })
+            .collect();
+        versions.sort_by_key(|(version, _)| std::cmp::Reverse(version.created_at));
+
+        // The current version is the newest enabled one; disabled versions
+        // cannot be read and are reported as deleted
+        if let Some((version, _)) = versions.iter_mut().find(|(_, enabled)| *enabled) {
+            version.is_current = true;
+        }
+        let versions: Vec<SecretVersion> = versions
+            .into_iter()
+            .map(|(mut version, enabled)| {
+                version.is_deleted = !enabled;
+                version
+            })
+            .collect();
+
+        debug!("Found {} versions for secret {}", versions.len(), key);
+        Ok(versions)
+    }
+
+    async fn get_secret_version(&self, key: &str, version: &str) -> Result<Secret> {
+        debug!("Retrieving secret {} version {}", key, version);
+
+        if version.is_empty() || !version.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(SecretError::InvalidSecret(format!(
+                "Invalid version identifier: {}",
+                version
+            )));
+        }
+        self.get_bundle(key, Some(version)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{Matcher, Server};
+
+    fn store(server: &Server) -> AzureKeyVaultStore {
+        AzureKeyVaultStore::with_access_token(server.url(), "test-token".to_string())
+    }
+
+    #[test]
+    fn test_secret_name_mapping() {
+        assert_eq!(AzureKeyVaultStore::secret_name("openai/api_key"), "openai-api-key");
+        assert_eq!(
+            version_id("https://v.vault.azure.net/secrets/openai-api-key/abc123"),
+            Some("abc123".to_string())
+        );
+        assert_eq!(version_id("https://v.vault.azure.net/secrets/openai-api-key"), None);
+    }
+
+    #[tokio::test]
+    async fn test_get_secret_with_tags() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/secrets/openai-api-key")
+            .match_query(Matcher::UrlEncoded("api-version".into(), API_VERSION.into()))
+            .match_header("authorization", "Bearer test-token")
+            .with_body(
+                r#"{
+                    "value": "sk-test",
+                    "id": "https://v.vault.azure.net/secrets/openai-api-key/abc123",
+                    "attributes": {"enabled": true, "created": 1735689600},
+                    "tags": {"team": "ml", "orchestrator-key": "openai/api_key"}
+                }"#,
+            )
+            .create_async()
+            .await;
+
+        let secret = store(&server).get_secret("openai/api_key").await.unwrap();
+        assert_eq!(secret.value, "sk-test");
+        assert_eq!(secret.version.as_deref(), Some("abc123"));
+        assert_eq!(secret.metadata["team"], "ml");
+        assert!(!secret.metadata.contains_key(KEY_TAG));
+        assert_eq!(secret.created_at.timestamp(), 1735689600);
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_missing_secret() {
+        let mut server = Server::new_async().await;
+        server
+            .mock("GET", "/secrets/missing")
+            .match_query(Matcher::Any)
+            .with_status(404)
+            .with_body(r#"{"error": {"code": "SecretNotFound", "message": "Secret not found"}}"#)
+            .create_async()
+            .await;
+
+        let result = store(&server).get_secret("missing").await;
+        assert!(matches!(result, Err(SecretError::NotFound(key)) if key == "missing"));
+    }
+
+    #[tokio::test]
+    async fn test_list_secrets_uses_key_tag() {
+        let mut server = Server::new_async().await;
+        server
+            .mock("GET", "/secrets")
+            .match_query(Matcher::Any)
+            .with_body(
+                r#"{"value": [
+                    {"id": "https://v.vault.azure.net/secrets/openai-api-key", "tags": {"orchestrator-key": "openai/api_key"}},
+                    {"id": "https://v.vault.azure.net/secrets/db-password"}
+                ]}"#,
+            )
+            .create_async()
+            .await;
+
+        let keys = store(&server).list_secrets("openai/").await.unwrap();
+        assert_eq!(keys, vec!["openai/api_key".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_get_secret_versions() {
+        let mut server = Server::new_async().await;
+        server
+            .mock("GET", "/secrets/db-password/versions")
+            .match_query(Matcher::Any)
+            .with_body(
+                r#"{"value": [
+                    {"id": "https://v.vault.azure.net/secrets/db-password/v1", "attributes": {"enabled": true, "created": 1735689600}},
+                    {"id": "https://v.vault.azure.net/secrets/db-password/v3", "attributes": {"enabled": false, "created": 1735862400}},
+                    {"id": "https://v.vault.azure.net/secrets/db-password/v2", "attributes": {"enabled": true, "created": 1735776000}}
+                ]}"#,
+            )
+            .create_async()
+            .await;
+
+        let versions = store(&server).get_secret_versions("db-password").await.unwrap();
+        let ids: Vec<&str> = versions.iter().map(|v| v.version.as_str()).collect();
+        assert_eq!(ids, vec!["v3", "v2", "v1"]);
+        assert!(versions[0].is_deleted && !versions[0].is_current);
+        assert!(versions[1].is_current);
+        assert!(!versions[2].is_current);
+    }
+}
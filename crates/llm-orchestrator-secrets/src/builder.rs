@@ -4,12 +4,16 @@
 //! Secret manager builder and factory.
 
 use crate::aws::AwsSecretStore;
+use crate::azure::AzureKeyVaultStore;
 use crate::cache::SecretCache;
 use crate::env::EnvSecretStore;
+use crate::file::FileSecretStore;
+use crate::gcp::GcpSecretStore;
 use crate::traits::{Result, SecretError, SecretStore};
 use crate::vault::VaultSecretStore;
 use aws_sdk_secretsmanager::config::Region;
 use chrono::Duration;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tracing::info;
 
@@ -20,6 +24,12 @@ pub enum SecretStoreType {
     Vault,
     /// AWS Secrets Manager backend.
     AwsSecretsManager,
+    /// Google Cloud Secret Manager backend.
+    Gcp,
+    /// Azure Key Vault backend.
+    AzureKeyVault,
+    /// Encrypted local file backend.
+    File,
     /// Environment variable backend.
     Environment,
 }
@@ -49,10 +59,18 @@ pub struct SecretManagerBuilder {
     cache_enabled: bool,
     /// Cache TTL duration.
     cache_ttl: Duration,
+    /// Interval of the background cache refresh, if enabled.
+    refresh_interval: Option<Duration>,
     /// Vault-specific configuration.
     vault_config: Option<VaultConfig>,
     /// AWS-specific configuration.
     aws_config: Option<AwsConfig>,
+    /// GCP-specific configuration.
+    gcp_config: Option<GcpConfig>,
+    /// Azure-specific configuration.
+    azure_config: Option<AzureConfig>,
+    /// Encrypted file configuration.
+    file_config: Option<FileConfig>,
     /// Environment variable prefix.
     env_prefix: Option<String>,
 }
@@ -140,6 +158,145 @@ impl AwsConfig {
     }
 }
 
+/// Configuration for Google Cloud Secret Manager.
+#[derive(Debug, Clone, Default)]
+pub struct GcpConfig {
+    /// Google Cloud project ID.
+    pub project_id: Option<String>,
+    /// Service account or user credentials file.
+    pub credentials_path: Option<PathBuf>,
+}
+
+impl GcpConfig {
+    /// Create a new GCP configuration for a specific project.
+    pub fn new(project_id: String) -> Self {
+        Self {
+            project_id: Some(project_id),
+            credentials_path: None,
+        }
+    }
+
+    /// Create configuration that will use Application Default Credentials
+    /// and the default project from environment.
+    pub fn from_env() -> Self {
+        Self::default()
+    }
+
+    /// Set the credentials file.
+    pub fn with_credentials_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.credentials_path = Some(path.into());
+        self
+    }
+}
+
+/// Configuration for Azure Key Vault.
+#[derive(Debug, Clone)]
+pub struct AzureConfig {
+    /// Vault URL (e.g. `https://my-vault.vault.azure.net`).
+    pub vault_url: String,
+    /// Azure AD tenant ID (client credentials).
+    pub tenant_id: Option<String>,
+    /// Application or user-assigned managed identity client ID.
+    pub client_id: Option<String>,
+    /// Client secret (client credentials).
+    pub client_secret: Option<String>,
+}
+
+impl AzureConfig {
+    /// Create a configuration that authenticates with the system-assigned managed identity.
+    pub fn new(vault_url: String) -> Self {
+        Self {
+            vault_url,
+            tenant_id: None,
+            client_id: None,
+            client_secret: None,
+        }
+    }
+
+    /// Authenticate as a service principal.
+    pub fn with_client_credentials(
+        mut self,
+        tenant_id: String,
+        client_id: String,
+        client_secret: String,
+    ) -> Self {
+        self.tenant_id = Some(tenant_id);
+        self.client_id = Some(client_id);
+        self.client_secret = Some(client_secret);
+        self
+    }
+
+    /// Authenticate with a user-assigned managed identity.
+    pub fn with_managed_identity(mut self, client_id: String) -> Self {
+        self.client_id = Some(client_id);
+        self
+    }
+
+    /// Load Azure configuration from environment variables.
+    ///
+    /// Reads:
+    /// - `AZURE_KEYVAULT_URL` - Vault URL
+    /// - `AZURE_TENANT_ID` - Optional tenant ID
+    /// - `AZURE_CLIENT_ID` - Optional client ID
+    /// - `AZURE_CLIENT_SECRET` - Optional client secret
+    pub fn from_env() -> Result<Self> {
+        let vault_url = std::env::var("AZURE_KEYVAULT_URL")
+            .map_err(|_| SecretError::EnvVarNotFound("AZURE_KEYVAULT_URL".to_string()))?;
+
+        Ok(Self {
+            vault_url,
+            tenant_id: std::env::var("AZURE_TENANT_ID").ok(),
+            client_id: std::env::var("AZURE_CLIENT_ID").ok(),
+            client_secret: std::env::var("AZURE_CLIENT_SECRET").ok(),
+        })
+    }
+}
+
+/// Configuration for the encrypted file store.
+#[derive(Debug, Clone)]
+pub struct FileConfig {
+    /// Path of the encrypted secrets file.
+    pub path: PathBuf,
+    /// Source of the encryption key.
+    pub key: FileKey,
+}
+
+/// Encryption key source for the file store.
+#[derive(Clone)]
+pub enum FileKey {
+    /// Derive the key from a passphrase.
+    Passphrase(String),
+    /// Read the key from a key file.
+    KeyFile(PathBuf),
+}
+
+impl std::fmt::Debug for FileKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FileKey::Passphrase(_) => f.write_str("Passphrase(***)"),
+            FileKey::KeyFile(path) => f.debug_tuple("KeyFile").field(path).finish(),
+        }
+    }
+}
+
+impl FileConfig {
+    /// Create a configuration with a passphrase-derived key.
+    pub fn with_passphrase(path: impl Into<PathBuf>, passphrase: String) -> Self {
+        Self {
+            path: path.into(),
+            key: FileKey::Passphrase(passphrase),
+        }
+    }
+
+    /// Create a configuration with a key file.
+    pub fn with_key_file(path: impl Into<PathBuf>, key_path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            key: FileKey::KeyFile(key_path.into()),
+        }
+    }
+}
+
 impl SecretManagerBuilder {
     /// Create a new secret manager builder.
     ///
@@ -151,8 +308,12 @@ impl SecretManagerBuilder {
             store_type,
             cache_enabled: false,
             cache_ttl: Duration::minutes(5),
+            refresh_interval: None,
             vault_config: None,
             aws_config: None,
+            gcp_config: None,
+            azure_config: None,
+            file_config: None,
             env_prefix: None,
         }
     }
@@ -168,6 +329,15 @@ impl SecretManagerBuilder {
         self
     }
 
+    /// Refresh cached secrets in the background before they expire.
+    ///
+    /// Only takes effect when caching is enabled. Due entries are checked
+    /// every `interval`.
+    pub fn with_background_refresh(mut self, interval: Duration) -> Self {
+        self.refresh_interval = Some(interval);
+        self
+    }
+
     /// Disable caching (enabled by default if `with_cache` was called).
     pub fn without_cache(mut self) -> Self {
         self.cache_enabled = false;
@@ -191,6 +361,34 @@ impl SecretManagerBuilder {
         self
     }
 
+    /// Set GCP configuration.
+    ///
+    /// This is optional for `SecretStoreType::Gcp`.
+    /// If not provided, Application Default Credentials and the default
+    /// project are used.
+    pub fn with_gcp_config(mut self, config: GcpConfig) -> Self {
+        self.gcp_config = Some(config);
+        self
+    }
+
+    /// Set Azure configuration.
+    ///
+    /// This is optional for `SecretStoreType::AzureKeyVault`.
+    /// If not provided, configuration is read from environment variables.
+    pub fn with_azure_config(mut self, config: AzureConfig) -> Self {
+        self.azure_config = Some(config);
+        self
+    }
+
+    /// Set encrypted file configuration.
+    ///
+    /// This is optional for `SecretStoreType::File`.
+    /// If not provided, configuration is read from environment variables.
+    pub fn with_file_config(mut self, config: FileConfig) -> Self {
+        self.file_config = Some(config);
+        self
+    }
+
     /// Set environment variable prefix.
     ///
     /// This is optional for `SecretStoreType::Environment`.
@@ -242,6 +440,53 @@ impl SecretManagerBuilder {
                 Arc::new(store)
             }
 
+            SecretStoreType::Gcp => {
+                let config = self.gcp_config.unwrap_or_default();
+                let store =
+                    GcpSecretStore::from_config(config.project_id, config.credentials_path).await?;
+
+                Arc::new(store)
+            }
+
+            SecretStoreType::AzureKeyVault => {
+                let config = match self.azure_config {
+                    Some(config) => config,
+                    None => AzureConfig::from_env()?,
+                };
+
+                let store = match (config.tenant_id, config.client_id, config.client_secret) {
+                    (Some(tenant_id), Some(client_id), Some(client_secret)) => {
+                        AzureKeyVaultStore::with_client_credentials(
+                            config.vault_url,
+                            tenant_id,
+                            client_id,
+                            client_secret,
+                        )
+                    }
+                    (_, client_id, _) => {
+                        AzureKeyVaultStore::with_managed_identity(config.vault_url, client_id)
+                    }
+                };
+
+                Arc::new(store)
+            }
+
+            SecretStoreType::File => {
+                let store = match self.file_config {
+                    Some(FileConfig {
+                        path,
+                        key: FileKey::Passphrase(passphrase),
+                    }) => FileSecretStore::with_passphrase(path, passphrase),
+                    Some(FileConfig {
+                        path,
+                        key: FileKey::KeyFile(key_path),
+                    }) => FileSecretStore::with_key_file(path, key_path)?,
+                    None => FileSecretStore::from_env()?,
+                };
+
+                Arc::new(store)
+            }
+
             SecretStoreType::Environment => {
                 let store = if let Some(prefix) = self.env_prefix {
                     EnvSecretStore::with_prefix(prefix)
@@ -259,7 +504,11 @@ impl SecretManagerBuilder {
                 "Enabling cache with TTL of {} seconds",
                 self.cache_ttl.num_seconds()
             );
-            Ok(Arc::new(SecretCache::new(backend, self.cache_ttl)))
+            let cache = Arc::new(SecretCache::new(backend, self.cache_ttl));
+            if let Some(interval) = self.refresh_interval {
+                cache.spawn_background_refresh(interval);
+            }
+            Ok(cache)
         } else {
             Ok(backend)
         }
@@ -338,6 +587,41 @@ mod tests {
         std::env::remove_var("VAULT_NAMESPACE");
     }
 
+    #[test]
+    fn test_gcp_config() {
+        let config = GcpConfig::new("my-project".to_string()).with_credentials_file("/tmp/sa.json");
+        assert_eq!(config.project_id.as_deref(), Some("my-project"));
+        assert_eq!(config.credentials_path, Some(PathBuf::from("/tmp/sa.json")));
+        assert!(GcpConfig::from_env().project_id.is_none());
+    }
+
+    #[test]
+    fn test_azure_config() {
+        let config = AzureConfig::new("https://v.vault.azure.net".to_string())
+            .with_client_credentials("tenant".to_string(), "client".to_string(), "secret".to_string());
+        assert_eq!(config.tenant_id.as_deref(), Some("tenant"));
+        assert_eq!(config.client_secret.as_deref(), Some("secret"));
+
+        let config = AzureConfig::new("https://v.vault.azure.net".to_string())
+            .with_managed_identity("identity".to_string());
+        assert_eq!(config.client_id.as_deref(), Some("identity"));
+        assert!(config.tenant_id.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_build_file_store() {
+        let path = std::env::temp_dir().join("llm-orchestrator-builder-test.enc");
+        let config = FileConfig::with_passphrase(path, "hunter2-passphrase".to_string());
+        assert!(!format!("{:?}", config).contains("hunter2"));
+
+        let store = SecretManagerBuilder::new(SecretStoreType::File)
+            .with_file_config(config)
+            .build()
+            .await
+            .unwrap();
+        assert!(store.health_check().await.is_ok());
+    }
+
     #[tokio::test]
     async fn test_build_env_store() {
         let store = SecretManagerBuilder::build_env(None).await.unwrap();
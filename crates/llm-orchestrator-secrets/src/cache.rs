@@ -4,7 +4,9 @@
 //! In-memory secret cache with TTL.
 //!
 //! Provides a caching layer for secret stores to reduce backend calls
-//! and improve performance.
+//! and improve performance. Entries can be refreshed in the background
+//! before they expire, and subscribers are notified when a secret's version
+//! changes so rotated credentials are picked up by long-running workers.
 
 use crate::models::{Secret, SecretMetadata, SecretVersion};
 use crate::traits::{Result, SecretStore};
@@ -12,8 +14,22 @@ use async_trait::async_trait;
 use chrono::{DateTime, Duration, Utc};
 use parking_lot::RwLock;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use tracing::{debug, trace};
+use tracing::{debug, trace, warn};
+
+/// Default fraction of the TTL after which an entry is refreshed.
+const DEFAULT_REFRESH_RATIO: f64 = 0.8;
+
+/// Callback invoked with the new secret when a cached secret changes.
+pub type SecretChangeCallback = Arc<dyn Fn(&Secret) + Send + Sync>;
+
+/// Identifies a subscription created with [`SecretCache::subscribe`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SubscriptionId(u64);
+
+/// Change callbacks keyed by secret key.
+type Subscribers = HashMap<String, Vec<(SubscriptionId, SecretChangeCallback)>>;
 
 /// Cached secret with expiration.
 #[derive(Debug, Clone)]
@@ -22,6 +38,8 @@ struct CachedSecret {
     secret: Secret,
     /// When this cache entry expires.
     expires_at: DateTime<Utc>,
+    /// When this cache entry becomes due for a background refresh.
+    refresh_at: DateTime<Utc>,
 }
 
 impl CachedSecret {
@@ -29,6 +47,16 @@ impl CachedSecret {
     fn is_expired(&self) -> bool {
         Utc::now() >= self.expires_at
     }
+
+    /// Check if this cache entry should be refreshed.
+    fn is_refresh_due(&self) -> bool {
+        Utc::now() >= self.refresh_at
+    }
+}
+
+/// Returns true if `new` is a different version or value than `old`.
+fn has_changed(old: &Secret, new: &Secret) -> bool {
+    old.version != new.version || old.value != new.value
 }
 
 /// Secret cache wrapper that adds TTL-based caching to any SecretStore.
@@ -40,6 +68,8 @@ impl CachedSecret {
 /// - Automatic expiration checking
 /// - Manual cache invalidation
 /// - Cache statistics tracking
+/// - Proactive background refresh (by default at 80% of the TTL)
+/// - Change subscriptions for rotation-aware consumers
 ///
 /// # Example
 ///
@@ -67,8 +97,14 @@ pub struct SecretCache<S: SecretStore + ?Sized> {
     cache: Arc<RwLock<HashMap<String, CachedSecret>>>,
     /// Time-to-live for cached secrets.
     ttl: Duration,
+    /// Fraction of the TTL after which an entry is due for refresh.
+    refresh_ratio: f64,
     /// Cache statistics.
     stats: Arc<RwLock<CacheStats>>,
+    /// Change callbacks keyed by secret key.
+    subscribers: Arc<RwLock<Subscribers>>,
+    /// Next subscription identifier.
+    next_subscription: AtomicU64,
 }
 
 /// Cache statistics for monitoring.
@@ -82,6 +118,12 @@ pub struct CacheStats {
     pub expirations: u64,
     /// Total number of manual invalidations.
     pub invalidations: u64,
+    /// Total number of successful refreshes.
+    pub refreshes: u64,
+    /// Total number of failed refreshes.
+    pub refresh_failures: u64,
+    /// Total number of detected version changes.
+    pub version_changes: u64,
 }
 
 impl CacheStats {
@@ -114,10 +156,21 @@ impl<S: SecretStore + ?Sized> SecretCache<S> {
             backend,
             cache: Arc::new(RwLock::new(HashMap::new())),
             ttl,
+            refresh_ratio: DEFAULT_REFRESH_RATIO,
             stats: Arc::new(RwLock::new(CacheStats::default())),
+            subscribers: Arc::new(RwLock::new(HashMap::new())),
+            next_subscription: AtomicU64::new(0),
         }
     }
 
+    /// Set the fraction of the TTL after which entries are refreshed.
+    ///
+    /// The ratio is clamped to `0.0..=1.0`. Defaults to `0.8`.
+    pub fn with_refresh_ratio(mut self, ratio: f64) -> Self {
+        self.refresh_ratio = ratio.clamp(0.0, 1.0);
+        self
+    }
+
     /// Create a new secret cache with default TTL (5 minutes).
     pub fn with_default_ttl(backend: Arc<S>) -> Self {
         Self::new(backend, Duration::minutes(5))
@@ -137,7 +190,9 @@ impl<S: SecretStore + ?Sized> SecretCache<S> {
                     return Ok(cached.secret.clone());
                 } else {
                     debug!("Cache entry expired for key: {}", key);
-                    self.stats.write().expirations += 1;
+                    let mut stats = self.stats.write();
+                    stats.expirations += 1;
+                    stats.misses += 1;
                     // Entry is expired, fall through to fetch from backend
                 }
             } else {
@@ -148,22 +203,173 @@ impl<S: SecretStore + ?Sized> SecretCache<S> {
 
         // Not in cache or expired, fetch from backend
         let secret = self.backend.get_secret(key).await?;
+        self.store(key, &secret);
 
-        // Store in cache
-        {
-            let mut cache_guard = self.cache.write();
-            let expires_at = Utc::now() + self.ttl;
-            cache_guard.insert(
-                key.to_string(),
-                CachedSecret {
-                    secret: secret.clone(),
-                    expires_at,
-                },
-            );
-            debug!("Cached secret {} until {}", key, expires_at);
+        Ok(secret)
+    }
+
+    /// Fetch a secret from the backend and replace the cached entry.
+    ///
+    /// Subscribers are notified if the version or value changed.
+    pub async fn refresh(&self, key: &str) -> Result<Secret> {
+        trace!("Refreshing cache entry for key: {}", key);
+
+        match self.backend.get_secret(key).await {
+            Ok(secret) => {
+                self.stats.write().refreshes += 1;
+                self.store(key, &secret);
+                Ok(secret)
+            }
+            Err(e) => {
+                self.stats.write().refresh_failures += 1;
+                Err(e)
+            }
         }
+    }
 
-        Ok(secret)
+    /// Refresh every entry that has passed its refresh point.
+    ///
+    /// Entries that fail to refresh keep serving their cached value until
+    /// they expire. Returns the number of entries refreshed successfully.
+    pub async fn refresh_due(&self) -> usize {
+        let due: Vec<String> = self
+            .cache
+            .read()
+            .iter()
+            .filter(|(_, cached)| cached.is_refresh_due())
+            .map(|(key, _)| key.clone())
+            .collect();
+
+        let mut refreshed = 0;
+        for key in due {
+            match self.refresh(&key).await {
+                Ok(_) => refreshed += 1,
+                Err(e) => warn!("Failed to refresh cached secret {}: {}", key, e),
+            }
+        }
+        refreshed
+    }
+
+    /// Spawn a task that refreshes due entries every `interval`.
+    ///
+    /// The task holds only a weak reference and stops once the cache is
+    /// dropped.
+    pub fn spawn_background_refresh(self: &Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()>
+    where
+        S: 'static,
+    {
+        let cache = Arc::downgrade(self);
+        let period = interval
+            .to_std()
+            .ok()
+            .filter(|period| !period.is_zero())
+            .unwrap_or(std::time::Duration::from_secs(1));
+        debug!("Starting background secret refresh every {:?}", period);
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(period);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            loop {
+                ticker.tick().await;
+                let Some(cache) = cache.upgrade() else {
+                    debug!("Secret cache dropped, stopping background refresh");
+                    break;
+                };
+                cache.refresh_due().await;
+            }
+        })
+    }
+
+    /// Register a callback invoked when the secret under `key` changes.
+    ///
+    /// Changes are detected whenever the cache fetches the secret again,
+    /// whether on expiry, background refresh, write-through or rotation.
+    pub fn subscribe(
+        &self,
+        key: &str,
+        callback: impl Fn(&Secret) + Send + Sync + 'static,
+    ) -> SubscriptionId {
+        let id = SubscriptionId(self.next_subscription.fetch_add(1, Ordering::Relaxed));
+        self.subscribers
+            .write()
+            .entry(key.to_string())
+            .or_default()
+            .push((id, Arc::new(callback)));
+        debug!("Added change subscription for key: {}", key);
+        id
+    }
+
+    /// Remove a subscription. Returns false if it did not exist.
+    pub fn unsubscribe(&self, id: SubscriptionId) -> bool {
+        let mut subscribers = self.subscribers.write();
+        let mut removed = false;
+        subscribers.retain(|_, callbacks| {
+            let before = callbacks.len();
+            callbacks.retain(|(existing, _)| *existing != id);
+            removed |= callbacks.len() != before;
+            !callbacks.is_empty()
+        });
+        removed
+    }
+
+    /// Check whether any subscriber is registered for `key`.
+    fn has_subscribers(&self, key: &str) -> bool {
+        self.subscribers.read().contains_key(key)
+    }
+
+    /// Insert a secret and notify subscribers if it replaced a different one.
+    fn store(&self, key: &str, secret: &Secret) {
+        let now = Utc::now();
+        let refresh_after =
+            Duration::milliseconds((self.ttl.num_milliseconds() as f64 * self.refresh_ratio) as i64);
+        let expires_at = now + self.ttl;
+
+        let previous = self.cache.write().insert(
+            key.to_string(),
+            CachedSecret {
+                secret: secret.clone(),
+                expires_at,
+                refresh_at: now + refresh_after,
+            },
+        );
+        debug!("Cached secret {} until {}", key, expires_at);
+
+        if previous.is_some_and(|previous| has_changed(&previous.secret, secret)) {
+            self.notify(key, secret);
+        }
+    }
+
+    /// Invoke the change callbacks registered for `key`.
+    fn notify(&self, key: &str, secret: &Secret) {
+        debug!(
+            "Secret {} changed (version {})",
+            key,
+            secret.version.as_deref().unwrap_or("unknown")
+        );
+        self.stats.write().version_changes += 1;
+
+        // Clone the callbacks so they run without holding the lock
+        let callbacks: Vec<SecretChangeCallback> = self
+            .subscribers
+            .read()
+            .get(key)
+            .map(|callbacks| callbacks.iter().map(|(_, cb)| cb.clone()).collect())
+            .unwrap_or_default();
+        for callback in callbacks {
+            callback(secret);
+        }
+    }
+
+    /// Refresh subscribed keys after a write, otherwise drop the entry.
+    async fn after_write(&self, key: &str) {
+        if self.cache.read().contains_key(key) && self.has_subscribers(key) {
+            if let Err(e) = self.refresh(key).await {
+                warn!("Failed to refresh secret {} after write: {}", key, e);
+                self.invalidate(key);
+            }
+        } else {
+            self.invalidate(key);
+        }
     }
 
     /// Invalidate a specific cache entry.
@@ -236,11 +442,10 @@ impl<S: SecretStore + ?Sized> SecretStore for SecretCache<S> {
         value: &str,
         metadata: Option<SecretMetadata>,
     ) -> Result<()> {
-        // Invalidate cache for this key
-        self.invalidate(key);
-
-        // Forward to backend
-        self.backend.put_secret(key, value, metadata).await
+        // Forward to backend, then refresh or invalidate the cached entry
+        self.backend.put_secret(key, value, metadata).await?;
+        self.after_write(key).await;
+        Ok(())
     }
 
     async fn delete_secret(&self, key: &str) -> Result<()> {
@@ -257,11 +462,10 @@ impl<S: SecretStore + ?Sized> SecretStore for SecretCache<S> {
     }
 
     async fn rotate_secret(&self, key: &str) -> Result<Secret> {
-        // Invalidate cache for this key
-        self.invalidate(key);
-
-        // Forward to backend
-        self.backend.rotate_secret(key).await
+        // Forward to backend and cache the new version
+        let secret = self.backend.rotate_secret(key).await?;
+        self.store(key, &secret);
+        Ok(secret)
     }
 
     async fn health_check(&self) -> Result<()> {
@@ -429,4 +633,65 @@ mod tests {
         env::remove_var("TEST_CLEANUP_KEY1");
         env::remove_var("TEST_CLEANUP_KEY2");
     }
+
+    #[tokio::test]
+    async fn test_background_refresh_notifies_on_change() {
+        env::set_var("TEST_ROTATE_KEY", "old_value");
+
+        let backend = Arc::new(EnvSecretStore::new());
+        let cache = Arc::new(SecretCache::new(backend, Duration::milliseconds(200)));
+        let _ = cache.get("test/rotate/key").await.unwrap();
+
+        let seen = Arc::new(RwLock::new(Vec::new()));
+        let seen_clone = seen.clone();
+        cache.subscribe("test/rotate/key", move |secret| {
+            seen_clone.write().push(secret.value.clone());
+        });
+
+        // Nothing is due before 80% of the TTL
+        assert_eq!(cache.refresh_due().await, 0);
+
+        env::set_var("TEST_ROTATE_KEY", "new_value");
+        let handle = cache.spawn_background_refresh(Duration::milliseconds(20));
+        tokio::time::sleep(tokio::time::Duration::from_millis(250)).await;
+
+        // The refreshed value is served without waiting for expiry
+        assert_eq!(cache.get("test/rotate/key").await.unwrap().value, "new_value");
+        assert_eq!(seen.read().as_slice(), ["new_value".to_string()]);
+
+        let stats = cache.stats();
+        assert!(stats.refreshes >= 1);
+        assert_eq!(stats.version_changes, 1);
+
+        handle.abort();
+        env::remove_var("TEST_ROTATE_KEY");
+    }
+
+    #[tokio::test]
+    async fn test_unsubscribe() {
+        env::set_var("TEST_UNSUBSCRIBE_KEY", "value1");
+
+        let backend = Arc::new(EnvSecretStore::new());
+        let cache = SecretCache::new(backend, Duration::minutes(5));
+        let _ = cache.get("test/unsubscribe/key").await.unwrap();
+
+        let calls = Arc::new(AtomicU64::new(0));
+        let calls_clone = calls.clone();
+        let id = cache.subscribe("test/unsubscribe/key", move |_| {
+            calls_clone.fetch_add(1, Ordering::Relaxed);
+        });
+
+        env::set_var("TEST_UNSUBSCRIBE_KEY", "value2");
+        cache.refresh("test/unsubscribe/key").await.unwrap();
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+
+        assert!(cache.unsubscribe(id));
+        assert!(!cache.unsubscribe(id));
+
+        env::set_var("TEST_UNSUBSCRIBE_KEY", "value3");
+        cache.refresh("test/unsubscribe/key").await.unwrap();
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+
+        env::remove_var("TEST_UNSUBSCRIBE_KEY");
+    }
 }
@@ -0,0 +1,558 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Encrypted file secret store implementation.
+//!
+//! Secrets are kept in a single local file encrypted with AES-256-GCM. The key
+//! is either derived from a passphrase with Argon2id or read from a key file.
+
+use crate::models::{Secret, SecretMetadata, SecretVersion};
+use crate::traits::{Result, SecretError, SecretStore};
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use argon2::Argon2;
+use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use tokio::sync::Mutex;
+use tracing::{debug, info, warn};
+
+/// Current file format version.
+const FORMAT_VERSION: u32 = 1;
+
+/// Length of the AES-256 key in bytes.
+const KEY_LEN: usize = 32;
+
+/// Length of the Argon2 salt in bytes.
+const SALT_LEN: usize = 16;
+
+/// Length of an AES-GCM nonce in bytes.
+const NONCE_LEN: usize = 12;
+
+/// Source of the encryption key.
+enum KeySource {
+    /// Key derived from a passphrase and the salt stored in the file.
+    Passphrase(String),
+    /// Raw key read from a key file.
+    Key([u8; KEY_LEN]),
+}
+
+/// On-disk envelope around the encrypted secrets.
+#[derive(Serialize, Deserialize)]
+struct EncryptedFile {
+    version: u32,
+    /// Base64 Argon2 salt, present when the key is derived from a passphrase.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    salt: Option<String>,
+    /// Base64 AES-GCM nonce.
+    nonce: String,
+    /// Base64 ciphertext of the serialized [`SecretEntries`].
+    ciphertext: String,
+}
+
+/// Decrypted contents of the store.
+type SecretEntries = BTreeMap<String, SecretEntry>;
+
+#[derive(Serialize, Deserialize)]
+struct SecretEntry {
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    tags: HashMap<String, String>,
+    versions: Vec<StoredVersion>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredVersion {
+    version: u64,
+    value: String,
+    created_at: DateTime<Utc>,
+}
+
+impl SecretEntry {
+    fn current(&self) -> Option<&StoredVersion> {
+        self.versions.last()
+    }
+
+    fn to_secret(&self, key: &str, version: &StoredVersion) -> Secret {
+        let mut secret = Secret::new(key.to_string(), version.value.clone())
+            .with_version(version.version.to_string())
+            .with_metadata(self.tags.clone())
+            .add_metadata("source".to_string(), "file".to_string());
+        if let Some(description) = &self.description {
+            secret = secret.add_metadata("description".to_string(), description.clone());
+        }
+        secret.created_at = version.created_at;
+        secret
+    }
+}
+
+/// Secret store backed by an encrypted local file.
+///
+/// Meant for laptops and CI where no secret manager is available but plain
+/// environment variables are too weak. Every write keeps the previous values
+/// as older versions.
+///
+/// # Example
+///
+/// ```no_run
+/// use llm_orchestrator_secrets::{FileSecretStore, SecretStore};
+///
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let store = FileSecretStore::with_passphrase(
+///     "secrets.enc",
+///     "correct horse battery staple".to_string(),
+/// );
+///
+/// store.put_secret("openai/api_key", "sk-...", None).await?;
+/// let secret = store.get_secret("openai/api_key").await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct FileSecretStore {
+    /// Path of the encrypted file.
+    path: PathBuf,
+    /// Source of the encryption key.
+    key_source: KeySource,
+    /// Serializes read-modify-write cycles.
+    lock: Mutex<()>,
+}
+
+impl FileSecretStore {
+    /// Create a store whose key is derived from a passphrase.
+    pub fn with_passphrase(path: impl Into<PathBuf>, passphrase: String) -> Self {
+        Self::new(path.into(), KeySource::Passphrase(passphrase))
+    }
+
+    /// Create a store whose key is read from a key file.
+    ///
+    /// The key file holds 32 random bytes, either raw or base64-encoded.
+    /// See [`FileSecretStore::generate_key_file`].
+    pub fn with_key_file(path: impl Into<PathBuf>, key_path: impl AsRef<Path>) -> Result<Self> {
+        let content = std::fs::read(key_path.as_ref()).map_err(|e| {
+            SecretError::AuthenticationFailed(format!(
+                "Failed to read key file {}: {}",
+                key_path.as_ref().display(),
+                e
+            ))
+        })?;
+
+        let bytes = if content.len() == KEY_LEN {
+            content
+        } else {
+            let text = String::from_utf8_lossy(&content);
+            BASE64.decode(text.trim()).map_err(|_| {
+                SecretError::InvalidSecret("Key file must contain a base64-encoded key".to_string())
+            })?
+        };
+        let key: [u8; KEY_LEN] = bytes.try_into().map_err(|_| {
+            SecretError::InvalidSecret(format!("Key file must contain a {}-byte key", KEY_LEN))
+        })?;
+
+        Ok(Self::new(path.into(), KeySource::Key(key)))
+    }
+
+    /// Create a store from environment variables.
+    ///
+    /// Reads:
+    /// - `SECRETS_FILE` - Path of the encrypted file
+    /// - `SECRETS_KEY_FILE` - Key file, or
+    /// - `SECRETS_PASSPHRASE` - Passphrase
+    pub fn from_env() -> Result<Self> {
+        let path = std::env::var("SECRETS_FILE")
+            .map_err(|_| SecretError::EnvVarNotFound("SECRETS_FILE".to_string()))?;
+
+        if let Ok(key_path) = std::env::var("SECRETS_KEY_FILE") {
+            return Self::with_key_file(path, key_path);
+        }
+        let passphrase = std::env::var("SECRETS_PASSPHRASE")
+            .map_err(|_| SecretError::EnvVarNotFound("SECRETS_PASSPHRASE".to_string()))?;
+        Ok(Self::with_passphrase(path, passphrase))
+    }
+
+    /// Write a new random base64-encoded key to `key_path`.
+    pub fn generate_key_file(key_path: impl AsRef<Path>) -> Result<()> {
+        let mut key = [0u8; KEY_LEN];
+        OsRng.fill_bytes(&mut key);
+        write_private(key_path.as_ref(), BASE64.encode(key).as_bytes())
+    }
+
+    /// Returns the path of the encrypted file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn new(path: PathBuf, key_source: KeySource) -> Self {
+        debug!("Initialized file secret store at {}", path.display());
+        Self {
+            path,
+            key_source,
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// Derive or return the encryption key for a salt.
+    fn cipher(&self, salt: Option<&[u8]>) -> Result<Aes256Gcm> {
+        let key = match (&self.key_source, salt) {
+            (KeySource::Key(key), _) => *key,
+            (KeySource::Passphrase(passphrase), Some(salt)) => {
+                let mut key = [0u8; KEY_LEN];
+                Argon2::default()
+                    .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+                    .map_err(|e| SecretError::Other(format!("Key derivation failed: {}", e)))?;
+                key
+            }
+            (KeySource::Passphrase(_), None) => {
+                return Err(SecretError::AuthenticationFailed(
+                    "Secrets file was not encrypted with a passphrase".to_string(),
+                ))
+            }
+        };
+        Ok(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key)))
+    }
+
+    /// Read and decrypt the file; a missing file is an empty store.
+    async fn load(&self) -> Result<SecretEntries> {
+        let content = match tokio::fs::read(&self.path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(SecretEntries::new()),
+            Err(e) => {
+                return Err(SecretError::BackendUnavailable(format!(
+                    "Failed to read {}: {}",
+                    self.path.display(),
+                    e
+                )))
+            }
+        };
+
+        let file: EncryptedFile = serde_json::from_slice(&content)
+            .map_err(|e| SecretError::SerializationError(format!("Invalid secrets file: {}", e)))?;
+        if file.version != FORMAT_VERSION {
+            return Err(SecretError::NotSupported(format!(
+                "Secrets file format version {}",
+                file.version
+            )));
+        }
+
+        let salt = file.salt.as_deref().map(decode).transpose()?;
+        let nonce = decode(&file.nonce)?;
+        if nonce.len() != NONCE_LEN {
+            return Err(SecretError::SerializationError(
+                "Invalid secrets file: malformed nonce".to_string(),
+            ));
+        }
+        let ciphertext = decode(&file.ciphertext)?;
+
+        let plaintext = self
+            .cipher(salt.as_deref())?
+            .decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref())
+            .map_err(|_| {
+                SecretError::AuthenticationFailed(
+                    "Failed to decrypt secrets file: wrong key or corrupted file".to_string(),
+                )
+            })?;
+
+        serde_json::from_slice(&plaintext).map_err(|e| SecretError::SerializationError(e.to_string()))
+    }
+
+    /// Encrypt and write the store with a fresh salt and nonce.
+    async fn save(&self, entries: &SecretEntries) -> Result<()> {
+        let plaintext =
+            serde_json::to_vec(entries).map_err(|e| SecretError::SerializationError(e.to_string()))?;
+
+        let salt = match self.key_source {
+            KeySource::Passphrase(_) => {
+                let mut salt = [0u8; SALT_LEN];
+                OsRng.fill_bytes(&mut salt);
+                Some(salt)
+            }
+            KeySource::Key(_) => None,
+        };
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher(salt.as_ref().map(|s| s.as_slice()))?
+            .encrypt(&nonce, plaintext.as_ref())
+            .map_err(|_| SecretError::Other("Failed to encrypt secrets file".to_string()))?;
+
+        let file = EncryptedFile {
+            version: FORMAT_VERSION,
+            salt: salt.map(|s| BASE64.encode(s)),
+            nonce: BASE64.encode(nonce),
+            ciphertext: BASE64.encode(ciphertext),
+        };
+        let content = serde_json::to_vec_pretty(&file)
+            .map_err(|e| SecretError::SerializationError(e.to_string()))?;
+
+        // Write to a temporary file first so a crash never truncates the store
+        let tmp_path = self.path.with_extension("tmp");
+        write_private(&tmp_path, &content)?;
+        tokio::fs::rename(&tmp_path, &self.path).await.map_err(|e| {
+            SecretError::BackendUnavailable(format!("Failed to write {}: {}", self.path.display(), e))
+        })
+    }
+}
+
+fn decode(value: &str) -> Result<Vec<u8>> {
+    BASE64
+        .decode(value)
+        .map_err(|e| SecretError::SerializationError(format!("Invalid secrets file encoding: {}", e)))
+}
+
+/// Write a file readable only by the current user.
+fn write_private(path: &Path, content: &[u8]) -> Result<()> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    let mut file = options.open(path).map_err(|e| {
+        SecretError::BackendUnavailable(format!("Failed to write {}: {}", path.display(), e))
+    })?;
+    std::io::Write::write_all(&mut file, content)
+        .and_then(|_| file.sync_all())
+        .map_err(|e| SecretError::BackendUnavailable(format!("Failed to write {}: {}", path.display(), e)))
+}
+
+#[async_trait]
+impl SecretStore for FileSecretStore {
+    async fn get_secret(&self, key: &str) -> Result<Secret> {
+        debug!("Retrieving secret from file: {}", key);
+
+        let entries = self.load().await?;
+        let entry = entries
+            .get(key)
+            .ok_or_else(|| SecretError::NotFound(key.to_string()))?;
+        let current = entry
+            .current()
+            .ok_or_else(|| SecretError::NotFound(key.to_string()))?;
+
+        Ok(entry.to_secret(key, current))
+    }
+
+    async fn put_secret(
+        &self,
+        key: &str,
+        value: &str,
+        metadata: Option<SecretMetadata>,
+    ) -> Result<()> {
+        debug!("Storing secret in file: {}", key);
+
+        let _guard = self.lock.lock().await;
+        let mut entries = self.load().await?;
+        let entry = entries.entry(key.to_string()).or_insert_with(|| SecretEntry {
+            description: None,
+            tags: HashMap::new(),
+            versions: Vec::new(),
+        });
+
+        if let Some(metadata) = metadata {
+            entry.description = metadata.description;
+            entry.tags = metadata.tags;
+        }
+        let version = entry.current().map_or(1, |v| v.version + 1);
+        entry.versions.push(StoredVersion {
+            version,
+            value: value.to_string(),
+            created_at: Utc::now(),
+        });
+
+        self.save(&entries).await?;
+        info!("Successfully stored secret: {} (version {})", key, version);
+        Ok(())
+    }
+
+    async fn delete_secret(&self, key: &str) -> Result<()> {
+        debug!("Deleting secret from file: {}", key);
+
+        let _guard = self.lock.lock().await;
+        let mut entries = self.load().await?;
+        if entries.remove(key).is_none() {
+            return Err(SecretError::NotFound(key.to_string()));
+        }
+
+        self.save(&entries).await?;
+        info!("Successfully deleted secret: {}", key);
+        Ok(())
+    }
+
+    async fn list_secrets(&self, prefix: &str) -> Result<Vec<String>> {
+        debug!("Listing secrets with prefix: {}", prefix);
+
+        let entries = self.load().await?;
+        Ok(entries
+            .keys()
+            .filter(|key| key.starts_with(prefix))
+            .cloned()
+            .collect())
+    }
+
+    async fn rotate_secret(&self, key: &str) -> Result<Secret> {
+        debug!("Rotating secret: {}", key);
+
+        // Rotation adds a new version; the new value must be generated externally
+        let current = self.get_secret(key).await?;
+        warn!(
+            "Secret rotation for {} - new value should be generated externally",
+            key
+        );
+        self.put_secret(key, &current.value, None).await?;
+
+        self.get_secret(key).await
+    }
+
+    async fn health_check(&self) -> Result<()> {
+        debug!("Performing file secret store health check");
+        self.load().await?;
+        Ok(())
+    }
+
+    async fn get_secret_versions(&self, key: &str) -> Result<Vec<SecretVersion>> {
+        debug!("Retrieving versions for secret: {}", key);
+
+        let entries = self.load().await?;
+        let entry = entries
+            .get(key)
+            .ok_or_else(|| SecretError::NotFound(key.to_string()))?;
+        let current = entry.current().map(|v| v.version);
+
+        Ok(entry
+            .versions
+            .iter()
+            .rev()
+            .map(|v| {
+                let version = SecretVersion::new(v.version.to_string(), v.created_at);
+                if Some(v.version) == current {
+                    version.mark_current()
+                } else {
+                    version
+                }
+            })
+            .collect())
+    }
+
+    async fn get_secret_version(&self, key: &str, version: &str) -> Result<Secret> {
+        debug!("Retrieving secret {} version {}", key, version);
+
+        let number: u64 = version.parse().map_err(|_| {
+            SecretError::InvalidSecret(format!("Invalid version number: {}", version))
+        })?;
+        let entries = self.load().await?;
+        let entry = entries
+            .get(key)
+            .ok_or_else(|| SecretError::NotFound(key.to_string()))?;
+        let stored = entry
+            .versions
+            .iter()
+            .find(|v| v.version == number)
+            .ok_or_else(|| SecretError::NotFound(format!("{} version {}", key, version)))?;
+
+        Ok(entry.to_secret(key, stored))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_passphrase_round_trip_and_versions() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("secrets.enc");
+        let store = FileSecretStore::with_passphrase(&path, "passphrase".to_string());
+
+        assert!(store.list_secrets("").await.unwrap().is_empty());
+        store
+            .put_secret(
+                "openai/api_key",
+                "sk-first",
+                Some(SecretMetadata::new().add_tag("team".to_string(), "ml".to_string())),
+            )
+            .await
+            .unwrap();
+        store.put_secret("openai/api_key", "sk-second", None).await.unwrap();
+
+        // The file never contains plaintext
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(!content.contains("sk-second") && !content.contains("openai"));
+
+        let reopened = FileSecretStore::with_passphrase(&path, "passphrase".to_string());
+        let secret = reopened.get_secret("openai/api_key").await.unwrap();
+        assert_eq!(secret.value, "sk-second");
+        assert_eq!(secret.version.as_deref(), Some("2"));
+        assert_eq!(secret.metadata["team"], "ml");
+
+        let versions = reopened.get_secret_versions("openai/api_key").await.unwrap();
+        assert_eq!(versions.len(), 2);
+        assert!(versions[0].is_current && !versions[1].is_current);
+        let first = reopened.get_secret_version("openai/api_key", "1").await.unwrap();
+        assert_eq!(first.value, "sk-first");
+
+        assert_eq!(
+            reopened.list_secrets("openai/").await.unwrap(),
+            vec!["openai/api_key".to_string()]
+        );
+        reopened.delete_secret("openai/api_key").await.unwrap();
+        assert!(matches!(
+            reopened.get_secret("openai/api_key").await,
+            Err(SecretError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_wrong_passphrase_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("secrets.enc");
+        FileSecretStore::with_passphrase(&path, "right".to_string())
+            .put_secret("key", "value", None)
+            .await
+            .unwrap();
+
+        let store = FileSecretStore::with_passphrase(&path, "wrong".to_string());
+        assert!(matches!(
+            store.get_secret("key").await,
+            Err(SecretError::AuthenticationFailed(_))
+        ));
+        assert!(store.health_check().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_truncated_nonce_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("secrets.enc");
+        let store = FileSecretStore::with_passphrase(&path, "passphrase".to_string());
+        store.put_secret("key", "value", None).await.unwrap();
+
+        let mut file: serde_json::Value = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        file["nonce"] = BASE64.encode([0u8; 5]).into();
+        std::fs::write(&path, file.to_string()).unwrap();
+        assert!(matches!(
+            store.get_secret("key").await,
+            Err(SecretError::SerializationError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_key_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let key_path = dir.path().join("secrets.key");
+        FileSecretStore::generate_key_file(&key_path).unwrap();
+
+        let path = dir.path().join("secrets.enc");
+        let store = FileSecretStore::with_key_file(&path, &key_path).unwrap();
+        store.put_secret("db/password", "hunter2", None).await.unwrap();
+        store.rotate_secret("db/password").await.unwrap();
+
+        let reopened = FileSecretStore::with_key_file(&path, &key_path).unwrap();
+        let secret = reopened.get_secret("db/password").await.unwrap();
+        assert_eq!(secret.value, "hunter2");
+        assert_eq!(secret.version.as_deref(), Some("2"));
+
+        std::fs::write(&key_path, "not a key").unwrap();
+        assert!(FileSecretStore::with_key_file(&path, &key_path).is_err());
+    }
+}
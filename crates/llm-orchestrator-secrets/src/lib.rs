@@ -6,14 +6,19 @@
 //! This crate provides secure secret storage and retrieval with support for:
 //! - HashiCorp Vault (KV v2)
 //! - AWS Secrets Manager
+//! - Google Cloud Secret Manager
+//! - Azure Key Vault
+//! - Encrypted local files
 //! - Environment variables (fallback)
 //! - In-memory caching with TTL
+//! - Redaction of secret values in logs and persisted data
 //!
 //! # Features
 //!
-//! - **Multiple backends**: Vault, AWS Secrets Manager, or environment variables
+//! - **Multiple backends**: Vault, AWS Secrets Manager, GCP Secret Manager, Azure Key Vault, encrypted files, or environment variables
 //! - **Automatic caching**: Optional TTL-based caching to reduce backend calls
-//! - **Secret rotation**: Support for rotating secrets without downtime
+//! - **Secret rotation**: Support for rotating secrets without downtime, with background
+//!   refresh and change subscriptions so long-running workers pick up new versions
 //! - **Version management**: Access historical versions of secrets (where supported)
 //! - **Security**: Zero secrets in logs, secure token handling
 //!
@@ -61,6 +66,45 @@
 //! # }
 //! ```
 //!
+//! ## Using Google Cloud Secret Manager
+//!
+//! ```no_run
+//! use llm_orchestrator_secrets::{GcpSecretStore, SecretStore};
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! // Uses Application Default Credentials and GOOGLE_CLOUD_PROJECT
+//! let store = GcpSecretStore::from_env().await?;
+//! let secret = store.get_secret("prod/api/key").await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! ## Using Azure Key Vault
+//!
+//! ```no_run
+//! use llm_orchestrator_secrets::{AzureKeyVaultStore, SecretStore};
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! // Uses AZURE_KEYVAULT_URL and a service principal or managed identity
+//! let store = AzureKeyVaultStore::from_env()?;
+//! let secret = store.get_secret("prod/api/key").await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! ## Using an Encrypted File
+//!
+//! ```no_run
+//! use llm_orchestrator_secrets::{FileSecretStore, SecretStore};
+//!
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! FileSecretStore::generate_key_file("secrets.key")?;
+//! let store = FileSecretStore::with_key_file("secrets.enc", "secrets.key")?;
+//! store.put_secret("openai/api_key", "sk-...", None).await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
 //! ## Using the Builder with Caching
 //!
 //! ```no_run
@@ -90,21 +134,32 @@
 //!
 //! - **Caching**: Reduces backend calls from ~100ms to <1ms for cached secrets
 //! - **TTL**: Default 5 minutes balances freshness with performance
+//! - **Refresh**: Background refresh renews entries at 80% of the TTL so reads never block on expiry
 //! - **Cleanup**: Run `cleanup_expired()` periodically to prevent memory growth
 
 pub mod aws;
+pub mod azure;
 pub mod builder;
 pub mod cache;
 pub mod env;
+pub mod file;
+pub mod gcp;
 pub mod models;
+pub mod redaction;
 pub mod traits;
 pub mod vault;
 
 // Re-export main types for convenience
 pub use aws::AwsSecretStore;
-pub use builder::{AwsConfig, SecretManagerBuilder, SecretStoreType, VaultConfig};
-pub use cache::{CacheStats, SecretCache};
+pub use azure::AzureKeyVaultStore;
+pub use builder::{
+    AwsConfig, AzureConfig, FileConfig, FileKey, GcpConfig, SecretManagerBuilder, SecretStoreType, VaultConfig,
+};
+pub use cache::{CacheStats, SecretCache, SecretChangeCallback, SubscriptionId};
 pub use env::EnvSecretStore;
+pub use file::FileSecretStore;
+pub use gcp::GcpSecretStore;
 pub use models::{Secret, SecretMetadata, SecretVersion};
+pub use redaction::{Redactor, REDACTED};
 pub use traits::{Result, SecretError, SecretStore};
 pub use vault::VaultSecretStore;
@@ -28,7 +28,7 @@ use vaultrs::kv2;
 /// # Example
 ///
 /// ```no_run
-/// use llm_orchestrator_secrets::VaultSecretStore;
+/// use llm_orchestrator_secrets::{SecretStore, VaultSecretStore};
 ///
 /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
 /// let store = VaultSecretStore::new(
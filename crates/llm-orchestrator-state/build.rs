@@ -0,0 +1,7 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+fn main() {
+    // Re-embed migrations when they change
+    println!("cargo:rerun-if-changed=migrations");
+}
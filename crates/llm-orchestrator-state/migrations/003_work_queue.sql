@@ -0,0 +1,19 @@
+-- Work queue for distributed workflow execution
+
+CREATE TABLE IF NOT EXISTS work_queue (
+    id UUID PRIMARY KEY,
+    payload TEXT NOT NULL, -- JSON stored as TEXT
+    status VARCHAR(50) NOT NULL,
+    worker_id VARCHAR(255),
+    lease_expires_at BIGINT, -- Unix epoch milliseconds, portable across backends
+    attempts INTEGER NOT NULL DEFAULT 0,
+    enqueued_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    error TEXT
+);
+
+-- Index for claiming the oldest queued task
+CREATE INDEX IF NOT EXISTS idx_work_queue_status ON work_queue(status, enqueued_at);
+
+-- Index for finding expired leases
+CREATE INDEX IF NOT EXISTS idx_work_queue_lease ON work_queue(status, lease_expires_at);
@@ -0,0 +1,21 @@
+-- Dead-letter capture for steps that exhausted their retries
+
+CREATE TABLE IF NOT EXISTS dead_letters (
+    id UUID PRIMARY KEY,
+    run_id UUID NOT NULL,
+    workflow_name VARCHAR(255) NOT NULL,
+    step_id VARCHAR(255) NOT NULL,
+    step TEXT NOT NULL, -- Step definition as JSON
+    context TEXT NOT NULL, -- Inputs and outputs as JSON
+    request TEXT, -- Provider request payload as JSON
+    error TEXT NOT NULL,
+    attempts TEXT NOT NULL, -- Attempt history as JSON
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    resolved_at TIMESTAMP WITH TIME ZONE
+);
+
+-- Index for listing unresolved entries
+CREATE INDEX IF NOT EXISTS idx_dead_letters_resolved ON dead_letters(resolved_at, created_at);
+
+-- Index for finding entries of a run
+CREATE INDEX IF NOT EXISTS idx_dead_letters_run ON dead_letters(run_id);
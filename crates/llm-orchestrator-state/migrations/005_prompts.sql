@@ -0,0 +1,18 @@
+-- Named, versioned prompt templates
+
+CREATE TABLE IF NOT EXISTS prompt_versions (
+    name VARCHAR(255) NOT NULL,
+    version INTEGER NOT NULL,
+    template TEXT NOT NULL,
+    system TEXT,
+    description TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    PRIMARY KEY (name, version)
+);
+
+-- Version a prompt name resolves to when no version is given
+CREATE TABLE IF NOT EXISTS promoted_prompts (
+    name VARCHAR(255) PRIMARY KEY,
+    version INTEGER NOT NULL,
+    promoted_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
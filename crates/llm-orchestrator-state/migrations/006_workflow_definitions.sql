@@ -0,0 +1,15 @@
+-- Published workflow definitions, addressable by name and version
+
+CREATE TABLE IF NOT EXISTS workflow_definitions (
+    name VARCHAR(255) NOT NULL,
+    version VARCHAR(255) NOT NULL,
+    definition TEXT NOT NULL, -- Workflow YAML as published
+    checksum VARCHAR(64) NOT NULL, -- SHA-256 of the definition, hex encoded
+    created_by VARCHAR(255),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    deprecated_at TIMESTAMP WITH TIME ZONE,
+    PRIMARY KEY (name, version)
+);
+
+-- Index for resolving the latest version of a workflow
+CREATE INDEX IF NOT EXISTS idx_workflow_definitions_created ON workflow_definitions(name, created_at);
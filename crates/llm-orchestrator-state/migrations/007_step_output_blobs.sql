@@ -0,0 +1,14 @@
+-- Step outputs too large to store inline in step_states
+
+CREATE TABLE IF NOT EXISTS step_output_blobs (
+    blob_key VARCHAR(512) PRIMARY KEY,
+    workflow_state_id UUID NOT NULL,
+    step_id VARCHAR(255) NOT NULL,
+    data TEXT NOT NULL, -- Serialized outputs, encrypted when encryption is enabled
+    size_bytes BIGINT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    FOREIGN KEY (workflow_state_id) REFERENCES workflow_states(id) ON DELETE CASCADE
+);
+
+-- Index for removing a run's blobs
+CREATE INDEX IF NOT EXISTS idx_step_output_blobs_state ON step_output_blobs(workflow_state_id);
@@ -0,0 +1,6 @@
+-- Tenant each run belongs to; NULL for runs recorded without one
+
+ALTER TABLE workflow_states ADD COLUMN tenant_id VARCHAR(255);
+
+-- Index for listing a tenant's runs by start time
+CREATE INDEX IF NOT EXISTS idx_tenant_started_at ON workflow_states(tenant_id, started_at DESC);
@@ -0,0 +1,9 @@
+-- Memory kept across runs, per session and key
+
+CREATE TABLE IF NOT EXISTS session_memory (
+    session_id VARCHAR(255) NOT NULL,
+    memory_key VARCHAR(255) NOT NULL,
+    value TEXT NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    PRIMARY KEY (session_id, memory_key)
+);
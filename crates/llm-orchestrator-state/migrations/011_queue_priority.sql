@@ -0,0 +1,10 @@
+-- Priority class (0 high, 1 normal, 2 low) and tenant of queued runs
+
+ALTER TABLE work_queue ADD COLUMN priority INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE work_queue ADD COLUMN tenant_id VARCHAR(255);
+
+-- Index for claiming the oldest queued task of the highest priority
+CREATE INDEX IF NOT EXISTS idx_work_queue_priority ON work_queue(status, priority, enqueued_at);
+
+-- Index for counting each tenant's running tasks
+CREATE INDEX IF NOT EXISTS idx_work_queue_tenant ON work_queue(status, tenant_id);
@@ -0,0 +1,91 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Compression of archived run contexts.
+//!
+//! [`StateRetentionManager`](crate::StateRetentionManager) can compress the
+//! contexts of old finished runs in place. Like encrypted values, a compressed
+//! value is a JSON envelope, so it is read back transparently and rows that
+//! were never compressed stay readable:
+//!
+//! ```json
+//! {"$compressed": {"v": 1, "alg": "gzip", "data": "..."}}
+//! ```
+
+use crate::traits::{StateStoreError, StateStoreResult};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::{Read, Write};
+
+/// Field marking a compressed value.
+const ENVELOPE_FIELD: &str = "$compressed";
+
+/// Current envelope format version.
+const ENVELOPE_VERSION: u32 = 1;
+
+/// Compression algorithm of new envelopes.
+const ALGORITHM: &str = "gzip";
+
+/// Compressed value as stored in a JSON column.
+#[derive(Serialize, Deserialize)]
+struct Envelope {
+    v: u32,
+    alg: String,
+    /// Base64 of the compressed serialized value.
+    data: String,
+}
+
+/// Returns true if `value` is a compressed envelope.
+pub(crate) fn is_compressed(value: &Value) -> bool {
+    value.as_object().is_some_and(|o| o.len() == 1 && o.contains_key(ENVELOPE_FIELD))
+}
+
+/// `LIKE` pattern matching unencrypted compressed columns.
+pub(crate) fn compressed_pattern() -> String {
+    format!("{{\"{}\"%", ENVELOPE_FIELD)
+}
+
+/// Compress a value into an envelope.
+pub(crate) fn compress(value: &Value) -> StateStoreResult<Value> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(&serde_json::to_vec(value)?)
+        .and_then(|_| encoder.finish())
+        .map(|data| {
+            let envelope = Envelope {
+                v: ENVELOPE_VERSION,
+                alg: ALGORITHM.to_string(),
+                data: BASE64.encode(data),
+            };
+            serde_json::json!({ ENVELOPE_FIELD: envelope })
+        })
+        .map_err(|e| StateStoreError::Serialization(format!("Failed to compress value: {}", e)))
+}
+
+/// Decompress an envelope; values that are not compressed are returned unchanged.
+pub(crate) fn decompress(value: Value) -> StateStoreResult<Value> {
+    if !is_compressed(&value) {
+        return Ok(value);
+    }
+    let envelope: Envelope = serde_json::from_value(value[ENVELOPE_FIELD].clone())?;
+    if envelope.v != ENVELOPE_VERSION || envelope.alg != ALGORITHM {
+        return Err(StateStoreError::Serialization(format!(
+            "Unsupported compression envelope: version {}, algorithm {}",
+            envelope.v, envelope.alg
+        )));
+    }
+    let data = BASE64
+        .decode(&envelope.data)
+        .map_err(|e| StateStoreError::Serialization(format!("Invalid base64: {}", e)))?;
+
+    let mut json = Vec::new();
+    GzDecoder::new(data.as_slice())
+        .read_to_end(&mut json)
+        .map_err(|e| StateStoreError::Serialization(format!("Failed to decompress value: {}", e)))?;
+    Ok(serde_json::from_slice(&json)?)
+}
@@ -0,0 +1,300 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Envelope encryption of persisted workflow data.
+//!
+//! Run contexts, step outputs, and checkpoint snapshots often contain user
+//! documents and LLM outputs. When a store is given a [`StateEncryption`], each
+//! of these JSON values is encrypted with a fresh AES-256-GCM data key, and the
+//! data key is encrypted ("wrapped") with the master key. The column still holds
+//! JSON, so rows written before encryption was enabled stay readable:
+//!
+//! ```json
+//! {"$encrypted": {"v": 1, "kid": "state-key@3", "dek": "...", "nonce": "...", "ciphertext": "..."}}
+//! ```
+
+use crate::compression::decompress;
+use crate::traits::{StateStoreError, StateStoreResult};
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Length of the master key in bytes.
+pub const KEY_LEN: usize = 32;
+
+/// Length of an AES-GCM nonce in bytes.
+const NONCE_LEN: usize = 12;
+
+/// Field marking an encrypted value.
+const ENVELOPE_FIELD: &str = "$encrypted";
+
+/// Current envelope format version.
+const ENVELOPE_VERSION: u32 = 1;
+
+/// Encrypted value as stored in a JSON column.
+#[derive(Serialize, Deserialize)]
+struct Envelope {
+    v: u32,
+    /// ID of the master key that wrapped the data key.
+    kid: String,
+    /// Base64 nonce and wrapped data key.
+    dek: String,
+    /// Base64 nonce of the value ciphertext.
+    nonce: String,
+    /// Base64 ciphertext of the serialized value.
+    ciphertext: String,
+}
+
+/// Master keys used to encrypt persisted contexts, outputs, and snapshots.
+///
+/// New values are encrypted with the primary key; previous keys are kept so
+/// rows written before a key rotation can still be read.
+///
+/// # Example
+///
+/// ```no_run
+/// # use llm_orchestrator_state::{SqliteStateStore, StateEncryption};
+/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let encryption = StateEncryption::from_base64("state-key", &std::env::var("STATE_KEY")?)?;
+/// let store = SqliteStateStore::new("./workflows.db").await?.with_encryption(encryption);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct StateEncryption {
+    /// ID of the key new values are encrypted with.
+    key_id: String,
+    /// All known keys by ID, including the primary key.
+    keys: HashMap<String, Aes256Gcm>,
+}
+
+impl std::fmt::Debug for StateEncryption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut key_ids: Vec<_> = self.keys.keys().collect();
+        key_ids.sort();
+        f.debug_struct("StateEncryption")
+            .field("key_id", &self.key_id)
+            .field("key_ids", &key_ids)
+            .finish()
+    }
+}
+
+impl StateEncryption {
+    /// Create an encryptor from a 32-byte master key.
+    pub fn new(key_id: impl Into<String>, key: &[u8]) -> StateStoreResult<Self> {
+        let key_id = key_id.into();
+        let cipher = cipher(&key_id, key)?;
+        Ok(Self {
+            keys: HashMap::from([(key_id.clone(), cipher)]),
+            key_id,
+        })
+    }
+
+    /// Create an encryptor from a base64-encoded 32-byte master key.
+    pub fn from_base64(key_id: impl Into<String>, key: &str) -> StateStoreResult<Self> {
+        Self::new(key_id, &decode(key.trim())?)
+    }
+
+    /// Keep a previous master key for decrypting values written before a rotation.
+    pub fn with_previous_key(mut self, key_id: impl Into<String>, key: &[u8]) -> StateStoreResult<Self> {
+        let key_id = key_id.into();
+        let cipher = cipher(&key_id, key)?;
+        self.keys.entry(key_id).or_insert(cipher);
+        Ok(self)
+    }
+
+    /// Load the master key from a secret holding a base64-encoded 32-byte key.
+    ///
+    /// Older versions of the secret are kept for decryption where the backend
+    /// supports versions, so rotating the secret does not strand existing rows.
+    #[cfg(feature = "secrets")]
+    pub async fn from_secret_store(
+        store: &dyn llm_orchestrator_secrets::SecretStore,
+        name: &str,
+    ) -> StateStoreResult<Self> {
+        let key_id = |version: Option<&str>| match version {
+            Some(version) => format!("{}@{}", name, version),
+            None => name.to_string(),
+        };
+        let secret = store
+            .get_secret(name)
+            .await
+            .map_err(|e| StateStoreError::Configuration(format!("Failed to load state encryption key: {}", e)))?;
+        let mut encryption = Self::from_base64(key_id(secret.version.as_deref()), &secret.value)?;
+
+        // Backends without versioning only provide the current key
+        let versions = store.get_secret_versions(name).await.unwrap_or_default();
+        for version in versions.iter().filter(|v| !v.is_current && !v.is_deleted) {
+            match store.get_secret_version(name, &version.version).await {
+                Ok(previous) => {
+                    encryption =
+                        encryption.with_previous_key(key_id(Some(&version.version)), &decode(previous.value.trim())?)?;
+                }
+                Err(e) => tracing::warn!("Skipping state encryption key {}: {}", key_id(Some(&version.version)), e),
+            }
+        }
+        Ok(encryption)
+    }
+
+    /// ID of the key new values are encrypted with.
+    pub fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    /// Returns true if `value` is an encrypted envelope.
+    pub fn is_encrypted(value: &Value) -> bool {
+        value.as_object().is_some_and(|o| o.len() == 1 && o.contains_key(ENVELOPE_FIELD))
+    }
+
+    /// Encrypt a value under a fresh data key.
+    pub fn encrypt(&self, value: &Value) -> StateStoreResult<Value> {
+        let plaintext = serde_json::to_vec(value)?;
+
+        let data_key = Aes256Gcm::generate_key(&mut OsRng);
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = Aes256Gcm::new(&data_key)
+            .encrypt(&nonce, plaintext.as_ref())
+            .map_err(|_| StateStoreError::Encryption("Failed to encrypt value".to_string()))?;
+
+        let dek_nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let wrapped = self.keys[&self.key_id]
+            .encrypt(&dek_nonce, data_key.as_slice())
+            .map_err(|_| StateStoreError::Encryption("Failed to wrap data key".to_string()))?;
+
+        let envelope = Envelope {
+            v: ENVELOPE_VERSION,
+            kid: self.key_id.clone(),
+            dek: BASE64.encode([dek_nonce.as_slice(), &wrapped].concat()),
+            nonce: BASE64.encode(nonce),
+            ciphertext: BASE64.encode(ciphertext),
+        };
+        Ok(serde_json::json!({ ENVELOPE_FIELD: envelope }))
+    }
+
+    /// Decrypt an envelope; values that are not encrypted are returned unchanged.
+    pub fn decrypt(&self, value: Value) -> StateStoreResult<Value> {
+        if !Self::is_encrypted(&value) {
+            return Ok(value);
+        }
+        let envelope: Envelope = serde_json::from_value(value[ENVELOPE_FIELD].clone())?;
+        if envelope.v != ENVELOPE_VERSION {
+            return Err(StateStoreError::Encryption(format!(
+                "Unsupported envelope version {}",
+                envelope.v
+            )));
+        }
+        let master = self.keys.get(&envelope.kid).ok_or_else(|| {
+            StateStoreError::Encryption(format!("Unknown state encryption key: {}", envelope.kid))
+        })?;
+
+        let dek = decode(&envelope.dek)?;
+        if dek.len() <= NONCE_LEN {
+            return Err(StateStoreError::Encryption("Malformed data key".to_string()));
+        }
+        let (dek_nonce, wrapped) = dek.split_at(NONCE_LEN);
+        let data_key = master
+            .decrypt(Nonce::from_slice(dek_nonce), wrapped)
+            .map_err(|_| StateStoreError::Encryption(format!("Failed to unwrap data key with {}", envelope.kid)))?;
+
+        let nonce = decode(&envelope.nonce)?;
+        if nonce.len() != NONCE_LEN {
+            return Err(StateStoreError::Encryption("Malformed nonce".to_string()));
+        }
+        let plaintext = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&data_key))
+            .decrypt(Nonce::from_slice(&nonce), decode(&envelope.ciphertext)?.as_ref())
+            .map_err(|_| StateStoreError::Encryption("Failed to decrypt value: corrupted ciphertext".to_string()))?;
+
+        Ok(serde_json::from_slice(&plaintext)?)
+    }
+}
+
+fn cipher(key_id: &str, key: &[u8]) -> StateStoreResult<Aes256Gcm> {
+    if key.len() != KEY_LEN {
+        return Err(StateStoreError::Configuration(format!(
+            "State encryption key {} must be {} bytes, got {}",
+            key_id,
+            KEY_LEN,
+            key.len()
+        )));
+    }
+    Ok(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key)))
+}
+
+fn decode(value: &str) -> StateStoreResult<Vec<u8>> {
+    BASE64
+        .decode(value)
+        .map_err(|e| StateStoreError::Encryption(format!("Invalid base64: {}", e)))
+}
+
+/// Serialize a JSON column value, encrypting it if encryption is enabled.
+pub(crate) fn encode_column(encryption: Option<&StateEncryption>, value: &Value) -> StateStoreResult<String> {
+    match encryption {
+        Some(encryption) => Ok(serde_json::to_string(&encryption.encrypt(value)?)?),
+        None => Ok(serde_json::to_string(value)?),
+    }
+}
+
+/// Parse a JSON column value, decrypting and decompressing it as needed.
+pub(crate) fn decode_column(encryption: Option<&StateEncryption>, text: &str) -> StateStoreResult<Value> {
+    decompress(decrypt_column(encryption, text)?)
+}
+
+/// Parse a JSON column value, decrypting it if it is encrypted.
+pub(crate) fn decrypt_column(encryption: Option<&StateEncryption>, text: &str) -> StateStoreResult<Value> {
+    let value: Value = serde_json::from_str(text)?;
+    match encryption {
+        Some(encryption) => encryption.decrypt(value),
+        None if StateEncryption::is_encrypted(&value) => Err(StateStoreError::Encryption(
+            "Value is encrypted but the store has no encryption key".to_string(),
+        )),
+        None => Ok(value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_round_trip_and_rotation() {
+        let old = StateEncryption::new("k1", &[1u8; KEY_LEN]).unwrap();
+        let value = json!({"inputs": {"document": "quarterly report"}});
+
+        let encrypted = old.encrypt(&value).unwrap();
+        assert!(StateEncryption::is_encrypted(&encrypted));
+        assert!(!encrypted.to_string().contains("quarterly"));
+        assert_eq!(old.decrypt(encrypted.clone()).unwrap(), value);
+
+        // Plain values pass through
+        assert_eq!(old.decrypt(value.clone()).unwrap(), value);
+
+        // A rotated keyring still reads values written with the old key
+        let rotated = StateEncryption::new("k2", &[2u8; KEY_LEN])
+            .unwrap()
+            .with_previous_key("k1", &[1u8; KEY_LEN])
+            .unwrap();
+        assert_eq!(rotated.decrypt(encrypted.clone()).unwrap(), value);
+        assert_eq!(rotated.encrypt(&value).unwrap()[ENVELOPE_FIELD]["kid"], "k2");
+
+        let other = StateEncryption::new("k1", &[3u8; KEY_LEN]).unwrap();
+        assert!(matches!(other.decrypt(encrypted.clone()), Err(StateStoreError::Encryption(_))));
+        assert!(matches!(
+            decode_column(None, &encrypted.to_string()),
+            Err(StateStoreError::Encryption(_))
+        ));
+    }
+
+    #[test]
+    fn test_rejects_bad_keys() {
+        assert!(matches!(
+            StateEncryption::new("short", &[0u8; 16]),
+            Err(StateStoreError::Configuration(_))
+        ));
+        assert!(StateEncryption::from_base64("b64", &BASE64.encode([7u8; KEY_LEN])).is_ok());
+    }
+}
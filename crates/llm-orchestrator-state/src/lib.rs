@@ -6,8 +6,12 @@
 //! This crate provides database-backed state management for workflows with support for:
 //! - Workflow state persistence (PostgreSQL and SQLite)
 //! - Automatic checkpointing for recovery
+//! - Versioned schema migrations tracked in the database
 //! - Connection pooling and transactions
 //! - Workflow resumption after crashes
+//! - Offloading of large step outputs to a blob table or object store
+//! - Optional envelope encryption of contexts, outputs, and snapshots at rest
+//! - Retention policies that purge old finished runs and compress their contexts
 //!
 //! # Examples
 //!
@@ -55,19 +59,37 @@
 //! # }
 //! ```
 
+mod compression;
+pub mod encryption;
+pub mod migrations;
 pub mod models;
+pub mod offload;
 pub mod postgres;
+pub mod retention;
 pub mod sqlite;
+pub mod tenant;
 pub mod traits;
 
 #[cfg(test)]
 mod tests;
 
 // Re-export commonly used types
-pub use models::{Checkpoint, StepState, StepStatus, WorkflowState, WorkflowStatus};
+pub use encryption::StateEncryption;
+pub use migrations::{MigrationStatus, MIGRATOR};
+pub use models::{
+    Checkpoint, DeadLetter, DeadLetterAttempt, ModelUsage, PromptVersion, QueueTask, QueueTaskStatus, RunPage,
+    RunPriority, RunQuery, RunSortField, RunSummary, StepState, StepStatus, UsageSummary, WorkflowDefinition,
+    WorkflowState, WorkflowStatus,
+};
+pub use offload::{BlobStore, FileBlobStore, OutputOffload, DEFAULT_OFFLOAD_THRESHOLD};
 pub use postgres::PostgresStateStore;
+pub use retention::{RetentionPolicy, RetentionReport, StateRetentionManager};
 pub use sqlite::SqliteStateStore;
-pub use traits::{StateStore, StateStoreError, StateStoreResult};
+pub use tenant::TenantStateStore;
+pub use traits::{
+    DeadLetterQueue, PromptVersionStore, RetentionStore, SchemaMigrations, SessionMemoryStore, StateStore,
+    StateStoreError, StateStoreResult, UsageStore, WorkQueue, WorkflowDefinitionStore,
+};
 
 /// Library version.
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
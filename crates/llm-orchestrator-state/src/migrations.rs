@@ -0,0 +1,59 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Versioned schema migrations shared by the PostgreSQL and SQLite stores.
+//!
+//! Migrations live in `migrations/` as `<version>_<description>.sql` and are
+//! embedded at compile time. Applied versions are recorded, with a checksum,
+//! in the `_sqlx_migrations` table, so each migration runs exactly once and
+//! editing an applied migration is reported instead of silently re-run.
+
+use crate::traits::{StateStoreError, StateStoreResult};
+use sqlx::migrate::{AppliedMigration, MigrateError, Migrator};
+use std::collections::HashMap;
+
+/// Migrations bundled with this crate.
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+/// Name of the table recording applied migrations.
+pub(crate) const MIGRATIONS_TABLE: &str = "_sqlx_migrations";
+
+/// A bundled migration and whether it has been applied to the database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationStatus {
+    /// Migration version, taken from the file name prefix.
+    pub version: i64,
+
+    /// Migration description, taken from the rest of the file name.
+    pub description: String,
+
+    /// Whether the migration has been applied.
+    pub applied: bool,
+}
+
+/// Match bundled migrations against the ones recorded in the database.
+pub(crate) fn migration_status(applied: &[AppliedMigration]) -> StateStoreResult<Vec<MigrationStatus>> {
+    let applied: HashMap<i64, &AppliedMigration> = applied.iter().map(|m| (m.version, m)).collect();
+
+    MIGRATOR
+        .iter()
+        .filter(|m| m.migration_type.is_up_migration())
+        .map(|m| {
+            if let Some(recorded) = applied.get(&m.version) {
+                if recorded.checksum != m.checksum {
+                    return Err(migration_error(MigrateError::VersionMismatch(m.version)));
+                }
+            }
+            Ok(MigrationStatus {
+                version: m.version,
+                description: m.description.to_string(),
+                applied: applied.contains_key(&m.version),
+            })
+        })
+        .collect()
+}
+
+/// Convert a migration failure into a store error.
+pub(crate) fn migration_error(err: MigrateError) -> StateStoreError {
+    StateStoreError::Database(format!("Migration failed: {}", err))
+}
@@ -23,6 +23,8 @@ pub enum WorkflowStatus {
     Completed,
     /// Workflow failed with an error.
     Failed,
+    /// Workflow was cancelled before finishing.
+    Cancelled,
 }
 
 impl std::fmt::Display for WorkflowStatus {
@@ -33,6 +35,7 @@ impl std::fmt::Display for WorkflowStatus {
             Self::Paused => write!(f, "paused"),
             Self::Completed => write!(f, "completed"),
             Self::Failed => write!(f, "failed"),
+            Self::Cancelled => write!(f, "cancelled"),
         }
     }
 }
@@ -47,6 +50,7 @@ impl std::str::FromStr for WorkflowStatus {
             "paused" => Ok(Self::Paused),
             "completed" => Ok(Self::Completed),
             "failed" => Ok(Self::Failed),
+            "cancelled" => Ok(Self::Cancelled),
             _ => Err(format!("Invalid workflow status: {}", s)),
         }
     }
@@ -66,6 +70,8 @@ pub enum StepStatus {
     Failed,
     /// Step was skipped.
     Skipped,
+    /// Step was cancelled before finishing.
+    Cancelled,
 }
 
 impl std::fmt::Display for StepStatus {
@@ -76,6 +82,7 @@ impl std::fmt::Display for StepStatus {
             Self::Completed => write!(f, "completed"),
             Self::Failed => write!(f, "failed"),
             Self::Skipped => write!(f, "skipped"),
+            Self::Cancelled => write!(f, "cancelled"),
         }
     }
 }
@@ -90,6 +97,7 @@ impl std::str::FromStr for StepStatus {
             "completed" => Ok(Self::Completed),
             "failed" => Ok(Self::Failed),
             "skipped" => Ok(Self::Skipped),
+            "cancelled" => Ok(Self::Cancelled),
             _ => Err(format!("Invalid step status: {}", s)),
         }
     }
@@ -108,6 +116,9 @@ pub struct WorkflowState {
     pub status: WorkflowStatus,
     /// User ID who initiated the workflow.
     pub user_id: Option<String>,
+    /// Tenant the run belongs to.
+    #[serde(default)]
+    pub tenant_id: Option<String>,
     /// Timestamp when workflow started.
     pub started_at: DateTime<Utc>,
     /// Timestamp when workflow was last updated.
@@ -121,6 +132,9 @@ pub struct WorkflowState {
     /// Individual step states.
     #[serde(default)]
     pub steps: HashMap<String, StepState>,
+    /// Requests and tokens per provider and model.
+    #[serde(default)]
+    pub usage: Vec<ModelUsage>,
 }
 
 impl WorkflowState {
@@ -138,15 +152,23 @@ impl WorkflowState {
             workflow_name: workflow_name.into(),
             status: WorkflowStatus::Pending,
             user_id,
+            tenant_id: None,
             started_at: now,
             updated_at: now,
             completed_at: None,
             context,
             error: None,
             steps: HashMap::new(),
+            usage: Vec::new(),
         }
     }
 
+    /// Assign the run to a tenant.
+    pub fn with_tenant_id(mut self, tenant_id: impl Into<String>) -> Self {
+        self.tenant_id = Some(tenant_id.into());
+        self
+    }
+
     /// Mark workflow as running.
     pub fn mark_running(&mut self) {
         self.status = WorkflowStatus::Running;
@@ -176,6 +198,233 @@ impl WorkflowState {
     }
 }
 
+/// Summary of a workflow run, without its context and step states.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunSummary {
+    /// Unique identifier of the run's state record.
+    pub id: Uuid,
+    /// Workflow ID.
+    pub workflow_id: String,
+    /// Workflow name.
+    pub workflow_name: String,
+    /// Execution status.
+    pub status: WorkflowStatus,
+    /// User ID who initiated the workflow.
+    pub user_id: Option<String>,
+    /// Tenant the run belongs to.
+    #[serde(default)]
+    pub tenant_id: Option<String>,
+    /// Timestamp when the run started.
+    pub started_at: DateTime<Utc>,
+    /// Timestamp when the run was last updated.
+    pub updated_at: DateTime<Utc>,
+    /// Timestamp when the run finished (if finished).
+    pub completed_at: Option<DateTime<Utc>>,
+    /// Error message if failed.
+    pub error: Option<String>,
+}
+
+impl RunSummary {
+    /// Wall-clock duration of a finished run.
+    pub fn duration(&self) -> Option<chrono::Duration> {
+        self.completed_at.map(|completed_at| completed_at - self.started_at)
+    }
+}
+
+/// Column run history is sorted by.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunSortField {
+    /// When the run started.
+    #[default]
+    StartedAt,
+    /// When the run was last updated.
+    UpdatedAt,
+    /// When the run finished.
+    CompletedAt,
+    /// Workflow name.
+    WorkflowName,
+}
+
+impl RunSortField {
+    /// Name of the `workflow_states` column.
+    pub fn as_column(&self) -> &'static str {
+        match self {
+            Self::StartedAt => "started_at",
+            Self::UpdatedAt => "updated_at",
+            Self::CompletedAt => "completed_at",
+            Self::WorkflowName => "workflow_name",
+        }
+    }
+}
+
+impl std::str::FromStr for RunSortField {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "started_at" | "started" => Ok(Self::StartedAt),
+            "updated_at" | "updated" => Ok(Self::UpdatedAt),
+            "completed_at" | "completed" => Ok(Self::CompletedAt),
+            "workflow_name" | "name" => Ok(Self::WorkflowName),
+            _ => Err(format!("Invalid run sort field: {}", s)),
+        }
+    }
+}
+
+/// Filters, sorting and pagination for run history.
+///
+/// Matches every run by default, returning the 50 most recently started.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunQuery {
+    /// Only runs of this workflow.
+    pub workflow_name: Option<String>,
+    /// Only runs with this status.
+    pub status: Option<WorkflowStatus>,
+    /// Only runs started by this user.
+    pub user_id: Option<String>,
+    /// Only runs of this tenant.
+    #[serde(default)]
+    pub tenant_id: Option<String>,
+    /// Only runs started at or after this time.
+    pub started_after: Option<DateTime<Utc>>,
+    /// Only runs started at or before this time.
+    pub started_before: Option<DateTime<Utc>>,
+    /// Column to sort by.
+    pub sort_by: RunSortField,
+    /// Sort in ascending instead of descending order.
+    pub ascending: bool,
+    /// Maximum number of runs per page.
+    pub limit: usize,
+    /// Number of matching runs to skip.
+    pub offset: usize,
+}
+
+impl Default for RunQuery {
+    fn default() -> Self {
+        Self {
+            workflow_name: None,
+            status: None,
+            user_id: None,
+            tenant_id: None,
+            started_after: None,
+            started_before: None,
+            sort_by: RunSortField::default(),
+            ascending: false,
+            limit: 50,
+            offset: 0,
+        }
+    }
+}
+
+impl RunQuery {
+    /// Create a query matching every run.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only runs of one workflow.
+    pub fn workflow_name(mut self, workflow_name: impl Into<String>) -> Self {
+        self.workflow_name = Some(workflow_name.into());
+        self
+    }
+
+    /// Only runs with one status.
+    pub fn status(mut self, status: WorkflowStatus) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    /// Only runs started by one user.
+    pub fn user_id(mut self, user_id: impl Into<String>) -> Self {
+        self.user_id = Some(user_id.into());
+        self
+    }
+
+    /// Only runs of one tenant.
+    pub fn tenant_id(mut self, tenant_id: impl Into<String>) -> Self {
+        self.tenant_id = Some(tenant_id.into());
+        self
+    }
+
+    /// Only runs started at or after `start`.
+    pub fn since(mut self, start: DateTime<Utc>) -> Self {
+        self.started_after = Some(start);
+        self
+    }
+
+    /// Only runs started at or before `end`.
+    pub fn until(mut self, end: DateTime<Utc>) -> Self {
+        self.started_before = Some(end);
+        self
+    }
+
+    /// Sort by `field`, newest or largest first unless `ascending`.
+    pub fn sort_by(mut self, field: RunSortField, ascending: bool) -> Self {
+        self.sort_by = field;
+        self.ascending = ascending;
+        self
+    }
+
+    /// Select a page of `page_size` runs; pages are numbered from 1.
+    pub fn page(mut self, page: usize, page_size: usize) -> Self {
+        self.limit = page_size;
+        self.offset = page.saturating_sub(1) * page_size;
+        self
+    }
+}
+
+/// One page of run history.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunPage {
+    /// Matching runs on this page.
+    pub runs: Vec<RunSummary>,
+    /// Total number of matching runs across all pages.
+    pub total: u64,
+    /// Offset of the first run on this page.
+    pub offset: usize,
+    /// Maximum number of runs per page.
+    pub limit: usize,
+}
+
+impl RunPage {
+    /// Check if more matching runs follow this page.
+    pub fn has_more(&self) -> bool {
+        ((self.offset + self.runs.len()) as u64) < self.total
+    }
+}
+
+/// Requests and tokens of one provider and model.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModelUsage {
+    /// Provider name.
+    pub provider: String,
+    /// Model name.
+    pub model: String,
+    /// Requests sent, including failed ones.
+    pub requests: u64,
+    /// Requests that returned an error.
+    pub failed_requests: u64,
+    /// Prompt tokens.
+    pub input_tokens: u64,
+    /// Completion tokens.
+    pub output_tokens: u64,
+    /// Total tokens.
+    pub total_tokens: u64,
+    /// Cost in US dollars.
+    pub cost_usd: f64,
+}
+
+/// Usage of one provider and model summed over the runs matching a query.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UsageSummary {
+    /// Number of runs that used the model.
+    pub runs: u64,
+    /// Summed usage.
+    #[serde(flatten)]
+    pub usage: ModelUsage,
+}
+
 /// Step execution state.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StepState {
@@ -337,3 +586,317 @@ mod tests {
         assert_eq!(checkpoint.snapshot, json!({"state": "data"}));
     }
 }
+
+/// Work queue task status.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QueueTaskStatus {
+    /// Task is waiting for a worker.
+    Queued,
+    /// Task is leased to a worker.
+    Running,
+    /// Task finished.
+    Completed,
+    /// Task could not be executed.
+    Failed,
+    /// Task was cancelled.
+    Cancelled,
+}
+
+impl std::fmt::Display for QueueTaskStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Queued => write!(f, "queued"),
+            Self::Running => write!(f, "running"),
+            Self::Completed => write!(f, "completed"),
+            Self::Failed => write!(f, "failed"),
+            Self::Cancelled => write!(f, "cancelled"),
+        }
+    }
+}
+
+impl std::str::FromStr for QueueTaskStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "queued" => Ok(Self::Queued),
+            "running" => Ok(Self::Running),
+            "completed" => Ok(Self::Completed),
+            "failed" => Ok(Self::Failed),
+            "cancelled" => Ok(Self::Cancelled),
+            _ => Err(format!("Invalid queue task status: {}", s)),
+        }
+    }
+}
+
+/// Priority class of a queued run.
+///
+/// Workers claim every queued run of a higher class before any of a lower
+/// one, so interactive requests are not held up by batch jobs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RunPriority {
+    /// Claimed first, e.g. for interactive requests.
+    High,
+    /// The default.
+    #[default]
+    Normal,
+    /// Claimed only when no other runs are queued, e.g. for batch jobs.
+    Low,
+}
+
+impl RunPriority {
+    /// Rank stored in the queue; lower ranks are claimed first.
+    pub fn rank(self) -> i32 {
+        match self {
+            Self::High => 0,
+            Self::Normal => 1,
+            Self::Low => 2,
+        }
+    }
+
+    /// Priority of a stored rank.
+    pub fn from_rank(rank: i32) -> Self {
+        match rank {
+            i32::MIN..=0 => Self::High,
+            1 => Self::Normal,
+            _ => Self::Low,
+        }
+    }
+}
+
+impl std::fmt::Display for RunPriority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::High => write!(f, "high"),
+            Self::Normal => write!(f, "normal"),
+            Self::Low => write!(f, "low"),
+        }
+    }
+}
+
+impl std::str::FromStr for RunPriority {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "high" => Ok(Self::High),
+            "normal" => Ok(Self::Normal),
+            "low" => Ok(Self::Low),
+            _ => Err(format!("Invalid run priority: {} (expected high, normal, or low)", s)),
+        }
+    }
+}
+
+/// A unit of work in the distributed work queue.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueueTask {
+    /// Unique task identifier.
+    pub id: Uuid,
+    /// Task payload (e.g. workflow definition and inputs).
+    pub payload: Value,
+    /// Current status.
+    pub status: QueueTaskStatus,
+    /// Worker currently holding the lease.
+    pub worker_id: Option<String>,
+    /// When the current lease expires.
+    pub lease_expires_at: Option<DateTime<Utc>>,
+    /// Number of times the task has been claimed.
+    pub attempts: i32,
+    /// Timestamp when the task was enqueued.
+    pub enqueued_at: DateTime<Utc>,
+    /// Timestamp of last update.
+    pub updated_at: DateTime<Utc>,
+    /// Error message if failed.
+    pub error: Option<String>,
+    /// Priority class the task is claimed in.
+    #[serde(default)]
+    pub priority: RunPriority,
+    /// Tenant the run belongs to; workers share capacity fairly between tenants.
+    #[serde(default)]
+    pub tenant_id: Option<String>,
+}
+
+impl QueueTask {
+    /// Create a new queued task.
+    pub fn new(payload: Value) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            payload,
+            status: QueueTaskStatus::Queued,
+            worker_id: None,
+            lease_expires_at: None,
+            attempts: 0,
+            enqueued_at: now,
+            updated_at: now,
+            error: None,
+            priority: RunPriority::Normal,
+            tenant_id: None,
+        }
+    }
+
+    /// Set the priority class.
+    pub fn with_priority(mut self, priority: RunPriority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Assign the run to a tenant.
+    pub fn with_tenant_id(mut self, tenant_id: impl Into<String>) -> Self {
+        self.tenant_id = Some(tenant_id.into());
+        self
+    }
+}
+
+/// One failed attempt of a dead-lettered step.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeadLetterAttempt {
+    /// Attempt number (starting at 1).
+    pub attempt: u32,
+    /// Error returned by the attempt.
+    pub error: String,
+    /// When the attempt failed.
+    pub failed_at: DateTime<Utc>,
+}
+
+/// Failure context of a step that exhausted its retries.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadLetter {
+    /// Unique identifier.
+    pub id: Uuid,
+    /// Run the step belonged to.
+    pub run_id: Uuid,
+    /// Workflow name.
+    pub workflow_name: String,
+    /// Failed step ID.
+    pub step_id: String,
+    /// Step definition.
+    pub step: Value,
+    /// Workflow inputs and step outputs at the time of failure.
+    pub context: Value,
+    /// Last provider request sent by the step (including the rendered prompt).
+    pub request: Option<Value>,
+    /// Final error.
+    pub error: String,
+    /// History of failed attempts.
+    pub attempts: Vec<DeadLetterAttempt>,
+    /// Timestamp when the entry was created.
+    pub created_at: DateTime<Utc>,
+    /// Timestamp when a retry succeeded.
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+impl DeadLetter {
+    /// Create a new dead-letter entry.
+    pub fn new(
+        run_id: Uuid,
+        workflow_name: impl Into<String>,
+        step_id: impl Into<String>,
+        step: Value,
+        context: Value,
+        error: impl Into<String>,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            run_id,
+            workflow_name: workflow_name.into(),
+            step_id: step_id.into(),
+            step,
+            context,
+            request: None,
+            error: error.into(),
+            attempts: Vec::new(),
+            created_at: Utc::now(),
+            resolved_at: None,
+        }
+    }
+
+    /// Returns true once a retry has succeeded.
+    pub fn is_resolved(&self) -> bool {
+        self.resolved_at.is_some()
+    }
+}
+
+/// A version of a named prompt template.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PromptVersion {
+    /// Prompt name.
+    pub name: String,
+    /// Version number (starting at 1).
+    pub version: u32,
+    /// Prompt template (Handlebars syntax).
+    pub template: String,
+    /// System prompt used with the template.
+    pub system: Option<String>,
+    /// What changed in this version.
+    pub description: Option<String>,
+    /// Timestamp when the version was created.
+    pub created_at: DateTime<Utc>,
+}
+
+impl PromptVersion {
+    /// Create a new prompt version.
+    pub fn new(name: impl Into<String>, version: u32, template: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            version,
+            template: template.into(),
+            system: None,
+            description: None,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// A published version of a workflow definition.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkflowDefinition {
+    /// Workflow name.
+    pub name: String,
+    /// Workflow version.
+    pub version: String,
+    /// Workflow YAML as published.
+    pub definition: String,
+    /// SHA-256 of the definition, hex encoded.
+    pub checksum: String,
+    /// User or service that published the version.
+    pub created_by: Option<String>,
+    /// Timestamp when the version was published.
+    pub created_at: DateTime<Utc>,
+    /// Timestamp when the version was deprecated.
+    pub deprecated_at: Option<DateTime<Utc>>,
+}
+
+impl WorkflowDefinition {
+    /// Create a new workflow definition version.
+    pub fn new(name: impl Into<String>, version: impl Into<String>, definition: impl Into<String>) -> Self {
+        let definition = definition.into();
+        Self {
+            name: name.into(),
+            version: version.into(),
+            checksum: Self::compute_checksum(&definition),
+            definition,
+            created_by: None,
+            created_at: Utc::now(),
+            deprecated_at: None,
+        }
+    }
+
+    /// Compute the checksum stored with a definition.
+    pub fn compute_checksum(definition: &str) -> String {
+        use sha2::{Digest, Sha256};
+
+        format!("{:x}", Sha256::digest(definition.as_bytes()))
+    }
+
+    /// Returns true if the stored definition still matches its checksum.
+    pub fn verify_checksum(&self) -> bool {
+        Self::compute_checksum(&self.definition) == self.checksum
+    }
+
+    /// Returns true once the version has been deprecated.
+    pub fn is_deprecated(&self) -> bool {
+        self.deprecated_at.is_some()
+    }
+}
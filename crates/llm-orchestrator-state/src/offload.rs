@@ -0,0 +1,279 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Offloading of large step outputs.
+//!
+//! Embeddings and long completions can make the `outputs` column of
+//! `step_states` very large. When a store is given an [`OutputOffload`], outputs
+//! whose serialized size exceeds the threshold are written to the
+//! `step_output_blobs` table, or to a [`BlobStore`] backend, and the step
+//! record keeps a small reference instead:
+//!
+//! ```json
+//! {"$blob": {"backend": "database", "key": "step-outputs/<run id>/<step id>", "size": 1048576}}
+//! ```
+//!
+//! References are resolved transparently when a run is loaded. Offloaded data
+//! is encrypted like inline outputs when the store has a
+//! [`StateEncryption`](crate::StateEncryption).
+
+use crate::traits::{StateStoreError, StateStoreResult};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use tracing::warn;
+use uuid::Uuid;
+
+/// Default size in bytes above which step outputs are offloaded.
+pub const DEFAULT_OFFLOAD_THRESHOLD: usize = 256 * 1024;
+
+/// Field marking an offloaded value.
+pub(crate) const REFERENCE_FIELD: &str = "$blob";
+
+/// Backend name for blobs kept in the `step_output_blobs` table.
+pub(crate) const DATABASE_BACKEND: &str = "database";
+
+/// Object storage for offloaded step outputs.
+#[async_trait]
+pub trait BlobStore: Send + Sync {
+    /// Backend name recorded in blob references.
+    fn name(&self) -> &str;
+
+    /// Store a blob, replacing any existing blob with the same key.
+    async fn put(&self, key: &str, data: &[u8]) -> StateStoreResult<()>;
+
+    /// Fetch a blob by key.
+    async fn get(&self, key: &str) -> StateStoreResult<Vec<u8>>;
+
+    /// Delete a blob; deleting a missing blob is not an error.
+    async fn delete(&self, key: &str) -> StateStoreResult<()>;
+}
+
+/// Blob store keeping each blob as a file under a root directory.
+#[derive(Debug, Clone)]
+pub struct FileBlobStore {
+    root: PathBuf,
+}
+
+impl FileBlobStore {
+    /// Create a blob store rooted at `root`; directories are created on demand.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn path(&self, key: &str) -> StateStoreResult<PathBuf> {
+        let relative = Path::new(key);
+        if key.is_empty() || !relative.components().all(|c| matches!(c, Component::Normal(_))) {
+            return Err(StateStoreError::Configuration(format!("Invalid blob key: {}", key)));
+        }
+        Ok(self.root.join(relative))
+    }
+}
+
+#[async_trait]
+impl BlobStore for FileBlobStore {
+    fn name(&self) -> &str {
+        "file"
+    }
+
+    async fn put(&self, key: &str, data: &[u8]) -> StateStoreResult<()> {
+        let path = self.path(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await.map_err(|e| blob_io_error(key, e))?;
+        }
+
+        // Write to a temporary file first so readers never see a partial blob
+        let tmp = path.with_extension("tmp");
+        tokio::fs::write(&tmp, data).await.map_err(|e| blob_io_error(key, e))?;
+        tokio::fs::rename(&tmp, &path).await.map_err(|e| blob_io_error(key, e))
+    }
+
+    async fn get(&self, key: &str) -> StateStoreResult<Vec<u8>> {
+        match tokio::fs::read(self.path(key)?).await {
+            Ok(data) => Ok(data),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Err(blob_not_found(key)),
+            Err(e) => Err(blob_io_error(key, e)),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> StateStoreResult<()> {
+        match tokio::fs::remove_file(self.path(key)?).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(blob_io_error(key, e)),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Settings for offloading large step outputs.
+#[derive(Clone)]
+pub struct OutputOffload {
+    threshold: usize,
+    backend: Option<Arc<dyn BlobStore>>,
+}
+
+impl std::fmt::Debug for OutputOffload {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OutputOffload")
+            .field("threshold", &self.threshold)
+            .field("backend", &self.backend.as_ref().map_or(DATABASE_BACKEND, |b| b.name()))
+            .finish()
+    }
+}
+
+impl Default for OutputOffload {
+    fn default() -> Self {
+        Self::new(DEFAULT_OFFLOAD_THRESHOLD)
+    }
+}
+
+impl OutputOffload {
+    /// Offload outputs larger than `threshold_bytes` to the `step_output_blobs` table.
+    pub fn new(threshold_bytes: usize) -> Self {
+        Self {
+            threshold: threshold_bytes,
+            backend: None,
+        }
+    }
+
+    /// Offload to an object-store backend instead of the database.
+    pub fn with_backend(mut self, backend: Arc<dyn BlobStore>) -> Self {
+        self.backend = Some(backend);
+        self
+    }
+
+    /// Size in bytes above which outputs are offloaded.
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    /// Object-store backend, or `None` when blobs are kept in the database.
+    pub fn backend(&self) -> Option<&Arc<dyn BlobStore>> {
+        self.backend.as_ref()
+    }
+
+    pub(crate) fn should_offload(&self, column: &str) -> bool {
+        column.len() > self.threshold
+    }
+}
+
+/// Reference to an offloaded value, stored in place of the value.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct BlobReference {
+    pub backend: String,
+    pub key: String,
+    pub size: usize,
+}
+
+impl BlobReference {
+    /// Serialize the reference as a column value.
+    pub fn to_column(&self) -> StateStoreResult<String> {
+        Ok(serde_json::to_string(&serde_json::json!({ REFERENCE_FIELD: self }))?)
+    }
+
+    /// Parse a column value, returning `None` if it is not a reference.
+    pub fn from_column(text: &str) -> Option<Self> {
+        if !text.starts_with(&format!("{{\"{}\"", REFERENCE_FIELD)) {
+            return None;
+        }
+        let mut value: serde_json::Value = serde_json::from_str(text).ok()?;
+        serde_json::from_value(value.get_mut(REFERENCE_FIELD)?.take()).ok()
+    }
+}
+
+/// `LIKE` pattern matching column values that are blob references.
+pub(crate) fn reference_pattern() -> String {
+    format!("{{\"{}\"%", REFERENCE_FIELD)
+}
+
+/// Blob key for the outputs of one step of a run.
+pub(crate) fn blob_key(workflow_state_id: &Uuid, step_id: &str) -> String {
+    // Step IDs are user-defined; escape anything that is not safe in a path or URL
+    let mut escaped = String::with_capacity(step_id.len());
+    for byte in step_id.bytes() {
+        if byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_' {
+            escaped.push(byte as char);
+        } else {
+            escaped.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    format!("step-outputs/{}/{}", workflow_state_id, escaped)
+}
+
+/// Fetch an offloaded value from the configured object-store backend.
+pub(crate) async fn fetch_external(offload: Option<&OutputOffload>, reference: &BlobReference) -> StateStoreResult<String> {
+    let backend = offload
+        .and_then(|o| o.backend())
+        .filter(|b| b.name() == reference.backend)
+        .ok_or_else(|| {
+            StateStoreError::Configuration(format!(
+                "Step output {} is stored in the '{}' blob store, which is not configured",
+                reference.key, reference.backend
+            ))
+        })?;
+    String::from_utf8(backend.get(&reference.key).await?)
+        .map_err(|e| StateStoreError::Serialization(format!("Blob {} is not UTF-8: {}", reference.key, e)))
+}
+
+/// Delete the object-store blobs referenced by the given column values.
+///
+/// Blobs in the database are removed with their run; failures here are logged
+/// so that pruning old runs is never blocked by the object store.
+pub(crate) async fn delete_external(offload: Option<&OutputOffload>, columns: &[String]) {
+    let Some(backend) = offload.and_then(|o| o.backend()) else {
+        return;
+    };
+    for reference in columns.iter().filter_map(|c| BlobReference::from_column(c)) {
+        if reference.backend != backend.name() {
+            continue;
+        }
+        if let Err(e) = backend.delete(&reference.key).await {
+            warn!("Failed to delete step output blob {}: {}", reference.key, e);
+        }
+    }
+}
+
+pub(crate) fn blob_not_found(key: &str) -> StateStoreError {
+    StateStoreError::NotFound(format!("Step output blob {}", key))
+}
+
+fn blob_io_error(key: &str, err: std::io::Error) -> StateStoreError {
+    StateStoreError::Other(format!("Blob {}: {}", key, err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reference_round_trip_and_keys() {
+        let reference = BlobReference {
+            backend: DATABASE_BACKEND.to_string(),
+            key: "step-outputs/run/summarize".to_string(),
+            size: 42,
+        };
+        let column = reference.to_column().unwrap();
+        let parsed = BlobReference::from_column(&column).unwrap();
+        assert_eq!(parsed.key, reference.key);
+        assert_eq!(parsed.size, 42);
+        assert!(BlobReference::from_column(r#"{"text": "hello"}"#).is_none());
+
+        let id = Uuid::nil();
+        assert_eq!(blob_key(&id, "embed_docs"), format!("step-outputs/{}/embed_docs", id));
+        assert_eq!(blob_key(&id, "../x"), format!("step-outputs/{}/%2E%2E%2Fx", id));
+    }
+
+    #[tokio::test]
+    async fn test_file_blob_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = FileBlobStore::new(dir.path());
+
+        store.put("step-outputs/run/a", b"payload").await.unwrap();
+        assert_eq!(store.get("step-outputs/run/a").await.unwrap(), b"payload");
+
+        store.delete("step-outputs/run/a").await.unwrap();
+        store.delete("step-outputs/run/a").await.unwrap();
+        assert!(matches!(store.get("step-outputs/run/a").await, Err(StateStoreError::NotFound(_))));
+        assert!(matches!(store.put("../escape", b"x").await, Err(StateStoreError::Configuration(_))));
+    }
+}
@@ -3,11 +3,27 @@
 
 //! PostgreSQL implementation of the StateStore trait.
 
-use crate::models::{Checkpoint, StepState, WorkflowState, WorkflowStatus};
-use crate::traits::{StateStore, StateStoreError, StateStoreResult};
+use crate::compression::{compress, compressed_pattern, is_compressed};
+use crate::encryption::{decode_column, decrypt_column, encode_column, StateEncryption};
+use crate::migrations::{migration_error, migration_status, MigrationStatus, MIGRATIONS_TABLE, MIGRATOR};
+use crate::models::{
+    Checkpoint, DeadLetter, ModelUsage, PromptVersion, QueueTask, QueueTaskStatus, RunPage, RunPriority, RunQuery,
+    RunSummary, StepState, UsageSummary, WorkflowDefinition, WorkflowState, WorkflowStatus,
+};
+use crate::offload::{
+    blob_key, blob_not_found, delete_external, fetch_external, reference_pattern, BlobReference, OutputOffload,
+    DATABASE_BACKEND,
+};
+use crate::traits::{
+    append_memory_entries, definition_insert_error, definition_not_found, lease_expiry_millis, prompt_insert_error,
+    push_run_conditions, run_order_clause, DeadLetterQueue, PromptVersionStore, RunQueryParam, SchemaMigrations,
+    SessionMemoryStore, StateStore, RetentionStore, StateStoreError, StateStoreResult, UsageStore, WorkQueue,
+    WorkflowDefinitionStore,
+};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
-use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+use sqlx::migrate::Migrate;
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions, PgRow};
 use sqlx::{ConnectOptions, PgPool, Row};
 use std::str::FromStr;
 use std::time::Duration;
@@ -17,10 +33,13 @@ use uuid::Uuid;
 /// PostgreSQL state store implementation.
 pub struct PostgresStateStore {
     pool: PgPool,
+    encryption: Option<StateEncryption>,
+    offload: Option<OutputOffload>,
 }
 
 impl PostgresStateStore {
-    /// Create a new PostgreSQL state store with connection pooling.
+    /// Create a new PostgreSQL state store with connection pooling, applying any
+    /// pending migrations.
     ///
     /// # Arguments
     /// * `database_url` - PostgreSQL connection string
@@ -43,6 +62,19 @@ impl PostgresStateStore {
         database_url: impl AsRef<str>,
         min_connections: Option<u32>,
         max_connections: Option<u32>,
+    ) -> StateStoreResult<Self> {
+        let store = Self::connect(database_url, min_connections, max_connections).await?;
+        store.migrate().await?;
+        Ok(store)
+    }
+
+    /// Connect to PostgreSQL without applying migrations.
+    ///
+    /// Use [`SchemaMigrations`] to inspect or apply migrations explicitly.
+    pub async fn connect(
+        database_url: impl AsRef<str>,
+        min_connections: Option<u32>,
+        max_connections: Option<u32>,
     ) -> StateStoreResult<Self> {
         let min_conn = min_connections.unwrap_or(5);
         let max_conn = max_connections.unwrap_or(20);
@@ -72,40 +104,104 @@ impl PostgresStateStore {
 
         info!("PostgreSQL connection pool established");
 
-        let store = Self { pool };
-
-        // Run migrations
-        store.run_migrations().await?;
+        Ok(Self {
+            pool,
+            encryption: None,
+            offload: None,
+        })
+    }
 
-        Ok(store)
+    /// Get the connection pool (for advanced use cases).
+    pub fn pool(&self) -> &PgPool {
+        &self.pool
     }
 
-    /// Run database migrations.
-    async fn run_migrations(&self) -> StateStoreResult<()> {
-        info!("Running database migrations");
+    /// Encrypt run contexts, step outputs, checkpoint snapshots, and dead-letter
+    /// contexts before they are written.
+    ///
+    /// Rows written without encryption remain readable.
+    pub fn with_encryption(mut self, encryption: StateEncryption) -> Self {
+        self.encryption = Some(encryption);
+        self
+    }
 
-        // Read migration files
-        let migration_001 = include_str!("../migrations/001_initial_schema.sql");
-        let migration_002 = include_str!("../migrations/002_checkpoints.sql");
+    /// Store step outputs larger than the offload threshold outside the
+    /// `step_states` table.
+    ///
+    /// Offloaded outputs are rehydrated when runs are loaded.
+    pub fn with_output_offload(mut self, offload: OutputOffload) -> Self {
+        self.offload = Some(offload);
+        self
+    }
 
-        // Execute migrations
-        sqlx::query(migration_001)
-            .execute(&self.pool)
-            .await
-            .map_err(|e| StateStoreError::Database(format!("Migration 001 failed: {}", e)))?;
+    /// Serialize step outputs, offloading them if they exceed the threshold.
+    async fn encode_outputs(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        state: &WorkflowState,
+        step_id: &str,
+        outputs: &serde_json::Value,
+    ) -> StateStoreResult<String> {
+        let column = encode_column(self.encryption.as_ref(), outputs)?;
+        let Some(offload) = self.offload.as_ref().filter(|o| o.should_offload(&column)) else {
+            return Ok(column);
+        };
 
-        sqlx::query(migration_002)
-            .execute(&self.pool)
-            .await
-            .map_err(|e| StateStoreError::Database(format!("Migration 002 failed: {}", e)))?;
+        let key = blob_key(&state.id, step_id);
+        let backend = match offload.backend() {
+            Some(backend) => {
+                backend.put(&key, column.as_bytes()).await?;
+                backend.name().to_string()
+            }
+            None => {
+                sqlx::query(
+                    r#"
+                    INSERT INTO step_output_blobs (
+                        blob_key, workflow_state_id, step_id, data, size_bytes, created_at
+                    ) VALUES ($1, $2, $3, $4, $5, $6)
+                    ON CONFLICT (blob_key) DO UPDATE SET
+                        data = EXCLUDED.data,
+                        size_bytes = EXCLUDED.size_bytes,
+                        created_at = EXCLUDED.created_at
+                    "#
+                )
+                .bind(&key)
+                .bind(state.id)
+                .bind(step_id)
+                .bind(&column)
+                .bind(column.len() as i64)
+                .bind(Utc::now())
+                .execute(&mut **tx)
+                .await?;
+                DATABASE_BACKEND.to_string()
+            }
+        };
 
-        info!("Database migrations completed successfully");
-        Ok(())
+        debug!("Offloaded outputs of step {} ({} bytes) to {}", step_id, column.len(), backend);
+        BlobReference {
+            backend,
+            key,
+            size: column.len(),
+        }
+        .to_column()
     }
 
-    /// Get the connection pool (for advanced use cases).
-    pub fn pool(&self) -> &PgPool {
-        &self.pool
+    /// Parse step outputs, fetching them from blob storage if they were offloaded.
+    async fn decode_outputs(&self, column: &str) -> StateStoreResult<serde_json::Value> {
+        let Some(reference) = BlobReference::from_column(column) else {
+            return decode_column(self.encryption.as_ref(), column);
+        };
+
+        let data = if reference.backend == DATABASE_BACKEND {
+            sqlx::query_scalar::<_, String>("SELECT data FROM step_output_blobs WHERE blob_key = $1")
+                .bind(&reference.key)
+                .fetch_optional(&self.pool)
+                .await?
+                .ok_or_else(|| blob_not_found(&reference.key))?
+        } else {
+            fetch_external(self.offload.as_ref(), &reference).await?
+        };
+        decode_column(self.encryption.as_ref(), &data)
     }
 }
 
@@ -117,15 +213,15 @@ impl StateStore for PostgresStateStore {
         let mut tx = self.pool.begin().await?;
 
         // Serialize context to JSON string
-        let context_json = serde_json::to_string(&state.context)?;
+        let context_json = encode_column(self.encryption.as_ref(), &state.context)?;
 
         // Upsert workflow state
         sqlx::query(
             r#"
             INSERT INTO workflow_states (
                 id, workflow_id, workflow_name, status, user_id,
-                started_at, updated_at, completed_at, context, error
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+                started_at, updated_at, completed_at, context, error, tenant_id
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
             ON CONFLICT (id) DO UPDATE SET
                 status = EXCLUDED.status,
                 updated_at = EXCLUDED.updated_at,
@@ -144,12 +240,13 @@ impl StateStore for PostgresStateStore {
         .bind(state.completed_at)
         .bind(context_json)
         .bind(&state.error)
+        .bind(&state.tenant_id)
         .execute(&mut *tx)
         .await?;
 
         // Save step states
         for (step_id, step_state) in &state.steps {
-            let outputs_json = serde_json::to_string(&step_state.outputs)?;
+            let outputs_json = self.encode_outputs(&mut tx, state, step_id, &step_state.outputs).await?;
 
             sqlx::query(
                 r#"
@@ -178,6 +275,36 @@ impl StateStore for PostgresStateStore {
             .await?;
         }
 
+        // Save usage per provider and model
+        for usage in &state.usage {
+            sqlx::query(
+                r#"
+                INSERT INTO run_usage (
+                    workflow_state_id, provider, model, requests, failed_requests,
+                    input_tokens, output_tokens, total_tokens, cost_usd
+                ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                ON CONFLICT (workflow_state_id, provider, model) DO UPDATE SET
+                    requests = EXCLUDED.requests,
+                    failed_requests = EXCLUDED.failed_requests,
+                    input_tokens = EXCLUDED.input_tokens,
+                    output_tokens = EXCLUDED.output_tokens,
+                    total_tokens = EXCLUDED.total_tokens,
+                    cost_usd = EXCLUDED.cost_usd
+                "#
+            )
+            .bind(state.id)
+            .bind(&usage.provider)
+            .bind(&usage.model)
+            .bind(usage.requests as i64)
+            .bind(usage.failed_requests as i64)
+            .bind(usage.input_tokens as i64)
+            .bind(usage.output_tokens as i64)
+            .bind(usage.total_tokens as i64)
+            .bind(usage.cost_usd)
+            .execute(&mut *tx)
+            .await?;
+        }
+
         tx.commit().await?;
 
         debug!("Workflow state saved successfully: id={}", state.id);
@@ -190,7 +317,7 @@ impl StateStore for PostgresStateStore {
         // Load workflow state
         let row = sqlx::query(
             r#"
-            SELECT id, workflow_id, workflow_name, status, user_id,
+            SELECT id, workflow_id, workflow_name, status, user_id, tenant_id,
                    started_at, updated_at, completed_at, context, error
             FROM workflow_states
             WHERE id = $1
@@ -206,7 +333,7 @@ impl StateStore for PostgresStateStore {
             .map_err(StateStoreError::InvalidState)?;
 
         let context_str: String = row.get("context");
-        let context = serde_json::from_str(&context_str)?;
+        let context = decode_column(self.encryption.as_ref(), &context_str)?;
 
         let mut state = WorkflowState {
             id: workflow_id,
@@ -214,12 +341,14 @@ impl StateStore for PostgresStateStore {
             workflow_name: row.get("workflow_name"),
             status,
             user_id: row.get("user_id"),
+            tenant_id: row.get("tenant_id"),
             started_at: row.get("started_at"),
             updated_at: row.get("updated_at"),
             completed_at: row.get("completed_at"),
             context,
             error: row.get("error"),
             steps: Default::default(),
+            usage: Vec::new(),
         };
 
         // Load step states
@@ -243,7 +372,7 @@ impl StateStore for PostgresStateStore {
 
             let outputs_str: Option<String> = step_row.get("outputs");
             let outputs = if let Some(json_str) = outputs_str {
-                serde_json::from_str(&json_str)?
+                self.decode_outputs(&json_str).await?
             } else {
                 serde_json::Value::Null
             };
@@ -261,6 +390,21 @@ impl StateStore for PostgresStateStore {
             state.steps.insert(step_id, step_state);
         }
 
+        // Load usage
+        let usage_rows = sqlx::query(
+            r#"
+            SELECT provider, model, requests, failed_requests,
+                   input_tokens, output_tokens, total_tokens, cost_usd
+            FROM run_usage
+            WHERE workflow_state_id = $1
+            ORDER BY provider, model
+            "#
+        )
+        .bind(workflow_id)
+        .fetch_all(&self.pool)
+        .await?;
+        state.usage = usage_rows.iter().map(Self::model_usage_from_row).collect();
+
         debug!("Workflow state loaded successfully: id={}", id);
         Ok(state)
     }
@@ -315,10 +459,57 @@ impl StateStore for PostgresStateStore {
         Ok(workflows)
     }
 
+    async fn list_workflow_runs(&self, query: &RunQuery) -> StateStoreResult<RunPage> {
+        debug!("Listing workflow runs: {:?}", query);
+
+        let mut conditions = String::from(" WHERE 1=1");
+        let params = push_run_conditions(query, &mut conditions, |n| format!("${}", n));
+
+        let count_sql = format!("SELECT COUNT(*) FROM workflow_states{}", conditions);
+        let mut count_query = sqlx::query(&count_sql);
+        for param in &params {
+            count_query = match param {
+                RunQueryParam::Text(value) => count_query.bind(value),
+                RunQueryParam::Time(value) => count_query.bind(value),
+            };
+        }
+        let total: i64 = count_query.fetch_one(&self.pool).await?.get(0);
+
+        let sql = format!(
+            r#"
+            SELECT id, workflow_id, workflow_name, status, user_id, tenant_id,
+                   started_at, updated_at, completed_at, error
+            FROM workflow_states{}{}
+            "#,
+            conditions,
+            run_order_clause(query)
+        );
+        let mut runs_query = sqlx::query(&sql);
+        for param in &params {
+            runs_query = match param {
+                RunQueryParam::Text(value) => runs_query.bind(value),
+                RunQueryParam::Time(value) => runs_query.bind(value),
+            };
+        }
+        let rows = runs_query.fetch_all(&self.pool).await?;
+
+        let mut runs = Vec::with_capacity(rows.len());
+        for row in rows {
+            runs.push(Self::run_summary_from_row(&row)?);
+        }
+
+        Ok(RunPage {
+            runs,
+            total: total as u64,
+            offset: query.offset,
+            limit: query.limit,
+        })
+    }
+
     async fn create_checkpoint(&self, checkpoint: &Checkpoint) -> StateStoreResult<()> {
         debug!("Creating checkpoint: id={}, workflow_state_id={}", checkpoint.id, checkpoint.workflow_state_id);
 
-        let snapshot_json = serde_json::to_string(&checkpoint.snapshot)?;
+        let snapshot_json = encode_column(self.encryption.as_ref(), &checkpoint.snapshot)?;
 
         sqlx::query(
             r#"
@@ -359,7 +550,7 @@ impl StateStore for PostgresStateStore {
 
         if let Some(row) = row_opt {
             let snapshot_str: String = row.get("snapshot");
-            let snapshot = serde_json::from_str(&snapshot_str)?;
+            let snapshot = decode_column(self.encryption.as_ref(), &snapshot_str)?;
 
             let checkpoint = Checkpoint {
                 id: row.get("id"),
@@ -392,7 +583,8 @@ impl StateStore for PostgresStateStore {
         .await?;
 
         let snapshot_str: String = row.get("snapshot");
-        let state: WorkflowState = serde_json::from_str(&snapshot_str)?;
+        let state: WorkflowState =
+            serde_json::from_value(decode_column(self.encryption.as_ref(), &snapshot_str)?)?;
 
         debug!("Successfully restored state from checkpoint: id={}", checkpoint_id);
         Ok(state)
@@ -401,6 +593,24 @@ impl StateStore for PostgresStateStore {
     async fn delete_old_states(&self, older_than: DateTime<Utc>) -> StateStoreResult<u64> {
         debug!("Deleting states older than: {}", older_than);
 
+        // Blobs in the database go with their run; object-store blobs are deleted here
+        if self.offload.as_ref().is_some_and(|o| o.backend().is_some()) {
+            let references: Vec<String> = sqlx::query_scalar(
+                r#"
+                SELECT s.outputs FROM step_states s
+                JOIN workflow_states w ON w.id = s.workflow_state_id
+                WHERE w.updated_at < $1
+                  AND w.status IN ('completed', 'failed')
+                  AND s.outputs LIKE $2
+                "#
+            )
+            .bind(older_than)
+            .bind(reference_pattern())
+            .fetch_all(&self.pool)
+            .await?;
+            delete_external(self.offload.as_ref(), &references).await;
+        }
+
         let result = sqlx::query(
             r#"
             DELETE FROM workflow_states
@@ -459,6 +669,819 @@ impl StateStore for PostgresStateStore {
     }
 }
 
+
+/// Columns selected when loading a queue task.
+const QUEUE_TASK_COLUMNS: &str =
+    "id, payload, status, worker_id, lease_expires_at, attempts, enqueued_at, updated_at, error, priority, tenant_id";
+
+impl PostgresStateStore {
+    /// Convert a `work_queue` row into a task.
+    fn queue_task_from_row(row: &PgRow) -> StateStoreResult<QueueTask> {
+        let status_str: String = row.get("status");
+        let status = QueueTaskStatus::from_str(&status_str)
+            .map_err(StateStoreError::InvalidState)?;
+
+        let payload_str: String = row.get("payload");
+        let lease_millis: Option<i64> = row.get("lease_expires_at");
+
+        Ok(QueueTask {
+            id: row.get("id"),
+            payload: serde_json::from_str(&payload_str)?,
+            status,
+            worker_id: row.get("worker_id"),
+            lease_expires_at: lease_millis.and_then(DateTime::<Utc>::from_timestamp_millis),
+            attempts: row.get("attempts"),
+            enqueued_at: row.get("enqueued_at"),
+            updated_at: row.get("updated_at"),
+            error: row.get("error"),
+            priority: RunPriority::from_rank(row.get("priority")),
+            tenant_id: row.get("tenant_id"),
+        })
+    }
+}
+
+#[async_trait]
+impl WorkQueue for PostgresStateStore {
+    async fn enqueue_task(&self, task: &QueueTask) -> StateStoreResult<()> {
+        debug!("Enqueuing task: id={}", task.id);
+
+        let payload_json = serde_json::to_string(&task.payload)?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO work_queue (id, payload, status, attempts, enqueued_at, updated_at, priority, tenant_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#
+        )
+        .bind(task.id)
+        .bind(payload_json)
+        .bind(task.status.to_string())
+        .bind(task.attempts)
+        .bind(task.enqueued_at)
+        .bind(task.updated_at)
+        .bind(task.priority.rank())
+        .bind(&task.tenant_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn claim_task(&self, worker_id: &str, lease: Duration) -> StateStoreResult<Option<QueueTask>> {
+        // SKIP LOCKED lets concurrent workers claim different rows without blocking
+        let row_opt = sqlx::query(&format!(
+            r#"
+            UPDATE work_queue
+            SET status = 'running', worker_id = $1, lease_expires_at = $2,
+                attempts = attempts + 1, updated_at = $3
+            WHERE id = (
+                SELECT id FROM work_queue queued
+                WHERE status = 'queued'
+                ORDER BY priority,
+                    (SELECT COUNT(*) FROM work_queue running
+                     WHERE running.status = 'running'
+                       AND COALESCE(running.tenant_id, '') = COALESCE(queued.tenant_id, '')),
+                    enqueued_at
+                LIMIT 1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING {}
+            "#,
+            QUEUE_TASK_COLUMNS
+        ))
+        .bind(worker_id)
+        .bind(lease_expiry_millis(lease))
+        .bind(Utc::now())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let task = row_opt.as_ref().map(Self::queue_task_from_row).transpose()?;
+        if let Some(task) = &task {
+            debug!("Task claimed: id={}, worker_id={}", task.id, worker_id);
+        }
+        Ok(task)
+    }
+
+    async fn heartbeat_task(&self, task_id: &Uuid, worker_id: &str, lease: Duration) -> StateStoreResult<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE work_queue
+            SET lease_expires_at = $1, updated_at = $2
+            WHERE id = $3 AND worker_id = $4 AND status = 'running'
+            "#
+        )
+        .bind(lease_expiry_millis(lease))
+        .bind(Utc::now())
+        .bind(task_id)
+        .bind(worker_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn complete_task(&self, task_id: &Uuid, worker_id: &str) -> StateStoreResult<()> {
+        let result = sqlx::query(
+            r#"
+            UPDATE work_queue
+            SET status = 'completed', lease_expires_at = NULL, updated_at = $1
+            WHERE id = $2 AND worker_id = $3 AND status = 'running'
+            "#
+        )
+        .bind(Utc::now())
+        .bind(task_id)
+        .bind(worker_id)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(StateStoreError::InvalidState(format!(
+                "Task {} is not leased to worker {}",
+                task_id, worker_id
+            )));
+        }
+        Ok(())
+    }
+
+    async fn fail_task(&self, task_id: &Uuid, worker_id: &str, error: &str) -> StateStoreResult<()> {
+        let result = sqlx::query(
+            r#"
+            UPDATE work_queue
+            SET status = 'failed', lease_expires_at = NULL, error = $1, updated_at = $2
+            WHERE id = $3 AND worker_id = $4 AND status = 'running'
+            "#
+        )
+        .bind(error)
+        .bind(Utc::now())
+        .bind(task_id)
+        .bind(worker_id)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(StateStoreError::InvalidState(format!(
+                "Task {} is not leased to worker {}",
+                task_id, worker_id
+            )));
+        }
+        Ok(())
+    }
+
+    async fn cancel_task(&self, task_id: &Uuid) -> StateStoreResult<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE work_queue
+            SET status = 'cancelled', lease_expires_at = NULL, updated_at = $1
+            WHERE id = $2 AND status IN ('queued', 'running')
+            "#
+        )
+        .bind(Utc::now())
+        .bind(task_id)
+        .execute(&self.pool)
+        .await?;
+
+        let cancelled = result.rows_affected() > 0;
+        if cancelled {
+            info!("Task cancelled: id={}", task_id);
+        }
+        Ok(cancelled)
+    }
+
+    async fn recover_expired_tasks(&self) -> StateStoreResult<u64> {
+        let result = sqlx::query(
+            r#"
+            UPDATE work_queue
+            SET status = 'queued', worker_id = NULL, lease_expires_at = NULL, updated_at = $1
+            WHERE status = 'running' AND lease_expires_at < $2
+            "#
+        )
+        .bind(Utc::now())
+        .bind(Utc::now().timestamp_millis())
+        .execute(&self.pool)
+        .await?;
+
+        let recovered = result.rows_affected();
+        if recovered > 0 {
+            warn!("Recovered {} tasks with expired leases", recovered);
+        }
+        Ok(recovered)
+    }
+
+    async fn get_task(&self, task_id: &Uuid) -> StateStoreResult<QueueTask> {
+        let row = sqlx::query(&format!("SELECT {} FROM work_queue WHERE id = $1", QUEUE_TASK_COLUMNS))
+            .bind(task_id)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Self::queue_task_from_row(&row)
+    }
+
+    async fn count_tasks(&self, status: QueueTaskStatus) -> StateStoreResult<u64> {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM work_queue WHERE status = $1")
+            .bind(status.to_string())
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(count as u64)
+    }
+}
+
+const DEAD_LETTER_COLUMNS: &str =
+    "id, run_id, workflow_name, step_id, step, context, request, error, attempts, created_at, resolved_at";
+
+impl PostgresStateStore {
+    /// Convert a `dead_letters` row into an entry.
+    fn dead_letter_from_row(&self, row: &PgRow) -> StateStoreResult<DeadLetter> {
+        let step_str: String = row.get("step");
+        let context_str: String = row.get("context");
+        let request_str: Option<String> = row.get("request");
+        let attempts_str: String = row.get("attempts");
+
+        Ok(DeadLetter {
+            id: row.get("id"),
+            run_id: row.get("run_id"),
+            workflow_name: row.get("workflow_name"),
+            step_id: row.get("step_id"),
+            step: serde_json::from_str(&step_str)?,
+            context: decode_column(self.encryption.as_ref(), &context_str)?,
+            request: request_str.map(|r| serde_json::from_str(&r)).transpose()?,
+            error: row.get("error"),
+            attempts: serde_json::from_str(&attempts_str)?,
+            created_at: row.get("created_at"),
+            resolved_at: row.get("resolved_at"),
+        })
+    }
+}
+
+#[async_trait]
+impl DeadLetterQueue for PostgresStateStore {
+    async fn add_dead_letter(&self, entry: &DeadLetter) -> StateStoreResult<()> {
+        debug!("Adding dead letter: id={}, step_id={}", entry.id, entry.step_id);
+
+        let request_json = entry.request.as_ref().map(serde_json::to_string).transpose()?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO dead_letters
+                (id, run_id, workflow_name, step_id, step, context, request, error, attempts, created_at, resolved_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            "#
+        )
+        .bind(entry.id)
+        .bind(entry.run_id)
+        .bind(&entry.workflow_name)
+        .bind(&entry.step_id)
+        .bind(serde_json::to_string(&entry.step)?)
+        .bind(encode_column(self.encryption.as_ref(), &entry.context)?)
+        .bind(request_json)
+        .bind(&entry.error)
+        .bind(serde_json::to_string(&entry.attempts)?)
+        .bind(entry.created_at)
+        .bind(entry.resolved_at)
+        .execute(&self.pool)
+        .await?;
+
+        warn!("Step {} of run {} dead-lettered: id={}", entry.step_id, entry.run_id, entry.id);
+        Ok(())
+    }
+
+    async fn list_dead_letters(&self, include_resolved: bool) -> StateStoreResult<Vec<DeadLetter>> {
+        let filter = if include_resolved { "" } else { "WHERE resolved_at IS NULL" };
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM dead_letters {} ORDER BY created_at DESC",
+            DEAD_LETTER_COLUMNS, filter
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(|row| self.dead_letter_from_row(row)).collect()
+    }
+
+    async fn get_dead_letter(&self, id: &Uuid) -> StateStoreResult<DeadLetter> {
+        let row = sqlx::query(&format!("SELECT {} FROM dead_letters WHERE id = $1", DEAD_LETTER_COLUMNS))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| StateStoreError::NotFound(format!("Dead letter {} not found", id)))?;
+
+        self.dead_letter_from_row(&row)
+    }
+
+    async fn resolve_dead_letter(&self, id: &Uuid) -> StateStoreResult<()> {
+        let result = sqlx::query("UPDATE dead_letters SET resolved_at = $1 WHERE id = $2")
+            .bind(Utc::now())
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(StateStoreError::NotFound(format!("Dead letter {} not found", id)));
+        }
+        info!("Dead letter resolved: id={}", id);
+        Ok(())
+    }
+}
+
+const PROMPT_COLUMNS: &str = "name, version, template, system, description, created_at";
+
+impl PostgresStateStore {
+    /// Convert a `prompt_versions` row into a prompt version.
+    fn prompt_version_from_row(row: &PgRow) -> PromptVersion {
+        PromptVersion {
+            name: row.get("name"),
+            version: row.get::<i32, _>("version") as u32,
+            template: row.get("template"),
+            system: row.get("system"),
+            description: row.get("description"),
+            created_at: row.get("created_at"),
+        }
+    }
+}
+
+#[async_trait]
+impl PromptVersionStore for PostgresStateStore {
+    async fn add_prompt_version(&self, prompt: &PromptVersion) -> StateStoreResult<()> {
+        debug!("Adding prompt version: name={}, version={}", prompt.name, prompt.version);
+
+        sqlx::query(&format!(
+            "INSERT INTO prompt_versions ({}) VALUES ($1, $2, $3, $4, $5, $6)",
+            PROMPT_COLUMNS
+        ))
+        .bind(&prompt.name)
+        .bind(prompt.version as i32)
+        .bind(&prompt.template)
+        .bind(&prompt.system)
+        .bind(&prompt.description)
+        .bind(prompt.created_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| prompt_insert_error(e, prompt))?;
+
+        info!("Prompt version added: name={}, version={}", prompt.name, prompt.version);
+        Ok(())
+    }
+
+    async fn list_prompt_names(&self) -> StateStoreResult<Vec<String>> {
+        let rows = sqlx::query("SELECT DISTINCT name FROM prompt_versions ORDER BY name")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows.iter().map(|row| row.get("name")).collect())
+    }
+
+    async fn list_prompt_versions(&self, name: &str) -> StateStoreResult<Vec<PromptVersion>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM prompt_versions WHERE name = $1 ORDER BY version",
+            PROMPT_COLUMNS
+        ))
+        .bind(name)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(Self::prompt_version_from_row).collect())
+    }
+
+    async fn promote_prompt_version(&self, name: &str, version: u32) -> StateStoreResult<()> {
+        let exists = sqlx::query("SELECT 1 FROM prompt_versions WHERE name = $1 AND version = $2")
+            .bind(name)
+            .bind(version as i32)
+            .fetch_optional(&self.pool)
+            .await?
+            .is_some();
+        if !exists {
+            return Err(StateStoreError::NotFound(format!("Prompt {} version {} not found", name, version)));
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO promoted_prompts (name, version, promoted_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (name) DO UPDATE SET version = excluded.version, promoted_at = excluded.promoted_at
+            "#
+        )
+        .bind(name)
+        .bind(version as i32)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+
+        info!("Prompt version promoted: name={}, version={}", name, version);
+        Ok(())
+    }
+
+    async fn promoted_prompt_version(&self, name: &str) -> StateStoreResult<Option<u32>> {
+        let row = sqlx::query("SELECT version FROM promoted_prompts WHERE name = $1")
+            .bind(name)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|row| row.get::<i32, _>("version") as u32))
+    }
+}
+
+#[async_trait]
+impl SessionMemoryStore for PostgresStateStore {
+    async fn get_session_memory(&self, session_id: &str, key: &str) -> StateStoreResult<Option<serde_json::Value>> {
+        let row = sqlx::query("SELECT value FROM session_memory WHERE session_id = $1 AND memory_key = $2")
+            .bind(session_id)
+            .bind(key)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        row.map(|row| serde_json::from_str(&row.get::<String, _>("value")).map_err(Into::into))
+            .transpose()
+    }
+
+    async fn put_session_memory(&self, session_id: &str, key: &str, value: &serde_json::Value) -> StateStoreResult<()> {
+        debug!("Writing session memory: session={}, key={}", session_id, key);
+
+        sqlx::query(
+            r#"
+            INSERT INTO session_memory (session_id, memory_key, value, updated_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (session_id, memory_key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at
+            "#
+        )
+        .bind(session_id)
+        .bind(key)
+        .bind(serde_json::to_string(value)?)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn append_session_memory(
+        &self,
+        session_id: &str,
+        key: &str,
+        entries: &[serde_json::Value],
+    ) -> StateStoreResult<usize> {
+        debug!("Appending to session memory: session={}, key={}, entries={}", session_id, key, entries.len());
+
+        // Create the list first so the row exists to be locked
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(
+            r#"
+            INSERT INTO session_memory (session_id, memory_key, value, updated_at)
+            VALUES ($1, $2, '[]', $3)
+            ON CONFLICT (session_id, memory_key) DO NOTHING
+            "#
+        )
+        .bind(session_id)
+        .bind(key)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        let row = sqlx::query("SELECT value FROM session_memory WHERE session_id = $1 AND memory_key = $2 FOR UPDATE")
+            .bind(session_id)
+            .bind(key)
+            .fetch_one(&mut *tx)
+            .await?;
+        let (value, len) = append_memory_entries(session_id, key, &row.get::<String, _>("value"), entries)?;
+
+        sqlx::query("UPDATE session_memory SET value = $1, updated_at = $2 WHERE session_id = $3 AND memory_key = $4")
+            .bind(value)
+            .bind(Utc::now())
+            .bind(session_id)
+            .bind(key)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(len)
+    }
+}
+
+const DEFINITION_COLUMNS: &str = "name, version, definition, checksum, created_by, created_at, deprecated_at";
+
+impl PostgresStateStore {
+    /// Convert a `workflow_definitions` row into a workflow definition.
+    fn workflow_definition_from_row(row: &PgRow) -> WorkflowDefinition {
+        WorkflowDefinition {
+            name: row.get("name"),
+            version: row.get("version"),
+            definition: row.get("definition"),
+            checksum: row.get("checksum"),
+            created_by: row.get("created_by"),
+            created_at: row.get("created_at"),
+            deprecated_at: row.get("deprecated_at"),
+        }
+    }
+}
+
+#[async_trait]
+impl WorkflowDefinitionStore for PostgresStateStore {
+    async fn publish_workflow_definition(&self, definition: &WorkflowDefinition) -> StateStoreResult<()> {
+        debug!("Publishing workflow definition: name={}, version={}", definition.name, definition.version);
+
+        sqlx::query(&format!(
+            "INSERT INTO workflow_definitions ({}) VALUES ($1, $2, $3, $4, $5, $6, $7)",
+            DEFINITION_COLUMNS
+        ))
+        .bind(&definition.name)
+        .bind(&definition.version)
+        .bind(&definition.definition)
+        .bind(&definition.checksum)
+        .bind(&definition.created_by)
+        .bind(definition.created_at)
+        .bind(definition.deprecated_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| definition_insert_error(e, definition))?;
+
+        info!(
+            "Workflow definition published: name={}, version={}, checksum={}",
+            definition.name, definition.version, definition.checksum
+        );
+        Ok(())
+    }
+
+    async fn list_workflow_definitions(&self, include_deprecated: bool) -> StateStoreResult<Vec<WorkflowDefinition>> {
+        let filter = if include_deprecated { "" } else { "WHERE deprecated_at IS NULL" };
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM workflow_definitions {} ORDER BY name, created_at",
+            DEFINITION_COLUMNS, filter
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(Self::workflow_definition_from_row).collect())
+    }
+
+    async fn get_workflow_definition(&self, name: &str, version: Option<&str>) -> StateStoreResult<WorkflowDefinition> {
+        let row = match version {
+            Some(version) => {
+                sqlx::query(&format!(
+                    "SELECT {} FROM workflow_definitions WHERE name = $1 AND version = $2",
+                    DEFINITION_COLUMNS
+                ))
+                .bind(name)
+                .bind(version)
+                .fetch_optional(&self.pool)
+                .await?
+            }
+            None => {
+                sqlx::query(&format!(
+                    r#"
+                    SELECT {} FROM workflow_definitions
+                    WHERE name = $1 AND deprecated_at IS NULL
+                    ORDER BY created_at DESC
+                    LIMIT 1
+                    "#,
+                    DEFINITION_COLUMNS
+                ))
+                .bind(name)
+                .fetch_optional(&self.pool)
+                .await?
+            }
+        };
+
+        row.as_ref()
+            .map(Self::workflow_definition_from_row)
+            .ok_or_else(|| definition_not_found(name, version))
+    }
+
+    async fn deprecate_workflow_definition(&self, name: &str, version: &str) -> StateStoreResult<()> {
+        let result = sqlx::query(
+            "UPDATE workflow_definitions SET deprecated_at = COALESCE(deprecated_at, $1) WHERE name = $2 AND version = $3",
+        )
+        .bind(Utc::now())
+        .bind(name)
+        .bind(version)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(definition_not_found(name, Some(version)));
+        }
+        info!("Workflow definition deprecated: name={}, version={}", name, version);
+        Ok(())
+    }
+}
+
+impl PostgresStateStore {
+    /// Convert a `run_usage` row, or a sum of them, into model usage.
+    fn model_usage_from_row(row: &PgRow) -> ModelUsage {
+        let count = |column: &str| row.get::<i64, _>(column) as u64;
+        ModelUsage {
+            provider: row.get("provider"),
+            model: row.get("model"),
+            requests: count("requests"),
+            failed_requests: count("failed_requests"),
+            input_tokens: count("input_tokens"),
+            output_tokens: count("output_tokens"),
+            total_tokens: count("total_tokens"),
+            cost_usd: row.get("cost_usd"),
+        }
+    }
+}
+
+#[async_trait]
+impl UsageStore for PostgresStateStore {
+    async fn usage_summary(&self, query: &RunQuery) -> StateStoreResult<Vec<UsageSummary>> {
+        debug!("Summarizing usage: {:?}", query);
+
+        let mut conditions = String::from(" WHERE 1=1");
+        let params = push_run_conditions(query, &mut conditions, |n| format!("${}", n));
+        let sql = format!(
+            r#"
+            SELECT u.provider, u.model, COUNT(*) AS runs,
+                   CAST(SUM(u.requests) AS BIGINT) AS requests,
+                   CAST(SUM(u.failed_requests) AS BIGINT) AS failed_requests,
+                   CAST(SUM(u.input_tokens) AS BIGINT) AS input_tokens,
+                   CAST(SUM(u.output_tokens) AS BIGINT) AS output_tokens,
+                   CAST(SUM(u.total_tokens) AS BIGINT) AS total_tokens,
+                   CAST(SUM(u.cost_usd) AS DOUBLE PRECISION) AS cost_usd
+            FROM run_usage u
+            JOIN workflow_states w ON w.id = u.workflow_state_id{}
+            GROUP BY u.provider, u.model
+            ORDER BY u.provider, u.model
+            "#,
+            conditions
+        );
+        let mut summary_query = sqlx::query(&sql);
+        for param in &params {
+            summary_query = match param {
+                RunQueryParam::Text(value) => summary_query.bind(value),
+                RunQueryParam::Time(value) => summary_query.bind(value),
+            };
+        }
+        let rows = summary_query.fetch_all(&self.pool).await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| UsageSummary {
+                runs: row.get::<i64, _>("runs") as u64,
+                usage: Self::model_usage_from_row(row),
+            })
+            .collect())
+    }
+}
+
+/// Finish time of a run, for runs persisted before completion times were recorded too.
+const FINISHED_AT: &str = "COALESCE(completed_at, updated_at)";
+
+impl PostgresStateStore {
+    /// Convert a `workflow_states` row into a run summary.
+    fn run_summary_from_row(row: &PgRow) -> StateStoreResult<RunSummary> {
+        let id: Uuid = row.get("id");
+        let status_str: String = row.get("status");
+        let status = WorkflowStatus::from_str(&status_str)
+            .map_err(StateStoreError::InvalidState)?;
+
+        Ok(RunSummary {
+            id,
+            workflow_id: row.get("workflow_id"),
+            workflow_name: row.get("workflow_name"),
+            status,
+            user_id: row.get("user_id"),
+            tenant_id: row.get("tenant_id"),
+            started_at: row.get("started_at"),
+            updated_at: row.get("updated_at"),
+            completed_at: row.get("completed_at"),
+            error: row.get("error"),
+        })
+    }
+}
+
+#[async_trait]
+impl RetentionStore for PostgresStateStore {
+    async fn list_finished_runs(
+        &self,
+        status: WorkflowStatus,
+        finished_before: DateTime<Utc>,
+    ) -> StateStoreResult<Vec<RunSummary>> {
+        debug!("Listing {} runs finished before {}", status, finished_before);
+
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT id, workflow_id, workflow_name, status, user_id, tenant_id,
+                   started_at, updated_at, completed_at, error
+            FROM workflow_states
+            WHERE status = $1 AND {finished} < $2
+            ORDER BY {finished} ASC
+            "#,
+            finished = FINISHED_AT
+        ))
+        .bind(status.to_string())
+        .bind(finished_before)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(Self::run_summary_from_row).collect()
+    }
+
+    async fn delete_runs(&self, ids: &[Uuid]) -> StateStoreResult<u64> {
+        let mut deleted = 0;
+        for id in ids {
+            // Blobs in the database go with their run; object-store blobs are deleted here
+            if self.offload.as_ref().is_some_and(|o| o.backend().is_some()) {
+                let references: Vec<String> = sqlx::query_scalar(
+                    "SELECT outputs FROM step_states WHERE workflow_state_id = $1 AND outputs LIKE $2"
+                )
+                .bind(id)
+                .bind(reference_pattern())
+                .fetch_all(&self.pool)
+                .await?;
+                delete_external(self.offload.as_ref(), &references).await;
+            }
+
+            deleted += sqlx::query("DELETE FROM workflow_states WHERE id = $1")
+                .bind(id)
+                .execute(&self.pool)
+                .await?
+                .rows_affected();
+        }
+
+        debug!("Deleted {} workflow states", deleted);
+        Ok(deleted)
+    }
+
+    async fn compress_contexts(&self, finished_before: DateTime<Utc>, dry_run: bool) -> StateStoreResult<u64> {
+        debug!("Compressing contexts of runs finished before {}", finished_before);
+
+        // Encrypted contexts only reveal whether they are compressed once decrypted
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT id, context
+            FROM workflow_states
+            WHERE status IN ('completed', 'failed', 'cancelled')
+              AND {} < $1
+              AND context NOT LIKE $2
+            "#,
+            FINISHED_AT
+        ))
+        .bind(finished_before)
+        .bind(compressed_pattern())
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut compressed = 0;
+        for row in rows {
+            let context_str: String = row.get("context");
+            let context = decrypt_column(self.encryption.as_ref(), &context_str)?;
+            if is_compressed(&context) {
+                continue;
+            }
+            compressed += 1;
+            if dry_run {
+                continue;
+            }
+
+            sqlx::query("UPDATE workflow_states SET context = $1 WHERE id = $2")
+                .bind(encode_column(self.encryption.as_ref(), &compress(&context)?)?)
+                .bind(row.get::<Uuid, _>("id"))
+                .execute(&self.pool)
+                .await?;
+        }
+
+        if compressed > 0 && !dry_run {
+            info!("Compressed {} run contexts", compressed);
+        }
+        Ok(compressed)
+    }
+}
+
+#[async_trait]
+impl SchemaMigrations for PostgresStateStore {
+    async fn migration_status(&self) -> StateStoreResult<Vec<MigrationStatus>> {
+        let mut conn = self.pool.acquire().await?;
+
+        // Databases created before migrations were tracked have no table yet
+        let tracked: bool = sqlx::query_scalar("SELECT to_regclass($1) IS NOT NULL")
+            .bind(MIGRATIONS_TABLE)
+            .fetch_one(&mut *conn)
+            .await?;
+        let applied = if tracked {
+            conn.list_applied_migrations().await.map_err(migration_error)?
+        } else {
+            Vec::new()
+        };
+
+        migration_status(&applied)
+    }
+
+    async fn migrate(&self) -> StateStoreResult<Vec<MigrationStatus>> {
+        let pending: Vec<_> = self.migration_status().await?.into_iter().filter(|m| !m.applied).collect();
+        if pending.is_empty() {
+            debug!("Database schema is up to date");
+            return Ok(pending);
+        }
+
+        info!("Applying {} database migrations", pending.len());
+        MIGRATOR.run(&self.pool).await.map_err(migration_error)?;
+        info!("Database migrations completed successfully");
+
+        Ok(pending
+            .into_iter()
+            .map(|m| MigrationStatus { applied: true, ..m })
+            .collect())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,248 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! Retention of finished runs.
+//!
+//! A [`StateRetentionManager`] applies a [`RetentionPolicy`] to the runs in a
+//! store: completed runs and failed (or cancelled) runs are deleted after
+//! their own retention periods, and the contexts of runs kept longer can be
+//! compressed in place. Active runs are never touched.
+
+use crate::models::{RunSummary, WorkflowStatus};
+use crate::traits::{RetentionStore, StateStoreResult};
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::time;
+
+/// How long finished runs are kept. Unset periods keep runs forever.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    /// Days to keep completed runs.
+    pub completed_days: Option<u32>,
+    /// Days to keep failed and cancelled runs.
+    pub failed_days: Option<u32>,
+    /// Days after which the contexts of finished runs are compressed.
+    pub compress_after_days: Option<u32>,
+}
+
+impl RetentionPolicy {
+    /// Create a policy that keeps every run uncompressed.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Delete completed runs after `days`.
+    pub fn with_completed_days(mut self, days: u32) -> Self {
+        self.completed_days = Some(days);
+        self
+    }
+
+    /// Delete failed and cancelled runs after `days`.
+    pub fn with_failed_days(mut self, days: u32) -> Self {
+        self.failed_days = Some(days);
+        self
+    }
+
+    /// Compress the contexts of finished runs after `days`.
+    pub fn with_compress_after_days(mut self, days: u32) -> Self {
+        self.compress_after_days = Some(days);
+        self
+    }
+}
+
+/// Outcome of applying a retention policy.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RetentionReport {
+    /// Whether nothing was changed, only reported.
+    pub dry_run: bool,
+    /// Runs deleted, or that would be deleted, oldest first per status.
+    pub purged: Vec<RunSummary>,
+    /// Number of run contexts compressed, or that would be compressed.
+    pub compressed: u64,
+}
+
+/// Applies a retention policy to persisted runs.
+pub struct StateRetentionManager {
+    store: Arc<dyn RetentionStore>,
+    policy: RetentionPolicy,
+}
+
+impl StateRetentionManager {
+    /// Create a new retention manager
+    pub fn new(store: Arc<dyn RetentionStore>, policy: RetentionPolicy) -> Self {
+        Self { store, policy }
+    }
+
+    /// Get the retention policy
+    pub fn policy(&self) -> &RetentionPolicy {
+        &self.policy
+    }
+
+    /// Delete and compress runs as the policy says
+    pub async fn cleanup(&self) -> StateStoreResult<RetentionReport> {
+        self.apply(false).await
+    }
+
+    /// Report what [`cleanup`](Self::cleanup) would delete and compress, without changing anything
+    pub async fn dry_run(&self) -> StateStoreResult<RetentionReport> {
+        self.apply(true).await
+    }
+
+    async fn apply(&self, dry_run: bool) -> StateStoreResult<RetentionReport> {
+        let mut report = RetentionReport {
+            dry_run,
+            ..Default::default()
+        };
+
+        let periods = [
+            (WorkflowStatus::Completed, self.policy.completed_days),
+            (WorkflowStatus::Failed, self.policy.failed_days),
+            (WorkflowStatus::Cancelled, self.policy.failed_days),
+        ];
+        for (status, days) in periods {
+            let Some(cutoff) = days.map(cutoff_date) else {
+                continue;
+            };
+            let runs = self.store.list_finished_runs(status, cutoff).await?;
+            if !dry_run && !runs.is_empty() {
+                let ids: Vec<_> = runs.iter().map(|run| run.id).collect();
+                self.store.delete_runs(&ids).await?;
+            }
+            report.purged.extend(runs);
+        }
+
+        // Runs purged above are gone, so only kept runs are compressed
+        if let Some(days) = self.policy.compress_after_days {
+            report.compressed = self.store.compress_contexts(cutoff_date(days), dry_run).await?;
+        }
+
+        tracing::info!(
+            dry_run,
+            purged_count = report.purged.len(),
+            compressed_count = report.compressed,
+            "Run retention completed"
+        );
+
+        Ok(report)
+    }
+
+    /// Start background cleanup task
+    /// Returns a handle that can be used to cancel the task
+    pub fn start_background_cleanup(self: Arc<Self>, interval: time::Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval_timer = time::interval(interval);
+
+            loop {
+                interval_timer.tick().await;
+
+                match self.cleanup().await {
+                    Ok(report) => {
+                        tracing::debug!(purged_count = report.purged.len(), "Background run retention completed");
+                    }
+                    Err(e) => {
+                        tracing::error!(error = %e, "Background run retention failed");
+                    }
+                }
+            }
+        })
+    }
+}
+
+/// Finish time before which runs are past a retention period of `days`.
+fn cutoff_date(days: u32) -> DateTime<Utc> {
+    Utc::now() - Duration::days(days as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::WorkflowState;
+    use crate::sqlite::SqliteStateStore;
+    use crate::traits::StateStore;
+    use serde_json::json;
+    use std::time::Duration as StdDuration;
+
+    /// Save a run that finished `days` ago with `status`.
+    async fn finished_run(store: &SqliteStateStore, status: WorkflowStatus, days: i64) -> uuid::Uuid {
+        let mut state = WorkflowState::new("wf", format!("{}-{}", status, days), None, json!({"inputs": {"topic": "retention"}}));
+        match status {
+            WorkflowStatus::Completed => state.mark_completed(),
+            WorkflowStatus::Failed => state.mark_failed("boom"),
+            status => state.status = status,
+        }
+        let finished = Utc::now() - Duration::days(days);
+        state.updated_at = finished;
+        state.completed_at = state.completed_at.map(|_| finished);
+        store.save_workflow_state(&state).await.unwrap();
+        state.id
+    }
+
+    #[tokio::test]
+    async fn test_retention_purges_per_status() {
+        let store = Arc::new(SqliteStateStore::new(":memory:").await.unwrap());
+        let old_completed = finished_run(&store, WorkflowStatus::Completed, 40).await;
+        let recent_completed = finished_run(&store, WorkflowStatus::Completed, 10).await;
+        let old_failed = finished_run(&store, WorkflowStatus::Failed, 10).await;
+        let recent_failed = finished_run(&store, WorkflowStatus::Failed, 1).await;
+        let running = finished_run(&store, WorkflowStatus::Running, 400).await;
+
+        let manager = StateRetentionManager::new(
+            store.clone(),
+            RetentionPolicy::new().with_completed_days(30).with_failed_days(7),
+        );
+
+        // A dry run reports the runs without deleting them
+        let report = manager.dry_run().await.unwrap();
+        assert!(report.dry_run);
+        let purged: Vec<_> = report.purged.iter().map(|run| run.id).collect();
+        assert_eq!(purged, vec![old_completed, old_failed]);
+        assert!(store.load_workflow_state(&old_completed).await.is_ok());
+
+        let report = manager.cleanup().await.unwrap();
+        assert_eq!(report.purged.len(), 2);
+        assert!(store.load_workflow_state(&old_completed).await.is_err());
+        assert!(store.load_workflow_state(&old_failed).await.is_err());
+        for kept in [recent_completed, recent_failed, running] {
+            assert!(store.load_workflow_state(&kept).await.is_ok());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retention_compresses_old_contexts() {
+        let store = Arc::new(SqliteStateStore::new(":memory:").await.unwrap());
+        let old = finished_run(&store, WorkflowStatus::Completed, 10).await;
+        let recent = finished_run(&store, WorkflowStatus::Completed, 1).await;
+
+        let manager = StateRetentionManager::new(store.clone(), RetentionPolicy::new().with_compress_after_days(7));
+        assert_eq!(manager.dry_run().await.unwrap().compressed, 1);
+        assert_eq!(manager.cleanup().await.unwrap().compressed, 1);
+        assert_eq!(manager.cleanup().await.unwrap().compressed, 0);
+
+        let raw: String = sqlx::query_scalar("SELECT context FROM workflow_states WHERE id = ?1")
+            .bind(old.to_string())
+            .fetch_one(store.pool())
+            .await
+            .unwrap();
+        assert!(raw.starts_with("{\"$compressed\""));
+
+        // Compressed contexts read back unchanged
+        for id in [old, recent] {
+            let state = store.load_workflow_state(&id).await.unwrap();
+            assert_eq!(state.context["inputs"]["topic"], "retention");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_background_retention() {
+        let store = Arc::new(SqliteStateStore::new(":memory:").await.unwrap());
+        let old = finished_run(&store, WorkflowStatus::Cancelled, 10).await;
+
+        let manager = Arc::new(StateRetentionManager::new(store.clone(), RetentionPolicy::new().with_failed_days(1)));
+        let handle = manager.start_background_cleanup(StdDuration::from_millis(100));
+        tokio::time::sleep(StdDuration::from_millis(200)).await;
+        handle.abort();
+
+        assert!(store.load_workflow_state(&old).await.is_err());
+    }
+}
@@ -0,0 +1,131 @@
+// Copyright (c) 2025 LLM DevOps
+// SPDX-License-Identifier: Apache-2.0
+
+//! State store confined to one tenant.
+
+use crate::models::{Checkpoint, RunPage, RunQuery, WorkflowState};
+use crate::traits::{StateStore, StateStoreError, StateStoreResult};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Wraps a state store so that every run, checkpoint and run listing is
+/// confined to one tenant.
+///
+/// Runs of other tenants are reported as not found, listings are always
+/// filtered by the tenant whatever the query says, and saving a run that
+/// does not carry the tenant fails. Deleting old states spans all tenants and
+/// is rejected; run it against the unscoped store.
+pub struct TenantStateStore {
+    inner: Arc<dyn StateStore>,
+    tenant_id: String,
+}
+
+impl TenantStateStore {
+    /// Confine `inner` to the runs of `tenant_id`.
+    pub fn new(inner: Arc<dyn StateStore>, tenant_id: impl Into<String>) -> Self {
+        Self {
+            inner,
+            tenant_id: tenant_id.into(),
+        }
+    }
+
+    /// Tenant this store is confined to.
+    pub fn tenant_id(&self) -> &str {
+        &self.tenant_id
+    }
+
+    fn owns(&self, state: &WorkflowState) -> bool {
+        state.tenant_id.as_deref() == Some(self.tenant_id.as_str())
+    }
+
+    fn owned(&self, state: WorkflowState) -> StateStoreResult<WorkflowState> {
+        if self.owns(&state) {
+            Ok(state)
+        } else {
+            Err(StateStoreError::NotFound(format!("Workflow state {}", state.id)))
+        }
+    }
+
+    /// Fails unless the run `id` exists and belongs to the tenant.
+    async fn check_run(&self, id: &Uuid) -> StateStoreResult<()> {
+        self.load_workflow_state(id).await.map(|_| ())
+    }
+}
+
+#[async_trait]
+impl StateStore for TenantStateStore {
+    async fn save_workflow_state(&self, state: &WorkflowState) -> StateStoreResult<()> {
+        if !self.owns(state) {
+            return Err(StateStoreError::TenantViolation(format!(
+                "Workflow state {} does not belong to tenant {}",
+                state.id, self.tenant_id
+            )));
+        }
+        // Updates must not overwrite another tenant's run with the same ID
+        match self.inner.load_workflow_state(&state.id).await {
+            Ok(existing) if !self.owns(&existing) => {
+                return Err(StateStoreError::TenantViolation(format!(
+                    "Workflow state {} belongs to another tenant",
+                    state.id
+                )));
+            }
+            Ok(_) | Err(StateStoreError::NotFound(_)) => {}
+            Err(e) => return Err(e),
+        }
+        self.inner.save_workflow_state(state).await
+    }
+
+    async fn load_workflow_state(&self, id: &Uuid) -> StateStoreResult<WorkflowState> {
+        self.owned(self.inner.load_workflow_state(id).await?)
+    }
+
+    async fn load_workflow_state_by_workflow_id(&self, workflow_id: &str) -> StateStoreResult<WorkflowState> {
+        self.owned(self.inner.load_workflow_state_by_workflow_id(workflow_id).await?)
+    }
+
+    async fn list_active_workflows(&self) -> StateStoreResult<Vec<WorkflowState>> {
+        let mut states = self.inner.list_active_workflows().await?;
+        states.retain(|state| self.owns(state));
+        Ok(states)
+    }
+
+    async fn list_workflow_runs(&self, query: &RunQuery) -> StateStoreResult<RunPage> {
+        let query = query.clone().tenant_id(self.tenant_id.clone());
+        self.inner.list_workflow_runs(&query).await
+    }
+
+    async fn create_checkpoint(&self, checkpoint: &Checkpoint) -> StateStoreResult<()> {
+        self.check_run(&checkpoint.workflow_state_id).await?;
+        self.inner.create_checkpoint(checkpoint).await
+    }
+
+    async fn get_latest_checkpoint(&self, workflow_state_id: &Uuid) -> StateStoreResult<Option<Checkpoint>> {
+        self.check_run(workflow_state_id).await?;
+        self.inner.get_latest_checkpoint(workflow_state_id).await
+    }
+
+    async fn restore_from_checkpoint(&self, checkpoint_id: &Uuid) -> StateStoreResult<WorkflowState> {
+        let state = self.inner.restore_from_checkpoint(checkpoint_id).await?;
+        // Snapshots taken before tenants were recorded lack the field, so ask the run itself
+        self.check_run(&state.id).await?;
+        Ok(state)
+    }
+
+    async fn delete_old_states(&self, _older_than: DateTime<Utc>) -> StateStoreResult<u64> {
+        Err(StateStoreError::TenantViolation(format!(
+            "Deleting old states applies to all tenants and cannot run for tenant {}",
+            self.tenant_id
+        )))
+    }
+
+    async fn cleanup_old_checkpoints(&self, workflow_state_id: &Uuid, keep_count: usize) -> StateStoreResult<u64> {
+        self.check_run(workflow_state_id).await?;
+        self.inner.cleanup_old_checkpoints(workflow_state_id, keep_count).await
+    }
+
+    async fn health_check(&self) -> StateStoreResult<()> {
+        self.inner.health_check().await
+    }
+}
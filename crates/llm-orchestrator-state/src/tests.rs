@@ -15,6 +15,7 @@ mod unit_tests {
         assert_eq!(WorkflowStatus::Paused.to_string(), "paused");
         assert_eq!(WorkflowStatus::Completed.to_string(), "completed");
         assert_eq!(WorkflowStatus::Failed.to_string(), "failed");
+        assert_eq!(WorkflowStatus::Cancelled.to_string(), "cancelled");
     }
 
     #[test]
@@ -43,6 +44,7 @@ mod unit_tests {
         assert_eq!(StepStatus::from_str("pending").unwrap(), StepStatus::Pending);
         assert_eq!(StepStatus::from_str("RUNNING").unwrap(), StepStatus::Running);
         assert_eq!(StepStatus::from_str("Skipped").unwrap(), StepStatus::Skipped);
+        assert_eq!(StepStatus::from_str("cancelled").unwrap(), StepStatus::Cancelled);
         assert!(StepStatus::from_str("invalid").is_err());
     }
 
@@ -258,7 +260,19 @@ mod unit_tests {
 #[cfg(test)]
 mod sqlite_integration_tests {
     use crate::{StateStore, SqliteStateStore, WorkflowState, Checkpoint};
+    use crate::{QueueTask, QueueTaskStatus, RunPriority, WorkQueue};
+    use crate::{DeadLetter, DeadLetterAttempt, DeadLetterQueue, StateStoreError};
+    use crate::{PromptVersion, PromptVersionStore, SessionMemoryStore};
+    use crate::{WorkflowDefinition, WorkflowDefinitionStore};
+    use crate::{RunQuery, RunSortField, WorkflowStatus};
+    use crate::{StateEncryption, StepState};
+    use crate::{SchemaMigrations, MIGRATOR};
+    use crate::{FileBlobStore, OutputOffload};
+    use crate::{ModelUsage, UsageStore};
+    use crate::TenantStateStore;
+    use std::sync::Arc;
     use serde_json::json;
+    use std::time::Duration;
     
 
     #[tokio::test]
@@ -454,4 +468,575 @@ mod sqlite_integration_tests {
         assert_eq!(loaded.steps.get("step-1").unwrap().status, crate::StepStatus::Completed);
         assert_eq!(loaded.steps.get("step-2").unwrap().status, crate::StepStatus::Running);
     }
+
+    #[tokio::test]
+    async fn test_work_queue_claim_and_complete() {
+        let store = SqliteStateStore::new(":memory:").await.unwrap();
+
+        let first = QueueTask::new(json!({"run": 1}));
+        let mut second = QueueTask::new(json!({"run": 2}));
+        second.enqueued_at = first.enqueued_at + chrono::Duration::seconds(1);
+        store.enqueue_task(&first).await.unwrap();
+        store.enqueue_task(&second).await.unwrap();
+
+        // Oldest task is claimed first, and each task only once
+        let claimed = store.claim_task("worker-a", Duration::from_secs(30)).await.unwrap().unwrap();
+        assert_eq!(claimed.id, first.id);
+        assert_eq!(claimed.status, QueueTaskStatus::Running);
+        assert_eq!(claimed.worker_id.as_deref(), Some("worker-a"));
+        assert_eq!(claimed.attempts, 1);
+        assert_eq!(claimed.payload, json!({"run": 1}));
+
+        assert_eq!(store.count_tasks(QueueTaskStatus::Queued).await.unwrap(), 1);
+        let claimed_b = store.claim_task("worker-b", Duration::from_secs(30)).await.unwrap().unwrap();
+        assert_eq!(claimed_b.id, second.id);
+        assert_eq!(store.count_tasks(QueueTaskStatus::Queued).await.unwrap(), 0);
+        assert_eq!(store.count_tasks(QueueTaskStatus::Running).await.unwrap(), 2);
+        assert!(store.claim_task("worker-c", Duration::from_secs(30)).await.unwrap().is_none());
+
+        // Only the lease holder can heartbeat or complete
+        assert!(store.heartbeat_task(&first.id, "worker-a", Duration::from_secs(30)).await.unwrap());
+        assert!(!store.heartbeat_task(&first.id, "worker-b", Duration::from_secs(30)).await.unwrap());
+        assert!(store.complete_task(&first.id, "worker-b").await.is_err());
+        store.complete_task(&first.id, "worker-a").await.unwrap();
+
+        store.fail_task(&second.id, "worker-b", "boom").await.unwrap();
+        let failed = store.get_task(&second.id).await.unwrap();
+        assert_eq!(failed.status, QueueTaskStatus::Failed);
+        assert_eq!(failed.error.as_deref(), Some("boom"));
+    }
+
+    #[tokio::test]
+    async fn test_work_queue_recovers_expired_leases() {
+        let store = SqliteStateStore::new(":memory:").await.unwrap();
+
+        let task = QueueTask::new(json!({}));
+        store.enqueue_task(&task).await.unwrap();
+
+        // Simulate a worker that crashed right after claiming
+        store.claim_task("crashed", Duration::from_millis(0)).await.unwrap().unwrap();
+        tokio::time::sleep(Duration::from_millis(5)).await;
+
+        assert_eq!(store.recover_expired_tasks().await.unwrap(), 1);
+        let recovered = store.get_task(&task.id).await.unwrap();
+        assert_eq!(recovered.status, QueueTaskStatus::Queued);
+        assert!(recovered.worker_id.is_none());
+
+        let reclaimed = store.claim_task("healthy", Duration::from_secs(30)).await.unwrap().unwrap();
+        assert_eq!(reclaimed.id, task.id);
+        assert_eq!(reclaimed.attempts, 2);
+        assert!(!store.heartbeat_task(&task.id, "crashed", Duration::from_secs(30)).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_dead_letter_roundtrip() {
+        let store = SqliteStateStore::new(":memory:").await.unwrap();
+
+        let mut entry = DeadLetter::new(
+            uuid::Uuid::new_v4(),
+            "Test Workflow",
+            "summarize",
+            json!({"id": "summarize", "type": "llm"}),
+            json!({"inputs": {"topic": "rust"}, "outputs": {}}),
+            "Provider 'openai' error: rate limited",
+        );
+        entry.request = Some(json!({"model": "gpt-4", "prompt": "Summarize rust"}));
+        entry.attempts = vec![DeadLetterAttempt {
+            attempt: 1,
+            error: "rate limited".to_string(),
+            failed_at: chrono::Utc::now(),
+        }];
+        store.add_dead_letter(&entry).await.unwrap();
+
+        let loaded = store.get_dead_letter(&entry.id).await.unwrap();
+        assert_eq!(loaded.run_id, entry.run_id);
+        assert_eq!(loaded.request, entry.request);
+        assert_eq!(loaded.attempts, entry.attempts);
+        assert!(!loaded.is_resolved());
+        assert_eq!(store.list_dead_letters(false).await.unwrap().len(), 1);
+
+        // Resolved entries are hidden unless requested
+        store.resolve_dead_letter(&entry.id).await.unwrap();
+        assert!(store.list_dead_letters(false).await.unwrap().is_empty());
+        assert!(store.list_dead_letters(true).await.unwrap()[0].is_resolved());
+
+        assert!(matches!(
+            store.get_dead_letter(&uuid::Uuid::new_v4()).await,
+            Err(StateStoreError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_prompt_versions_and_promotion() {
+        let store = SqliteStateStore::new(":memory:").await.unwrap();
+
+        let mut v1 = PromptVersion::new("summarize", 1, "Summarize {{ text }}");
+        v1.system = Some("Be brief.".to_string());
+        store.add_prompt_version(&v1).await.unwrap();
+        let v2 = PromptVersion::new("summarize", 2, "Summarize {{ text }} in one line");
+        store.add_prompt_version(&v2).await.unwrap();
+        store.add_prompt_version(&PromptVersion::new("classify", 1, "Classify {{ text }}")).await.unwrap();
+
+        // Versions are immutable once written
+        assert!(matches!(
+            store.add_prompt_version(&v2).await,
+            Err(StateStoreError::InvalidState(_))
+        ));
+
+        assert_eq!(store.list_prompt_names().await.unwrap(), vec!["classify", "summarize"]);
+        let versions = store.list_prompt_versions("summarize").await.unwrap();
+        assert_eq!(versions.len(), 2);
+        assert_eq!(versions[0].system.as_deref(), Some("Be brief."));
+        assert_eq!(versions[1].template, v2.template);
+
+        assert_eq!(store.promoted_prompt_version("summarize").await.unwrap(), None);
+        store.promote_prompt_version("summarize", 2).await.unwrap();
+        store.promote_prompt_version("summarize", 1).await.unwrap();
+        assert_eq!(store.promoted_prompt_version("summarize").await.unwrap(), Some(1));
+        assert!(matches!(
+            store.promote_prompt_version("summarize", 3).await,
+            Err(StateStoreError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_session_memory() {
+        let store = SqliteStateStore::new(":memory:").await.unwrap();
+
+        assert_eq!(store.get_session_memory("s1", "chat").await.unwrap(), None);
+        assert_eq!(store.append_session_memory("s1", "chat", &[json!({"role": "user"})]).await.unwrap(), 1);
+        assert_eq!(store.append_session_memory("s1", "chat", &[json!(2), json!(3)]).await.unwrap(), 3);
+        assert_eq!(
+            store.get_session_memory("s1", "chat").await.unwrap(),
+            Some(json!([{"role": "user"}, 2, 3]))
+        );
+        assert_eq!(store.get_session_memory("s2", "chat").await.unwrap(), None);
+
+        // Writes replace the value; only lists can be appended to
+        store.put_session_memory("s1", "chat", &json!({"name": "Ada"})).await.unwrap();
+        assert_eq!(store.get_session_memory("s1", "chat").await.unwrap(), Some(json!({"name": "Ada"})));
+        assert!(matches!(
+            store.append_session_memory("s1", "chat", &[json!(4)]).await,
+            Err(StateStoreError::InvalidState(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_schema_migrations() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.db");
+        let store = SqliteStateStore::connect(&path).await.unwrap();
+
+        let status = store.migration_status().await.unwrap();
+        assert_eq!(status.len(), MIGRATOR.iter().count());
+        assert!(status.iter().all(|m| !m.applied));
+        assert_eq!(status[0].version, 1);
+        assert_eq!(status[0].description, "initial schema");
+
+        // Pending migrations are applied once and recorded
+        let applied = store.migrate().await.unwrap();
+        assert_eq!(applied.len(), status.len());
+        assert!(store.migration_status().await.unwrap().iter().all(|m| m.applied));
+        assert!(store.migrate().await.unwrap().is_empty());
+        store.health_check().await.unwrap();
+
+        // Reopening an up-to-date database applies nothing
+        drop(store);
+        let store = SqliteStateStore::new(&path).await.unwrap();
+        assert!(store.migration_status().await.unwrap().iter().all(|m| m.applied));
+    }
+
+    #[tokio::test]
+    async fn test_migrations_adopt_untracked_schema() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("legacy.db");
+        let store = SqliteStateStore::connect(&path).await.unwrap();
+
+        // Databases created before migrations were tracked already have the tables
+        sqlx::raw_sql(include_str!("../migrations/001_initial_schema.sql"))
+            .execute(store.pool())
+            .await
+            .unwrap();
+        assert!(store.migration_status().await.unwrap().iter().all(|m| !m.applied));
+
+        store.migrate().await.unwrap();
+        assert!(store.migration_status().await.unwrap().iter().all(|m| m.applied));
+    }
+
+    #[tokio::test]
+    async fn test_step_output_offload() {
+        let store = SqliteStateStore::new(":memory:")
+            .await
+            .unwrap()
+            .with_output_offload(OutputOffload::new(256));
+
+        let embedding: Vec<f64> = (0..100).map(|i| i as f64 / 7.0).collect();
+        let mut state = WorkflowState::new("wf-embed", "Embed", None, json!({}));
+        let mut large = StepState::new("embed");
+        large.mark_completed(json!({"embedding": embedding}));
+        let mut small = StepState::new("classify");
+        small.mark_completed(json!({"label": "spam"}));
+        state.steps.insert("embed".to_string(), large);
+        state.steps.insert("classify".to_string(), small);
+        store.save_workflow_state(&state).await.unwrap();
+
+        // Only the large output is replaced by a reference
+        let outputs: Vec<(String, String)> =
+            sqlx::query_as("SELECT step_id, outputs FROM step_states ORDER BY step_id")
+                .fetch_all(store.pool())
+                .await
+                .unwrap();
+        assert_eq!(outputs[0].1, r#"{"label":"spam"}"#);
+        assert!(outputs[1].1.starts_with(r#"{"$blob""#));
+        let blobs: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM step_output_blobs")
+            .fetch_one(store.pool())
+            .await
+            .unwrap();
+        assert_eq!(blobs, 1);
+
+        let loaded = store.load_workflow_state(&state.id).await.unwrap();
+        assert_eq!(loaded.steps["embed"].outputs, json!({"embedding": embedding}));
+        assert_eq!(loaded.steps["classify"].outputs, json!({"label": "spam"}));
+    }
+
+    #[tokio::test]
+    async fn test_step_output_offload_to_blob_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let blobs = dir.path().join("blobs");
+        let store = SqliteStateStore::new(":memory:")
+            .await
+            .unwrap()
+            .with_encryption(StateEncryption::new("test-key", &[5u8; 32]).unwrap())
+            .with_output_offload(OutputOffload::new(64).with_backend(Arc::new(FileBlobStore::new(&blobs))));
+
+        let completion = "a long completion ".repeat(20);
+        let mut state = WorkflowState::new("wf-long", "Long", None, json!({}));
+        let mut step = StepState::new("draft");
+        step.mark_completed(json!({"text": completion}));
+        state.steps.insert("draft".to_string(), step);
+        state.mark_completed();
+        store.save_workflow_state(&state).await.unwrap();
+
+        // The blob is written encrypted to the object store
+        let path = blobs.join("step-outputs").join(state.id.to_string()).join("draft");
+        let data = std::fs::read_to_string(&path).unwrap();
+        assert!(data.contains("$encrypted"));
+        assert!(!data.contains("long completion"));
+
+        let loaded = store.load_workflow_state(&state.id).await.unwrap();
+        assert_eq!(loaded.steps["draft"].outputs["text"], json!(completion));
+
+        // Pruning the run removes its blobs
+        store.delete_old_states(chrono::Utc::now() + chrono::Duration::hours(1)).await.unwrap();
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_encryption_at_rest() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.db");
+        let encryption = StateEncryption::new("test-key", &[9u8; 32]).unwrap();
+        let store = SqliteStateStore::new(&path).await.unwrap().with_encryption(encryption.clone());
+
+        let mut state = WorkflowState::new("wf-secret", "Secret", None, json!({"inputs": {"doc": "merger terms"}}));
+        let mut step = StepState::new("summarize");
+        step.mark_completed(json!({"summary": "merger approved"}));
+        state.steps.insert("summarize".to_string(), step);
+        store.save_workflow_state(&state).await.unwrap();
+        store
+            .create_checkpoint(&Checkpoint::new(state.id, "summarize", serde_json::to_value(&state).unwrap()))
+            .await
+            .unwrap();
+
+        // Nothing readable is written to the JSON columns
+        let raw: (String, String, String) = sqlx::query_as(
+            "SELECT w.context, s.outputs, c.snapshot FROM workflow_states w \
+             JOIN step_states s ON s.workflow_state_id = w.id JOIN checkpoints c ON c.workflow_state_id = w.id",
+        )
+        .fetch_one(store.pool())
+        .await
+        .unwrap();
+        for column in [&raw.0, &raw.1, &raw.2] {
+            assert!(!column.contains("merger"), "plaintext in {}", column);
+        }
+
+        let loaded = store.load_workflow_state(&state.id).await.unwrap();
+        assert_eq!(loaded.context, state.context);
+        assert_eq!(loaded.steps["summarize"].outputs["summary"], "merger approved");
+        let checkpoint = store.get_latest_checkpoint(&state.id).await.unwrap().unwrap();
+        assert_eq!(store.restore_from_checkpoint(&checkpoint.id).await.unwrap().context, state.context);
+
+        // Without the key the data cannot be read
+        let plain = SqliteStateStore::new(&path).await.unwrap();
+        assert!(matches!(
+            plain.load_workflow_state(&state.id).await,
+            Err(StateStoreError::Encryption(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_list_workflow_runs() {
+        let store = SqliteStateStore::new(":memory:").await.unwrap();
+
+        let base = chrono::Utc::now() - chrono::Duration::days(1);
+        for (i, (name, user)) in [("ingest", "alice"), ("ingest", "bob"), ("report", "alice"), ("ingest", "alice")]
+            .into_iter()
+            .enumerate()
+        {
+            let mut state = WorkflowState::new(format!("wf-{}", i), name, Some(user.to_string()), json!({}));
+            state.started_at = base + chrono::Duration::hours(i as i64);
+            if i == 1 {
+                state.mark_failed("provider timeout");
+            } else {
+                state.mark_completed();
+            }
+            store.save_workflow_state(&state).await.unwrap();
+        }
+
+        // Newest first by default
+        let page = store.list_workflow_runs(&RunQuery::new()).await.unwrap();
+        assert_eq!(page.total, 4);
+        assert_eq!(page.runs[0].workflow_id, "wf-3");
+        assert!(!page.has_more());
+
+        let page = store
+            .list_workflow_runs(&RunQuery::new().workflow_name("ingest").user_id("alice"))
+            .await
+            .unwrap();
+        let ids: Vec<_> = page.runs.iter().map(|r| r.workflow_id.as_str()).collect();
+        assert_eq!(ids, vec!["wf-3", "wf-0"]);
+
+        let page = store
+            .list_workflow_runs(&RunQuery::new().status(WorkflowStatus::Failed))
+            .await
+            .unwrap();
+        assert_eq!(page.total, 1);
+        assert_eq!(page.runs[0].error.as_deref(), Some("provider timeout"));
+
+        let page = store
+            .list_workflow_runs(
+                &RunQuery::new()
+                    .since(base + chrono::Duration::minutes(30))
+                    .until(base + chrono::Duration::minutes(150)),
+            )
+            .await
+            .unwrap();
+        let ids: Vec<_> = page.runs.iter().map(|r| r.workflow_id.as_str()).collect();
+        assert_eq!(ids, vec!["wf-2", "wf-1"]);
+
+        // Oldest first, two per page
+        let query = RunQuery::new().sort_by(RunSortField::StartedAt, true).page(2, 2);
+        let page = store.list_workflow_runs(&query).await.unwrap();
+        let ids: Vec<_> = page.runs.iter().map(|r| r.workflow_id.as_str()).collect();
+        assert_eq!(ids, vec!["wf-2", "wf-3"]);
+        assert_eq!((page.offset, page.total), (2, 4));
+        assert!(!page.has_more());
+    }
+
+    #[tokio::test]
+    async fn test_usage_persisted_and_summarized() {
+        let store = SqliteStateStore::new(":memory:").await.unwrap();
+        let usage = |provider: &str, model: &str, requests: u64, tokens: u64| ModelUsage {
+            provider: provider.to_string(),
+            model: model.to_string(),
+            requests,
+            failed_requests: 0,
+            input_tokens: tokens / 2,
+            output_tokens: tokens / 2,
+            total_tokens: tokens,
+            cost_usd: 0.5,
+        };
+
+        let base = chrono::Utc::now() - chrono::Duration::days(1);
+        let runs = [
+            ("ingest", vec![usage("openai", "gpt-4", 2, 100), usage("openai", "embed", 1, 10)]),
+            ("ingest", vec![usage("openai", "gpt-4", 1, 40)]),
+            ("report", vec![usage("anthropic", "claude", 3, 60)]),
+        ];
+        let mut first = None;
+        for (i, (name, run_usage)) in runs.into_iter().enumerate() {
+            let mut state = WorkflowState::new(format!("wf-{}", i), name, None, json!({}));
+            state.started_at = base + chrono::Duration::hours(i as i64);
+            state.usage = run_usage;
+            store.save_workflow_state(&state).await.unwrap();
+            first.get_or_insert(state);
+        }
+
+        // Saving again replaces the run's totals instead of adding to them
+        let mut first = first.unwrap();
+        first.usage[0].requests = 4;
+        store.save_workflow_state(&first).await.unwrap();
+        let loaded = store.load_workflow_state(&first.id).await.unwrap();
+        assert_eq!(loaded.usage.len(), 2);
+        assert_eq!(loaded.usage[1], first.usage[0]);
+
+        let summary = store.usage_summary(&RunQuery::new()).await.unwrap();
+        let rows: Vec<_> = summary
+            .iter()
+            .map(|s| (s.usage.provider.as_str(), s.usage.model.as_str(), s.runs, s.usage.requests, s.usage.total_tokens))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![("anthropic", "claude", 1, 3, 60), ("openai", "embed", 1, 1, 10), ("openai", "gpt-4", 2, 5, 140)]
+        );
+        assert_eq!(summary[2].usage.cost_usd, 1.0);
+
+        let summary = store
+            .usage_summary(&RunQuery::new().workflow_name("ingest").since(base + chrono::Duration::minutes(30)))
+            .await
+            .unwrap();
+        assert_eq!(summary.len(), 1);
+        assert_eq!((summary[0].runs, summary[0].usage.requests), (1, 1));
+    }
+
+    #[tokio::test]
+    async fn test_workflow_definition_registry() {
+        let store = SqliteStateStore::new(":memory:").await.unwrap();
+
+        let mut v1 = WorkflowDefinition::new("summarize", "1.0", "name: summarize\nversion: \"1.0\"\n");
+        v1.created_by = Some("alice".to_string());
+        store.publish_workflow_definition(&v1).await.unwrap();
+        let mut v2 = WorkflowDefinition::new("summarize", "2.0", "name: summarize\nversion: \"2.0\"\n");
+        v2.created_at = v1.created_at + chrono::Duration::seconds(1);
+        store.publish_workflow_definition(&v2).await.unwrap();
+
+        // Published versions are immutable
+        assert!(matches!(
+            store.publish_workflow_definition(&v1).await,
+            Err(StateStoreError::InvalidState(_))
+        ));
+
+        let loaded = store.get_workflow_definition("summarize", Some("1.0")).await.unwrap();
+        assert_eq!(loaded, v1);
+        assert!(loaded.verify_checksum());
+        assert_eq!(store.get_workflow_definition("summarize", None).await.unwrap().version, "2.0");
+
+        // Deprecated versions are skipped when resolving the latest
+        store.deprecate_workflow_definition("summarize", "2.0").await.unwrap();
+        assert_eq!(store.get_workflow_definition("summarize", None).await.unwrap().version, "1.0");
+        assert!(store.get_workflow_definition("summarize", Some("2.0")).await.unwrap().is_deprecated());
+        assert_eq!(store.list_workflow_definitions(false).await.unwrap().len(), 1);
+        assert_eq!(store.list_workflow_definitions(true).await.unwrap().len(), 2);
+
+        assert!(matches!(
+            store.deprecate_workflow_definition("summarize", "3.0").await,
+            Err(StateStoreError::NotFound(_))
+        ));
+        store.deprecate_workflow_definition("summarize", "1.0").await.unwrap();
+        assert!(matches!(
+            store.get_workflow_definition("summarize", None).await,
+            Err(StateStoreError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_work_queue_priorities_and_tenant_fairness() {
+        let store = SqliteStateStore::new(":memory:").await.unwrap();
+        let lease = Duration::from_secs(30);
+        let start = chrono::Utc::now();
+        let enqueue = |task: QueueTask, seconds: i64| {
+            let mut task = task;
+            task.enqueued_at = start + chrono::Duration::seconds(seconds);
+            let store = &store;
+            async move {
+                store.enqueue_task(&task).await.unwrap();
+                task.id
+            }
+        };
+
+        // A batch tenant queues three runs before an interactive tenant queues one
+        let mut batch = Vec::new();
+        for i in 0..3 {
+            batch.push(enqueue(QueueTask::new(json!({"batch": i})).with_tenant_id("batch"), i).await);
+        }
+        let interactive = enqueue(QueueTask::new(json!({})).with_tenant_id("interactive"), 10).await;
+        let urgent = enqueue(QueueTask::new(json!({})).with_priority(RunPriority::High), 20).await;
+        let cleanup = enqueue(QueueTask::new(json!({})).with_priority(RunPriority::Low), -10).await;
+
+        // Higher priorities first, then the tenant with the fewest running runs, then the oldest
+        let mut claimed = Vec::new();
+        while let Some(task) = store.claim_task("worker", lease).await.unwrap() {
+            claimed.push(task.id);
+        }
+        assert_eq!(claimed, vec![urgent, batch[0], interactive, batch[1], batch[2], cleanup]);
+
+        let task = store.get_task(&urgent).await.unwrap();
+        assert_eq!(task.priority, RunPriority::High);
+        assert_eq!(store.get_task(&interactive).await.unwrap().tenant_id.as_deref(), Some("interactive"));
+        assert_eq!("LOW".parse::<RunPriority>().unwrap(), RunPriority::Low);
+        assert!("urgent".parse::<RunPriority>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_work_queue_cancel() {
+        let store = SqliteStateStore::new(":memory:").await.unwrap();
+
+        let queued = QueueTask::new(json!({"run": 1}));
+        store.enqueue_task(&queued).await.unwrap();
+        assert!(store.cancel_task(&queued.id).await.unwrap());
+        assert!(store.claim_task("worker-a", Duration::from_secs(30)).await.unwrap().is_none());
+
+        // A running task loses its lease so the worker can stop
+        let running = QueueTask::new(json!({"run": 2}));
+        store.enqueue_task(&running).await.unwrap();
+        store.claim_task("worker-a", Duration::from_secs(30)).await.unwrap().unwrap();
+        assert!(store.cancel_task(&running.id).await.unwrap());
+        assert!(!store.heartbeat_task(&running.id, "worker-a", Duration::from_secs(30)).await.unwrap());
+        assert_eq!(store.get_task(&running.id).await.unwrap().status, QueueTaskStatus::Cancelled);
+
+        // Finished tasks cannot be cancelled
+        assert!(!store.cancel_task(&running.id).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_tenant_store_is_confined_to_tenant() {
+        let inner: Arc<dyn StateStore> = Arc::new(SqliteStateStore::new(":memory:").await.unwrap());
+        let acme = TenantStateStore::new(inner.clone(), "acme");
+
+        let own = WorkflowState::new("wf-acme", "ingest", None, json!({})).with_tenant_id("acme");
+        let other = WorkflowState::new("wf-globex", "ingest", None, json!({})).with_tenant_id("globex");
+        acme.save_workflow_state(&own).await.unwrap();
+        inner.save_workflow_state(&other).await.unwrap();
+
+        // Runs of other tenants, or of none, cannot be written or read
+        assert!(matches!(
+            acme.save_workflow_state(&other).await,
+            Err(StateStoreError::TenantViolation(_))
+        ));
+        assert!(matches!(
+            acme.save_workflow_state(&WorkflowState::new("wf", "ingest", None, json!({}))).await,
+            Err(StateStoreError::TenantViolation(_))
+        ));
+        let mut hijack = other.clone();
+        hijack.tenant_id = Some("acme".to_string());
+        assert!(matches!(
+            acme.save_workflow_state(&hijack).await,
+            Err(StateStoreError::TenantViolation(_))
+        ));
+        assert_eq!(acme.load_workflow_state(&own.id).await.unwrap().tenant_id.as_deref(), Some("acme"));
+        assert!(matches!(acme.load_workflow_state(&other.id).await, Err(StateStoreError::NotFound(_))));
+        assert!(matches!(
+            acme.load_workflow_state_by_workflow_id("wf-globex").await,
+            Err(StateStoreError::NotFound(_))
+        ));
+
+        // Listings are filtered even when the query names another tenant
+        let page = acme.list_workflow_runs(&RunQuery::new().tenant_id("globex")).await.unwrap();
+        assert_eq!(page.total, 1);
+        assert_eq!(page.runs[0].tenant_id.as_deref(), Some("acme"));
+        assert_eq!(inner.list_workflow_runs(&RunQuery::new()).await.unwrap().total, 2);
+
+        // Checkpoints follow their run
+        let checkpoint = Checkpoint::new(other.id, "step1", serde_json::to_value(&other).unwrap());
+        inner.create_checkpoint(&checkpoint).await.unwrap();
+        assert!(acme.create_checkpoint(&checkpoint).await.is_err());
+        assert!(acme.get_latest_checkpoint(&other.id).await.is_err());
+        assert!(acme.restore_from_checkpoint(&checkpoint.id).await.is_err());
+        assert!(acme.cleanup_old_checkpoints(&other.id, 0).await.is_err());
+
+        assert!(matches!(
+            acme.delete_old_states(chrono::Utc::now()).await,
+            Err(StateStoreError::TenantViolation(_))
+        ));
+    }
 }
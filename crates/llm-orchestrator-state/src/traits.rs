@@ -3,9 +3,14 @@
 
 //! Traits for state persistence.
 
-use crate::models::{Checkpoint, WorkflowState};
+use crate::migrations::MigrationStatus;
+use crate::models::{
+    Checkpoint, DeadLetter, PromptVersion, QueueTask, QueueTaskStatus, RunPage, RunQuery, RunSummary, UsageSummary,
+    WorkflowDefinition, WorkflowState, WorkflowStatus,
+};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
+use std::time::Duration;
 use thiserror::Error;
 
 /// Error types for state store operations.
@@ -35,6 +40,14 @@ pub enum StateStoreError {
     #[error("Configuration error: {0}")]
     Configuration(String),
 
+    /// Encryption or decryption error.
+    #[error("Encryption error: {0}")]
+    Encryption(String),
+
+    /// Operation crosses a tenant boundary.
+    #[error("Tenant violation: {0}")]
+    TenantViolation(String),
+
     /// Other error.
     #[error("Other error: {0}")]
     Other(String),
@@ -59,6 +72,16 @@ impl From<serde_json::Error> for StateStoreError {
 /// Result type for state store operations.
 pub type StateStoreResult<T> = Result<T, StateStoreError>;
 
+/// Trait for applying the versioned schema migrations.
+#[async_trait]
+pub trait SchemaMigrations: Send + Sync {
+    /// List the bundled migrations and whether each has been applied.
+    async fn migration_status(&self) -> StateStoreResult<Vec<MigrationStatus>>;
+
+    /// Apply pending migrations, returning the ones that were applied.
+    async fn migrate(&self) -> StateStoreResult<Vec<MigrationStatus>>;
+}
+
 /// Trait for workflow state persistence and recovery.
 #[async_trait]
 pub trait StateStore: Send + Sync {
@@ -74,6 +97,9 @@ pub trait StateStore: Send + Sync {
     /// List all active workflows (running or paused).
     async fn list_active_workflows(&self) -> StateStoreResult<Vec<WorkflowState>>;
 
+    /// List past and active runs matching `query`, one page at a time.
+    async fn list_workflow_runs(&self, query: &RunQuery) -> StateStoreResult<RunPage>;
+
     /// Create a checkpoint.
     async fn create_checkpoint(&self, checkpoint: &Checkpoint) -> StateStoreResult<()>;
 
@@ -92,3 +118,256 @@ pub trait StateStore: Send + Sync {
     /// Health check for the state store.
     async fn health_check(&self) -> StateStoreResult<()>;
 }
+
+/// Work queue shared by distributed workers.
+///
+/// Tasks are claimed under a time-limited lease that the worker renews with
+/// [`heartbeat_task`](WorkQueue::heartbeat_task). If a worker crashes its lease
+/// expires and [`recover_expired_tasks`](WorkQueue::recover_expired_tasks)
+/// returns the task to the queue for another worker.
+#[async_trait]
+pub trait WorkQueue: Send + Sync {
+    /// Add a task to the queue.
+    async fn enqueue_task(&self, task: &QueueTask) -> StateStoreResult<()>;
+
+    /// Atomically claim the next queued task for `worker_id`.
+    ///
+    /// Tasks of a higher [`RunPriority`](crate::RunPriority) are claimed
+    /// first. Within a priority, the tenant with the fewest running tasks goes
+    /// first, so one tenant's backlog cannot starve the others; ties go to the
+    /// oldest task.
+    async fn claim_task(&self, worker_id: &str, lease: Duration) -> StateStoreResult<Option<QueueTask>>;
+
+    /// Extend the lease on a task. Returns false if the worker no longer holds it.
+    async fn heartbeat_task(&self, task_id: &uuid::Uuid, worker_id: &str, lease: Duration) -> StateStoreResult<bool>;
+
+    /// Mark a leased task as completed.
+    async fn complete_task(&self, task_id: &uuid::Uuid, worker_id: &str) -> StateStoreResult<()>;
+
+    /// Mark a leased task as failed.
+    async fn fail_task(&self, task_id: &uuid::Uuid, worker_id: &str, error: &str) -> StateStoreResult<()>;
+
+    /// Cancel a queued or running task. Returns false if the task already finished.
+    ///
+    /// A running task's worker notices the cancellation on its next heartbeat.
+    async fn cancel_task(&self, task_id: &uuid::Uuid) -> StateStoreResult<bool>;
+
+    /// Return tasks with expired leases to the queue. Returns the number of recovered tasks.
+    async fn recover_expired_tasks(&self) -> StateStoreResult<u64>;
+
+    /// Load a task by ID.
+    async fn get_task(&self, task_id: &uuid::Uuid) -> StateStoreResult<QueueTask>;
+
+    /// Count the tasks with `status`, e.g. the queue length.
+    async fn count_tasks(&self, status: QueueTaskStatus) -> StateStoreResult<u64>;
+}
+
+/// Storage for failure context of steps that exhausted their retries.
+#[async_trait]
+pub trait DeadLetterQueue: Send + Sync {
+    /// Store a dead-letter entry.
+    async fn add_dead_letter(&self, entry: &DeadLetter) -> StateStoreResult<()>;
+
+    /// List entries, newest first. Resolved entries are only included if requested.
+    async fn list_dead_letters(&self, include_resolved: bool) -> StateStoreResult<Vec<DeadLetter>>;
+
+    /// Load an entry by ID.
+    async fn get_dead_letter(&self, id: &uuid::Uuid) -> StateStoreResult<DeadLetter>;
+
+    /// Mark an entry as resolved after a successful retry.
+    async fn resolve_dead_letter(&self, id: &uuid::Uuid) -> StateStoreResult<()>;
+}
+
+/// Storage for named, versioned prompt templates.
+#[async_trait]
+pub trait PromptVersionStore: Send + Sync {
+    /// Store a new prompt version. Fails if the version already exists.
+    async fn add_prompt_version(&self, prompt: &PromptVersion) -> StateStoreResult<()>;
+
+    /// List the names of all stored prompts, in alphabetical order.
+    async fn list_prompt_names(&self) -> StateStoreResult<Vec<String>>;
+
+    /// List the versions of a prompt, oldest first.
+    async fn list_prompt_versions(&self, name: &str) -> StateStoreResult<Vec<PromptVersion>>;
+
+    /// Make `version` the one the prompt's name resolves to.
+    async fn promote_prompt_version(&self, name: &str, version: u32) -> StateStoreResult<()>;
+
+    /// Version the prompt's name resolves to, if one was promoted.
+    async fn promoted_prompt_version(&self, name: &str) -> StateStoreResult<Option<u32>>;
+}
+
+/// Registry of published workflow definitions.
+#[async_trait]
+pub trait WorkflowDefinitionStore: Send + Sync {
+    /// Publish a workflow definition. Fails if the version already exists.
+    async fn publish_workflow_definition(&self, definition: &WorkflowDefinition) -> StateStoreResult<()>;
+
+    /// List published definitions by name, oldest version first.
+    async fn list_workflow_definitions(&self, include_deprecated: bool) -> StateStoreResult<Vec<WorkflowDefinition>>;
+
+    /// Load a version of a workflow, or its newest non-deprecated version when `version` is `None`.
+    async fn get_workflow_definition(&self, name: &str, version: Option<&str>) -> StateStoreResult<WorkflowDefinition>;
+
+    /// Mark a version as deprecated so it is no longer resolved as the latest.
+    async fn deprecate_workflow_definition(&self, name: &str, version: &str) -> StateStoreResult<()>;
+}
+
+/// Usage accounting over persisted runs.
+#[async_trait]
+pub trait UsageStore: Send + Sync {
+    /// Sum the usage of the runs matching `query`, per provider and model.
+    ///
+    /// The query's filters apply; its sorting and pagination are ignored.
+    async fn usage_summary(&self, query: &RunQuery) -> StateStoreResult<Vec<UsageSummary>>;
+}
+
+/// Retention of finished runs.
+///
+/// A run's finish time is its `completed_at`, or its `updated_at` for runs
+/// persisted before completion times were recorded.
+#[async_trait]
+pub trait RetentionStore: Send + Sync {
+    /// List the runs with `status` that finished before `finished_before`, oldest first.
+    async fn list_finished_runs(
+        &self,
+        status: WorkflowStatus,
+        finished_before: DateTime<Utc>,
+    ) -> StateStoreResult<Vec<RunSummary>>;
+
+    /// Delete runs with their steps, checkpoints, and offloaded outputs. Returns the number deleted.
+    async fn delete_runs(&self, ids: &[uuid::Uuid]) -> StateStoreResult<u64>;
+
+    /// Compress the contexts of finished runs that finished before `finished_before`.
+    ///
+    /// Returns the number of contexts compressed; with `dry_run`, the number
+    /// that would be, without changing them.
+    async fn compress_contexts(&self, finished_before: DateTime<Utc>, dry_run: bool) -> StateStoreResult<u64>;
+}
+
+/// Memory kept across runs, per session and key.
+#[async_trait]
+pub trait SessionMemoryStore: Send + Sync {
+    /// Value stored under `key` in a session, if any.
+    async fn get_session_memory(&self, session_id: &str, key: &str) -> StateStoreResult<Option<serde_json::Value>>;
+
+    /// Store `value` under `key` in a session, replacing what was there.
+    async fn put_session_memory(&self, session_id: &str, key: &str, value: &serde_json::Value) -> StateStoreResult<()>;
+
+    /// Append `entries` to the list stored under `key` in a session, starting one if the key is unset.
+    ///
+    /// Returns the length of the list. Fails if the key holds something other than a list.
+    async fn append_session_memory(
+        &self,
+        session_id: &str,
+        key: &str,
+        entries: &[serde_json::Value],
+    ) -> StateStoreResult<usize>;
+}
+
+/// Append `entries` to a stored session memory value, returning the new value and its length.
+pub(crate) fn append_memory_entries(
+    session_id: &str,
+    key: &str,
+    stored: &str,
+    entries: &[serde_json::Value],
+) -> StateStoreResult<(String, usize)> {
+    match serde_json::from_str(stored)? {
+        serde_json::Value::Array(mut list) => {
+            list.extend_from_slice(entries);
+            Ok((serde_json::to_string(&list)?, list.len()))
+        }
+        _ => Err(StateStoreError::InvalidState(format!(
+            "Memory key {} of session {} does not hold a list",
+            key, session_id
+        ))),
+    }
+}
+
+/// Map a primary-key conflict on a new prompt version to an invalid-state error.
+pub(crate) fn prompt_insert_error(err: sqlx::Error, prompt: &PromptVersion) -> StateStoreError {
+    match &err {
+        sqlx::Error::Database(db) if db.is_unique_violation() => StateStoreError::InvalidState(format!(
+            "Prompt {} version {} already exists",
+            prompt.name, prompt.version
+        )),
+        _ => err.into(),
+    }
+}
+
+/// Map a primary-key conflict on a new workflow definition to an invalid-state error.
+pub(crate) fn definition_insert_error(err: sqlx::Error, definition: &WorkflowDefinition) -> StateStoreError {
+    match &err {
+        sqlx::Error::Database(db) if db.is_unique_violation() => StateStoreError::InvalidState(format!(
+            "Workflow {} version {} already exists",
+            definition.name, definition.version
+        )),
+        _ => err.into(),
+    }
+}
+
+/// Describe a workflow lookup for not-found errors.
+pub(crate) fn definition_not_found(name: &str, version: Option<&str>) -> StateStoreError {
+    match version {
+        Some(version) => StateStoreError::NotFound(format!("Workflow {} version {} not found", name, version)),
+        None => StateStoreError::NotFound(format!("No active version of workflow {} found", name)),
+    }
+}
+
+/// A value bound to a run history query.
+pub(crate) enum RunQueryParam {
+    Text(String),
+    Time(DateTime<Utc>),
+}
+
+/// Append the `WHERE` conditions of a run query to `sql`, returning the bound parameters.
+///
+/// `placeholder` renders the n-th (1-based) bind parameter in the backend's syntax.
+pub(crate) fn push_run_conditions(
+    query: &RunQuery,
+    sql: &mut String,
+    placeholder: fn(usize) -> String,
+) -> Vec<RunQueryParam> {
+    let mut params = Vec::new();
+    let mut push = |sql: &mut String, condition: &str, param: RunQueryParam| {
+        params.push(param);
+        sql.push_str(&format!(" AND {} {}", condition, placeholder(params.len())));
+    };
+
+    if let Some(workflow_name) = &query.workflow_name {
+        push(sql, "workflow_name =", RunQueryParam::Text(workflow_name.clone()));
+    }
+    if let Some(status) = &query.status {
+        push(sql, "status =", RunQueryParam::Text(status.to_string()));
+    }
+    if let Some(user_id) = &query.user_id {
+        push(sql, "user_id =", RunQueryParam::Text(user_id.clone()));
+    }
+    if let Some(tenant_id) = &query.tenant_id {
+        push(sql, "tenant_id =", RunQueryParam::Text(tenant_id.clone()));
+    }
+    if let Some(start) = query.started_after {
+        push(sql, "started_at >=", RunQueryParam::Time(start));
+    }
+    if let Some(end) = query.started_before {
+        push(sql, "started_at <=", RunQueryParam::Time(end));
+    }
+
+    params
+}
+
+/// `ORDER BY` and `LIMIT` clauses of a run query, with the run ID as a tie-breaker for stable pages.
+pub(crate) fn run_order_clause(query: &RunQuery) -> String {
+    let direction = if query.ascending { "ASC" } else { "DESC" };
+    format!(
+        " ORDER BY {column} {direction}, id {direction} LIMIT {} OFFSET {}",
+        query.limit,
+        query.offset,
+        column = query.sort_by.as_column(),
+    )
+}
+
+/// Compute the lease expiry as Unix epoch milliseconds.
+pub(crate) fn lease_expiry_millis(lease: Duration) -> i64 {
+    Utc::now().timestamp_millis() + lease.as_millis() as i64
+}